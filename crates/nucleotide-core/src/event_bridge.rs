@@ -3,7 +3,7 @@
 
 use helix_core::{Assoc, ChangeSet, Operation, Rope};
 use helix_view::DocumentId;
-use nucleotide_events::v2::document::{ChangeType, DocumentLineChange};
+use nucleotide_events::v2::document::{ChangeType, DocumentLineChange, DocumentViewState};
 use nucleotide_logging::{debug, info, instrument, trace, warn};
 use std::sync::OnceLock;
 use tokio::sync::mpsc;
@@ -25,6 +25,8 @@ pub enum BridgedEvent {
     DocumentClosed {
         doc_id: DocumentId,
         was_modified: bool,
+        path: Option<std::path::PathBuf>,
+        view_state: Option<DocumentViewState>,
     },
     LanguageServerInitialized {
         server_id: helix_lsp::LanguageServerId,
@@ -232,6 +234,30 @@ pub fn register_event_hooks() {
     register_hook!(move |event: &mut DocumentDidClose<'_>| {
         let doc_id = event.doc.id();
         let was_modified = event.doc.is_modified();
+        let path = event.doc.path().map(|path| path.to_path_buf());
+        // Views are detached before the hook runs, but their selections and
+        // scroll positions stay with the document; any of them is close
+        // enough to reopen at.
+        let view_state = event
+            .doc
+            .selections()
+            .iter()
+            .next()
+            .map(|(view_id, selection)| {
+                let text = event.doc.text().slice(..);
+                let coords = |pos| helix_core::coords_at_pos(text, pos);
+                let top = event.doc.view_offset(*view_id).anchor.min(text.len_chars());
+                DocumentViewState {
+                    ranges: selection
+                        .ranges()
+                        .iter()
+                        .map(|range| (coords(range.anchor), coords(range.head)))
+                        .collect(),
+                    primary_index: selection.primary_index(),
+                    cursor: coords(selection.primary().cursor(text)),
+                    top_line: text.char_to_line(top),
+                }
+            });
         info!(
             doc_id = ?doc_id,
             was_modified = was_modified,
@@ -240,6 +266,8 @@ pub fn register_event_hooks() {
         send_bridged_event(BridgedEvent::DocumentClosed {
             doc_id,
             was_modified,
+            path,
+            view_state,
         });
         Ok(())
     });
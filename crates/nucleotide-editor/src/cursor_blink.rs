@@ -0,0 +1,67 @@
+// ABOUTME: Blink phase of an editor view's cursor
+// ABOUTME: Edits, cursor movement and mode changes restart the solid phase before blinking
+
+use std::time::{Duration, Instant};
+
+use helix_view::{DocumentId, Editor, ViewId, document::Mode};
+
+/// Cursor state a frame was drawn with. A change means the user typed,
+/// moved the cursor or switched modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorActivityKey {
+    doc_id: DocumentId,
+    version: i32,
+    anchor: usize,
+    head: usize,
+    mode: Mode,
+}
+
+impl CursorActivityKey {
+    pub fn for_view(editor: &Editor, view_id: ViewId) -> Option<Self> {
+        let doc_id = editor.tree.try_get(view_id)?.doc;
+        let document = editor.document(doc_id)?;
+        let primary = document.selection(view_id).primary();
+        Some(Self {
+            doc_id,
+            version: document.version(),
+            anchor: primary.anchor,
+            head: primary.head,
+            mode: editor.mode(),
+        })
+    }
+}
+
+/// When the cursor of one view last saw activity.
+#[derive(Debug, Clone)]
+pub struct EditorCursorBlink {
+    last_activity: Instant,
+    key: Option<CursorActivityKey>,
+}
+
+impl EditorCursorBlink {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            last_activity: now,
+            key: None,
+        }
+    }
+
+    /// Restarts the solid phase, as when the view gains focus.
+    pub fn reset(&mut self, now: Instant) {
+        self.last_activity = now;
+    }
+
+    /// Records the cursor state of the frame being drawn, restarting the
+    /// solid phase when it differs from the previous frame's.
+    pub fn observe(&mut self, key: Option<CursorActivityKey>, now: Instant) {
+        if self.key != key {
+            self.key = key;
+            self.last_activity = now;
+        }
+    }
+
+    /// Time since the last activity.
+    pub fn idle(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.last_activity)
+    }
+}
@@ -68,10 +68,12 @@ where
         _inspector_id: Option<&InspectorElementId>,
         bounds: Bounds<Pixels>,
         _request_layout: &mut Self::RequestLayoutState,
-        _window: &mut Window,
+        window: &mut Window,
         cx: &mut App,
     ) -> Self::PrepaintState {
-        EditorTextMetrics::resolve(cx.text_system(), &self.text_style).layout_for_bounds(bounds)
+        EditorTextMetrics::resolve(cx.text_system(), &self.text_style)
+            .snapped_to_scale(window.scale_factor())
+            .layout_for_bounds(bounds)
     }
 
     fn paint(
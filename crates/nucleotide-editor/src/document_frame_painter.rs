@@ -303,6 +303,9 @@ pub struct NativeEditorFramePrepareParams<'a> {
     pub text_style: &'a TextStyle,
     pub font_size: Pixels,
     pub is_focused: bool,
+    /// False while a blinking cursor is in its hidden phase, or when the
+    /// cursor of an unfocused view is hidden
+    pub cursor_visible: bool,
     pub soft_wrap_minimum_columns: u16,
     pub theme_styles: NativeEditorFrameThemeStyles,
     pub palette: NativeEditorFramePalette,
@@ -337,6 +340,7 @@ pub struct NativeEditorFrameRenderParams<'a> {
     pub text_style: &'a TextStyle,
     pub font_size: Pixels,
     pub is_focused: bool,
+    pub cursor_visible: bool,
     pub focus: &'a FocusHandle,
     pub soft_wrap_minimum_columns: u16,
     pub theme_styles: NativeEditorFrameThemeStyles,
@@ -421,7 +425,11 @@ pub fn prepare_native_editor_frame(
     let syntax_loader = editor.syn_loader.load();
     let editor_config = editor.config();
     let editor_mode = editor.mode();
-    let (_, cursor_kind) = editor.cursor();
+    let cursor_kind = if params.cursor_visible {
+        editor.cursor().1
+    } else {
+        CursorKind::Hidden
+    };
     let cursor_shape = editor_config.cursor_shape.clone();
     let editor_rulers = editor_config.rulers.clone();
     let cursorline_enabled = editor_config.cursorline && params.is_focused;
@@ -475,6 +483,7 @@ pub fn render_native_editor_frame(
         text_style,
         font_size,
         is_focused,
+        cursor_visible,
         focus,
         soft_wrap_minimum_columns,
         theme_styles,
@@ -491,6 +500,7 @@ pub fn render_native_editor_frame(
         text_style,
         font_size,
         is_focused,
+        cursor_visible,
         soft_wrap_minimum_columns,
         theme_styles,
         palette,
@@ -1859,6 +1869,7 @@ mod tests {
             text_style: &text_style,
             font_size: px(16.0),
             is_focused: true,
+            cursor_visible: true,
             soft_wrap_minimum_columns: EDITOR_MINIMUM_VIEWPORT_COLUMNS,
             theme_styles: NativeEditorFrameThemeStyles::default(),
             palette: paint_palette(),
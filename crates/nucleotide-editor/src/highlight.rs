@@ -16,7 +16,7 @@ use helix_view::{
     Document, Theme, View,
     document::Mode,
     editor::WhitespaceRenderValue,
-    graphics::{Color, CursorKind, Style},
+    graphics::{Color, CursorKind, Modifier, Style, UnderlineStyle},
     view::ViewPosition,
 };
 use nucleotide_logging::trace;
@@ -698,6 +698,178 @@ pub fn gpui_hsla_to_helix_color(c: Hsla) -> Option<Color> {
     ))
 }
 
+/// A run of identically styled text with theme colors resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyledTextSpan {
+    pub text: String,
+    /// `None` uses the theme's default text color
+    pub fg: Option<Hsla>,
+    pub bg: Option<Hsla>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+pub struct StyledDocumentLinesParams<'a> {
+    pub doc: &'a Document,
+    pub view: &'a View,
+    pub theme: &'a Theme,
+    pub syntax_loader: &'a helix_core::syntax::Loader,
+    /// Document line indices to style
+    pub lines: Range<usize>,
+    /// Paint the view's selections with `ui.selection`
+    pub include_selection: bool,
+}
+
+/// Syntax-highlighted spans for whole document lines, without cursors,
+/// diagnostics or virtual text. Used to export snippets outside the editor.
+pub fn styled_document_lines(params: StyledDocumentLinesParams<'_>) -> Vec<Vec<StyledTextSpan>> {
+    let text = params.doc.text().slice(..);
+    let last_line = params.lines.end.min(text.len_lines());
+    let first_line = params.lines.start.min(last_line);
+    if first_line == last_line {
+        return Vec::new();
+    }
+
+    let anchor = text.line_to_char(first_line);
+    let height = u16::try_from(last_line - first_line + 1).unwrap_or(u16::MAX);
+    let syntax_highlighter =
+        doc_syntax_highlights(params.doc, anchor, height, params.syntax_loader);
+    let default_style = params.theme.get("ui.text");
+    let text_style = Style {
+        fg: default_style.fg,
+        bg: None,
+        ..Default::default()
+    };
+    let mut syntax_hl = SyntaxHighlighter::new(syntax_highlighter, text, params.theme, text_style);
+
+    let mut overlays = Vec::new();
+    if params.include_selection
+        && let Some(selection_scope) = params.theme.find_highlight_exact("ui.selection")
+    {
+        let ranges = params
+            .doc
+            .selection(params.view.id)
+            .iter()
+            .filter(|range| range.from() < range.to())
+            .map(|range| range.from()..range.to())
+            .collect();
+        overlays.push(OverlayHighlights::Homogeneous {
+            highlight: selection_scope,
+            ranges,
+        });
+    }
+    let mut overlay_hl = OverlayHighlighter::new(overlays, params.theme);
+
+    styled_lines(text, &mut syntax_hl, &mut overlay_hl, first_line..last_line)
+}
+
+/// Syntax-highlighted spans for lines of text that belong to no document,
+/// such as a file preview. `syntax` must have been parsed from `text`.
+pub fn styled_text_lines(
+    text: RopeSlice<'_>,
+    syntax: Option<&syntax::Syntax>,
+    theme: &Theme,
+    syntax_loader: &helix_core::syntax::Loader,
+    lines: Range<usize>,
+) -> Vec<Vec<StyledTextSpan>> {
+    let last_line = lines.end.min(text.len_lines());
+    let first_line = lines.start.min(last_line);
+    if first_line == last_line {
+        return Vec::new();
+    }
+
+    let range = viewport_byte_range(
+        text,
+        first_line,
+        u16::try_from(last_line - first_line + 1).unwrap_or(u16::MAX),
+    );
+    let highlighter = syntax.filter(|_| range.start < range.end).map(|syntax| {
+        syntax.highlighter(text, syntax_loader, range.start as u32..range.end as u32)
+    });
+    let text_style = Style {
+        fg: theme.get("ui.text").fg,
+        bg: None,
+        ..Default::default()
+    };
+    let mut syntax_hl = SyntaxHighlighter::new(highlighter, text, theme, text_style);
+    let mut overlay_hl = OverlayHighlighter::new(Vec::new(), theme);
+
+    styled_lines(text, &mut syntax_hl, &mut overlay_hl, first_line..last_line)
+}
+
+fn styled_lines(
+    text: RopeSlice<'_>,
+    syntax_hl: &mut SyntaxHighlighter<'_, '_, '_>,
+    overlay_hl: &mut OverlayHighlighter<'_>,
+    lines: Range<usize>,
+) -> Vec<Vec<StyledTextSpan>> {
+    lines
+        .map(|line| {
+            let line_start = text.line_to_char(line);
+            let line_end = line_start
+                + line_text_without_trailing_newline(text.line(line))
+                    .chars()
+                    .count();
+            styled_line_spans(text, syntax_hl, overlay_hl, line_start, line_end)
+        })
+        .collect()
+}
+
+fn styled_line_spans(
+    text: RopeSlice<'_>,
+    syntax_hl: &mut SyntaxHighlighter<'_, '_, '_>,
+    overlay_hl: &mut OverlayHighlighter<'_>,
+    line_start: usize,
+    line_end: usize,
+) -> Vec<StyledTextSpan> {
+    let mut spans: Vec<StyledTextSpan> = Vec::new();
+    let mut position = line_start;
+    while position < line_end {
+        while position >= syntax_hl.pos {
+            syntax_hl.advance();
+        }
+        while position >= overlay_hl.pos {
+            overlay_hl.advance();
+        }
+
+        let next_pos = syntax_hl.pos.min(overlay_hl.pos).min(line_end);
+        if next_pos == position {
+            break;
+        }
+
+        let style = syntax_hl.style.patch(overlay_hl.style);
+        let has_modifier = |modifier| {
+            style.add_modifier.contains(modifier) && !style.sub_modifier.contains(modifier)
+        };
+        let span = StyledTextSpan {
+            text: text.slice(position..next_pos).to_string(),
+            fg: style.fg.and_then(helix_color_to_hsla),
+            bg: style.bg.and_then(helix_color_to_hsla),
+            bold: has_modifier(Modifier::BOLD),
+            italic: has_modifier(Modifier::ITALIC),
+            underline: style
+                .underline_style
+                .is_some_and(|underline| underline != UnderlineStyle::Reset),
+        };
+        match spans.last_mut() {
+            Some(last)
+                if last.fg == span.fg
+                    && last.bg == span.bg
+                    && last.bold == span.bold
+                    && last.italic == span.italic
+                    && last.underline == span.underline =>
+            {
+                last.text.push_str(&span.text);
+            }
+            _ => spans.push(span),
+        }
+        position = next_pos;
+    }
+
+    spans
+}
+
 fn syntax_highlight_window(text: RopeSlice<'_>, view_position: ViewPosition) -> (usize, u16) {
     let anchor = view_position.anchor.min(text.len_chars());
     let lines_from_anchor = text.len_lines() - text.char_to_line(anchor);
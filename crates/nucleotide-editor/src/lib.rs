@@ -8,6 +8,7 @@
 pub const EDITOR_MINIMUM_VIEWPORT_COLUMNS: u16 = 10;
 
 pub mod cursor;
+pub mod cursor_blink;
 pub mod cursor_style;
 pub mod diagnostics;
 pub mod document_element;
@@ -51,6 +52,7 @@ pub use cursor::{
     shaped_editor_cursor_plan, soft_wrap_cursor_paint_plan, soft_wrap_cursor_paint_position,
     unwrapped_cursor_paint_plan, unwrapped_cursor_paint_position,
 };
+pub use cursor_blink::{CursorActivityKey, EditorCursorBlink};
 pub use cursor_style::{cursor_has_reversed_modifier, cursor_style_for_mode};
 pub use diagnostics::{
     DiagnosticGutterMarkerPaintPlan, DiagnosticGutterMarkerPaintPlanParams,
@@ -86,10 +88,11 @@ pub use gutter::{
 pub use highlight::{
     DiagnosticOverlaySpans, EditorLineHighlightContext, HighlightLineParams,
     SoftWrapHighlightedLineRunsBatchParams, SoftWrapHighlightedLineRunsParams,
-    UnwrappedHighlightedLine, UnwrappedHighlightedLineParams, UnwrappedHighlightedLinesParams,
-    diagnostic_overlay_spans, gpui_hsla_to_helix_color, highlight_line,
-    soft_wrap_highlighted_line_runs, soft_wrap_highlighted_line_runs_batch, text_style_at_position,
-    unwrapped_highlighted_line, unwrapped_highlighted_lines,
+    StyledDocumentLinesParams, StyledTextSpan, UnwrappedHighlightedLine,
+    UnwrappedHighlightedLineParams, UnwrappedHighlightedLinesParams, diagnostic_overlay_spans,
+    gpui_hsla_to_helix_color, highlight_line, soft_wrap_highlighted_line_runs,
+    soft_wrap_highlighted_line_runs_batch, styled_document_lines, styled_text_lines,
+    text_style_at_position, unwrapped_highlighted_line, unwrapped_highlighted_lines,
 };
 pub use hit_test::{EditorHitTestResult, hit_test_document_position};
 pub use inline_diagnostics::{
@@ -135,12 +138,13 @@ pub use scrollbar::{
 };
 pub use selection::{
     EditorPointerSelectionOutcome, EditorPointerSelectionPhase, EditorPointerSelectionUpdate,
-    EditorSelectionDragState, EditorSelectionUpdate, apply_pointer_selection,
-    begin_editor_pointer_selection_at_event, begin_pointer_selection,
-    begin_pointer_selection_at_event, log_pointer_selection_outcome, pointer_selection_anchor,
-    primary_selection_anchor, selection_for_range, update_editor_pointer_selection_at_event,
-    update_editor_pointer_selection_at_event_outcome, update_pointer_selection,
-    update_pointer_selection_at_event,
+    EditorSelectionDragState, EditorSelectionGranularity, EditorSelectionUnit,
+    EditorSelectionUpdate, apply_pointer_selection, begin_editor_pointer_selection_at_event,
+    begin_pointer_selection, begin_pointer_selection_at_event, begin_unit_pointer_selection,
+    drag_selection_range, line_range_at, log_pointer_selection_outcome, pointer_selection_anchor,
+    primary_selection_anchor, selection_for_range, unit_drag_range, unit_range_at,
+    update_editor_pointer_selection_at_event, update_editor_pointer_selection_at_event_outcome,
+    update_pointer_selection, update_pointer_selection_at_event, word_range_at,
 };
 pub use soft_wrap::{
     DocumentSoftWrapRenderPlanParams, SoftWrapLinePaintPlan, SoftWrapRenderPlan,
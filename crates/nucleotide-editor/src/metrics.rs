@@ -2,6 +2,7 @@
 // ABOUTME: Resolves GPUI text style measurements used by editor layout and input surfaces
 
 use gpui::{Bounds, Pixels, TextStyle, TextSystem, px};
+use nucleotide_types::snap_to_device_pixels;
 
 use crate::EditorLayout;
 
@@ -35,6 +36,20 @@ impl EditorTextMetrics {
         }
     }
 
+    /// Snaps the line height to whole device pixels for `scale_factor` so row
+    /// baselines stay crisp at fractional scales. Column metrics keep their
+    /// measured advance: glyphs are shaped at their true width, and a rounded
+    /// column would drift the cursor away from the text on long lines.
+    pub fn snapped_to_scale(self, scale_factor: f32) -> Self {
+        Self {
+            line_height: px(snap_to_device_pixels(
+                f32::from(self.line_height),
+                scale_factor,
+            )),
+            ..self
+        }
+    }
+
     pub fn layout_for_bounds(&self, bounds: Bounds<Pixels>) -> EditorLayout {
         let columns = ((bounds.size.width / self.em_width).floor() as usize).max(1);
         let rows = ((bounds.size.height / self.line_height).floor() as usize).max(1);
@@ -89,4 +104,21 @@ mod tests {
         assert_eq!(layout.columns, 1);
         assert_eq!(layout.rows, 1);
     }
+
+    #[test]
+    fn snapped_to_scale_rounds_line_height_only() {
+        let metrics = EditorTextMetrics {
+            font_size: px(13.0),
+            line_height: px(17.55),
+            em_width: px(7.8),
+            cell_width: px(7.83),
+        };
+
+        let snapped = metrics.snapped_to_scale(2.0);
+
+        assert_eq!(snapped.line_height, px(17.5));
+        assert_eq!(snapped.cell_width, px(7.83));
+        assert_eq!(snapped.em_width, px(7.8));
+        assert_eq!(snapped.font_size, px(13.0));
+    }
 }
@@ -1,19 +1,49 @@
 // ABOUTME: Native editor pointer-selection state and Helix selection updates
-// ABOUTME: Keeps click, shift-click, and drag selection logic with editor input
+// ABOUTME: Keeps click, shift-click, multi-click, and drag selection logic with editor input
 
 use std::{cell::Cell, rc::Rc};
 
-use helix_core::{Range, Selection, SmallVec};
+use helix_core::{Range, RopeSlice, Selection, SmallVec, line_ending::line_end_char_index};
 use helix_view::{Document, DocumentId, Editor, ViewId};
 use nucleotide_logging::{debug, trace};
+use nucleotide_types::{CharClass, MouseSelectionConfig};
 
 use crate::{
     EditorHitTestResult, EditorSurfacePointerEvent, LineLayoutCache, hit_test_document_position,
 };
 
+/// How much text a click selects, from how many clicks it took.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EditorSelectionGranularity {
+    #[default]
+    Char,
+    Word,
+    Line,
+}
+
+impl EditorSelectionGranularity {
+    pub fn from_click_count(click_count: usize) -> Self {
+        match click_count {
+            0 | 1 => Self::Char,
+            2 => Self::Word,
+            _ => Self::Line,
+        }
+    }
+}
+
+/// Word or line a multi-click selected. Dragging afterwards extends the
+/// selection by whole units from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EditorSelectionUnit {
+    pub granularity: EditorSelectionGranularity,
+    pub start: usize,
+    pub end: usize,
+}
+
 #[derive(Clone, Default)]
 pub struct EditorSelectionDragState {
     anchor: Rc<Cell<Option<usize>>>,
+    unit: Rc<Cell<Option<EditorSelectionUnit>>>,
 }
 
 impl EditorSelectionDragState {
@@ -23,10 +53,21 @@ impl EditorSelectionDragState {
 
     pub fn set_anchor(&self, anchor: usize) {
         self.anchor.set(Some(anchor));
+        self.unit.set(None);
+    }
+
+    pub fn unit(&self) -> Option<EditorSelectionUnit> {
+        self.unit.get()
+    }
+
+    pub fn set_unit(&self, unit: EditorSelectionUnit) {
+        self.anchor.set(Some(unit.start));
+        self.unit.set(Some(unit));
     }
 
     pub fn clear(&self) {
         self.anchor.set(None);
+        self.unit.set(None);
     }
 }
 
@@ -145,6 +186,113 @@ pub fn selection_for_range(
     (selection, update)
 }
 
+/// Run of word, whitespace or punctuation characters at `char_idx`. A click
+/// past the end of a line takes the run before it; on an empty line the
+/// line ending is selected.
+pub fn word_range_at(
+    text: RopeSlice,
+    char_idx: usize,
+    config: &MouseSelectionConfig,
+) -> (usize, usize) {
+    let len = text.len_chars();
+    if len == 0 {
+        return (0, 0);
+    }
+
+    let mut char_idx = char_idx.min(len - 1);
+    let mut class = config.char_class(text.char(char_idx));
+    if class == CharClass::LineEnding {
+        let before = char_idx
+            .checked_sub(1)
+            .map(|idx| (idx, config.char_class(text.char(idx))))
+            .filter(|(_, class)| *class != CharClass::LineEnding);
+        match before {
+            Some((idx, before_class)) => {
+                char_idx = idx;
+                class = before_class;
+            }
+            None => return line_ending_range_at(text, char_idx),
+        }
+    }
+
+    let mut start = char_idx;
+    while start > 0 && config.char_class(text.char(start - 1)) == class {
+        start -= 1;
+    }
+    let mut end = char_idx + 1;
+    while end < len && config.char_class(text.char(end)) == class {
+        end += 1;
+    }
+    (start, end)
+}
+
+fn line_ending_range_at(text: RopeSlice, char_idx: usize) -> (usize, usize) {
+    let line = text.char_to_line(char_idx);
+    (
+        line_end_char_index(&text, line),
+        text.line_to_char(line + 1),
+    )
+}
+
+/// The line at `char_idx`, with or without its line ending.
+pub fn line_range_at(
+    text: RopeSlice,
+    char_idx: usize,
+    include_line_ending: bool,
+) -> (usize, usize) {
+    let line = text.char_to_line(char_idx.min(text.len_chars()));
+    let end = if include_line_ending {
+        text.line_to_char(line + 1)
+    } else {
+        line_end_char_index(&text, line)
+    };
+    (text.line_to_char(line), end)
+}
+
+/// The unit of `granularity` at `char_idx`. Char granularity is the
+/// position itself.
+pub fn unit_range_at(
+    text: RopeSlice,
+    char_idx: usize,
+    granularity: EditorSelectionGranularity,
+    config: &MouseSelectionConfig,
+) -> (usize, usize) {
+    match granularity {
+        EditorSelectionGranularity::Char => (char_idx, char_idx),
+        EditorSelectionGranularity::Word => word_range_at(text, char_idx, config),
+        EditorSelectionGranularity::Line => {
+            line_range_at(text, char_idx, config.triple_click_includes_line_ending)
+        }
+    }
+}
+
+/// Anchor and head covering the unit a drag began on and the unit under the
+/// pointer. The anchor stays on the far side of the first unit so the head
+/// follows the pointer in either direction.
+pub fn unit_drag_range(origin: (usize, usize), current: (usize, usize)) -> (usize, usize) {
+    if current.0 < origin.0 {
+        (origin.1, current.0)
+    } else {
+        (origin.0, origin.1.max(current.1))
+    }
+}
+
+/// Anchor and head of the drag selection with the pointer at `hit_char_idx`.
+pub fn drag_selection_range(
+    text: RopeSlice,
+    drag_state: &EditorSelectionDragState,
+    hit_char_idx: usize,
+    config: &MouseSelectionConfig,
+) -> Option<(usize, usize)> {
+    match drag_state.unit() {
+        Some(unit) => {
+            let current = unit_range_at(text, hit_char_idx, unit.granularity, config);
+            Some(unit_drag_range((unit.start, unit.end), current))
+        }
+        None => drag_state.anchor().map(|anchor| (anchor, hit_char_idx)),
+    }
+}
+
 pub fn primary_selection_anchor(document: &Document, view_id: ViewId) -> usize {
     document.selection(view_id).primary().anchor
 }
@@ -177,19 +325,35 @@ pub fn begin_pointer_selection(
     update
 }
 
+/// Selects the word or line at `hit_char_idx` for a double or triple click.
+pub fn begin_unit_pointer_selection(
+    document: &mut Document,
+    view_id: ViewId,
+    drag_state: &EditorSelectionDragState,
+    hit_char_idx: usize,
+    granularity: EditorSelectionGranularity,
+    config: &MouseSelectionConfig,
+) -> EditorSelectionUpdate {
+    let (start, end) = unit_range_at(document.text().slice(..), hit_char_idx, granularity, config);
+    let update = apply_pointer_selection(document, view_id, start, end);
+    drag_state.set_unit(EditorSelectionUnit {
+        granularity,
+        start: update.anchor,
+        end: update.head,
+    });
+    update
+}
+
 pub fn update_pointer_selection(
     document: &mut Document,
     view_id: ViewId,
     drag_state: &EditorSelectionDragState,
     hit_char_idx: usize,
+    config: &MouseSelectionConfig,
 ) -> Option<EditorSelectionUpdate> {
-    let anchor = drag_state.anchor()?;
-    Some(apply_pointer_selection(
-        document,
-        view_id,
-        anchor,
-        hit_char_idx,
-    ))
+    let (anchor, head) =
+        drag_selection_range(document.text().slice(..), drag_state, hit_char_idx, config)?;
+    Some(apply_pointer_selection(document, view_id, anchor, head))
 }
 
 pub fn begin_pointer_selection_at_event(
@@ -199,6 +363,7 @@ pub fn begin_pointer_selection_at_event(
     line_cache: &LineLayoutCache,
     drag_state: &EditorSelectionDragState,
     event: EditorSurfacePointerEvent,
+    config: &MouseSelectionConfig,
 ) -> Option<EditorPointerSelectionUpdate> {
     let Some(hit_test) = hit_test_document_position(event, gutter_columns, line_cache, document)
     else {
@@ -206,13 +371,23 @@ pub fn begin_pointer_selection_at_event(
         return None;
     };
 
-    let selection = begin_pointer_selection(
-        document,
-        view_id,
-        drag_state,
-        hit_test.char_idx,
-        event.modifiers.shift,
-    );
+    let selection = match EditorSelectionGranularity::from_click_count(event.click_count) {
+        EditorSelectionGranularity::Char => begin_pointer_selection(
+            document,
+            view_id,
+            drag_state,
+            hit_test.char_idx,
+            event.modifiers.shift,
+        ),
+        granularity => begin_unit_pointer_selection(
+            document,
+            view_id,
+            drag_state,
+            hit_test.char_idx,
+            granularity,
+            config,
+        ),
+    };
 
     Some(EditorPointerSelectionUpdate {
         hit_test,
@@ -227,9 +402,11 @@ pub fn update_pointer_selection_at_event(
     line_cache: &LineLayoutCache,
     drag_state: &EditorSelectionDragState,
     event: EditorSurfacePointerEvent,
+    config: &MouseSelectionConfig,
 ) -> Option<EditorPointerSelectionUpdate> {
     let hit_test = hit_test_document_position(event, gutter_columns, line_cache, document)?;
-    let selection = update_pointer_selection(document, view_id, drag_state, hit_test.char_idx)?;
+    let selection =
+        update_pointer_selection(document, view_id, drag_state, hit_test.char_idx, config)?;
 
     Some(EditorPointerSelectionUpdate {
         hit_test,
@@ -251,6 +428,7 @@ pub fn begin_editor_pointer_selection_at_event(
     line_cache: &LineLayoutCache,
     drag_state: &EditorSelectionDragState,
     event: EditorSurfacePointerEvent,
+    config: &MouseSelectionConfig,
 ) -> Option<EditorPointerSelectionUpdate> {
     let Some(gutter_columns) = editor_gutter_columns(editor, doc_id, view_id)
         .map(|columns| columns.saturating_add(extra_gutter_columns))
@@ -267,6 +445,7 @@ pub fn begin_editor_pointer_selection_at_event(
         line_cache,
         drag_state,
         event,
+        config,
     )
 }
 
@@ -278,6 +457,7 @@ pub fn update_editor_pointer_selection_at_event(
     line_cache: &LineLayoutCache,
     drag_state: &EditorSelectionDragState,
     event: EditorSurfacePointerEvent,
+    config: &MouseSelectionConfig,
 ) -> Option<EditorPointerSelectionUpdate> {
     let gutter_columns =
         editor_gutter_columns(editor, doc_id, view_id)?.saturating_add(extra_gutter_columns);
@@ -290,6 +470,7 @@ pub fn update_editor_pointer_selection_at_event(
         line_cache,
         drag_state,
         event,
+        config,
     )
 }
 
@@ -301,14 +482,19 @@ pub fn update_editor_pointer_selection_at_event_outcome(
     line_cache: &LineLayoutCache,
     drag_state: &EditorSelectionDragState,
     event: EditorSurfacePointerEvent,
+    config: &MouseSelectionConfig,
 ) -> Option<(EditorPointerSelectionUpdate, bool)> {
     let gutter_columns =
         editor_gutter_columns(editor, doc_id, view_id)?.saturating_add(extra_gutter_columns);
     let document = editor.document_mut(doc_id)?;
     let hit_test = hit_test_document_position(event, gutter_columns, line_cache, document)?;
-    let anchor = drag_state.anchor()?;
-    let (selection, update) =
-        selection_for_range(document.text().len_chars(), anchor, hit_test.char_idx);
+    let (anchor, head) = drag_selection_range(
+        document.text().slice(..),
+        drag_state,
+        hit_test.char_idx,
+        config,
+    )?;
+    let (selection, update) = selection_for_range(document.text().len_chars(), anchor, head);
     let selection = selection.ensure_invariants(document.text().slice(..));
     let changed = document.selection(view_id) != &selection;
 
@@ -327,9 +513,13 @@ pub fn update_editor_pointer_selection_at_event_outcome(
 
 #[cfg(test)]
 mod tests {
+    use helix_core::Rope;
+    use nucleotide_types::MouseSelectionConfig;
+
     use super::{
-        EditorSelectionDragState, EditorSelectionUpdate, pointer_selection_anchor,
-        selection_for_range,
+        EditorSelectionDragState, EditorSelectionGranularity, EditorSelectionUnit,
+        EditorSelectionUpdate, drag_selection_range, line_range_at, pointer_selection_anchor,
+        selection_for_range, word_range_at,
     };
 
     #[test]
@@ -370,4 +560,92 @@ mod tests {
         state.clear();
         assert_eq!(state.anchor(), None);
     }
+
+    #[test]
+    fn click_count_picks_granularity() {
+        assert_eq!(
+            EditorSelectionGranularity::from_click_count(1),
+            EditorSelectionGranularity::Char
+        );
+        assert_eq!(
+            EditorSelectionGranularity::from_click_count(2),
+            EditorSelectionGranularity::Word
+        );
+        assert_eq!(
+            EditorSelectionGranularity::from_click_count(4),
+            EditorSelectionGranularity::Line
+        );
+    }
+
+    #[test]
+    fn double_click_selects_runs_of_one_character_class() {
+        let rope = Rope::from("let foo_bar = kebab-case;\n\nend");
+        let text = rope.slice(..);
+        let config = MouseSelectionConfig::default();
+
+        assert_eq!(word_range_at(text, 5, &config), (4, 11));
+        assert_eq!(word_range_at(text, 11, &config), (11, 12));
+        assert_eq!(word_range_at(text, 14, &config), (14, 19));
+        // Past the end of a line, the run before the line ending.
+        assert_eq!(word_range_at(text, 25, &config), (24, 25));
+        // An empty line selects its line ending.
+        assert_eq!(word_range_at(text, 26, &config), (26, 27));
+        assert_eq!(word_range_at(text, 40, &config), (27, 30));
+
+        let kebab = MouseSelectionConfig {
+            word_characters: "-".into(),
+            ..MouseSelectionConfig::default()
+        };
+        assert_eq!(word_range_at(text, 16, &kebab), (14, 24));
+    }
+
+    #[test]
+    fn triple_click_selects_the_line() {
+        let rope = Rope::from("one\ntwo\r\nthree");
+        let text = rope.slice(..);
+
+        assert_eq!(line_range_at(text, 1, true), (0, 4));
+        assert_eq!(line_range_at(text, 5, true), (4, 9));
+        assert_eq!(line_range_at(text, 5, false), (4, 7));
+        assert_eq!(line_range_at(text, 20, true), (9, 14));
+    }
+
+    #[test]
+    fn drag_after_multi_click_extends_by_whole_units() {
+        let rope = Rope::from("alpha beta gamma\nnext line\n");
+        let text = rope.slice(..);
+        let config = MouseSelectionConfig::default();
+        let state = EditorSelectionDragState::default();
+        state.set_unit(EditorSelectionUnit {
+            granularity: EditorSelectionGranularity::Word,
+            start: 6,
+            end: 10,
+        });
+
+        assert_eq!(
+            drag_selection_range(text, &state, 13, &config),
+            Some((6, 16))
+        );
+        assert_eq!(
+            drag_selection_range(text, &state, 2, &config),
+            Some((10, 0))
+        );
+        assert_eq!(
+            drag_selection_range(text, &state, 8, &config),
+            Some((6, 10))
+        );
+
+        state.set_unit(EditorSelectionUnit {
+            granularity: EditorSelectionGranularity::Line,
+            start: 0,
+            end: 17,
+        });
+        assert_eq!(
+            drag_selection_range(text, &state, 20, &config),
+            Some((0, 27))
+        );
+
+        state.set_anchor(3);
+        assert_eq!(drag_selection_range(text, &state, 8, &config), Some((3, 8)));
+    }
 }
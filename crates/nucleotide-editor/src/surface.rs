@@ -13,7 +13,7 @@ use gpui::{
 use crate::{
     EditorScrollbar, EditorScrollbarState, EditorViewport, LineLayoutCache, ViewportScrollUpdate,
 };
-use nucleotide_types::scrollbar::SCROLLBAR_THICKNESS;
+use nucleotide_types::{ScrollSettings, scrollbar::SCROLLBAR_THICKNESS};
 
 type ScrollCallback = Rc<dyn Fn(&EditorViewport, ViewportScrollUpdate, &mut App)>;
 type PointerCallback = Rc<dyn Fn(EditorSurfacePointerEvent, &mut App) -> bool>;
@@ -65,6 +65,8 @@ pub struct EditorSurfacePointerEvent {
     pub bounds: Bounds<Pixels>,
     pub line_height: Pixels,
     pub cell_width: Pixels,
+    /// Clicks in the current multi-click, 1 for drags
+    pub click_count: usize,
 }
 
 pub struct EditorSurface {
@@ -212,6 +214,7 @@ impl EditorSurface {
         bounds: Bounds<Pixels>,
         position: Point<Pixels>,
         modifiers: Modifiers,
+        click_count: usize,
     ) -> EditorSurfacePointerEvent {
         let metrics = metrics.get();
         EditorSurfacePointerEvent {
@@ -220,6 +223,7 @@ impl EditorSurface {
             bounds,
             line_height: metrics.line_height,
             cell_width: metrics.cell_width,
+            click_count,
         }
     }
 }
@@ -266,7 +270,11 @@ impl RenderOnce for EditorSurface {
 
             let line_height = metrics.get().line_height;
             let raw_delta = event.delta.pixel_delta(line_height);
-            let delta = point(raw_delta.x, raw_delta.y);
+            let wheel = cx
+                .try_global::<ScrollSettings>()
+                .map(|settings| settings.editor)
+                .unwrap_or_default();
+            let delta = wheel.adjust_pixel_delta(point(raw_delta.x, raw_delta.y), &event.modifiers);
             let scroll_update = viewport.scroll_by_delta(delta);
 
             if !scroll_update.changed {
@@ -300,7 +308,13 @@ impl RenderOnce for EditorSurface {
                 }
 
                 let changed = on_mouse_down(
-                    Self::surface_event(metrics.clone(), bounds, event.position, event.modifiers),
+                    Self::surface_event(
+                        metrics.clone(),
+                        bounds,
+                        event.position,
+                        event.modifiers,
+                        event.click_count,
+                    ),
                     cx,
                 );
 
@@ -328,7 +342,13 @@ impl RenderOnce for EditorSurface {
                 }
 
                 let changed = on_mouse_drag(
-                    Self::surface_event(metrics.clone(), bounds, event.position, event.modifiers),
+                    Self::surface_event(
+                        metrics.clone(),
+                        bounds,
+                        event.position,
+                        event.modifiers,
+                        1,
+                    ),
                     cx,
                 );
 
@@ -354,7 +374,13 @@ impl RenderOnce for EditorSurface {
                 }
 
                 let changed = on_mouse_up_inside(
-                    Self::surface_event(metrics.clone(), bounds, event.position, event.modifiers),
+                    Self::surface_event(
+                        metrics.clone(),
+                        bounds,
+                        event.position,
+                        event.modifiers,
+                        event.click_count,
+                    ),
                     cx,
                 );
 
@@ -375,7 +401,13 @@ impl RenderOnce for EditorSurface {
                 };
 
                 let changed = on_mouse_up_out(
-                    Self::surface_event(metrics.clone(), bounds, event.position, event.modifiers),
+                    Self::surface_event(
+                        metrics.clone(),
+                        bounds,
+                        event.position,
+                        event.modifiers,
+                        event.click_count,
+                    ),
                     cx,
                 );
 
@@ -637,6 +669,51 @@ mod tests {
         assert!(viewport.scroll_position().y > px(0.0));
     }
 
+    #[gpui::test]
+    fn editor_surface_applies_wheel_scroll_settings(cx: &mut TestAppContext) {
+        let view_entity_id = cx.update(|cx| {
+            cx.set_global(ScrollSettings {
+                editor: nucleotide_types::WheelScrollConfig {
+                    lines_per_tick: 2.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+            let entity: Entity<Empty> = cx.new(|_| Empty);
+            entity.entity_id()
+        });
+
+        let mut viewport = EditorViewport::new(px(20.0));
+        viewport.set_layout(px(20.0), size(px(100.0), px(200.0)), 50);
+        let metrics = EditorSurfaceMetrics::new(px(20.0), px(8.0));
+
+        let window = cx.add_empty_window();
+        window.draw(
+            point(px(0.0), px(0.0)),
+            size(px(112.0), px(200.0)),
+            |_, _| {
+                EditorSurface::new(
+                    view_entity_id,
+                    viewport.clone(),
+                    metrics.clone(),
+                    EditorScrollbarState::default(),
+                    EditorScrollbarState::default(),
+                    div().size_full(),
+                )
+                .into_element()
+            },
+        );
+
+        window.simulate_event(ScrollWheelEvent {
+            position: point(px(10.0), px(10.0)),
+            delta: ScrollDelta::Pixels(point(px(0.0), px(-40.0))),
+            modifiers: gpui::Modifiers::none(),
+            touch_phase: TouchPhase::Moved,
+        });
+
+        assert_eq!(viewport.scroll_position().y, px(80.0));
+    }
+
     struct SurfacePointerFocusHost {
         view_entity_id: EntityId,
         focus: FocusHandle,
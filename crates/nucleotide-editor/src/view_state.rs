@@ -10,6 +10,7 @@ use std::{
 use gpui::{Pixels, Point, Size, TextStyle, TextSystem, px};
 use helix_view::{DocumentId, Editor, Theme, ViewId};
 use nucleotide_logging::PerfTimer;
+use nucleotide_types::MouseSelectionConfig;
 
 use crate::{
     CursorOverlayPlan, EditorCursorReveal, EditorOverlayState, EditorPointerSelectionOutcome,
@@ -29,6 +30,7 @@ pub struct EditorViewState {
     vertical_scrollbar_state: EditorScrollbarState,
     horizontal_scrollbar_state: EditorScrollbarState,
     selection_drag_state: EditorSelectionDragState,
+    mouse_selection: Rc<RefCell<MouseSelectionConfig>>,
     overlay_state: EditorOverlayState,
     line_height: Rc<Cell<Pixels>>,
     gutter_extra_columns: Rc<Cell<u16>>,
@@ -77,6 +79,7 @@ impl EditorViewState {
             vertical_scrollbar_state: EditorScrollbarState::default(),
             horizontal_scrollbar_state: EditorScrollbarState::default(),
             selection_drag_state: EditorSelectionDragState::default(),
+            mouse_selection: Rc::new(RefCell::new(MouseSelectionConfig::default())),
             overlay_state: EditorOverlayState::new(),
             line_height: Rc::new(Cell::new(line_height)),
             gutter_extra_columns: Rc::new(Cell::new(0)),
@@ -336,6 +339,7 @@ impl EditorViewState {
             &line_cache,
             &self.selection_drag_state,
             event,
+            &self.mouse_selection.borrow(),
         )
     }
 
@@ -355,6 +359,7 @@ impl EditorViewState {
             &line_cache,
             &self.selection_drag_state,
             event,
+            &self.mouse_selection.borrow(),
         )
     }
 
@@ -426,6 +431,11 @@ impl EditorViewState {
         }
     }
 
+    /// Word characters and line rules used by double- and triple-clicks.
+    pub fn set_mouse_selection_config(&self, config: MouseSelectionConfig) {
+        *self.mouse_selection.borrow_mut() = config;
+    }
+
     pub fn clear_pointer_selection(&self) {
         self.selection_drag_state.clear();
     }
@@ -446,6 +456,7 @@ impl EditorViewState {
             &line_cache,
             &self.selection_drag_state,
             event,
+            &self.mouse_selection.borrow(),
         )
     }
 
@@ -568,6 +579,7 @@ mod tests {
             bounds: Bounds::new(point(px(0.0), px(0.0)), size(px(80.0), px(20.0))),
             line_height: px(20.0),
             cell_width: px(8.0),
+            click_count: 1,
         }
     }
 
@@ -18,6 +18,12 @@ use tokio::time::{Duration, Instant, timeout};
 
 const DIRECTORY_SHELL_CAPTURE_TIMEOUT_SECONDS: u64 = 10;
 const PROCESS_SHELL_CAPTURE_TIMEOUT_SECONDS: u64 = 10;
+/// How long a captured directory shell environment is reused before the shell runs again.
+const DIRECTORY_ENVIRONMENT_CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+/// Failed or timed out captures are retried sooner than successful ones.
+const FALLBACK_ENVIRONMENT_CACHE_TTL: Duration = Duration::from_secs(60);
+/// Printed before the environment dump so greetings and prompt hook output can be skipped.
+const ENVIRONMENT_OUTPUT_MARKER: &str = "__NUCLEOTIDE_ENVIRONMENT__";
 
 /// Error types for shell environment operations
 #[derive(Debug, thiserror::Error)]
@@ -63,6 +69,7 @@ pub struct CachedEnvironment {
     pub origin: EnvironmentOrigin,
     pub directory: PathBuf,
     native_watch_state: Option<Vec<WatchedFileState>>,
+    captured_at: Instant,
 }
 
 /// Central environment management system following Zed's ProjectEnvironment pattern
@@ -82,6 +89,12 @@ pub struct ProjectEnvironment {
 
     /// Semaphore to limit concurrent shell executions
     shell_execution_semaphore: Arc<Semaphore>,
+
+    /// Timeout for directory shell captures before falling back to the baseline
+    shell_capture_timeout_seconds: u64,
+
+    /// How long shell captured environments stay cached
+    cache_ttl: Duration,
 }
 
 impl ProjectEnvironment {
@@ -100,9 +113,24 @@ impl ProjectEnvironment {
             environment_errors: Arc::new(RwLock::new(HashMap::new())),
             process_shell_environment: Arc::new(RwLock::new(None)),
             shell_execution_semaphore: Arc::new(Semaphore::new(3)), // Limit concurrent shell executions
+            shell_capture_timeout_seconds: DIRECTORY_SHELL_CAPTURE_TIMEOUT_SECONDS,
+            cache_ttl: DIRECTORY_ENVIRONMENT_CACHE_TTL,
         }
     }
 
+    /// Override how long a directory shell capture may run before the inherited
+    /// environment is used instead
+    pub fn with_shell_capture_timeout(mut self, timeout_seconds: u64) -> Self {
+        self.shell_capture_timeout_seconds = timeout_seconds.max(1);
+        self
+    }
+
+    /// Override how long captured directory environments are reused
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
     /// Get environment for directory following priority: CLI > directory shell > process
     #[instrument(skip(self), fields(directory = %directory.display()))]
     pub async fn get_environment_for_directory(
@@ -133,7 +161,7 @@ impl ProjectEnvironment {
             let cache = self.directory_environments.read().await;
             cache.get(directory).cloned()
         } {
-            if cached_environment_is_current(&cached) {
+            if cached_environment_is_current(&cached, self.cache_ttl) {
                 debug!("Using cached directory environment");
                 return Ok(cached.environment);
             }
@@ -157,7 +185,7 @@ impl ProjectEnvironment {
             let cache = self.directory_environments.read().await;
             cache.get(&canonical_dir).cloned()
         } {
-            if cached_environment_is_current(&cached) {
+            if cached_environment_is_current(&cached, self.cache_ttl) {
                 debug!("Using cached directory environment");
                 return Ok(cached.environment);
             }
@@ -182,6 +210,7 @@ impl ProjectEnvironment {
                     origin: EnvironmentOrigin::NativeFlake,
                     directory: canonical_dir.clone(),
                     native_watch_state: Some(native_env.watch_state.clone()),
+                    captured_at: Instant::now(),
                 };
 
                 {
@@ -243,6 +272,7 @@ impl ProjectEnvironment {
                 origin: EnvironmentOrigin::DirectoryShell,
                 directory: directory.to_path_buf(),
                 native_watch_state: None,
+                captured_at: Instant::now(),
             };
 
             {
@@ -408,7 +438,7 @@ impl ProjectEnvironment {
         match capture_shell_environment_with_timeout(
             &shell,
             directory,
-            DIRECTORY_SHELL_CAPTURE_TIMEOUT_SECONDS,
+            self.shell_capture_timeout_seconds,
             Some(&baseline_env),
             cancellation,
         )
@@ -433,6 +463,7 @@ impl ProjectEnvironment {
                     origin: EnvironmentOrigin::DirectoryShell,
                     directory: directory.clone(),
                     native_watch_state: None,
+                    captured_at: Instant::now(),
                 };
 
                 {
@@ -489,6 +520,7 @@ impl ProjectEnvironment {
                     origin: EnvironmentOrigin::Process,
                     directory: directory.clone(),
                     native_watch_state: None,
+                    captured_at: Instant::now(),
                 };
                 self.directory_environments
                     .write()
@@ -590,7 +622,7 @@ impl ProjectEnvironment {
             let cache = self.directory_environments.read().await;
             cache
                 .get(directory)
-                .filter(|cached| cached_environment_is_current(cached))
+                .filter(|cached| cached_environment_is_current(cached, self.cache_ttl))
                 .map(|cached| cached.origin.clone())
         } {
             return Some(origin);
@@ -603,7 +635,7 @@ impl ProjectEnvironment {
         let cache = self.directory_environments.read().await;
         cache
             .get(&canonical_dir)
-            .filter(|cached| cached_environment_is_current(cached))
+            .filter(|cached| cached_environment_is_current(cached, self.cache_ttl))
             .map(|cached| cached.origin.clone())
     }
 
@@ -688,14 +720,18 @@ enum WatchedPathState {
     Missing,
 }
 
-fn cached_environment_is_current(cached: &CachedEnvironment) -> bool {
+fn cached_environment_is_current(cached: &CachedEnvironment, ttl: Duration) -> bool {
     match cached.origin {
         EnvironmentOrigin::NativeFlake => cached
             .native_watch_state
             .as_deref()
             .is_some_and(watched_files_are_current),
-        EnvironmentOrigin::Cli | EnvironmentOrigin::DirectoryShell | EnvironmentOrigin::Process => {
-            true
+        EnvironmentOrigin::Cli => true,
+        // Shell hooks (direnv, asdf, mise) can change without touching anything
+        // we watch, so shell captures expire instead.
+        EnvironmentOrigin::DirectoryShell => cached.captured_at.elapsed() < ttl,
+        EnvironmentOrigin::Process => {
+            cached.captured_at.elapsed() < ttl.min(FALLBACK_ENVIRONMENT_CACHE_TTL)
         }
     }
 }
@@ -1488,7 +1524,10 @@ pub mod shell_command_builder {
         match shell_name {
             "fish" => {
                 // Fish requires special handling to trigger hooks
-                command_string = format!("cd {}; emit fish_prompt; env -0", escaped_dir);
+                command_string = format!(
+                    "cd {}; emit fish_prompt; printf '%s\\0' {}; env -0",
+                    escaped_dir, ENVIRONMENT_OUTPUT_MARKER
+                );
                 command.arg("-l").arg("-i").arg("-c").arg(command_string);
             }
             "tcsh" | "csh" => {
@@ -1499,7 +1538,12 @@ pub mod shell_command_builder {
             }
             "nu" => {
                 // Nushell does not allow non-interactive login shells. Use eval mode.
-                let nu_command = format!("cd {}; ^env -0; exit", escaped_dir);
+                // External `env` applies ENV_CONVERSIONS, so list values such as
+                // PATH are written in their exported string form.
+                let nu_command = format!(
+                    "cd {}; ^printf '%s\\0' {}; ^env -0; exit",
+                    escaped_dir, ENVIRONMENT_OUTPUT_MARKER
+                );
                 command.arg("-l").arg("-e").arg(nu_command);
             }
             _ => {
@@ -1720,6 +1764,7 @@ pub fn parse_shell_environment(
     output: &[u8],
 ) -> Result<HashMap<String, String>, ShellEnvironmentError> {
     let output_str = String::from_utf8_lossy(output);
+    let output_str = strip_environment_preamble(&output_str);
     let mut env_map = HashMap::new();
 
     if output_str.contains('\0') {
@@ -1741,6 +1786,15 @@ pub fn parse_shell_environment(
     Ok(env_map)
 }
 
+/// Drops anything a shell printed before the environment marker, such as a fish
+/// greeting, nushell banner or prompt hook output.
+fn strip_environment_preamble(output: &str) -> &str {
+    let marker = format!("{ENVIRONMENT_OUTPUT_MARKER}\0");
+    output
+        .rfind(&marker)
+        .map_or(output, |start| &output[start + marker.len()..])
+}
+
 fn insert_env_line(env_map: &mut HashMap<String, String>, line: &str) {
     if line.is_empty() {
         return;
@@ -2149,13 +2203,20 @@ mod tests {
             origin: EnvironmentOrigin::NativeFlake,
             directory: temp_dir.path().to_path_buf(),
             native_watch_state: Some(watch_state),
+            captured_at: Instant::now(),
         };
 
-        assert!(cached_environment_is_current(&cached));
+        assert!(cached_environment_is_current(
+            &cached,
+            DIRECTORY_ENVIRONMENT_CACHE_TTL
+        ));
 
         std::fs::write(envrc_path, "use flake .#dev\n").unwrap();
 
-        assert!(!cached_environment_is_current(&cached));
+        assert!(!cached_environment_is_current(
+            &cached,
+            DIRECTORY_ENVIRONMENT_CACHE_TTL
+        ));
     }
 
     #[tokio::test]
@@ -2216,6 +2277,7 @@ mod tests {
             origin: EnvironmentOrigin::NativeFlake,
             directory: canonical_dir.clone(),
             native_watch_state: Some(snapshot_watched_files(vec![envrc_path])),
+            captured_at: Instant::now(),
         };
 
         project_env
@@ -2243,6 +2305,7 @@ mod tests {
             origin: EnvironmentOrigin::Process,
             directory: raw_dir.clone(),
             native_watch_state: None,
+            captured_at: Instant::now(),
         };
 
         project_env
@@ -2335,6 +2398,100 @@ esac
         assert!(calls.contains("wipe-history"));
     }
 
+    #[cfg(unix)]
+    fn write_fake_shell(dir: &Path, name: &str, script: &str) -> Option<PathBuf> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let shell = dir.join(name);
+        std::fs::write(&shell, format!("#!/bin/sh\n{script}\n")).unwrap();
+        let mut permissions = std::fs::metadata(&shell).unwrap().permissions();
+        permissions.set_mode(0o755);
+        std::fs::set_permissions(&shell, permissions).unwrap();
+        generated_executable_is_allowed(&shell).then_some(shell)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_fish_capture_skips_greeting_before_marker() {
+        let temp_dir = executable_tempdir();
+        let Some(fake_fish) = write_fake_shell(
+            temp_dir.path(),
+            "fish",
+            &format!(
+                "printf 'Welcome to fish\\n=oops\\0'; printf '%s\\0' {ENVIRONMENT_OUTPUT_MARKER}; printf 'FOO=bar\\0PATH=/fish/bin\\0'"
+            ),
+        ) else {
+            return;
+        };
+
+        let env = capture_shell_environment_with_timeout(
+            &fake_fish.to_string_lossy(),
+            temp_dir.path(),
+            5,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(env.get("FOO"), Some(&"bar".to_string()));
+        assert_eq!(env.get("PATH"), Some(&"/fish/bin".to_string()));
+        assert_eq!(env.len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_shell_capture_times_out() {
+        let temp_dir = executable_tempdir();
+        let Some(slow_shell) = write_fake_shell(temp_dir.path(), "bash", "sleep 5") else {
+            return;
+        };
+
+        let started = Instant::now();
+        let error = capture_shell_environment_with_timeout(
+            &slow_shell.to_string_lossy(),
+            temp_dir.path(),
+            1,
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(error, ShellEnvironmentError::Timeout(1)));
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+
+    #[test]
+    fn test_shell_captured_environments_expire_after_ttl() {
+        let captured = |origin, age| CachedEnvironment {
+            environment: HashMap::new(),
+            origin,
+            directory: PathBuf::from("/project"),
+            native_watch_state: None,
+            captured_at: Instant::now() - age,
+        };
+        let ttl = Duration::from_secs(600);
+
+        assert!(cached_environment_is_current(
+            &captured(EnvironmentOrigin::DirectoryShell, Duration::from_secs(120)),
+            ttl
+        ));
+        assert!(!cached_environment_is_current(
+            &captured(EnvironmentOrigin::DirectoryShell, Duration::from_secs(601)),
+            ttl
+        ));
+        // Fallbacks after a failed capture are retried sooner.
+        assert!(!cached_environment_is_current(
+            &captured(EnvironmentOrigin::Process, Duration::from_secs(120)),
+            ttl
+        ));
+        assert!(cached_environment_is_current(
+            &captured(EnvironmentOrigin::Cli, Duration::from_secs(6000)),
+            ttl
+        ));
+    }
+
     #[test]
     fn test_resolve_program_from_env_path_uses_baseline_path() {
         let empty_bin = tempfile::tempdir().unwrap();
@@ -2436,6 +2593,11 @@ esac
         assert!(fish_args.contains(&"-l".to_string()));
         assert!(fish_args.contains(&"-i".to_string()));
         assert!(fish_args.contains(&"-c".to_string()));
+        assert!(
+            fish_args
+                .iter()
+                .any(|arg| arg.contains(ENVIRONMENT_OUTPUT_MARKER))
+        );
 
         let nu = shell_command_builder::build_environment_capture_command(
             "/opt/homebrew/bin/nu",
@@ -2449,6 +2611,11 @@ esac
         assert!(nu_args.contains(&"-l".to_string()));
         assert!(nu_args.contains(&"-e".to_string()));
         assert!(!nu_args.contains(&"-i".to_string()));
+        assert!(
+            nu_args
+                .iter()
+                .any(|arg| arg.contains(ENVIRONMENT_OUTPUT_MARKER))
+        );
 
         let csh = shell_command_builder::build_environment_capture_command(
             "/bin/tcsh",
@@ -2503,4 +2670,15 @@ esac
         assert_eq!(parsed.get("HOME"), Some(&"/Users/test".to_string()));
         assert_eq!(parsed.get("SHELL"), Some(&"/bin/bash".to_string()));
     }
+    #[test]
+    fn test_environment_parsing_skips_nushell_banner() {
+        let output = format!(
+            "Welcome to Nushell\n\u{1b}[32mstartup time: 12ms\n{ENVIRONMENT_OUTPUT_MARKER}\0PATH=/nu/bin:/usr/bin\0EDITOR=hx\0"
+        );
+        let parsed = parse_shell_environment(output.as_bytes()).unwrap();
+
+        assert_eq!(parsed.get("PATH"), Some(&"/nu/bin:/usr/bin".to_string()));
+        assert_eq!(parsed.get("EDITOR"), Some(&"hx".to_string()));
+        assert_eq!(parsed.len(), 2);
+    }
 }
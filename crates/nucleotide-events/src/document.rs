@@ -10,6 +10,18 @@ pub struct DocumentLineChange {
     pub new_lines: Range<usize>,
 }
 
+/// Selection and scroll position of a document in a view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentViewState {
+    /// Selection ranges as `(anchor, head)`
+    pub ranges: Vec<(helix_core::Position, helix_core::Position)>,
+    pub primary_index: usize,
+    /// Cursor of the primary range
+    pub cursor: helix_core::Position,
+    /// Line at the top of the view
+    pub top_line: usize,
+}
+
 /// Document domain events - covers document lifecycle, content changes, and save operations
 /// Following event sourcing principles: all events are immutable facts about what has happened
 #[derive(Debug, Clone)]
@@ -33,6 +45,9 @@ pub enum Event {
     Closed {
         doc_id: DocumentId,
         was_modified: bool,
+        path: Option<PathBuf>,
+        /// Selections and scroll position when the document was closed
+        view_state: Option<DocumentViewState>,
     },
 
     /// Document saved to disk
@@ -12,6 +12,10 @@ pub mod terminal;
 pub mod ui;
 pub mod workspace;
 
+// Deterministic test doubles for event-driven flows
+#[cfg(any(test, feature = "test-support"))]
+pub mod testing;
+
 // Re-export V2 bounded context events
 pub mod v2 {
     pub use crate::completion;
@@ -148,6 +148,12 @@ pub enum ProjectLspCommand {
         span: Span,
     },
 
+    /// End the active project session and stop its servers without opening another.
+    CloseProjectSession {
+        response: oneshot::Sender<Result<(), ProjectLspCommandError>>,
+        span: Span,
+    },
+
     /// Detect project and start servers if needed
     DetectAndStartProject {
         workspace_root: PathBuf,
@@ -77,6 +77,8 @@ pub struct TaskTemplate {
     pub command: CommandSpec,
     pub source: Option<SourceLocation>,
     pub tags: Vec<String>,
+    /// What the task does, such as a Makefile comment, shown beside its label
+    pub description: Option<String>,
 }
 
 /// Fully resolved task ready for execution.
@@ -98,6 +100,10 @@ impl ResolvedTask {
     pub fn source(&self) -> Option<&SourceLocation> {
         self.template.source.as_ref()
     }
+
+    pub fn description(&self) -> Option<&str> {
+        self.template.description.as_deref()
+    }
 }
 
 /// Run domain events.
@@ -15,7 +15,13 @@ pub enum Event {
         id: TerminalId,
         cwd: Option<PathBuf>,
         shell: Option<String>,
+        /// Arguments passed to `shell`
+        args: Vec<String>,
         env: Vec<(String, String)>,
+        /// Terminal profile the session was created from
+        profile: Option<String>,
+        /// Lines kept above the viewport; unset uses the emulator default
+        scrollback_lines: Option<usize>,
     },
 
     /// Request to spawn a specific command in a terminal session
@@ -25,6 +31,7 @@ pub enum Event {
         program: String,
         args: Vec<String>,
         env: Vec<(String, String)>,
+        scrollback_lines: Option<usize>,
     },
 
     /// Terminal viewport resized with explicit cell metrics.
@@ -0,0 +1,375 @@
+// ABOUTME: Deterministic in-memory event bus for unit testing event-driven flows
+// ABOUTME: Records events in order, asserts on sequences and drives debounced handlers on a virtual clock
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use tokio::sync::{broadcast, mpsc};
+
+/// An event captured by [`TestEventBus`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEvent<E> {
+    /// Position in publish order, starting at 0
+    pub sequence: usize,
+    /// Virtual time at which the event was published
+    pub at: Duration,
+    pub event: E,
+}
+
+/// A named predicate for [`TestEventBus::assert_in_order`].
+pub type EventMatcher<'a, E> = (&'a str, &'a dyn Fn(&E) -> bool);
+
+type Handler<E> = Box<dyn FnMut(&E, &TestEventBus<E>) + Send>;
+type BatchHandler<E> = Box<dyn FnMut(Vec<E>, &TestEventBus<E>) + Send>;
+
+struct DebouncedHandler<E> {
+    delay: Duration,
+    filter: Box<dyn Fn(&E) -> bool + Send>,
+    pending: Vec<E>,
+    deadline: Option<Duration>,
+    handler: Option<BatchHandler<E>>,
+}
+
+struct BusState<E> {
+    now: Duration,
+    recorded: Vec<RecordedEvent<E>>,
+    handlers: Vec<Option<Handler<E>>>,
+    debounced: Vec<DebouncedHandler<E>>,
+    queue: VecDeque<E>,
+    dispatching: bool,
+}
+
+/// Single-threaded event bus for tests.
+///
+/// Every published event is recorded with its publish order and the bus's
+/// virtual time, then handed to the registered handlers. Handlers may
+/// publish further events; those are delivered after the current event, so
+/// dispatch order is always breadth-first and reproducible. Debounced
+/// handlers only fire when [`TestEventBus::advance`] moves the virtual
+/// clock past their quiet period, so tests never sleep.
+pub struct TestEventBus<E> {
+    state: Arc<Mutex<BusState<E>>>,
+}
+
+impl<E> Clone for TestEventBus<E> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+impl<E: Clone + Debug + Send + 'static> Default for TestEventBus<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Clone + Debug + Send + 'static> TestEventBus<E> {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(BusState {
+                now: Duration::ZERO,
+                recorded: Vec::new(),
+                handlers: Vec::new(),
+                debounced: Vec::new(),
+                queue: VecDeque::new(),
+                dispatching: false,
+            })),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, BusState<E>> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Calls `handler` for every event published from now on.
+    pub fn subscribe(&self, handler: impl FnMut(&E, &TestEventBus<E>) + Send + 'static) {
+        self.state().handlers.push(Some(Box::new(handler)));
+    }
+
+    /// Collects events matching `filter` and calls `handler` with the batch
+    /// once `delay` of virtual time has passed without another match.
+    pub fn subscribe_debounced(
+        &self,
+        delay: Duration,
+        filter: impl Fn(&E) -> bool + Send + 'static,
+        handler: impl FnMut(Vec<E>, &TestEventBus<E>) + Send + 'static,
+    ) {
+        self.state().debounced.push(DebouncedHandler {
+            delay,
+            filter: Box::new(filter),
+            pending: Vec::new(),
+            deadline: None,
+            handler: Some(Box::new(handler)),
+        });
+    }
+
+    /// Records `event` and delivers it to the handlers.
+    pub fn publish(&self, event: E) {
+        {
+            let mut state = self.state();
+            state.queue.push_back(event);
+            if state.dispatching {
+                return;
+            }
+            state.dispatching = true;
+        }
+
+        loop {
+            let event = {
+                let mut state = self.state();
+                let Some(event) = state.queue.pop_front() else {
+                    state.dispatching = false;
+                    return;
+                };
+                let record = RecordedEvent {
+                    sequence: state.recorded.len(),
+                    at: state.now,
+                    event: event.clone(),
+                };
+                state.recorded.push(record);
+                let now = state.now;
+                for debounced in &mut state.debounced {
+                    if (debounced.filter)(&event) {
+                        debounced.pending.push(event.clone());
+                        debounced.deadline = Some(now + debounced.delay);
+                    }
+                }
+                event
+            };
+
+            let handler_count = self.state().handlers.len();
+            for index in 0..handler_count {
+                let Some(mut handler) = self.state().handlers[index].take() else {
+                    continue;
+                };
+                handler(&event, self);
+                self.state().handlers[index] = Some(handler);
+            }
+        }
+    }
+
+    /// Publishes everything currently buffered in `receiver`, for flows that
+    /// emit on a broadcast channel.
+    pub fn record_broadcast(&self, receiver: &mut broadcast::Receiver<E>) -> usize {
+        let mut count = 0;
+        loop {
+            match receiver.try_recv() {
+                Ok(event) => {
+                    self.publish(event);
+                    count += 1;
+                }
+                Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                    panic!("test event receiver lagged and lost {skipped} events");
+                }
+                Err(_) => return count,
+            }
+        }
+    }
+
+    /// Publishes everything currently buffered in `receiver`, for flows that
+    /// emit on an unbounded mpsc channel.
+    pub fn record_unbounded(&self, receiver: &mut mpsc::UnboundedReceiver<E>) -> usize {
+        let mut count = 0;
+        while let Ok(event) = receiver.try_recv() {
+            self.publish(event);
+            count += 1;
+        }
+        count
+    }
+
+    /// The virtual time elapsed since the bus was created.
+    pub fn now(&self) -> Duration {
+        self.state().now
+    }
+
+    /// Moves the virtual clock forward by `by`, firing debounced handlers
+    /// whose quiet period ends within that span in deadline order.
+    pub fn advance(&self, by: Duration) {
+        let target = self.now() + by;
+        loop {
+            let due = {
+                let mut state = self.state();
+                let due = state
+                    .debounced
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, debounced)| {
+                        debounced
+                            .deadline
+                            .filter(|deadline| *deadline <= target)
+                            .map(|deadline| (deadline, index))
+                    })
+                    .min();
+                match due {
+                    Some((deadline, index)) => {
+                        state.now = state.now.max(deadline);
+                        let debounced = &mut state.debounced[index];
+                        debounced.deadline = None;
+                        let batch = std::mem::take(&mut debounced.pending);
+                        debounced
+                            .handler
+                            .take()
+                            .map(|handler| (index, batch, handler))
+                    }
+                    None => {
+                        state.now = target;
+                        None
+                    }
+                }
+            };
+
+            let Some((index, batch, mut handler)) = due else {
+                return;
+            };
+            handler(batch, self);
+            self.state().debounced[index].handler = Some(handler);
+        }
+    }
+
+    /// Every event published so far, in order.
+    pub fn events(&self) -> Vec<E> {
+        self.state()
+            .recorded
+            .iter()
+            .map(|record| record.event.clone())
+            .collect()
+    }
+
+    /// Every event published so far with its sequence number and time.
+    pub fn recorded(&self) -> Vec<RecordedEvent<E>> {
+        self.state().recorded.clone()
+    }
+
+    /// Number of recorded events matching `predicate`.
+    pub fn count(&self, predicate: impl Fn(&E) -> bool) -> usize {
+        self.state()
+            .recorded
+            .iter()
+            .filter(|record| predicate(&record.event))
+            .count()
+    }
+
+    /// Forgets the recorded events; handlers and the clock are kept.
+    pub fn clear(&self) {
+        self.state().recorded.clear();
+    }
+
+    /// Asserts that events matching each of `expected` were recorded in that
+    /// order. Other events may appear in between.
+    #[track_caller]
+    pub fn assert_in_order(&self, expected: &[EventMatcher<'_, E>]) {
+        let recorded = self.events();
+        let mut remaining = recorded.iter();
+        for (name, predicate) in expected {
+            if !remaining.any(predicate) {
+                panic!(
+                    "expected a `{name}` event after the previous expectations, \
+                     recorded events were: {recorded:#?}"
+                );
+            }
+        }
+    }
+
+    /// Asserts that no recorded event matches `predicate`.
+    #[track_caller]
+    pub fn assert_none(&self, name: &str, predicate: impl Fn(&E) -> bool) {
+        let recorded = self.events();
+        if let Some(event) = recorded.iter().find(|event| predicate(event)) {
+            panic!("unexpected `{name}` event {event:?}, recorded events were: {recorded:#?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum TestEvent {
+        Changed(u32),
+        Saved(u32),
+        Refreshed(Vec<u32>),
+    }
+
+    #[test]
+    fn events_published_by_handlers_follow_the_current_event() {
+        let bus = TestEventBus::new();
+        bus.subscribe(|event, bus| {
+            if let TestEvent::Changed(id) = event {
+                bus.publish(TestEvent::Saved(*id));
+            }
+        });
+
+        bus.publish(TestEvent::Changed(1));
+        bus.publish(TestEvent::Changed(2));
+
+        assert_eq!(
+            bus.events(),
+            vec![
+                TestEvent::Changed(1),
+                TestEvent::Saved(1),
+                TestEvent::Changed(2),
+                TestEvent::Saved(2),
+            ]
+        );
+        bus.assert_in_order(&[
+            ("first save", &|event| *event == TestEvent::Saved(1)),
+            ("second change", &|event| *event == TestEvent::Changed(2)),
+        ]);
+        assert_eq!(bus.recorded()[3].sequence, 3);
+    }
+
+    #[test]
+    fn debounced_handlers_fire_once_the_virtual_clock_passes_the_quiet_period() {
+        let bus = TestEventBus::new();
+        bus.subscribe_debounced(
+            Duration::from_millis(100),
+            |event| matches!(event, TestEvent::Changed(_)),
+            |batch, bus| {
+                let ids = batch
+                    .into_iter()
+                    .filter_map(|event| match event {
+                        TestEvent::Changed(id) => Some(id),
+                        _ => None,
+                    })
+                    .collect();
+                bus.publish(TestEvent::Refreshed(ids));
+            },
+        );
+
+        bus.publish(TestEvent::Changed(1));
+        bus.advance(Duration::from_millis(60));
+        bus.publish(TestEvent::Changed(2));
+        bus.advance(Duration::from_millis(60));
+        bus.assert_none("refresh", |event| matches!(event, TestEvent::Refreshed(_)));
+
+        bus.advance(Duration::from_millis(60));
+        let recorded = bus.recorded();
+        let refresh = recorded.last().unwrap();
+        assert_eq!(refresh.event, TestEvent::Refreshed(vec![1, 2]));
+        assert_eq!(refresh.at, Duration::from_millis(160));
+        assert_eq!(bus.now(), Duration::from_millis(180));
+    }
+
+    #[test]
+    fn channel_events_are_recorded_in_arrival_order() {
+        let bus = TestEventBus::new();
+        let (sender, mut receiver) = broadcast::channel(8);
+        sender.send(TestEvent::Changed(1)).unwrap();
+        sender.send(TestEvent::Saved(1)).unwrap();
+
+        assert_eq!(bus.record_broadcast(&mut receiver), 2);
+        assert_eq!(bus.count(|event| matches!(event, TestEvent::Saved(_))), 1);
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        sender.send(TestEvent::Changed(2)).unwrap();
+        assert_eq!(bus.record_unbounded(&mut receiver), 1);
+        assert_eq!(bus.events().len(), 3);
+    }
+}
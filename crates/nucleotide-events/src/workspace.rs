@@ -96,6 +96,18 @@ pub enum Event {
     FileOpRequested {
         intent: FileOpIntent,
     },
+
+    /// A requested file operation finished
+    FileOpCompleted {
+        intent: FileOpIntent,
+        outcome: FileOpOutcome,
+    },
+
+    /// A requested file operation could not be performed
+    FileOpFailed {
+        intent: FileOpIntent,
+        error: String,
+    },
 }
 
 /// File operation intent kinds initiated from UI (e.g., context menu)
@@ -108,6 +120,31 @@ pub enum FileOpIntent {
     Duplicate { path: PathBuf, target_name: String },
     CopyPath { path: PathBuf, kind: PathCopyKind },
     RevealInOs { path: PathBuf },
+    UndoLast,
+}
+
+/// What a completed file operation changed, journaled so it can be undone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileOpOutcome {
+    Created {
+        path: PathBuf,
+        is_dir: bool,
+    },
+    Renamed {
+        old_path: PathBuf,
+        new_path: PathBuf,
+        is_dir: bool,
+    },
+    Deleted {
+        path: PathBuf,
+        is_dir: bool,
+        mode: DeleteMode,
+    },
+    Duplicated {
+        source: PathBuf,
+        target: PathBuf,
+        is_dir: bool,
+    },
 }
 
 /// Kind of path to copy to clipboard
@@ -70,7 +70,7 @@ pub fn create_file_layer(
     // Note: For now we'll use daily rotation, but we could extend this
     // to use size-based rotation based on config.max_size_mb
     let file_appender = rolling::daily(directory, file_name);
-    let (non_blocking_writer, _guard) = non_blocking(file_appender);
+    let (non_blocking_writer, guard) = non_blocking(file_appender);
 
     let layer = fmt::layer()
         .with_writer(non_blocking_writer)
@@ -82,10 +82,8 @@ pub fn create_file_layer(
         .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
         .boxed();
 
-    // Note: We're intentionally leaking the guard here because it needs to live
-    // for the lifetime of the application. In a real application, you'd want to
-    // store this guard somewhere and drop it on shutdown.
-    std::mem::forget(_guard);
+    // The worker lives until shutdown flushes it.
+    crate::subscriber::keep_file_worker(guard);
 
     Ok(Some(layer))
 }
@@ -4,6 +4,7 @@
 pub mod config;
 pub mod layers;
 pub mod performance;
+pub mod recent;
 pub mod reload;
 pub mod subscriber;
 
@@ -21,9 +22,13 @@ pub use config::{LoggingConfig, default_log_file_path, default_remote_log_file_p
 // Re-export initialization functions and reload handle
 pub use reload::LoggingReloadHandle;
 pub use subscriber::{
-    init_file_subscriber, init_stderr_subscriber, init_subscriber, init_subscriber_with_reload,
+    flush_logs, init_file_subscriber, init_stderr_subscriber, init_subscriber,
+    init_subscriber_with_reload,
 };
 
+// Re-export the recent log tail used by error reports
+pub use recent::recent_log_lines;
+
 // Re-export performance monitoring utilities
 pub use performance::{PerfStats, PerfTimer};
 
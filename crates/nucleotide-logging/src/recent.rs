@@ -0,0 +1,115 @@
+// ABOUTME: In-memory ring buffer holding the most recent formatted log lines
+// ABOUTME: Lets crash and error reports include a log tail without reading the log file back
+
+use std::collections::VecDeque;
+use std::io;
+use std::sync::LazyLock;
+
+use parking_lot::Mutex;
+use tracing_subscriber::Layer;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Lines kept in memory; older lines are dropped.
+pub const RECENT_LOG_CAPACITY: usize = 200;
+
+static RECENT_LOGS: LazyLock<Mutex<VecDeque<String>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::with_capacity(RECENT_LOG_CAPACITY)));
+
+/// Writer target that appends formatted events to the recent log buffer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecentLogs;
+
+impl<'a> MakeWriter<'a> for RecentLogs {
+    type Writer = RecentLogWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RecentLogWriter::default()
+    }
+}
+
+/// Buffers one formatted event and pushes its lines when dropped.
+#[derive(Debug, Default)]
+pub struct RecentLogWriter {
+    buffer: Vec<u8>,
+}
+
+impl io::Write for RecentLogWriter {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for RecentLogWriter {
+    fn drop(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let text = String::from_utf8_lossy(&self.buffer);
+        push_lines(text.lines());
+    }
+}
+
+fn push_lines<'a>(lines: impl Iterator<Item = &'a str>) {
+    let mut recent = RECENT_LOGS.lock();
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        if recent.len() >= RECENT_LOG_CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(line.to_string());
+    }
+}
+
+/// Plain-text fmt layer that feeds the recent log buffer.
+pub fn recent_log_layer<S>() -> impl Layer<S> + Send + Sync + 'static
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_subscriber::fmt::layer()
+        .with_ansi(false)
+        .with_target(true)
+        .with_writer(RecentLogs)
+}
+
+/// Up to `limit` of the most recent log lines, oldest first.
+///
+/// Returns nothing rather than blocking when the buffer is locked, so this
+/// is safe to call from a panic hook that fired while logging.
+pub fn recent_log_lines(limit: usize) -> Vec<String> {
+    let Some(recent) = RECENT_LOGS.try_lock() else {
+        return Vec::new();
+    };
+    let skip = recent.len().saturating_sub(limit);
+    recent.iter().skip(skip).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn formatted_events_are_kept_as_the_recent_tail() {
+        let subscriber = tracing_subscriber::registry().with(recent_log_layer());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(attempt = 3, "recent log tail marker");
+        });
+
+        let lines = recent_log_lines(RECENT_LOG_CAPACITY);
+        let line = lines
+            .iter()
+            .find(|line| line.contains("recent log tail marker"))
+            .expect("event captured in the recent log buffer");
+        assert!(line.contains("WARN"));
+        assert!(line.contains("attempt=3"));
+
+        push_lines(std::iter::repeat_n("filler", RECENT_LOG_CAPACITY + 10));
+        assert_eq!(recent_log_lines(usize::MAX).len(), RECENT_LOG_CAPACITY);
+        assert_eq!(recent_log_lines(5), vec!["filler"; 5]);
+    }
+}
@@ -1,10 +1,14 @@
 // ABOUTME: Tracing subscriber initialization and layer composition
 // ABOUTME: Combines console, file, and JSON layers with filtering for complete logging setup
 
+use std::sync::{Mutex, PoisonError};
+
 use anyhow::{Context, Result};
+use tracing_appender::non_blocking::WorkerGuard;
 
 use crate::config::LoggingConfig;
 use crate::layers::create_env_filter;
+use crate::recent::recent_log_layer;
 use crate::reload::LoggingReloadHandle;
 
 fn create_file_appender(
@@ -40,12 +44,30 @@ fn create_file_appender(
         })
 }
 
+/// Workers writing log files. A worker writes out the lines it still holds
+/// when dropped, so they are kept until [`flush_logs`] runs at shutdown.
+static FILE_WORKERS: Mutex<Vec<WorkerGuard>> = Mutex::new(Vec::new());
+
+pub(crate) fn keep_file_worker(guard: WorkerGuard) {
+    FILE_WORKERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(guard);
+}
+
+/// Writes out buffered log lines and stops the file log workers. Lines
+/// logged afterwards no longer reach the log file, so call this last.
+pub fn flush_logs() {
+    let workers = std::mem::take(&mut *FILE_WORKERS.lock().unwrap_or_else(PoisonError::into_inner));
+    drop(workers);
+}
+
 fn create_file_writer(
     config: &LoggingConfig,
 ) -> Result<tracing_appender::non_blocking::NonBlocking> {
     let (file_writer, guard) = tracing_appender::non_blocking(create_file_appender(config)?);
     // The application subscriber lives for the process lifetime, so its worker must do the same.
-    Box::leak(Box::new(guard));
+    keep_file_worker(guard);
     Ok(file_writer)
 }
 
@@ -113,8 +135,10 @@ pub fn init_subscriber(config: LoggingConfig) -> Result<()> {
     // Create environment filter
     let env_filter = create_env_filter(&config).context("Failed to create environment filter")?;
 
-    // Start with the registry and filter
-    let registry = tracing_subscriber::registry().with(env_filter);
+    // Start with the registry, filter and the in-memory tail used by error reports
+    let registry = tracing_subscriber::registry()
+        .with(env_filter)
+        .with(recent_log_layer());
 
     // For now, let's just use a simple approach that works
     // We'll create console output with optional file output
@@ -176,8 +200,10 @@ pub fn init_subscriber_with_reload(config: LoggingConfig) -> Result<LoggingReloa
     let env_filter = create_env_filter(&config).context("Failed to create environment filter")?;
     let (filter_layer, filter_handle) = reload::Layer::new(env_filter);
 
-    // Start with the registry and reloadable filter
-    let registry = tracing_subscriber::registry().with(filter_layer);
+    // Start with the registry, reloadable filter and the in-memory tail used by error reports
+    let registry = tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(recent_log_layer());
 
     // Set up the same output configuration as the non-reloadable version
     if config.output.file {
@@ -15,7 +15,7 @@ use nucleotide_workspace::{WorkspacePathMapping, classify_workspace_location, po
 use serde_json::Value as JsonValue;
 use tokio::sync::broadcast;
 
-use crate::{ProjectLspError, ProjectLspManager};
+use crate::{LspTrafficInspector, ProjectLspError, ProjectLspManager};
 
 // Define a dyn-compatible trait for environment providers using boxed futures
 #[allow(clippy::type_complexity)]
@@ -103,6 +103,8 @@ pub struct HelixLspBridge {
     environment_provider: Option<Arc<dyn EnvironmentProvider>>,
     /// Optional provider for temporary launch shims, used by remote workspaces.
     launch_proxy_provider: Option<Arc<dyn LspLaunchProxyProvider>>,
+    /// Wraps local server launches in the traffic-recording stdio proxy.
+    traffic_inspector: Option<LspTrafficInspector>,
     /// Map of (workspace_root, server_name) -> LanguageServerId to scope reuse by workspace
     workspace_server_map: Arc<std::sync::Mutex<HashMap<(PathBuf, String), LanguageServerId>>>,
     /// Temporary proxy shims must outlive server startup because POSIX shebang
@@ -117,6 +119,7 @@ impl HelixLspBridge {
             project_event_tx,
             environment_provider: None,
             launch_proxy_provider: None,
+            traffic_inspector: None,
             workspace_server_map: Arc::new(std::sync::Mutex::new(HashMap::new())),
             launch_proxy_cleanup_registry: Arc::new(LaunchProxyCleanupRegistry::default()),
         }
@@ -185,6 +188,7 @@ impl HelixLspBridge {
             project_event_tx,
             environment_provider: Some(environment_provider),
             launch_proxy_provider: None,
+            traffic_inspector: None,
             workspace_server_map: Arc::new(std::sync::Mutex::new(HashMap::new())),
             launch_proxy_cleanup_registry: Arc::new(LaunchProxyCleanupRegistry::default()),
         }
@@ -199,11 +203,18 @@ impl HelixLspBridge {
             project_event_tx,
             environment_provider: Some(environment_provider),
             launch_proxy_provider: Some(launch_proxy_provider),
+            traffic_inspector: None,
             workspace_server_map: Arc::new(std::sync::Mutex::new(HashMap::new())),
             launch_proxy_cleanup_registry: Arc::new(LaunchProxyCleanupRegistry::default()),
         }
     }
 
+    /// Record JSON-RPC traffic for locally launched servers.
+    pub fn with_traffic_inspector(mut self, inspector: LspTrafficInspector) -> Self {
+        self.traffic_inspector = Some(inspector);
+        self
+    }
+
     /// Start a language server through Helix's registry
     #[instrument(skip(self, editor), fields(
         workspace_root = %workspace_root.display(),
@@ -356,6 +367,41 @@ impl HelixLspBridge {
         stopped
     }
 
+    /// Drop bookkeeping for a server whose process already exited.
+    ///
+    /// Unlike [`Self::stop_server`] this leaves the server's diagnostics on
+    /// documents so a replacement can take them over. Returns the workspace
+    /// root the server was started for through this bridge.
+    pub fn forget_exited_server(
+        &self,
+        editor: &mut Editor,
+        server_id: LanguageServerId,
+    ) -> Option<PathBuf> {
+        if let Some(client) = editor.language_servers.get_by_id(server_id).cloned() {
+            for document in editor.documents.values_mut() {
+                // Documents only list initialized servers; a server that died
+                // during startup can only be matched by name.
+                let attached = !client.is_initialized()
+                    || document
+                        .language_servers()
+                        .any(|server| server.id() == server_id);
+                if attached {
+                    document.remove_language_server_by_name(client.name());
+                }
+            }
+        }
+
+        editor.language_servers.remove_by_id(server_id);
+        self.launch_proxy_cleanup_registry.release(server_id);
+
+        let mut map = self.workspace_server_map.lock().unwrap();
+        let workspace_root = map
+            .iter()
+            .find_map(|((root, _), id)| (*id == server_id).then(|| root.clone()));
+        map.retain(|_, &mut id| id != server_id);
+        workspace_root
+    }
+
     /// Find existing server for workspace and server name
     #[allow(clippy::ptr_arg)]
     fn find_existing_server(
@@ -562,58 +608,40 @@ impl HelixLspBridge {
             );
         };
 
-        // Optionally wrap the server launch through our stdio proxy by PATH shimming.
-        // Controlled via env var NUCLEOTIDE_LSP_USE_PROXY=1.
-        let mut shim_dir_to_cleanup: Option<std::path::PathBuf> = None;
-        if std::env::var("NUCLEOTIDE_LSP_USE_PROXY")
-            .is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-        {
-            // Best-effort: build a shim dir with an executable named `server_name` that execs our proxy.
-            if let Ok(real_path) = which::which(server_name) {
-                use std::fs;
-                use std::io::Write as _;
-                let shim_dir =
-                    std::env::temp_dir().join(format!("nuc-lsp-shims-{}", std::process::id()));
-                let _ = fs::create_dir_all(&shim_dir);
-                let shim_path = shim_dir.join(server_name);
-
-                let log_dir = std::path::Path::new("logs").join("lsp");
-                let _ = fs::create_dir_all(&log_dir);
-                let log_file = log_dir.join(format!(
-                    "proxy-{}-{}.jsonl",
-                    server_name,
-                    chrono::Utc::now().timestamp_millis()
-                ));
-
-                // Write a simple POSIX shell wrapper
-                let script = format!(
-                    "#!/bin/sh\nexec nucleotide-lsp-proxy --server-cmd '{}' --log '{}' -- \"$@\"\n",
-                    real_path.display(),
-                    log_file.display()
-                );
-                if let Ok(mut f) = fs::File::create(&shim_path) {
-                    let _ = f.write_all(script.as_bytes());
-                    let _ = f.flush();
-                    #[cfg(unix)]
-                    {
-                        use std::os::unix::fs::PermissionsExt;
-                        let _ = fs::set_permissions(&shim_path, fs::Permissions::from_mode(0o755));
+        // Wrap direct local launches with the traffic inspector. The shim is created
+        // after environment injection so the real server resolves against the project
+        // PATH, and the shim directory is then prepended so Helix launches the proxy.
+        if !launch_proxy_enabled && let Some(inspector) = &self.traffic_inspector {
+            match inspector.create_launch_shim(server_name, &server_command) {
+                Ok(Some(proxy)) => {
+                    if !original_env_vars.iter().any(|(key, _)| key == "PATH") {
+                        original_env_vars.push(("PATH".to_string(), std::env::var("PATH").ok()));
                     }
+                    let new_path = prepend_path_entry(&proxy.path_dir);
+                    // SAFETY: Server startup runs serially on the UI thread and restores PATH.
+                    unsafe { std::env::set_var("PATH", &new_path) };
+                    launch_proxy_cleanup_paths = proxy.cleanup_paths;
+                    info!(
+                        server_name = %server_name,
+                        shim_dir = %proxy.path_dir.display(),
+                        proxy = %proxy.description,
+                        "Enabled LSP traffic inspector"
+                    );
+                }
+                Ok(None) => {
+                    debug!(
+                        server_name = %server_name,
+                        server_command = %server_command,
+                        "Traffic inspector skipped server launched by explicit path"
+                    );
+                }
+                Err(error) => {
+                    warn!(
+                        server_name = %server_name,
+                        %error,
+                        "Traffic inspector could not wrap server; launching directly"
+                    );
                 }
-
-                // Prepend to PATH via our temporary env injection mechanism
-                let original = std::env::var("PATH").ok();
-                original_env_vars.push(("PATH".to_string(), original));
-                let new_path = prepend_path_entry(&shim_dir);
-                unsafe { std::env::set_var("PATH", &new_path) };
-                shim_dir_to_cleanup = Some(shim_dir);
-                info!(
-                    server_name = %server_name,
-                    shim_dir = %shim_dir_to_cleanup.as_ref().unwrap().display(),
-                    "Enabled LSP proxy via PATH shim"
-                );
-            } else {
-                warn!(server_name = %server_name, "NUCLEOTIDE_LSP_USE_PROXY enabled but real server not found in PATH");
             }
         }
 
@@ -680,12 +708,6 @@ impl HelixLspBridge {
             }
         }
 
-        // Best-effort cleanup of shims (directory may remain if in use)
-        if let Some(dir) = shim_dir_to_cleanup {
-            let _ = std::fs::remove_file(dir.join(server_name));
-            let _ = std::fs::remove_dir(dir);
-        }
-
         match server {
             Some(Ok(client)) => {
                 if !launch_proxy_cleanup_paths.is_empty() {
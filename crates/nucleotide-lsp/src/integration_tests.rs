@@ -14,7 +14,7 @@ mod lsp_lifecycle_integration_tests {
     use tokio::sync::RwLock;
     use tokio::time::sleep;
 
-    use crate::{ProjectDetector, ProjectLspConfig, ProjectLspManager};
+    use crate::{CrashRestartPolicy, ProjectDetector, ProjectLspConfig, ProjectLspManager};
 
     /// Test configuration for integration tests
     #[derive(Debug, Clone)]
@@ -200,6 +200,7 @@ edition = "2021"
             startup_timeout: Duration::from_millis(500),
             max_concurrent_startups: 3,
             project_markers: nucleotide_types::ProjectMarkersConfig::default(),
+            crash_restart: CrashRestartPolicy::default(),
         };
 
         let manager = ProjectLspManager::new(config, None);
@@ -7,6 +7,8 @@ pub mod lsp_completion_trigger;
 pub mod lsp_state;
 pub mod lsp_status;
 pub mod project_lsp_manager;
+pub mod request_resilience;
+pub mod traffic_inspector;
 
 #[cfg(test)]
 pub mod integration_tests;
@@ -23,6 +25,15 @@ pub use lsp_state::{
 };
 pub use lsp_status::LspStatus;
 pub use project_lsp_manager::{
-    ManagedServer, ProjectDetector, ProjectInfo, ProjectLspConfig, ProjectLspError,
-    ProjectLspManager, ServerLifecycleManager,
+    CrashRestartDecision, CrashRestartPolicy, ManagedServer, ProjectDetector, ProjectInfo,
+    ProjectLspConfig, ProjectLspError, ProjectLspManager, ServerLifecycleManager,
+};
+pub use request_resilience::{
+    LspMethodClass, LspRequestError, LspRequestGuard, LspRequestPolicy, ServerRequestHealth,
+    is_idempotent_method,
+};
+pub use traffic_inspector::{
+    LspTrafficInspector, MethodLatencyStats, TRAFFIC_PROXY_FLAG, TrafficDirection, TrafficEntry,
+    TrafficExchange, TrafficProxyArgs, method_latency_stats, pair_exchanges, parse_traffic_log,
+    run_traffic_proxy, traffic_log_path,
 };
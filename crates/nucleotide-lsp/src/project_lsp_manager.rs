@@ -2,8 +2,8 @@
 // ABOUTME: Coordinates between project detection and Helix's LSP system
 
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use helix_lsp::LanguageServerId;
@@ -50,6 +50,8 @@ pub struct ProjectLspConfig {
     pub max_concurrent_startups: usize,
     /// Project markers configuration for custom project detection
     pub project_markers: ProjectMarkersConfig,
+    /// Restart policy for servers that exit unexpectedly
+    pub crash_restart: CrashRestartPolicy,
 }
 
 impl Default for ProjectLspConfig {
@@ -60,10 +62,61 @@ impl Default for ProjectLspConfig {
             startup_timeout: Duration::from_secs(10),
             max_concurrent_startups: 3,
             project_markers: ProjectMarkersConfig::default(),
+            crash_restart: CrashRestartPolicy::default(),
         }
     }
 }
 
+/// Exponential backoff for restarting crashed language servers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashRestartPolicy {
+    /// Restart crashed servers automatically
+    pub enabled: bool,
+    /// Delay before the first restart; doubled for every further attempt
+    pub initial_backoff: Duration,
+    /// Upper bound for the restart delay
+    pub max_backoff: Duration,
+    /// Restarts attempted before a server is left stopped
+    pub max_restarts: u32,
+    /// Uptime after which a restarted server's crash count starts over
+    pub stable_after: Duration,
+}
+
+impl Default for CrashRestartPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_restarts: 5,
+            stable_after: Duration::from_secs(60),
+        }
+    }
+}
+
+impl CrashRestartPolicy {
+    /// Delay before restart `attempt` (1-based).
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// What to do about a server that exited unexpectedly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrashRestartDecision {
+    /// Start the server again after `delay`
+    Restart { attempt: u32, delay: Duration },
+    /// The restart budget is spent; leave the server stopped
+    GiveUp { restarts: u32 },
+    /// Automatic restarts are turned off
+    Disabled,
+}
+
 /// Manages LSP servers at the project level
 #[derive(Clone)]
 pub struct ProjectLspManager {
@@ -436,8 +489,31 @@ impl ProjectLspManager {
         removed
     }
 
+    /// Record an unexpected exit of `server_name` and decide whether to
+    /// restart it.
+    ///
+    /// Crash counts are kept per workspace and server name because the
+    /// replacement process gets a fresh server id.
+    pub fn record_server_crash(
+        &self,
+        workspace_root: &Path,
+        server_name: &str,
+    ) -> CrashRestartDecision {
+        let decision =
+            self.lifecycle_manager
+                .record_crash(workspace_root, server_name, Instant::now());
+        warn!(
+            workspace_root = %workspace_root.display(),
+            server_name = %server_name,
+            decision = ?decision,
+            "Language server exited unexpectedly"
+        );
+        decision
+    }
+
     /// Remove all bookkeeping owned by a closed project session.
     pub async fn remove_project(&self, workspace_root: &std::path::Path) -> Vec<ManagedServer> {
+        self.lifecycle_manager.clear_crash_history(workspace_root);
         self.projects.write().await.remove(workspace_root);
         let removed = self
             .servers
@@ -904,11 +980,18 @@ fn custom_project_name_has_token(project_name: &str, token: &str) -> bool {
         .any(|part| part == token)
 }
 
+/// Restarts spent on one workspace server since it was last stable
+#[derive(Debug, Clone, Copy)]
+struct CrashHistory {
+    restarts: u32,
+    last_restart_at: Instant,
+}
+
 /// Server lifecycle management
 pub struct ServerLifecycleManager {
-    #[allow(dead_code)]
     config: ProjectLspConfig,
     helix_bridge: Arc<RwLock<Option<Arc<HelixLspBridge>>>>,
+    crash_history: Mutex<HashMap<(PathBuf, String), CrashHistory>>,
 }
 
 impl ServerLifecycleManager {
@@ -916,9 +999,58 @@ impl ServerLifecycleManager {
         Self {
             config,
             helix_bridge: Arc::new(RwLock::new(None)),
+            crash_history: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Count a crash against the workspace server and pick the next step.
+    ///
+    /// A server that stayed up for `stable_after` since its last restart gets
+    /// its full restart budget back.
+    pub fn record_crash(
+        &self,
+        workspace_root: &Path,
+        server_name: &str,
+        now: Instant,
+    ) -> CrashRestartDecision {
+        let policy = &self.config.crash_restart;
+        if !policy.enabled {
+            return CrashRestartDecision::Disabled;
+        }
+
+        let mut history = self.crash_history.lock().unwrap();
+        let key = (workspace_root.to_path_buf(), server_name.to_string());
+        if history.get(&key).is_some_and(|entry| {
+            now.saturating_duration_since(entry.last_restart_at) >= policy.stable_after
+        }) {
+            history.remove(&key);
+        }
+
+        let restarts = history.get(&key).map_or(0, |entry| entry.restarts);
+        if restarts >= policy.max_restarts {
+            return CrashRestartDecision::GiveUp { restarts };
+        }
+
+        let attempt = restarts + 1;
+        let delay = policy.backoff_for(attempt);
+        history.insert(
+            key,
+            CrashHistory {
+                restarts: attempt,
+                last_restart_at: now + delay,
+            },
+        );
+        CrashRestartDecision::Restart { attempt, delay }
+    }
+
+    /// Forget crash counts for every server of a closed workspace.
+    pub fn clear_crash_history(&self, workspace_root: &Path) {
+        self.crash_history
+            .lock()
+            .unwrap()
+            .retain(|(root, _), _| root != workspace_root);
+    }
+
     /// Set the Helix bridge for actual LSP integration
     pub async fn set_helix_bridge(&self, bridge: Arc<HelixLspBridge>) {
         info!("ServerLifecycleManager: Setting Helix bridge");
@@ -1092,6 +1224,35 @@ mod tests {
         assert_eq!(project.language_servers, vec!["rust-analyzer"]);
     }
 
+    #[tokio::test]
+    async fn registering_a_project_announces_it_once() {
+        let manager = ProjectLspManager::new(ProjectLspConfig::default(), None);
+        let mut events = manager.get_event_sender().subscribe();
+        let bus = nucleotide_events::testing::TestEventBus::new();
+        let workspace_root = PathBuf::from("/home/me/project");
+
+        manager
+            .register_project(
+                workspace_root.clone(),
+                ProjectType::Rust,
+                vec!["rust-analyzer".to_string()],
+            )
+            .await;
+        bus.record_broadcast(&mut events);
+
+        let detected = |event: &ProjectLspEvent| {
+            matches!(
+                event,
+                ProjectLspEvent::ProjectDetected { workspace_root: root, .. }
+                    if *root == workspace_root
+            )
+        };
+        assert_eq!(bus.count(detected), 1);
+        bus.assert_none("server startup", |event| {
+            matches!(event, ProjectLspEvent::ServerStartupRequested { .. })
+        });
+    }
+
     #[tokio::test]
     async fn project_lsp_manager_upserts_managed_servers_by_workspace_and_name() {
         let manager = ProjectLspManager::new(ProjectLspConfig::default(), None);
@@ -1218,6 +1379,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn crash_restart_backoff_doubles_up_to_the_cap() {
+        let policy = CrashRestartPolicy {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(3),
+            ..CrashRestartPolicy::default()
+        };
+
+        assert_eq!(policy.backoff_for(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff_for(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff_for(3), Duration::from_secs(2));
+        assert_eq!(policy.backoff_for(4), Duration::from_secs(3));
+        assert_eq!(policy.backoff_for(40), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn crash_restarts_stop_at_the_cap_until_the_server_is_stable() {
+        let lifecycle_manager = ServerLifecycleManager::new(ProjectLspConfig {
+            crash_restart: CrashRestartPolicy {
+                max_restarts: 2,
+                stable_after: Duration::from_secs(60),
+                ..CrashRestartPolicy::default()
+            },
+            ..ProjectLspConfig::default()
+        });
+        let root = Path::new("/home/me/project");
+        let start = Instant::now();
+
+        assert!(matches!(
+            lifecycle_manager.record_crash(root, "rust-analyzer", start),
+            CrashRestartDecision::Restart { attempt: 1, .. }
+        ));
+        assert!(matches!(
+            lifecycle_manager.record_crash(root, "rust-analyzer", start + Duration::from_secs(2)),
+            CrashRestartDecision::Restart { attempt: 2, .. }
+        ));
+        assert_eq!(
+            lifecycle_manager.record_crash(root, "rust-analyzer", start + Duration::from_secs(4)),
+            CrashRestartDecision::GiveUp { restarts: 2 }
+        );
+        assert!(matches!(
+            lifecycle_manager.record_crash(root, "taplo", start + Duration::from_secs(4)),
+            CrashRestartDecision::Restart { attempt: 1, .. }
+        ));
+
+        assert!(matches!(
+            lifecycle_manager.record_crash(root, "rust-analyzer", start + Duration::from_secs(120)),
+            CrashRestartDecision::Restart { attempt: 1, .. }
+        ));
+
+        lifecycle_manager.clear_crash_history(root);
+        assert!(matches!(
+            lifecycle_manager.record_crash(root, "taplo", start + Duration::from_secs(5)),
+            CrashRestartDecision::Restart { attempt: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn crash_restarts_can_be_disabled() {
+        let lifecycle_manager = ServerLifecycleManager::new(ProjectLspConfig {
+            crash_restart: CrashRestartPolicy {
+                enabled: false,
+                ..CrashRestartPolicy::default()
+            },
+            ..ProjectLspConfig::default()
+        });
+
+        assert_eq!(
+            lifecycle_manager.record_crash(Path::new("/p"), "gopls", Instant::now()),
+            CrashRestartDecision::Disabled
+        );
+    }
+
     #[tokio::test]
     async fn test_server_lifecycle_manager_creation() {
         let config = ProjectLspConfig::default();
@@ -0,0 +1,490 @@
+// ABOUTME: Timeout, retry and circuit-breaker policy for requests sent to language servers
+// ABOUTME: Tracks per-server request health so repeatedly failing servers are marked degraded
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use helix_lsp::LanguageServerId;
+use nucleotide_logging::{debug, warn};
+
+/// JSON-RPC error code servers return when a request raced with an edit.
+const CONTENT_MODIFIED: i64 = -32801;
+
+/// Broad request categories that share a timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LspMethodClass {
+    /// Latency-sensitive requests issued while typing or hovering.
+    Interactive,
+    /// Explicit jumps and searches the user is waiting on.
+    Navigation,
+    /// Requests that compute edits: formatting, rename, code actions.
+    Editing,
+    /// Everything else, including workspace-wide queries.
+    Background,
+}
+
+impl LspMethodClass {
+    pub fn for_method(method: &str) -> Self {
+        match method {
+            "textDocument/completion"
+            | "completionItem/resolve"
+            | "textDocument/hover"
+            | "textDocument/signatureHelp"
+            | "textDocument/documentHighlight" => Self::Interactive,
+            "textDocument/definition"
+            | "textDocument/declaration"
+            | "textDocument/typeDefinition"
+            | "textDocument/implementation"
+            | "textDocument/references"
+            | "textDocument/documentSymbol"
+            | "workspace/symbol" => Self::Navigation,
+            "textDocument/formatting"
+            | "textDocument/rangeFormatting"
+            | "textDocument/rename"
+            | "textDocument/prepareRename"
+            | "textDocument/codeAction"
+            | "codeAction/resolve" => Self::Editing,
+            _ => Self::Background,
+        }
+    }
+}
+
+/// Whether a request can be re-sent without side effects on the server.
+pub fn is_idempotent_method(method: &str) -> bool {
+    !matches!(
+        method,
+        "workspace/executeCommand" | "workspace/willRenameFiles" | "shutdown"
+    )
+}
+
+/// Timeouts, retries and circuit-breaker thresholds for LSP requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LspRequestPolicy {
+    pub interactive_timeout: Duration,
+    pub navigation_timeout: Duration,
+    pub editing_timeout: Duration,
+    pub background_timeout: Duration,
+    /// Extra attempts for idempotent requests that time out or race an edit.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles on each further attempt.
+    pub retry_backoff: Duration,
+    /// Consecutive failures after which a server is marked degraded.
+    pub failure_threshold: u32,
+    /// How long a degraded server is skipped before a probe request is allowed.
+    pub degraded_cooldown: Duration,
+}
+
+impl Default for LspRequestPolicy {
+    fn default() -> Self {
+        Self {
+            interactive_timeout: Duration::from_secs(5),
+            navigation_timeout: Duration::from_secs(10),
+            editing_timeout: Duration::from_secs(15),
+            background_timeout: Duration::from_secs(30),
+            max_retries: 1,
+            retry_backoff: Duration::from_millis(100),
+            failure_threshold: 5,
+            degraded_cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl LspRequestPolicy {
+    pub fn timeout_for(&self, class: LspMethodClass) -> Duration {
+        match class {
+            LspMethodClass::Interactive => self.interactive_timeout,
+            LspMethodClass::Navigation => self.navigation_timeout,
+            LspMethodClass::Editing => self.editing_timeout,
+            LspMethodClass::Background => self.background_timeout,
+        }
+    }
+}
+
+/// Request health of one language server as seen by the circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerRequestHealth {
+    Healthy,
+    /// Requests are short-circuited until `retry_at`.
+    Degraded {
+        consecutive_failures: u32,
+        retry_at: Instant,
+    },
+    /// The cooldown elapsed; the next request decides whether the server recovers.
+    Probing {
+        consecutive_failures: u32,
+    },
+}
+
+impl ServerRequestHealth {
+    pub fn is_degraded(&self) -> bool {
+        !matches!(self, Self::Healthy)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LspRequestError {
+    #[error("language server does not support {method}")]
+    Unsupported { method: &'static str },
+    #[error("language server is degraded; retrying in {}s", retry_in.as_secs().max(1))]
+    CircuitOpen { retry_in: Duration },
+    #[error("{method} timed out after {}ms", timeout.as_millis())]
+    TimedOut {
+        method: &'static str,
+        timeout: Duration,
+    },
+    #[error(transparent)]
+    Lsp(#[from] helix_lsp::Error),
+}
+
+impl LspRequestError {
+    /// Failures that say something about the server's health, as opposed to
+    /// ordinary protocol errors such as a cancelled or invalid request.
+    fn counts_against_server(&self) -> bool {
+        match self {
+            Self::TimedOut { .. } => true,
+            Self::Lsp(error) => matches!(
+                error,
+                helix_lsp::Error::Timeout(_)
+                    | helix_lsp::Error::StreamClosed
+                    | helix_lsp::Error::IO(_)
+                    | helix_lsp::Error::Parse(_)
+            ),
+            Self::Unsupported { .. } | Self::CircuitOpen { .. } => false,
+        }
+    }
+
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::TimedOut { .. } => true,
+            Self::Lsp(helix_lsp::Error::Timeout(_)) => true,
+            Self::Lsp(helix_lsp::Error::Rpc(error)) => error.code.code() == CONTENT_MODIFIED,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+#[derive(Debug, Default)]
+struct GuardInner {
+    policy: LspRequestPolicy,
+    servers: HashMap<LanguageServerId, BreakerState>,
+}
+
+/// Shared resilience layer wrapped around individual LSP requests.
+#[derive(Debug, Clone, Default)]
+pub struct LspRequestGuard {
+    inner: Arc<Mutex<GuardInner>>,
+}
+
+impl LspRequestGuard {
+    pub fn new(policy: LspRequestPolicy) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(GuardInner {
+                policy,
+                servers: HashMap::new(),
+            })),
+        }
+    }
+
+    pub fn set_policy(&self, policy: LspRequestPolicy) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.policy = policy;
+        }
+    }
+
+    pub fn policy(&self) -> LspRequestPolicy {
+        self.inner
+            .lock()
+            .map(|inner| inner.policy.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn health(&self, server_id: LanguageServerId) -> ServerRequestHealth {
+        self.health_at(server_id, Instant::now())
+    }
+
+    fn health_at(&self, server_id: LanguageServerId, now: Instant) -> ServerRequestHealth {
+        let Ok(inner) = self.inner.lock() else {
+            return ServerRequestHealth::Healthy;
+        };
+        match inner.servers.get(&server_id) {
+            Some(BreakerState {
+                consecutive_failures,
+                open_until: Some(retry_at),
+            }) => {
+                if now < *retry_at {
+                    ServerRequestHealth::Degraded {
+                        consecutive_failures: *consecutive_failures,
+                        retry_at: *retry_at,
+                    }
+                } else {
+                    ServerRequestHealth::Probing {
+                        consecutive_failures: *consecutive_failures,
+                    }
+                }
+            }
+            _ => ServerRequestHealth::Healthy,
+        }
+    }
+
+    /// Drops breaker state for a server that exited or restarted.
+    pub fn forget(&self, server_id: LanguageServerId) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.servers.remove(&server_id);
+        }
+    }
+
+    fn admit(&self, server_id: LanguageServerId, now: Instant) -> Result<(), LspRequestError> {
+        match self.health_at(server_id, now) {
+            ServerRequestHealth::Degraded { retry_at, .. } => Err(LspRequestError::CircuitOpen {
+                retry_in: retry_at - now,
+            }),
+            ServerRequestHealth::Healthy | ServerRequestHealth::Probing { .. } => Ok(()),
+        }
+    }
+
+    fn record_success(&self, server_id: LanguageServerId) {
+        if let Ok(mut inner) = self.inner.lock()
+            && inner
+                .servers
+                .remove(&server_id)
+                .is_some_and(|state| state.open_until.is_some())
+        {
+            debug!(server_id = ?server_id, "Language server recovered from degraded state");
+        }
+    }
+
+    fn record_failure(&self, server_id: LanguageServerId, now: Instant) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        let threshold = inner.policy.failure_threshold.max(1);
+        let cooldown = inner.policy.degraded_cooldown;
+        let state = inner.servers.entry(server_id).or_default();
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= threshold {
+            if state.open_until.is_none_or(|retry_at| now >= retry_at) {
+                warn!(
+                    server_id = ?server_id,
+                    consecutive_failures = state.consecutive_failures,
+                    cooldown_secs = cooldown.as_secs(),
+                    "Marking language server degraded after repeated request failures"
+                );
+            }
+            state.open_until = Some(now + cooldown);
+        }
+    }
+
+    /// Sends a request through the policy: short-circuits degraded servers,
+    /// applies the method class timeout, retries idempotent requests that
+    /// timed out or raced an edit, and feeds the outcome to the breaker.
+    ///
+    /// `issue` is called once per attempt and returns `None` when the server
+    /// does not support the request.
+    pub async fn request<T, F, Fut>(
+        &self,
+        server_id: LanguageServerId,
+        method: &'static str,
+        mut issue: F,
+    ) -> Result<T, LspRequestError>
+    where
+        F: FnMut() -> Option<Fut>,
+        Fut: Future<Output = helix_lsp::Result<T>>,
+    {
+        self.admit(server_id, Instant::now())?;
+
+        let policy = self.policy();
+        let timeout = policy.timeout_for(LspMethodClass::for_method(method));
+        let max_retries = if is_idempotent_method(method) {
+            policy.max_retries
+        } else {
+            0
+        };
+
+        let mut attempt = 0;
+        loop {
+            let future = issue().ok_or(LspRequestError::Unsupported { method })?;
+            let result = match tokio::time::timeout(timeout, future).await {
+                Ok(Ok(response)) => Ok(response),
+                Ok(Err(error)) => Err(LspRequestError::from(error)),
+                Err(_) => Err(LspRequestError::TimedOut { method, timeout }),
+            };
+
+            let error = match result {
+                Ok(response) => {
+                    self.record_success(server_id);
+                    return Ok(response);
+                }
+                Err(error) => error,
+            };
+
+            if error.counts_against_server() {
+                self.record_failure(server_id, Instant::now());
+            }
+            if attempt >= max_retries
+                || !error.is_retryable()
+                || self.admit(server_id, Instant::now()).is_err()
+            {
+                return Err(error);
+            }
+
+            debug!(
+                server_id = ?server_id,
+                method,
+                attempt = attempt + 1,
+                %error,
+                "Retrying LSP request"
+            );
+            tokio::time::sleep(policy.retry_backoff * 2u32.saturating_pow(attempt)).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn server() -> LanguageServerId {
+        slotmap::KeyData::from_ffi(7).into()
+    }
+
+    fn fast_policy() -> LspRequestPolicy {
+        LspRequestPolicy {
+            interactive_timeout: Duration::from_millis(20),
+            navigation_timeout: Duration::from_millis(20),
+            editing_timeout: Duration::from_millis(20),
+            background_timeout: Duration::from_millis(20),
+            max_retries: 2,
+            retry_backoff: Duration::from_millis(1),
+            failure_threshold: 3,
+            degraded_cooldown: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn methods_map_to_timeout_classes() {
+        let policy = LspRequestPolicy::default();
+        assert_eq!(
+            LspMethodClass::for_method("textDocument/hover"),
+            LspMethodClass::Interactive
+        );
+        assert_eq!(
+            LspMethodClass::for_method("textDocument/references"),
+            LspMethodClass::Navigation
+        );
+        assert_eq!(
+            policy.timeout_for(LspMethodClass::for_method("textDocument/formatting")),
+            policy.editing_timeout
+        );
+        assert_eq!(
+            LspMethodClass::for_method("rust-analyzer/expandMacro"),
+            LspMethodClass::Background
+        );
+        assert!(is_idempotent_method("textDocument/definition"));
+        assert!(!is_idempotent_method("workspace/executeCommand"));
+    }
+
+    #[tokio::test]
+    async fn idempotent_requests_retry_after_timeouts() {
+        let guard = LspRequestGuard::new(fast_policy());
+        let attempts = AtomicU32::new(0);
+
+        let response = guard
+            .request(server(), "textDocument/hover", || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                Some(async move {
+                    if attempt == 0 {
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                    Ok::<_, helix_lsp::Error>(attempt)
+                })
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response, 1);
+        assert_eq!(guard.health(server()), ServerRequestHealth::Healthy);
+    }
+
+    #[tokio::test]
+    async fn non_idempotent_requests_are_not_retried() {
+        let guard = LspRequestGuard::new(fast_policy());
+        let attempts = AtomicU32::new(0);
+
+        let result = guard
+            .request(server(), "workspace/executeCommand", || {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Some(async { Err::<(), _>(helix_lsp::Error::StreamClosed) })
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(LspRequestError::Lsp(helix_lsp::Error::StreamClosed))
+        ));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn breaker_opens_after_repeated_failures_and_recovers_after_probe() {
+        let guard = LspRequestGuard::new(fast_policy());
+        let fail = || Some(async { Err::<(), _>(helix_lsp::Error::StreamClosed) });
+
+        for _ in 0..3 {
+            let _ = guard
+                .request(server(), "textDocument/definition", fail)
+                .await;
+        }
+        assert!(matches!(
+            guard.health(server()),
+            ServerRequestHealth::Degraded {
+                consecutive_failures: 3,
+                ..
+            }
+        ));
+        assert!(matches!(
+            guard
+                .request(server(), "textDocument/definition", fail)
+                .await,
+            Err(LspRequestError::CircuitOpen { .. })
+        ));
+
+        // Once the cooldown elapses a probe is let through and success closes the breaker.
+        let later = Instant::now() + Duration::from_secs(61);
+        assert!(matches!(
+            guard.health_at(server(), later),
+            ServerRequestHealth::Probing { .. }
+        ));
+        assert!(guard.admit(server(), later).is_ok());
+        guard.record_success(server());
+        assert_eq!(guard.health(server()), ServerRequestHealth::Healthy);
+    }
+
+    #[tokio::test]
+    async fn protocol_errors_do_not_degrade_servers() {
+        let guard = LspRequestGuard::new(fast_policy());
+        for _ in 0..5 {
+            let _ = guard
+                .request(server(), "textDocument/rename", || {
+                    Some(async {
+                        Err::<(), _>(helix_lsp::Error::Rpc(helix_lsp::jsonrpc::Error {
+                            code: helix_lsp::jsonrpc::ErrorCode::InvalidParams,
+                            message: "bad position".to_string(),
+                            data: None,
+                        }))
+                    })
+                })
+                .await;
+        }
+        assert_eq!(guard.health(server()), ServerRequestHealth::Healthy);
+    }
+}
@@ -0,0 +1,863 @@
+// ABOUTME: Built-in LSP traffic inspector that wraps local language servers in a stdio proxy
+// ABOUTME: Records JSON-RPC traffic into rotating per-server files and pairs requests with responses
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::Value as JsonValue;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::LspLaunchProxy;
+
+/// Hidden first argument that switches the `nucl` binary into traffic proxy mode.
+pub const TRAFFIC_PROXY_FLAG: &str = "--lsp-traffic-proxy";
+
+/// Size at which a server's traffic log is rotated.
+pub const DEFAULT_MAX_LOG_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Number of log generations kept per server, including the active file.
+pub const DEFAULT_MAX_LOG_FILES: usize = 3;
+
+const MAX_HEADER_LINE_BYTES: usize = 8192;
+
+/// Settings for wrapping locally launched language servers with the traffic proxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LspTrafficInspector {
+    /// Executable that understands [`TRAFFIC_PROXY_FLAG`], normally the running `nucl`.
+    pub proxy_executable: PathBuf,
+    /// Directory holding one `<server>.jsonl` log per language server.
+    pub log_dir: PathBuf,
+    pub max_log_bytes: u64,
+    pub max_log_files: usize,
+}
+
+impl LspTrafficInspector {
+    pub fn new(proxy_executable: PathBuf, log_dir: PathBuf) -> Self {
+        Self {
+            proxy_executable,
+            log_dir,
+            max_log_bytes: DEFAULT_MAX_LOG_BYTES,
+            max_log_files: DEFAULT_MAX_LOG_FILES,
+        }
+    }
+
+    /// Writes a PATH shim named after `server_command` that relaunches the real
+    /// server through the traffic proxy.
+    ///
+    /// Returns `None` for commands given as explicit paths, because Helix does
+    /// not consult PATH for those and the shim would never be picked up.
+    pub fn create_launch_shim(
+        &self,
+        server_name: &str,
+        server_command: &str,
+    ) -> Result<Option<LspLaunchProxy>> {
+        if Path::new(server_command).file_name() != Some(server_command.as_ref()) {
+            return Ok(None);
+        }
+
+        let server_path = which::which(server_command)
+            .with_context(|| format!("{server_command} was not found in PATH"))?;
+        let shim_dir = std::env::temp_dir().join(format!(
+            "nuc-lsp-traffic-{}-{}",
+            std::process::id(),
+            Utc::now().timestamp_micros()
+        ));
+        fs::create_dir_all(&shim_dir)
+            .with_context(|| format!("failed to create {}", shim_dir.display()))?;
+
+        let shim_path = shim_dir.join(launch_shim_file_name(server_command));
+        let proxy_args = [
+            TRAFFIC_PROXY_FLAG.to_string(),
+            "--server-name".to_string(),
+            server_name.to_string(),
+            "--server-cmd".to_string(),
+            server_path.display().to_string(),
+            "--log-dir".to_string(),
+            self.log_dir.display().to_string(),
+            "--max-log-bytes".to_string(),
+            self.max_log_bytes.to_string(),
+            "--max-log-files".to_string(),
+            self.max_log_files.to_string(),
+            "--".to_string(),
+        ];
+        if let Err(error) = write_launch_shim(&shim_path, &self.proxy_executable, &proxy_args) {
+            let _ = fs::remove_dir_all(&shim_dir);
+            return Err(error);
+        }
+
+        Ok(Some(LspLaunchProxy {
+            path_dir: shim_dir.clone(),
+            cleanup_paths: vec![shim_path, shim_dir],
+            description: format!(
+                "traffic inspector ({})",
+                traffic_log_path(&self.log_dir, server_name).display()
+            ),
+        }))
+    }
+}
+
+fn launch_shim_file_name(server_command: &str) -> String {
+    if cfg!(windows) {
+        format!("{server_command}.cmd")
+    } else {
+        server_command.to_string()
+    }
+}
+
+#[cfg(unix)]
+fn write_launch_shim(shim_path: &Path, executable: &Path, args: &[String]) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut script = format!(
+        "#!/bin/sh\nexec {}",
+        sh_quote(&executable.display().to_string())
+    );
+    for arg in args {
+        script.push(' ');
+        script.push_str(&sh_quote(arg));
+    }
+    script.push_str(" \"$@\"\n");
+
+    fs::write(shim_path, script)
+        .with_context(|| format!("failed to write {}", shim_path.display()))?;
+    fs::set_permissions(shim_path, fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("failed to mark {} executable", shim_path.display()))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn write_launch_shim(shim_path: &Path, executable: &Path, args: &[String]) -> Result<()> {
+    let mut script = format!("@echo off\r\n\"{}\"", executable.display());
+    for arg in args {
+        script.push_str(&format!(" \"{arg}\""));
+    }
+    script.push_str(" %*\r\n");
+
+    fs::write(shim_path, script).with_context(|| format!("failed to write {}", shim_path.display()))
+}
+
+#[cfg(unix)]
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Arguments accepted after [`TRAFFIC_PROXY_FLAG`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrafficProxyArgs {
+    pub server_name: String,
+    pub server_cmd: String,
+    pub server_args: Vec<String>,
+    pub log_dir: PathBuf,
+    pub max_log_bytes: u64,
+    pub max_log_files: usize,
+}
+
+impl TrafficProxyArgs {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut server_name = None;
+        let mut server_cmd = None;
+        let mut server_args = Vec::new();
+        let mut log_dir = None;
+        let mut max_log_bytes = DEFAULT_MAX_LOG_BYTES;
+        let mut max_log_files = DEFAULT_MAX_LOG_FILES;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--server-name" => {
+                    server_name = Some(args.next().context("--server-name requires a value")?);
+                }
+                "--server-cmd" => {
+                    server_cmd = Some(args.next().context("--server-cmd requires a value")?);
+                }
+                "--log-dir" => {
+                    log_dir = Some(PathBuf::from(
+                        args.next().context("--log-dir requires a path")?,
+                    ));
+                }
+                "--max-log-bytes" => {
+                    max_log_bytes = args
+                        .next()
+                        .context("--max-log-bytes requires a value")?
+                        .parse()
+                        .context("--max-log-bytes must be a number")?;
+                }
+                "--max-log-files" => {
+                    max_log_files = args
+                        .next()
+                        .context("--max-log-files requires a value")?
+                        .parse()
+                        .context("--max-log-files must be a number")?;
+                }
+                "--" => {
+                    server_args.extend(args);
+                    break;
+                }
+                other => return Err(anyhow!("unexpected traffic proxy argument: {other}")),
+            }
+        }
+
+        let server_cmd = server_cmd.context("--server-cmd is required")?;
+        Ok(Self {
+            server_name: server_name.unwrap_or_else(|| server_cmd.clone()),
+            server_cmd,
+            server_args,
+            log_dir: log_dir.context("--log-dir is required")?,
+            max_log_bytes,
+            max_log_files: max_log_files.max(1),
+        })
+    }
+}
+
+/// Runs the proxy until either side of the stdio stream closes.
+///
+/// Logging failures never interrupt forwarding; the server keeps working and
+/// the inspector simply stops recording.
+pub fn run_traffic_proxy(args: TrafficProxyArgs) -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("failed to start traffic proxy runtime")?;
+    runtime.block_on(proxy_traffic(args))
+}
+
+async fn proxy_traffic(args: TrafficProxyArgs) -> Result<()> {
+    let log = match RotatingTrafficLog::open(
+        &args.log_dir,
+        &args.server_name,
+        args.max_log_bytes,
+        args.max_log_files,
+    ) {
+        Ok(log) => Some(log),
+        Err(error) => {
+            eprintln!(
+                "lsp traffic proxy: failed to open log in {}: {error}",
+                args.log_dir.display()
+            );
+            None
+        }
+    };
+    let log = Arc::new(Mutex::new(log));
+
+    let mut child = nucleotide_process::tokio_command(&args.server_cmd)
+        .args(&args.server_args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::inherit())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("failed to spawn language server {}", args.server_cmd))?;
+    let server_stdin = child.stdin.take().context("language server has no stdin")?;
+    let server_stdout = child
+        .stdout
+        .take()
+        .context("language server has no stdout")?;
+
+    let to_server = forward_messages(
+        tokio::io::BufReader::new(tokio::io::stdin()),
+        server_stdin,
+        TrafficDirection::EditorToServer,
+        Arc::clone(&log),
+    );
+    let to_editor = forward_messages(
+        tokio::io::BufReader::new(server_stdout),
+        tokio::io::stdout(),
+        TrafficDirection::ServerToEditor,
+        Arc::clone(&log),
+    );
+
+    tokio::pin!(to_server, to_editor);
+    let result = tokio::select! {
+        result = &mut to_editor => result,
+        // The editor closed its side; let the server flush its replies and exit.
+        result = &mut to_server => result.and(to_editor.await),
+    };
+    if let Err(error) = result {
+        eprintln!("lsp traffic proxy: {error}");
+    }
+
+    // Give the server a moment to finish a clean shutdown before killing it.
+    if tokio::time::timeout(Duration::from_secs(2), child.wait())
+        .await
+        .is_err()
+    {
+        let _ = child.kill().await;
+    }
+    Ok(())
+}
+
+async fn forward_messages<R, W>(
+    mut reader: R,
+    mut writer: W,
+    direction: TrafficDirection,
+    log: Arc<Mutex<Option<RotatingTrafficLog>>>,
+) -> io::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    loop {
+        let body = match read_message(&mut reader).await {
+            Ok(body) => body,
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(error) => return Err(error),
+        };
+
+        if let Ok(mut guard) = log.lock()
+            && let Some(active) = guard.as_mut()
+            && let Err(error) = active.record(direction, &body)
+        {
+            eprintln!("lsp traffic proxy: stopped recording: {error}");
+            *guard = None;
+        }
+
+        write_message(&mut writer, &body).await?;
+    }
+}
+
+/// Reads one `Content-Length` framed message body.
+pub async fn read_message<R: AsyncBufRead + Unpin>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut content_length = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "stream closed",
+            ));
+        }
+        if line.len() > MAX_HEADER_LINE_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "header line too long",
+            ));
+        }
+
+        let header = line.trim_end();
+        if header.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = Some(value.trim().parse::<usize>().map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("bad content-length: {error}"),
+                )
+            })?);
+        }
+    }
+
+    let mut body = vec![0; content_length.unwrap_or_default()];
+    reader.read_exact(&mut body).await?;
+    Ok(body)
+}
+
+/// Writes one message body with `Content-Length` framing.
+pub async fn write_message<W: AsyncWrite + Unpin>(writer: &mut W, body: &[u8]) -> io::Result<()> {
+    writer
+        .write_all(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes())
+        .await?;
+    writer.write_all(body).await?;
+    writer.flush().await
+}
+
+/// Active traffic log for a language server: `<log_dir>/<server>.jsonl`.
+pub fn traffic_log_path(log_dir: &Path, server_name: &str) -> PathBuf {
+    let file_stem: String = server_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    log_dir.join(format!("{file_stem}.jsonl"))
+}
+
+fn rotated_log_path(path: &Path, generation: usize) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".{generation}"));
+    path.with_file_name(file_name)
+}
+
+/// Size-capped JSONL traffic log. Each proxy session starts a fresh file and
+/// older sessions shift to `.1`, `.2`, ... until `max_files` is reached.
+pub struct RotatingTrafficLog {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    written: u64,
+}
+
+impl RotatingTrafficLog {
+    pub fn open(
+        log_dir: &Path,
+        server_name: &str,
+        max_bytes: u64,
+        max_files: usize,
+    ) -> io::Result<Self> {
+        fs::create_dir_all(log_dir)?;
+        let path = traffic_log_path(log_dir, server_name);
+        let max_files = max_files.max(1);
+        if fs::metadata(&path).is_ok_and(|metadata| metadata.len() > 0) {
+            shift_generations(&path, max_files)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            max_bytes,
+            max_files,
+            file,
+            written: 0,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record(&mut self, direction: TrafficDirection, body: &[u8]) -> io::Result<()> {
+        let mut line = traffic_log_line(direction, body, Utc::now());
+        line.push('\n');
+
+        if self.written > 0 && self.written + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.file.flush()?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        shift_generations(&self.path, self.max_files)?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn shift_generations(path: &Path, max_files: usize) -> io::Result<()> {
+    if max_files <= 1 {
+        return ignore_not_found(fs::remove_file(path));
+    }
+    for generation in (1..max_files - 1).rev() {
+        ignore_not_found(fs::rename(
+            rotated_log_path(path, generation),
+            rotated_log_path(path, generation + 1),
+        ))?;
+    }
+    ignore_not_found(fs::rename(path, rotated_log_path(path, 1)))
+}
+
+fn ignore_not_found(result: io::Result<()>) -> io::Result<()> {
+    match result {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// Serializes one message in the same shape as the standalone `nucleotide-lsp-proxy`.
+fn traffic_log_line(direction: TrafficDirection, body: &[u8], timestamp: DateTime<Utc>) -> String {
+    let message = serde_json::from_slice::<JsonValue>(body).ok();
+    let field = |name: &str| {
+        message
+            .as_ref()
+            .and_then(|message| message.get(name))
+            .cloned()
+    };
+    serde_json::json!({
+        "ts": timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+        "direction": direction.as_str(),
+        "method": field("method"),
+        "id": field("id"),
+        "raw": String::from_utf8_lossy(body),
+    })
+    .to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrafficDirection {
+    EditorToServer,
+    ServerToEditor,
+}
+
+impl TrafficDirection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::EditorToServer => "out",
+            Self::ServerToEditor => "in",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "out" => Some(Self::EditorToServer),
+            "in" => Some(Self::ServerToEditor),
+            _ => None,
+        }
+    }
+
+    fn reverse(self) -> Self {
+        match self {
+            Self::EditorToServer => Self::ServerToEditor,
+            Self::ServerToEditor => Self::EditorToServer,
+        }
+    }
+}
+
+/// One decoded line of a traffic log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrafficEntry {
+    /// One-based line number in the log file.
+    pub line: usize,
+    pub timestamp: DateTime<Utc>,
+    pub direction: TrafficDirection,
+    pub method: Option<String>,
+    /// JSON-encoded request id, so `1` and `"1"` stay distinct.
+    pub id: Option<String>,
+    pub is_error: bool,
+}
+
+/// Decodes a traffic log, skipping lines that are not valid entries.
+pub fn parse_traffic_log(contents: &str) -> Vec<TrafficEntry> {
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let entry = serde_json::from_str::<JsonValue>(line).ok()?;
+            let timestamp = DateTime::parse_from_rfc3339(entry.get("ts")?.as_str()?)
+                .ok()?
+                .with_timezone(&Utc);
+            let direction = TrafficDirection::parse(entry.get("direction")?.as_str()?)?;
+            let message = entry
+                .get("raw")
+                .and_then(JsonValue::as_str)
+                .and_then(|raw| serde_json::from_str::<JsonValue>(raw).ok())
+                .unwrap_or(entry);
+
+            Some(TrafficEntry {
+                line: index + 1,
+                timestamp,
+                direction,
+                method: message
+                    .get("method")
+                    .and_then(JsonValue::as_str)
+                    .map(str::to_string),
+                id: message
+                    .get("id")
+                    .filter(|id| !id.is_null())
+                    .map(JsonValue::to_string),
+                is_error: message.get("error").is_some(),
+            })
+        })
+        .collect()
+}
+
+/// A request matched with its response, if one arrived.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrafficExchange {
+    pub method: String,
+    pub id: String,
+    pub initiator: TrafficDirection,
+    pub requested_at: DateTime<Utc>,
+    pub request_line: usize,
+    pub response_line: Option<usize>,
+    pub latency: Option<Duration>,
+    pub failed: bool,
+}
+
+/// Pairs requests with responses by id. Ids are scoped per initiator because
+/// the editor and the server number their requests independently.
+pub fn pair_exchanges(entries: &[TrafficEntry]) -> Vec<TrafficExchange> {
+    let mut exchanges: Vec<TrafficExchange> = Vec::new();
+    let mut pending: HashMap<(TrafficDirection, String), usize> = HashMap::new();
+
+    for entry in entries {
+        let Some(id) = entry.id.clone() else {
+            continue;
+        };
+        match &entry.method {
+            Some(method) => {
+                pending.insert((entry.direction, id.clone()), exchanges.len());
+                exchanges.push(TrafficExchange {
+                    method: method.clone(),
+                    id,
+                    initiator: entry.direction,
+                    requested_at: entry.timestamp,
+                    request_line: entry.line,
+                    response_line: None,
+                    latency: None,
+                    failed: false,
+                });
+            }
+            None => {
+                let Some(index) = pending.remove(&(entry.direction.reverse(), id)) else {
+                    continue;
+                };
+                let exchange = &mut exchanges[index];
+                exchange.response_line = Some(entry.line);
+                exchange.latency = (entry.timestamp - exchange.requested_at).to_std().ok();
+                exchange.failed = entry.is_error;
+            }
+        }
+    }
+
+    exchanges
+}
+
+/// Latency summary for one JSON-RPC method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodLatencyStats {
+    pub method: String,
+    pub requests: usize,
+    pub pending: usize,
+    pub failed: usize,
+    pub average: Option<Duration>,
+    pub p95: Option<Duration>,
+    pub max: Option<Duration>,
+}
+
+/// Per-method latency statistics, slowest p95 first.
+pub fn method_latency_stats(exchanges: &[TrafficExchange]) -> Vec<MethodLatencyStats> {
+    let mut by_method: BTreeMap<&str, Vec<&TrafficExchange>> = BTreeMap::new();
+    for exchange in exchanges {
+        by_method
+            .entry(exchange.method.as_str())
+            .or_default()
+            .push(exchange);
+    }
+
+    let mut stats: Vec<_> = by_method
+        .into_iter()
+        .map(|(method, exchanges)| {
+            let mut latencies: Vec<Duration> = exchanges
+                .iter()
+                .filter_map(|exchange| exchange.latency)
+                .collect();
+            latencies.sort();
+
+            let average = (!latencies.is_empty())
+                .then(|| latencies.iter().sum::<Duration>() / latencies.len() as u32);
+            let p95 = (!latencies.is_empty()).then(|| {
+                let rank = (latencies.len() * 95).div_ceil(100);
+                latencies[rank.saturating_sub(1)]
+            });
+
+            MethodLatencyStats {
+                method: method.to_string(),
+                requests: exchanges.len(),
+                pending: exchanges
+                    .iter()
+                    .filter(|exchange| exchange.response_line.is_none())
+                    .count(),
+                failed: exchanges.iter().filter(|exchange| exchange.failed).count(),
+                average,
+                p95,
+                max: latencies.last().copied(),
+            }
+        })
+        .collect();
+
+    stats.sort_by(|a, b| b.p95.cmp(&a.p95).then_with(|| a.method.cmp(&b.method)));
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn log_line(ts: &str, direction: &str, raw: &str) -> String {
+        serde_json::json!({ "ts": ts, "direction": direction, "raw": raw }).to_string()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "nucleotide-lsp-traffic-{name}-{}-{nanos}",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn pairs_requests_with_responses_per_initiator() {
+        let log = [
+            log_line(
+                "2026-01-01T00:00:00.000Z",
+                "out",
+                r#"{"jsonrpc":"2.0","id":1,"method":"textDocument/hover"}"#,
+            ),
+            log_line(
+                "2026-01-01T00:00:00.010Z",
+                "in",
+                r#"{"jsonrpc":"2.0","id":1,"method":"workspace/configuration"}"#,
+            ),
+            log_line(
+                "2026-01-01T00:00:00.020Z",
+                "out",
+                r#"{"jsonrpc":"2.0","id":1,"result":[]}"#,
+            ),
+            log_line(
+                "2026-01-01T00:00:00.050Z",
+                "in",
+                r#"{"jsonrpc":"2.0","method":"window/logMessage"}"#,
+            ),
+            log_line(
+                "2026-01-01T00:00:00.120Z",
+                "in",
+                r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32603}}"#,
+            ),
+            "not json".to_string(),
+            log_line(
+                "2026-01-01T00:00:00.200Z",
+                "out",
+                r#"{"jsonrpc":"2.0","id":"1","method":"shutdown"}"#,
+            ),
+        ]
+        .join("\n");
+
+        let entries = parse_traffic_log(&log);
+        assert_eq!(entries.len(), 6);
+
+        let exchanges = pair_exchanges(&entries);
+        assert_eq!(exchanges.len(), 3);
+
+        assert_eq!(exchanges[0].method, "textDocument/hover");
+        assert_eq!(exchanges[0].response_line, Some(5));
+        assert_eq!(exchanges[0].latency, Some(Duration::from_millis(120)));
+        assert!(exchanges[0].failed);
+
+        assert_eq!(exchanges[1].method, "workspace/configuration");
+        assert_eq!(exchanges[1].initiator, TrafficDirection::ServerToEditor);
+        assert_eq!(exchanges[1].latency, Some(Duration::from_millis(10)));
+
+        assert_eq!(exchanges[2].method, "shutdown");
+        assert_eq!(exchanges[2].request_line, 7);
+        assert_eq!(exchanges[2].response_line, None);
+    }
+
+    #[test]
+    fn latency_stats_summarize_each_method() {
+        let at = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let exchange = |method: &str, latency_ms: Option<u64>| TrafficExchange {
+            method: method.to_string(),
+            id: "1".to_string(),
+            initiator: TrafficDirection::EditorToServer,
+            requested_at: at,
+            request_line: 1,
+            response_line: latency_ms.map(|_| 2),
+            latency: latency_ms.map(Duration::from_millis),
+            failed: false,
+        };
+        let mut exchanges: Vec<_> = (1..=20)
+            .map(|ms| exchange("textDocument/completion", Some(ms * 10)))
+            .collect();
+        exchanges.push(exchange("textDocument/completion", None));
+        exchanges.push(exchange("textDocument/hover", Some(5)));
+
+        let stats = method_latency_stats(&exchanges);
+        assert_eq!(stats.len(), 2);
+
+        let completion = &stats[0];
+        assert_eq!(completion.method, "textDocument/completion");
+        assert_eq!(completion.requests, 21);
+        assert_eq!(completion.pending, 1);
+        assert_eq!(completion.average, Some(Duration::from_millis(105)));
+        assert_eq!(completion.p95, Some(Duration::from_millis(190)));
+        assert_eq!(completion.max, Some(Duration::from_millis(200)));
+
+        assert_eq!(stats[1].method, "textDocument/hover");
+        assert_eq!(stats[1].p95, Some(Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn rotating_log_keeps_bounded_generations() {
+        let dir = temp_dir("rotate");
+        let body = br#"{"jsonrpc":"2.0","method":"initialized"}"#;
+
+        let mut log = RotatingTrafficLog::open(&dir, "rust analyzer", 200, 3).unwrap();
+        assert_eq!(log.path(), dir.join("rust-analyzer.jsonl"));
+        for _ in 0..8 {
+            log.record(TrafficDirection::EditorToServer, body).unwrap();
+        }
+
+        let active = fs::read_to_string(log.path()).unwrap();
+        let entries = parse_traffic_log(&active);
+        assert!(!entries.is_empty());
+        assert_eq!(entries[0].method.as_deref(), Some("initialized"));
+        assert!(rotated_log_path(log.path(), 1).exists());
+        assert!(rotated_log_path(log.path(), 2).exists());
+        assert!(!rotated_log_path(log.path(), 3).exists());
+
+        // A new proxy session starts a fresh active file.
+        drop(log);
+        let log = RotatingTrafficLog::open(&dir, "rust analyzer", 200, 3).unwrap();
+        assert_eq!(fs::metadata(log.path()).unwrap().len(), 0);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn proxy_args_require_server_and_log_dir() {
+        let args = TrafficProxyArgs::parse(
+            [
+                "--server-name",
+                "rust-analyzer",
+                "--server-cmd",
+                "/usr/bin/rust-analyzer",
+                "--log-dir",
+                "/tmp/logs",
+                "--max-log-files",
+                "0",
+                "--",
+                "--stdio",
+            ]
+            .map(str::to_string),
+        )
+        .unwrap();
+        assert_eq!(args.server_args, vec!["--stdio".to_string()]);
+        assert_eq!(args.max_log_files, 1);
+        assert_eq!(args.max_log_bytes, DEFAULT_MAX_LOG_BYTES);
+
+        assert!(TrafficProxyArgs::parse(["--server-cmd", "x"].map(str::to_string)).is_err());
+    }
+
+    #[tokio::test]
+    async fn framing_round_trips_messages() {
+        let mut framed = Vec::new();
+        write_message(&mut framed, br#"{"id":1}"#).await.unwrap();
+        write_message(&mut framed, br#"{"id":2}"#).await.unwrap();
+
+        let mut reader = tokio::io::BufReader::new(framed.as_slice());
+        assert_eq!(read_message(&mut reader).await.unwrap(), br#"{"id":1}"#);
+        assert_eq!(read_message(&mut reader).await.unwrap(), br#"{"id":2}"#);
+        assert_eq!(
+            read_message(&mut reader).await.unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+}
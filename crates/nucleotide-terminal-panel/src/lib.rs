@@ -1,12 +1,16 @@
 // ABOUTME: Bottom terminal panel with chrome, title, actions, and terminal view mounting
 
+use gpui::prelude::FluentBuilder;
 use gpui::{
     App, AppContext, Context, Entity, FocusHandle, Focusable, FontWeight, InteractiveElement,
-    IntoElement, MouseButton, ParentElement, Render, Styled, Window, div, px, svg,
+    IntoElement, MouseButton, ParentElement, Render, StatefulInteractiveElement, Styled,
+    Subscription, Window, div, px, svg,
 };
 use nucleotide_events::v2::terminal::TerminalId;
-use nucleotide_terminal_view::{TerminalView, get_view_model};
-use nucleotide_ui::{Button, ButtonSize, ButtonVariant, ThemedContext, Toolbar, Tooltipped};
+use nucleotide_terminal_view::{TerminalView, TerminalViewEvent, get_view_model};
+use nucleotide_ui::{
+    Button, ButtonSize, ButtonVariant, TextTooltip, ThemedContext, Toolbar, Tooltipped,
+};
 use std::sync::Arc;
 
 pub const TERMINAL_PANEL_HEADER_HEIGHT_PX: f32 = 32.0;
@@ -20,6 +24,10 @@ pub fn snapped_terminal_panel_height(panel_height_px: f32, cell_height_px: f32)
 }
 
 type CloseHandler = Arc<dyn Fn(TerminalId, &mut Window, &mut App) + 'static>;
+/// Opens a file position clicked in the output: path, one-based line and
+/// column.
+type OpenFileHandler =
+    Arc<dyn Fn(std::path::PathBuf, Option<usize>, Option<usize>, &mut App) + 'static>;
 
 /// Minimal terminal panel that mounts a TerminalView for a given TerminalId
 pub struct TerminalPanel {
@@ -28,8 +36,9 @@ pub struct TerminalPanel {
     pub view_entity: Option<Entity<nucleotide_terminal_view::TerminalView>>,
     focus: FocusHandle,
     title: String,
-    title_poll_started: bool,
+    view_subscription: Option<Subscription>,
     on_close: Option<CloseHandler>,
+    on_open_file: Option<OpenFileHandler>,
 }
 
 impl TerminalPanel {
@@ -40,8 +49,9 @@ impl TerminalPanel {
             view_entity: None,
             focus: cx.focus_handle(),
             title: terminal_display_title(active),
-            title_poll_started: false,
+            view_subscription: None,
             on_close: None,
+            on_open_file: None,
         }
     }
 
@@ -53,46 +63,48 @@ impl TerminalPanel {
         self
     }
 
+    pub fn on_open_file(
+        mut self,
+        handler: impl Fn(std::path::PathBuf, Option<usize>, Option<usize>, &mut App) + 'static,
+    ) -> Self {
+        self.on_open_file = Some(Arc::new(handler));
+        self
+    }
+
     pub fn initialize(&mut self, cx: &mut Context<Self>) {
-        self.ensure_title_poll(cx);
-
-        if self.view_entity.is_none()
-            && let Some(model) = get_view_model(self.active)
-        {
-            let focus = self.focus.clone();
-            let created = cx.new(|cx| TerminalView::new(model, focus, cx));
-            self.view_entity = Some(created);
-        }
+        self.ensure_view(cx);
     }
 
-    fn ensure_title_poll(&mut self, cx: &mut Context<Self>) {
-        if self.title_poll_started {
+    /// Mounts the view once the runtime has registered the session model and
+    /// follows its title changes.
+    fn ensure_view(&mut self, cx: &mut Context<Self>) {
+        if self.view_entity.is_some() {
             return;
         }
+        let Some(model) = get_view_model(self.active) else {
+            return;
+        };
 
-        self.title_poll_started = true;
-        let active = self.active;
-        cx.spawn(async move |this, cx| {
-            loop {
-                cx.background_executor()
-                    .timer(std::time::Duration::from_millis(100))
-                    .await;
-
-                let next_title = terminal_display_title(active);
-                if this
-                    .update(cx, |panel, cx| {
-                        if panel.title != next_title {
-                            panel.title = next_title;
-                            cx.notify();
-                        }
-                    })
-                    .is_err()
-                {
-                    break;
+        let focus = self.focus.clone();
+        let created = cx.new(|cx| TerminalView::new(model, focus, cx));
+        self.view_subscription = Some(cx.subscribe(
+            &created,
+            |panel, _view, event: &TerminalViewEvent, cx| match event {
+                TerminalViewEvent::TitleChanged(title) => {
+                    if panel.title != *title {
+                        panel.title = title.clone();
+                        cx.notify();
+                    }
                 }
-            }
-        })
-        .detach();
+                TerminalViewEvent::OpenFile { path, line, column } => {
+                    if let Some(handler) = panel.on_open_file.clone() {
+                        handler(path.clone(), *line, *column, cx);
+                    }
+                }
+            },
+        ));
+        self.view_entity = Some(created);
+        self.title = terminal_display_title(self.active);
     }
 }
 
@@ -113,25 +125,26 @@ fn terminal_display_title(id: TerminalId) -> String {
         .unwrap_or_else(|| "Terminal".to_string())
 }
 
+fn terminal_current_dir(id: TerminalId) -> Option<std::path::PathBuf> {
+    get_view_model(id).and_then(|model| {
+        model
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .current_dir()
+    })
+}
+
 impl Render for TerminalPanel {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        self.ensure_title_poll(cx);
-
         // Lazy-initialize the view when the model becomes available
-        if self.view_entity.is_none()
-            && let Some(model) = get_view_model(self.active)
-        {
-            let focus = self.focus.clone();
-            let created = cx.new(|cx| TerminalView::new(model, focus, cx));
-            self.view_entity = Some(created);
-        }
+        self.ensure_view(cx);
 
         let theme = cx.theme();
         let tokens = &theme.tokens;
         let bg = tokens.chrome.surface;
         let border = tokens.status_bar_tokens().border;
-        self.title = terminal_display_title(self.active);
         let title = self.title.clone();
+        let cwd = terminal_current_dir(self.active);
         let terminal_id = self.active;
         let close_handler = self.on_close.clone();
         let panel_focus = self.focus.clone();
@@ -166,6 +179,7 @@ impl Render for TerminalPanel {
             )
             .child(
                 div()
+                    .id("terminal-panel-title")
                     .flex_1()
                     .min_w(px(0.0))
                     .overflow_hidden()
@@ -174,6 +188,12 @@ impl Render for TerminalPanel {
                     .text_size(tokens.sizes.text_sm)
                     .font_weight(FontWeight::MEDIUM)
                     .text_color(tokens.chrome.text_on_chrome)
+                    .when_some(cwd, |title, cwd| {
+                        let cwd = cwd.display().to_string();
+                        title.tooltip(move |_window, cx| {
+                            cx.new(|_| TextTooltip::new(cwd.clone())).into()
+                        })
+                    })
                     .child(title),
             )
             .child(close_button);
@@ -201,7 +221,7 @@ impl Render for TerminalPanel {
                     .min_h(px(0.0))
                     .w_full()
                     .overflow_hidden()
-                    .bg(tokens.editor.background)
+                    .bg(tokens.terminal.background)
                     .child(view.clone()),
             );
         } else {
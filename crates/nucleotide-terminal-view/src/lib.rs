@@ -8,15 +8,17 @@ use gpui::prelude::FluentBuilder;
 #[cfg(feature = "emulator")]
 use gpui::{Bounds, Hsla, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, hsla, rgb};
 use gpui::{
-    Context, FocusHandle, FontWeight, InteractiveElement, IntoElement, MouseButton, ParentElement,
-    Render, Styled, Window, div,
+    Context, EventEmitter, FocusHandle, FontWeight, InteractiveElement, IntoElement, MouseButton,
+    ParentElement, Render, Styled, Window, div,
 };
 #[cfg(feature = "emulator")]
 use nucleotide_terminal::frame::{
-    Cell, DEFAULT_BACKGROUND, DEFAULT_FOREGROUND, FramePayload, GridDiff, GridSnapshot,
-    TerminalInputMode, ansi_color_index,
+    Cell, CellWidth, DEFAULT_BACKGROUND, DEFAULT_FOREGROUND, FramePayload, GridDiff, GridRow,
+    GridSnapshot, TerminalCursorShape, TerminalCursorStyle, TerminalInputMode, ansi_color_index,
 };
 #[cfg(feature = "emulator")]
+use nucleotide_terminal::shell_integration::{ShellEvent, ShellMark};
+#[cfg(feature = "emulator")]
 use nucleotide_types::scrollbar::SCROLLBAR_THICKNESS;
 use nucleotide_ui::ThemedContext;
 #[cfg(feature = "emulator")]
@@ -27,11 +29,51 @@ use once_cell::sync::Lazy;
 #[cfg(feature = "emulator")]
 use std::cell::Cell as LayoutCell;
 use std::collections::HashMap;
+use std::path::PathBuf;
 #[cfg(feature = "emulator")]
 use std::rc::Rc;
 use std::sync::{Arc, Mutex, MutexGuard};
 
 const DEFAULT_TERMINAL_TITLE: &str = "Terminal";
+/// Commands remembered for gutter marks and command jumps; older ones are
+/// forgotten first.
+#[cfg(feature = "emulator")]
+const MAX_COMMAND_BLOCKS: usize = 1_000;
+/// Key context of a focused terminal view.
+pub const TERMINAL_CONTEXT: &str = "Terminal";
+
+/// Binds the terminal view's keys. Copy takes the secondary modifier so it
+/// shares ctrl-c with the shell on Linux and Windows: the key copies while
+/// text is selected and otherwise reaches the program.
+pub fn init(cx: &mut gpui::App) {
+    cx.bind_keys([
+        gpui::KeyBinding::new(
+            "secondary-c",
+            nucleotide_ui::actions::terminal::Copy,
+            Some(TERMINAL_CONTEXT),
+        ),
+        // ctrl-v stays with the program outside macOS
+        gpui::KeyBinding::new(
+            if cfg!(target_os = "macos") {
+                "cmd-v"
+            } else {
+                "ctrl-shift-v"
+            },
+            nucleotide_ui::actions::terminal::Paste,
+            Some(TERMINAL_CONTEXT),
+        ),
+        gpui::KeyBinding::new(
+            "secondary-shift-up",
+            nucleotide_ui::actions::terminal::PreviousCommand,
+            Some(TERMINAL_CONTEXT),
+        ),
+        gpui::KeyBinding::new(
+            "secondary-shift-down",
+            nucleotide_ui::actions::terminal::NextCommand,
+            Some(TERMINAL_CONTEXT),
+        ),
+    ]);
+}
 
 fn lock_or_recover<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
     mutex
@@ -123,6 +165,66 @@ impl TerminalSelection {
     }
 }
 
+/// The link under the pointer: its row, the columns it spans there and
+/// its target.
+#[cfg(feature = "emulator")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TerminalLink {
+    row: usize,
+    cols: std::ops::Range<usize>,
+    uri: Arc<str>,
+}
+
+#[cfg(feature = "emulator")]
+impl TerminalLink {
+    fn covers(&self, row: usize, col: usize, cell: &Cell) -> bool {
+        self.row == row && self.cols.contains(&col) && cell.hyperlink.as_deref() == Some(&*self.uri)
+    }
+}
+
+/// A command reported by shell integration, marked in the gutter at its
+/// prompt.
+#[cfg(feature = "emulator")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandBlock {
+    /// Scrollback line of the prompt, counted from the oldest line
+    pub prompt_line: usize,
+    pub status: CommandStatus,
+}
+
+#[cfg(feature = "emulator")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandStatus {
+    /// The prompt is waiting for a command line
+    Editing,
+    Running,
+    Finished {
+        exit_code: Option<i32>,
+    },
+}
+
+/// A run of identically styled terminal cells with palette colors resolved.
+#[cfg(feature = "emulator")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerminalStyledSpan {
+    pub text: String,
+    pub fg: Hsla,
+    /// `None` for the terminal's default background
+    pub bg: Option<Hsla>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+/// The visible terminal grid as styled rows, used to export the terminal.
+#[cfg(feature = "emulator")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerminalStyledGrid {
+    pub foreground: Hsla,
+    pub background: Hsla,
+    pub rows: Vec<Vec<TerminalStyledSpan>>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TerminalSpawnFailure {
     pub message: String,
@@ -133,7 +235,7 @@ pub struct TerminalSpawnFailure {
 pub struct TerminalViewModel {
     pub id: TerminalId,
     #[cfg(feature = "emulator")]
-    grid: Vec<Vec<Cell>>, // current grid
+    grid: Vec<GridRow>, // current grid
     #[cfg(feature = "emulator")]
     cols: u16,
     #[cfg(feature = "emulator")]
@@ -159,15 +261,37 @@ pub struct TerminalViewModel {
     #[cfg(feature = "emulator")]
     wheel_scroll_remainder: f32,
     #[cfg(feature = "emulator")]
+    horizontal_wheel_remainder: f32,
+    #[cfg(feature = "emulator")]
+    alternate_scroll_enabled: bool,
+    #[cfg(feature = "emulator")]
     input_mode: TerminalInputMode,
     #[cfg(feature = "emulator")]
-    input_tx: Option<std::sync::mpsc::Sender<Vec<u8>>>,
+    cursor_style: TerminalCursorStyle,
+    /// Whether the terminal view has focus; unfocused cursors are hollow
+    #[cfg(feature = "emulator")]
+    focused: bool,
+    #[cfg(feature = "emulator")]
+    input_tx: Option<std::sync::mpsc::Sender<nucleotide_terminal::session::SessionInput>>,
     #[cfg(feature = "emulator")]
     selection: Option<TerminalSelection>,
     #[cfg(feature = "emulator")]
     mouse_selecting: bool,
+    /// Link under the pointer, underlined and opened by cmd-click
+    #[cfg(feature = "emulator")]
+    hovered_link: Option<TerminalLink>,
+    /// Commands in prompt order, from shell integration marks
+    #[cfg(feature = "emulator")]
+    command_blocks: Vec<CommandBlock>,
     window_title: Option<String>,
+    /// Title shown until the program reports one, usually the shell name
+    fallback_title: Option<String>,
+    /// Set when `display_title` changed since the last `take_title_change`
+    title_changed: bool,
     spawn_failure: Option<TerminalSpawnFailure>,
+    palette_override: Option<TerminalPaletteOverride>,
+    /// Working directory shared with the session's PTY reader
+    cwd_tracker: Option<Arc<Mutex<Option<PathBuf>>>>,
     /// Set to true when the shell process has exited
     exited: bool,
 }
@@ -203,15 +327,31 @@ impl TerminalViewModel {
             #[cfg(feature = "emulator")]
             wheel_scroll_remainder: 0.0,
             #[cfg(feature = "emulator")]
+            horizontal_wheel_remainder: 0.0,
+            #[cfg(feature = "emulator")]
+            alternate_scroll_enabled: true,
+            #[cfg(feature = "emulator")]
             input_mode: TerminalInputMode::default(),
             #[cfg(feature = "emulator")]
+            cursor_style: TerminalCursorStyle::default(),
+            #[cfg(feature = "emulator")]
+            focused: false,
+            #[cfg(feature = "emulator")]
             input_tx: None,
             #[cfg(feature = "emulator")]
             selection: None,
             #[cfg(feature = "emulator")]
             mouse_selecting: false,
+            #[cfg(feature = "emulator")]
+            hovered_link: None,
+            #[cfg(feature = "emulator")]
+            command_blocks: Vec::new(),
             window_title: None,
+            fallback_title: None,
+            title_changed: false,
             spawn_failure: None,
+            palette_override: None,
+            cwd_tracker: None,
             exited: false,
         }
     }
@@ -225,7 +365,10 @@ impl TerminalViewModel {
     }
 
     #[cfg(feature = "emulator")]
-    pub fn set_input_sender(&mut self, tx: std::sync::mpsc::Sender<Vec<u8>>) {
+    pub fn set_input_sender(
+        &mut self,
+        tx: std::sync::mpsc::Sender<nucleotide_terminal::session::SessionInput>,
+    ) {
         self.input_tx = Some(tx);
     }
 
@@ -251,10 +394,18 @@ impl TerminalViewModel {
         let x = (f32::from(position.x) - f32::from(bounds.origin.x)).max(0.0);
         let y = (f32::from(position.y) - f32::from(bounds.origin.y)).max(0.0);
 
-        Some(TerminalCellPosition {
-            row: ((y / cell_height).floor() as usize).min(rows - 1),
-            col: ((x / cell_width).floor() as usize).min(cols - 1),
-        })
+        let row = ((y / cell_height).floor() as usize).min(rows - 1);
+        let mut col = ((x / cell_width).floor() as usize).min(cols - 1);
+        // The right half of a wide character belongs to the character.
+        if col > 0
+            && self.grid[row]
+                .get(col)
+                .is_some_and(|cell| cell.width == CellWidth::Spacer)
+        {
+            col -= 1;
+        }
+
+        Some(TerminalCellPosition { row, col })
     }
 
     #[cfg(feature = "emulator")]
@@ -286,6 +437,71 @@ impl TerminalViewModel {
         self.mouse_selecting = false;
     }
 
+    #[cfg(feature = "emulator")]
+    pub fn clear_selection(&mut self) {
+        if self.selection.take().is_some() {
+            self.mouse_selecting = false;
+            self.dirty.mark_all();
+        }
+    }
+
+    /// The link at `position`, spanning the neighbouring cells with the
+    /// same target.
+    #[cfg(feature = "emulator")]
+    fn link_at(&self, position: TerminalCellPosition) -> Option<TerminalLink> {
+        let cells = self.grid.get(position.row)?;
+        let uri = cells.get(position.col)?.hyperlink.clone()?;
+        let same_link = |cell: &Cell| cell.hyperlink.as_deref() == Some(&*uri);
+        let start = cells[..position.col]
+            .iter()
+            .rposition(|cell| !same_link(cell))
+            .map_or(0, |col| col + 1);
+        let end = cells[position.col..]
+            .iter()
+            .position(|cell| !same_link(cell))
+            .map_or(cells.len(), |offset| position.col + offset);
+
+        Some(TerminalLink {
+            row: position.row,
+            cols: start..end,
+            uri,
+        })
+    }
+
+    /// The existing file printed at `position`, with relative paths resolved
+    /// against the directory the shell last reported.
+    #[cfg(feature = "emulator")]
+    fn file_reference_at(&self, position: TerminalCellPosition) -> Option<TerminalViewEvent> {
+        let cells = self.grid.get(position.row)?;
+        let (text, offsets) = nucleotide_terminal::hyperlink::row_text(cells);
+        let reference =
+            nucleotide_terminal::hyperlink::file_reference_at(&text, *offsets.get(position.col)?)?;
+        let path = PathBuf::from(&reference.path);
+        let path = if path.is_absolute() {
+            path
+        } else {
+            self.current_dir()?.join(path)
+        };
+        path.is_file().then_some(TerminalViewEvent::OpenFile {
+            path,
+            line: reference.line,
+            column: reference.column,
+        })
+    }
+
+    /// Returns whether the hovered link changed.
+    #[cfg(feature = "emulator")]
+    fn set_hovered_link(&mut self, link: Option<TerminalLink>) -> bool {
+        if self.hovered_link == link {
+            return false;
+        }
+        for row in [&self.hovered_link, &link].into_iter().flatten() {
+            self.dirty.mark(row.row);
+        }
+        self.hovered_link = link;
+        true
+    }
+
     #[cfg(feature = "emulator")]
     pub fn selected_text(&self) -> Option<String> {
         let selection = self.selection?;
@@ -318,12 +534,14 @@ impl TerminalViewModel {
                 continue;
             };
 
-            let line = cells
+            let mut line = String::new();
+            for cell in cells
                 .iter()
                 .skip(row_start)
                 .take(row_end.saturating_sub(row_start) + 1)
-                .map(|cell| cell.ch)
-                .collect::<String>();
+            {
+                cell.push_text(&mut line);
+            }
             text.push_str(line.trim_end());
             if row != end.row {
                 text.push('\n');
@@ -333,11 +551,104 @@ impl TerminalViewModel {
         (!text.is_empty()).then_some(text)
     }
 
+    /// Text of the visible grid, one line per row with trailing blanks
+    /// trimmed. Scrollback is not included.
+    #[cfg(feature = "emulator")]
+    pub fn visible_text(&self) -> String {
+        self.grid
+            .iter()
+            .map(|cells| {
+                let mut line = String::new();
+                for cell in cells.iter() {
+                    cell.push_text(&mut line);
+                }
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Resolves the visible grid against the theme's terminal palette.
+    /// Trailing blank cells are dropped and the mouse selection is painted.
+    #[cfg(feature = "emulator")]
+    pub fn styled_grid(&self, tokens: &DesignTokens) -> TerminalStyledGrid {
+        let palette = TerminalAnsiPalette::resolve(tokens, self.palette_override.as_ref());
+        let cols = self.cols as usize;
+        let rows = self
+            .grid
+            .iter()
+            .enumerate()
+            .map(|(row, cells)| {
+                let used = cells
+                    .iter()
+                    .rposition(|cell| {
+                        cell.ch != ' ' || cell.bg != DEFAULT_BACKGROUND || cell.inverse
+                    })
+                    .map_or(0, |last| last + 1);
+                let mut spans: Vec<TerminalStyledSpan> = Vec::new();
+                for (col, cell) in cells.iter().take(used).enumerate() {
+                    if cell.width == CellWidth::Spacer {
+                        continue;
+                    }
+                    let (fg, bg) = if cell.inverse {
+                        (cell.bg, cell.fg)
+                    } else {
+                        (cell.fg, cell.bg)
+                    };
+                    let mut bg = palette.background_for_code(bg);
+                    let mut fg = ColorTheory::ensure_contrast(
+                        bg.unwrap_or(palette.default_background),
+                        palette.foreground_for_code(fg),
+                        ContrastRatios::AA_NORMAL,
+                    );
+                    if self.selection.is_some_and(|selection| {
+                        selection.contains(TerminalCellPosition { row, col }, cols)
+                    }) {
+                        bg = Some(tokens.terminal.selection);
+                        fg = tokens.terminal.selection_text;
+                    }
+
+                    match spans.last_mut() {
+                        Some(last)
+                            if last.fg == fg
+                                && last.bg == bg
+                                && last.bold == cell.bold
+                                && last.italic == cell.italic
+                                && last.underline == cell.underline =>
+                        {
+                            cell.push_text(&mut last.text);
+                        }
+                        _ => spans.push(TerminalStyledSpan {
+                            text: {
+                                let mut text = String::new();
+                                cell.push_text(&mut text);
+                                text
+                            },
+                            fg,
+                            bg,
+                            bold: cell.bold,
+                            italic: cell.italic,
+                            underline: cell.underline,
+                        }),
+                    }
+                }
+                spans
+            })
+            .collect();
+
+        TerminalStyledGrid {
+            foreground: palette.default_foreground,
+            background: palette.default_background,
+            rows,
+        }
+    }
+
     #[cfg(feature = "emulator")]
     pub fn apply_frame(&mut self, frame: FramePayload) {
         match frame {
             FramePayload::Full(snapshot) => self.set_snapshot(snapshot),
             FramePayload::Diff(diff) => self.apply_diff(diff),
+            FramePayload::Shell(event) => self.apply_shell_event(event),
             FramePayload::Raw(_) => {}
         }
     }
@@ -356,6 +667,16 @@ impl TerminalViewModel {
         self.input_mode
     }
 
+    /// Records whether the view has focus, redrawing the cursor row when
+    /// the cursor switches between solid and hollow.
+    #[cfg(feature = "emulator")]
+    pub fn set_focused(&mut self, focused: bool) {
+        if self.focused != focused {
+            self.focused = focused;
+            self.dirty.mark(self.cursor_row as usize);
+        }
+    }
+
     #[cfg(feature = "emulator")]
     fn set_snapshot(&mut self, snapshot: GridSnapshot) {
         self.cols = snapshot.cols;
@@ -363,14 +684,17 @@ impl TerminalViewModel {
         self.grid = snapshot.rows;
         self.cursor_row = snapshot.cursor_row;
         self.cursor_col = snapshot.cursor_col;
-        self.history_size = snapshot.history_size;
+        let alternate_screen =
+            self.input_mode.alternate_screen || snapshot.input_mode.alternate_screen;
+        self.set_history_size(snapshot.history_size, alternate_screen);
         // Don't overwrite display_offset during scrollbar drag to avoid
         // race conditions where stale frames snap the scroll position back.
         if !self.scroll_dragging {
             self.display_offset = snapshot.display_offset;
         }
         self.input_mode = snapshot.input_mode;
-        self.window_title = snapshot.title.and_then(sanitize_terminal_title);
+        self.cursor_style = snapshot.cursor_style;
+        self.update_window_title(snapshot.title.and_then(sanitize_terminal_title));
         self.dirty.resize_and_fill(self.grid.len(), true);
     }
 
@@ -379,27 +703,31 @@ impl TerminalViewModel {
         if self.grid.is_empty() || self.cols == 0 || self.rows == 0 {
             return;
         }
-        // Update cursor position first
-        self.cursor_row = diff.cursor_row.min(self.rows.saturating_sub(1));
-        self.cursor_col = diff.cursor_col.min(self.cols.saturating_sub(1));
+        // Update cursor position first, redrawing the rows it left and entered
+        let cursor_row = diff.cursor_row.min(self.rows.saturating_sub(1));
+        let cursor_col = diff.cursor_col.min(self.cols.saturating_sub(1));
+        if (cursor_row, cursor_col) != (self.cursor_row, self.cursor_col) {
+            self.dirty.mark(self.cursor_row as usize);
+            self.dirty.mark(cursor_row as usize);
+        }
+        self.cursor_row = cursor_row;
+        self.cursor_col = cursor_col;
+        self.set_history_size(diff.history_size, self.input_mode.alternate_screen);
+        if self.cursor_style != diff.cursor_style {
+            self.cursor_style = diff.cursor_style;
+            self.dirty.mark(self.cursor_row as usize);
+        }
         if let Some(delta) = diff.scrolled {
             let visible_rows = self.grid.len();
+            let blank = blank_row(self.cols as usize);
             if delta > 0 {
                 let d = (delta as usize).min(visible_rows);
-                for _ in 0..d {
-                    self.grid.remove(0);
-                }
-                for _ in 0..d {
-                    self.grid.push(vec![blank_cell(); self.cols as usize]);
-                }
+                self.grid.drain(..d);
+                self.grid.resize(visible_rows, blank);
             } else if delta < 0 {
                 let d = ((-delta) as usize).min(visible_rows);
-                for _ in 0..d {
-                    self.grid.pop();
-                }
-                for _ in 0..d {
-                    self.grid.insert(0, vec![blank_cell(); self.cols as usize]);
-                }
+                self.grid.truncate(visible_rows - d);
+                self.grid.splice(0..0, std::iter::repeat_n(blank, d));
             }
             self.dirty.mark_all();
         }
@@ -408,19 +736,148 @@ impl TerminalViewModel {
             if row >= self.grid.len() {
                 continue;
             }
+            let cells = row_mut(&mut self.grid[row]);
             for range in line.ranges {
-                let start = range.col as usize;
-                let end = (start + range.cells.len()).min(self.grid[row].len());
-                for (i, cell) in range.cells.into_iter().enumerate() {
-                    if start + i < end {
-                        self.grid[row][start + i] = cell;
-                    }
+                let start = (range.col as usize).min(cells.len());
+                let end = (start + range.cells.len()).min(cells.len());
+                cells[start..end].clone_from_slice(&range.cells[..end - start]);
+            }
+            self.dirty.mark(row);
+        }
+    }
+
+    /// Sets the scrollback length. Scrollback only shrinks when it is
+    /// cleared, which takes the marked commands with it; the alternate
+    /// screen has none of its own and leaves them in place.
+    #[cfg(feature = "emulator")]
+    fn set_history_size(&mut self, history_size: usize, alternate_screen: bool) {
+        if history_size < self.history_size && !alternate_screen && !self.command_blocks.is_empty()
+        {
+            self.command_blocks.clear();
+            self.dirty.mark_all();
+        }
+        self.history_size = history_size;
+    }
+
+    #[cfg(feature = "emulator")]
+    fn apply_shell_event(&mut self, event: ShellEvent) {
+        match event.mark {
+            ShellMark::PromptStarted => {
+                // A prompt drawn on or above earlier ones means the screen
+                // was redrawn over them.
+                let kept = self
+                    .command_blocks
+                    .partition_point(|block| block.prompt_line < event.line);
+                let stale = self
+                    .command_blocks
+                    .drain(kept..)
+                    .map(|block| block.prompt_line)
+                    .collect::<Vec<_>>();
+                for line in stale {
+                    self.mark_line_dirty(line);
+                }
+                if self.command_blocks.len() >= MAX_COMMAND_BLOCKS {
+                    self.command_blocks.remove(0);
+                }
+                self.command_blocks.push(CommandBlock {
+                    prompt_line: event.line,
+                    status: CommandStatus::Editing,
+                });
+                self.mark_line_dirty(event.line);
+            }
+            ShellMark::InputStarted => {}
+            ShellMark::CommandStarted => {
+                if let Some(block) = self.command_blocks.last_mut()
+                    && block.status == CommandStatus::Editing
+                {
+                    block.status = CommandStatus::Running;
+                    let line = block.prompt_line;
+                    self.mark_line_dirty(line);
+                }
+            }
+            ShellMark::CommandFinished { exit_code } => {
+                if let Some(block) = self.command_blocks.last_mut()
+                    && block.status == CommandStatus::Running
+                {
+                    block.status = CommandStatus::Finished { exit_code };
+                    let line = block.prompt_line;
+                    self.mark_line_dirty(line);
                 }
             }
+        }
+    }
+
+    #[cfg(feature = "emulator")]
+    pub fn command_blocks(&self) -> &[CommandBlock] {
+        &self.command_blocks
+    }
+
+    /// Scrollback line shown in the top row.
+    #[cfg(feature = "emulator")]
+    fn top_line(&self) -> usize {
+        self.history_size.saturating_sub(self.display_offset)
+    }
+
+    #[cfg(feature = "emulator")]
+    fn mark_line_dirty(&mut self, line: usize) {
+        if let Some(row) = line.checked_sub(self.top_line()) {
             self.dirty.mark(row);
         }
     }
 
+    /// Status of the command whose prompt is drawn in `row`, for its gutter
+    /// mark.
+    #[cfg(feature = "emulator")]
+    fn command_at_row(&self, row: usize) -> Option<CommandStatus> {
+        if self.input_mode.alternate_screen {
+            return None;
+        }
+        let line = self.top_line() + row;
+        self.command_blocks
+            .binary_search_by_key(&line, |block| block.prompt_line)
+            .ok()
+            .map(|index| self.command_blocks[index].status)
+    }
+
+    /// Scrolls the prompt of the last command above the top row into view
+    /// at the top. Returns `false` when there is none.
+    #[cfg(feature = "emulator")]
+    pub fn scroll_to_previous_command(&mut self) -> bool {
+        if self.input_mode.alternate_screen {
+            return false;
+        }
+        let top = self.top_line();
+        match self
+            .command_blocks
+            .iter()
+            .rev()
+            .find(|block| block.prompt_line < top)
+        {
+            Some(block) => self.set_display_offset(self.history_size - block.prompt_line),
+            None => false,
+        }
+    }
+
+    /// Scrolls the prompt of the first command below the top row to the
+    /// top, or back to the live bottom after the last command.
+    #[cfg(feature = "emulator")]
+    pub fn scroll_to_next_command(&mut self) -> bool {
+        if self.input_mode.alternate_screen {
+            return false;
+        }
+        let top = self.top_line();
+        match self
+            .command_blocks
+            .iter()
+            .find(|block| block.prompt_line > top)
+        {
+            Some(block) => {
+                self.set_display_offset(self.history_size.saturating_sub(block.prompt_line))
+            }
+            None => self.scroll_to_bottom(),
+        }
+    }
+
     #[cfg(feature = "emulator")]
     pub fn take_dirty_rows(&mut self) -> Vec<usize> {
         self.dirty.take()
@@ -433,6 +890,33 @@ impl TerminalViewModel {
         self.set_display_offset(0)
     }
 
+    /// Scrolls history for shift+page up/down (a page less one line) and
+    /// shift+home/end (oldest line / live bottom). Returns `true` when the
+    /// key was consumed; full-screen programs get these keys unchanged.
+    #[cfg(feature = "emulator")]
+    pub fn scroll_for_keystroke(&mut self, keystroke: &gpui::Keystroke) -> bool {
+        let modifiers = &keystroke.modifiers;
+        if !modifiers.shift
+            || modifiers.control
+            || modifiers.alt
+            || modifiers.platform
+            || self.input_mode.alternate_screen
+        {
+            return false;
+        }
+
+        let page = self.rows.saturating_sub(1).max(1) as usize;
+        let display_offset = match keystroke.key.as_str() {
+            "pageup" => self.display_offset.saturating_add(page),
+            "pagedown" => self.display_offset.saturating_sub(page),
+            "home" => self.history_size,
+            "end" => 0,
+            _ => return false,
+        };
+        self.set_display_offset(display_offset);
+        true
+    }
+
     #[cfg(feature = "emulator")]
     fn set_display_offset(&mut self, display_offset: usize) -> bool {
         self.set_display_offset_internal(display_offset, true)
@@ -500,32 +984,70 @@ impl TerminalViewModel {
         self.set_display_offset_internal(new_offset, false)
     }
 
+    /// Horizontal wheel movement only has somewhere to go in full-screen
+    /// programs, where it becomes left/right arrow input.
+    #[cfg(feature = "emulator")]
+    fn scroll_wheel_horizontally_by_pixel_delta(&mut self, pixel_delta_x: f32) -> bool {
+        if !self.should_send_alternate_scroll_input() {
+            self.horizontal_wheel_remainder = 0.0;
+            return false;
+        }
+
+        let cell_w = self.cell_width.max(1.0);
+        let raw_columns = self.horizontal_wheel_remainder + pixel_delta_x / cell_w;
+        let whole_columns = raw_columns.trunc() as i32;
+        self.horizontal_wheel_remainder = raw_columns - whole_columns as f32;
+
+        if whole_columns == 0 {
+            return false;
+        }
+
+        let sequence: &[u8] = match (whole_columns > 0, self.input_mode.application_cursor) {
+            (true, true) => b"\x1bOD",
+            (true, false) => b"\x1b[D",
+            (false, true) => b"\x1bOC",
+            (false, false) => b"\x1b[C",
+        };
+        self.send_repeated_input(sequence, whole_columns.unsigned_abs() as usize)
+    }
+
+    #[cfg(feature = "emulator")]
+    pub fn set_alternate_scroll_enabled(&mut self, enabled: bool) {
+        self.alternate_scroll_enabled = enabled;
+    }
+
     #[cfg(feature = "emulator")]
     fn should_send_alternate_scroll_input(&self) -> bool {
-        self.input_mode.alternate_screen
+        self.alternate_scroll_enabled
+            && self.input_mode.alternate_screen
             && self.input_mode.alternate_scroll
             && !self.input_mode.mouse_mode
     }
 
     #[cfg(feature = "emulator")]
     fn send_alternate_scroll_input(&self, line_delta: i32) -> bool {
-        let Some(tx) = &self.input_tx else {
-            return false;
-        };
-
         let sequence: &[u8] = match (line_delta > 0, self.input_mode.application_cursor) {
             (true, true) => b"\x1bOA",
             (true, false) => b"\x1b[A",
             (false, true) => b"\x1bOB",
             (false, false) => b"\x1b[B",
         };
-        let line_count = line_delta.unsigned_abs() as usize;
-        let mut bytes = Vec::with_capacity(sequence.len() * line_count);
-        for _ in 0..line_count {
+        self.send_repeated_input(sequence, line_delta.unsigned_abs() as usize)
+    }
+
+    #[cfg(feature = "emulator")]
+    fn send_repeated_input(&self, sequence: &[u8], count: usize) -> bool {
+        let Some(tx) = &self.input_tx else {
+            return false;
+        };
+
+        let mut bytes = Vec::with_capacity(sequence.len() * count);
+        for _ in 0..count {
             bytes.extend_from_slice(sequence);
         }
 
-        tx.send(bytes).is_ok()
+        tx.send(nucleotide_terminal::session::SessionInput::Bytes(bytes))
+            .is_ok()
     }
 
     #[cfg(feature = "emulator")]
@@ -544,18 +1066,13 @@ impl TerminalViewModel {
         let cols_usize = cols.max(1) as usize;
         let rows_usize = rows.max(1) as usize;
 
-        if self.grid.len() > rows_usize {
-            self.grid.truncate(rows_usize);
-        }
-        while self.grid.len() < rows_usize {
-            self.grid.push(vec![blank_cell(); cols_usize]);
-        }
+        self.grid.resize(rows_usize, blank_row(cols_usize));
 
         for row in &mut self.grid {
-            if row.len() > cols_usize {
-                row.truncate(cols_usize);
-            } else if row.len() < cols_usize {
-                row.resize(cols_usize, blank_cell());
+            if row.len() != cols_usize {
+                let mut cells = row.to_vec();
+                cells.resize(cols_usize, blank_cell());
+                *row = cells.into();
             }
         }
 
@@ -570,6 +1087,20 @@ impl TerminalViewModel {
         }
     }
 
+    /// Replaces the profile colors drawn over the theme palette.
+    pub fn set_palette_override(&mut self, palette: Option<TerminalPaletteOverride>) {
+        if self.palette_override == palette {
+            return;
+        }
+        self.palette_override = palette;
+        #[cfg(feature = "emulator")]
+        self.dirty.mark_all();
+    }
+
+    pub fn palette_override(&self) -> Option<TerminalPaletteOverride> {
+        self.palette_override
+    }
+
     pub fn set_exited(&mut self) {
         self.exited = true;
     }
@@ -579,20 +1110,53 @@ impl TerminalViewModel {
     }
 
     pub fn set_window_title(&mut self, title: impl AsRef<str>) {
-        self.window_title = sanitize_terminal_title(title);
+        self.update_window_title(sanitize_terminal_title(title));
+    }
+
+    fn update_window_title(&mut self, title: Option<String>) {
+        if self.window_title != title {
+            self.window_title = title;
+            self.title_changed = true;
+        }
     }
 
     pub fn window_title(&self) -> Option<&str> {
         self.window_title.as_deref()
     }
 
+    pub fn set_cwd_tracker(&mut self, tracker: Arc<Mutex<Option<PathBuf>>>) {
+        self.cwd_tracker = Some(tracker);
+    }
+
+    /// The session's working directory as last reported by its shell.
+    pub fn current_dir(&self) -> Option<PathBuf> {
+        self.cwd_tracker
+            .as_ref()
+            .and_then(|tracker| lock_or_recover(tracker).clone())
+    }
+
+    /// Sets the title used while the program has not reported one.
+    pub fn set_fallback_title(&mut self, title: impl AsRef<str>) {
+        let title = sanitize_terminal_title(title);
+        if self.fallback_title != title {
+            self.fallback_title = title;
+            self.title_changed = self.title_changed || self.window_title.is_none();
+        }
+    }
+
     pub fn display_title(&self) -> String {
         self.window_title
             .as_deref()
+            .or(self.fallback_title.as_deref())
             .unwrap_or(DEFAULT_TERMINAL_TITLE)
             .to_string()
     }
 
+    /// Returns the new display title if it changed since the last call.
+    pub fn take_title_change(&mut self) -> Option<String> {
+        std::mem::take(&mut self.title_changed).then(|| self.display_title())
+    }
+
     pub fn set_spawn_failure(&mut self, message: impl Into<String>, details: Vec<String>) {
         self.spawn_failure = Some(TerminalSpawnFailure {
             message: message.into(),
@@ -610,16 +1174,34 @@ impl TerminalViewModel {
     }
 }
 
+#[cfg(feature = "emulator")]
+fn blank_row(cols: usize) -> GridRow {
+    vec![blank_cell(); cols].into()
+}
+
+/// Cells of a row for writing, copying the row first if a frame or another
+/// row still shares it.
+#[cfg(feature = "emulator")]
+fn row_mut(row: &mut GridRow) -> &mut [Cell] {
+    if GridRow::get_mut(row).is_none() {
+        *row = GridRow::from(&row[..]);
+    }
+    GridRow::get_mut(row).expect("row is unshared after copying")
+}
+
 #[cfg(feature = "emulator")]
 fn blank_cell() -> Cell {
     Cell {
         ch: ' ',
+        combining: None,
+        width: CellWidth::Narrow,
         fg: DEFAULT_FOREGROUND,
         bg: DEFAULT_BACKGROUND,
         bold: false,
         italic: false,
         underline: false,
         inverse: false,
+        hyperlink: None,
     }
 }
 
@@ -699,6 +1281,20 @@ impl nucleotide_ui::scrollbar::ScrollableHandle for TerminalScrollHandle {
 }
 
 /// A simple GPUI component that renders a TerminalViewModel as text lines.
+/// Events emitted by [`TerminalView`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TerminalViewEvent {
+    /// The program-reported title (OSC 0/2) or its fallback changed
+    TitleChanged(String),
+    /// A file position printed in the output was secondary-clicked. The
+    /// line and column are one-based, as printed.
+    OpenFile {
+        path: PathBuf,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+}
+
 pub struct TerminalView {
     pub model: Arc<Mutex<TerminalViewModel>>,
     focus: FocusHandle,
@@ -731,20 +1327,29 @@ impl TerminalView {
                     cx.background_executor()
                         .timer(std::time::Duration::from_millis(32))
                         .await;
-                    let has_updates = {
-                        let guard = lock_or_recover(poll_model.as_ref());
+                    let (has_updates, title_change) = {
+                        let mut guard = lock_or_recover(poll_model.as_ref());
+                        let title_change = guard.take_title_change();
                         let has_terminal_state_change =
                             guard.has_exited() || guard.has_spawn_failure();
                         #[cfg(feature = "emulator")]
-                        {
-                            has_terminal_state_change || guard.has_dirty_rows()
-                        }
+                        let has_updates = has_terminal_state_change || guard.has_dirty_rows();
                         #[cfg(not(feature = "emulator"))]
-                        {
-                            has_terminal_state_change
-                        }
+                        let has_updates = has_terminal_state_change;
+                        (has_updates, title_change)
                     };
-                    if has_updates && this.update(cx, |_, cx| cx.notify()).is_err() {
+                    if (has_updates || title_change.is_some())
+                        && this
+                            .update(cx, |_, cx| {
+                                if let Some(title) = title_change {
+                                    cx.emit(TerminalViewEvent::TitleChanged(title));
+                                }
+                                if has_updates {
+                                    cx.notify();
+                                }
+                            })
+                            .is_err()
+                    {
                         break;
                     }
                 }
@@ -763,13 +1368,67 @@ impl TerminalView {
     }
 }
 
+impl TerminalView {
+    /// Copies the selection and clears it, so the next ctrl-c reaches the
+    /// program again. Without a selection the key goes to the terminal.
+    #[cfg(feature = "emulator")]
+    fn copy(
+        &mut self,
+        _: &nucleotide_ui::actions::terminal::Copy,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mut model = lock_or_recover(self.model.as_ref());
+        let Some(text) = model.selected_text() else {
+            cx.propagate();
+            return;
+        };
+        cx.write_to_clipboard(gpui::ClipboardItem::new_string(text));
+        model.clear_selection();
+        cx.notify();
+    }
+
+    #[cfg(feature = "emulator")]
+    fn previous_command(
+        &mut self,
+        _: &nucleotide_ui::actions::terminal::PreviousCommand,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if lock_or_recover(self.model.as_ref()).scroll_to_previous_command() {
+            cx.notify();
+        }
+    }
+
+    #[cfg(feature = "emulator")]
+    fn next_command(
+        &mut self,
+        _: &nucleotide_ui::actions::terminal::NextCommand,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if lock_or_recover(self.model.as_ref()).scroll_to_next_command() {
+            cx.notify();
+        }
+    }
+}
+
+impl EventEmitter<TerminalViewEvent> for TerminalView {}
+
 impl Render for TerminalView {
-    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
         let theme = _cx.theme();
         let tokens = &theme.tokens;
-        let default_bg = tokens.editor.background;
-        let default_fg = tokens.editor.text_primary;
-        let failure = { lock_or_recover(self.model.as_ref()).spawn_failure() };
+        let (failure, palette_override) = {
+            let guard = lock_or_recover(self.model.as_ref());
+            (guard.spawn_failure(), guard.palette_override())
+        };
+        let default_bg = palette_override
+            .and_then(|palette| palette.background)
+            .unwrap_or(tokens.terminal.background);
+        let default_fg = palette_override
+            .and_then(|palette| palette.foreground)
+            .unwrap_or(tokens.terminal.foreground);
 
         if let Some(failure) = failure {
             let focus = self.focus.clone();
@@ -847,7 +1506,12 @@ impl Render for TerminalView {
             }
 
             // Take dirty rows and update only those
-            let dirty_rows = { lock_or_recover(self.model.as_ref()).take_dirty_rows() };
+            let focused = self.focus.is_focused(window);
+            let dirty_rows = {
+                let mut model = lock_or_recover(self.model.as_ref());
+                model.set_focused(focused);
+                model.take_dirty_rows()
+            };
             for idx in dirty_rows {
                 if let Some(ent) = self.rows.get(idx) {
                     ent.update(_cx, |row, cx| row.mark_dirty(cx));
@@ -876,9 +1540,20 @@ impl Render for TerminalView {
                 content
                     .id("terminal-content")
                     .on_scroll_wheel(move |event, window, cx| {
+                        let settings = cx
+                            .try_global::<nucleotide_types::ScrollSettings>()
+                            .copied()
+                            .unwrap_or_default();
+                        let delta = settings.terminal.adjust_pixel_delta(
+                            event.delta.pixel_delta(window.line_height()),
+                            &event.modifiers,
+                        );
                         let mut guard = lock_or_recover(scroll_model.as_ref());
-                        let delta_y = f32::from(event.delta.pixel_delta(window.line_height()).y);
-                        if guard.scroll_wheel_by_pixel_delta(delta_y) {
+                        guard.set_alternate_scroll_enabled(settings.terminal_alternate_scroll);
+                        let scrolled_x =
+                            guard.scroll_wheel_horizontally_by_pixel_delta(f32::from(delta.x));
+                        let scrolled_y = guard.scroll_wheel_by_pixel_delta(f32::from(delta.y));
+                        if scrolled_x || scrolled_y {
                             window.refresh();
                         }
                         cx.stop_propagation();
@@ -887,6 +1562,7 @@ impl Render for TerminalView {
             let selection_model = self.model.clone();
             let mouse_down_bounds = Rc::clone(&content_bounds);
             let focus = self.focus.clone();
+            let view = _cx.entity().downgrade();
             let interactive_content = interactive_content.on_mouse_down(
                 MouseButton::Left,
                 move |event: &MouseDownEvent, window, cx| {
@@ -895,25 +1571,57 @@ impl Render for TerminalView {
                     };
                     focus.focus(window, cx);
                     let mut model = lock_or_recover(selection_model.as_ref());
-                    if let Some(position) = model.cell_position_for_pointer(bounds, event.position)
+                    let Some(position) = model.cell_position_for_pointer(bounds, event.position)
+                    else {
+                        return;
+                    };
+                    if event.modifiers.secondary()
+                        && let Some(link) = model.link_at(position)
                     {
+                        cx.open_url(&link.uri);
+                    } else if event.modifiers.secondary()
+                        && let Some(open_file) = model.file_reference_at(position)
+                    {
+                        drop(model);
+                        let _ = view.update(cx, |_view, cx| cx.emit(open_file));
+                    } else {
                         model.start_mouse_selection(position);
-                        cx.stop_propagation();
                     }
+                    cx.stop_propagation();
+                },
+            );
+
+            let focus = self.focus.clone();
+            let interactive_content = interactive_content.on_mouse_down(
+                MouseButton::Right,
+                move |_event: &MouseDownEvent, window, cx| {
+                    focus.focus(window, cx);
+                    focus.dispatch_action(
+                        &nucleotide_ui::actions::terminal::OpenContextMenu,
+                        window,
+                        cx,
+                    );
+                    cx.stop_propagation();
                 },
             );
 
             let selection_model = self.model.clone();
             let mouse_move_bounds = Rc::clone(&content_bounds);
             let interactive_content =
-                interactive_content.on_mouse_move(move |event: &MouseMoveEvent, _window, cx| {
-                    if !event.dragging() {
-                        return;
-                    }
+                interactive_content.on_mouse_move(move |event: &MouseMoveEvent, window, cx| {
                     let Some(bounds) = mouse_move_bounds.get() else {
                         return;
                     };
                     let mut model = lock_or_recover(selection_model.as_ref());
+                    if !event.dragging() {
+                        let link = model
+                            .cell_position_for_pointer(bounds, event.position)
+                            .and_then(|position| model.link_at(position));
+                        if model.set_hovered_link(link) {
+                            window.refresh();
+                        }
+                        return;
+                    }
                     if let Some(position) = model.cell_position_for_pointer(bounds, event.position)
                         && model.extend_mouse_selection(position)
                     {
@@ -921,6 +1629,16 @@ impl Render for TerminalView {
                     }
                 });
 
+            let link_hovered = lock_or_recover(self.model.as_ref()).hovered_link.is_some();
+            let hover_model = self.model.clone();
+            let interactive_content = interactive_content
+                .when(link_hovered, |content| content.cursor_pointer())
+                .on_hover(move |hovered, window, _cx| {
+                    if !hovered && lock_or_recover(hover_model.as_ref()).set_hovered_link(None) {
+                        window.refresh();
+                    }
+                });
+
             let selection_model = self.model.clone();
             let interactive_content = interactive_content.on_mouse_up(
                 MouseButton::Left,
@@ -945,6 +1663,10 @@ impl Render for TerminalView {
                 .overflow_hidden()
                 .bg(default_bg)
                 .track_focus(&self.focus)
+                .key_context(TERMINAL_CONTEXT)
+                .on_action(_cx.listener(Self::copy))
+                .on_action(_cx.listener(Self::previous_command))
+                .on_action(_cx.listener(Self::next_command))
                 .on_children_prepainted({
                     let content_bounds = Rc::clone(&content_bounds);
                     move |bounds, _window, _cx| {
@@ -985,6 +1707,15 @@ impl Render for TerminalView {
 #[cfg(feature = "emulator")]
 const RGB_COLOR_MAX: u32 = 0x00ff_ffff;
 
+/// Colors a terminal profile sets over the theme-derived ANSI palette.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TerminalPaletteOverride {
+    pub foreground: Option<Hsla>,
+    pub background: Option<Hsla>,
+    /// Black through bright white; `None` keeps the theme color.
+    pub ansi: [Option<Hsla>; 16],
+}
+
 #[cfg(feature = "emulator")]
 #[derive(Debug, Clone, Copy)]
 struct TerminalAnsiPalette {
@@ -995,66 +1726,31 @@ struct TerminalAnsiPalette {
 
 #[cfg(feature = "emulator")]
 impl TerminalAnsiPalette {
-    fn from_tokens(tokens: &DesignTokens) -> Self {
-        let default_background = Self::opaque(tokens.editor.background);
-        let default_foreground =
-            Self::ensure_readable(default_background, tokens.editor.text_primary);
-        let is_dark = ColorTheory::relative_luminance(default_background) < 0.5;
-        let neutral_saturation = default_background.s * 0.2;
-
-        let black_seed = hsla(
-            default_background.h,
-            neutral_saturation,
-            if is_dark { 0.32 } else { 0.08 },
-            1.0,
-        );
-        let bright_black_seed = hsla(
-            default_background.h,
-            neutral_saturation,
-            if is_dark { 0.48 } else { 0.28 },
-            1.0,
-        );
-        let white_seed = hsla(
-            default_background.h,
-            neutral_saturation,
-            if is_dark { 0.84 } else { 0.36 },
-            1.0,
-        );
+    fn resolve(tokens: &DesignTokens, palette_override: Option<&TerminalPaletteOverride>) -> Self {
+        let mut palette = Self::from_tokens(tokens);
+        let Some(palette_override) = palette_override else {
+            return palette;
+        };
 
-        let red = Self::ensure_readable(default_background, tokens.editor.error);
-        let green = Self::ensure_readable(default_background, tokens.editor.success);
-        let yellow = Self::ensure_readable(default_background, tokens.editor.warning);
-        let blue_seed =
-            ColorTheory::mix_oklch(tokens.chrome.primary, tokens.editor.focus_ring, 0.25);
-        let blue = Self::ensure_readable(default_background, blue_seed);
-        let magenta_seed = ColorTheory::mix_oklch(tokens.editor.error, blue, 0.55);
-        let magenta = Self::ensure_readable(default_background, magenta_seed);
-        let cyan_seed = ColorTheory::mix_oklch(tokens.editor.success, blue, 0.50);
-        let cyan = Self::ensure_readable(default_background, cyan_seed);
-
-        let colors = [
-            Self::ensure_readable(default_background, black_seed),
-            red,
-            green,
-            yellow,
-            blue,
-            magenta,
-            cyan,
-            Self::ensure_readable(default_background, white_seed),
-            Self::ensure_readable(default_background, bright_black_seed),
-            Self::bright_variant(default_background, red, is_dark),
-            Self::bright_variant(default_background, green, is_dark),
-            Self::bright_variant(default_background, yellow, is_dark),
-            Self::bright_variant(default_background, blue, is_dark),
-            Self::bright_variant(default_background, magenta, is_dark),
-            Self::bright_variant(default_background, cyan, is_dark),
-            Self::ensure_readable(default_background, default_foreground),
-        ];
+        if let Some(background) = palette_override.background {
+            palette.default_background = Self::opaque(background);
+        }
+        if let Some(foreground) = palette_override.foreground {
+            palette.default_foreground = Self::opaque(foreground);
+        }
+        for (color, custom) in palette.colors.iter_mut().zip(palette_override.ansi) {
+            if let Some(custom) = custom {
+                *color = Self::opaque(custom);
+            }
+        }
+        palette
+    }
 
+    fn from_tokens(tokens: &DesignTokens) -> Self {
         Self {
-            default_foreground,
-            default_background,
-            colors,
+            default_foreground: tokens.terminal.foreground,
+            default_background: tokens.terminal.background,
+            colors: tokens.terminal.ansi,
         }
     }
 
@@ -1086,18 +1782,6 @@ impl TerminalAnsiPalette {
         }
     }
 
-    fn ensure_readable(background: Hsla, color: Hsla) -> Hsla {
-        ColorTheory::ensure_contrast(background, Self::opaque(color), ContrastRatios::AA_NORMAL)
-    }
-
-    fn bright_variant(background: Hsla, color: Hsla, is_dark: bool) -> Hsla {
-        let delta = if is_dark { 0.10 } else { -0.10 };
-        Self::ensure_readable(
-            background,
-            ColorTheory::adjust_oklab_lightness(color, delta),
-        )
-    }
-
     fn opaque(color: Hsla) -> Hsla {
         hsla(color.h, color.s, color.l, 1.0)
     }
@@ -1126,15 +1810,18 @@ mod tests {
 
         let mut model = TerminalViewModel::new(TerminalId(1));
         model.set_snapshot(GridSnapshot {
-            rows: vec![vec![Cell {
+            rows: vec![GridRow::from([Cell {
                 ch: 'a',
+                combining: None,
+                width: CellWidth::Narrow,
                 fg: DEFAULT_FOREGROUND,
                 bg: DEFAULT_BACKGROUND,
                 bold: false,
                 italic: false,
                 underline: false,
                 inverse: false,
-            }]],
+                hyperlink: None,
+            }])],
             cols: 1,
             rows_len: 1,
             cursor_row: 0,
@@ -1143,6 +1830,7 @@ mod tests {
             history_size: 0,
             display_offset: 0,
             input_mode: TerminalInputMode::default(),
+            cursor_style: TerminalCursorStyle::default(),
         });
         model.cell_width = 8.0;
         model.cell_height = 20.0;
@@ -1245,6 +1933,27 @@ mod tests {
         assert_eq!(delta, 1);
     }
 
+    #[test]
+    fn terminal_shift_page_keys_scroll_history_by_pages() {
+        let model = scroll_model(100, 0);
+        let mut model = model.lock().unwrap();
+        let shift = |key: &str| gpui::Keystroke::parse(&format!("shift-{key}")).unwrap();
+
+        assert!(model.scroll_for_keystroke(&shift("pageup")));
+        assert_eq!(model.display_offset, 23);
+        assert!(model.scroll_for_keystroke(&shift("home")));
+        assert_eq!(model.display_offset, 100);
+        assert!(model.scroll_for_keystroke(&shift("pagedown")));
+        assert_eq!(model.display_offset, 77);
+        assert!(model.scroll_for_keystroke(&shift("end")));
+        assert_eq!(model.display_offset, 0);
+
+        assert!(!model.scroll_for_keystroke(&gpui::Keystroke::parse("pageup").unwrap()));
+        model.input_mode.alternate_screen = true;
+        assert!(!model.scroll_for_keystroke(&shift("pageup")));
+        assert_eq!(model.display_offset, 0);
+    }
+
     #[test]
     fn terminal_wheel_scroll_discards_remainder_at_edges() {
         let mut model = TerminalViewModel::new(TerminalId(1));
@@ -1276,7 +1985,37 @@ mod tests {
 
         assert!(model.scroll_wheel_by_pixel_delta(10.0));
         assert_eq!(model.display_offset, 0);
-        assert_eq!(rx.try_recv().unwrap(), b"\x1b[A".to_vec());
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            nucleotide_terminal::session::SessionInput::Bytes(b"\x1b[A".to_vec())
+        );
+    }
+
+    #[test]
+    fn terminal_horizontal_wheel_sends_arrows_only_in_alternate_scroll() {
+        let mut model = TerminalViewModel::new(TerminalId(1));
+        let (tx, rx) = std::sync::mpsc::channel();
+        model.set_input_sender(tx);
+        model.cell_width = 8.0;
+        model.cell_height = 10.0;
+
+        assert!(!model.scroll_wheel_horizontally_by_pixel_delta(-16.0));
+        assert!(rx.try_recv().is_err());
+
+        model.input_mode = TerminalInputMode {
+            alternate_screen: true,
+            alternate_scroll: true,
+            ..TerminalInputMode::default()
+        };
+        assert!(model.scroll_wheel_horizontally_by_pixel_delta(-16.0));
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            nucleotide_terminal::session::SessionInput::Bytes(b"\x1b[C\x1b[C".to_vec())
+        );
+
+        model.set_alternate_scroll_enabled(false);
+        assert!(!model.scroll_wheel_by_pixel_delta(10.0));
+        assert!(rx.try_recv().is_err());
     }
 
     #[test]
@@ -1304,11 +2043,96 @@ mod tests {
             scrolled: Some(1),
             cursor_row: 0,
             cursor_col: 0,
+            cursor_style: TerminalCursorStyle::default(),
+            history_size: 0,
         }));
 
         assert_eq!(model.take_dirty_rows(), vec![0, 1, 2]);
     }
 
+    #[test]
+    fn terminal_shell_marks_track_commands_and_jumps_between_prompts() {
+        let model = scroll_model(10, 0);
+        let mut model = lock_or_recover(model.as_ref());
+        let mut mark =
+            |mark, line| model.apply_frame(FramePayload::Shell(ShellEvent { mark, line }));
+        mark(ShellMark::PromptStarted, 2);
+        mark(ShellMark::CommandStarted, 2);
+        mark(ShellMark::CommandFinished { exit_code: Some(1) }, 3);
+        mark(ShellMark::PromptStarted, 8);
+        mark(ShellMark::CommandStarted, 8);
+        mark(ShellMark::PromptStarted, 12);
+
+        let statuses = model
+            .command_blocks()
+            .iter()
+            .map(|block| (block.prompt_line, block.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                (2, CommandStatus::Finished { exit_code: Some(1) }),
+                (8, CommandStatus::Running),
+                (12, CommandStatus::Editing),
+            ]
+        );
+        assert_eq!(model.command_at_row(2), Some(CommandStatus::Editing));
+        assert_eq!(model.command_at_row(1), None);
+
+        assert!(model.scroll_to_previous_command());
+        assert_eq!(model.display_offset, 2);
+        assert!(model.scroll_to_previous_command());
+        assert_eq!(model.display_offset, 8);
+        assert_eq!(
+            model.command_at_row(0),
+            Some(CommandStatus::Finished { exit_code: Some(1) })
+        );
+        assert!(!model.scroll_to_previous_command());
+        assert!(model.scroll_to_next_command());
+        assert_eq!(model.display_offset, 2);
+        assert!(model.scroll_to_next_command());
+        assert_eq!(model.display_offset, 0);
+        assert!(!model.scroll_to_next_command());
+
+        // A prompt redrawn above later ones replaces them
+        model.apply_frame(FramePayload::Shell(ShellEvent {
+            mark: ShellMark::PromptStarted,
+            line: 8,
+        }));
+        assert_eq!(model.command_blocks().len(), 2);
+        assert_eq!(model.command_blocks()[1].status, CommandStatus::Editing);
+    }
+
+    #[test]
+    fn terminal_diff_copies_only_the_rows_it_writes() {
+        use nucleotide_terminal::frame::{ChangedLine, ChangedRange};
+
+        let mut model = TerminalViewModel::new(TerminalId(1));
+        model.resize_grid(3, 2, Some((8.0, 16.0)));
+        let sent = model.grid.clone();
+
+        let mut cell = blank_cell();
+        cell.ch = 'x';
+        model.apply_frame(FramePayload::Diff(GridDiff {
+            lines: vec![ChangedLine {
+                row: 1,
+                ranges: vec![ChangedRange {
+                    col: 2,
+                    cells: vec![cell.clone(), cell],
+                }],
+            }],
+            scrolled: None,
+            cursor_row: 0,
+            cursor_col: 0,
+            cursor_style: TerminalCursorStyle::default(),
+            history_size: 0,
+        }));
+
+        assert!(GridRow::ptr_eq(&model.grid[0], &sent[0]));
+        assert_eq!(model.grid[1][2].ch, 'x');
+        assert_eq!(sent[1][2].ch, ' ');
+    }
+
     #[test]
     fn terminal_diff_scroll_clamps_delta_to_visible_rows() {
         let mut model = TerminalViewModel::new(TerminalId(1));
@@ -1320,6 +2144,8 @@ mod tests {
             scrolled: Some(8),
             cursor_row: 0,
             cursor_col: 0,
+            cursor_style: TerminalCursorStyle::default(),
+            history_size: 0,
         }));
 
         assert_eq!(model.grid.len(), 3);
@@ -1380,12 +2206,12 @@ mod tests {
     fn terminal_mouse_selection_returns_text_across_rows() {
         let mut model = TerminalViewModel::new(TerminalId(1));
         model.resize_grid(3, 2, Some((8.0, 16.0)));
-        model.grid[0][0].ch = 'a';
-        model.grid[0][1].ch = 'b';
-        model.grid[0][2].ch = 'c';
-        model.grid[1][0].ch = 'd';
-        model.grid[1][1].ch = 'e';
-        model.grid[1][2].ch = 'f';
+        row_mut(&mut model.grid[0])[0].ch = 'a';
+        row_mut(&mut model.grid[0])[1].ch = 'b';
+        row_mut(&mut model.grid[0])[2].ch = 'c';
+        row_mut(&mut model.grid[1])[0].ch = 'd';
+        row_mut(&mut model.grid[1])[1].ch = 'e';
+        row_mut(&mut model.grid[1])[2].ch = 'f';
 
         model.start_mouse_selection(TerminalCellPosition { row: 0, col: 1 });
         assert!(model.extend_mouse_selection(TerminalCellPosition { row: 1, col: 1 }));
@@ -1394,6 +2220,193 @@ mod tests {
         assert_eq!(model.selected_text().as_deref(), Some("bc\nde"));
     }
 
+    #[gpui::test]
+    fn terminal_copy_action_copies_and_clears_the_selection(cx: &mut TestAppContext) {
+        cx.update(install_test_globals);
+
+        let mut model = TerminalViewModel::new(TerminalId(1));
+        model.resize_grid(3, 1, Some((8.0, 16.0)));
+        row_mut(&mut model.grid[0])[0].ch = 'a';
+        row_mut(&mut model.grid[0])[1].ch = 'b';
+        model.start_mouse_selection(TerminalCellPosition { row: 0, col: 0 });
+        model.extend_mouse_selection(TerminalCellPosition { row: 0, col: 1 });
+        model.finish_mouse_selection();
+        let model = Arc::new(Mutex::new(model));
+
+        let (view, cx) = cx.add_window_view(|_, cx| {
+            let focus = cx.focus_handle();
+            TerminalView::new(model.clone(), focus, cx)
+        });
+        cx.update(|window, cx| {
+            let focus = view.read(cx).focus.clone();
+            window.focus(&focus, cx);
+        });
+        cx.run_until_parked();
+
+        cx.dispatch_action(nucleotide_ui::actions::terminal::Copy);
+
+        assert_eq!(
+            cx.read_from_clipboard().and_then(|item| item.text()),
+            Some("ab".to_string())
+        );
+        assert!(lock_or_recover(model.as_ref()).selected_text().is_none());
+    }
+
+    #[test]
+    fn terminal_text_joins_wide_and_combined_cells() {
+        let mut model = TerminalViewModel::new(TerminalId(1));
+        model.resize_grid(4, 1, Some((8.0, 16.0)));
+        let cells = row_mut(&mut model.grid[0]);
+        cells[0].ch = '漢';
+        cells[0].width = CellWidth::Wide;
+        cells[1].width = CellWidth::Spacer;
+        cells[2].ch = 'e';
+        cells[2].combining = Some("\u{301}".into());
+
+        assert_eq!(model.visible_text(), "漢e\u{301}");
+
+        let bounds = Bounds::new(point(px(0.0), px(0.0)), gpui::size(px(32.0), px(16.0)));
+        assert_eq!(
+            model.cell_position_for_pointer(bounds, point(px(12.0), px(4.0))),
+            Some(TerminalCellPosition { row: 0, col: 0 })
+        );
+
+        model.start_mouse_selection(TerminalCellPosition { row: 0, col: 0 });
+        assert!(model.extend_mouse_selection(TerminalCellPosition { row: 0, col: 2 }));
+        model.finish_mouse_selection();
+        assert_eq!(model.selected_text().as_deref(), Some("漢e\u{301}"));
+    }
+
+    #[test]
+    fn hovered_link_spans_cells_with_the_same_target() {
+        let mut model = TerminalViewModel::new(TerminalId(1));
+        model.resize_grid(6, 2, Some((8.0, 16.0)));
+        let docs: Arc<str> = Arc::from("https://docs.rs");
+        let cells = row_mut(&mut model.grid[1]);
+        for cell in &mut cells[1..4] {
+            cell.hyperlink = Some(docs.clone());
+        }
+        cells[4].hyperlink = Some(Arc::from("https://crates.io"));
+        model.take_dirty_rows();
+
+        let link = model.link_at(TerminalCellPosition { row: 1, col: 2 });
+        assert_eq!(
+            link,
+            Some(TerminalLink {
+                row: 1,
+                cols: 1..4,
+                uri: docs,
+            })
+        );
+        assert_eq!(model.link_at(TerminalCellPosition { row: 1, col: 0 }), None);
+
+        assert!(model.set_hovered_link(link.clone()));
+        assert!(!model.set_hovered_link(link));
+        assert_eq!(model.take_dirty_rows(), vec![1]);
+        assert!(model.set_hovered_link(None));
+        assert_eq!(model.take_dirty_rows(), vec![1]);
+    }
+
+    #[test]
+    fn file_references_resolve_against_the_reported_directory() {
+        let text = "at src/lib.rs:12:5 or src/gone.rs:1";
+        let mut model = TerminalViewModel::new(TerminalId(1));
+        model.resize_grid(text.len() as u16, 1, Some((8.0, 16.0)));
+        for (cell, ch) in row_mut(&mut model.grid[0]).iter_mut().zip(text.chars()) {
+            cell.ch = ch;
+        }
+        let at = |model: &TerminalViewModel, col| {
+            model.file_reference_at(TerminalCellPosition { row: 0, col })
+        };
+
+        assert_eq!(at(&model, 5), None, "relative paths need a directory");
+
+        let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        model.set_cwd_tracker(Arc::new(Mutex::new(Some(manifest_dir.clone()))));
+        assert_eq!(
+            at(&model, 5),
+            Some(TerminalViewEvent::OpenFile {
+                path: manifest_dir.join("src/lib.rs"),
+                line: Some(12),
+                column: Some(5),
+            })
+        );
+        assert_eq!(at(&model, 0), None);
+        assert_eq!(at(&model, 25), None, "missing files are not opened");
+    }
+
+    #[test]
+    fn styled_grid_trims_blank_cells_and_applies_inverse() {
+        let mut model = TerminalViewModel::new(TerminalId(1));
+        model.resize_grid(4, 1, Some((8.0, 16.0)));
+        row_mut(&mut model.grid[0])[0].ch = 'a';
+        row_mut(&mut model.grid[0])[1].ch = 'b';
+        row_mut(&mut model.grid[0])[1].inverse = true;
+
+        let tokens = DesignTokens::dark();
+        let grid = model.styled_grid(&tokens);
+        let palette = TerminalAnsiPalette::from_tokens(&tokens);
+
+        assert_eq!(grid.rows.len(), 1);
+        assert_eq!(
+            grid.rows[0]
+                .iter()
+                .map(|span| span.text.as_str())
+                .collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        assert_eq!(grid.rows[0][0].bg, None);
+        assert_eq!(grid.rows[0][1].bg, Some(palette.default_foreground));
+    }
+
+    #[test]
+    fn visible_text_trims_trailing_blanks() {
+        let mut model = TerminalViewModel::new(TerminalId(1));
+        model.resize_grid(4, 2, Some((8.0, 16.0)));
+        row_mut(&mut model.grid[0])[0].ch = 'o';
+        row_mut(&mut model.grid[0])[1].ch = 'k';
+        row_mut(&mut model.grid[1])[2].ch = '!';
+
+        assert_eq!(model.visible_text(), "ok\n  !");
+    }
+
+    #[test]
+    fn palette_override_replaces_profile_colors_only() {
+        let mut model = TerminalViewModel::new(TerminalId(1));
+        model.resize_grid(2, 1, Some((8.0, 16.0)));
+        row_mut(&mut model.grid[0])[0].ch = 'a';
+        row_mut(&mut model.grid[0])[0].fg = ansi_color(1);
+        row_mut(&mut model.grid[0])[1].ch = 'b';
+        row_mut(&mut model.grid[0])[1].fg = ansi_color(2);
+        model.take_dirty_rows();
+
+        let tokens = DesignTokens::dark();
+        let theme_palette = TerminalAnsiPalette::from_tokens(&tokens);
+        let background: Hsla = rgb(0x101010).into();
+        let red: Hsla = rgb(0xff5555).into();
+        let mut ansi = [None; 16];
+        ansi[1] = Some(red);
+        model.set_palette_override(Some(TerminalPaletteOverride {
+            background: Some(background),
+            ansi,
+            ..TerminalPaletteOverride::default()
+        }));
+        assert_eq!(model.take_dirty_rows(), vec![0]);
+
+        let grid = model.styled_grid(&tokens);
+        assert_eq!(grid.background, background);
+        assert_eq!(grid.foreground, theme_palette.default_foreground);
+        assert_eq!(grid.rows[0][0].fg, red);
+        assert_eq!(
+            grid.rows[0][1].fg,
+            ColorTheory::ensure_contrast(
+                background,
+                theme_palette.colors[2],
+                ContrastRatios::AA_NORMAL
+            )
+        );
+    }
+
     fn hue_distance(a: Hsla, b: Hsla) -> f32 {
         let raw = (a.h - b.h).abs();
         raw.min(1.0 - raw)
@@ -1430,15 +2443,27 @@ impl Render for TerminalRowView {
         let theme = cx.theme();
         let tokens = &theme.tokens;
         let editor_font = cx.global::<nucleotide_types::EditorFontConfig>();
-        let ansi_palette = TerminalAnsiPalette::from_tokens(tokens);
 
-        let (grid_row, cursor_row, cursor_col, cell_width, cell_height, selection, cols) = {
+        let (
+            grid_row,
+            cursor_row,
+            cursor_col,
+            cell_width,
+            cell_height,
+            selection,
+            cols,
+            palette_override,
+            cursor_style,
+            focused,
+            hovered_link,
+            command,
+        ) = {
             let guard = lock_or_recover(self.model.as_ref());
-            let row = if self.row_index < guard.grid.len() {
-                guard.grid[self.row_index].clone()
-            } else {
-                Vec::new()
-            };
+            let row = guard
+                .grid
+                .get(self.row_index)
+                .cloned()
+                .unwrap_or_else(|| GridRow::from([]));
             (
                 row,
                 guard.cursor_row as usize,
@@ -1447,8 +2472,14 @@ impl Render for TerminalRowView {
                 guard.cell_height,
                 guard.selection,
                 guard.cols as usize,
+                guard.palette_override,
+                guard.cursor_style,
+                guard.focused,
+                guard.hovered_link.clone(),
+                guard.command_at_row(self.row_index),
             )
         };
+        let ansi_palette = TerminalAnsiPalette::resolve(tokens, palette_override.as_ref());
 
         let fallback_cell_width = editor_font.size * 0.6;
         let applied_cell_width = if cell_width > 0.0 {
@@ -1465,6 +2496,7 @@ impl Render for TerminalRowView {
         let line_height_px = gpui::px(applied_line_height);
 
         let mut line = div()
+            .relative()
             .flex()
             .flex_row()
             .w_full()
@@ -1486,9 +2518,11 @@ impl Render for TerminalRowView {
         let mut cur_inverse = false;
         let mut cur_selected = false;
         let mut buf = String::new();
+        let mut buf_cells = 0usize;
 
         let flush_run = |line_in: gpui::Div,
                          text: &mut String,
+                         text_cells: &mut usize,
                          fg: u32,
                          bg: u32,
                          bold: bool,
@@ -1496,6 +2530,7 @@ impl Render for TerminalRowView {
                          underline: bool,
                          inverse: bool,
                          selected: bool| {
+            let cell_count = std::mem::take(text_cells).max(1);
             if text.is_empty() {
                 return line_in;
             }
@@ -1508,11 +2543,10 @@ impl Render for TerminalRowView {
                 ContrastRatios::AA_NORMAL,
             );
             if selected {
-                contrast_bg = tokens.editor.selection_primary;
-                mapped_fg = tokens.editor.text_on_primary;
+                contrast_bg = tokens.terminal.selection;
+                mapped_fg = tokens.terminal.selection_text;
             }
 
-            let cell_count = text.chars().count().max(1);
             let rendered_text = terminal_render_text(&std::mem::take(text));
             let mut run = div()
                 .w(gpui::px(applied_cell_width * cell_count as f32))
@@ -1535,12 +2569,19 @@ impl Render for TerminalRowView {
         };
 
         for (i, cell) in grid_row.iter().enumerate() {
+            // The wide character before a spacer is drawn across both columns
+            if cell.width == CellWidth::Spacer {
+                continue;
+            }
             let (fg, bg, bold, italic, underline, inverse) = (
                 cell.fg,
                 cell.bg,
                 cell.bold,
                 cell.italic,
-                cell.underline,
+                cell.underline
+                    || hovered_link
+                        .as_ref()
+                        .is_some_and(|link| link.covers(self.row_index, i, cell)),
                 cell.inverse,
             );
             let selected = selection.is_some_and(|selection| {
@@ -1564,6 +2605,7 @@ impl Render for TerminalRowView {
                 line = flush_run(
                     line,
                     &mut buf,
+                    &mut buf_cells,
                     cur_fg,
                     cur_bg,
                     cur_bold,
@@ -1581,11 +2623,14 @@ impl Render for TerminalRowView {
                 cur_selected = selected;
             }
             // Cursor rendering: render a block cursor at (cursor_row, cursor_col)
-            if self.row_index == cursor_row && i == cursor_col {
+            let on_cursor =
+                i == cursor_col || (cell.width == CellWidth::Wide && i + 1 == cursor_col);
+            if cursor_style.visible && self.row_index == cursor_row && on_cursor {
                 // Flush any accumulated text
                 line = flush_run(
                     line,
                     &mut buf,
+                    &mut buf_cells,
                     cur_fg,
                     cur_bg,
                     cur_bold,
@@ -1594,27 +2639,84 @@ impl Render for TerminalRowView {
                     cur_inverse,
                     cur_selected,
                 );
-                // Render the cursor cell as a block using theme tokens
+                let mut cursor_text = String::new();
+                cell.push_text(&mut cursor_text);
+                let cursor_width = gpui::px(applied_cell_width * cell.columns() as f32);
+                let cursor_color = tokens.terminal.cursor;
                 let mut run = div()
-                    .w(gpui::px(applied_cell_width))
+                    .relative()
+                    .w(cursor_width)
                     .h(line_height_px)
                     .flex_shrink_0()
                     .overflow_hidden()
-                    .child(terminal_render_text(&cell.ch.to_string()));
-                run = run.bg(tokens.editor.cursor_normal);
-                run = run.text_color(tokens.editor.text_on_primary);
-                // Make cursor more prominent
-                run = run.font_weight(FontWeight::BOLD);
+                    .child(terminal_render_text(&cursor_text));
+                if focused && cursor_style.shape == TerminalCursorShape::Block {
+                    run = run
+                        .bg(cursor_color)
+                        .text_color(tokens.terminal.cursor_text)
+                        .font_weight(FontWeight::BOLD);
+                } else {
+                    // Bar, underline and hollow cursors are drawn over the
+                    // cell so its text stays in place.
+                    let (fg, bg) = if cell.inverse {
+                        (cell.bg, cell.fg)
+                    } else {
+                        (cell.fg, cell.bg)
+                    };
+                    let bg = ansi_palette
+                        .background_for_code(bg)
+                        .unwrap_or(ansi_palette.default_background);
+                    run = run.bg(bg).text_color(ColorTheory::ensure_contrast(
+                        bg,
+                        ansi_palette.foreground_for_code(fg),
+                        ContrastRatios::AA_NORMAL,
+                    ));
+                    let overlay = div().absolute().left_0();
+                    let overlay = if !focused {
+                        overlay
+                            .top_0()
+                            .size_full()
+                            .border_1()
+                            .border_color(cursor_color)
+                    } else if cursor_style.shape == TerminalCursorShape::Bar {
+                        overlay.top_0().h_full().w(gpui::px(2.0)).bg(cursor_color)
+                    } else {
+                        overlay
+                            .bottom_0()
+                            .w_full()
+                            .h(gpui::px(2.0))
+                            .bg(cursor_color)
+                    };
+                    run = run.child(overlay);
+                }
                 line = line.child(run);
                 // Do not push this char into the normal buffer
                 continue;
             }
-            buf.push(cell.ch);
+            cell.push_text(&mut buf);
+            buf_cells += cell.columns();
+            // A wide glyph's advance rarely matches two cells exactly, so it
+            // ends its run to keep the columns after it in place.
+            if cell.width == CellWidth::Wide {
+                line = flush_run(
+                    line,
+                    &mut buf,
+                    &mut buf_cells,
+                    cur_fg,
+                    cur_bg,
+                    cur_bold,
+                    cur_italic,
+                    cur_underline,
+                    cur_inverse,
+                    cur_selected,
+                );
+            }
         }
         // flush last
         line = flush_run(
             line,
             &mut buf,
+            &mut buf_cells,
             cur_fg,
             cur_bg,
             cur_bold,
@@ -1624,6 +2726,28 @@ impl Render for TerminalRowView {
             cur_selected,
         );
 
+        // Shell integration marks each prompt with its command's outcome
+        if let Some(status) = command {
+            let color = match status {
+                CommandStatus::Editing | CommandStatus::Running => {
+                    tokens.editor.text_secondary.opacity(0.5)
+                }
+                CommandStatus::Finished {
+                    exit_code: None | Some(0),
+                } => tokens.editor.success.opacity(0.7),
+                CommandStatus::Finished { .. } => tokens.editor.error,
+            };
+            line = line.child(
+                div()
+                    .absolute()
+                    .left_0()
+                    .top_0()
+                    .h_full()
+                    .w(gpui::px(2.0))
+                    .bg(color),
+            );
+        }
+
         line
     }
 }
@@ -1697,4 +2821,24 @@ mod registry_tests {
         assert_eq!(model.window_title(), None);
         assert_eq!(model.display_title(), "Terminal");
     }
+
+    #[test]
+    fn terminal_title_changes_fall_back_to_shell_name() {
+        let mut model = TerminalViewModel::new(TerminalId(11));
+        assert_eq!(model.take_title_change(), None);
+
+        model.set_fallback_title("zsh");
+        assert_eq!(model.take_title_change().as_deref(), Some("zsh"));
+        assert_eq!(model.take_title_change(), None);
+
+        model.set_window_title("vim file.rs");
+        assert_eq!(model.take_title_change().as_deref(), Some("vim file.rs"));
+
+        model.set_window_title("vim file.rs");
+        model.set_fallback_title("bash");
+        assert_eq!(model.take_title_change(), None);
+
+        model.set_window_title("");
+        assert_eq!(model.take_title_change().as_deref(), Some("bash"));
+    }
 }
@@ -24,6 +24,11 @@ pub mod frame {
         }
     }
 
+    /// One grid row. Rows are shared between the engine, queued frames and the
+    /// view model, and copied only when a row is written while still shared.
+    #[cfg(feature = "emulator")]
+    pub type GridRow = std::sync::Arc<[Cell]>;
+
     #[derive(Debug, Clone)]
     pub enum FramePayload {
         Raw(Vec<u8>),
@@ -31,12 +36,38 @@ pub mod frame {
         Full(GridSnapshot),
         #[cfg(feature = "emulator")]
         Diff(GridDiff),
+        /// A shell integration mark, sent after the frame showing its line
+        #[cfg(feature = "emulator")]
+        Shell(crate::shell_integration::ShellEvent),
+    }
+
+    impl FramePayload {
+        /// Whether the frame replaces everything sent before it, so earlier
+        /// frames still queued can be dropped. Diffs apply on top of them.
+        pub fn is_full(&self) -> bool {
+            match self {
+                #[cfg(feature = "emulator")]
+                Self::Full(_) => true,
+                _ => false,
+            }
+        }
+
+        /// Whether the payload carries a shell integration mark rather than
+        /// grid contents. Marks are kept when a full frame replaces the
+        /// frames queued before it.
+        pub fn is_shell_event(&self) -> bool {
+            match self {
+                #[cfg(feature = "emulator")]
+                Self::Shell(_) => true,
+                _ => false,
+            }
+        }
     }
 
     #[cfg(feature = "emulator")]
     #[derive(Debug, Clone)]
     pub struct GridSnapshot {
-        pub rows: Vec<Vec<Cell>>, // row-major
+        pub rows: Vec<GridRow>, // row-major
         pub cols: u16,
         pub rows_len: u16,
         pub cursor_row: u16,
@@ -45,6 +76,32 @@ pub mod frame {
         pub history_size: usize,
         pub display_offset: usize,
         pub input_mode: TerminalInputMode,
+        pub cursor_style: TerminalCursorStyle,
+    }
+
+    /// Cursor appearance requested by the program, via DECSCUSR for the shape
+    /// and DECTCEM for visibility.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct TerminalCursorStyle {
+        pub shape: TerminalCursorShape,
+        pub visible: bool,
+    }
+
+    impl Default for TerminalCursorStyle {
+        fn default() -> Self {
+            Self {
+                shape: TerminalCursorShape::Block,
+                visible: true,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub enum TerminalCursorShape {
+        #[default]
+        Block,
+        Underline,
+        Bar,
     }
 
     #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -53,15 +110,21 @@ pub mod frame {
         pub alternate_screen: bool,
         pub alternate_scroll: bool,
         pub mouse_mode: bool,
+        /// The program asked for pastes wrapped in `ESC [200~` and
+        /// `ESC [201~` (DECSET 2004)
+        pub bracketed_paste: bool,
     }
 
     #[cfg(feature = "emulator")]
     #[derive(Debug, Clone)]
     pub struct GridDiff {
         pub lines: Vec<ChangedLine>,
+        /// Rows the previous grid scrolled up by before `lines` apply
         pub scrolled: Option<i32>,
         pub cursor_row: u16,
         pub cursor_col: u16,
+        pub cursor_style: TerminalCursorStyle,
+        pub history_size: usize,
     }
 
     #[cfg(feature = "emulator")]
@@ -79,15 +142,60 @@ pub mod frame {
     }
 
     #[cfg(feature = "emulator")]
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
     pub struct Cell {
+        /// First character of the cell's grapheme cluster
         pub ch: char,
+        /// Rest of the cluster: combining marks, variation selectors and
+        /// joined emoji. `None` for the common single-character cell.
+        pub combining: Option<std::sync::Arc<str>>,
+        pub width: CellWidth,
         pub fg: u32,
         pub bg: u32,
         pub bold: bool,
         pub italic: bool,
         pub underline: bool,
         pub inverse: bool,
+        /// Target of the link the cell is part of: an OSC 8 URI, or a URL
+        /// found in the row's text.
+        pub hyperlink: Option<std::sync::Arc<str>>,
+    }
+
+    #[cfg(feature = "emulator")]
+    impl Cell {
+        /// Appends the cell's grapheme cluster. Spacers add nothing, their
+        /// wide character already covers the column.
+        pub fn push_text(&self, text: &mut String) {
+            if self.width == CellWidth::Spacer {
+                return;
+            }
+            text.push(self.ch);
+            if let Some(combining) = &self.combining {
+                text.push_str(combining);
+            }
+        }
+
+        /// Columns the cell's text is drawn across.
+        pub fn columns(&self) -> usize {
+            match self.width {
+                CellWidth::Narrow => 1,
+                CellWidth::Wide => 2,
+                CellWidth::Spacer => 0,
+            }
+        }
+    }
+
+    /// Columns a cell's character occupies.
+    #[cfg(feature = "emulator")]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub enum CellWidth {
+        #[default]
+        Narrow,
+        /// Double-width character such as CJK or emoji; the next cell is its
+        /// spacer.
+        Wide,
+        /// Second column of the wide character before it.
+        Spacer,
     }
 }
 
@@ -100,6 +208,7 @@ pub mod session {
     use std::sync::{Arc, Mutex};
     use tokio::sync::mpsc::{self, Receiver};
 
+    use crate::cwd::{Osc7Scanner, SharedTerminalCwd};
     use crate::frame::FramePayload;
 
     /// Control messages for the emulator engine (only when emulator feature is enabled)
@@ -116,6 +225,15 @@ pub mod session {
         },
     }
 
+    /// Input queued for a session's writer.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum SessionInput {
+        /// Raw bytes such as encoded keystrokes.
+        Bytes(Vec<u8>),
+        /// Clipboard text, encoded by [`TerminalSession::paste`].
+        Paste { text: String, bracketed: bool },
+    }
+
     #[derive(Debug, Clone, Default)]
     pub struct TerminalSessionCfg {
         pub cwd: Option<PathBuf>,
@@ -125,6 +243,11 @@ pub mod session {
         pub env: Vec<(String, String)>,
         pub cols: Option<u16>,
         pub rows: Option<u16>,
+        /// Name of the configured profile this session was started from
+        pub profile: Option<String>,
+        /// Lines of history kept above the viewport. Unset uses
+        /// `engine::DEFAULT_SCROLLBACK_LINES`.
+        pub scrollback_lines: Option<usize>,
     }
 
     pub struct TerminalSession {
@@ -132,6 +255,7 @@ pub mod session {
         master: Box<dyn portable_pty::MasterPty + Send>,
         child: Box<dyn portable_pty::Child + Send>,
         writer: Arc<Mutex<Box<dyn Write + Send>>>,
+        cwd: SharedTerminalCwd,
         #[cfg(feature = "emulator")]
         control_tx: std::sync::mpsc::Sender<ControlMsg>,
     }
@@ -175,6 +299,11 @@ pub mod session {
             let writer = pair.master.take_writer().context("take PTY writer")?;
 
             let writer = Arc::new(Mutex::new(writer));
+            // Command sessions (tasks, SSH proxies) start in a local directory
+            // that says nothing about where their shell ends up.
+            let start_dir = cfg.program.is_none().then(|| cfg.cwd.clone()).flatten();
+            let cwd: SharedTerminalCwd = Arc::new(Mutex::new(start_dir));
+            let reader_cwd = Arc::clone(&cwd);
 
             // Create output channel and blocking read loop
             let (tx, rx) = mpsc::channel::<FramePayload>(1024);
@@ -185,7 +314,7 @@ pub mod session {
 
             #[cfg(feature = "emulator")]
             {
-                use crate::engine::Engine;
+                use crate::engine::{DEFAULT_SCROLLBACK_LINES, Engine};
                 use std::time::{Duration, Instant};
                 let engine_writer = writer.clone();
 
@@ -194,10 +323,12 @@ pub mod session {
                 let (data_tx, data_rx) = std::sync::mpsc::channel::<Vec<u8>>();
                 std::thread::spawn(move || {
                     let mut buf = vec![0u8; 8192];
+                    let mut cwd_scanner = Osc7Scanner::default();
                     loop {
                         match reader.read(&mut buf) {
                             Ok(0) => break,
                             Ok(n) => {
+                                record_reported_cwd(&reader_cwd, &mut cwd_scanner, &buf[..n]);
                                 if data_tx.send(buf[..n].to_vec()).is_err() {
                                     break;
                                 }
@@ -208,9 +339,10 @@ pub mod session {
                 });
 
                 tokio::task::spawn_blocking(move || {
-                    let mut engine = Engine::new(
+                    let mut engine = Engine::with_scrollback(
                         cfg.cols.unwrap_or(80),
                         cfg.rows.unwrap_or(24),
+                        cfg.scrollback_lines.unwrap_or(DEFAULT_SCROLLBACK_LINES),
                         Some(engine_writer),
                     );
                     let mut last_emit = Instant::now();
@@ -246,7 +378,7 @@ pub mod session {
                             }
                             Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
                             Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
-                                if let Some(frame) = engine.take_frame() {
+                                for frame in engine.take_frames() {
                                     let _ = tx.try_send(frame);
                                 }
                                 break;
@@ -255,8 +387,9 @@ pub mod session {
                         // Rate-limited frame emission
                         if needs_frame && last_emit.elapsed() >= window {
                             if engine
-                                .take_frame()
-                                .is_some_and(|frame| tx.try_send(frame).is_err())
+                                .take_frames()
+                                .into_iter()
+                                .any(|frame| tx.try_send(frame).is_err())
                             {
                                 break;
                             }
@@ -271,10 +404,12 @@ pub mod session {
             {
                 tokio::task::spawn_blocking(move || {
                     let mut buf = vec![0u8; 8192];
+                    let mut cwd_scanner = Osc7Scanner::default();
                     loop {
                         match reader.read(&mut buf) {
                             Ok(0) => break, // EOF
                             Ok(n) => {
+                                record_reported_cwd(&reader_cwd, &mut cwd_scanner, &buf[..n]);
                                 if tx.try_send(FramePayload::Raw(buf[..n].to_vec())).is_err() {
                                     break;
                                 }
@@ -290,6 +425,7 @@ pub mod session {
                 master: pair.master,
                 child,
                 writer,
+                cwd,
                 #[cfg(feature = "emulator")]
                 control_tx,
             };
@@ -297,6 +433,20 @@ pub mod session {
             Ok((session, rx))
         }
 
+        /// Directory last reported by the shell via OSC 7. Shell sessions
+        /// start with their configured directory; command sessions with none.
+        pub fn current_dir(&self) -> Option<PathBuf> {
+            self.cwd
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clone()
+        }
+
+        /// Shared handle that follows the session's reported directory.
+        pub fn cwd_tracker(&self) -> SharedTerminalCwd {
+            Arc::clone(&self.cwd)
+        }
+
         pub async fn write(&self, bytes: &[u8]) -> std::io::Result<()> {
             self.write_sync(bytes)
         }
@@ -312,6 +462,20 @@ pub mod session {
             guard.flush()
         }
 
+        /// Writes pasted `text`, wrapped for bracketed paste when the program
+        /// asked for it.
+        pub fn paste(&self, text: &str, bracketed: bool) -> std::io::Result<()> {
+            self.write_sync(&crate::paste::paste_bytes(text, bracketed))
+        }
+
+        /// Writes queued input.
+        pub fn send(&self, input: &SessionInput) -> std::io::Result<()> {
+            match input {
+                SessionInput::Bytes(bytes) => self.write_sync(bytes),
+                SessionInput::Paste { text, bracketed } => self.paste(text, *bracketed),
+            }
+        }
+
         pub async fn resize(&self, cols: u16, rows: u16) -> std::io::Result<()> {
             let size = PtySize {
                 rows,
@@ -394,6 +558,40 @@ pub mod session {
         }
     }
 
+    fn record_reported_cwd(cwd: &SharedTerminalCwd, scanner: &mut Osc7Scanner, bytes: &[u8]) {
+        if let Some(reported) = scanner.scan(bytes) {
+            *cwd.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(reported);
+        }
+    }
+
+    /// Short name of the program a session runs (`zsh`, `pwsh`), used as the
+    /// terminal title until the program reports one with OSC 0/2.
+    pub fn terminal_display_name(cfg: &TerminalSessionCfg) -> Option<String> {
+        if let Some(program) = cfg.program.as_deref().or(cfg.shell.as_deref()) {
+            return program_display_name(program);
+        }
+
+        #[cfg(windows)]
+        {
+            program_display_name(&windows_shell::default_shell_command().0)
+        }
+        #[cfg(not(windows))]
+        {
+            std::env::var("SHELL")
+                .ok()
+                .and_then(|shell| program_display_name(&shell))
+        }
+    }
+
+    fn program_display_name(program: &str) -> Option<String> {
+        let file_name = program.trim().rsplit(['/', '\\']).next()?;
+        let name = file_name
+            .strip_suffix(".exe")
+            .or_else(|| file_name.strip_suffix(".EXE"))
+            .unwrap_or(file_name);
+        (!name.is_empty()).then(|| name.to_string())
+    }
+
     enum ShellEnvMode<'a> {
         Preserve,
         ExplicitShell(&'a str),
@@ -752,6 +950,23 @@ pub mod session {
             assert!(cmd.get_env("SHELL").is_none());
         }
 
+        #[test]
+        fn terminal_display_name_uses_program_file_name() {
+            let cfg = TerminalSessionCfg {
+                shell: Some("/usr/local/bin/fish".to_string()),
+                ..TerminalSessionCfg::default()
+            };
+            assert_eq!(terminal_display_name(&cfg).as_deref(), Some("fish"));
+
+            let cfg = TerminalSessionCfg {
+                program: Some(r"C:\Program Files\PowerShell\7\pwsh.exe".to_string()),
+                shell: Some("/bin/bash".to_string()),
+                ..TerminalSessionCfg::default()
+            };
+            assert_eq!(terminal_display_name(&cfg).as_deref(), Some("pwsh"));
+            assert_eq!(program_display_name("  "), None);
+        }
+
         #[test]
         fn explicit_shell_session_sets_shell_environment_to_override() {
             let env = terminal_env_with_defaults_from(
@@ -806,31 +1021,49 @@ pub mod session {
 
 #[cfg(feature = "emulator")]
 pub mod engine {
+    use crate::cursor::CursorStyleScanner;
     use crate::frame::{
-        Cell, DEFAULT_BACKGROUND, DEFAULT_FOREGROUND, FramePayload, GridSnapshot,
-        TerminalInputMode, ansi_color,
+        Cell, CellWidth, ChangedLine, ChangedRange, DEFAULT_BACKGROUND, DEFAULT_FOREGROUND,
+        FramePayload, GridDiff, GridRow, GridSnapshot, TerminalCursorStyle, TerminalInputMode,
+        ansi_color,
     };
+    use crate::hyperlink::{Osc8Scanner, link_row};
+    use crate::shell_integration::{Osc133Scanner, ShellEvent};
     use libghostty_vt::render::{CellIterator, RenderState, RowIterator};
     use libghostty_vt::style::{PaletteIndex, RgbColor, Style, StyleColor, Underline};
     use libghostty_vt::{Terminal, TerminalOptions};
     use std::io::Write;
     use std::sync::{Arc, Mutex};
+    use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
     const DEFAULT_CELL_WIDTH: f32 = 8.0;
     const DEFAULT_CELL_HEIGHT: f32 = 16.0;
-    const DEFAULT_SCROLLBACK_LINES: usize = 10_000;
+    /// History kept above the viewport when a session doesn't configure it.
+    pub const DEFAULT_SCROLLBACK_LINES: usize = 10_000;
+    /// Share of the grid's cells a frame may change and still be sent as a
+    /// diff rather than in full.
+    const DIFF_COVERAGE_THRESHOLD: f32 = 0.45;
 
     pub struct Engine {
         cols: u16,
         rows: u16,
         cell_width: f32,
         cell_height: f32,
-        grid: Vec<Vec<Cell>>,
+        /// Rows of the last frame, reused by the next frame where unchanged
+        grid: Vec<GridRow>,
+        /// The last frame besides its rows; `None` until a full frame is sent
+        last_frame: Option<SentFrame>,
         terminal: Option<Terminal<'static, 'static>>,
         render_state: Option<RenderState<'static>>,
         row_iter: Option<RowIterator<'static>>,
         cell_iter: Option<CellIterator<'static>>,
+        cursor_style: CursorStyleScanner,
+        hyperlinks: Osc8Scanner,
+        shell_marks: Osc133Scanner,
+        /// Marks reported since the last frame, sent after it
+        shell_events: Vec<ShellEvent>,
         pty_writer: Option<Arc<Mutex<Box<dyn Write + Send>>>>,
+        scrollback_lines: usize,
     }
 
     impl Engine {
@@ -838,6 +1071,17 @@ pub mod engine {
             cols: u16,
             rows: u16,
             pty_writer: Option<Arc<Mutex<Box<dyn Write + Send>>>>,
+        ) -> Self {
+            Self::with_scrollback(cols, rows, DEFAULT_SCROLLBACK_LINES, pty_writer)
+        }
+
+        /// An engine keeping `scrollback_lines` lines of history above the
+        /// viewport. Zero disables scrollback.
+        pub fn with_scrollback(
+            cols: u16,
+            rows: u16,
+            scrollback_lines: usize,
+            pty_writer: Option<Arc<Mutex<Box<dyn Write + Send>>>>,
         ) -> Self {
             let cols = cols.max(1);
             let rows = rows.max(1);
@@ -847,24 +1091,73 @@ pub mod engine {
                 cell_width: DEFAULT_CELL_WIDTH,
                 cell_height: DEFAULT_CELL_HEIGHT,
                 grid: blank_grid(cols, rows),
+                last_frame: None,
                 terminal: None,
                 render_state: None,
                 row_iter: None,
                 cell_iter: None,
+                cursor_style: CursorStyleScanner::default(),
+                hyperlinks: Osc8Scanner::default(),
+                shell_marks: Osc133Scanner::default(),
+                shell_events: Vec::new(),
                 pty_writer,
+                scrollback_lines,
             };
             engine.rebuild_terminal();
             engine
         }
 
         pub fn feed_bytes(&mut self, bytes: &[u8]) {
-            if self.ensure_initialized()
-                && let Some(terminal) = &mut self.terminal
-            {
+            if !self.ensure_initialized() {
+                return;
+            }
+            // Marks are placed on the cursor line once the output before
+            // them has been written.
+            let mut written = 0;
+            for (end, mark) in self.shell_marks.scan(bytes) {
+                self.write_vt(&bytes[written..end]);
+                written = end;
+                if let Some(line) = self.cursor_line() {
+                    self.shell_events.push(ShellEvent { mark, line });
+                }
+            }
+            self.write_vt(&bytes[written..]);
+        }
+
+        fn write_vt(&mut self, bytes: &[u8]) {
+            if let Some(terminal) = &mut self.terminal {
                 terminal.vt_write(bytes);
+                self.cursor_style.scan(bytes);
+                self.hyperlinks.scan(bytes);
             }
         }
 
+        /// Scrollback line of the cursor. `None` on the alternate screen,
+        /// where full-screen programs run without prompts of their own.
+        fn cursor_line(&mut self) -> Option<usize> {
+            let terminal = self.terminal.as_mut()?;
+            if alternate_screen_active(terminal) {
+                return None;
+            }
+            let render_state = self.render_state.as_mut()?;
+            let cursor = render_state
+                .update(terminal)
+                .ok()?
+                .cursor_viewport()
+                .ok()
+                .flatten()?;
+            let (history_size, display_offset) = scroll_position(terminal);
+            Some(history_size.saturating_sub(display_offset) + usize::from(cursor.y))
+        }
+
+        /// The next frame followed by the shell marks reported up to it.
+        pub fn take_frames(&mut self) -> Vec<FramePayload> {
+            let mut frames = Vec::with_capacity(self.shell_events.len() + 1);
+            frames.extend(self.take_frame());
+            frames.extend(self.shell_events.drain(..).map(FramePayload::Shell));
+            frames
+        }
+
         pub fn take_frame(&mut self) -> Option<FramePayload> {
             if !self.ensure_initialized() {
                 return None;
@@ -879,48 +1172,62 @@ pub mod engine {
             let cols = snapshot.cols().unwrap_or(self.cols).max(1);
             let rows_len = snapshot.rows().unwrap_or(self.rows).max(1);
             let blank = blank_cell();
-            let mut grid = vec![vec![blank; cols as usize]; rows_len as usize];
+            let mut grid = Vec::with_capacity(rows_len as usize);
+            let mut cells = Vec::with_capacity(cols as usize);
 
-            let mut row_index = 0usize;
             let mut row_iter = row_iter_handle.update(&snapshot).ok()?;
             while let Some(row) = row_iter.next() {
-                if row_index >= grid.len() {
+                if grid.len() >= rows_len as usize {
                     break;
                 }
 
-                let mut col_index = 0usize;
+                cells.clear();
                 let mut cell_iter = cell_iter_handle.update(row).ok()?;
                 while let Some(cell) = cell_iter.next() {
-                    if col_index >= grid[row_index].len() {
+                    if cells.len() >= cols as usize {
                         break;
                     }
 
                     let style = cell.style().unwrap_or_default();
-                    grid[row_index][col_index] = Cell {
-                        ch: first_grapheme_char(cell.graphemes().ok().as_deref()),
+                    let (ch, combining) = split_grapheme(cell.graphemes().ok().as_deref());
+                    // Ghostty lays a wide character out as the character
+                    // followed by an empty spacer cell.
+                    let width = if cells
+                        .last()
+                        .is_some_and(|previous| previous.width == CellWidth::Wide)
+                    {
+                        CellWidth::Spacer
+                    } else if cells.len() + 1 < cols as usize
+                        && grapheme_is_wide(ch, combining.as_deref())
+                    {
+                        CellWidth::Wide
+                    } else {
+                        CellWidth::Narrow
+                    };
+                    cells.push(Cell {
+                        ch,
+                        combining,
+                        width,
                         fg: foreground_cell_color(&style, cell.fg_color().ok().flatten()),
                         bg: background_cell_color(&style, cell.bg_color().ok().flatten()),
                         bold: style.bold,
                         italic: style.italic,
                         underline: style.underline != Underline::None,
                         inverse: style.inverse,
-                    };
-                    col_index += 1;
+                        hyperlink: None,
+                    });
                 }
+                cells.resize(cols as usize, blank.clone());
+                link_row(&mut cells, &self.hyperlinks);
 
-                row_index += 1;
+                grid.push(reuse_or_share_row(self.grid.get(grid.len()), &cells));
+            }
+            if grid.len() < rows_len as usize {
+                grid.resize(rows_len as usize, blank_row(cols));
             }
 
             let cursor = snapshot.cursor_viewport().ok().flatten();
-            let scrollbar = terminal.scrollbar().ok();
-            let history_size = scrollbar
-                .as_ref()
-                .map(|scrollbar| scrollbar.total.saturating_sub(scrollbar.len) as usize)
-                .unwrap_or_else(|| terminal.scrollback_rows().unwrap_or(0));
-            let display_offset = scrollbar
-                .as_ref()
-                .map(|scrollbar| history_size.saturating_sub(scrollbar.offset as usize))
-                .unwrap_or(0);
+            let (history_size, display_offset) = scroll_position(terminal);
             let title = terminal
                 .title()
                 .ok()
@@ -928,10 +1235,10 @@ pub mod engine {
 
             self.cols = cols;
             self.rows = rows_len;
-            self.grid = grid;
+            let previous = std::mem::replace(&mut self.grid, grid);
 
-            Some(FramePayload::Full(GridSnapshot {
-                rows: self.grid.clone(),
+            let snapshot = GridSnapshot {
+                rows: Vec::new(),
                 cols,
                 rows_len,
                 cursor_row: cursor.map(|cursor| cursor.y).unwrap_or(0),
@@ -943,21 +1250,51 @@ pub mod engine {
                     application_cursor: terminal
                         .mode(libghostty_vt::terminal::Mode::DECCKM)
                         .unwrap_or(false),
-                    alternate_screen: terminal
-                        .mode(libghostty_vt::terminal::Mode::ALT_SCREEN)
-                        .unwrap_or(false)
-                        || terminal
-                            .mode(libghostty_vt::terminal::Mode::ALT_SCREEN_LEGACY)
-                            .unwrap_or(false)
-                        || terminal
-                            .mode(libghostty_vt::terminal::Mode::ALT_SCREEN_SAVE)
-                            .unwrap_or(false),
+                    alternate_screen: alternate_screen_active(terminal),
                     alternate_scroll: terminal
                         .mode(libghostty_vt::terminal::Mode::ALT_SCROLL)
                         .unwrap_or(false),
                     mouse_mode: terminal.is_mouse_tracking().unwrap_or(false),
+                    bracketed_paste: terminal
+                        .mode(libghostty_vt::terminal::Mode::BRACKETED_PASTE)
+                        .unwrap_or(false),
                 },
-            }))
+                cursor_style: self.cursor_style.style(),
+            };
+            self.next_frame(&previous, snapshot)
+        }
+
+        /// Sends `snapshot` as a diff against the last frame when only its
+        /// rows, cursor and history changed and few enough cells did, and in
+        /// full otherwise. `None` when nothing changed.
+        fn next_frame(
+            &mut self,
+            previous: &[GridRow],
+            mut snapshot: GridSnapshot,
+        ) -> Option<FramePayload> {
+            let sent = SentFrame::of(&snapshot);
+            let last = self.last_frame.replace(sent.clone());
+            if let Some(last) = last.filter(|last| last.diffable(&sent)) {
+                let (lines, scrolled, changed) =
+                    diff_grids(previous, &mut self.grid, snapshot.cols);
+                if lines.is_empty() && scrolled.is_none() && last == sent {
+                    return None;
+                }
+                let cells = usize::from(snapshot.cols) * self.grid.len();
+                if changed as f32 <= cells as f32 * DIFF_COVERAGE_THRESHOLD {
+                    return Some(FramePayload::Diff(GridDiff {
+                        lines,
+                        scrolled,
+                        cursor_row: snapshot.cursor_row,
+                        cursor_col: snapshot.cursor_col,
+                        cursor_style: snapshot.cursor_style,
+                        history_size: snapshot.history_size,
+                    }));
+                }
+            }
+
+            snapshot.rows = self.grid.clone();
+            Some(FramePayload::Full(snapshot))
         }
 
         pub fn resize_with_metrics(
@@ -972,6 +1309,7 @@ pub mod engine {
             self.cell_width = cell_width.max(1.0);
             self.cell_height = cell_height.max(1.0);
             self.grid = blank_grid(self.cols, self.rows);
+            self.last_frame = None;
 
             if self.ensure_initialized()
                 && let Some(terminal) = &mut self.terminal
@@ -1027,7 +1365,7 @@ pub mod engine {
             let mut terminal = match Terminal::new(TerminalOptions {
                 cols: self.cols,
                 rows: self.rows,
-                max_scrollback: DEFAULT_SCROLLBACK_LINES,
+                max_scrollback: self.scrollback_lines,
             }) {
                 Ok(terminal) => terminal,
                 Err(_) => {
@@ -1054,32 +1392,213 @@ pub mod engine {
             );
 
             self.terminal = Some(terminal);
+            self.last_frame = None;
+            self.cursor_style = CursorStyleScanner::default();
+            self.hyperlinks = Osc8Scanner::default();
+            self.shell_marks = Osc133Scanner::default();
+            self.shell_events.clear();
             self.render_state = RenderState::new().ok();
             self.row_iter = RowIterator::new().ok();
             self.cell_iter = CellIterator::new().ok();
         }
     }
 
-    fn blank_grid(cols: u16, rows: u16) -> Vec<Vec<Cell>> {
-        vec![vec![blank_cell(); cols as usize]; rows as usize]
+    /// Lines of history above the screen, and how far the viewport is
+    /// scrolled up into them.
+    fn scroll_position(terminal: &mut Terminal<'static, 'static>) -> (usize, usize) {
+        let scrollbar = terminal.scrollbar().ok();
+        let history_size = scrollbar
+            .as_ref()
+            .map(|scrollbar| scrollbar.total.saturating_sub(scrollbar.len) as usize)
+            .unwrap_or_else(|| terminal.scrollback_rows().unwrap_or(0));
+        let display_offset = scrollbar
+            .as_ref()
+            .map(|scrollbar| history_size.saturating_sub(scrollbar.offset as usize))
+            .unwrap_or(0);
+        (history_size, display_offset)
+    }
+
+    fn alternate_screen_active(terminal: &mut Terminal<'static, 'static>) -> bool {
+        [
+            libghostty_vt::terminal::Mode::ALT_SCREEN,
+            libghostty_vt::terminal::Mode::ALT_SCREEN_LEGACY,
+            libghostty_vt::terminal::Mode::ALT_SCREEN_SAVE,
+        ]
+        .into_iter()
+        .any(|mode| terminal.mode(mode).unwrap_or(false))
+    }
+
+    /// What a frame showed besides its rows.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct SentFrame {
+        cols: u16,
+        rows_len: u16,
+        title: Option<String>,
+        display_offset: usize,
+        input_mode: TerminalInputMode,
+        history_size: usize,
+        cursor: (u16, u16),
+        cursor_style: TerminalCursorStyle,
+    }
+
+    impl SentFrame {
+        fn of(snapshot: &GridSnapshot) -> Self {
+            Self {
+                cols: snapshot.cols,
+                rows_len: snapshot.rows_len,
+                title: snapshot.title.clone(),
+                display_offset: snapshot.display_offset,
+                input_mode: snapshot.input_mode,
+                history_size: snapshot.history_size,
+                cursor: (snapshot.cursor_row, snapshot.cursor_col),
+                cursor_style: snapshot.cursor_style,
+            }
+        }
+
+        /// Whether `next` differs only in what a [`GridDiff`] carries.
+        fn diffable(&self, next: &Self) -> bool {
+            self.cols == next.cols
+                && self.rows_len == next.rows_len
+                && self.title == next.title
+                && self.display_offset == next.display_offset
+                && self.input_mode == next.input_mode
+        }
+    }
+
+    /// The lines of `grid` that differ from `previous`, the rows `previous`
+    /// scrolled up by when treating the change as a scroll touches fewer
+    /// cells, and the number of changed cells. Scrolled rows of `grid` are
+    /// shared with `previous`.
+    fn diff_grids(
+        previous: &[GridRow],
+        grid: &mut [GridRow],
+        cols: u16,
+    ) -> (Vec<ChangedLine>, Option<i32>, usize) {
+        let unchanged = |old: &GridRow, new: &GridRow| GridRow::ptr_eq(old, new) || old == new;
+        let changed_cells = |old: &[Cell], new: &[Cell]| {
+            new.iter()
+                .enumerate()
+                .filter(|&(col, cell)| old.get(col) != Some(cell))
+                .count()
+        };
+        let blank = blank_row(cols);
+        let len = grid.len().min(previous.len());
+
+        let direct = grid
+            .iter()
+            .zip(previous)
+            .filter(|(new, old)| !unchanged(old, new))
+            .map(|(new, old)| changed_cells(old, new))
+            .sum::<usize>();
+        let mut scrolled = None;
+        if direct > 0
+            && let Some(lines) = (1..len).find(|&lines| {
+                (0..len - lines).all(|row| unchanged(&previous[row + lines], &grid[row]))
+            })
+        {
+            let revealed = grid[len - lines..]
+                .iter()
+                .map(|new| changed_cells(&blank, new))
+                .sum::<usize>();
+            if revealed < direct {
+                for (row, shared) in grid[..len - lines].iter_mut().zip(&previous[lines..]) {
+                    *row = shared.clone();
+                }
+                scrolled = Some(lines);
+            }
+        }
+
+        let mut lines = Vec::new();
+        let mut changed = 0;
+        for (row, new) in grid.iter().enumerate() {
+            let old = previous.get(row + scrolled.unwrap_or(0)).unwrap_or(&blank);
+            if unchanged(old, new) {
+                continue;
+            }
+            let ranges = changed_ranges(old, new);
+            changed += ranges.iter().map(|range| range.cells.len()).sum::<usize>();
+            lines.push(ChangedLine {
+                row: row as u32,
+                ranges,
+            });
+        }
+        (lines, scrolled.map(|lines| lines as i32), changed)
+    }
+
+    /// Runs of cells in `new` that differ from `old`.
+    fn changed_ranges(old: &[Cell], new: &[Cell]) -> Vec<ChangedRange> {
+        let mut ranges = Vec::new();
+        let mut col = 0;
+        while col < new.len() {
+            if old.get(col) == Some(&new[col]) {
+                col += 1;
+                continue;
+            }
+            let start = col;
+            while col < new.len() && old.get(col) != Some(&new[col]) {
+                col += 1;
+            }
+            ranges.push(ChangedRange {
+                col: start as u16,
+                cells: new[start..col].to_vec(),
+            });
+        }
+        ranges
+    }
+
+    fn blank_grid(cols: u16, rows: u16) -> Vec<GridRow> {
+        vec![blank_row(cols); rows as usize]
+    }
+
+    fn blank_row(cols: u16) -> GridRow {
+        vec![blank_cell(); cols as usize].into()
+    }
+
+    /// Shares the previous frame's row when its cells are unchanged, so a
+    /// frame only allocates the rows that were redrawn.
+    fn reuse_or_share_row(previous: Option<&GridRow>, cells: &[Cell]) -> GridRow {
+        match previous {
+            Some(previous) if **previous == *cells => previous.clone(),
+            _ => cells.into(),
+        }
     }
 
     fn blank_cell() -> Cell {
         Cell {
             ch: ' ',
+            combining: None,
+            width: CellWidth::Narrow,
             fg: DEFAULT_FOREGROUND,
             bg: DEFAULT_BACKGROUND,
             bold: false,
             italic: false,
             underline: false,
             inverse: false,
+            hyperlink: None,
         }
     }
 
-    fn first_grapheme_char(graphemes: Option<&[char]>) -> char {
-        graphemes
-            .and_then(|graphemes| graphemes.first().copied())
-            .unwrap_or(' ')
+    /// Splits a cell's grapheme cluster into its first character and the
+    /// characters combined with it.
+    fn split_grapheme(graphemes: Option<&[char]>) -> (char, Option<Arc<str>>) {
+        match graphemes {
+            Some([first, rest @ ..]) if !rest.is_empty() => {
+                (*first, Some(rest.iter().collect::<String>().into()))
+            }
+            Some([first, ..]) => (*first, None),
+            _ => (' ', None),
+        }
+    }
+
+    fn grapheme_is_wide(ch: char, combining: Option<&str>) -> bool {
+        match combining {
+            None => ch.width() == Some(2),
+            Some(combining) => {
+                let mut cluster = String::from(ch);
+                cluster.push_str(combining);
+                cluster.width() >= 2
+            }
+        }
     }
 
     fn foreground_cell_color(style: &Style, resolved: Option<RgbColor>) -> u32 {
@@ -1110,6 +1629,7 @@ pub mod engine {
     #[cfg(test)]
     mod tests {
         use super::*;
+        use crate::frame::TerminalCursorShape;
 
         #[test]
         fn truecolor_values_remain_literal_rgb() {
@@ -1135,6 +1655,31 @@ pub mod engine {
             assert!(snapshot.input_mode.application_cursor);
         }
 
+        #[test]
+        fn bracketed_paste_mode_is_reported_in_frames() {
+            let mut engine = Engine::new(5, 2, None);
+
+            engine.feed_bytes(b"\x1b[?2004h");
+
+            let Some(FramePayload::Full(snapshot)) = engine.take_frame() else {
+                panic!("expected full snapshot");
+            };
+            assert!(snapshot.input_mode.bracketed_paste);
+        }
+
+        #[test]
+        fn cursor_style_is_reported_in_frames() {
+            let mut engine = Engine::new(5, 2, None);
+
+            engine.feed_bytes(b"\x1b[5 q\x1b[?25l");
+
+            let Some(FramePayload::Full(snapshot)) = engine.take_frame() else {
+                panic!("expected full snapshot");
+            };
+            assert_eq!(snapshot.cursor_style.shape, TerminalCursorShape::Bar);
+            assert!(!snapshot.cursor_style.visible);
+        }
+
         #[test]
         fn terminal_title_is_reported_in_frames() {
             let mut engine = Engine::new(5, 2, None);
@@ -1147,6 +1692,118 @@ pub mod engine {
             assert_eq!(snapshot.title.as_deref(), Some("Nucleotide Shell"));
         }
 
+        #[test]
+        fn unchanged_rows_are_shared_between_frames() {
+            let mut engine = Engine::new(5, 3, None);
+
+            engine.feed_bytes(b"one\r\ntwo");
+            let Some(FramePayload::Full(first)) = engine.take_frame() else {
+                panic!("expected full snapshot");
+            };
+
+            engine.feed_bytes(b"!");
+            let Some(FramePayload::Diff(diff)) = engine.take_frame() else {
+                panic!("expected diff");
+            };
+
+            assert!(GridRow::ptr_eq(&first.rows[0], &engine.grid[0]));
+            assert!(!GridRow::ptr_eq(&first.rows[1], &engine.grid[1]));
+            assert_eq!(diff.lines.len(), 1);
+            assert_eq!(diff.lines[0].row, 1);
+            assert_eq!(diff.lines[0].ranges[0].col, 3);
+            assert_eq!(diff.lines[0].ranges[0].cells[0].ch, '!');
+            assert_eq!(diff.cursor_col, 4);
+        }
+
+        #[test]
+        fn unchanged_terminal_sends_no_frame() {
+            let mut engine = Engine::new(5, 3, None);
+
+            engine.feed_bytes(b"one");
+            assert!(matches!(engine.take_frame(), Some(FramePayload::Full(_))));
+            assert!(engine.take_frame().is_none());
+        }
+
+        #[test]
+        fn output_scrolling_the_screen_is_sent_as_a_scroll() {
+            let mut engine = Engine::new(8, 4, None);
+
+            engine.feed_bytes(b"aaaaaaaa\r\nbbbbbbbb\r\ncccccccc\r\ndddddddd");
+            let Some(FramePayload::Full(first)) = engine.take_frame() else {
+                panic!("expected full snapshot");
+            };
+
+            engine.feed_bytes(b"\r\ne");
+            let Some(FramePayload::Diff(diff)) = engine.take_frame() else {
+                panic!("expected diff");
+            };
+
+            assert_eq!(diff.scrolled, Some(1));
+            assert_eq!(diff.lines.len(), 1);
+            assert_eq!(diff.lines[0].row, 3);
+            assert!(GridRow::ptr_eq(&first.rows[1], &engine.grid[0]));
+        }
+
+        #[test]
+        fn frames_changing_most_cells_are_sent_in_full() {
+            let mut engine = Engine::new(4, 2, None);
+
+            engine.feed_bytes(b"abcd");
+            assert!(engine.take_frame().is_some());
+
+            engine.feed_bytes(b"\x1b[Hwxyz\r\nwxy");
+            assert!(matches!(engine.take_frame(), Some(FramePayload::Full(_))));
+        }
+
+        #[test]
+        fn shell_marks_follow_the_frame_on_the_lines_they_were_reported() {
+            use crate::shell_integration::ShellMark;
+
+            let mut engine = Engine::new(10, 3, None);
+
+            engine.feed_bytes(b"\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C\x07");
+            engine.feed_bytes(b"a\r\nb\r\n\x1b]133;D;1\x07");
+            let frames = engine.take_frames();
+
+            assert!(matches!(frames[0], FramePayload::Full(_)));
+            let events = frames[1..]
+                .iter()
+                .map(|frame| match frame {
+                    FramePayload::Shell(event) => (event.mark, event.line),
+                    other => panic!("expected a shell event, got {other:?}"),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                events,
+                [
+                    (ShellMark::PromptStarted, 0),
+                    (ShellMark::InputStarted, 0),
+                    (ShellMark::CommandStarted, 1),
+                    (ShellMark::CommandFinished { exit_code: Some(1) }, 3),
+                ]
+            );
+            assert!(engine.take_frames().is_empty());
+        }
+
+        #[test]
+        fn wide_characters_and_combining_marks_keep_columns_aligned() {
+            let mut engine = Engine::new(6, 1, None);
+
+            engine.feed_bytes("漢e\u{301}x".as_bytes());
+            let Some(FramePayload::Full(snapshot)) = engine.take_frame() else {
+                panic!("expected full snapshot");
+            };
+
+            let row = &snapshot.rows[0];
+            assert_eq!(row[0].ch, '漢');
+            assert_eq!(row[0].width, CellWidth::Wide);
+            assert_eq!(row[1].width, CellWidth::Spacer);
+            assert_eq!(row[2].ch, 'e');
+            assert_eq!(row[2].combining.as_deref(), Some("\u{301}"));
+            assert_eq!(row[3].ch, 'x');
+            assert_eq!(snapshot.cursor_col, 4);
+        }
+
         #[cfg(not(all(windows, target_arch = "aarch64")))]
         #[test]
         fn scrollback_display_offset_uses_bottom_zero_convention() {
@@ -1849,6 +2506,843 @@ mod emulator {
     }
 }
 */
+/// Working-directory reports (OSC 7) from shells running in a session.
+pub mod cwd {
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+
+    /// Latest working directory of a session, shared between its reader
+    /// thread and the UI.
+    pub type SharedTerminalCwd = Arc<Mutex<Option<PathBuf>>>;
+
+    /// OSC payloads longer than this are not working-directory reports.
+    const MAX_OSC_PAYLOAD: usize = 4096;
+    const ESC: u8 = 0x1b;
+    const BEL: u8 = 0x07;
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    enum ScanState {
+        #[default]
+        Ground,
+        Escape,
+        Osc,
+        OscEscape,
+    }
+
+    /// Finds OSC 7 (`ESC ] 7 ; file://host/path`, ended by BEL or ST) in PTY
+    /// output. Sequences may be split across reads.
+    #[derive(Debug, Default)]
+    pub struct Osc7Scanner {
+        state: ScanState,
+        payload: Vec<u8>,
+    }
+
+    impl Osc7Scanner {
+        /// Scans `bytes` and returns the last directory reported in them.
+        pub fn scan(&mut self, bytes: &[u8]) -> Option<PathBuf> {
+            let mut reported = None;
+            for &byte in bytes {
+                self.state = match (self.state, byte) {
+                    (ScanState::Osc, BEL) | (ScanState::OscEscape, b'\\') => {
+                        if let Some(path) = self.finish() {
+                            reported = Some(path);
+                        }
+                        ScanState::Ground
+                    }
+                    (ScanState::Osc, ESC) => ScanState::OscEscape,
+                    (ScanState::Osc, _) if self.payload.len() < MAX_OSC_PAYLOAD => {
+                        self.payload.push(byte);
+                        ScanState::Osc
+                    }
+                    (ScanState::Escape, b']') => {
+                        self.payload.clear();
+                        ScanState::Osc
+                    }
+                    (_, ESC) => ScanState::Escape,
+                    _ => ScanState::Ground,
+                };
+            }
+            reported
+        }
+
+        fn finish(&mut self) -> Option<PathBuf> {
+            let payload = std::mem::take(&mut self.payload);
+            let uri = payload.strip_prefix(b"7;")?;
+            parse_file_uri(std::str::from_utf8(uri).ok()?)
+        }
+    }
+
+    /// Parses the `file://host/path` URI of an OSC 7 report. The host is
+    /// ignored: remote shells report paths on their own machine, which is
+    /// where their new terminals start too.
+    pub fn parse_file_uri(uri: &str) -> Option<PathBuf> {
+        let rest = uri
+            .strip_prefix("file://")
+            .or_else(|| uri.strip_prefix("kitty-shell-cwd://"))?;
+        let path = &rest[rest.find('/')?..];
+        let path = percent_decode(path)?;
+        // file:///C:/Users/me on Windows
+        let path = match path.as_bytes() {
+            [b'/', drive, b':', ..] if cfg!(windows) && drive.is_ascii_alphabetic() => {
+                path[1..].to_string()
+            }
+            _ => path,
+        };
+        Some(PathBuf::from(path))
+    }
+
+    fn percent_decode(value: &str) -> Option<String> {
+        let bytes = value.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut index = 0;
+        while index < bytes.len() {
+            if bytes[index] == b'%' {
+                let hex = std::str::from_utf8(bytes.get(index + 1..index + 3)?).ok()?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                index += 3;
+            } else {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+        String::from_utf8(decoded).ok()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn scanner_reports_directories_split_across_reads() {
+            let mut scanner = Osc7Scanner::default();
+            assert_eq!(
+                scanner.scan(b"$ cd src\r\n\x1b]7;file://devbox/home/me/pro"),
+                None
+            );
+            assert_eq!(
+                scanner.scan(b"ject%20one/src\x07$ "),
+                Some(PathBuf::from("/home/me/project one/src"))
+            );
+
+            assert_eq!(
+                scanner.scan(b"\x1b]0;vim\x07\x1b]7;file:///tmp\x1b\\\x1b]7;file:///var\x1b\\"),
+                Some(PathBuf::from("/var"))
+            );
+        }
+
+        #[test]
+        fn scanner_ignores_other_and_malformed_sequences() {
+            let mut scanner = Osc7Scanner::default();
+            assert_eq!(scanner.scan(b"\x1b]2;file:///tmp\x07"), None);
+            assert_eq!(scanner.scan(b"\x1b]7;https://example.com/x\x07"), None);
+            assert_eq!(scanner.scan(b"\x1b]7;file:///bad%zz\x07"), None);
+            assert_eq!(scanner.scan(b"\x1b[31m]7;file:///tmp\x07"), None);
+        }
+    }
+}
+
+/// Encoding of clipboard text pasted into a terminal.
+pub mod paste {
+    const ESC: char = '\x1b';
+    pub const BRACKETED_PASTE_START: &[u8] = b"\x1b[200~";
+    pub const BRACKETED_PASTE_END: &[u8] = b"\x1b[201~";
+
+    /// The bytes that paste `text`. Line breaks become carriage returns, as
+    /// typed. With `bracketed`, the text is wrapped in bracketed-paste
+    /// markers and loses its escape characters, so it can't end the paste
+    /// early and run what follows.
+    pub fn paste_bytes(text: &str, bracketed: bool) -> Vec<u8> {
+        let text = text.replace("\r\n", "\r").replace('\n', "\r");
+        if !bracketed {
+            return text.into_bytes();
+        }
+        let mut bytes = BRACKETED_PASTE_START.to_vec();
+        bytes.extend(
+            text.chars()
+                .filter(|ch| *ch != ESC)
+                .collect::<String>()
+                .bytes(),
+        );
+        bytes.extend_from_slice(BRACKETED_PASTE_END);
+        bytes
+    }
+
+    /// Lines in `text` that would each be entered on their own; a single
+    /// trailing line break doesn't start another.
+    pub fn line_count(text: &str) -> usize {
+        let text = text
+            .strip_suffix("\r\n")
+            .or_else(|| text.strip_suffix(['\n', '\r']))
+            .unwrap_or(text);
+        text.replace("\r\n", "\n").split(['\n', '\r']).count()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn pastes_enter_line_breaks_as_carriage_returns() {
+            assert_eq!(paste_bytes("ls\r\ncd /\n", false), b"ls\rcd /\r");
+            assert_eq!(
+                paste_bytes("echo \x1b[201~rm -rf x\n", true),
+                b"\x1b[200~echo [201~rm -rf x\r\x1b[201~"
+            );
+        }
+
+        #[test]
+        fn trailing_line_break_does_not_count_as_a_line() {
+            assert_eq!(line_count("ls"), 1);
+            assert_eq!(line_count("ls\n"), 1);
+            assert_eq!(line_count("ls\r\ncd /\r\n"), 2);
+            assert_eq!(line_count("a\n\nb"), 3);
+        }
+    }
+}
+
+/// Cursor shape (DECSCUSR) and visibility (DECTCEM) requests in PTY output.
+pub mod cursor {
+    use crate::frame::{TerminalCursorShape, TerminalCursorStyle};
+
+    /// CSI sequences with more parameter bytes than this are not cursor
+    /// requests.
+    const MAX_CSI_PARAMS: usize = 32;
+    const ESC: u8 = 0x1b;
+    const CAN: u8 = 0x18;
+    const SUB: u8 = 0x1a;
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    enum ScanState {
+        #[default]
+        Ground,
+        Escape,
+        Csi,
+    }
+
+    /// Follows the cursor style programs ask for. Sequences may be split
+    /// across reads.
+    #[derive(Debug, Default)]
+    pub struct CursorStyleScanner {
+        state: ScanState,
+        params: Vec<u8>,
+        style: TerminalCursorStyle,
+    }
+
+    impl CursorStyleScanner {
+        pub fn scan(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.state = match (self.state, byte) {
+                    (ScanState::Escape, b'[') => {
+                        self.params.clear();
+                        ScanState::Csi
+                    }
+                    // RIS resets the terminal, cursor included
+                    (ScanState::Escape, b'c') => {
+                        self.style = TerminalCursorStyle::default();
+                        ScanState::Ground
+                    }
+                    (_, ESC) => ScanState::Escape,
+                    (ScanState::Csi, CAN | SUB) => ScanState::Ground,
+                    (ScanState::Csi, 0x20..=0x3f) => {
+                        if self.params.len() < MAX_CSI_PARAMS {
+                            self.params.push(byte);
+                        }
+                        ScanState::Csi
+                    }
+                    (ScanState::Csi, 0x40..=0x7e) => {
+                        self.dispatch(byte);
+                        ScanState::Ground
+                    }
+                    // Control characters inside a sequence run without ending it
+                    (ScanState::Csi, 0x00..=0x1f) => ScanState::Csi,
+                    _ => ScanState::Ground,
+                };
+            }
+        }
+
+        pub fn style(&self) -> TerminalCursorStyle {
+            self.style
+        }
+
+        fn dispatch(&mut self, final_byte: u8) {
+            let params = std::mem::take(&mut self.params);
+            match (final_byte, params.as_slice()) {
+                (b'q', [shape @ .., b' ']) => self.set_shape(shape),
+                (b'h' | b'l', [b'?', modes @ ..])
+                    if modes.split(|byte| *byte == b';').any(|mode| mode == b"25") =>
+                {
+                    self.style.visible = final_byte == b'h';
+                }
+                // DECSTR soft reset
+                (b'p', b"!") => self.style = TerminalCursorStyle::default(),
+                _ => {}
+            }
+            self.params = params;
+        }
+
+        fn set_shape(&mut self, param: &[u8]) {
+            self.style.shape = match param {
+                b"" | b"0" | b"1" | b"2" => TerminalCursorShape::Block,
+                b"3" | b"4" => TerminalCursorShape::Underline,
+                b"5" | b"6" => TerminalCursorShape::Bar,
+                _ => return,
+            };
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn scanner_follows_shape_and_visibility_across_reads() {
+            let mut scanner = CursorStyleScanner::default();
+            assert_eq!(scanner.style(), TerminalCursorStyle::default());
+
+            scanner.scan(b"$ vim\r\n\x1b[6");
+            assert_eq!(scanner.style().shape, TerminalCursorShape::Block);
+            scanner.scan(b" q\x1b[?12;25l");
+            assert_eq!(
+                scanner.style(),
+                TerminalCursorStyle {
+                    shape: TerminalCursorShape::Bar,
+                    visible: false,
+                }
+            );
+
+            scanner.scan(b"\x1b[4 q\x1b[?25h");
+            assert_eq!(
+                scanner.style(),
+                TerminalCursorStyle {
+                    shape: TerminalCursorShape::Underline,
+                    visible: true,
+                }
+            );
+
+            scanner.scan(b"\x1b[?25l\x1b[!p");
+            assert_eq!(scanner.style(), TerminalCursorStyle::default());
+        }
+
+        #[test]
+        fn scanner_ignores_other_sequences() {
+            let mut scanner = CursorStyleScanner::default();
+            scanner.scan(b"\x1b[25l\x1b[5q\x1b[?1049l\x1b[9 q");
+            assert_eq!(scanner.style(), TerminalCursorStyle::default());
+        }
+    }
+}
+
+/// Shell integration marks (OSC 133, from the FinalTerm protocol that iTerm2,
+/// VS Code, WezTerm and Ghostty shell scripts emit) delimiting prompts,
+/// command lines and command output.
+pub mod shell_integration {
+    /// OSC payloads longer than this are not shell integration marks.
+    const MAX_OSC_PAYLOAD: usize = 256;
+    const ESC: u8 = 0x1b;
+    const BEL: u8 = 0x07;
+
+    /// A point in a command's life reported by the shell.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ShellMark {
+        /// `A`: the prompt is about to be drawn
+        PromptStarted,
+        /// `B`: the prompt ended and the command line starts
+        InputStarted,
+        /// `C`: the command line was submitted and its output starts
+        CommandStarted,
+        /// `D`: the command finished, with its exit status when reported
+        CommandFinished { exit_code: Option<i32> },
+    }
+
+    /// A mark and the terminal line it was reported on, counted from the
+    /// oldest line of the scrollback.
+    #[cfg(feature = "emulator")]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ShellEvent {
+        pub mark: ShellMark,
+        pub line: usize,
+    }
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    enum ScanState {
+        #[default]
+        Ground,
+        Escape,
+        Osc,
+        OscEscape,
+    }
+
+    /// Finds OSC 133 marks (`ESC ] 133 ; A`, ended by BEL or ST) in PTY
+    /// output. Sequences may be split across reads.
+    #[derive(Debug, Default)]
+    pub struct Osc133Scanner {
+        state: ScanState,
+        payload: Vec<u8>,
+        overflowed: bool,
+    }
+
+    impl Osc133Scanner {
+        /// Scans `bytes` and returns each mark in them with the offset just
+        /// past its sequence.
+        pub fn scan(&mut self, bytes: &[u8]) -> Vec<(usize, ShellMark)> {
+            let mut marks = Vec::new();
+            for (index, &byte) in bytes.iter().enumerate() {
+                self.state = match (self.state, byte) {
+                    (ScanState::Osc, BEL) | (ScanState::OscEscape, b'\\') => {
+                        if let Some(mark) = self.finish() {
+                            marks.push((index + 1, mark));
+                        }
+                        ScanState::Ground
+                    }
+                    (ScanState::Osc, ESC) => ScanState::OscEscape,
+                    (ScanState::Osc, _) => {
+                        if self.payload.len() < MAX_OSC_PAYLOAD {
+                            self.payload.push(byte);
+                        } else {
+                            self.overflowed = true;
+                        }
+                        ScanState::Osc
+                    }
+                    (ScanState::Escape, b']') => {
+                        self.payload.clear();
+                        self.overflowed = false;
+                        ScanState::Osc
+                    }
+                    (_, ESC) => ScanState::Escape,
+                    _ => ScanState::Ground,
+                };
+            }
+            marks
+        }
+
+        fn finish(&mut self) -> Option<ShellMark> {
+            let payload = std::mem::take(&mut self.payload);
+            if self.overflowed {
+                return None;
+            }
+            parse_mark(std::str::from_utf8(payload.strip_prefix(b"133;")?).ok()?)
+        }
+    }
+
+    /// Parses the part of an OSC 133 payload after `133;`. Options such as
+    /// `aid=` or `k=` after the kind are ignored.
+    fn parse_mark(payload: &str) -> Option<ShellMark> {
+        let mut params = payload.split(';');
+        let mark = match params.next()? {
+            "A" => ShellMark::PromptStarted,
+            "B" => ShellMark::InputStarted,
+            "C" => ShellMark::CommandStarted,
+            "D" => ShellMark::CommandFinished {
+                exit_code: params.next().and_then(|code| code.parse().ok()),
+            },
+            _ => return None,
+        };
+        Some(mark)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn scanner_reports_marks_and_their_offsets_across_reads() {
+            let mut scanner = Osc133Scanner::default();
+            let prompt = b"\x1b]133;A\x07$ \x1b]133;B\x07";
+            assert_eq!(
+                scanner.scan(prompt),
+                [(8, ShellMark::PromptStarted), (18, ShellMark::InputStarted)]
+            );
+
+            assert!(scanner.scan(b"ls\r\n\x1b]133;C\x1b").is_empty());
+            assert_eq!(scanner.scan(b"\\"), [(1, ShellMark::CommandStarted)]);
+            assert_eq!(
+                scanner.scan(b"src\r\n\x1b]133;D;2;aid=14\x07"),
+                [(22, ShellMark::CommandFinished { exit_code: Some(2) })]
+            );
+            assert_eq!(
+                scanner.scan(b"\x1b]133;D\x07"),
+                [(8, ShellMark::CommandFinished { exit_code: None })]
+            );
+        }
+
+        #[test]
+        fn scanner_ignores_other_and_malformed_sequences() {
+            let mut scanner = Osc133Scanner::default();
+            assert!(
+                scanner
+                    .scan(b"\x1b]7;file:///tmp\x07\x1b]133;Z\x07")
+                    .is_empty()
+            );
+            assert!(scanner.scan(b"\x1b[31m]133;A\x07").is_empty());
+            let mut long = b"\x1b]133;A;".to_vec();
+            long.extend(std::iter::repeat_n(b'x', 300));
+            long.push(0x07);
+            assert!(scanner.scan(&long).is_empty());
+        }
+    }
+}
+
+/// OSC 8 hyperlinks, plain-text URLs and file positions in terminal output.
+#[cfg(feature = "emulator")]
+pub mod hyperlink {
+    use crate::frame::{Cell, CellWidth};
+    use regex::Regex;
+    use std::collections::VecDeque;
+    use std::ops::Range;
+    use std::sync::{Arc, LazyLock};
+
+    /// OSC payloads longer than this are not hyperlinks.
+    const MAX_OSC_PAYLOAD: usize = 4096;
+    /// Link labels longer than this are not matched against the grid.
+    const MAX_LABEL_LEN: usize = 512;
+    /// OSC 8 links remembered for matching, most recent last.
+    const MAX_LINKS: usize = 64;
+    const ESC: u8 = 0x1b;
+    const BEL: u8 = 0x07;
+
+    static URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#"\b(?:(?:https?|ftp|file)://|mailto:)[^\s<>"'`]+"#).expect("valid URL regex")
+    });
+    /// A path, optionally followed by `:line` or `:line:column` as compilers
+    /// and test runners print them.
+    static FILE_REFERENCE_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r#"(?P<path>(?:[A-Za-z]:)?[^\s:()\[\]{}<>"'`,;|]+)(?::(?P<line>\d+)(?::(?P<column>\d+))?)?"#,
+        )
+        .expect("valid file reference regex")
+    });
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    enum ScanState {
+        #[default]
+        Ground,
+        Escape,
+        Csi,
+        Osc,
+        OscEscape,
+    }
+
+    /// Follows OSC 8 hyperlinks (`ESC ] 8 ; params ; URI`, ended by BEL or
+    /// ST and closed by an empty URI) and remembers the text printed inside
+    /// them. Frames are built from the render state's text and style, so the
+    /// remembered labels are matched against the grid rows instead.
+    #[derive(Debug, Default)]
+    pub struct Osc8Scanner {
+        state: ScanState,
+        payload: Vec<u8>,
+        /// URI of the link being printed
+        open: Option<Arc<str>>,
+        label: Vec<u8>,
+        links: VecDeque<(String, Arc<str>)>,
+    }
+
+    impl Osc8Scanner {
+        pub fn scan(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.state = match (self.state, byte) {
+                    (ScanState::Osc, BEL) | (ScanState::OscEscape, b'\\') => {
+                        self.finish_osc();
+                        ScanState::Ground
+                    }
+                    (ScanState::Osc, ESC) => ScanState::OscEscape,
+                    (ScanState::Osc, _) => {
+                        if self.payload.len() < MAX_OSC_PAYLOAD {
+                            self.payload.push(byte);
+                        }
+                        ScanState::Osc
+                    }
+                    (ScanState::Escape, b']') => {
+                        self.payload.clear();
+                        ScanState::Osc
+                    }
+                    (ScanState::Escape, b'[') => ScanState::Csi,
+                    (_, ESC) => ScanState::Escape,
+                    (ScanState::Csi, 0x40..=0x7e) => ScanState::Ground,
+                    (ScanState::Csi, _) => ScanState::Csi,
+                    (ScanState::Ground, b'\r' | b'\n') => {
+                        // A label wrapped by the program continues on the
+                        // next line under the same link.
+                        self.finish_label();
+                        ScanState::Ground
+                    }
+                    (ScanState::Ground, 0x20..) if byte != 0x7f => {
+                        if self.open.is_some() && self.label.len() <= MAX_LABEL_LEN {
+                            self.label.push(byte);
+                        }
+                        ScanState::Ground
+                    }
+                    _ => ScanState::Ground,
+                };
+            }
+        }
+
+        /// Remembered labels and the URIs they link to, oldest first.
+        pub fn links(&self) -> impl Iterator<Item = (&str, &Arc<str>)> {
+            self.links.iter().map(|(label, uri)| (label.as_str(), uri))
+        }
+
+        fn finish_osc(&mut self) {
+            let payload = std::mem::take(&mut self.payload);
+            let Some(rest) = payload.strip_prefix(b"8;") else {
+                return;
+            };
+            let Some(separator) = rest.iter().position(|&byte| byte == b';') else {
+                return;
+            };
+            self.finish_label();
+            self.open = std::str::from_utf8(&rest[separator + 1..])
+                .ok()
+                .filter(|uri| !uri.is_empty())
+                .map(Arc::from);
+        }
+
+        fn finish_label(&mut self) {
+            let label = std::mem::take(&mut self.label);
+            let Some(uri) = self.open.clone() else {
+                return;
+            };
+            if label.len() > MAX_LABEL_LEN {
+                return;
+            }
+            let label = String::from_utf8_lossy(&label).trim().to_string();
+            if label.is_empty() {
+                return;
+            }
+            self.links.retain(|(existing, _)| *existing != label);
+            if self.links.len() == MAX_LINKS {
+                self.links.pop_front();
+            }
+            self.links.push_back((label, uri));
+        }
+    }
+
+    /// The text of a row and the byte offset of each cell's text in it.
+    /// Spacers share their wide character's offset.
+    pub fn row_text(cells: &[Cell]) -> (String, Vec<usize>) {
+        let mut text = String::new();
+        let mut offsets = Vec::with_capacity(cells.len());
+        for cell in cells {
+            if cell.width == CellWidth::Spacer {
+                offsets.push(offsets.last().copied().unwrap_or(0));
+            } else {
+                offsets.push(text.len());
+            }
+            cell.push_text(&mut text);
+        }
+        (text, offsets)
+    }
+
+    /// Links the cells of a row that show a remembered OSC 8 label or a URL.
+    /// OSC 8 links win where both match.
+    pub fn link_row(cells: &mut [Cell], scanner: &Osc8Scanner) {
+        let (text, offsets) = row_text(cells);
+        if text.trim().is_empty() {
+            return;
+        }
+
+        for (label, uri) in scanner.links() {
+            for (start, _) in text.match_indices(label) {
+                link_cells(cells, &offsets, start..start + label.len(), uri);
+            }
+        }
+        for range in detect_urls(&text) {
+            let uri = Arc::from(&text[range.clone()]);
+            link_cells(cells, &offsets, range, &uri);
+        }
+    }
+
+    fn link_cells(cells: &mut [Cell], offsets: &[usize], range: Range<usize>, uri: &Arc<str>) {
+        for (cell, offset) in cells.iter_mut().zip(offsets) {
+            if range.contains(offset) && cell.hyperlink.is_none() {
+                cell.hyperlink = Some(uri.clone());
+            }
+        }
+    }
+
+    /// Byte ranges of the URLs in `text`, leaving out punctuation that ends
+    /// the sentence around them.
+    pub fn detect_urls(text: &str) -> Vec<Range<usize>> {
+        URL_RE
+            .find_iter(text)
+            .filter_map(|found| {
+                let url = trim_url(found.as_str());
+                let (_, rest) = url.split_once(':')?;
+                (!rest.trim_start_matches('/').is_empty())
+                    .then_some(found.start()..found.start() + url.len())
+            })
+            .collect()
+    }
+
+    /// A file position printed in terminal output, like `src/main.rs:42:7`.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FileReference {
+        /// The path as printed, relative to the working directory of the
+        /// program that printed it unless absolute
+        pub path: String,
+        /// One-based
+        pub line: Option<usize>,
+        /// One-based
+        pub column: Option<usize>,
+    }
+
+    /// The file reference in `text` that covers byte `offset`. Only words
+    /// with a directory separator or a file extension count as paths.
+    pub fn file_reference_at(text: &str, offset: usize) -> Option<FileReference> {
+        let captures = FILE_REFERENCE_RE.captures_iter(text).find(|captures| {
+            captures
+                .get(0)
+                .is_some_and(|found| found.range().contains(&offset))
+        })?;
+        let path = captures["path"].trim_end_matches(['.', '!', '?']);
+        let looks_like_path = path.contains(['/', '\\'])
+            || path.rsplit_once('.').is_some_and(|(stem, extension)| {
+                !stem.is_empty() && extension.starts_with(|ch: char| ch.is_ascii_alphabetic())
+            });
+        if !looks_like_path {
+            return None;
+        }
+        let number = |name| {
+            captures
+                .name(name)
+                .and_then(|number| number.as_str().parse::<usize>().ok())
+                .filter(|number| *number > 0)
+        };
+        Some(FileReference {
+            path: path.to_string(),
+            line: number("line"),
+            column: number("column"),
+        })
+    }
+
+    fn trim_url(mut url: &str) -> &str {
+        loop {
+            let trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?']);
+            let trimmed = match trimmed.chars().next_back() {
+                Some(close @ (')' | ']')) => {
+                    let open = if close == ')' { '(' } else { '[' };
+                    if trimmed.matches(open).count() < trimmed.matches(close).count() {
+                        &trimmed[..trimmed.len() - 1]
+                    } else {
+                        trimmed
+                    }
+                }
+                _ => trimmed,
+            };
+            if trimmed.len() == url.len() {
+                return url;
+            }
+            url = trimmed;
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::frame::{DEFAULT_BACKGROUND, DEFAULT_FOREGROUND};
+
+        fn row(text: &str) -> Vec<Cell> {
+            text.chars()
+                .map(|ch| Cell {
+                    ch,
+                    combining: None,
+                    width: CellWidth::Narrow,
+                    fg: DEFAULT_FOREGROUND,
+                    bg: DEFAULT_BACKGROUND,
+                    bold: false,
+                    italic: false,
+                    underline: false,
+                    inverse: false,
+                    hyperlink: None,
+                })
+                .collect()
+        }
+
+        fn links(cells: &[Cell]) -> Vec<Option<&str>> {
+            cells.iter().map(|cell| cell.hyperlink.as_deref()).collect()
+        }
+
+        #[test]
+        fn scanner_remembers_labels_split_across_reads() {
+            let mut scanner = Osc8Scanner::default();
+            scanner.scan(b"see \x1b]8;id=1;https://example.com/a\x1b\\the \x1b[1mdo");
+            scanner.scan(b"cs\x1b[0m\x1b]8;;\x1b\\ and \x1b]8;;file:///tmp/x\x07x\x1b]8;;\x07");
+
+            assert_eq!(
+                scanner
+                    .links()
+                    .map(|(label, uri)| (label, &**uri))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("the docs", "https://example.com/a"),
+                    ("x", "file:///tmp/x")
+                ]
+            );
+        }
+
+        #[test]
+        fn urls_drop_trailing_punctuation_but_keep_balanced_parens() {
+            let text = "at https://example.com/a. (see https://en.wikipedia.org/wiki/Rust_(language)) or mailto:me@example.com, not https://";
+            let urls = detect_urls(text)
+                .into_iter()
+                .map(|range| &text[range])
+                .collect::<Vec<_>>();
+            assert_eq!(
+                urls,
+                vec![
+                    "https://example.com/a",
+                    "https://en.wikipedia.org/wiki/Rust_(language)",
+                    "mailto:me@example.com"
+                ]
+            );
+        }
+
+        #[test]
+        fn file_references_carry_the_printed_line_and_column() {
+            let text = "  --> src/main.rs:42:7 | see ../lib/a.py:3, README.md. and notes";
+            let at = |needle: &str| file_reference_at(text, text.find(needle).unwrap());
+
+            assert_eq!(
+                at("main"),
+                Some(FileReference {
+                    path: "src/main.rs".to_string(),
+                    line: Some(42),
+                    column: Some(7),
+                })
+            );
+            assert_eq!(
+                at("a.py"),
+                Some(FileReference {
+                    path: "../lib/a.py".to_string(),
+                    line: Some(3),
+                    column: None,
+                })
+            );
+            assert_eq!(at("README").unwrap().path, "README.md");
+            assert_eq!(at("-->"), None);
+            assert_eq!(at("notes"), None);
+        }
+
+        #[test]
+        fn link_row_prefers_osc8_links_over_detected_urls() {
+            let mut scanner = Osc8Scanner::default();
+            scanner.scan(b"\x1b]8;;https://docs.rs\x07docs\x1b]8;;\x07");
+
+            let mut cells = row("docs http://x.io ");
+            link_row(&mut cells, &scanner);
+
+            let docs = Some("https://docs.rs");
+            let url = Some("http://x.io");
+            assert_eq!(
+                links(&cells),
+                vec![
+                    docs, docs, docs, docs, None, url, url, url, url, url, url, url, url, url, url,
+                    url, None
+                ]
+            );
+        }
+    }
+}
+
 pub mod bounds {
     use portable_pty::PtySize;
 
@@ -0,0 +1,117 @@
+// ABOUTME: Editor cursor settings: blinking, per-mode shapes and the cursor in inactive views
+// ABOUTME: Pure timing rules for when a blinking cursor is drawn
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// How the document cursor is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CursorConfig {
+    /// Blink the cursor in the focused document. `false` keeps it solid.
+    pub blink: bool,
+    /// Milliseconds the cursor is shown, and then hidden, per blink
+    pub blink_interval_ms: u64,
+    /// Milliseconds after the last edit or cursor movement before blinking
+    /// resumes, so the cursor stays solid while typing
+    pub blink_resume_delay_ms: u64,
+    /// Hide the cursor in documents without focus instead of drawing a
+    /// hollow block
+    pub hide_inactive: bool,
+    /// Use a bar in insert mode and an underline in select mode when Helix's
+    /// `editor.cursor-shape` is left at its all-block default
+    pub mode_shapes: bool,
+}
+
+impl Default for CursorConfig {
+    fn default() -> Self {
+        Self {
+            blink: true,
+            blink_interval_ms: 530,
+            blink_resume_delay_ms: 500,
+            hide_inactive: false,
+            mode_shapes: true,
+        }
+    }
+}
+
+/// Shortest blink phase, so a zero interval does not spin the renderer.
+const MIN_BLINK_INTERVAL: Duration = Duration::from_millis(50);
+
+impl CursorConfig {
+    pub fn blink_interval(&self) -> Duration {
+        Duration::from_millis(self.blink_interval_ms).max(MIN_BLINK_INTERVAL)
+    }
+
+    pub fn blink_resume_delay(&self) -> Duration {
+        Duration::from_millis(self.blink_resume_delay_ms)
+    }
+
+    /// Whether a blinking cursor is drawn `idle` after the last activity.
+    pub fn blink_visible(&self, idle: Duration) -> bool {
+        let Some(blinking) = self.blinking_for(idle) else {
+            return true;
+        };
+        (blinking.as_nanos() / self.blink_interval().as_nanos()).is_multiple_of(2)
+    }
+
+    /// Time until the cursor next appears or disappears, or `None` while it
+    /// stays solid.
+    pub fn next_blink_toggle(&self, idle: Duration) -> Option<Duration> {
+        if !self.blink {
+            return None;
+        }
+        let Some(blinking) = self.blinking_for(idle) else {
+            return Some(self.blink_resume_delay() - idle + self.blink_interval());
+        };
+        let interval = self.blink_interval().as_nanos();
+        let into_phase = blinking.as_nanos() % interval;
+        Some(Duration::from_nanos((interval - into_phase) as u64))
+    }
+
+    /// How long the cursor has been blinking, `None` while it is solid.
+    fn blinking_for(&self, idle: Duration) -> Option<Duration> {
+        if !self.blink {
+            return None;
+        }
+        idle.checked_sub(self.blink_resume_delay())
+    }
+}
+
+#[cfg(feature = "gpui-bridge")]
+impl gpui::Global for CursorConfig {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_stays_solid_while_typing_then_blinks() {
+        let config = CursorConfig {
+            blink_interval_ms: 500,
+            blink_resume_delay_ms: 300,
+            ..CursorConfig::default()
+        };
+        let ms = Duration::from_millis;
+
+        assert!(config.blink_visible(ms(0)));
+        assert!(config.blink_visible(ms(799)));
+        assert!(!config.blink_visible(ms(800)));
+        assert!(config.blink_visible(ms(1300)));
+
+        assert_eq!(config.next_blink_toggle(ms(100)), Some(ms(700)));
+        assert_eq!(config.next_blink_toggle(ms(900)), Some(ms(400)));
+    }
+
+    #[test]
+    fn solid_cursor_never_toggles() {
+        let config = CursorConfig {
+            blink: false,
+            ..CursorConfig::default()
+        };
+
+        assert!(config.blink_visible(Duration::from_secs(10)));
+        assert_eq!(config.next_blink_toggle(Duration::from_secs(10)), None);
+    }
+}
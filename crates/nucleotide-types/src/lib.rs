@@ -3,9 +3,13 @@
 
 pub mod completion;
 pub mod config;
+pub mod cursor_config;
 pub mod editor_types;
 pub mod font_config;
+pub mod mouse_selection;
+pub mod pixel_snap;
 pub mod project_config;
+pub mod scroll_config;
 #[cfg(feature = "gpui-bridge")]
 pub mod scrollbar;
 pub mod vcs;
@@ -13,7 +17,11 @@ pub mod vcs;
 // Re-export commonly used types
 pub use completion::CompletionTrigger;
 pub use config::{FontConfig, FontWeight};
+pub use cursor_config::CursorConfig;
 pub use editor_types::{EditorStatus, Severity};
 pub use font_config::{EditorFontConfig, Font, FontSettings, FontStyle, UiFontConfig};
+pub use mouse_selection::{CharClass, MouseSelectionConfig};
+pub use pixel_snap::{snap_cell_size, snap_to_device_pixels};
 pub use project_config::{ProjectMarker, ProjectMarkersConfig, RootStrategy};
+pub use scroll_config::{ScrollModifier, ScrollSettings, WheelScrollConfig};
 pub use vcs::{DiffChangeType, DiffHunkInfo, VcsStatus};
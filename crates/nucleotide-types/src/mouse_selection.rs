@@ -0,0 +1,81 @@
+// ABOUTME: Mouse selection settings for double- and triple-click selection in documents
+// ABOUTME: Decides which characters a double-click treats as part of a word
+
+use serde::{Deserialize, Serialize};
+
+/// What a run of characters selected by a double-click is made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    Word,
+    Whitespace,
+    LineEnding,
+    Punctuation,
+}
+
+/// Double- and triple-click behaviour in documents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MouseSelectionConfig {
+    /// Characters selected as part of a word on double-click, besides
+    /// letters, digits and `_`. `"-"` selects kebab-case names whole.
+    pub word_characters: String,
+    /// Triple-click selects the line's line ending along with its text, so
+    /// deleting the selection removes the whole line.
+    pub triple_click_includes_line_ending: bool,
+}
+
+impl Default for MouseSelectionConfig {
+    fn default() -> Self {
+        Self {
+            word_characters: String::new(),
+            triple_click_includes_line_ending: true,
+        }
+    }
+}
+
+impl MouseSelectionConfig {
+    pub fn is_word_char(&self, ch: char) -> bool {
+        ch.is_alphanumeric() || ch == '_' || self.word_characters.contains(ch)
+    }
+
+    pub fn char_class(&self, ch: char) -> CharClass {
+        if matches!(
+            ch,
+            '\n' | '\r' | '\u{000B}' | '\u{000C}' | '\u{0085}' | '\u{2028}' | '\u{2029}'
+        ) {
+            CharClass::LineEnding
+        } else if self.is_word_char(ch) {
+            CharClass::Word
+        } else if ch.is_whitespace() {
+            CharClass::Whitespace
+        } else {
+            CharClass::Punctuation
+        }
+    }
+}
+
+#[cfg(feature = "gpui-bridge")]
+impl gpui::Global for MouseSelectionConfig {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configured_characters_join_words() {
+        let config = MouseSelectionConfig::default();
+        assert_eq!(config.char_class('a'), CharClass::Word);
+        assert_eq!(config.char_class('_'), CharClass::Word);
+        assert_eq!(config.char_class('-'), CharClass::Punctuation);
+        assert_eq!(config.char_class('\t'), CharClass::Whitespace);
+        assert_eq!(config.char_class('\n'), CharClass::LineEnding);
+
+        let kebab = MouseSelectionConfig {
+            word_characters: "-$".into(),
+            ..MouseSelectionConfig::default()
+        };
+        assert_eq!(kebab.char_class('-'), CharClass::Word);
+        assert_eq!(kebab.char_class('$'), CharClass::Word);
+        assert_eq!(kebab.char_class('.'), CharClass::Punctuation);
+    }
+}
@@ -0,0 +1,57 @@
+//! Rounding of logical cell metrics to whole device pixels.
+//!
+//! Cell sizes are measured as `f32` logical pixels. At fractional scale
+//! factors (1.25, 1.5, ...) an unrounded line height puts every row baseline
+//! at a different sub-pixel offset, and a terminal sized from unrounded cells
+//! can disagree with the grid it paints. Snapping per window scale keeps both
+//! on the device pixel grid.
+
+/// Rounds a logical length to the nearest whole number of device pixels at
+/// `scale_factor`, never going below one device pixel.
+///
+/// Invalid scale factors leave the value unchanged.
+pub fn snap_to_device_pixels(value: f32, scale_factor: f32) -> f32 {
+    if !value.is_finite() || !scale_factor.is_finite() || scale_factor <= 0.0 {
+        return value;
+    }
+
+    (value * scale_factor).round().max(1.0) / scale_factor
+}
+
+/// Snaps a `(cell_width, cell_height)` pair to device pixels at `scale_factor`.
+pub fn snap_cell_size(cell_width: f32, cell_height: f32, scale_factor: f32) -> (f32, f32) {
+    (
+        snap_to_device_pixels(cell_width, scale_factor),
+        snap_to_device_pixels(cell_height, scale_factor),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snaps_to_whole_device_pixels_at_fractional_scale() {
+        // 17.55 logical px * 1.5 = 26.325 device px -> 26 device px
+        let snapped = snap_to_device_pixels(17.55, 1.5);
+        assert!((snapped * 1.5 - 26.0).abs() < f32::EPSILON * 32.0);
+
+        // 8.4 logical px * 1.25 = 10.5 device px -> 11 device px
+        let snapped = snap_to_device_pixels(8.4, 1.25);
+        assert!((snapped * 1.25 - 11.0).abs() < f32::EPSILON * 32.0);
+    }
+
+    #[test]
+    fn integer_scale_rounds_to_whole_logical_pixels() {
+        assert_eq!(snap_to_device_pixels(19.6, 1.0), 20.0);
+        assert_eq!(snap_to_device_pixels(19.6, 2.0), 19.5);
+        assert_eq!(snap_cell_size(7.8, 18.2, 2.0), (8.0, 18.0));
+    }
+
+    #[test]
+    fn keeps_at_least_one_device_pixel_and_ignores_invalid_scale() {
+        assert_eq!(snap_to_device_pixels(0.1, 2.0), 0.5);
+        assert_eq!(snap_to_device_pixels(9.3, 0.0), 9.3);
+        assert_eq!(snap_to_device_pixels(9.3, f32::NAN), 9.3);
+    }
+}
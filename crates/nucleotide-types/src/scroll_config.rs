@@ -0,0 +1,167 @@
+// ABOUTME: Mouse wheel scroll settings shared by the editor and terminal surfaces
+// ABOUTME: Pure speed, fast-scroll modifier and shift-to-horizontal rules
+
+use serde::{Deserialize, Serialize};
+
+/// Modifier that multiplies wheel speed while held.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScrollModifier {
+    #[default]
+    Alt,
+    Control,
+    /// Cmd on macOS, Super/Windows elsewhere
+    Platform,
+    /// Fast scrolling disabled
+    None,
+}
+
+#[cfg(feature = "gpui-bridge")]
+impl ScrollModifier {
+    pub fn is_held(self, modifiers: &gpui::Modifiers) -> bool {
+        match self {
+            Self::Alt => modifiers.alt,
+            Self::Control => modifiers.control,
+            Self::Platform => modifiers.platform,
+            Self::None => false,
+        }
+    }
+}
+
+/// Wheel behaviour for one scrollable surface.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WheelScrollConfig {
+    /// Lines moved per line of wheel movement reported by the platform.
+    /// Trackpad pixel deltas are scaled by the same factor.
+    pub lines_per_tick: f32,
+    /// Modifier that applies `fast_scroll_multiplier` while held
+    pub fast_scroll_modifier: ScrollModifier,
+    pub fast_scroll_multiplier: f32,
+    /// Turn vertical wheel movement into horizontal scrolling while shift is held
+    pub shift_scrolls_horizontally: bool,
+}
+
+impl Default for WheelScrollConfig {
+    fn default() -> Self {
+        Self {
+            lines_per_tick: 1.0,
+            fast_scroll_modifier: ScrollModifier::Alt,
+            fast_scroll_multiplier: 5.0,
+            shift_scrolls_horizontally: true,
+        }
+    }
+}
+
+impl WheelScrollConfig {
+    /// Applies speed and shift handling to a raw `(x, y)` wheel delta.
+    ///
+    /// Platforms that already report shift+wheel as horizontal movement are
+    /// left alone; only purely vertical deltas are rotated.
+    pub fn adjust_delta(&self, delta: (f32, f32), fast: bool, shift: bool) -> (f32, f32) {
+        let mut factor = sanitized_factor(self.lines_per_tick);
+        if fast && self.fast_scroll_modifier != ScrollModifier::None {
+            factor *= sanitized_factor(self.fast_scroll_multiplier);
+        }
+
+        let (x, y) = delta;
+        let (x, y) = if shift && self.shift_scrolls_horizontally && x == 0.0 {
+            (y, 0.0)
+        } else {
+            (x, y)
+        };
+
+        (x * factor, y * factor)
+    }
+
+    #[cfg(feature = "gpui-bridge")]
+    pub fn adjust_pixel_delta(
+        &self,
+        delta: gpui::Point<gpui::Pixels>,
+        modifiers: &gpui::Modifiers,
+    ) -> gpui::Point<gpui::Pixels> {
+        let (x, y) = self.adjust_delta(
+            (f32::from(delta.x), f32::from(delta.y)),
+            self.fast_scroll_modifier.is_held(modifiers),
+            modifiers.shift,
+        );
+        gpui::point(gpui::px(x), gpui::px(y))
+    }
+}
+
+fn sanitized_factor(value: f32) -> f32 {
+    if value.is_finite() && value > 0.0 {
+        value
+    } else {
+        1.0
+    }
+}
+
+/// Resolved wheel settings for every scrollable surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollSettings {
+    pub editor: WheelScrollConfig,
+    pub terminal: WheelScrollConfig,
+    /// Send arrow keys for wheel movement in full-screen terminal programs
+    /// (alternate screen) that did not request mouse reporting
+    pub terminal_alternate_scroll: bool,
+}
+
+impl Default for ScrollSettings {
+    fn default() -> Self {
+        Self {
+            editor: WheelScrollConfig::default(),
+            terminal: WheelScrollConfig::default(),
+            terminal_alternate_scroll: true,
+        }
+    }
+}
+
+#[cfg(feature = "gpui-bridge")]
+impl gpui::Global for ScrollSettings {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_leaves_deltas_unchanged() {
+        let config = WheelScrollConfig::default();
+        assert_eq!(config.adjust_delta((2.0, -6.0), false, false), (2.0, -6.0));
+    }
+
+    #[test]
+    fn lines_per_tick_and_fast_modifier_scale_deltas() {
+        let config = WheelScrollConfig {
+            lines_per_tick: 3.0,
+            ..WheelScrollConfig::default()
+        };
+        assert_eq!(config.adjust_delta((0.0, 1.0), false, false), (0.0, 3.0));
+        assert_eq!(config.adjust_delta((0.0, 1.0), true, false), (0.0, 15.0));
+
+        let no_fast = WheelScrollConfig {
+            fast_scroll_modifier: ScrollModifier::None,
+            ..config
+        };
+        assert_eq!(no_fast.adjust_delta((0.0, 1.0), true, false), (0.0, 3.0));
+
+        let invalid = WheelScrollConfig {
+            lines_per_tick: -2.0,
+            ..WheelScrollConfig::default()
+        };
+        assert_eq!(invalid.adjust_delta((0.0, 1.0), false, false), (0.0, 1.0));
+    }
+
+    #[test]
+    fn shift_rotates_only_vertical_deltas() {
+        let config = WheelScrollConfig::default();
+        assert_eq!(config.adjust_delta((0.0, 4.0), false, true), (4.0, 0.0));
+        assert_eq!(config.adjust_delta((4.0, 0.0), false, true), (4.0, 0.0));
+
+        let disabled = WheelScrollConfig {
+            shift_scrolls_horizontally: false,
+            ..config
+        };
+        assert_eq!(disabled.adjust_delta((0.0, 4.0), false, true), (0.0, 4.0));
+    }
+}
@@ -70,6 +70,7 @@ pub mod file_tree {
             SelectNextSearchMatch,
             SelectPrevSearchMatch,
             OpenFile,
+            TogglePreview,
             RefreshTree,
             // Context menu and common file ops
             OpenContextMenu,
@@ -81,6 +82,7 @@ pub mod file_tree {
             CopyPath,
             CopyRelativePath,
             RevealInOs,
+            OpenTerminalHere,
         ]
     );
 }
@@ -94,10 +96,12 @@ pub mod editor {
             Quit,
             OpenFile,
             OpenDirectory,
+            CloseFolder,
             OpenRemote,
             ReconnectRemote,
             CancelRemoteConnection,
             OpenSettings,
+            OpenWorkspaceSettings,
             ReloadConfiguration,
             Save,
             SaveAs,
@@ -107,8 +111,31 @@ pub mod editor {
             Redo,
             Copy,
             Paste,
+            DuplicateLines,
+            MoveLinesUp,
+            MoveLinesDown,
+            JoinLines,
+            ToggleComment,
+            ReflowParagraph,
+            TransformUpperCase,
+            TransformLowerCase,
+            TransformTitleCase,
+            TransformCamelCase,
+            TransformSnakeCase,
+            TransformKebabCase,
+            TransformPascalCase,
+            SortLines,
+            SortLinesDescending,
+            SortLinesNumeric,
+            SortLinesNumericDescending,
+            UniqueLines,
+            ReverseLines,
+            ShuffleLines,
+            AlignBy,
+            GenerateDocComment,
             IncreaseFontSize,
             DecreaseFontSize,
+            OpenContextMenu,
         ]
     );
 }
@@ -116,7 +143,17 @@ pub mod editor {
 pub mod help {
     use super::actions;
 
-    actions!(help, [About, OpenTutorial, ThemeDebug, ComponentGallery,]);
+    actions!(
+        help,
+        [
+            About,
+            OpenTutorial,
+            ThemeDebug,
+            ComponentGallery,
+            TogglePerformanceHud,
+            ToggleFocusDebugger,
+        ]
+    );
 }
 
 pub mod updates {
@@ -134,22 +171,81 @@ pub mod workspace {
             ShowBufferPicker,
             ShowCodeActions,
             ToggleFileTree,
+            RevealActiveFileInTree,
             ToggleDocumentation,
+            ShowTypeHierarchy,
+            ToggleRegexTester,
             ToggleTerminal,
             ShowFileFinder,
             NewFile,
+            NewProject,
+            NewScratchBuffer,
+            ShowScratchBuffers,
+            CompareActiveFileWith,
+            CompareWithClipboard,
+            NextDifference,
+            PreviousDifference,
+            CopyDifferenceLeft,
+            CopyDifferenceRight,
+            CloseComparison,
             NewWindow,
             ShowCommandPrompt,
             ShowRunnables,
             RunNearest,
             RunFileTests,
             RunLast,
+            ShowRunConfigurations,
+            RunConfiguration,
+            ShowRunProblems,
+            ShowCargoSettings,
+            ShowNpmScripts,
+            ShowIndentation,
             SplitPaneRight,
             SplitPaneLeft,
             SplitPaneUp,
             SplitPaneDown,
+            ShowLayoutMenu,
             UnpinAllTabs,
             TogglePreviewTab,
+            ShowAllTabs,
+            ActivateNextTab,
+            ActivatePreviousTab,
+            ActivateLastUsedTab,
+            ReopenClosedTab,
+            ShowLspTraffic,
+            ShowEnvironmentInspector,
+            ExportSnippetAsSvg,
+            ExportSnippetAsHtml,
+            ExportTerminalAsSvg,
+            ExportTerminalAsHtml,
+            ExportDocumentAsHtml,
+            ExportDocumentAsPdf,
+            ShareFile,
+            ClearUsageData,
+        ]
+    );
+}
+
+pub mod git {
+    use super::actions;
+
+    actions!(
+        git,
+        [
+            Fetch,
+            Pull,
+            Push,
+            Stash,
+            StashPop,
+            CancelOperation,
+            ShowConflicts,
+            NextConflict,
+            PreviousConflict,
+            AcceptOurs,
+            AcceptTheirs,
+            AcceptBoth,
+            ShowLog,
+            ShowFileHistory,
         ]
     );
 }
@@ -221,6 +317,33 @@ pub mod remote_connection_manager {
     );
 }
 
+pub mod find_bar {
+    use super::actions;
+
+    actions!(
+        find_bar,
+        [
+            FindNext,
+            FindPrevious,
+            ReplaceNext,
+            ReplaceAll,
+            ToggleCaseSensitive,
+            ToggleWholeWord,
+            ToggleRegex,
+            Dismiss,
+        ]
+    );
+}
+
+pub mod terminal {
+    use super::actions;
+
+    actions!(
+        terminal,
+        [Copy, Paste, OpenContextMenu, PreviousCommand, NextCommand,]
+    );
+}
+
 pub mod text_input {
     use super::actions;
 
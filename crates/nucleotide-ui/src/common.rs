@@ -49,9 +49,11 @@ impl ModalStyle {
         let is_dark = surface.l < 0.5;
         let chrome = crate::tokens::ChromeTokens::from_surface_color(surface, is_dark);
         let editor = crate::tokens::EditorTokens::fallback(is_dark);
+        let terminal = crate::tokens::TerminalTokens::from_editor(&editor, chrome.primary);
         let tokens = crate::DesignTokens {
             editor,
             chrome,
+            terminal,
             sizes: crate::tokens::SizeTokens::default(),
         };
         let dd = tokens.dropdown_tokens();
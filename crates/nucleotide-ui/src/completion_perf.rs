@@ -1,9 +1,11 @@
 // ABOUTME: Performance monitoring and metrics for the completion system
-// ABOUTME: Tracks timing, memory usage, and provides optimization insights
+// ABOUTME: Tracks timing, memory usage, and enforces per-keystroke latency budgets
 
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
+use gpui::Global;
+
 /// Performance metrics for completion operations
 #[derive(Debug, Clone)]
 pub struct CompletionMetrics {
@@ -136,6 +138,18 @@ impl RollingWindow {
         }
     }
 
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn is_full(&self) -> bool {
+        self.values.len() >= self.max_size
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+    }
+
     #[cfg(test)]
     fn max_value(&self) -> Duration {
         self.values.iter().max().copied().unwrap_or(Duration::ZERO)
@@ -376,6 +390,217 @@ impl Default for PerformanceMonitor {
     }
 }
 
+/// Latency budgets for one completion keystroke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompletionLatencyBudget {
+    /// From the completion trigger until the popup has items to show
+    pub trigger_to_popup: Duration,
+    /// Fuzzy filtering of the candidates for one query
+    pub filter: Duration,
+    /// Building the popup's elements for one frame
+    pub render: Duration,
+}
+
+impl Default for CompletionLatencyBudget {
+    fn default() -> Self {
+        Self {
+            trigger_to_popup: Duration::from_millis(150),
+            filter: Duration::from_millis(8),
+            render: Duration::from_millis(4),
+        }
+    }
+}
+
+/// Stage of a completion keystroke measured against the budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionStage {
+    TriggerToPopup,
+    Filter,
+    Render,
+}
+
+/// Candidates kept per completion request when performance is healthy.
+pub const DEFAULT_COMPLETION_CANDIDATE_CAP: usize = 2000;
+/// The cap is never reduced below this.
+pub const MIN_COMPLETION_CANDIDATE_CAP: usize = 100;
+/// Samples averaged before a stage is judged against its budget.
+const BUDGET_SAMPLES: usize = 8;
+
+/// Measures completion latency and degrades the completion pipeline while
+/// the rolling averages exceed their budgets: locality scoring is turned
+/// off first when popups are slow, then the candidate cap is halved. Once
+/// every stage runs under half its budget the steps are undone one at a time.
+#[derive(Debug)]
+pub struct CompletionBudget {
+    limits: CompletionLatencyBudget,
+    trigger_to_popup: RollingWindow,
+    filter: RollingWindow,
+    render: RollingWindow,
+    candidate_cap: usize,
+    locality_scoring: bool,
+    adjustments: u64,
+}
+
+impl Global for CompletionBudget {}
+
+impl Default for CompletionBudget {
+    fn default() -> Self {
+        Self::new(CompletionLatencyBudget::default())
+    }
+}
+
+impl CompletionBudget {
+    pub fn new(limits: CompletionLatencyBudget) -> Self {
+        Self {
+            limits,
+            trigger_to_popup: RollingWindow::new(BUDGET_SAMPLES),
+            filter: RollingWindow::new(BUDGET_SAMPLES),
+            render: RollingWindow::new(BUDGET_SAMPLES),
+            candidate_cap: DEFAULT_COMPLETION_CANDIDATE_CAP,
+            locality_scoring: true,
+            adjustments: 0,
+        }
+    }
+
+    /// Maximum number of candidates handed to the completion popup.
+    pub fn candidate_cap(&self) -> usize {
+        self.candidate_cap
+    }
+
+    /// Whether items should be ranked by distance to their uses in the document.
+    pub fn locality_scoring_enabled(&self) -> bool {
+        self.locality_scoring
+    }
+
+    /// Records one measurement and adjusts the pipeline if needed.
+    pub fn record(&mut self, stage: CompletionStage, duration: Duration) {
+        self.window_mut(stage).push(duration);
+
+        let window = self.window(stage);
+        if window.is_full() && window.average() > self.limit(stage) {
+            self.degrade(stage);
+            self.window_mut(stage).clear();
+        } else if self.is_degraded() && self.all_stages_comfortable() {
+            self.recover();
+            for stage in [
+                CompletionStage::TriggerToPopup,
+                CompletionStage::Filter,
+                CompletionStage::Render,
+            ] {
+                self.window_mut(stage).clear();
+            }
+        }
+    }
+
+    fn window(&self, stage: CompletionStage) -> &RollingWindow {
+        match stage {
+            CompletionStage::TriggerToPopup => &self.trigger_to_popup,
+            CompletionStage::Filter => &self.filter,
+            CompletionStage::Render => &self.render,
+        }
+    }
+
+    fn window_mut(&mut self, stage: CompletionStage) -> &mut RollingWindow {
+        match stage {
+            CompletionStage::TriggerToPopup => &mut self.trigger_to_popup,
+            CompletionStage::Filter => &mut self.filter,
+            CompletionStage::Render => &mut self.render,
+        }
+    }
+
+    fn limit(&self, stage: CompletionStage) -> Duration {
+        match stage {
+            CompletionStage::TriggerToPopup => self.limits.trigger_to_popup,
+            CompletionStage::Filter => self.limits.filter,
+            CompletionStage::Render => self.limits.render,
+        }
+    }
+
+    fn is_degraded(&self) -> bool {
+        !self.locality_scoring || self.candidate_cap < DEFAULT_COMPLETION_CANDIDATE_CAP
+    }
+
+    /// Every stage has a full window averaging under half its budget.
+    fn all_stages_comfortable(&self) -> bool {
+        [
+            CompletionStage::TriggerToPopup,
+            CompletionStage::Filter,
+            CompletionStage::Render,
+        ]
+        .into_iter()
+        .all(|stage| {
+            let window = self.window(stage);
+            window.is_full() && window.average() < self.limit(stage) / 2
+        })
+    }
+
+    fn degrade(&mut self, stage: CompletionStage) {
+        if stage == CompletionStage::TriggerToPopup && self.locality_scoring {
+            self.locality_scoring = false;
+        } else if self.candidate_cap > MIN_COMPLETION_CANDIDATE_CAP {
+            self.candidate_cap = (self.candidate_cap / 2).max(MIN_COMPLETION_CANDIDATE_CAP);
+        } else {
+            return;
+        }
+        self.adjustments += 1;
+        nucleotide_logging::info!(
+            stage = ?stage,
+            candidate_cap = self.candidate_cap,
+            locality_scoring = self.locality_scoring,
+            "Completion latency over budget; reducing completion work"
+        );
+    }
+
+    fn recover(&mut self) {
+        if self.candidate_cap < DEFAULT_COMPLETION_CANDIDATE_CAP {
+            self.candidate_cap = (self.candidate_cap * 2).min(DEFAULT_COMPLETION_CANDIDATE_CAP);
+        } else {
+            self.locality_scoring = true;
+        }
+        self.adjustments += 1;
+        nucleotide_logging::info!(
+            candidate_cap = self.candidate_cap,
+            locality_scoring = self.locality_scoring,
+            "Completion latency back under budget; restoring completion work"
+        );
+    }
+
+    /// Lines for the performance HUD.
+    pub fn hud_lines(&self) -> Vec<String> {
+        let stage_line = |label: &str, stage: CompletionStage| {
+            let window = self.window(stage);
+            let average = window.average();
+            let limit = self.limit(stage);
+            format!(
+                "{label:<16} {:>6.1}ms / {:>5.1}ms{}",
+                average.as_secs_f64() * 1000.0,
+                limit.as_secs_f64() * 1000.0,
+                if window.len() > 0 && average > limit {
+                    "  over"
+                } else {
+                    ""
+                }
+            )
+        };
+
+        vec![
+            "Completion".to_string(),
+            stage_line("trigger → popup", CompletionStage::TriggerToPopup),
+            stage_line("filter", CompletionStage::Filter),
+            stage_line("render", CompletionStage::Render),
+            format!(
+                "candidate cap    {} / {DEFAULT_COMPLETION_CANDIDATE_CAP}",
+                self.candidate_cap
+            ),
+            format!(
+                "locality scoring {}",
+                if self.locality_scoring { "on" } else { "off" }
+            ),
+            format!("adjustments      {}", self.adjustments),
+        ]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -503,4 +728,66 @@ mod tests {
                 .any(|r| r.contains("Cache hit ratio is low"))
         );
     }
+
+    #[test]
+    fn slow_popups_disable_locality_scoring_before_capping_candidates() {
+        let mut budget = CompletionBudget::default();
+        let slow = Duration::from_millis(400);
+
+        for _ in 0..BUDGET_SAMPLES {
+            budget.record(CompletionStage::TriggerToPopup, slow);
+        }
+        assert!(!budget.locality_scoring_enabled());
+        assert_eq!(budget.candidate_cap(), DEFAULT_COMPLETION_CANDIDATE_CAP);
+
+        for _ in 0..BUDGET_SAMPLES {
+            budget.record(CompletionStage::TriggerToPopup, slow);
+        }
+        assert_eq!(budget.candidate_cap(), DEFAULT_COMPLETION_CANDIDATE_CAP / 2);
+    }
+
+    #[test]
+    fn slow_filtering_halves_the_cap_down_to_the_floor() {
+        let mut budget = CompletionBudget::default();
+
+        for _ in 0..(BUDGET_SAMPLES * 10) {
+            budget.record(CompletionStage::Filter, Duration::from_millis(50));
+        }
+
+        assert_eq!(budget.candidate_cap(), MIN_COMPLETION_CANDIDATE_CAP);
+        assert!(budget.locality_scoring_enabled());
+    }
+
+    #[test]
+    fn fast_stages_restore_the_pipeline_one_step_at_a_time() {
+        let mut budget = CompletionBudget::default();
+        for _ in 0..BUDGET_SAMPLES {
+            budget.record(CompletionStage::Filter, Duration::from_millis(50));
+        }
+        for _ in 0..BUDGET_SAMPLES {
+            budget.record(CompletionStage::TriggerToPopup, Duration::from_millis(400));
+        }
+        assert!(!budget.locality_scoring_enabled());
+
+        let record_fast = |budget: &mut CompletionBudget| {
+            for _ in 0..BUDGET_SAMPLES {
+                budget.record(CompletionStage::TriggerToPopup, Duration::from_millis(1));
+                budget.record(CompletionStage::Filter, Duration::from_micros(100));
+                budget.record(CompletionStage::Render, Duration::from_micros(100));
+            }
+        };
+
+        record_fast(&mut budget);
+        assert_eq!(budget.candidate_cap(), DEFAULT_COMPLETION_CANDIDATE_CAP);
+        assert!(!budget.locality_scoring_enabled());
+
+        record_fast(&mut budget);
+        assert!(budget.locality_scoring_enabled());
+        assert!(
+            budget
+                .hud_lines()
+                .iter()
+                .any(|line| line == "adjustments      4")
+        );
+    }
 }
@@ -7,6 +7,9 @@ use gpui::{
 };
 use std::cmp::max;
 
+/// Space between the cursor line and a popup placed above or below it.
+const CURSOR_GAP: Pixels = px(2.0);
+
 /// Position preference for popup placement
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PopupPlacement {
@@ -129,6 +132,70 @@ impl PopupPositioner {
         }
     }
 
+    /// Places a popup of `content_size` just below the `cursor` cell, or just
+    /// above it when it would run off the bottom of `window_bounds` and fits
+    /// above (or there is simply more room there). The popup starts at the
+    /// cursor column and shifts left to stay inside the window. `previous`
+    /// is kept while it still fits, so a list that grows or shrinks while
+    /// typing doesn't jump from one side of the cursor to the other.
+    pub fn position_at_cursor(
+        &self,
+        cursor: Bounds<Pixels>,
+        content_size: Size<Pixels>,
+        window_bounds: Bounds<Pixels>,
+        previous: Option<PopupPlacement>,
+    ) -> PopupPosition {
+        let margin = self.constraints.margin;
+        let window_top = window_bounds.origin.y + margin;
+        let window_bottom = window_bounds.origin.y + window_bounds.size.height - margin;
+        let window_left = window_bounds.origin.x + margin;
+        let window_right = window_bounds.origin.x + window_bounds.size.width - margin;
+
+        let cursor_bottom = cursor.origin.y + cursor.size.height;
+        let available_space = AvailableSpace {
+            above: max(px(0.0), cursor.origin.y - CURSOR_GAP - window_top),
+            below: max(px(0.0), window_bottom - cursor_bottom - CURSOR_GAP),
+            left: max(px(0.0), cursor.origin.x - window_left),
+            right: max(px(0.0), window_right - cursor.origin.x),
+        };
+
+        let fits_below = content_size.height <= available_space.below;
+        let fits_above = content_size.height <= available_space.above;
+        let placement = match previous {
+            Some(PopupPlacement::Above) if fits_above => PopupPlacement::Above,
+            Some(PopupPlacement::Below) if fits_below => PopupPlacement::Below,
+            _ if fits_below => PopupPlacement::Below,
+            _ if fits_above || available_space.above > available_space.below => {
+                PopupPlacement::Above
+            }
+            _ => PopupPlacement::Below,
+        };
+
+        let preferred_y = match placement {
+            PopupPlacement::Above => cursor.origin.y - CURSOR_GAP - content_size.height,
+            _ => cursor_bottom + CURSOR_GAP,
+        };
+        // A popup taller than either side is pinned to the window instead.
+        let y = preferred_y
+            .min(window_bottom - content_size.height)
+            .max(window_top);
+        let x = cursor
+            .origin
+            .x
+            .min(window_right - content_size.width)
+            .max(window_left);
+
+        PopupPosition {
+            bounds: Bounds {
+                origin: point(x, y),
+                size: content_size,
+            },
+            placement,
+            constrained: y != preferred_y || x != cursor.origin.x,
+            available_space,
+        }
+    }
+
     /// Calculate available space in all directions from anchor
     fn calculate_available_space(
         &self,
@@ -534,6 +601,47 @@ mod tests {
         assert!(x < px(300.0)); // Should be constrained
     }
 
+    #[test]
+    fn cursor_popups_flip_above_near_the_bottom_and_stay_in_the_window() {
+        let positioner = PopupPositioner::new(PopupConstraints::default());
+        let window_bounds = Bounds {
+            origin: point(px(0.0), px(0.0)),
+            size: size(px(800.0), px(600.0)),
+        };
+        let popup = size(px(300.0), px(200.0));
+        let cursor_at = |x: f32, y: f32| Bounds {
+            origin: point(px(x), px(y)),
+            size: size(px(8.0), px(20.0)),
+        };
+
+        let below =
+            positioner.position_at_cursor(cursor_at(100.0, 100.0), popup, window_bounds, None);
+        assert_eq!(below.placement, PopupPlacement::Below);
+        assert_eq!(below.bounds.origin, point(px(100.0), px(122.0)));
+        assert!(!below.constrained);
+
+        let above =
+            positioner.position_at_cursor(cursor_at(100.0, 500.0), popup, window_bounds, None);
+        assert_eq!(above.placement, PopupPlacement::Above);
+        assert_eq!(above.bounds.origin.y, px(298.0));
+
+        // Near the right edge the popup, docs panel included, shifts left.
+        let wide = size(px(700.0), px(200.0));
+        let right =
+            positioner.position_at_cursor(cursor_at(600.0, 100.0), wide, window_bounds, None);
+        assert_eq!(right.bounds.origin.x, px(92.0));
+        assert!(right.constrained);
+
+        // Once above, the popup stays there while it fits, even with room below.
+        let sticky = positioner.position_at_cursor(
+            cursor_at(100.0, 300.0),
+            popup,
+            window_bounds,
+            Some(PopupPlacement::Above),
+        );
+        assert_eq!(sticky.placement, PopupPlacement::Above);
+    }
+
     #[test]
     fn test_smart_popup_creation() {
         let anchor = point(px(100.0), px(200.0));
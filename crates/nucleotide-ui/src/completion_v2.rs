@@ -4,8 +4,8 @@
 use gpui::prelude::FluentBuilder;
 use gpui::{
     App, Context, DismissEvent, EventEmitter, FocusHandle, Focusable, InteractiveElement,
-    IntoElement, KeyBinding, ParentElement, Render, SharedString, StatefulInteractiveElement,
-    Styled, Task, Window, div, px, relative,
+    IntoElement, KeyBinding, ParentElement, Pixels, Render, SharedString, Size,
+    StatefulInteractiveElement, Styled, Task, Window, div, px, relative, size,
 };
 use std::cmp::Ordering as CmpOrdering;
 use std::sync::Arc;
@@ -18,7 +18,9 @@ use crate::completion_docs::{
 use crate::completion_error::{
     CompletionError, CompletionErrorHandler, ErrorContext, ErrorHandlingResult,
 };
-use crate::completion_perf::{PerformanceMonitor, PerformanceTimer};
+use crate::completion_perf::{
+    CompletionBudget, CompletionStage, PerformanceMonitor, PerformanceTimer,
+};
 use crate::completion_renderer::{CompletionItemElement, CompletionListState};
 use crate::debouncer::{CompletionDebouncer, create_completion_debouncer};
 // use crate::fuzzy::{FuzzyConfig, match_strings}; // Unused in synchronous filtering
@@ -33,6 +35,10 @@ const COMPLETION_VISIBLE_ROWS: usize = 6;
 const COMPLETION_ROW_HEIGHT_PX: f32 = 32.0;
 const COMPLETION_LIST_MAX_HEIGHT_PX: f32 =
     COMPLETION_VISIBLE_ROWS as f32 * COMPLETION_ROW_HEIGHT_PX;
+const COMPLETION_DOCS_WIDTH_PX: f32 = 390.0;
+/// Border and padding above and below the list rows.
+const COMPLETION_CHROME_PX: f32 = 6.0;
+const COMPLETION_DOCS_GAP_PX: f32 = 4.0;
 
 pub(crate) fn init(cx: &mut App) {
     cx.bind_keys([
@@ -364,6 +370,23 @@ fn compare_completion_items(left: &CompletionItem, right: &CompletionItem) -> Cm
         .then_with(|| left.source_index.cmp(&right.source_index))
 }
 
+/// Keeps the `cap` items ranked highest before any query is typed, so an
+/// over-budget completion request hands less work to filtering and rendering.
+pub fn cap_completion_candidates(items: &mut Vec<CompletionItem>, cap: usize) {
+    if items.len() <= cap {
+        return;
+    }
+    items.sort_by(|left, right| {
+        right
+            .preselect
+            .cmp(&left.preselect)
+            .then_with(|| right.selection_priority.cmp(&left.selection_priority))
+            .then_with(|| right.locality_score.cmp(&left.locality_score))
+            .then_with(|| compare_completion_items(left, right))
+    });
+    items.truncate(cap);
+}
+
 fn compare_completion_text(left: &str, right: &str) -> CmpOrdering {
     left.to_lowercase()
         .cmp(&right.to_lowercase())
@@ -951,6 +974,7 @@ impl CompletionView {
             let (_, duration) = timer.stop();
             self.performance_monitor
                 .record_filter(duration, true, false);
+            Self::record_filter_budget(duration, cx);
 
             nucleotide_logging::debug!(
                 query = %query,
@@ -976,6 +1000,7 @@ impl CompletionView {
             );
             // Can optimize by filtering existing results
             self.filter_existing_results(&query, cx);
+            Self::record_filter_budget(timer.stop().1, cx);
             return;
         }
 
@@ -1040,6 +1065,7 @@ impl CompletionView {
             self.visible = !self.filtered_entries.is_empty();
             self.update_list_state();
             self.update_documentation_for_selection(cx);
+            Self::record_filter_budget(timer.stop().1, cx);
             cx.notify();
             return;
         }
@@ -1130,10 +1156,16 @@ impl CompletionView {
         let (_, duration) = timer.stop();
         self.performance_monitor
             .record_filter(duration, false, false);
+        Self::record_filter_budget(duration, cx);
 
         cx.notify();
     }
 
+    fn record_filter_budget(duration: std::time::Duration, cx: &mut Context<Self>) {
+        cx.default_global::<CompletionBudget>()
+            .record(CompletionStage::Filter, duration);
+    }
+
     /// Try to get optimization base from cache
     fn try_optimization_from_cache(&mut self, query: &str) -> Option<Arc<[StringMatch]>> {
         // Look for shorter queries that we can build upon
@@ -1943,6 +1975,67 @@ impl CompletionView {
         None
     }
 
+    /// Width of the list, estimated from the longest visible entry.
+    fn list_width(&self) -> f32 {
+        let base_width = 220.0; // Minimum practical width
+
+        // Find the longest text in visible items
+        let max_text_length = self
+            .filtered_entries
+            .iter()
+            .take(COMPLETION_VISIBLE_ROWS)
+            .map(|string_match| {
+                self.item_for_match(string_match)
+                    .map(|item| {
+                        // Calculate total text length including signature and type info
+                        let mut total_len = item
+                            .display_text
+                            .as_ref()
+                            .map_or(item.text.len(), |text| text.len());
+
+                        if let Some(sig) = &item.signature_info {
+                            total_len += sig.len();
+                        }
+                        if let Some(type_info) = &item.type_info {
+                            total_len += type_info.len() + 1;
+                        }
+                        if let Some(detail) = &item.detail {
+                            total_len += detail.len() + 1;
+                        }
+                        total_len
+                    })
+                    .unwrap_or(0)
+            })
+            .max()
+            .unwrap_or(0);
+
+        // Estimate pixel width (rough approximation: 8px per character)
+        let estimated_width: f32 = base_width + (max_text_length as f32 * 7.5);
+
+        // Cap the width to reasonable bounds
+        estimated_width.min(840.0).max(base_width)
+    }
+
+    /// Size the popup renders at, including the docs panel beside the list,
+    /// so the overlay can place it before layout.
+    pub fn estimated_popup_size(&self) -> Size<Pixels> {
+        let rows = self
+            .filtered_entries
+            .len()
+            .clamp(1, COMPLETION_VISIBLE_ROWS);
+        let list_height = rows as f32 * COMPLETION_ROW_HEIGHT_PX + COMPLETION_CHROME_PX;
+        let list_width = self.list_width();
+
+        if self.selected_documentation_preview().is_some() {
+            size(
+                px(list_width + COMPLETION_DOCS_GAP_PX + COMPLETION_DOCS_WIDTH_PX),
+                px(list_height.max(COMPLETION_LIST_MAX_HEIGHT_PX)),
+            )
+        } else {
+            size(px(list_width), px(list_height))
+        }
+    }
+
     /// Tune performance parameters based on system capabilities
     pub fn tune_performance_parameters(&mut self) {
         // Adjust parameters based on current performance
@@ -2015,6 +2108,7 @@ impl EventEmitter<CompletionWarningEvent> for CompletionView {}
 
 impl Render for CompletionView {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let render_started = std::time::Instant::now();
         nucleotide_logging::debug!(
             visible = self.is_visible(),
             all_items_count = self.all_items.len(),
@@ -2089,7 +2183,7 @@ impl Render for CompletionView {
             item_count = self.filtered_entries.len(),
             "Completion view render completed"
         );
-        container
+        let popup = container
             .absolute()
             // Remove hardcoded positioning - parent will handle this
             .child(
@@ -2104,57 +2198,7 @@ impl Render for CompletionView {
                             .id("completion-list")
                             .flex()
                             .flex_col()
-                            // Calculate optimal width based on content
-                            .w({
-                                // Calculate optimal width based on the longest item
-                                let base_width = 220.0; // Minimum practical width
-
-                                // Find the longest text in visible items
-                                let max_text_length = self
-                                    .filtered_entries
-                                    .iter()
-                                    .take(max_visible_items)
-                                    .map(|string_match| {
-                                        self.item_for_match(string_match)
-                                            .map(|item| {
-                                                // Calculate total text length including signature and type info
-                                                let mut total_len = item
-                                                    .display_text
-                                                    .as_ref()
-                                                    .map_or(item.text.len(), |text| text.len());
-
-                                                if let Some(sig) = &item.signature_info {
-                                                    total_len += sig.len();
-                                                }
-                                                if let Some(type_info) = &item.type_info {
-                                                    total_len += type_info.len() + 1;
-                                                }
-                                                if let Some(detail) = &item.detail {
-                                                    total_len += detail.len() + 1;
-                                                }
-                                                total_len
-                                            })
-                                            .unwrap_or(0)
-                                    })
-                                    .max()
-                                    .unwrap_or(0);
-
-                                // Estimate pixel width (rough approximation: 8px per character)
-                                let estimated_width: f32 =
-                                    base_width + (max_text_length as f32 * 7.5);
-
-                                // Cap the width to reasonable bounds
-                                let optimal_width = estimated_width.min(840.0).max(base_width);
-
-                                nucleotide_logging::debug!(
-                                    max_text_length = max_text_length,
-                                    estimated_width = estimated_width,
-                                    optimal_width = optimal_width,
-                                    "Calculated dynamic completion width"
-                                );
-
-                                px(optimal_width)
-                            })
+                            .w(px(self.list_width()))
                             .bg(tokens.chrome.popup_background)
                             .border_1()
                             .border_color(tokens.chrome.popup_border)
@@ -2338,7 +2382,7 @@ impl Render for CompletionView {
                                 .id("completion-documentation-preview")
                                 .flex()
                                 .flex_col()
-                                .w(px(390.0))
+                                .w(px(COMPLETION_DOCS_WIDTH_PX))
                                 .max_w(px(460.0))
                                 .max_h(max_container_height)
                                 .overflow_hidden()
@@ -2354,7 +2398,11 @@ impl Render for CompletionView {
                                 .child(documentation),
                         )
                     }),
-            )
+            );
+
+        cx.default_global::<CompletionBudget>()
+            .record(CompletionStage::Render, render_started.elapsed());
+        popup
     }
 }
 
@@ -2374,6 +2422,21 @@ mod tests {
         assert!(candidate.id > 0); // Should have generated an ID
     }
 
+    #[test]
+    fn capping_candidates_keeps_preselected_and_remembered_items() {
+        let mut items: Vec<CompletionItem> = ["delta", "alpha", "charlie", "bravo"]
+            .into_iter()
+            .map(CompletionItem::new)
+            .collect();
+        items[0].preselect = true;
+        items[2].selection_priority = 3;
+
+        cap_completion_candidates(&mut items, 3);
+
+        let labels: Vec<&str> = items.iter().map(|item| item.text.as_ref()).collect();
+        assert_eq!(labels, ["delta", "charlie", "alpha"]);
+    }
+
     #[test]
     fn test_string_match_ordering() {
         let match1 = StringMatch::new(1, 100, vec![0, 1, 2]);
@@ -2660,6 +2723,48 @@ mod tests {
             });
         }
 
+        #[gpui::test]
+        async fn estimated_popup_size_widens_for_the_docs_panel(cx: &mut TestAppContext) {
+            let (completion_view, _cx) = cx.add_window_view(|_window, cx| {
+                let mut view = CompletionView::new(cx);
+                view.set_items_with_filter(
+                    vec![
+                        CompletionItem::new("len"),
+                        CompletionItem::new("push").with_documentation("Appends an element"),
+                    ],
+                    None,
+                    cx,
+                );
+                view
+            });
+
+            cx.run_until_parked();
+
+            completion_view.update(cx, |view, _cx| {
+                let list_width = px(view.list_width());
+                view.selected_index = view
+                    .filtered_entries
+                    .iter()
+                    .position(|entry| {
+                        view.item_for_match(entry)
+                            .is_some_and(|item| item.documentation.is_none())
+                    })
+                    .expect("undocumented item");
+                assert_eq!(
+                    view.estimated_popup_size(),
+                    size(
+                        list_width,
+                        px(2.0 * COMPLETION_ROW_HEIGHT_PX + COMPLETION_CHROME_PX)
+                    )
+                );
+
+                view.selected_index = 1 - view.selected_index;
+                let with_docs = view.estimated_popup_size();
+                assert!(with_docs.width > list_width + px(COMPLETION_DOCS_WIDTH_PX));
+                assert_eq!(with_docs.height, px(COMPLETION_LIST_MAX_HEIGHT_PX));
+            });
+        }
+
         #[gpui::test]
         async fn test_set_items_with_filter_basic(cx: &mut TestAppContext) {
             // Test that set_items_with_filter correctly applies an initial filter
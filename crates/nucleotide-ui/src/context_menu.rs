@@ -1,6 +1,10 @@
-// ABOUTME: Shared context-menu open-state controller
+// ABOUTME: Shared context-menu open state and the popup menu it shows
 // ABOUTME: Popup menu rendering lives in menu::PopupMenu
 
+use gpui::{Context, DismissEvent, Entity, Subscription};
+
+use crate::menu::PopupMenu;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ContextMenuController {
     open: bool,
@@ -44,10 +48,73 @@ impl Default for ContextMenuController {
     }
 }
 
+/// A context menu: where it is open and the popup menu it shows. The menu
+/// is built for each opening and dropped on close, so it always reflects the
+/// target it was opened on.
+#[derive(Default)]
+pub struct ContextMenu {
+    controller: ContextMenuController,
+    menu: Option<Entity<PopupMenu>>,
+    _dismiss_subscription: Option<Subscription>,
+}
+
+impl ContextMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.controller.is_open()
+    }
+
+    pub fn position(&self) -> (f32, f32) {
+        self.controller.position()
+    }
+
+    /// Opens the menu at `position`, discarding any menu built for an
+    /// earlier opening.
+    pub fn open_at(&mut self, position: (f32, f32)) {
+        self.controller.open_at(position);
+        self.menu = None;
+        self._dismiss_subscription = None;
+    }
+
+    /// The menu built for this opening, if any.
+    pub fn menu(&self) -> Option<&Entity<PopupMenu>> {
+        self.menu.as_ref()
+    }
+
+    /// Stores the menu built for this opening. `on_dismiss` runs when the
+    /// menu dismisses itself, after an item runs or on escape.
+    pub fn set_menu<V: 'static>(
+        &mut self,
+        menu: Entity<PopupMenu>,
+        cx: &mut Context<V>,
+        on_dismiss: impl Fn(&mut V, &mut Context<V>) + 'static,
+    ) {
+        self._dismiss_subscription = Some(
+            cx.subscribe(&menu, move |view, _menu, _event: &DismissEvent, cx| {
+                on_dismiss(view, cx)
+            }),
+        );
+        self.menu = Some(menu);
+    }
+
+    /// Closes the menu and drops it. Returns whether anything was open.
+    pub fn close(&mut self) -> bool {
+        let closed = self.controller.close();
+        let had_menu = self.menu.take().is_some();
+        let had_subscription = self._dismiss_subscription.take().is_some();
+        closed || had_menu || had_subscription
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use gpui::AppContext as _;
+
     #[test]
     fn controller_opens_at_position() {
         let mut controller = ContextMenuController::new();
@@ -70,4 +137,49 @@ mod tests {
         assert!(!controller.is_open());
         assert!(!controller.close());
     }
+
+    #[gpui::test]
+    fn context_menu_rebuilds_its_menu_for_each_opening(cx: &mut gpui::TestAppContext) {
+        struct Owner {
+            context_menu: ContextMenu,
+            dismissals: usize,
+        }
+
+        let owner = cx.new(|_| Owner {
+            context_menu: ContextMenu::new(),
+            dismissals: 0,
+        });
+        let window = cx.add_empty_window();
+
+        window.update(|window, cx| {
+            owner.update(cx, |owner, cx| {
+                owner.context_menu.open_at((4.0, 8.0));
+                let menu = PopupMenu::build(window, cx, |menu, _, _| menu.label("Copy"));
+                owner
+                    .context_menu
+                    .set_menu(menu, cx, |owner, _| owner.dismissals += 1);
+                assert!(owner.context_menu.menu().is_some());
+
+                owner.context_menu.open_at((16.0, 32.0));
+                assert!(owner.context_menu.menu().is_none());
+                assert_eq!(owner.context_menu.position(), (16.0, 32.0));
+                assert!(owner.context_menu.close());
+                assert!(!owner.context_menu.is_open());
+                assert!(!owner.context_menu.close());
+            });
+        });
+
+        window.update(|window, cx| {
+            let menu = PopupMenu::build(window, cx, |menu, _, _| menu.label("Paste"));
+            owner.update(cx, |owner, cx| {
+                owner.context_menu.open_at((0.0, 0.0));
+                owner
+                    .context_menu
+                    .set_menu(menu.clone(), cx, |owner, _| owner.dismissals += 1);
+            });
+            menu.update(cx, |_, cx| cx.emit(DismissEvent));
+        });
+
+        assert_eq!(owner.read_with(cx, |owner, _| owner.dismissals), 1);
+    }
 }
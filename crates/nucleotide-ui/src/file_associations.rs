@@ -0,0 +1,133 @@
+// ABOUTME: User-configured file name associations for icons and languages
+// ABOUTME: Consulted before the built-in extension mapping and language detection
+
+use std::sync::{Arc, LazyLock, RwLock};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+/// Maps files whose name matches `glob` to an icon and/or a language id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileAssociation {
+    /// Glob matched against the file name, e.g. `*.mdx` or `Dockerfile.*`
+    pub glob: String,
+    /// Language id passed to Helix, e.g. `markdown`
+    pub language: Option<String>,
+    /// Extension whose icon to show, e.g. `md`
+    pub icon: Option<String>,
+}
+
+/// Compiled associations; earlier entries win when several globs match.
+#[derive(Debug, Default)]
+pub struct FileAssociations {
+    globs: GlobSet,
+    associations: Vec<FileAssociation>,
+}
+
+impl FileAssociations {
+    /// Compiles `associations`, skipping entries whose glob does not parse.
+    pub fn new(associations: impl IntoIterator<Item = FileAssociation>) -> Self {
+        let mut builder = GlobSetBuilder::new();
+        let mut compiled = Vec::new();
+        for association in associations {
+            match Glob::new(&association.glob) {
+                Ok(glob) => {
+                    builder.add(glob);
+                    compiled.push(association);
+                }
+                Err(error) => {
+                    nucleotide_logging::warn!(
+                        glob = %association.glob,
+                        error = %error,
+                        "Ignoring file association with an invalid glob"
+                    );
+                }
+            }
+        }
+
+        match builder.build() {
+            Ok(globs) => Self {
+                globs,
+                associations: compiled,
+            },
+            Err(error) => {
+                nucleotide_logging::warn!(error = %error, "Failed to build file associations");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.associations.is_empty()
+    }
+
+    fn matching<'a>(&'a self, file_name: &str) -> impl Iterator<Item = &'a FileAssociation> {
+        self.globs
+            .matches(file_name)
+            .into_iter()
+            .map(|index| &self.associations[index])
+    }
+
+    /// Icon key configured for `file_name`, if any.
+    pub fn icon_for(&self, file_name: &str) -> Option<&str> {
+        self.matching(file_name)
+            .find_map(|association| association.icon.as_deref())
+    }
+
+    /// Language id configured for `file_name`, if any.
+    pub fn language_for(&self, file_name: &str) -> Option<&str> {
+        self.matching(file_name)
+            .find_map(|association| association.language.as_deref())
+    }
+}
+
+static FILE_ASSOCIATIONS: LazyLock<RwLock<Arc<FileAssociations>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(FileAssociations::default())));
+
+/// Replaces the associations used by [`crate::FileIcon`] and language detection.
+pub fn set_file_associations(associations: FileAssociations) {
+    let mut current = FILE_ASSOCIATIONS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *current = Arc::new(associations);
+}
+
+/// The associations currently in effect.
+pub fn file_associations() -> Arc<FileAssociations> {
+    FILE_ASSOCIATIONS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn association(glob: &str, language: Option<&str>, icon: Option<&str>) -> FileAssociation {
+        FileAssociation {
+            glob: glob.to_string(),
+            language: language.map(str::to_string),
+            icon: icon.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn earlier_associations_win_and_fields_fall_through() {
+        let associations = FileAssociations::new([
+            association("Dockerfile.dev", Some("dockerfile"), None),
+            association("Dockerfile.*", Some("bash"), Some("sh")),
+            association("*.mdx", Some("markdown"), Some("md")),
+            association("[invalid", Some("rust"), None),
+        ]);
+
+        assert_eq!(
+            associations.language_for("Dockerfile.dev"),
+            Some("dockerfile")
+        );
+        assert_eq!(associations.icon_for("Dockerfile.dev"), Some("sh"));
+        assert_eq!(associations.language_for("page.mdx"), Some("markdown"));
+        assert_eq!(associations.icon_for("page.mdx"), Some("md"));
+        assert_eq!(associations.language_for("main.rs"), None);
+        assert_eq!(associations.icon_for("[invalid"), None);
+    }
+}
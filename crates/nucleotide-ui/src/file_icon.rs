@@ -4,6 +4,7 @@
 use gpui::{Hsla, IntoElement, Styled, Svg, svg};
 use std::path::Path;
 
+use crate::file_associations::file_associations;
 use crate::tokens::STANDARD_ICON_SIZE;
 
 /// File icon component that provides consistent icons across the application
@@ -25,16 +26,12 @@ impl FileIcon {
     /// Create a new file icon from a file path
     pub fn from_path(path: &Path, is_expanded: bool) -> Self {
         let is_directory = path.is_dir();
-        let extension = if is_directory {
-            None
-        } else {
-            path.extension()
-                .and_then(|ext| ext.to_str())
-                .map(|s| s.to_lowercase())
-        };
+        if !is_directory && let Some(file_name) = path.file_name().and_then(|name| name.to_str()) {
+            return Self::from_file_name(file_name);
+        }
 
         Self {
-            extension,
+            extension: None,
             is_directory,
             is_expanded,
             size: STANDARD_ICON_SIZE,
@@ -42,6 +39,20 @@ impl FileIcon {
         }
     }
 
+    /// Create a file icon from a file name, honouring configured file associations
+    pub fn from_file_name(file_name: &str) -> Self {
+        let extension = file_associations()
+            .icon_for(file_name)
+            .map(str::to_string)
+            .or_else(|| {
+                Path::new(file_name)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(str::to_string)
+            });
+        Self::from_extension(extension.as_deref())
+    }
+
     /// Create a file icon from extension string
     pub fn from_extension(extension: Option<&str>) -> Self {
         Self {
@@ -247,6 +258,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn file_names_use_their_extension_case_insensitively() {
+        assert_eq!(
+            FileIcon::from_file_name("README.MD").icon_path(),
+            "icons/file-md.svg"
+        );
+        assert_eq!(
+            FileIcon::from_file_name("Makefile").icon_path(),
+            "icons/file.svg"
+        );
+    }
+
     #[test]
     fn uses_semantic_fallbacks_for_unmapped_extensions() {
         assert_eq!(
@@ -139,9 +139,9 @@ impl FocusCoordinator {
         self.file_tree.read().ok().and_then(|g| g.clone())
     }
 
-    /// Focus the given role if a handle is registered. Returns true on success.
-    pub fn focus_role(&self, window: &mut Window, cx: &mut App, role: FocusRole) -> bool {
-        let handle = match role {
+    /// The handle registered for `role`, if any.
+    pub fn role_focus(&self, role: FocusRole) -> Option<FocusHandle> {
+        match role {
             FocusRole::Editor => self.editor_focus(),
             FocusRole::Completion => self.completion_focus(),
             FocusRole::Prompt => self.prompt_focus(),
@@ -149,8 +149,20 @@ impl FocusCoordinator {
             FocusRole::Picker => self.picker_focus(),
             FocusRole::Diagnostics => self.diagnostics_focus(),
             FocusRole::FileTree => self.file_tree_focus(),
-        };
-        if let Some(h) = handle {
+        }
+    }
+
+    /// Roles with a registered handle, in [`FocusRole::ALL`] order.
+    pub fn registered_roles(&self) -> Vec<(FocusRole, FocusHandle)> {
+        FocusRole::ALL
+            .into_iter()
+            .filter_map(|role| Some((role, self.role_focus(role)?)))
+            .collect()
+    }
+
+    /// Focus the given role if a handle is registered. Returns true on success.
+    pub fn focus_role(&self, window: &mut Window, cx: &mut App, role: FocusRole) -> bool {
+        if let Some(h) = self.role_focus(role) {
             if !h.is_focused(window) {
                 h.focus(window, cx);
             }
@@ -185,6 +197,18 @@ pub enum FocusRole {
     FileTree,
 }
 
+impl FocusRole {
+    pub const ALL: [FocusRole; 7] = [
+        FocusRole::Editor,
+        FocusRole::Completion,
+        FocusRole::Prompt,
+        FocusRole::Terminal,
+        FocusRole::Picker,
+        FocusRole::Diagnostics,
+        FocusRole::FileTree,
+    ];
+}
+
 /// Wraps a focus scope with standard Tab and Shift-Tab traversal actions.
 #[derive(IntoElement)]
 pub struct FocusTraversal {
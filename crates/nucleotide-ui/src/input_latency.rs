@@ -0,0 +1,284 @@
+// ABOUTME: End-to-end typing latency tracing from keypress to the next editor render
+// ABOUTME: Correlates each key through Helix command execution and keeps P50/P95 histograms
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use gpui::Global;
+
+/// Samples kept per histogram; percentiles describe the most recent keys.
+const HISTOGRAM_SAMPLES: usize = 512;
+/// Keys waiting for a render; older ones are dropped.
+const MAX_PENDING_KEYS: usize = 64;
+/// A key not rendered within this long is abandoned rather than recorded.
+const STALE_KEY_AFTER: Duration = Duration::from_secs(1);
+/// Completed keys between percentile summaries in the log.
+const LOG_SUMMARY_EVERY: u64 = 256;
+
+static NEXT_INPUT_LATENCY_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Correlation id carried by one key from the input coordinator through
+/// Helix command execution to the render that shows its effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InputLatencyId(u64);
+
+impl InputLatencyId {
+    fn next() -> Self {
+        Self(NEXT_INPUT_LATENCY_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    pub fn get(self) -> u64 {
+        self.0
+    }
+}
+
+/// Recent latencies of one stage, summarised as percentiles.
+#[derive(Debug)]
+pub struct LatencyHistogram {
+    samples: VecDeque<Duration>,
+    capacity: usize,
+}
+
+impl LatencyHistogram {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn record(&mut self, duration: Duration) {
+        if self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(duration);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Nearest-rank percentile, `percentile` in 0..=100.
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<Duration> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = ((percentile.clamp(0.0, 100.0) / 100.0) * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.saturating_sub(1).min(sorted.len() - 1)])
+    }
+
+    pub fn p50(&self) -> Option<Duration> {
+        self.percentile(50.0)
+    }
+
+    pub fn p95(&self) -> Option<Duration> {
+        self.percentile(95.0)
+    }
+}
+
+#[derive(Debug)]
+struct PendingKey {
+    id: InputLatencyId,
+    received_at: Instant,
+    command_finished_at: Option<Instant>,
+}
+
+/// Traces keys from the moment the input coordinator sees them until the
+/// next document render, split into queueing, Helix command execution and
+/// the wait for the frame.
+#[derive(Debug)]
+pub struct InputLatencyTracker {
+    pending: VecDeque<PendingKey>,
+    /// Keypress until Helix starts executing the key
+    dispatch: LatencyHistogram,
+    /// Helix command execution for the key
+    command: LatencyHistogram,
+    /// Keypress until the document has been rendered
+    total: LatencyHistogram,
+    completed: u64,
+}
+
+impl Global for InputLatencyTracker {}
+
+impl Default for InputLatencyTracker {
+    fn default() -> Self {
+        Self {
+            pending: VecDeque::new(),
+            dispatch: LatencyHistogram::new(HISTOGRAM_SAMPLES),
+            command: LatencyHistogram::new(HISTOGRAM_SAMPLES),
+            total: LatencyHistogram::new(HISTOGRAM_SAMPLES),
+            completed: 0,
+        }
+    }
+}
+
+impl InputLatencyTracker {
+    /// Starts tracing a key received at `received_at`.
+    pub fn begin(&mut self, received_at: Instant) -> InputLatencyId {
+        let id = InputLatencyId::next();
+        if self.pending.len() >= MAX_PENDING_KEYS {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(PendingKey {
+            id,
+            received_at,
+            command_finished_at: None,
+        });
+        id
+    }
+
+    /// Records that Helix executed the key between `started_at` and `finished_at`.
+    pub fn record_command(
+        &mut self,
+        id: InputLatencyId,
+        started_at: Instant,
+        finished_at: Instant,
+    ) {
+        let Some(key) = self.pending.iter_mut().find(|key| key.id == id) else {
+            return;
+        };
+        key.command_finished_at = Some(finished_at);
+        self.dispatch
+            .record(started_at.saturating_duration_since(key.received_at));
+        self.command
+            .record(finished_at.saturating_duration_since(started_at));
+    }
+
+    /// Completes every executed key at a document render happening at `rendered_at`.
+    pub fn record_render(&mut self, rendered_at: Instant) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        let mut remaining = VecDeque::with_capacity(self.pending.len());
+        for key in self.pending.drain(..) {
+            let total = rendered_at.saturating_duration_since(key.received_at);
+            match key.command_finished_at {
+                Some(command_finished_at) if command_finished_at <= rendered_at => {
+                    self.total.record(total);
+                    self.completed += 1;
+                    nucleotide_logging::trace!(
+                        latency_id = key.id.get(),
+                        total_ms = total.as_secs_f64() * 1000.0,
+                        "Key rendered"
+                    );
+                }
+                // Keys handled outside Helix never reach a command.
+                _ if total > STALE_KEY_AFTER => {}
+                _ => remaining.push_back(key),
+            }
+        }
+        self.pending = remaining;
+
+        if self.completed > 0 && self.completed.is_multiple_of(LOG_SUMMARY_EVERY) {
+            self.log_summary();
+        }
+    }
+
+    pub fn total(&self) -> &LatencyHistogram {
+        &self.total
+    }
+
+    fn log_summary(&self) {
+        nucleotide_logging::info!(
+            keys = self.completed,
+            total_p50_ms = millis(self.total.p50()),
+            total_p95_ms = millis(self.total.p95()),
+            dispatch_p50_ms = millis(self.dispatch.p50()),
+            dispatch_p95_ms = millis(self.dispatch.p95()),
+            command_p50_ms = millis(self.command.p50()),
+            command_p95_ms = millis(self.command.p95()),
+            "Input latency"
+        );
+    }
+
+    /// Lines for the performance HUD.
+    pub fn hud_lines(&self) -> Vec<String> {
+        let stage_line = |label: &str, histogram: &LatencyHistogram| {
+            format!(
+                "{label:<16} {:>6.1}ms p50 {:>6.1}ms p95",
+                millis(histogram.p50()),
+                millis(histogram.p95())
+            )
+        };
+
+        vec![
+            "Input latency".to_string(),
+            stage_line("key → dispatch", &self.dispatch),
+            stage_line("helix command", &self.command),
+            stage_line("key → render", &self.total),
+            format!("keys traced      {}", self.completed),
+        ]
+    }
+}
+
+fn millis(duration: Option<Duration>) -> f64 {
+    duration.map_or(0.0, |duration| duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let mut histogram = LatencyHistogram::new(100);
+        assert_eq!(histogram.p50(), None);
+        for ms in (1..=100).rev() {
+            histogram.record(Duration::from_millis(ms));
+        }
+
+        assert_eq!(histogram.p50(), Some(Duration::from_millis(50)));
+        assert_eq!(histogram.p95(), Some(Duration::from_millis(95)));
+        assert_eq!(
+            histogram.percentile(100.0),
+            Some(Duration::from_millis(100))
+        );
+
+        histogram.record(Duration::from_millis(500));
+        assert_eq!(histogram.len(), 100);
+        assert_eq!(histogram.percentile(0.0), Some(Duration::from_millis(1)));
+        assert_eq!(
+            histogram.percentile(100.0),
+            Some(Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn keys_complete_at_the_first_render_after_their_command() {
+        let mut tracker = InputLatencyTracker::default();
+        let start = Instant::now();
+        let executed = tracker.begin(start);
+        let not_yet_executed = tracker.begin(start + Duration::from_millis(1));
+
+        tracker.record_command(
+            executed,
+            start + Duration::from_millis(2),
+            start + Duration::from_millis(5),
+        );
+        tracker.record_render(start + Duration::from_millis(10));
+
+        assert_eq!(tracker.total().len(), 1);
+        assert_eq!(tracker.total().p50(), Some(Duration::from_millis(10)));
+        assert_eq!(tracker.dispatch.p50(), Some(Duration::from_millis(2)));
+        assert_eq!(tracker.command.p50(), Some(Duration::from_millis(3)));
+
+        tracker.record_render(start + Duration::from_secs(2));
+        assert_eq!(tracker.total().len(), 1);
+        assert!(tracker.pending.is_empty());
+
+        tracker.record_command(
+            not_yet_executed,
+            start + Duration::from_secs(3),
+            start + Duration::from_secs(3),
+        );
+        assert_eq!(tracker.command.len(), 1);
+    }
+}
@@ -328,7 +328,7 @@ fn key_label(key: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tokens::{ChromeTokens, DesignTokens, EditorTokens, SizeTokens};
+    use crate::tokens::{ChromeTokens, DesignTokens, EditorTokens, SizeTokens, TerminalTokens};
     use gpui::hsla;
     use nucleotide_appearance::{NativeChromePalette, SystemAppearance};
 
@@ -343,6 +343,7 @@ mod tests {
                 },
                 hsla(0.0, 0.70, 0.45, 1.0),
             )),
+            terminal: TerminalTokens::fallback(is_dark),
             sizes: SizeTokens::default(),
         })
     }
@@ -430,13 +430,75 @@ impl RenderOnce for Toolbar {
     }
 }
 
+/// Lane of a segmented status bar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum StatusBarAlign {
+    #[default]
+    Left,
+    /// The flexible space between the left and right lanes
+    Center,
+    Right,
+}
+
+/// One item of a segmented status bar. When the segments don't fit, the
+/// lowest priority ones are hidden first.
+pub struct StatusBarSegment {
+    align: StatusBarAlign,
+    priority: u8,
+    width: Pixels,
+    element: AnyElement,
+}
+
+impl StatusBarSegment {
+    /// `width` is the space the segment needs. It decides whether the
+    /// segment fits; the element sizes itself.
+    pub fn new(width: Pixels, element: impl IntoElement) -> Self {
+        Self {
+            align: StatusBarAlign::default(),
+            priority: 0,
+            width,
+            element: element.into_any_element(),
+        }
+    }
+
+    pub fn align(mut self, align: StatusBarAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// Which segments fit in `available`. Segments are hidden lowest priority
+/// first and, among equal priorities, last declared first.
+fn visible_segments(segments: &[(Pixels, u8)], available: Pixels) -> Vec<bool> {
+    let mut visible = vec![true; segments.len()];
+    let mut used: f32 = segments.iter().map(|(width, _)| f32::from(*width)).sum();
+    let mut order = (0..segments.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&index| (segments[index].1, std::cmp::Reverse(index)));
+    for index in order {
+        if used <= f32::from(available) {
+            break;
+        }
+        visible[index] = false;
+        used -= f32::from(segments[index].0);
+    }
+    visible
+}
+
 #[derive(IntoElement)]
 pub struct StatusBar {
     id: ElementId,
     active: bool,
     top_border: bool,
+    leading_border: bool,
     leading: Option<(Pixels, AnyElement)>,
     content: Option<AnyElement>,
+    segments: Vec<StatusBarSegment>,
+    available_width: Option<Pixels>,
     trailing: Option<AnyElement>,
     children: Vec<AnyElement>,
 }
@@ -447,8 +509,11 @@ impl StatusBar {
             id: id.into(),
             active: true,
             top_border: true,
+            leading_border: true,
             leading: None,
             content: None,
+            segments: Vec::new(),
+            available_width: None,
             trailing: None,
             children: Vec::new(),
         }
@@ -466,6 +531,13 @@ impl StatusBar {
         self
     }
 
+    /// Control whether the top border also runs over the leading region.
+    /// Disable this when the leading region continues a sidebar above it.
+    pub fn leading_border(mut self, leading_border: bool) -> Self {
+        self.leading_border = leading_border;
+        self
+    }
+
     /// Add a fixed-width region before the editor-aligned status content.
     pub fn leading(mut self, width: Pixels, child: impl IntoElement) -> Self {
         self.leading = Some((width, child.into_any_element()));
@@ -478,6 +550,19 @@ impl StatusBar {
         self
     }
 
+    /// Add a segment to the status content, in place of `content`.
+    pub fn segment(mut self, segment: StatusBarSegment) -> Self {
+        self.segments.push(segment);
+        self
+    }
+
+    /// Width the segments must fit in. Defaults to the window width less
+    /// the leading region.
+    pub fn available_width(mut self, width: Pixels) -> Self {
+        self.available_width = Some(width);
+        self
+    }
+
     /// Add a fixed trailing utility region.
     pub fn trailing(mut self, child: impl IntoElement) -> Self {
         self.trailing = Some(child.into_any_element());
@@ -492,7 +577,7 @@ impl ParentElement for StatusBar {
 }
 
 impl RenderOnce for StatusBar {
-    fn render(self, _window: &mut gpui::Window, cx: &mut App) -> impl IntoElement {
+    fn render(self, window: &mut gpui::Window, cx: &mut App) -> impl IntoElement {
         let tokens = &cx.global::<crate::Theme>().tokens;
         let status = tokens.status_bar_tokens();
         let background = if self.active {
@@ -501,7 +586,21 @@ impl RenderOnce for StatusBar {
             status.background_inactive
         };
 
-        let zoned = self.leading.is_some() || self.content.is_some() || self.trailing.is_some();
+        let zoned = self.leading.is_some()
+            || self.content.is_some()
+            || !self.segments.is_empty()
+            || self.trailing.is_some();
+        let full_border = self.top_border && self.leading_border;
+        let zone_border = self.top_border && !self.leading_border;
+        let content = self.content.or_else(|| {
+            (!self.segments.is_empty()).then(|| {
+                let leading_width = self.leading.as_ref().map_or(px(0.0), |(width, _)| *width);
+                let available = self
+                    .available_width
+                    .unwrap_or_else(|| window.viewport_size().width - leading_width);
+                render_segments(self.segments, available)
+            })
+        });
         let mut bar = div()
             .id(self.id)
             .flex()
@@ -510,7 +609,7 @@ impl RenderOnce for StatusBar {
             .h(tokens.sizes.statusbar_height)
             .min_w(px(0.0))
             .bg(background)
-            .when(self.top_border, |bar| {
+            .when(full_border, |bar| {
                 bar.border_t_1().border_color(status.border)
             })
             .text_size(tokens.sizes.text_sm)
@@ -528,11 +627,28 @@ impl RenderOnce for StatusBar {
                         .child(leading),
                 );
             }
-            if let Some(content) = self.content {
-                bar = bar.child(div().flex_1().min_w_0().h_full().child(content));
+            if let Some(content) = content {
+                bar = bar.child(
+                    div()
+                        .flex_1()
+                        .min_w_0()
+                        .h_full()
+                        .when(zone_border, |zone| {
+                            zone.border_t_1().border_color(status.border)
+                        })
+                        .child(content),
+                );
             }
             if let Some(trailing) = self.trailing {
-                bar = bar.child(div().flex_none().h_full().child(trailing));
+                bar = bar.child(
+                    div()
+                        .flex_none()
+                        .h_full()
+                        .when(zone_border, |zone| {
+                            zone.border_t_1().border_color(status.border)
+                        })
+                        .child(trailing),
+                );
             }
         } else {
             bar = bar
@@ -546,6 +662,48 @@ impl RenderOnce for StatusBar {
     }
 }
 
+fn render_segments(segments: Vec<StatusBarSegment>, available: Pixels) -> AnyElement {
+    let visible = visible_segments(
+        &segments
+            .iter()
+            .map(|segment| (segment.width, segment.priority))
+            .collect::<Vec<_>>(),
+        available,
+    );
+    let mut left = div().flex_none().h_full().flex().flex_row().items_center();
+    let mut center = div()
+        .flex_1()
+        .min_w_0()
+        .h_full()
+        .flex()
+        .flex_row()
+        .items_center()
+        .justify_center()
+        .overflow_hidden();
+    let mut right = div().flex_none().h_full().flex().flex_row().items_center();
+    for (segment, visible) in segments.into_iter().zip(visible) {
+        if !visible {
+            continue;
+        }
+        match segment.align {
+            StatusBarAlign::Left => left = left.child(segment.element),
+            StatusBarAlign::Center => center = center.child(segment.element),
+            StatusBarAlign::Right => right = right.child(segment.element),
+        }
+    }
+
+    div()
+        .size_full()
+        .min_w_0()
+        .flex()
+        .flex_row()
+        .items_center()
+        .child(left)
+        .child(center)
+        .child(right)
+        .into_any_element()
+}
+
 #[cfg(test)]
 mod tests {
     use gpui::{Context, IntoElement, ParentElement as _, Render, TestAppContext, div};
@@ -569,6 +727,23 @@ mod tests {
                 )
                 .child(EditorPaneGrid::new("editor-pane-grid").child(div().child("Editor")))
                 .child(BottomPanel::new("bottom-panel").height(gpui::px(48.0)))
+                .child(
+                    StatusBar::new("segmented-status-bar")
+                        .leading_border(false)
+                        .leading(gpui::px(120.0), div())
+                        .segment(
+                            StatusBarSegment::new(gpui::px(60.0), div().child("NOR"))
+                                .priority(u8::MAX),
+                        )
+                        .segment(
+                            StatusBarSegment::new(gpui::px(0.0), div().child("main.rs"))
+                                .align(StatusBarAlign::Center),
+                        )
+                        .segment(
+                            StatusBarSegment::new(gpui::px(80.0), div().child("UTF-8"))
+                                .align(StatusBarAlign::Right),
+                        ),
+                )
                 .footer(StatusBar::new("status-bar").child("Ready"))
         }
     }
@@ -633,6 +808,33 @@ mod tests {
         assert!(!seamless_status_bar.top_border);
     }
 
+    #[test]
+    fn status_bar_segments_hide_lowest_priority_first() {
+        let segments = [
+            (px(80.0), 255),
+            (px(60.0), 10),
+            (px(40.0), 50),
+            (px(60.0), 10),
+        ];
+
+        assert_eq!(
+            visible_segments(&segments, px(240.0)),
+            [true, true, true, true]
+        );
+        assert_eq!(
+            visible_segments(&segments, px(200.0)),
+            [true, true, true, false]
+        );
+        assert_eq!(
+            visible_segments(&segments, px(130.0)),
+            [true, false, true, false]
+        );
+        assert_eq!(
+            visible_segments(&segments, px(50.0)),
+            [false, false, false, false]
+        );
+    }
+
     #[gpui::test]
     fn semantic_layout_wrappers_render(cx: &mut TestAppContext) {
         init_layout_test(cx);
@@ -20,10 +20,12 @@ pub mod component_gallery;
 pub mod confirm_dialog;
 pub mod context_menu;
 pub mod debouncer;
+pub mod file_associations;
 pub mod file_icon;
 pub mod focus;
 pub mod info_box;
 pub mod input;
+pub mod input_latency;
 pub mod key_hint_view;
 pub mod layout;
 pub mod list_item;
@@ -32,8 +34,11 @@ pub mod menu;
 pub mod modal_layer;
 pub mod navigable;
 pub mod notification;
+pub mod overlay_manager;
 pub mod overlay_surface;
+pub mod path_match;
 pub mod picker;
+pub mod picker_source;
 pub mod picker_view;
 pub mod progress_indicator;
 pub mod prompt;
@@ -81,6 +86,7 @@ pub use completion_icons::{
     CompletionIconConfig, create_completion_icon, create_themed_completion_icon,
     get_completion_icon_color, get_completion_icon_svg,
 };
+pub use completion_perf::{CompletionBudget, CompletionStage};
 pub use completion_popup::{
     AvailableSpace, PopupConstraints, PopupPlacement, PopupPosition, PopupPositioner, SmartPopup,
     create_completion_popup,
@@ -93,20 +99,24 @@ pub use completion_v2::{
     CompleteViaHelixEvent, CompletionEdit, CompletionItem, CompletionItemKind,
     CompletionMenuAction, CompletionOffsetEncoding, CompletionPosition, CompletionRange,
     CompletionTextEdit, CompletionView, CompletionWarningEvent, Position, StringMatch,
-    StringMatchCandidate, completion_menu_action_for_key,
+    StringMatchCandidate, cap_completion_candidates, completion_menu_action_for_key,
 };
 pub use component_gallery::ComponentGallery;
 pub use confirm_dialog::{
     ConfirmDialog, ConfirmDialogEvent, ConfirmDialogView, DialogDescription, DialogFooter,
     DialogHeader, DialogTitle,
 };
-pub use context_menu::ContextMenuController;
+pub use context_menu::{ContextMenu, ContextMenuController};
+pub use file_associations::{
+    FileAssociation, FileAssociations, file_associations, set_file_associations,
+};
 pub use file_icon::FileIcon;
 pub use focus::{FOCUS_TRAVERSAL_CONTEXT, FocusCoordinator, FocusRole, FocusTraversal};
 pub use input::{InputSize, InputVariant};
+pub use input_latency::{InputLatencyId, InputLatencyTracker};
 pub use layout::{
-    AppShell, BottomPanel, EditorPaneGrid, Panel, PanelLayout, PanelVariant, StatusBar, Toolbar,
-    WorkspaceChrome,
+    AppShell, BottomPanel, EditorPaneGrid, Panel, PanelLayout, PanelVariant, StatusBar,
+    StatusBarAlign, StatusBarSegment, Toolbar, WorkspaceChrome,
 };
 pub use list_item::{
     ListItem, ListItemSpacing, ListItemState, ListItemVariant, SelectionMode, SelectionState,
@@ -117,6 +127,7 @@ pub use markdown::{
 pub use menu::{MenuCheckSide, PopupMenu, PopupMenuItem, PopupMenuSurface};
 pub use modal_layer::{DismissDecision, ModalLayer, ModalOpenedEvent, ModalView};
 pub use navigable::{NAVIGABLE_CONTEXT, Navigable, NavigableEntry};
+pub use overlay_manager::{DismissPolicy, Dismissal, OverlayManager, PopupId, PopupLayer};
 pub use overlay_surface::{OVERLAY_SURFACE_CONTEXT, OverlaySurface};
 pub use picker::Picker;
 pub use progress_indicator::IndeterminateProgressIndicator;
@@ -140,7 +151,7 @@ pub use theme_debug::ThemeDebugView;
 pub use tokens::{
     CheckboxTokens, ChromeTokens, ColorContext, CompletionIconTokens, ControlDensity,
     DensityMetrics, DesignTokens, EditorTokens, FileTreeTokens, ShadowToken, SizeTokens,
-    StatusBarTokens, TabBarTokens, TitleBarTokens,
+    StatusBarTokens, TabBarTokens, TerminalTokens, TitleBarTokens,
 };
 pub use traits::{
     Component, ComponentBuilder, ComponentFactory, ComponentState, ComponentStyles, Composable,
@@ -21,8 +21,10 @@ use pulldown_cmark::{
 };
 use std::{
     borrow::Cow,
+    cell::Cell,
     ops::Range,
     panic::{AssertUnwindSafe, catch_unwind},
+    rc::Rc,
     sync::Arc,
 };
 
@@ -137,6 +139,16 @@ pub struct MarkdownElement {
     source: SharedString,
     style: MarkdownStyle,
     parse_mode: MarkdownParseMode,
+    on_task_toggle: Option<TaskToggle>,
+}
+
+#[derive(Clone)]
+struct TaskToggle(Rc<dyn Fn(usize, &mut Window, &mut gpui::App)>);
+
+impl std::fmt::Debug for TaskToggle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TaskToggle")
+    }
 }
 
 pub fn markdown(source: impl Into<SharedString>, style: MarkdownStyle) -> MarkdownElement {
@@ -144,6 +156,7 @@ pub fn markdown(source: impl Into<SharedString>, style: MarkdownStyle) -> Markdo
         source: source.into(),
         style,
         parse_mode: MarkdownParseMode::CommonMark,
+        on_task_toggle: None,
     }
 }
 
@@ -152,6 +165,58 @@ pub fn markdown_extended(source: impl Into<SharedString>, style: MarkdownStyle)
         source: source.into(),
         style,
         parse_mode: MarkdownParseMode::Extended,
+        on_task_toggle: None,
+    }
+}
+
+impl MarkdownElement {
+    /// Make task checkboxes clickable. The callback receives the clicked
+    /// task's index in document order, matching [`task_marker_lines`].
+    pub fn on_task_toggle(
+        mut self,
+        callback: impl Fn(usize, &mut Window, &mut gpui::App) + 'static,
+    ) -> Self {
+        self.on_task_toggle = Some(TaskToggle(Rc::new(callback)));
+        self
+    }
+}
+
+/// Source line of each task list checkbox, in document order.
+pub fn task_marker_lines(source: &str) -> Vec<usize> {
+    // Normalization only replaces NUL characters, so line numbers still
+    // match the original source.
+    let source = normalize_commonmark_source(source);
+    let mut lines = Vec::new();
+    let mut line = 0;
+    let mut scanned = 0;
+    for (event, range) in Parser::new_ext(&source, extended_options()).into_offset_iter() {
+        if let Event::TaskListMarker(_) = event {
+            line += source[scanned..range.start].matches('\n').count();
+            scanned = range.start;
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+fn extended_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_GFM
+}
+
+/// Hands out checkbox indices in render order, which follows parse order.
+struct TaskCheckboxes {
+    on_toggle: Option<TaskToggle>,
+    next_index: Cell<usize>,
+}
+
+impl TaskCheckboxes {
+    fn next(&self) -> Option<(usize, TaskToggle)> {
+        let index = self.next_index.get();
+        self.next_index.set(index + 1);
+        self.on_toggle.clone().map(|on_toggle| (index, on_toggle))
     }
 }
 
@@ -165,7 +230,11 @@ impl RenderOnce for MarkdownElement {
             .try_global::<MarkdownSyntaxLoader>()
             .map(MarkdownSyntaxLoader::loader);
 
-        render_document(document, self.style, helix_theme, syntax_loader)
+        let tasks = TaskCheckboxes {
+            on_toggle: self.on_task_toggle,
+            next_index: Cell::new(0),
+        };
+        render_document(document, self.style, helix_theme, syntax_loader, &tasks)
     }
 }
 
@@ -637,12 +706,7 @@ impl MarkdownParser {
         let source = normalize_commonmark_source(source);
         let options = match mode {
             MarkdownParseMode::CommonMark => Options::empty(),
-            MarkdownParseMode::Extended => {
-                Options::ENABLE_TABLES
-                    | Options::ENABLE_STRIKETHROUGH
-                    | Options::ENABLE_TASKLISTS
-                    | Options::ENABLE_GFM
-            }
+            MarkdownParseMode::Extended => extended_options(),
         };
 
         Self {
@@ -1467,6 +1531,7 @@ fn render_document(
     style: MarkdownStyle,
     helix_theme: Option<&helix_view::Theme>,
     syntax_loader: Option<&syntax::Loader>,
+    tasks: &TaskCheckboxes,
 ) -> gpui::Div {
     let gap = block_gap(&style);
     let elements = render_blocks(
@@ -1474,6 +1539,7 @@ fn render_document(
         &style,
         helix_theme,
         syntax_loader,
+        tasks,
         "markdown",
     );
 
@@ -1501,6 +1567,7 @@ fn render_blocks(
     style: &MarkdownStyle,
     helix_theme: Option<&helix_view::Theme>,
     syntax_loader: Option<&syntax::Loader>,
+    tasks: &TaskCheckboxes,
     id_prefix: &str,
 ) -> Vec<gpui::AnyElement> {
     blocks
@@ -1582,6 +1649,7 @@ fn render_blocks(
                 style,
                 helix_theme,
                 syntax_loader,
+                tasks,
                 &format!("{id_prefix}-list-item-{block_index}"),
             )
             .into_any_element(),
@@ -1591,6 +1659,7 @@ fn render_blocks(
                 style,
                 helix_theme,
                 syntax_loader,
+                tasks,
                 &format!("{id_prefix}-block-quote-{block_index}"),
             )
             .into_any_element(),
@@ -2944,6 +3013,7 @@ fn render_list_item(
     style: &MarkdownStyle,
     helix_theme: Option<&helix_view::Theme>,
     syntax_loader: Option<&syntax::Loader>,
+    tasks: &TaskCheckboxes,
     block_id: &str,
 ) -> gpui::Div {
     let MarkdownBlock::ListItem {
@@ -2964,7 +3034,17 @@ fn render_list_item(
         px(34.0)
     };
     let marker = if let Some(checked) = checked {
-        render_task_checkbox(checked, style).into_any_element()
+        let checkbox = render_task_checkbox(checked, style);
+        match tasks.next() {
+            Some((task_index, on_toggle)) => checkbox
+                .cursor_pointer()
+                .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+                    cx.stop_propagation();
+                    (on_toggle.0)(task_index, window, cx);
+                })
+                .into_any_element(),
+            None => checkbox.into_any_element(),
+        }
     } else if ordered {
         div()
             .w(marker_width)
@@ -2987,6 +3067,7 @@ fn render_list_item(
         style,
         helix_theme,
         syntax_loader,
+        tasks,
         &format!("{block_id}-child"),
     );
     let needs_empty_placeholder =
@@ -3071,6 +3152,7 @@ fn render_block_quote(
     style: &MarkdownStyle,
     helix_theme: Option<&helix_view::Theme>,
     syntax_loader: Option<&syntax::Loader>,
+    tasks: &TaskCheckboxes,
     block_id: &str,
 ) -> impl IntoElement {
     let border_color = kind
@@ -3081,6 +3163,7 @@ fn render_block_quote(
         style,
         helix_theme,
         syntax_loader,
+        tasks,
         &format!("{block_id}-content"),
     );
     let is_empty = content.is_empty();
@@ -3300,6 +3383,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn task_marker_lines_skip_code_blocks() {
+        let source = "# Todo\n\n- [ ] write\n- [x] test\n  - [ ] nested\n\n```\n- [ ] not a task\n```\n\n> - [X] quoted\n";
+
+        assert_eq!(task_marker_lines(source), vec![2, 3, 4, 10]);
+        assert_eq!(task_marker_lines("- plain\n"), Vec::<usize>::new());
+    }
+
     #[test]
     fn parses_extended_links_task_lists_quotes_and_tables() {
         let document = MarkdownDocument::parse_extended(
@@ -4,8 +4,8 @@
 use gpui::prelude::FluentBuilder;
 use gpui::{
     Action, Anchor, App, AppContext as _, Axis, Bounds, Context, DismissEvent, Edges, Entity,
-    EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement, MouseButton,
-    MouseDownEvent, OwnedMenuItem, ParentElement, Pixels, Point, Render, ScrollHandle,
+    EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement, KeyDownEvent,
+    MouseButton, MouseDownEvent, OwnedMenuItem, ParentElement, Pixels, Point, Render, ScrollHandle,
     SharedString, Stateful, StatefulInteractiveElement, Styled, Subscription, WeakEntity, Window,
     anchored, div, px, svg,
 };
@@ -38,6 +38,7 @@ pub enum PopupMenuItem {
     Label(SharedString),
     Item {
         label: SharedString,
+        icon: Option<SharedString>,
         shortcut: Option<SharedString>,
         disabled: bool,
         checked: bool,
@@ -45,6 +46,7 @@ pub enum PopupMenuItem {
     },
     Submenu {
         label: SharedString,
+        icon: Option<SharedString>,
         disabled: bool,
         menu: Entity<PopupMenu>,
     },
@@ -54,6 +56,7 @@ impl PopupMenuItem {
     pub fn new(label: impl Into<SharedString>) -> Self {
         Self::Item {
             label: label.into(),
+            icon: None,
             shortcut: None,
             disabled: false,
             checked: false,
@@ -72,6 +75,7 @@ impl PopupMenuItem {
     pub fn submenu(label: impl Into<SharedString>, menu: Entity<PopupMenu>) -> Self {
         Self::Submenu {
             label: label.into(),
+            icon: None,
             disabled: false,
             menu,
         }
@@ -88,6 +92,20 @@ impl PopupMenuItem {
         self
     }
 
+    /// Shows the svg at `path` before the label.
+    pub fn icon(mut self, path: impl Into<SharedString>) -> Self {
+        match &mut self {
+            Self::Item {
+                icon: item_icon, ..
+            }
+            | Self::Submenu {
+                icon: item_icon, ..
+            } => *item_icon = Some(path.into()),
+            Self::Separator | Self::Label(_) => {}
+        }
+        self
+    }
+
     pub fn shortcut(mut self, shortcut: impl Into<SharedString>) -> Self {
         if let Self::Item {
             shortcut: item_shortcut,
@@ -146,6 +164,25 @@ impl PopupMenuItem {
         matches!(self, Self::Item { checked: true, .. })
     }
 
+    fn has_icon(&self) -> bool {
+        matches!(
+            self,
+            Self::Item { icon: Some(_), .. } | Self::Submenu { icon: Some(_), .. }
+        )
+    }
+
+    /// The letter that jumps to the item: the first letter or digit of its
+    /// label, lowercased.
+    fn mnemonic(&self) -> Option<char> {
+        match self {
+            Self::Item { label, .. } | Self::Submenu { label, .. } => label
+                .chars()
+                .find(|ch| ch.is_alphanumeric())
+                .map(|ch| ch.to_lowercase().next().unwrap_or(ch)),
+            Self::Separator | Self::Label(_) => None,
+        }
+    }
+
     fn has_shortcut(&self) -> bool {
         matches!(
             self,
@@ -204,6 +241,14 @@ fn shortcut_label_for_action(action: &dyn Action) -> Option<&'static str> {
         Some("Ctrl+Alt+R")
     } else if action.partial_eq(&workspace::RunFileTests) {
         Some("Ctrl+Alt+T")
+    } else if action.partial_eq(&workspace::ActivateNextTab) {
+        Some("Ctrl+Alt+Right")
+    } else if action.partial_eq(&workspace::ActivatePreviousTab) {
+        Some("Ctrl+Alt+Left")
+    } else if action.partial_eq(&workspace::ActivateLastUsedTab) {
+        Some("Ctrl+`")
+    } else if action.partial_eq(&workspace::ReopenClosedTab) {
+        Some("Ctrl+Shift+T")
     } else {
         None
     }
@@ -469,6 +514,10 @@ impl PopupMenu {
         self.menu_items.iter().any(PopupMenuItem::has_shortcut)
     }
 
+    fn has_icon_column(&self) -> bool {
+        self.menu_items.iter().any(PopupMenuItem::has_icon)
+    }
+
     pub(crate) fn active_submenu(&self) -> Option<Entity<PopupMenu>> {
         self.selected_index
             .and_then(|index| self.menu_items.get(index))
@@ -523,6 +572,55 @@ impl PopupMenu {
         self.set_selected_index(next, cx);
     }
 
+    /// Typing an item's mnemonic selects it, moving on to the next match
+    /// when several items share the letter and activating it when it is the
+    /// only one.
+    fn select_mnemonic(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let keystroke = &event.keystroke;
+        let modifiers = keystroke.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.platform || modifiers.function {
+            return;
+        }
+        let mut chars = keystroke
+            .key_char
+            .as_deref()
+            .unwrap_or(&keystroke.key)
+            .chars();
+        let (Some(typed), None) = (chars.next(), chars.next()) else {
+            return;
+        };
+        if !typed.is_alphanumeric() {
+            return;
+        }
+
+        let typed = typed.to_lowercase().next().unwrap_or(typed);
+        let matches: Vec<usize> = self
+            .clickable_indices()
+            .filter(|index| self.menu_items[*index].mnemonic() == Some(typed))
+            .collect();
+        let Some(&first) = matches.first() else {
+            return;
+        };
+        cx.stop_propagation();
+
+        if matches.len() == 1 {
+            self.set_selected_index(Some(first), cx);
+            self.confirm(&Confirm, window, cx);
+            return;
+        }
+
+        let next = self
+            .selected_index
+            .and_then(|selected| matches.iter().copied().find(|index| *index > selected))
+            .unwrap_or(first);
+        self.set_selected_index(Some(next), cx);
+    }
+
     fn select_left(&mut self, _: &SelectLeft, window: &mut Window, cx: &mut Context<Self>) {
         let handled = if matches!(self.submenu_anchor.0, Anchor::TopLeft | Anchor::BottomLeft) {
             self.unselect_submenu(cx)
@@ -745,6 +843,35 @@ impl PopupMenu {
             .when(checked, |this| this.child("✓"))
     }
 
+    fn render_icon(
+        &self,
+        icon: Option<SharedString>,
+        disabled: bool,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let tokens = cx.theme().tokens;
+        let dropdown = tokens.dropdown_tokens();
+
+        div()
+            .w(tokens.sizes.space_5)
+            .flex_shrink_0()
+            .flex()
+            .items_center()
+            .justify_center()
+            .when_some(icon, |this, icon| {
+                this.child(
+                    svg()
+                        .path(icon)
+                        .size(tokens.sizes.text_md)
+                        .text_color(if disabled {
+                            dropdown.icon_color_disabled
+                        } else {
+                            dropdown.icon_color
+                        }),
+                )
+            })
+    }
+
     fn render_shortcut(
         &self,
         shortcut: Option<SharedString>,
@@ -771,6 +898,7 @@ impl PopupMenu {
         index: usize,
         item: &PopupMenuItem,
         has_check_column: bool,
+        has_icon_column: bool,
         has_shortcut_column: bool,
         cx: &mut Context<Self>,
     ) -> gpui::AnyElement {
@@ -796,6 +924,7 @@ impl PopupMenu {
                 .into_any_element(),
             PopupMenuItem::Item {
                 label,
+                icon,
                 shortcut,
                 disabled,
                 checked,
@@ -816,6 +945,9 @@ impl PopupMenu {
                             .when(has_check_column, |this| {
                                 this.child(self.render_indicator(is_checked_left, *disabled, cx))
                             })
+                            .when(has_icon_column, |this| {
+                                this.child(self.render_icon(icon.clone(), *disabled, cx))
+                            })
                             .child(div().flex_1().min_w(px(120.0)).child(label.clone()))
                             .when(has_shortcut_column, |this| {
                                 this.child(self.render_shortcut(shortcut.clone(), *disabled, cx))
@@ -828,6 +960,7 @@ impl PopupMenu {
             }
             PopupMenuItem::Submenu {
                 label,
+                icon,
                 disabled,
                 menu,
             } => {
@@ -847,6 +980,9 @@ impl PopupMenu {
                             .when(has_check_column, |this| {
                                 this.child(self.render_indicator(false, *disabled, cx))
                             })
+                            .when(has_icon_column, |this| {
+                                this.child(self.render_icon(icon.clone(), *disabled, cx))
+                            })
                             .child(div().flex_1().child(label.clone()))
                             .child(
                                 svg()
@@ -946,6 +1082,7 @@ impl Render for PopupMenu {
             .menu_items
             .iter()
             .any(|item| self.check_side.is_left() && item.is_checked());
+        let has_icon_column = self.has_icon_column();
         let has_shortcut_column = self.has_shortcut_column();
         let min_width = popup_menu_effective_min_width(
             self.min_width,
@@ -976,6 +1113,7 @@ impl Render for PopupMenu {
             .on_action(cx.listener(Self::select_right))
             .on_action(cx.listener(Self::confirm))
             .on_action(cx.listener(Self::dismiss))
+            .on_key_down(cx.listener(Self::select_mnemonic))
             .on_mouse_down_out(cx.listener(Self::on_mouse_down_out))
             .relative()
             .occlude()
@@ -1010,6 +1148,7 @@ impl Render for PopupMenu {
                                     index,
                                     item,
                                     has_check_column,
+                                    has_icon_column,
                                     has_shortcut_column,
                                     cx,
                                 )
@@ -1023,6 +1162,9 @@ impl Render for PopupMenu {
 mod tests {
     use super::*;
 
+    use std::cell::Cell;
+    use std::rc::Rc;
+
     use gpui::{TestAppContext, point, size};
 
     use crate::{DesignTokens, Theme};
@@ -1190,4 +1332,52 @@ mod tests {
 
         assert_eq!(menu.read_with(cx, |menu, _| menu.selected_index), Some(0));
     }
+
+    #[gpui::test]
+    fn typed_mnemonics_cycle_shared_letters_and_activate_unique_ones(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            cx.set_global(Theme::from_tokens(DesignTokens::dark()));
+        });
+
+        let (root, cx) = cx.add_window_view(|window, cx| {
+            let first_focus = cx.focus_handle();
+            let second_focus = cx.focus_handle();
+            let menu = PopupMenu::build(window, cx, |menu, _, _| {
+                menu.item(PopupMenuItem::new("Copy"))
+                    .item(PopupMenuItem::new("Cut").icon("icons/square-scissors.svg"))
+                    .item(PopupMenuItem::new("Clear").disabled(true))
+                    .separator()
+                    .item(PopupMenuItem::new("Paste"))
+            });
+
+            TestRoot {
+                menu,
+                first_focus,
+                second_focus,
+            }
+        });
+
+        let menu = root.read_with(cx, |root, _| root.menu.clone());
+        let dismissed = Rc::new(Cell::new(false));
+        let _subscription = cx.update({
+            let dismissed = Rc::clone(&dismissed);
+            |_, cx| cx.subscribe(&menu, move |_, _: &DismissEvent, _| dismissed.set(true))
+        });
+        menu.update_in(cx, |menu, window, cx| menu.focus_handle.focus(window, cx));
+        cx.run_until_parked();
+
+        let selected =
+            |cx: &mut gpui::VisualTestContext| menu.read_with(cx, |menu, _| menu.selected_index);
+        cx.simulate_keystrokes("c");
+        assert_eq!(selected(cx), Some(0));
+        cx.simulate_keystrokes("c");
+        assert_eq!(selected(cx), Some(1));
+        cx.simulate_keystrokes("c");
+        assert_eq!(selected(cx), Some(0));
+        assert!(!dismissed.get());
+
+        cx.simulate_keystrokes("p");
+        assert_eq!(selected(cx), Some(4));
+        assert!(dismissed.get());
+    }
 }
@@ -0,0 +1,328 @@
+// ABOUTME: App-wide stack of open popups: completion, hover, signature help, menus and modals
+// ABOUTME: Resolves their z-order, which one owns focus, and what Escape or a click outside closes
+
+use gpui::{App, Global};
+
+/// Name a popup is registered under. Owners keep one constant per popup
+/// they show, and close their popup once the manager no longer has it open.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PopupId(&'static str);
+
+impl PopupId {
+    pub const fn new(name: &'static str) -> Self {
+        Self(name)
+    }
+
+    pub fn name(self) -> &'static str {
+        self.0
+    }
+}
+
+/// Layer a popup is drawn in, lowest first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PopupLayer {
+    Hover,
+    SignatureHelp,
+    Completion,
+    ContextMenu,
+    Modal,
+}
+
+impl PopupLayer {
+    /// Whether popups in this layer take keyboard focus. Editor popups
+    /// leave it with the editor.
+    pub fn takes_focus(self) -> bool {
+        matches!(self, Self::ContextMenu | Self::Modal)
+    }
+
+    /// Whether opening a popup in this layer closes an open one in `other`.
+    /// Modals stack on each other; everything else gives way to them.
+    pub fn displaces(self, other: PopupLayer) -> bool {
+        match self {
+            Self::Modal => other != Self::Modal,
+            Self::ContextMenu => other <= Self::ContextMenu,
+            Self::Completion => matches!(other, Self::Hover | Self::Completion),
+            Self::SignatureHelp => matches!(other, Self::Hover | Self::SignatureHelp),
+            Self::Hover => other == Self::Hover,
+        }
+    }
+
+    pub fn default_policy(self) -> DismissPolicy {
+        match self {
+            // Signature help follows the cursor, so it stays up while the
+            // user clicks around the call.
+            Self::SignatureHelp => DismissPolicy::ESCAPE,
+            Self::Hover | Self::Completion | Self::ContextMenu | Self::Modal => {
+                DismissPolicy::LIGHT
+            }
+        }
+    }
+}
+
+/// What closes a popup besides its owner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DismissPolicy {
+    pub escape: bool,
+    pub click_outside: bool,
+}
+
+impl DismissPolicy {
+    /// Escape or a click outside
+    pub const LIGHT: Self = Self {
+        escape: true,
+        click_outside: true,
+    };
+    pub const ESCAPE: Self = Self {
+        escape: true,
+        click_outside: false,
+    };
+    /// Only the owner closes it
+    pub const NONE: Self = Self {
+        escape: false,
+        click_outside: false,
+    };
+}
+
+/// Input that may dismiss open popups.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dismissal {
+    /// Escape closes the topmost popup, if its policy allows.
+    Escape,
+    /// A click closes the popups stacked above the one it landed in, or all
+    /// of them when it landed outside every popup.
+    Click { inside: Option<PopupId> },
+}
+
+#[derive(Clone, Copy, Debug)]
+struct OpenPopup {
+    id: PopupId,
+    layer: PopupLayer,
+    policy: DismissPolicy,
+}
+
+/// Open popups, bottom to top. Popups are ordered by layer, then by when
+/// they opened, so a menu always draws above a completion list and a modal
+/// above both. The topmost popup that takes focus owns it; the others must
+/// not grab focus while they render.
+#[derive(Clone, Debug, Default)]
+pub struct OverlayManager {
+    stack: Vec<OpenPopup>,
+}
+
+impl Global for OverlayManager {}
+
+impl OverlayManager {
+    /// Opens `id` with its layer's dismiss policy, returning the popups it
+    /// displaced. Reopening a popup moves it to the top of its layer.
+    pub fn open(&mut self, id: PopupId, layer: PopupLayer) -> Vec<PopupId> {
+        self.open_with_policy(id, layer, layer.default_policy())
+    }
+
+    pub fn open_with_policy(
+        &mut self,
+        id: PopupId,
+        layer: PopupLayer,
+        policy: DismissPolicy,
+    ) -> Vec<PopupId> {
+        self.stack.retain(|popup| popup.id != id);
+        let mut displaced = Vec::new();
+        self.stack.retain(|popup| {
+            let keep = !layer.displaces(popup.layer);
+            if !keep {
+                displaced.push(popup.id);
+            }
+            keep
+        });
+        let at = self.stack.partition_point(|popup| popup.layer <= layer);
+        self.stack.insert(at, OpenPopup { id, layer, policy });
+        displaced
+    }
+
+    /// Removes `id`, returning whether it was open.
+    pub fn close(&mut self, id: PopupId) -> bool {
+        let len = self.stack.len();
+        self.stack.retain(|popup| popup.id != id);
+        self.stack.len() != len
+    }
+
+    pub fn is_open(&self, id: PopupId) -> bool {
+        self.stack.iter().any(|popup| popup.id == id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Open popups, bottom first.
+    pub fn z_order(&self) -> impl Iterator<Item = PopupId> + '_ {
+        self.stack.iter().map(|popup| popup.id)
+    }
+
+    /// Position of `id` in the z-order, 0 being the bottom.
+    pub fn z_index(&self, id: PopupId) -> Option<usize> {
+        self.stack.iter().position(|popup| popup.id == id)
+    }
+
+    pub fn top(&self) -> Option<PopupId> {
+        self.stack.last().map(|popup| popup.id)
+    }
+
+    /// The topmost popup that takes focus.
+    pub fn focus_owner(&self) -> Option<PopupId> {
+        self.stack
+            .iter()
+            .rev()
+            .find(|popup| popup.layer.takes_focus())
+            .map(|popup| popup.id)
+    }
+
+    /// Whether `id` may move focus to itself.
+    pub fn may_focus(&self, id: PopupId) -> bool {
+        self.focus_owner() == Some(id)
+    }
+
+    /// Popups `dismissal` would close, top first.
+    pub fn dismissed_by(&self, dismissal: Dismissal) -> Vec<PopupId> {
+        match dismissal {
+            Dismissal::Escape => self
+                .stack
+                .last()
+                .filter(|popup| popup.policy.escape)
+                .map(|popup| vec![popup.id])
+                .unwrap_or_default(),
+            Dismissal::Click { inside } => {
+                let above = inside
+                    .and_then(|id| self.z_index(id))
+                    .map_or(0, |index| index + 1);
+                self.stack[above..]
+                    .iter()
+                    .rev()
+                    .filter(|popup| popup.policy.click_outside)
+                    .map(|popup| popup.id)
+                    .collect()
+            }
+        }
+    }
+
+    /// Closes the popups `dismissal` dismisses, returning them top first.
+    pub fn dismiss(&mut self, dismissal: Dismissal) -> Vec<PopupId> {
+        let dismissed = self.dismissed_by(dismissal);
+        self.stack.retain(|popup| !dismissed.contains(&popup.id));
+        dismissed
+    }
+
+    /// Opens `id` in the app-wide manager. Owners of the displaced popups
+    /// close them when they observe the change.
+    pub fn open_global(id: PopupId, layer: PopupLayer, cx: &mut App) -> Vec<PopupId> {
+        cx.default_global::<Self>().open(id, layer)
+    }
+
+    /// Closes `id` in the app-wide manager. Leaves the global untouched, and
+    /// its observers unnotified, when `id` was not open.
+    pub fn close_global(id: PopupId, cx: &mut App) -> bool {
+        if !Self::is_open_global(id, cx) {
+            return false;
+        }
+        cx.global_mut::<Self>().close(id)
+    }
+
+    pub fn is_open_global(id: PopupId, cx: &App) -> bool {
+        cx.try_global::<Self>()
+            .is_some_and(|manager| manager.is_open(id))
+    }
+
+    /// Whether `id` may move focus to itself. Popups the manager does not
+    /// know about never take focus from one it does.
+    pub fn may_focus_global(id: PopupId, cx: &App) -> bool {
+        cx.try_global::<Self>()
+            .is_none_or(|manager| manager.may_focus(id) || manager.focus_owner().is_none())
+    }
+
+    /// Applies `dismissal` to the app-wide manager, returning the popups it
+    /// closed.
+    pub fn dismiss_global(dismissal: Dismissal, cx: &mut App) -> Vec<PopupId> {
+        let would_dismiss = cx
+            .try_global::<Self>()
+            .is_some_and(|manager| !manager.dismissed_by(dismissal).is_empty());
+        if !would_dismiss {
+            return Vec::new();
+        }
+        cx.global_mut::<Self>().dismiss(dismissal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOVER: PopupId = PopupId::new("hover");
+    const SIGNATURE: PopupId = PopupId::new("signature-help");
+    const COMPLETION: PopupId = PopupId::new("completion");
+    const MENU: PopupId = PopupId::new("menu");
+    const OTHER_MENU: PopupId = PopupId::new("other-menu");
+    const PICKER: PopupId = PopupId::new("picker");
+    const CONFIRM: PopupId = PopupId::new("confirm");
+
+    #[test]
+    fn popups_stack_by_layer_and_displace_what_they_cover() {
+        let mut manager = OverlayManager::default();
+        assert!(
+            manager
+                .open(SIGNATURE, PopupLayer::SignatureHelp)
+                .is_empty()
+        );
+        assert!(manager.open(COMPLETION, PopupLayer::Completion).is_empty());
+        assert!(manager.open(HOVER, PopupLayer::Hover).is_empty());
+        assert_eq!(
+            manager.z_order().collect::<Vec<_>>(),
+            [HOVER, SIGNATURE, COMPLETION]
+        );
+        assert_eq!(manager.focus_owner(), None);
+
+        // A second completion list replaces the first and the hover under it.
+        assert_eq!(manager.open(COMPLETION, PopupLayer::Completion), [HOVER]);
+
+        assert_eq!(
+            manager.open(MENU, PopupLayer::ContextMenu),
+            [SIGNATURE, COMPLETION]
+        );
+        assert_eq!(manager.open(OTHER_MENU, PopupLayer::ContextMenu), [MENU]);
+        assert_eq!(manager.open(PICKER, PopupLayer::Modal), [OTHER_MENU]);
+        assert!(manager.open(CONFIRM, PopupLayer::Modal).is_empty());
+        assert_eq!(manager.focus_owner(), Some(CONFIRM));
+        assert!(!manager.may_focus(PICKER));
+
+        // Editor popups opened under a modal stay below it.
+        manager.open(COMPLETION, PopupLayer::Completion);
+        assert_eq!(manager.top(), Some(CONFIRM));
+        assert_eq!(manager.z_index(COMPLETION), Some(0));
+
+        assert!(manager.close(CONFIRM));
+        assert!(!manager.close(CONFIRM));
+        assert_eq!(manager.focus_owner(), Some(PICKER));
+    }
+
+    #[test]
+    fn dismissals_follow_each_popups_policy() {
+        let mut manager = OverlayManager::default();
+        manager.open(SIGNATURE, PopupLayer::SignatureHelp);
+        manager.open(COMPLETION, PopupLayer::Completion);
+        manager.open_with_policy(MENU, PopupLayer::ContextMenu, DismissPolicy::NONE);
+
+        assert!(manager.dismiss(Dismissal::Escape).is_empty());
+        assert!(
+            manager
+                .dismiss(Dismissal::Click { inside: Some(MENU) })
+                .is_empty()
+        );
+        assert_eq!(
+            manager.dismiss(Dismissal::Click { inside: None }),
+            [COMPLETION]
+        );
+        assert_eq!(manager.z_order().collect::<Vec<_>>(), [SIGNATURE, MENU]);
+
+        manager.close(MENU);
+        assert_eq!(manager.dismiss(Dismissal::Escape), [SIGNATURE]);
+        assert!(manager.is_empty());
+    }
+}
@@ -0,0 +1,243 @@
+// ABOUTME: Path-aware fuzzy matching for the file finder
+// ABOUTME: Scores per path segment, boosts file name matches and parses extension/directory filters
+
+/// A file finder query: `.rs` tokens filter by extension, tokens ending in
+/// `/` filter by directory, and the remaining terms are matched fuzzily.
+/// Terms containing `/` match path segments in order, so `ws/mod` finds
+/// `workspace/mod.rs`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathQuery {
+    extensions: Vec<String>,
+    directories: Vec<String>,
+    terms: Vec<String>,
+}
+
+const CHAR_SCORE: usize = 16;
+const CONSECUTIVE_BONUS: usize = 8;
+const BOUNDARY_BONUS: usize = 24;
+const PREFIX_BONUS: usize = 48;
+const EXACT_BONUS: usize = 200;
+const FILE_NAME_BONUS: usize = 300;
+const MAX_GAP_PENALTY: usize = 16;
+
+impl PathQuery {
+    pub fn parse(query: &str) -> Self {
+        let mut parsed = Self::default();
+        for token in query.split_whitespace() {
+            let token = token.to_lowercase();
+            if is_extension_filter(&token) {
+                parsed.extensions.push(token);
+            } else if token.len() > 1 && token.ends_with('/') {
+                parsed
+                    .directories
+                    .push(token.trim_start_matches("./").to_string());
+            } else {
+                parsed.terms.push(token);
+            }
+        }
+        parsed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.extensions.is_empty() && self.directories.is_empty() && self.terms.is_empty()
+    }
+
+    /// Score `path`, relative to the project root, or `None` when it is
+    /// filtered out or a term doesn't match.
+    pub fn score(&self, path: &str) -> Option<usize> {
+        let path = path.replace('\\', "/").to_lowercase();
+        let file_name = path.rsplit('/').next().unwrap_or(&path);
+
+        if !self.extensions.is_empty()
+            && !self
+                .extensions
+                .iter()
+                .any(|extension| file_name.ends_with(extension.as_str()))
+        {
+            return None;
+        }
+        if !self.directories.iter().all(|directory| {
+            path.starts_with(directory.as_str()) || path.contains(&format!("/{directory}"))
+        }) {
+            return None;
+        }
+
+        self.terms.iter().try_fold(0usize, |total, term| {
+            let score = if term.contains('/') {
+                segments_score(term, &path)?
+            } else {
+                term_score(term, &path, file_name)?
+            };
+            Some(total.saturating_add(score))
+        })
+    }
+}
+
+fn is_extension_filter(token: &str) -> bool {
+    token.len() > 1
+        && token.starts_with('.')
+        && token[1..]
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || ch == '.' || ch == '_' || ch == '-')
+}
+
+/// A term matched against the file name, boosted, or else the whole path.
+fn term_score(term: &str, path: &str, file_name: &str) -> Option<usize> {
+    let in_file_name = fuzzy_score(term, file_name)
+        .map(|score| score.saturating_mul(2).saturating_add(FILE_NAME_BONUS));
+    let in_path = fuzzy_score(term, path);
+    in_file_name.max(in_path)
+}
+
+/// Each `/`-separated part of `term` matched against a later path segment
+/// than the part before it.
+fn segments_score(term: &str, path: &str) -> Option<usize> {
+    let parts: Vec<&str> = term.split('/').filter(|part| !part.is_empty()).collect();
+    let segments: Vec<&str> = path.split('/').collect();
+    let mut next_segment = 0;
+    let mut total = 0usize;
+
+    for (part_index, part) in parts.iter().enumerate() {
+        let (segment_index, score) = segments
+            .iter()
+            .enumerate()
+            .skip(next_segment)
+            .find_map(|(index, segment)| fuzzy_score(part, segment).map(|score| (index, score)))?;
+        total = total.saturating_add(score);
+        if part_index + 1 == parts.len() && segment_index + 1 == segments.len() {
+            total = total.saturating_add(FILE_NAME_BONUS);
+        }
+        next_segment = segment_index + 1;
+    }
+    Some(total)
+}
+
+/// Subsequence match of `query` in `text`, both lowercase. Contiguous runs,
+/// matches at word boundaries and prefixes score higher; gaps cost points.
+fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    if text == query {
+        return Some(query.chars().count() * (CHAR_SCORE + CONSECUTIVE_BONUS) + EXACT_BONUS);
+    }
+
+    let text_chars: Vec<char> = text.chars().collect();
+    let query_chars: Vec<char> = query.chars().collect();
+
+    // Prefer a contiguous occurrence, earliest at a boundary.
+    let contiguous = (0..=text_chars.len().saturating_sub(query_chars.len()))
+        .filter(|&start| text_chars[start..].starts_with(&query_chars))
+        .max_by_key(|&start| (is_boundary(&text_chars, start), std::cmp::Reverse(start)));
+    if let Some(start) = contiguous {
+        let mut score = query_chars.len() * (CHAR_SCORE + CONSECUTIVE_BONUS);
+        if start == 0 {
+            score += PREFIX_BONUS;
+        } else if is_boundary(&text_chars, start) {
+            score += BOUNDARY_BONUS;
+        }
+        return Some(score.saturating_sub(start.min(MAX_GAP_PENALTY)));
+    }
+
+    let mut score = 0usize;
+    let mut previous: Option<usize> = None;
+    let mut search_from = 0;
+    for &query_char in &query_chars {
+        let index =
+            (search_from..text_chars.len()).find(|&index| text_chars[index] == query_char)?;
+        score += CHAR_SCORE;
+        if is_boundary(&text_chars, index) {
+            score += BOUNDARY_BONUS;
+        }
+        match previous {
+            Some(previous) if index == previous + 1 => score += CONSECUTIVE_BONUS,
+            Some(previous) => score = score.saturating_sub((index - previous).min(MAX_GAP_PENALTY)),
+            None => score = score.saturating_sub(index.min(MAX_GAP_PENALTY)),
+        }
+        previous = Some(index);
+        search_from = index + 1;
+    }
+    Some(score)
+}
+
+fn is_boundary(text: &[char], index: usize) -> bool {
+    index == 0 || matches!(text[index - 1], '/' | '_' | '-' | '.' | ' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rank<'a>(query: &str, paths: &[&'a str]) -> Vec<&'a str> {
+        let query = PathQuery::parse(query);
+        let mut scored: Vec<_> = paths
+            .iter()
+            .filter_map(|path| query.score(path).map(|score| (*path, score)))
+            .collect();
+        scored.sort_by(|(a_path, a), (b_path, b)| b.cmp(a).then_with(|| a_path.cmp(b_path)));
+        scored.into_iter().map(|(path, _)| path).collect()
+    }
+
+    #[test]
+    fn parses_filters_and_terms() {
+        let query = PathQuery::parse(".rs src/ Picker ./crates/");
+        assert_eq!(query.extensions, vec![".rs"]);
+        assert_eq!(query.directories, vec!["src/", "crates/"]);
+        assert_eq!(query.terms, vec!["picker"]);
+        assert!(PathQuery::parse("   ").is_empty());
+        assert_eq!(PathQuery::parse(".").terms, vec!["."]);
+    }
+
+    #[test]
+    fn slash_terms_match_path_segments_in_order() {
+        let paths = [
+            "crates/nucleotide/src/workspace/mod.rs",
+            "crates/nucleotide/src/workspace/tab_switcher.rs",
+            "crates/nucleotide/src/overlay.rs",
+            "docs/modules.md",
+        ];
+        assert_eq!(
+            rank("ws/mod", &paths),
+            vec!["crates/nucleotide/src/workspace/mod.rs"]
+        );
+        assert_eq!(rank("mod/ws", &paths), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn file_name_matches_rank_above_directory_matches() {
+        let paths = ["src/picker/mod.rs", "src/picker_view.rs", "picker.rs"];
+        assert_eq!(
+            rank("picker", &paths),
+            vec!["picker.rs", "src/picker_view.rs", "src/picker/mod.rs"]
+        );
+    }
+
+    #[test]
+    fn extension_and_directory_filters_narrow_results() {
+        let paths = [
+            "src/main.rs",
+            "src/main.ts",
+            "tests/main.rs",
+            "crates/app/src/lib.rs",
+        ];
+        assert_eq!(
+            rank(".rs main", &paths),
+            vec!["src/main.rs", "tests/main.rs"]
+        );
+        assert_eq!(
+            rank("src/ .rs", &paths),
+            vec!["crates/app/src/lib.rs", "src/main.rs"]
+        );
+        assert_eq!(
+            rank(".ts .rs src/ main", &paths),
+            vec!["src/main.rs", "src/main.ts"]
+        );
+    }
+
+    #[test]
+    fn every_term_has_to_match() {
+        let paths = ["src/config/theme.rs", "src/theme_manager.rs"];
+        assert_eq!(rank("theme config", &paths), vec!["src/config/theme.rs"]);
+        assert_eq!(rank("theme xyz", &paths), Vec::<&str>::new());
+    }
+}
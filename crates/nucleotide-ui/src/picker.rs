@@ -5,6 +5,7 @@ use gpui::{
 };
 use std::sync::Arc;
 
+use crate::picker_source::{PickerItemSink, PickerSource};
 use crate::picker_view::{PickerItem, PickerView};
 
 pub type PickerPreviewText = (String, Option<std::path::PathBuf>);
@@ -24,6 +25,10 @@ pub type PickerPreviewTextTaskProvider = Arc<
         + Sync,
 >;
 
+/// Receives each query typed into a picker that supplies its own matches.
+pub type PickerQueryChangeHandler =
+    Arc<dyn Fn(SharedString, gpui::WeakEntity<PickerView>, &mut App) + Send + Sync>;
+
 #[derive(Clone)]
 pub enum Picker {
     Native {
@@ -33,6 +38,9 @@ pub enum Picker {
         show_preview: bool,
         preview_text_provider: Option<PickerPreviewTextProvider>,
         preview_text_task_provider: Option<PickerPreviewTextTaskProvider>,
+        on_query_change: Option<PickerQueryChangeHandler>,
+        /// Source supplying the items and confirming the chosen one
+        source: Option<Arc<dyn PickerSource>>,
     },
 }
 
@@ -50,7 +58,33 @@ impl Picker {
             show_preview: false,
             preview_text_provider: None,
             preview_text_task_provider: None,
+            on_query_change: None,
+            source: None,
+        }
+    }
+
+    /// Picker filled and confirmed by `source`.
+    pub fn from_source(source: Arc<dyn PickerSource>) -> Self {
+        let mut picker =
+            Self::native(source.title(), Vec::new(), |_| {}).with_preview(source.show_preview());
+        if source.show_preview() {
+            let preview_source = source.clone();
+            picker = picker
+                .with_preview_text_provider_fn(move |item, cx| preview_source.preview(item, cx));
+        }
+        if source.searches_as_you_type() {
+            let search_source = source.clone();
+            picker = picker.with_query_change_fn(move |query, picker, cx| {
+                search_source.search(query, PickerItemSink::new(picker), cx);
+            });
+        }
+        match &mut picker {
+            Picker::Native {
+                source: picker_source,
+                ..
+            } => *picker_source = Some(source),
         }
+        picker
     }
 
     /// Configure whether this picker should render the preview pane.
@@ -104,6 +138,21 @@ impl Picker {
         self
     }
 
+    /// Search as the user types: `f` receives every query along with the
+    /// picker, and fills it through `PickerView::set_items` and
+    /// `PickerView::extend_items` instead of fuzzy filtering `items`.
+    pub fn with_query_change_fn(
+        mut self,
+        f: impl Fn(SharedString, gpui::WeakEntity<PickerView>, &mut App) + Send + Sync + 'static,
+    ) -> Self {
+        match &mut self {
+            Picker::Native {
+                on_query_change, ..
+            } => *on_query_change = Some(Arc::new(f)),
+        }
+        self
+    }
+
     /// Create a native directory picker
     pub fn native_directory(
         title: impl Into<SharedString>,
@@ -121,6 +170,8 @@ impl Picker {
             show_preview: false,
             preview_text_provider: None,
             preview_text_task_provider: None,
+            on_query_change: None,
+            source: None,
         }
     }
 }
@@ -343,4 +394,61 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn native_picker_can_store_query_change_handler() {
+        let picker = Picker::native("Live Grep", Vec::new(), |_| {});
+        match &picker {
+            Picker::Native {
+                on_query_change, ..
+            } => assert!(on_query_change.is_none()),
+        }
+
+        let picker = picker.with_query_change_fn(|_query, _picker, _cx| {});
+        match picker {
+            Picker::Native {
+                on_query_change, ..
+            } => assert!(on_query_change.is_some()),
+        }
+    }
+
+    #[test]
+    fn source_picker_takes_the_title_preview_and_search_of_its_source() {
+        struct Symbols;
+
+        impl PickerSource for Symbols {
+            fn title(&self) -> SharedString {
+                "Symbols".into()
+            }
+
+            fn items(&self, _sink: PickerItemSink, _cx: &mut App) {}
+
+            fn searches_as_you_type(&self) -> bool {
+                true
+            }
+
+            fn show_preview(&self) -> bool {
+                true
+            }
+
+            fn confirm(&self, _item: &PickerItem, _cx: &mut App) {}
+        }
+
+        match Picker::from_source(Arc::new(Symbols)) {
+            Picker::Native {
+                title,
+                show_preview,
+                preview_text_provider,
+                on_query_change,
+                source,
+                ..
+            } => {
+                assert_eq!(title.as_ref(), "Symbols");
+                assert!(show_preview);
+                assert!(preview_text_provider.is_some());
+                assert!(on_query_change.is_some());
+                assert!(source.is_some());
+            }
+        }
+    }
 }
@@ -0,0 +1,158 @@
+// ABOUTME: PickerSource trait for pickers that supply their own items, previews and confirm action
+// ABOUTME: Sources are registered by name and opened as native pickers without bespoke overlay wiring
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use gpui::{App, AppContext, Global, SharedString, WeakEntity};
+
+use crate::picker::PickerPreviewText;
+use crate::picker_view::{PickerItem, PickerView};
+
+/// Supplies a picker's items and acts on the chosen one. Implement it and
+/// open the source with `Picker::from_source`, or register it by name in
+/// [`PickerSources`].
+///
+/// `items` and `confirm` run deferred, outside the update that opened or
+/// closed the picker, so sources may update workspace entities directly.
+pub trait PickerSource: Send + Sync + 'static {
+    /// Title shown above the picker.
+    fn title(&self) -> SharedString;
+
+    /// Fills the picker once it is open. Sources with a fixed list set it
+    /// right away; slower ones stream batches in from a task.
+    fn items(&self, sink: PickerItemSink, cx: &mut App);
+
+    /// Whether each query goes to [`Self::search`] instead of fuzzy
+    /// filtering the items.
+    fn searches_as_you_type(&self) -> bool {
+        false
+    }
+
+    /// Refills the picker for `query`, for sources that search as the user
+    /// types.
+    fn search(&self, _query: SharedString, _sink: PickerItemSink, _cx: &mut App) {}
+
+    /// Whether the picker shows a preview pane.
+    fn show_preview(&self) -> bool {
+        false
+    }
+
+    /// Preview text of `item`, and the path it was read from.
+    fn preview(&self, _item: &PickerItem, _cx: &mut App) -> Option<PickerPreviewText> {
+        None
+    }
+
+    /// Acts on the chosen item, after the picker has closed.
+    fn confirm(&self, item: &PickerItem, cx: &mut App);
+}
+
+/// Handle a source fills an open picker through. Calls return false once
+/// the picker has closed, so streaming sources know to stop.
+#[derive(Clone)]
+pub struct PickerItemSink {
+    picker: WeakEntity<PickerView>,
+}
+
+impl PickerItemSink {
+    pub fn new(picker: WeakEntity<PickerView>) -> Self {
+        Self { picker }
+    }
+
+    /// Replaces the items, selecting the first one.
+    pub fn set(&self, items: Vec<PickerItem>, cx: &mut impl AppContext) -> bool {
+        self.picker
+            .update(cx, |picker, cx| picker.set_items(items, cx))
+            .is_ok()
+    }
+
+    /// Appends a batch without moving the selection.
+    pub fn extend(&self, items: Vec<PickerItem>, cx: &mut impl AppContext) -> bool {
+        self.picker
+            .update(cx, |picker, cx| picker.extend_items(items, cx))
+            .is_ok()
+    }
+
+    pub fn set_title(&self, title: impl Into<SharedString>, cx: &mut impl AppContext) -> bool {
+        let title = title.into();
+        self.picker
+            .update(cx, |picker, cx| picker.set_title(title, cx))
+            .is_ok()
+    }
+}
+
+/// Picker sources by name. The app-wide registry is a global; workspaces
+/// keep their own for sources tied to their state.
+#[derive(Clone, Default)]
+pub struct PickerSources {
+    sources: BTreeMap<SharedString, Arc<dyn PickerSource>>,
+}
+
+impl Global for PickerSources {}
+
+impl PickerSources {
+    /// Adds `source` under `name`, replacing a source registered before.
+    pub fn register(&mut self, name: impl Into<SharedString>, source: Arc<dyn PickerSource>) {
+        self.sources.insert(name.into(), source);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn PickerSource>> {
+        self.sources.get(name).cloned()
+    }
+
+    /// Registered names, in order.
+    pub fn names(&self) -> impl Iterator<Item = &SharedString> {
+        self.sources.keys()
+    }
+
+    /// Registers `source` in the app-wide registry.
+    pub fn register_global(
+        name: impl Into<SharedString>,
+        source: Arc<dyn PickerSource>,
+        cx: &mut App,
+    ) {
+        cx.default_global::<Self>().register(name, source);
+    }
+}
+
+impl std::fmt::Debug for PickerSources {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.sources.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(&'static str);
+
+    impl PickerSource for Fixed {
+        fn title(&self) -> SharedString {
+            self.0.into()
+        }
+
+        fn items(&self, _sink: PickerItemSink, _cx: &mut App) {}
+
+        fn confirm(&self, _item: &PickerItem, _cx: &mut App) {}
+    }
+
+    #[test]
+    fn sources_are_found_by_name_and_replaced() {
+        let mut sources = PickerSources::default();
+        sources.register("themes", Arc::new(Fixed("Themes")));
+        sources.register("branches", Arc::new(Fixed("Branches")));
+        sources.register("themes", Arc::new(Fixed("Color Themes")));
+
+        let names = sources
+            .names()
+            .map(|name| name.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["branches", "themes"]);
+        assert_eq!(
+            sources.get("themes").unwrap().title().as_ref(),
+            "Color Themes"
+        );
+        assert!(sources.get("symbols").is_none());
+    }
+}
@@ -59,6 +59,13 @@ pub enum ColumnData {
         code: Option<String>,
         tags: Vec<helix_core::diagnostic::DiagnosticTag>,
     },
+    /// A line matched by a text search, with the byte ranges of the matches
+    /// within `line_text` to highlight.
+    SearchMatch {
+        location: String,
+        line_text: String,
+        match_ranges: Vec<Range<usize>>,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -125,6 +132,29 @@ impl PickerItem {
         }
     }
 
+    /// Create a new PickerItem for a text search match at `location`
+    pub fn with_search_match(
+        location: impl Into<String>,
+        line_text: impl Into<String>,
+        match_ranges: Vec<Range<usize>>,
+        file_path: std::path::PathBuf,
+        data: Arc<dyn std::any::Any + Send + Sync>,
+    ) -> Self {
+        let location = location.into();
+        Self {
+            label: location.clone().into(),
+            sublabel: None,
+            data,
+            file_path: Some(file_path),
+            vcs_status: None,
+            columns: Some(ColumnData::SearchMatch {
+                location,
+                line_text: line_text.into(),
+                match_ranges,
+            }),
+        }
+    }
+
     /// Create a new PickerItem with buffer columns for table display
     pub fn with_buffer_columns(
         id: impl Into<String>,
@@ -151,6 +181,7 @@ impl PickerItem {
 // Type aliases for callbacks
 type PickerSelectCallback = Box<dyn FnMut(&PickerItem, &mut Context<PickerView>) + 'static>;
 type PickerCancelCallback = Box<dyn FnMut(&mut Context<PickerView>) + 'static>;
+type PickerQueryChangedCallback = Box<dyn Fn(SharedString, &mut Context<PickerView>) + 'static>;
 
 fn str_prefix_at_byte_limit(value: &str, max_bytes: usize) -> &str {
     let limit = max_bytes.min(value.len());
@@ -241,6 +272,16 @@ pub struct PickerView {
     initial_preview_loaded: bool,
     preview_task: Option<Task<()>>,
 
+    // Optional extra ranking score per item, e.g. from usage frecency
+    rank_boost_cb: Option<Box<dyn Fn(&PickerItem) -> usize>>,
+    // Position among recently used items, listed first for an empty query
+    recent_rank_cb: Option<Box<dyn Fn(&PickerItem) -> Option<usize>>>,
+
+    // When set, the owner matches queries and supplies the items
+    query_changed_cb: Option<PickerQueryChangedCallback>,
+    // Match labels as project-relative paths, see `PathQuery`
+    path_matching: bool,
+
     // Callbacks
     on_select: Option<PickerSelectCallback>,
     on_cancel: Option<PickerCancelCallback>,
@@ -380,6 +421,10 @@ impl PickerView {
             preview_text_task_provider_cb: None,
             initial_preview_loaded: false,
             preview_task: None,
+            rank_boost_cb: None,
+            recent_rank_cb: None,
+            query_changed_cb: None,
+            path_matching: false,
             on_select: None,
             on_cancel: None,
             style: PickerStyle::default(),
@@ -425,6 +470,10 @@ impl PickerView {
             preview_text_task_provider_cb: None,
             initial_preview_loaded: false,
             preview_task: None,
+            rank_boost_cb: None,
+            recent_rank_cb: None,
+            query_changed_cb: None,
+            path_matching: false,
             on_select: None,
             on_cancel: None,
             style,
@@ -504,6 +553,45 @@ impl PickerView {
         self
     }
 
+    /// Add a per-item score to matches. With an empty query items are
+    /// ordered by this score alone; ties keep their original order.
+    pub fn with_rank_boost_fn(mut self, f: impl Fn(&PickerItem) -> usize + 'static) -> Self {
+        self.rank_boost_cb = Some(Box::new(f));
+        self.refilter();
+        self
+    }
+
+    /// List items `f` ranks, lowest first, ahead of the others while the
+    /// query is empty. Typed queries rank as usual.
+    pub fn with_recent_rank_fn(
+        mut self,
+        f: impl Fn(&PickerItem) -> Option<usize> + 'static,
+    ) -> Self {
+        self.recent_rank_cb = Some(Box::new(f));
+        self.refilter();
+        self
+    }
+
+    /// Hand query changes to `f` instead of fuzzy filtering the items. The
+    /// owner supplies the matches with [`Self::set_items`] and
+    /// [`Self::extend_items`], which are shown in the order given.
+    pub fn with_query_changed_fn(
+        mut self,
+        f: impl Fn(SharedString, &mut Context<Self>) + 'static,
+    ) -> Self {
+        self.query_changed_cb = Some(Box::new(f));
+        self.refilter();
+        self
+    }
+
+    /// Match item labels as paths: per-segment scoring, file name boosts and
+    /// `.ext` / `dir/` filters in the query.
+    pub fn with_path_matching(mut self) -> Self {
+        self.path_matching = true;
+        self.refilter();
+        self
+    }
+
     pub fn with_items(mut self, items: Vec<PickerItem>) -> Self {
         self.items = items;
         // Reasonable assumption: pickers won't have more than u32::MAX items
@@ -528,6 +616,37 @@ impl PickerView {
         self
     }
 
+    /// Replace the items, keeping the query, and select the first one.
+    pub fn set_items(&mut self, items: Vec<PickerItem>, cx: &mut Context<Self>) {
+        self.items = items;
+        self.matcher = None;
+        self.refilter();
+        self.selected_index = 0;
+        self.scroll_to_first_item();
+        self.load_preview_for_selected_item(cx);
+        cx.notify();
+    }
+
+    /// Append streamed items without moving the selection.
+    pub fn extend_items(&mut self, items: Vec<PickerItem>, cx: &mut Context<Self>) {
+        if items.is_empty() {
+            return;
+        }
+        let had_matches = !self.filtered_indices.is_empty();
+        self.items.extend(items);
+        self.matcher = None;
+        self.refilter();
+        if !had_matches {
+            self.load_preview_for_selected_item(cx);
+        }
+        cx.notify();
+    }
+
+    pub fn set_title(&mut self, title: impl Into<SharedString>, cx: &mut Context<Self>) {
+        self.title = Some(title.into());
+        cx.notify();
+    }
+
     pub fn with_style(mut self, style: PickerStyle) -> Self {
         self.style = style;
         self
@@ -582,6 +701,11 @@ impl PickerView {
 
     fn apply_query(&mut self, query: SharedString, cx: &mut Context<Self>) {
         self.query = query;
+        if let Some(query_changed) = &self.query_changed_cb {
+            query_changed(self.query.clone(), cx);
+            cx.notify();
+            return;
+        }
         self.filter_items(cx);
         self.selected_index = 0;
         // Scroll to top when query changes
@@ -591,7 +715,26 @@ impl PickerView {
     }
 
     fn filter_items(&mut self, _cx: &mut Context<Self>) {
+        self.refilter();
+    }
+
+    fn refilter(&mut self) {
+        if self.query_changed_cb.is_some() {
+            let item_count = u32::try_from(self.items.len()).unwrap_or(u32::MAX);
+            self.filtered_indices = (0..item_count).collect();
+            self.rebuild_diagnostic_display_rows();
+            return;
+        }
+
         let query = self.query.to_string();
+        let path_query = self
+            .path_matching
+            .then(|| crate::path_match::PathQuery::parse(&query));
+        let boost = |idx: usize| {
+            self.rank_boost_cb
+                .as_ref()
+                .map_or(0, |boost| boost(&self.items[idx]))
+        };
         let mut scored_items = self
             .items
             .iter()
@@ -606,15 +749,31 @@ impl PickerView {
             })
             .filter_map(|(idx, item)| {
                 if query.is_empty() {
-                    Some((idx, usize::MAX))
+                    Some((idx, boost(idx)))
+                } else if let Some(path_query) = &path_query {
+                    path_query
+                        .score(&item.label)
+                        .map(|score| (idx, score.saturating_add(boost(idx))))
                 } else {
                     let search_text = Self::item_search_text(item);
-                    Self::fuzzy_score(&query, &search_text).map(|score| (idx, score))
+                    Self::fuzzy_score(&query, &search_text)
+                        .map(|score| (idx, score.saturating_add(boost(idx))))
                 }
             })
             .collect::<Vec<_>>();
 
-        if !query.is_empty() {
+        if query.is_empty() {
+            if let Some(recent_rank) = &self.recent_rank_cb {
+                scored_items.sort_by_key(|(idx, score)| {
+                    (
+                        recent_rank(&self.items[*idx]).unwrap_or(usize::MAX),
+                        std::cmp::Reverse(*score),
+                    )
+                });
+            } else if self.rank_boost_cb.is_some() {
+                scored_items.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+            }
+        } else {
             scored_items.sort_by(|(a_idx, a_score), (b_idx, b_score)| {
                 b_score.cmp(a_score).then_with(|| {
                     self.items[*a_idx]
@@ -785,6 +944,10 @@ impl PickerView {
                         text.push_str(Self::diagnostic_tag_label(tag));
                     }
                 }
+                ColumnData::SearchMatch { line_text, .. } => {
+                    text.push(' ');
+                    text.push_str(line_text);
+                }
             }
         }
 
@@ -1416,6 +1579,70 @@ impl PickerView {
             .into_any_element()
     }
 
+    fn render_search_match_item(
+        &self,
+        item: &PickerItem,
+        cx: &mut Context<Self>,
+    ) -> gpui::AnyElement {
+        let Some(ColumnData::SearchMatch {
+            location,
+            line_text,
+            match_ranges,
+        }) = item.columns.as_ref()
+        else {
+            return div().into_any_element();
+        };
+
+        let tokens = cx.global::<crate::Theme>().tokens;
+        let highlight = gpui::HighlightStyle {
+            font_weight: Some(gpui::FontWeight::BOLD),
+            background_color: Some(tokens.editor.selection_secondary),
+            ..Default::default()
+        };
+        // Leading indentation only pushes the match out of view.
+        let trimmed = line_text.trim_start();
+        let offset = line_text.len() - trimmed.len();
+        let highlights = match_ranges
+            .iter()
+            .filter(|range| range.end <= line_text.len() && range.start >= offset)
+            .filter(|range| {
+                line_text.is_char_boundary(range.start) && line_text.is_char_boundary(range.end)
+            })
+            .map(|range| (range.start - offset..range.end - offset, highlight))
+            .collect::<Vec<_>>();
+
+        div()
+            .flex()
+            .flex_1()
+            .w_full()
+            .min_w(px(0.0))
+            .items_center()
+            .gap_3()
+            .font_family(Self::ui_font_family(cx))
+            .child(
+                div()
+                    .flex_shrink_0()
+                    .max_w(px(320.0))
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .text_size(tokens.sizes.text_sm)
+                    .text_color(self.style.modal_style.prompt_text)
+                    .child(location.clone()),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w(px(0.0))
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .child(
+                        gpui::StyledText::new(SharedString::from(trimmed.to_string()))
+                            .with_highlights(highlights),
+                    ),
+            )
+            .into_any_element()
+    }
+
     fn render_diagnostic_item(
         &self,
         item: &PickerItem,
@@ -1988,6 +2215,8 @@ impl PickerView {
                                                                             is_selected,
                                                                             cx,
                                                                         ),
+                                                                    Some(ColumnData::SearchMatch { .. }) => picker
+                                                                        .render_search_match_item(item, cx),
                                                                     None => {
                                                                         // File picker or other non-buffer items
                                                                         div()
@@ -2267,6 +2496,110 @@ mod tests {
         assert!(search_text.contains("Unnecessary"));
     }
 
+    #[gpui::test]
+    fn rank_boost_orders_empty_and_tied_queries(cx: &mut TestAppContext) {
+        let items = ["src/a.rs", "src/b.rs", "src/c.rs"]
+            .into_iter()
+            .map(|path| PickerItem::from_file_path(path, path.into(), Arc::new(())))
+            .collect();
+        let picker = cx.new(|cx| {
+            PickerView::new(cx)
+                .with_items(items)
+                .with_rank_boost_fn(|item| match item.label.as_ref() {
+                    "src/c.rs" => 50,
+                    _ => 0,
+                })
+        });
+
+        picker.update(cx, |picker, _| {
+            assert_eq!(picker.filtered_indices, vec![2, 0, 1]);
+
+            picker.query = "src".into();
+            picker.refilter();
+            assert_eq!(picker.filtered_indices, vec![2, 0, 1]);
+
+            picker.query = "a.rs".into();
+            picker.refilter();
+            assert_eq!(picker.filtered_indices, vec![0]);
+        });
+    }
+
+    #[gpui::test]
+    fn recent_items_lead_an_empty_query(cx: &mut TestAppContext) {
+        let items = ["src/a.rs", "src/b.rs", "src/c.rs", "src/d.rs"]
+            .into_iter()
+            .map(|path| PickerItem::from_file_path(path, path.into(), Arc::new(())))
+            .collect();
+        let picker = cx.new(|cx| {
+            PickerView::new(cx)
+                .with_items(items)
+                .with_rank_boost_fn(|item| usize::from(item.label.as_ref() == "src/b.rs"))
+                .with_recent_rank_fn(|item| match item.label.as_ref() {
+                    "src/d.rs" => Some(0),
+                    "src/c.rs" => Some(1),
+                    _ => None,
+                })
+        });
+
+        picker.update(cx, |picker, _| {
+            assert_eq!(picker.filtered_indices, vec![3, 2, 1, 0]);
+
+            picker.query = "a".into();
+            picker.refilter();
+            assert_eq!(picker.filtered_indices, vec![0]);
+        });
+    }
+
+    #[gpui::test]
+    fn query_changed_fn_replaces_fuzzy_filtering(cx: &mut TestAppContext) {
+        let queries = std::rc::Rc::new(std::cell::RefCell::new(Vec::<String>::new()));
+        let recorded = queries.clone();
+        let item = |path: &str| PickerItem::from_file_path(path, path.into(), Arc::new(()));
+        let picker = cx.new(|cx| {
+            PickerView::new(cx)
+                .with_items(vec![item("src/b.rs"), item("src/a.rs")])
+                .with_query_changed_fn(move |query, _cx| {
+                    recorded.borrow_mut().push(query.to_string());
+                })
+        });
+
+        picker.update(cx, |picker, cx| {
+            picker.set_query("zzz", cx);
+            assert_eq!(picker.filtered_indices, vec![0, 1]);
+
+            picker.extend_items(vec![item("src/c.rs")], cx);
+            assert_eq!(picker.filtered_indices, vec![0, 1, 2]);
+
+            picker.set_items(vec![item("src/d.rs")], cx);
+            assert_eq!(picker.filtered_indices, vec![0]);
+            assert_eq!(picker.query.as_ref(), "zzz");
+        });
+        assert_eq!(*queries.borrow(), vec!["zzz".to_string()]);
+    }
+
+    #[gpui::test]
+    fn path_matching_ranks_file_names_and_applies_filters(cx: &mut TestAppContext) {
+        let items = ["src/picker/mod.rs", "src/picker_view.rs", "docs/picker.md"]
+            .into_iter()
+            .map(|path| PickerItem::from_file_path(path, path.into(), Arc::new(())))
+            .collect();
+        let picker = cx.new(|cx| PickerView::new(cx).with_items(items).with_path_matching());
+
+        picker.update(cx, |picker, _| {
+            picker.query = "picker".into();
+            picker.refilter();
+            assert_eq!(picker.filtered_indices, vec![2, 1, 0]);
+
+            picker.query = ".rs picker".into();
+            picker.refilter();
+            assert_eq!(picker.filtered_indices, vec![1, 0]);
+
+            picker.query = "pi/mod".into();
+            picker.refilter();
+            assert_eq!(picker.filtered_indices, vec![0]);
+        });
+    }
+
     #[test]
     fn fuzzy_score_searches_ordered_characters_and_exact_substrings() {
         let exact = PickerView::fuzzy_score("write", "write Save file").expect("exact score");
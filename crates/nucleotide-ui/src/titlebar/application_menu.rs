@@ -111,6 +111,13 @@ impl ApplicationMenu {
 
         if !was_open && index.is_some() {
             self.action_context = window.focused(cx);
+            // Menus are rebuilt as workspace state changes; show the current ones.
+            self.entries = cx
+                .get_menus()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|menu| MenuEntry { menu })
+                .collect();
         }
 
         if self.open_index != index {
@@ -251,7 +251,8 @@ impl RenderOnce for LinuxWindowControl {
                     }
                     LinuxControlType::Close => {
                         debug!("Close button clicked");
-                        cx.quit();
+                        // Quit through the workspace so background services shut down first
+                        window.dispatch_action(Box::new(crate::actions::editor::Quit), cx);
                     }
                 }
             })
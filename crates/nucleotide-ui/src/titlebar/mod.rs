@@ -28,12 +28,18 @@ pub use linux_titlebar::LinuxTitlebar;
 #[cfg(target_os = "linux")]
 pub use linux_window_controls::LinuxWindowControls;
 
+#[cfg(target_os = "windows")]
+use gpui::WindowControlArea;
 use gpui::{
-    AnyView, AppContext, Context, Entity, Hsla, IntoElement, ParentElement, Pixels, Render, Styled,
+    Action, AnyElement, AnyView, AppContext, Context, Entity, Hsla, InteractiveElement,
+    IntoElement, ParentElement, Pixels, Render, SharedString, StatefulInteractiveElement, Styled,
     Window, div, px,
 };
-#[cfg(target_os = "windows")]
-use gpui::{InteractiveElement, WindowControlArea};
+
+use crate::actions::workspace::{
+    RevealActiveFileInTree, ShowLayoutMenu, ToggleFileTree, ToggleTerminal,
+};
+use crate::{Button, ButtonSize, ButtonVariant, Tooltipped};
 
 #[cfg(target_os = "windows")]
 const WINDOWS_CONTROL_BUTTON_SIZE: f32 = 46.0;
@@ -50,6 +56,61 @@ const WINDOWS_UI_FONT_FAMILY: &str = "Segoe UI Variable";
 
 const TITLEBAR_ACTION_LANE_WIDTH: f32 = 32.0;
 const TITLEBAR_ACTION_RIGHT_INSET: f32 = 8.0;
+const TITLEBAR_QUICK_ACTION_WIDTH: f32 = 28.0;
+/// Room left of the title for the native traffic lights.
+const MAC_TRAFFIC_LIGHT_INSET: f32 = 82.0;
+const TITLEBAR_QUICK_ACTION_COUNT: usize = 3;
+
+/// Project and document shown in the title bar.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TitleBarDocument {
+    pub project: Option<SharedString>,
+    /// Path of the focused document, relative to the project when inside it
+    pub path: Option<SharedString>,
+    pub dirty: bool,
+}
+
+/// Which parts of the title bar fit in the width it is given. The quick
+/// actions go first, then the project name; the path is ellipsized last.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct TitleBarOverflow {
+    show_project: bool,
+    show_quick_actions: bool,
+}
+
+impl TitleBarOverflow {
+    fn for_width(width: f32) -> Self {
+        Self {
+            show_project: width >= 420.0,
+            show_quick_actions: width >= 560.0,
+        }
+    }
+}
+
+/// Id, icon, tooltip and action of each quick action button.
+fn quick_actions()
+-> [(&'static str, &'static str, &'static str, Box<dyn Action>); TITLEBAR_QUICK_ACTION_COUNT] {
+    [
+        (
+            "titlebar-toggle-file-tree",
+            "icons/folder-tree.svg",
+            "Toggle File Tree",
+            Box::new(ToggleFileTree),
+        ),
+        (
+            "titlebar-toggle-terminal",
+            "icons/terminal.svg",
+            "Toggle Terminal",
+            Box::new(ToggleTerminal),
+        ),
+        (
+            "titlebar-layout",
+            "icons/columns-2.svg",
+            "Change Layout",
+            Box::new(ShowLayoutMenu),
+        ),
+    ]
+}
 
 #[cfg(target_os = "windows")]
 fn windows_caption_controls_width() -> f32 {
@@ -67,6 +128,7 @@ fn windows_titlebar_content_width(viewport_width: f32) -> f32 {
 pub struct TitleBar {
     platform_titlebar: Entity<PlatformTitleBar>,
     filename: String,
+    document: TitleBarDocument,
     leading_sidebar_background: Option<platform_titlebar::TitleBarLeadingSidebarBackground>,
     trailing_view: Option<AnyView>,
     #[cfg(not(target_os = "macos"))]
@@ -93,6 +155,7 @@ impl TitleBar {
         Self {
             platform_titlebar,
             filename: "Nucleotide".to_string(),
+            document: TitleBarDocument::default(),
             leading_sidebar_background: None,
             trailing_view: None,
             #[cfg(not(target_os = "macos"))]
@@ -108,6 +171,14 @@ impl TitleBar {
         true
     }
 
+    pub fn set_document(&mut self, document: TitleBarDocument) -> bool {
+        if self.document == document {
+            return false;
+        }
+        self.document = document;
+        true
+    }
+
     pub fn set_leading_sidebar_background(
         &mut self,
         width: Pixels,
@@ -149,22 +220,176 @@ impl TitleBar {
     pub fn height(window: &Window, cx: &gpui::App) -> gpui::Pixels {
         PlatformTitleBar::height(window, cx)
     }
+
+    fn has_document_info(&self) -> bool {
+        self.document.project.is_some() || self.document.path.is_some()
+    }
+
+    /// Project name, clickable path and dirty indicator, or `None` when
+    /// there is nothing to show beyond the application name.
+    fn render_document_info(
+        &self,
+        overflow: TitleBarOverflow,
+        cx: &gpui::App,
+    ) -> Option<AnyElement> {
+        if !self.has_document_info() {
+            return None;
+        }
+        let tokens = cx.global::<crate::Theme>().tokens;
+        let titlebar_tokens = tokens.titlebar_tokens();
+        let project = self
+            .document
+            .project
+            .clone()
+            .filter(|_| overflow.show_project);
+
+        Some(
+            div()
+                .flex()
+                .flex_row()
+                .items_center()
+                .gap_1()
+                .min_w_0()
+                .overflow_hidden()
+                .whitespace_nowrap()
+                .text_size(tokens.sizes.text_md)
+                .when_some(project, |info, project| {
+                    info.child(
+                        div()
+                            .flex_none()
+                            .font_weight(gpui::FontWeight::MEDIUM)
+                            .text_color(titlebar_tokens.foreground)
+                            .child(project),
+                    )
+                    .when(self.document.path.is_some(), |info| {
+                        info.child(
+                            div()
+                                .flex_none()
+                                .text_color(tokens.chrome.text_chrome_secondary)
+                                .child("/"),
+                        )
+                    })
+                })
+                .when_some(self.document.path.clone(), |info, path| {
+                    info.child(
+                        div()
+                            .id("titlebar-document-path")
+                            .min_w_0()
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .cursor_pointer()
+                            .text_color(tokens.chrome.text_chrome_secondary)
+                            .hover(|style| style.text_color(titlebar_tokens.foreground))
+                            .tooltip(|_window, cx| {
+                                cx.new(|_| crate::TextTooltip::new("Reveal in File Tree"))
+                                    .into()
+                            })
+                            .on_click(|_event, window, cx| {
+                                window.dispatch_action(Box::new(RevealActiveFileInTree), cx);
+                            })
+                            .child(path),
+                    )
+                })
+                .when(self.document.dirty, |info| {
+                    info.child(
+                        div()
+                            .flex_none()
+                            .size(px(6.0))
+                            .rounded_full()
+                            .bg(titlebar_tokens.foreground),
+                    )
+                })
+                .into_any_element(),
+        )
+    }
+
+    fn render_quick_actions(&self) -> AnyElement {
+        let mut actions = div().flex().flex_row().items_center().h_full();
+        for (id, icon, tooltip, action) in quick_actions() {
+            actions = actions.child(
+                Button::icon_only(id, icon)
+                    .variant(ButtonVariant::Ghost)
+                    .size(ButtonSize::ExtraSmall)
+                    .tooltip(tooltip)
+                    .aria_label(tooltip)
+                    .on_click(move |_event, window, cx| {
+                        window.dispatch_action(action.boxed_clone(), cx);
+                    }),
+            );
+        }
+        actions.into_any_element()
+    }
+
+    /// Quick actions ending `right` from the window edge and, when there is
+    /// any, the document info centered across the title bar.
+    fn render_overlays(
+        &self,
+        titlebar: gpui::Div,
+        overflow: TitleBarOverflow,
+        left: Pixels,
+        right: Pixels,
+        height: Pixels,
+        cx: &gpui::App,
+    ) -> gpui::Div {
+        let quick_actions_width = if overflow.show_quick_actions {
+            px(TITLEBAR_QUICK_ACTION_WIDTH * TITLEBAR_QUICK_ACTION_COUNT as f32)
+        } else {
+            px(0.0)
+        };
+        // Inset both sides equally so the info stays centered in the window.
+        let inset = left.max(quick_actions_width + right);
+        titlebar
+            .when_some(self.render_document_info(overflow, cx), |titlebar, info| {
+                titlebar.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left(inset)
+                        .right(inset)
+                        .h(height)
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .child(info),
+                )
+            })
+            .when(overflow.show_quick_actions, |titlebar| {
+                titlebar.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .right(right)
+                        .h(height)
+                        .child(self.render_quick_actions()),
+                )
+            })
+    }
 }
 
 impl Render for TitleBar {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        #[cfg(not(target_os = "windows"))]
-        let _ = window;
-
         // Update platform titlebar with content
         let leading_sidebar_background = self.leading_sidebar_background;
+        let show_platform_title = !self.has_document_info();
         self.platform_titlebar.update(cx, |titlebar, _cx| {
             titlebar.set_title(self.filename.clone());
             titlebar.set_leading_sidebar_background(leading_sidebar_background);
             #[cfg(target_os = "windows")]
-            titlebar.set_show_title(false);
+            {
+                let _ = show_platform_title;
+                titlebar.set_show_title(false);
+            }
+            #[cfg(not(target_os = "windows"))]
+            titlebar.set_show_title(show_platform_title);
         });
 
+        let height = TitleBar::height(window, cx);
+        let trailing_width = if self.trailing_view.is_some() {
+            TITLEBAR_ACTION_LANE_WIDTH
+        } else {
+            0.0
+        };
+
         #[cfg(target_os = "windows")]
         {
             if let Some(menu) = &self.application_menu {
@@ -173,6 +398,15 @@ impl Render for TitleBar {
                 )));
                 let titlebar_tokens = cx.global::<crate::Theme>().tokens.titlebar_tokens();
                 let tokens = cx.global::<crate::Theme>().tokens;
+                let overflow = TitleBarOverflow::for_width(f32::from(content_width));
+                let quick_actions_width = if overflow.show_quick_actions {
+                    TITLEBAR_QUICK_ACTION_WIDTH * TITLEBAR_QUICK_ACTION_COUNT as f32
+                } else {
+                    0.0
+                };
+                let title = self
+                    .render_document_info(overflow, cx)
+                    .unwrap_or_else(|| self.filename.clone().into_any_element());
 
                 return div()
                     .relative()
@@ -191,7 +425,7 @@ impl Render for TitleBar {
                             .flex()
                             .flex_row()
                             .items_center()
-                            .pr(px(TITLEBAR_ACTION_LANE_WIDTH))
+                            .pr(px(TITLEBAR_ACTION_LANE_WIDTH + quick_actions_width))
                             .overflow_hidden()
                             .child(menu.clone())
                             .child(
@@ -209,9 +443,19 @@ impl Render for TitleBar {
                                     .font_family(WINDOWS_UI_FONT_FAMILY)
                                     .text_size(tokens.sizes.text_sm)
                                     .text_color(tokens.chrome.text_chrome_secondary)
-                                    .child(self.filename.clone()),
+                                    .child(title),
                             ),
                     )
+                    .when(overflow.show_quick_actions, |titlebar| {
+                        titlebar.child(
+                            div()
+                                .absolute()
+                                .top_0()
+                                .right(px(windows_caption_controls_width() + trailing_width))
+                                .h_full()
+                                .child(self.render_quick_actions()),
+                        )
+                    })
                     .when_some(self.trailing_view.clone(), |titlebar, trailing_view| {
                         titlebar.child(
                             div()
@@ -243,34 +487,43 @@ impl Render for TitleBar {
         {
             if let Some(menu) = &self.application_menu {
                 let titlebar_view = self.platform_titlebar.clone();
+                let titlebar = div().relative().w_full().child(titlebar_view).when_some(
+                    self.trailing_view.clone(),
+                    |titlebar, trailing_view| {
+                        titlebar.child(
+                            div()
+                                .absolute()
+                                .top_0()
+                                .right(px(TITLEBAR_ACTION_RIGHT_INSET))
+                                .w(px(TITLEBAR_ACTION_LANE_WIDTH))
+                                .h(height)
+                                .flex()
+                                .items_center()
+                                .justify_center()
+                                .child(trailing_view),
+                        )
+                    },
+                );
+                let titlebar = self.render_overlays(
+                    titlebar,
+                    TitleBarOverflow::for_width(f32::from(window.viewport_size().width)),
+                    px(0.0),
+                    px(TITLEBAR_ACTION_RIGHT_INSET + trailing_width),
+                    height,
+                    cx,
+                );
                 return div()
                     .flex()
                     .flex_col()
                     .w_full()
-                    .child(div().relative().w_full().child(titlebar_view).when_some(
-                        self.trailing_view.clone(),
-                        |titlebar, trailing_view| {
-                            titlebar.child(
-                                div()
-                                    .absolute()
-                                    .top_0()
-                                    .right(px(TITLEBAR_ACTION_RIGHT_INSET))
-                                    .w(px(TITLEBAR_ACTION_LANE_WIDTH))
-                                    .h(TitleBar::height(window, cx))
-                                    .flex()
-                                    .items_center()
-                                    .justify_center()
-                                    .child(trailing_view),
-                            )
-                        },
-                    ))
+                    .child(titlebar)
                     .child(menu.clone())
                     .into_any_element();
             }
         }
 
         // macOS (or fallback): overlay application actions without affecting the centred title.
-        div()
+        let titlebar = div()
             .relative()
             .w_full()
             .child(self.platform_titlebar.clone())
@@ -281,14 +534,52 @@ impl Render for TitleBar {
                         .top_0()
                         .right(px(TITLEBAR_ACTION_RIGHT_INSET))
                         .w(px(TITLEBAR_ACTION_LANE_WIDTH))
-                        .h(TitleBar::height(window, cx))
+                        .h(height)
                         .flex()
                         .items_center()
                         .justify_center()
                         .child(trailing_view),
                 )
-            })
-            .into_any_element()
+            });
+        self.render_overlays(
+            titlebar,
+            TitleBarOverflow::for_width(f32::from(window.viewport_size().width)),
+            px(MAC_TRAFFIC_LIGHT_INSET),
+            px(TITLEBAR_ACTION_RIGHT_INSET + trailing_width),
+            height,
+            cx,
+        )
+        .into_any_element()
+    }
+}
+
+#[cfg(test)]
+mod overflow_tests {
+    use super::TitleBarOverflow;
+
+    #[test]
+    fn narrow_titlebars_drop_quick_actions_before_the_project_name() {
+        assert_eq!(
+            TitleBarOverflow::for_width(1200.0),
+            TitleBarOverflow {
+                show_project: true,
+                show_quick_actions: true,
+            }
+        );
+        assert_eq!(
+            TitleBarOverflow::for_width(480.0),
+            TitleBarOverflow {
+                show_project: true,
+                show_quick_actions: false,
+            }
+        );
+        assert_eq!(
+            TitleBarOverflow::for_width(300.0),
+            TitleBarOverflow {
+                show_project: false,
+                show_quick_actions: false,
+            }
+        );
     }
 }
 
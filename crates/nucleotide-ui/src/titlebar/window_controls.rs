@@ -292,7 +292,8 @@ fn activate_window_control(control_type: WindowControlType, window: &mut Window,
             window.zoom_window();
         }
         WindowControlType::Close => {
-            cx.quit();
+            // Quit through the workspace so background services shut down first
+            window.dispatch_action(Box::new(crate::actions::editor::Quit), cx);
         }
     }
 }
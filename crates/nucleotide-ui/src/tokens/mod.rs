@@ -503,12 +503,29 @@ pub struct ChromeTokens {
     pub text_chrome_disabled: Hsla,
 }
 
+/// Terminal colors - derived from the editor's Helix colors so terminals
+/// follow the theme without one of their own
+#[derive(Debug, Clone, Copy)]
+pub struct TerminalTokens {
+    pub foreground: Hsla,
+    pub background: Hsla,
+    /// Black through bright white, each readable on `background`
+    pub ansi: [Hsla; 16],
+
+    pub cursor: Hsla,
+    pub cursor_text: Hsla,
+    pub selection: Hsla,
+    pub selection_text: Hsla,
+    pub search_match: Hsla,
+}
+
 /// Design tokens combining colors and sizes
 /// Now composed of separated editor and chrome token systems
 #[derive(Debug, Clone, Copy)]
 pub struct DesignTokens {
     pub editor: EditorTokens,
     pub chrome: ChromeTokens,
+    pub terminal: TerminalTokens,
     pub sizes: SizeTokens,
 }
 
@@ -1129,12 +1146,115 @@ impl ChromeTokens {
     }
 }
 
+impl TerminalTokens {
+    /// Derive the terminal palette from editor colors. Red, green and yellow
+    /// follow the error, success and warning colors, blue follows the accent,
+    /// and the remaining hues are mixed from those.
+    pub fn from_editor(editor: &EditorTokens, accent: Hsla) -> Self {
+        let background = opaque(editor.background);
+        let foreground = readable_on(background, editor.text_primary);
+        let is_dark = ColorTheory::relative_luminance(background) < 0.5;
+        let neutral_saturation = background.s * 0.2;
+        let neutral = |dark_lightness, light_lightness| {
+            readable_on(
+                background,
+                hsla(
+                    background.h,
+                    neutral_saturation,
+                    if is_dark {
+                        dark_lightness
+                    } else {
+                        light_lightness
+                    },
+                    1.0,
+                ),
+            )
+        };
+        let bright = |color: Hsla| {
+            let delta = if is_dark { 0.10 } else { -0.10 };
+            readable_on(
+                background,
+                ColorTheory::adjust_oklab_lightness(color, delta),
+            )
+        };
+
+        let red = readable_on(background, editor.error);
+        let green = readable_on(background, editor.success);
+        let yellow = readable_on(background, editor.warning);
+        let blue = readable_on(
+            background,
+            ColorTheory::mix_oklch(accent, editor.focus_ring, 0.25),
+        );
+        let magenta = readable_on(background, ColorTheory::mix_oklch(editor.error, blue, 0.55));
+        let cyan = readable_on(
+            background,
+            ColorTheory::mix_oklch(editor.success, blue, 0.50),
+        );
+
+        let cursor = opaque(editor.cursor_normal);
+        let white = hsla(0.0, 0.0, 1.0, 1.0);
+        let black = hsla(0.0, 0.0, 0.0, 1.0);
+        let cursor_text = if ColorTheory::contrast_ratio(cursor, white)
+            >= ColorTheory::contrast_ratio(cursor, black)
+        {
+            white
+        } else {
+            black
+        };
+
+        Self {
+            foreground,
+            background,
+            ansi: [
+                neutral(0.32, 0.08),
+                red,
+                green,
+                yellow,
+                blue,
+                magenta,
+                cyan,
+                neutral(0.84, 0.36),
+                neutral(0.48, 0.28),
+                bright(red),
+                bright(green),
+                bright(yellow),
+                bright(blue),
+                bright(magenta),
+                bright(cyan),
+                foreground,
+            ],
+            cursor,
+            cursor_text,
+            selection: editor.selection_primary,
+            selection_text: editor.text_on_primary,
+            search_match: editor.cursor_match,
+        }
+    }
+
+    /// Create fallback terminal tokens for testing
+    pub fn fallback(is_dark: bool) -> Self {
+        Self::from_editor(
+            &EditorTokens::fallback(is_dark),
+            ChromeTokens::fallback(is_dark).primary,
+        )
+    }
+}
+
+fn opaque(color: Hsla) -> Hsla {
+    hsla(color.h, color.s, color.l, 1.0)
+}
+
+fn readable_on(background: Hsla, color: Hsla) -> Hsla {
+    ColorTheory::ensure_contrast(background, opaque(color), ContrastRatios::AA_NORMAL)
+}
+
 impl DesignTokens {
     /// Create design tokens for light theme
     pub fn light() -> Self {
         Self {
             editor: EditorTokens::fallback(false),
             chrome: ChromeTokens::fallback(false),
+            terminal: TerminalTokens::fallback(false),
             sizes: SizeTokens::default(),
         }
     }
@@ -1144,6 +1264,7 @@ impl DesignTokens {
         Self {
             editor: EditorTokens::fallback(true),
             chrome: ChromeTokens::fallback(true),
+            terminal: TerminalTokens::fallback(true),
             sizes: SizeTokens::default(),
         }
     }
@@ -1191,10 +1312,12 @@ impl DesignTokens {
             editor_background,
             is_dark_theme,
         );
+        let terminal = TerminalTokens::from_editor(&editor, chrome.primary);
 
         Self {
             editor,
             chrome,
+            terminal,
             sizes: SizeTokens::default(),
         }
     }
@@ -1209,10 +1332,12 @@ impl DesignTokens {
         editor.background = editor_background;
 
         let chrome = ChromeTokens::from_native_chrome_palette(native_palette);
+        let terminal = TerminalTokens::from_editor(&editor, chrome.primary);
 
         Self {
             editor,
             chrome,
+            terminal,
             sizes: SizeTokens::native_chrome(),
         }
     }
@@ -5,7 +5,7 @@ use crate::DesignTokens;
 use crate::styling::{ColorTheory, ContrastRatios};
 use crate::tokens::{
     ChromeTokens, ColorContext, ControlDensity, DensityMetrics, EditorTokens, SizeTokens,
-    TitleBarTokens,
+    TerminalTokens, TitleBarTokens,
 };
 use nucleotide_appearance::{
     HelixThemeColors, NativeChromePalette, SystemAppearance, default_windows_accent_color,
@@ -28,6 +28,31 @@ mod typography_token_tests {
     }
 }
 
+#[cfg(test)]
+mod terminal_token_tests {
+    use super::*;
+
+    #[test]
+    fn terminal_tokens_follow_editor_colors() {
+        for is_dark in [false, true] {
+            let editor = EditorTokens::fallback(is_dark);
+            let terminal = TerminalTokens::fallback(is_dark);
+
+            assert_eq!(terminal.background, editor.background);
+            assert_eq!(terminal.selection, editor.selection_primary);
+            assert_eq!(terminal.search_match, editor.cursor_match);
+            assert!(
+                ColorTheory::contrast_ratio(terminal.background, terminal.foreground)
+                    >= ContrastRatios::AA_NORMAL
+            );
+            assert!(
+                ColorTheory::contrast_ratio(terminal.cursor, terminal.cursor_text)
+                    >= ContrastRatios::AA_LARGE
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 mod density_token_tests {
     use super::*;
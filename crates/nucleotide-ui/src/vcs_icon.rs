@@ -35,6 +35,15 @@ impl VcsIcon {
         }
     }
 
+    /// Create a VCS icon from a file name, honouring configured file associations
+    pub fn from_file_name(file_name: &str) -> Self {
+        Self {
+            file_icon: FileIcon::from_file_name(file_name),
+            vcs_status: None,
+            container_size: STANDARD_ICON_SIZE,
+        }
+    }
+
     /// Create a VCS icon from file extension
     pub fn from_extension(extension: Option<&str>) -> Self {
         Self {
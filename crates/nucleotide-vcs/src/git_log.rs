@@ -0,0 +1,243 @@
+// ABOUTME: Reads commit history from git and lays out branch graph lanes
+// ABOUTME: Backs the commit log and per-file history views
+
+use std::path::Path;
+
+const FIELD_SEPARATOR: char = '\x1f';
+const RECORD_SEPARATOR: char = '\x1e';
+const LOG_FORMAT: &str = "--format=%H%x1f%P%x1f%an%x1f%ad%x1f%s%x1f%D%x1e";
+
+/// One commit in the history view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitInfo {
+    pub hash: String,
+    pub parents: Vec<String>,
+    pub author: String,
+    /// Author date as `YYYY-MM-DD`
+    pub date: String,
+    pub subject: String,
+    /// Decorations such as `HEAD -> main, origin/main`
+    pub refs: String,
+}
+
+impl CommitInfo {
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(8)]
+    }
+}
+
+/// Parses `git log` output written with [`LOG_FORMAT`].
+pub fn parse_git_log(output: &str) -> Vec<CommitInfo> {
+    output
+        .split(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').split(FIELD_SEPARATOR);
+            let hash = fields.next()?.trim();
+            if hash.is_empty() {
+                return None;
+            }
+            Some(CommitInfo {
+                hash: hash.to_string(),
+                parents: fields
+                    .next()?
+                    .split_whitespace()
+                    .map(str::to_string)
+                    .collect(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+                refs: fields.next().unwrap_or_default().trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Lays out graph lanes for commits in `git log` order (children before
+/// parents) and returns one row of glyphs per commit, e.g. `│ ●` or `●─╮`.
+pub fn commit_graph_rows(commits: &[CommitInfo]) -> Vec<String> {
+    // Each lane holds the hash of the commit expected next in that column.
+    let mut lanes: Vec<Option<&str>> = Vec::new();
+    let mut rows = Vec::with_capacity(commits.len());
+
+    for commit in commits {
+        let column = lanes
+            .iter()
+            .position(|lane| *lane == Some(commit.hash.as_str()))
+            .or_else(|| lanes.iter().position(Option::is_none))
+            .unwrap_or_else(|| {
+                lanes.push(None);
+                lanes.len() - 1
+            });
+
+        // Other lanes waiting for this commit merge into it here.
+        let mut joined = Vec::new();
+        for (index, lane) in lanes.iter_mut().enumerate() {
+            if index != column && *lane == Some(commit.hash.as_str()) {
+                *lane = None;
+                joined.push(index);
+            }
+        }
+
+        lanes[column] = commit.parents.first().map(String::as_str);
+        let mut forked = Vec::new();
+        for parent in commit.parents.iter().skip(1) {
+            if lanes.contains(&Some(parent.as_str())) {
+                continue;
+            }
+            let lane = lanes.iter().position(Option::is_none).unwrap_or_else(|| {
+                lanes.push(None);
+                lanes.len() - 1
+            });
+            lanes[lane] = Some(parent.as_str());
+            forked.push(lane);
+        }
+
+        rows.push(render_graph_row(&lanes, column, &joined, &forked));
+        while lanes.last().is_some_and(Option::is_none) {
+            lanes.pop();
+        }
+    }
+
+    rows
+}
+
+fn render_graph_row(
+    lanes: &[Option<&str>],
+    column: usize,
+    joined: &[usize],
+    forked: &[usize],
+) -> String {
+    let edges: Vec<usize> = joined.iter().chain(forked).copied().collect();
+    let width = lanes
+        .len()
+        .max(edges.iter().max().map_or(0, |edge| edge + 1))
+        .max(column + 1);
+    let reach_left = edges.iter().copied().filter(|edge| *edge < column).min();
+    let reach_right = edges.iter().copied().filter(|edge| *edge > column).max();
+
+    let mut row = String::new();
+    for index in 0..width {
+        let glyph = if index == column {
+            '●'
+        } else if joined.contains(&index) {
+            if index < column { '╰' } else { '╯' }
+        } else if forked.contains(&index) {
+            if index < column { '╭' } else { '╮' }
+        } else if reach_left.is_some_and(|left| index > left && index < column)
+            || reach_right.is_some_and(|right| index < right && index > column)
+        {
+            '─'
+        } else if lanes.get(index).is_some_and(Option::is_some) {
+            '│'
+        } else {
+            ' '
+        };
+        row.push(glyph);
+
+        if index + 1 < width {
+            let connects = reach_left.is_some_and(|left| index >= left && index < column)
+                || reach_right.is_some_and(|right| index >= column && index < right);
+            row.push(if connects { '─' } else { ' ' });
+        }
+    }
+    row.trim_end().to_string()
+}
+
+/// Runs `git log` in `root_path`, optionally following a single path.
+pub fn run_git_log(
+    root_path: &Path,
+    path: Option<&Path>,
+    max_count: usize,
+) -> Result<Vec<CommitInfo>, String> {
+    let mut command = nucleotide_process::command("git");
+    command
+        .arg("log")
+        .arg(format!("--max-count={max_count}"))
+        .arg("--date=short")
+        .arg(LOG_FORMAT)
+        .current_dir(root_path);
+    if let Some(path) = path {
+        // `--follow` only works for a single file; directories list every commit touching them.
+        if !path.is_dir() {
+            command.arg("--follow");
+        }
+        command.arg("--").arg(path);
+    } else {
+        command.arg("--topo-order").arg("--all");
+    }
+
+    let output = command
+        .output()
+        .map_err(|error| format!("Could not run git log: {error}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(parse_git_log(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Runs `git show` for `hash`, limited to `path` when given.
+pub fn run_git_show(root_path: &Path, hash: &str, path: Option<&Path>) -> Result<String, String> {
+    let mut command = nucleotide_process::command("git");
+    command
+        .args(["show", "--stat", "--patch", "--format=fuller", "--no-color"])
+        .arg(hash)
+        .current_dir(root_path);
+    if let Some(path) = path {
+        command.arg("--").arg(path);
+    }
+
+    let output = command
+        .output()
+        .map_err(|error| format!("Could not run git show: {error}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(hash: &str, parents: &[&str]) -> CommitInfo {
+        CommitInfo {
+            hash: hash.to_string(),
+            parents: parents.iter().map(|parent| parent.to_string()).collect(),
+            author: "Author".to_string(),
+            date: "2025-01-01".to_string(),
+            subject: format!("commit {hash}"),
+            refs: String::new(),
+        }
+    }
+
+    #[test]
+    fn git_log_records_are_parsed() {
+        let output = "aaa\x1fbbb ccc\x1fAda\x1f2025-01-02\x1fMerge branch\x1fHEAD -> main\x1e\n\
+                      bbb\x1f\x1fGrace\x1f2025-01-01\x1fInitial commit\x1f\x1e\n";
+        let commits = parse_git_log(output);
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].parents, ["bbb", "ccc"]);
+        assert_eq!(commits[0].refs, "HEAD -> main");
+        assert_eq!(commits[1].author, "Grace");
+        assert!(commits[1].parents.is_empty());
+    }
+
+    #[test]
+    fn linear_history_uses_one_lane() {
+        let commits = [commit("c", &["b"]), commit("b", &["a"]), commit("a", &[])];
+        assert_eq!(commit_graph_rows(&commits), ["●", "●", "●"]);
+    }
+
+    #[test]
+    fn merge_forks_and_joins_lanes() {
+        // m merges feature (f) into main (b); both branch from a.
+        let commits = [
+            commit("m", &["b", "f"]),
+            commit("f", &["a"]),
+            commit("b", &["a"]),
+            commit("a", &[]),
+        ];
+        assert_eq!(commit_graph_rows(&commits), ["●─╮", "│ ●", "● │", "●─╯"]);
+    }
+}
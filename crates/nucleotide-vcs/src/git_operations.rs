@@ -0,0 +1,321 @@
+// ABOUTME: Runs git fetch, pull, push and stash for the monitored repository
+// ABOUTME: Parses git's progress output and reports credential failures and cancellation
+
+use nucleotide_logging::{debug, warn};
+use std::io::Read;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// How often a running operation checks for exit or cancellation.
+const GIT_OPERATION_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Repository operations that can be started from the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GitOperation {
+    Fetch,
+    Pull,
+    Push,
+    /// Stash local changes, including untracked files
+    Stash,
+    /// Re-apply and drop the most recent stash
+    StashPop,
+}
+
+impl GitOperation {
+    /// Arguments passed to `git`
+    pub fn args(self) -> &'static [&'static str] {
+        match self {
+            Self::Fetch => &["fetch", "--progress"],
+            Self::Pull => &["pull", "--progress"],
+            Self::Push => &["push", "--progress"],
+            Self::Stash => &["stash", "push", "--include-untracked"],
+            Self::StashPop => &["stash", "pop"],
+        }
+    }
+
+    /// Human readable name, e.g. for notifications
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Fetch => "Fetch",
+            Self::Pull => "Pull",
+            Self::Push => "Push",
+            Self::Stash => "Stash",
+            Self::StashPop => "Stash pop",
+        }
+    }
+
+    /// Whether the operation can change files in the working tree
+    pub fn changes_working_tree(self) -> bool {
+        !matches!(self, Self::Fetch | Self::Push)
+    }
+}
+
+/// A progress line reported by git, e.g. `Receiving objects:  45% (9/20)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitOperationProgress {
+    pub phase: String,
+    pub percent: Option<u8>,
+}
+
+/// Why a git operation did not complete
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitOperationError {
+    /// Cancelled by the user
+    Cancelled,
+    /// Another operation is still running
+    AlreadyRunning(GitOperation),
+    /// The remote rejected or asked for credentials
+    Authentication(String),
+    /// Any other failure, with git's error message
+    Failed(String),
+}
+
+impl std::fmt::Display for GitOperationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::AlreadyRunning(operation) => {
+                write!(f, "{} is still running", operation.label())
+            }
+            Self::Authentication(message) | Self::Failed(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for GitOperationError {}
+
+/// Shared flag used to cancel a running operation
+#[derive(Debug, Clone, Default)]
+pub struct GitOperationCancel(Arc<AtomicBool>);
+
+impl GitOperationCancel {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Runs `operation` in `root_path`, blocking until git exits or the operation
+/// is cancelled. Returns git's last line of output on success.
+///
+/// Terminal prompts are disabled so a missing credential fails immediately
+/// instead of waiting on a prompt nobody can answer.
+pub fn run_git_operation(
+    root_path: &Path,
+    operation: GitOperation,
+    cancel: &GitOperationCancel,
+    mut on_progress: impl FnMut(GitOperationProgress),
+) -> Result<String, GitOperationError> {
+    let mut child = nucleotide_process::command("git")
+        .args(operation.args())
+        .current_dir(root_path)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| GitOperationError::Failed(format!("Could not start git: {error}")))?;
+
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let stdout_reader = std::thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stdout.read_to_string(&mut output);
+        output
+    });
+
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let (line_tx, line_rx) = mpsc::channel();
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buffer = [0u8; 4096];
+        let mut pending = Vec::new();
+        while let Ok(read) = stderr.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+            for &byte in &buffer[..read] {
+                // Progress updates rewrite the current line with `\r`.
+                if byte == b'\r' || byte == b'\n' {
+                    if !pending.is_empty() {
+                        let _ = line_tx.send(String::from_utf8_lossy(&pending).into_owned());
+                        pending.clear();
+                    }
+                } else {
+                    pending.push(byte);
+                }
+            }
+        }
+        if !pending.is_empty() {
+            let _ = line_tx.send(String::from_utf8_lossy(&pending).into_owned());
+        }
+    });
+
+    let mut messages = Vec::new();
+    let mut last_progress = None;
+    let mut handle_line = |line: String| match parse_git_progress(&line) {
+        Some(progress) => {
+            if last_progress.as_ref() != Some(&progress) {
+                last_progress = Some(progress.clone());
+                on_progress(progress);
+            }
+        }
+        None => messages.push(line),
+    };
+
+    let status = loop {
+        while let Ok(line) = line_rx.try_recv() {
+            handle_line(line);
+        }
+
+        if cancel.is_cancelled() {
+            debug!(operation = ?operation, "VCS: Cancelling git operation");
+            if let Err(error) = child.kill() {
+                warn!(error = %error, "VCS: Could not kill git process");
+            }
+            let _ = child.wait();
+            return Err(GitOperationError::Cancelled);
+        }
+
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => std::thread::sleep(GIT_OPERATION_POLL_INTERVAL),
+            Err(error) => return Err(GitOperationError::Failed(error.to_string())),
+        }
+    };
+
+    let _ = stderr_reader.join();
+    for line in line_rx.try_iter() {
+        handle_line(line);
+    }
+    let stdout = stdout_reader.join().unwrap_or_default();
+
+    if status.success() {
+        let summary = stdout
+            .lines()
+            .chain(messages.iter().map(String::as_str))
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .unwrap_or("Done")
+            .to_string();
+        return Ok(summary);
+    }
+
+    let message = failure_message(&messages).unwrap_or_else(|| {
+        format!(
+            "git {} exited with {}",
+            operation.args()[0],
+            status
+                .code()
+                .map_or_else(|| "a signal".to_string(), |code| format!("status {code}"))
+        )
+    });
+    if messages.iter().any(|line| is_credential_failure(line)) {
+        Err(GitOperationError::Authentication(message))
+    } else {
+        Err(GitOperationError::Failed(message))
+    }
+}
+
+/// Parses a git progress line such as `remote: Counting objects: 100% (5/5), done.`
+pub fn parse_git_progress(line: &str) -> Option<GitOperationProgress> {
+    let line = line.trim();
+    let line = line.strip_prefix("remote:").map_or(line, str::trim_start);
+    let (phase, rest) = line.split_once(':')?;
+    let percent_end = rest.find('%')?;
+    let percent = rest[..percent_end].trim().parse::<u8>().ok()?;
+    Some(GitOperationProgress {
+        phase: phase.trim().to_string(),
+        percent: Some(percent.min(100)),
+    })
+}
+
+/// Whether a line of git's error output means credentials are missing or wrong.
+pub fn is_credential_failure(line: &str) -> bool {
+    const MARKERS: &[&str] = &[
+        "authentication failed",
+        "could not read username",
+        "could not read password",
+        "terminal prompts disabled",
+        "invalid username or password",
+        "permission denied (publickey",
+        "host key verification failed",
+    ];
+    let line = line.to_ascii_lowercase();
+    MARKERS.iter().any(|marker| line.contains(marker))
+}
+
+/// The most useful error line: git's `fatal:`/`error:` line if present,
+/// otherwise the first line it printed (later lines are usually hints).
+fn failure_message(messages: &[String]) -> Option<String> {
+    messages
+        .iter()
+        .rfind(|line| line.starts_with("fatal:") || line.starts_with("error:"))
+        .or_else(|| messages.iter().find(|line| !line.trim().is_empty()))
+        .map(|line| line.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn git_progress_lines_are_parsed() {
+        assert_eq!(
+            parse_git_progress("Receiving objects:  45% (9/20)"),
+            Some(GitOperationProgress {
+                phase: "Receiving objects".into(),
+                percent: Some(45),
+            })
+        );
+        assert_eq!(
+            parse_git_progress("remote: Counting objects: 100% (5/5), done."),
+            Some(GitOperationProgress {
+                phase: "Counting objects".into(),
+                percent: Some(100),
+            })
+        );
+        assert_eq!(
+            parse_git_progress("Writing objects: 100% (3/3), 280 bytes | 280.00 KiB/s, done."),
+            Some(GitOperationProgress {
+                phase: "Writing objects".into(),
+                percent: Some(100),
+            })
+        );
+        assert_eq!(parse_git_progress("From github.com:owner/repo"), None);
+        assert_eq!(parse_git_progress("fatal: not a git repository"), None);
+    }
+
+    #[test]
+    fn credential_failures_are_detected() {
+        assert!(is_credential_failure(
+            "fatal: Authentication failed for 'https://example.com/repo.git/'"
+        ));
+        assert!(is_credential_failure(
+            "fatal: could not read Username for 'https://example.com': terminal prompts disabled"
+        ));
+        assert!(is_credential_failure(
+            "git@example.com: Permission denied (publickey)."
+        ));
+        assert!(!is_credential_failure(
+            "error: failed to push some refs to 'origin'"
+        ));
+    }
+
+    #[test]
+    fn failure_message_prefers_fatal_lines() {
+        let messages = vec![
+            "fatal: couldn't find remote ref missing".to_string(),
+            "hint: see git help".to_string(),
+        ];
+        assert_eq!(
+            failure_message(&messages).as_deref(),
+            Some("fatal: couldn't find remote ref missing")
+        );
+    }
+}
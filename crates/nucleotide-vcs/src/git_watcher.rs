@@ -0,0 +1,149 @@
+// ABOUTME: Watches git metadata (index, HEAD and refs) for the monitored repository
+// ABOUTME: Lets the VCS service invalidate its status cache without polling
+
+use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded};
+use notify::{Event, RecursiveMode, Watcher};
+use nucleotide_logging::{debug, warn};
+use std::path::{Path, PathBuf};
+
+/// Kind of git metadata that changed on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GitMetadataChange {
+    /// `.git/index` was rewritten (staging, checkout, stash, ...)
+    Index,
+    /// `HEAD`, `packed-refs` or a local branch below `refs/heads/` changed
+    Refs,
+}
+
+/// Filesystem watcher for a repository's git directory
+pub struct GitMetadataWatcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
+impl GitMetadataWatcher {
+    /// Watch `git_dir` and forward classified changes to the returned receiver.
+    pub fn new(git_dir: PathBuf) -> notify::Result<(Self, UnboundedReceiver<GitMetadataChange>)> {
+        let (tx, rx) = unbounded();
+        let event_git_dir = git_dir.clone();
+        let mut watcher = notify::recommended_watcher(move |result| {
+            forward_event(&event_git_dir, result, &tx);
+        })?;
+
+        // Git replaces the index and loose refs by renaming lock files into
+        // place, so the directories are watched rather than the files.
+        watcher.watch(&git_dir, RecursiveMode::NonRecursive)?;
+        let heads_dir = git_dir.join("refs").join("heads");
+        if heads_dir.is_dir()
+            && let Err(error) = watcher.watch(&heads_dir, RecursiveMode::Recursive)
+        {
+            warn!(error = %error, heads_dir = %heads_dir.display(), "VCS: Could not watch git branches");
+        }
+
+        debug!(git_dir = %git_dir.display(), "VCS: Watching git metadata");
+        Ok((Self { _watcher: watcher }, rx))
+    }
+}
+
+fn forward_event(
+    git_dir: &Path,
+    result: notify::Result<Event>,
+    tx: &UnboundedSender<GitMetadataChange>,
+) {
+    match result {
+        Ok(event) => {
+            for change in event
+                .paths
+                .iter()
+                .filter_map(|path| classify_git_metadata_path(git_dir, path))
+            {
+                // Receiver dropped means monitoring stopped.
+                let _ = tx.unbounded_send(change);
+            }
+        }
+        Err(error) => warn!(error = %error, "VCS: Git metadata watcher error"),
+    }
+}
+
+/// Maps a path inside the git directory to the metadata it represents.
+/// Lock files, objects, logs, tags and remote-tracking refs are ignored since
+/// they cannot change the status of the working tree.
+pub(crate) fn classify_git_metadata_path(git_dir: &Path, path: &Path) -> Option<GitMetadataChange> {
+    let relative = path.strip_prefix(git_dir).ok()?;
+    if relative
+        .extension()
+        .is_some_and(|extension| extension == "lock")
+    {
+        return None;
+    }
+
+    if relative == Path::new("index") {
+        Some(GitMetadataChange::Index)
+    } else if relative == Path::new("HEAD")
+        || relative == Path::new("packed-refs")
+        || relative.starts_with("refs/heads")
+    {
+        Some(GitMetadataChange::Refs)
+    } else {
+        None
+    }
+}
+
+/// Resolves the git directory for `root_path`, following `.git` files used
+/// by worktrees and submodules.
+pub(crate) fn resolve_git_dir(root_path: &Path) -> Option<PathBuf> {
+    let output = nucleotide_process::command("git")
+        .args(["rev-parse", "--absolute-git-dir"])
+        .current_dir(root_path)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let git_dir = std::str::from_utf8(&output.stdout).ok()?.trim();
+    (!git_dir.is_empty()).then(|| PathBuf::from(git_dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn git_metadata_paths_are_classified() {
+        let git_dir = Path::new("/repo/.git");
+
+        assert_eq!(
+            classify_git_metadata_path(git_dir, &git_dir.join("index")),
+            Some(GitMetadataChange::Index)
+        );
+        assert_eq!(
+            classify_git_metadata_path(git_dir, &git_dir.join("HEAD")),
+            Some(GitMetadataChange::Refs)
+        );
+        assert_eq!(
+            classify_git_metadata_path(git_dir, &git_dir.join("refs/heads/main")),
+            Some(GitMetadataChange::Refs)
+        );
+        assert_eq!(
+            classify_git_metadata_path(git_dir, &git_dir.join("refs/remotes/origin/main")),
+            None
+        );
+        assert_eq!(
+            classify_git_metadata_path(git_dir, &git_dir.join("index.lock")),
+            None
+        );
+        assert_eq!(
+            classify_git_metadata_path(git_dir, &git_dir.join("refs/heads/main.lock")),
+            None
+        );
+        assert_eq!(
+            classify_git_metadata_path(git_dir, &git_dir.join("objects/ab/cdef")),
+            None
+        );
+        assert_eq!(
+            classify_git_metadata_path(git_dir, Path::new("/repo/src/index")),
+            None
+        );
+    }
+}
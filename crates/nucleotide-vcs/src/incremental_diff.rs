@@ -0,0 +1,424 @@
+// ABOUTME: Incremental diff hunks: reuse the previous diff and re-diff only around an edit
+// ABOUTME: Hunks away from the edit are kept (shifted), the edited window is diffed again
+
+use nucleotide_types::{DiffChangeType, DiffHunkInfo};
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Unchanged lines re-diffed around an edit so nearby hunks can realign.
+const CONTEXT_LINES: u32 = 3;
+/// Windows whose line comparison table would be larger than this fall back
+/// to a full diff.
+const MAX_WINDOW_CELLS: usize = 1 << 20;
+
+/// Lines replaced by an edit: `old_lines` of the previous text became
+/// `new_lines` of the current one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffEdit {
+    pub old_lines: Range<u32>,
+    pub new_lines: Range<u32>,
+}
+
+impl DiffEdit {
+    pub fn new(old_lines: Range<u32>, new_lines: Range<u32>) -> Self {
+        Self {
+            old_lines,
+            new_lines,
+        }
+    }
+
+    /// Combine this edit with one applied after it into a single edit from
+    /// the text before `self` to the text after `next`.
+    pub fn then(&self, next: &DiffEdit) -> DiffEdit {
+        let old_start = if next.old_lines.start < self.new_lines.start {
+            next.old_lines.start
+        } else {
+            self.old_lines.start
+        };
+        let old_end = if next.old_lines.end > self.new_lines.end {
+            next.old_lines.end - self.new_lines.end + self.old_lines.end
+        } else {
+            self.old_lines.end
+        };
+        let new_start = if self.new_lines.start < next.old_lines.start {
+            self.new_lines.start
+        } else {
+            next.new_lines.start
+        };
+        let new_end = if self.new_lines.end > next.old_lines.end {
+            self.new_lines.end - next.old_lines.end + next.new_lines.end
+        } else {
+            next.new_lines.end
+        };
+
+        DiffEdit::new(old_start..old_end, new_start..new_end)
+    }
+
+    fn shift(&self) -> i64 {
+        i64::from(self.new_lines.end) - i64::from(self.old_lines.end)
+    }
+}
+
+/// The part of the diff an edit invalidated and the hunks kept around it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncrementalDiff {
+    /// Base lines to diff again.
+    pub before: Range<u32>,
+    /// Current lines to diff again.
+    pub after: Range<u32>,
+    prefix: Vec<DiffHunkInfo>,
+    suffix: Vec<DiffHunkInfo>,
+}
+
+impl IncrementalDiff {
+    /// Plan an update of `previous` (sorted hunks of the text before `edit`).
+    ///
+    /// Returns `None` when the edit does not fit the previous diff, in which
+    /// case the file needs a full diff.
+    pub fn plan(
+        previous: &[DiffHunkInfo],
+        edit: &DiffEdit,
+        base_len: u32,
+        text_len: u32,
+    ) -> Option<Self> {
+        let shift = edit.shift();
+        let mut start = edit
+            .old_lines
+            .start
+            .min(edit.new_lines.start)
+            .saturating_sub(CONTEXT_LINES);
+        let max_old_end = u32::try_from(i64::from(text_len) - shift).ok()?;
+        let mut end = edit
+            .old_lines
+            .end
+            .saturating_add(CONTEXT_LINES)
+            .min(max_old_end)
+            .max(edit.old_lines.end);
+
+        // Grow the window until no hunk straddles its edges.
+        loop {
+            let (mut grown_start, mut grown_end) = (start, end);
+            for hunk in touching(previous, start, end) {
+                grown_start = grown_start.min(hunk.after_start);
+                grown_end = grown_end.max(hunk.after_end);
+            }
+            if (grown_start, grown_end) == (start, end) {
+                break;
+            }
+            (start, end) = (grown_start, grown_end);
+        }
+
+        let mut prefix = Vec::new();
+        let mut dirty_delta = 0i64;
+        let mut suffix = Vec::new();
+        for hunk in previous {
+            if hunk.after_end < start {
+                prefix.push(hunk.clone());
+            } else if hunk.after_start > end {
+                suffix.push(DiffHunkInfo {
+                    after_start: shift_line(hunk.after_start, shift)?,
+                    after_end: shift_line(hunk.after_end, shift)?,
+                    ..hunk.clone()
+                });
+            } else {
+                dirty_delta += line_delta(hunk);
+            }
+        }
+        let prefix_delta: i64 = prefix.iter().map(line_delta).sum();
+
+        let before_start = shift_line(start, prefix_delta)?;
+        let before_end = shift_line(end, prefix_delta + dirty_delta)?;
+        let after_end = shift_line(end, shift)?;
+        if before_start > before_end || before_end > base_len || after_end > text_len {
+            return None;
+        }
+
+        Some(Self {
+            before: before_start..before_end,
+            after: start..after_end,
+            prefix,
+            suffix,
+        })
+    }
+
+    /// Splice hunks of the re-diffed window, relative to its start, between
+    /// the kept hunks.
+    pub fn finish(self, window_hunks: Vec<DiffHunkInfo>) -> Vec<DiffHunkInfo> {
+        let mut hunks = self.prefix;
+        hunks.extend(window_hunks.into_iter().map(|hunk| DiffHunkInfo {
+            after_start: hunk.after_start + self.after.start,
+            after_end: hunk.after_end + self.after.start,
+            before_start: hunk.before_start + self.before.start,
+            before_end: hunk.before_end + self.before.start,
+            ..hunk
+        }));
+        hunks.extend(self.suffix);
+        hunks
+    }
+}
+
+fn touching(hunks: &[DiffHunkInfo], start: u32, end: u32) -> impl Iterator<Item = &DiffHunkInfo> {
+    hunks
+        .iter()
+        .filter(move |hunk| hunk.after_start <= end && hunk.after_end >= start)
+}
+
+fn line_delta(hunk: &DiffHunkInfo) -> i64 {
+    i64::from(hunk.before_end - hunk.before_start) - i64::from(hunk.after_end - hunk.after_start)
+}
+
+fn shift_line(line: u32, delta: i64) -> Option<u32> {
+    u32::try_from(i64::from(line) + delta).ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowDiffError {
+    /// The window is too large to diff line by line.
+    TooLarge,
+    /// A newer edit arrived while diffing.
+    Cancelled,
+}
+
+/// Diff two line windows, returning hunks relative to their starts.
+pub fn diff_lines<T: PartialEq>(
+    before: &[T],
+    after: &[T],
+    cancelled: &AtomicBool,
+) -> Result<Vec<DiffHunkInfo>, WindowDiffError> {
+    let prefix = before
+        .iter()
+        .zip(after)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let old = &before[prefix..before.len() - suffix];
+    let new = &after[prefix..after.len() - suffix];
+
+    if old.is_empty() && new.is_empty() {
+        return Ok(Vec::new());
+    }
+    if old.is_empty() || new.is_empty() {
+        return Ok(vec![hunk(prefix, old.len(), prefix, new.len())]);
+    }
+    if old.len().saturating_mul(new.len()) > MAX_WINDOW_CELLS {
+        return Err(WindowDiffError::TooLarge);
+    }
+
+    // Longest common subsequence lengths of the suffixes `old[i..]`, `new[j..]`.
+    let width = new.len() + 1;
+    let mut lcs = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(WindowDiffError::Cancelled);
+        }
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut hunks = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut hunk_i, mut hunk_j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            if (hunk_i, hunk_j) != (i, j) {
+                hunks.push(hunk(
+                    prefix + hunk_i,
+                    i - hunk_i,
+                    prefix + hunk_j,
+                    j - hunk_j,
+                ));
+            }
+            i += 1;
+            j += 1;
+            (hunk_i, hunk_j) = (i, j);
+        } else if j == new.len()
+            || (i < old.len() && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+        {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    if (hunk_i, hunk_j) != (i, j) {
+        hunks.push(hunk(
+            prefix + hunk_i,
+            i - hunk_i,
+            prefix + hunk_j,
+            j - hunk_j,
+        ));
+    }
+    Ok(hunks)
+}
+
+fn hunk(before_start: usize, removed: usize, after_start: usize, added: usize) -> DiffHunkInfo {
+    let change_type = match (removed, added) {
+        (0, _) => DiffChangeType::Addition,
+        (_, 0) => DiffChangeType::Deletion,
+        _ => DiffChangeType::Modification,
+    };
+    DiffHunkInfo::new(
+        after_start as u32,
+        (after_start + added) as u32,
+        before_start as u32,
+        (before_start + removed) as u32,
+        change_type,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<&str> {
+        text.split_inclusive('\n').collect()
+    }
+
+    fn full_diff(base: &str, text: &str) -> Vec<DiffHunkInfo> {
+        diff_lines(&lines(base), &lines(text), &AtomicBool::new(false)).unwrap()
+    }
+
+    fn incremental(base: &str, previous: &str, edit: DiffEdit, text: &str) -> Vec<DiffHunkInfo> {
+        let (base, text_lines) = (lines(base), lines(text));
+        let plan = IncrementalDiff::plan(
+            &full_diff(&base.concat(), previous),
+            &edit,
+            base.len() as u32,
+            text_lines.len() as u32,
+        )
+        .expect("edit fits the previous diff");
+        let window = diff_lines(
+            &base[plan.before.start as usize..plan.before.end as usize],
+            &text_lines[plan.after.start as usize..plan.after.end as usize],
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        plan.finish(window)
+    }
+
+    fn numbered(count: usize) -> String {
+        (0..count).map(|line| format!("line {line}\n")).collect()
+    }
+
+    #[test]
+    fn diff_lines_classifies_hunks() {
+        assert_eq!(
+            full_diff("a\nb\nc\n", "a\nx\nb\nc\n"),
+            vec![DiffHunkInfo::new(1, 2, 1, 1, DiffChangeType::Addition)]
+        );
+        assert_eq!(
+            full_diff("a\nb\nc\n", "a\nc\n"),
+            vec![DiffHunkInfo::new(1, 1, 1, 2, DiffChangeType::Deletion)]
+        );
+        assert_eq!(
+            full_diff("a\nb\nc\nd\n", "a\nB\nc\nD\n"),
+            vec![
+                DiffHunkInfo::new(1, 2, 1, 2, DiffChangeType::Modification),
+                DiffHunkInfo::new(3, 4, 3, 4, DiffChangeType::Modification),
+            ]
+        );
+    }
+
+    #[test]
+    fn incremental_update_matches_full_diff() {
+        let base = numbered(40);
+        let previous = base
+            .replace("line 5\n", "changed 5\n")
+            .replace("line 30\n", "");
+        let text = previous.replace("line 18\n", "line 18\ninserted\ninserted\n");
+
+        let hunks = incremental(&base, &previous, DiffEdit::new(18..19, 18..21), &text);
+
+        assert_eq!(hunks, full_diff(&base, &text));
+        assert_eq!(hunks.len(), 3);
+        assert_eq!(hunks[2].after_start, 32);
+    }
+
+    #[test]
+    fn incremental_update_merges_with_a_nearby_hunk() {
+        let base = numbered(20);
+        let previous = base.replace("line 8\n", "changed 8\n");
+        let text = previous.replace("line 9\n", "changed 9\n");
+
+        let hunks = incremental(&base, &previous, DiffEdit::new(9..10, 9..10), &text);
+
+        assert_eq!(
+            hunks,
+            vec![DiffHunkInfo::new(
+                8,
+                10,
+                8,
+                10,
+                DiffChangeType::Modification
+            )]
+        );
+    }
+
+    #[test]
+    fn reverting_an_edit_removes_its_hunk() {
+        let base = numbered(20);
+        let previous = base.replace("line 12\n", "changed 12\n");
+
+        let hunks = incremental(&base, &previous, DiffEdit::new(12..13, 12..13), &base);
+
+        assert!(hunks.is_empty());
+    }
+
+    #[test]
+    fn edits_compose_into_one() {
+        // Insert two lines after line 3, then delete line 10 of the result.
+        let first = DiffEdit::new(3..4, 3..6);
+        let second = DiffEdit::new(10..12, 10..11);
+        assert_eq!(first.then(&second), DiffEdit::new(3..10, 3..11));
+
+        // A second edit inside the first stays within its range.
+        assert_eq!(
+            first.then(&DiffEdit::new(4..5, 4..5)),
+            DiffEdit::new(3..4, 3..6)
+        );
+    }
+
+    #[test]
+    fn composed_edits_update_like_a_full_diff() {
+        let base = numbered(30);
+        let middle = base.replace("line 3\n", "line 3\nnew a\nnew b\n");
+        let text = middle.replace("line 20\n", "");
+        let edit = DiffEdit::new(3..4, 3..6).then(&DiffEdit::new(22..23, 22..22));
+
+        assert_eq!(
+            incremental(&base, &base, edit, &text),
+            full_diff(&base, &text)
+        );
+    }
+
+    #[test]
+    fn oversized_and_cancelled_windows_are_reported() {
+        let before: Vec<u32> = (0..2000).collect();
+        let after: Vec<u32> = (2000..4000).collect();
+        assert_eq!(
+            diff_lines(&before, &after, &AtomicBool::new(false)),
+            Err(WindowDiffError::TooLarge)
+        );
+        assert_eq!(
+            diff_lines(&before[..10], &after[..10], &AtomicBool::new(true)),
+            Err(WindowDiffError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn inconsistent_edits_need_a_full_diff() {
+        assert_eq!(
+            IncrementalDiff::plan(&[], &DiffEdit::new(8..9, 8..9), 5, 5),
+            None
+        );
+    }
+}
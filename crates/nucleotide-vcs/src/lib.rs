@@ -1,9 +1,16 @@
 // ABOUTME: Version control system integration crate for centralized VCS monitoring
 // ABOUTME: Provides caching, bulk operations, and event-driven VCS status updates
 
+pub mod git_log;
+pub mod git_operations;
+pub mod git_watcher;
+pub mod incremental_diff;
 pub mod vcs_service;
 
 // Re-export main types for easy access
+pub use git_log::CommitInfo;
+pub use git_operations::{GitOperation, GitOperationError, GitOperationProgress};
+pub use incremental_diff::DiffEdit;
 pub use vcs_service::{CacheStats, VcsConfig, VcsEvent, VcsService, VcsServiceHandle};
 
 // Re-export VCS types from nucleotide-types
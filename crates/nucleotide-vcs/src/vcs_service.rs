@@ -1,6 +1,12 @@
 // ABOUTME: Centralized VCS service for monitoring git status across the application
 // ABOUTME: Provides events and queries for file modification status in version control
 
+use crate::git_operations::{
+    GitOperation, GitOperationCancel, GitOperationError, GitOperationProgress, run_git_operation,
+};
+use crate::git_watcher::{GitMetadataChange, GitMetadataWatcher, resolve_git_dir};
+use crate::incremental_diff::{DiffEdit, IncrementalDiff, WindowDiffError, diff_lines};
+use futures::StreamExt;
 use gpui::{App, AppContext, Context, Entity, EventEmitter};
 use helix_core::Rope;
 use helix_vcs::{DiffHandle, DiffProviderRegistry, Hunk};
@@ -13,6 +19,8 @@ use nucleotide_workspace::{
 use std::cell::RefCell;
 use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
 /// Events broadcast by the VCS service
@@ -43,6 +51,18 @@ pub enum VcsEvent {
         previous_head: Option<String>,
         current_head: String,
     },
+    /// A fetch, pull, push or stash started
+    GitOperationStarted { operation: GitOperation },
+    /// Progress reported by a running git operation
+    GitOperationProgress {
+        operation: GitOperation,
+        progress: GitOperationProgress,
+    },
+    /// A git operation completed, failed or was cancelled
+    GitOperationFinished {
+        operation: GitOperation,
+        result: Result<String, GitOperationError>,
+    },
     /// VCS service encountered an error
     Error { message: String },
 }
@@ -174,6 +194,14 @@ pub struct VcsService {
     diff_handles: HashMap<PathBuf, DiffHandle>,
     /// Cached diff hunks for files
     diff_hunks_cache: HashMap<PathBuf, Vec<DiffHunkInfo>>,
+    /// Diff base of each cached diff, reused by incremental updates
+    diff_bases: HashMap<PathBuf, Rope>,
+    /// Edits waiting for the diff debounce to elapse
+    pending_diffs: HashMap<PathBuf, PendingDiff>,
+    /// Incremental diffs running in the background
+    diffs_in_flight: HashMap<PathBuf, InFlightDiff>,
+    /// Bumped for every scheduled diff update
+    diff_generation: u64,
     /// Access order for bounded diff metadata caches
     diff_access_order: VecDeque<PathBuf>,
     /// Configuration
@@ -194,11 +222,37 @@ pub struct VcsService {
     cache_cleanup_interval: Duration,
     /// Maximum cache size before forcing cleanup
     max_cache_size: usize,
+    /// Watcher for the index and branches of a local repository
+    git_watcher: Option<GitMetadataWatcher>,
+    /// Bumped whenever monitoring stops so stale watcher tasks exit
+    monitor_generation: u64,
+    /// A full refresh was requested while another one was running
+    full_refresh_queued: bool,
+    /// Bumped when a full refresh starts; older per-path results are dropped
+    full_refresh_generation: u64,
+    /// Paths changed while a full refresh was running
+    queued_status_paths: HashSet<PathBuf>,
+    /// Status changes waiting to be broadcast as a single event
+    pending_status_changes: HashMap<PathBuf, VcsStatus>,
+    /// Whether a flush of `pending_status_changes` is scheduled
+    status_flush_scheduled: bool,
+    /// The running fetch/pull/push/stash and its cancellation flag
+    git_operation: Option<(GitOperation, GitOperationCancel)>,
 }
 
 const DIFF_CACHE_CAPACITY: usize = 128;
+/// Quiet period after an edit before its diff is recomputed.
+const DIFF_UPDATE_DEBOUNCE: Duration = Duration::from_millis(150);
+/// Continuous typing still refreshes the diff at least this often.
+const DIFF_UPDATE_MAX_DELAY: Duration = Duration::from_secs(1);
 const DIFF_METADATA_READ_LIMIT_BYTES: u64 = 4 * 1024 * 1024;
 const DIFF_METADATA_COMMAND_TIMEOUT_MS: u64 = 10_000;
+/// Larger batches of changed paths fall back to a full status refresh.
+const INCREMENTAL_STATUS_PATH_LIMIT: usize = 64;
+/// Window in which status changes are merged into one `StatusUpdated` event.
+const STATUS_EVENT_BATCH_WINDOW: Duration = Duration::from_millis(50);
+/// Git rewrites several metadata files per operation; wait for it to settle.
+const GIT_METADATA_DEBOUNCE: Duration = Duration::from_millis(100);
 
 impl VcsService {
     /// Create a new VCS service
@@ -214,6 +268,10 @@ impl VcsService {
             diff_provider: DiffProviderRegistry::default(),
             diff_handles: HashMap::new(),
             diff_hunks_cache: HashMap::new(),
+            diff_bases: HashMap::new(),
+            pending_diffs: HashMap::new(),
+            diffs_in_flight: HashMap::new(),
+            diff_generation: 0,
             diff_access_order: VecDeque::new(),
             config,
             last_check: None,
@@ -224,6 +282,14 @@ impl VcsService {
             last_cache_cleanup: None,
             cache_cleanup_interval: Duration::from_secs(30), // Clean every 30 seconds
             max_cache_size: 5000,                            // Maximum 5000 cached entries
+            git_watcher: None,
+            monitor_generation: 0,
+            full_refresh_queued: false,
+            full_refresh_generation: 0,
+            queued_status_paths: HashSet::new(),
+            pending_status_changes: HashMap::new(),
+            status_flush_scheduled: false,
+            git_operation: None,
         }
     }
 
@@ -276,8 +342,8 @@ impl VcsService {
             // not block on spawning git or reading repository state.
             self.refresh_status_async(cx);
 
-            // Schedule periodic updates
-            self.schedule_next_check();
+            // Invalidate on index and branch changes instead of polling
+            self.start_git_metadata_watcher(cx);
 
             // Broadcast that we started monitoring
             self.emit_vcs_event(
@@ -293,6 +359,7 @@ impl VcsService {
     /// Stop monitoring
     pub fn stop_monitoring(&mut self) {
         info!("VCS: Stopping monitoring");
+        self.cancel_git_operation();
         self.is_monitoring = false;
         self.root_path = None;
         self.repository_head = None;
@@ -303,9 +370,147 @@ impl VcsService {
         }
         self.diff_handles.clear();
         self.diff_hunks_cache.clear();
+        self.diff_bases.clear();
         self.diff_access_order.clear();
+        self.pending_diffs.clear();
+        for (_, in_flight) in self.diffs_in_flight.drain() {
+            in_flight.cancel.store(true, Ordering::Relaxed);
+        }
         self.last_check = None;
         self.status_refresh_in_flight = false;
+        self.git_watcher = None;
+        self.monitor_generation = self.monitor_generation.wrapping_add(1);
+        self.full_refresh_queued = false;
+        self.queued_status_paths.clear();
+        self.pending_status_changes.clear();
+    }
+
+    fn uses_local_git(&self) -> bool {
+        self.workspace_backend
+            .as_ref()
+            .is_none_or(|backend| matches!(backend.identity(), WorkspaceIdentity::Local))
+    }
+
+    fn start_git_metadata_watcher(&mut self, cx: &mut Context<Self>) {
+        if !self.uses_local_git() {
+            return;
+        }
+        let Some(root_path) = self.root_path.clone() else {
+            return;
+        };
+        let generation = self.monitor_generation;
+
+        cx.spawn(async move |this, cx| {
+            let resolve_root = root_path.clone();
+            let git_dir = cx
+                .background_executor()
+                .spawn(async move { resolve_git_dir(&resolve_root) })
+                .await;
+            let Some(git_dir) = git_dir else {
+                debug!(root_path = %root_path.display(), "VCS: No git directory to watch");
+                return;
+            };
+
+            let (watcher, mut changes) = match GitMetadataWatcher::new(git_dir) {
+                Ok(watcher) => watcher,
+                Err(error) => {
+                    warn!(error = %error, "VCS: Could not watch git metadata");
+                    return;
+                }
+            };
+            let registered = this.update(cx, |service, _cx| {
+                let current = service.monitor_generation == generation && service.is_monitoring;
+                if current {
+                    service.git_watcher = Some(watcher);
+                }
+                current
+            });
+            if registered.ok() != Some(true) {
+                return;
+            }
+
+            // Ends when `stop_monitoring` drops the watcher and its sender.
+            while let Some(change) = changes.next().await {
+                let mut batch = HashSet::from([change]);
+                cx.background_executor().timer(GIT_METADATA_DEBOUNCE).await;
+                while let Ok(change) = changes.try_recv() {
+                    batch.insert(change);
+                }
+
+                let handled = this.update(cx, |service, cx| {
+                    let current = service.monitor_generation == generation;
+                    if current {
+                        service.handle_git_metadata_changes(&batch, cx);
+                    }
+                    current
+                });
+                if handled.ok() != Some(true) {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn handle_git_metadata_changes(
+        &mut self,
+        changes: &HashSet<GitMetadataChange>,
+        cx: &mut Context<Self>,
+    ) {
+        debug!(changes = ?changes, "VCS: Git metadata changed");
+        if changes.contains(&GitMetadataChange::Index) {
+            // Staging, checkout or stash can change any path's status.
+            self.invalidate_status_cache();
+            self.refresh_status_async(cx);
+        } else {
+            // Branch updates only matter for status when HEAD moved.
+            self.refresh_head_async(cx);
+        }
+    }
+
+    /// Marks every cached status stale while keeping it visible until the
+    /// next refresh replaces it.
+    fn invalidate_status_cache(&mut self) {
+        let invalidated = self.cache_timestamps.len();
+        self.cache_timestamps.clear();
+        if invalidated > 0
+            && let Ok(mut stats) = self.cache_stats.try_borrow_mut()
+        {
+            stats.invalidations += invalidated as u64;
+        }
+    }
+
+    fn refresh_head_async(&mut self, cx: &mut Context<Self>) {
+        let Some(root_path) = self.root_path.clone() else {
+            return;
+        };
+        let workspace_backend = self.workspace_backend.clone();
+        let head_root_path = root_path.clone();
+
+        cx.spawn(async move |this, cx| {
+            let head_result = cx
+                .background_executor()
+                .spawn(async move {
+                    run_git_head_with_backend(workspace_backend, &head_root_path).await
+                })
+                .await;
+
+            let _ = this.update(cx, |service, cx| {
+                if service.root_path.as_ref() != Some(&root_path) || !service.is_monitoring {
+                    return;
+                }
+                match head_result {
+                    Ok(head) if head.head != service.repository_head => {
+                        service.refresh_status_async(cx);
+                    }
+                    Ok(head) => service.update_repository_state(head.head, head.display_ref, cx),
+                    Err(error) => {
+                        debug!(error = %error, "VCS: Could not read repository head");
+                    }
+                }
+            });
+        })
+        .detach();
     }
 
     fn absolute_path(&self, path: &Path) -> Option<PathBuf> {
@@ -388,7 +593,9 @@ impl VcsService {
             );
 
             // Remove diff data if no base is available
+            self.cancel_diff_in_flight(&abs_path);
             self.diff_handles.remove(&abs_path);
+            self.diff_bases.remove(&abs_path);
             if self.diff_hunks_cache.remove(&abs_path).is_some() {
                 // Emit empty diff if we had hunks before
                 self.emit_vcs_event(
@@ -475,7 +682,7 @@ impl VcsService {
     ) {
         let diff_base_string = String::from_utf8_lossy(&diff_base_bytes);
         let diff_base = Rope::from_str(&diff_base_string);
-        let diff_handle = DiffHandle::new(diff_base, file_content);
+        let diff_handle = DiffHandle::new(diff_base.clone(), file_content);
 
         let hunks: Vec<DiffHunkInfo> = {
             let diff = diff_handle.load();
@@ -485,7 +692,7 @@ impl VcsService {
                 .collect()
         };
 
-        self.insert_file_diff(abs_path.clone(), diff_handle, hunks.clone());
+        self.insert_file_diff(abs_path.clone(), diff_base, diff_handle, hunks.clone());
 
         debug!(
             file_path = %abs_path.display(),
@@ -503,6 +710,177 @@ impl VcsService {
         );
     }
 
+    /// Update a file's diff after an edit, once editing pauses.
+    ///
+    /// Edits made while the debounce is running are merged, and a diff still
+    /// being computed is cancelled when a newer edit arrives. When the file
+    /// already has a diff, only the lines around the edits are diffed again.
+    pub fn schedule_file_diff(
+        &mut self,
+        file_path: &Path,
+        file_content: Rope,
+        edit: DiffEdit,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(abs_path) = self.absolute_path(file_path) else {
+            debug!("Cannot update file diff without root path");
+            return;
+        };
+
+        let mut edit = Some(edit);
+        let mut since = Instant::now();
+        if let Some(pending) = self.pending_diffs.remove(&abs_path) {
+            edit = chain_edits(pending.edit, edit);
+            since = pending.since;
+        }
+        if let Some(in_flight) = self.diffs_in_flight.remove(&abs_path) {
+            in_flight.cancel.store(true, Ordering::Relaxed);
+            edit = chain_edits(in_flight.edit, edit);
+        }
+
+        self.diff_generation = self.diff_generation.wrapping_add(1);
+        let generation = self.diff_generation;
+        self.pending_diffs.insert(
+            abs_path.clone(),
+            PendingDiff {
+                edit,
+                text: file_content,
+                generation,
+                since,
+            },
+        );
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(DIFF_UPDATE_DEBOUNCE).await;
+            let _ = this.update(cx, |service, cx| {
+                service.run_pending_diff(abs_path, generation, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn run_pending_diff(&mut self, abs_path: PathBuf, generation: u64, cx: &mut Context<Self>) {
+        let Some(pending) = self.pending_diffs.get(&abs_path) else {
+            return;
+        };
+        if pending.generation != generation && pending.since.elapsed() < DIFF_UPDATE_MAX_DELAY {
+            // A newer edit restarted the debounce.
+            return;
+        }
+        let Some(pending) = self.pending_diffs.remove(&abs_path) else {
+            return;
+        };
+
+        let plan = pending.edit.as_ref().and_then(|edit| {
+            let diff_base = self.diff_bases.get(&abs_path)?;
+            let previous = self.diff_hunks_cache.get(&abs_path)?;
+            IncrementalDiff::plan(
+                previous,
+                edit,
+                u32::try_from(diff_base.len_lines()).ok()?,
+                u32::try_from(pending.text.len_lines()).ok()?,
+            )
+        });
+        let (Some(plan), Some(diff_base)) = (plan, self.diff_bases.get(&abs_path).cloned()) else {
+            self.update_file_diff(&abs_path, pending.text, cx);
+            return;
+        };
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.diffs_in_flight.insert(
+            abs_path.clone(),
+            InFlightDiff {
+                generation,
+                edit: pending.edit,
+                cancel: cancel.clone(),
+            },
+        );
+
+        let text = pending.text;
+        cx.spawn(async move |this, cx| {
+            let (before, after) = (plan.before.clone(), plan.after.clone());
+            let window_text = text.clone();
+            let result = cx
+                .background_executor()
+                .spawn(async move {
+                    let before: Vec<_> = before.map(|line| diff_base.line(line as usize)).collect();
+                    let after: Vec<_> = after.map(|line| window_text.line(line as usize)).collect();
+                    diff_lines(&before, &after, &cancel)
+                })
+                .await;
+
+            let _ = this.update(cx, |service, cx| {
+                service.finish_incremental_diff(abs_path, generation, plan, result, text, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn finish_incremental_diff(
+        &mut self,
+        abs_path: PathBuf,
+        generation: u64,
+        plan: IncrementalDiff,
+        result: Result<Vec<DiffHunkInfo>, WindowDiffError>,
+        file_content: Rope,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .diffs_in_flight
+            .get(&abs_path)
+            .is_none_or(|in_flight| in_flight.generation != generation)
+        {
+            debug!(
+                file_path = %abs_path.display(),
+                "VCS: Dropping superseded incremental diff"
+            );
+            return;
+        }
+        self.diffs_in_flight.remove(&abs_path);
+
+        let window_hunks = match result {
+            Ok(window_hunks) => window_hunks,
+            Err(WindowDiffError::TooLarge) => {
+                self.update_file_diff(&abs_path, file_content, cx);
+                return;
+            }
+            Err(WindowDiffError::Cancelled) => return,
+        };
+        if !self.diff_hunks_cache.contains_key(&abs_path) {
+            // Evicted while diffing; the next edit starts from a full diff.
+            return;
+        }
+
+        let hunks = plan.finish(window_hunks);
+        if let Some(diff_handle) = self.diff_handles.get(&abs_path) {
+            diff_handle.update_document(file_content, false);
+        }
+        self.diff_hunks_cache
+            .insert(abs_path.clone(), hunks.clone());
+        self.touch_diff_cache_entry(&abs_path);
+
+        debug!(
+            file_path = %abs_path.display(),
+            hunk_count = hunks.len(),
+            "Updated diff hunks for file incrementally"
+        );
+
+        self.emit_vcs_event(
+            VcsEvent::DiffHunksUpdated {
+                file_path: abs_path,
+                hunks,
+                diff_base_revision: self.repository_head.clone(),
+            },
+            cx,
+        );
+    }
+
+    fn cancel_diff_in_flight(&mut self, abs_path: &Path) {
+        if let Some(in_flight) = self.diffs_in_flight.remove(abs_path) {
+            in_flight.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
     /// Refresh VCS state after debounced filesystem watcher events.
     pub fn refresh_after_file_system_changes(
         &mut self,
@@ -536,13 +914,105 @@ impl VcsService {
             change_count = affected_paths.len(),
             "VCS: Refreshing after filesystem changes"
         );
-        self.refresh_status_async(cx);
+        self.refresh_status_for_paths(affected_paths.clone(), cx);
 
         for path in affected_paths {
             self.refresh_diff_metadata_from_workspace(&path, cx);
         }
     }
 
+    /// Refresh the status of a few changed paths without rescanning the
+    /// repository. Remote workspaces and large batches use a full refresh.
+    fn refresh_status_for_paths(&mut self, paths: Vec<PathBuf>, cx: &mut Context<Self>) {
+        if !self.uses_local_git() || paths.len() > INCREMENTAL_STATUS_PATH_LIMIT {
+            self.refresh_status_async(cx);
+            return;
+        }
+
+        if self.status_refresh_in_flight {
+            // The running refresh may have read these paths before they changed.
+            self.queued_status_paths.extend(paths);
+            return;
+        }
+
+        let Some(root_path) = self.root_path.clone() else {
+            return;
+        };
+        let generation = self.full_refresh_generation;
+        let query_root_path = root_path.clone();
+        let query_paths = paths.clone();
+
+        debug!(
+            path_count = paths.len(),
+            "VCS: Starting incremental status refresh"
+        );
+        cx.spawn(async move |this, cx| {
+            let status_result = cx
+                .background_executor()
+                .spawn(async move { run_git_status_for_paths(&query_root_path, &query_paths) })
+                .await;
+
+            let _ = this.update(cx, |service, cx| {
+                if service.root_path.as_ref() != Some(&root_path) || !service.is_monitoring {
+                    debug!("VCS: Ignoring stale incremental status result");
+                    return;
+                }
+                if service.full_refresh_generation != generation {
+                    debug!("VCS: Incremental status superseded by a full refresh");
+                    return;
+                }
+
+                match status_result {
+                    Ok(statuses) => {
+                        let changes = service.apply_path_statuses(&paths, statuses);
+                        service.queue_status_changes(changes, cx);
+                    }
+                    Err(error) => {
+                        warn!(error = %error, "VCS: Incremental status failed, refreshing fully");
+                        service.refresh_status_async(cx);
+                    }
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Merge statuses for `queried` paths into the cache and return what
+    /// changed. Cached entries at or below a queried path that git no longer
+    /// reports are clean.
+    fn apply_path_statuses(
+        &mut self,
+        queried: &[PathBuf],
+        statuses: HashMap<PathBuf, VcsStatus>,
+    ) -> HashMap<PathBuf, VcsStatus> {
+        let mut changes = HashMap::new();
+        let cleaned: Vec<PathBuf> = self
+            .status_cache
+            .keys()
+            .filter(|path| !statuses.contains_key(*path))
+            .filter(|path| queried.iter().any(|queried| path.starts_with(queried)))
+            .cloned()
+            .collect();
+        for path in cleaned {
+            self.status_cache.remove(&path);
+            self.cache_timestamps.remove(&path);
+            changes.insert(path, VcsStatus::Clean);
+        }
+
+        let now = Instant::now();
+        for (path, status) in statuses {
+            if self.status_cache.insert(path.clone(), status) != Some(status) {
+                changes.insert(path.clone(), status);
+            }
+            self.cache_timestamps.insert(path, now);
+        }
+
+        if !changes.is_empty() {
+            self.status_revision = self.status_revision.wrapping_add(1);
+        }
+        changes
+    }
+
     fn refresh_diff_metadata_from_workspace(&mut self, abs_path: &Path, cx: &mut Context<Self>) {
         let path = abs_path.to_path_buf();
         let workspace_backend = self.workspace_backend.clone();
@@ -595,7 +1065,9 @@ impl VcsService {
     }
 
     fn clear_file_diff(&mut self, abs_path: &Path, cx: &mut Context<Self>) {
+        self.cancel_diff_in_flight(abs_path);
         self.diff_handles.remove(abs_path);
+        self.diff_bases.remove(abs_path);
         self.diff_access_order.retain(|path| path != abs_path);
         if self.diff_hunks_cache.remove(abs_path).is_some() {
             self.emit_vcs_event(
@@ -612,10 +1084,17 @@ impl VcsService {
     fn insert_file_diff(
         &mut self,
         abs_path: PathBuf,
+        diff_base: Rope,
         diff_handle: DiffHandle,
         hunks: Vec<DiffHunkInfo>,
     ) {
+        self.cancel_diff_in_flight(&abs_path);
+        if let Some(pending) = self.pending_diffs.get_mut(&abs_path) {
+            // Pending edits were made against the replaced diff.
+            pending.edit = None;
+        }
         self.diff_handles.insert(abs_path.clone(), diff_handle);
+        self.diff_bases.insert(abs_path.clone(), diff_base);
         self.diff_hunks_cache.insert(abs_path.clone(), hunks);
         self.touch_diff_cache_entry(&abs_path);
         self.evict_diff_cache_to_capacity();
@@ -635,6 +1114,7 @@ impl VcsService {
             };
             self.diff_handles.remove(&evicted);
             self.diff_hunks_cache.remove(&evicted);
+            self.diff_bases.remove(&evicted);
         }
     }
 
@@ -871,6 +1351,103 @@ impl VcsService {
         self.refresh_status_async(cx);
     }
 
+    /// Run a fetch, pull, push or stash in the monitored repository. Progress
+    /// and the outcome are reported through `GitOperation*` events.
+    pub fn run_git_operation(&mut self, operation: GitOperation, cx: &mut Context<Self>) {
+        let finish_early = |service: &Self, error: GitOperationError, cx: &mut Context<Self>| {
+            service.emit_vcs_event(
+                VcsEvent::GitOperationFinished {
+                    operation,
+                    result: Err(error),
+                },
+                cx,
+            );
+        };
+
+        if let Some((running, _)) = &self.git_operation {
+            let error = GitOperationError::AlreadyRunning(*running);
+            finish_early(self, error, cx);
+            return;
+        }
+        let Some(root_path) = self.root_path.clone().filter(|_| self.is_monitoring) else {
+            let error = GitOperationError::Failed("No repository is open".to_string());
+            finish_early(self, error, cx);
+            return;
+        };
+        if !self.uses_local_git() {
+            let error = GitOperationError::Failed(
+                "Git operations are only available for local workspaces".to_string(),
+            );
+            finish_early(self, error, cx);
+            return;
+        }
+
+        info!(operation = ?operation, root_path = %root_path.display(), "VCS: Starting git operation");
+        let cancel = GitOperationCancel::default();
+        self.git_operation = Some((operation, cancel.clone()));
+        self.emit_vcs_event(VcsEvent::GitOperationStarted { operation }, cx);
+
+        let (progress_tx, mut progress_rx) = futures::channel::mpsc::unbounded();
+        let operation_root = root_path.clone();
+        let task = cx.background_executor().spawn(async move {
+            run_git_operation(&operation_root, operation, &cancel, |progress| {
+                let _ = progress_tx.unbounded_send(progress);
+            })
+        });
+
+        cx.spawn(async move |this, cx| {
+            // Ends when the operation finishes and drops the sender.
+            while let Some(progress) = progress_rx.next().await {
+                let _ = this.update(cx, |service, cx| {
+                    service.emit_vcs_event(
+                        VcsEvent::GitOperationProgress {
+                            operation,
+                            progress,
+                        },
+                        cx,
+                    );
+                });
+            }
+            let result = task.await;
+
+            let _ = this.update(cx, |service, cx| {
+                service.git_operation = None;
+                match &result {
+                    Ok(_) => info!(operation = ?operation, "VCS: Git operation finished"),
+                    Err(error) => {
+                        warn!(operation = ?operation, error = %error, "VCS: Git operation failed")
+                    }
+                }
+                service.emit_vcs_event(VcsEvent::GitOperationFinished { operation, result }, cx);
+
+                // Pull and stash rewrite files; fetch and push move
+                // remote-tracking refs only, so a HEAD check is enough.
+                if service.root_path.as_ref() == Some(&root_path) && service.is_monitoring {
+                    if operation.changes_working_tree() {
+                        service.invalidate_status_cache();
+                        service.refresh_status_async(cx);
+                    } else {
+                        service.refresh_head_async(cx);
+                    }
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Cancel the running git operation, if any
+    pub fn cancel_git_operation(&mut self) {
+        if let Some((operation, cancel)) = &self.git_operation {
+            info!(operation = ?operation, "VCS: Cancelling git operation");
+            cancel.cancel();
+        }
+    }
+
+    /// The git operation that is currently running
+    pub fn running_git_operation(&self) -> Option<GitOperation> {
+        self.git_operation.as_ref().map(|(operation, _)| *operation)
+    }
+
     /// Check if a repository is being monitored
     pub fn is_monitoring(&self) -> bool {
         self.is_monitoring && self.root_path.is_some()
@@ -890,11 +1467,13 @@ impl VcsService {
 
         if self.status_refresh_in_flight {
             debug!(root_path = %root_path.display(), "VCS: Status refresh already in flight");
+            self.full_refresh_queued = true;
             return;
         }
 
         self.maintain_cache();
         self.status_refresh_in_flight = true;
+        self.full_refresh_generation = self.full_refresh_generation.wrapping_add(1);
         let max_files = self.config.max_files;
         let refresh_root_path = root_path.clone();
         let workspace_backend = self.workspace_backend.clone();
@@ -943,6 +1522,14 @@ impl VcsService {
                     }
 
                     service.last_check = Some(Instant::now());
+
+                    if std::mem::take(&mut service.full_refresh_queued) {
+                        service.queued_status_paths.clear();
+                        service.refresh_status_async(cx);
+                    } else if !service.queued_status_paths.is_empty() {
+                        let paths = service.queued_status_paths.drain().collect();
+                        service.refresh_status_for_paths(paths, cx);
+                    }
                 });
             }
         })
@@ -1039,21 +1626,68 @@ impl VcsService {
             self.cache_timestamps.remove(&path);
         }
 
-        // Emit changes if any
-        if !changes.is_empty() {
-            debug!(change_count = changes.len(), "VCS: Status changes detected");
-            self.emit_vcs_event(VcsEvent::StatusUpdated { changes }, cx);
+        self.queue_status_changes(changes, cx);
+    }
+
+    /// Queue status changes to be broadcast together with any others that
+    /// arrive within the batch window.
+    fn queue_status_changes(
+        &mut self,
+        changes: HashMap<PathBuf, VcsStatus>,
+        cx: &mut Context<Self>,
+    ) {
+        if changes.is_empty() {
+            return;
         }
+
+        self.pending_status_changes.extend(changes);
+        if self.status_flush_scheduled {
+            return;
+        }
+
+        self.status_flush_scheduled = true;
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(STATUS_EVENT_BATCH_WINDOW)
+                .await;
+            let _ = this.update(cx, |service, cx| service.flush_status_changes(cx));
+        })
+        .detach();
     }
 
-    /// Schedule the next status check
-    fn schedule_next_check(&self) {
-        // For now, we'll only refresh on demand to avoid async complexity
-        // TODO: Add periodic background refresh using a timer
-        debug!("VCS: Status check scheduled (currently on-demand only)");
+    fn flush_status_changes(&mut self, cx: &mut Context<Self>) {
+        self.status_flush_scheduled = false;
+        let changes = std::mem::take(&mut self.pending_status_changes);
+        if changes.is_empty() {
+            return;
+        }
+
+        debug!(change_count = changes.len(), "VCS: Status changes detected");
+        self.emit_vcs_event(VcsEvent::StatusUpdated { changes }, cx);
     }
 }
 
+/// An edit whose diff update is waiting for the debounce.
+struct PendingDiff {
+    /// Lines changed since the cached diff; `None` needs a full diff.
+    edit: Option<DiffEdit>,
+    text: Rope,
+    generation: u64,
+    /// When the first of the merged edits was scheduled.
+    since: Instant,
+}
+
+/// An incremental diff running in the background.
+struct InFlightDiff {
+    generation: u64,
+    edit: Option<DiffEdit>,
+    cancel: Arc<AtomicBool>,
+}
+
+fn chain_edits(first: Option<DiffEdit>, next: Option<DiffEdit>) -> Option<DiffEdit> {
+    Some(first?.then(&next?))
+}
+
 struct GitRefreshResult {
     status: HashMap<PathBuf, VcsStatus>,
     head: Option<String>,
@@ -1332,6 +1966,65 @@ fn run_git_status(
     Ok(status_map)
 }
 
+/// `git status` limited to `paths`, used to refresh a few changed files
+/// without rescanning the whole repository.
+fn run_git_status_for_paths(
+    root_path: &Path,
+    paths: &[PathBuf],
+) -> Result<HashMap<PathBuf, VcsStatus>, String> {
+    let pathspecs: Vec<String> = paths
+        .iter()
+        .filter_map(|path| path.strip_prefix(root_path).ok())
+        .filter(|relative| !relative.as_os_str().is_empty())
+        .map(|relative| format!(":(literal){}", relative.to_string_lossy()))
+        .collect();
+    if pathspecs.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let output = nucleotide_process::command("git")
+        .args([
+            "status",
+            "--porcelain=v1",
+            "-z",
+            "--untracked-files=all",
+            "--",
+        ])
+        .args(&pathspecs)
+        .current_dir(root_path)
+        .output()
+        .map_err(|e| format!("Failed to execute git: {}", e))?;
+
+    if !output.status.success() {
+        if git_error_is_not_repository(&String::from_utf8_lossy(&output.stderr)) {
+            return Ok(HashMap::new());
+        }
+        return Err(format!("Git command failed with status: {}", output.status));
+    }
+
+    Ok(parse_git_status_z_output(root_path, &output.stdout))
+}
+
+fn parse_git_status_z_output(root_path: &Path, stdout: &[u8]) -> HashMap<PathBuf, VcsStatus> {
+    let mut status_map = HashMap::new();
+    let mut fields = stdout
+        .split(|byte| *byte == 0)
+        .filter(|field| !field.is_empty());
+
+    while let Some(field) = fields.next() {
+        if matches!(field.first(), Some(b'R' | b'C')) {
+            // Renames and copies are followed by their original path.
+            fields.next();
+        }
+        let field = String::from_utf8_lossy(field);
+        if let Some((file_path, status)) = parse_git_status_line(&field) {
+            status_map.insert(root_path.join(file_path), status);
+        }
+    }
+
+    status_map
+}
+
 fn git_error_is_not_repository(message: &str) -> bool {
     message.contains("not a git repository")
 }
@@ -1406,6 +2099,20 @@ impl VcsServiceHandle {
         });
     }
 
+    /// Run a fetch, pull, push or stash in the monitored repository
+    pub fn run_git_operation(&self, operation: GitOperation, cx: &mut App) {
+        self.service.update(cx, |service, cx| {
+            service.run_git_operation(operation, cx);
+        });
+    }
+
+    /// Cancel the running git operation, if any
+    pub fn cancel_git_operation(&self, cx: &mut App) {
+        self.service.update(cx, |service, _cx| {
+            service.cancel_git_operation();
+        });
+    }
+
     /// Get VCS status with caching (preferred method for all components)
     pub fn get_status_cached(&self, path: &Path, cx: &App) -> Option<VcsStatus> {
         self.service.read(cx).get_status_cached(path)
@@ -1505,8 +2212,9 @@ mod tests {
 
     fn insert_test_diff(service: &mut VcsService, index: usize) -> PathBuf {
         let path = PathBuf::from(format!("/repo/file-{index}.rs"));
-        let diff_handle = DiffHandle::new(Rope::from_str("base\n"), Rope::from_str("current\n"));
-        service.insert_file_diff(path.clone(), diff_handle, Vec::new());
+        let diff_base = Rope::from_str("base\n");
+        let diff_handle = DiffHandle::new(diff_base.clone(), Rope::from_str("current\n"));
+        service.insert_file_diff(path.clone(), diff_base, diff_handle, Vec::new());
         path
     }
 
@@ -1540,6 +2248,84 @@ mod tests {
         assert_eq!(parse_git_status_line("↪ src/lib.rs"), None);
     }
 
+    #[test]
+    fn parse_git_status_z_output_skips_rename_origins() {
+        let root = Path::new("/repo");
+        let status = parse_git_status_z_output(
+            root,
+            b" M src/lib.rs\0R  src/new.rs\0src/old.rs\0?? notes.md\0",
+        );
+
+        assert_eq!(
+            status,
+            HashMap::from([
+                (root.join("src/lib.rs"), VcsStatus::Modified),
+                (root.join("src/new.rs"), VcsStatus::Renamed),
+                (root.join("notes.md"), VcsStatus::Untracked),
+            ])
+        );
+    }
+
+    #[test]
+    fn local_git_status_for_paths_reports_only_requested_paths() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let init = nucleotide_process::command("git")
+            .args(["init", "--quiet"])
+            .current_dir(root)
+            .status()
+            .unwrap();
+        assert!(init.success());
+        std::fs::write(root.join("a.txt"), "a\n").unwrap();
+        std::fs::write(root.join("b.txt"), "b\n").unwrap();
+
+        let status = run_git_status_for_paths(root, &[root.join("a.txt")]).unwrap();
+
+        assert_eq!(
+            status,
+            HashMap::from([(root.join("a.txt"), VcsStatus::Untracked)])
+        );
+    }
+
+    #[test]
+    fn path_statuses_only_touch_queried_paths() {
+        let mut service = VcsService::new(VcsConfig::default());
+        let root = PathBuf::from("/repo");
+        for (path, status) in [
+            ("a.rs", VcsStatus::Modified),
+            ("b.rs", VcsStatus::Modified),
+            ("dir/c.rs", VcsStatus::Untracked),
+        ] {
+            service.status_cache.insert(root.join(path), status);
+        }
+
+        let changes = service.apply_path_statuses(
+            &[root.join("a.rs"), root.join("dir")],
+            HashMap::from([(root.join("a.rs"), VcsStatus::Added)]),
+        );
+
+        assert_eq!(
+            changes,
+            HashMap::from([
+                (root.join("a.rs"), VcsStatus::Added),
+                (root.join("dir/c.rs"), VcsStatus::Clean),
+            ])
+        );
+        assert_eq!(
+            service.get_status(&root.join("b.rs")),
+            Some(VcsStatus::Modified)
+        );
+        assert_eq!(service.get_status(&root.join("dir/c.rs")), None);
+        assert_eq!(service.status_revision(), 1);
+
+        let unchanged = service.apply_path_statuses(
+            &[root.join("a.rs")],
+            HashMap::from([(root.join("a.rs"), VcsStatus::Added)]),
+        );
+        assert!(unchanged.is_empty());
+        assert_eq!(service.status_revision(), 1);
+    }
+
     #[test]
     fn git_error_recognizes_not_repository_message() {
         assert!(git_error_is_not_repository(
@@ -1684,6 +2470,36 @@ mod tests {
         ))));
     }
 
+    #[tokio::test]
+    async fn replacing_a_diff_cancels_incremental_updates() {
+        let mut service = VcsService::new(VcsConfig::default());
+        let path = insert_test_diff(&mut service, 0);
+        service.pending_diffs.insert(
+            path.clone(),
+            PendingDiff {
+                edit: Some(DiffEdit::new(0..1, 0..1)),
+                text: Rope::from_str("edited\n"),
+                generation: 2,
+                since: Instant::now(),
+            },
+        );
+        let cancel = Arc::new(AtomicBool::new(false));
+        service.diffs_in_flight.insert(
+            path.clone(),
+            InFlightDiff {
+                generation: 1,
+                edit: None,
+                cancel: cancel.clone(),
+            },
+        );
+
+        insert_test_diff(&mut service, 0);
+
+        assert!(cancel.load(Ordering::Relaxed));
+        assert!(service.diffs_in_flight.is_empty());
+        assert_eq!(service.pending_diffs[&path].edit, None);
+    }
+
     #[tokio::test]
     async fn diff_cache_keeps_refreshed_entries() {
         let mut service = VcsService::new(VcsConfig::default());
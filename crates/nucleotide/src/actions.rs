@@ -14,6 +14,25 @@ pub mod project_tree {
     }
 }
 
+pub mod app_menu {
+    use std::path::PathBuf;
+
+    /// Brings an open window to the front from the Window menu.
+    #[derive(Clone, PartialEq, Debug, gpui::Action)]
+    #[action(namespace = app_menu, no_json)]
+    pub struct ActivateWindow {
+        pub window_id: u64,
+    }
+
+    /// Opens a file or project from the Open Recent submenu.
+    #[derive(Clone, PartialEq, Debug, gpui::Action)]
+    #[action(namespace = app_menu, no_json)]
+    pub struct OpenRecent {
+        pub path: PathBuf,
+        pub directory: bool,
+    }
+}
+
 pub(crate) mod tab_menus {
     use crate::workspace::{TabBarNewMenuIntent, TabBarSplitMenuIntent, TabContextMenuIntent};
 
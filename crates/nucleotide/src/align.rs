@@ -0,0 +1,157 @@
+// ABOUTME: Aligns the selected lines on a delimiter such as `=` or `:` by padding with spaces
+// ABOUTME: Previews the delimiters that will line up as selections before the edit is applied
+
+use helix_core::{Range, Rope, RopeSlice, Selection, Tendril, Transaction};
+use regex::Regex;
+
+use crate::reflow::display_width;
+
+/// Prompt command asking for a delimiter, or aligning on the one given.
+pub const ALIGN_COMMAND: &str = "align-by";
+/// Prompt commands for aligning, with completion descriptions.
+pub const ALIGN_COMMANDS: &[(&str, &str)] = &[(
+    ALIGN_COMMAND,
+    "Align the selected lines on a delimiter, or on a /regex/",
+)];
+/// Label of the prompt asking for the delimiter.
+pub const ALIGN_PROMPT: &str = "align:";
+
+/// Regex for a delimiter typed at the prompt: the text itself, or a regex
+/// when wrapped in slashes. `None` for empty input.
+pub fn delimiter_regex(input: &str) -> Result<Option<Regex>, String> {
+    if input.is_empty() {
+        return Ok(None);
+    }
+    let pattern = match input
+        .strip_prefix('/')
+        .and_then(|rest| rest.strip_suffix('/'))
+        .filter(|pattern| !pattern.is_empty())
+    {
+        Some(pattern) => pattern.to_string(),
+        None => regex::escape(input),
+    };
+    Regex::new(&pattern)
+        .map(Some)
+        .map_err(|error| format!("Invalid regex: {error}"))
+}
+
+/// First delimiter on a line, as document char positions and the display
+/// column it starts at.
+struct Delimiter {
+    start: usize,
+    end: usize,
+    column: usize,
+}
+
+/// The first non-empty match on each line covered by the selection.
+fn delimiters(
+    text: RopeSlice,
+    selection: &Selection,
+    regex: &Regex,
+    tab_width: usize,
+) -> Vec<Delimiter> {
+    let mut lines = selection
+        .ranges()
+        .iter()
+        .flat_map(|range| {
+            let (start, end) = range.line_range(text);
+            start..=end
+        })
+        .collect::<Vec<_>>();
+    lines.sort_unstable();
+    lines.dedup();
+
+    lines
+        .into_iter()
+        .filter_map(|line| {
+            let content = text.line(line).to_string();
+            let content = content.trim_end_matches(['\n', '\r']);
+            let found = regex
+                .find_iter(content)
+                .find(|found| found.start() != found.end())?;
+            let before = &content[..found.start()];
+            let start = text.line_to_char(line) + before.chars().count();
+            Some(Delimiter {
+                start,
+                end: start + found.as_str().chars().count(),
+                column: display_width(before, tab_width.max(1)),
+            })
+        })
+        .collect()
+}
+
+/// Pads the selected lines with spaces before their first delimiter so the
+/// delimiters share a column. Returns `None` when fewer than two lines have
+/// one, or they are aligned already.
+pub fn align_transaction(
+    text: &Rope,
+    selection: &Selection,
+    regex: &Regex,
+    tab_width: usize,
+) -> Option<Transaction> {
+    let delimiters = delimiters(text.slice(..), selection, regex, tab_width);
+    if delimiters.len() < 2 {
+        return None;
+    }
+    let target = delimiters.iter().map(|delimiter| delimiter.column).max()?;
+    let changes = delimiters
+        .iter()
+        .filter(|delimiter| delimiter.column < target)
+        .map(|delimiter| {
+            let padding = " ".repeat(target - delimiter.column);
+            (
+                delimiter.start,
+                delimiter.start,
+                Some(Tendril::from(padding)),
+            )
+        })
+        .collect::<Vec<_>>();
+    (!changes.is_empty()).then(|| Transaction::change(text, changes.into_iter()))
+}
+
+/// Selection of the delimiters [`align_transaction`] would line up, to show
+/// them before aligning. `None` when no line has one.
+pub fn preview_selection(
+    text: &Rope,
+    selection: &Selection,
+    regex: &Regex,
+    tab_width: usize,
+) -> Option<Selection> {
+    let ranges = delimiters(text.slice(..), selection, regex, tab_width)
+        .into_iter()
+        .map(|delimiter| Range::new(delimiter.start, delimiter.end))
+        .collect::<Vec<_>>();
+    (!ranges.is_empty()).then(|| Selection::new(ranges.into(), 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selected_lines_align_on_their_first_delimiter() {
+        let mut text = Rope::from("a = 1\nlonger = 2 = 3\nno delimiter\n\tb = 4\n");
+        let selection = Selection::single(0, text.len_chars());
+        let regex = delimiter_regex("=").unwrap().unwrap();
+
+        let preview = preview_selection(&text, &selection, &regex, 4).unwrap();
+        assert_eq!(
+            preview.ranges(),
+            &[Range::new(2, 3), Range::new(13, 14), Range::new(37, 38)]
+        );
+
+        let transaction = align_transaction(&text, &selection, &regex, 4).unwrap();
+        assert!(transaction.apply(&mut text));
+        assert_eq!(text, "a      = 1\nlonger = 2 = 3\nno delimiter\n\tb  = 4\n");
+        assert!(align_transaction(&text, &selection, &regex, 4).is_none());
+    }
+
+    #[test]
+    fn slashes_make_the_delimiter_a_regex() {
+        let regex = delimiter_regex("/:+/").unwrap().unwrap();
+        assert!(regex.is_match("a:: b"));
+        assert!(!delimiter_regex(".").unwrap().unwrap().is_match("a"));
+        assert!(delimiter_regex("").unwrap().is_none());
+        assert!(delimiter_regex("/(/").is_err());
+    }
+}
@@ -0,0 +1,459 @@
+// ABOUTME: Insert-mode hooks for paste re-indent, surround-with-pair, type-over and tag renaming
+// ABOUTME: Runs around Helix's own character and paste handling so each keystroke edits once
+
+use std::ops::Range as CharRange;
+use std::sync::LazyLock;
+
+use helix_core::{
+    Range, Rope, Selection, Tendril, Transaction,
+    auto_pairs::{self, AutoPairs},
+    graphemes::next_grapheme_boundary,
+    syntax::Syntax,
+};
+use helix_term::{commands, events::PostInsertChar};
+use helix_view::{DocumentId, Editor, ViewId, document::Mode};
+
+use crate::config::EditingConfig;
+
+/// Register holding a re-indented copy of the clipboard while it is pasted.
+/// A private-use character, so it never collides with a user register.
+pub const REINDENTED_PASTE_REGISTER: char = '\u{f8ff}';
+
+/// Pairs used for surrounding when Helix's auto-pairs are turned off.
+static DEFAULT_AUTO_PAIRS: LazyLock<AutoPairs> = LazyLock::new(AutoPairs::default);
+
+/// Where the first line of a paste lands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasteStart {
+    /// After other text on the line; the first line is kept as is
+    MidLine,
+    /// After the line's indentation; the first line loses its own
+    AtIndent,
+    /// At the start of a line, as for linewise pastes
+    LineStart,
+}
+
+fn leading_whitespace(line: &str) -> &str {
+    let end = line.len() - line.trim_start_matches([' ', '\t']).len();
+    &line[..end]
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// `text` with its common indentation replaced by `indent`, keeping the
+/// lines' indentation relative to each other. Blank lines lose their
+/// whitespace. Returns `None` when nothing changes.
+pub fn reindent_paste(text: &str, indent: &str, start: PasteStart) -> Option<String> {
+    let lines = text.split_inclusive('\n').collect::<Vec<_>>();
+    let skip = usize::from(start == PasteStart::MidLine);
+    if lines.len() <= skip {
+        return None;
+    }
+
+    let common = lines[skip..]
+        .iter()
+        .filter(|line| !is_blank(line))
+        .map(|line| leading_whitespace(line))
+        .reduce(|common, leading| {
+            let shared = common
+                .bytes()
+                .zip(leading.bytes())
+                .take_while(|(a, b)| a == b)
+                .count();
+            &common[..shared]
+        })?;
+
+    let mut reindented = String::with_capacity(text.len());
+    for (index, line) in lines.iter().enumerate() {
+        if index < skip {
+            reindented.push_str(line);
+        } else if is_blank(line) {
+            reindented.push_str(line.trim_start_matches([' ', '\t']));
+        } else {
+            if !(index == 0 && start == PasteStart::AtIndent) {
+                reindented.push_str(indent);
+            }
+            reindented.push_str(&line[common.len()..]);
+        }
+    }
+    (reindented != text).then_some(reindented)
+}
+
+/// Stages a re-indented copy of the clipboard in
+/// [`REINDENTED_PASTE_REGISTER`] for a paste in `mode`. Returns false, with
+/// nothing staged, when the plain clipboard should be pasted: several
+/// cursors or clipboard values, or text that needs no change.
+pub fn stage_reindented_paste(editor: &mut Editor, mode: Mode) -> bool {
+    let view_id = editor.tree.focus;
+    let Some(doc) = editor
+        .tree
+        .try_get(view_id)
+        .and_then(|view| editor.documents.get(&view.doc))
+    else {
+        return false;
+    };
+    let selection = doc.selection(view_id);
+    if selection.len() != 1 {
+        return false;
+    }
+    let Some(values) = editor.registers.read('+', editor) else {
+        return false;
+    };
+    let values = values.map(|value| value.into_owned()).collect::<Vec<_>>();
+    let [clipboard] = values.as_slice() else {
+        return false;
+    };
+
+    let text = doc.text().slice(..);
+    let range = selection.primary();
+    let linewise = clipboard.ends_with('\n');
+    let pos = match mode {
+        Mode::Normal => range.from(),
+        Mode::Insert | Mode::Select => range.cursor(text),
+    };
+    let line = text.char_to_line(pos);
+    let line_text = text.line(line).to_string();
+    let indent = leading_whitespace(&line_text);
+    let before_cursor = pos - text.line_to_char(line);
+    let start = if mode == Mode::Normal && linewise {
+        PasteStart::LineStart
+    } else if before_cursor > 0 && before_cursor <= indent.chars().count() {
+        PasteStart::AtIndent
+    } else {
+        PasteStart::MidLine
+    };
+
+    let Some(reindented) = reindent_paste(clipboard, indent, start) else {
+        return false;
+    };
+    editor
+        .registers
+        .write(REINDENTED_PASTE_REGISTER, vec![reindented])
+        .is_ok()
+}
+
+/// Whether every range covers more than one grapheme, so it selects text
+/// rather than being a cursor.
+fn selects_text(text: &Rope, selection: &Selection) -> bool {
+    let slice = text.slice(..);
+    selection
+        .ranges()
+        .iter()
+        .all(|range| next_grapheme_boundary(slice, range.from()) < range.to())
+}
+
+/// Wraps every range of `selection` in `open` and `close`, keeping the
+/// wrapped text selected.
+pub fn surround_transaction(
+    text: &Rope,
+    selection: &Selection,
+    open: char,
+    close: char,
+) -> Transaction {
+    let mut changes = Vec::with_capacity(selection.len() * 2);
+    let mut ranges = Vec::with_capacity(selection.len());
+    for (index, range) in selection.ranges().iter().enumerate() {
+        changes.push((
+            range.from(),
+            range.from(),
+            Some(Tendril::from(open.to_string())),
+        ));
+        changes.push((
+            range.to(),
+            range.to(),
+            Some(Tendril::from(close.to_string())),
+        ));
+        let shift = index * 2 + 1;
+        ranges.push(Range::new(range.anchor + shift, range.head + shift));
+    }
+    Transaction::change(text, changes.into_iter())
+        .with_selection(Selection::new(ranges.into(), selection.primary_index()))
+}
+
+/// Syntax node kinds, by substring, whose text is prose rather than code.
+const PROSE_NODE_KINDS: &[&str] = &["comment", "string"];
+
+/// Whether `byte` lies inside a comment or string node. A comment that
+/// doesn't close with `*/` runs to the end of its line, so a cursor right
+/// after it is still inside.
+fn in_comment_or_string(syntax: &Syntax, text: &Rope, byte: usize) -> bool {
+    let Some(mut node) = syntax.named_descendant_for_byte_range(byte as u32, byte as u32) else {
+        return false;
+    };
+    loop {
+        let kind = node.kind();
+        if PROSE_NODE_KINDS.iter().any(|prose| kind.contains(prose)) {
+            let range = node.byte_range();
+            let (start, end) = (range.start as usize, range.end as usize);
+            let end_char = text.byte_to_char(end);
+            let open_ended = kind.contains("comment")
+                && text.slice(end_char.saturating_sub(2)..end_char) != "*/";
+            if start < byte && (byte < end || (open_ended && byte == end)) {
+                return true;
+            }
+        }
+        let Some(parent) = node.parent() else {
+            return false;
+        };
+        node = parent;
+    }
+}
+
+/// Handles a typed character before Helix does, for the settings Helix has
+/// no equivalent of. Returns true when the character was inserted here.
+pub fn insert_char(config: &EditingConfig, cx: &mut commands::Context<'_>, ch: char) -> bool {
+    if !config.surround_selection
+        && config.type_over
+        && config.auto_close_pairs_in_comments_and_strings
+        && config.languages.is_empty()
+    {
+        return false;
+    }
+    let view_id = cx.editor.tree.focus;
+    let Some(view) = cx.editor.tree.try_get(view_id) else {
+        return false;
+    };
+    let doc_id = view.doc;
+    let Some(doc) = cx.editor.documents.get(&doc_id) else {
+        return false;
+    };
+    let loader = cx.editor.syn_loader.load();
+    let doc_pairs = doc.auto_pairs(cx.editor, &loader, view);
+    let text = doc.text();
+    let selection = doc.selection(view_id);
+    let slice = text.slice(..);
+    let language = doc.language_name();
+
+    let surround = config
+        .surround_selection_for(language)
+        .then(|| doc_pairs.unwrap_or(&DEFAULT_AUTO_PAIRS).get(ch))
+        .flatten()
+        .filter(|pair| pair.open == ch && selects_text(text, selection))
+        .map(|pair| surround_transaction(text, selection, pair.open, pair.close));
+
+    // The language's setting decides whether a pair is closed, unless the
+    // cursor is in a comment or string where pairs are turned off.
+    let cursor = slice.char_to_byte(selection.primary().cursor(slice));
+    let pairs_wanted = config
+        .auto_close_pairs_for(language)
+        .unwrap_or(doc_pairs.is_some())
+        && (config.auto_close_pairs_in_comments_and_strings
+            || !doc
+                .syntax()
+                .is_some_and(|syntax| in_comment_or_string(syntax, text, cursor)));
+    // Helix has pairs off everywhere; close them here for this language.
+    let paired = (surround.is_none() && pairs_wanted && doc_pairs.is_none())
+        .then(|| auto_pairs::hook(text, selection, ch, &DEFAULT_AUTO_PAIRS))
+        .flatten();
+    // Helix's auto-pairs step over a matching closing character; insert it
+    // plainly instead when type-over is off. Where pairs are unwanted, an
+    // opening character is inserted plainly too.
+    let plain = doc_pairs
+        .and_then(|pairs| pairs.get(ch))
+        .filter(|pair| {
+            let steps_over = pair.close == ch
+                && selection
+                    .ranges()
+                    .iter()
+                    .any(|range| slice.get_char(range.cursor(slice)) == Some(ch));
+            if steps_over {
+                !config.type_over
+            } else {
+                !pairs_wanted
+            }
+        })
+        .map(|_| Transaction::insert(text, selection, Tendril::from(ch.to_string())));
+
+    let surrounded = surround.is_some();
+    let Some(transaction) = surround.or(paired).or(plain) else {
+        return false;
+    };
+    drop(loader);
+    let Some(doc) = cx.editor.documents.get_mut(&doc_id) else {
+        return false;
+    };
+    doc.apply(&transaction, view_id);
+    if !surrounded {
+        helix_event::dispatch(PostInsertChar { c: ch, cx });
+    }
+    true
+}
+
+/// Syntax nodes of opening and closing tags in the HTML and JSX grammars.
+const OPENING_TAG_KINDS: &[&str] = &["start_tag", "jsx_opening_element"];
+const CLOSING_TAG_KINDS: &[&str] = &["end_tag", "jsx_closing_element"];
+
+/// The names of an opening tag holding the cursor and of its closing tag,
+/// captured before a keystroke so the closing tag can follow the edit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagPair {
+    pub doc_id: DocumentId,
+    pub view_id: ViewId,
+    pub open: CharRange<usize>,
+    pub close: CharRange<usize>,
+    pub name: String,
+    pub len_chars: usize,
+}
+
+/// Char range of a syntax node's byte range.
+fn char_range(text: &Rope, bytes: CharRange<u32>) -> CharRange<usize> {
+    text.byte_to_char(bytes.start as usize)..text.byte_to_char(bytes.end as usize)
+}
+
+/// The tag pair whose opening tag name holds the cursor of the focused view,
+/// when it has a single cursor and the closing tag has the same name.
+pub fn tag_pair_at_cursor(editor: &Editor) -> Option<TagPair> {
+    let view_id = editor.tree.focus;
+    let doc_id = editor.tree.try_get(view_id)?.doc;
+    let doc = editor.documents.get(&doc_id)?;
+    let selection = doc.selection(view_id);
+    if selection.len() != 1 {
+        return None;
+    }
+    let text = doc.text();
+    let cursor = selection.primary().cursor(text.slice(..));
+    let byte = text.char_to_byte(cursor) as u32;
+
+    let syntax = doc.syntax()?;
+    let mut node = syntax.named_descendant_for_byte_range(byte, byte)?;
+    while !OPENING_TAG_KINDS.contains(&node.kind()) {
+        node = node.parent()?;
+    }
+    // A tag's name is its first named child.
+    let open = char_range(text, node.named_child(0)?.byte_range());
+    if !open.contains(&cursor) && open.end != cursor {
+        return None;
+    }
+    let element = node.parent()?;
+    let closing = (0..element.named_child_count())
+        .rev()
+        .filter_map(|index| element.named_child(index))
+        .find(|child| CLOSING_TAG_KINDS.contains(&child.kind()))?;
+    let close = char_range(text, closing.named_child(0)?.byte_range());
+
+    let name = text.slice(open.clone()).to_string();
+    (text.slice(close.clone()) == name.as_str()).then(|| TagPair {
+        doc_id,
+        view_id,
+        open,
+        close,
+        name,
+        len_chars: text.len_chars(),
+    })
+}
+
+fn is_tag_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || matches!(ch, '-' | '_' | '.' | ':'))
+}
+
+/// Renames the closing tag of `pair` after an edit that left the cursor at
+/// `cursor` inside the opening tag name. `None` when the edit was elsewhere
+/// or the opening tag no longer has a valid name.
+pub fn tag_rename_transaction(text: &Rope, pair: &TagPair, cursor: usize) -> Option<Transaction> {
+    let delta = text.len_chars() as isize - pair.len_chars as isize;
+    let open_end = pair.open.end.checked_add_signed(delta)?;
+    let close =
+        pair.close.start.checked_add_signed(delta)?..pair.close.end.checked_add_signed(delta)?;
+    if delta == 0 || !(pair.open.start..=open_end).contains(&cursor) || close.end > text.len_chars()
+    {
+        return None;
+    }
+    let name = text.slice(pair.open.start..open_end).to_string();
+    if !is_tag_name(&name) || text.slice(close.clone()) != pair.name.as_str() {
+        return None;
+    }
+    Some(Transaction::change(
+        text,
+        std::iter::once((close.start, close.end, Some(Tendril::from(name)))),
+    ))
+}
+
+/// Mirrors an edit of the opening tag name in `pair` onto its closing tag.
+/// The rename joins the insert-mode edit, so one undo reverts both.
+pub fn rename_closing_tag(editor: &mut Editor, pair: &TagPair) {
+    if editor.tree.focus != pair.view_id || editor.mode() != Mode::Insert {
+        return;
+    }
+    let Some(doc) = editor.documents.get_mut(&pair.doc_id) else {
+        return;
+    };
+    let text = doc.text();
+    let cursor = doc.selection(pair.view_id).primary().cursor(text.slice(..));
+    if let Some(transaction) = tag_rename_transaction(text, pair, cursor) {
+        doc.apply(&transaction, pair.view_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pastes_take_the_indentation_at_the_cursor() {
+        let copied = "if ready {\n        start();\n    }\n";
+        assert_eq!(
+            reindent_paste(copied, "\t", PasteStart::MidLine).as_deref(),
+            Some("if ready {\n\t    start();\n\t}\n")
+        );
+
+        let lines = "    a\n\n      b\n";
+        assert_eq!(
+            reindent_paste(lines, "  ", PasteStart::LineStart).as_deref(),
+            Some("  a\n\n    b\n")
+        );
+        assert_eq!(
+            reindent_paste(lines, "  ", PasteStart::AtIndent).as_deref(),
+            Some("a\n\n    b\n")
+        );
+        assert_eq!(reindent_paste("word", "    ", PasteStart::MidLine), None);
+        assert_eq!(reindent_paste("a\nb\n", "", PasteStart::LineStart), None);
+    }
+
+    #[test]
+    fn surrounding_keeps_the_wrapped_text_selected() {
+        let mut text = Rope::from("one two");
+        let selection = Selection::new(
+            [Range::new(0, 3), Range::new(7, 4)].into_iter().collect(),
+            1,
+        );
+        assert!(selects_text(&text, &selection));
+
+        let transaction = surround_transaction(&text, &selection, '(', ')');
+        assert!(transaction.apply(&mut text));
+        assert_eq!(text, "(one) (two)");
+        let selection = transaction.selection().unwrap();
+        assert_eq!(selection.ranges(), &[Range::new(1, 4), Range::new(10, 7)]);
+        assert_eq!(selection.primary_index(), 1);
+
+        assert!(!selects_text(&Rope::from("ab"), &Selection::single(0, 1)));
+    }
+
+    #[test]
+    fn closing_tags_follow_edits_of_the_opening_name() {
+        let pair = TagPair {
+            doc_id: DocumentId::default(),
+            view_id: ViewId::default(),
+            open: 1..4,
+            close: 12..15,
+            name: "div".to_string(),
+            len_chars: 16,
+        };
+        // "<div>hello</div>" with "s" typed after "div".
+        let mut text = Rope::from("<divs>hello</div>");
+        let transaction = tag_rename_transaction(&text, &pair, 5).unwrap();
+        assert!(transaction.apply(&mut text));
+        assert_eq!(text, "<divs>hello</divs>");
+
+        // A space ends the name, so the closing tag is left alone.
+        let spaced = Rope::from("<div >hello</div>");
+        assert!(tag_rename_transaction(&spaced, &pair, 5).is_none());
+        // Edits outside the opening name are not mirrored.
+        let body = Rope::from("<div>hello!</div>");
+        assert!(tag_rename_transaction(&body, &pair, 11).is_none());
+    }
+}
@@ -25,6 +25,9 @@ use helix_view::{
     keyboard::{KeyCode, KeyModifiers},
 };
 use nucleotide_logging::{PerfTimer, debug, info};
+
+use super::editing_hooks;
+use crate::config::EditingConfig;
 use std::{
     borrow::Cow,
     num::NonZeroUsize,
@@ -98,6 +101,8 @@ pub enum NativePromptRequest {
     ReverseSearch,
     GlobalSearch,
     RegexSelection(crate::types::RegexSelectionAction),
+    /// The GUI find bar, opened by the platform find shortcut.
+    FindBar,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -131,6 +136,11 @@ pub enum EditorSemanticAction {
     FindPrevious,
     NextBuffer,
     PreviousBuffer,
+    DuplicateLines,
+    MoveLinesUp,
+    MoveLinesDown,
+    JoinLines,
+    ToggleComment,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -174,6 +184,11 @@ impl EditorInputBridge {
         }
     }
 
+    /// Sets the paste, bracket and tag conveniences applied on top of Helix.
+    pub fn set_editing_config(&mut self, config: EditingConfig) {
+        self.native_commands.editing = config;
+    }
+
     pub fn handle_key(
         &mut self,
         key: KeyEvent,
@@ -211,9 +226,16 @@ impl EditorInputBridge {
         let mut semantic_shortcut_requested = None;
         let mut viewport_scroll_requested = None;
         let mut viewport_cursor_requested = None;
+        let tag_pair = (self.native_commands.editing.rename_paired_tags
+            && editor.mode() == Mode::Insert)
+            .then(|| editing_hooks::tag_pair_at_cursor(editor))
+            .flatten();
         let native_input_result = self
             .native_commands
             .handle_key(key, compositor, editor, jobs);
+        if let Some(pair) = &tag_pair {
+            editing_hooks::rename_closing_tag(editor, pair);
+        }
         let reset_diff_change_executed = self.native_commands.take_reset_diff_change_executed();
 
         match native_input_result {
@@ -316,7 +338,7 @@ impl EditorInputBridge {
         let mut prompt_requested = None;
 
         if action == EditorSemanticAction::Find {
-            prompt_requested = Some(NativePromptRequest::Search);
+            prompt_requested = Some(NativePromptRequest::FindBar);
         } else {
             let commands: &[&MappableCommand] = match action {
                 EditorSemanticAction::Undo => &[&MappableCommand::undo],
@@ -332,6 +354,11 @@ impl EditorInputBridge {
                 EditorSemanticAction::FindPrevious => &[&MappableCommand::search_prev],
                 EditorSemanticAction::NextBuffer => &[&MappableCommand::goto_next_buffer],
                 EditorSemanticAction::PreviousBuffer => &[&MappableCommand::goto_previous_buffer],
+                EditorSemanticAction::JoinLines => &[&MappableCommand::join_selections],
+                EditorSemanticAction::ToggleComment => &[&MappableCommand::toggle_comments],
+                EditorSemanticAction::DuplicateLines
+                | EditorSemanticAction::MoveLinesUp
+                | EditorSemanticAction::MoveLinesDown => &[],
                 EditorSemanticAction::Find => unreachable!(),
             };
             let callbacks = {
@@ -346,9 +373,13 @@ impl EditorInputBridge {
                 let mut last_mode = context.editor.mode();
                 if action == EditorSemanticAction::Paste {
                     self.native_commands.prepare_non_key_paste(&mut context);
-                    execute_clipboard_paste(&mut context, &mut last_mode);
+                    execute_clipboard_paste(
+                        &mut context,
+                        &mut last_mode,
+                        self.native_commands.editing.paste_reindent,
+                    );
                     context.editor.count = None;
-                } else {
+                } else if !execute_line_edit(action, context.editor) {
                     for command in commands {
                         execute_native_command(command, &mut context, &mut last_mode);
                     }
@@ -389,6 +420,7 @@ struct NativeCommandInput {
     current_insert_replay: InsertReplay,
     last_insert_replay: Option<InsertReplay>,
     reset_diff_change_executed: bool,
+    editing: EditingConfig,
 }
 
 enum NativeInputResult {
@@ -487,6 +519,7 @@ impl NativeCommandInput {
             current_insert_replay: InsertReplay::default(),
             last_insert_replay: None,
             reset_diff_change_executed: false,
+            editing: EditingConfig::default(),
         }
     }
 
@@ -755,7 +788,7 @@ impl NativeCommandInput {
 
                 if is_native_insert_paste_shortcut(key) {
                     let mut last_mode = mode;
-                    execute_clipboard_paste(context, &mut last_mode);
+                    execute_clipboard_paste(context, &mut last_mode, self.editing.paste_reindent);
                     self.current_insert_replay.keys.push(key);
                     return NativeCommandResult::Handled(Vec::new());
                 }
@@ -780,13 +813,15 @@ impl NativeCommandInput {
                         }
                         FallbackShortcut::Editor(action) => {
                             self.current_insert_replay.keys.push(key);
-                            execute_editor_semantic_action(action, context)
+                            execute_editor_semantic_action(action, context, &self.editing)
                         }
                     };
                 }
 
                 if let Some(ch) = key.char() {
-                    commands::insert::insert_char(context, ch);
+                    if !editing_hooks::insert_char(&self.editing, context, ch) {
+                        commands::insert::insert_char(context, ch);
+                    }
                     self.current_insert_replay.keys.push(key);
                     NativeCommandResult::Handled(Vec::new())
                 } else {
@@ -946,7 +981,7 @@ impl NativeCommandInput {
                             NativeCommandResult::RequestSemanticShortcut(request)
                         }
                         FallbackShortcut::Editor(action) => {
-                            execute_editor_semantic_action(action, context)
+                            execute_editor_semantic_action(action, context, &self.editing)
                         }
                     };
                 }
@@ -1223,16 +1258,20 @@ fn execute_native_command(
 fn execute_editor_semantic_action(
     action: EditorSemanticAction,
     context: &mut commands::Context<'_>,
+    editing: &EditingConfig,
 ) -> NativeCommandResult {
     if action == EditorSemanticAction::Find {
         return NativeCommandResult::RequestPrompt {
             callbacks: Vec::new(),
-            request: NativePromptRequest::Search,
+            request: NativePromptRequest::FindBar,
         };
     }
     if action == EditorSemanticAction::Paste {
         let mut last_mode = context.editor.mode();
-        execute_clipboard_paste(context, &mut last_mode);
+        execute_clipboard_paste(context, &mut last_mode, editing.paste_reindent);
+        return NativeCommandResult::Handled(Vec::new());
+    }
+    if execute_line_edit(action, context.editor) {
         return NativeCommandResult::Handled(Vec::new());
     }
     let commands: &[&MappableCommand] = match action {
@@ -1249,6 +1288,11 @@ fn execute_editor_semantic_action(
         EditorSemanticAction::FindPrevious => &[&MappableCommand::search_prev],
         EditorSemanticAction::NextBuffer => &[&MappableCommand::goto_next_buffer],
         EditorSemanticAction::PreviousBuffer => &[&MappableCommand::goto_previous_buffer],
+        EditorSemanticAction::JoinLines => &[&MappableCommand::join_selections],
+        EditorSemanticAction::ToggleComment => &[&MappableCommand::toggle_comments],
+        EditorSemanticAction::DuplicateLines
+        | EditorSemanticAction::MoveLinesUp
+        | EditorSemanticAction::MoveLinesDown => unreachable!(),
         EditorSemanticAction::Find => unreachable!(),
     };
     let mut last_mode = context.editor.mode();
@@ -1258,10 +1302,58 @@ fn execute_editor_semantic_action(
     NativeCommandResult::Handled(Vec::new())
 }
 
-fn execute_clipboard_paste(context: &mut commands::Context<'_>, last_mode: &mut Mode) {
+/// Applies the duplicate and move line actions to the focused view. Returns
+/// false for actions that are not line edits.
+fn execute_line_edit(action: EditorSemanticAction, editor: &mut Editor) -> bool {
+    use crate::line_edits::{LineDirection, duplicate_lines_transaction, move_lines_transaction};
+
+    let direction = match action {
+        EditorSemanticAction::DuplicateLines => None,
+        EditorSemanticAction::MoveLinesUp => Some(LineDirection::Up),
+        EditorSemanticAction::MoveLinesDown => Some(LineDirection::Down),
+        _ => return false,
+    };
+    let view_id = editor.tree.focus;
+    let Some(doc) = editor
+        .tree
+        .try_get(view_id)
+        .and_then(|view| editor.documents.get_mut(&view.doc))
+    else {
+        return true;
+    };
+    let (text, selection) = (doc.text(), doc.selection(view_id));
+    let transaction = match direction {
+        None => Some(duplicate_lines_transaction(text, selection)),
+        Some(direction) => move_lines_transaction(text, selection, direction),
+    };
+    if let Some(transaction) = transaction {
+        doc.apply(&transaction, view_id);
+    }
+    true
+}
+
+/// Pastes the clipboard, re-indented to the cursor's line when `reindent`
+/// is set and the text needs it.
+fn execute_clipboard_paste(
+    context: &mut commands::Context<'_>,
+    last_mode: &mut Mode,
+    reindent: bool,
+) {
     let mode = context.editor.mode();
+    let register = if reindent && editing_hooks::stage_reindented_paste(context.editor, mode) {
+        editing_hooks::REINDENTED_PASTE_REGISTER
+    } else {
+        '+'
+    };
     if mode == Mode::Normal {
-        execute_native_command(&MappableCommand::paste_clipboard_before, context, last_mode);
+        if register == '+' {
+            execute_native_command(&MappableCommand::paste_clipboard_before, context, last_mode);
+        } else {
+            context.register = Some(register);
+            execute_native_command(&MappableCommand::paste_before, context, last_mode);
+            context.register = None;
+            context.editor.registers.remove(register);
+        }
         return;
     }
 
@@ -1275,12 +1367,15 @@ fn execute_clipboard_paste(context: &mut commands::Context<'_>, last_mode: &mut
         paste_register(
             context,
             KeyEvent {
-                code: KeyCode::Char('+'),
+                code: KeyCode::Char(register),
                 modifiers: KeyModifiers::empty(),
             },
         );
     }
     context.editor.autoinfo = previous_autoinfo;
+    if register != '+' {
+        context.editor.registers.remove(register);
+    }
 
     if mode == Mode::Select {
         context.editor.mode = Mode::Normal;
@@ -2178,6 +2273,7 @@ pub fn resolve_fallback_shortcut(
             Intent::RunFileTests,
         ),
         ('b', KeyModifiers::CONTROL, Intent::ToggleFileTree),
+        ('`', KeyModifiers::CONTROL, Intent::ActivateLastUsedTab),
     ];
     if let Some((_, _, intent)) = custom
         .into_iter()
@@ -2200,6 +2296,7 @@ pub fn resolve_fallback_shortcut(
         ('o', primary | KeyModifiers::SHIFT, Intent::OpenDirectory),
         ('s', primary, Intent::Save),
         ('w', primary, Intent::CloseFile),
+        ('t', primary | KeyModifiers::SHIFT, Intent::ReopenClosedTab),
         ('n', primary, Intent::NewFile),
         ('p', primary, Intent::ShowFileFinder),
         (
@@ -2270,6 +2367,33 @@ pub fn resolve_fallback_shortcut(
     if let Some(action) = semantic {
         return Some(EditorFallback(action));
     }
+    if key_is(key, '/', primary) {
+        return Some(EditorFallback(Editor::ToggleComment));
+    }
+    if key_is(key, 'j', primary) {
+        return Some(EditorFallback(Editor::JoinLines));
+    }
+
+    match (key.code, key.modifiers) {
+        (KeyCode::Up, KeyModifiers::ALT) => return Some(EditorFallback(Editor::MoveLinesUp)),
+        (KeyCode::Down, KeyModifiers::ALT) => return Some(EditorFallback(Editor::MoveLinesDown)),
+        (KeyCode::Down, modifiers) if modifiers == KeyModifiers::ALT | KeyModifiers::SHIFT => {
+            return Some(EditorFallback(Editor::DuplicateLines));
+        }
+        (KeyCode::Right, modifiers) if modifiers == primary | KeyModifiers::ALT => {
+            return Some(Workspace(Intent::ActivateNextTab));
+        }
+        (KeyCode::Left, modifiers) if modifiers == primary | KeyModifiers::ALT => {
+            return Some(Workspace(Intent::ActivatePreviousTab));
+        }
+        (KeyCode::Tab, KeyModifiers::CONTROL) => {
+            return Some(Workspace(Intent::SwitchRecentDocument));
+        }
+        (KeyCode::Tab, modifiers) if modifiers == KeyModifiers::CONTROL | KeyModifiers::SHIFT => {
+            return Some(Workspace(Intent::SwitchRecentDocumentReverse));
+        }
+        _ => {}
+    }
 
     let desktop = matches!(platform, TargetPlatform::Linux | TargetPlatform::Windows);
     match (key.code, key.modifiers) {
@@ -2277,14 +2401,6 @@ pub fn resolve_fallback_shortcut(
         (KeyCode::F(3), KeyModifiers::SHIFT) if desktop => {
             Some(EditorFallback(Editor::FindPrevious))
         }
-        (KeyCode::Tab, KeyModifiers::CONTROL) if desktop => {
-            Some(EditorFallback(Editor::NextBuffer))
-        }
-        (KeyCode::Tab, modifiers)
-            if desktop && modifiers == KeyModifiers::CONTROL | KeyModifiers::SHIFT =>
-        {
-            Some(EditorFallback(Editor::PreviousBuffer))
-        }
         (KeyCode::PageDown, KeyModifiers::CONTROL) if desktop => {
             Some(EditorFallback(Editor::NextBuffer))
         }
@@ -2780,6 +2896,27 @@ mod tests {
             ("C-,", TargetPlatform::Windows, Intent::OpenSettings),
             ("C-0", TargetPlatform::Linux, Intent::ResetFontSize),
             ("C-F4", TargetPlatform::Windows, Intent::CloseFile),
+            (
+                "Meta-A-right",
+                TargetPlatform::MacOS,
+                Intent::ActivateNextTab,
+            ),
+            (
+                "C-A-left",
+                TargetPlatform::Linux,
+                Intent::ActivatePreviousTab,
+            ),
+            ("C-`", TargetPlatform::Windows, Intent::ActivateLastUsedTab),
+            ("C-`", TargetPlatform::MacOS, Intent::ActivateLastUsedTab),
+            ("Meta-S-t", TargetPlatform::MacOS, Intent::ReopenClosedTab),
+            ("C-S-t", TargetPlatform::Linux, Intent::ReopenClosedTab),
+            ("C-tab", TargetPlatform::Linux, Intent::SwitchRecentDocument),
+            ("C-tab", TargetPlatform::MacOS, Intent::SwitchRecentDocument),
+            (
+                "C-S-tab",
+                TargetPlatform::Windows,
+                Intent::SwitchRecentDocumentReverse,
+            ),
         ] {
             assert_eq!(
                 resolve_fallback_shortcut(Mode::Normal, KeyEvent::from_str(key).unwrap(), platform),
@@ -2789,6 +2926,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn line_edit_shortcuts_resolve_to_editor_actions() {
+        use EditorSemanticAction as Action;
+
+        for (key, platform, action) in [
+            ("A-up", TargetPlatform::Linux, Action::MoveLinesUp),
+            ("A-down", TargetPlatform::MacOS, Action::MoveLinesDown),
+            ("A-S-down", TargetPlatform::Windows, Action::DuplicateLines),
+            ("C-/", TargetPlatform::Linux, Action::ToggleComment),
+            ("Meta-/", TargetPlatform::MacOS, Action::ToggleComment),
+            ("Meta-j", TargetPlatform::MacOS, Action::JoinLines),
+        ] {
+            assert_eq!(
+                resolve_fallback_shortcut(Mode::Insert, KeyEvent::from_str(key).unwrap(), platform),
+                Some(FallbackShortcut::Editor(action)),
+                "failed shortcut {key} on {platform:?}"
+            );
+        }
+    }
+
     #[test]
     fn translated_shifted_physical_chords_match_fallbacks() {
         let translated = |key: &str, key_char: &str, control: bool, platform: bool| {
@@ -2854,8 +3011,6 @@ mod tests {
             ("C-S-z", TargetPlatform::Linux, Action::Redo),
             ("F3", TargetPlatform::Linux, Action::FindNext),
             ("S-F3", TargetPlatform::Windows, Action::FindPrevious),
-            ("C-tab", TargetPlatform::Linux, Action::NextBuffer),
-            ("C-S-tab", TargetPlatform::Windows, Action::PreviousBuffer),
             ("C-pagedown", TargetPlatform::Linux, Action::NextBuffer),
             ("C-pageup", TargetPlatform::Windows, Action::PreviousBuffer),
             ("C-ins", TargetPlatform::Linux, Action::Copy),
@@ -2887,7 +3042,7 @@ mod tests {
             &mut jobs,
         );
 
-        assert_eq!(outcome.prompt_requested, Some(NativePromptRequest::Search));
+        assert_eq!(outcome.prompt_requested, Some(NativePromptRequest::FindBar));
         assert_eq!(focused_selection_fragments(&editor), before);
     }
 
@@ -1,6 +1,7 @@
 // ABOUTME: Application module decomposition for V2 event system migration
 // ABOUTME: Contains domain-specific handlers and main Application implementation
 
+pub mod editing_hooks;
 pub mod editor_input;
 #[cfg(feature = "terminal-emulator-core")]
 pub mod terminal_handler;
@@ -26,6 +27,27 @@ impl TerminalRuntimeHandle {
     ) -> bool {
         false
     }
+
+    pub fn paste(
+        &self,
+        _id: nucleotide_events::v2::terminal::TerminalId,
+        _text: &str,
+        _bracketed: bool,
+    ) -> bool {
+        false
+    }
+
+    pub fn queue_input(
+        &self,
+        _id: nucleotide_events::v2::terminal::TerminalId,
+        _input: nucleotide_terminal::session::SessionInput,
+    ) -> bool {
+        false
+    }
+
+    pub fn shutdown_all(&self) -> usize {
+        0
+    }
 }
 pub use workspace_file_ops::WorkspaceFileOpHandler;
 
@@ -48,6 +70,9 @@ use helix_view::{
     },
     input::KeyEvent,
     keyboard::{KeyCode, KeyModifiers},
+    language_server_merge::{
+        LanguageServerMergePolicies, LanguageServerMergePolicy, MergeStrategy,
+    },
     view::View,
 };
 use nucleotide_events::{ProjectLspCommand, ProjectLspCommandError};
@@ -147,11 +172,18 @@ impl nucleotide_lsp::EnvironmentProvider for ProjectEnvironmentProvider {
 
 pub struct WorkspaceDocumentSaveHandler {
     workspace_backend: WorkspaceBackendHandle,
+    save_config: crate::config::SaveConfig,
 }
 
 impl WorkspaceDocumentSaveHandler {
-    pub fn new(workspace_backend: WorkspaceBackendHandle) -> Self {
-        Self { workspace_backend }
+    pub fn new(
+        workspace_backend: WorkspaceBackendHandle,
+        save_config: crate::config::SaveConfig,
+    ) -> Self {
+        Self {
+            workspace_backend,
+            save_config,
+        }
     }
 }
 
@@ -162,8 +194,20 @@ impl helix_view::editor::DocumentSaveHandler for WorkspaceDocumentSaveHandler {
         path: Option<PathBuf>,
         force: bool,
     ) -> Option<anyhow::Result<helix_view::document::DocumentSavedEventFuture>> {
-        let target_path =
-            workspace_document_save_target(&self.workspace_backend.identity(), doc, path)?;
+        if should_use_native_save_for_settings_file(doc.path(), path.as_deref()) {
+            return None;
+        }
+
+        let Some(target_path) =
+            workspace_document_save_target(&self.workspace_backend.identity(), doc, path.clone())
+        else {
+            let save_config = self.save_config.clone();
+            let save = doc.save_with(path, force, move |save| {
+                let policy = save_config.policy_for(&save.path);
+                crate::local_save::write_local_document(save, policy)
+            });
+            return Some(save.map(|future| future.boxed()));
+        };
         let workspace_backend = self.workspace_backend.clone();
         let save = doc.save_with(Some(target_path), force, move |save| {
             let workspace_backend = workspace_backend.clone();
@@ -503,10 +547,84 @@ fn project_lsp_config(config: &crate::config::LspConfig) -> nucleotide_lsp::Proj
     nucleotide_lsp::ProjectLspConfig {
         enable_proactive_startup: config.project_lsp_startup,
         startup_timeout: Duration::from_millis(config.startup_timeout_ms),
+        crash_restart: nucleotide_lsp::CrashRestartPolicy {
+            enabled: config.restart.enabled,
+            initial_backoff: Duration::from_millis(config.restart.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.restart.max_backoff_ms),
+            max_restarts: config.restart.max_restarts,
+            stable_after: Duration::from_secs(config.restart.stable_after_secs),
+        },
         ..nucleotide_lsp::ProjectLspConfig::default()
     }
 }
 
+fn lsp_request_policy(config: &crate::config::LspConfig) -> nucleotide_lsp::LspRequestPolicy {
+    let requests = &config.requests;
+    nucleotide_lsp::LspRequestPolicy {
+        interactive_timeout: Duration::from_millis(requests.interactive_timeout_ms),
+        navigation_timeout: Duration::from_millis(requests.navigation_timeout_ms),
+        editing_timeout: Duration::from_millis(requests.editing_timeout_ms),
+        background_timeout: Duration::from_millis(requests.background_timeout_ms),
+        max_retries: requests.max_retries,
+        failure_threshold: requests.failure_threshold,
+        degraded_cooldown: Duration::from_secs(requests.degraded_cooldown_secs),
+        ..nucleotide_lsp::LspRequestPolicy::default()
+    }
+}
+
+fn language_server_merge_policies(
+    config: &crate::config::LspConfig,
+) -> LanguageServerMergePolicies {
+    let strategy = |strategy: crate::config::LspMergeStrategy| match strategy {
+        crate::config::LspMergeStrategy::All => MergeStrategy::All,
+        crate::config::LspMergeStrategy::First => MergeStrategy::First,
+    };
+    config
+        .merge
+        .iter()
+        .map(|(language, merge)| {
+            (
+                language.clone(),
+                LanguageServerMergePolicy {
+                    priority: merge.priority.clone(),
+                    completion: strategy(merge.completion),
+                    hover: strategy(merge.hover),
+                    diagnostics: strategy(merge.diagnostics),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Switches `doc_id` to the language configured for its file name in
+/// `[[file_associations]]`, relaunching its language servers for the new language.
+pub(crate) fn apply_file_association_language(editor: &mut Editor, doc_id: DocumentId) {
+    let associations = nucleotide_ui::file_associations();
+    if associations.is_empty() {
+        return;
+    }
+    let loader = editor.syn_loader.load();
+    let Some(doc) = editor.document_mut(doc_id) else {
+        return;
+    };
+    let Some(language) = doc
+        .path()
+        .and_then(|path| path.file_name())
+        .and_then(|name| name.to_str())
+        .and_then(|name| associations.language_for(name))
+    else {
+        return;
+    };
+    if doc.language_name() == Some(language) {
+        return;
+    }
+    if let Err(error) = doc.set_language_by_language_id(language, &loader) {
+        warn!(%error, language, "Ignoring file association with an unknown language");
+        return;
+    }
+    editor.refresh_language_servers(doc_id);
+}
+
 fn canonical_project_lsp_root(workspace_root: &Path) -> PathBuf {
     if classify_workspace_location(workspace_root).is_remote() {
         return workspace_root.to_path_buf();
@@ -793,6 +911,27 @@ fn request_document_diagnostics_for_language_servers(
     });
 }
 
+/// True when the document's merge policy only takes diagnostics from a
+/// higher priority server than `server_id`.
+fn diagnostics_excluded_by_merge_policy(
+    editor: &Editor,
+    server_id: LanguageServerId,
+    uri: &Uri,
+    feature: syntax::config::LanguageServerFeature,
+) -> bool {
+    let Some(doc) = uri.as_path().and_then(|path| editor.document_by_path(path)) else {
+        return false;
+    };
+    doc.language_servers_with_feature(feature)
+        .any(|language_server| language_server.id() == server_id)
+        && !doc
+            .language_servers_for_merge(&editor.language_server_merge, feature, |policy| {
+                policy.diagnostics
+            })
+            .iter()
+            .any(|language_server| language_server.id() == server_id)
+}
+
 fn handle_pull_diagnostics_response(
     editor: &mut Editor,
     result: lsp::DocumentDiagnosticReportResult,
@@ -803,6 +942,16 @@ fn handle_pull_diagnostics_response(
     let lsp::DocumentDiagnosticReportResult::Report(report) = result else {
         return;
     };
+    if provider.language_server_id().is_some_and(|server_id| {
+        diagnostics_excluded_by_merge_policy(
+            editor,
+            server_id,
+            &uri,
+            syntax::config::LanguageServerFeature::PullDiagnostics,
+        )
+    }) {
+        return;
+    }
 
     let result_id = match report {
         lsp::DocumentDiagnosticReport::Full(report) => {
@@ -885,6 +1034,38 @@ pub enum LspCompletionTrigger {
     Incomplete,
 }
 
+/// In-flight LSP requests behind one completion, retries included, so they can
+/// be cancelled with `$/cancelRequest` once the user has typed past them.
+#[derive(Clone, Default)]
+pub struct CompletionCancellation {
+    requests: Arc<Mutex<Vec<(Arc<helix_lsp::Client>, helix_lsp::jsonrpc::Id)>>>,
+}
+
+impl CompletionCancellation {
+    fn track(&self, client: Arc<helix_lsp::Client>, id: helix_lsp::jsonrpc::Id) {
+        if let Ok(mut requests) = self.requests.lock() {
+            requests.push((client, id));
+        }
+    }
+
+    /// Cancels every tracked request. Requests that already finished are
+    /// ignored by the server.
+    pub fn cancel(&self) {
+        let requests = match self.requests.lock() {
+            Ok(mut requests) => std::mem::take(&mut *requests),
+            Err(_) => return,
+        };
+        for (client, id) in requests {
+            nucleotide_logging::debug!(
+                server_id = ?client.id(),
+                request_id = ?id,
+                "Cancelling superseded completion request"
+            );
+            client.cancel_request(id);
+        }
+    }
+}
+
 pub struct PendingCompletionRequest {
     prefix: String,
     retained_items: Vec<nucleotide_events::completion::CompletionItem>,
@@ -892,9 +1073,14 @@ pub struct PendingCompletionRequest {
     local_futures: FuturesOrdered<LocalCompletionFuture>,
     lsp_error: Option<anyhow::Error>,
     lsp_futures: FuturesOrdered<CompletionServerFuture>,
+    cancellation: CompletionCancellation,
 }
 
 impl PendingCompletionRequest {
+    pub fn cancellation(&self) -> CompletionCancellation {
+        self.cancellation.clone()
+    }
+
     pub async fn collect(
         self,
     ) -> anyhow::Result<(
@@ -910,6 +1096,7 @@ impl PendingCompletionRequest {
             mut local_futures,
             mut lsp_error,
             mut lsp_futures,
+            cancellation: _,
         } = self;
 
         let lsp_collect = async move {
@@ -1712,6 +1899,7 @@ use nucleotide_logging::{
 };
 use nucleotide_lsp::lsp_state::DiagnosticInfo;
 
+use crate::startup_profile::{StartupPhase, StartupProfile};
 use crate::types::{AppEvent, Update};
 use editor_input::EditorInputBridge;
 use gpui::EventEmitter;
@@ -1879,6 +2067,20 @@ impl ProjectLspSupervisor {
         Some(transition)
     }
 
+    /// Ends the active session, returning its root. Requests still waiting
+    /// on it are told the session went stale.
+    fn close_session(&mut self) -> Option<PathBuf> {
+        let previous_root = self.active_root.take()?;
+        self.generation = self.generation.wrapping_add(1).max(1);
+        self.retrying_servers.clear();
+        self.session_in_flight = false;
+        self.session_result = None;
+        for waiter in self.session_waiters.drain(..) {
+            let _ = waiter.send(Err(ProjectLspCommandError::StaleProjectSession));
+        }
+        Some(previous_root)
+    }
+
     fn complete_session(
         &mut self,
         generation: u64,
@@ -1921,6 +2123,15 @@ impl ProjectLspSupervisor {
     }
 }
 
+/// Language server whose process exited without the editor stopping it.
+#[derive(Debug, Clone)]
+struct CrashedLspServer {
+    server_id: LanguageServerId,
+    server_name: String,
+    language_id: Option<String>,
+    workspace_root: Option<PathBuf>,
+}
+
 struct PendingLspWorkspaceEdit {
     server_id: LanguageServerId,
     request_id: helix_lsp::jsonrpc::Id,
@@ -1960,6 +2171,8 @@ pub struct Application {
     pub jobs: Jobs,
     pub lsp_progress: LspProgressMap,
     pub lsp_state: Option<gpui::Entity<nucleotide_lsp::LspState>>,
+    /// Timeout, retry and circuit-breaker policy shared by LSP requests.
+    pub lsp_request_guard: nucleotide_lsp::LspRequestGuard,
     pub project_directory: Option<PathBuf>,
     pub workspace_backend: WorkspaceBackendHandle,
     pub event_bridge_rx: Option<event_bridge::BridgedEventReceiver>,
@@ -1977,6 +2190,11 @@ pub struct Application {
     project_env_overrides: HashMap<String, Option<String>>,
     prewarmed_lsp_startups: HashSet<(PathBuf, String, String)>,
     pending_lsp_workspace_edits: VecDeque<PendingLspWorkspaceEdit>,
+    /// Crashed servers waiting for the restart policy to run.
+    pending_lsp_crashes: Vec<CrashedLspServer>,
+    /// Crashed servers whose diagnostics are kept until a replacement
+    /// publishes its own, keyed by the crashed server id.
+    crashed_lsp_servers: HashMap<LanguageServerId, CrashedLspServer>,
     pub terminal_runtime: TerminalRuntimeHandle,
     maintenance_wake: Option<MaintenanceWake>,
 }
@@ -1985,6 +2203,8 @@ pub struct Application {
 pub struct InputKeyEvent {
     pub key: KeyEvent,
     pub is_held: bool,
+    /// Correlates the key with its latency trace, for keys typed into an editor
+    pub latency_id: Option<nucleotide_ui::InputLatencyId>,
 }
 
 impl InputKeyEvent {
@@ -1992,11 +2212,16 @@ impl InputKeyEvent {
         Self {
             key,
             is_held: false,
+            latency_id: None,
         }
     }
 
     pub fn from_key_down(key: KeyEvent, is_held: bool) -> Self {
-        Self { key, is_held }
+        Self {
+            key,
+            is_held,
+            latency_id: None,
+        }
     }
 }
 
@@ -2015,6 +2240,17 @@ impl InputEvent {
         Self::Key(InputKeyEvent::from_key_down(key, is_held))
     }
 
+    pub fn traced_key_down(
+        key: KeyEvent,
+        is_held: bool,
+        latency_id: nucleotide_ui::InputLatencyId,
+    ) -> Self {
+        Self::Key(InputKeyEvent {
+            latency_id: Some(latency_id),
+            ..InputKeyEvent::from_key_down(key, is_held)
+        })
+    }
+
     pub fn semantic(action: editor_input::EditorSemanticAction) -> Self {
         Self::Semantic(action)
     }
@@ -2084,6 +2320,58 @@ impl Application {
         )
     }
 
+    /// Apply the previewed replacements of a textual project rename to one
+    /// file, opening it if needed. The edits stay unsaved and undoable, like
+    /// an LSP rename. Returns the number of replacements made.
+    pub(crate) fn apply_project_rename(
+        &mut self,
+        file: &crate::project_rename::FileRename,
+    ) -> Result<usize, String> {
+        let action = if self.editor.tree.views().count() == 0 {
+            helix_view::editor::Action::VerticalSplit
+        } else {
+            helix_view::editor::Action::Load
+        };
+        let doc_id = open_workspace_document(
+            &mut self.editor,
+            &self.workspace_backend,
+            &file.path,
+            action,
+        )
+        .map_err(|err| format!("failed to open {}: {err}", file.path.display()))?;
+        let doc = self
+            .editor
+            .document(doc_id)
+            .ok_or_else(|| format!("{} is not open", file.path.display()))?;
+
+        let text = doc.text().slice(..);
+        let mut text_edits = Vec::new();
+        for line in &file.lines {
+            let current = (line.line < text.len_lines()).then(|| text.line(line.line).to_string());
+            if current.as_deref().map(str::trim_end) != Some(line.text.trim_end()) {
+                return Err(format!("{} changed since the preview", file.path.display()));
+            }
+            text_edits.extend(line.edits.iter().map(|edit| lsp::TextEdit {
+                range: lsp::Range::new(
+                    lsp::Position::new(line.line as u32, edit.start as u32),
+                    lsp::Position::new(line.line as u32, edit.end as u32),
+                ),
+                new_text: edit.replacement.clone(),
+            }));
+        }
+
+        let replacements = text_edits.len();
+        apply_workspace_text_edits_to_open_document(
+            &mut self.editor,
+            doc_id,
+            None,
+            text_edits,
+            OffsetEncoding::Utf8,
+        )
+        .map_err(|_| format!("failed to edit {}", file.path.display()))?;
+        Ok(replacements)
+    }
+
     pub(crate) fn set_workspace_backend(&mut self, workspace_backend: WorkspaceBackendHandle) {
         self.workspace_backend = workspace_backend.clone();
         if let Some(system) = &self.project_lsp_system {
@@ -2091,9 +2379,16 @@ impl Application {
                 .environment_provider
                 .set_workspace_backend(workspace_backend.clone());
         }
+        self.install_save_handler();
+    }
+
+    /// Routes saves through the workspace backend and the configured local
+    /// save strategy.
+    fn install_save_handler(&mut self) {
         self.editor
             .set_save_handler(Some(Arc::new(WorkspaceDocumentSaveHandler::new(
-                workspace_backend,
+                self.workspace_backend.clone(),
+                self.config.gui.file_ops.save.clone(),
             ))));
     }
 
@@ -2319,6 +2614,7 @@ impl Application {
                         cx.notify();
                     });
                 }
+                self.lsp_request_guard.forget(*server_id);
             }
             event_bridge::BridgedEvent::DocumentOpened { .. }
             | event_bridge::BridgedEvent::DocumentChanged { .. }
@@ -2389,7 +2685,12 @@ impl Application {
         if !self.project_lsp_initialization_attempted {
             self.project_lsp_initialization_attempted = true;
             info!("🚀 INIT: Initializing project LSP system");
+            let started = Instant::now();
             self.initialize_project_lsp_system(handle);
+            if cx.has_global::<StartupProfile>() {
+                cx.global_mut::<StartupProfile>()
+                    .record(StartupPhase::LspManagerStart, started.elapsed());
+            }
         }
 
         let mut commands_processed = 0;
@@ -2764,6 +3065,12 @@ impl Application {
                 response,
                 ..
             } => self.schedule_open_project_session(workspace_root, response, cx, handle),
+            ProjectLspCommand::CloseProjectSession { response, .. } => {
+                let previous_root = self.project_lsp_supervisor.close_session();
+                self.end_previous_project_session(previous_root, cx, handle);
+                let _ = response.send(Ok(()));
+                self.sync_lsp_state(cx);
+            }
             ProjectLspCommand::StartServer {
                 workspace_root,
                 server_name,
@@ -3843,6 +4150,295 @@ impl Application {
         .detach();
     }
 
+    /// Drop a language server whose process went away and queue it for the
+    /// crash restart policy. Servers stopped by the editor are already gone
+    /// from the registry and never reach this point.
+    fn handle_language_server_exit(&mut self, server_id: LanguageServerId) {
+        let Some(client) = self.editor.language_servers.get_by_id(server_id).cloned() else {
+            return;
+        };
+        let server_name = client.name().to_string();
+        let language_id = self
+            .editor
+            .documents()
+            .find(|doc| {
+                doc.language_servers()
+                    .any(|server| server.id() == server_id)
+            })
+            .and_then(|doc| doc.language_name())
+            .map(str::to_string);
+
+        let workspace_root = match self.helix_lsp_bridge_handle() {
+            Some(bridge) => bridge.forget_exited_server(&mut self.editor, server_id),
+            None => {
+                self.editor.language_servers.remove_by_id(server_id);
+                None
+            }
+        }
+        .or_else(|| self.project_directory.clone());
+
+        warn!(
+            server_id = ?server_id,
+            server_name = %server_name,
+            language_id = ?language_id,
+            "Language server exited unexpectedly"
+        );
+        helix_event::dispatch(helix_view::events::LanguageServerExited {
+            editor: &mut self.editor,
+            server_id,
+        });
+
+        self.pending_lsp_crashes.push(CrashedLspServer {
+            server_id,
+            server_name,
+            language_id,
+            workspace_root,
+        });
+    }
+
+    fn schedule_lsp_crash_restarts(&mut self, cx: &mut gpui::Context<crate::Core>) {
+        for crashed in std::mem::take(&mut self.pending_lsp_crashes) {
+            self.schedule_lsp_crash_restart(crashed, cx);
+        }
+    }
+
+    fn schedule_lsp_crash_restart(
+        &mut self,
+        mut crashed: CrashedLspServer,
+        cx: &mut gpui::Context<crate::Core>,
+    ) {
+        use nucleotide_lsp::CrashRestartDecision;
+
+        if crashed.language_id.is_none() {
+            crashed.language_id = self.lsp_state.as_ref().and_then(|state| {
+                state
+                    .read(cx)
+                    .project_session
+                    .as_ref()?
+                    .servers
+                    .iter()
+                    .find(|server| server.server_name == crashed.server_name)
+                    .map(|server| server.language_id.clone())
+            });
+        }
+        let runtime = tokio::runtime::Handle::try_current().ok();
+        if let Some(manager) = self.project_lsp_manager_handle()
+            && let Some(runtime) = &runtime
+        {
+            let server_id = crashed.server_id;
+            runtime.spawn(async move {
+                manager.remove_managed_server(server_id).await;
+            });
+        }
+
+        let decision = match (
+            self.project_lsp_manager_handle(),
+            crashed.workspace_root.as_deref(),
+            crashed.language_id.as_deref(),
+            runtime.as_ref(),
+        ) {
+            (Some(manager), Some(workspace_root), Some(_), Some(_)) => {
+                manager.record_server_crash(workspace_root, &crashed.server_name)
+            }
+            _ => CrashRestartDecision::Disabled,
+        };
+
+        let server_name = crashed.server_name.clone();
+        let CrashRestartDecision::Restart { attempt, delay } = decision else {
+            self.clear_crashed_lsp_diagnostics(crashed.server_id);
+            let message = match decision {
+                CrashRestartDecision::GiveUp { restarts } => format!(
+                    "Language server {server_name} keeps crashing; stopped after {restarts} restarts"
+                ),
+                _ => format!("Language server {server_name} exited unexpectedly"),
+            };
+            if let Some(language_id) = crashed.language_id.as_deref()
+                && let Some(state) = &self.lsp_state
+            {
+                let error = message.clone();
+                state.update(cx, |state, cx| {
+                    state.update_project_server_lifecycle(
+                        language_id,
+                        &server_name,
+                        ProjectServerLifecycle::Failed {
+                            error: error.clone(),
+                        },
+                        Some(error),
+                    );
+                    cx.notify();
+                });
+            }
+            if let CrashRestartDecision::GiveUp { restarts } = decision {
+                let error =
+                    anyhow::anyhow!("{server_name} crashed {} times in a row", restarts + 1);
+                cx.emit(crate::Update::ErrorReport(Box::new(
+                    crate::error_report::ErrorReport::error(message.clone(), &error),
+                )));
+            }
+            self.set_editor_status_feedback(cx, message, crate::types::Severity::Error);
+            return;
+        };
+        let (Some(runtime), Some(workspace_root), Some(language_id)) = (
+            runtime,
+            crashed.workspace_root.clone(),
+            crashed.language_id.clone(),
+        ) else {
+            return;
+        };
+        let workspace_root = canonical_project_lsp_root(&workspace_root);
+
+        let max_restarts = self.config.gui.lsp.restart.max_restarts;
+        let message = format!(
+            "Language server {server_name} crashed; restarting in {:.1}s (attempt {attempt} of {max_restarts})",
+            delay.as_secs_f64()
+        );
+        if let Some(state) = &self.lsp_state {
+            let error = message.clone();
+            state.update(cx, |state, cx| {
+                state.update_project_server_lifecycle(
+                    &language_id,
+                    &server_name,
+                    ProjectServerLifecycle::RetryScheduled {
+                        attempt: attempt as usize,
+                        next_retry_at: Instant::now() + delay,
+                    },
+                    Some(error),
+                );
+                cx.notify();
+            });
+        }
+        self.set_editor_status_feedback(cx, message, crate::types::Severity::Warning);
+
+        let crashed_server_id = crashed.server_id;
+        self.crashed_lsp_servers.insert(crashed_server_id, crashed);
+        let generation = self.project_lsp_supervisor.generation;
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(delay).await;
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+
+            let response = this.update(cx, |app, cx| {
+                if !app
+                    .project_lsp_supervisor
+                    .allows_start(generation, &workspace_root)
+                {
+                    app.clear_crashed_lsp_diagnostics(crashed_server_id);
+                    return None;
+                }
+                let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+                app.schedule_lsp_server_start(
+                    workspace_root,
+                    server_name.clone(),
+                    language_id.clone(),
+                    Some(response_tx),
+                    cx,
+                    &runtime,
+                );
+                Some(response_rx)
+            });
+            let Some(response) = response else {
+                return;
+            };
+
+            let started = matches!(response.await, Ok(Ok(_)));
+            this.update(cx, |app, cx| {
+                if started {
+                    app.clear_crashed_lsp_diagnostics_without_documents(crashed_server_id);
+                } else {
+                    app.clear_crashed_lsp_diagnostics(crashed_server_id);
+                }
+                if let Some(state) = &app.lsp_state {
+                    state.update(cx, |state, cx| {
+                        let lifecycle = if started {
+                            ProjectServerLifecycle::Running
+                        } else {
+                            ProjectServerLifecycle::Failed {
+                                error: format!("{server_name} could not be restarted"),
+                            }
+                        };
+                        state.update_project_server_lifecycle(
+                            &language_id,
+                            &server_name,
+                            lifecycle,
+                            None,
+                        );
+                        cx.notify();
+                    });
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Hand `uri` over from crashed instances of `server_name` to the server
+    /// that is publishing diagnostics for it now.
+    fn retire_crashed_lsp_diagnostics_for(&mut self, server_name: &str, uri: &Uri) {
+        let crashed_ids: Vec<_> = self
+            .crashed_lsp_servers
+            .values()
+            .filter(|crashed| crashed.server_name == server_name)
+            .map(|crashed| crashed.server_id)
+            .collect();
+        for crashed_id in crashed_ids {
+            if let Some(diagnostics) = self.editor.diagnostics.get_mut(uri) {
+                diagnostics
+                    .retain(|(_, provider)| provider.language_server_id() != Some(crashed_id));
+                if diagnostics.is_empty() {
+                    self.editor.diagnostics.remove(uri);
+                }
+            }
+            for doc in self.editor.documents_mut() {
+                if doc.uri().as_ref() == Some(uri) {
+                    doc.clear_diagnostics_for_language_server(crashed_id);
+                }
+            }
+            self.forget_crashed_lsp_server_without_diagnostics(crashed_id);
+        }
+    }
+
+    /// Drop diagnostics of a crashed server for files that are not open.
+    /// Open documents keep theirs until the replacement republishes them.
+    fn clear_crashed_lsp_diagnostics_without_documents(&mut self, crashed_id: LanguageServerId) {
+        let open_uris: Vec<Uri> = self
+            .editor
+            .documents()
+            .filter_map(|doc| doc.uri())
+            .collect();
+        self.editor.diagnostics.retain(|uri, diagnostics| {
+            if !open_uris.contains(uri) {
+                diagnostics
+                    .retain(|(_, provider)| provider.language_server_id() != Some(crashed_id));
+            }
+            !diagnostics.is_empty()
+        });
+        self.forget_crashed_lsp_server_without_diagnostics(crashed_id);
+    }
+
+    fn clear_crashed_lsp_diagnostics(&mut self, crashed_id: LanguageServerId) {
+        self.editor.diagnostics.retain(|_, diagnostics| {
+            diagnostics.retain(|(_, provider)| provider.language_server_id() != Some(crashed_id));
+            !diagnostics.is_empty()
+        });
+        for doc in self.editor.documents_mut() {
+            doc.clear_diagnostics_for_language_server(crashed_id);
+        }
+        self.crashed_lsp_servers.remove(&crashed_id);
+    }
+
+    fn forget_crashed_lsp_server_without_diagnostics(&mut self, crashed_id: LanguageServerId) {
+        let has_diagnostics = self.editor.diagnostics.values().any(|diagnostics| {
+            diagnostics
+                .iter()
+                .any(|(_, provider)| provider.language_server_id() == Some(crashed_id))
+        });
+        if !has_diagnostics {
+            self.crashed_lsp_servers.remove(&crashed_id);
+        }
+    }
+
     fn record_project_lsp_server_in_background(
         &self,
         handle: &tokio::runtime::Handle,
@@ -4143,6 +4739,7 @@ impl Application {
                 language_id.clone(),
             ),
             ProjectLspCommand::OpenProjectSession { .. }
+            | ProjectLspCommand::CloseProjectSession { .. }
             | ProjectLspCommand::DetectAndStartProject { .. }
             | ProjectLspCommand::StopServer { .. }
             | ProjectLspCommand::RestartServersForWorkspaceChange { .. }
@@ -4295,9 +4892,13 @@ impl Application {
             event_bridge::BridgedEvent::DocumentClosed {
                 doc_id,
                 was_modified,
+                path,
+                view_state,
             } => Some(AppEvent::Document(DocumentEvent::Closed {
                 doc_id: *doc_id,
                 was_modified: *was_modified,
+                path: path.clone(),
+                view_state: view_state.clone(),
             })),
 
             event_bridge::BridgedEvent::DiagnosticsChanged { doc_id } => {
@@ -4485,6 +5086,19 @@ impl Application {
                             );
                             return;
                         }
+                        if diagnostics_excluded_by_merge_policy(
+                            &self.editor,
+                            server_id,
+                            &uri,
+                            syntax::config::LanguageServerFeature::Diagnostics,
+                        ) {
+                            debug!(
+                                server_id = ?server_id,
+                                server_name = %language_server.name(),
+                                "Discarding diagnostics from lower priority server"
+                            );
+                            return;
+                        }
 
                         // DIAG: Summarize incoming diagnostics by severity
                         let total = diagnostics.len();
@@ -4515,6 +5129,9 @@ impl Application {
                             "DIAG: Received LSP publishDiagnostics"
                         );
 
+                        let server_name = language_server.name().to_string();
+                        self.retire_crashed_lsp_diagnostics_for(&server_name, &uri);
+
                         // Handle diagnostics through the editor like Helix does
                         let provider = helix_core::diagnostic::DiagnosticProvider::Lsp {
                             server_id,
@@ -4525,6 +5142,9 @@ impl Application {
                             .handle_lsp_diagnostics(&provider, uri, version, diagnostics);
                         trace!("DIAG: Forwarded diagnostics to Helix editor for application");
                     }
+                    Notification::Exit => {
+                        self.handle_language_server_exit(server_id);
+                    }
                     Notification::ProgressMessage(params) => {
                         use helix_lsp::lsp;
 
@@ -5254,10 +5874,22 @@ impl Application {
         }
     }
 
+    fn emit_prompt_request(
+        request: editor_input::NativePromptRequest,
+        cx: &mut gpui::Context<crate::Core>,
+    ) {
+        if request == editor_input::NativePromptRequest::FindBar {
+            cx.emit(crate::Update::ShowFindBar);
+        } else {
+            cx.emit(crate::Update::Prompt(Self::create_native_prompt(request)));
+        }
+    }
+
     fn create_native_prompt(request: editor_input::NativePromptRequest) -> crate::prompt::Prompt {
         let prompt = match request {
             editor_input::NativePromptRequest::Command => ":",
-            editor_input::NativePromptRequest::Search => "search:",
+            editor_input::NativePromptRequest::Search
+            | editor_input::NativePromptRequest::FindBar => "search:",
             editor_input::NativePromptRequest::ReverseSearch => "rsearch:",
             editor_input::NativePromptRequest::GlobalSearch => "global-search:",
             editor_input::NativePromptRequest::RegexSelection(action) => match action {
@@ -5463,7 +6095,7 @@ impl Application {
                     });
                 }
                 if let Some(request) = outcome.prompt_requested {
-                    cx.emit(crate::Update::Prompt(Self::create_native_prompt(request)));
+                    Self::emit_prompt_request(request, cx);
                 }
                 self.emit_overlays(cx);
                 cx.emit(crate::Update::Redraw);
@@ -5483,15 +6115,21 @@ impl Application {
                     key = ?key,
                     is_held = input.is_held,
                     is_navigation_repeat,
+                    latency_id = input.latency_id.map(|id| id.get()),
                     "Handling key event in Application"
                 );
 
+                let command_started_at = std::time::Instant::now();
                 let outcome = self.editor_input.handle_key(
                     key,
                     &mut self.compositor,
                     &mut self.editor,
                     &mut self.jobs,
                 );
+                if let Some(latency_id) = input.latency_id {
+                    cx.default_global::<nucleotide_ui::InputLatencyTracker>()
+                        .record_command(latency_id, command_started_at, std::time::Instant::now());
+                }
                 let has_explicit_ui_request = outcome.completion_requested.is_some()
                     || outcome.picker_requested.is_some()
                     || outcome.prompt_requested.is_some()
@@ -5578,7 +6216,7 @@ impl Application {
                 }
 
                 if let Some(request) = outcome.prompt_requested {
-                    cx.emit(crate::Update::Prompt(Self::create_native_prompt(request)));
+                    Self::emit_prompt_request(request, cx);
                 }
 
                 if let Some(request) = outcome.picker_requested {
@@ -6087,6 +6725,11 @@ impl Application {
         match event {
             EditorEvent::DocumentSaved(event) => {
                 self.handle_document_write(&event);
+                if let Err(error) = &event
+                    && let Some(failure) = crate::save_failure::SaveFailure::from_error(error)
+                {
+                    cx.emit(crate::Update::SaveFailed(failure));
+                }
                 if let Ok(event) = event {
                     let v2_event = DocumentEvent::Saved {
                         doc_id: event.doc_id,
@@ -6116,6 +6759,7 @@ impl Application {
                     "Received EditorEvent::LanguageServerMessage"
                 );
                 self.handle_language_server_message(call, id);
+                self.schedule_lsp_crash_restarts(cx);
                 self.sync_lsp_state(cx);
                 cx.emit(crate::Update::Redraw);
             }
@@ -6167,6 +6811,14 @@ impl Application {
         debug!("Old bufferline config: {:?}", old_config.bufferline);
 
         self.config = new_config;
+        self.lsp_request_guard
+            .set_policy(lsp_request_policy(&self.config.gui.lsp));
+        helix_lsp::document_sync::set_did_change_coalesce_window(Duration::from_millis(
+            self.config.gui.lsp.did_change_coalesce_ms,
+        ));
+        self.editor.language_server_merge = language_server_merge_policies(&self.config.gui.lsp);
+        nucleotide_ui::set_file_associations(self.config.file_associations());
+        self.install_save_handler();
         let mut updated_helix_config = self.config.to_helix_config();
         // Nucleotide always runs Helix in GUI true-colour mode. Preserve the
         // startup invariant when replacing the runtime config arc.
@@ -6183,6 +6835,8 @@ impl Application {
             |config: &Config| &config.keys,
         ));
         self.editor_input = EditorInputBridge::new(Keymaps::new(native_keys));
+        self.editor_input
+            .set_editing_config(self.config.gui.editor.editing.clone());
 
         self.editor.refresh_config(&old_config);
         debug!(
@@ -6198,6 +6852,11 @@ impl Application {
             }
         }
 
+        let doc_ids: Vec<DocumentId> = self.editor.documents().map(|doc| doc.id()).collect();
+        for doc_id in doc_ids {
+            apply_file_association_language(&mut self.editor, doc_id);
+        }
+
         cx.emit(crate::Update::Redraw);
     }
 
@@ -6222,7 +6881,10 @@ impl Application {
             }
             helix_view::editor::ConfigEvent::Refresh => {
                 info!("Config refresh requested - reloading from files");
-                match crate::config::Config::load() {
+                let project_root = self.project_directory.as_deref().filter(|_| {
+                    matches!(self.workspace_backend.identity(), WorkspaceIdentity::Local)
+                });
+                match crate::config::Config::load_for_project(project_root) {
                     Ok(fresh_config) => self.apply_reloaded_config(fresh_config, cx),
                     Err(error) => {
                         error!(%error, "Failed to refresh config from files");
@@ -6447,6 +7109,31 @@ fn lsp_locations_from_definition_response(
 }
 
 type LspLocationFuture = BoxFuture<'static, anyhow::Result<Vec<crate::types::LspLocation>>>;
+
+/// Routes a request through the shared timeout/retry/circuit-breaker policy.
+/// `first` is the request that was already issued; `reissue` sends it again
+/// when the policy decides to retry.
+fn guarded_lsp_request<T, Fut>(
+    guard: &nucleotide_lsp::LspRequestGuard,
+    server_id: LanguageServerId,
+    method: &'static str,
+    first: Fut,
+    mut reissue: impl FnMut() -> Option<Fut> + Send + 'static,
+) -> impl Future<Output = Result<T, nucleotide_lsp::LspRequestError>> + Send + 'static
+where
+    T: Send + 'static,
+    Fut: Future<Output = helix_lsp::Result<T>> + Send + 'static,
+{
+    let guard = guard.clone();
+    let mut first = Some(first);
+    async move {
+        guard
+            .request(server_id, method, move || {
+                first.take().or_else(&mut reissue)
+            })
+            .await
+    }
+}
 type SymbolItemFuture = BoxFuture<'static, anyhow::Result<Vec<NativeSymbolItem>>>;
 
 const WORKSPACE_SYNTAX_SYMBOL_FILE_LIMIT: usize = 10_000;
@@ -6836,6 +7523,17 @@ fn lsp_location_from_location(
     })
 }
 
+fn type_hierarchy_entry(
+    item: lsp::TypeHierarchyItem,
+    offset_encoding: OffsetEncoding,
+) -> crate::type_hierarchy::TypeHierarchyEntry {
+    let location = lsp_location_from_location(
+        lsp::Location::new(item.uri.clone(), item.selection_range),
+        offset_encoding,
+    );
+    crate::type_hierarchy::TypeHierarchyEntry { item, location }
+}
+
 fn lsp_location_path_from_url(uri: lsp::Url) -> Result<PathBuf, String> {
     if let Some(path) = remote_lsp_file_url_path(&uri) {
         return Ok(path);
@@ -7006,6 +7704,42 @@ impl Application {
         result
     }
 
+    /// Sends `shutdown` and `exit` to every language server. The returned
+    /// task finishes once both have been written to each server.
+    pub fn shutdown_language_servers(
+        &mut self,
+        handle: &tokio::runtime::Handle,
+    ) -> tokio::task::JoinHandle<()> {
+        let language_servers = &self.editor.language_servers;
+        let clients = language_servers.iter_clients().cloned().collect::<Vec<_>>();
+        for client in &clients {
+            language_servers
+                .file_event_handler
+                .remove_client(client.id());
+            client.force_shutdown();
+        }
+        info!(servers = clients.len(), "Shutting down language servers");
+
+        handle.spawn(async move {
+            for client in clients {
+                client.wait_shutdown_flushed().await;
+            }
+        })
+    }
+
+    /// Stops the project LSP manager's health checks and managed servers.
+    pub fn stop_project_lsp_manager(
+        &self,
+        handle: &tokio::runtime::Handle,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        let manager = self.project_lsp_manager_handle()?;
+        Some(handle.spawn(async move {
+            if let Err(error) = manager.stop().await {
+                warn!(%error, "Failed to stop project LSP manager");
+            }
+        }))
+    }
+
     fn project_lsp_manager_handle(&self) -> Option<ProjectLspManager> {
         self.project_lsp_system
             .as_ref()
@@ -7087,11 +7821,26 @@ impl Application {
         ));
         let launch_proxy_provider =
             Arc::new(RemoteLspLaunchProxyProvider::from_config(&self.config));
-        let helix_bridge = nucleotide_lsp::HelixLspBridge::new_with_environment_and_launch_proxy(
-            event_tx,
-            env_provider.clone(),
-            launch_proxy_provider,
-        );
+        let mut helix_bridge =
+            nucleotide_lsp::HelixLspBridge::new_with_environment_and_launch_proxy(
+                event_tx,
+                env_provider.clone(),
+                launch_proxy_provider,
+            );
+        if self.config.gui.lsp.traffic_inspector {
+            match std::env::current_exe() {
+                Ok(proxy_executable) => {
+                    let log_dir = crate::lsp_traffic_logger::inspector_log_directory();
+                    info!(log_dir = %log_dir.display(), "LSP traffic inspector enabled");
+                    helix_bridge = helix_bridge.with_traffic_inspector(
+                        nucleotide_lsp::LspTrafficInspector::new(proxy_executable, log_dir),
+                    );
+                }
+                Err(error) => {
+                    warn!(%error, "LSP traffic inspector disabled: cannot locate executable");
+                }
+            }
+        }
         self.project_lsp_system = Some(ProjectLspSystem {
             manager: project_manager.clone(),
             bridge: helix_bridge.clone(),
@@ -7197,12 +7946,14 @@ impl Application {
 
         let (local_items, local_futures) =
             self.collect_local_completion_items(cursor, doc_id, &prefix);
+        let cancellation = CompletionCancellation::default();
         let (lsp_futures, lsp_error) = match self.prepare_lsp_completion_futures(
             cursor,
             doc_id,
             view_id,
             trigger,
             server_filter.as_deref(),
+            &cancellation,
         ) {
             Ok(futures) => (futures, None),
             Err(err) => {
@@ -7221,6 +7972,7 @@ impl Application {
             local_futures,
             lsp_error,
             lsp_futures,
+            cancellation,
         })
     }
 
@@ -7233,6 +7985,7 @@ impl Application {
         view_id: helix_view::ViewId,
         trigger: LspCompletionTrigger,
         server_filter: Option<&[u64]>,
+        cancellation: &CompletionCancellation,
     ) -> anyhow::Result<FuturesOrdered<CompletionServerFuture>> {
         nucleotide_logging::info!(
             cursor = cursor,
@@ -7270,8 +8023,15 @@ impl Application {
             return Err(anyhow::anyhow!("View document mismatch"));
         }
 
+        // Futures resolve in priority order, so completion dedupe keeps the
+        // item from the highest priority server.
         let language_servers: Vec<_> = doc
-            .language_servers_with_feature(syntax::config::LanguageServerFeature::Completion)
+            .language_servers_for_merge(
+                &self.editor.language_server_merge,
+                syntax::config::LanguageServerFeature::Completion,
+                |policy| policy.completion,
+            )
+            .into_iter()
             .filter(|language_server| {
                 server_filter.is_none_or(|server_filter| {
                     server_filter.contains(&language_server.id().data().as_ffi())
@@ -7355,7 +8115,7 @@ impl Application {
                 }),
             );
 
-            let Some(completion_future) = language_server.completion(
+            let Some((request_id, completion_future)) = language_server.completion_with_id(
                 doc_id_lsp.clone(),
                 position,
                 None,
@@ -7369,6 +8129,38 @@ impl Application {
             };
 
             let server_id = language_server.id();
+            let completion_future = match self.editor.language_servers.get_by_id(server_id) {
+                Some(client) => {
+                    let client = client.clone();
+                    cancellation.track(client.clone(), request_id);
+                    let retry_cancellation = cancellation.clone();
+                    let retry_identifier = doc_id_lsp.clone();
+                    let retry_context = completion_context.clone();
+                    guarded_lsp_request(
+                        &self.lsp_request_guard,
+                        server_id,
+                        "textDocument/completion",
+                        completion_future,
+                        move || {
+                            let (request_id, completion_future) = client.completion_with_id(
+                                retry_identifier.clone(),
+                                position,
+                                None,
+                                retry_context.clone(),
+                            )?;
+                            retry_cancellation.track(client.clone(), request_id);
+                            Some(completion_future)
+                        },
+                    )
+                    .boxed()
+                }
+                None => async move {
+                    completion_future
+                        .await
+                        .map_err(nucleotide_lsp::LspRequestError::from)
+                }
+                .boxed(),
+            };
             lsp_futures.push_back(
                 async move {
                     completion_future
@@ -7576,6 +8368,7 @@ impl Application {
     async fn handle_lsp_command(&mut self, command: ProjectLspCommand) {
         let span = match &command {
             ProjectLspCommand::OpenProjectSession { span, .. } => span.clone(),
+            ProjectLspCommand::CloseProjectSession { span, .. } => span.clone(),
             ProjectLspCommand::DetectAndStartProject { span, .. } => span.clone(),
             ProjectLspCommand::StartServer { span, .. } => span.clone(),
             ProjectLspCommand::StopServer { span, .. } => span.clone(),
@@ -7595,6 +8388,11 @@ impl Application {
                     "OpenProjectSession requires the GPUI command dispatcher".to_string(),
                 )));
             }
+            ProjectLspCommand::CloseProjectSession { response, .. } => {
+                let _ = response.send(Err(ProjectLspCommandError::Internal(
+                    "CloseProjectSession requires the GPUI command dispatcher".to_string(),
+                )));
+            }
             ProjectLspCommand::StartServer {
                 workspace_root,
                 server_name,
@@ -8500,6 +9298,7 @@ impl Application {
         let include_declaration = self.editor.config().lsp.goto_reference_include_declaration;
         let mut futures: FuturesOrdered<LspLocationFuture> = FuturesOrdered::new();
         let workspace_identity = self.workspace_backend.identity();
+        let mut references_origin = None;
 
         {
             let Some(view) = self.editor.tree.try_get(self.editor.tree.focus) else {
@@ -8525,19 +9324,53 @@ impl Application {
                     .set_error("LSP navigation requires a file-backed document");
                 return;
             };
+            if request == editor_input::NativeLspNavigationRequest::GotoReference {
+                references_origin = doc.path().map(|path| {
+                    let text = doc.text();
+                    let cursor = doc.selection(view.id).primary().cursor(text.slice(..));
+                    crate::references_panel::ReferencesOrigin {
+                        path: path.to_path_buf(),
+                        position: helix_core::coords_at_pos(text.slice(..), cursor),
+                        symbol: crate::references_panel::symbol_at(text, cursor),
+                    }
+                });
+            }
 
             for language_server in doc.language_servers_with_feature(feature) {
                 let offset_encoding = language_server.offset_encoding();
                 let position = doc.position(view.id, offset_encoding);
+                let Some(client) = self
+                    .editor
+                    .language_servers
+                    .get_by_id(language_server.id())
+                    .cloned()
+                else {
+                    continue;
+                };
+                let server_id = client.id();
+                let retry_identifier = identifier.clone();
 
                 match request {
                     editor_input::NativeLspNavigationRequest::GotoDeclaration => {
                         if let Some(future) =
                             language_server.goto_declaration(identifier.clone(), position, None)
                         {
+                            let response = guarded_lsp_request(
+                                &self.lsp_request_guard,
+                                server_id,
+                                "textDocument/declaration",
+                                future,
+                                move || {
+                                    client.goto_declaration(
+                                        retry_identifier.clone(),
+                                        position,
+                                        None,
+                                    )
+                                },
+                            );
                             futures.push_back(
                                 async move {
-                                    let response = future.await?;
+                                    let response = response.await?;
                                     Ok(lsp_locations_from_definition_response(
                                         response,
                                         offset_encoding,
@@ -8551,9 +9384,18 @@ impl Application {
                         if let Some(future) =
                             language_server.goto_definition(identifier.clone(), position, None)
                         {
+                            let response = guarded_lsp_request(
+                                &self.lsp_request_guard,
+                                server_id,
+                                "textDocument/definition",
+                                future,
+                                move || {
+                                    client.goto_definition(retry_identifier.clone(), position, None)
+                                },
+                            );
                             futures.push_back(
                                 async move {
-                                    let response = future.await?;
+                                    let response = response.await?;
                                     Ok(lsp_locations_from_definition_response(
                                         response,
                                         offset_encoding,
@@ -8567,9 +9409,22 @@ impl Application {
                         if let Some(future) =
                             language_server.goto_type_definition(identifier.clone(), position, None)
                         {
+                            let response = guarded_lsp_request(
+                                &self.lsp_request_guard,
+                                server_id,
+                                "textDocument/typeDefinition",
+                                future,
+                                move || {
+                                    client.goto_type_definition(
+                                        retry_identifier.clone(),
+                                        position,
+                                        None,
+                                    )
+                                },
+                            );
                             futures.push_back(
                                 async move {
-                                    let response = future.await?;
+                                    let response = response.await?;
                                     Ok(lsp_locations_from_definition_response(
                                         response,
                                         offset_encoding,
@@ -8583,9 +9438,22 @@ impl Application {
                         if let Some(future) =
                             language_server.goto_implementation(identifier.clone(), position, None)
                         {
+                            let response = guarded_lsp_request(
+                                &self.lsp_request_guard,
+                                server_id,
+                                "textDocument/implementation",
+                                future,
+                                move || {
+                                    client.goto_implementation(
+                                        retry_identifier.clone(),
+                                        position,
+                                        None,
+                                    )
+                                },
+                            );
                             futures.push_back(
                                 async move {
-                                    let response = future.await?;
+                                    let response = response.await?;
                                     Ok(lsp_locations_from_definition_response(
                                         response,
                                         offset_encoding,
@@ -8596,25 +9464,13 @@ impl Application {
                         }
                     }
                     editor_input::NativeLspNavigationRequest::GotoReference => {
-                        if let Some(future) = language_server.goto_reference(
+                        if let Some(future) = self.references_request(
+                            client,
                             identifier.clone(),
                             position,
                             include_declaration,
-                            None,
                         ) {
-                            futures.push_back(
-                                async move {
-                                    let locations = future.await?;
-                                    Ok(locations
-                                        .into_iter()
-                                        .flatten()
-                                        .filter_map(|location| {
-                                            lsp_location_from_location(location, offset_encoding)
-                                        })
-                                        .collect())
-                                }
-                                .boxed(),
-                            );
+                            futures.push_back(future);
                         }
                     }
                 }
@@ -8642,7 +9498,13 @@ impl Application {
 
             if let Some(core) = core.upgrade() {
                 core.update(cx, move |core, cx| {
-                    core.finish_lsp_navigation(title, empty_message, locations, cx);
+                    core.finish_lsp_navigation(
+                        title,
+                        empty_message,
+                        locations,
+                        references_origin,
+                        cx,
+                    );
                 });
             }
         })
@@ -8898,11 +9760,27 @@ impl Application {
                 .filter(|server| seen_language_servers.insert(server.id()))
             {
                 let offset_encoding = language_server.offset_encoding();
+                let Some(client) = self
+                    .editor
+                    .language_servers
+                    .get_by_id(language_server.id())
+                    .cloned()
+                else {
+                    continue;
+                };
+                let server_id = client.id();
                 if workspace {
                     if let Some(future) = language_server.workspace_symbols(String::new()) {
+                        let response = guarded_lsp_request(
+                            &self.lsp_request_guard,
+                            server_id,
+                            "workspace/symbol",
+                            future,
+                            move || client.workspace_symbols(String::new()),
+                        );
                         futures.push_back(
                             async move {
-                                let response = future.await?;
+                                let response = response.await?;
                                 Ok(workspace_symbol_items_from_response(
                                     response,
                                     offset_encoding,
@@ -8913,11 +9791,18 @@ impl Application {
                     }
                 } else if let Some(path) = doc_path.clone()
                     && let Some(identifier) = document_lsp_identifier(doc)
-                    && let Some(future) = language_server.document_symbols(identifier)
+                    && let Some(future) = language_server.document_symbols(identifier.clone())
                 {
+                    let response = guarded_lsp_request(
+                        &self.lsp_request_guard,
+                        server_id,
+                        "textDocument/documentSymbol",
+                        future,
+                        move || client.document_symbols(identifier.clone()),
+                    );
                     futures.push_back(
                         async move {
-                            let response = future.await?;
+                            let response = response.await?;
                             Ok(document_symbol_items_from_response(
                                 response,
                                 path,
@@ -9064,10 +9949,23 @@ impl Application {
         title: String,
         empty_message: String,
         locations: Vec<crate::types::LspLocation>,
+        references_origin: Option<crate::references_panel::ReferencesOrigin>,
         cx: &mut gpui::Context<crate::Core>,
     ) {
+        let panel_threshold = self.config.gui.references.panel_threshold;
         match locations.as_slice() {
             [] => self.editor.set_error(empty_message),
+            _ if references_origin.is_some() && locations.len() >= panel_threshold.max(2) => {
+                if let Some(origin) = references_origin {
+                    cx.emit(crate::Update::References(
+                        crate::references_panel::ReferencesResult {
+                            origin,
+                            locations,
+                            error: None,
+                        },
+                    ));
+                }
+            }
             [location] => match self.jump_to_lsp_location(location) {
                 Ok((doc_id, view_id)) => {
                     cx.emit(crate::Update::SelectionChanged { doc_id, view_id });
@@ -9084,6 +9982,290 @@ impl Application {
         cx.emit(crate::Update::Redraw);
     }
 
+    fn references_request(
+        &self,
+        client: Arc<helix_lsp::Client>,
+        identifier: lsp::TextDocumentIdentifier,
+        position: lsp::Position,
+        include_declaration: bool,
+    ) -> Option<LspLocationFuture> {
+        let offset_encoding = client.offset_encoding();
+        let future =
+            client.goto_reference(identifier.clone(), position, include_declaration, None)?;
+        let response = guarded_lsp_request(
+            &self.lsp_request_guard,
+            client.id(),
+            "textDocument/references",
+            future,
+            move || client.goto_reference(identifier.clone(), position, include_declaration, None),
+        );
+        Some(
+            async move {
+                let locations = response.await?;
+                Ok(locations
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|location| lsp_location_from_location(location, offset_encoding))
+                    .collect())
+            }
+            .boxed(),
+        )
+    }
+
+    /// Repeat the references request behind the references panel and send
+    /// the new results to it, however many there are.
+    pub fn refresh_references(
+        &mut self,
+        origin: crate::references_panel::ReferencesOrigin,
+        cx: &mut gpui::Context<crate::Core>,
+    ) {
+        let include_declaration = self.editor.config().lsp.goto_reference_include_declaration;
+        let mut futures: FuturesOrdered<LspLocationFuture> = FuturesOrdered::new();
+        let error = match self.open_navigation_document(&origin.path) {
+            Ok(doc_id) => {
+                let doc = self.editor.document(doc_id);
+                match doc.and_then(|doc| Some((doc, document_lsp_identifier(doc)?))) {
+                    Some((doc, identifier)) => {
+                        let text = doc.text().slice(..);
+                        let cursor = pos_at_coords(text, origin.position, true);
+                        for language_server in doc.language_servers_with_feature(
+                            syntax::config::LanguageServerFeature::GotoReference,
+                        ) {
+                            let Some(client) = self
+                                .editor
+                                .language_servers
+                                .get_by_id(language_server.id())
+                                .cloned()
+                            else {
+                                continue;
+                            };
+                            let position = helix_lsp::util::pos_to_lsp_pos(
+                                doc.text(),
+                                cursor,
+                                client.offset_encoding(),
+                            );
+                            if let Some(future) = self.references_request(
+                                client,
+                                identifier.clone(),
+                                position,
+                                include_declaration,
+                            ) {
+                                futures.push_back(future);
+                            }
+                        }
+                        futures.is_empty().then(|| {
+                            editor_input::NativeLspNavigationRequest::GotoReference
+                                .unsupported_message()
+                                .to_string()
+                        })
+                    }
+                    None => Some("LSP navigation requires a file-backed document".to_string()),
+                }
+            }
+            Err(err) => Some(err.to_string()),
+        };
+        if let Some(error) = error {
+            cx.emit(crate::Update::References(
+                crate::references_panel::ReferencesResult {
+                    origin,
+                    locations: Vec::new(),
+                    error: Some(error),
+                },
+            ));
+            return;
+        }
+
+        cx.spawn(async move |core, cx| {
+            let mut locations = Vec::new();
+            let mut error = None;
+            while let Some(response) = futures_util::StreamExt::next(&mut futures).await {
+                match response {
+                    Ok(mut response_locations) => locations.append(&mut response_locations),
+                    Err(err) => {
+                        warn!(error = %err, "LSP references request failed");
+                        error = Some(err.to_string());
+                    }
+                }
+            }
+
+            if let Some(core) = core.upgrade() {
+                core.update(cx, move |_core, cx| {
+                    cx.emit(crate::Update::References(
+                        crate::references_panel::ReferencesResult {
+                            origin,
+                            // A failing server doesn't hide what the others found.
+                            error: error.filter(|_| locations.is_empty()),
+                            locations,
+                        },
+                    ));
+                });
+            }
+        })
+        .detach();
+    }
+
+    /// Prepare the type hierarchy of the symbol under the cursor with the
+    /// first language server that supports it. The answer is sent as
+    /// `Update::TypeHierarchy` tagged with `generation`.
+    pub fn prepare_type_hierarchy(&mut self, generation: u64, cx: &mut gpui::Context<crate::Core>) {
+        use crate::type_hierarchy::TypeHierarchyUpdate;
+
+        let request = (|| {
+            let view = self
+                .editor
+                .tree
+                .try_get(self.editor.tree.focus)
+                .ok_or("No active view for the type hierarchy")?;
+            let doc = self
+                .editor
+                .document(view.doc)
+                .ok_or("No active document for the type hierarchy")?;
+            let identifier = document_lsp_identifier(doc)
+                .ok_or("The type hierarchy requires a file-backed document")?;
+            let client = doc
+                .language_servers()
+                .find(|server| server.is_initialized() && server.supports_type_hierarchy())
+                .and_then(|server| self.editor.language_servers.get_by_id(server.id()))
+                .cloned()
+                .ok_or("No language server with type hierarchy support")?;
+            let position = doc.position(view.id, client.offset_encoding());
+            Ok::<_, &str>((client, identifier, position))
+        })();
+        let (client, identifier, position) = match request {
+            Ok(request) => request,
+            Err(error) => {
+                cx.emit(crate::Update::TypeHierarchy(
+                    TypeHierarchyUpdate::Prepared {
+                        generation,
+                        root: Err(error.to_string()),
+                    },
+                ));
+                return;
+            }
+        };
+
+        let server_id = client.id();
+        let offset_encoding = client.offset_encoding();
+        let Some(future) = client.prepare_type_hierarchy(identifier.clone(), position) else {
+            return;
+        };
+        let response = guarded_lsp_request(
+            &self.lsp_request_guard,
+            server_id,
+            "textDocument/prepareTypeHierarchy",
+            future,
+            move || client.prepare_type_hierarchy(identifier.clone(), position),
+        );
+        cx.spawn(async move |core, cx| {
+            let root = match response.await {
+                Ok(items) => items
+                    .into_iter()
+                    .flatten()
+                    .next()
+                    .map(|item| (server_id, type_hierarchy_entry(item, offset_encoding)))
+                    .ok_or_else(|| "No type hierarchy at the cursor".to_string()),
+                Err(err) => {
+                    warn!(error = %err, "LSP type hierarchy request failed");
+                    Err(err.to_string())
+                }
+            };
+            if let Some(core) = core.upgrade() {
+                core.update(cx, move |_core, cx| {
+                    cx.emit(crate::Update::TypeHierarchy(
+                        TypeHierarchyUpdate::Prepared { generation, root },
+                    ));
+                });
+            }
+        })
+        .detach();
+    }
+
+    /// Request the supertypes or subtypes of `item` for tree node `node`.
+    pub fn resolve_type_hierarchy(
+        &mut self,
+        generation: u64,
+        server_id: LanguageServerId,
+        direction: crate::type_hierarchy::TypeHierarchyDirection,
+        node: usize,
+        item: lsp::TypeHierarchyItem,
+        cx: &mut gpui::Context<crate::Core>,
+    ) {
+        use crate::type_hierarchy::{TypeHierarchyDirection, TypeHierarchyUpdate};
+
+        let client = self.editor.language_servers.get_by_id(server_id).cloned();
+        let offset_encoding = client
+            .as_ref()
+            .map_or(OffsetEncoding::default(), |client| client.offset_encoding());
+        let response = client.and_then(|client| match direction {
+            TypeHierarchyDirection::Supertypes => {
+                let retry = client.clone();
+                let retry_item = item.clone();
+                client.type_hierarchy_supertypes(item).map(|future| {
+                    guarded_lsp_request(
+                        &self.lsp_request_guard,
+                        server_id,
+                        "typeHierarchy/supertypes",
+                        future,
+                        move || retry.type_hierarchy_supertypes(retry_item.clone()),
+                    )
+                    .boxed()
+                })
+            }
+            TypeHierarchyDirection::Subtypes => {
+                let retry = client.clone();
+                let retry_item = item.clone();
+                client.type_hierarchy_subtypes(item).map(|future| {
+                    guarded_lsp_request(
+                        &self.lsp_request_guard,
+                        server_id,
+                        "typeHierarchy/subtypes",
+                        future,
+                        move || retry.type_hierarchy_subtypes(retry_item.clone()),
+                    )
+                    .boxed()
+                })
+            }
+        });
+        let Some(response) = response else {
+            cx.emit(crate::Update::TypeHierarchy(
+                TypeHierarchyUpdate::Resolved {
+                    generation,
+                    direction,
+                    node,
+                    children: Err("The language server is no longer running".to_string()),
+                },
+            ));
+            return;
+        };
+
+        cx.spawn(async move |core, cx| {
+            let children = match response.await {
+                Ok(items) => Ok(items
+                    .into_iter()
+                    .flatten()
+                    .map(|item| type_hierarchy_entry(item, offset_encoding))
+                    .collect()),
+                Err(err) => {
+                    warn!(error = %err, "LSP type hierarchy request failed");
+                    Err(err.to_string())
+                }
+            };
+            if let Some(core) = core.upgrade() {
+                core.update(cx, move |_core, cx| {
+                    cx.emit(crate::Update::TypeHierarchy(
+                        TypeHierarchyUpdate::Resolved {
+                            generation,
+                            direction,
+                            node,
+                            children,
+                        },
+                    ));
+                });
+            }
+        })
+        .detach();
+    }
+
     pub fn jump_to_lsp_location(
         &mut self,
         location: &crate::types::LspLocation,
@@ -9497,7 +10679,7 @@ pub(crate) fn workspace_backend_for_project_directory_with_config(
 pub fn init_editor(
     args: Args,
     helix_config: Config,
-    gui_config: crate::config::Config,
+    mut gui_config: crate::config::Config,
     lang_loader: syntax::Loader,
 ) -> Result<Application, Error> {
     use helix_view::editor::Action;
@@ -9556,6 +10738,15 @@ pub fn init_editor(
         project_directory.as_deref(),
         &gui_config,
     )?;
+    let mut helix_config = helix_config;
+    if matches!(workspace_backend.identity(), WorkspaceIdentity::Local)
+        && let Some(project_directory) = project_directory.as_deref()
+        && gui_config
+            .apply_workspace_settings_from(project_directory)
+            .is_some()
+    {
+        helix_config.editor.auto_format = gui_config.helix.editor.auto_format;
+    }
 
     let mut theme_parent_dirs = vec![helix_loader::config_dir()];
     theme_parent_dirs.extend(helix_loader::runtime_dirs().iter().cloned());
@@ -9675,7 +10866,6 @@ pub fn init_editor(
 
     // CRITICAL: Enable true_color support for GUI mode before creating the editor
     // This is required for themes to work correctly
-    let mut helix_config = helix_config;
     helix_config.editor.true_color = true;
 
     let config = Arc::new(ArcSwap::from_pointee(helix_config));
@@ -9742,7 +10932,10 @@ pub fn init_editor(
     );
     editor.set_save_handler(Some(Arc::new(WorkspaceDocumentSaveHandler::new(
         workspace_backend.clone(),
+        gui_config.gui.file_ops.save.clone(),
     ))));
+    editor.language_server_merge = language_server_merge_policies(&gui_config.gui.lsp);
+    nucleotide_ui::set_file_associations(gui_config.file_associations());
 
     if args.load_tutor {
         let path = helix_loader::runtime_file(Path::new("tutor"));
@@ -9797,6 +10990,7 @@ pub fn init_editor(
                         doc_id = ?doc_id,
                         "Successfully opened file from CLI"
                     );
+                    apply_file_association_language(&mut editor, doc_id);
 
                     // Log document info
                     if let Some(doc) = editor.document(doc_id) {
@@ -9849,7 +11043,8 @@ pub fn init_editor(
         height: 25,
     });
     let native_keymaps = Keymaps::new(native_keys);
-    let editor_input = EditorInputBridge::new(native_keymaps);
+    let mut editor_input = EditorInputBridge::new(native_keymaps);
+    editor_input.set_editing_config(gui_config.gui.editor.editing.clone());
     let jobs = Jobs::new();
 
     // CRITICAL: Create ProjectEnvironment BEFORE LSP system so LSP can get proper environment
@@ -9879,6 +11074,10 @@ pub fn init_editor(
 
     let terminal_runtime = TerminalRuntimeHandle::new();
 
+    helix_lsp::document_sync::set_did_change_coalesce_window(Duration::from_millis(
+        gui_config.gui.lsp.did_change_coalesce_ms,
+    ));
+
     Ok(Application {
         editor,
         compositor,
@@ -9886,6 +11085,9 @@ pub fn init_editor(
         jobs,
         lsp_progress: LspProgressMap::new(),
         lsp_state: None, // Will be initialized when Application is wrapped in a GPUI entity
+        lsp_request_guard: nucleotide_lsp::LspRequestGuard::new(lsp_request_policy(
+            &gui_config.gui.lsp,
+        )),
         project_directory,
         workspace_backend,
         event_bridge_rx: Some(bridge_rx),
@@ -9901,6 +11103,8 @@ pub fn init_editor(
         project_env_overrides: HashMap::new(),
         prewarmed_lsp_startups: HashSet::new(),
         pending_lsp_workspace_edits: VecDeque::new(),
+        pending_lsp_crashes: Vec::new(),
+        crashed_lsp_servers: HashMap::new(),
         terminal_runtime,
         maintenance_wake: None,
     })
@@ -10179,6 +11383,27 @@ pub(crate) fn reload_workspace_document_from_read(
     Ok(())
 }
 
+/// Keep a buffer over its file's changes on disk by adopting the disk
+/// version, so the next save overwrites the file instead of failing as an
+/// external modification.
+pub(crate) fn keep_document_over_disk_changes(
+    editor: &mut Editor,
+    doc_id: DocumentId,
+    read: &FileRead,
+) -> Result<(), Error> {
+    let doc = editor
+        .document_mut(doc_id)
+        .with_context(|| format!("document {doc_id:?} is no longer open"))?;
+    doc.set_path_with_metadata(Some(&read.path), Some(read.readonly), read.modified);
+    doc.set_file_version(
+        read.version
+            .as_ref()
+            .map(|version| version.as_bytes().to_vec()),
+    );
+    set_remote_document_lsp_url(doc, &read.path);
+    Ok(())
+}
+
 fn set_remote_document_lsp_url(doc: &mut Document, display_path: &Path) {
     let location = classify_workspace_location(display_path);
     if !location.is_remote() {
@@ -11767,17 +12992,18 @@ fn should_update_env_var(key: &str) -> bool {
 // when processing certain patterns in our codebase
 #[cfg(test)]
 #[allow(dead_code)]
-mod tests {
+pub(crate) mod tests {
 
     use super::editor_input::NativeLspNavigationRequest;
     use super::{
-        Application, EditorInputBridge, LspCompletionTrigger, MaintenanceWake,
-        NativeOpenFileOutcome, NativeSymbolItem, NativeSymbolTarget, PendingCompletionRequest,
-        ProjectEnvironmentProvider, ProjectLspSupervisor, RemoteLspLaunchProxyProvider,
-        WorkspaceDocumentSaveHandler, bridged_event_needs_gpui_context, buffer_text_matches_path,
-        buffer_text_matches_string, buffer_word_completion_items, char_index_for_line_col,
-        coalesce_bridged_events, completion_context_for_trigger, configured_project_servers,
-        current_dir_is_executable_dir, dedupe_completion_items, detect_project_lsp_metadata,
+        Application, CompletionCancellation, EditorInputBridge, LspCompletionTrigger,
+        MaintenanceWake, NativeOpenFileOutcome, NativeSymbolItem, NativeSymbolTarget,
+        PendingCompletionRequest, ProjectEnvironmentProvider, ProjectLspSupervisor,
+        RemoteLspLaunchProxyProvider, WorkspaceDocumentSaveHandler,
+        bridged_event_needs_gpui_context, buffer_text_matches_path, buffer_text_matches_string,
+        buffer_word_completion_items, char_index_for_line_col, coalesce_bridged_events,
+        completion_context_for_trigger, configured_project_servers, current_dir_is_executable_dir,
+        dedupe_completion_items, detect_project_lsp_metadata,
         detect_project_type_from_workspace_backend, detect_project_type_from_workspace_listing,
         diagnostic_picker_path_label, diagnostic_severity_label,
         discover_project_languages_with_backend, file_picker_current_directory,
@@ -11864,7 +13090,7 @@ mod tests {
         StatusChanged(String, crate::types::Severity),
     }
 
-    static TEST_RUNTIME: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
+    pub(crate) static TEST_RUNTIME: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
         tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
@@ -11932,6 +13158,8 @@ mod tests {
             &event_bridge::BridgedEvent::DocumentClosed {
                 doc_id,
                 was_modified: false,
+                path: None,
+                view_state: None,
             }
         ));
     }
@@ -12342,6 +13570,7 @@ mod tests {
         let mut editor = new_test_editor();
         editor.set_save_handler(Some(Arc::new(WorkspaceDocumentSaveHandler::new(
             backend.clone(),
+            crate::config::SaveConfig::default(),
         ))));
 
         let doc_id = open_workspace_document(
@@ -12747,6 +13976,7 @@ mod tests {
         let mut editor = new_test_editor();
         editor.set_save_handler(Some(Arc::new(WorkspaceDocumentSaveHandler::new(
             backend.clone(),
+            crate::config::SaveConfig::default(),
         ))));
 
         let doc_id = open_workspace_document(
@@ -13021,7 +14251,7 @@ mod tests {
         );
     }
 
-    fn new_test_application(cx: &mut gpui::TestAppContext) -> Entity<Application> {
+    pub(crate) fn new_test_application(cx: &mut gpui::TestAppContext) -> Entity<Application> {
         cx.new(|cx| {
             let _runtime = TEST_RUNTIME.enter();
             let helix_config = Arc::new(ArcSwap::from_pointee(HelixConfig::default()));
@@ -13054,6 +14284,7 @@ mod tests {
                 jobs: Jobs::new(),
                 lsp_progress: LspProgressMap::new(),
                 lsp_state: None,
+                lsp_request_guard: nucleotide_lsp::LspRequestGuard::default(),
                 project_directory: None,
                 workspace_backend: local_workspace_backend(),
                 event_bridge_rx: None,
@@ -13074,6 +14305,8 @@ mod tests {
                 project_env_overrides: HashMap::new(),
                 prewarmed_lsp_startups: HashSet::new(),
                 pending_lsp_workspace_edits: std::collections::VecDeque::new(),
+                pending_lsp_crashes: Vec::new(),
+                crashed_lsp_servers: HashMap::new(),
                 terminal_runtime: crate::application::TerminalRuntimeHandle::new(),
                 maintenance_wake: None,
             };
@@ -13867,6 +15100,7 @@ mod tests {
         let config = crate::config::LspConfig {
             project_lsp_startup: true,
             startup_timeout_ms: 7_500,
+            ..crate::config::LspConfig::default()
         };
 
         let manager_config = project_lsp_config(&config);
@@ -14175,6 +15409,34 @@ language-servers = ["taplo"]
         assert_eq!(cached.generation, result.generation);
     }
 
+    #[test]
+    fn project_lsp_supervisor_close_ends_session_and_fails_waiters() {
+        let root = PathBuf::from("/workspace/project");
+        let mut supervisor = ProjectLspSupervisor::default();
+        assert!(supervisor.close_session().is_none());
+
+        let (waiter_tx, waiter_rx) = tokio::sync::oneshot::channel();
+        let transition = supervisor
+            .queue_session_open(root.clone(), waiter_tx)
+            .expect("request starts the session");
+
+        assert_eq!(supervisor.close_session(), Some(root.clone()));
+        assert!(!supervisor.is_current(transition.generation, &root));
+        assert!(!supervisor.allows_start(transition.generation, &root));
+        assert!(matches!(
+            TEST_RUNTIME.block_on(waiter_rx).unwrap(),
+            Err(ProjectLspCommandError::StaleProjectSession)
+        ));
+
+        // Reopening the same root after a close starts a fresh session.
+        let (reopen_tx, _reopen_rx) = tokio::sync::oneshot::channel();
+        let reopened = supervisor
+            .queue_session_open(root, reopen_tx)
+            .expect("reopening starts a new session");
+        assert!(reopened.previous_root.is_none());
+        assert!(reopened.generation > transition.generation);
+    }
+
     #[test]
     fn project_lsp_inventory_discovers_nested_languages_before_document_open() {
         let project = tempdir().unwrap();
@@ -14838,6 +16100,7 @@ language-servers = ["taplo"]
             local_futures: FuturesOrdered::new(),
             lsp_error: None,
             lsp_futures,
+            cancellation: CompletionCancellation::default(),
         };
 
         let (items, prefix, is_incomplete, incomplete_server_ids) =
@@ -14882,6 +16145,7 @@ language-servers = ["taplo"]
             local_futures: FuturesOrdered::new(),
             lsp_error: None,
             lsp_futures,
+            cancellation: CompletionCancellation::default(),
         };
 
         let (items, _, _, _) = request.collect().await.expect("completion results");
@@ -14902,6 +16166,7 @@ language-servers = ["taplo"]
             local_futures: FuturesOrdered::new(),
             lsp_error: Some(anyhow::anyhow!("no completion server")),
             lsp_futures: FuturesOrdered::new(),
+            cancellation: CompletionCancellation::default(),
         };
 
         let (items, prefix, is_incomplete, incomplete_server_ids) =
@@ -14942,6 +16207,7 @@ language-servers = ["taplo"]
             local_futures: FuturesOrdered::new(),
             lsp_error: None,
             lsp_futures,
+            cancellation: CompletionCancellation::default(),
         };
 
         let (items, prefix, is_incomplete, incomplete_server_ids) =
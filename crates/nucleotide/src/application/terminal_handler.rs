@@ -13,12 +13,15 @@ use std::time::Duration;
 use nucleotide_terminal::TerminalBounds;
 #[cfg(feature = "terminal-emulator-core")]
 use nucleotide_terminal::session::ControlMsg;
-use nucleotide_terminal::session::{TerminalSession, TerminalSessionCfg};
+use nucleotide_terminal::session::{
+    SessionInput, TerminalSession, TerminalSessionCfg, terminal_display_name,
+};
 use nucleotide_terminal_view::{TerminalViewModel, register_view_model};
 
 /// Shared map of terminal input senders, allowing the UI thread to bypass the
 /// event queue and write keystrokes directly to the PTY background writer.
-pub type TerminalInputSenders = Arc<Mutex<HashMap<TerminalId, std::sync::mpsc::Sender<Vec<u8>>>>>;
+pub type TerminalInputSenders =
+    Arc<Mutex<HashMap<TerminalId, std::sync::mpsc::Sender<SessionInput>>>>;
 
 #[derive(Clone)]
 pub struct TerminalRuntimeHandle {
@@ -46,12 +49,38 @@ impl TerminalRuntimeHandle {
         }
     }
 
+    /// Hangs up every session, killing shells that outlive the hangup, and
+    /// returns how many were running. Blocks while the shells exit.
+    pub fn shutdown_all(&self) -> usize {
+        match self.inner.lock() {
+            Ok(mut handler) => handler.shutdown_all(),
+            Err(poisoned) => poisoned.into_inner().shutdown_all(),
+        }
+    }
+
     pub fn send_input(&self, id: TerminalId, bytes: Vec<u8>) -> bool {
+        self.queue_input(id, SessionInput::Bytes(bytes))
+    }
+
+    /// Queues clipboard text for the session to encode and write, behind
+    /// any keystrokes already queued.
+    pub fn paste(&self, id: TerminalId, text: &str, bracketed: bool) -> bool {
+        self.queue_input(
+            id,
+            SessionInput::Paste {
+                text: text.to_string(),
+                bracketed,
+            },
+        )
+    }
+
+    /// Queues `input` on the session's writer thread.
+    pub fn queue_input(&self, id: TerminalId, input: SessionInput) -> bool {
         self.input_senders
             .lock()
             .ok()
             .and_then(|senders| senders.get(&id).cloned())
-            .is_some_and(|sender| sender.send(bytes).is_ok())
+            .is_some_and(|sender| sender.send(input).is_ok())
     }
 }
 
@@ -86,7 +115,7 @@ struct SessionEntry {
     exit_task: std::thread::JoinHandle<()>,
     exit_reported: Arc<AtomicBool>,
     // Background input writer to avoid blocking on each key press
-    input_tx: std::sync::mpsc::Sender<Vec<u8>>,
+    input_tx: std::sync::mpsc::Sender<SessionInput>,
     #[allow(dead_code)]
     input_task: std::thread::JoinHandle<()>,
     view: Arc<Mutex<TerminalViewModel>>,
@@ -184,6 +213,9 @@ fn terminal_spawn_failure_details(cfg: &TerminalSessionCfg, error: &anyhow::Erro
     if let Some(cwd) = cfg.cwd.as_ref() {
         details.insert(1, format!("Working directory: {}", cwd.display()));
     }
+    if let Some(profile) = cfg.profile.as_ref() {
+        details.insert(0, format!("Profile: {profile}"));
+    }
 
     if terminal_program_is_ssh(cfg) {
         details.push(
@@ -229,7 +261,11 @@ impl TerminalRuntimeHandler {
     #[allow(clippy::await_holding_lock)]
     fn handle_spawn(&mut self, id: TerminalId, cfg: &TerminalSessionCfg) {
         let cfg = cfg.clone();
-        let view = Arc::new(Mutex::new(TerminalViewModel::new(id)));
+        let mut view_model = TerminalViewModel::new(id);
+        if let Some(name) = terminal_display_name(&cfg) {
+            view_model.set_fallback_title(name);
+        }
+        let view = Arc::new(Mutex::new(view_model));
         register_view_model(id, view.clone());
 
         let (session, mut rx) =
@@ -244,6 +280,8 @@ impl TerminalRuntimeHandler {
                 }
             };
 
+        lock_view_model(view.as_ref(), id, "set_cwd_tracker")
+            .set_cwd_tracker(session.cwd_tracker());
         #[cfg(feature = "terminal-emulator-core")]
         {
             lock_view_model(view.as_ref(), id, "set_control_sender")
@@ -254,18 +292,25 @@ impl TerminalRuntimeHandler {
         // Wrap session for cross-thread access and create a non-blocking input queue
         let session_arc = Arc::new(Mutex::new(session));
 
-        // Spawn a blocking thread to consume frames, coalescing bursts to the latest
+        // Spawn a blocking thread to consume frames, dropping those a queued full frame replaces
         let session_for_exit = Arc::clone(&session_arc);
         let exit_reported = Arc::new(AtomicBool::new(false));
         let rx_exit_reported = Arc::clone(&exit_reported);
         let handle = std::thread::spawn(move || {
-            while let Some(mut frame) = futures_executor::block_on(rx.recv()) {
-                // Drain any queued frames to coalesce updates
+            while let Some(frame) = futures_executor::block_on(rx.recv()) {
+                // Drain queued frames; diffs build on earlier frames, so only a
+                // full frame lets the grid frames before it go.
+                let mut frames = vec![frame];
                 while let Ok(next) = rx.try_recv() {
-                    frame = next;
+                    if next.is_full() {
+                        frames.retain(|frame| frame.is_shell_event());
+                    }
+                    frames.push(next);
                 }
                 let mut guard = lock_view_model(view_clone.as_ref(), id, "apply_frame");
-                guard.apply_frame(frame);
+                for frame in frames {
+                    guard.apply_frame(frame);
+                }
             }
             // Channel closed: reap the shell and mark the shared view model exited.
             lock_view_model(view_clone.as_ref(), id, "set_exited").set_exited();
@@ -296,17 +341,17 @@ impl TerminalRuntimeHandler {
             }
         });
 
-        let (tx, rx_input) = std::sync::mpsc::channel::<Vec<u8>>();
+        let (tx, rx_input) = std::sync::mpsc::channel::<SessionInput>();
         #[cfg(feature = "terminal-emulator-core")]
         if let Ok(mut guard) = view.lock() {
             guard.set_input_sender(tx.clone());
         }
         let session_for_input = session_arc.clone();
         let input_task = std::thread::spawn(move || {
-            while let Ok(bytes) = rx_input.recv() {
+            while let Ok(input) = rx_input.recv() {
                 // Best-effort synchronous write; no block_on overhead
                 if let Ok(guard) = session_for_input.lock() {
-                    let _ = guard.write_sync(&bytes);
+                    let _ = guard.send(&input);
                 }
             }
         });
@@ -424,22 +469,39 @@ impl TerminalRuntimeHandler {
         }
     }
 
+    fn shutdown_all(&mut self) -> usize {
+        let ids = self.sessions.keys().copied().collect::<Vec<_>>();
+        for id in &ids {
+            self.handle_event(&TerminalEvent::Exited {
+                id: *id,
+                code: None,
+                signal: None,
+            });
+        }
+        ids.len()
+    }
+
     fn handle_event(&mut self, event: &TerminalEvent) {
         match event {
             TerminalEvent::SpawnRequested {
                 id,
                 cwd,
                 shell,
+                args,
                 env,
+                profile,
+                scrollback_lines,
             } => {
                 let cfg = TerminalSessionCfg {
                     cwd: cwd.clone(),
                     shell: shell.clone(),
                     program: None,
-                    args: Vec::new(),
+                    args: args.clone(),
                     env: env.clone(),
                     cols: Some(80),
                     rows: Some(24),
+                    profile: profile.clone(),
+                    scrollback_lines: *scrollback_lines,
                 };
                 self.handle_spawn(*id, &cfg);
             }
@@ -449,6 +511,7 @@ impl TerminalRuntimeHandler {
                 program,
                 args,
                 env,
+                scrollback_lines,
             } => {
                 let cfg = TerminalSessionCfg {
                     cwd: cwd.clone(),
@@ -458,6 +521,8 @@ impl TerminalRuntimeHandler {
                     env: env.clone(),
                     cols: Some(80),
                     rows: Some(24),
+                    profile: None,
+                    scrollback_lines: *scrollback_lines,
                 };
                 self.handle_spawn(*id, &cfg);
             }
@@ -472,7 +537,7 @@ impl TerminalRuntimeHandler {
             }
             TerminalEvent::Input { id, bytes } => {
                 if let Some(entry) = self.sessions.get(id) {
-                    let _ = entry.input_tx.send(bytes.clone());
+                    let _ = entry.input_tx.send(SessionInput::Bytes(bytes.clone()));
                 }
             }
             TerminalEvent::Exited { id, .. } => {
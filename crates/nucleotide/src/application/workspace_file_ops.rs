@@ -1,27 +1,66 @@
 // ABOUTME: Backend-aware workspace file operation handler
-// ABOUTME: Routes file tree mutations through WorkspaceBackend instead of host filesystem calls
+// ABOUTME: Routes file tree mutations through WorkspaceBackend and journals them for undo
 
 use std::{
+    collections::VecDeque,
     future::Future,
     io,
     path::{Path, PathBuf},
     process::Command,
+    sync::{Arc, Mutex},
 };
 
 use nucleotide_events::v2::workspace::{
-    DeleteMode, Event as WorkspaceEvent, FileOpIntent, PathCopyKind,
+    DeleteMode, Event as WorkspaceEvent, FileOpIntent, FileOpOutcome, PathCopyKind,
 };
 use nucleotide_logging::{error, info, warn};
-use nucleotide_workspace::{WorkspaceBackendHandle, WorkspaceError, WorkspaceIdentity};
+use nucleotide_workspace::{
+    FileKind, ReadOptions, WorkspaceBackendHandle, WorkspaceError, WorkspaceIdentity,
+};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+/// Prompt command that reverses the most recent file operation.
+pub const UNDO_FILE_OP_COMMAND: &str = "undo-file-op";
+
+/// Completed operations remembered for undo.
+const FILE_OP_JOURNAL_CAPACITY: usize = 50;
+/// Duplicated files larger than this are not compared, so never removed by undo.
+const UNDO_COPY_COMPARE_LIMIT_BYTES: u64 = 8 * 1024 * 1024;
 
 pub struct WorkspaceFileOpHandler {
     backend: WorkspaceBackendHandle,
     runtime: tokio::runtime::Handle,
+    journal: Arc<Mutex<FileOpJournal>>,
+    events_tx: UnboundedSender<WorkspaceEvent>,
+    events_rx: Option<UnboundedReceiver<WorkspaceEvent>>,
 }
 
 impl WorkspaceFileOpHandler {
     pub fn new(backend: WorkspaceBackendHandle, runtime: tokio::runtime::Handle) -> Self {
-        Self { backend, runtime }
+        let (events_tx, events_rx) = unbounded_channel();
+        Self {
+            backend,
+            runtime,
+            journal: Arc::default(),
+            events_tx,
+            events_rx: Some(events_rx),
+        }
+    }
+
+    /// Take the receiver of `FileOpCompleted` and `FileOpFailed` events,
+    /// leaving None in its place.
+    pub fn take_event_receiver(&mut self) -> Option<UnboundedReceiver<WorkspaceEvent>> {
+        self.events_rx.take()
+    }
+
+    /// Whether the journal holds an operation to undo.
+    pub fn can_undo(&self) -> bool {
+        !self
+            .journal
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entries
+            .is_empty()
     }
 
     fn create_child_path(
@@ -39,12 +78,31 @@ impl WorkspaceFileOpHandler {
 
     fn spawn_file_op<F>(&self, intent: FileOpIntent, future: F)
     where
-        F: Future<Output = Result<(), WorkspaceError>> + Send + 'static,
+        F: Future<Output = Result<FileOpOutcome, WorkspaceError>> + Send + 'static,
     {
+        let journal = self.journal.clone();
+        let events_tx = self.events_tx.clone();
         self.runtime.spawn(async move {
-            if let Err(err) = future.await {
-                error!(error = %err, intent = ?intent, "Failed to perform workspace file operation");
-            }
+            let event = match future.await {
+                Ok(outcome) => {
+                    // Undoing is not itself undoable.
+                    if intent != FileOpIntent::UndoLast {
+                        journal
+                            .lock()
+                            .unwrap_or_else(|poisoned| poisoned.into_inner())
+                            .record(outcome.clone());
+                    }
+                    WorkspaceEvent::FileOpCompleted { intent, outcome }
+                }
+                Err(err) => {
+                    error!(error = %err, intent = ?intent, "Failed to perform workspace file operation");
+                    WorkspaceEvent::FileOpFailed {
+                        intent,
+                        error: err.to_string(),
+                    }
+                }
+            };
+            let _ = events_tx.send(event);
         });
     }
 
@@ -58,7 +116,10 @@ impl WorkspaceFileOpHandler {
             },
             async move {
                 backend.create_file(&path).await?;
-                Ok(())
+                Ok(FileOpOutcome::Created {
+                    path,
+                    is_dir: false,
+                })
             },
         );
         Ok(())
@@ -74,7 +135,7 @@ impl WorkspaceFileOpHandler {
             },
             async move {
                 backend.create_dir(&path).await?;
-                Ok(())
+                Ok(FileOpOutcome::Created { path, is_dir: true })
             },
         );
         Ok(())
@@ -95,8 +156,12 @@ impl WorkspaceFileOpHandler {
                 new_name: new_name.to_string(),
             },
             async move {
-                backend.rename_path(&old_path, &new_path).await?;
-                Ok(())
+                let stat = backend.rename_path(&old_path, &new_path).await?;
+                Ok(FileOpOutcome::Renamed {
+                    old_path,
+                    new_path,
+                    is_dir: stat.kind == FileKind::Directory,
+                })
             },
         );
         Ok(())
@@ -118,8 +183,12 @@ impl WorkspaceFileOpHandler {
                 mode,
             },
             async move {
-                backend.delete_path(&path).await?;
-                Ok(())
+                let stat = backend.delete_path(&path).await?;
+                Ok(FileOpOutcome::Deleted {
+                    path,
+                    is_dir: stat.kind == FileKind::Directory,
+                    mode,
+                })
             },
         );
         Ok(())
@@ -140,13 +209,36 @@ impl WorkspaceFileOpHandler {
                 target_name: target_name.to_string(),
             },
             async move {
-                backend.copy_path(&source_path, &target_path).await?;
-                Ok(())
+                let stat = backend.copy_path(&source_path, &target_path).await?;
+                Ok(FileOpOutcome::Duplicated {
+                    source: source_path,
+                    target: target_path,
+                    is_dir: stat.kind == FileKind::Directory,
+                })
             },
         );
         Ok(())
     }
 
+    fn handle_undo_last(&self) -> Result<(), WorkspaceError> {
+        let outcome = self
+            .journal
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .pop()
+            .ok_or_else(|| WorkspaceError::CommandFailed {
+                operation: "undo file operation",
+                path: PathBuf::new(),
+                message: "no file operation to undo".to_string(),
+            })?;
+        let step = undo_step(&outcome)?;
+        let backend = self.backend.clone();
+        self.spawn_file_op(FileOpIntent::UndoLast, async move {
+            perform_undo(backend, step).await
+        });
+        Ok(())
+    }
+
     fn handle_file_op(&self, intent: &FileOpIntent) -> Result<(), WorkspaceError> {
         match intent {
             FileOpIntent::NewFile { parent, name } => self.handle_new_file(parent, name),
@@ -161,6 +253,7 @@ impl WorkspaceFileOpHandler {
                 Ok(())
             }
             FileOpIntent::RevealInOs { path } => self.handle_reveal_in_os(path),
+            FileOpIntent::UndoLast => self.handle_undo_last(),
         }
     }
 
@@ -188,6 +281,144 @@ impl WorkspaceFileOpHandler {
 
         if let Err(err) = self.handle_file_op(intent) {
             error!(error = %err, intent = ?intent, "Failed to perform workspace file operation");
+            let _ = self.events_tx.send(WorkspaceEvent::FileOpFailed {
+                intent: intent.clone(),
+                error: err.to_string(),
+            });
+        }
+    }
+}
+
+/// Completed file operations, oldest first.
+#[derive(Debug, Default)]
+struct FileOpJournal {
+    entries: VecDeque<FileOpOutcome>,
+}
+
+impl FileOpJournal {
+    fn record(&mut self, outcome: FileOpOutcome) {
+        if self.entries.len() == FILE_OP_JOURNAL_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(outcome);
+    }
+
+    fn pop(&mut self) -> Option<FileOpOutcome> {
+        self.entries.pop_back()
+    }
+}
+
+/// How a journaled operation is reversed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum UndoStep {
+    /// Move `from` back to `to`.
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+        is_dir: bool,
+    },
+    /// Remove a created path that is still empty.
+    RemoveCreated { path: PathBuf, is_dir: bool },
+    /// Remove a duplicated file that still matches its source.
+    RemoveCopy { source: PathBuf, target: PathBuf },
+}
+
+fn undo_step(outcome: &FileOpOutcome) -> Result<UndoStep, WorkspaceError> {
+    match outcome {
+        FileOpOutcome::Created { path, is_dir } => Ok(UndoStep::RemoveCreated {
+            path: path.clone(),
+            is_dir: *is_dir,
+        }),
+        FileOpOutcome::Renamed {
+            old_path,
+            new_path,
+            is_dir,
+        } => Ok(UndoStep::Rename {
+            from: new_path.clone(),
+            to: old_path.clone(),
+            is_dir: *is_dir,
+        }),
+        FileOpOutcome::Duplicated { source, target, .. } => Ok(UndoStep::RemoveCopy {
+            source: source.clone(),
+            target: target.clone(),
+        }),
+        FileOpOutcome::Deleted { path, mode, .. } => Err(WorkspaceError::CommandFailed {
+            operation: "undo delete",
+            path: path.clone(),
+            message: match mode {
+                DeleteMode::Trash => "restore it from the trash".to_string(),
+                DeleteMode::Permanent => "it was deleted permanently".to_string(),
+            },
+        }),
+    }
+}
+
+async fn perform_undo(
+    backend: WorkspaceBackendHandle,
+    step: UndoStep,
+) -> Result<FileOpOutcome, WorkspaceError> {
+    match step {
+        UndoStep::Rename { from, to, is_dir } => {
+            if backend.stat(&to).await.is_ok() {
+                return Err(WorkspaceError::CommandFailed {
+                    operation: "undo rename",
+                    path: to,
+                    message: "a file already exists at the original path".to_string(),
+                });
+            }
+            backend.rename_path(&from, &to).await?;
+            Ok(FileOpOutcome::Renamed {
+                old_path: from,
+                new_path: to,
+                is_dir,
+            })
+        }
+        UndoStep::RemoveCreated { path, is_dir } => {
+            let untouched = if is_dir {
+                backend.list_dir(&path).await?.entries.is_empty()
+            } else {
+                backend.stat(&path).await?.size == 0
+            };
+            if !untouched {
+                return Err(WorkspaceError::CommandFailed {
+                    operation: "undo create",
+                    path,
+                    message: "it is no longer empty".to_string(),
+                });
+            }
+            backend.delete_path(&path).await?;
+            Ok(FileOpOutcome::Deleted {
+                path,
+                is_dir,
+                mode: DeleteMode::Permanent,
+            })
+        }
+        UndoStep::RemoveCopy { source, target } => {
+            if backend.stat(&target).await?.kind != FileKind::File {
+                return Err(WorkspaceError::CommandFailed {
+                    operation: "undo duplicate",
+                    path: target,
+                    message: "duplicated folders are not removed automatically".to_string(),
+                });
+            }
+            let options = ReadOptions {
+                max_bytes: Some(UNDO_COPY_COMPARE_LIMIT_BYTES),
+            };
+            let copy = backend.read_file(&target, options).await?;
+            let original = backend.read_file(&source, options).await?;
+            if copy.truncated || copy.bytes != original.bytes {
+                return Err(WorkspaceError::CommandFailed {
+                    operation: "undo duplicate",
+                    path: target,
+                    message: "the copy no longer matches the original".to_string(),
+                });
+            }
+            backend.delete_path(&target).await?;
+            Ok(FileOpOutcome::Deleted {
+                path: target,
+                is_dir: false,
+                mode: DeleteMode::Permanent,
+            })
         }
     }
 }
@@ -386,4 +617,127 @@ mod tests {
 
         panic!("timed out waiting for async file creation");
     }
+
+    #[test]
+    fn journal_forgets_the_oldest_operations() {
+        let mut journal = FileOpJournal::default();
+        for index in 0..=FILE_OP_JOURNAL_CAPACITY {
+            journal.record(FileOpOutcome::Created {
+                path: PathBuf::from(format!("/repo/{index}.rs")),
+                is_dir: false,
+            });
+        }
+
+        assert_eq!(journal.entries.len(), FILE_OP_JOURNAL_CAPACITY);
+        assert_eq!(
+            journal.pop(),
+            Some(FileOpOutcome::Created {
+                path: PathBuf::from(format!("/repo/{FILE_OP_JOURNAL_CAPACITY}.rs")),
+                is_dir: false,
+            })
+        );
+    }
+
+    #[test]
+    fn permanent_deletes_cannot_be_undone() {
+        assert_eq!(
+            undo_step(&FileOpOutcome::Renamed {
+                old_path: PathBuf::from("/repo/a.rs"),
+                new_path: PathBuf::from("/repo/b.rs"),
+                is_dir: false,
+            })
+            .unwrap(),
+            UndoStep::Rename {
+                from: PathBuf::from("/repo/b.rs"),
+                to: PathBuf::from("/repo/a.rs"),
+                is_dir: false,
+            }
+        );
+        assert!(
+            undo_step(&FileOpOutcome::Deleted {
+                path: PathBuf::from("/repo/a.rs"),
+                is_dir: false,
+                mode: DeleteMode::Permanent,
+            })
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn undo_reverses_the_last_rename() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let old_path = temp_dir.path().join("old.rs");
+        let new_path = temp_dir.path().join("new.rs");
+        std::fs::write(&old_path, "fn main() {}").unwrap();
+        let mut handler = WorkspaceFileOpHandler::new(
+            nucleotide_workspace::local_workspace_backend(),
+            tokio::runtime::Handle::current(),
+        );
+        let mut events = handler.take_event_receiver().unwrap();
+
+        handler.dispatch(&WorkspaceEvent::FileOpRequested {
+            intent: FileOpIntent::Rename {
+                path: old_path.clone(),
+                new_name: "new.rs".to_string(),
+            },
+        });
+        assert!(matches!(
+            events.recv().await,
+            Some(WorkspaceEvent::FileOpCompleted {
+                outcome: FileOpOutcome::Renamed { .. },
+                ..
+            })
+        ));
+        assert!(new_path.exists());
+
+        handler.dispatch(&WorkspaceEvent::FileOpRequested {
+            intent: FileOpIntent::UndoLast,
+        });
+        assert!(matches!(
+            events.recv().await,
+            Some(WorkspaceEvent::FileOpCompleted {
+                intent: FileOpIntent::UndoLast,
+                ..
+            })
+        ));
+        assert!(old_path.exists());
+        assert!(!new_path.exists());
+
+        handler.dispatch(&WorkspaceEvent::FileOpRequested {
+            intent: FileOpIntent::UndoLast,
+        });
+        assert!(matches!(
+            events.recv().await,
+            Some(WorkspaceEvent::FileOpFailed { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn undo_keeps_created_files_that_have_content() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut handler = WorkspaceFileOpHandler::new(
+            nucleotide_workspace::local_workspace_backend(),
+            tokio::runtime::Handle::current(),
+        );
+        let mut events = handler.take_event_receiver().unwrap();
+
+        handler.dispatch(&WorkspaceEvent::FileOpRequested {
+            intent: FileOpIntent::NewFile {
+                parent: temp_dir.path().to_path_buf(),
+                name: "notes.md".to_string(),
+            },
+        });
+        events.recv().await.unwrap();
+        std::fs::write(temp_dir.path().join("notes.md"), "keep me").unwrap();
+
+        handler.dispatch(&WorkspaceEvent::FileOpRequested {
+            intent: FileOpIntent::UndoLast,
+        });
+
+        assert!(matches!(
+            events.recv().await,
+            Some(WorkspaceEvent::FileOpFailed { .. })
+        ));
+        assert!(temp_dir.path().join("notes.md").exists());
+    }
 }
@@ -0,0 +1,668 @@
+// ABOUTME: Cargo package, target and feature selection for Rust projects
+// ABOUTME: Applies the selection to cargo tasks and rust-analyzer settings and persists it per project
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use gpui::Global;
+use nucleotide_events::v2::run::CommandSpec;
+use serde::{Deserialize, Serialize};
+
+/// Prompt command opening the Cargo settings picker.
+pub const CARGO_SETTINGS_COMMAND: &str = "cargo-settings";
+/// Prompt commands for the Cargo integration, with completion descriptions.
+pub const CARGO_COMMANDS: &[(&str, &str)] = &[(
+    CARGO_SETTINGS_COMMAND,
+    "Choose the Cargo package, target and features used by runs and rust-analyzer",
+)];
+/// Language server receiving the feature selection.
+pub const RUST_ANALYZER: &str = "rust-analyzer";
+const STORE_FILE_NAME: &str = "cargo-selections.toml";
+/// Projects remembered; the least recently used is dropped first.
+const MAX_PROJECTS: usize = 20;
+const METADATA_TIMEOUT: Duration = Duration::from_secs(30);
+const METADATA_MAX_OUTPUT_BYTES: usize = 16 * 1024 * 1024;
+/// Cargo subcommands that take package and feature flags.
+const FEATURE_SUBCOMMANDS: &[&str] = &["build", "check", "clippy", "run", "test", "bench", "doc"];
+/// Cargo subcommands the selected target applies to. Test and bench tasks
+/// already name what they run.
+const TARGET_SUBCOMMANDS: &[&str] = &["build", "check", "clippy", "run"];
+const TARGET_FLAGS: &[&str] = &[
+    "--lib",
+    "--bin",
+    "--bins",
+    "--example",
+    "--examples",
+    "--test",
+    "--tests",
+    "--bench",
+    "--benches",
+    "--all-targets",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CargoTargetKind {
+    Lib,
+    Bin,
+    Example,
+    Test,
+    Bench,
+}
+
+impl CargoTargetKind {
+    fn from_metadata(kinds: &[String]) -> Option<Self> {
+        kinds.iter().find_map(|kind| match kind.as_str() {
+            "lib" | "rlib" | "dylib" | "cdylib" | "staticlib" | "proc-macro" => Some(Self::Lib),
+            "bin" => Some(Self::Bin),
+            "example" => Some(Self::Example),
+            "test" => Some(Self::Test),
+            "bench" => Some(Self::Bench),
+            _ => None,
+        })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Lib => "lib",
+            Self::Bin => "bin",
+            Self::Example => "example",
+            Self::Test => "test",
+            Self::Bench => "bench",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CargoTarget {
+    pub kind: CargoTargetKind,
+    pub name: String,
+}
+
+impl CargoTarget {
+    /// Cargo arguments selecting this target.
+    pub fn args(&self) -> Vec<String> {
+        match self.kind {
+            CargoTargetKind::Lib => vec!["--lib".to_string()],
+            kind => vec![format!("--{}", kind.label()), self.name.clone()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CargoPackage {
+    pub name: String,
+    pub manifest_path: PathBuf,
+    pub targets: Vec<CargoTarget>,
+    /// Feature names other than `default`, sorted
+    pub features: Vec<String>,
+}
+
+/// Workspace members reported by `cargo metadata --no-deps`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CargoWorkspace {
+    pub packages: Vec<CargoPackage>,
+}
+
+impl CargoWorkspace {
+    pub fn package(&self, name: &str) -> Option<&CargoPackage> {
+        self.packages.iter().find(|package| package.name == name)
+    }
+
+    /// Packages the selection draws targets and features from: the selected
+    /// one, or every member.
+    pub fn selected_packages<'a>(
+        &'a self,
+        selection: &CargoSelection,
+    ) -> impl Iterator<Item = &'a CargoPackage> {
+        let package = selection.package.clone();
+        self.packages
+            .iter()
+            .filter(move |candidate| package.as_ref().is_none_or(|name| &candidate.name == name))
+    }
+}
+
+#[derive(Deserialize)]
+struct MetadataJson {
+    packages: Vec<MetadataPackage>,
+}
+
+#[derive(Deserialize)]
+struct MetadataPackage {
+    name: String,
+    manifest_path: PathBuf,
+    #[serde(default)]
+    targets: Vec<MetadataTarget>,
+    #[serde(default)]
+    features: std::collections::BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct MetadataTarget {
+    name: String,
+    kind: Vec<String>,
+}
+
+/// Parses the JSON printed by `cargo metadata --format-version 1`.
+pub fn parse_metadata(json: &str) -> Result<CargoWorkspace> {
+    let metadata: MetadataJson =
+        serde_json::from_str(json).context("failed to parse cargo metadata")?;
+    let packages = metadata
+        .packages
+        .into_iter()
+        .map(|package| CargoPackage {
+            name: package.name,
+            manifest_path: package.manifest_path,
+            targets: package
+                .targets
+                .into_iter()
+                .filter_map(|target| {
+                    Some(CargoTarget {
+                        kind: CargoTargetKind::from_metadata(&target.kind)?,
+                        name: target.name,
+                    })
+                })
+                .collect(),
+            features: package
+                .features
+                .into_keys()
+                .filter(|feature| feature != "default")
+                .collect(),
+        })
+        .collect();
+    Ok(CargoWorkspace { packages })
+}
+
+/// Runs `cargo metadata` for the workspace at `root`. Blocks until cargo
+/// exits or times out.
+pub fn load_workspace(root: &Path) -> Result<CargoWorkspace> {
+    let mut command = nucleotide_process::command("cargo");
+    command
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .current_dir(root)
+        .stdin(std::process::Stdio::null());
+    let output = nucleotide_process::output_with_limits(
+        &mut command,
+        nucleotide_process::OutputLimits::new(
+            METADATA_TIMEOUT,
+            METADATA_MAX_OUTPUT_BYTES,
+            64 * 1024,
+        ),
+    )
+    .context("failed to run cargo metadata")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("cargo metadata failed");
+        anyhow::bail!("{}", reason.trim());
+    }
+    parse_metadata(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Package, target and features passed to cargo tasks and rust-analyzer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CargoSelection {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<CargoTarget>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub all_features: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_default_features: bool,
+}
+
+impl CargoSelection {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn toggle_feature(&mut self, feature: &str) {
+        if let Some(index) = self.features.iter().position(|name| name == feature) {
+            self.features.remove(index);
+        } else {
+            self.features.push(feature.to_string());
+            self.features.sort();
+        }
+    }
+
+    /// Drops the target and features that `workspace` no longer provides
+    /// for the selected package.
+    pub fn retain_available(&mut self, workspace: &CargoWorkspace) {
+        if self
+            .package
+            .as_deref()
+            .is_some_and(|name| workspace.package(name).is_none())
+        {
+            self.package = None;
+        }
+        let packages = workspace.selected_packages(self).collect::<Vec<_>>();
+        if let Some(target) = &self.target
+            && !packages
+                .iter()
+                .any(|package| package.targets.contains(target))
+        {
+            self.target = None;
+        }
+        self.features.retain(|feature| {
+            packages
+                .iter()
+                .any(|package| package.features.contains(feature))
+        });
+    }
+
+    /// Short description for the status line and picker headers.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![
+            self.package
+                .clone()
+                .unwrap_or_else(|| "all packages".into()),
+        ];
+        if let Some(target) = &self.target {
+            parts.push(format!("{} {}", target.kind.label(), target.name));
+        }
+        if self.all_features {
+            parts.push("all features".to_string());
+        } else if !self.features.is_empty() {
+            parts.push(self.features.join(", "));
+        }
+        if self.no_default_features {
+            parts.push("no default features".to_string());
+        }
+        parts.join(" · ")
+    }
+
+    /// Cargo arguments for the feature flags.
+    fn feature_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.all_features {
+            args.push("--all-features".to_string());
+        } else if !self.features.is_empty() {
+            args.push("--features".to_string());
+            args.push(self.features.join(","));
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".to_string());
+        }
+        args
+    }
+
+    /// Adds the selection to a cargo command. Flags the command already
+    /// sets are left alone, so applying twice changes nothing.
+    pub fn apply_to_command(&self, command: &mut CommandSpec) {
+        let is_cargo = Path::new(&command.program)
+            .file_stem()
+            .is_some_and(|stem| stem == "cargo");
+        let Some(subcommand) = command.args.first().cloned() else {
+            return;
+        };
+        if !is_cargo || !FEATURE_SUBCOMMANDS.contains(&subcommand.as_str()) {
+            return;
+        }
+
+        let end = command
+            .args
+            .iter()
+            .position(|arg| arg == "--")
+            .unwrap_or(command.args.len());
+        let cargo_args = &command.args[1..end];
+        let has = |flags: &[&str]| {
+            cargo_args.iter().any(|arg| {
+                flags
+                    .iter()
+                    .any(|flag| arg == flag || arg.starts_with(&format!("{flag}=")))
+            })
+        };
+
+        let mut added = Vec::new();
+        if let Some(package) = &self.package
+            && !has(&["-p", "--package", "--workspace", "--all"])
+        {
+            added.extend(["--package".to_string(), package.clone()]);
+        }
+        if let Some(target) = &self.target
+            && TARGET_SUBCOMMANDS.contains(&subcommand.as_str())
+            && !has(TARGET_FLAGS)
+        {
+            added.extend(target.args());
+        }
+        if !has(&[
+            "-F",
+            "--features",
+            "--all-features",
+            "--no-default-features",
+        ]) {
+            added.extend(self.feature_args());
+        }
+        command.args.splice(end..end, added);
+    }
+
+    /// rust-analyzer settings for the feature selection, or `None` when the
+    /// server's own configuration should be used unchanged.
+    pub fn rust_analyzer_settings(&self) -> Option<serde_json::Value> {
+        let features = if self.all_features {
+            serde_json::json!("all")
+        } else {
+            serde_json::json!(self.features)
+        };
+        (self.all_features || !self.features.is_empty() || self.no_default_features).then(|| {
+            serde_json::json!({
+                "cargo": {
+                    "features": features,
+                    "noDefaultFeatures": self.no_default_features,
+                }
+            })
+        })
+    }
+}
+
+/// A row of the Cargo settings picker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CargoChoice {
+    ShowPackages,
+    ShowTargets,
+    ShowFeatures,
+    Package(Option<String>),
+    Target(Option<CargoTarget>),
+    ToggleFeature(String),
+    ToggleAllFeatures,
+    ToggleNoDefaultFeatures,
+    Reset,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectCargoSelection {
+    pub root: PathBuf,
+    pub selection: CargoSelection,
+}
+
+/// The Cargo selection of each project.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CargoSelectionStore {
+    /// Most recently changed project first
+    #[serde(default)]
+    pub projects: Vec<ProjectCargoSelection>,
+}
+
+impl CargoSelectionStore {
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .with_context(|| format!("failed to parse {}", path.display())),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error).with_context(|| format!("failed to read {}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        let contents = toml::to_string_pretty(self).context("failed to encode cargo selections")?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    pub fn get(&self, root: &Path) -> Option<&CargoSelection> {
+        self.projects
+            .iter()
+            .find(|project| project.root == root)
+            .map(|project| &project.selection)
+    }
+
+    /// Stores `selection` for `root`; a default selection forgets the
+    /// project.
+    pub fn set(&mut self, root: &Path, selection: CargoSelection) {
+        self.projects.retain(|project| project.root != root);
+        if selection.is_default() {
+            return;
+        }
+        self.projects.insert(
+            0,
+            ProjectCargoSelection {
+                root: root.to_path_buf(),
+                selection,
+            },
+        );
+        self.projects.truncate(MAX_PROJECTS);
+    }
+}
+
+/// The Cargo selections for this session, installed as a GPUI global.
+#[derive(Debug)]
+pub struct CargoSelections {
+    store: CargoSelectionStore,
+    path: PathBuf,
+}
+
+impl Global for CargoSelections {}
+
+impl CargoSelections {
+    pub fn load_default() -> Self {
+        let path = default_store_path();
+        let store = CargoSelectionStore::load(&path).unwrap_or_else(|error| {
+            nucleotide_logging::warn!(error = %error, "Ignoring unreadable cargo selections");
+            CargoSelectionStore::default()
+        });
+        Self { store, path }
+    }
+
+    pub fn get(&self, root: &Path) -> CargoSelection {
+        self.store.get(root).cloned().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, root: &Path, selection: CargoSelection) {
+        if self.get(root) == selection {
+            return;
+        }
+        self.store.set(root, selection);
+        if let Err(error) = self.store.save(&self.path) {
+            nucleotide_logging::warn!(error = %error, "Failed to save cargo selections");
+        }
+    }
+}
+
+pub fn default_store_path() -> PathBuf {
+    helix_loader::config_dir().join(STORE_FILE_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METADATA: &str = r#"{
+        "packages": [
+            {
+                "name": "app",
+                "manifest_path": "/work/app/Cargo.toml",
+                "targets": [
+                    { "name": "app", "kind": ["lib"] },
+                    { "name": "app", "kind": ["bin"] },
+                    { "name": "demo", "kind": ["example"] },
+                    { "name": "build-script-build", "kind": ["custom-build"] }
+                ],
+                "features": { "default": ["tls"], "tls": [], "metrics": [] }
+            },
+            {
+                "name": "server",
+                "manifest_path": "/work/app/crates/server/Cargo.toml",
+                "targets": [{ "name": "server", "kind": ["bin"] }],
+                "features": {}
+            }
+        ],
+        "workspace_root": "/work/app"
+    }"#;
+
+    fn command(args: &[&str]) -> CommandSpec {
+        CommandSpec::new("cargo").with_args(args.iter().copied())
+    }
+
+    #[test]
+    fn metadata_lists_packages_targets_and_features() {
+        let workspace = parse_metadata(METADATA).unwrap();
+        assert_eq!(workspace.packages.len(), 2);
+
+        let app = workspace.package("app").unwrap();
+        assert_eq!(
+            app.targets,
+            vec![
+                CargoTarget {
+                    kind: CargoTargetKind::Lib,
+                    name: "app".into()
+                },
+                CargoTarget {
+                    kind: CargoTargetKind::Bin,
+                    name: "app".into()
+                },
+                CargoTarget {
+                    kind: CargoTargetKind::Example,
+                    name: "demo".into()
+                },
+            ]
+        );
+        assert_eq!(app.features, vec!["metrics", "tls"]);
+        assert!(parse_metadata("not json").is_err());
+    }
+
+    #[test]
+    fn selection_is_added_to_cargo_commands_once() {
+        let selection = CargoSelection {
+            package: Some("server".into()),
+            target: Some(CargoTarget {
+                kind: CargoTargetKind::Bin,
+                name: "server".into(),
+            }),
+            features: vec!["metrics".into(), "tls".into()],
+            all_features: false,
+            no_default_features: true,
+        };
+
+        let mut run = command(&["run", "--", "--port", "8080"]);
+        selection.apply_to_command(&mut run);
+        assert_eq!(
+            run.args,
+            vec![
+                "run",
+                "--package",
+                "server",
+                "--bin",
+                "server",
+                "--features",
+                "metrics,tls",
+                "--no-default-features",
+                "--",
+                "--port",
+                "8080",
+            ]
+        );
+        let applied = run.clone();
+        selection.apply_to_command(&mut run);
+        assert_eq!(run, applied);
+
+        let mut test = command(&["test", "-p", "app", "parses_input", "--", "--nocapture"]);
+        selection.apply_to_command(&mut test);
+        assert_eq!(
+            test.args,
+            vec![
+                "test",
+                "-p",
+                "app",
+                "parses_input",
+                "--features",
+                "metrics,tls",
+                "--no-default-features",
+                "--",
+                "--nocapture",
+            ]
+        );
+
+        let mut other = CommandSpec::new("npm").with_args(["run", "build"]);
+        selection.apply_to_command(&mut other);
+        assert_eq!(other.args, vec!["run", "build"]);
+    }
+
+    #[test]
+    fn rust_analyzer_settings_follow_the_feature_selection() {
+        assert_eq!(CargoSelection::default().rust_analyzer_settings(), None);
+
+        let selection = CargoSelection {
+            features: vec!["tls".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            selection.rust_analyzer_settings(),
+            Some(serde_json::json!({
+                "cargo": { "features": ["tls"], "noDefaultFeatures": false }
+            }))
+        );
+
+        let selection = CargoSelection {
+            features: vec!["tls".into()],
+            all_features: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            selection.rust_analyzer_settings(),
+            Some(serde_json::json!({
+                "cargo": { "features": "all", "noDefaultFeatures": false }
+            }))
+        );
+    }
+
+    #[test]
+    fn unavailable_choices_are_dropped() {
+        let workspace = parse_metadata(METADATA).unwrap();
+        let mut selection = CargoSelection {
+            package: Some("server".into()),
+            target: Some(CargoTarget {
+                kind: CargoTargetKind::Example,
+                name: "demo".into(),
+            }),
+            features: vec!["tls".into()],
+            ..Default::default()
+        };
+        selection.retain_available(&workspace);
+        assert_eq!(selection.target, None);
+        assert!(selection.features.is_empty());
+
+        selection.package = Some("removed".into());
+        selection.retain_available(&workspace);
+        assert_eq!(selection.package, None);
+    }
+
+    #[test]
+    fn selections_are_persisted_per_project() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join(STORE_FILE_NAME);
+        let app = Path::new("/work/app");
+        let selection = CargoSelection {
+            package: Some("server".into()),
+            target: Some(CargoTarget {
+                kind: CargoTargetKind::Bin,
+                name: "server".into(),
+            }),
+            features: vec!["tls".into()],
+            all_features: false,
+            no_default_features: true,
+        };
+
+        let mut store = CargoSelectionStore::default();
+        store.set(app, selection.clone());
+        store.set(Path::new("/work/other"), CargoSelection::default());
+        assert_eq!(store.get(app), Some(&selection));
+        assert_eq!(store.projects.len(), 1);
+
+        store.save(&path).unwrap();
+        assert_eq!(CargoSelectionStore::load(&path).unwrap(), store);
+
+        store.set(app, CargoSelection::default());
+        assert!(store.projects.is_empty());
+    }
+}
@@ -12,6 +12,8 @@ use std::path::{MAIN_SEPARATOR, Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+use crate::usage_stats::FrecencyScores;
+
 const WORKSPACE_PATH_SEPARATOR: char = '/';
 
 const RUNNABLE_COMMANDS: &[(&str, &str)] = &[
@@ -39,7 +41,10 @@ pub struct CommandCompletionCache {
     languages: Vec<String>,
     configured_language_servers: Vec<String>,
     active_language_servers: Vec<String>,
+    picker_sources: Vec<String>,
     complete_filesystem_paths: bool,
+    command_usage: FrecencyScores,
+    theme_usage: FrecencyScores,
 }
 
 impl CommandCompletionCache {
@@ -88,7 +93,10 @@ impl CommandCompletionCache {
             languages,
             configured_language_servers,
             active_language_servers,
+            picker_sources: Vec::new(),
             complete_filesystem_paths: true,
+            command_usage: FrecencyScores::default(),
+            theme_usage: FrecencyScores::default(),
         }
     }
 
@@ -96,6 +104,19 @@ impl CommandCompletionCache {
         self.complete_filesystem_paths = enabled;
         self
     }
+
+    /// Names of the registered pickers, for `picker <name>`.
+    pub fn with_picker_sources(mut self, names: Vec<String>) -> Self {
+        self.picker_sources = names;
+        self
+    }
+
+    /// Ranks command and theme names by how often and how recently they were used.
+    pub fn with_usage(mut self, commands: FrecencyScores, themes: FrecencyScores) -> Self {
+        self.command_usage = commands;
+        self.theme_usage = themes;
+        self
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -171,7 +192,10 @@ impl Default for CommandCompletionCache {
             languages: Vec::new(),
             configured_language_servers: Vec::new(),
             active_language_servers: Vec::new(),
+            picker_sources: Vec::new(),
             complete_filesystem_paths: true,
+            command_usage: FrecencyScores::default(),
+            theme_usage: FrecencyScores::default(),
         }
     }
 }
@@ -193,11 +217,12 @@ pub fn get_command_completions_with_cache(
     cache: Option<&CommandCompletionCache>,
 ) -> Vec<CompletionItem> {
     let parts: Vec<&str> = input.split_whitespace().collect();
+    let command_usage = cache.map(|cache| &cache.command_usage);
 
     if parts.is_empty() {
-        complete_command_names("")
+        complete_command_names("", command_usage)
     } else if parts.len() == 1 && !input_ends_with_whitespace(input) {
-        complete_command_names(parts[0])
+        complete_command_names(parts[0], command_usage)
     } else {
         let default_cache;
         let cache = if let Some(cache) = cache {
@@ -211,7 +236,7 @@ pub fn get_command_completions_with_cache(
 }
 
 /// Complete command names, showing aliases but not as separate entries.
-fn complete_command_names(pattern: &str) -> Vec<CompletionItem> {
+fn complete_command_names(pattern: &str, usage: Option<&FrecencyScores>) -> Vec<CompletionItem> {
     let mut matched_commands: Vec<(&TypableCommand, u16)> = Vec::new();
 
     for cmd in TYPABLE_COMMAND_LIST {
@@ -248,12 +273,29 @@ fn complete_command_names(pattern: &str) -> Vec<CompletionItem> {
                     description: Some(cmd.doc.to_string().into()),
                     display_text,
                 },
-                score,
+                score.saturating_add(usage.map_or(0, |usage| usage.boost(cmd.name))),
             )
         })
         .collect::<Vec<_>>();
 
-    for (name, description) in RUNNABLE_COMMANDS {
+    for (name, description) in RUNNABLE_COMMANDS
+        .iter()
+        .chain(crate::repl::REPL_COMMANDS)
+        .chain(crate::run_configurations::RUN_CONFIGURATION_COMMANDS)
+        .chain(crate::cargo_integration::CARGO_COMMANDS)
+        .chain(crate::npm_scripts::NPM_SCRIPT_COMMANDS)
+        .chain(crate::indentation::INDENTATION_COMMANDS)
+        .chain(crate::reflow::REFLOW_COMMANDS)
+        .chain(crate::text_transforms::TEXT_TRANSFORM_COMMANDS)
+        .chain(crate::line_edits::LINE_OPERATION_COMMANDS)
+        .chain(crate::align::ALIGN_COMMANDS)
+        .chain(crate::doc_comments::DOC_COMMENT_COMMANDS)
+        .chain(crate::regex_tester::REGEX_TESTER_COMMANDS)
+        .chain(crate::scratch_buffers::SCRATCH_COMMANDS)
+        .chain(crate::file_compare::COMPARE_COMMANDS)
+        .chain(crate::content_index::CONTENT_INDEX_COMMANDS)
+        .chain(crate::workspace::picker_sources::PICKER_COMMANDS)
+    {
         if let Some((_, score)) = fuzzy_match(pattern, std::iter::once(*name), false)
             .into_iter()
             .next()
@@ -264,11 +306,146 @@ fn complete_command_names(pattern: &str) -> Vec<CompletionItem> {
                     description: Some((*description).to_string().into()),
                     display_text: None,
                 },
-                score,
+                score.saturating_add(usage.map_or(0, |usage| usage.boost(name))),
+            ));
+        }
+    }
+
+    if let Some((_, score)) = fuzzy_match(
+        pattern,
+        std::iter::once(crate::usage_stats::CLEAR_USAGE_DATA_COMMAND),
+        false,
+    )
+    .into_iter()
+    .next()
+    {
+        items.push((
+            CompletionItem {
+                text: crate::usage_stats::CLEAR_USAGE_DATA_COMMAND.into(),
+                description: Some("Forget locally recorded command, file and theme usage".into()),
+                display_text: None,
+            },
+            score,
+        ));
+    }
+
+    let undo_file_op = crate::application::workspace_file_ops::UNDO_FILE_OP_COMMAND;
+    if let Some((_, score)) = fuzzy_match(pattern, std::iter::once(undo_file_op), false)
+        .into_iter()
+        .next()
+    {
+        items.push((
+            CompletionItem {
+                text: undo_file_op.into(),
+                description: Some("Undo the last file rename, creation or duplicate".into()),
+                display_text: None,
+            },
+            score.saturating_add(usage.map_or(0, |usage| usage.boost(undo_file_op))),
+        ));
+    }
+
+    for (command, description) in [
+        (
+            crate::task_list::TOGGLE_TASK_COMMAND,
+            "Check or uncheck the task list item on the cursor line",
+        ),
+        (
+            crate::task_list::ARCHIVE_DONE_TASKS_COMMAND,
+            "Move completed task list items to the Done section",
+        ),
+    ] {
+        if let Some((_, score)) = fuzzy_match(pattern, std::iter::once(command), false)
+            .into_iter()
+            .next()
+        {
+            items.push((
+                CompletionItem {
+                    text: command.into(),
+                    description: Some(description.into()),
+                    display_text: None,
+                },
+                score.saturating_add(usage.map_or(0, |usage| usage.boost(command))),
             ));
         }
     }
 
+    let live_grep = crate::live_grep::LIVE_GREP_COMMAND;
+    if let Some((_, score)) = fuzzy_match(pattern, std::iter::once(live_grep), false)
+        .into_iter()
+        .next()
+    {
+        items.push((
+            CompletionItem {
+                text: live_grep.into(),
+                description: Some("Search the project as you type".into()),
+                display_text: None,
+            },
+            score.saturating_add(usage.map_or(0, |usage| usage.boost(live_grep))),
+        ));
+    }
+
+    let reopen_closed_tab = crate::recent_files::REOPEN_CLOSED_TAB_COMMAND;
+    if let Some((_, score)) = fuzzy_match(pattern, std::iter::once(reopen_closed_tab), false)
+        .into_iter()
+        .next()
+    {
+        items.push((
+            CompletionItem {
+                text: reopen_closed_tab.into(),
+                description: Some("Reopen the most recently closed tab".into()),
+                display_text: None,
+            },
+            score.saturating_add(usage.map_or(0, |usage| usage.boost(reopen_closed_tab))),
+        ));
+    }
+
+    let type_hierarchy = crate::type_hierarchy::TYPE_HIERARCHY_COMMAND;
+    if let Some((_, score)) = fuzzy_match(pattern, std::iter::once(type_hierarchy), false)
+        .into_iter()
+        .next()
+    {
+        items.push((
+            CompletionItem {
+                text: type_hierarchy.into(),
+                description: Some("Show the supertypes and subtypes of the symbol".into()),
+                display_text: None,
+            },
+            score.saturating_add(usage.map_or(0, |usage| usage.boost(type_hierarchy))),
+        ));
+    }
+
+    let hex_edit = crate::hex_editor::HEX_EDIT_COMMAND;
+    if let Some((_, score)) = fuzzy_match(pattern, std::iter::once(hex_edit), false)
+        .into_iter()
+        .next()
+    {
+        items.push((
+            CompletionItem {
+                text: hex_edit.into(),
+                description: Some("Open the current file in the hex editor".into()),
+                display_text: None,
+            },
+            score.saturating_add(usage.map_or(0, |usage| usage.boost(hex_edit))),
+        ));
+    }
+
+    let rename_in_files = crate::project_rename::RENAME_IN_FILES_COMMAND;
+    if let Some((_, score)) = fuzzy_match(pattern, std::iter::once(rename_in_files), false)
+        .into_iter()
+        .next()
+    {
+        items.push((
+            CompletionItem {
+                text: rename_in_files.into(),
+                description: Some(
+                    "Rename an identifier and its case variants across the project".into(),
+                ),
+                display_text: None,
+            },
+            score.saturating_add(usage.map_or(0, |usage| usage.boost(rename_in_files))),
+        ));
+    }
+
     items.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
     items.into_iter().map(|(item, _)| item).collect()
 }
@@ -283,6 +460,44 @@ fn complete_command_arguments_with_cache(
         return Vec::new();
     };
 
+    if context.command == crate::scratch_buffers::SCRATCH_NEW_COMMAND {
+        if context.arg_index != 0 {
+            return Vec::new();
+        }
+        return complete_list(
+            input,
+            context.current_arg,
+            &cache.languages,
+            false,
+            |language| format!("New {language} scratch buffer"),
+        );
+    }
+
+    if context.command == crate::workspace::picker_sources::PICKER_COMMAND {
+        if context.arg_index != 0 {
+            return Vec::new();
+        }
+        return complete_list(
+            input,
+            context.current_arg,
+            &cache.picker_sources,
+            false,
+            |name| format!("Open the {name} picker"),
+        );
+    }
+
+    if context.command == crate::file_compare::COMPARE_WITH_COMMAND {
+        if context.arg_index != 0 || !cache.complete_filesystem_paths {
+            return Vec::new();
+        }
+        return complete_filesystem_paths(
+            input,
+            context.current_arg,
+            PathCompletionKind::FileOrDirectory,
+            "Compare with",
+        );
+    }
+
     let command = TYPABLE_COMMAND_LIST
         .iter()
         .find(|cmd| cmd.name == context.command || cmd.aliases.contains(&context.command));
@@ -292,9 +507,12 @@ fn complete_command_arguments_with_cache(
     };
 
     match cmd.name {
-        "theme" if context.arg_index == 0 => {
-            complete_themes(input, context.current_arg, context.command)
-        }
+        "theme" if context.arg_index == 0 => complete_themes(
+            input,
+            context.current_arg,
+            context.command,
+            &cache.theme_usage,
+        ),
         "toggle-option" | "set-option" | "get-option" if context.arg_index == 0 => {
             complete_settings(input, context.current_arg, cmd.name, &cache.settings)
         }
@@ -351,8 +569,13 @@ fn complete_command_arguments_with_cache(
     }
 }
 
-/// Complete theme names.
-fn complete_themes(input: &str, current_arg: &str, command: &str) -> Vec<CompletionItem> {
+/// Complete theme names, recently used themes first.
+fn complete_themes(
+    input: &str,
+    current_arg: &str,
+    command: &str,
+    usage: &FrecencyScores,
+) -> Vec<CompletionItem> {
     let mut names =
         helix_view::theme::Loader::read_names(&helix_loader::config_dir().join("themes"));
 
@@ -366,7 +589,16 @@ fn complete_themes(input: &str, current_arg: &str, command: &str) -> Vec<Complet
     names.sort();
     names.dedup();
 
-    fuzzy_match(current_arg, names, false)
+    let mut matches = fuzzy_match(current_arg, names, false)
+        .into_iter()
+        .map(|(name, score)| {
+            let score = score.saturating_add(usage.boost(&name));
+            (name, score)
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+    matches
         .into_iter()
         .map(|(name, _score)| CompletionItem {
             text: replace_current_arg(input, current_arg, &name).into(),
@@ -897,6 +1129,24 @@ mod tests {
         assert!(SETTINGS_KEYS.len() > 20);
     }
 
+    #[test]
+    fn frequently_used_commands_are_listed_first() {
+        use crate::usage_stats::{UsageKind, UsageStore};
+
+        let mut store = UsageStore::default();
+        for _ in 0..3 {
+            store.record(UsageKind::Command, "vsplit", 0);
+        }
+        let cache = CommandCompletionCache::default()
+            .with_usage(store.scores(UsageKind::Command, 0), Default::default());
+
+        let items = get_command_completions_with_cache("", Some(&cache));
+        assert_eq!(items[0].text.as_ref(), "vsplit");
+
+        let without_usage = get_command_completions_with_cache("", None);
+        assert_ne!(without_usage[0].text.as_ref(), "vsplit");
+    }
+
     #[test]
     fn cached_setting_completion_preserves_typed_alias() {
         let cache = CommandCompletionCache {
@@ -913,6 +1163,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn picker_command_completes_registered_names() {
+        let cache = CommandCompletionCache {
+            picker_sources: vec!["compare".to_string(), "scratch-buffers".to_string()],
+            ..CommandCompletionCache::default()
+        };
+
+        let items = get_command_completions_with_cache("picker scr", Some(&cache));
+        assert!(
+            items
+                .iter()
+                .any(|item| item.text.as_ref() == "picker scratch-buffers")
+        );
+    }
+
     #[test]
     fn cached_context_completion_preserves_typed_aliases() {
         let cache = CommandCompletionCache {
@@ -1,12 +1,18 @@
 // ABOUTME: This file implements the GUI-specific configuration system for nucleotide
 // ABOUTME: It loads nucleotide.toml and falls back to config.toml for unspecified values
 
+use crate::document_export::PageSize;
 use crate::file_tree::FileTreeDisplayDensity;
 use helix_loader::config_dir;
 use helix_term::config::Config as HelixConfig;
 use nucleotide_appearance::UiChromeStyle;
-use nucleotide_types::{FontConfig, FontWeight, ProjectMarkersConfig};
+use nucleotide_terminal_view::TerminalPaletteOverride;
+use nucleotide_types::{
+    CursorConfig, FontConfig, FontWeight, MouseSelectionConfig, ProjectMarkersConfig,
+    ScrollModifier, ScrollSettings, WheelScrollConfig,
+};
 use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 
 /// Default theme for light mode
@@ -33,6 +39,45 @@ const WINDOWS_DEFAULT_DPI: f32 = 96.0;
 /// Complete example configuration used for new `nucleotide.toml` files.
 pub const NUCLEOTIDE_EXAMPLE_CONFIG: &str = include_str!("../nucleotide.example.toml");
 
+/// Project-relative path of the workspace settings file.
+pub const WORKSPACE_SETTINGS_PATH: &str = ".nucleotide/settings.toml";
+
+/// Starting content for new workspace settings files.
+pub const WORKSPACE_SETTINGS_TEMPLATE: &str = r#"# Nucleotide workspace settings
+# Values set here override nucleotide.toml and config.toml for this project only.
+# Precedence: built-in defaults < global settings < workspace settings.
+
+# [theme]
+# mode = "dark"
+# light_theme = "nucleotide-sandcastle"
+# dark_theme = "nucleotide-teal"
+
+# [editor]
+# font_size = 14.0
+# format_on_save = true
+
+# [file_tree]
+# density = "compact"
+# flatten_empty_directories = false
+
+# [terminal]
+# shell = "/bin/zsh"
+# default_profile = "project"
+
+# [[file_associations]]
+# glob = "*.mdx"
+# language = "markdown"
+# icon = "md"
+
+# [[run]]
+# name = "test"
+# command = "cargo"
+# args = ["test"]
+# cwd = "crates/app"
+# env = { RUST_BACKTRACE = "1" }
+# pre_launch = "build"
+"#;
+
 fn normalize_ui_font(mut font: FontConfig) -> FontConfig {
     if matches!(
         font.family.as_str(),
@@ -234,11 +279,203 @@ pub struct UiConfig {
 }
 
 /// Editor-specific GUI configuration
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EditorGuiConfig {
     /// Font used in the editor
     #[serde(default, deserialize_with = "deserialize_editor_font")]
     pub font: Option<FontConfig>,
+
+    /// Mouse wheel behaviour in documents
+    #[serde(default)]
+    pub scroll: WheelScrollConfig,
+
+    /// Paste and bracket conveniences in insert mode
+    #[serde(default)]
+    pub editing: EditingConfig,
+
+    /// Word characters and line rules for double- and triple-click selection
+    #[serde(default)]
+    pub mouse_selection: MouseSelectionConfig,
+
+    /// Cursor blinking and per-mode cursor shapes
+    #[serde(default)]
+    pub cursor: CursorConfig,
+
+    /// How the active split is told apart from the others
+    #[serde(default)]
+    pub splits: SplitsConfig,
+
+    /// Reopen files at the selections and scroll position they were last
+    /// closed with, also after a restart.
+    #[serde(default = "default_true")]
+    pub restore_cursor_position: bool,
+}
+
+impl Default for EditorGuiConfig {
+    fn default() -> Self {
+        Self {
+            font: None,
+            scroll: WheelScrollConfig::default(),
+            editing: EditingConfig::default(),
+            mouse_selection: MouseSelectionConfig::default(),
+            cursor: CursorConfig::default(),
+            splits: SplitsConfig::default(),
+            restore_cursor_position: true,
+        }
+    }
+}
+
+/// Marker drawn on the focused split while the editor is split.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitFocusIndicator {
+    /// No marker; rely on dimming or the cursor alone.
+    None,
+    /// Accent strip along the focused split's left edge.
+    #[default]
+    Edge,
+    /// Accent border around the focused split.
+    Border,
+}
+
+/// Affordances for the active split. Only drawn while more than one split
+/// is open.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SplitsConfig {
+    #[serde(default)]
+    pub focus_indicator: SplitFocusIndicator,
+
+    /// How strongly inactive splits are washed out toward the editor
+    /// background, from 0.0 (off) to 1.0.
+    #[serde(default)]
+    pub dim_inactive: f32,
+}
+
+impl Default for SplitsConfig {
+    fn default() -> Self {
+        Self {
+            focus_indicator: SplitFocusIndicator::Edge,
+            dim_inactive: 0.0,
+        }
+    }
+}
+
+impl SplitsConfig {
+    /// Opacity of the background wash over inactive splits, 0.0 when
+    /// dimming is off.
+    pub fn inactive_dim_opacity(&self) -> f32 {
+        if self.dim_inactive.is_finite() {
+            self.dim_inactive.clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Gives each mode its own cursor shape when Helix's `editor.cursor-shape`
+/// is left at its all-block default: a bar in insert mode and an underline
+/// in select mode.
+pub fn apply_cursor_mode_shapes(cursor: &CursorConfig, editor: &mut helix_view::editor::Config) {
+    use helix_view::{editor::CursorShapeConfig, graphics::CursorKind};
+
+    if cursor.mode_shapes && editor.cursor_shape == CursorShapeConfig::default() {
+        editor.cursor_shape =
+            CursorShapeConfig::new(CursorKind::Block, CursorKind::Underline, CursorKind::Bar);
+    }
+}
+
+/// Editing conveniences layered over Helix's insert mode.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EditingConfig {
+    /// Re-indent multi-line pastes to the indentation at the cursor.
+    #[serde(default = "default_true")]
+    pub paste_reindent: bool,
+
+    /// Insert the closing bracket or quote after an opening one. Sets
+    /// Helix's `editor.auto-pairs`; unset keeps the Helix setting.
+    #[serde(default)]
+    pub auto_close_pairs: Option<bool>,
+
+    /// Typing a closing bracket or quote in front of the same character
+    /// moves past it instead of inserting another.
+    #[serde(default = "default_true")]
+    pub type_over: bool,
+
+    /// Typing an opening bracket or quote while text is selected surrounds
+    /// the selection with the pair.
+    #[serde(default = "default_true")]
+    pub surround_selection: bool,
+
+    /// Editing the name of an HTML or JSX opening tag renames the matching
+    /// closing tag.
+    #[serde(default = "default_true")]
+    pub rename_paired_tags: bool,
+
+    /// Insert the closing bracket or quote inside comments and strings too.
+    /// Off, typing there inserts only the character typed.
+    #[serde(default = "default_true")]
+    pub auto_close_pairs_in_comments_and_strings: bool,
+
+    /// Overrides keyed by language name (`[editor.editing.languages.<language>]`)
+    #[serde(default)]
+    pub languages: HashMap<String, EditingLanguageConfig>,
+}
+
+/// Pair settings for one language; unset fields follow `[editor.editing]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EditingLanguageConfig {
+    #[serde(default)]
+    pub auto_close_pairs: Option<bool>,
+    #[serde(default)]
+    pub surround_selection: Option<bool>,
+}
+
+impl Default for EditingConfig {
+    fn default() -> Self {
+        Self {
+            paste_reindent: true,
+            auto_close_pairs: None,
+            type_over: true,
+            surround_selection: true,
+            rename_paired_tags: true,
+            auto_close_pairs_in_comments_and_strings: true,
+            languages: HashMap::new(),
+        }
+    }
+}
+
+impl EditingConfig {
+    /// Applies `auto_close_pairs` to the Helix editor config, keeping a
+    /// custom pair list when pairs are enabled.
+    pub fn apply_to_helix(&self, editor: &mut helix_view::editor::Config) {
+        use helix_core::syntax::config::AutoPairConfig;
+
+        match self.auto_close_pairs {
+            Some(false) => editor.auto_pairs = AutoPairConfig::Enable(false),
+            Some(true) if matches!(editor.auto_pairs, AutoPairConfig::Enable(false)) => {
+                editor.auto_pairs = AutoPairConfig::Enable(true);
+            }
+            Some(true) | None => {}
+        }
+    }
+
+    /// `auto_close_pairs` for a document in `language`.
+    pub fn auto_close_pairs_for(&self, language: Option<&str>) -> Option<bool> {
+        self.language(language)
+            .and_then(|overrides| overrides.auto_close_pairs)
+            .or(self.auto_close_pairs)
+    }
+
+    /// `surround_selection` for a document in `language`.
+    pub fn surround_selection_for(&self, language: Option<&str>) -> bool {
+        self.language(language)
+            .and_then(|overrides| overrides.surround_selection)
+            .unwrap_or(self.surround_selection)
+    }
+
+    fn language(&self, language: Option<&str>) -> Option<&EditingLanguageConfig> {
+        self.languages.get(language?)
+    }
 }
 
 /// Theme mode selection
@@ -760,6 +997,27 @@ pub struct FileTreeUiConfig {
     /// Collapse single-child directory chains into one visible row.
     #[serde(default = "default_true")]
     pub flatten_empty_directories: bool,
+    /// Show size and modified-time columns at the end of each row.
+    #[serde(default)]
+    pub metadata_columns: bool,
+    /// Show size and modified time in a tooltip when hovering a row.
+    #[serde(default)]
+    pub metadata_tooltips: bool,
+    /// Entries scanned when opening a project before warning that it is too
+    /// large and listing only its root. 0 never warns.
+    #[serde(default = "default_large_project_entries")]
+    pub large_project_entries: usize,
+    /// Children a directory lists before a "show more" row.
+    #[serde(default = "default_directory_page_size")]
+    pub directory_page_size: usize,
+}
+
+fn default_large_project_entries() -> usize {
+    crate::file_tree::DEFAULT_SCAN_LIMIT
+}
+
+fn default_directory_page_size() -> usize {
+    crate::file_tree::DEFAULT_PAGE_SIZE
 }
 
 impl Default for FileTreeUiConfig {
@@ -767,6 +1025,228 @@ impl Default for FileTreeUiConfig {
         Self {
             density: FileTreeDisplayDensity::Default,
             flatten_empty_directories: true,
+            metadata_columns: false,
+            metadata_tooltips: false,
+            large_project_entries: default_large_project_entries(),
+            directory_page_size: default_directory_page_size(),
+        }
+    }
+}
+
+/// Embedded terminal configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalConfig {
+    /// Shell for new terminal sessions. Unset uses the login shell.
+    #[serde(default)]
+    pub shell: Option<String>,
+
+    /// Lines of history kept above the viewport. Unset uses 10,000.
+    #[serde(default)]
+    pub scrollback_lines: Option<usize>,
+
+    /// Mouse wheel behaviour; unset fields follow `[editor.scroll]`.
+    #[serde(default)]
+    pub scroll: TerminalScrollConfig,
+
+    /// Profile used for new terminals. Unset uses `shell` with no profile.
+    #[serde(default)]
+    pub default_profile: Option<String>,
+
+    /// Named terminal setups offered when creating a new terminal.
+    #[serde(default)]
+    pub profiles: Vec<TerminalProfileConfig>,
+
+    /// Ask before pasting text with several lines, which the shell may run
+    /// one by one.
+    #[serde(default = "default_true")]
+    pub confirm_multiline_paste: bool,
+}
+
+impl Default for TerminalConfig {
+    fn default() -> Self {
+        Self {
+            shell: None,
+            scrollback_lines: None,
+            scroll: TerminalScrollConfig::default(),
+            default_profile: None,
+            profiles: Vec::new(),
+            confirm_multiline_paste: true,
+        }
+    }
+}
+
+impl TerminalConfig {
+    pub fn profile(&self, name: &str) -> Option<&TerminalProfileConfig> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// The configured default profile, ignoring names that match no profile.
+    pub fn default_profile(&self) -> Option<&TerminalProfileConfig> {
+        let name = self.default_profile.as_deref()?;
+        let profile = self.profile(name);
+        if profile.is_none() {
+            nucleotide_logging::warn!(
+                profile = name,
+                "Default terminal profile is not defined; using the terminal shell"
+            );
+        }
+        profile
+    }
+}
+
+/// Where a terminal profile starts its shell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminalCwdStrategy {
+    /// The project root, or the home directory without a project
+    #[default]
+    ProjectRoot,
+    /// The directory of the focused document, falling back to the project root
+    CurrentFile,
+    Home,
+}
+
+/// A named terminal setup from `[[terminal.profiles]]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TerminalProfileConfig {
+    pub name: String,
+    /// Shell for this profile. Unset uses `terminal.shell`.
+    #[serde(default)]
+    pub shell: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Added to the project environment, replacing variables of the same name.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub cwd: TerminalCwdStrategy,
+    #[serde(default)]
+    pub palette: TerminalPaletteConfig,
+}
+
+/// Terminal color overrides as `#rrggbb` strings. Unset colors follow the theme.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TerminalPaletteConfig {
+    #[serde(default)]
+    pub foreground: Option<String>,
+    #[serde(default)]
+    pub background: Option<String>,
+    /// Up to 16 ANSI colors, black through bright white
+    #[serde(default)]
+    pub ansi: Vec<String>,
+}
+
+impl TerminalPaletteConfig {
+    /// Parses the configured colors, or `None` when nothing is overridden.
+    /// Unparseable colors are logged and skipped.
+    pub fn to_override(&self) -> Option<TerminalPaletteOverride> {
+        fn parse(value: &str) -> Option<gpui::Hsla> {
+            match gpui::Rgba::try_from(value.trim()) {
+                Ok(rgba) => Some(rgba.into()),
+                Err(error) => {
+                    nucleotide_logging::warn!(
+                        color = value,
+                        error = %error,
+                        "Ignoring invalid terminal palette color"
+                    );
+                    None
+                }
+            }
+        }
+
+        let mut palette = TerminalPaletteOverride {
+            foreground: self.foreground.as_deref().and_then(parse),
+            background: self.background.as_deref().and_then(parse),
+            ..TerminalPaletteOverride::default()
+        };
+        for (slot, value) in palette.ansi.iter_mut().zip(&self.ansi) {
+            *slot = parse(value);
+        }
+
+        (palette != TerminalPaletteOverride::default()).then_some(palette)
+    }
+}
+
+/// Terminal wheel overrides on top of the editor scroll settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalScrollConfig {
+    #[serde(default)]
+    pub lines_per_tick: Option<f32>,
+    #[serde(default)]
+    pub fast_scroll_modifier: Option<ScrollModifier>,
+    #[serde(default)]
+    pub fast_scroll_multiplier: Option<f32>,
+    #[serde(default)]
+    pub shift_scrolls_horizontally: Option<bool>,
+    /// Translate the wheel into arrow keys in full-screen programs (less,
+    /// man, htop) that run on the alternate screen without mouse reporting.
+    #[serde(default = "default_true")]
+    pub alternate_scroll: bool,
+}
+
+impl Default for TerminalScrollConfig {
+    fn default() -> Self {
+        Self {
+            lines_per_tick: None,
+            fast_scroll_modifier: None,
+            fast_scroll_multiplier: None,
+            shift_scrolls_horizontally: None,
+            alternate_scroll: true,
+        }
+    }
+}
+
+impl TerminalScrollConfig {
+    /// Fills unset fields from the editor scroll settings.
+    pub fn resolve(&self, editor: &WheelScrollConfig) -> WheelScrollConfig {
+        WheelScrollConfig {
+            lines_per_tick: self.lines_per_tick.unwrap_or(editor.lines_per_tick),
+            fast_scroll_modifier: self
+                .fast_scroll_modifier
+                .unwrap_or(editor.fast_scroll_modifier),
+            fast_scroll_multiplier: self
+                .fast_scroll_multiplier
+                .unwrap_or(editor.fast_scroll_multiplier),
+            shift_scrolls_horizontally: self
+                .shift_scrolls_horizontally
+                .unwrap_or(editor.shift_scrolls_horizontally),
+        }
+    }
+}
+
+/// Document export (File > Export) layout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportConfig {
+    /// CSS font family for HTML exports. Unset uses the editor font.
+    #[serde(default)]
+    pub font_family: Option<String>,
+    /// Font family for PDF exports. Fonts are not embedded, so anything but
+    /// Courier falls back to it with a warning. Unset uses Courier.
+    #[serde(default)]
+    pub pdf_font_family: Option<String>,
+    /// Font size in points.
+    #[serde(default = "default_export_font_size")]
+    pub font_size: f32,
+    /// Paper size for PDF output and HTML printing.
+    #[serde(default)]
+    pub page_size: PageSize,
+    /// Print line numbers in the margin.
+    #[serde(default = "default_true")]
+    pub line_numbers: bool,
+}
+
+fn default_export_font_size() -> f32 {
+    10.0
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            font_family: None,
+            pdf_font_family: None,
+            font_size: default_export_font_size(),
+            page_size: PageSize::default(),
+            line_numbers: true,
         }
     }
 }
@@ -781,6 +1261,113 @@ pub struct LspConfig {
     /// Timeout for LSP startup in milliseconds
     #[serde(default = "default_lsp_startup_timeout")]
     pub startup_timeout_ms: u64,
+
+    /// Route local language servers through the built-in traffic inspector,
+    /// which records JSON-RPC messages for the LSP traffic viewer
+    #[serde(default)]
+    pub traffic_inspector: bool,
+
+    /// How long document edits are buffered and merged before being sent to
+    /// language servers, in milliseconds. Zero sends every edit immediately
+    #[serde(default = "default_did_change_coalesce_ms")]
+    pub did_change_coalesce_ms: u64,
+
+    /// Timeouts, retries and circuit breaker for requests to running servers
+    #[serde(default)]
+    pub requests: LspRequestConfig,
+
+    /// Automatic restarts for servers that exit unexpectedly
+    #[serde(default)]
+    pub restart: LspRestartConfig,
+
+    /// How results are combined when several servers attach to one document,
+    /// keyed by language name (`[lsp.merge.<language>]`)
+    #[serde(default)]
+    pub merge: HashMap<String, LspMergeConfig>,
+}
+
+/// Resilience policy for LSP requests (`[lsp.requests]`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct LspRequestConfig {
+    /// Completion, hover and signature help
+    pub interactive_timeout_ms: u64,
+    /// Go to definition, references and symbol searches
+    pub navigation_timeout_ms: u64,
+    /// Formatting, rename and code actions
+    pub editing_timeout_ms: u64,
+    /// Any other request
+    pub background_timeout_ms: u64,
+    /// Retries for idempotent requests that time out or race an edit
+    pub max_retries: u32,
+    /// Consecutive failures before a server is marked degraded
+    pub failure_threshold: u32,
+    /// Seconds a degraded server is skipped before requests are tried again
+    pub degraded_cooldown_secs: u64,
+}
+
+impl Default for LspRequestConfig {
+    fn default() -> Self {
+        Self {
+            interactive_timeout_ms: 5000,
+            navigation_timeout_ms: 10000,
+            editing_timeout_ms: 15000,
+            background_timeout_ms: 30000,
+            max_retries: 1,
+            failure_threshold: 5,
+            degraded_cooldown_secs: 30,
+        }
+    }
+}
+
+/// Crash restart policy for language servers (`[lsp.restart]`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct LspRestartConfig {
+    /// Restart a server automatically when its process exits unexpectedly
+    pub enabled: bool,
+    /// Restarts attempted before the server is left stopped
+    pub max_restarts: u32,
+    /// Delay before the first restart; doubled for each further attempt
+    pub initial_backoff_ms: u64,
+    /// Upper bound for the restart delay
+    pub max_backoff_ms: u64,
+    /// Uptime after which a server's crash count starts over
+    pub stable_after_secs: u64,
+}
+
+impl Default for LspRestartConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_restarts: 5,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30000,
+            stable_after_secs: 60,
+        }
+    }
+}
+
+/// Multi-server merge policy for one language (`[lsp.merge.<language>]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct LspMergeConfig {
+    /// Server names, highest priority first; also picks the formatting server
+    pub priority: Vec<String>,
+    pub completion: LspMergeStrategy,
+    pub hover: LspMergeStrategy,
+    pub diagnostics: LspMergeStrategy,
+}
+
+/// How results from several servers are combined
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LspMergeStrategy {
+    /// Merge every server's results in priority order, dropping duplicates
+    #[default]
+    All,
+    /// Only use the highest priority server
+    First,
 }
 
 fn default_lsp_startup_timeout() -> u64 {
@@ -791,11 +1378,20 @@ fn default_project_lsp_startup() -> bool {
     true
 }
 
+fn default_did_change_coalesce_ms() -> u64 {
+    30
+}
+
 impl Default for LspConfig {
     fn default() -> Self {
         Self {
             project_lsp_startup: default_project_lsp_startup(),
             startup_timeout_ms: default_lsp_startup_timeout(),
+            traffic_inspector: false,
+            did_change_coalesce_ms: default_did_change_coalesce_ms(),
+            requests: LspRequestConfig::default(),
+            restart: LspRestartConfig::default(),
+            merge: HashMap::new(),
         }
     }
 }
@@ -812,6 +1408,32 @@ impl LspConfig {
             return Err("LSP startup timeout should not exceed 60 seconds".to_string());
         }
 
+        let requests = &self.requests;
+        if [
+            requests.interactive_timeout_ms,
+            requests.navigation_timeout_ms,
+            requests.editing_timeout_ms,
+            requests.background_timeout_ms,
+        ]
+        .contains(&0)
+        {
+            return Err("LSP request timeouts must be greater than 0".to_string());
+        }
+
+        if requests.failure_threshold == 0 {
+            return Err("LSP request failure_threshold must be at least 1".to_string());
+        }
+
+        if self.restart.initial_backoff_ms == 0 {
+            return Err("LSP restart initial_backoff_ms must be greater than 0".to_string());
+        }
+
+        if self.restart.max_backoff_ms < self.restart.initial_backoff_ms {
+            return Err(
+                "LSP restart max_backoff_ms must not be lower than initial_backoff_ms".to_string(),
+            );
+        }
+
         // Log warnings for potentially problematic configurations
         if self.startup_timeout_ms < 1000 {
             nucleotide_logging::warn!(
@@ -839,6 +1461,53 @@ impl LspConfig {
             config.startup_timeout_ms = 60000;
         }
 
+        let defaults = LspRequestConfig::default();
+        let requests = &mut config.requests;
+        for (timeout, default) in [
+            (
+                &mut requests.interactive_timeout_ms,
+                defaults.interactive_timeout_ms,
+            ),
+            (
+                &mut requests.navigation_timeout_ms,
+                defaults.navigation_timeout_ms,
+            ),
+            (
+                &mut requests.editing_timeout_ms,
+                defaults.editing_timeout_ms,
+            ),
+            (
+                &mut requests.background_timeout_ms,
+                defaults.background_timeout_ms,
+            ),
+        ] {
+            if *timeout == 0 {
+                nucleotide_logging::warn!(
+                    default_ms = default,
+                    "Invalid LSP request timeout 0, using default"
+                );
+                *timeout = default;
+            }
+        }
+        if requests.failure_threshold == 0 {
+            nucleotide_logging::warn!("LSP request failure_threshold must be at least 1");
+            requests.failure_threshold = 1;
+        }
+
+        let restart = &mut config.restart;
+        if restart.initial_backoff_ms == 0 {
+            nucleotide_logging::warn!("Invalid LSP restart backoff 0, using default 500ms");
+            restart.initial_backoff_ms = LspRestartConfig::default().initial_backoff_ms;
+        }
+        if restart.max_backoff_ms < restart.initial_backoff_ms {
+            nucleotide_logging::warn!(
+                max_backoff_ms = restart.max_backoff_ms,
+                initial_backoff_ms = restart.initial_backoff_ms,
+                "LSP restart max backoff below initial backoff, raising it"
+            );
+            restart.max_backoff_ms = restart.initial_backoff_ms;
+        }
+
         config
     }
 }
@@ -873,6 +1542,118 @@ impl Default for UpdatesConfig {
     }
 }
 
+/// Local usage statistics used to rank pickers.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UsageConfig {
+    /// Record command, file and theme usage on this machine for frecency ranking.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl Default for UsageConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Find All References results panel.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReferencesConfig {
+    /// Show references in the panel instead of a picker from this many
+    /// results. A single reference is always jumped to.
+    #[serde(default = "default_references_panel_threshold")]
+    pub panel_threshold: usize,
+
+    /// Lines shown above and below each reference.
+    #[serde(default = "default_references_context_lines")]
+    pub context_lines: usize,
+
+    /// Close the panel after jumping to a reference.
+    #[serde(default)]
+    pub clear_on_navigate: bool,
+}
+
+fn default_references_panel_threshold() -> usize {
+    10
+}
+
+fn default_references_context_lines() -> usize {
+    1
+}
+
+impl Default for ReferencesConfig {
+    fn default() -> Self {
+        Self {
+            panel_threshold: default_references_panel_threshold(),
+            context_lines: default_references_context_lines(),
+            clear_on_navigate: false,
+        }
+    }
+}
+
+/// Scratch buffers created with `scratch-new`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScratchConfig {
+    /// Keep scratch buffers across restarts, saved in the app data dir on quit.
+    #[serde(default)]
+    pub persist: bool,
+
+    /// Let quit commands close scratch buffers without an unsaved changes
+    /// error. Their contents are saved first when `persist` is on.
+    #[serde(default)]
+    pub skip_unsaved_prompt: bool,
+}
+
+/// Background trigram index of the project's file contents, used by
+/// project search and live grep.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContentIndexConfig {
+    /// Index local projects when they are opened.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Keep the index in the app data dir, so reopening a project only
+    /// reads the files that changed.
+    #[serde(default = "default_true")]
+    pub persist: bool,
+
+    /// Larger files are not indexed; searches still read them from disk.
+    #[serde(default = "default_content_index_max_file_size_kb")]
+    pub max_file_size_kb: u64,
+
+    /// Stop adding files past this many.
+    #[serde(default = "default_content_index_max_files")]
+    pub max_files: usize,
+
+    /// Stop adding files once the index takes about this much memory.
+    #[serde(default = "default_content_index_max_memory_mb")]
+    pub max_memory_mb: usize,
+}
+
+fn default_content_index_max_file_size_kb() -> u64 {
+    1024
+}
+
+fn default_content_index_max_files() -> usize {
+    200_000
+}
+
+fn default_content_index_max_memory_mb() -> usize {
+    256
+}
+
+impl Default for ContentIndexConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            persist: true,
+            max_file_size_kb: default_content_index_max_file_size_kb(),
+            max_files: default_content_index_max_files(),
+            max_memory_mb: default_content_index_max_memory_mb(),
+        }
+    }
+}
+
 /// GUI-specific configuration that extends Helix configuration
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GuiConfig {
@@ -912,6 +1693,26 @@ pub struct GuiConfig {
     #[serde(default)]
     pub file_tree: FileTreeUiConfig,
 
+    /// Embedded terminal settings
+    #[serde(default)]
+    pub terminal: TerminalConfig,
+
+    /// Document export layout
+    #[serde(default)]
+    pub export: ExportConfig,
+
+    /// Icon and language overrides by file name glob; earlier entries win
+    #[serde(default)]
+    pub file_associations: Vec<FileAssociationConfig>,
+
+    /// Templates offered when creating a file from the file tree
+    #[serde(default)]
+    pub file_templates: Vec<FileTemplateConfig>,
+
+    /// Scaffolding commands offered by New Project; built-in ones when empty
+    #[serde(default)]
+    pub project_templates: Vec<ProjectTemplateConfig>,
+
     /// LSP feature flags and configuration
     #[serde(default)]
     pub lsp: LspConfig,
@@ -931,6 +1732,105 @@ pub struct GuiConfig {
     /// Application update settings.
     #[serde(default)]
     pub updates: UpdatesConfig,
+
+    /// Local usage statistics for frecency ranking.
+    #[serde(default)]
+    pub usage: UsageConfig,
+
+    /// Find All References panel
+    #[serde(default)]
+    pub references: ReferencesConfig,
+
+    /// Scratch buffer persistence and quit behaviour
+    #[serde(default)]
+    pub scratch: ScratchConfig,
+
+    /// Project content index for faster search
+    #[serde(default)]
+    pub content_index: ContentIndexConfig,
+
+    /// REPLs for the send-to-REPL commands, keyed by language name.
+    /// Python, JavaScript, TypeScript and Ruby have built-in defaults.
+    #[serde(default)]
+    pub repl: HashMap<String, ReplCommandConfig>,
+
+    /// Run configurations of the open project, from its workspace settings
+    #[serde(skip)]
+    pub run_configurations: Vec<RunConfiguration>,
+}
+
+/// REPL program started by `repl-start` for one language.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplCommandConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Wrap sent code in bracketed-paste markers. Turn off for REPLs that
+    /// echo the markers instead of treating them as a paste.
+    #[serde(default = "default_true")]
+    pub bracketed_paste: bool,
+}
+
+/// One `[[run]]` entry of the workspace settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunConfiguration {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Working directory relative to the project root
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+    /// Name of a run configuration to run first; this one starts only when
+    /// it succeeds
+    #[serde(default)]
+    pub pre_launch: Option<String>,
+}
+
+/// One `[[file_associations]]` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileAssociationConfig {
+    /// Glob matched against the file name, e.g. `*.mdx` or `Dockerfile.*`
+    pub glob: String,
+    /// Helix language id used for highlighting and language server routing
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Extension whose icon to show, e.g. `md`
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+/// One `[[file_templates]]` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileTemplateConfig {
+    /// Name shown in the template picker
+    pub name: String,
+    /// Glob matched against the new file's name, e.g. `*_test.rs`
+    #[serde(default)]
+    pub glob: Option<String>,
+    /// Helix language id the new file must have, e.g. `rust`
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Snippet file holding the template, relative to the `templates` folder
+    /// of the config dir
+    pub file: PathBuf,
+}
+
+/// One `[[project_templates]]` entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectTemplateConfig {
+    /// Name shown in the New Project picker
+    pub name: String,
+    /// Program that scaffolds the project, e.g. `cargo`
+    pub command: String,
+    /// Arguments of `command`; `{name}` is replaced by the project name
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Shown beside the name in the picker
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 /// Delete behavior preference
@@ -949,12 +1849,132 @@ pub struct FileOpsConfig {
     /// How delete should behave: move to trash or delete permanently
     #[serde(default)]
     pub delete_behavior: DeleteBehavior,
+    /// Reload unmodified buffers when their file changes on disk. Buffers
+    /// with unsaved edits always ask first.
+    #[serde(default = "default_true")]
+    pub auto_reload: bool,
+    /// How local files are written on save
+    #[serde(default)]
+    pub save: SaveConfig,
 }
 
 impl Default for FileOpsConfig {
     fn default() -> Self {
         Self {
             delete_behavior: DeleteBehavior::Trash,
+            auto_reload: true,
+            save: SaveConfig::default(),
+        }
+    }
+}
+
+/// How a save puts the new contents in place
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveStrategy {
+    /// Write a temporary file next to the target and rename it over the
+    /// target. Files with several hard links are written in place instead.
+    #[default]
+    Atomic,
+    /// Truncate and rewrite the existing file, keeping its inode so hard
+    /// links and watchers that follow the inode keep working
+    InPlace,
+}
+
+/// Whether a copy of the previous contents is kept on save
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveBackup {
+    #[default]
+    None,
+    /// `name~` next to the file
+    Suffix,
+    /// A copy in `backup_dir`, named after the file's full path
+    Directory,
+}
+
+/// What is flushed to disk before a save reports success
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveFsync {
+    Never,
+    /// The written file
+    #[default]
+    File,
+    /// The written file and its directory, so a rename survives a crash
+    Full,
+}
+
+/// `[file_ops.save]` settings, with overrides for files on particular
+/// filesystems or under particular directories.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SaveConfig {
+    #[serde(default)]
+    pub strategy: SaveStrategy,
+    #[serde(default)]
+    pub backup: SaveBackup,
+    /// Where `backup = "directory"` keeps copies. Defaults to the
+    /// `backups` folder in the cache directory.
+    #[serde(default)]
+    pub backup_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub fsync: SaveFsync,
+    #[serde(default)]
+    pub filesystems: Vec<SaveFilesystemConfig>,
+}
+
+/// One `[[file_ops.save.filesystems]]` entry. Settings left out fall back to
+/// `[file_ops.save]`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SaveFilesystemConfig {
+    /// Mount point or directory the entry applies to
+    pub path: PathBuf,
+    #[serde(default)]
+    pub strategy: Option<SaveStrategy>,
+    #[serde(default)]
+    pub backup: Option<SaveBackup>,
+    #[serde(default)]
+    pub backup_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub fsync: Option<SaveFsync>,
+}
+
+/// Save settings resolved for one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavePolicy {
+    pub strategy: SaveStrategy,
+    pub backup: SaveBackup,
+    pub backup_dir: PathBuf,
+    pub fsync: SaveFsync,
+}
+
+impl SaveConfig {
+    /// Settings for `path`, taking the most specific matching filesystem
+    /// entry over the defaults.
+    pub fn policy_for(&self, path: &Path) -> SavePolicy {
+        let filesystem = self
+            .filesystems
+            .iter()
+            .filter(|filesystem| path.starts_with(helix_stdx::path::expand_tilde(&filesystem.path)))
+            .max_by_key(|filesystem| filesystem.path.components().count());
+
+        let backup_dir = filesystem
+            .and_then(|filesystem| filesystem.backup_dir.as_ref())
+            .or(self.backup_dir.as_ref())
+            .map(|dir| helix_stdx::path::expand_tilde(dir).into_owned())
+            .unwrap_or_else(|| helix_loader::cache_dir().join("backups"));
+
+        SavePolicy {
+            strategy: filesystem
+                .and_then(|filesystem| filesystem.strategy)
+                .unwrap_or(self.strategy),
+            backup: filesystem
+                .and_then(|filesystem| filesystem.backup)
+                .unwrap_or(self.backup),
+            backup_dir,
+            fsync: filesystem
+                .and_then(|filesystem| filesystem.fsync)
+                .unwrap_or(self.fsync),
         }
     }
 }
@@ -976,6 +1996,44 @@ impl Config {
         Self::load_from_dir(&config_dir)
     }
 
+    /// Load configuration from the standard locations, then apply the
+    /// workspace settings of `project_root` when it has any.
+    pub fn load_for_project(project_root: Option<&Path>) -> anyhow::Result<Self> {
+        let mut config = Self::load()?;
+        if let Some(project_root) = project_root {
+            config.apply_workspace_settings_from(project_root);
+        }
+        Ok(config)
+    }
+
+    /// Apply `.nucleotide/settings.toml` from `project_root` over this config.
+    ///
+    /// Returns the settings file path when one was applied. Invalid workspace
+    /// settings are logged and ignored so a broken project file never hides
+    /// the global configuration.
+    pub fn apply_workspace_settings_from(&mut self, project_root: &Path) -> Option<PathBuf> {
+        match WorkspaceSettings::load(project_root) {
+            Ok(Some(settings)) => {
+                settings.apply_to(self);
+                let path = workspace_settings_path(project_root);
+                nucleotide_logging::info!(
+                    settings_path = %path.display(),
+                    "Applied workspace settings"
+                );
+                Some(path)
+            }
+            Ok(None) => None,
+            Err(err) => {
+                nucleotide_logging::warn!(
+                    project_root = %project_root.display(),
+                    error = %err,
+                    "Failed to load workspace settings, using global configuration"
+                );
+                None
+            }
+        }
+    }
+
     /// Load configuration from a specific directory
     pub fn load_from_dir(dir: &Path) -> anyhow::Result<Self> {
         // First, load the base Helix configuration
@@ -1007,6 +2065,12 @@ impl Config {
             }
         }
 
+        gui_config
+            .editor
+            .editing
+            .apply_to_helix(&mut helix_config.editor);
+        apply_cursor_mode_shapes(&gui_config.editor.cursor, &mut helix_config.editor);
+
         Ok(Self {
             helix: helix_config,
             gui: gui_config,
@@ -1024,6 +2088,17 @@ impl Config {
         self.helix.clone()
     }
 
+    /// Compiled file associations for icons and language detection.
+    pub fn file_associations(&self) -> nucleotide_ui::FileAssociations {
+        nucleotide_ui::FileAssociations::new(self.gui.file_associations.iter().map(|entry| {
+            nucleotide_ui::FileAssociation {
+                glob: entry.glob.clone(),
+                language: entry.language.clone(),
+                icon: entry.icon.clone(),
+            }
+        }))
+    }
+
     /// Get the editor font configuration
     pub fn editor_font(&self) -> FontConfig {
         // Resolve the editor font, falling back to UI font if not set
@@ -1051,6 +2126,25 @@ impl Config {
         }
     }
 
+    /// Wheel settings for the editor and terminal surfaces.
+    pub fn scroll_settings(&self) -> ScrollSettings {
+        let editor = self.gui.editor.scroll;
+        ScrollSettings {
+            editor,
+            terminal: self.gui.terminal.scroll.resolve(&editor),
+            terminal_alternate_scroll: self.gui.terminal.scroll.alternate_scroll,
+        }
+    }
+
+    /// REPL for `language`: the configured one, else the built-in default.
+    pub fn repl_command(&self, language: &str) -> Option<ReplCommandConfig> {
+        self.gui
+            .repl
+            .get(language)
+            .cloned()
+            .or_else(|| crate::repl::default_repl_command(language))
+    }
+
     /// Get the UI font configuration
     pub fn ui_font(&self) -> FontConfig {
         normalize_ui_font(self.gui.ui.font.clone().unwrap_or_else(default_ui_font))
@@ -1103,54 +2197,235 @@ impl Config {
         self.gui.project_markers.enable_builtin_fallback
     }
 
-    /// Get the project markers configuration
-    pub fn project_markers(&self) -> &ProjectMarkersConfig {
-        &self.gui.project_markers
-    }
+    /// Get the project markers configuration
+    pub fn project_markers(&self) -> &ProjectMarkersConfig {
+        &self.gui.project_markers
+    }
+
+    /// Build remote backend options from nucleotide.toml, then apply environment overrides.
+    pub fn remote_workspace_backend_options(
+        &self,
+    ) -> nucleotide_remote::RemoteWorkspaceBackendOptions {
+        let ssh = &self.gui.remote.ssh;
+        let wsl = &self.gui.remote.wsl;
+        let mut options = nucleotide_remote::RemoteWorkspaceBackendOptions {
+            ssh_helper_install_policy: ssh.helper_install.to_backend_policy(),
+            wsl_helper_install_policy: wsl.helper_install.to_backend_policy(),
+            ssh_connect_timeout_secs: ssh.connect_timeout_secs.filter(|timeout| *timeout > 0),
+            ssh_extra_args: ssh
+                .extra_args
+                .iter()
+                .map(std::ffi::OsString::from)
+                .collect(),
+            ..nucleotide_remote::RemoteWorkspaceBackendOptions::default()
+        };
+
+        if let Some(helper_path) = ssh.helper_path.as_ref()
+            && !helper_path.as_os_str().is_empty()
+        {
+            options.ssh_helper_path = Some(helper_path.clone());
+            options.ssh_helper_path_is_override = true;
+        }
+
+        if let Some(helper_path) = wsl.helper_path.as_ref()
+            && !helper_path.as_os_str().is_empty()
+        {
+            options.wsl_helper_path = Some(helper_path.clone());
+            options.wsl_helper_path_is_override = true;
+        }
+
+        if let Some(use_control_master) = ssh.use_control_master {
+            options.ssh_control_path = if use_control_master {
+                options
+                    .ssh_control_path
+                    .or_else(nucleotide_remote::default_ssh_control_path)
+            } else {
+                None
+            };
+        }
+
+        options.with_environment_overrides()
+    }
+}
+
+/// Location of the workspace settings file for `project_root`.
+pub fn workspace_settings_path(project_root: &Path) -> PathBuf {
+    project_root.join(WORKSPACE_SETTINGS_PATH)
+}
+
+/// Per-project overrides loaded from `.nucleotide/settings.toml`.
+///
+/// Every field is optional. Set values replace the global setting, which in
+/// turn replaces the built-in default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceSettings {
+    #[serde(default)]
+    pub theme: WorkspaceThemeSettings,
+
+    #[serde(default)]
+    pub editor: WorkspaceEditorSettings,
+
+    #[serde(default)]
+    pub file_tree: WorkspaceFileTreeSettings,
+
+    #[serde(default)]
+    pub terminal: WorkspaceTerminalSettings,
+
+    /// Checked before the global associations.
+    #[serde(default)]
+    pub file_associations: Vec<FileAssociationConfig>,
+
+    /// Named launch commands offered by the Run menu
+    #[serde(default, rename = "run")]
+    pub run_configurations: Vec<RunConfiguration>,
+}
+
+/// Workspace overrides for [`ThemeConfig`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceThemeSettings {
+    #[serde(default)]
+    pub mode: Option<ThemeMode>,
+    #[serde(default)]
+    pub light_theme: Option<String>,
+    #[serde(default)]
+    pub dark_theme: Option<String>,
+}
+
+/// Workspace overrides for editor settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceEditorSettings {
+    /// Editor font size in points.
+    #[serde(default)]
+    pub font_size: Option<f32>,
+    /// Format documents on save. Maps to Helix `editor.auto-format`.
+    #[serde(default)]
+    pub format_on_save: Option<bool>,
+}
+
+/// Workspace overrides for [`FileTreeUiConfig`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceFileTreeSettings {
+    #[serde(default)]
+    pub density: Option<FileTreeDisplayDensity>,
+    #[serde(default)]
+    pub flatten_empty_directories: Option<bool>,
+    #[serde(default)]
+    pub metadata_columns: Option<bool>,
+    #[serde(default)]
+    pub metadata_tooltips: Option<bool>,
+    #[serde(default)]
+    pub large_project_entries: Option<usize>,
+    #[serde(default)]
+    pub directory_page_size: Option<usize>,
+}
+
+/// Workspace overrides for [`TerminalConfig`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceTerminalSettings {
+    #[serde(default)]
+    pub shell: Option<String>,
+    /// Name of a `[[terminal.profiles]]` entry to use for this project
+    #[serde(default)]
+    pub default_profile: Option<String>,
+}
+
+impl WorkspaceSettings {
+    /// Load workspace settings for `project_root`, or `None` when the project
+    /// has no settings file.
+    pub fn load(project_root: &Path) -> anyhow::Result<Option<Self>> {
+        let settings_path = workspace_settings_path(project_root);
+        if !settings_path.is_file() {
+            return Ok(None);
+        }
+
+        let settings_str = std::fs::read_to_string(&settings_path)?;
+        let settings = toml::from_str(&settings_str)?;
+        Ok(Some(settings))
+    }
+
+    /// Merge these overrides over `config`.
+    pub fn apply_to(&self, config: &mut Config) {
+        let theme = &mut config.gui.theme;
+        if let Some(mode) = self.theme.mode.clone() {
+            theme.mode = mode;
+        }
+        if let Some(light_theme) = self.theme.light_theme.clone() {
+            theme.light_theme = Some(light_theme);
+        }
+        if let Some(dark_theme) = self.theme.dark_theme.clone() {
+            theme.dark_theme = Some(dark_theme);
+        }
+
+        match self.editor.font_size {
+            Some(size) if size.is_finite() && size > 0.0 => {
+                let mut font = config
+                    .gui
+                    .editor
+                    .font
+                    .clone()
+                    .unwrap_or_else(|| config.gui.ui.font.clone().unwrap_or_default());
+                font.size = size;
+                config.gui.editor.font = Some(font);
+            }
+            Some(size) => {
+                nucleotide_logging::warn!(
+                    font_size = size,
+                    "Ignoring invalid workspace editor font size"
+                );
+            }
+            None => {}
+        }
+        if let Some(format_on_save) = self.editor.format_on_save {
+            config.helix.editor.auto_format = format_on_save;
+        }
 
-    /// Build remote backend options from nucleotide.toml, then apply environment overrides.
-    pub fn remote_workspace_backend_options(
-        &self,
-    ) -> nucleotide_remote::RemoteWorkspaceBackendOptions {
-        let ssh = &self.gui.remote.ssh;
-        let wsl = &self.gui.remote.wsl;
-        let mut options = nucleotide_remote::RemoteWorkspaceBackendOptions {
-            ssh_helper_install_policy: ssh.helper_install.to_backend_policy(),
-            wsl_helper_install_policy: wsl.helper_install.to_backend_policy(),
-            ssh_connect_timeout_secs: ssh.connect_timeout_secs.filter(|timeout| *timeout > 0),
-            ssh_extra_args: ssh
-                .extra_args
-                .iter()
-                .map(std::ffi::OsString::from)
-                .collect(),
-            ..nucleotide_remote::RemoteWorkspaceBackendOptions::default()
-        };
+        if let Some(density) = self.file_tree.density {
+            config.gui.file_tree.density = density;
+        }
+        if let Some(flatten) = self.file_tree.flatten_empty_directories {
+            config.gui.file_tree.flatten_empty_directories = flatten;
+        }
+        if let Some(columns) = self.file_tree.metadata_columns {
+            config.gui.file_tree.metadata_columns = columns;
+        }
+        if let Some(tooltips) = self.file_tree.metadata_tooltips {
+            config.gui.file_tree.metadata_tooltips = tooltips;
+        }
+        if let Some(entries) = self.file_tree.large_project_entries {
+            config.gui.file_tree.large_project_entries = entries;
+        }
+        if let Some(page_size) = self.file_tree.directory_page_size {
+            config.gui.file_tree.directory_page_size = page_size;
+        }
 
-        if let Some(helper_path) = ssh.helper_path.as_ref()
-            && !helper_path.as_os_str().is_empty()
+        if let Some(shell) = self
+            .terminal
+            .shell
+            .clone()
+            .filter(|shell| !shell.trim().is_empty())
         {
-            options.ssh_helper_path = Some(helper_path.clone());
-            options.ssh_helper_path_is_override = true;
+            config.gui.terminal.shell = Some(shell);
         }
-
-        if let Some(helper_path) = wsl.helper_path.as_ref()
-            && !helper_path.as_os_str().is_empty()
+        if let Some(profile) = self
+            .terminal
+            .default_profile
+            .clone()
+            .filter(|profile| !profile.trim().is_empty())
         {
-            options.wsl_helper_path = Some(helper_path.clone());
-            options.wsl_helper_path_is_override = true;
+            config.gui.terminal.default_profile = Some(profile);
         }
 
-        if let Some(use_control_master) = ssh.use_control_master {
-            options.ssh_control_path = if use_control_master {
-                options
-                    .ssh_control_path
-                    .or_else(nucleotide_remote::default_ssh_control_path)
-            } else {
-                None
-            };
+        if !self.file_associations.is_empty() {
+            let global = std::mem::take(&mut config.gui.file_associations);
+            config.gui.file_associations = self
+                .file_associations
+                .iter()
+                .cloned()
+                .chain(global)
+                .collect();
         }
 
-        options.with_environment_overrides()
+        config.gui.run_configurations = self.run_configurations.clone();
     }
 }
 
@@ -1313,6 +2588,7 @@ fn load_gui_config(dir: &Path) -> anyhow::Result<GuiConfig> {
     nucleotide_logging::info!(
         project_lsp_startup = config.lsp.project_lsp_startup,
         lsp_startup_timeout_ms = config.lsp.startup_timeout_ms,
+        lsp_traffic_inspector = config.lsp.traffic_inspector,
         project_markers_enabled = config.project_markers.enable_project_markers,
         project_detection_timeout_ms = config.project_markers.detection_timeout_ms,
         builtin_fallback_enabled = config.project_markers.enable_builtin_fallback,
@@ -1642,11 +2918,19 @@ enable_keep_preview_on_code_navigation = true
 [file_tree]
 density = "compact"
 flatten_empty_directories = false
+metadata_tooltips = true
 
 [updates]
 enabled = false
 check_on_startup = false
 auto_download = true
+
+[usage]
+enabled = false
+
+[references]
+panel_threshold = 1
+clear_on_navigate = true
 "#;
 
         let config: GuiConfig = toml::from_str(config_str).expect("Failed to parse GuiConfig");
@@ -1695,6 +2979,10 @@ auto_download = true
         assert!(!config.updates.enabled);
         assert!(!config.updates.check_on_startup);
         assert!(config.updates.auto_download);
+        assert!(!config.usage.enabled);
+        assert_eq!(config.references.panel_threshold, 1);
+        assert_eq!(config.references.context_lines, 1);
+        assert!(config.references.clear_on_navigate);
         assert!(!config.tab_bar.show);
         assert!(!config.tab_bar.show_nav_history_buttons);
         assert!(!config.tab_bar.show_tab_bar_buttons);
@@ -1724,6 +3012,8 @@ auto_download = true
         assert!(config.preview_tabs.enable_keep_preview_on_code_navigation);
         assert_eq!(config.file_tree.density, FileTreeDisplayDensity::Compact);
         assert!(!config.file_tree.flatten_empty_directories);
+        assert!(!config.file_tree.metadata_columns);
+        assert!(config.file_tree.metadata_tooltips);
     }
 
     #[test]
@@ -1733,6 +3023,7 @@ auto_download = true
         assert!(config.updates.enabled);
         assert!(config.updates.check_on_startup);
         assert!(!config.updates.auto_download);
+        assert!(config.usage.enabled);
     }
 
     #[test]
@@ -1814,6 +3105,11 @@ auto_download = true
         assert!(config.preview_tabs.enabled);
         assert_eq!(config.file_tree.density, FileTreeDisplayDensity::Default);
         assert_eq!(config.file_ops.delete_behavior, DeleteBehavior::Trash);
+        assert_eq!(config.file_ops.save.strategy, SaveStrategy::Atomic);
+        assert_eq!(config.file_ops.save.backup, SaveBackup::None);
+        assert_eq!(config.file_ops.save.fsync, SaveFsync::File);
+        assert_eq!(config.export.page_size, PageSize::A4);
+        assert!(config.export.line_numbers);
         assert_eq!(
             config.remote.ssh.helper_install,
             RemoteSshHelperInstall::Auto
@@ -1872,8 +3168,36 @@ auto_download = true
             "[file_tree]",
             "density",
             "flatten_empty_directories",
+            "metadata_columns",
+            "metadata_tooltips",
+            "[terminal]",
+            "shell",
+            "scrollback_lines",
+            "confirm_multiline_paste",
+            "[export]",
+            "font_family",
+            "pdf_font_family",
+            "font_size",
+            "page_size",
+            "line_numbers",
+            "[[file_associations]]",
+            "glob",
+            "language",
+            "icon",
+            "[[file_templates]]",
+            "file",
+            "[[project_templates]]",
+            "command",
+            "args",
             "[file_ops]",
             "delete_behavior",
+            "auto_reload",
+            "[file_ops.save]",
+            "strategy",
+            "backup",
+            "backup_dir",
+            "fsync",
+            "[[file_ops.save.filesystems]]",
             "[remote.ssh]",
             "helper_install",
             "helper_path",
@@ -1884,6 +3208,25 @@ auto_download = true
             "[lsp]",
             "project_lsp_startup",
             "startup_timeout_ms",
+            "traffic_inspector",
+            "did_change_coalesce_ms",
+            "[lsp.requests]",
+            "interactive_timeout_ms",
+            "navigation_timeout_ms",
+            "editing_timeout_ms",
+            "background_timeout_ms",
+            "max_retries",
+            "failure_threshold",
+            "degraded_cooldown_secs",
+            "[lsp.restart]",
+            "max_restarts",
+            "initial_backoff_ms",
+            "max_backoff_ms",
+            "stable_after_secs",
+            "[lsp.merge.<language>]",
+            "completion",
+            "hover",
+            "diagnostics",
             "[project_markers]",
             "enable_project_markers",
             "detection_timeout_ms",
@@ -1901,6 +3244,43 @@ auto_download = true
         }
     }
 
+    #[test]
+    fn save_policy_prefers_the_most_specific_filesystem_entry() {
+        let config: GuiConfig = toml::from_str(
+            r#"
+            [file_ops.save]
+            backup = "suffix"
+            backup_dir = "/var/backups/editor"
+
+            [[file_ops.save.filesystems]]
+            path = "/mnt"
+            fsync = "full"
+
+            [[file_ops.save.filesystems]]
+            path = "/mnt/nfs"
+            strategy = "in_place"
+            backup = "directory"
+            "#,
+        )
+        .expect("save config parses");
+        let save = &config.file_ops.save;
+
+        let nfs = save.policy_for(Path::new("/mnt/nfs/project/main.rs"));
+        assert_eq!(nfs.strategy, SaveStrategy::InPlace);
+        assert_eq!(nfs.backup, SaveBackup::Directory);
+        assert_eq!(nfs.backup_dir, PathBuf::from("/var/backups/editor"));
+        assert_eq!(nfs.fsync, SaveFsync::File);
+
+        let mounted = save.policy_for(Path::new("/mnt/usb/notes.md"));
+        assert_eq!(mounted.strategy, SaveStrategy::Atomic);
+        assert_eq!(mounted.backup, SaveBackup::Suffix);
+        assert_eq!(mounted.fsync, SaveFsync::Full);
+
+        let home = save.policy_for(Path::new("/home/me/mnt/nfs/notes.md"));
+        assert_eq!(home.strategy, SaveStrategy::Atomic);
+        assert_eq!(home.fsync, SaveFsync::File);
+    }
+
     #[test]
     fn remote_ssh_config_maps_to_backend_options() {
         let config: GuiConfig = toml::from_str(
@@ -2203,12 +3583,14 @@ file_icons = false
 [lsp]
 project_lsp_startup = true
 startup_timeout_ms = 3000
+traffic_inspector = true
 "#;
 
         let config: GuiConfig = toml::from_str(config_str).expect("Failed to parse LSP config");
 
         assert!(config.lsp.project_lsp_startup);
         assert_eq!(config.lsp.startup_timeout_ms, 3000);
+        assert!(config.lsp.traffic_inspector);
     }
 
     #[test]
@@ -2218,6 +3600,7 @@ startup_timeout_ms = 3000
         // Test default values
         assert!(config.lsp.project_lsp_startup);
         assert_eq!(config.lsp.startup_timeout_ms, 5000);
+        assert!(!config.lsp.traffic_inspector);
         assert_eq!(config.max_tabs, None);
         assert!(config.tab_bar.show);
         assert!(config.tab_bar.show_nav_history_buttons);
@@ -2268,6 +3651,265 @@ startup_timeout_ms = 3000
         assert!(!unflattened.flatten_empty_directories);
     }
 
+    #[test]
+    fn workspace_settings_override_global_values_they_set() {
+        let mut gui = GuiConfig::default();
+        gui.theme.dark_theme = Some("global-dark".to_string());
+        gui.theme.light_theme = Some("global-light".to_string());
+        gui.terminal.shell = Some("/bin/bash".to_string());
+        let mut config = Config {
+            helix: HelixConfig::default(),
+            gui,
+        };
+
+        let settings: WorkspaceSettings = toml::from_str(
+            r#"
+            [theme]
+            dark_theme = "project-dark"
+
+            [editor]
+            font_size = 16.5
+            format_on_save = false
+
+            [file_tree]
+            density = "compact"
+
+            [terminal]
+            shell = "/usr/bin/fish"
+            default_profile = "nix"
+            "#,
+        )
+        .expect("workspace settings should parse");
+        settings.apply_to(&mut config);
+
+        assert_eq!(config.gui.theme.get_dark_theme(), "project-dark");
+        assert_eq!(config.gui.theme.get_light_theme(), "global-light");
+        assert_eq!(config.editor_font().size, 16.5);
+        assert!(!config.helix.editor.auto_format);
+        assert_eq!(
+            config.gui.file_tree.density,
+            FileTreeDisplayDensity::Compact
+        );
+        assert!(config.gui.file_tree.flatten_empty_directories);
+        assert_eq!(config.gui.terminal.shell.as_deref(), Some("/usr/bin/fish"));
+        assert_eq!(config.gui.terminal.default_profile.as_deref(), Some("nix"));
+    }
+
+    #[test]
+    fn repl_commands_prefer_config_over_builtin_defaults() {
+        let gui: GuiConfig = toml::from_str(
+            r#"
+            [repl.python]
+            command = "ipython"
+            args = ["--no-banner"]
+            bracketed_paste = false
+
+            [repl.scheme]
+            command = "guile"
+            "#,
+        )
+        .expect("repl config should parse");
+        let config = Config {
+            helix: HelixConfig::default(),
+            gui,
+        };
+
+        let python = config.repl_command("python").expect("python repl");
+        assert_eq!(python.command, "ipython");
+        assert_eq!(python.args, vec!["--no-banner".to_string()]);
+        assert!(!python.bracketed_paste);
+        assert!(config.repl_command("scheme").unwrap().bracketed_paste);
+        assert_eq!(config.repl_command("ruby").unwrap().command, "irb");
+        assert!(config.repl_command("rust").is_none());
+    }
+
+    #[test]
+    fn terminal_profiles_parse_with_palette_overrides() {
+        let gui: GuiConfig = toml::from_str(
+            r##"
+            [terminal]
+            default_profile = "nix"
+            scrollback_lines = 50000
+
+            [[terminal.profiles]]
+            name = "nix"
+            shell = "/bin/bash"
+            args = ["-l"]
+            cwd = "current_file"
+            env = { IN_NIX_SHELL = "impure" }
+            palette = { background = "#101010", ansi = ["#000000", "not-a-color"] }
+
+            [[terminal.profiles]]
+            name = "plain"
+            "##,
+        )
+        .expect("terminal profiles should parse");
+        assert_eq!(gui.terminal.scrollback_lines, Some(50_000));
+
+        let profile = gui
+            .terminal
+            .default_profile()
+            .expect("default profile should resolve");
+        assert_eq!(profile.shell.as_deref(), Some("/bin/bash"));
+        assert_eq!(profile.args, vec!["-l".to_string()]);
+        assert_eq!(profile.cwd, TerminalCwdStrategy::CurrentFile);
+        assert_eq!(
+            profile.env.get("IN_NIX_SHELL").map(String::as_str),
+            Some("impure")
+        );
+
+        let palette = profile
+            .palette
+            .to_override()
+            .expect("palette should override colors");
+        assert!(palette.background.is_some());
+        assert!(palette.foreground.is_none());
+        assert!(palette.ansi[0].is_some());
+        assert!(palette.ansi[1].is_none());
+
+        let plain = gui.terminal.profile("plain").expect("plain profile");
+        assert_eq!(plain.cwd, TerminalCwdStrategy::ProjectRoot);
+        assert!(plain.palette.to_override().is_none());
+
+        let missing = TerminalConfig {
+            default_profile: Some("missing".to_string()),
+            ..gui.terminal.clone()
+        };
+        assert!(missing.default_profile().is_none());
+    }
+
+    #[test]
+    fn workspace_settings_ignore_invalid_values_and_missing_files() {
+        let mut config = Config {
+            helix: HelixConfig::default(),
+            gui: GuiConfig::default(),
+        };
+        let settings: WorkspaceSettings = toml::from_str(
+            r#"
+            [editor]
+            font_size = -1.0
+
+            [terminal]
+            shell = "  "
+            "#,
+        )
+        .expect("workspace settings should parse");
+        settings.apply_to(&mut config);
+
+        assert!(config.gui.editor.font.is_none());
+        assert!(config.gui.terminal.shell.is_none());
+
+        let temp_dir = tempfile::TempDir::new().expect("should create temp directory");
+        assert!(
+            WorkspaceSettings::load(temp_dir.path())
+                .expect("missing settings should not error")
+                .is_none()
+        );
+        assert!(
+            config
+                .apply_workspace_settings_from(temp_dir.path())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn workspace_file_associations_are_checked_before_global_ones() {
+        let gui: GuiConfig = toml::from_str(
+            r#"
+            [[file_associations]]
+            glob = "*.mdx"
+            language = "markdown"
+            icon = "md"
+
+            [[file_associations]]
+            glob = "Dockerfile.*"
+            language = "dockerfile"
+            "#,
+        )
+        .expect("file associations should parse");
+        let mut config = Config {
+            helix: HelixConfig::default(),
+            gui,
+        };
+        let settings: WorkspaceSettings = toml::from_str(
+            r#"
+            [[file_associations]]
+            glob = "*.mdx"
+            language = "jsx"
+            "#,
+        )
+        .expect("workspace file associations should parse");
+        settings.apply_to(&mut config);
+
+        let associations = config.file_associations();
+        assert_eq!(associations.language_for("page.mdx"), Some("jsx"));
+        assert_eq!(associations.icon_for("page.mdx"), Some("md"));
+        assert_eq!(
+            associations.language_for("Dockerfile.dev"),
+            Some("dockerfile")
+        );
+    }
+
+    #[test]
+    fn workspace_run_configurations_parse_from_settings() {
+        let mut config = Config {
+            helix: HelixConfig::default(),
+            gui: GuiConfig::default(),
+        };
+        let settings: WorkspaceSettings = toml::from_str(
+            r#"
+            [[run]]
+            name = "build"
+            command = "cargo"
+            args = ["build"]
+
+            [[run]]
+            name = "serve"
+            command = "cargo"
+            args = ["run", "--", "--port", "8080"]
+            cwd = "crates/server"
+            env = { RUST_LOG = "debug" }
+            pre_launch = "build"
+            "#,
+        )
+        .expect("run configurations should parse");
+        settings.apply_to(&mut config);
+
+        let runs = &config.gui.run_configurations;
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].cwd, None);
+        assert_eq!(runs[1].cwd.as_deref(), Some(Path::new("crates/server")));
+        assert_eq!(
+            runs[1].env.get("RUST_LOG").map(String::as_str),
+            Some("debug")
+        );
+        assert_eq!(runs[1].pre_launch.as_deref(), Some("build"));
+    }
+
+    #[test]
+    fn workspace_settings_template_loads_from_project_root() {
+        let temp_dir = tempfile::TempDir::new().expect("should create temp directory");
+        let settings_path = workspace_settings_path(temp_dir.path());
+        std::fs::create_dir_all(settings_path.parent().unwrap()).unwrap();
+        std::fs::write(&settings_path, WORKSPACE_SETTINGS_TEMPLATE).unwrap();
+        assert!(
+            WorkspaceSettings::load(temp_dir.path())
+                .expect("template should parse")
+                .is_some()
+        );
+
+        std::fs::write(&settings_path, "[terminal]\nshell = \"/bin/zsh\"\n").unwrap();
+        let mut config = Config {
+            helix: HelixConfig::default(),
+            gui: GuiConfig::default(),
+        };
+        assert_eq!(
+            config.apply_workspace_settings_from(temp_dir.path()),
+            Some(settings_path)
+        );
+        assert_eq!(config.gui.terminal.shell.as_deref(), Some("/bin/zsh"));
+    }
+
     #[test]
     fn test_config_convenience_methods() {
         let mut gui_config = GuiConfig::default();
@@ -2363,6 +4005,77 @@ dark_theme = "monokai"
         assert_eq!(sanitized.startup_timeout_ms, 3000);
     }
 
+    #[test]
+    fn lsp_restart_policy_parses_and_sanitizes() {
+        let config: GuiConfig = toml::from_str(
+            r#"
+[lsp.restart]
+max_restarts = 2
+initial_backoff_ms = 0
+max_backoff_ms = 100
+"#,
+        )
+        .expect("Failed to parse LSP restart config");
+
+        let restart = &config.lsp.restart;
+        assert!(restart.enabled);
+        assert_eq!(restart.max_restarts, 2);
+        assert_eq!(restart.stable_after_secs, 60);
+        assert!(config.lsp.validate().is_err());
+
+        let sanitized = config.lsp.sanitized();
+        assert_eq!(sanitized.restart.initial_backoff_ms, 500);
+        assert_eq!(sanitized.restart.max_backoff_ms, 500);
+        assert!(sanitized.validate().is_ok());
+    }
+
+    #[test]
+    fn lsp_merge_policy_parses_per_language() {
+        let config: GuiConfig = toml::from_str(
+            r#"
+[lsp.merge.typescript]
+priority = ["typescript-language-server", "tailwindcss"]
+hover = "first"
+"#,
+        )
+        .expect("Failed to parse LSP merge config");
+
+        let merge = &config.lsp.merge["typescript"];
+        assert_eq!(
+            merge.priority,
+            ["typescript-language-server", "tailwindcss"]
+        );
+        assert_eq!(merge.completion, LspMergeStrategy::All);
+        assert_eq!(merge.hover, LspMergeStrategy::First);
+        assert_eq!(merge.diagnostics, LspMergeStrategy::All);
+        assert!(!config.lsp.merge.contains_key("rust"));
+    }
+
+    #[test]
+    fn lsp_request_policy_parses_and_sanitizes() {
+        let config: GuiConfig = toml::from_str(
+            r#"
+[lsp.requests]
+interactive_timeout_ms = 0
+navigation_timeout_ms = 2500
+failure_threshold = 0
+"#,
+        )
+        .expect("Failed to parse LSP request config");
+
+        let requests = &config.lsp.requests;
+        assert_eq!(requests.navigation_timeout_ms, 2500);
+        assert_eq!(requests.editing_timeout_ms, 15000);
+        assert_eq!(requests.max_retries, 1);
+        assert!(config.lsp.validate().is_err());
+
+        let sanitized = config.lsp.sanitized();
+        assert_eq!(sanitized.requests.interactive_timeout_ms, 5000);
+        assert_eq!(sanitized.requests.navigation_timeout_ms, 2500);
+        assert_eq!(sanitized.requests.failure_threshold, 1);
+        assert!(sanitized.validate().is_ok());
+    }
+
     #[test]
     fn test_project_marker_parsing() {
         let config_str = r#"
@@ -2825,6 +4538,180 @@ size = 18.0
         assert_eq!(editor_font.line_height, default_editor_font.line_height);
     }
 
+    #[test]
+    fn terminal_scroll_settings_follow_editor_unless_overridden() {
+        let gui_config: GuiConfig = toml::from_str(
+            r#"
+[editor.scroll]
+lines_per_tick = 3.0
+fast_scroll_modifier = "control"
+
+[terminal.scroll]
+fast_scroll_multiplier = 8.0
+alternate_scroll = false
+"#,
+        )
+        .expect("scroll config should parse");
+        let config = Config {
+            helix: HelixConfig::default(),
+            gui: gui_config,
+        };
+
+        let settings = config.scroll_settings();
+        assert_eq!(settings.editor.lines_per_tick, 3.0);
+        assert_eq!(settings.editor.fast_scroll_multiplier, 5.0);
+        assert_eq!(settings.terminal.lines_per_tick, 3.0);
+        assert_eq!(
+            settings.terminal.fast_scroll_modifier,
+            ScrollModifier::Control
+        );
+        assert_eq!(settings.terminal.fast_scroll_multiplier, 8.0);
+        assert!(settings.terminal.shift_scrolls_horizontally);
+        assert!(!settings.terminal_alternate_scroll);
+
+        let default_config = Config {
+            helix: HelixConfig::default(),
+            gui: GuiConfig::default(),
+        };
+        assert_eq!(default_config.scroll_settings(), ScrollSettings::default());
+    }
+
+    #[test]
+    fn mouse_selection_word_characters_are_configurable() {
+        let config: GuiConfig = toml::from_str(
+            r#"
+[editor.mouse_selection]
+word_characters = "-$"
+"#,
+        )
+        .expect("mouse selection config should parse");
+
+        let mouse = &config.editor.mouse_selection;
+        assert!(mouse.is_word_char('-'));
+        assert!(!mouse.is_word_char('.'));
+        assert!(mouse.triple_click_includes_line_ending);
+    }
+
+    #[test]
+    fn split_affordances_parse_and_clamp_dimming() {
+        let config: GuiConfig = toml::from_str(
+            r#"
+[editor.splits]
+focus_indicator = "border"
+dim_inactive = 1.5
+"#,
+        )
+        .expect("splits config should parse");
+
+        let splits = &config.editor.splits;
+        assert_eq!(splits.focus_indicator, SplitFocusIndicator::Border);
+        assert_eq!(splits.inactive_dim_opacity(), 1.0);
+        assert_eq!(SplitsConfig::default().inactive_dim_opacity(), 0.0);
+    }
+
+    #[test]
+    fn cursor_mode_shapes_fill_in_unconfigured_helix_shapes() {
+        use helix_view::{document::Mode, editor::CursorShapeConfig, graphics::CursorKind};
+
+        let mut helix = HelixConfig::default();
+        apply_cursor_mode_shapes(&CursorConfig::default(), &mut helix.editor);
+        assert_eq!(
+            helix.editor.cursor_shape.from_mode(Mode::Normal),
+            CursorKind::Block
+        );
+        assert_eq!(
+            helix.editor.cursor_shape.from_mode(Mode::Insert),
+            CursorKind::Bar
+        );
+        assert_eq!(
+            helix.editor.cursor_shape.from_mode(Mode::Select),
+            CursorKind::Underline
+        );
+
+        let configured =
+            CursorShapeConfig::new(CursorKind::Block, CursorKind::Block, CursorKind::Underline);
+        helix.editor.cursor_shape = configured.clone();
+        apply_cursor_mode_shapes(&CursorConfig::default(), &mut helix.editor);
+        assert_eq!(helix.editor.cursor_shape, configured);
+
+        let mut block_only = HelixConfig::default();
+        let config = CursorConfig {
+            mode_shapes: false,
+            ..CursorConfig::default()
+        };
+        apply_cursor_mode_shapes(&config, &mut block_only.editor);
+        assert_eq!(block_only.editor.cursor_shape, CursorShapeConfig::default());
+    }
+
+    #[test]
+    fn editing_config_sets_helix_auto_pairs_only_when_configured() {
+        use helix_core::syntax::config::AutoPairConfig;
+
+        let gui_config: GuiConfig = toml::from_str(
+            r#"
+[editor.editing]
+auto_close_pairs = false
+surround_selection = false
+"#,
+        )
+        .expect("editing config should parse");
+        let editing = &gui_config.editor.editing;
+        assert!(editing.paste_reindent);
+        assert!(editing.type_over);
+        assert!(!editing.surround_selection);
+        assert!(editing.rename_paired_tags);
+
+        let mut helix = HelixConfig::default();
+        editing.apply_to_helix(&mut helix.editor);
+        assert!(matches!(
+            helix.editor.auto_pairs,
+            AutoPairConfig::Enable(false)
+        ));
+
+        let enable = EditingConfig {
+            auto_close_pairs: Some(true),
+            ..EditingConfig::default()
+        };
+        enable.apply_to_helix(&mut helix.editor);
+        assert!(matches!(
+            helix.editor.auto_pairs,
+            AutoPairConfig::Enable(true)
+        ));
+
+        helix.editor.auto_pairs = AutoPairConfig::Enable(false);
+        EditingConfig::default().apply_to_helix(&mut helix.editor);
+        assert!(matches!(
+            helix.editor.auto_pairs,
+            AutoPairConfig::Enable(false)
+        ));
+    }
+
+    #[test]
+    fn editing_language_overrides_win_over_the_global_pair_settings() {
+        let gui_config: GuiConfig = toml::from_str(
+            r#"
+[editor.editing]
+auto_close_pairs = true
+auto_close_pairs_in_comments_and_strings = false
+
+[editor.editing.languages.markdown]
+auto_close_pairs = false
+
+[editor.editing.languages.rust]
+surround_selection = false
+"#,
+        )
+        .expect("editing language overrides should parse");
+        let editing = &gui_config.editor.editing;
+
+        assert!(!editing.auto_close_pairs_in_comments_and_strings);
+        assert_eq!(editing.auto_close_pairs_for(Some("markdown")), Some(false));
+        assert_eq!(editing.auto_close_pairs_for(Some("rust")), Some(true));
+        assert_eq!(editing.auto_close_pairs_for(None), Some(true));
+        assert!(!editing.surround_selection_for(Some("rust")));
+        assert!(editing.surround_selection_for(Some("markdown")));
+    }
+
     #[test]
     fn windows_logfont_height_is_normalized_to_logical_pixels() {
         assert_eq!(
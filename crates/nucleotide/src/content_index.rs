@@ -0,0 +1,1008 @@
+// ABOUTME: Trigram index of project file contents, built in the background and kept on disk
+// ABOUTME: Narrows project search and live grep to the files that can contain a match
+
+use std::collections::{HashMap, HashSet};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result, bail};
+use nucleotide_workspace::TextSearchMatch;
+
+/// Prompt command reporting the state of the content index.
+pub const CONTENT_INDEX_STATUS_COMMAND: &str = "content-index-status";
+/// Prompt command indexing the project again from scratch.
+pub const CONTENT_INDEX_REBUILD_COMMAND: &str = "content-index-rebuild";
+/// Prompt commands for the content index, with completion descriptions.
+pub const CONTENT_INDEX_COMMANDS: &[(&str, &str)] = &[
+    (
+        CONTENT_INDEX_STATUS_COMMAND,
+        "Show the state of the project content index",
+    ),
+    (
+        CONTENT_INDEX_REBUILD_COMMAND,
+        "Index the project contents again from scratch",
+    ),
+];
+
+const STORE_MAGIC: &[u8; 8] = b"NUCIDX01";
+/// Files with a NUL byte this close to the start are treated as binary.
+const BINARY_PROBE_BYTES: usize = 8 * 1024;
+/// Tombstoned files are only compacted away once there are this many.
+const COMPACT_AFTER_REMOVALS: usize = 1024;
+
+/// Three bytes of text, ASCII case folded, packed into an integer.
+pub type Trigram = u32;
+
+/// Folds ASCII case only, so the trigrams of a query line up with the file
+/// bytes. The few non-ASCII characters that fold to ASCII letters, such as
+/// the Kelvin sign, are not found by case-insensitive queries.
+fn trigram(bytes: [u8; 3]) -> Trigram {
+    u32::from_le_bytes([
+        bytes[0].to_ascii_lowercase(),
+        bytes[1].to_ascii_lowercase(),
+        bytes[2].to_ascii_lowercase(),
+        0,
+    ])
+}
+
+/// Distinct trigrams of `bytes`, leaving out those spanning a line break
+/// since searches match within a line.
+fn trigrams(bytes: &[u8]) -> Vec<Trigram> {
+    let mut trigrams = bytes
+        .windows(3)
+        .filter(|window| !window.contains(&b'\n'))
+        .map(|window| trigram([window[0], window[1], window[2]]))
+        .collect::<Vec<_>>();
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    trigrams
+}
+
+/// Bounds on what gets indexed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexLimits {
+    /// Larger files are left out, and always searched on disk
+    pub max_file_bytes: u64,
+    pub max_files: usize,
+    /// Postings kept in memory, four bytes each
+    pub max_postings: usize,
+}
+
+impl IndexLimits {
+    pub fn from_config(config: &crate::config::ContentIndexConfig) -> Self {
+        Self {
+            max_file_bytes: config.max_file_size_kb.saturating_mul(1024),
+            max_files: config.max_files,
+            max_postings: config.max_memory_mb.saturating_mul(1024 * 1024) / 4,
+        }
+    }
+}
+
+/// Which files of the project are walked, following the file picker's
+/// hidden and ignore file settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkOptions {
+    pub hidden: bool,
+    pub parents: bool,
+    pub ignore: bool,
+    pub git_ignore: bool,
+    pub git_global: bool,
+    pub git_exclude: bool,
+    pub follow_links: bool,
+    pub max_depth: Option<usize>,
+    /// Extra ignore file applied to the whole walk
+    pub ignore_file: Option<PathBuf>,
+}
+
+impl WalkOptions {
+    pub fn from_file_picker(config: &helix_view::editor::FilePickerConfig) -> Self {
+        Self {
+            hidden: config.hidden,
+            parents: config.parents,
+            ignore: config.ignore,
+            git_ignore: config.git_ignore,
+            git_global: config.git_global,
+            git_exclude: config.git_exclude,
+            follow_links: config.follow_symlinks,
+            max_depth: config.max_depth,
+            ignore_file: Some(helix_loader::config_dir().join("ignore")),
+        }
+    }
+
+    /// Walks the files under `root`. With `only`, the walk descends just far
+    /// enough to reach that path and everything below it, still applying
+    /// the ignore files of every directory on the way.
+    fn files(&self, root: &Path, only: Option<&Path>) -> impl Iterator<Item = PathBuf> {
+        let mut builder = ignore::WalkBuilder::new(root);
+        builder
+            .hidden(self.hidden)
+            .parents(self.parents)
+            .ignore(self.ignore)
+            .git_ignore(self.git_ignore)
+            .git_global(self.git_global)
+            .git_exclude(self.git_exclude)
+            .follow_links(self.follow_links)
+            .max_depth(self.max_depth)
+            .require_git(false);
+        if let Some(ignore_file) = self.ignore_file.as_deref().filter(|path| path.is_file()) {
+            builder.add_ignore(ignore_file);
+        }
+        let only = only.map(Path::to_path_buf);
+        builder.filter_entry(move |entry| {
+            entry.file_name() != ".git"
+                && only.as_deref().is_none_or(|only| {
+                    only.starts_with(entry.path()) || entry.path().starts_with(only)
+                })
+        });
+        builder
+            .build()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_some_and(|kind| kind.is_file()))
+            .map(ignore::DirEntry::into_path)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IndexedFile {
+    /// Path relative to the index root
+    path: PathBuf,
+    len: u64,
+    modified_ms: u64,
+    live: bool,
+}
+
+/// Contents of the project's text files, as the trigrams each one contains.
+#[derive(Debug, Clone)]
+pub struct ContentIndex {
+    root: PathBuf,
+    files: Vec<IndexedFile>,
+    by_path: HashMap<PathBuf, u32>,
+    /// Ids of the files containing each trigram, ascending
+    postings: HashMap<Trigram, Vec<u32>>,
+    posting_count: usize,
+    removed: usize,
+    /// Files too large to index, searched whatever the query
+    oversized: HashSet<PathBuf>,
+    /// A limit stopped files from being added
+    truncated: bool,
+}
+
+impl ContentIndex {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            files: Vec::new(),
+            by_path: HashMap::new(),
+            postings: HashMap::new(),
+            posting_count: 0,
+            removed: 0,
+            oversized: HashSet::new(),
+            truncated: false,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.by_path.len()
+    }
+
+    pub fn trigram_count(&self) -> usize {
+        self.postings.len()
+    }
+
+    /// Approximate memory taken by the postings.
+    pub fn postings_bytes(&self) -> usize {
+        self.posting_count * std::mem::size_of::<u32>()
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Brings the index up to date with the files on disk, reading only
+    /// those whose size or modification time changed. Returns false when
+    /// cancelled part way.
+    pub fn refresh(
+        &mut self,
+        options: &WalkOptions,
+        limits: &IndexLimits,
+        cancel: &AtomicBool,
+        mut progress: impl FnMut(usize),
+    ) -> bool {
+        self.truncated = false;
+        let mut seen = HashSet::new();
+        let root = self.root.clone();
+        for (walked, path) in options.files(&root, None).enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                return false;
+            }
+            if walked % 500 == 0 {
+                progress(walked);
+            }
+            if let Some(relative) = self.relative(&path) {
+                seen.insert(relative.clone());
+                self.index_file(&path, relative, limits);
+            }
+        }
+
+        let gone = self
+            .by_path
+            .keys()
+            .filter(|path| !seen.contains(*path))
+            .cloned()
+            .collect::<Vec<_>>();
+        for path in gone {
+            self.remove_file(&path);
+        }
+        self.oversized.retain(|path| seen.contains(path));
+        self.compact_if_sparse();
+        true
+    }
+
+    /// Re-indexes the files at or below the changed `paths`, as reported by
+    /// the file watcher.
+    pub fn update(&mut self, paths: &[PathBuf], options: &WalkOptions, limits: &IndexLimits) {
+        for path in paths {
+            let Some(relative) = self.relative(path) else {
+                continue;
+            };
+            let below = self
+                .by_path
+                .keys()
+                .filter(|indexed| indexed.starts_with(&relative))
+                .cloned()
+                .collect::<Vec<_>>();
+            for indexed in below {
+                self.remove_file(&indexed);
+            }
+            self.oversized
+                .retain(|oversized| !oversized.starts_with(&relative));
+            if path.exists() {
+                let root = self.root.clone();
+                for file in options.files(&root, Some(path)) {
+                    if let Some(relative) = self.relative(&file) {
+                        self.index_file(&file, relative, limits);
+                    }
+                }
+            }
+        }
+        self.compact_if_sparse();
+    }
+
+    fn relative(&self, path: &Path) -> Option<PathBuf> {
+        path.strip_prefix(&self.root)
+            .ok()
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+    }
+
+    /// Adds the file at `path`, unless the index already has it unchanged
+    /// or it is binary, too large, or past the limits.
+    fn index_file(&mut self, path: &Path, relative: PathBuf, limits: &IndexLimits) {
+        let Ok(metadata) = std::fs::metadata(path) else {
+            self.remove_file(&relative);
+            return;
+        };
+        let len = metadata.len();
+        let modified_ms = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since| since.as_millis() as u64);
+        if let Some(&id) = self.by_path.get(&relative) {
+            let file = &self.files[id as usize];
+            if file.len == len && file.modified_ms == modified_ms {
+                return;
+            }
+            self.remove_file(&relative);
+        }
+
+        self.oversized.remove(&relative);
+        if len > limits.max_file_bytes {
+            self.oversized.insert(relative);
+            return;
+        }
+        if self.by_path.len() >= limits.max_files || self.posting_count >= limits.max_postings {
+            self.truncated = true;
+            return;
+        }
+        let Ok(bytes) = std::fs::read(path) else {
+            return;
+        };
+        if bytes[..bytes.len().min(BINARY_PROBE_BYTES)].contains(&0) {
+            return;
+        }
+        self.add_file(relative, len, modified_ms, &bytes);
+    }
+
+    fn add_file(&mut self, path: PathBuf, len: u64, modified_ms: u64, bytes: &[u8]) {
+        let id = self.files.len() as u32;
+        let trigrams = trigrams(bytes);
+        self.posting_count += trigrams.len();
+        for trigram in trigrams {
+            self.postings.entry(trigram).or_default().push(id);
+        }
+        self.by_path.insert(path.clone(), id);
+        self.files.push(IndexedFile {
+            path,
+            len,
+            modified_ms,
+            live: true,
+        });
+    }
+
+    /// Drops a file, leaving its postings to be compacted away later.
+    fn remove_file(&mut self, path: &Path) {
+        if let Some(id) = self.by_path.remove(path) {
+            self.files[id as usize].live = false;
+            self.removed += 1;
+        }
+    }
+
+    fn compact_if_sparse(&mut self) {
+        if self.removed >= COMPACT_AFTER_REMOVALS && self.removed * 2 >= self.files.len() {
+            self.compact();
+        }
+    }
+
+    /// Renumbers the live files and drops the postings of removed ones.
+    fn compact(&mut self) {
+        let mut new_ids = vec![u32::MAX; self.files.len()];
+        let mut files = Vec::with_capacity(self.by_path.len());
+        for (id, file) in std::mem::take(&mut self.files).into_iter().enumerate() {
+            if file.live {
+                new_ids[id] = files.len() as u32;
+                files.push(file);
+            }
+        }
+
+        self.postings.retain(|_, ids| {
+            ids.retain_mut(|id| {
+                *id = new_ids[*id as usize];
+                *id != u32::MAX
+            });
+            ids.shrink_to_fit();
+            !ids.is_empty()
+        });
+        self.posting_count = self.postings.values().map(Vec::len).sum();
+        self.by_path = files
+            .iter()
+            .enumerate()
+            .map(|(id, file)| (file.path.clone(), id as u32))
+            .collect();
+        self.files = files;
+        self.removed = 0;
+    }
+
+    /// Relative paths of the files containing every one of `trigrams`, and
+    /// of those too large to index, in path order.
+    pub fn candidates(&self, trigrams: &[Trigram]) -> Vec<&Path> {
+        const NONE: &[u32] = &[];
+        let mut lists = trigrams
+            .iter()
+            .map(|trigram| self.postings.get(trigram).map_or(NONE, Vec::as_slice))
+            .collect::<Vec<_>>();
+        lists.sort_by_key(|ids| ids.len());
+        let (first, rest) = lists.split_first().unwrap_or((&NONE, &[]));
+
+        let mut paths = first
+            .iter()
+            .filter(|&&id| rest.iter().all(|ids| ids.binary_search(&id).is_ok()))
+            .map(|&id| &self.files[id as usize])
+            .filter(|file| file.live)
+            .map(|file| file.path.as_path())
+            .chain(self.oversized.iter().map(PathBuf::as_path))
+            .collect::<Vec<_>>();
+        paths.sort_unstable();
+        paths
+    }
+
+    /// Matches of `regex` in the candidate files for `trigrams`, read from
+    /// disk and in path order. Files in `excluded` are skipped.
+    pub fn search(
+        &self,
+        regex: &regex::Regex,
+        trigrams: &[Trigram],
+        excluded: &[PathBuf],
+        limit: usize,
+        cancel: &AtomicBool,
+    ) -> Vec<TextSearchMatch> {
+        let mut matches = Vec::new();
+        for relative_path in self.candidates(trigrams) {
+            if matches.len() >= limit || cancel.load(Ordering::Relaxed) {
+                break;
+            }
+            if excluded.iter().any(|excluded| excluded == relative_path) {
+                continue;
+            }
+            let Ok(bytes) = std::fs::read(self.root.join(relative_path)) else {
+                continue;
+            };
+            let text = String::from_utf8_lossy(&bytes);
+            for (line, line_text) in text.lines().enumerate() {
+                if let Some(found) = regex.find(line_text) {
+                    matches.push(TextSearchMatch {
+                        relative_path: relative_path.to_path_buf(),
+                        line_number: line + 1,
+                        line_text: line_text.trim_end().to_string(),
+                        start: found.start(),
+                        end: found.end(),
+                    });
+                    if matches.len() >= limit {
+                        break;
+                    }
+                }
+            }
+        }
+        matches
+    }
+
+    /// Writes the live files and their postings to `path`.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("failed to create {}", path.display()))?;
+        let mut out = BufWriter::new(file);
+
+        let mut new_ids = vec![u32::MAX; self.files.len()];
+        let live = self
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, file)| file.live)
+            .collect::<Vec<_>>();
+        for (new_id, (id, _)) in live.iter().enumerate() {
+            new_ids[*id] = new_id as u32;
+        }
+
+        out.write_all(STORE_MAGIC)?;
+        write_bytes(&mut out, self.root.to_string_lossy().as_bytes())?;
+        out.write_all(&[u8::from(self.truncated)])?;
+        out.write_all(&(live.len() as u32).to_le_bytes())?;
+        for (_, file) in &live {
+            write_bytes(&mut out, file.path.to_string_lossy().as_bytes())?;
+            out.write_all(&file.len.to_le_bytes())?;
+            out.write_all(&file.modified_ms.to_le_bytes())?;
+        }
+        out.write_all(&(self.oversized.len() as u32).to_le_bytes())?;
+        for path in &self.oversized {
+            write_bytes(&mut out, path.to_string_lossy().as_bytes())?;
+        }
+        out.write_all(&(self.postings.len() as u32).to_le_bytes())?;
+        for (trigram, ids) in &self.postings {
+            let ids = ids
+                .iter()
+                .map(|id| new_ids[*id as usize])
+                .filter(|id| *id != u32::MAX)
+                .collect::<Vec<_>>();
+            out.write_all(&trigram.to_le_bytes())?;
+            out.write_all(&(ids.len() as u32).to_le_bytes())?;
+            for id in ids {
+                out.write_all(&id.to_le_bytes())?;
+            }
+        }
+        out.flush()
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Reads an index saved for `root`. Returns `None` when there is none,
+    /// or it was saved for another root.
+    pub fn load(path: &Path, root: &Path) -> Result<Option<Self>> {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => {
+                return Err(error).with_context(|| format!("failed to read {}", path.display()));
+            }
+        };
+        let mut reader = StoreReader { bytes: &bytes };
+        if reader.take(STORE_MAGIC.len())? != STORE_MAGIC {
+            bail!("{} is not a content index", path.display());
+        }
+        if reader.string()? != root.to_string_lossy() {
+            return Ok(None);
+        }
+
+        let mut index = Self::new(root.to_path_buf());
+        index.truncated = reader.take(1)?[0] != 0;
+        for id in 0..reader.u32()? {
+            let path = PathBuf::from(reader.string()?);
+            index.by_path.insert(path.clone(), id);
+            index.files.push(IndexedFile {
+                path,
+                len: reader.u64()?,
+                modified_ms: reader.u64()?,
+                live: true,
+            });
+        }
+        for _ in 0..reader.u32()? {
+            index.oversized.insert(PathBuf::from(reader.string()?));
+        }
+        for _ in 0..reader.u32()? {
+            let trigram = reader.u32()?;
+            let ids = (0..reader.u32()?)
+                .map(|_| reader.u32())
+                .collect::<Result<Vec<_>>>()?;
+            if ids.iter().any(|id| *id as usize >= index.files.len()) {
+                bail!("{} is corrupt", path.display());
+            }
+            index.posting_count += ids.len();
+            index.postings.insert(trigram, ids);
+        }
+        Ok(Some(index))
+    }
+}
+
+fn write_bytes(out: &mut impl Write, bytes: &[u8]) -> std::io::Result<()> {
+    out.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    out.write_all(bytes)
+}
+
+struct StoreReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> StoreReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            bail!("content index ends early");
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let len = self.u32()? as usize;
+        Ok(String::from_utf8(self.take(len)?.to_vec())?)
+    }
+}
+
+/// Trigrams every match of `pattern` contains, or `None` when the pattern
+/// gives none to narrow the search by.
+pub fn required_trigrams(pattern: &str) -> Option<Vec<Trigram>> {
+    let mut trigrams = required_literals(pattern)?
+        .iter()
+        .flat_map(|literal| {
+            literal
+                .as_bytes()
+                .windows(3)
+                .map(|window| trigram([window[0], window[1], window[2]]))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    (!trigrams.is_empty()).then_some(trigrams)
+}
+
+/// Literal runs every match of `pattern` must contain. Anything the scan
+/// does not model, such as groups and classes, only ends a run; top-level
+/// alternation and verbose mode give up.
+fn required_literals(pattern: &str) -> Option<Vec<String>> {
+    if has_verbose_flag(pattern) {
+        return None;
+    }
+
+    let mut literals = Vec::new();
+    let mut run = String::new();
+    let mut depth = 0usize;
+    let mut chars = pattern.chars().peekable();
+    let end_run = |literals: &mut Vec<String>, run: &mut String| {
+        if run.len() >= 3 {
+            literals.push(std::mem::take(run));
+        } else {
+            run.clear();
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        let literal = match c {
+            '\\' => {
+                let escaped = chars.next()?;
+                if escaped.is_ascii_alphanumeric() {
+                    skip_escape_arguments(escaped, &mut chars);
+                    None
+                } else {
+                    Some(escaped)
+                }
+            }
+            '(' => {
+                depth += 1;
+                None
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                None
+            }
+            '|' if depth == 0 => return None,
+            '[' => {
+                skip_class(&mut chars);
+                None
+            }
+            '.' | '^' | '$' | '|' | '*' | '+' | '?' | '{' | '}' => None,
+            c => Some(c),
+        };
+
+        // Contents of groups may be optional or alternatives.
+        let Some(literal) = literal.filter(|_| depth == 0) else {
+            end_run(&mut literals, &mut run);
+            continue;
+        };
+        // Characters with non-ASCII case variants are matched by bytes the
+        // index does not fold together.
+        if !literal.is_ascii() && literal.to_lowercase().ne(literal.to_uppercase()) {
+            end_run(&mut literals, &mut run);
+            continue;
+        }
+
+        match chars.peek().copied() {
+            Some('*' | '?') => {
+                end_run(&mut literals, &mut run);
+                chars.next();
+            }
+            Some('+') => {
+                run.push(literal);
+                end_run(&mut literals, &mut run);
+                chars.next();
+            }
+            Some('{') => {
+                chars.next();
+                let repetition = chars.by_ref().take_while(|c| *c != '}').collect::<String>();
+                let min = repetition.split(',').next().unwrap_or_default();
+                if min.trim().parse::<usize>().is_ok_and(|min| min > 0) {
+                    run.push(literal);
+                }
+                end_run(&mut literals, &mut run);
+            }
+            _ => run.push(literal),
+        }
+    }
+    end_run(&mut literals, &mut run);
+    Some(literals)
+}
+
+fn has_verbose_flag(pattern: &str) -> bool {
+    pattern.match_indices("(?").any(|(start, _)| {
+        pattern[start + 2..]
+            .chars()
+            .take_while(|c| *c != ')' && *c != ':')
+            .any(|c| c == 'x')
+    })
+}
+
+/// Skips what follows an escape like `\x41`, `\u{e9}` or `\pL`.
+fn skip_escape_arguments(escaped: char, chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    let digits = match escaped {
+        'x' => 2,
+        'u' => 4,
+        'U' => 8,
+        'p' | 'P' => 1,
+        _ => return,
+    };
+    if chars.peek() == Some(&'{') {
+        chars.by_ref().take_while(|c| *c != '}').for_each(drop);
+    } else {
+        chars.by_ref().take(digits).for_each(drop);
+    }
+}
+
+/// Skips a character class whose `[` was just read, including nested ones.
+fn skip_class(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    chars.next_if_eq(&'^');
+    chars.next_if_eq(&']');
+    let mut depth = 1;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The project's index, shared between the workspace and the background
+/// tasks building and updating it.
+#[derive(Debug)]
+pub struct ContentIndexHandle {
+    pub root: PathBuf,
+    pub index: RwLock<ContentIndex>,
+    /// The first build finished, so searches can rely on the index
+    pub ready: AtomicBool,
+    /// Stops a build in progress
+    pub cancel: AtomicBool,
+}
+
+impl ContentIndexHandle {
+    pub fn new(root: PathBuf) -> Arc<Self> {
+        Arc::new(Self {
+            index: RwLock::new(ContentIndex::new(root.clone())),
+            root,
+            ready: AtomicBool::new(false),
+            cancel: AtomicBool::new(false),
+        })
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+}
+
+/// The workspace's current index and the watcher changes waiting for it.
+#[derive(Debug, Default)]
+pub struct ContentIndexState {
+    handle: Option<Arc<ContentIndexHandle>>,
+    /// Changed paths to apply once the index is ready
+    pending: Vec<PathBuf>,
+    /// An update applying `pending` is already scheduled
+    update_scheduled: bool,
+}
+
+impl ContentIndexState {
+    /// Starts a new index of `root`, cancelling the current one.
+    pub fn start(&mut self, root: PathBuf) -> Arc<ContentIndexHandle> {
+        self.stop();
+        let handle = ContentIndexHandle::new(root);
+        self.handle = Some(handle.clone());
+        handle
+    }
+
+    /// Cancels the current index and drops the changes queued for it.
+    pub fn stop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.cancel.store(true, Ordering::Relaxed);
+        }
+        self.pending.clear();
+    }
+
+    pub fn handle(&self) -> Option<&Arc<ContentIndexHandle>> {
+        self.handle.as_ref()
+    }
+
+    /// Whether an index of `root` is built or being built.
+    pub fn covers(&self, root: &Path) -> bool {
+        self.handle
+            .as_ref()
+            .is_some_and(|handle| handle.root == root)
+    }
+
+    /// Whether `handle` is still the current index.
+    pub fn is_current(&self, handle: &Arc<ContentIndexHandle>) -> bool {
+        self.handle
+            .as_ref()
+            .is_some_and(|current| Arc::ptr_eq(current, handle))
+    }
+
+    /// Queues changed paths for the index, dropping them when there is no
+    /// index. Returns whether the caller should schedule an update applying
+    /// them, which is once until [`Self::finish_scheduled_update`].
+    pub fn queue_changes(&mut self, paths: impl IntoIterator<Item = PathBuf>) -> bool {
+        if self.handle.is_none() {
+            return false;
+        }
+        self.pending.extend(paths);
+        !std::mem::replace(&mut self.update_scheduled, true)
+    }
+
+    /// Marks the scheduled update as run, so later changes schedule another.
+    pub fn finish_scheduled_update(&mut self) {
+        self.update_scheduled = false;
+    }
+
+    /// The queued changes, sorted and without duplicates, once the index is
+    /// ready to apply them.
+    pub fn take_pending(&mut self) -> Option<(Arc<ContentIndexHandle>, Vec<PathBuf>)> {
+        let handle = self.handle.clone().filter(|handle| handle.is_ready())?;
+        if self.pending.is_empty() {
+            return None;
+        }
+        let mut paths = std::mem::take(&mut self.pending);
+        paths.sort_unstable();
+        paths.dedup();
+        Some((handle, paths))
+    }
+
+    /// The index of `root`, once it is built and covers every file a search
+    /// would read.
+    pub fn ready_for(&self, root: &Path) -> Option<Arc<ContentIndexHandle>> {
+        self.handle.clone().filter(|handle| {
+            handle.root == root
+                && handle.is_ready()
+                && handle.index.read().is_ok_and(|index| !index.is_truncated())
+        })
+    }
+}
+
+/// Where the index of `root` is kept between sessions.
+pub fn store_path(root: &Path) -> PathBuf {
+    // FNV-1a, so the name stays the same across builds.
+    let hash = root
+        .to_string_lossy()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    dirs::data_local_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("nucleotide")
+        .join("content_index")
+        .join(format!("{hash:016x}.idx"))
+}
+
+/// Status line for the index.
+pub fn status_label(index: &ContentIndex, ready: bool) -> String {
+    if !ready {
+        return format!("Indexing project contents ({} files)", index.file_count());
+    }
+    let partial = if index.is_truncated() {
+        "; limits reached, the rest is searched on disk"
+    } else {
+        ""
+    };
+    format!(
+        "Content index: {} files, {} trigrams, {:.1} MB{partial}",
+        index.file_count(),
+        index.trigram_count(),
+        index.postings_bytes() as f64 / (1024.0 * 1024.0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> IndexLimits {
+        IndexLimits {
+            max_file_bytes: 1024 * 1024,
+            max_files: 1000,
+            max_postings: 1_000_000,
+        }
+    }
+
+    fn options() -> WalkOptions {
+        WalkOptions {
+            hidden: true,
+            parents: false,
+            ignore: true,
+            git_ignore: true,
+            git_global: false,
+            git_exclude: false,
+            follow_links: false,
+            max_depth: None,
+            ignore_file: None,
+        }
+    }
+
+    #[test]
+    fn only_required_literals_narrow_the_search() {
+        let literals = |pattern| required_literals(pattern);
+        assert_eq!(literals("fn main"), Some(vec!["fn main".into()]));
+        assert_eq!(
+            literals(r"\bparse_\w+\(config\)"),
+            Some(vec!["parse_".into(), "(config)".into()])
+        );
+        assert_eq!(
+            literals("colou?r[s]? handler+ (a|b)xyz"),
+            Some(vec!["colo".into(), " handler".into(), "xyz".into()])
+        );
+        assert_eq!(literals("ab{0,2}cde"), Some(vec!["cde".into()]));
+        assert_eq!(literals("foo|bar"), None);
+        assert_eq!(literals("(?x) foo bar"), None);
+        assert_eq!(required_trigrams("a.b"), None);
+        assert_eq!(
+            required_trigrams("AbCd"),
+            Some(trigrams(b"abcd")),
+            "query trigrams are case folded like the files'"
+        );
+    }
+
+    #[test]
+    fn queued_changes_wait_for_the_index_to_be_ready() {
+        let mut state = ContentIndexState::default();
+        assert!(!state.queue_changes([PathBuf::from("/project/a.rs")]));
+
+        let first = state.start(PathBuf::from("/project"));
+        assert!(state.covers(Path::new("/project")) && state.is_current(&first));
+        assert!(state.queue_changes([PathBuf::from("/project/b.rs")]));
+        assert!(!state.queue_changes([PathBuf::from("/project/b.rs")]));
+        assert!(state.take_pending().is_none());
+
+        let second = state.start(PathBuf::from("/project"));
+        assert!(first.cancel.load(Ordering::Relaxed) && !state.is_current(&first));
+        second.ready.store(true, Ordering::Release);
+        assert!(state.take_pending().is_none());
+
+        state.finish_scheduled_update();
+        assert!(
+            state.queue_changes(
+                ["/project/c.rs", "/project/b.rs", "/project/c.rs"].map(PathBuf::from)
+            )
+        );
+        let (handle, paths) = state.take_pending().unwrap();
+        assert!(Arc::ptr_eq(&handle, &second));
+        assert_eq!(paths, ["/project/b.rs", "/project/c.rs"].map(PathBuf::from));
+        assert!(state.ready_for(Path::new("/project")).is_some());
+        assert!(state.ready_for(Path::new("/other")).is_none());
+
+        state.stop();
+        assert!(second.cancel.load(Ordering::Relaxed) && state.handle().is_none());
+    }
+
+    #[test]
+    fn index_finds_candidates_and_follows_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn parse_config() {}\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("target/out.rs"), "fn parse_config() {}\n").unwrap();
+        std::fs::write(root.join("blob.bin"), b"parse_config\0").unwrap();
+
+        let mut index = ContentIndex::new(root.to_path_buf());
+        assert!(index.refresh(&options(), &limits(), &AtomicBool::new(false), |_| {}));
+        let query = required_trigrams("Parse_Config").unwrap();
+        assert_eq!(index.candidates(&query), [Path::new("src/lib.rs")]);
+
+        let regex = crate::live_grep::line_regex("parse_config", true).unwrap();
+        let matches = index.search(&regex, &query, &[], 10, &AtomicBool::new(false));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line_number, 1);
+        assert_eq!(matches[0].start, 7);
+
+        std::fs::write(root.join("src/main.rs"), "fn main() { parse_config() }\n").unwrap();
+        std::fs::remove_file(root.join("src/lib.rs")).unwrap();
+        index.update(
+            &[root.join("src/main.rs"), root.join("src/lib.rs")],
+            &options(),
+            &limits(),
+        );
+        assert_eq!(index.candidates(&query), [Path::new("src/main.rs")]);
+
+        let store = root.join("index.idx");
+        index.save(&store).unwrap();
+        let loaded = ContentIndex::load(&store, root).unwrap().unwrap();
+        assert_eq!(loaded.file_count(), 1);
+        assert_eq!(loaded.candidates(&query), [Path::new("src/main.rs")]);
+        assert!(
+            ContentIndex::load(&store, Path::new("/elsewhere"))
+                .unwrap()
+                .is_none()
+        );
+
+        // Files too large to index are searched whatever the query.
+        let small = IndexLimits {
+            max_file_bytes: 8,
+            ..limits()
+        };
+        index.update(&[root.join("src/main.rs")], &options(), &small);
+        let unrelated = required_trigrams("nowhere").unwrap();
+        assert_eq!(index.candidates(&unrelated), [Path::new("src/main.rs")]);
+    }
+}
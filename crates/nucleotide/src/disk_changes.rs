@@ -0,0 +1,172 @@
+// ABOUTME: Handling of open files that change on disk: change detection and the conflict prompt
+// ABOUTME: Renders the disk-versus-buffer comparison as a unified diff
+
+use std::sync::atomic::AtomicBool;
+use std::time::SystemTime;
+
+use helix_view::DocumentId;
+use nucleotide_vcs::incremental_diff::diff_lines;
+use nucleotide_vcs::{DiffChangeType, DiffHunkInfo};
+
+/// Unchanged lines shown around each change in a comparison.
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskChangeAction {
+    Reload,
+    KeepMine,
+    Compare,
+}
+
+/// Picker payload for a buffer with unsaved edits whose file changed on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskChangeChoice {
+    pub doc_id: DocumentId,
+    pub action: DiskChangeAction,
+}
+
+/// Whether the file read after a watcher event differs from the version the
+/// buffer was loaded from or last saved as. Our own saves also produce
+/// watcher events; those leave the file at the buffer's saved version.
+pub fn changed_since_load(
+    loaded_version: Option<&[u8]>,
+    last_saved_time: SystemTime,
+    disk_version: Option<&[u8]>,
+    disk_modified: Option<SystemTime>,
+) -> bool {
+    if let (Some(loaded), Some(disk)) = (loaded_version, disk_version) {
+        return loaded != disk;
+    }
+    disk_modified.is_none_or(|modified| modified > last_saved_time)
+}
+
+/// Unified diff from the file on disk to the buffer.
+pub fn unified_diff(label: &str, disk: &str, buffer: &str) -> String {
+    let old: Vec<&str> = disk.lines().collect();
+    let new: Vec<&str> = buffer.lines().collect();
+    let hunks = diff_lines(&old, &new, &AtomicBool::new(false)).unwrap_or_else(|_| {
+        // Too large to diff line by line; show one replacement.
+        vec![DiffHunkInfo::new(
+            0,
+            new.len() as u32,
+            0,
+            old.len() as u32,
+            DiffChangeType::Modification,
+        )]
+    });
+
+    let mut diff = format!("--- {label} (on disk)\n+++ {label} (buffer)\n");
+    let mut group_start = 0;
+    while group_start < hunks.len() {
+        let mut group_end = group_start + 1;
+        while group_end < hunks.len()
+            && hunks[group_end].before_start as usize
+                <= hunks[group_end - 1].before_end as usize + 2 * CONTEXT_LINES
+        {
+            group_end += 1;
+        }
+        let group = &hunks[group_start..group_end];
+        let (first, last) = (&group[0], &group[group.len() - 1]);
+
+        let previous_end = group_start
+            .checked_sub(1)
+            .map_or(0, |index| hunks[index].before_end as usize);
+        let next_start = hunks
+            .get(group_end)
+            .map_or(old.len(), |hunk| hunk.before_start as usize);
+        let leading = CONTEXT_LINES.min(first.before_start as usize - previous_end);
+        let trailing = CONTEXT_LINES.min(next_start - last.before_end as usize);
+
+        let old_start = first.before_start as usize - leading;
+        let old_end = last.before_end as usize + trailing;
+        let new_start = first.after_start as usize - leading;
+        let new_end = last.after_end as usize + trailing;
+        diff.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_end),
+            hunk_range(new_start, new_end)
+        ));
+
+        let mut line = old_start;
+        for hunk in group {
+            for context in &old[line..hunk.before_start as usize] {
+                diff.push_str(&format!(" {context}\n"));
+            }
+            for removed in &old[hunk.before_start as usize..hunk.before_end as usize] {
+                diff.push_str(&format!("-{removed}\n"));
+            }
+            for added in &new[hunk.after_start as usize..hunk.after_end as usize] {
+                diff.push_str(&format!("+{added}\n"));
+            }
+            line = hunk.before_end as usize;
+        }
+        for context in &old[line..old_end] {
+            diff.push_str(&format!(" {context}\n"));
+        }
+
+        group_start = group_end;
+    }
+    diff
+}
+
+/// `start,count` with a 1-based start, or the preceding line for an empty range.
+fn hunk_range(start: usize, end: usize) -> String {
+    match end - start {
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        count => format!("{},{count}", start + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn own_saves_are_not_external_changes() {
+        let saved = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+        let later = saved + Duration::from_secs(1);
+
+        assert!(!changed_since_load(
+            Some(b"v1"),
+            saved,
+            Some(b"v1"),
+            Some(later)
+        ));
+        assert!(changed_since_load(
+            Some(b"v1"),
+            saved,
+            Some(b"v2"),
+            Some(saved)
+        ));
+        assert!(!changed_since_load(None, saved, Some(b"v2"), Some(saved)));
+        assert!(changed_since_load(None, saved, None, Some(later)));
+        assert!(changed_since_load(None, saved, None, None));
+    }
+
+    #[test]
+    fn unified_diff_groups_nearby_changes_with_context() {
+        let disk = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn\n";
+        let buffer = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\nm\nn\nextra\n";
+
+        assert_eq!(
+            unified_diff("src/lib.rs", disk, buffer),
+            "--- src/lib.rs (on disk)\n+++ src/lib.rs (buffer)\n\
+             @@ -1,5 +1,5 @@\n a\n-b\n+B\n c\n d\n e\n\
+             @@ -12,3 +12,4 @@\n l\n m\n n\n+extra\n"
+        );
+    }
+
+    #[test]
+    fn unified_diff_of_identical_text_has_no_hunks() {
+        assert_eq!(
+            unified_diff("a.txt", "same\n", "same\n"),
+            "--- a.txt (on disk)\n+++ a.txt (buffer)\n"
+        );
+        assert_eq!(
+            unified_diff("a.txt", "", "new\n"),
+            "--- a.txt (on disk)\n+++ a.txt (buffer)\n@@ -0,0 +1 @@\n+new\n"
+        );
+    }
+}
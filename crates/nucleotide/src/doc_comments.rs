@@ -0,0 +1,333 @@
+// ABOUTME: Generates doc comment skeletons for the function at the cursor from its syntax tree
+// ABOUTME: Builds a snippet in the language's doc style with tabstops for the summary, params and return
+
+use std::ops::Range;
+
+use helix_core::Rope;
+use helix_view::Document;
+
+/// Prompt command inserting a doc comment above the function at the cursor.
+pub const DOC_COMMENT_COMMAND: &str = "generate-doc-comment";
+/// Prompt commands for doc comments, with completion descriptions.
+pub const DOC_COMMENT_COMMANDS: &[(&str, &str)] = &[(
+    DOC_COMMENT_COMMAND,
+    "Insert a doc comment skeleton for the function at the cursor",
+)];
+
+/// Syntax node kinds of function and method definitions across grammars.
+const FUNCTION_KINDS: &[&str] = &[
+    "function_item",
+    "function_signature_item",
+    "function_declaration",
+    "function_definition",
+    "generator_function_declaration",
+    "method_definition",
+    "method_declaration",
+    "method_signature",
+    "abstract_method_signature",
+    "constructor_declaration",
+];
+/// Function expressions, documented only when assigned to a variable.
+const FUNCTION_EXPRESSION_KINDS: &[&str] = &["arrow_function", "function_expression"];
+/// Fields naming the thing a parameter node declares, tried in order.
+const PARAMETER_NAME_FIELDS: &[&str] = &["pattern", "name", "declarator", "left"];
+/// Fields holding a function's return type across grammars.
+const RETURN_TYPE_FIELDS: &[&str] = &["return_type", "result", "returns", "type"];
+
+/// How a language writes doc comments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocStyle {
+    /// `///` lines with Markdown sections
+    RustDoc,
+    /// A `/** ... */` block with `@param` and `@returns` tags
+    JsDoc,
+    /// A Python docstring in Google style, as the first statement of the body
+    Docstring,
+    /// Lines starting with the language's comment token
+    Line(String),
+}
+
+impl DocStyle {
+    /// Doc style of a language, falling back to its line comment token.
+    pub fn for_language(language: &str, comment_token: Option<&str>) -> Option<Self> {
+        match language {
+            "rust" => Some(DocStyle::RustDoc),
+            "javascript" | "jsx" | "typescript" | "tsx" | "java" | "kotlin" | "php" | "c"
+            | "cpp" | "c-sharp" | "scala" => Some(DocStyle::JsDoc),
+            "python" => Some(DocStyle::Docstring),
+            _ => comment_token
+                .filter(|token| !token.is_empty())
+                .map(|token| DocStyle::Line(token.to_string())),
+        }
+    }
+}
+
+/// What a doc comment describes about a function.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FunctionSignature {
+    pub params: Vec<String>,
+    pub returns: bool,
+}
+
+/// `text` with the characters the snippet syntax treats specially escaped.
+fn escape_snippet(text: &str) -> String {
+    text.chars()
+        .flat_map(|ch| match ch {
+            '$' | '}' | '\\' => vec!['\\', ch],
+            _ => vec![ch],
+        })
+        .collect()
+}
+
+/// Snippet of a doc comment skeleton for `signature` in `style`, ending in a
+/// newline. The summary is the first tabstop, followed by one per parameter
+/// and one for the return value.
+pub fn doc_snippet(style: &DocStyle, signature: &FunctionSignature) -> String {
+    let mut tabstop = 1;
+    let mut next_tabstop = || {
+        tabstop += 1;
+        format!("${tabstop}")
+    };
+    let params = signature
+        .params
+        .iter()
+        .map(|param| (escape_snippet(param), next_tabstop()))
+        .collect::<Vec<_>>();
+    let returns = signature.returns.then(next_tabstop);
+
+    let mut lines = vec!["${1:Summary}".to_string()];
+    match style {
+        DocStyle::RustDoc => {
+            if !params.is_empty() {
+                lines.extend([String::new(), "# Arguments".into(), String::new()]);
+                lines.extend(
+                    params
+                        .iter()
+                        .map(|(name, stop)| format!("* `{name}` - {stop}")),
+                );
+            }
+            if let Some(stop) = returns {
+                lines.extend([String::new(), "# Returns".into(), String::new(), stop]);
+            }
+        }
+        DocStyle::Docstring => {
+            if !params.is_empty() {
+                lines.extend([String::new(), "Args:".into()]);
+                lines.extend(
+                    params
+                        .iter()
+                        .map(|(name, stop)| format!("    {name}: {stop}")),
+                );
+            }
+            if let Some(stop) = returns {
+                lines.extend([String::new(), "Returns:".into(), format!("    {stop}")]);
+            }
+        }
+        DocStyle::JsDoc | DocStyle::Line(_) => {
+            if !params.is_empty() || returns.is_some() {
+                lines.push(String::new());
+            }
+            lines.extend(
+                params
+                    .iter()
+                    .map(|(name, stop)| format!("@param {name} {stop}")),
+            );
+            lines.extend(returns.map(|stop| format!("@returns {stop}")));
+        }
+    }
+
+    let prefixed = |prefix: &str, lines: &[String]| {
+        lines
+            .iter()
+            .map(|line| {
+                if line.is_empty() {
+                    format!("{}\n", prefix.trim_end())
+                } else {
+                    format!("{prefix}{line}\n")
+                }
+            })
+            .collect::<String>()
+    };
+    match style {
+        DocStyle::RustDoc => prefixed("/// ", &lines),
+        DocStyle::JsDoc => format!("/**\n{} */\n", prefixed(" * ", &lines)),
+        DocStyle::Docstring => {
+            let body = lines
+                .iter()
+                .map(|line| format!("{line}\n"))
+                .collect::<String>();
+            format!("\"\"\"{body}\"\"\"\n")
+        }
+        DocStyle::Line(token) => prefixed(&format!("{} ", escape_snippet(token)), &lines),
+    }
+}
+
+/// Char range of a syntax node's byte range.
+fn char_range(text: &Rope, bytes: Range<u32>) -> Range<usize> {
+    text.byte_to_char(bytes.start as usize)..text.byte_to_char(bytes.end as usize)
+}
+
+/// Where to insert the doc comment for the function at `cursor`, and the
+/// snippet to insert there.
+pub fn doc_comment_at(doc: &Document, cursor: usize) -> Result<(usize, String), String> {
+    let language = doc.language_name().unwrap_or("text");
+    let comment_token = doc
+        .language_config()
+        .and_then(|config| config.comment_tokens.as_ref())
+        .and_then(|tokens| tokens.first())
+        .map(String::as_str);
+    let style = DocStyle::for_language(language, comment_token)
+        .ok_or_else(|| format!("Doc comments are not supported for {language}"))?;
+    let syntax = doc
+        .syntax()
+        .ok_or_else(|| "No syntax tree for this document".to_string())?;
+
+    // A cursor in a line's indentation belongs to the line's first node.
+    let text = doc.text();
+    let line_start = text.line_to_char(text.char_to_line(cursor));
+    let indent = text
+        .slice(line_start..)
+        .chars()
+        .take_while(|ch| *ch == ' ' || *ch == '\t')
+        .count();
+    let byte = text.char_to_byte(cursor.max(line_start + indent)) as u32;
+
+    let no_function = || "No function at the cursor".to_string();
+    let mut node = syntax
+        .named_descendant_for_byte_range(byte, byte)
+        .ok_or_else(no_function)?;
+    loop {
+        let kind = node.kind();
+        let assigned = FUNCTION_EXPRESSION_KINDS.contains(&kind)
+            && node
+                .parent()
+                .is_some_and(|parent| parent.kind() == "variable_declarator");
+        if FUNCTION_KINDS.contains(&kind) || assigned {
+            break;
+        }
+        node = node.parent().ok_or_else(no_function)?;
+    }
+
+    // C declares its parameters in a (possibly nested) declarator.
+    let mut holder = Some(node);
+    let mut parameters = None;
+    while let Some(current) = holder {
+        parameters = current
+            .child_by_field_name("parameters")
+            .or_else(|| current.child_by_field_name("parameter"));
+        if parameters.is_some() {
+            break;
+        }
+        holder = current.child_by_field_name("declarator");
+    }
+    let params = parameters
+        .map(|parameters| match parameters.named_child_count() {
+            // A lone arrow function parameter is the name itself.
+            0 => vec![parameters],
+            count => (0..count)
+                .filter_map(|index| parameters.named_child(index))
+                .collect(),
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|mut param| {
+            loop {
+                match param.kind() {
+                    "identifier" | "simple_identifier" | "variable_name" => {
+                        let name = text.slice(char_range(text, param.byte_range())).to_string();
+                        break (!matches!(name.as_str(), "self" | "cls" | "this")).then_some(name);
+                    }
+                    "self_parameter" | "this" | "comment" => break None,
+                    _ => {}
+                }
+                param = PARAMETER_NAME_FIELDS
+                    .iter()
+                    .find_map(|field| param.child_by_field_name(field))
+                    .or_else(|| param.named_child(0))?;
+            }
+        })
+        .collect::<Vec<_>>();
+    let returns = RETURN_TYPE_FIELDS
+        .iter()
+        .find_map(|field| node.child_by_field_name(field))
+        .is_some_and(|ty| {
+            let ty = text.slice(char_range(text, ty.byte_range())).to_string();
+            !matches!(ty.trim(), "void" | "None" | "()" | "Unit")
+        });
+    let snippet = doc_snippet(&style, &FunctionSignature { params, returns });
+
+    let function = char_range(text, node.byte_range());
+    let insert_at = match style {
+        // Docstrings open the body, which has to start on a line of its own.
+        DocStyle::Docstring => {
+            let body = node
+                .child_by_field_name("body")
+                .map(|body| char_range(text, body.byte_range()))
+                .ok_or_else(no_function)?;
+            if text.char_to_line(body.start) == text.char_to_line(function.start) {
+                return Err("The function body must start on its own line".to_string());
+            }
+            body.start
+        }
+        _ => {
+            let line_start = text.line_to_char(text.char_to_line(function.start));
+            line_start
+                + text
+                    .slice(line_start..)
+                    .chars()
+                    .take_while(|ch| *ch == ' ' || *ch == '\t')
+                    .count()
+        }
+    };
+    Ok((insert_at, snippet))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signature(params: &[&str], returns: bool) -> FunctionSignature {
+        FunctionSignature {
+            params: params.iter().map(|param| param.to_string()).collect(),
+            returns,
+        }
+    }
+
+    #[test]
+    fn skeletons_follow_the_language_style() {
+        assert_eq!(
+            doc_snippet(&DocStyle::RustDoc, &signature(&["path"], true)),
+            "/// ${1:Summary}\n///\n/// # Arguments\n///\n/// * `path` - $2\n///\n\
+             /// # Returns\n///\n/// $3\n"
+        );
+        assert_eq!(
+            doc_snippet(&DocStyle::JsDoc, &signature(&["$a", "b"], false)),
+            "/**\n * ${1:Summary}\n *\n * @param \\$a $2\n * @param b $3\n */\n"
+        );
+        assert_eq!(
+            doc_snippet(&DocStyle::Docstring, &signature(&["x"], true)),
+            "\"\"\"${1:Summary}\n\nArgs:\n    x: $2\n\nReturns:\n    $3\n\"\"\"\n"
+        );
+        assert_eq!(
+            doc_snippet(&DocStyle::Line("#".into()), &signature(&[], false)),
+            "# ${1:Summary}\n"
+        );
+    }
+
+    #[test]
+    fn languages_pick_a_doc_style() {
+        assert_eq!(
+            DocStyle::for_language("rust", Some("//")),
+            Some(DocStyle::RustDoc)
+        );
+        assert_eq!(
+            DocStyle::for_language("typescript", Some("//")),
+            Some(DocStyle::JsDoc)
+        );
+        assert_eq!(
+            DocStyle::for_language("go", Some("//")),
+            Some(DocStyle::Line("//".into()))
+        );
+        assert_eq!(DocStyle::for_language("text", None), None);
+    }
+}
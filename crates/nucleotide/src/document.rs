@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 use gpui::prelude::FluentBuilder;
 use gpui::{
@@ -11,6 +12,7 @@ use gpui::{
 use helix_view::{DocumentId, ViewId};
 use nucleotide_events::v2::run::ResolvedTask;
 use nucleotide_types::scrollbar::SCROLLBAR_THICKNESS;
+use nucleotide_types::{CursorConfig, MouseSelectionConfig};
 use nucleotide_ui::ThemedContext as UIThemedContext;
 use nucleotide_ui::scrollbar::{Scrollbar, ScrollbarState};
 use nucleotide_ui::theme_manager::HelixThemedContext;
@@ -21,11 +23,12 @@ use nucleotide_workspace::{WorkspaceBackendHandle, WorkspaceIdentity};
 
 use crate::{Core, Input, InputEvent};
 use nucleotide_editor::{
-    DiagnosticSeverityIconColors, EDITOR_MINIMUM_VIEWPORT_COLUMNS, EditorCursorReveal,
-    EditorLayout, EditorPointerSelectionPhase, EditorSurfacePointerEvent, EditorViewLayoutSnapshot,
-    EditorViewState, NativeEditorFramePalette, NativeEditorFrameRenderParams,
-    NativeEditorFrameThemeStyles, NativeEditorView, ViewportScrollUpdate,
-    log_pointer_selection_outcome, render_native_editor_frame, run_gutter_extra_columns,
+    CursorActivityKey, DiagnosticSeverityIconColors, EDITOR_MINIMUM_VIEWPORT_COLUMNS,
+    EditorCursorBlink, EditorCursorReveal, EditorLayout, EditorPointerSelectionPhase,
+    EditorSurfacePointerEvent, EditorViewLayoutSnapshot, EditorViewState, NativeEditorFramePalette,
+    NativeEditorFrameRenderParams, NativeEditorFrameThemeStyles, NativeEditorView,
+    ViewportScrollUpdate, log_pointer_selection_outcome, render_native_editor_frame,
+    run_gutter_extra_columns,
 };
 
 fn handle_editor_pointer_selection(
@@ -36,6 +39,12 @@ fn handle_editor_pointer_selection(
     event: EditorSurfacePointerEvent,
     cx: &mut App,
 ) -> bool {
+    if phase == EditorPointerSelectionPhase::Begin
+        && let Some(config) = cx.try_global::<MouseSelectionConfig>()
+    {
+        editor_state.set_mouse_selection_config(config.clone());
+    }
+
     let outcome = core.update(cx, |core, cx| {
         let outcome = editor_state.handle_pointer_selection_for_view_outcome(
             &mut core.editor,
@@ -79,6 +88,29 @@ fn request_file_picker(core: &Entity<Core>, cx: &mut App) {
     core.update(cx, |_core, cx| cx.emit(crate::Update::ShowFilePicker));
 }
 
+/// Toggles the `index`th task checkbox of the rendered Markdown document.
+fn toggle_rendered_task(core: &Entity<Core>, view_id: ViewId, index: usize, cx: &mut App) {
+    core.update(cx, |core, cx| {
+        let Some(doc_id) = core.editor.tree.try_get(view_id).map(|view| view.doc) else {
+            return;
+        };
+        let Some(doc) = core.editor.documents.get(&doc_id) else {
+            return;
+        };
+        let text = doc.text();
+        let Some(transaction) = nucleotide_ui::markdown::task_marker_lines(&text.to_string())
+            .get(index)
+            .and_then(|&line| crate::task_list::toggle_task_transaction(text, line))
+        else {
+            return;
+        };
+
+        crate::task_list::apply_task_edit(&mut core.editor, doc_id, view_id, &transaction);
+        cx.emit(crate::Update::Redraw);
+        cx.notify();
+    });
+}
+
 fn should_show_empty_scratch_state(has_path: bool, text_len: usize) -> bool {
     !has_path && text_len == 0
 }
@@ -107,6 +139,9 @@ pub struct DocumentView {
     focus: FocusHandle,
     is_focused: bool,
     editor_state: EditorViewState,
+    cursor_blink: EditorCursorBlink,
+    /// Re-renders when the blinking cursor next appears or disappears
+    cursor_blink_task: Option<gpui::Task<()>>,
     markdown_modes: BTreeMap<DocumentId, MarkdownDisplayMode>,
     markdown_scroll_handle: gpui::ScrollHandle,
     markdown_scrollbar_state: ScrollbarState,
@@ -138,6 +173,8 @@ impl DocumentView {
             focus: focus.clone(),
             is_focused,
             editor_state,
+            cursor_blink: EditorCursorBlink::new(Instant::now()),
+            cursor_blink_task: None,
             markdown_modes: BTreeMap::new(),
             markdown_scroll_handle,
             markdown_scrollbar_state,
@@ -150,9 +187,34 @@ impl DocumentView {
     pub fn set_focused(&mut self, is_focused: bool) -> bool {
         let changed = self.is_focused != is_focused;
         self.is_focused = is_focused;
+        if changed && is_focused {
+            self.cursor_blink.reset(Instant::now());
+        }
         changed
     }
 
+    /// Whether the cursor is drawn this frame. Schedules the re-render for
+    /// the next blink while the view has focus.
+    fn update_cursor_blink(&mut self, cx: &mut Context<Self>) -> bool {
+        let config = cx.try_global::<CursorConfig>().copied().unwrap_or_default();
+        if !self.is_focused {
+            self.cursor_blink_task = None;
+            return !config.hide_inactive;
+        }
+
+        let now = Instant::now();
+        let key = CursorActivityKey::for_view(&self.core.read(cx).editor, self.view_id);
+        self.cursor_blink.observe(key, now);
+        let idle = self.cursor_blink.idle(now);
+        self.cursor_blink_task = config.next_blink_toggle(idle).map(|delay| {
+            cx.spawn(async move |this, cx| {
+                cx.background_executor().timer(delay).await;
+                this.update(cx, |_, cx| cx.notify()).ok();
+            })
+        });
+        config.blink_visible(idle)
+    }
+
     pub fn update_text_style(&mut self, style: TextStyle) -> bool {
         if self.style == style {
             return false;
@@ -532,7 +594,11 @@ impl EventEmitter<DismissEvent> for DocumentView {}
 
 impl Render for DocumentView {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // Keys Helix has executed are now on their way to the screen.
+        cx.default_global::<nucleotide_ui::InputLatencyTracker>()
+            .record_render(Instant::now());
         let runnable_tasks_by_line = self.runnable_tasks_by_line(cx);
+        let cursor_visible = self.update_cursor_blink(cx);
         let layout_snapshot = self.editor_state.layout_snapshot();
         let desired_gutter_extra_columns = if runnable_tasks_by_line.is_empty() {
             0
@@ -583,6 +649,7 @@ impl Render for DocumentView {
                         style: &style,
                         focus: &paint_focus,
                         is_focused,
+                        cursor_visible,
                         editor_state,
                         bounds,
                         layout: after_layout,
@@ -739,6 +806,17 @@ impl Render for DocumentView {
             .relative()
             .flex()
             .flex_col()
+            .on_mouse_down(MouseButton::Right, {
+                let core = self.core.clone();
+                let view_id = self.view_id;
+                let focus = self.focus.clone();
+                move |_event, window, cx| {
+                    focus_editor_view(&core, view_id, cx);
+                    window.focus(&focus, cx);
+                    focus.dispatch_action(&crate::actions::editor::OpenContextMenu, window, cx);
+                    cx.stop_propagation();
+                }
+            })
             .child(content)
             .when_some(empty_scratch_state, gpui::ParentElement::child)
             .when_some(controls, gpui::ParentElement::child)
@@ -807,8 +885,17 @@ impl DocumentView {
         markdown_style.code_font_family = SharedString::from(editor_font.family.clone());
         let focus = self.focus.clone();
         let click_focus = focus.clone();
+        let toggle_focus = focus.clone();
         let core = self.core.clone();
+        let toggle_core = self.core.clone();
         let view_id = self.view_id;
+        let markdown = markdown_extended(snapshot.source.clone(), markdown_style).on_task_toggle(
+            move |index, window, cx| {
+                focus_editor_view(&toggle_core, view_id, cx);
+                window.focus(&toggle_focus, cx);
+                toggle_rendered_task(&toggle_core, view_id, index, cx);
+            },
+        );
         let scroll_content = div()
             .id(SharedString::from(format!(
                 "markdown-rendered-content-{:?}",
@@ -826,7 +913,7 @@ impl DocumentView {
             })
             .px(tokens.sizes.space_8)
             .py(tokens.sizes.space_8)
-            .child(markdown_extended(snapshot.source.clone(), markdown_style));
+            .child(markdown);
 
         div()
             .id(SharedString::from(format!(
@@ -986,6 +1073,7 @@ struct DocumentPaintParams<'a> {
     style: &'a TextStyle,
     focus: &'a FocusHandle,
     is_focused: bool,
+    cursor_visible: bool,
     editor_state: &'a mut EditorViewState,
     bounds: Bounds<Pixels>,
     layout: &'a mut EditorLayout,
@@ -1002,6 +1090,7 @@ fn paint_document_content(
         style,
         focus,
         is_focused,
+        cursor_visible,
         editor_state,
         bounds,
         layout,
@@ -1029,6 +1118,7 @@ fn paint_document_content(
                 text_style: style,
                 font_size: style.font_size.to_pixels(px(16.0)),
                 is_focused,
+                cursor_visible,
                 focus,
                 soft_wrap_minimum_columns: EDITOR_MINIMUM_VIEWPORT_COLUMNS,
                 theme_styles,
@@ -1198,6 +1288,7 @@ mod tests {
                     column: 0,
                 }),
                 tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                description: None,
             },
             command,
         }
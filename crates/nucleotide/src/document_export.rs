@@ -0,0 +1,738 @@
+// ABOUTME: Renders a whole syntax-highlighted document as printable HTML or PDF
+// ABOUTME: Paginates lines for the configured page size and reports progress while rendering
+
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use crate::snippet_export::{ExportColor, ExportLine, SnippetExport, escape_markup, expand_tabs};
+
+/// Page margin on every side, in points.
+const PDF_MARGIN: f32 = 42.0;
+/// Courier glyphs advance 600/1000 of the font size.
+const PDF_CHAR_ADVANCE: f32 = 0.6;
+const PDF_LINE_SPACING: f32 = 1.3;
+const PDF_FONTS: [&str; 4] = [
+    "Courier",
+    "Courier-Bold",
+    "Courier-Oblique",
+    "Courier-BoldOblique",
+];
+/// Configured families PDF output can honour with the fonts above.
+const PDF_FONT_FAMILIES: [&str; 3] = ["courier", "courier new", "monospace"];
+
+/// Something a document export had to print differently than asked.
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+pub enum DocumentExportWarning {
+    #[error("PDF exports can't embed \"{0}\", so this one uses Courier")]
+    PdfFontFallback(String),
+    #[error(
+        "Courier can't print {count} character(s) in this document, such as {example:?}; they print as \"?\""
+    )]
+    UnprintablePdfText { count: usize, example: char },
+}
+
+/// A rendered document and what it had to print differently.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentExport {
+    pub contents: Vec<u8>,
+    pub warnings: Vec<DocumentExportWarning>,
+}
+
+/// Paper size for PDF output and the HTML `@page` rule.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PageSize {
+    #[default]
+    A4,
+    Letter,
+    Legal,
+}
+
+impl PageSize {
+    /// Width and height in points.
+    pub fn dimensions(self) -> (f32, f32) {
+        match self {
+            Self::A4 => (595.28, 841.89),
+            Self::Letter => (612.0, 792.0),
+            Self::Legal => (612.0, 1008.0),
+        }
+    }
+
+    fn css_name(self) -> &'static str {
+        match self {
+            Self::A4 => "A4",
+            Self::Letter => "letter",
+            Self::Legal => "legal",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentExportFormat {
+    Html,
+    Pdf,
+}
+
+impl DocumentExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Pdf => "pdf",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Html => "HTML",
+            Self::Pdf => "PDF",
+        }
+    }
+}
+
+/// Layout settings for document exports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentExportOptions {
+    /// CSS font family for HTML.
+    pub font_family: String,
+    /// Font family for PDF. Fonts are not embedded, so anything but Courier
+    /// falls back to it; unset uses it.
+    pub pdf_font_family: Option<String>,
+    /// Font size in points
+    pub font_size: f32,
+    pub page_size: PageSize,
+    pub line_numbers: bool,
+}
+
+/// Renders `document` and reports progress as a percentage.
+pub fn render_document(
+    document: &SnippetExport,
+    options: &DocumentExportOptions,
+    format: DocumentExportFormat,
+    on_progress: impl FnMut(u8),
+) -> DocumentExport {
+    match format {
+        DocumentExportFormat::Html => DocumentExport {
+            contents: render_html(document, options, on_progress).into_bytes(),
+            warnings: Vec::new(),
+        },
+        DocumentExportFormat::Pdf => render_pdf(document, options, on_progress),
+    }
+}
+
+fn percent(done: usize, total: usize) -> u8 {
+    (done * 100)
+        .checked_div(total)
+        .map_or(100, |percent| percent.min(100) as u8)
+}
+
+fn render_html(
+    document: &SnippetExport,
+    options: &DocumentExportOptions,
+    mut on_progress: impl FnMut(u8),
+) -> String {
+    let title = escape_markup(&document.title);
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n\
+         @page {{ size: {page}; margin: 15mm; }}\n\
+         html, body {{ margin: 0; background: {background}; -webkit-print-color-adjust: exact; print-color-adjust: exact; }}\n\
+         header {{ padding: 8px 12px; color: {gutter}; font: 10pt {font}; border-bottom: 1px solid {gutter}; }}\n\
+         pre {{ margin: 0; padding: 12px; color: {foreground}; font: {size}pt/1.4 {font}; white-space: pre-wrap; overflow-wrap: anywhere; }}\n\
+         .ln {{ color: {gutter}; user-select: none; }}\n\
+         </style>\n</head>\n<body>\n<header>{title}</header>\n<pre>",
+        page = options.page_size.css_name(),
+        background = document.background.hex(),
+        foreground = document.foreground.hex(),
+        gutter = document.gutter.hex(),
+        font = escape_markup(&options.font_family),
+        size = options.font_size,
+    );
+
+    let gutter_width = line_number_width(document, options);
+    let total = document.lines.len();
+    for (index, line) in document.lines.iter().enumerate() {
+        if index > 0 {
+            html.push('\n');
+        }
+        if gutter_width > 0 {
+            let number = line.number.map(|n| n.to_string()).unwrap_or_default();
+            let _ = write!(html, "<span class=\"ln\">{number:>gutter_width$}  </span>");
+        }
+        let mut column = 0;
+        for span in &line.spans {
+            let text = escape_markup(&expand_tabs(&span.text, &mut column));
+            let mut style = String::new();
+            if let Some(foreground) = span.foreground {
+                let _ = write!(style, "color:{};", foreground.hex());
+            }
+            if let Some(background) = span.background {
+                let _ = write!(style, "background:{};", background.hex());
+            }
+            if span.bold {
+                style.push_str("font-weight:bold;");
+            }
+            if span.italic {
+                style.push_str("font-style:italic;");
+            }
+            if span.underline {
+                style.push_str("text-decoration:underline;");
+            }
+            if style.is_empty() {
+                html.push_str(&text);
+            } else {
+                let _ = write!(html, "<span style=\"{style}\">{text}</span>");
+            }
+        }
+        if index % 1000 == 999 {
+            on_progress(percent(index + 1, total));
+        }
+    }
+
+    html.push_str("</pre>\n</body>\n</html>\n");
+    on_progress(100);
+    html
+}
+
+fn line_number_width(document: &SnippetExport, options: &DocumentExportOptions) -> usize {
+    if !options.line_numbers {
+        return 0;
+    }
+    document
+        .lines
+        .iter()
+        .filter_map(|line| line.number)
+        .max()
+        .map_or(0, |number| number.to_string().len())
+}
+
+/// One styled piece of a PDF row, already tab-expanded.
+struct PdfRun {
+    column: usize,
+    text: String,
+    foreground: ExportColor,
+    background: Option<ExportColor>,
+    font: usize,
+    underline: bool,
+}
+
+/// A printed row: a document line or the continuation of a wrapped one.
+struct PdfRow {
+    number: Option<usize>,
+    runs: Vec<PdfRun>,
+}
+
+/// Splits lines into rows of at most `columns` characters.
+fn wrap_rows(document: &SnippetExport, columns: usize) -> Vec<PdfRow> {
+    let columns = columns.max(1);
+    let mut rows = Vec::with_capacity(document.lines.len());
+    for line in &document.lines {
+        rows.extend(wrap_line(document, line, columns));
+    }
+    rows
+}
+
+fn wrap_line(document: &SnippetExport, line: &ExportLine, columns: usize) -> Vec<PdfRow> {
+    let mut rows = vec![PdfRow {
+        number: line.number,
+        runs: Vec::new(),
+    }];
+    // Tabs expand against the unwrapped line; `column` is the position in the row.
+    let mut line_column = 0;
+    let mut column = 0;
+    for span in &line.spans {
+        let font = usize::from(span.bold) | (usize::from(span.italic) << 1);
+        let foreground = span.foreground.unwrap_or(document.foreground);
+        for ch in expand_tabs(&span.text, &mut line_column).chars() {
+            if column == columns {
+                rows.push(PdfRow {
+                    number: None,
+                    runs: Vec::new(),
+                });
+                column = 0;
+            }
+            let runs = &mut rows.last_mut().expect("rows is never empty").runs;
+            match runs.last_mut() {
+                Some(run)
+                    if run.font == font
+                        && run.foreground == foreground
+                        && run.background == span.background
+                        && run.underline == span.underline
+                        && run.column + run.text.chars().count() == column =>
+                {
+                    run.text.push(ch);
+                }
+                _ => runs.push(PdfRun {
+                    column,
+                    text: ch.to_string(),
+                    foreground,
+                    background: span.background,
+                    font,
+                    underline: span.underline,
+                }),
+            }
+            column += 1;
+        }
+    }
+    rows
+}
+
+/// WinAnsiEncoding code for `ch`, if the standard fonts have a glyph for it.
+fn win_ansi_code(ch: char) -> Option<u8> {
+    let code = match ch {
+        ' '..='~' | '\u{a0}'..='\u{ff}' => ch as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8a,
+        '‹' => 0x8b,
+        'Œ' => 0x8c,
+        'Ž' => 0x8e,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9a,
+        '›' => 0x9b,
+        'œ' => 0x9c,
+        'ž' => 0x9e,
+        'Ÿ' => 0x9f,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Counts the characters in `document` the standard fonts have no glyph
+/// for, which print as `?`. Control characters have no glyph in any font
+/// and are not counted.
+fn unprintable_pdf_text(document: &SnippetExport) -> Option<DocumentExportWarning> {
+    let mut unprintable = std::iter::once(document.title.as_str())
+        .chain(
+            document
+                .lines
+                .iter()
+                .flat_map(|line| line.spans.iter().map(|span| span.text.as_str())),
+        )
+        .flat_map(str::chars)
+        .filter(|ch| !ch.is_control() && win_ansi_code(*ch).is_none());
+    let example = unprintable.next()?;
+    Some(DocumentExportWarning::UnprintablePdfText {
+        count: unprintable.count() + 1,
+        example,
+    })
+}
+
+/// Encodes text for a WinAnsi string literal. Characters without a code
+/// print as `?`.
+fn pdf_string(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len() + 2);
+    encoded.push('(');
+    for ch in text.chars() {
+        match ch {
+            '(' | ')' | '\\' => {
+                encoded.push('\\');
+                encoded.push(ch);
+            }
+            ' '..='~' => encoded.push(ch),
+            _ => match win_ansi_code(ch) {
+                Some(code) => {
+                    let _ = write!(encoded, "\\{code:03o}");
+                }
+                None => encoded.push('?'),
+            },
+        }
+    }
+    encoded.push(')');
+    encoded
+}
+
+fn pdf_color(color: ExportColor) -> String {
+    format!(
+        "{:.3} {:.3} {:.3}",
+        f32::from(color.r) / 255.0,
+        f32::from(color.g) / 255.0,
+        f32::from(color.b) / 255.0
+    )
+}
+
+fn render_pdf(
+    document: &SnippetExport,
+    options: &DocumentExportOptions,
+    mut on_progress: impl FnMut(u8),
+) -> DocumentExport {
+    let mut warnings = Vec::new();
+    if let Some(family) = options.pdf_font_family.as_deref()
+        && !PDF_FONT_FAMILIES.contains(&family.trim().to_lowercase().as_str())
+    {
+        warnings.push(DocumentExportWarning::PdfFontFallback(family.to_string()));
+    }
+    warnings.extend(unprintable_pdf_text(document));
+
+    let (page_width, page_height) = options.page_size.dimensions();
+    let font_size = options.font_size.clamp(4.0, 72.0);
+    let char_width = font_size * PDF_CHAR_ADVANCE;
+    let line_height = font_size * PDF_LINE_SPACING;
+    let header_height = line_height * 2.0;
+
+    let gutter_width = line_number_width(document, options);
+    let gutter_columns = if gutter_width > 0 {
+        gutter_width + 2
+    } else {
+        0
+    };
+    let text_columns = ((page_width - PDF_MARGIN * 2.0) / char_width) as usize;
+    let rows = wrap_rows(document, text_columns.saturating_sub(gutter_columns));
+    let rows_per_page =
+        (((page_height - PDF_MARGIN * 2.0 - header_height) / line_height) as usize).max(1);
+    let page_count = rows.len().div_ceil(rows_per_page).max(1);
+
+    let mut contents = Vec::with_capacity(page_count);
+    for page in 0..page_count {
+        let mut content = String::new();
+        let _ = writeln!(
+            content,
+            "{} rg 0 0 {page_width:.2} {page_height:.2} re f",
+            pdf_color(document.background)
+        );
+        let header_y = page_height - PDF_MARGIN - font_size;
+        let _ = writeln!(
+            content,
+            "BT /F1 {font_size:.2} Tf {} rg 1 0 0 1 {PDF_MARGIN:.2} {header_y:.2} Tm {} Tj ET",
+            pdf_color(document.gutter),
+            pdf_string(&document.title)
+        );
+        let page_label = format!("{} / {page_count}", page + 1);
+        let _ = writeln!(
+            content,
+            "BT /F1 {font_size:.2} Tf 1 0 0 1 {:.2} {header_y:.2} Tm {} Tj ET",
+            page_width - PDF_MARGIN - page_label.len() as f32 * char_width,
+            pdf_string(&page_label)
+        );
+
+        let text_left = PDF_MARGIN + gutter_columns as f32 * char_width;
+        let page_rows = rows.iter().skip(page * rows_per_page).take(rows_per_page);
+        for (index, row) in page_rows.enumerate() {
+            let top = page_height - PDF_MARGIN - header_height - index as f32 * line_height;
+            let baseline = top - font_size;
+
+            for run in &row.runs {
+                let x = text_left + run.column as f32 * char_width;
+                let width = run.text.chars().count() as f32 * char_width;
+                if let Some(background) = run.background {
+                    let _ = writeln!(
+                        content,
+                        "{} rg {x:.2} {:.2} {width:.2} {line_height:.2} re f",
+                        pdf_color(background),
+                        top - line_height
+                    );
+                }
+                if run.underline {
+                    let _ = writeln!(
+                        content,
+                        "{} rg {x:.2} {:.2} {width:.2} 0.6 re f",
+                        pdf_color(run.foreground),
+                        baseline - 1.5
+                    );
+                }
+            }
+
+            if let Some(number) = row.number.filter(|_| gutter_width > 0) {
+                let label = number.to_string();
+                let _ = writeln!(
+                    content,
+                    "BT /F1 {font_size:.2} Tf {} rg 1 0 0 1 {:.2} {baseline:.2} Tm {} Tj ET",
+                    pdf_color(document.gutter),
+                    PDF_MARGIN + (gutter_width - label.len()) as f32 * char_width,
+                    pdf_string(&label)
+                );
+            }
+            for run in row.runs.iter().filter(|run| !run.text.trim().is_empty()) {
+                let _ = writeln!(
+                    content,
+                    "BT /F{} {font_size:.2} Tf {} rg 1 0 0 1 {:.2} {baseline:.2} Tm {} Tj ET",
+                    run.font + 1,
+                    pdf_color(run.foreground),
+                    text_left + run.column as f32 * char_width,
+                    pdf_string(&run.text)
+                );
+            }
+        }
+
+        contents.push(content);
+        on_progress(percent(page + 1, page_count));
+    }
+
+    DocumentExport {
+        contents: write_pdf(&document.title, page_width, page_height, &contents),
+        warnings,
+    }
+}
+
+/// Assembles the PDF objects and cross-reference table.
+fn write_pdf(title: &str, page_width: f32, page_height: f32, contents: &[String]) -> Vec<u8> {
+    let font_ids = 3..3 + PDF_FONTS.len();
+    let first_page_id = font_ids.end;
+    let info_id = first_page_id + contents.len() * 2;
+
+    let mut objects = Vec::with_capacity(info_id);
+    objects.push("<< /Type /Catalog /Pages 2 0 R >>".to_string());
+    let kids: Vec<String> = (0..contents.len())
+        .map(|page| format!("{} 0 R", first_page_id + page * 2))
+        .collect();
+    objects.push(format!(
+        "<< /Type /Pages /Kids [{}] /Count {} >>",
+        kids.join(" "),
+        contents.len()
+    ));
+    for font in PDF_FONTS {
+        objects.push(format!(
+            "<< /Type /Font /Subtype /Type1 /BaseFont /{font} /Encoding /WinAnsiEncoding >>"
+        ));
+    }
+    let fonts: Vec<String> = font_ids
+        .enumerate()
+        .map(|(index, id)| format!("/F{} {id} 0 R", index + 1))
+        .collect();
+    for (page, content) in contents.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {page_width:.2} {page_height:.2}] \
+             /Resources << /Font << {} >> >> /Contents {} 0 R >>",
+            fonts.join(" "),
+            first_page_id + page * 2 + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{content}endstream",
+            content.len()
+        ));
+    }
+    objects.push(format!(
+        "<< /Title {} /Producer (Nucleotide) >>",
+        pdf_string(title)
+    ));
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        let _ = write!(pdf, "{} 0 obj\n{object}\nendobj\n", index + 1);
+    }
+    let xref_offset = pdf.len();
+    let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(pdf, "{offset:010} 00000 n ");
+    }
+    let _ = write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R /Info {info_id} 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+        objects.len() + 1
+    );
+    pdf.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snippet_export::ExportSpan;
+
+    const WHITE: ExportColor = ExportColor {
+        r: 0xff,
+        g: 0xff,
+        b: 0xff,
+    };
+    const BLACK: ExportColor = ExportColor { r: 0, g: 0, b: 0 };
+
+    fn document(lines: &[&str]) -> SnippetExport {
+        SnippetExport {
+            title: "lib.rs".to_string(),
+            lines: lines
+                .iter()
+                .enumerate()
+                .map(|(index, text)| ExportLine {
+                    number: Some(index + 1),
+                    spans: vec![ExportSpan {
+                        text: text.to_string(),
+                        foreground: None,
+                        background: None,
+                        bold: index == 0,
+                        italic: false,
+                        underline: false,
+                    }],
+                })
+                .collect(),
+            foreground: BLACK,
+            background: WHITE,
+            gutter: BLACK,
+            frame: WHITE,
+        }
+    }
+
+    fn options(page_size: PageSize) -> DocumentExportOptions {
+        DocumentExportOptions {
+            font_family: "monospace".to_string(),
+            pdf_font_family: None,
+            font_size: 10.0,
+            page_size,
+            line_numbers: true,
+        }
+    }
+
+    #[test]
+    fn long_lines_wrap_into_unnumbered_rows() {
+        let rows = wrap_rows(&document(&["abcdefgh", "x"]), 3);
+        let text: Vec<(Option<usize>, String)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.number,
+                    row.runs.iter().map(|run| run.text.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            text,
+            [
+                (Some(1), "abc".to_string()),
+                (None, "def".to_string()),
+                (None, "gh".to_string()),
+                (Some(2), "x".to_string()),
+            ]
+        );
+        assert_eq!(rows[0].runs[0].font, 1);
+    }
+
+    #[test]
+    fn pdf_output_has_a_page_per_screenful_and_a_valid_xref() {
+        let lines: Vec<String> = (0..100).map(|n| format!("line (n) {n}")).collect();
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let mut progress = Vec::new();
+        let pdf = render_document(
+            &document(&lines),
+            &options(PageSize::A4),
+            DocumentExportFormat::Pdf,
+            |percent| progress.push(percent),
+        );
+        assert!(pdf.warnings.is_empty());
+        let pdf = String::from_utf8(pdf.contents).expect("PDF output is ASCII");
+
+        let pages = pdf.matches("/Type /Page ").count();
+        assert_eq!(pages, 2);
+        assert_eq!(progress, [50, 100]);
+        assert!(pdf.contains("(line \\(n\\) 99) Tj"));
+        assert!(pdf.contains("/BaseFont /Courier-Bold"));
+
+        let xref_offset: usize = pdf
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .and_then(|offset| offset.parse().ok())
+            .expect("startxref offset");
+        assert!(pdf[xref_offset..].starts_with("xref\n"));
+        let first_object = pdf.find("1 0 obj").expect("catalog object");
+        assert!(pdf.contains(&format!("{first_object:010} 00000 n ")));
+    }
+
+    #[test]
+    fn html_output_uses_page_size_and_font_settings() {
+        let html = String::from_utf8(
+            render_document(
+                &document(&["fn <main>"]),
+                &options(PageSize::Letter),
+                DocumentExportFormat::Html,
+                |_| {},
+            )
+            .contents,
+        )
+        .expect("HTML output is UTF-8");
+
+        assert!(html.contains("@page { size: letter; margin: 15mm; }"));
+        assert!(html.contains("font: 10pt/1.4 monospace;"));
+        assert!(html.contains(
+            "<span class=\"ln\">1  </span><span style=\"font-weight:bold;\">fn &lt;main&gt;</span>"
+        ));
+    }
+
+    #[test]
+    fn pdf_strings_use_win_ansi_codes() {
+        assert_eq!(pdf_string("é€“x”\\"), "(\\351\\200\\223x\\224\\\\)");
+        assert_eq!(pdf_string("a\u{c}b"), "(a?b)");
+    }
+
+    #[test]
+    fn pdf_export_replaces_text_courier_cannot_print() {
+        let document = document(&["café “quoted”", "let π = 3.14; // → π"]);
+        let pdf = render_document(
+            &document,
+            &options(PageSize::A4),
+            DocumentExportFormat::Pdf,
+            |_| {},
+        );
+        assert_eq!(
+            pdf.warnings,
+            [DocumentExportWarning::UnprintablePdfText {
+                count: 3,
+                example: 'π',
+            }]
+        );
+        let pdf = String::from_utf8(pdf.contents).expect("PDF output is ASCII");
+        assert!(pdf.contains("(let ? = 3.14; // ? ?) Tj"));
+
+        let html = render_document(
+            &document,
+            &options(PageSize::A4),
+            DocumentExportFormat::Html,
+            |_| {},
+        );
+        assert!(html.warnings.is_empty());
+        assert!(
+            String::from_utf8(html.contents)
+                .unwrap()
+                .contains("let π = 3.14;")
+        );
+    }
+
+    #[test]
+    fn pdf_export_falls_back_to_courier_for_other_font_families() {
+        let mut options = options(PageSize::A4);
+        options.font_family = "\"JetBrains Mono\", monospace".to_string();
+        let render = |options: &DocumentExportOptions| {
+            render_document(
+                &document(&["fn main() {}"]),
+                options,
+                DocumentExportFormat::Pdf,
+                |_| {},
+            )
+        };
+
+        // The HTML family never applies to PDF output.
+        assert!(render(&options).warnings.is_empty());
+
+        options.pdf_font_family = Some("JetBrains Mono".to_string());
+        let pdf = render(&options);
+        assert_eq!(
+            pdf.warnings,
+            [DocumentExportWarning::PdfFontFallback(
+                "JetBrains Mono".to_string()
+            )]
+        );
+        assert!(
+            String::from_utf8(pdf.contents)
+                .unwrap()
+                .contains("/BaseFont /Courier ")
+        );
+
+        options.pdf_font_family = Some("Courier New".to_string());
+        assert!(render(&options).warnings.is_empty());
+    }
+}
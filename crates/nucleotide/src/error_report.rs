@@ -0,0 +1,267 @@
+// ABOUTME: Structured reports for panics and recoverable errors with a pre-filled GitHub issue body
+// ABOUTME: Panics are persisted next to the log file so the next launch can offer to report them
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Log lines included in a report.
+const REPORT_LOG_LINES: usize = 40;
+/// Where users file bugs.
+const ISSUE_URL: &str = "https://github.com/iainh/nucleotide/issues/new";
+const PENDING_CRASH_REPORT_FILE: &str = "last-crash.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorReportKind {
+    Panic,
+    Error,
+}
+
+/// The environment the error happened in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemInfo {
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+}
+
+impl SystemInfo {
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+        }
+    }
+}
+
+/// A panic or recoverable error with the context needed to report it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorReport {
+    pub kind: ErrorReportKind,
+    /// One-line description shown as the dialog message and issue title
+    pub summary: String,
+    /// `file:line:column` for panics
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    /// Error chain or backtrace
+    pub details: Option<String>,
+    pub log_tail: Vec<String>,
+    pub system: SystemInfo,
+    pub occurred_at: String,
+}
+
+impl ErrorReport {
+    fn new(kind: ErrorReportKind, summary: String) -> Self {
+        Self {
+            kind,
+            summary,
+            location: None,
+            thread: None,
+            details: None,
+            log_tail: nucleotide_logging::recent_log_lines(REPORT_LOG_LINES),
+            system: SystemInfo::current(),
+            occurred_at: chrono::Local::now().to_rfc3339(),
+        }
+    }
+
+    /// Report for a panic caught by the panic hook.
+    pub fn panic(
+        message: String,
+        location: Option<String>,
+        thread: Option<String>,
+        backtrace: Option<String>,
+    ) -> Self {
+        Self {
+            location,
+            thread,
+            details: backtrace,
+            ..Self::new(ErrorReportKind::Panic, message)
+        }
+    }
+
+    /// Report for a recoverable error, `context` saying what was being attempted.
+    pub fn error(context: impl Into<String>, error: &anyhow::Error) -> Self {
+        Self {
+            details: Some(format!("{error:#}")),
+            ..Self::new(ErrorReportKind::Error, context.into())
+        }
+    }
+
+    pub fn dialog_title(&self) -> &'static str {
+        match self.kind {
+            ErrorReportKind::Panic => "Nucleotide Crashed",
+            ErrorReportKind::Error => "Something Went Wrong",
+        }
+    }
+
+    /// Short description for the error dialog.
+    pub fn dialog_message(&self) -> String {
+        let mut message = match self.kind {
+            ErrorReportKind::Panic => {
+                format!("The previous session ended unexpectedly: {}", self.summary)
+            }
+            ErrorReportKind::Error => self.summary.clone(),
+        };
+        if self.kind == ErrorReportKind::Error
+            && let Some(details) = self.details.as_deref().and_then(|d| d.lines().next())
+        {
+            message.push_str(&format!("\n\n{details}"));
+        }
+        if let Some(location) = &self.location {
+            message.push_str(&format!("\n\nAt {location}"));
+        }
+        message.push_str("\n\nCopy a pre-filled issue report to share it with the maintainers.");
+        message
+    }
+
+    pub fn issue_title(&self) -> String {
+        let prefix = match self.kind {
+            ErrorReportKind::Panic => "Crash",
+            ErrorReportKind::Error => "Error",
+        };
+        let summary = self.summary.lines().next().unwrap_or_default();
+        format!("{prefix}: {summary}")
+    }
+
+    /// Markdown issue body following the bug report template.
+    pub fn issue_body(&self) -> String {
+        let mut body = String::new();
+        body.push_str(&format!("<!-- File at {ISSUE_URL} -->\n"));
+        body.push_str(&format!("## {}\n\n", self.issue_title()));
+        body.push_str("### What happened\n\n");
+        body.push_str(&format!("{}\n\n", self.summary));
+        body.push_str("### Steps to reproduce\n\n1. \n\n");
+
+        body.push_str("### Context\n\n");
+        if let Some(location) = &self.location {
+            body.push_str(&format!("- Location: `{location}`\n"));
+        }
+        if let Some(thread) = &self.thread {
+            body.push_str(&format!("- Thread: `{thread}`\n"));
+        }
+        body.push_str(&format!("- Time: {}\n\n", self.occurred_at));
+
+        if let Some(details) = &self.details {
+            body.push_str("<details><summary>Details</summary>\n\n```\n");
+            body.push_str(details.trim_end());
+            body.push_str("\n```\n\n</details>\n\n");
+        }
+
+        body.push_str("### System\n\n");
+        body.push_str(&format!("- Nucleotide: {}\n", self.system.version));
+        body.push_str(&format!("- OS: {}\n", self.system.os));
+        body.push_str(&format!("- Architecture: {}\n\n", self.system.arch));
+
+        if !self.log_tail.is_empty() {
+            body.push_str(&format!(
+                "<details><summary>Recent log ({} lines)</summary>\n\n```\n",
+                self.log_tail.len()
+            ));
+            for line in &self.log_tail {
+                body.push_str(line);
+                body.push('\n');
+            }
+            body.push_str("```\n\n</details>\n");
+        }
+        body
+    }
+
+    /// Persists the report so the next launch can show it.
+    pub fn save_pending(&self) -> std::io::Result<()> {
+        self.save_pending_to(&pending_crash_report_path())
+    }
+
+    /// Removes and returns the report left by a crashed session, if any.
+    pub fn take_pending() -> Option<Self> {
+        Self::take_pending_from(&pending_crash_report_path())
+    }
+
+    fn save_pending_to(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    fn take_pending_from(path: &Path) -> Option<Self> {
+        let json = std::fs::read_to_string(path).ok()?;
+        if let Err(e) = std::fs::remove_file(path) {
+            nucleotide_logging::warn!(
+                error = %e,
+                path = %path.display(),
+                "Failed to remove pending crash report"
+            );
+        }
+        match serde_json::from_str(&json) {
+            Ok(report) => Some(report),
+            Err(e) => {
+                nucleotide_logging::warn!(error = %e, "Ignoring unreadable pending crash report");
+                None
+            }
+        }
+    }
+}
+
+fn pending_crash_report_path() -> PathBuf {
+    nucleotide_logging::default_log_file_path().with_file_name(PENDING_CRASH_REPORT_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn panic_report() -> ErrorReport {
+        ErrorReport {
+            log_tail: vec!["INFO nucleotide: opened file".to_string()],
+            ..ErrorReport::panic(
+                "index out of bounds".to_string(),
+                Some("src/document.rs:10:5".to_string()),
+                Some("main".to_string()),
+                Some("0: nucleotide::document::render".to_string()),
+            )
+        }
+    }
+
+    #[test]
+    fn issue_body_carries_the_structured_context() {
+        let body = panic_report().issue_body();
+
+        assert!(body.contains("## Crash: index out of bounds"));
+        assert!(body.contains("- Location: `src/document.rs:10:5`"));
+        assert!(body.contains("- Thread: `main`"));
+        assert!(body.contains("0: nucleotide::document::render"));
+        assert!(body.contains(&format!("- OS: {}", std::env::consts::OS)));
+        assert!(body.contains("Recent log (1 lines)"));
+        assert!(body.contains("INFO nucleotide: opened file"));
+    }
+
+    #[test]
+    fn error_reports_include_the_error_chain() {
+        let error = anyhow::anyhow!("connection refused").context("starting rust-analyzer");
+        let report = ErrorReport::error("Language server stopped", &error);
+
+        assert_eq!(report.issue_title(), "Error: Language server stopped");
+        assert_eq!(
+            report.details.as_deref(),
+            Some("starting rust-analyzer: connection refused")
+        );
+        assert!(
+            report
+                .dialog_message()
+                .contains("starting rust-analyzer: connection refused")
+        );
+    }
+
+    #[test]
+    fn pending_crash_reports_are_taken_once() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("logs").join(PENDING_CRASH_REPORT_FILE);
+        let report = panic_report();
+
+        report.save_pending_to(&path).unwrap();
+        assert_eq!(ErrorReport::take_pending_from(&path), Some(report));
+        assert_eq!(ErrorReport::take_pending_from(&path), None);
+    }
+}
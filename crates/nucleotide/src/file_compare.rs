@@ -0,0 +1,311 @@
+// ABOUTME: Compares the active file side by side with another buffer, a file or the clipboard
+// ABOUTME: Finds line differences between the two sides, for navigation and copying across
+
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+
+use helix_core::{Rope, Selection, Tendril, Transaction};
+use helix_vcs::DiffHandle;
+use helix_view::{DocumentId, Editor, ViewId};
+use nucleotide_vcs::incremental_diff::diff_lines;
+
+/// Prompt command comparing the active file with the file at a path, or
+/// listing what to compare it with.
+pub const COMPARE_WITH_COMMAND: &str = "compare-with";
+/// Prompt command comparing the active file with the clipboard.
+pub const COMPARE_CLIPBOARD_COMMAND: &str = "compare-with-clipboard";
+pub const COMPARE_NEXT_COMMAND: &str = "compare-next";
+pub const COMPARE_PREVIOUS_COMMAND: &str = "compare-previous";
+pub const COMPARE_COPY_LEFT_COMMAND: &str = "compare-copy-left";
+pub const COMPARE_COPY_RIGHT_COMMAND: &str = "compare-copy-right";
+pub const COMPARE_CLOSE_COMMAND: &str = "compare-close";
+/// Prompt commands for comparisons, with completion descriptions.
+pub const COMPARE_COMMANDS: &[(&str, &str)] = &[
+    (
+        COMPARE_WITH_COMMAND,
+        "Compare the active file with an open buffer, the clipboard, or the file at a path",
+    ),
+    (
+        COMPARE_CLIPBOARD_COMMAND,
+        "Compare the active file with the clipboard",
+    ),
+    (COMPARE_NEXT_COMMAND, "Go to the next difference"),
+    (COMPARE_PREVIOUS_COMMAND, "Go to the previous difference"),
+    (
+        COMPARE_COPY_LEFT_COMMAND,
+        "Copy the difference at the cursor from the right side to the left",
+    ),
+    (
+        COMPARE_COPY_RIGHT_COMMAND,
+        "Copy the difference at the cursor from the left side to the right",
+    ),
+    (COMPARE_CLOSE_COMMAND, "Close the comparison"),
+];
+
+/// What to compare the active file with, from the compare menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareChoice {
+    Buffer(DocumentId),
+    Clipboard,
+    /// Ask for the path of a file
+    File,
+}
+
+/// What the right side of a comparison shows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompareTarget {
+    Buffer(DocumentId),
+    File(PathBuf),
+    /// Read-only text, such as the clipboard's
+    Text(String),
+}
+
+/// A comparison command other than starting one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareAction {
+    Next,
+    Previous,
+    CopyLeft,
+    CopyRight,
+    Close,
+}
+
+impl CompareAction {
+    pub fn from_command(command: &str) -> Option<Self> {
+        match command.trim().trim_start_matches(':') {
+            COMPARE_NEXT_COMMAND => Some(Self::Next),
+            COMPARE_PREVIOUS_COMMAND => Some(Self::Previous),
+            COMPARE_COPY_LEFT_COMMAND => Some(Self::CopyLeft),
+            COMPARE_COPY_RIGHT_COMMAND => Some(Self::CopyRight),
+            COMPARE_CLOSE_COMMAND => Some(Self::Close),
+            _ => None,
+        }
+    }
+
+    pub fn command(self) -> &'static str {
+        match self {
+            Self::Next => COMPARE_NEXT_COMMAND,
+            Self::Previous => COMPARE_PREVIOUS_COMMAND,
+            Self::CopyLeft => COMPARE_COPY_LEFT_COMMAND,
+            Self::CopyRight => COMPARE_COPY_RIGHT_COMMAND,
+            Self::Close => COMPARE_CLOSE_COMMAND,
+        }
+    }
+}
+
+/// Path given to a `compare-with [path]` command line. Returns `None` for
+/// other commands, and `Some(None)` when no path is given.
+pub fn parse_compare_with(command: &str) -> Option<Option<&str>> {
+    let path = command
+        .trim()
+        .trim_start_matches(':')
+        .strip_prefix(COMPARE_WITH_COMMAND)
+        .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))?
+        .trim();
+    Some((!path.is_empty()).then_some(path))
+}
+
+/// One side of a comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// Lines that differ between the two sides, as line ranges of each. An
+/// empty range is where the other side's lines are missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompareHunk {
+    pub left: Range<usize>,
+    pub right: Range<usize>,
+}
+
+impl CompareHunk {
+    pub fn lines(&self, side: Side) -> &Range<usize> {
+        match side {
+            Side::Left => &self.left,
+            Side::Right => &self.right,
+        }
+    }
+}
+
+/// A comparison shown in two views side by side. The documents' own
+/// differs are set aside while each gutter marks the changes against the
+/// other side.
+pub struct Comparison {
+    pub left: (DocumentId, ViewId),
+    pub right: (DocumentId, ViewId),
+    pub left_diff: Option<DiffHandle>,
+    pub right_diff: Option<DiffHandle>,
+    /// The right side was made for the comparison and closes with it
+    pub right_temporary: bool,
+}
+
+impl Comparison {
+    pub fn side_of(&self, view_id: ViewId) -> Option<Side> {
+        if view_id == self.left.1 {
+            Some(Side::Left)
+        } else if view_id == self.right.1 {
+            Some(Side::Right)
+        } else {
+            None
+        }
+    }
+
+    pub fn includes(&self, doc_id: DocumentId) -> bool {
+        self.left.0 == doc_id || self.right.0 == doc_id
+    }
+}
+
+/// Points each side's differ at the other side's text, so the gutters mark
+/// the differences between them.
+pub fn sync_diff_bases(editor: &mut Editor, comparison: &Comparison) {
+    let text_of = |doc_id| {
+        editor
+            .document(doc_id)
+            .map(|doc| doc.text().to_string().into_bytes())
+    };
+    let (Some(left), Some(right)) = (text_of(comparison.left.0), text_of(comparison.right.0))
+    else {
+        return;
+    };
+    if let Some(doc) = editor.document_mut(comparison.left.0) {
+        doc.set_diff_base(right);
+    }
+    if let Some(doc) = editor.document_mut(comparison.right.0) {
+        doc.set_diff_base(left);
+    }
+}
+
+/// Differing lines of `left` and `right`.
+pub fn compare_hunks(left: &Rope, right: &Rope) -> Vec<CompareHunk> {
+    // Lines keep their endings so a missing final newline is a difference.
+    let left_lines = left.lines().map(String::from).collect::<Vec<_>>();
+    let right_lines = right.lines().map(String::from).collect::<Vec<_>>();
+    match diff_lines(&left_lines, &right_lines, &AtomicBool::new(false)) {
+        Ok(hunks) => hunks
+            .into_iter()
+            .map(|hunk| CompareHunk {
+                left: hunk.before_start as usize..hunk.before_end as usize,
+                right: hunk.after_start as usize..hunk.after_end as usize,
+            })
+            .collect(),
+        // Too large to diff line by line; the sides differ as a whole.
+        Err(_) if left_lines != right_lines => vec![CompareHunk {
+            left: 0..left_lines.len(),
+            right: 0..right_lines.len(),
+        }],
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Index of the hunk after (or before) `line` of `side`, wrapping around.
+pub fn adjacent_hunk(
+    hunks: &[CompareHunk],
+    side: Side,
+    line: usize,
+    forward: bool,
+) -> Option<usize> {
+    if forward {
+        hunks
+            .iter()
+            .position(|hunk| hunk.lines(side).start > line)
+            .or_else(|| (!hunks.is_empty()).then_some(0))
+    } else {
+        hunks
+            .iter()
+            .rposition(|hunk| hunk.lines(side).end.max(hunk.lines(side).start + 1) <= line)
+            .or_else(|| hunks.len().checked_sub(1))
+    }
+}
+
+/// Index of the hunk at `line` of `side`, including one whose lines are
+/// missing just before `line`.
+pub fn hunk_at(hunks: &[CompareHunk], side: Side, line: usize) -> Option<usize> {
+    hunks.iter().position(|hunk| {
+        let lines = hunk.lines(side);
+        lines.contains(&line) || (lines.is_empty() && lines.start == line)
+    })
+}
+
+/// Selection of a hunk's lines, or a cursor where missing lines would go.
+pub fn hunk_selection(text: &Rope, lines: &Range<usize>) -> Selection {
+    let start = text.line_to_char(lines.start.min(text.len_lines()));
+    let end = text.line_to_char(lines.end.min(text.len_lines()));
+    if start == end {
+        Selection::point(start)
+    } else {
+        Selection::single(start, end)
+    }
+}
+
+/// Replaces the `to_lines` of `to` with the `from_lines` of `from`.
+pub fn copy_hunk_transaction(
+    from: &Rope,
+    from_lines: &Range<usize>,
+    to: &Rope,
+    to_lines: &Range<usize>,
+) -> Transaction {
+    let text = from
+        .slice(from.line_to_char(from_lines.start)..from.line_to_char(from_lines.end))
+        .to_string();
+    let start = to.line_to_char(to_lines.start);
+    let end = to.line_to_char(to_lines.end);
+    let replacement = (!text.is_empty()).then(|| Tendril::from(text));
+    Transaction::change(to, std::iter::once((start, end, replacement)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hunks_pair_the_differing_lines_of_both_sides() {
+        let left = Rope::from("a\nb\nc\nd\n");
+        let right = Rope::from("a\nB\nc\nd\ne\n");
+        let hunks = compare_hunks(&left, &right);
+        assert_eq!(
+            hunks,
+            [
+                CompareHunk {
+                    left: 1..2,
+                    right: 1..2,
+                },
+                CompareHunk {
+                    left: 4..4,
+                    right: 4..5,
+                },
+            ]
+        );
+
+        assert_eq!(adjacent_hunk(&hunks, Side::Left, 0, true), Some(0));
+        assert_eq!(adjacent_hunk(&hunks, Side::Left, 1, true), Some(1));
+        assert_eq!(adjacent_hunk(&hunks, Side::Left, 4, true), Some(0));
+        assert_eq!(adjacent_hunk(&hunks, Side::Right, 5, false), Some(1));
+        assert_eq!(adjacent_hunk(&hunks, Side::Right, 4, false), Some(0));
+        assert_eq!(adjacent_hunk(&hunks, Side::Right, 0, false), Some(1));
+        assert_eq!(hunk_at(&hunks, Side::Left, 4), Some(1));
+        assert_eq!(hunk_at(&hunks, Side::Right, 2), None);
+    }
+
+    #[test]
+    fn copying_a_hunk_makes_the_sides_agree_there() {
+        let left = Rope::from("a\nb\nc\n");
+        let mut right = Rope::from("a\nB\nx\nc\n");
+        let hunks = compare_hunks(&left, &right);
+        assert_eq!(hunks.len(), 1);
+
+        let transaction = copy_hunk_transaction(&left, &hunks[0].left, &right, &hunks[0].right);
+        assert!(transaction.apply(&mut right));
+        assert_eq!(right, left);
+        assert!(compare_hunks(&left, &right).is_empty());
+
+        assert_eq!(
+            parse_compare_with(":compare-with src/a.rs"),
+            Some(Some("src/a.rs"))
+        );
+        assert_eq!(parse_compare_with("compare-with"), Some(None));
+        assert_eq!(parse_compare_with("compare-with-clipboard"), None);
+    }
+}
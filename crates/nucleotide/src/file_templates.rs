@@ -0,0 +1,276 @@
+// ABOUTME: User file templates offered when creating a file from the file tree
+// ABOUTME: Matches templates by glob or language and resolves their snippet variables
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Datelike, Local, Timelike};
+use globset::Glob;
+use helix_core::snippets::SnippetRenderCtx;
+
+use crate::config::FileTemplateConfig;
+
+/// Folder of the config dir that relative template files are read from.
+pub const FILE_TEMPLATES_DIR: &str = "templates";
+
+/// Folder template files are read from when their path is relative.
+pub fn templates_dir() -> PathBuf {
+    helix_loader::config_dir().join(FILE_TEMPLATES_DIR)
+}
+
+/// A template that can fill a new file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTemplate {
+    pub name: String,
+    /// Snippet file holding the template text
+    pub source: PathBuf,
+}
+
+/// Template picker row for the file about to be created. `template` is
+/// `None` for an empty file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTemplateChoice {
+    pub parent: PathBuf,
+    pub name: String,
+    pub template: Option<FileTemplate>,
+}
+
+/// Templates of `entries` that apply to a file at `path` in `language`, in
+/// configuration order.
+///
+/// An entry applies when its glob matches the file name and its language
+/// matches `language`; an entry with neither applies to every file.
+pub fn matching_templates(
+    entries: &[FileTemplateConfig],
+    templates_dir: &Path,
+    path: &Path,
+    language: Option<&str>,
+) -> Vec<FileTemplate> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    entries
+        .iter()
+        .filter(|entry| {
+            entry
+                .language
+                .as_deref()
+                .is_none_or(|wanted| language == Some(wanted))
+        })
+        .filter(|entry| {
+            entry
+                .glob
+                .as_deref()
+                .is_none_or(|glob| glob_matches(glob, &file_name))
+        })
+        .map(|entry| FileTemplate {
+            name: entry.name.clone(),
+            source: templates_dir.join(&entry.file),
+        })
+        .collect()
+}
+
+fn glob_matches(glob: &str, file_name: &str) -> bool {
+    match Glob::new(glob) {
+        Ok(glob) => glob.compile_matcher().is_match(file_name),
+        Err(error) => {
+            nucleotide_logging::warn!(
+                glob = %glob,
+                error = %error,
+                "Ignoring file template with an invalid glob"
+            );
+            false
+        }
+    }
+}
+
+/// Values of the snippet variables a template can use for the file at `path`.
+#[derive(Debug, Clone)]
+pub struct TemplateVariables {
+    path: PathBuf,
+    root: Option<PathBuf>,
+    now: DateTime<Local>,
+}
+
+impl TemplateVariables {
+    pub fn new(path: PathBuf, root: Option<PathBuf>, now: DateTime<Local>) -> Self {
+        Self { path, root, now }
+    }
+
+    /// Value of the variable `name`, or `None` for unknown variables so the
+    /// snippet falls back to its default text.
+    pub fn resolve(&self, name: &str) -> Option<String> {
+        let now = &self.now;
+        let value = match name {
+            "TM_FILENAME" => self.path.file_name()?.to_string_lossy().into_owned(),
+            "TM_FILENAME_BASE" => self.path.file_stem()?.to_string_lossy().into_owned(),
+            "TM_DIRECTORY" => self.path.parent()?.display().to_string(),
+            "TM_FILEPATH" => self.path.display().to_string(),
+            "RELATIVE_FILEPATH" => self.relative_path().display().to_string(),
+            "WORKSPACE_NAME" => self
+                .root
+                .as_deref()?
+                .file_name()?
+                .to_string_lossy()
+                .into_owned(),
+            "WORKSPACE_FOLDER" => self.root.as_deref()?.display().to_string(),
+            "MODULE_PATH" => self.module_path(),
+            "CURRENT_YEAR" => now.year().to_string(),
+            "CURRENT_YEAR_SHORT" => format!("{:02}", now.year() % 100),
+            "CURRENT_MONTH" => format!("{:02}", now.month()),
+            "CURRENT_MONTH_NAME" => now.format("%B").to_string(),
+            "CURRENT_MONTH_NAME_SHORT" => now.format("%b").to_string(),
+            "CURRENT_DATE" => format!("{:02}", now.day()),
+            "CURRENT_DAY_NAME" => now.format("%A").to_string(),
+            "CURRENT_DAY_NAME_SHORT" => now.format("%a").to_string(),
+            "CURRENT_HOUR" => format!("{:02}", now.hour()),
+            "CURRENT_MINUTE" => format!("{:02}", now.minute()),
+            "CURRENT_SECOND" => format!("{:02}", now.second()),
+            "CURRENT_SECONDS_UNIX" => now.timestamp().to_string(),
+            "DATE" => now.format("%Y-%m-%d").to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Snippet render context of `base` that resolves these variables.
+    pub fn render_ctx(self, base: SnippetRenderCtx) -> SnippetRenderCtx {
+        SnippetRenderCtx {
+            resolve_var: Box::new(move |name| self.resolve(name).map(Into::into)),
+            ..base
+        }
+    }
+
+    fn relative_path(&self) -> &Path {
+        self.root
+            .as_deref()
+            .and_then(|root| self.path.strip_prefix(root).ok())
+            .unwrap_or(&self.path)
+    }
+
+    /// The file's module path below the project root, e.g. `net::client`
+    /// for `src/net/client.rs` and `app.net.client` for `app/net/client.py`.
+    /// A leading `src` or `lib` folder and trailing `mod`, `index` or
+    /// `__init__` names are left out.
+    fn module_path(&self) -> String {
+        let relative = self.relative_path().with_extension("");
+        let mut segments = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        if segments.len() > 1 && matches!(segments[0].as_str(), "src" | "lib") {
+            segments.remove(0);
+        }
+        if segments.len() > 1
+            && matches!(
+                segments.last().map(String::as_str),
+                Some("mod" | "index" | "__init__")
+            )
+        {
+            segments.pop();
+        }
+        let separator = match self.path.extension().and_then(|ext| ext.to_str()) {
+            Some("rs") => "::",
+            _ => ".",
+        };
+        segments.join(separator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn template(name: &str, glob: Option<&str>, language: Option<&str>) -> FileTemplateConfig {
+        FileTemplateConfig {
+            name: name.to_string(),
+            glob: glob.map(str::to_string),
+            language: language.map(str::to_string),
+            file: PathBuf::from(format!("{name}.snippet")),
+        }
+    }
+
+    fn variables(path: &str) -> TemplateVariables {
+        TemplateVariables::new(
+            PathBuf::from(path),
+            Some(PathBuf::from("/work/app")),
+            Local.with_ymd_and_hms(2026, 3, 7, 9, 5, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn templates_match_by_glob_and_language() {
+        let entries = [
+            template("test", Some("*_test.rs"), None),
+            template("module", None, Some("rust")),
+            template("component", Some("*.tsx"), Some("tsx")),
+            template("license", None, None),
+        ];
+        let names = |path: &str, language: Option<&str>| {
+            matching_templates(
+                &entries,
+                Path::new("/config/templates"),
+                Path::new(path),
+                language,
+            )
+            .into_iter()
+            .map(|template| template.name)
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            names("/work/app/src/net_test.rs", Some("rust")),
+            ["test", "module", "license"]
+        );
+        assert_eq!(
+            names("/work/app/src/lib.rs", Some("rust")),
+            ["module", "license"]
+        );
+        assert_eq!(names("/work/app/ui/Button.tsx", None), ["license"]);
+        assert_eq!(
+            matching_templates(
+                &entries[..1],
+                Path::new("/config/templates"),
+                Path::new("/work/app/a_test.rs"),
+                None
+            )[0]
+            .source,
+            PathBuf::from("/config/templates/test.snippet")
+        );
+    }
+
+    #[test]
+    fn variables_describe_the_new_file_and_the_current_date() {
+        let variables = variables("/work/app/src/net/client.rs");
+
+        assert_eq!(variables.resolve("TM_FILENAME").unwrap(), "client.rs");
+        assert_eq!(variables.resolve("TM_FILENAME_BASE").unwrap(), "client");
+        assert_eq!(
+            variables.resolve("RELATIVE_FILEPATH").unwrap(),
+            "src/net/client.rs"
+        );
+        assert_eq!(variables.resolve("WORKSPACE_NAME").unwrap(), "app");
+        assert_eq!(variables.resolve("MODULE_PATH").unwrap(), "net::client");
+        assert_eq!(variables.resolve("DATE").unwrap(), "2026-03-07");
+        assert_eq!(variables.resolve("CURRENT_MONTH").unwrap(), "03");
+        assert_eq!(variables.resolve("CURRENT_DAY_NAME").unwrap(), "Saturday");
+        assert_eq!(variables.resolve("UNKNOWN"), None);
+    }
+
+    #[test]
+    fn module_paths_leave_out_package_entry_files() {
+        assert_eq!(
+            variables("/work/app/src/net/mod.rs")
+                .resolve("MODULE_PATH")
+                .unwrap(),
+            "net"
+        );
+        assert_eq!(
+            variables("/work/app/app/net/__init__.py")
+                .resolve("MODULE_PATH")
+                .unwrap(),
+            "app.net"
+        );
+    }
+}
@@ -50,6 +50,9 @@ pub struct FileTreeEntry {
     pub flattened_segments: Option<Arc<[FileTreeFlattenedSegment]>>,
     /// Whether this row directly matches the current tree search query.
     pub is_search_match: bool,
+    /// Siblings left out of the current projection from this row on. When
+    /// nonzero the row stands in for them as its directory's "show more" row.
+    pub hidden_siblings: usize,
 }
 
 /// Unique identifier for file tree entries
@@ -112,6 +115,7 @@ impl FileTreeEntry {
             set_size: 1,
             flattened_segments: None,
             is_search_match: false,
+            hidden_siblings: 0,
         }
     }
 
@@ -143,6 +147,7 @@ impl FileTreeEntry {
             set_size: 1,
             flattened_segments: None,
             is_search_match: false,
+            hidden_siblings: 0,
         }
     }
 
@@ -180,6 +185,7 @@ impl FileTreeEntry {
             set_size: 1,
             flattened_segments: None,
             is_search_match: false,
+            hidden_siblings: 0,
         }
     }
 
@@ -3,6 +3,7 @@
 
 pub mod entry;
 pub mod icons;
+pub mod preview;
 // pub mod project_header;
 pub mod sidebar;
 pub mod tree;
@@ -21,11 +22,15 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 pub const FILE_TREE_CONTEXT: &str = "FileTree";
+/// Entries the initial load scans before warning that the project is large.
+pub const DEFAULT_SCAN_LIMIT: usize = 100_000;
+/// Children a directory lists before its "show more" row.
+pub const DEFAULT_PAGE_SIZE: usize = 1_000;
 
 pub fn init(cx: &mut App) {
     use crate::actions::file_tree::{
         ClearSearch, Delete, OpenFile, SelectNext, SelectNextSearchMatch, SelectPrev,
-        SelectPrevSearchMatch, StartSearch, ToggleExpanded,
+        SelectPrevSearchMatch, StartSearch, ToggleExpanded, TogglePreview,
     };
 
     cx.bind_keys([
@@ -41,7 +46,7 @@ pub fn init(cx: &mut App) {
         KeyBinding::new("right", ToggleExpanded, Some(FILE_TREE_CONTEXT)),
         KeyBinding::new("h", ToggleExpanded, Some(FILE_TREE_CONTEXT)),
         KeyBinding::new("l", ToggleExpanded, Some(FILE_TREE_CONTEXT)),
-        KeyBinding::new("space", ToggleExpanded, Some(FILE_TREE_CONTEXT)),
+        KeyBinding::new("space", TogglePreview, Some(FILE_TREE_CONTEXT)),
         KeyBinding::new("enter", OpenFile, Some(FILE_TREE_CONTEXT)),
         KeyBinding::new("delete", Delete, Some(FILE_TREE_CONTEXT)),
     ]);
@@ -94,6 +99,11 @@ pub enum FileTreeEvent {
     ToggleVisibility,
     /// Request that the workspace opens the file tree search prompt
     SearchRequested { initial_query: Option<String> },
+    /// The file shown in the preview popup changed; `None` closes it
+    PreviewChanged { path: Option<PathBuf> },
+    /// The project was too large to load; the user wants to pick a
+    /// subfolder to open instead
+    ChooseRootRequested,
 }
 
 /// Types of file system events
@@ -170,6 +180,22 @@ pub struct FileTreeConfig {
     pub density: FileTreeDisplayDensity,
     /// Render the file tree over a translucent native window backdrop.
     pub translucent_background: bool,
+    /// File metadata shown besides entry names.
+    pub metadata: FileTreeMetadataDisplay,
+    /// Entries the initial load scans before giving up and listing only the
+    /// root; `None` scans without a limit.
+    pub scan_limit: Option<usize>,
+    /// Children listed per directory before a "show more" row.
+    pub page_size: usize,
+}
+
+/// Where rows show file size and modified time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileTreeMetadataDisplay {
+    /// Size and modified-time columns at the end of each row.
+    pub columns: bool,
+    /// A tooltip with the size and modified time when hovering a row.
+    pub tooltips: bool,
 }
 
 impl Default for FileTreeConfig {
@@ -183,6 +209,9 @@ impl Default for FileTreeConfig {
             search_mode: FileTreeSearchMode::ExpandMatches,
             density: FileTreeDisplayDensity::Default,
             translucent_background: false,
+            metadata: FileTreeMetadataDisplay::default(),
+            scan_limit: Some(DEFAULT_SCAN_LIMIT),
+            page_size: DEFAULT_PAGE_SIZE,
         }
     }
 }
@@ -0,0 +1,267 @@
+// ABOUTME: Space-bar preview of the selected file tree entry
+// ABOUTME: Reads a short excerpt or the file's metadata without opening a tab
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use gpui::{
+    AnyElement, FontStyle, FontWeight, HighlightStyle, InteractiveElement, IntoElement, ObjectFit,
+    ParentElement, Pixels, SharedString, Styled, StyledText, div, img, prelude::FluentBuilder, px,
+};
+use nucleotide_editor::StyledTextSpan;
+use nucleotide_ui::DesignTokens;
+
+use crate::snippet_export::expand_tabs;
+
+/// Lines of a text file shown in the preview.
+pub const PREVIEW_MAX_LINES: usize = 40;
+/// Bytes read to fill the excerpt and to tell text from binary.
+const PREVIEW_MAX_BYTES: u64 = 64 * 1024;
+pub const PREVIEW_WIDTH: Pixels = px(480.0);
+const PREVIEW_IMAGE_HEIGHT: Pixels = px(280.0);
+
+/// What the preview shows for a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilePreviewSource {
+    /// The first lines of a text file
+    Text {
+        excerpt: String,
+        truncated: bool,
+    },
+    Image,
+    /// A binary file, described by its metadata alone
+    Binary,
+}
+
+/// A file read for the preview, before syntax highlighting.
+#[derive(Debug, Clone)]
+pub struct FilePreviewFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    pub source: FilePreviewSource,
+}
+
+/// A preview ready to render. Text lines carry syntax colors.
+#[derive(Debug, Clone)]
+pub struct FilePreview {
+    pub file: FilePreviewFile,
+    pub lines: Vec<Vec<StyledTextSpan>>,
+}
+
+/// Reads what the preview needs from `path`. Images are left to the image
+/// loader; everything else is sniffed from its first bytes.
+pub fn read_file_preview(path: &Path, is_image: bool) -> std::io::Result<FilePreviewFile> {
+    let metadata = std::fs::metadata(path)?;
+    let source = if is_image {
+        FilePreviewSource::Image
+    } else {
+        let mut head = Vec::new();
+        std::fs::File::open(path)?
+            .take(PREVIEW_MAX_BYTES)
+            .read_to_end(&mut head)?;
+        let read_whole_file = (head.len() as u64) == metadata.len();
+        match text_excerpt(&head, PREVIEW_MAX_LINES) {
+            Some((excerpt, more_lines)) => FilePreviewSource::Text {
+                excerpt,
+                truncated: more_lines || !read_whole_file,
+            },
+            None => FilePreviewSource::Binary,
+        }
+    };
+
+    Ok(FilePreviewFile {
+        path: path.to_path_buf(),
+        size: metadata.len(),
+        modified: metadata.modified().ok(),
+        source,
+    })
+}
+
+/// The first `max_lines` lines of `bytes` and whether more follow, or `None`
+/// when a NUL byte marks the file as binary.
+fn text_excerpt(bytes: &[u8], max_lines: usize) -> Option<(String, bool)> {
+    if bytes.contains(&0) {
+        return None;
+    }
+    let text = String::from_utf8_lossy(bytes);
+    let mut lines = text.split_inclusive('\n');
+    let excerpt: String = lines.by_ref().take(max_lines).collect();
+    let more_lines = lines.next().is_some();
+    Some((excerpt, more_lines))
+}
+
+/// `1.5 KB`-style size with one decimal above a kilobyte.
+pub fn format_file_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+fn preview_details(file: &FilePreviewFile) -> String {
+    let kind = match &file.source {
+        FilePreviewSource::Text { .. } => "Text",
+        FilePreviewSource::Image => "Image",
+        FilePreviewSource::Binary => "Binary",
+    };
+    let mut details = format!("{kind} · {}", format_file_size(file.size));
+    if let Some(modified) = file.modified {
+        let modified: chrono::DateTime<chrono::Local> = modified.into();
+        details.push_str(&format!(
+            " · Modified {}",
+            modified.format("%Y-%m-%d %H:%M")
+        ));
+    }
+    details
+}
+
+fn styled_preview_line(spans: &[StyledTextSpan]) -> StyledText {
+    let mut text = String::new();
+    let mut column = 0;
+    let mut highlights = Vec::new();
+    for span in spans {
+        let start = text.len();
+        text.push_str(&expand_tabs(&span.text, &mut column));
+        let style = HighlightStyle {
+            color: span.fg,
+            font_weight: span.bold.then_some(FontWeight::BOLD),
+            font_style: span.italic.then_some(FontStyle::Italic),
+            ..Default::default()
+        };
+        if start < text.len() && style != HighlightStyle::default() {
+            highlights.push((start..text.len(), style));
+        }
+    }
+    StyledText::new(text).with_highlights(highlights)
+}
+
+/// The preview card: the file name and details above the excerpt, image or
+/// nothing more for binaries.
+pub fn render_file_preview(
+    preview: &FilePreview,
+    tokens: &DesignTokens,
+    code_font: SharedString,
+) -> AnyElement {
+    let file = &preview.file;
+    let name = file
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| file.path.display().to_string());
+
+    let header = div()
+        .flex()
+        .flex_col()
+        .gap(tokens.sizes.space_1)
+        .px(tokens.sizes.space_4)
+        .py(tokens.sizes.space_3)
+        .border_b_1()
+        .border_color(tokens.chrome.popup_border)
+        .child(
+            div()
+                .text_size(tokens.sizes.text_md)
+                .font_weight(FontWeight::SEMIBOLD)
+                .truncate()
+                .child(name),
+        )
+        .child(
+            div()
+                .text_size(tokens.sizes.text_xs)
+                .text_color(tokens.editor.text_secondary)
+                .child(preview_details(file)),
+        );
+
+    let body = match &file.source {
+        FilePreviewSource::Text { truncated, .. } => Some(
+            div()
+                .flex()
+                .flex_col()
+                .px(tokens.sizes.space_4)
+                .py(tokens.sizes.space_3)
+                .bg(tokens.editor.background)
+                .text_color(tokens.editor.text_primary)
+                .font_family(code_font)
+                .text_size(tokens.sizes.text_sm)
+                .overflow_hidden()
+                .children(
+                    preview
+                        .lines
+                        .iter()
+                        .map(|spans| div().whitespace_nowrap().child(styled_preview_line(spans))),
+                )
+                .when(*truncated, |body| {
+                    body.child(
+                        div()
+                            .pt(tokens.sizes.space_2)
+                            .text_color(tokens.editor.text_secondary)
+                            .child("…"),
+                    )
+                })
+                .into_any_element(),
+        ),
+        FilePreviewSource::Image => Some(
+            div()
+                .flex()
+                .justify_center()
+                .p(tokens.sizes.space_3)
+                .h(PREVIEW_IMAGE_HEIGHT)
+                .child(
+                    img(file.path.clone())
+                        .object_fit(ObjectFit::Contain)
+                        .max_w_full()
+                        .h_full(),
+                )
+                .into_any_element(),
+        ),
+        FilePreviewSource::Binary => None,
+    };
+
+    div()
+        .id("file-tree-preview")
+        .w(PREVIEW_WIDTH)
+        .flex()
+        .flex_col()
+        .bg(tokens.chrome.popup_background)
+        .text_color(tokens.chrome.popup_foreground)
+        .border_1()
+        .border_color(tokens.chrome.popup_border)
+        .rounded(tokens.sizes.radius_md)
+        .shadow(vec![tokens.chrome.shadow_md.to_box_shadow(false)])
+        .overflow_hidden()
+        // The preview never takes focus; clicks on it must not reach the
+        // editor underneath.
+        .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| cx.stop_propagation())
+        .child(header)
+        .children(body)
+        .into_any_element()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_excerpts_stop_at_the_line_limit_and_reject_binaries() {
+        assert_eq!(
+            text_excerpt(b"one\ntwo\nthree\n", 2),
+            Some(("one\ntwo\n".to_string(), true))
+        );
+        assert_eq!(
+            text_excerpt(b"one\ntwo", 2),
+            Some(("one\ntwo".to_string(), false))
+        );
+        assert_eq!(text_excerpt(b"\x7fELF\0\0", 2), None);
+        assert_eq!(format_file_size(512), "512 B");
+        assert_eq!(format_file_size(1536), "1.5 KB");
+        assert_eq!(format_file_size(3 * 1024 * 1024), "3.0 MB");
+    }
+}
@@ -1,7 +1,7 @@
 // ABOUTME: Sidebar row model derived from file tree entries
 // ABOUTME: Keeps project-tree rendering inputs separate from FileTreeView state
 
-use std::{path::PathBuf, sync::Arc};
+use std::{path::PathBuf, sync::Arc, time::SystemTime};
 
 use gpui::prelude::FluentBuilder;
 use gpui::{
@@ -11,14 +11,16 @@ use gpui::{
 };
 use nucleotide_types::VcsStatus;
 use nucleotide_ui::tokens::FileTreeTokens;
-use nucleotide_ui::{Theme, ThemedContext as _, VcsIcon};
+use nucleotide_ui::{TextTooltip, Theme, ThemedContext as _, VcsIcon};
 
 use crate::file_tree::{
-    FileKind, FileTreeDisplayDensity, FileTreeEntry, entry::FileTreeFlattenedSegment,
-    icons::chevron_icon,
+    FileKind, FileTreeDisplayDensity, FileTreeEntry, FileTreeMetadataDisplay,
+    entry::FileTreeFlattenedSegment, icons::chevron_icon, preview::format_file_size,
 };
 
 const PROJECT_TREE_FILENAME_CHAR_WIDTH_PX: f32 = 8.0;
+const PROJECT_TREE_SIZE_COLUMN_PX: f32 = 64.0;
+const PROJECT_TREE_MODIFIED_COLUMN_PX: f32 = 40.0;
 type ProjectTreeMouseDownHandler = Arc<dyn Fn(&MouseDownEvent, &mut Window, &mut App) + 'static>;
 
 #[derive(Clone, Copy)]
@@ -68,6 +70,8 @@ impl ProjectTreeDensityMetrics {
 pub enum ProjectTreeRowAction {
     ToggleDirectory,
     OpenFile,
+    /// List the next page of the parent directory's children
+    ShowMore,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -102,6 +106,8 @@ pub enum ProjectTreeContextMenuIntent {
     CopyPath,
     CopyRelativePath,
     RevealInOs,
+    OpenTerminalHere,
+    ShowHistory,
 }
 
 impl ProjectTreeContextMenuIntent {
@@ -115,6 +121,8 @@ impl ProjectTreeContextMenuIntent {
             Self::CopyPath,
             Self::CopyRelativePath,
             Self::RevealInOs,
+            Self::OpenTerminalHere,
+            Self::ShowHistory,
         ]
     }
 
@@ -128,6 +136,8 @@ impl ProjectTreeContextMenuIntent {
             Self::CopyPath => "Copy Path",
             Self::CopyRelativePath => "Copy Relative Path",
             Self::RevealInOs => "Reveal in OS",
+            Self::OpenTerminalHere => "Open Terminal Here",
+            Self::ShowHistory => "Show History",
         }
     }
 }
@@ -165,6 +175,11 @@ pub struct ProjectTreeRow {
     pub is_hidden: bool,
     pub is_search_match: bool,
     pub vcs_status: Option<VcsStatus>,
+    /// File size; `None` for directories
+    pub size: Option<u64>,
+    pub modified: Option<SystemTime>,
+    /// Children of the parent not listed yet; nonzero for "show more" rows
+    pub hidden_siblings: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -230,11 +245,16 @@ impl ProjectTreeRow {
             is_hidden: entry.is_hidden,
             is_search_match: entry.is_search_match,
             vcs_status: vcs_status.or(entry.git_status),
+            size: matches!(entry.kind, FileKind::File { .. }).then_some(entry.size),
+            modified: entry.mtime,
+            hidden_siblings: entry.hidden_siblings,
         }
     }
 
     pub fn primary_action(&self) -> ProjectTreeRowAction {
-        if self.is_directory() {
+        if self.is_show_more() {
+            ProjectTreeRowAction::ShowMore
+        } else if self.is_directory() {
             ProjectTreeRowAction::ToggleDirectory
         } else {
             ProjectTreeRowAction::OpenFile
@@ -271,8 +291,13 @@ impl ProjectTreeRow {
         self.depth == 0 && self.is_directory()
     }
 
+    /// Whether the row stands in for children not listed yet.
+    pub fn is_show_more(&self) -> bool {
+        self.hidden_siblings > 0
+    }
+
     pub fn can_be_dragged(&self) -> bool {
-        !self.is_root()
+        !self.is_root() && !self.is_show_more()
     }
 
     pub fn dragged_entry(&self) -> ProjectTreeDraggedEntry {
@@ -285,6 +310,7 @@ impl ProjectTreeRow {
 
     pub fn can_accept_drop(&self, dragged: &ProjectTreeDraggedEntry) -> bool {
         self.is_directory()
+            && !self.is_show_more()
             && self.path != dragged.path
             && !self.path.starts_with(&dragged.path)
             && dragged.path.parent() != Some(self.path.as_path())
@@ -315,10 +341,12 @@ impl From<&FileKind> for ProjectTreeRowKind {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_project_tree_row(
     row: ProjectTreeRow,
     style: ProjectTreeRowStyle<'_>,
     density: FileTreeDisplayDensity,
+    metadata: FileTreeMetadataDisplay,
     trailing: Option<AnyElement>,
     on_mouse_down: ProjectTreeMouseDownHandler,
     on_click: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
@@ -329,7 +357,18 @@ pub fn render_project_tree_row(
     let row_foreground = row_text_color(&row, theme, file_tree_tokens);
     let metrics = ProjectTreeDensityMetrics::new(density);
     let indentation = px(row.depth as f32 * metrics.indent_px);
-    let min_row_width = project_tree_row_min_width(&row, density);
+    let now = SystemTime::now();
+    let show_more = row.is_show_more();
+    // The root row keeps its action buttons where the columns would be.
+    let metadata_columns = (metadata.columns && !row.is_root() && !show_more)
+        .then(|| render_metadata_columns(&row, now, file_tree_tokens));
+    let metadata_tooltip = (metadata.tooltips && !show_more)
+        .then(|| project_tree_metadata_tooltip(&row, now))
+        .flatten();
+    let mut min_row_width = project_tree_row_min_width(&row, density);
+    if metadata.columns {
+        min_row_width += project_tree_metadata_columns_width(density);
+    }
     let drop_target_row = row.clone();
     let drop_style_row = row.clone();
     let drop_event_row = row.clone();
@@ -374,6 +413,9 @@ pub fn render_project_tree_row(
             on_right_mouse_down(event, window, cx);
         })
         .on_click(on_click)
+        .when_some(metadata_tooltip, |row, tooltip| {
+            row.tooltip(move |_window, cx| cx.new(|_| TextTooltip::new(tooltip.clone())).into())
+        })
         .child(
             div()
                 .w_full()
@@ -404,8 +446,11 @@ pub fn render_project_tree_row(
                     })
                 })
                 .child(render_chevron_slot(&row, file_tree_tokens, metrics))
-                .child(render_icon(&row, theme, file_tree_tokens, metrics))
+                .when(!show_more, |div| {
+                    div.child(render_icon(&row, theme, file_tree_tokens, metrics))
+                })
                 .child(render_filename(&row, theme, file_tree_tokens))
+                .when_some(metadata_columns, |row, columns| row.child(columns))
                 .when_some(trailing, |row, trailing| row.child(trailing))
                 .when_some(render_git_status_lane(&row, theme, metrics), |row, lane| {
                     row.child(lane)
@@ -424,7 +469,7 @@ fn render_chevron_slot(
         .flex()
         .items_center()
         .justify_center()
-        .when(row.is_directory(), |div| {
+        .when(row.is_directory() && !row.is_show_more(), |div| {
             div.child(render_chevron(row, file_tree_tokens, metrics))
         })
         .into_any_element()
@@ -452,7 +497,7 @@ fn render_icon(
         ProjectTreeRowKind::Directory { .. } => VcsIcon::directory(row.is_expanded)
             .size(metrics.icon_size_px)
             .text_color(icon_color),
-        ProjectTreeRowKind::File { extension } => VcsIcon::from_extension(extension.as_deref())
+        ProjectTreeRowKind::File { .. } => VcsIcon::from_file_name(&row.file_name)
             .size(metrics.icon_size_px)
             .text_color(icon_color),
         ProjectTreeRowKind::Symlink { target_exists, .. } => VcsIcon::symlink(*target_exists)
@@ -511,6 +556,8 @@ fn row_text_color(
 ) -> gpui::Hsla {
     if row.is_selected {
         file_tree_tokens.item_text_selected
+    } else if row.is_show_more() {
+        file_tree_tokens.item_text_secondary
     } else if let Some(status) = git_status_for_display(row) {
         match status {
             VcsStatus::Deleted => theme.tokens.editor.vcs_deleted,
@@ -548,6 +595,82 @@ fn chevron_color(row: &ProjectTreeRow, file_tree_tokens: FileTreeTokens) -> gpui
     }
 }
 
+fn render_metadata_columns(
+    row: &ProjectTreeRow,
+    now: SystemTime,
+    file_tree_tokens: FileTreeTokens,
+) -> gpui::AnyElement {
+    let cell = |width: f32, text: Option<String>| {
+        div()
+            .w(px(width))
+            .flex_shrink_0()
+            .flex()
+            .justify_end()
+            .whitespace_nowrap()
+            .children(text)
+    };
+    div()
+        .ml_auto()
+        .flex()
+        .flex_shrink_0()
+        .items_center()
+        .text_xs()
+        .text_color(file_tree_tokens.item_text_secondary)
+        .child(cell(
+            PROJECT_TREE_SIZE_COLUMN_PX,
+            row.size.map(format_file_size),
+        ))
+        .child(cell(
+            PROJECT_TREE_MODIFIED_COLUMN_PX,
+            row.modified.map(|modified| compact_age(modified, now)),
+        ))
+        .into_any_element()
+}
+
+/// Width the metadata columns add to a row.
+pub(crate) fn project_tree_metadata_columns_width(density: FileTreeDisplayDensity) -> f32 {
+    let metrics = ProjectTreeDensityMetrics::new(density);
+    PROJECT_TREE_SIZE_COLUMN_PX + PROJECT_TREE_MODIFIED_COLUMN_PX + metrics.row_gap_px
+}
+
+/// `5m`, `3h`, `2d`: the time since `modified`, short enough for a column.
+fn compact_age(modified: SystemTime, now: SystemTime) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+    const WEEK: u64 = 7 * DAY;
+    const YEAR: u64 = 365 * DAY;
+
+    // Clock skew can put modification times in the future.
+    let seconds = now
+        .duration_since(modified)
+        .map(|age| age.as_secs())
+        .unwrap_or(0);
+    match seconds {
+        0..MINUTE => "now".to_string(),
+        MINUTE..HOUR => format!("{}m", seconds / MINUTE),
+        HOUR..DAY => format!("{}h", seconds / HOUR),
+        DAY..WEEK => format!("{}d", seconds / DAY),
+        WEEK..YEAR => format!("{}w", seconds / WEEK),
+        _ => format!("{}y", seconds / YEAR),
+    }
+}
+
+fn project_tree_metadata_tooltip(row: &ProjectTreeRow, now: SystemTime) -> Option<String> {
+    let modified = row.modified.map(|modified| {
+        let age = match compact_age(modified, now) {
+            age if age == "now" => "just now".to_string(),
+            age => format!("{age} ago"),
+        };
+        let date: chrono::DateTime<chrono::Local> = modified.into();
+        format!("Modified {age} ({})", date.format("%Y-%m-%d %H:%M"))
+    });
+    match (row.size.map(format_file_size), modified) {
+        (Some(size), Some(modified)) => Some(format!("{size} · {modified}")),
+        (size, modified) => size.or(modified),
+    }
+}
+
 fn render_git_status_lane(
     row: &ProjectTreeRow,
     theme: &Theme,
@@ -580,7 +703,7 @@ fn render_git_status_lane(
 
 fn git_status_for_display(row: &ProjectTreeRow) -> Option<VcsStatus> {
     row.vcs_status
-        .filter(|status| should_render_git_status(*status))
+        .filter(|status| !row.is_show_more() && should_render_git_status(*status))
 }
 
 fn should_render_git_status(status: VcsStatus) -> bool {
@@ -664,6 +787,10 @@ fn project_tree_row_min_width_for(
 }
 
 fn display_name(entry: &FileTreeEntry) -> String {
+    if entry.hidden_siblings > 0 {
+        return format!("Show {} more…", entry.hidden_siblings);
+    }
+
     if let Some(segments) = &entry.flattened_segments {
         return segments
             .iter()
@@ -695,6 +822,36 @@ fn display_name(entry: &FileTreeEntry) -> String {
 mod tests {
     use super::*;
     use crate::file_tree::entry::FileTreeEntryId;
+    use std::time::Duration;
+
+    #[test]
+    fn metadata_shows_compact_ages_and_file_sizes() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let ago = |seconds| now - Duration::from_secs(seconds);
+        assert_eq!(compact_age(ago(30), now), "now");
+        assert_eq!(compact_age(ago(5 * 60), now), "5m");
+        assert_eq!(compact_age(ago(3 * 3600), now), "3h");
+        assert_eq!(compact_age(ago(2 * 86_400), now), "2d");
+        assert_eq!(compact_age(ago(21 * 86_400), now), "3w");
+        assert_eq!(compact_age(ago(800 * 86_400), now), "2y");
+        assert_eq!(compact_age(now + Duration::from_secs(60), now), "now");
+
+        let file = FileTreeEntry::new_file(
+            FileTreeEntryId(2),
+            PathBuf::from("/workspace/src/main.rs"),
+            2048,
+            Some(ago(90)),
+        );
+        let row = ProjectTreeRow::from_entry(&file, false, None);
+        let tooltip = project_tree_metadata_tooltip(&row, now).unwrap();
+        assert!(tooltip.starts_with("2.0 KB · Modified 1m ago ("));
+
+        let directory =
+            FileTreeEntry::new_directory(FileTreeEntryId(3), PathBuf::from("/workspace/src"), None);
+        let row = ProjectTreeRow::from_entry(&directory, false, None);
+        assert_eq!(row.size, None);
+        assert_eq!(project_tree_metadata_tooltip(&row, now), None);
+    }
 
     #[test]
     fn row_from_entry_uses_root_directory_name() {
@@ -1026,6 +1183,24 @@ mod tests {
         assert_eq!(symlink_row.primary_action(), ProjectTreeRowAction::OpenFile);
     }
 
+    #[test]
+    fn show_more_row_pages_instead_of_opening_its_entry() {
+        let mut entry = FileTreeEntry::new_directory(
+            FileTreeEntryId(8),
+            PathBuf::from("/workspace/logs/2024"),
+            None,
+        );
+        entry.depth = 2;
+        entry.hidden_siblings = 1_200;
+
+        let row = ProjectTreeRow::from_entry(&entry, false, Some(VcsStatus::Modified));
+
+        assert_eq!(row.file_name, "Show 1200 more…");
+        assert_eq!(row.primary_action(), ProjectTreeRowAction::ShowMore);
+        assert!(!row.can_be_dragged());
+        assert_eq!(git_status_for_display(&row), None);
+    }
+
     #[test]
     fn row_primary_click_event_activates_primary_action() {
         let file = FileTreeEntry::new_file(
@@ -1169,7 +1344,9 @@ mod tests {
                 "Duplicate",
                 "Copy Path",
                 "Copy Relative Path",
-                "Reveal in OS"
+                "Reveal in OS",
+                "Open Terminal Here",
+                "Show History"
             ]
         );
     }
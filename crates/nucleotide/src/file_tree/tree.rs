@@ -50,6 +50,19 @@ pub struct FileTree {
     search_query: Option<String>,
     /// Compiled wildcard matcher for search queries containing `*` or `?`.
     search_glob: Option<GlobMatcher>,
+    /// Entries the running initial scan may still add before giving up.
+    scan_budget: Option<usize>,
+    /// Whether the initial load hit the scan limit and listed the root alone.
+    scan_limit_reached: bool,
+    /// Children listed for directories paged past the configured page size.
+    shown_children: HashMap<PathBuf, usize>,
+}
+
+/// The initial scan found more entries than [`FileTreeConfig::scan_limit`].
+#[derive(Debug, thiserror::Error)]
+#[error("the project has more than {limit} entries")]
+struct ScanLimitReached {
+    limit: usize,
 }
 
 impl FileTree {
@@ -94,6 +107,9 @@ impl FileTree {
             gitignore,
             search_query: None,
             search_glob: None,
+            scan_budget: None,
+            scan_limit_reached: false,
+            shown_children: HashMap::new(),
         }
     }
 
@@ -127,6 +143,28 @@ impl FileTree {
         }
     }
 
+    /// Whether the initial load found more entries than the scan limit and
+    /// listed only the root directory.
+    pub fn scan_limit_reached(&self) -> bool {
+        self.scan_limit_reached
+    }
+
+    /// List another page of `dir`'s children in place of its "show more" row.
+    pub fn show_more_children(&mut self, dir: &Path) {
+        let dir = normalize_tree_path(dir);
+        let page_size = self.config.page_size.max(1);
+        let shown = self.shown_children.entry(dir).or_insert(page_size);
+        *shown = shown.saturating_add(page_size);
+        self.invalidate_cache();
+    }
+
+    /// Whether `path` is currently shown as the "show more" row of its parent.
+    pub fn is_show_more_row(&mut self, path: &Path) -> bool {
+        self.visible_entries()
+            .iter()
+            .any(|entry| entry.hidden_siblings > 0 && entry.path == path)
+    }
+
     /// Get the current normalized search query.
     pub fn search_query(&self) -> Option<&str> {
         self.search_query.as_deref()
@@ -161,7 +199,12 @@ impl FileTree {
 
         let root_path = self.root_path.clone();
         let max_depth = self.config.initial_depth.max(1);
-        let (mut entries, _) = self.scan_directory_recursive(&root_path, 1, max_depth)?;
+        self.scan_budget = self.config.scan_limit;
+        let scanned = self.scan_directory_recursive(&root_path, 1, max_depth);
+        let (mut entries, _) = match self.finish_budgeted_scan(scanned)? {
+            Some(scanned) => scanned,
+            None => self.scan_directory_recursive(&root_path, 1, 1)?,
+        };
         let directory_child_parents = directory_child_parent_paths(&root_path, &entries);
 
         self.entries.clear();
@@ -188,8 +231,12 @@ impl FileTree {
 
         let root_path = self.root_path.clone();
         let max_depth = self.config.initial_depth.max(1);
-        let (mut entries, _) =
-            self.scan_directory_recursive_with_backend(backend, &root_path, 1, max_depth)?;
+        self.scan_budget = self.config.scan_limit;
+        let scanned = self.scan_directory_recursive_with_backend(backend, &root_path, 1, max_depth);
+        let (mut entries, _) = match self.finish_budgeted_scan(scanned)? {
+            Some(scanned) => scanned,
+            None => self.scan_directory_recursive_with_backend(backend, &root_path, 1, 1)?,
+        };
         let directory_child_parents = directory_child_parent_paths(&root_path, &entries);
 
         self.entries.clear();
@@ -217,9 +264,22 @@ impl FileTree {
 
         let root_path = self.root_path.clone();
         let max_depth = self.config.initial_depth.max(1);
-        let (mut entries, _) = self
-            .scan_directory_recursive_with_backend_async(backend, root_path.clone(), 1, max_depth)
-            .await?;
+        self.scan_budget = self.config.scan_limit;
+        let scanned = self
+            .scan_directory_recursive_with_backend_async(
+                backend.clone(),
+                root_path.clone(),
+                1,
+                max_depth,
+            )
+            .await;
+        let (mut entries, _) = match self.finish_budgeted_scan(scanned)? {
+            Some(scanned) => scanned,
+            None => {
+                self.scan_directory_recursive_with_backend_async(backend, root_path.clone(), 1, 1)
+                    .await?
+            }
+        };
         let directory_child_parents = directory_child_parent_paths(&root_path, &entries);
 
         self.entries.clear();
@@ -244,6 +304,8 @@ impl FileTree {
         self.path_to_id.clear();
         self.expanded_dirs.clear();
         self.loading_dirs.clear();
+        self.shown_children.clear();
+        self.scan_limit_reached = false;
         self.next_id = 1;
         self.is_loaded = false;
         self.invalidate_cache();
@@ -350,8 +412,33 @@ impl FileTree {
             .cloned()
             .collect();
         let set_size = visible_children.len();
+        // Search results are never paged so every match stays reachable.
+        let shown = if search_active {
+            set_size
+        } else {
+            self.shown_children
+                .get(parent_path)
+                .copied()
+                .unwrap_or(self.config.page_size.max(1))
+        };
 
         for (index, child_path) in visible_children.iter().enumerate() {
+            if index == shown {
+                if let Some(entry) = self.entries.get(child_path) {
+                    let mut row = entry.clone();
+                    row.depth = depth;
+                    row.level = depth + 1;
+                    row.pos_in_set = index + 1;
+                    row.set_size = set_size;
+                    row.ancestor_paths = Arc::<[PathBuf]>::from(ancestor_paths.clone());
+                    row.is_expanded = false;
+                    row.is_visible = true;
+                    row.hidden_siblings = set_size - index;
+                    result.push(row);
+                }
+                break;
+            }
+
             let ProjectionRow {
                 path,
                 flattened_segments,
@@ -610,6 +697,34 @@ impl FileTree {
     }
 
     /// Invalidate the visible entries cache.
+    /// Ends the initial scan's entry budget. `None` when the scan hit the
+    /// limit, so the caller lists the root directory alone.
+    fn finish_budgeted_scan<T>(&mut self, scanned: Result<T>) -> Result<Option<T>> {
+        self.scan_budget = None;
+        match scanned {
+            Err(error) if error.is::<ScanLimitReached>() => {
+                self.scan_limit_reached = true;
+                Ok(None)
+            }
+            scanned => scanned.map(Some),
+        }
+    }
+
+    /// Counts one scanned entry against the initial scan's budget.
+    fn charge_scan_budget(&mut self) -> Result<()> {
+        match self.scan_budget.as_mut() {
+            Some(0) => Err(ScanLimitReached {
+                limit: self.config.scan_limit.unwrap_or_default(),
+            }
+            .into()),
+            Some(remaining) => {
+                *remaining -= 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
     fn invalidate_cache(&mut self) {
         self.visible_entries_cache = None;
     }
@@ -1121,9 +1236,10 @@ impl FileTree {
             }
 
             immediate_count += 1;
+            self.charge_scan_budget()?;
             let mut file_entry = self.entry_from_metadata(path.clone(), metadata, current_depth);
 
-            if file_entry.is_directory() && current_depth < max_depth {
+            if file_entry.is_directory() && current_depth < max_depth && !file_entry.is_ignored {
                 let (children, child_count) =
                     self.scan_directory_recursive(&path, current_depth + 1, max_depth)?;
                 if let FileKind::Directory {
@@ -1168,9 +1284,10 @@ impl FileTree {
             }
 
             immediate_count += 1;
+            self.charge_scan_budget()?;
             let mut file_entry = self.entry_from_directory_entry(directory_entry, current_depth);
 
-            if file_entry.is_directory() && current_depth < max_depth {
+            if file_entry.is_directory() && current_depth < max_depth && !file_entry.is_ignored {
                 let (children, child_count) = self.scan_directory_recursive_with_backend(
                     backend,
                     &path,
@@ -1223,10 +1340,12 @@ impl FileTree {
                 }
 
                 immediate_count += 1;
+                self.charge_scan_budget()?;
                 let mut file_entry =
                     self.entry_from_directory_entry(directory_entry, current_depth);
 
-                if file_entry.is_directory() && current_depth < max_depth {
+                if file_entry.is_directory() && current_depth < max_depth && !file_entry.is_ignored
+                {
                     let (children, child_count) = self
                         .scan_directory_recursive_with_backend_async(
                             backend.clone(),
@@ -1809,6 +1928,9 @@ mod tests {
             search_mode: FileTreeSearchMode::ExpandMatches,
             density: crate::file_tree::FileTreeDisplayDensity::Default,
             translucent_background: false,
+            metadata: Default::default(),
+            scan_limit: None,
+            page_size: crate::file_tree::DEFAULT_PAGE_SIZE,
         }
     }
 
@@ -2032,6 +2154,100 @@ mod tests {
         assert!(entry.is_ignored);
     }
 
+    #[test]
+    fn initial_load_past_the_scan_limit_lists_only_the_root() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let src = root.join("src");
+        fs::create_dir(&src).unwrap();
+        for index in 0..5 {
+            fs::write(src.join(format!("{index}.rs")), "").unwrap();
+        }
+        fs::write(root.join("README.md"), "# Project\n").unwrap();
+
+        let mut tree = FileTree::new(
+            root.clone(),
+            FileTreeConfig {
+                scan_limit: Some(4),
+                ..config()
+            },
+        );
+        tree.load().unwrap();
+
+        assert!(tree.scan_limit_reached());
+        assert_eq!(
+            visible_paths(&mut tree),
+            vec![root.clone(), src.clone(), root.join("README.md")]
+        );
+        assert!(tree.entry_by_path(&src.join("0.rs")).is_none());
+
+        tree.set_config(config());
+        tree.refresh().unwrap();
+        assert!(!tree.scan_limit_reached());
+        assert!(tree.entry_by_path(&src.join("0.rs")).is_some());
+    }
+
+    #[test]
+    fn initial_load_lists_ignored_directories_without_scanning_them() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let target = root.join("target");
+        fs::create_dir_all(target.join("debug")).unwrap();
+        fs::write(root.join(".gitignore"), "target/\n").unwrap();
+
+        let mut tree = FileTree::new(root, config());
+        tree.load().unwrap();
+
+        let entry = tree.entry_by_path(&target).expect("ignored directory");
+        assert!(entry.is_ignored);
+        assert!(matches!(
+            entry.kind,
+            FileKind::Directory {
+                is_loaded: false,
+                ..
+            }
+        ));
+        assert!(tree.entry_by_path(&target.join("debug")).is_none());
+    }
+
+    #[test]
+    fn large_directories_page_children_behind_a_show_more_row() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        for index in 0..5 {
+            fs::write(root.join(format!("{index}.txt")), "").unwrap();
+        }
+
+        let mut tree = FileTree::new(
+            root.clone(),
+            FileTreeConfig {
+                page_size: 2,
+                ..config()
+            },
+        );
+        tree.load().unwrap();
+
+        let rows = tree.visible_entries();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[3].path, root.join("2.txt"));
+        assert_eq!(rows[3].hidden_siblings, 3);
+        assert!(tree.is_show_more_row(&root.join("2.txt")));
+
+        tree.show_more_children(&root);
+        let rows = tree.visible_entries();
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[3].hidden_siblings, 0);
+        assert_eq!(rows[5].path, root.join("4.txt"));
+        assert_eq!(rows[5].hidden_siblings, 1);
+
+        tree.set_search_query(Some("txt".to_string()));
+        assert!(
+            tree.visible_entries()
+                .iter()
+                .all(|row| row.hidden_siblings == 0)
+        );
+    }
+
     #[test]
     fn initial_load_expands_root_and_exposes_child_directories() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -9,14 +9,15 @@ use crate::file_tree::{
         ProjectTreeContextMenuIntent, ProjectTreeDraggedEntry, ProjectTreeRow,
         ProjectTreeRowAction, ProjectTreeRowEvent, ProjectTreeRowStyle,
         project_tree_entry_min_width, project_tree_entry_min_width_with_vcs,
-        render_project_tree_row,
+        project_tree_metadata_columns_width, render_project_tree_row,
     },
 };
 use gpui::prelude::FluentBuilder;
 use gpui::{
-    App, ClickEvent, Context, EventEmitter, FocusHandle, Focusable, InteractiveElement,
-    IntoElement, MouseButton, MouseDownEvent, ParentElement, Render, ScrollHandle, ScrollStrategy,
-    StatefulInteractiveElement, Styled, UniformListScrollHandle, Window, div, px, uniform_list,
+    App, ClickEvent, Context, DragMoveEvent, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, MouseButton, MouseDownEvent, ParentElement, Render,
+    ScrollHandle, ScrollStrategy, StatefulInteractiveElement, Styled, UniformListScrollHandle,
+    Window, div, px, uniform_list,
 };
 use nucleotide_logging::{debug, error, warn};
 use nucleotide_types::{VcsStatus, scrollbar::SCROLLBAR_THICKNESS};
@@ -409,6 +410,24 @@ fn remote_file_tree_poll_interval(idle_polls: u32) -> std::time::Duration {
     std::time::Duration::from_secs(seconds)
 }
 
+/// Paths to drag out of the window for a drag of `dragged`: the whole
+/// selection when the row is part of it, leaving out entries already inside
+/// a selected directory.
+fn external_drag_paths(dragged: &Path, selected_paths: &BTreeSet<PathBuf>) -> Vec<PathBuf> {
+    if !selected_paths.contains(dragged) {
+        return vec![dragged.to_path_buf()];
+    }
+    selected_paths
+        .iter()
+        .filter(|path| {
+            !selected_paths
+                .iter()
+                .any(|other| other != *path && path.starts_with(other))
+        })
+        .cloned()
+        .collect()
+}
+
 /// File tree view component
 pub struct FileTreeView {
     /// The underlying file tree data
@@ -464,6 +483,8 @@ pub struct FileTreeView {
     presentation_cache: Option<FileTreePresentationCache>,
     presentation_cache_hits: u64,
     presentation_cache_misses: u64,
+    /// File shown in the space-bar preview, which follows the selection.
+    preview_path: Option<PathBuf>,
 }
 
 impl FileTreeView {
@@ -514,6 +535,7 @@ impl FileTreeView {
             presentation_cache: None,
             presentation_cache_hits: 0,
             presentation_cache_misses: 0,
+            preview_path: None,
         };
 
         // Auto-select the first entry if there are any entries
@@ -586,6 +608,7 @@ impl FileTreeView {
             presentation_cache: None,
             presentation_cache_hits: 0,
             presentation_cache_misses: 0,
+            preview_path: None,
         };
 
         instance.start_initial_load(cx);
@@ -1482,6 +1505,9 @@ impl FileTreeView {
             cx.emit(FileTreeEvent::SelectionChanged {
                 path: selected_path,
             });
+            if self.preview_path.is_some() {
+                self.preview_selected_file(cx);
+            }
         }
 
         if set_changed {
@@ -1786,7 +1812,10 @@ impl FileTreeView {
         if let Some(path) = self.selected_path.clone()
             && let Some(entry) = self.tree.entry_by_path(&path)
         {
-            if entry.is_file() {
+            if self.tree.is_show_more_row(&path) {
+                self.show_more(&path, cx);
+            } else if entry.is_file() {
+                self.close_preview(cx);
                 cx.emit(FileTreeEvent::OpenFile {
                     path,
                     focus_editor: false,
@@ -1797,6 +1826,45 @@ impl FileTreeView {
         }
     }
 
+    /// Shows the selected file in the preview popup, or closes the preview
+    /// when it is open. Directories expand and collapse instead.
+    pub fn toggle_preview(&mut self, cx: &mut Context<Self>) {
+        if self.close_preview(cx) {
+            return;
+        }
+        let Some(path) = self.selected_path.clone() else {
+            return;
+        };
+        match self.tree.entry_by_path(&path) {
+            Some(entry) if entry.is_directory() => self.toggle_directory(&path, cx),
+            Some(entry) if entry.is_file() => self.preview_selected_file(cx),
+            _ => {}
+        }
+    }
+
+    /// Closes the preview popup. Returns whether it was open.
+    pub fn close_preview(&mut self, cx: &mut Context<Self>) -> bool {
+        if self.preview_path.take().is_none() {
+            return false;
+        }
+        cx.emit(FileTreeEvent::PreviewChanged { path: None });
+        true
+    }
+
+    /// Points the open preview at the selection, closing it when the
+    /// selection is not a file.
+    fn preview_selected_file(&mut self, cx: &mut Context<Self>) {
+        let path = self
+            .selected_path
+            .clone()
+            .filter(|path| self.tree.entry_by_path(path).is_some_and(|e| e.is_file()));
+        if self.preview_path == path {
+            return;
+        }
+        self.preview_path = path.clone();
+        cx.emit(FileTreeEvent::PreviewChanged { path });
+    }
+
     /// Request a common project-tree operation for the selected entry.
     pub fn request_selected_operation(
         &mut self,
@@ -1824,6 +1892,31 @@ impl FileTreeView {
     }
 
     /// Select next entry
+    /// Hands a row drag that leaves the window to the platform, so the files
+    /// can be dropped into other applications.
+    fn drag_out_of_window(
+        &mut self,
+        event: &DragMoveEvent<ProjectTreeDraggedEntry>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let position = event.event.position;
+        let viewport = window.viewport_size();
+        let inside = position.x >= px(0.0)
+            && position.y >= px(0.0)
+            && position.x < viewport.width
+            && position.y < viewport.height;
+        if inside || !matches!(self.workspace_backend.identity(), WorkspaceIdentity::Local) {
+            return;
+        }
+
+        let paths = external_drag_paths(&event.drag(cx).path, &self.selected_paths);
+        if window.start_external_drag(&paths) {
+            debug!(count = paths.len(), "Dragging files out of the file tree");
+            cx.stop_active_drag(window);
+        }
+    }
+
     pub fn select_next(&mut self, cx: &mut Context<Self>) {
         let entries = self.tree.visible_entries();
         if entries.is_empty() {
@@ -2183,6 +2276,11 @@ impl FileTreeView {
         if let FileTreeEvent::FileSystemChanged { path, kind } = &event {
             debug!(path = ?path, kind = ?kind, "Handling file system event");
 
+            // Saving new ignore patterns retries a load cut short by the limit.
+            if self.tree.scan_limit_reached() && *path == self.tree.root_path().join(".ignore") {
+                self.start_initial_load(cx);
+            }
+
             use crate::file_tree::FileSystemEventKind;
             match kind {
                 FileSystemEventKind::Created => {
@@ -2306,8 +2404,11 @@ impl FileTreeView {
             .into();
         let width_measure_item_index = widest_project_tree_entry_index(entries, density);
         let mut statuses = vcs_statuses.iter().copied();
-        let content_width =
+        let mut content_width =
             project_tree_content_width(entries, density, |_| statuses.next().flatten());
+        if self.tree.config().metadata.columns {
+            content_width += project_tree_metadata_columns_width(density);
+        }
         let cache = FileTreePresentationCache {
             tree_revision: self.tree_revision,
             density,
@@ -2585,9 +2686,89 @@ impl FileTreeView {
                 path,
                 focus_editor: should_focus_editor_for_project_tree_open(click_count),
             }),
+            ProjectTreeRowAction::ShowMore => self.show_more(&path, cx),
         }
     }
 
+    /// Lists the next page of children in place of the "show more" row at
+    /// `path`, which becomes the first newly listed child.
+    fn show_more(&mut self, path: &Path, cx: &mut Context<Self>) {
+        let Some(parent) = path.parent() else {
+            return;
+        };
+        self.tree.show_more_children(parent);
+        self.tree_revision = self.tree_revision.wrapping_add(1);
+        cx.notify();
+    }
+
+    /// Reloads the tree without the entry limit that cut the initial load
+    /// short.
+    fn load_past_scan_limit(&mut self, cx: &mut Context<Self>) {
+        let mut config = self.tree.config().clone();
+        config.scan_limit = None;
+        self.tree.set_config(config);
+        self.start_initial_load(cx);
+    }
+
+    /// Offers to load anyway, open a subfolder instead, or edit the project's
+    /// `.ignore` file when the initial load found too many entries.
+    fn render_scan_limit_warning(&self, cx: &mut Context<Self>) -> impl IntoElement + use<> {
+        let theme = cx.theme().clone();
+        let tokens = &theme.tokens;
+        let limit = self.tree.config().scan_limit.unwrap_or_default();
+        let ignore_file = self.tree.root_path().join(".ignore");
+
+        div()
+            .flex()
+            .flex_col()
+            .flex_none()
+            .gap(tokens.sizes.space_2)
+            .m(tokens.sizes.space_2)
+            .p(tokens.sizes.space_3)
+            .rounded(tokens.sizes.radius_md)
+            .border_1()
+            .border_color(tokens.editor.warning)
+            .text_size(tokens.sizes.text_sm)
+            .text_color(tokens.chrome.text_on_chrome)
+            .child(format!(
+                "This folder has more than {limit} entries, so only its top level is listed."
+            ))
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .gap(tokens.sizes.space_2)
+                    .child(
+                        Button::new("file-tree-load-anyway", "Load Anyway")
+                            .size(ButtonSize::Small)
+                            .on_click(cx.listener(|view, _event, _window, cx| {
+                                view.load_past_scan_limit(cx);
+                                cx.stop_propagation();
+                            })),
+                    )
+                    .child(
+                        Button::new("file-tree-open-subfolder", "Open Subfolder…")
+                            .size(ButtonSize::Small)
+                            .on_click(cx.listener(|_view, _event, _window, cx| {
+                                cx.emit(FileTreeEvent::ChooseRootRequested);
+                                cx.stop_propagation();
+                            })),
+                    )
+                    .child(
+                        Button::new("file-tree-edit-ignore", "Edit Ignore Patterns")
+                            .size(ButtonSize::Small)
+                            .tooltip("Ignored folders are listed but not scanned ahead")
+                            .on_click(cx.listener(move |_view, _event, _window, cx| {
+                                cx.emit(FileTreeEvent::OpenFile {
+                                    path: ignore_file.clone(),
+                                    focus_editor: true,
+                                });
+                                cx.stop_propagation();
+                            })),
+                    ),
+            )
+    }
+
     fn handle_project_tree_row_event(
         &mut self,
         row_event: ProjectTreeRowEvent,
@@ -2862,6 +3043,7 @@ impl FileTreeView {
             row,
             ProjectTreeRowStyle::new(&theme, file_tree_tokens),
             density,
+            self.tree.config().metadata,
             root_actions,
             Arc::new({
                 let left_click_row = left_click_row.clone();
@@ -2926,6 +3108,9 @@ mod tests {
             search_mode: crate::file_tree::FileTreeSearchMode::ExpandMatches,
             density: FileTreeDisplayDensity::Default,
             translucent_background: false,
+            metadata: Default::default(),
+            scan_limit: None,
+            page_size: crate::file_tree::DEFAULT_PAGE_SIZE,
         }
     }
 
@@ -3245,6 +3430,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dragging_a_selected_row_out_takes_the_whole_selection() {
+        let selected = [
+            "/p/src",
+            "/p/src/main.rs",
+            "/p/Cargo.toml",
+            "/p/caf\u{e9} notes.md",
+        ]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect::<BTreeSet<_>>();
+        assert_eq!(
+            external_drag_paths(Path::new("/p/Cargo.toml"), &selected),
+            ["/p/Cargo.toml", "/p/caf\u{e9} notes.md", "/p/src"].map(PathBuf::from)
+        );
+        assert_eq!(
+            external_drag_paths(Path::new("/p/README.md"), &selected),
+            [PathBuf::from("/p/README.md")]
+        );
+    }
+
     #[gpui::test]
     async fn remote_watch_disconnect_restarts_polling_at_fast_interval(cx: &mut TestAppContext) {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -4049,6 +4255,78 @@ mod tests {
         }));
     }
 
+    #[gpui::test]
+    async fn opening_the_show_more_row_lists_the_next_page(cx: &mut TestAppContext) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root_path = temp_dir.path().to_path_buf();
+        for index in 0..3 {
+            std::fs::write(root_path.join(format!("{index}.txt")), "").unwrap();
+        }
+        let show_more_path = root_path.join("1.txt");
+        let config = FileTreeConfig {
+            page_size: 1,
+            ..test_config()
+        };
+
+        let view = cx.new(|cx| FileTreeView::new(root_path.clone(), config, cx));
+        let events = subscribe_file_tree_events(cx, &view);
+
+        view.update(cx, |view, cx| {
+            view.select_path(Some(show_more_path.clone()), cx);
+            view.open_selected(cx);
+        });
+
+        view.update(cx, |view, _cx| {
+            assert!(!view.tree.is_show_more_row(&show_more_path));
+            assert!(view.tree.is_show_more_row(&root_path.join("2.txt")));
+        });
+        assert!(!events.borrow().iter().any(|event| matches!(
+            event,
+            FileTreeEvent::OpenFile { path, .. } if *path == show_more_path
+        )));
+    }
+
+    #[gpui::test]
+    async fn preview_follows_file_selection_and_closes_on_toggle(cx: &mut TestAppContext) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root_path = temp_dir.path().to_path_buf();
+        let readme_path = root_path.join("README.md");
+        let src_path = root_path.join("src");
+        std::fs::write(&readme_path, "readme\n").unwrap();
+        std::fs::create_dir(&src_path).unwrap();
+
+        let view = cx.new(|cx| FileTreeView::new(root_path, test_config(), cx));
+        let events = subscribe_file_tree_events(cx, &view);
+
+        view.update(cx, |view, cx| {
+            view.select_path(Some(readme_path.clone()), cx);
+            view.toggle_preview(cx);
+            assert_eq!(view.preview_path, Some(readme_path.clone()));
+
+            view.select_path(Some(src_path.clone()), cx);
+            assert_eq!(view.preview_path, None);
+
+            view.select_path(Some(readme_path.clone()), cx);
+            view.toggle_preview(cx);
+            view.toggle_preview(cx);
+            assert_eq!(view.preview_path, None);
+        });
+        cx.run_until_parked();
+
+        let previews: Vec<_> = events
+            .borrow()
+            .iter()
+            .filter_map(|event| match event {
+                FileTreeEvent::PreviewChanged { path } => Some(path.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            previews,
+            vec![Some(readme_path.clone()), None, Some(readme_path), None]
+        );
+    }
+
     #[gpui::test]
     async fn search_query_selects_first_visible_match(cx: &mut TestAppContext) {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -4288,6 +4566,11 @@ impl Render for FileTreeView {
                     cx.stop_propagation();
                 }),
             )
+            .on_drag_move(cx.listener(
+                |view, event: &DragMoveEvent<ProjectTreeDraggedEntry>, window, cx| {
+                    view.drag_out_of_window(event, window, cx);
+                },
+            ))
             // Handle FileTree actions
             .on_action(cx.listener(
                 |view, _: &crate::actions::file_tree::SelectNext, _window, cx| {
@@ -4306,7 +4589,9 @@ impl Render for FileTreeView {
             ))
             .on_action(cx.listener(
                 |view, _: &crate::actions::file_tree::ClearSearch, _window, cx| {
-                    view.clear_search_query(cx);
+                    if !view.close_preview(cx) {
+                        view.clear_search_query(cx);
+                    }
                 },
             ))
             .on_action(cx.listener(
@@ -4325,11 +4610,17 @@ impl Render for FileTreeView {
                     if let Some(selected_path) = view.selected_path.clone()
                         && let Some(entry) = view.tree.entry_by_path(&selected_path)
                         && entry.is_directory()
+                        && !view.tree.is_show_more_row(&selected_path)
                     {
                         view.toggle_directory(&selected_path, cx);
                     }
                 },
             ))
+            .on_action(cx.listener(
+                |view, _: &crate::actions::file_tree::TogglePreview, _window, cx| {
+                    view.toggle_preview(cx);
+                },
+            ))
             .on_action(cx.listener(
                 |view, _: &crate::actions::file_tree::OpenFile, _window, cx| {
                     view.open_selected(cx);
@@ -4378,6 +4669,17 @@ impl Render for FileTreeView {
                     view.request_selected_operation(ProjectTreeContextMenuIntent::RevealInOs, cx);
                 },
             ))
+            .on_action(cx.listener(
+                |view, _: &crate::actions::file_tree::OpenTerminalHere, _window, cx| {
+                    view.request_selected_operation(
+                        ProjectTreeContextMenuIntent::OpenTerminalHere,
+                        cx,
+                    );
+                },
+            ))
+            .when(self.tree.scan_limit_reached(), |tree| {
+                tree.child(self.render_scan_limit_warning(cx))
+            })
             .child(
                 // Zed-style: wrap the list row in a flex_1 container with min_h(0)
                 div()
@@ -0,0 +1,602 @@
+// ABOUTME: GUI find bar for the focused document with replace, match count and search toggles
+// ABOUTME: Builds Helix search patterns so `n`/`N` keep working with the same query afterwards
+
+use std::ops::Range;
+
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    App, AppContext as _, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, KeyBinding, ParentElement, Render, SharedString, Styled,
+    Window, div, px,
+};
+use helix_core::{Rope, RopeSlice, Transaction};
+use helix_stdx::rope::{self, RopeSliceExt};
+use nucleotide_ui::actions::find_bar::{
+    Dismiss, FindNext, FindPrevious, ReplaceAll, ReplaceNext, ToggleCaseSensitive, ToggleRegex,
+    ToggleWholeWord,
+};
+use nucleotide_ui::actions::text_input::SelectAll;
+use nucleotide_ui::{
+    Button, ButtonSize, ButtonVariant, TextInput, TextInputEvent, TextInputFocusStyle,
+    ThemedContext, Tooltipped,
+};
+
+const FIND_BAR_CONTEXT: &str = "FindBar";
+/// Matches counted in one document; larger counts are shown as a lower bound.
+pub const MAX_FIND_MATCHES: usize = 10_000;
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("shift-enter", FindPrevious, Some(FIND_BAR_CONTEXT)),
+        KeyBinding::new("escape", Dismiss, Some(FIND_BAR_CONTEXT)),
+        KeyBinding::new("alt-c", ToggleCaseSensitive, Some(FIND_BAR_CONTEXT)),
+        KeyBinding::new("alt-w", ToggleWholeWord, Some(FIND_BAR_CONTEXT)),
+        KeyBinding::new("alt-r", ToggleRegex, Some(FIND_BAR_CONTEXT)),
+        KeyBinding::new("secondary-alt-enter", ReplaceAll, Some(FIND_BAR_CONTEXT)),
+    ]);
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FindOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub regex: bool,
+}
+
+/// Helix search pattern for `query`, with the options inlined so the search
+/// register repeats exactly this search regardless of `smart-case`.
+pub fn search_pattern(query: &str, options: FindOptions) -> String {
+    let body = if options.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    let body = if options.whole_word {
+        format!(r"\b(?:{body})\b")
+    } else {
+        body
+    };
+    let flags = if options.case_sensitive {
+        "(?-i)"
+    } else {
+        "(?i)"
+    };
+    format!("{flags}{body}")
+}
+
+pub fn compile(query: &str, options: FindOptions) -> Result<rope::Regex, String> {
+    rope::RegexBuilder::new()
+        .syntax(rope::Config::new().multi_line(true))
+        .build(&search_pattern(query, options))
+        .map_err(|err| err.to_string())
+}
+
+/// Non-empty matches as char ranges, capped at [`MAX_FIND_MATCHES`].
+pub fn find_matches(text: RopeSlice<'_>, regex: &rope::Regex) -> Vec<Range<usize>> {
+    regex
+        .find_iter(text.regex_input())
+        .filter(|mat| mat.start() != mat.end())
+        .take(MAX_FIND_MATCHES)
+        .map(|mat| text.byte_to_char(mat.start())..text.byte_to_char(mat.end()))
+        .collect()
+}
+
+/// Index of the match that is exactly `selection`, if any.
+pub fn current_match(matches: &[Range<usize>], selection: &Range<usize>) -> Option<usize> {
+    matches.iter().position(|mat| mat == selection)
+}
+
+/// First match starting at or after `from`.
+pub fn next_match(matches: &[Range<usize>], from: usize, wrap: bool) -> Option<usize> {
+    matches
+        .iter()
+        .position(|mat| mat.start >= from)
+        .or_else(|| (wrap && !matches.is_empty()).then_some(0))
+}
+
+/// Last match starting before `before`.
+pub fn previous_match(matches: &[Range<usize>], before: usize, wrap: bool) -> Option<usize> {
+    matches
+        .iter()
+        .rposition(|mat| mat.start < before)
+        .or_else(|| matches.len().checked_sub(1).filter(|_| wrap))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindStep {
+    /// Re-run from where the bar was opened, as the query is typed
+    Incremental,
+    Next,
+    Previous,
+}
+
+/// Match to select for `step` given the primary selection.
+pub fn step_target(
+    matches: &[Range<usize>],
+    selection: &Range<usize>,
+    origin: usize,
+    step: FindStep,
+    wrap: bool,
+) -> Option<usize> {
+    match step {
+        FindStep::Incremental => next_match(matches, origin, wrap),
+        FindStep::Next => {
+            let from = if current_match(matches, selection).is_some() {
+                selection.end
+            } else {
+                selection.start
+            };
+            next_match(matches, from, wrap)
+        }
+        FindStep::Previous => previous_match(matches, selection.start, wrap),
+    }
+}
+
+/// Replaces every match with `replacement` in one transaction.
+pub fn replace_all_transaction(
+    text: &Rope,
+    matches: &[Range<usize>],
+    replacement: &str,
+) -> Transaction {
+    Transaction::change(
+        text,
+        matches
+            .iter()
+            .map(|mat| (mat.start, mat.end, Some(replacement.into()))),
+    )
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FindStatus {
+    #[default]
+    Idle,
+    Matches {
+        current: Option<usize>,
+        total: usize,
+    },
+    InvalidPattern,
+}
+
+impl FindStatus {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Idle => String::new(),
+            Self::Matches { total: 0, .. } => "No results".to_string(),
+            Self::Matches { current, total } => {
+                let total = if *total >= MAX_FIND_MATCHES {
+                    format!("{MAX_FIND_MATCHES}+")
+                } else {
+                    total.to_string()
+                };
+                match current {
+                    Some(index) => format!("{} of {total}", index + 1),
+                    None if total == "1" => "1 match".to_string(),
+                    None => format!("{total} matches"),
+                }
+            }
+            Self::InvalidPattern => "Invalid regex".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindBarEvent {
+    QueryChanged,
+    FindNext,
+    FindPrevious,
+    ReplaceNext,
+    ReplaceAll,
+    Dismissed,
+}
+
+pub struct FindBar {
+    query_input: Entity<TextInput>,
+    replace_input: Entity<TextInput>,
+    options: FindOptions,
+    status: FindStatus,
+}
+
+impl FindBar {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let query_input = cx.new(|cx| {
+            TextInput::new("find-bar-query", cx)
+                .size(nucleotide_ui::InputSize::Small)
+                .focus_style(TextInputFocusStyle::Chrome)
+                .placeholder("Find")
+        });
+        cx.subscribe(&query_input, Self::handle_query_input_event)
+            .detach();
+        let replace_input = cx.new(|cx| {
+            TextInput::new("find-bar-replace", cx)
+                .size(nucleotide_ui::InputSize::Small)
+                .focus_style(TextInputFocusStyle::Chrome)
+                .placeholder("Replace")
+        });
+        cx.subscribe(&replace_input, Self::handle_replace_input_event)
+            .detach();
+
+        Self {
+            query_input,
+            replace_input,
+            options: FindOptions::default(),
+            status: FindStatus::Idle,
+        }
+    }
+
+    pub fn query(&self, cx: &App) -> String {
+        self.query_input.read(cx).value().to_string()
+    }
+
+    pub fn replacement(&self, cx: &App) -> String {
+        self.replace_input.read(cx).value().to_string()
+    }
+
+    pub fn options(&self) -> FindOptions {
+        self.options
+    }
+
+    pub fn set_status(&mut self, status: FindStatus, cx: &mut Context<Self>) {
+        if self.status != status {
+            self.status = status;
+            cx.notify();
+        }
+    }
+
+    /// Replaces the query without re-running the search.
+    pub fn set_query(&mut self, query: String, cx: &mut Context<Self>) {
+        self.query_input
+            .update(cx, |input, cx| input.set_value_silent(query, cx));
+    }
+
+    /// Focuses the query field with its text selected so typing replaces it.
+    pub fn focus_query(&self, window: &mut Window, cx: &mut App) {
+        self.query_input.update(cx, |input, cx| {
+            input.select_all(&SelectAll, window, cx);
+        });
+        window.focus(&self.query_input.focus_handle(cx), cx);
+    }
+
+    fn handle_query_input_event(
+        &mut self,
+        _input: Entity<TextInput>,
+        event: &TextInputEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            TextInputEvent::Changed(_) => cx.emit(FindBarEvent::QueryChanged),
+            TextInputEvent::Submitted(_) => cx.emit(FindBarEvent::FindNext),
+            TextInputEvent::Cancelled => cx.emit(FindBarEvent::Dismissed),
+        }
+    }
+
+    fn handle_replace_input_event(
+        &mut self,
+        _input: Entity<TextInput>,
+        event: &TextInputEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            TextInputEvent::Changed(_) => {}
+            TextInputEvent::Submitted(_) => cx.emit(FindBarEvent::ReplaceNext),
+            TextInputEvent::Cancelled => cx.emit(FindBarEvent::Dismissed),
+        }
+    }
+
+    fn toggle_option(&mut self, toggle: impl FnOnce(&mut FindOptions), cx: &mut Context<Self>) {
+        toggle(&mut self.options);
+        cx.emit(FindBarEvent::QueryChanged);
+        cx.notify();
+    }
+
+    fn render_toggle(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        tooltip: &'static str,
+        active: bool,
+        toggle: fn(&mut FindOptions),
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        Button::new(id, label)
+            .variant(if active {
+                ButtonVariant::Secondary
+            } else {
+                ButtonVariant::Ghost
+            })
+            .size(ButtonSize::ExtraSmall)
+            .tooltip(tooltip)
+            .aria_label(tooltip)
+            .activate_on_mouse_down()
+            .on_click(cx.listener(move |this, _event, _window, cx| {
+                this.toggle_option(toggle, cx);
+                cx.stop_propagation();
+            }))
+    }
+
+    fn render_action_button(
+        &self,
+        id: &'static str,
+        icon: &'static str,
+        tooltip: &'static str,
+        event: FindBarEvent,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        Button::icon_only(id, icon)
+            .variant(ButtonVariant::Ghost)
+            .size(ButtonSize::ExtraSmall)
+            .tooltip(tooltip)
+            .aria_label(tooltip)
+            .activate_on_mouse_down()
+            .on_click(cx.listener(move |_this, _event, _window, cx| {
+                cx.emit(event);
+                cx.stop_propagation();
+            }))
+    }
+
+    fn render_text_button(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        tooltip: &'static str,
+        event: FindBarEvent,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        Button::new(id, label)
+            .variant(ButtonVariant::Ghost)
+            .size(ButtonSize::ExtraSmall)
+            .tooltip(tooltip)
+            .activate_on_mouse_down()
+            .on_click(cx.listener(move |_this, _event, _window, cx| {
+                cx.emit(event);
+                cx.stop_propagation();
+            }))
+    }
+}
+
+impl Focusable for FindBar {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.query_input.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<FindBarEvent> for FindBar {}
+
+impl Render for FindBar {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let tokens = cx.theme().tokens;
+        let status_color = if self.status == FindStatus::InvalidPattern {
+            tokens.editor.error
+        } else {
+            tokens.chrome.text_chrome_secondary
+        };
+        let options = self.options;
+
+        div()
+            .id("find-bar")
+            .key_context(FIND_BAR_CONTEXT)
+            .on_action(cx.listener(|_this, _: &FindNext, _window, cx| {
+                cx.emit(FindBarEvent::FindNext);
+            }))
+            .on_action(cx.listener(|_this, _: &FindPrevious, _window, cx| {
+                cx.emit(FindBarEvent::FindPrevious);
+            }))
+            .on_action(cx.listener(|_this, _: &ReplaceNext, _window, cx| {
+                cx.emit(FindBarEvent::ReplaceNext);
+            }))
+            .on_action(cx.listener(|_this, _: &ReplaceAll, _window, cx| {
+                cx.emit(FindBarEvent::ReplaceAll);
+            }))
+            .on_action(cx.listener(|_this, _: &Dismiss, _window, cx| {
+                cx.emit(FindBarEvent::Dismissed);
+            }))
+            .on_action(cx.listener(|this, _: &ToggleCaseSensitive, _window, cx| {
+                this.toggle_option(|options| options.case_sensitive ^= true, cx);
+            }))
+            .on_action(cx.listener(|this, _: &ToggleWholeWord, _window, cx| {
+                this.toggle_option(|options| options.whole_word ^= true, cx);
+            }))
+            .on_action(cx.listener(|this, _: &ToggleRegex, _window, cx| {
+                this.toggle_option(|options| options.regex ^= true, cx);
+            }))
+            // Keep clicks inside the bar from refocusing the editor.
+            .on_mouse_down(gpui::MouseButton::Left, |_event, _window, cx| {
+                cx.stop_propagation();
+            })
+            .flex()
+            .flex_col()
+            .gap(tokens.sizes.space_1)
+            .p(tokens.sizes.space_2)
+            .w(px(440.0))
+            .rounded(tokens.sizes.radius_md)
+            .border_1()
+            .border_color(tokens.chrome.border_default)
+            .bg(tokens.chrome.surface)
+            .shadow(vec![tokens.chrome.shadow_md.to_box_shadow(false)])
+            .text_size(tokens.sizes.text_sm)
+            .text_color(tokens.chrome.text_on_chrome)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(tokens.sizes.space_1)
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w(px(0.0))
+                            .child(self.query_input.clone()),
+                    )
+                    .child(self.render_toggle(
+                        "find-bar-case",
+                        "Aa",
+                        "Match Case (Alt+C)",
+                        options.case_sensitive,
+                        |options| options.case_sensitive ^= true,
+                        cx,
+                    ))
+                    .child(self.render_toggle(
+                        "find-bar-word",
+                        "ab",
+                        "Match Whole Word (Alt+W)",
+                        options.whole_word,
+                        |options| options.whole_word ^= true,
+                        cx,
+                    ))
+                    .child(self.render_toggle(
+                        "find-bar-regex",
+                        ".*",
+                        "Use Regular Expression (Alt+R)",
+                        options.regex,
+                        |options| options.regex ^= true,
+                        cx,
+                    ))
+                    .child(
+                        div()
+                            .min_w(px(72.0))
+                            .text_color(status_color)
+                            .text_size(tokens.sizes.text_xs)
+                            .when(self.status != FindStatus::Idle, |this| {
+                                this.child(SharedString::from(self.status.label()))
+                            }),
+                    )
+                    .child(self.render_action_button(
+                        "find-bar-previous",
+                        "icons/chevron-up.svg",
+                        "Previous Match (Shift+Enter)",
+                        FindBarEvent::FindPrevious,
+                        cx,
+                    ))
+                    .child(self.render_action_button(
+                        "find-bar-next",
+                        "icons/chevron-down.svg",
+                        "Next Match (Enter)",
+                        FindBarEvent::FindNext,
+                        cx,
+                    ))
+                    .child(self.render_action_button(
+                        "find-bar-close",
+                        "icons/close.svg",
+                        "Close (Escape)",
+                        FindBarEvent::Dismissed,
+                        cx,
+                    )),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(tokens.sizes.space_1)
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w(px(0.0))
+                            .child(self.replace_input.clone()),
+                    )
+                    .child(self.render_text_button(
+                        "find-bar-replace-next",
+                        "Replace",
+                        "Replace (Enter in the replace field)",
+                        FindBarEvent::ReplaceNext,
+                        cx,
+                    ))
+                    .child(self.render_text_button(
+                        "find-bar-replace-all",
+                        "All",
+                        "Replace All",
+                        FindBarEvent::ReplaceAll,
+                        cx,
+                    )),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches_for(text: &str, query: &str, options: FindOptions) -> Vec<Range<usize>> {
+        let rope = Rope::from(text);
+        find_matches(rope.slice(..), &compile(query, options).unwrap())
+    }
+
+    #[test]
+    fn options_are_encoded_in_the_search_pattern() {
+        let plain = FindOptions::default();
+        assert_eq!(search_pattern("a.b", plain), r"(?i)a\.b");
+        assert_eq!(
+            search_pattern(
+                "a.b",
+                FindOptions {
+                    case_sensitive: true,
+                    whole_word: true,
+                    regex: true,
+                }
+            ),
+            r"(?-i)\b(?:a.b)\b"
+        );
+
+        assert_eq!(matches_for("Foo foo food", "foo", plain).len(), 3);
+        let whole_case = FindOptions {
+            case_sensitive: true,
+            whole_word: true,
+            regex: false,
+        };
+        assert_eq!(matches_for("Foo foo food", "foo", whole_case), vec![4..7]);
+        assert!(
+            compile(
+                "(",
+                FindOptions {
+                    regex: true,
+                    ..plain
+                }
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn navigation_wraps_around_the_document() {
+        let matches = vec![2..4, 10..12, 20..22];
+
+        assert_eq!(current_match(&matches, &(10..12)), Some(1));
+        assert_eq!(current_match(&matches, &(10..11)), None);
+        assert_eq!(next_match(&matches, 4, true), Some(1));
+        assert_eq!(next_match(&matches, 21, true), Some(0));
+        assert_eq!(next_match(&matches, 21, false), None);
+        assert_eq!(previous_match(&matches, 10, true), Some(0));
+        assert_eq!(previous_match(&matches, 2, true), Some(2));
+        assert_eq!(previous_match(&matches, 2, false), None);
+        assert_eq!(next_match(&[], 0, true), None);
+        assert_eq!(previous_match(&[], 0, true), None);
+
+        let step = |selection, step| step_target(&matches, &selection, 0, step, true);
+        assert_eq!(step(10..12, FindStep::Next), Some(2));
+        assert_eq!(step(10..11, FindStep::Next), Some(1));
+        assert_eq!(step(10..12, FindStep::Previous), Some(0));
+        assert_eq!(step(10..12, FindStep::Incremental), Some(0));
+    }
+
+    #[test]
+    fn replace_all_rewrites_every_match() {
+        let mut rope = Rope::from("let a = a + a;");
+        let whole_word = FindOptions {
+            whole_word: true,
+            ..FindOptions::default()
+        };
+        let matches = find_matches(rope.slice(..), &compile("a", whole_word).unwrap());
+        let transaction = replace_all_transaction(&rope, &matches, "count");
+
+        assert!(transaction.apply(&mut rope));
+        assert_eq!(rope.to_string(), "let count = count + count;");
+    }
+
+    #[test]
+    fn status_labels_describe_the_match_count() {
+        assert_eq!(FindStatus::Idle.label(), "");
+        let label = |current, total| FindStatus::Matches { current, total }.label();
+        assert_eq!(label(None, 0), "No results");
+        assert_eq!(label(None, 1), "1 match");
+        assert_eq!(label(Some(2), 12), "3 of 12");
+        assert_eq!(label(None, MAX_FIND_MATCHES), "10000+ matches");
+        assert_eq!(FindStatus::InvalidPattern.label(), "Invalid regex");
+    }
+}
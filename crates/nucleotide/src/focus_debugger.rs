@@ -0,0 +1,176 @@
+// ABOUTME: Developer overlay describing where keyboard focus is and how it got there
+// ABOUTME: Lists focused roles, the key context chain, popup focus and recent input context changes
+
+use std::time::Instant;
+
+use nucleotide_ui::FocusRole;
+
+use crate::input_coordinator::{ContextTransition, InputContext};
+
+/// How a registered focus role relates to the focused element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoleFocus {
+    Focused,
+    /// A descendant of the role's handle has focus
+    ContainsFocus,
+    Unfocused,
+}
+
+/// Focus state gathered from the window and coordinators for one frame.
+#[derive(Debug, Clone)]
+pub struct FocusSnapshot {
+    /// Whether any element in the window has focus
+    pub has_focus: bool,
+    pub roles: Vec<(FocusRole, RoleFocus)>,
+    /// Key contexts from the window root down to the focused element
+    pub key_contexts: Vec<String>,
+    pub input_context: InputContext,
+    pub input_context_stack: Vec<InputContext>,
+    /// Open popups, bottom first
+    pub popups: Vec<&'static str>,
+    pub popup_focus_owner: Option<&'static str>,
+    pub transitions: Vec<ContextTransition>,
+}
+
+/// Lines of the focus debugger overlay, newest transition first.
+pub fn focus_debug_lines(snapshot: &FocusSnapshot, now: Instant) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    let focused = snapshot
+        .roles
+        .iter()
+        .filter(|(_, focus)| *focus != RoleFocus::Unfocused)
+        .map(|(role, focus)| match focus {
+            RoleFocus::Focused => format!("{role:?}"),
+            _ => format!("{role:?} (descendant)"),
+        })
+        .collect::<Vec<_>>();
+    let focused = match (snapshot.has_focus, focused.is_empty()) {
+        (false, _) => "nothing".to_string(),
+        (true, true) => "unregistered element".to_string(),
+        (true, false) => focused.join(", "),
+    };
+    lines.push(format!("focus     {focused}"));
+
+    let registered = snapshot
+        .roles
+        .iter()
+        .map(|(role, _)| format!("{role:?}"))
+        .collect::<Vec<_>>();
+    lines.push(format!("roles     {}", or_none(registered.join(" "))));
+
+    lines.push(format!(
+        "keys      {}",
+        or_none(snapshot.key_contexts.join(" > "))
+    ));
+
+    let mut input = snapshot
+        .input_context_stack
+        .iter()
+        .map(|context| format!("{context:?}"))
+        .collect::<Vec<_>>();
+    input.push(format!("{:?}", snapshot.input_context));
+    lines.push(format!("input     {}", input.join(" > ")));
+
+    let popups = snapshot
+        .popups
+        .iter()
+        .map(|popup| {
+            if Some(*popup) == snapshot.popup_focus_owner {
+                format!("{popup}*")
+            } else {
+                popup.to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+    lines.push(format!("popups    {}", or_none(popups.join(" < "))));
+
+    for transition in snapshot.transitions.iter().rev() {
+        let ago = now.saturating_duration_since(transition.at).as_secs_f32();
+        lines.push(format!(
+            "{ago:>6.1}s  {:?} -> {:?}",
+            transition.from, transition.to
+        ));
+    }
+
+    lines
+}
+
+fn or_none(text: String) -> String {
+    if text.is_empty() {
+        "none".to_string()
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn lines_describe_focus_context_and_transitions() {
+        let now = Instant::now();
+        let snapshot = FocusSnapshot {
+            has_focus: true,
+            roles: vec![
+                (FocusRole::Editor, RoleFocus::ContainsFocus),
+                (FocusRole::Picker, RoleFocus::Focused),
+                (FocusRole::FileTree, RoleFocus::Unfocused),
+            ],
+            key_contexts: vec!["Workspace".into(), "Picker".into()],
+            input_context: InputContext::Picker,
+            input_context_stack: vec![InputContext::Normal],
+            popups: vec!["tab-context-menu", "picker"],
+            popup_focus_owner: Some("picker"),
+            transitions: vec![
+                ContextTransition {
+                    from: InputContext::Normal,
+                    to: InputContext::Completion,
+                    at: now - Duration::from_secs(3),
+                },
+                ContextTransition {
+                    from: InputContext::Completion,
+                    to: InputContext::Picker,
+                    at: now - Duration::from_millis(500),
+                },
+            ],
+        };
+
+        assert_eq!(
+            focus_debug_lines(&snapshot, now),
+            [
+                "focus     Editor (descendant), Picker",
+                "roles     Editor Picker FileTree",
+                "keys      Workspace > Picker",
+                "input     Normal > Picker",
+                "popups    tab-context-menu < picker*",
+                "   0.5s  Completion -> Picker",
+                "   3.0s  Normal -> Completion",
+            ]
+        );
+
+        let unfocused = FocusSnapshot {
+            has_focus: false,
+            roles: Vec::new(),
+            key_contexts: Vec::new(),
+            input_context: InputContext::Normal,
+            input_context_stack: Vec::new(),
+            popups: Vec::new(),
+            popup_focus_owner: None,
+            transitions: Vec::new(),
+        };
+        assert_eq!(
+            focus_debug_lines(&unfocused, now),
+            [
+                "focus     nothing",
+                "roles     none",
+                "keys      none",
+                "input     Normal",
+                "popups    none",
+            ]
+        );
+    }
+}
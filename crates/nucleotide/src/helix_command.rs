@@ -27,6 +27,20 @@ pub(crate) fn execute_command_line(
     }
 }
 
+/// The primary name of the typable command `command` invokes, resolving aliases.
+pub(crate) fn typable_command_name(command: &str) -> Option<&'static str> {
+    let (cmd_name, _, _) = helix_core::command_line::split(command);
+    TYPABLE_COMMAND_MAP
+        .get(cmd_name)
+        .copied()
+        .or_else(|| {
+            TYPABLE_COMMAND_LIST
+                .iter()
+                .find(|cmd| cmd.aliases.contains(&cmd_name))
+        })
+        .map(|cmd| cmd.name)
+}
+
 #[derive(Debug)]
 enum CommandLookup {
     Empty,
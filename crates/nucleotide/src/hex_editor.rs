@@ -0,0 +1,897 @@
+// ABOUTME: Hex editor view for binary files with byte and ASCII panes
+// ABOUTME: Insert/overwrite editing, selection, copy as hex or bytes, undo and save requests
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    App, ClipboardItem, Context, EventEmitter, FocusHandle, Focusable, InteractiveElement,
+    IntoElement, KeyDownEvent, MouseButton, MouseDownEvent, ParentElement, Render, ScrollStrategy,
+    SharedString, Styled, UniformListScrollHandle, Window, div, px, uniform_list,
+};
+use nucleotide_ui::ThemedContext;
+
+/// Command opening the focused file in the hex editor.
+pub const HEX_EDIT_COMMAND: &str = "hex-edit";
+
+pub const BYTES_PER_ROW: usize = 16;
+/// Files larger than this are not loaded into the hex editor.
+pub const MAX_HEX_EDITOR_BYTES: u64 = 64 * 1024 * 1024;
+/// Bytes inspected when deciding whether a file is binary.
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+const PAGE_ROWS: usize = 32;
+const HEX_EDITOR_CONTEXT: &str = "HexEditor";
+
+/// Read `path` for the hex editor, returning its bytes and modification time.
+pub async fn read_hex_file(path: PathBuf) -> anyhow::Result<(Vec<u8>, SystemTime)> {
+    let metadata = tokio::fs::metadata(&path).await?;
+    if metadata.len() > MAX_HEX_EDITOR_BYTES {
+        anyhow::bail!(
+            "{} is too large for the hex editor ({} MiB limit)",
+            path.display(),
+            MAX_HEX_EDITOR_BYTES / (1024 * 1024)
+        );
+    }
+    let bytes = tokio::fs::read(&path).await?;
+    let modified = metadata.modified().unwrap_or_else(|_| SystemTime::now());
+    Ok((bytes, modified))
+}
+
+/// Whether `path` looks like a binary file: a NUL byte near the start, as
+/// Git and most editors decide it.
+pub fn is_binary_file(path: &Path) -> bool {
+    use std::io::Read;
+
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut head = Vec::with_capacity(BINARY_SNIFF_BYTES);
+    if file
+        .take(BINARY_SNIFF_BYTES as u64)
+        .read_to_end(&mut head)
+        .is_err()
+    {
+        return false;
+    }
+    head.contains(&0)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HexPane {
+    #[default]
+    Hex,
+    Ascii,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HexEditMode {
+    /// Typing replaces bytes and keeps the file length
+    #[default]
+    Overwrite,
+    Insert,
+}
+
+/// One reversible change: `removed` at `offset` replaced by `inserted`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HexSplice {
+    offset: usize,
+    removed: Vec<u8>,
+    inserted: Vec<u8>,
+    revision_before: u64,
+    revision_after: u64,
+}
+
+/// Bytes being edited with the cursor, selection and undo history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexBuffer {
+    bytes: Vec<u8>,
+    /// Byte offset of the cursor; `len()` is the append position
+    cursor: usize,
+    anchor: Option<usize>,
+    pane: HexPane,
+    mode: HexEditMode,
+    /// The high nibble of the byte at the cursor was just typed
+    half_byte: bool,
+    undo: Vec<HexSplice>,
+    redo: Vec<HexSplice>,
+    revision: u64,
+    next_revision: u64,
+    saved_revision: u64,
+}
+
+impl HexBuffer {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            cursor: 0,
+            anchor: None,
+            pane: HexPane::Hex,
+            mode: HexEditMode::Overwrite,
+            half_byte: false,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            revision: 0,
+            next_revision: 1,
+            saved_revision: 0,
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn pane(&self) -> HexPane {
+        self.pane
+    }
+
+    pub fn mode(&self) -> HexEditMode {
+        self.mode
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.revision != self.saved_revision
+    }
+
+    pub fn mark_saved(&mut self, revision: u64) {
+        self.saved_revision = revision;
+    }
+
+    /// Rows shown, including the row holding the append position.
+    pub fn row_count(&self) -> usize {
+        self.bytes.len() / BYTES_PER_ROW + 1
+    }
+
+    /// Selected bytes, including the byte under the cursor.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        let start = anchor.min(self.cursor);
+        let end = (anchor.max(self.cursor) + 1).min(self.bytes.len());
+        (start < end).then_some(start..end)
+    }
+
+    pub fn toggle_pane(&mut self) {
+        self.pane = match self.pane {
+            HexPane::Hex => HexPane::Ascii,
+            HexPane::Ascii => HexPane::Hex,
+        };
+        self.half_byte = false;
+    }
+
+    pub fn set_pane(&mut self, pane: HexPane) {
+        if self.pane != pane {
+            self.toggle_pane();
+        }
+    }
+
+    pub fn toggle_mode(&mut self) {
+        self.mode = match self.mode {
+            HexEditMode::Overwrite => HexEditMode::Insert,
+            HexEditMode::Insert => HexEditMode::Overwrite,
+        };
+        self.half_byte = false;
+    }
+
+    pub fn move_to(&mut self, offset: usize, extend: bool) {
+        if extend {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = offset.min(self.bytes.len());
+        self.half_byte = false;
+    }
+
+    pub fn move_by(&mut self, delta: isize, extend: bool) {
+        self.move_to(self.cursor.saturating_add_signed(delta), extend);
+    }
+
+    pub fn select_all(&mut self) {
+        if self.bytes.is_empty() {
+            return;
+        }
+        self.anchor = Some(0);
+        self.cursor = self.bytes.len() - 1;
+        self.half_byte = false;
+    }
+
+    /// Type one hex digit in the byte pane. The first digit of a byte sets
+    /// its high nibble, the second its low nibble and moves on.
+    pub fn type_hex_digit(&mut self, digit: u8) {
+        debug_assert!(digit < 16);
+        if let Some(selection) = self.selection() {
+            let start = selection.start;
+            self.splice(selection, vec![digit << 4]);
+            self.cursor = start;
+            self.half_byte = true;
+            return;
+        }
+
+        let cursor = self.cursor;
+        if self.half_byte {
+            let byte = (self.bytes[cursor] & 0xf0) | digit;
+            self.splice(cursor..cursor + 1, vec![byte]);
+            self.cursor = cursor + 1;
+            self.half_byte = false;
+        } else if self.mode == HexEditMode::Overwrite && cursor < self.bytes.len() {
+            let byte = (digit << 4) | (self.bytes[cursor] & 0x0f);
+            self.splice(cursor..cursor + 1, vec![byte]);
+            self.half_byte = true;
+        } else {
+            self.splice(cursor..cursor, vec![digit << 4]);
+            self.half_byte = true;
+        }
+    }
+
+    /// Type a byte in the ASCII pane.
+    pub fn type_byte(&mut self, byte: u8) {
+        self.write_bytes(vec![byte]);
+    }
+
+    /// Paste `bytes` over the selection, or at the cursor following the edit
+    /// mode.
+    pub fn write_bytes(&mut self, bytes: Vec<u8>) {
+        if bytes.is_empty() {
+            return;
+        }
+        let len = bytes.len();
+        let range = match self.selection() {
+            Some(selection) => selection,
+            None if self.mode == HexEditMode::Overwrite => {
+                self.cursor..(self.cursor + len).min(self.bytes.len())
+            }
+            None => self.cursor..self.cursor,
+        };
+        let start = range.start;
+        self.splice(range, bytes);
+        self.cursor = start + len;
+        self.half_byte = false;
+    }
+
+    /// Delete the selection or the byte before the cursor. In overwrite mode
+    /// this only moves back, keeping the file length.
+    pub fn backspace(&mut self) {
+        if let Some(selection) = self.selection() {
+            self.delete_range(selection);
+        } else if self.mode == HexEditMode::Overwrite {
+            self.move_by(-1, false);
+        } else if self.cursor > 0 {
+            self.delete_range(self.cursor - 1..self.cursor);
+        }
+    }
+
+    /// Delete the selection or the byte under the cursor.
+    pub fn delete(&mut self) {
+        let range = self
+            .selection()
+            .unwrap_or(self.cursor..(self.cursor + 1).min(self.bytes.len()));
+        if !range.is_empty() {
+            self.delete_range(range);
+        }
+    }
+
+    fn delete_range(&mut self, range: Range<usize>) {
+        let start = range.start;
+        self.splice(range, Vec::new());
+        self.cursor = start;
+        self.half_byte = false;
+    }
+
+    fn splice(&mut self, range: Range<usize>, inserted: Vec<u8>) {
+        let revision_after = self.next_revision;
+        self.next_revision += 1;
+        let removed = self
+            .bytes
+            .splice(range.clone(), inserted.iter().copied())
+            .collect();
+        self.undo.push(HexSplice {
+            offset: range.start,
+            removed,
+            inserted,
+            revision_before: self.revision,
+            revision_after,
+        });
+        self.redo.clear();
+        self.revision = revision_after;
+        self.anchor = None;
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(splice) = self.undo.pop() else {
+            return false;
+        };
+        let end = splice.offset + splice.inserted.len();
+        self.bytes
+            .splice(splice.offset..end, splice.removed.iter().copied());
+        self.cursor = splice.offset.min(self.bytes.len());
+        self.revision = splice.revision_before;
+        self.redo.push(splice);
+        self.anchor = None;
+        self.half_byte = false;
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let Some(splice) = self.redo.pop() else {
+            return false;
+        };
+        let end = splice.offset + splice.removed.len();
+        self.bytes
+            .splice(splice.offset..end, splice.inserted.iter().copied());
+        self.cursor = (splice.offset + splice.inserted.len()).min(self.bytes.len());
+        self.revision = splice.revision_after;
+        self.undo.push(splice);
+        self.anchor = None;
+        self.half_byte = false;
+        true
+    }
+
+    /// Selected bytes, or the byte under the cursor.
+    pub fn copied_bytes(&self) -> &[u8] {
+        let range = self
+            .selection()
+            .unwrap_or(self.cursor..(self.cursor + 1).min(self.bytes.len()));
+        &self.bytes[range]
+    }
+
+    /// Selected bytes as space-separated hex pairs.
+    pub fn copy_as_hex(&self) -> String {
+        format_hex(self.copied_bytes())
+    }
+
+    /// Selected bytes as text, with invalid UTF-8 replaced.
+    pub fn copy_as_text(&self) -> String {
+        String::from_utf8_lossy(self.copied_bytes()).into_owned()
+    }
+
+    pub fn position_label(&self) -> String {
+        let mode = match self.mode {
+            HexEditMode::Overwrite => "OVR",
+            HexEditMode::Insert => "INS",
+        };
+        match self.selection() {
+            Some(selection) => format!(
+                "0x{:08x} ({} selected) {mode}",
+                self.cursor,
+                selection.len()
+            ),
+            None => format!("0x{:08x} / 0x{:08x} {mode}", self.cursor, self.bytes.len()),
+        }
+    }
+}
+
+pub fn format_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Bytes written as hex pairs, allowing whitespace and a `0x` prefix on each
+/// pair. `None` unless the whole text is hex.
+pub fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    let digits: String = text
+        .split_whitespace()
+        .map(|word| word.trim_start_matches("0x"))
+        .collect();
+    if digits.is_empty() || !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(digits.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+/// How a byte is shown in the ASCII pane.
+pub fn ascii_char(byte: u8) -> char {
+    if byte.is_ascii_graphic() || byte == b' ' {
+        byte as char
+    } else {
+        '.'
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexEditorEvent {
+    Changed,
+    SaveRequested,
+}
+
+/// Editable hex view of one file, shown in its own tab.
+pub struct HexEditor {
+    path: PathBuf,
+    buffer: HexBuffer,
+    /// Modification time of the file when it was loaded or last saved
+    loaded_time: SystemTime,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    /// Closing with unsaved changes was asked for once already
+    discard_requested: bool,
+}
+
+impl HexEditor {
+    pub fn new(path: PathBuf, bytes: Vec<u8>, loaded_time: SystemTime, cx: &mut App) -> Self {
+        Self {
+            path,
+            buffer: HexBuffer::new(bytes),
+            loaded_time,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            discard_requested: false,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn buffer(&self) -> &HexBuffer {
+        &self.buffer
+    }
+
+    pub fn loaded_time(&self) -> SystemTime {
+        self.loaded_time
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.buffer.is_modified()
+    }
+
+    pub fn mark_saved(&mut self, revision: u64, save_time: SystemTime, cx: &mut Context<Self>) {
+        self.buffer.mark_saved(revision);
+        self.loaded_time = save_time;
+        self.discard_requested = false;
+        cx.emit(HexEditorEvent::Changed);
+        cx.notify();
+    }
+
+    /// Whether the tab may close. The first close with unsaved changes is
+    /// refused so they can be saved; closing again discards them.
+    pub fn confirm_close(&mut self) -> bool {
+        if !self.is_modified() || self.discard_requested {
+            return true;
+        }
+        self.discard_requested = true;
+        false
+    }
+
+    fn edited(&mut self, cx: &mut Context<Self>) {
+        self.discard_requested = false;
+        self.scroll_to_cursor();
+        cx.emit(HexEditorEvent::Changed);
+        cx.notify();
+    }
+
+    fn moved(&mut self, cx: &mut Context<Self>) {
+        self.scroll_to_cursor();
+        cx.notify();
+    }
+
+    fn scroll_to_cursor(&self) {
+        self.scroll_handle.scroll_to_item(
+            self.buffer.cursor() / BYTES_PER_ROW,
+            ScrollStrategy::Nearest,
+        );
+    }
+
+    fn copy(&self, as_hex: bool, cx: &mut Context<Self>) {
+        let text = if as_hex {
+            self.buffer.copy_as_hex()
+        } else {
+            self.buffer.copy_as_text()
+        };
+        cx.write_to_clipboard(ClipboardItem::new_string(text));
+    }
+
+    fn paste(&mut self, cx: &mut Context<Self>) {
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+        // Hex text pasted into the byte pane is read as bytes.
+        let bytes = match self.buffer.pane() {
+            HexPane::Hex => parse_hex(&text).unwrap_or_else(|| text.into_bytes()),
+            HexPane::Ascii => text.into_bytes(),
+        };
+        self.buffer.write_bytes(bytes);
+        self.edited(cx);
+    }
+
+    fn handle_key(&mut self, event: &KeyDownEvent, cx: &mut Context<Self>) -> bool {
+        let keystroke = &event.keystroke;
+        let modifiers = keystroke.modifiers;
+        let extend = modifiers.shift;
+        let row = BYTES_PER_ROW as isize;
+
+        if modifiers.secondary() {
+            match keystroke.key.as_str() {
+                "c" => self.copy(!modifiers.shift, cx),
+                "v" => self.paste(cx),
+                "a" => {
+                    self.buffer.select_all();
+                    self.moved(cx);
+                }
+                "z" if modifiers.shift => {
+                    if self.buffer.redo() {
+                        self.edited(cx);
+                    }
+                }
+                "z" => {
+                    if self.buffer.undo() {
+                        self.edited(cx);
+                    }
+                }
+                "s" => cx.emit(HexEditorEvent::SaveRequested),
+                _ => return false,
+            }
+            return true;
+        }
+
+        match keystroke.key.as_str() {
+            "left" => self.buffer.move_by(-1, extend),
+            "right" => self.buffer.move_by(1, extend),
+            "up" => self.buffer.move_by(-row, extend),
+            "down" => self.buffer.move_by(row, extend),
+            "pageup" => self.buffer.move_by(-row * PAGE_ROWS as isize, extend),
+            "pagedown" => self.buffer.move_by(row * PAGE_ROWS as isize, extend),
+            "home" => {
+                let start = self.buffer.cursor() - self.buffer.cursor() % BYTES_PER_ROW;
+                self.buffer.move_to(start, extend);
+            }
+            "end" => {
+                let row_end =
+                    self.buffer.cursor() - self.buffer.cursor() % BYTES_PER_ROW + BYTES_PER_ROW - 1;
+                self.buffer.move_to(row_end, extend);
+            }
+            "tab" => self.buffer.toggle_pane(),
+            "insert" => self.buffer.toggle_mode(),
+            "escape" => self.buffer.move_to(self.buffer.cursor(), false),
+            "backspace" => {
+                self.buffer.backspace();
+                self.edited(cx);
+                return true;
+            }
+            "delete" => {
+                self.buffer.delete();
+                self.edited(cx);
+                return true;
+            }
+            _ => {
+                let Some(typed) = keystroke.key_char.as_deref() else {
+                    return false;
+                };
+                let mut chars = typed.chars();
+                let (Some(ch), None) = (chars.next(), chars.next()) else {
+                    return false;
+                };
+                match self.buffer.pane() {
+                    HexPane::Hex => {
+                        let Some(digit) = ch.to_digit(16) else {
+                            return false;
+                        };
+                        self.buffer.type_hex_digit(digit as u8);
+                    }
+                    HexPane::Ascii if ch.is_ascii() && !ch.is_ascii_control() => {
+                        self.buffer.type_byte(ch as u8);
+                    }
+                    HexPane::Ascii => return false,
+                }
+                self.edited(cx);
+                return true;
+            }
+        }
+        self.moved(cx);
+        true
+    }
+
+    fn click_byte(
+        &mut self,
+        offset: usize,
+        pane: HexPane,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.focus_handle, cx);
+        self.buffer.set_pane(pane);
+        self.buffer.move_to(offset, event.modifiers.shift);
+        cx.notify();
+    }
+
+    fn render_row(&self, row: usize, focused: bool, cx: &mut Context<Self>) -> gpui::AnyElement {
+        let tokens = cx.theme().tokens;
+        let start = row * BYTES_PER_ROW;
+        let end = (start + BYTES_PER_ROW).min(self.buffer.len());
+        let selection = self.buffer.selection();
+        let cursor = self.buffer.cursor();
+        let active_pane = self.buffer.pane();
+
+        let cell_background = |offset: usize, pane: HexPane| {
+            if offset == cursor {
+                Some(if pane == active_pane && focused {
+                    tokens.editor.cursor_normal
+                } else {
+                    tokens.editor.selection_secondary
+                })
+            } else if selection
+                .as_ref()
+                .is_some_and(|selection| selection.contains(&offset))
+            {
+                Some(tokens.editor.selection_primary)
+            } else {
+                None
+            }
+        };
+        let cell_text_color = |offset: usize, pane: HexPane| {
+            if offset == cursor && pane == active_pane && focused {
+                tokens.editor.text_on_primary
+            } else {
+                tokens.editor.text_primary
+            }
+        };
+
+        // Cells run to the end of the row so the append position and empty
+        // cells keep the columns aligned.
+        let mut hex_cells = div().flex().flex_none();
+        let mut ascii_cells = div().flex().flex_none();
+        for offset in start..start + BYTES_PER_ROW {
+            let byte = (offset < end).then(|| self.buffer.bytes()[offset]);
+            let clickable = offset <= self.buffer.len();
+            let hex_label: SharedString =
+                byte.map_or("  ".into(), |byte| format!("{byte:02x}").into());
+            let ascii_label: SharedString =
+                byte.map_or(" ".into(), |byte| ascii_char(byte).to_string().into());
+
+            hex_cells = hex_cells.child(
+                div()
+                    .flex_none()
+                    .px(px(3.0))
+                    .when(offset % BYTES_PER_ROW == BYTES_PER_ROW / 2, |cell| {
+                        cell.ml(px(8.0))
+                    })
+                    .text_color(cell_text_color(offset, HexPane::Hex))
+                    .when_some(cell_background(offset, HexPane::Hex), |cell, color| {
+                        cell.bg(color)
+                    })
+                    .when(clickable, |cell| {
+                        cell.on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                                this.click_byte(offset, HexPane::Hex, event, window, cx);
+                            }),
+                        )
+                    })
+                    .child(hex_label),
+            );
+            ascii_cells = ascii_cells.child(
+                div()
+                    .flex_none()
+                    .text_color(cell_text_color(offset, HexPane::Ascii))
+                    .when_some(cell_background(offset, HexPane::Ascii), |cell, color| {
+                        cell.bg(color)
+                    })
+                    .when(clickable, |cell| {
+                        cell.on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                                this.click_byte(offset, HexPane::Ascii, event, window, cx);
+                            }),
+                        )
+                    })
+                    .child(ascii_label),
+            );
+        }
+
+        div()
+            .flex()
+            .flex_none()
+            .gap(px(16.0))
+            .px(tokens.sizes.space_3)
+            .child(
+                div()
+                    .flex_none()
+                    .text_color(tokens.editor.line_number)
+                    .child(SharedString::from(format!("{start:08x}"))),
+            )
+            .child(hex_cells)
+            .child(ascii_cells)
+            .into_any_element()
+    }
+}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+
+impl Render for HexEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let tokens = cx.theme().tokens;
+        let editor_font = cx.global::<crate::types::EditorFontConfig>();
+        let font_family = SharedString::from(editor_font.family.clone());
+        let font_size = px(editor_font.size);
+        let row_count = self.buffer.row_count();
+
+        div()
+            .id("hex-editor")
+            .key_context(HEX_EDITOR_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
+                if this.handle_key(event, cx) {
+                    cx.stop_propagation();
+                }
+            }))
+            .on_action(
+                cx.listener(|_this, _: &crate::actions::editor::Save, _window, cx| {
+                    cx.emit(HexEditorEvent::SaveRequested);
+                }),
+            )
+            .on_action(
+                cx.listener(|this, _: &crate::actions::text_input::Copy, _window, cx| {
+                    this.copy(true, cx);
+                }),
+            )
+            .on_action(
+                cx.listener(|this, _: &crate::actions::text_input::Paste, _window, cx| {
+                    this.paste(cx);
+                }),
+            )
+            .on_action(
+                cx.listener(|this, _: &crate::actions::text_input::Undo, _window, cx| {
+                    if this.buffer.undo() {
+                        this.edited(cx);
+                    }
+                }),
+            )
+            .on_action(
+                cx.listener(|this, _: &crate::actions::text_input::Redo, _window, cx| {
+                    if this.buffer.redo() {
+                        this.edited(cx);
+                    }
+                }),
+            )
+            .on_mouse_down(MouseButton::Left, {
+                let focus_handle = self.focus_handle.clone();
+                move |_event, window, cx| window.focus(&focus_handle, cx)
+            })
+            .size_full()
+            .flex()
+            .flex_col()
+            .bg(tokens.editor.background)
+            .font_family(font_family)
+            .text_size(font_size)
+            .text_color(tokens.editor.text_primary)
+            .child(
+                uniform_list(
+                    "hex-editor-rows",
+                    row_count,
+                    cx.processor(|this, range: Range<usize>, window, cx| {
+                        let focused = this.focus_handle.is_focused(window);
+                        range
+                            .map(|row| this.render_row(row, focused, cx))
+                            .collect::<Vec<_>>()
+                    }),
+                )
+                .track_scroll(&self.scroll_handle)
+                .py(tokens.sizes.space_2)
+                .size_full(),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_digits_fill_high_then_low_nibbles() {
+        let mut buffer = HexBuffer::new(vec![0x00, 0x11, 0x22]);
+        buffer.type_hex_digit(0xa);
+        assert_eq!(buffer.bytes(), &[0xa0, 0x11, 0x22]);
+        assert_eq!(buffer.cursor(), 0);
+        buffer.type_hex_digit(0xb);
+        assert_eq!(buffer.bytes(), &[0xab, 0x11, 0x22]);
+        assert_eq!(buffer.cursor(), 1);
+        assert!(buffer.is_modified());
+
+        buffer.toggle_mode();
+        buffer.type_hex_digit(0xf);
+        buffer.type_hex_digit(0xe);
+        assert_eq!(buffer.bytes(), &[0xab, 0xfe, 0x11, 0x22]);
+
+        buffer.move_to(buffer.len(), false);
+        buffer.type_hex_digit(0x1);
+        assert_eq!(buffer.bytes(), &[0xab, 0xfe, 0x11, 0x22, 0x10]);
+    }
+
+    #[test]
+    fn overwrite_keeps_length_and_insert_grows() {
+        let mut buffer = HexBuffer::new(b"abcd".to_vec());
+        buffer.set_pane(HexPane::Ascii);
+        buffer.type_byte(b'X');
+        assert_eq!(buffer.bytes(), b"Xbcd");
+        buffer.backspace();
+        assert_eq!(buffer.bytes(), b"Xbcd");
+        assert_eq!(buffer.cursor(), 0);
+
+        buffer.toggle_mode();
+        buffer.move_to(2, false);
+        buffer.type_byte(b'-');
+        assert_eq!(buffer.bytes(), b"Xb-cd");
+        buffer.backspace();
+        assert_eq!(buffer.bytes(), b"Xbcd");
+    }
+
+    #[test]
+    fn selection_copies_and_is_replaced_by_edits() {
+        let mut buffer = HexBuffer::new(vec![0xde, 0xad, 0xbe, 0xef]);
+        buffer.move_to(1, false);
+        buffer.move_to(2, true);
+        assert_eq!(buffer.selection(), Some(1..3));
+        assert_eq!(buffer.copy_as_hex(), "ad be");
+
+        buffer.write_bytes(vec![0x00]);
+        assert_eq!(buffer.bytes(), &[0xde, 0x00, 0xef]);
+        assert_eq!(buffer.cursor(), 2);
+
+        buffer.select_all();
+        buffer.delete();
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn undo_and_redo_track_the_saved_revision() {
+        let mut buffer = HexBuffer::new(vec![0x01, 0x02]);
+        buffer.type_hex_digit(0xf);
+        buffer.type_hex_digit(0xf);
+        buffer.mark_saved(buffer.revision());
+        assert!(!buffer.is_modified());
+
+        assert!(buffer.undo());
+        assert_eq!(buffer.bytes(), &[0xf1, 0x02]);
+        assert!(buffer.is_modified());
+        assert!(buffer.redo());
+        assert_eq!(buffer.bytes(), &[0xff, 0x02]);
+        assert!(!buffer.is_modified());
+
+        assert!(buffer.undo());
+        assert!(buffer.undo());
+        assert_eq!(buffer.bytes(), &[0x01, 0x02]);
+        assert!(!buffer.undo());
+    }
+
+    #[test]
+    fn hex_text_parses_with_spacing_and_prefixes() {
+        assert_eq!(
+            parse_hex("de ad 0xbe EF"),
+            Some(vec![0xde, 0xad, 0xbe, 0xef])
+        );
+        assert_eq!(parse_hex("cafe"), Some(vec![0xca, 0xfe]));
+        assert_eq!(parse_hex("abc"), None);
+        assert_eq!(parse_hex("hello"), None);
+        assert_eq!(format_hex(&[0x00, 0x7f]), "00 7f");
+        assert_eq!(ascii_char(b'A'), 'A');
+        assert_eq!(ascii_char(0x00), '.');
+    }
+}
@@ -0,0 +1,128 @@
+// ABOUTME: Indentation style of the focused document: statusline label and settings menu choices
+// ABOUTME: Builds undoable transactions converting leading whitespace between tabs and spaces
+
+use helix_core::indent::IndentStyle;
+use helix_core::{Rope, Transaction};
+
+/// Prompt command opening the indentation menu.
+pub const INDENTATION_COMMAND: &str = "indentation";
+/// Prompt commands for indentation, with completion descriptions.
+pub const INDENTATION_COMMANDS: &[(&str, &str)] = &[(
+    INDENTATION_COMMAND,
+    "Change or convert the indentation of the focused document",
+)];
+/// Space widths offered by the indentation menu.
+pub const SPACE_WIDTHS: &[u8] = &[2, 4, 8];
+
+/// An entry of the indentation menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentationChoice {
+    UseSpaces(u8),
+    UseTabs,
+    /// Detect the style from the document's content again
+    Detect,
+    ConvertToSpaces,
+    ConvertToTabs,
+}
+
+/// Statusline text for a document's indentation, such as `Spaces: 4`.
+pub fn statusline_label(style: IndentStyle, tab_width: usize) -> String {
+    match style {
+        IndentStyle::Spaces(width) => format!("Spaces: {width}"),
+        IndentStyle::Tabs => format!("Tab Size: {tab_width}"),
+    }
+}
+
+/// Display width of leading whitespace, with tabs advancing to the next
+/// multiple of `tab_width`.
+fn indent_width(whitespace: &str, tab_width: usize) -> usize {
+    whitespace.chars().fold(0, |width, ch| match ch {
+        '\t' => (width / tab_width + 1) * tab_width,
+        _ => width + 1,
+    })
+}
+
+/// Rewrites the leading whitespace of every line in `target` style. Levels
+/// are measured in `unit` columns, the width of one indent in the current
+/// style; columns left over keep their width as spaces. Returns `None` when
+/// nothing changes.
+pub fn convert_indentation_transaction(
+    text: &Rope,
+    unit: usize,
+    tab_width: usize,
+    target: IndentStyle,
+) -> Option<Transaction> {
+    let unit = unit.max(1);
+    let tab_width = tab_width.max(1);
+    let mut changes = Vec::new();
+
+    for (line_index, line) in text.lines().enumerate() {
+        let leading: String = line
+            .chars()
+            .take_while(|ch| *ch == ' ' || *ch == '\t')
+            .collect();
+        if leading.is_empty() {
+            continue;
+        }
+        let width = indent_width(&leading, tab_width);
+        let (levels, rest) = (width / unit, width % unit);
+        let replacement = match target {
+            IndentStyle::Tabs => format!("{}{}", "\t".repeat(levels), " ".repeat(rest)),
+            IndentStyle::Spaces(spaces) => " ".repeat(levels * spaces as usize + rest),
+        };
+        if replacement != leading {
+            let start = text.line_to_char(line_index);
+            let end = start + leading.chars().count();
+            changes.push((start, end, Some(replacement.into())));
+        }
+    }
+
+    (!changes.is_empty()).then(|| Transaction::change(text, changes.into_iter()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(text: &str, unit: usize, tab_width: usize, target: IndentStyle) -> String {
+        let mut rope = Rope::from(text);
+        let transaction = convert_indentation_transaction(&rope, unit, tab_width, target)
+            .expect("indentation should change");
+        assert!(transaction.apply(&mut rope));
+        rope.to_string()
+    }
+
+    #[test]
+    fn statusline_labels_name_the_style() {
+        assert_eq!(statusline_label(IndentStyle::Spaces(2), 4), "Spaces: 2");
+        assert_eq!(statusline_label(IndentStyle::Tabs, 8), "Tab Size: 8");
+    }
+
+    #[test]
+    fn tabs_convert_to_spaces_and_back() {
+        let tabs = "fn main() {\n\tif x {\n\t\ty();\n\t}\n}\n";
+        let spaces = convert(tabs, 4, 4, IndentStyle::Spaces(4));
+        assert_eq!(spaces, "fn main() {\n    if x {\n        y();\n    }\n}\n");
+        assert_eq!(convert(&spaces, 4, 4, IndentStyle::Tabs), tabs);
+    }
+
+    #[test]
+    fn conversion_keeps_levels_and_leftover_columns() {
+        // Two-space levels become tabs; the odd column stays a space.
+        assert_eq!(
+            convert("a\n  b\n     c\n", 2, 4, IndentStyle::Tabs),
+            "a\n\tb\n\t\t c\n"
+        );
+        // Mixed tabs and spaces are measured by display width.
+        assert_eq!(convert("  \tx\n", 4, 4, IndentStyle::Spaces(2)), "  x\n");
+        assert!(
+            convert_indentation_transaction(
+                &Rope::from("    a\nb\n"),
+                4,
+                4,
+                IndentStyle::Spaces(4)
+            )
+            .is_none()
+        );
+    }
+}
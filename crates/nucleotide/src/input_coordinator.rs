@@ -1,7 +1,9 @@
 // ABOUTME: Central hub for workspace keyboard context routing in Nucleotide
 // ABOUTME: Keeps app/Helix key handoff separate from component-owned GPUI actions
 
+use std::collections::VecDeque;
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
 use gpui::{KeyDownEvent, Window};
 use nucleotide_logging::{debug, info, instrument};
@@ -17,8 +19,19 @@ pub enum InputResult {
     NotHandled,
     /// Key was handled, no further processing needed
     Handled,
-    /// Key should be sent to Helix editor
-    SendToHelix(KeyEvent),
+    /// Key should be sent to Helix editor; `received_at` starts its latency trace
+    SendToHelix { key: KeyEvent, received_at: Instant },
+}
+
+/// Context switches kept for the focus debugger
+const CONTEXT_TRANSITION_HISTORY: usize = 12;
+
+/// A change of input context, as shown by the focus debugger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextTransition {
+    pub from: InputContext,
+    pub to: InputContext,
+    pub at: Instant,
 }
 
 /// Central coordinator for workspace-level keyboard context routing.
@@ -28,6 +41,8 @@ pub struct InputCoordinator {
     active_context: Arc<RwLock<InputContext>>,
     /// Context priority stack for modal behavior
     context_stack: Arc<RwLock<Vec<InputContext>>>,
+    /// Most recent context changes, oldest first
+    transitions: Arc<RwLock<VecDeque<ContextTransition>>>,
 }
 
 /// Input contexts that determine which shortcuts are active
@@ -55,6 +70,7 @@ impl InputCoordinator {
         Self {
             active_context: Arc::new(RwLock::new(InputContext::Normal)),
             context_stack: Arc::new(RwLock::new(Vec::new())),
+            transitions: Arc::new(RwLock::new(VecDeque::new())),
         }
     }
 
@@ -64,6 +80,19 @@ impl InputCoordinator {
         let mut active = self.active_context.write().unwrap();
         let old_context = *active;
         *active = new_context;
+        drop(active);
+
+        if old_context != new_context {
+            let mut transitions = self.transitions.write().unwrap();
+            if transitions.len() == CONTEXT_TRANSITION_HISTORY {
+                transitions.pop_front();
+            }
+            transitions.push_back(ContextTransition {
+                from: old_context,
+                to: new_context,
+                at: Instant::now(),
+            });
+        }
 
         info!(
             old_context = ?old_context,
@@ -105,9 +134,20 @@ impl InputCoordinator {
         *self.active_context.read().unwrap()
     }
 
+    /// Contexts pushed under the current one, bottom first
+    pub fn context_stack(&self) -> Vec<InputContext> {
+        self.context_stack.read().unwrap().clone()
+    }
+
+    /// Most recent context changes, oldest first
+    pub fn recent_transitions(&self) -> Vec<ContextTransition> {
+        self.transitions.read().unwrap().iter().copied().collect()
+    }
+
     /// Handle a key down event - main entry point for input processing
     #[instrument(skip(self, event, window))]
     pub fn handle_key_event(&self, event: &KeyDownEvent, window: &Window) -> InputResult {
+        let received_at = Instant::now();
         let current_context = self.current_context();
 
         debug!(
@@ -122,13 +162,18 @@ impl InputCoordinator {
         }
 
         // Delegate to context-specific handling
-        match current_context {
+        let key = match current_context {
             InputContext::Normal => self.handle_normal_context(event, window),
             InputContext::Completion => self.handle_completion_context(event, window),
             InputContext::FileTree => self.handle_file_tree_context(event, window),
             InputContext::Picker => self.handle_picker_context(event, window),
             InputContext::Modal => self.handle_modal_context(event, window),
             InputContext::Prompt => self.handle_prompt_context(event, window),
+        };
+
+        match key {
+            Some(key) => InputResult::SendToHelix { key, received_at },
+            None => InputResult::NotHandled,
         }
     }
 
@@ -154,8 +199,8 @@ impl InputCoordinator {
         None
     }
 
-    // Context-specific handlers
-    fn handle_normal_context(&self, event: &KeyDownEvent, _window: &Window) -> InputResult {
+    // Context-specific handlers return the key to send to Helix, if any
+    fn handle_normal_context(&self, event: &KeyDownEvent, _window: &Window) -> Option<KeyEvent> {
         debug!("Handling normal context input - sending to Helix editor");
 
         // Translate GPUI key event to Helix key event
@@ -166,44 +211,52 @@ impl InputCoordinator {
             "Sending key to Helix editor"
         );
 
-        InputResult::SendToHelix(helix_key)
+        Some(helix_key)
     }
 
-    fn handle_completion_context(&self, event: &KeyDownEvent, _window: &Window) -> InputResult {
+    fn handle_completion_context(
+        &self,
+        event: &KeyDownEvent,
+        _window: &Window,
+    ) -> Option<KeyEvent> {
         debug!("Handling completion context input");
 
         // For completion context, we might want to handle some keys specially
         // but for now, also send to Helix
         let helix_key = utils::translate_key(&event.keystroke);
-        InputResult::SendToHelix(helix_key)
+        Some(helix_key)
     }
 
-    fn handle_file_tree_context(&self, _event: &KeyDownEvent, _window: &Window) -> InputResult {
+    fn handle_file_tree_context(
+        &self,
+        _event: &KeyDownEvent,
+        _window: &Window,
+    ) -> Option<KeyEvent> {
         debug!("Handling file tree context input");
 
         // File tree should handle its own input, so don't send to Helix
-        InputResult::NotHandled
+        None
     }
 
-    fn handle_picker_context(&self, _event: &KeyDownEvent, _window: &Window) -> InputResult {
+    fn handle_picker_context(&self, _event: &KeyDownEvent, _window: &Window) -> Option<KeyEvent> {
         debug!("Handling picker context input");
 
         // Picker should handle its own input, so don't send to Helix
-        InputResult::NotHandled
+        None
     }
 
-    fn handle_modal_context(&self, _event: &KeyDownEvent, _window: &Window) -> InputResult {
+    fn handle_modal_context(&self, _event: &KeyDownEvent, _window: &Window) -> Option<KeyEvent> {
         debug!("Handling modal context input");
 
         // Modal should handle its own input, so don't send to Helix
-        InputResult::NotHandled
+        None
     }
 
-    fn handle_prompt_context(&self, _event: &KeyDownEvent, _window: &Window) -> InputResult {
+    fn handle_prompt_context(&self, _event: &KeyDownEvent, _window: &Window) -> Option<KeyEvent> {
         debug!("Handling prompt context input");
 
         // Prompt should handle its own input, so don't send to Helix
-        InputResult::NotHandled
+        None
     }
 }
 
@@ -269,4 +322,39 @@ mod tests {
         assert_eq!(popped, None);
         assert_eq!(coordinator.current_context(), InputContext::Normal);
     }
+
+    #[test]
+    fn test_transitions_record_context_changes() {
+        let coordinator = InputCoordinator::new();
+
+        // Re-entering the current context is not a transition
+        coordinator.switch_context(InputContext::Normal);
+        assert!(coordinator.recent_transitions().is_empty());
+
+        coordinator.push_context(InputContext::Picker);
+        assert_eq!(coordinator.context_stack(), [InputContext::Normal]);
+        coordinator.pop_context();
+
+        let transitions = coordinator
+            .recent_transitions()
+            .iter()
+            .map(|transition| (transition.from, transition.to))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            transitions,
+            [
+                (InputContext::Normal, InputContext::Picker),
+                (InputContext::Picker, InputContext::Normal),
+            ]
+        );
+
+        // Only the most recent changes are kept
+        for _ in 0..CONTEXT_TRANSITION_HISTORY {
+            coordinator.switch_context(InputContext::FileTree);
+            coordinator.switch_context(InputContext::Normal);
+        }
+        let transitions = coordinator.recent_transitions();
+        assert_eq!(transitions.len(), CONTEXT_TRANSITION_HISTORY);
+        assert_eq!(transitions.last().unwrap().to, InputContext::Normal);
+    }
 }
@@ -2,34 +2,73 @@
 // ABOUTME: Provides the core application and UI integration
 
 pub mod actions;
+pub mod align;
 pub mod application;
+pub mod cargo_integration;
 pub mod cli;
 // application_v2 merged into application module
 pub mod completion_interception;
 pub mod completions;
 pub mod config;
+pub mod content_index;
+pub mod disk_changes;
+pub mod doc_comments;
 pub mod document;
+pub mod document_export;
+pub mod error_report;
+pub mod file_compare;
+pub mod file_templates;
 pub mod file_tree;
+pub mod find_bar;
+pub mod focus_debugger;
 mod helix_command;
+pub mod hex_editor;
+pub mod indentation;
 pub mod input_coordinator;
 #[cfg(test)]
 pub mod integration_test_phase2;
+pub mod line_edits;
+pub mod live_grep;
+pub mod local_save;
 pub mod lsp_traffic_logger;
+#[cfg(target_os = "macos")]
+pub mod macos_services;
+pub mod menu_state;
+pub mod merge_conflicts;
+pub mod npm_scripts;
 pub mod overlay;
 pub mod picker_capability;
+pub mod project_rename;
+pub mod project_tasks;
+pub mod project_templates;
+pub mod recent_files;
+pub mod references_panel;
+pub mod reflow;
+pub mod regex_tester;
 mod remote_connection_manager;
 mod remote_connections;
 mod remote_open;
+pub mod repl;
+pub mod run_configurations;
 pub mod runnables;
+pub mod save_failure;
+pub mod scratch_buffers;
+pub mod shutdown;
+pub mod snippet_export;
+pub mod startup_profile;
 pub mod tab;
 pub mod tab_bar;
+pub mod task_list;
 pub mod terminal_input;
 #[cfg(test)]
 pub mod test_utils;
 #[cfg(test)]
 pub mod tests;
+pub mod text_transforms;
+pub mod type_hierarchy;
 pub mod types;
 pub mod updates;
+pub mod usage_stats;
 pub mod utils;
 pub mod workspace;
 
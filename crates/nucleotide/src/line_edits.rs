@@ -0,0 +1,441 @@
+// ABOUTME: Line-wise editing commands: duplicating, moving, sorting, deduplicating and shuffling lines
+// ABOUTME: Builds single transactions over every selection, keeping each selection on its lines
+
+use helix_core::{Range, Rope, RopeSlice, Selection, Tendril, Transaction};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineDirection {
+    Up,
+    Down,
+}
+
+/// Index of the last line that has content of its own; the empty line after
+/// a trailing line ending is not one.
+fn last_content_line(text: RopeSlice) -> usize {
+    let lines = text.len_lines();
+    let ends_with_newline =
+        text.len_chars() > 0 && matches!(text.char(text.len_chars() - 1), '\n' | '\r') && lines > 1;
+    lines - 1 - usize::from(ends_with_newline)
+}
+
+/// Inclusive line spans covered by the selection, with overlapping and
+/// adjacent spans merged, and the block index of each range.
+fn line_blocks(text: RopeSlice, selection: &Selection) -> (Vec<(usize, usize)>, Vec<usize>) {
+    let last = last_content_line(text);
+    let mut blocks: Vec<(usize, usize)> = Vec::new();
+    let mut range_blocks = Vec::with_capacity(selection.len());
+    for range in selection.ranges() {
+        let (start, end) = range.line_range(text);
+        let (start, end) = (start.min(last), end.min(last));
+        match blocks.last_mut() {
+            Some(block) if start <= block.1 + 1 => block.1 = block.1.max(end),
+            _ => blocks.push((start, end)),
+        }
+        range_blocks.push(blocks.len() - 1);
+    }
+    (blocks, range_blocks)
+}
+
+/// A line's text without its line ending, and the ending.
+fn split_line_ending(line: &str) -> (&str, &str) {
+    let content = line.trim_end_matches(['\n', '\r']);
+    (content, &line[content.len()..])
+}
+
+fn shift_range(range: &Range, delta: isize, bounds: (usize, usize)) -> Range {
+    let shift =
+        |pos: usize| (pos as isize + delta).clamp(bounds.0 as isize, bounds.1 as isize) as usize;
+    Range::new(shift(range.anchor), shift(range.head))
+}
+
+/// Copies the lines of every selection below them, selecting the copies.
+pub fn duplicate_lines_transaction(text: &Rope, selection: &Selection) -> Transaction {
+    let slice = text.slice(..);
+    let (blocks, range_blocks) = line_blocks(slice, selection);
+
+    let mut changes = Vec::with_capacity(blocks.len());
+    // Characters inserted up to and including each block.
+    let mut inserted = Vec::with_capacity(blocks.len());
+    let mut total = 0;
+    for &(start, end) in &blocks {
+        let from = slice.line_to_char(start);
+        let to = slice.line_to_char(end + 1);
+        let block = slice.slice(from..to).to_string();
+        let copy = if block.ends_with(['\n', '\r']) {
+            block
+        } else {
+            // The last line of the document has no line ending to copy.
+            format!("\n{block}")
+        };
+        total += copy.chars().count();
+        inserted.push(total);
+        changes.push((to, to, Some(Tendril::from(copy))));
+    }
+
+    let bounds = (0, slice.len_chars() + total);
+    let ranges = selection
+        .ranges()
+        .iter()
+        .zip(range_blocks)
+        .map(|(range, block)| shift_range(range, inserted[block] as isize, bounds))
+        .collect();
+    Transaction::change(text, changes.into_iter())
+        .with_selection(Selection::new(ranges, selection.primary_index()))
+}
+
+/// Swaps the lines of every selection with the line above or below them.
+/// Returns `None` when a selection is already at the first or last line.
+pub fn move_lines_transaction(
+    text: &Rope,
+    selection: &Selection,
+    direction: LineDirection,
+) -> Option<Transaction> {
+    let slice = text.slice(..);
+    let last = last_content_line(slice);
+    let (blocks, range_blocks) = line_blocks(slice, selection);
+    let at_edge = match direction {
+        LineDirection::Up => blocks.first().is_some_and(|&(start, _)| start == 0),
+        LineDirection::Down => blocks.last().is_some_and(|&(_, end)| end >= last),
+    };
+    if blocks.is_empty() || at_edge {
+        return None;
+    }
+
+    let mut changes = Vec::with_capacity(blocks.len());
+    // Signed shift and bounds of the region for each block's ranges.
+    let mut shifts = Vec::with_capacity(blocks.len());
+    for &(start, end) in &blocks {
+        let (first, last) = match direction {
+            LineDirection::Up => (start - 1, end),
+            LineDirection::Down => (start, end + 1),
+        };
+        let region_start = slice.line_to_char(first);
+        let region_end = slice.line_to_char(last + 1);
+        let lines = (first..=last)
+            .map(|line| slice.line(line).to_string())
+            .collect::<Vec<_>>();
+        let mut contents = lines
+            .iter()
+            .map(|line| split_line_ending(line).0)
+            .collect::<Vec<_>>();
+        // Line endings stay in place so a missing final newline stays last.
+        let endings = lines
+            .iter()
+            .map(|line| split_line_ending(line).1)
+            .collect::<Vec<_>>();
+        match direction {
+            LineDirection::Up => contents.rotate_left(1),
+            LineDirection::Down => contents.rotate_right(1),
+        }
+
+        let replacement = contents
+            .iter()
+            .zip(&endings)
+            .map(|(content, ending)| format!("{content}{ending}"))
+            .collect::<String>();
+        let block_start = slice.line_to_char(start);
+        let new_block_start = match direction {
+            LineDirection::Up => region_start,
+            LineDirection::Down => {
+                region_start + contents[0].chars().count() + endings[0].chars().count()
+            }
+        };
+        shifts.push((
+            new_block_start as isize - block_start as isize,
+            (region_start, region_end),
+        ));
+        changes.push((region_start, region_end, Some(Tendril::from(replacement))));
+    }
+
+    let ranges = selection
+        .ranges()
+        .iter()
+        .zip(range_blocks)
+        .map(|(range, block)| shift_range(range, shifts[block].0, shifts[block].1))
+        .collect();
+    Some(
+        Transaction::change(text, changes.into_iter())
+            .with_selection(Selection::new(ranges, selection.primary_index())),
+    )
+}
+
+/// An operation rearranging whole lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineOperation {
+    SortAscending,
+    SortDescending,
+    /// Sort by the number each line starts with
+    SortNumeric,
+    SortNumericDescending,
+    /// Remove repeated lines, keeping the first of each
+    Unique,
+    Reverse,
+    Shuffle,
+}
+
+/// Prompt commands for the line operations, with completion descriptions.
+pub const LINE_OPERATION_COMMANDS: &[(&str, &str)] = &[
+    (
+        "sort-lines",
+        "Sort the selected lines, or the whole buffer, ascending",
+    ),
+    (
+        "sort-lines-descending",
+        "Sort the selected lines, or the whole buffer, descending",
+    ),
+    (
+        "sort-lines-numeric",
+        "Sort the selected lines by their leading number",
+    ),
+    (
+        "sort-lines-numeric-descending",
+        "Sort the selected lines by their leading number, descending",
+    ),
+    (
+        "unique-lines",
+        "Remove duplicate lines from the selected lines",
+    ),
+    ("reverse-lines", "Reverse the order of the selected lines"),
+    (
+        "shuffle-lines",
+        "Shuffle the selected lines into a random order",
+    ),
+];
+
+impl LineOperation {
+    pub const ALL: [LineOperation; 7] = [
+        LineOperation::SortAscending,
+        LineOperation::SortDescending,
+        LineOperation::SortNumeric,
+        LineOperation::SortNumericDescending,
+        LineOperation::Unique,
+        LineOperation::Reverse,
+        LineOperation::Shuffle,
+    ];
+
+    /// Prompt command applying the operation.
+    pub fn command(self) -> &'static str {
+        match self {
+            LineOperation::SortAscending => "sort-lines",
+            LineOperation::SortDescending => "sort-lines-descending",
+            LineOperation::SortNumeric => "sort-lines-numeric",
+            LineOperation::SortNumericDescending => "sort-lines-numeric-descending",
+            LineOperation::Unique => "unique-lines",
+            LineOperation::Reverse => "reverse-lines",
+            LineOperation::Shuffle => "shuffle-lines",
+        }
+    }
+
+    pub fn from_command(command: &str) -> Option<Self> {
+        let command = command.trim().trim_start_matches(':');
+        Self::ALL
+            .into_iter()
+            .find(|operation| operation.command() == command)
+    }
+
+    /// Rearranges `lines`; `seed` drives the order of a shuffle.
+    pub fn apply(self, lines: &mut Vec<&str>, seed: u64) {
+        match self {
+            LineOperation::SortAscending => lines.sort(),
+            LineOperation::SortDescending => lines.sort_by(|a, b| b.cmp(a)),
+            LineOperation::SortNumeric => lines.sort_by(|a, b| compare_numeric(a, b)),
+            LineOperation::SortNumericDescending => {
+                lines.sort_by(|a, b| compare_numeric(b, a));
+            }
+            LineOperation::Unique => {
+                let mut seen = std::collections::HashSet::new();
+                lines.retain(|line| seen.insert(*line));
+            }
+            LineOperation::Reverse => lines.reverse(),
+            LineOperation::Shuffle => {
+                // Fisher-Yates over an xorshift sequence; no cryptographic
+                // quality is needed to shuffle lines.
+                let mut state = seed | 1;
+                for index in (1..lines.len()).rev() {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    lines.swap(index, (state % (index as u64 + 1)) as usize);
+                }
+            }
+        }
+    }
+}
+
+/// The number a line starts with, after its indentation.
+fn leading_number(line: &str) -> Option<f64> {
+    let line = line.trim_start();
+    let sign = usize::from(line.starts_with(['-', '+']));
+    let mut seen_point = false;
+    let len = line[sign..]
+        .char_indices()
+        .take_while(|&(_, ch)| {
+            let point = ch == '.' && !seen_point;
+            seen_point |= point;
+            ch.is_ascii_digit() || point
+        })
+        .map(|(index, ch)| index + ch.len_utf8())
+        .last()?;
+    line[..sign + len].parse().ok()
+}
+
+/// Orders lines by their leading number, with lines without one first and
+/// ties in text order.
+fn compare_numeric(a: &str, b: &str) -> std::cmp::Ordering {
+    match (leading_number(a), leading_number(b)) {
+        (Some(x), Some(y)) => x.total_cmp(&y).then_with(|| a.cmp(b)),
+        (x, y) => x.is_some().cmp(&y.is_some()).then_with(|| a.cmp(b)),
+    }
+}
+
+/// Applies `operation` to the lines of each selection, or to the whole
+/// buffer when every range is a cursor. Line endings keep their positions,
+/// so a missing final newline stays missing. Returns `None` when nothing
+/// changes.
+pub fn line_operation_transaction(
+    text: &Rope,
+    selection: &Selection,
+    operation: LineOperation,
+    seed: u64,
+) -> Option<Transaction> {
+    let slice = text.slice(..);
+    let blocks = if selection.ranges().iter().all(|range| range.len() <= 1) {
+        vec![(0, last_content_line(slice))]
+    } else {
+        line_blocks(slice, selection).0
+    };
+
+    let mut changes = Vec::with_capacity(blocks.len());
+    for (start, end) in blocks {
+        let from = slice.line_to_char(start);
+        let to = slice.line_to_char(end + 1);
+        let region = slice.slice(from..to).to_string();
+        let lines = region.split_inclusive('\n').collect::<Vec<_>>();
+        let endings = lines
+            .iter()
+            .map(|line| split_line_ending(line).1)
+            .collect::<Vec<_>>();
+        let mut contents = lines
+            .iter()
+            .map(|line| split_line_ending(line).0)
+            .collect::<Vec<_>>();
+        operation.apply(&mut contents, seed);
+
+        let last = contents.len().saturating_sub(1);
+        let replacement = contents
+            .iter()
+            .enumerate()
+            .map(|(index, content)| {
+                let ending = match index {
+                    index if index == last => endings.last().copied().unwrap_or_default(),
+                    index => endings[index],
+                };
+                format!("{content}{ending}")
+            })
+            .collect::<String>();
+        if replacement != region {
+            changes.push((from, to, Some(Tendril::from(replacement))));
+        }
+    }
+    (!changes.is_empty()).then(|| Transaction::change(text, changes.into_iter()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(text: &str, transaction: &Transaction) -> (String, Vec<(usize, usize)>) {
+        let mut rope = Rope::from(text);
+        assert!(transaction.apply(&mut rope));
+        let ranges = transaction
+            .selection()
+            .expect("line edits set the selection")
+            .ranges()
+            .iter()
+            .map(|range| (range.anchor, range.head))
+            .collect();
+        (rope.to_string(), ranges)
+    }
+
+    #[test]
+    fn duplicating_copies_each_selected_block_below_itself() {
+        let text = Rope::from("a\nbb\nc\nd");
+        let selection = Selection::new(
+            [Range::new(2, 3), Range::new(3, 4), Range::new(7, 8)]
+                .into_iter()
+                .collect(),
+            0,
+        );
+        let transaction = duplicate_lines_transaction(&text, &selection);
+        assert_eq!(
+            apply("a\nbb\nc\nd", &transaction),
+            (
+                "a\nbb\nbb\nc\nd\nd".to_string(),
+                vec![(5, 6), (6, 7), (12, 13)]
+            )
+        );
+    }
+
+    #[test]
+    fn moving_lines_swaps_them_with_their_neighbour() {
+        let text = Rope::from("one\ntwo\nthree\n");
+        let selection = Selection::single(4, 5);
+
+        let up = move_lines_transaction(&text, &selection, LineDirection::Up).unwrap();
+        assert_eq!(
+            apply("one\ntwo\nthree\n", &up),
+            ("two\none\nthree\n".to_string(), vec![(0, 1)])
+        );
+        let down = move_lines_transaction(&text, &selection, LineDirection::Down).unwrap();
+        assert_eq!(
+            apply("one\ntwo\nthree\n", &down),
+            ("one\nthree\ntwo\n".to_string(), vec![(10, 11)])
+        );
+
+        let last = Selection::single(9, 10);
+        assert!(move_lines_transaction(&text, &last, LineDirection::Down).is_none());
+        assert!(move_lines_transaction(&text, &Selection::point(0), LineDirection::Up).is_none());
+    }
+
+    #[test]
+    fn moving_the_final_line_keeps_the_missing_newline_last() {
+        let text = Rope::from("one\ntwo");
+        let selection = Selection::single(4, 7);
+        let up = move_lines_transaction(&text, &selection, LineDirection::Up).unwrap();
+        assert_eq!(
+            apply("one\ntwo", &up),
+            ("two\none".to_string(), vec![(0, 3)])
+        );
+    }
+
+    #[test]
+    fn line_operations_rearrange_selected_lines_or_the_buffer() {
+        let text = Rope::from("b\n10\na\n9\nb");
+        let whole = |operation| {
+            let transaction =
+                line_operation_transaction(&text, &Selection::point(0), operation, 7).unwrap();
+            let mut rope = text.clone();
+            assert!(transaction.apply(&mut rope));
+            rope.to_string()
+        };
+        assert_eq!(whole(LineOperation::SortAscending), "10\n9\na\nb\nb");
+        assert_eq!(whole(LineOperation::SortNumeric), "a\nb\nb\n9\n10");
+        assert_eq!(whole(LineOperation::Unique), "b\n10\na\n9");
+        assert_eq!(whole(LineOperation::Reverse), "b\n9\na\n10\nb");
+        let mut shuffled = whole(LineOperation::Shuffle)
+            .split('\n')
+            .collect::<Vec<_>>();
+        shuffled.sort();
+        assert_eq!(shuffled, ["10", "9", "a", "b", "b"]);
+
+        // Only the lines of the selection are sorted.
+        let selection = Selection::single(2, 7);
+        let mut rope = text.clone();
+        let transaction =
+            line_operation_transaction(&text, &selection, LineOperation::SortDescending, 0)
+                .unwrap();
+        assert!(transaction.apply(&mut rope));
+        assert_eq!(rope, "b\na\n10\n9\nb");
+        assert_eq!(leading_number("  -1.5 apples"), Some(-1.5));
+    }
+}
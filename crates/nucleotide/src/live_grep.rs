@@ -0,0 +1,84 @@
+// ABOUTME: Helpers for the live grep picker, which searches the project as the user types
+// ABOUTME: Match highlighting ranges, debounce and result limits, and preview excerpts
+
+use std::ops::Range;
+use std::time::Duration;
+
+/// Command opening the live grep picker.
+pub const LIVE_GREP_COMMAND: &str = "live-grep";
+pub const LIVE_GREP_TITLE: &str = "Live Grep";
+
+/// How long typing has to pause before a search starts.
+pub const LIVE_GREP_DEBOUNCE: Duration = Duration::from_millis(150);
+/// Matches shown per query; the full search panel has a higher limit.
+pub const LIVE_GREP_RESULT_LIMIT: usize = 1000;
+
+/// Lines shown above the match in the preview pane.
+const PREVIEW_CONTEXT_LINES: usize = 8;
+const PREVIEW_LINES: usize = 200;
+
+/// Regex for highlighting matches within a line, with the same smart case
+/// rule as the search itself.
+pub fn line_regex(query: &str, smart_case: bool) -> Result<regex::Regex, String> {
+    let case_insensitive = smart_case && !query.chars().any(char::is_uppercase);
+    regex::RegexBuilder::new(query)
+        .case_insensitive(case_insensitive)
+        .build()
+        .map_err(|err| err.to_string())
+}
+
+/// Byte ranges of the non-empty matches of `regex` in `line`.
+pub fn match_ranges(regex: &regex::Regex, line: &str) -> Vec<Range<usize>> {
+    regex
+        .find_iter(line)
+        .map(|found| found.range())
+        .filter(|range| !range.is_empty())
+        .collect()
+}
+
+/// The lines of `text` around the zero-based `line`, starting a few lines
+/// above it so the match is in view.
+pub fn preview_excerpt(text: &str, line: usize) -> String {
+    text.lines()
+        .skip(line.saturating_sub(PREVIEW_CONTEXT_LINES))
+        .take(PREVIEW_LINES)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_ranges_follow_smart_case() {
+        let regex = line_regex("foo", true).unwrap();
+        assert_eq!(match_ranges(&regex, "Foo bar foo"), vec![0..3, 8..11]);
+
+        let regex = line_regex("Foo", true).unwrap();
+        assert_eq!(match_ranges(&regex, "Foo bar foo"), vec![0..3]);
+
+        let regex = line_regex("foo", false).unwrap();
+        assert_eq!(match_ranges(&regex, "Foo bar foo"), vec![8..11]);
+
+        assert!(line_regex("(unclosed", true).is_err());
+    }
+
+    #[test]
+    fn empty_matches_are_not_highlighted() {
+        let regex = line_regex("x*", false).unwrap();
+        assert_eq!(match_ranges(&regex, "axxb"), vec![1..3]);
+    }
+
+    #[test]
+    fn preview_starts_above_the_match() {
+        let text = (0..30)
+            .map(|line| format!("line {line}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert!(preview_excerpt(&text, 20).starts_with("line 12\n"));
+        assert!(preview_excerpt(&text, 3).starts_with("line 0\n"));
+        assert_eq!(preview_excerpt(&text, 99), "");
+    }
+}
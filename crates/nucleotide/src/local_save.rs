@@ -0,0 +1,334 @@
+// ABOUTME: Writes documents to local disk using the configured save strategy
+// ABOUTME: Atomic rename or in-place writes, optional backup copies and the fsync policy
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::bail;
+use helix_stdx::faccess::{copy_metadata, hardlink_count, readonly};
+use helix_view::document::{DocumentSaveData, DocumentWriteResult, to_writer};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+use crate::config::{SaveBackup, SaveFsync, SavePolicy, SaveStrategy};
+
+/// Write `save` to its local path following `policy`.
+pub async fn write_local_document(
+    save: DocumentSaveData,
+    policy: SavePolicy,
+) -> anyhow::Result<DocumentWriteResult> {
+    let mut contents = Vec::new();
+    to_writer(&mut contents, save.encoding_with_bom_info, &save.text).await?;
+    let save_time = write_local_file(
+        &save.path,
+        &contents,
+        save.force,
+        save.last_saved_time,
+        &policy,
+    )
+    .await?;
+    Ok(DocumentWriteResult {
+        save_time,
+        file_version: None,
+    })
+}
+
+/// Write `contents` to `path` following `policy`, returning the file's new
+/// modification time. Unless `force` is set, refuses to overwrite a file
+/// changed after `last_saved_time`.
+pub async fn write_local_file(
+    path: &Path,
+    contents: &[u8],
+    force: bool,
+    last_saved_time: SystemTime,
+    policy: &SavePolicy,
+) -> anyhow::Result<SystemTime> {
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+    {
+        if force {
+            fs::create_dir_all(parent).await?;
+        } else {
+            bail!("can't save file, parent directory does not exist (use :w! to create it)");
+        }
+    }
+
+    // Protect against overwriting changes made externally
+    if !force
+        && let Ok(modified) = fs::metadata(path).await.and_then(|meta| meta.modified())
+        && last_saved_time < modified
+    {
+        bail!("file modified by an external process, use :w! to overwrite");
+    }
+
+    let write_path = resolve_symlink(path).await;
+    if readonly(&write_path) {
+        bail!(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "Path is read only"
+        ));
+    }
+
+    let exists = fs::try_exists(&write_path).await.unwrap_or(false);
+    if exists && let Some(backup_path) = backup_path(&write_path, policy) {
+        if let Some(parent) = backup_path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::copy(&write_path, &backup_path).await.map_err(|error| {
+            anyhow::anyhow!("failed to write backup {}: {error}", backup_path.display())
+        })?;
+    }
+
+    // Renaming over a file with several links would detach it from the
+    // others; assume a link when the count can't be read.
+    let is_hardlink = exists && hardlink_count(&write_path).unwrap_or(2) > 1;
+    match policy.strategy {
+        SaveStrategy::Atomic if !is_hardlink => {
+            write_atomic(contents, &write_path, exists, policy.fsync).await?
+        }
+        _ => write_in_place(contents, &write_path, exists, policy.fsync).await?,
+    }
+
+    Ok(fs::metadata(&write_path)
+        .await
+        .and_then(|meta| meta.modified())
+        .unwrap_or_else(|_| SystemTime::now()))
+}
+
+/// Where the previous contents of `path` are copied before a save.
+pub fn backup_path(path: &Path, policy: &SavePolicy) -> Option<PathBuf> {
+    let file_name = path.file_name()?;
+    match policy.backup {
+        SaveBackup::None => None,
+        SaveBackup::Suffix => {
+            let mut name = file_name.to_os_string();
+            name.push("~");
+            Some(path.with_file_name(name))
+        }
+        SaveBackup::Directory => {
+            // Name the copy after the whole path so files with the same name
+            // in different directories don't overwrite each other's backups.
+            let name = path
+                .to_string_lossy()
+                .trim_start_matches(['/', '\\'])
+                .replace(['/', '\\', ':'], "%");
+            Some(policy.backup_dir.join(format!("{name}~")))
+        }
+    }
+}
+
+/// Saves go to the target of a symlink rather than replacing the link.
+async fn resolve_symlink(path: &Path) -> PathBuf {
+    match fs::read_link(path).await {
+        Ok(target) if target.is_relative() => path
+            .parent()
+            .map_or(target.clone(), |parent| parent.join(&target)),
+        Ok(target) => target,
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+async fn write_atomic(
+    contents: &[u8],
+    write_path: &Path,
+    exists: bool,
+    fsync: SaveFsync,
+) -> anyhow::Result<()> {
+    let staged = sibling_path(write_path, "save");
+    let result = async {
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&staged)
+            .await?;
+        file.write_all(contents).await?;
+        file.flush().await?;
+        if fsync != SaveFsync::Never {
+            file.sync_all().await?;
+        }
+        drop(file);
+
+        if exists {
+            let (original, staged) = (write_path.to_path_buf(), staged.clone());
+            tokio::task::spawn_blocking(move || copy_metadata(&original, &staged))
+                .await?
+                .map_err(|error| anyhow::anyhow!("failed to copy file permissions: {error}"))?;
+        }
+        fs::rename(&staged, write_path).await?;
+        anyhow::Ok(())
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = fs::remove_file(&staged).await;
+    }
+    result?;
+
+    if fsync == SaveFsync::Full {
+        sync_parent_dir(write_path).await?;
+    }
+    Ok(())
+}
+
+async fn write_in_place(
+    contents: &[u8],
+    write_path: &Path,
+    exists: bool,
+    fsync: SaveFsync,
+) -> anyhow::Result<()> {
+    // Keep the old contents until the write succeeds so a failure halfway
+    // through doesn't leave a truncated file behind.
+    let recovery = if exists {
+        let recovery = sibling_path(write_path, "bck");
+        fs::copy(write_path, &recovery).await?;
+        Some(recovery)
+    } else {
+        None
+    };
+
+    let result = async {
+        let mut file = fs::File::create(write_path).await?;
+        file.write_all(contents).await?;
+        file.flush().await?;
+        if fsync != SaveFsync::Never {
+            file.sync_all().await?;
+        }
+        if fsync == SaveFsync::Full && !exists {
+            sync_parent_dir(write_path).await?;
+        }
+        anyhow::Ok(())
+    }
+    .await;
+
+    if let Some(recovery) = recovery {
+        if result.is_err()
+            && let Err(error) = fs::copy(&recovery, write_path).await
+        {
+            nucleotide_logging::error!(
+                "Failed to restore {} from {}: {error}",
+                write_path.display(),
+                recovery.display()
+            );
+            return result;
+        }
+        let _ = fs::remove_file(&recovery).await;
+    }
+    result
+}
+
+/// A hidden path next to `path` that no other save is using.
+fn sibling_path(path: &Path, kind: &str) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    path.with_file_name(format!(".{name}.{kind}-{}-{nanos}", std::process::id()))
+}
+
+#[cfg(unix)]
+async fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) => fs::File::open(parent).await?.sync_all().await,
+        None => Ok(()),
+    }
+}
+
+/// Directories can't be opened for syncing here; the file sync has to do.
+#[cfg(not(unix))]
+async fn sync_parent_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helix_core::Rope;
+
+    fn policy(strategy: SaveStrategy, backup: SaveBackup, backup_dir: &Path) -> SavePolicy {
+        SavePolicy {
+            strategy,
+            backup,
+            backup_dir: backup_dir.to_path_buf(),
+            fsync: SaveFsync::Full,
+        }
+    }
+
+    fn save_data(path: &Path, text: &str) -> DocumentSaveData {
+        DocumentSaveData {
+            path: path.to_path_buf(),
+            previous_path: Some(path.to_path_buf()),
+            text: Rope::from(text),
+            encoding_with_bom_info: (helix_core::encoding::UTF_8, false),
+            last_saved_time: SystemTime::now() + std::time::Duration::from_secs(60),
+            file_version: None,
+            force: false,
+        }
+    }
+
+    #[test]
+    fn backup_paths_follow_the_backup_mode() {
+        let dir = Path::new("/var/backups");
+        let path = Path::new("/home/me/src/main.rs");
+
+        assert_eq!(
+            backup_path(path, &policy(SaveStrategy::Atomic, SaveBackup::None, dir)),
+            None
+        );
+        assert_eq!(
+            backup_path(path, &policy(SaveStrategy::Atomic, SaveBackup::Suffix, dir)),
+            Some(PathBuf::from("/home/me/src/main.rs~"))
+        );
+        assert_eq!(
+            backup_path(
+                path,
+                &policy(SaveStrategy::Atomic, SaveBackup::Directory, dir)
+            ),
+            Some(PathBuf::from("/var/backups/home%me%src%main.rs~"))
+        );
+    }
+
+    #[tokio::test]
+    async fn atomic_saves_replace_the_file_and_keep_a_backup() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("notes.md");
+        std::fs::write(&path, "old\n").unwrap();
+
+        let policy = policy(SaveStrategy::Atomic, SaveBackup::Suffix, temp.path());
+        write_local_document(save_data(&path, "new\n"), policy)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new\n");
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("notes.md~")).unwrap(),
+            "old\n"
+        );
+        let leftovers = std::fs::read_dir(temp.path()).unwrap().count();
+        assert_eq!(leftovers, 2);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn in_place_saves_keep_hard_links() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("config.toml");
+        let link = temp.path().join("linked.toml");
+        std::fs::write(&path, "a = 1\n").unwrap();
+        std::fs::hard_link(&path, &link).unwrap();
+
+        for strategy in [SaveStrategy::InPlace, SaveStrategy::Atomic] {
+            let text = format!("strategy = \"{strategy:?}\"\n");
+            let policy = policy(strategy, SaveBackup::None, temp.path());
+            write_local_document(save_data(&path, &text), policy)
+                .await
+                .unwrap();
+            assert_eq!(std::fs::read_to_string(&link).unwrap(), text);
+        }
+        assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 2);
+    }
+}
@@ -25,6 +25,14 @@ pub fn log_directory() -> PathBuf {
         ))
 }
 
+/// Rotating per-server logs written by the built-in traffic inspector proxy.
+pub fn inspector_log_directory() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("nucleotide")
+        .join("lsp-traffic")
+}
+
 struct LoggerState {
     enabled: bool,
     inner: Mutex<Inner>,
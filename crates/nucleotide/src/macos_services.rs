@@ -0,0 +1,127 @@
+// ABOUTME: macOS Services provider and Share menu for the focused document
+// ABOUTME: Services text is handed to the app over a channel and opened as a scratch buffer
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use nucleotide_logging::{debug, warn};
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, NSObject};
+use objc2::{MainThreadOnly, class, define_class, msg_send};
+use objc2_app_kit::{NSApplication, NSPasteboard, NSPasteboardTypeString};
+use objc2_foundation::{MainThreadMarker, NSArray, NSPoint, NSRect, NSSize, NSString, NSURL};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// `NSMinYEdge`: the share picker opens below its anchor.
+const PREFERRED_EDGE_BELOW: usize = 1;
+/// Distance of the share picker's anchor from the window's top right corner.
+const SHARE_ANCHOR_INSET: f64 = 48.0;
+
+static SERVICE_TEXT: OnceLock<UnboundedSender<String>> = OnceLock::new();
+
+thread_local! {
+    /// The picker being shown. AppKit does not keep it alive on its own.
+    static SHARE_PICKER: RefCell<Option<Retained<AnyObject>>> = const { RefCell::new(None) };
+}
+
+define_class!(
+    // SAFETY: NSObject has no subclassing requirements and the provider
+    // does not implement Drop.
+    #[unsafe(super(NSObject))]
+    #[thread_kind = MainThreadOnly]
+    #[name = "NucleotideServicesProvider"]
+    struct ServicesProvider;
+
+    impl ServicesProvider {
+        /// The `NSMessage` of the "New Nucleotide Buffer With Selection"
+        /// service declared in the bundle's Info.plist.
+        #[unsafe(method(newBufferWithSelection:userData:error:))]
+        fn new_buffer_with_selection(
+            &self,
+            pasteboard: &NSPasteboard,
+            _user_data: Option<&NSString>,
+            _error: *mut *mut NSString,
+        ) {
+            let text = unsafe { pasteboard.stringForType(NSPasteboardTypeString) };
+            let Some(text) = text.map(|text| text.to_string()) else {
+                debug!("Services request without plain text");
+                return;
+            };
+            match SERVICE_TEXT.get() {
+                Some(sender) => {
+                    if sender.send(text).is_err() {
+                        warn!("Services request arrived after the app stopped listening");
+                    }
+                }
+                None => warn!("Services request arrived before the provider was installed"),
+            }
+        }
+    }
+);
+
+/// Registers the Services provider. Text sent to the service is delivered
+/// on `sender`.
+pub fn install_services_provider(sender: UnboundedSender<String>) {
+    let Some(mtm) = MainThreadMarker::new() else {
+        warn!("Services provider must be installed on the main thread");
+        return;
+    };
+    if SERVICE_TEXT.set(sender).is_err() {
+        return;
+    }
+
+    let provider: Retained<ServicesProvider> =
+        unsafe { msg_send![ServicesProvider::alloc(mtm), init] };
+    let app = NSApplication::sharedApplication(mtm);
+    unsafe {
+        let _: () = msg_send![&*app, setServicesProvider: &*provider];
+    }
+    // The provider serves requests for the rest of the process.
+    std::mem::forget(provider);
+}
+
+/// Shows the Share picker for `path`, anchored below the key window's top
+/// right corner.
+pub fn share_file(path: &Path) {
+    let Some(mtm) = MainThreadMarker::new() else {
+        warn!("Share picker must be shown on the main thread");
+        return;
+    };
+    let app = NSApplication::sharedApplication(mtm);
+
+    unsafe {
+        let window: *mut AnyObject = msg_send![&*app, keyWindow];
+        if window.is_null() {
+            return;
+        }
+        let view: *mut AnyObject = msg_send![window, contentView];
+        if view.is_null() {
+            return;
+        }
+        let bounds: NSRect = msg_send![view, bounds];
+        let anchor = NSRect::new(
+            NSPoint::new(
+                bounds.size.width - SHARE_ANCHOR_INSET,
+                bounds.size.height - SHARE_ANCHOR_INSET / 2.0,
+            ),
+            NSSize::new(1.0, 1.0),
+        );
+
+        let url = NSURL::fileURLWithPath(&NSString::from_str(&path.to_string_lossy()));
+        let items = NSArray::from_retained_slice(&[url]);
+        let picker: *mut AnyObject = msg_send![class!(NSSharingServicePicker), alloc];
+        let picker: *mut AnyObject = msg_send![picker, initWithItems: &*items];
+        let Some(picker) = Retained::from_raw(picker) else {
+            warn!(path = %path.display(), "Could not create a share picker");
+            return;
+        };
+        let _: () = msg_send![
+            &*picker,
+            showRelativeToRect: anchor,
+            ofView: view,
+            preferredEdge: PREFERRED_EDGE_BELOW
+        ];
+        SHARE_PICKER.with(|current| *current.borrow_mut() = Some(picker));
+    }
+}
@@ -2,7 +2,7 @@
 #![cfg_attr(all(windows, not(debug_assertions)), windows_subsystem = "windows")]
 
 use std::panic;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use helix_term::args::Args;
@@ -15,6 +15,7 @@ use gpui::{
 // Import from the library crate instead of re-declaring modules
 use nucleotide::application::{Application, MaintenanceWake};
 use nucleotide::input_coordinator::InputCoordinator;
+use nucleotide::startup_profile::{StartupPhase, StartupProfile};
 use nucleotide::{self, ThemeManager, config, info_box, notification, overlay, types, workspace};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -83,15 +84,14 @@ fn install_panic_handler() {
         );
 
         // Log backtrace if enabled
-        if let Ok(backtrace) = std::env::var("RUST_BACKTRACE")
-            && (backtrace == "1" || backtrace == "full")
+        let mut backtrace = None;
+        if let Ok(setting) = std::env::var("RUST_BACKTRACE")
+            && (setting == "1" || setting == "full")
         {
-            let bt = std::backtrace::Backtrace::capture();
-            nucleotide_logging::error!(
-                backtrace = %format!("{:?}", bt),
-                "Panic backtrace"
-            );
-            eprintln!("Backtrace:\n{:?}", bt);
+            let bt = format!("{:?}", std::backtrace::Backtrace::capture());
+            nucleotide_logging::error!(backtrace = %bt, "Panic backtrace");
+            eprintln!("Backtrace:\n{bt}");
+            backtrace = Some(bt);
         }
 
         // Log system information for debugging
@@ -102,6 +102,17 @@ fn install_panic_handler() {
             "System information at panic time"
         );
 
+        // Leave a structured report for the next launch to offer as an issue
+        let report = nucleotide::error_report::ErrorReport::panic(
+            panic_message.clone(),
+            location.clone(),
+            std::thread::current().name().map(str::to_string),
+            backtrace,
+        );
+        if let Err(e) = report.save_pending() {
+            eprintln!("Failed to save crash report: {e}");
+        }
+
         // Try to save any unsaved work would go here if we had access to the app state
         // For now, just log and exit gracefully
         error!("Fatal error: {panic_message}");
@@ -386,6 +397,16 @@ fn startup_protocol_request() -> Result<Option<ProtocolOpenRequest>> {
 fn apply_protocol_request_to_args(_args: &mut Args, _request: ProtocolOpenRequest) {}
 
 fn main() -> Result<()> {
+    let mut startup_profile = StartupProfile::new(Instant::now());
+
+    // Language servers wrapped by the LSP traffic inspector re-enter the binary
+    // here; serve the proxy before any GUI or updater startup runs.
+    let mut raw_args = std::env::args().skip(1);
+    if raw_args.next().as_deref() == Some(nucleotide_lsp::TRAFFIC_PROXY_FLAG) {
+        let proxy_args = nucleotide_lsp::TrafficProxyArgs::parse(raw_args)?;
+        return nucleotide_lsp::run_traffic_proxy(proxy_args);
+    }
+
     nucleotide::updates::run_startup_hooks();
 
     // Set HELIX_RUNTIME for packaged apps before any Helix runtime lookup occurs.
@@ -455,17 +476,21 @@ fn main() -> Result<()> {
     windows_single_instance::start_listener(platform_open_tx.clone());
 
     // Load our combined configuration (helix + gui)
-    let config = match crate::config::Config::load() {
-        Ok(config) => config,
-        Err(err) => {
-            error!("Failed to load configuration: {err}");
-            error!("Using default configuration");
-            nucleotide::config::Config {
-                helix: helix_term::config::Config::default(),
-                gui: nucleotide::config::GuiConfig::default(),
-            }
-        }
-    };
+    let config =
+        startup_profile.measure(
+            StartupPhase::ConfigLoad,
+            || match crate::config::Config::load() {
+                Ok(config) => config,
+                Err(err) => {
+                    error!("Failed to load configuration: {err}");
+                    error!("Using default configuration");
+                    nucleotide::config::Config {
+                        helix: helix_term::config::Config::default(),
+                        gui: nucleotide::config::GuiConfig::default(),
+                    }
+                }
+            },
+        );
 
     let workspace_trust = helix_loader::workspace_trust::WorkspaceTrust::new(
         (&config.helix.editor.workspace_trust).into(),
@@ -508,18 +533,22 @@ fn main() -> Result<()> {
 
     // Initialize the editor AFTER the Tokio runtime is created and entered
     // This is critical because Helix LSP components need an active Tokio runtime
-    let app = nucleotide::application::init_editor(
-        args,
-        config.helix.clone(),
-        config.clone(),
-        lang_loader,
-    )
-    .context("unable to create new application")?;
+    let app = startup_profile
+        .measure(StartupPhase::EditorInit, || {
+            nucleotide::application::init_editor(
+                args,
+                config.helix.clone(),
+                config.clone(),
+                lang_loader,
+            )
+        })
+        .context("unable to create new application")?;
 
     info!("Starting GUI main loop");
     gui_main(
         app,
         config,
+        startup_profile,
         handle.clone(),
         workspace_root,
         initial_dock_action,
@@ -852,40 +881,114 @@ use nucleotide::actions::window::{Hide, HideOthers, ShowAll};
 use nucleotide::actions::workspace::{
     NewFile, NewWindow, ShowBufferPicker, ShowCodeActions, ShowCommandPrompt, ShowFileFinder,
 };
+use nucleotide::actions::{
+    app_menu::{ActivateWindow, OpenRecent},
+    editor::{CloseFile, Save, SaveAs},
+};
 #[cfg(target_os = "windows")]
 use nucleotide::actions::{
     completion::TriggerCompletion,
-    editor::{CloseFile, DecreaseFontSize, IncreaseFontSize, Save, SaveAs},
+    editor::{DecreaseFontSize, IncreaseFontSize},
 };
 use nucleotide::actions::{
     editor::{
-        CancelRemoteConnection, OpenDirectory, OpenFile, OpenRemote, OpenSettings, Quit,
-        ReconnectRemote, ReloadConfiguration, RevertCurrentChange,
+        AlignBy, CancelRemoteConnection, CloseFolder, DuplicateLines, GenerateDocComment,
+        JoinLines, MoveLinesDown, MoveLinesUp, OpenDirectory, OpenFile, OpenRemote, OpenSettings,
+        OpenWorkspaceSettings, Quit, ReconnectRemote, ReflowParagraph, ReloadConfiguration,
+        ReverseLines, RevertCurrentChange, ShuffleLines, SortLines, SortLinesDescending,
+        SortLinesNumeric, SortLinesNumericDescending, ToggleComment, TransformCamelCase,
+        TransformKebabCase, TransformLowerCase, TransformPascalCase, TransformSnakeCase,
+        TransformTitleCase, TransformUpperCase, UniqueLines,
+    },
+    git::{
+        AcceptBoth, AcceptOurs, AcceptTheirs, CancelOperation, Fetch, NextConflict,
+        PreviousConflict, Pull, Push, ShowConflicts, ShowFileHistory, ShowLog, Stash, StashPop,
+    },
+    help::{
+        About, ComponentGallery, OpenTutorial, ThemeDebug, ToggleFocusDebugger,
+        TogglePerformanceHud,
     },
-    help::{About, ComponentGallery, OpenTutorial, ThemeDebug},
     test::{TestCompletion, TestPrompt},
     window::{Minimize, Zoom},
     workspace::{
-        RunFileTests, RunLast, RunNearest, ShowRunnables, SplitPaneDown, SplitPaneLeft,
-        SplitPaneRight, SplitPaneUp, ToggleDocumentation, ToggleFileTree, TogglePreviewTab,
-        ToggleTerminal, UnpinAllTabs,
+        ActivateLastUsedTab, ActivateNextTab, ActivatePreviousTab, CloseComparison,
+        CompareActiveFileWith, CompareWithClipboard, CopyDifferenceLeft, CopyDifferenceRight,
+        ExportDocumentAsHtml, ExportDocumentAsPdf, ExportSnippetAsHtml, ExportSnippetAsSvg,
+        ExportTerminalAsHtml, ExportTerminalAsSvg, NewProject, NewScratchBuffer, NextDifference,
+        PreviousDifference, ReopenClosedTab, RunConfiguration, RunFileTests, RunLast, RunNearest,
+        ShowAllTabs, ShowCargoSettings, ShowEnvironmentInspector, ShowIndentation, ShowLspTraffic,
+        ShowNpmScripts, ShowRunConfigurations, ShowRunProblems, ShowRunnables, ShowScratchBuffers,
+        ShowTypeHierarchy, SplitPaneDown, SplitPaneLeft, SplitPaneRight, SplitPaneUp,
+        ToggleDocumentation, ToggleFileTree, TogglePreviewTab, ToggleRegexTester, ToggleTerminal,
+        UnpinAllTabs,
     },
 };
+use nucleotide::menu_state::{AppMenuState, AppMenus, recent_path_label};
 
-fn app_menus() -> Vec<Menu> {
+fn app_menus(state: &AppMenuState) -> Vec<Menu> {
     #[cfg(target_os = "windows")]
     {
-        windows_app_menus()
+        windows_app_menus(state)
     }
 
     #[cfg(not(target_os = "windows"))]
     {
-        default_app_menus()
+        default_app_menus(state)
     }
 }
 
+/// Recent files of the current project, then other recent projects.
+fn open_recent_menu(state: &AppMenuState) -> MenuItem {
+    let mut items = state
+        .recent_files
+        .iter()
+        .map(|path| {
+            MenuItem::action(
+                recent_path_label(path, None),
+                OpenRecent {
+                    path: path.clone(),
+                    directory: false,
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    if !items.is_empty() && !state.recent_projects.is_empty() {
+        items.push(MenuItem::separator());
+    }
+    items.extend(state.recent_projects.iter().map(|path| {
+        MenuItem::action(
+            recent_path_label(path, None),
+            OpenRecent {
+                path: path.clone(),
+                directory: true,
+            },
+        )
+    }));
+    let empty = items.is_empty();
+    MenuItem::submenu(Menu::new("Open Recent").items(items)).disabled(empty)
+}
+
+/// One checked-when-active entry per open window.
+fn window_list_items(state: &AppMenuState) -> Vec<MenuItem> {
+    if state.windows.is_empty() {
+        return Vec::new();
+    }
+    let mut items = vec![MenuItem::separator()];
+    items.extend(state.windows.iter().map(|window| {
+        MenuItem::action(
+            window.title.clone(),
+            ActivateWindow {
+                window_id: window.window_id,
+            },
+        )
+        .checked(window.active)
+    }));
+    items
+}
+
 #[cfg(not(target_os = "windows"))]
-fn default_app_menus() -> Vec<Menu> {
+fn default_app_menus(state: &AppMenuState) -> Vec<Menu> {
+    let no_document = !state.has_document;
     vec![
         Menu {
             name: "Nucleotide".into(),
@@ -894,6 +997,7 @@ fn default_app_menus() -> Vec<Menu> {
                 MenuItem::action("About", About),
                 MenuItem::action("Check for Updates…", CheckForUpdates),
                 MenuItem::action("Settings...", OpenSettings),
+                MenuItem::action("Workspace Settings...", OpenWorkspaceSettings),
                 MenuItem::action("Reload Configuration", ReloadConfiguration),
                 MenuItem::separator(),
                 MenuItem::action("Hide Nucleotide", Hide),
@@ -908,9 +1012,48 @@ fn default_app_menus() -> Vec<Menu> {
             items: vec![
                 MenuItem::action("Open...", OpenFile),
                 MenuItem::action("Open Directory", OpenDirectory),
+                MenuItem::action("New Project...", NewProject),
+                open_recent_menu(state),
+                MenuItem::action("Close Folder", CloseFolder),
                 MenuItem::action("Open Remote...", OpenRemote),
                 MenuItem::action("Reconnect Remote", ReconnectRemote),
                 MenuItem::action("Cancel Remote Connection", CancelRemoteConnection),
+                MenuItem::separator(),
+                MenuItem::action("Save", Save).disabled(!state.has_dirty_document),
+                MenuItem::action("Save As...", SaveAs).disabled(no_document),
+                MenuItem::action("Close File", CloseFile).disabled(no_document),
+                MenuItem::separator(),
+                MenuItem::action("New Scratch Buffer...", NewScratchBuffer),
+                MenuItem::action("Scratch Buffers...", ShowScratchBuffers),
+                MenuItem::separator(),
+                MenuItem::submenu(Menu {
+                    name: "Compare".into(),
+                    disabled: false,
+                    items: vec![
+                        MenuItem::action("Active File With...", CompareActiveFileWith),
+                        MenuItem::action("Active File With Clipboard", CompareWithClipboard),
+                        MenuItem::separator(),
+                        MenuItem::action("Next Difference", NextDifference),
+                        MenuItem::action("Previous Difference", PreviousDifference),
+                        MenuItem::action("Copy Difference to Left", CopyDifferenceLeft),
+                        MenuItem::action("Copy Difference to Right", CopyDifferenceRight),
+                        MenuItem::separator(),
+                        MenuItem::action("Close Comparison", CloseComparison),
+                    ],
+                }),
+                MenuItem::submenu(Menu {
+                    name: "Export".into(),
+                    disabled: no_document,
+                    items: vec![
+                        MenuItem::action("Document as HTML", ExportDocumentAsHtml),
+                        MenuItem::action("Document as PDF", ExportDocumentAsPdf),
+                        MenuItem::separator(),
+                        MenuItem::action("Snippet as SVG", ExportSnippetAsSvg),
+                        MenuItem::action("Snippet as HTML", ExportSnippetAsHtml),
+                        MenuItem::action("Terminal as SVG", ExportTerminalAsSvg),
+                        MenuItem::action("Terminal as HTML", ExportTerminalAsHtml),
+                    ],
+                }),
             ],
         },
         Menu {
@@ -924,15 +1067,55 @@ fn default_app_menus() -> Vec<Menu> {
                 MenuItem::separator(),
                 MenuItem::action("Copy", Copy),
                 MenuItem::action("Paste", Paste),
+                MenuItem::separator(),
+                MenuItem::action("Duplicate Lines", DuplicateLines),
+                MenuItem::action("Move Lines Up", MoveLinesUp),
+                MenuItem::action("Move Lines Down", MoveLinesDown),
+                MenuItem::action("Join Lines", JoinLines),
+                MenuItem::action("Toggle Comment", ToggleComment),
+                MenuItem::action("Reflow Paragraph", ReflowParagraph),
+                MenuItem::action("Align By...", AlignBy),
+                MenuItem::action("Generate Doc Comment", GenerateDocComment),
+                MenuItem::submenu(Menu {
+                    name: "Transform".into(),
+                    disabled: no_document,
+                    items: vec![
+                        MenuItem::action("Upper Case", TransformUpperCase),
+                        MenuItem::action("Lower Case", TransformLowerCase),
+                        MenuItem::action("Title Case", TransformTitleCase),
+                        MenuItem::action("camelCase", TransformCamelCase),
+                        MenuItem::action("snake_case", TransformSnakeCase),
+                        MenuItem::action("kebab-case", TransformKebabCase),
+                        MenuItem::action("PascalCase", TransformPascalCase),
+                    ],
+                }),
+                MenuItem::submenu(Menu {
+                    name: "Sort Lines".into(),
+                    disabled: no_document,
+                    items: vec![
+                        MenuItem::action("Sort Ascending", SortLines),
+                        MenuItem::action("Sort Descending", SortLinesDescending),
+                        MenuItem::action("Sort Numerically", SortLinesNumeric),
+                        MenuItem::action("Sort Numerically Descending", SortLinesNumericDescending),
+                        MenuItem::action("Remove Duplicates", UniqueLines),
+                        MenuItem::action("Reverse", ReverseLines),
+                        MenuItem::action("Shuffle", ShuffleLines),
+                    ],
+                }),
+                MenuItem::separator(),
+                MenuItem::action("Indentation...", ShowIndentation),
             ],
         },
         Menu {
             name: "View".into(),
             disabled: false,
             items: vec![
-                MenuItem::action("Toggle File Tree", ToggleFileTree),
-                MenuItem::action("Toggle Documentation", ToggleDocumentation),
-                MenuItem::action("Toggle Terminal", ToggleTerminal),
+                MenuItem::action("File Tree", ToggleFileTree).checked(state.file_tree_visible),
+                MenuItem::action("Documentation", ToggleDocumentation)
+                    .checked(state.documentation_visible),
+                MenuItem::action("Type Hierarchy", ShowTypeHierarchy),
+                MenuItem::action("Toggle Regex Tester", ToggleRegexTester),
+                MenuItem::action("Terminal", ToggleTerminal).checked(state.terminal_visible),
                 MenuItem::separator(),
                 MenuItem::action("Split Right", SplitPaneRight),
                 MenuItem::action("Split Left", SplitPaneLeft),
@@ -941,6 +1124,12 @@ fn default_app_menus() -> Vec<Menu> {
                 MenuItem::separator(),
                 MenuItem::action("Toggle Preview Tab", TogglePreviewTab),
                 MenuItem::action("Unpin All Tabs", UnpinAllTabs),
+                MenuItem::separator(),
+                MenuItem::action("Show All Tabs", ShowAllTabs),
+                MenuItem::action("Next Tab", ActivateNextTab),
+                MenuItem::action("Previous Tab", ActivatePreviousTab),
+                MenuItem::action("Last Used Tab", ActivateLastUsedTab),
+                MenuItem::action("Reopen Closed Tab", ReopenClosedTab),
             ],
         },
         Menu {
@@ -952,15 +1141,48 @@ fn default_app_menus() -> Vec<Menu> {
                 MenuItem::action("Run File Tests", RunFileTests),
                 MenuItem::separator(),
                 MenuItem::action("Run Last", RunLast),
+                MenuItem::separator(),
+                MenuItem::action("Run Configurations...", ShowRunConfigurations),
+                MenuItem::action("Run Configuration", RunConfiguration),
+                MenuItem::action("Run Problems", ShowRunProblems),
+                MenuItem::action("Cargo Settings...", ShowCargoSettings),
+                MenuItem::action("NPM Scripts...", ShowNpmScripts),
             ],
         },
         Menu {
-            name: "Window".into(),
+            name: "Git".into(),
             disabled: false,
             items: vec![
+                MenuItem::action("Fetch", Fetch),
+                MenuItem::action("Pull", Pull),
+                MenuItem::action("Push", Push),
+                MenuItem::separator(),
+                MenuItem::action("Stash Changes", Stash),
+                MenuItem::action("Pop Stash", StashPop),
+                MenuItem::separator(),
+                MenuItem::action("Cancel Git Operation", CancelOperation),
+                MenuItem::separator(),
+                MenuItem::action("Merge Conflicts...", ShowConflicts),
+                MenuItem::action("Next Conflict", NextConflict),
+                MenuItem::action("Previous Conflict", PreviousConflict),
+                MenuItem::action("Accept Ours", AcceptOurs),
+                MenuItem::action("Accept Theirs", AcceptTheirs),
+                MenuItem::action("Accept Both", AcceptBoth),
+                MenuItem::separator(),
+                MenuItem::action("Show Log", ShowLog),
+                MenuItem::action("Show File History", ShowFileHistory),
+            ],
+        },
+        Menu {
+            name: "Window".into(),
+            disabled: false,
+            items: [
                 MenuItem::action("Minimize", Minimize),
                 MenuItem::action("Zoom", Zoom),
-            ],
+            ]
+            .into_iter()
+            .chain(window_list_items(state))
+            .collect(),
         },
         Menu {
             name: "Debug".into(),
@@ -971,6 +1193,11 @@ fn default_app_menus() -> Vec<Menu> {
                 MenuItem::separator(),
                 MenuItem::action("Component Gallery", ComponentGallery),
                 MenuItem::action("Theme Debug", ThemeDebug),
+                MenuItem::action("Performance HUD", TogglePerformanceHud),
+                MenuItem::action("Focus Debugger", ToggleFocusDebugger),
+                MenuItem::separator(),
+                MenuItem::action("Show LSP Traffic", ShowLspTraffic),
+                MenuItem::action("Show Environment", ShowEnvironmentInspector),
             ],
         },
         Menu {
@@ -982,23 +1209,54 @@ fn default_app_menus() -> Vec<Menu> {
 }
 
 #[cfg(target_os = "windows")]
-fn windows_app_menus() -> Vec<Menu> {
+fn windows_app_menus(state: &AppMenuState) -> Vec<Menu> {
+    let no_document = !state.has_document;
     vec![
         Menu::new("File").items([
             MenuItem::action("New File", NewFile),
             MenuItem::action("New Window", NewWindow),
+            MenuItem::action("New Project...", NewProject),
+            MenuItem::action("New Scratch Buffer...", NewScratchBuffer),
+            MenuItem::action("Scratch Buffers...", ShowScratchBuffers),
             MenuItem::separator(),
             MenuItem::action("Open File...", OpenFile),
             MenuItem::action("Open Folder...", OpenDirectory),
+            open_recent_menu(state),
+            MenuItem::action("Close Folder", CloseFolder),
             MenuItem::action("Open Remote...", OpenRemote),
             MenuItem::action("Reconnect Remote", ReconnectRemote),
             MenuItem::action("Cancel Remote Connection", CancelRemoteConnection),
             MenuItem::separator(),
-            MenuItem::action("Save", Save),
-            MenuItem::action("Save As...", SaveAs),
-            MenuItem::action("Close File", CloseFile),
+            MenuItem::action("Save", Save).disabled(!state.has_dirty_document),
+            MenuItem::action("Save As...", SaveAs).disabled(no_document),
+            MenuItem::action("Close File", CloseFile).disabled(no_document),
+            MenuItem::separator(),
+            MenuItem::submenu(Menu::new("Compare").items([
+                MenuItem::action("Active File With...", CompareActiveFileWith),
+                MenuItem::action("Active File With Clipboard", CompareWithClipboard),
+                MenuItem::separator(),
+                MenuItem::action("Next Difference", NextDifference),
+                MenuItem::action("Previous Difference", PreviousDifference),
+                MenuItem::action("Copy Difference to Left", CopyDifferenceLeft),
+                MenuItem::action("Copy Difference to Right", CopyDifferenceRight),
+                MenuItem::separator(),
+                MenuItem::action("Close Comparison", CloseComparison),
+            ])),
+            MenuItem::submenu(Menu::new("Export").disabled(no_document).items([
+                MenuItem::action("Document as HTML", ExportDocumentAsHtml),
+                MenuItem::action("Document as PDF", ExportDocumentAsPdf),
+                MenuItem::separator(),
+                MenuItem::action("Snippet as SVG", ExportSnippetAsSvg),
+                MenuItem::action("Snippet as HTML", ExportSnippetAsHtml),
+                MenuItem::action("Terminal as SVG", ExportTerminalAsSvg),
+                MenuItem::action("Terminal as HTML", ExportTerminalAsHtml),
+            ])),
+            #[cfg(target_os = "macos")]
+            MenuItem::action("Share...", nucleotide::actions::workspace::ShareFile)
+                .disabled(no_document),
             MenuItem::separator(),
             MenuItem::action("Settings...", OpenSettings),
+            MenuItem::action("Workspace Settings...", OpenWorkspaceSettings),
             MenuItem::action("Reload Configuration", ReloadConfiguration),
             MenuItem::separator(),
             MenuItem::action("Exit", Quit),
@@ -1014,15 +1272,47 @@ fn windows_app_menus() -> Vec<Menu> {
             MenuItem::separator(),
             MenuItem::action("Trigger Completion", TriggerCompletion),
             MenuItem::action("Code Actions", ShowCodeActions),
+            MenuItem::separator(),
+            MenuItem::action("Duplicate Lines", DuplicateLines),
+            MenuItem::action("Move Lines Up", MoveLinesUp),
+            MenuItem::action("Move Lines Down", MoveLinesDown),
+            MenuItem::action("Join Lines", JoinLines),
+            MenuItem::action("Toggle Comment", ToggleComment),
+            MenuItem::action("Reflow Paragraph", ReflowParagraph),
+            MenuItem::action("Align By...", AlignBy),
+            MenuItem::action("Generate Doc Comment", GenerateDocComment),
+            MenuItem::submenu(Menu::new("Transform").disabled(no_document).items([
+                MenuItem::action("Upper Case", TransformUpperCase),
+                MenuItem::action("Lower Case", TransformLowerCase),
+                MenuItem::action("Title Case", TransformTitleCase),
+                MenuItem::action("camelCase", TransformCamelCase),
+                MenuItem::action("snake_case", TransformSnakeCase),
+                MenuItem::action("kebab-case", TransformKebabCase),
+                MenuItem::action("PascalCase", TransformPascalCase),
+            ])),
+            MenuItem::submenu(Menu::new("Sort Lines").disabled(no_document).items([
+                MenuItem::action("Sort Ascending", SortLines),
+                MenuItem::action("Sort Descending", SortLinesDescending),
+                MenuItem::action("Sort Numerically", SortLinesNumeric),
+                MenuItem::action("Sort Numerically Descending", SortLinesNumericDescending),
+                MenuItem::action("Remove Duplicates", UniqueLines),
+                MenuItem::action("Reverse", ReverseLines),
+                MenuItem::action("Shuffle", ShuffleLines),
+            ])),
+            MenuItem::separator(),
+            MenuItem::action("Indentation...", ShowIndentation),
         ]),
         Menu::new("View").items([
             MenuItem::action("Command Palette...", ShowCommandPrompt),
             MenuItem::action("Go to File...", ShowFileFinder),
             MenuItem::action("Open Buffer...", ShowBufferPicker),
             MenuItem::separator(),
-            MenuItem::action("File Tree", ToggleFileTree),
-            MenuItem::action("Documentation", ToggleDocumentation),
-            MenuItem::action("Terminal", ToggleTerminal),
+            MenuItem::action("File Tree", ToggleFileTree).checked(state.file_tree_visible),
+            MenuItem::action("Documentation", ToggleDocumentation)
+                .checked(state.documentation_visible),
+            MenuItem::action("Type Hierarchy", ShowTypeHierarchy),
+            MenuItem::action("Regex Tester", ToggleRegexTester),
+            MenuItem::action("Terminal", ToggleTerminal).checked(state.terminal_visible),
             MenuItem::action("Preview Tab", TogglePreviewTab),
             MenuItem::separator(),
             MenuItem::submenu(Menu::new("Split").items([
@@ -1036,6 +1326,11 @@ fn windows_app_menus() -> Vec<Menu> {
             MenuItem::action("Decrease Font Size", DecreaseFontSize),
             MenuItem::separator(),
             MenuItem::action("Unpin All Tabs", UnpinAllTabs),
+            MenuItem::action("Show All Tabs", ShowAllTabs),
+            MenuItem::action("Next Tab", ActivateNextTab),
+            MenuItem::action("Previous Tab", ActivatePreviousTab),
+            MenuItem::action("Last Used Tab", ActivateLastUsedTab),
+            MenuItem::action("Reopen Closed Tab", ReopenClosedTab),
         ]),
         Menu::new("Run").items([
             MenuItem::action("Run...", ShowRunnables),
@@ -1043,17 +1338,52 @@ fn windows_app_menus() -> Vec<Menu> {
             MenuItem::action("Run File Tests", RunFileTests),
             MenuItem::separator(),
             MenuItem::action("Run Last", RunLast),
+            MenuItem::separator(),
+            MenuItem::action("Run Configurations...", ShowRunConfigurations),
+            MenuItem::action("Run Configuration", RunConfiguration),
+            MenuItem::action("Run Problems", ShowRunProblems),
+            MenuItem::action("Cargo Settings...", ShowCargoSettings),
+            MenuItem::action("NPM Scripts...", ShowNpmScripts),
         ]),
-        Menu::new("Window").items([
-            MenuItem::action("Minimize", Minimize),
-            MenuItem::action("Maximize/Restore", Zoom),
+        Menu::new("Git").items([
+            MenuItem::action("Fetch", Fetch),
+            MenuItem::action("Pull", Pull),
+            MenuItem::action("Push", Push),
+            MenuItem::separator(),
+            MenuItem::action("Stash Changes", Stash),
+            MenuItem::action("Pop Stash", StashPop),
+            MenuItem::separator(),
+            MenuItem::action("Cancel Git Operation", CancelOperation),
+            MenuItem::separator(),
+            MenuItem::action("Merge Conflicts...", ShowConflicts),
+            MenuItem::action("Next Conflict", NextConflict),
+            MenuItem::action("Previous Conflict", PreviousConflict),
+            MenuItem::action("Accept Ours", AcceptOurs),
+            MenuItem::action("Accept Theirs", AcceptTheirs),
+            MenuItem::action("Accept Both", AcceptBoth),
+            MenuItem::separator(),
+            MenuItem::action("Show Log", ShowLog),
+            MenuItem::action("Show File History", ShowFileHistory),
         ]),
+        Menu::new("Window").items(
+            [
+                MenuItem::action("Minimize", Minimize),
+                MenuItem::action("Maximize/Restore", Zoom),
+            ]
+            .into_iter()
+            .chain(window_list_items(state)),
+        ),
         Menu::new("Debug").items([
             MenuItem::action("Component Gallery", ComponentGallery),
             MenuItem::action("Theme Debug", ThemeDebug),
+            MenuItem::action("Performance HUD", TogglePerformanceHud),
+            MenuItem::action("Focus Debugger", ToggleFocusDebugger),
             MenuItem::separator(),
             MenuItem::action("Test Prompt", TestPrompt),
             MenuItem::action("Test Completion", TestCompletion),
+            MenuItem::separator(),
+            MenuItem::action("Show LSP Traffic", ShowLspTraffic),
+            MenuItem::action("Show Environment", ShowEnvironmentInspector),
         ]),
         Menu::new("Help").items([
             MenuItem::action("Tutorial", OpenTutorial),
@@ -1079,6 +1409,7 @@ use nucleotide::{EditorFontConfig, FontSettings, UiFontConfig};
 fn gui_main(
     mut app: Application,
     config: nucleotide::config::Config,
+    mut startup_profile: StartupProfile,
     handle: tokio::runtime::Handle,
     workspace_root: Option<std::path::PathBuf>,
     initial_dock_action: Option<usize>,
@@ -1122,6 +1453,8 @@ fn gui_main(
             nucleotide_ui::init(cx, None);
             overlay::init(cx);
             nucleotide::file_tree::init(cx);
+            nucleotide::find_bar::init(cx);
+            nucleotide_terminal_view::init(cx);
 
             // Initialize Linux platform detection if on Linux
             #[cfg(target_os = "linux")]
@@ -1145,6 +1478,7 @@ fn gui_main(
             let ui_font_config = config.ui_font();
 
             // Set up theme manager with Helix theme
+            let theme_started = Instant::now();
             let helix_theme = app.editor.theme.clone();
             #[allow(unused_mut)]
             let mut theme_manager =
@@ -1162,6 +1496,7 @@ fn gui_main(
             cx.set_global(nucleotide_ui::markdown::MarkdownSyntaxLoader::new(
                 app.editor.syn_loader.load_full(),
             ));
+            startup_profile.record(StartupPhase::ThemeInit, theme_started.elapsed());
 
             // Initialize centralized focus coordinator for input/focus management
             cx.set_global(nucleotide_ui::FocusCoordinator::default());
@@ -1200,9 +1535,24 @@ fn gui_main(
                 weight: ui_font_config.weight,
             });
 
+            // Wheel speed and shift/fast-scroll behaviour for editor and terminal
+            cx.set_global(config.scroll_settings());
+            cx.set_global(config.gui.editor.mouse_selection.clone());
+            cx.set_global(config.gui.editor.cursor);
+
             // Initialize preview tracker
             cx.set_global(nucleotide_core::preview_tracker::PreviewTracker::new());
 
+            // Local usage statistics for frecency ranking in pickers
+            cx.set_global(nucleotide::usage_stats::UsageTracker::load_default(
+                config.gui.usage.enabled,
+            ));
+            cx.set_global(nucleotide::recent_files::RecentFiles::load_default());
+            cx.set_global(
+                nucleotide::run_configurations::LastRunConfigurations::load_default(),
+            );
+            cx.set_global(nucleotide::cargo_integration::CargoSelections::load_default());
+
             let update_controller = cx.new(|cx| {
                 nucleotide::updates::UpdateController::new(config.gui.updates.clone(), cx)
             });
@@ -1217,6 +1567,9 @@ fn gui_main(
                 warn!(error = %error, "Failed to apply DirectWrite text rendering settings");
             }
 
+            // The workspace logs the report once its first frame is drawn.
+            cx.set_global(startup_profile);
+
             let options = window_options(cx, &config, is_dark_chrome);
 
             let window = cx.open_window(options, |#[allow(unused)] window, cx| {
@@ -1331,7 +1684,8 @@ fn gui_main(
                 nucleotide_logging::info!("Using direct Helix completion integration");
 
                 cx.activate(true);
-                cx.set_menus(app_menus());
+                cx.set_global(AppMenus::new(app_menus));
+                cx.set_menus(app_menus(&AppMenuState::default()));
 
                 #[cfg(any(target_os = "macos", target_os = "windows"))]
                 {
@@ -1380,6 +1734,11 @@ fn gui_main(
                         workspace.set_current_project_root(Some(root.clone()), cx);
                     }
 
+                    // Offer to report a crash from the previous session
+                    if let Some(report) = nucleotide::error_report::ErrorReport::take_pending() {
+                        workspace.report_error(report, cx);
+                    }
+
                     // Subscribe to self to handle Update events
                     cx.subscribe(&cx.entity(), |w: &mut workspace::Workspace, _, ev, cx| {
                         w.handle_event(ev, cx);
@@ -1516,6 +1875,33 @@ fn gui_main(
                 })
                 .detach();
 
+                // Text sent through the macOS Services menu opens in a new
+                // scratch buffer.
+                #[cfg(target_os = "macos")]
+                {
+                    let (service_tx, mut service_rx) =
+                        tokio::sync::mpsc::unbounded_channel::<String>();
+                    nucleotide::macos_services::install_services_provider(service_tx);
+                    let workspace_clone = workspace.clone();
+                    let window_handle = window.window_handle();
+                    cx.spawn(async move |cx| {
+                        while let Some(text) = service_rx.recv().await {
+                            info!(bytes = text.len(), "Processing Services request");
+                            if let Err(error) = window_handle.update(cx, |_, window, _cx| {
+                                window.activate_window();
+                            }) {
+                                warn!(error = %error, "Failed to activate window for Services request");
+                            }
+                            cx.update(|cx| {
+                                workspace_clone.update(cx, |workspace, cx| {
+                                    workspace.open_service_text(text, cx);
+                                });
+                            });
+                        }
+                    })
+                    .detach();
+                }
+
                 // Create and set titlebar after workspace is created.
                 let should_create_titlebar =
                     should_create_custom_titlebar(window.window_decorations());
@@ -1762,7 +2148,7 @@ mod tests {
 
     #[test]
     fn file_menu_exposes_open_remote_action() {
-        let menus = app_menus();
+        let menus = app_menus(&AppMenuState::default());
         let file_menu = menus
             .iter()
             .find(|menu| menu.name.as_ref() == "File")
@@ -1782,7 +2168,7 @@ mod tests {
     #[cfg(target_os = "windows")]
     #[test]
     fn windows_app_menus_use_windows_editor_conventions() {
-        let menus = app_menus();
+        let menus = app_menus(&AppMenuState::default());
         let names = menus
             .iter()
             .map(|menu| menu.name.as_ref())
@@ -1790,7 +2176,9 @@ mod tests {
 
         assert_eq!(
             names,
-            ["File", "Edit", "View", "Run", "Window", "Debug", "Help"]
+            [
+                "File", "Edit", "View", "Run", "Git", "Window", "Debug", "Help"
+            ]
         );
         assert!(menus.iter().all(|menu| menu.name.as_ref() != "Nucleotide"));
 
@@ -1816,7 +2204,7 @@ mod tests {
 
     #[test]
     fn debug_menu_exposes_component_gallery() {
-        let menus = app_menus();
+        let menus = app_menus(&AppMenuState::default());
         let debug_menu = menus
             .iter()
             .find(|menu| menu.name.as_ref() == "Debug")
@@ -1833,7 +2221,7 @@ mod tests {
     #[cfg(target_os = "windows")]
     #[test]
     fn windows_view_menu_groups_split_commands_under_submenu() {
-        let menus = app_menus();
+        let menus = app_menus(&AppMenuState::default());
         let view_menu = menus
             .iter()
             .find(|menu| menu.name.as_ref() == "View")
@@ -2022,7 +2410,7 @@ mod tests {
 
     #[test]
     fn view_menu_exposes_documentation_and_terminal_toggles() {
-        let menus = app_menus();
+        let menus = app_menus(&AppMenuState::default());
         let view_menu = menus
             .iter()
             .find(|menu| menu.name.as_ref() == "View")
@@ -2053,7 +2441,7 @@ mod tests {
 
     #[test]
     fn edit_menu_exposes_revert_current_change() {
-        let menus = app_menus();
+        let menus = app_menus(&AppMenuState::default());
         let edit_menu = menus
             .iter()
             .find(|menu| menu.name.as_ref() == "Edit")
@@ -2069,4 +2457,57 @@ mod tests {
 
         assert!(has_revert, "Edit menu should expose Revert Current Change");
     }
+
+    #[test]
+    fn menus_reflect_workspace_state() {
+        fn find<'a>(items: &'a [MenuItem], wanted: &str) -> &'a MenuItem {
+            items
+                .iter()
+                .find(|item| match item {
+                    MenuItem::Action { name, .. } => name.as_ref() == wanted,
+                    MenuItem::Submenu(menu) => menu.name.as_ref() == wanted,
+                    _ => false,
+                })
+                .unwrap_or_else(|| panic!("menu item {wanted} should exist"))
+        }
+        fn menu<'a>(menus: &'a [Menu], name: &str) -> &'a [MenuItem] {
+            &menus
+                .iter()
+                .find(|menu| menu.name.as_ref() == name)
+                .expect("menu should exist")
+                .items
+        }
+
+        let idle = app_menus(&AppMenuState::default());
+        assert!(find(menu(&idle, "File"), "Save").is_disabled());
+        assert!(find(menu(&idle, "File"), "Open Recent").is_disabled());
+        assert!(!find(menu(&idle, "View"), "File Tree").is_checked());
+
+        let state = AppMenuState {
+            has_document: true,
+            has_dirty_document: true,
+            file_tree_visible: true,
+            terminal_visible: true,
+            windows: vec![nucleotide::menu_state::MenuWindow {
+                window_id: 7,
+                title: "main.rs".to_string(),
+                active: true,
+            }],
+            recent_files: vec![PathBuf::from("/tmp/project/src/lib.rs")],
+            ..AppMenuState::default()
+        };
+        let menus = app_menus(&state);
+        assert!(!find(menu(&menus, "File"), "Save").is_disabled());
+        assert!(!find(menu(&menus, "File"), "Open Recent").is_disabled());
+        assert!(find(menu(&menus, "View"), "File Tree").is_checked());
+        assert!(find(menu(&menus, "View"), "Terminal").is_checked());
+        assert!(!find(menu(&menus, "View"), "Documentation").is_checked());
+        let window = find(menu(&menus, "Window"), "main.rs");
+        assert!(window.is_checked());
+        assert!(matches!(
+            window,
+            MenuItem::Action { action, .. }
+                if action.partial_eq(&nucleotide::actions::app_menu::ActivateWindow { window_id: 7 })
+        ));
+    }
 }
@@ -0,0 +1,147 @@
+// ABOUTME: Application menu state taken from the active workspace window
+// ABOUTME: Rebuilds the platform menus only when the state they reflect changes
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use gpui::{App, Global, Menu};
+
+/// Recent files listed in the Open Recent submenu.
+pub const MAX_MENU_RECENT_FILES: usize = 10;
+/// Recent projects listed in the Open Recent submenu.
+pub const MAX_MENU_RECENT_PROJECTS: usize = 5;
+
+/// An open window as listed in the Window menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuWindow {
+    pub window_id: u64,
+    pub title: String,
+    pub active: bool,
+}
+
+/// What the menus enable, check and list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppMenuState {
+    pub has_document: bool,
+    pub has_dirty_document: bool,
+    pub file_tree_visible: bool,
+    pub terminal_visible: bool,
+    pub documentation_visible: bool,
+    pub windows: Vec<MenuWindow>,
+    /// Files of the current project, most recent first
+    pub recent_files: Vec<PathBuf>,
+    /// Other project roots, most recent first
+    pub recent_projects: Vec<PathBuf>,
+}
+
+/// Builds the platform menus for a state. The binary owns the menu layout;
+/// the workspace only reports state.
+pub type AppMenuBuilder = fn(&AppMenuState) -> Vec<Menu>;
+
+/// The menus last installed and the window titles listed in them.
+pub struct AppMenus {
+    build: AppMenuBuilder,
+    current: Option<AppMenuState>,
+    window_titles: HashMap<u64, String>,
+}
+
+impl Global for AppMenus {}
+
+impl AppMenus {
+    pub fn new(build: AppMenuBuilder) -> Self {
+        Self {
+            build,
+            current: None,
+            window_titles: HashMap::new(),
+        }
+    }
+
+    /// Installs the menus for `state` unless they already show it.
+    pub fn refresh(state: AppMenuState, cx: &mut App) {
+        if !cx.has_global::<Self>() {
+            return;
+        }
+        let menus = {
+            let app_menus = cx.global_mut::<Self>();
+            if app_menus.current.as_ref() == Some(&state) {
+                return;
+            }
+            let menus = (app_menus.build)(&state);
+            app_menus.current = Some(state);
+            menus
+        };
+        cx.set_menus(menus);
+    }
+
+    pub fn set_window_title(window_id: u64, title: &str, cx: &mut App) {
+        if cx.has_global::<Self>() {
+            cx.global_mut::<Self>()
+                .window_titles
+                .insert(window_id, title.to_string());
+        }
+    }
+
+    /// Open windows in the order the platform reports them, skipping those
+    /// that never reported a title.
+    pub fn windows(active_window_id: u64, cx: &App) -> Vec<MenuWindow> {
+        let Some(app_menus) = cx.try_global::<Self>() else {
+            return Vec::new();
+        };
+        cx.windows()
+            .into_iter()
+            .filter_map(|window| {
+                let window_id = window.window_id().as_u64();
+                app_menus
+                    .window_titles
+                    .get(&window_id)
+                    .map(|title| MenuWindow {
+                        window_id,
+                        title: title.clone(),
+                        active: window_id == active_window_id,
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Menu label for a recent path: the file name followed by its parent
+/// directory, relative to `root` when inside it.
+pub fn recent_path_label(path: &Path, root: Option<&Path>) -> String {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    let parent = path
+        .parent()
+        .map(|parent| {
+            root.and_then(|root| parent.strip_prefix(root).ok())
+                .unwrap_or(parent)
+        })
+        .filter(|parent| !parent.as_os_str().is_empty());
+    match parent {
+        Some(parent) => format!("{name} — {}", parent.display()),
+        None => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_path_labels_name_the_file_before_its_directory() {
+        let root = Path::new("/work/nucleotide");
+        assert_eq!(
+            recent_path_label(Path::new("/work/nucleotide/Cargo.toml"), Some(root)),
+            "Cargo.toml"
+        );
+        assert_eq!(
+            recent_path_label(Path::new("/work/nucleotide/src/main.rs"), Some(root)),
+            format!("main.rs — {}", Path::new("src").display())
+        );
+        assert_eq!(
+            recent_path_label(Path::new("/work/other"), None),
+            format!("other — {}", Path::new("/work").display())
+        );
+    }
+}
@@ -0,0 +1,353 @@
+// ABOUTME: Detects git merge conflict markers in documents and resolves conflict blocks
+// ABOUTME: Builds single undoable transactions for accepting ours, theirs or both sides
+
+use std::ops::Range;
+
+use helix_core::{Rope, RopeSlice, Transaction};
+use helix_view::DocumentId;
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+/// A conflict block, as line indices of its markers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub start_line: usize,
+    /// `|||||||` line written by `merge.conflictStyle = diff3`/`zdiff3`
+    pub base_line: Option<usize>,
+    pub separator_line: usize,
+    pub end_line: usize,
+    pub ours_label: String,
+    pub theirs_label: String,
+}
+
+impl MergeConflict {
+    pub fn ours_lines(&self) -> Range<usize> {
+        self.start_line + 1..self.base_line.unwrap_or(self.separator_line)
+    }
+
+    pub fn base_lines(&self) -> Option<Range<usize>> {
+        self.base_line
+            .map(|base_line| base_line + 1..self.separator_line)
+    }
+
+    pub fn theirs_lines(&self) -> Range<usize> {
+        self.separator_line + 1..self.end_line
+    }
+
+    pub fn contains_line(&self, line: usize) -> bool {
+        (self.start_line..=self.end_line).contains(&line)
+    }
+}
+
+/// Which side of a conflict to keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeConflictResolution {
+    Ours,
+    Theirs,
+    /// Ours followed by theirs
+    Both,
+}
+
+impl MergeConflictResolution {
+    pub const ALL: [Self; 3] = [Self::Ours, Self::Theirs, Self::Both];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ours => "Accept ours",
+            Self::Theirs => "Accept theirs",
+            Self::Both => "Accept both",
+        }
+    }
+}
+
+/// Picker payload for resolving one conflict block.
+#[derive(Debug, Clone)]
+pub struct MergeConflictChoice {
+    pub doc_id: DocumentId,
+    /// Document version the conflict was parsed from; stale choices are dropped.
+    pub document_version: i32,
+    pub start_line: usize,
+    pub resolution: MergeConflictResolution,
+    /// Three-way rendering of the block shown in the picker preview
+    pub preview: String,
+}
+
+/// Direction for conflict navigation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictDirection {
+    Next,
+    Previous,
+}
+
+fn marker_label(line: &str, marker: &str) -> Option<String> {
+    let rest = line.strip_prefix(marker)?;
+    // `<<<<<<<<` is not a marker; git always follows the marker with a space or EOL.
+    if !(rest.is_empty() || rest.starts_with([' ', '\t', '\r', '\n'])) {
+        return None;
+    }
+    Some(rest.trim().to_string())
+}
+
+/// Finds complete conflict blocks. Unterminated or malformed blocks are skipped.
+pub fn parse_merge_conflicts(text: RopeSlice) -> Vec<MergeConflict> {
+    struct Open {
+        start_line: usize,
+        ours_label: String,
+        base_line: Option<usize>,
+        separator_line: Option<usize>,
+    }
+
+    let mut conflicts = Vec::new();
+    let mut open: Option<Open> = None;
+
+    for (line_index, line) in text.lines().enumerate() {
+        // Markers are ASCII and always at the start of the line.
+        if line.len_chars() < OURS_MARKER.len() {
+            continue;
+        }
+        let prefix: String = line.chars().take(OURS_MARKER.len()).collect();
+        if ![OURS_MARKER, BASE_MARKER, SEPARATOR_MARKER, THEIRS_MARKER].contains(&prefix.as_str()) {
+            continue;
+        }
+        let line = line.to_string();
+
+        if let Some(label) = marker_label(&line, OURS_MARKER) {
+            open = Some(Open {
+                start_line: line_index,
+                ours_label: label,
+                base_line: None,
+                separator_line: None,
+            });
+        } else if marker_label(&line, BASE_MARKER).is_some() {
+            if let Some(block) = open.as_mut()
+                && block.base_line.is_none()
+                && block.separator_line.is_none()
+            {
+                block.base_line = Some(line_index);
+            }
+        } else if line.trim_end() == SEPARATOR_MARKER {
+            if let Some(block) = open.as_mut()
+                && block.separator_line.is_none()
+            {
+                block.separator_line = Some(line_index);
+            }
+        } else if let Some(label) = marker_label(&line, THEIRS_MARKER)
+            && let Some(block) = open.take()
+            && let Some(separator_line) = block.separator_line
+        {
+            conflicts.push(MergeConflict {
+                start_line: block.start_line,
+                base_line: block.base_line,
+                separator_line,
+                end_line: line_index,
+                ours_label: block.ours_label,
+                theirs_label: label,
+            });
+        }
+    }
+
+    conflicts
+}
+
+/// The conflict containing `line`.
+pub fn conflict_at_line(conflicts: &[MergeConflict], line: usize) -> Option<&MergeConflict> {
+    conflicts
+        .iter()
+        .find(|conflict| conflict.contains_line(line))
+}
+
+/// The next or previous conflict relative to `line`, wrapping around the document.
+pub fn adjacent_conflict(
+    conflicts: &[MergeConflict],
+    line: usize,
+    direction: ConflictDirection,
+) -> Option<&MergeConflict> {
+    match direction {
+        ConflictDirection::Next => conflicts
+            .iter()
+            .find(|conflict| conflict.start_line > line)
+            .or_else(|| conflicts.first()),
+        ConflictDirection::Previous => conflicts
+            .iter()
+            .rev()
+            .find(|conflict| conflict.end_line < line)
+            .or_else(|| conflicts.last()),
+    }
+}
+
+fn lines_text(text: RopeSlice, lines: Range<usize>) -> String {
+    let start = text.line_to_char(lines.start.min(text.len_lines()));
+    let end = text.line_to_char(lines.end.min(text.len_lines()));
+    text.slice(start..end).to_string()
+}
+
+/// Replaces the whole conflict block, markers included, with the chosen side.
+pub fn merge_conflict_transaction(
+    text: &Rope,
+    conflict: &MergeConflict,
+    resolution: MergeConflictResolution,
+) -> Transaction {
+    let slice = text.slice(..);
+    let ours = lines_text(slice, conflict.ours_lines());
+    let theirs = lines_text(slice, conflict.theirs_lines());
+    let mut replacement = match resolution {
+        MergeConflictResolution::Ours => ours,
+        MergeConflictResolution::Theirs => theirs,
+        MergeConflictResolution::Both => {
+            let mut both = ours;
+            if !both.is_empty() && !both.ends_with('\n') {
+                both.push('\n');
+            }
+            both.push_str(&theirs);
+            both
+        }
+    };
+
+    let from = text.line_to_char(conflict.start_line);
+    let to = if conflict.end_line + 1 < text.len_lines() {
+        text.line_to_char(conflict.end_line + 1)
+    } else {
+        text.len_chars()
+    };
+    // A block at the end of a file without a trailing newline keeps it that way.
+    if to == text.len_chars() && to > 0 && slice.char(to - 1) != '\n' && replacement.ends_with('\n')
+    {
+        replacement.pop();
+    }
+
+    Transaction::change(text, std::iter::once((from, to, Some(replacement.into()))))
+}
+
+/// Three-way rendering of a conflict block for the picker preview.
+pub fn merge_conflict_preview(text: RopeSlice, conflict: &MergeConflict) -> String {
+    let mut preview = String::new();
+    let mut section = |title: String, body: String| {
+        preview.push_str(&format!("── {title} ──\n"));
+        preview.push_str(if body.is_empty() { "(empty)\n" } else { &body });
+        if !preview.ends_with('\n') {
+            preview.push('\n');
+        }
+        preview.push('\n');
+    };
+
+    section(
+        side_title("Ours", &conflict.ours_label),
+        lines_text(text, conflict.ours_lines()),
+    );
+    if let Some(base_lines) = conflict.base_lines() {
+        section("Base".to_string(), lines_text(text, base_lines));
+    }
+    section(
+        side_title("Theirs", &conflict.theirs_label),
+        lines_text(text, conflict.theirs_lines()),
+    );
+    preview
+}
+
+fn side_title(side: &str, label: &str) -> String {
+    if label.is_empty() {
+        side.to_string()
+    } else {
+        format!("{side} ({label})")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFLICTED: &str = concat!(
+        "fn main() {\n",
+        "<<<<<<< HEAD\n",
+        "    println!(\"ours\");\n",
+        "=======\n",
+        "    println!(\"theirs\");\n",
+        ">>>>>>> feature\n",
+        "}\n",
+    );
+
+    fn resolve(source: &str, resolution: MergeConflictResolution) -> String {
+        let mut text = Rope::from(source);
+        let conflicts = parse_merge_conflicts(text.slice(..));
+        let transaction = merge_conflict_transaction(&text, &conflicts[0], resolution);
+        assert!(transaction.apply(&mut text));
+        text.to_string()
+    }
+
+    #[test]
+    fn conflict_blocks_are_parsed_with_labels() {
+        let conflicts = parse_merge_conflicts(Rope::from(CONFLICTED).slice(..));
+        assert_eq!(
+            conflicts,
+            [MergeConflict {
+                start_line: 1,
+                base_line: None,
+                separator_line: 3,
+                end_line: 5,
+                ours_label: "HEAD".into(),
+                theirs_label: "feature".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn diff3_base_section_and_incomplete_blocks_are_handled() {
+        let text = Rope::from(
+            "<<<<<<< ours\na\n||||||| base\nb\n=======\nc\n>>>>>>> theirs\n<<<<<<< HEAD\nd\n",
+        );
+        let conflicts = parse_merge_conflicts(text.slice(..));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].base_lines(), Some(3..4));
+        assert_eq!(conflicts[0].ours_lines(), 1..2);
+        assert_eq!(conflicts[0].theirs_lines(), 5..6);
+    }
+
+    #[test]
+    fn resolutions_replace_the_whole_block() {
+        assert_eq!(
+            resolve(CONFLICTED, MergeConflictResolution::Ours),
+            "fn main() {\n    println!(\"ours\");\n}\n"
+        );
+        assert_eq!(
+            resolve(CONFLICTED, MergeConflictResolution::Theirs),
+            "fn main() {\n    println!(\"theirs\");\n}\n"
+        );
+        assert_eq!(
+            resolve(CONFLICTED, MergeConflictResolution::Both),
+            "fn main() {\n    println!(\"ours\");\n    println!(\"theirs\");\n}\n"
+        );
+    }
+
+    #[test]
+    fn conflict_at_end_of_file_keeps_missing_trailing_newline() {
+        assert_eq!(
+            resolve(
+                "<<<<<<< HEAD\na\n=======\nb\n>>>>>>> x",
+                MergeConflictResolution::Theirs
+            ),
+            "b"
+        );
+    }
+
+    #[test]
+    fn navigation_wraps_around() {
+        let text = Rope::from(format!("{CONFLICTED}{CONFLICTED}"));
+        let conflicts = parse_merge_conflicts(text.slice(..));
+        assert_eq!(conflicts.len(), 2);
+
+        let next = |line| adjacent_conflict(&conflicts, line, ConflictDirection::Next);
+        let previous = |line| adjacent_conflict(&conflicts, line, ConflictDirection::Previous);
+        assert_eq!(next(0).map(|c| c.start_line), Some(1));
+        assert_eq!(next(1).map(|c| c.start_line), Some(8));
+        assert_eq!(next(8).map(|c| c.start_line), Some(1));
+        assert_eq!(previous(8).map(|c| c.start_line), Some(1));
+        assert_eq!(previous(3).map(|c| c.start_line), Some(8));
+        assert_eq!(
+            conflict_at_line(&conflicts, 4).map(|c| c.start_line),
+            Some(1)
+        );
+    }
+}
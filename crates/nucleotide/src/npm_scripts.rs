@@ -0,0 +1,309 @@
+// ABOUTME: package.json script discovery for JavaScript and TypeScript projects
+// ABOUTME: Detects npm, yarn or pnpm and turns each script into a runnable task
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use nucleotide_events::v2::run::{
+    CommandSpec, ResolvedTask, RunKind, SourceLocation, TaskTemplate,
+};
+use serde::Deserialize;
+
+/// Prompt command opening the package.json scripts picker.
+pub const NPM_SCRIPTS_COMMAND: &str = "npm-scripts";
+/// Prompt commands for package.json scripts, with completion descriptions.
+pub const NPM_SCRIPT_COMMANDS: &[(&str, &str)] = &[(
+    NPM_SCRIPTS_COMMAND,
+    "Choose a package.json script and run it",
+)];
+const TAG_NPM_SCRIPT: &str = "npm-script";
+const PACKAGE_JSON: &str = "package.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageManager {
+    Npm,
+    Yarn,
+    Pnpm,
+}
+
+impl PackageManager {
+    pub fn program(self) -> &'static str {
+        match self {
+            Self::Npm => "npm",
+            Self::Yarn => "yarn",
+            Self::Pnpm => "pnpm",
+        }
+    }
+
+    /// The manager named by a `packageManager` field such as `pnpm@9.1.0`.
+    fn from_field(field: &str) -> Option<Self> {
+        match field.split('@').next()?.trim() {
+            "npm" => Some(Self::Npm),
+            "yarn" => Some(Self::Yarn),
+            "pnpm" => Some(Self::Pnpm),
+            _ => None,
+        }
+    }
+
+    fn from_lockfile(dir: &Path) -> Option<Self> {
+        [
+            ("pnpm-lock.yaml", Self::Pnpm),
+            ("yarn.lock", Self::Yarn),
+            ("package-lock.json", Self::Npm),
+            ("npm-shrinkwrap.json", Self::Npm),
+        ]
+        .into_iter()
+        .find(|(lockfile, _)| dir.join(lockfile).is_file())
+        .map(|(_, manager)| manager)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageJson {
+    name: Option<String>,
+    #[serde(default)]
+    scripts: BTreeMap<String, String>,
+    package_manager: Option<String>,
+}
+
+/// One entry of a package.json `scripts` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageScript {
+    pub name: String,
+    pub command: String,
+    /// Zero-based line of the script in package.json
+    pub line: usize,
+}
+
+/// The scripts of one package.json and the manager that runs them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageScripts {
+    pub manifest_path: PathBuf,
+    pub package_name: Option<String>,
+    pub manager: PackageManager,
+    pub scripts: Vec<PackageScript>,
+}
+
+impl PackageScripts {
+    /// Tasks running each script through the package manager, in the
+    /// package's directory. Labels are prefixed with the package name
+    /// when `prefix` is set.
+    pub fn tasks(&self, prefix: bool) -> Vec<ResolvedTask> {
+        let dir = self
+            .manifest_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        self.scripts
+            .iter()
+            .map(|script| {
+                let label = match (&self.package_name, prefix) {
+                    (Some(package), true) => format!("{package}: {}", script.name),
+                    _ => script.name.clone(),
+                };
+                let command = CommandSpec::new(self.manager.program())
+                    .with_args(["run".to_string(), script.name.clone()])
+                    .with_cwd(dir.clone());
+                ResolvedTask {
+                    template: TaskTemplate {
+                        label,
+                        kind: RunKind::Run,
+                        command: command.clone(),
+                        source: Some(SourceLocation {
+                            path: self.manifest_path.clone(),
+                            line: script.line,
+                            column: 0,
+                        }),
+                        tags: vec![TAG_NPM_SCRIPT.to_string()],
+                        description: None,
+                    },
+                    command,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Parses the scripts of the package.json at `manifest_path`. The package
+/// manager comes from `packageManager`, else from the nearest lockfile
+/// between the package and `root`, else npm.
+pub fn parse_package_scripts(
+    manifest_path: &Path,
+    contents: &str,
+    root: Option<&Path>,
+) -> Result<PackageScripts> {
+    let package: PackageJson = serde_json::from_str(contents)
+        .with_context(|| format!("failed to parse {}", manifest_path.display()))?;
+    let dir = manifest_path.parent().unwrap_or(Path::new("."));
+    let manager = package
+        .package_manager
+        .as_deref()
+        .and_then(PackageManager::from_field)
+        .or_else(|| {
+            dir.ancestors()
+                .take_while(|ancestor| root.is_none_or(|root| ancestor.starts_with(root)))
+                .find_map(PackageManager::from_lockfile)
+        })
+        .unwrap_or(PackageManager::Npm);
+
+    let mut scripts = package
+        .scripts
+        .into_iter()
+        .map(|(name, command)| PackageScript {
+            line: script_line(contents, &name),
+            name,
+            command,
+        })
+        .collect::<Vec<_>>();
+    // Keep the order the scripts are written in.
+    scripts.sort_by_key(|script| script.line);
+
+    Ok(PackageScripts {
+        manifest_path: manifest_path.to_path_buf(),
+        package_name: package.name,
+        manager,
+        scripts,
+    })
+}
+
+/// Line of the `"name":` key inside the `scripts` object, or 0.
+fn script_line(contents: &str, name: &str) -> usize {
+    let key = format!("{}:", serde_json::Value::String(name.to_string()));
+    let start = contents
+        .lines()
+        .position(|line| line.trim_start().starts_with("\"scripts\""))
+        .unwrap_or(0);
+    contents
+        .lines()
+        .enumerate()
+        .skip(start)
+        .find(|(_, line)| line.trim_start().replace("\": ", "\":").starts_with(&key))
+        .map_or(0, |(index, _)| index)
+}
+
+/// Scripts of the project's package.json and of the package containing
+/// `path`, when that is a different one. Blocks on file reads.
+pub fn discover(root: Option<&Path>, path: Option<&Path>) -> Result<Vec<PackageScripts>> {
+    let mut manifests = Vec::new();
+    if let Some(root) = root {
+        manifests.push(root.join(PACKAGE_JSON));
+    }
+    if let Some(nearest) = path.and_then(|path| {
+        path.ancestors()
+            .skip(1)
+            .take_while(|ancestor| root.is_none_or(|root| ancestor.starts_with(root)))
+            .map(|ancestor| ancestor.join(PACKAGE_JSON))
+            .find(|manifest| manifest.is_file())
+    }) && !manifests.contains(&nearest)
+    {
+        manifests.push(nearest);
+    }
+
+    let mut packages = Vec::new();
+    for manifest in manifests {
+        let contents = match std::fs::read_to_string(&manifest) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(error) => {
+                return Err(error)
+                    .with_context(|| format!("failed to read {}", manifest.display()));
+            }
+        };
+        let scripts = parse_package_scripts(&manifest, &contents, root)?;
+        if !scripts.scripts.is_empty() {
+            packages.push(scripts);
+        }
+    }
+    Ok(packages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKAGE: &str = r#"{
+  "name": "web",
+  "version": "1.0.0",
+  "scripts": {
+    "dev": "vite",
+    "build": "tsc && vite build",
+    "test": "vitest run"
+  }
+}"#;
+
+    #[test]
+    fn scripts_keep_their_order_and_run_through_the_manager() {
+        let temp = tempfile::tempdir().unwrap();
+        let manifest = temp.path().join(PACKAGE_JSON);
+        let scripts = parse_package_scripts(&manifest, PACKAGE, Some(temp.path())).unwrap();
+
+        assert_eq!(scripts.manager, PackageManager::Npm);
+        assert_eq!(
+            scripts
+                .scripts
+                .iter()
+                .map(|script| (script.name.as_str(), script.line))
+                .collect::<Vec<_>>(),
+            vec![("dev", 4), ("build", 5), ("test", 6)]
+        );
+
+        let tasks = scripts.tasks(true);
+        assert_eq!(tasks[1].label(), "web: build");
+        assert_eq!(tasks[1].command.program, "npm");
+        assert_eq!(tasks[1].command.args, vec!["run", "build"]);
+        assert_eq!(tasks[1].command.cwd.as_deref(), Some(temp.path()));
+        assert_eq!(tasks[1].source().map(|source| source.line), Some(5));
+        assert_eq!(scripts.tasks(false)[0].label(), "dev");
+    }
+
+    #[test]
+    fn package_manager_is_detected_from_field_and_lockfiles() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let package_dir = root.join("packages/app");
+        std::fs::create_dir_all(&package_dir).unwrap();
+        let manifest = package_dir.join(PACKAGE_JSON);
+
+        std::fs::write(root.join("yarn.lock"), "").unwrap();
+        let scripts = parse_package_scripts(&manifest, PACKAGE, Some(root)).unwrap();
+        assert_eq!(scripts.manager, PackageManager::Yarn);
+
+        std::fs::write(package_dir.join("pnpm-lock.yaml"), "").unwrap();
+        let scripts = parse_package_scripts(&manifest, PACKAGE, Some(root)).unwrap();
+        assert_eq!(scripts.manager, PackageManager::Pnpm);
+
+        let with_field = r#"{ "packageManager": "yarn@4.1.0", "scripts": { "lint": "eslint ." } }"#;
+        let scripts = parse_package_scripts(&manifest, with_field, Some(root)).unwrap();
+        assert_eq!(scripts.manager, PackageManager::Yarn);
+        assert_eq!(scripts.tasks(true)[0].label(), "lint");
+    }
+
+    #[test]
+    fn discovery_includes_the_package_of_the_focused_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let package_dir = root.join("packages/app");
+        std::fs::create_dir_all(package_dir.join("src")).unwrap();
+        std::fs::write(root.join(PACKAGE_JSON), PACKAGE).unwrap();
+        std::fs::write(
+            package_dir.join(PACKAGE_JSON),
+            r#"{ "name": "app", "scripts": { "start": "node ." } }"#,
+        )
+        .unwrap();
+
+        let packages = discover(Some(root), Some(&package_dir.join("src/index.ts"))).unwrap();
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[1].package_name.as_deref(), Some("app"));
+
+        let packages = discover(Some(root), Some(&root.join("README.md"))).unwrap();
+        assert_eq!(packages.len(), 1);
+        assert!(
+            discover(Some(&package_dir.join("src")), None)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(parse_package_scripts(&root.join(PACKAGE_JSON), "{", None).is_err());
+    }
+}
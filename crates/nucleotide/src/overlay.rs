@@ -1,21 +1,33 @@
+use crate::recent_files::recent_files;
 use crate::types::RegexSelectionAction;
+use crate::usage_stats::{UsageKind, record_usage, usage_scores};
 use gpui::{
-    App, AppContext, ClipboardItem, Context, DismissEvent, Entity, EventEmitter, FocusHandle,
-    Focusable, InteractiveElement, IntoElement, MouseButton, ParentElement, Pixels, Render, Styled,
-    Window, div, px,
+    App, AppContext, Bounds, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, MouseButton, ParentElement, Pixels, Render, Styled, Window,
+    div, point, px, size,
 };
 use helix_stdx::rope::RopeSliceExt;
 use nucleotide_terminal::TerminalBounds;
 use nucleotide_ui::ThemedContext as UIThemedContext;
 use nucleotide_ui::completion_v2::CompletionView;
+use nucleotide_ui::overlay_manager::{Dismissal, OverlayManager, PopupId, PopupLayer};
 use nucleotide_ui::picker::Picker;
+use nucleotide_ui::picker_source::{PickerItemSink, PickerSources};
 use nucleotide_ui::picker_view::{PickerItem, PickerView};
 use nucleotide_ui::prompt::Prompt;
 use nucleotide_ui::prompt_view::PromptView;
 use nucleotide_ui::theme_manager::HelixThemedContext; // bring dispatch_* trait methods into scope
-use nucleotide_ui::{CompletionMenuAction, OverlaySurface};
+use nucleotide_ui::{
+    CompletionMenuAction, OverlaySurface, PopupConstraints, PopupPlacement, PopupPositioner,
+};
 use std::sync::{Arc, Mutex};
 
+/// Names the overlay's popups are registered under in the overlay manager.
+pub const COMPLETION_POPUP: PopupId = PopupId::new("completion");
+const PICKER_POPUP: PopupId = PopupId::new("picker");
+const PROMPT_POPUP: PopupId = PopupId::new("prompt");
+const REMOTE_CONNECTION_MANAGER_POPUP: PopupId = PopupId::new("remote-connection-manager");
+
 pub fn init(cx: &mut App) {
     crate::remote_connection_manager::init(cx);
 }
@@ -27,6 +39,9 @@ pub struct OverlayView {
     remote_connection_manager_view:
         Option<Entity<crate::remote_connection_manager::RemoteConnectionManagerView>>,
     completion_view: Option<Entity<CompletionView>>,
+    /// Side of the cursor the completion popup last opened on, keyed by the
+    /// cursor's top edge so it only sticks while the cursor stays on a line
+    completion_placement: Option<(Pixels, PopupPlacement)>,
     terminal_panel: Option<Entity<nucleotide_terminal_panel::TerminalPanel>>,
     // Resizable terminal panel height (pixels)
     terminal_height_px: f32,
@@ -42,7 +57,7 @@ pub struct OverlayView {
     core: gpui::WeakEntity<crate::Core>,
     handle: tokio::runtime::Handle,
     // Cached terminal font metrics to avoid per-frame font measurement
-    cached_font_key: Option<(String, f32, nucleotide_types::FontWeight)>, // (family, size, weight)
+    cached_font_key: Option<(String, f32, nucleotide_types::FontWeight, f32)>, // (family, size, weight, scale)
     cached_char_width: Option<f32>,
     cached_line_height: Option<f32>,
 }
@@ -182,6 +197,7 @@ enum PromptSubmitAction {
     FileTreeSearch,
     RemoteOpen,
     RegexSelection(RegexSelectionAction),
+    Align,
 }
 
 fn prompt_submit_action(prompt_text: &str) -> PromptSubmitAction {
@@ -194,6 +210,7 @@ fn prompt_submit_action(prompt_text: &str) -> PromptSubmitAction {
         "split:" => PromptSubmitAction::RegexSelection(RegexSelectionAction::Split),
         "keep:" => PromptSubmitAction::RegexSelection(RegexSelectionAction::Keep),
         "remove:" => PromptSubmitAction::RegexSelection(RegexSelectionAction::Remove),
+        crate::align::ALIGN_PROMPT => PromptSubmitAction::Align,
         _ => PromptSubmitAction::Command,
     }
 }
@@ -337,6 +354,54 @@ fn apply_regex_selection(
     }
 }
 
+/// Previews the delimiters an `align:` prompt would line up by selecting
+/// them, or aligns on them when `commit` is set, as one undo step.
+fn apply_alignment(
+    core: &mut crate::Core,
+    input: &str,
+    snapshot: &RegexSelectionSnapshot,
+    commit: bool,
+) -> Result<RegexSelectionOutcome, String> {
+    if !restore_regex_selection(core, snapshot) {
+        return Ok(RegexSelectionOutcome::TargetUnavailable);
+    }
+    let Some(regex) = crate::align::delimiter_regex(input)? else {
+        return Ok(RegexSelectionOutcome::Restored);
+    };
+    let Some(doc) = core.editor.documents.get_mut(&snapshot.doc_id) else {
+        return Ok(RegexSelectionOutcome::TargetUnavailable);
+    };
+
+    if commit {
+        let Some(transaction) = crate::align::align_transaction(
+            doc.text(),
+            &snapshot.selection,
+            &regex,
+            doc.tab_width(),
+        ) else {
+            return Err("Nothing to align".to_string());
+        };
+        crate::task_list::apply_task_edit(
+            &mut core.editor,
+            snapshot.doc_id,
+            snapshot.view_id,
+            &transaction,
+        );
+    } else {
+        let Some(preview) = crate::align::preview_selection(
+            doc.text(),
+            &snapshot.selection,
+            &regex,
+            doc.tab_width(),
+        ) else {
+            return Ok(RegexSelectionOutcome::Restored);
+        };
+        doc.set_selection(snapshot.view_id, preview);
+        core.editor.ensure_cursor_in_view(snapshot.view_id);
+    }
+    Ok(RegexSelectionOutcome::Applied)
+}
+
 impl OverlayView {
     pub fn new(
         focus: &FocusHandle,
@@ -349,6 +414,7 @@ impl OverlayView {
             regex_selection_preview: None,
             remote_connection_manager_view: None,
             completion_view: None,
+            completion_placement: None,
             terminal_panel: None,
             terminal_height_px: 220.0,
             _terminal_resizing: false,
@@ -539,6 +605,7 @@ impl OverlayView {
             picker.cleanup(cx);
         });
         Self::clear_picker_focus(cx);
+        OverlayManager::close_global(PICKER_POPUP, cx);
         true
     }
 
@@ -569,6 +636,7 @@ impl OverlayView {
                 });
             }
             Self::clear_prompt_focus(cx);
+            OverlayManager::close_global(PROMPT_POPUP, cx);
         }
         dismissed
     }
@@ -577,6 +645,7 @@ impl OverlayView {
         let dismissed = self.remote_connection_manager_view.take().is_some();
         if dismissed {
             Self::clear_prompt_focus(cx);
+            OverlayManager::close_global(REMOTE_CONNECTION_MANAGER_POPUP, cx);
         }
         dismissed
     }
@@ -585,10 +654,23 @@ impl OverlayView {
         let dismissed = self.completion_view.take().is_some();
         if dismissed {
             Self::clear_completion_focus(cx);
+            OverlayManager::close_global(COMPLETION_POPUP, cx);
         }
         dismissed
     }
 
+    /// Closes the completion list once the overlay manager no longer has it
+    /// open, after another popup displaced it or a dismissal closed it. The
+    /// caller cleans up after it, so no DismissEvent is emitted.
+    pub fn close_displaced_completion(&mut self, cx: &mut Context<Self>) -> bool {
+        if self.completion_view.is_none() || OverlayManager::is_open_global(COMPLETION_POPUP, cx) {
+            return false;
+        }
+        self.clear_completion(cx);
+        cx.notify();
+        true
+    }
+
     fn clear_picker_focus(cx: &mut Context<Self>) {
         if let Some(coord) = cx.try_global::<nucleotide_ui::FocusCoordinator>().cloned() {
             coord.clear_picker_focus();
@@ -702,14 +784,16 @@ impl OverlayView {
                 let on_cancel = on_cancel.clone();
                 let submit_action = prompt_submit_action(&prompt_text);
                 let regex_selection_preview = match submit_action {
-                    PromptSubmitAction::RegexSelection(_) => self.core.upgrade().and_then(|core| {
-                        regex_selection_snapshot(core.read(cx)).map(|snapshot| {
-                            Arc::new(Mutex::new(RegexSelectionPreviewState {
-                                snapshot,
-                                phase: RegexSelectionPreviewPhase::Active,
-                            }))
+                    PromptSubmitAction::RegexSelection(_) | PromptSubmitAction::Align => {
+                        self.core.upgrade().and_then(|core| {
+                            regex_selection_snapshot(core.read(cx)).map(|snapshot| {
+                                Arc::new(Mutex::new(RegexSelectionPreviewState {
+                                    snapshot,
+                                    phase: RegexSelectionPreviewPhase::Active,
+                                }))
+                            })
                         })
-                    }),
+                    }
                     _ => None,
                 };
                 self.regex_selection_preview = regex_selection_preview.clone();
@@ -725,11 +809,7 @@ impl OverlayView {
                         view.set_text(&initial_input, cx);
                     }
 
-                    if let (
-                        PromptSubmitAction::RegexSelection(action),
-                        Some(preview_state),
-                    ) = (submit_action, regex_selection_preview.clone())
-                    {
+                    if let Some(preview_state) = regex_selection_preview.clone() {
                         let core_weak_change = self.core.clone();
                         view = view.on_change(move |input, cx| {
                             let snapshot = match preview_state.lock() {
@@ -748,9 +828,14 @@ impl OverlayView {
                             };
 
                             core.update(cx, |core, cx| {
-                                let result = apply_regex_selection(
-                                    core, action, input, &snapshot, false,
-                                );
+                                let result = match submit_action {
+                                    PromptSubmitAction::RegexSelection(action) => {
+                                        apply_regex_selection(
+                                            core, action, input, &snapshot, false,
+                                        )
+                                    }
+                                    _ => apply_alignment(core, input, &snapshot, false),
+                                };
                                 match result {
                                     Ok(RegexSelectionOutcome::Applied) => {
                                         cx.emit(crate::Update::SelectionChanged {
@@ -792,7 +877,18 @@ impl OverlayView {
                                     crate::completions::CommandCompletionCache::from_editor(
                                         &core.editor,
                                     )
-                                    .with_filesystem_paths(complete_filesystem_paths);
+                                    .with_filesystem_paths(complete_filesystem_paths)
+                                    .with_usage(
+                                        usage_scores(UsageKind::Command, cx),
+                                        usage_scores(UsageKind::Theme, cx),
+                                    )
+                                    .with_picker_sources(
+                                        cx.try_global::<PickerSources>()
+                                            .map(|sources| {
+                                                sources.names().map(ToString::to_string).collect()
+                                            })
+                                            .unwrap_or_default(),
+                                    );
                                 let remote_path_context = (!complete_filesystem_paths)
                                     .then(|| {
                                         core.project_directory.clone().map(|base_dir| {
@@ -942,7 +1038,8 @@ impl OverlayView {
                                     PromptSubmitAction::RemoteOpen => {
                                         cx.emit(crate::Update::OpenRemote(input.to_string()));
                                     }
-                                    PromptSubmitAction::RegexSelection(action) => {
+                                    PromptSubmitAction::RegexSelection(_)
+                                    | PromptSubmitAction::Align => {
                                         let snapshot = regex_selection_preview
                                             .as_ref()
                                             .and_then(|state| {
@@ -953,9 +1050,16 @@ impl OverlayView {
                                                 })
                                             });
                                         if let Some(snapshot) = snapshot {
-                                            let result = apply_regex_selection(
-                                                _core, action, input, &snapshot, true,
-                                            );
+                                            let result = match submit_action {
+                                                PromptSubmitAction::RegexSelection(action) => {
+                                                    apply_regex_selection(
+                                                        _core, action, input, &snapshot, true,
+                                                    )
+                                                }
+                                                _ => apply_alignment(
+                                                    _core, input, &snapshot, true,
+                                                ),
+                                            };
                                             match result {
                                                 Ok(RegexSelectionOutcome::Applied) => {
                                                     if let Some(state) = &regex_selection_preview
@@ -1052,6 +1156,7 @@ impl OverlayView {
                 // Focus will be handled by the prompt view's render method
 
                 self.native_prompt_view = Some(prompt_view);
+                OverlayManager::open_global(PROMPT_POPUP, PopupLayer::Modal, cx);
 
                 cx.notify();
             }
@@ -1090,6 +1195,7 @@ impl OverlayView {
                 .detach();
 
                 self.remote_connection_manager_view = Some(manager_view);
+                OverlayManager::open_global(REMOTE_CONNECTION_MANAGER_POPUP, PopupLayer::Modal, cx);
                 cx.notify();
             }
             crate::Update::Completion(completion_view) => {
@@ -1101,6 +1207,7 @@ impl OverlayView {
                 self.replace_completion(cx);
                 // Set up completion view with event subscription
                 self.completion_view = Some(completion_view.clone());
+                OverlayManager::open_global(COMPLETION_POPUP, PopupLayer::Completion, cx);
 
                 // Subscribe to dismiss events from completion view
                 cx.subscribe(
@@ -1164,12 +1271,17 @@ impl OverlayView {
                         show_preview,
                         preview_text_provider,
                         preview_text_task_provider,
+                        on_query_change,
+                        source,
                     } => {
                         let is_file_finder = title.as_ref() == "Open File";
                         let items = items.clone();
                         let on_select = on_select.clone();
                         let preview_text_provider = preview_text_provider.clone();
                         let preview_text_task_provider = preview_text_task_provider.clone();
+                        let on_query_change = on_query_change.clone();
+                        let source = source.clone();
+                        let source_for_select = source.clone();
                         let core_weak = self.core.clone();
                         let _items_count = items.len();
 
@@ -1182,6 +1294,38 @@ impl OverlayView {
                             view = view.with_title(title.clone());
 
                             view = view.with_items(items);
+                            if let Some(on_query_change) = on_query_change {
+                                view = view.with_query_changed_fn(move |query, picker_cx| {
+                                    on_query_change(query, picker_cx.weak_entity(), picker_cx);
+                                });
+                            }
+                            if is_file_finder {
+                                view = view.with_path_matching();
+                                let usage = usage_scores(UsageKind::File, cx);
+                                if !usage.is_empty() {
+                                    view = view.with_rank_boost_fn(move |item| {
+                                        item.file_path.as_ref().map_or(0, |path| {
+                                            usize::from(usage.boost(&path.to_string_lossy()))
+                                        })
+                                    });
+                                }
+                                let root = core_weak
+                                    .upgrade()
+                                    .and_then(|core| core.read(cx).project_directory.clone());
+                                let recent: std::collections::HashMap<std::path::PathBuf, usize> =
+                                    recent_files(root.as_deref(), cx)
+                                        .into_iter()
+                                        .enumerate()
+                                        .map(|(rank, file)| (file.path, rank))
+                                        .collect();
+                                if !recent.is_empty() {
+                                    view = view.with_recent_rank_fn(move |item| {
+                                        item.file_path
+                                            .as_ref()
+                                            .and_then(|path| recent.get(path).copied())
+                                    });
+                                }
+                            }
 
                             // Wire minimal preview open/close hooks
                             let open_core = core_weak.clone();
@@ -1574,6 +1718,11 @@ impl OverlayView {
                                         crate::runnables::task_preview_text(task),
                                         task.source().map(|source| source.path.clone()),
                                     ));
+                                } else if let Some(choice) = item
+                                    .data
+                                    .downcast_ref::<crate::merge_conflicts::MergeConflictChoice>()
+                                {
+                                    return Some((choice.preview.clone(), None));
                                 }
 
                                 if let Some(provider) = picker_preview_text_provider.as_ref() {
@@ -1605,6 +1754,19 @@ impl OverlayView {
                                 // Proactively dismiss the picker first so focus can restore immediately
                                 picker_cx.emit(gpui::DismissEvent);
 
+                                if is_file_finder
+                                    && let Some(path) = &selected_item.file_path
+                                {
+                                    record_usage(UsageKind::File, &path.to_string_lossy(), picker_cx);
+                                }
+
+                                // Sources confirm their own items once the picker is gone
+                                if let Some(source) = source_for_select.clone() {
+                                    let item = selected_item.clone();
+                                    picker_cx.defer(move |cx| source.confirm(&item, cx));
+                                    return;
+                                }
+
                                 // Handle LSP code action items (action, server id, offset)
                                 if let Some((action, ls_id, _offset)) = selected_item
                                     .data
@@ -1759,6 +1921,149 @@ impl OverlayView {
                                         });
                                     }
                                 }
+                                else if let Some(entry) = selected_item
+                                    .data
+                                    .downcast_ref::<crate::types::CommitLogEntry>()
+                                {
+                                    if let Some(core) = core_for_on_select.upgrade() {
+                                        let entry = entry.clone();
+                                        core.update(picker_cx, |_core, core_cx| {
+                                            core_cx.emit(crate::Update::ShowCommitDiff(entry));
+                                        });
+                                    }
+                                }
+                                else if let Some(request) = selected_item
+                                    .data
+                                    .downcast_ref::<crate::types::EnvironmentRecapture>()
+                                {
+                                    if let Some(core) = core_for_on_select.upgrade() {
+                                        let request = request.clone();
+                                        core.update(picker_cx, |_core, core_cx| {
+                                            core_cx.emit(crate::Update::RecaptureEnvironment(request));
+                                        });
+                                    }
+                                }
+                                else if let Some(choice) = selected_item
+                                    .data
+                                    .downcast_ref::<crate::types::TerminalProfileChoice>()
+                                {
+                                    if let Some(core) = core_for_on_select.upgrade() {
+                                        let choice = choice.clone();
+                                        core.update(picker_cx, |_core, core_cx| {
+                                            core_cx.emit(crate::Update::OpenTerminalProfile(choice));
+                                        });
+                                    }
+                                }
+                                else if let Some(choice) = selected_item
+                                    .data
+                                    .downcast_ref::<crate::merge_conflicts::MergeConflictChoice>()
+                                {
+                                    if let Some(core) = core_for_on_select.upgrade() {
+                                        let choice = choice.clone();
+                                        core.update(picker_cx, |_core, core_cx| {
+                                            core_cx.emit(crate::Update::ResolveMergeConflict(choice));
+                                        });
+                                    }
+                                }
+                                else if let Some(choice) = selected_item
+                                    .data
+                                    .downcast_ref::<crate::project_rename::ProjectRenameChoice>()
+                                {
+                                    if let Some(core) = core_for_on_select.upgrade() {
+                                        let choice = choice.clone();
+                                        core.update(picker_cx, |_core, core_cx| {
+                                            core_cx.emit(crate::Update::ProjectRename(choice));
+                                        });
+                                    }
+                                }
+                                else if let Some(choice) = selected_item
+                                    .data
+                                    .downcast_ref::<crate::run_configurations::RunConfigurationChoice>()
+                                {
+                                    if let Some(core) = core_for_on_select.upgrade() {
+                                        let choice = choice.clone();
+                                        core.update(picker_cx, |_core, core_cx| {
+                                            core_cx.emit(crate::Update::RunConfiguration(choice));
+                                        });
+                                    }
+                                }
+                                else if let Some(choice) = selected_item
+                                    .data
+                                    .downcast_ref::<crate::cargo_integration::CargoChoice>()
+                                {
+                                    if let Some(core) = core_for_on_select.upgrade() {
+                                        let choice = choice.clone();
+                                        core.update(picker_cx, |_core, core_cx| {
+                                            core_cx.emit(crate::Update::Cargo(choice));
+                                        });
+                                    }
+                                }
+                                else if let Some(choice) = selected_item
+                                    .data
+                                    .downcast_ref::<crate::indentation::IndentationChoice>()
+                                {
+                                    if let Some(core) = core_for_on_select.upgrade() {
+                                        let choice = *choice;
+                                        core.update(picker_cx, |_core, core_cx| {
+                                            core_cx.emit(crate::Update::Indentation(choice));
+                                        });
+                                    }
+                                }
+                                else if let Some(choice) = selected_item
+                                    .data
+                                    .downcast_ref::<crate::file_templates::FileTemplateChoice>()
+                                {
+                                    if let Some(core) = core_for_on_select.upgrade() {
+                                        let choice = choice.clone();
+                                        core.update(picker_cx, |_core, core_cx| {
+                                            core_cx.emit(crate::Update::FileTemplate(choice));
+                                        });
+                                    }
+                                }
+                                else if let Some(choice) = selected_item
+                                    .data
+                                    .downcast_ref::<crate::terminal_input::TerminalPasteChoice>()
+                                {
+                                    if let Some(core) = core_for_on_select.upgrade() {
+                                        let choice = choice.clone();
+                                        core.update(picker_cx, |_core, core_cx| {
+                                            core_cx.emit(crate::Update::TerminalPaste(choice));
+                                        });
+                                    }
+                                }
+                                else if let Some(template) = selected_item
+                                    .data
+                                    .downcast_ref::<crate::config::ProjectTemplateConfig>()
+                                {
+                                    if let Some(core) = core_for_on_select.upgrade() {
+                                        let template = template.clone();
+                                        core.update(picker_cx, |_core, core_cx| {
+                                            core_cx.emit(crate::Update::ProjectTemplate(template));
+                                        });
+                                    }
+                                }
+                                else if let Some(choice) = selected_item
+                                    .data
+                                    .downcast_ref::<crate::disk_changes::DiskChangeChoice>()
+                                {
+                                    if let Some(core) = core_for_on_select.upgrade() {
+                                        let choice = choice.clone();
+                                        core.update(picker_cx, |_core, core_cx| {
+                                            core_cx.emit(crate::Update::DiskChange(choice));
+                                        });
+                                    }
+                                }
+                                else if let Some(choice) = selected_item
+                                    .data
+                                    .downcast_ref::<crate::save_failure::SaveFailureChoice>()
+                                {
+                                    if let Some(core) = core_for_on_select.upgrade() {
+                                        let choice = choice.clone();
+                                        core.update(picker_cx, |_core, core_cx| {
+                                            core_cx.emit(crate::Update::ResolveSaveFailure(choice));
+                                        });
+                                    }
+                                }
                                 // Extract the file path from the selected item for opening
                                 else if let Some(path) =
                                     selected_item.data.downcast_ref::<std::path::PathBuf>()
@@ -1810,7 +2115,13 @@ impl OverlayView {
                         )
                         .detach();
 
+                        if let Some(source) = source {
+                            let sink = PickerItemSink::new(picker_view.downgrade());
+                            cx.defer(move |cx| source.items(sink, cx));
+                        }
+
                         self.native_picker_view = Some(picker_view);
+                        OverlayManager::open_global(PICKER_POPUP, PopupLayer::Modal, cx);
                     }
                 }
 
@@ -1908,8 +2219,9 @@ impl OverlayView {
         }
     }
 
-    /// Calculate cursor-based completion position using exact cursor coordinates when available
-    fn calculate_completion_position(&self, cx: &Context<Self>) -> (gpui::Pixels, gpui::Pixels) {
+    /// Bounds of the primary cursor cell, from the DocumentView's last paint
+    /// when available
+    fn completion_cursor_bounds(&self, cx: &Context<Self>) -> Bounds<Pixels> {
         let layout_info = self.get_workspace_layout_info(cx);
 
         // Use exact cursor coordinates if available from DocumentView rendering
@@ -1921,8 +2233,7 @@ impl OverlayView {
                 size = ?cursor_size,
                 "Using exact cursor coordinates"
             );
-            // cursor_pos is already the top-left of cursor, we want bottom-left for completion
-            return (cursor_pos.x, cursor_pos.y + cursor_size.height);
+            return Bounds::new(cursor_pos, cursor_size);
         }
 
         // Fallback to calculated position if no exact coordinates available
@@ -1937,7 +2248,10 @@ impl OverlayView {
                 Some(view) => (view, view.doc),
                 None => {
                     // No focused view; fallback to conservative defaults
-                    return (px(0.0), px(0.0));
+                    return Bounds::new(
+                        point(px(0.0), px(0.0)),
+                        size(layout_info.char_width, layout_info.line_height),
+                    );
                 }
             };
 
@@ -1974,7 +2288,10 @@ impl OverlayView {
                         let cursor_y =
                             document_area_y + layout_info.line_height * (relative_row as f32);
 
-                        return (cursor_x, cursor_y + layout_info.line_height); // Position below cursor
+                        return Bounds::new(
+                            point(cursor_x, cursor_y),
+                            size(layout_info.char_width, layout_info.line_height),
+                        );
                     }
                 }
             }
@@ -1992,7 +2309,10 @@ impl OverlayView {
             layout_info.title_bar_height,
             layout_info.tab_bar_height
         );
-        (fallback_x, fallback_y)
+        Bounds::new(
+            point(fallback_x, fallback_y),
+            size(layout_info.char_width, layout_info.line_height),
+        )
     }
 
     /// Get workspace layout information for completion positioning
@@ -2037,13 +2357,16 @@ impl gpui::Global for WorkspaceLayoutInfo {}
 
 impl OverlayView {
     #[inline]
-    fn terminal_metrics(&mut self, cx: &mut Context<Self>) -> (f32, f32) {
-        // Build a cache key from the current editor font settings
+    fn terminal_metrics(&mut self, window: &Window, cx: &mut Context<Self>) -> (f32, f32) {
+        // Build a cache key from the current editor font settings and the
+        // window scale, so moving to another monitor re-derives the cells
         let editor_font = cx.global::<nucleotide_types::EditorFontConfig>();
+        let scale_factor = window.scale_factor();
         let font_key = (
             editor_font.family.clone(),
             editor_font.size,
             editor_font.weight,
+            scale_factor,
         );
 
         let need_recalc = match &self.cached_font_key {
@@ -2075,6 +2398,9 @@ impl OverlayView {
                 (editor_font.size * 1.35).max(1.0)
             };
 
+            // Whole device pixels keep the PTY grid and painted cells in step
+            let (char_w, line_h) = nucleotide_types::snap_cell_size(char_w, line_h, scale_factor);
+
             self.cached_font_key = Some(font_key);
             self.cached_char_width = Some(char_w);
             self.cached_line_height = Some(line_h);
@@ -2133,7 +2459,7 @@ impl EventEmitter<nucleotide_ui::completion_v2::CompleteViaHelixEvent> for Overl
 impl EventEmitter<nucleotide_ui::completion_v2::CompletionWarningEvent> for OverlayView {}
 
 impl Render for OverlayView {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         // Check what type of overlay we should render
         if let Some(picker_view) = &self.native_picker_view {
             let theme = cx.theme();
@@ -2142,6 +2468,7 @@ impl Render for OverlayView {
             return OverlaySurface::new()
                 .top(tokens.sizes.space_8)
                 .on_light_dismiss(cx.listener(|this: &mut OverlayView, _e, window, cx| {
+                    OverlayManager::dismiss_global(Dismissal::Click { inside: None }, cx);
                     window.disable_focus();
                     this.dismiss_picker(cx);
                 }))
@@ -2161,6 +2488,7 @@ impl Render for OverlayView {
             return OverlaySurface::new()
                 .top(tokens.sizes.space_8)
                 .on_light_dismiss(cx.listener(|this: &mut OverlayView, _e, window, cx| {
+                    OverlayManager::dismiss_global(Dismissal::Click { inside: None }, cx);
                     window.disable_focus();
                     this.dismiss_prompt(cx);
                 }))
@@ -2180,6 +2508,7 @@ impl Render for OverlayView {
             return OverlaySurface::new()
                 .top(tokens.sizes.space_8)
                 .on_light_dismiss(cx.listener(|this: &mut OverlayView, _e, window, cx| {
+                    OverlayManager::dismiss_global(Dismissal::Click { inside: None }, cx);
                     window.disable_focus();
                     this.dismiss_remote_connection_manager(cx);
                 }))
@@ -2193,13 +2522,26 @@ impl Render for OverlayView {
 
         if let Some(completion_view) = &self.completion_view {
             nucleotide_logging::trace!("DIAG: Render overlay branch: completion");
-            use gpui::{Anchor, anchored, point};
-
-            // Calculate proper completion position based on cursor location
-            let (cursor_x, cursor_y) = self.calculate_completion_position(cx);
+            use gpui::{Anchor, anchored};
+
+            // Place the popup below the primary cursor, or above it near the
+            // bottom of the window, keeping the docs panel on screen
+            let cursor = self.completion_cursor_bounds(cx);
+            let popup_size = completion_view.read(cx).estimated_popup_size();
+            let previous = self
+                .completion_placement
+                .filter(|(cursor_top, _)| *cursor_top == cursor.origin.y)
+                .map(|(_, placement)| placement);
+            let position = PopupPositioner::new(PopupConstraints::default()).position_at_cursor(
+                cursor,
+                popup_size,
+                Bounds::new(point(px(0.0), px(0.0)), window.viewport_size()),
+                previous,
+            );
+            self.completion_placement = Some((cursor.origin.y, position.placement));
             nucleotide_logging::debug!(
-                x = ?cursor_x,
-                y = ?cursor_y,
+                origin = ?position.bounds.origin,
+                placement = ?position.placement,
                 "Rendering completion popup at calculated position"
             );
 
@@ -2214,15 +2556,15 @@ impl Render for OverlayView {
                 .on_mouse_down(
                     MouseButton::Left,
                     cx.listener(|this: &mut OverlayView, _e, window, cx| {
+                        OverlayManager::dismiss_global(Dismissal::Click { inside: None }, cx);
                         window.disable_focus();
                         this.dismiss_completion(cx);
                     }),
                 )
                 .child(
                     anchored()
-                        .position(point(cursor_x, cursor_y))
-                        .anchor(Anchor::TopLeft) // Anchor top-left of completion to cursor position
-                        .offset(point(px(0.0), px(2.0))) // Small offset below cursor
+                        .position(position.bounds.origin)
+                        .anchor(Anchor::TopLeft)
                         .snap_to_window_with_margin(px(8.0))
                         // Consume clicks inside the popup so they don't dismiss
                         .child(
@@ -2248,7 +2590,7 @@ impl Render for OverlayView {
                 }
 
                 // Constrain terminal height to avoid covering the entire editor
-                let window_h = f32::from(_window.bounds().size.height);
+                let window_h = f32::from(window.bounds().size.height);
                 let max_h = (window_h * 0.6).max(120.0);
                 let clamped_h = self.terminal_height_px.clamp(80.0, max_h);
                 if (clamped_h - self.terminal_height_px).abs() > 0.5 {
@@ -2263,8 +2605,8 @@ impl Render for OverlayView {
                 // the visible area. Snap the rendered panel height to whole
                 // cells so the split and terminal surface cannot diverge.
                 let layout = self.get_workspace_layout_info(cx);
-                let window_width = f32::from(_window.bounds().size.width);
-                let (char_w, line_h) = self.terminal_metrics(cx);
+                let window_width = f32::from(window.bounds().size.width);
+                let (char_w, line_h) = self.terminal_metrics(window, cx);
                 let terminal_content_height = (self.terminal_height_px
                     - nucleotide_terminal_panel::TERMINAL_PANEL_HEADER_HEIGHT_PX)
                     .max(line_h);
@@ -2359,31 +2701,15 @@ impl Render for OverlayView {
                             if !panel_focus_for_keys.is_focused(window) {
                                 return;
                             }
-                            if event.keystroke.modifiers.secondary()
-                                && event.keystroke.key.as_str() == "c"
-                                && let Some(text) = this
-                                    .terminal_panel
-                                    .as_ref()
-                                    .and_then(|panel| {
-                                        nucleotide_terminal_view::get_view_model(
-                                            panel.read(cx).active,
-                                        )
-                                    })
-                                    .and_then(|model| {
-                                        model
-                                            .lock()
-                                            .unwrap_or_else(|poisoned| poisoned.into_inner())
-                                            .selected_text()
-                                    })
-                            {
-                                cx.write_to_clipboard(ClipboardItem::new_string(text));
-                                cx.stop_propagation();
-                                return;
-                            }
                             if let Some(core) = this.core.upgrade() {
                                 let maybe_id =
                                     this.terminal_panel.as_ref().map(|p| p.read(cx).active);
                                 if let Some(id) = maybe_id {
+                                    if crate::terminal_input::scroll_terminal_for_key(id, event) {
+                                        window.refresh();
+                                        cx.stop_propagation();
+                                        return;
+                                    }
                                     let bytes =
                                         crate::terminal_input::encode_key_event_for_terminal(
                                             id, event,
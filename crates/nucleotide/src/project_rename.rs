@@ -0,0 +1,414 @@
+// ABOUTME: Textual project-wide rename for languages without LSP rename support
+// ABOUTME: Expands a name into its case variants and plans whole-word replacements per file
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Prompt command that previews a textual rename across the project.
+pub const RENAME_IN_FILES_COMMAND: &str = "rename-in-files";
+
+/// Matched lines searched before the preview is cut short.
+pub const RENAME_IN_FILES_MATCH_LIMIT: usize = 10_000;
+
+/// One spelling of the renamed identifier and its replacement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseVariant {
+    pub from: String,
+    pub to: String,
+}
+
+/// Parse `rename-in-files [old] new`; `old` defaults to `word_at_cursor`.
+pub fn parse_command(
+    command: &str,
+    word_at_cursor: impl FnOnce() -> Option<String>,
+) -> Option<Result<(String, String), String>> {
+    let mut parts = command.split_whitespace();
+    if parts.next()?.trim_start_matches(':') != RENAME_IN_FILES_COMMAND {
+        return None;
+    }
+
+    let args: Vec<&str> = parts.collect();
+    Some(match args.as_slice() {
+        [old, new] => Ok((old.to_string(), new.to_string())),
+        [new] => word_at_cursor()
+            .map(|old| (old, new.to_string()))
+            .ok_or_else(|| "No identifier under the cursor to rename".to_string()),
+        _ => Err(format!(
+            "Usage: {RENAME_IN_FILES_COMMAND} [old-name] new-name"
+        )),
+    })
+}
+
+/// The spellings of `old` to replace, each paired with `new` in the same
+/// convention: as typed, snake_case, SCREAMING_SNAKE_CASE, camelCase and
+/// PascalCase.
+pub fn case_variants(old: &str, new: &str) -> Result<Vec<CaseVariant>, String> {
+    for name in [old, new] {
+        if !is_identifier(name) {
+            return Err(format!("'{name}' is not an identifier"));
+        }
+    }
+    if old == new {
+        return Err("The new name is the same as the old one".to_string());
+    }
+
+    let (old_words, new_words) = (split_words(old), split_words(new));
+    let conventions: [fn(&[String]) -> String; 4] =
+        [snake_case, screaming_snake_case, camel_case, pascal_case];
+
+    let mut variants = vec![CaseVariant {
+        from: old.to_string(),
+        to: new.to_string(),
+    }];
+    for convention in conventions {
+        let from = convention(&old_words);
+        if variants.iter().any(|variant| variant.from == from) {
+            continue;
+        }
+        variants.push(CaseVariant {
+            from,
+            to: convention(&new_words),
+        });
+    }
+    Ok(variants)
+}
+
+/// Regex finding lines that may contain any of `variants` as a whole word.
+pub fn search_pattern(variants: &[CaseVariant]) -> String {
+    let alternatives: Vec<String> = variants
+        .iter()
+        .map(|variant| regex::escape(&variant.from))
+        .collect();
+    format!(r"\b(?:{})\b", alternatives.join("|"))
+}
+
+/// The identifier touching byte offset `cursor` in `line`.
+pub fn identifier_at(line: &str, cursor: usize) -> Option<&str> {
+    let cursor = cursor.min(line.len());
+    let start = line[..cursor]
+        .char_indices()
+        .rev()
+        .take_while(|&(_, ch)| is_identifier_char(ch))
+        .last()
+        .map_or(cursor, |(index, _)| index);
+    let end = line[cursor..]
+        .char_indices()
+        .find(|&(_, ch)| !is_identifier_char(ch))
+        .map_or(line.len(), |(index, _)| cursor + index);
+    let word = &line[start..end];
+    is_identifier(word).then_some(word)
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(is_identifier_char)
+}
+
+fn is_identifier_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Lowercase words of an identifier, split at underscores and case changes
+/// (`HTTPServer` is `http`, `server`).
+fn split_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for part in name.split('_').filter(|part| !part.is_empty()) {
+        let chars: Vec<char> = part.chars().collect();
+        let mut word = String::new();
+        for (index, &ch) in chars.iter().enumerate() {
+            let previous = index.checked_sub(1).map(|index| chars[index]);
+            let next = chars.get(index + 1);
+            let starts_word = ch.is_uppercase()
+                && previous.is_some_and(|previous| {
+                    previous.is_lowercase()
+                        || previous.is_numeric()
+                        || (previous.is_uppercase() && next.is_some_and(|next| next.is_lowercase()))
+                });
+            if starts_word && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            word.extend(ch.to_lowercase());
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn snake_case(words: &[String]) -> String {
+    words.join("_")
+}
+
+fn screaming_snake_case(words: &[String]) -> String {
+    words.join("_").to_uppercase()
+}
+
+fn camel_case(words: &[String]) -> String {
+    words
+        .iter()
+        .enumerate()
+        .map(|(index, word)| {
+            if index == 0 {
+                word.clone()
+            } else {
+                capitalize(word)
+            }
+        })
+        .collect()
+}
+
+fn pascal_case(words: &[String]) -> String {
+    words.iter().map(|word| capitalize(word)).collect()
+}
+
+/// A replacement within one line, as byte offsets into the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineEdit {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+/// Whole-word occurrences of `variants` in `line`.
+pub fn line_edits(line: &str, variants: &[CaseVariant]) -> Vec<LineEdit> {
+    let mut edits = Vec::new();
+    let mut word_start = None;
+    for (index, ch) in line
+        .char_indices()
+        .chain(std::iter::once((line.len(), ' ')))
+    {
+        match (word_start, is_identifier_char(ch)) {
+            (None, true) => word_start = Some(index),
+            (Some(start), false) => {
+                let word = &line[start..index];
+                if let Some(variant) = variants.iter().find(|variant| variant.from == word) {
+                    edits.push(LineEdit {
+                        start,
+                        end: index,
+                        replacement: variant.to.clone(),
+                    });
+                }
+                word_start = None;
+            }
+            _ => {}
+        }
+    }
+    edits
+}
+
+/// A line with at least one replacement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineRename {
+    /// Zero-based line number
+    pub line: usize,
+    pub text: String,
+    pub edits: Vec<LineEdit>,
+}
+
+impl LineRename {
+    /// The line after its replacements.
+    pub fn renamed_text(&self) -> String {
+        let mut renamed = String::with_capacity(self.text.len());
+        let mut copied = 0;
+        for edit in &self.edits {
+            renamed.push_str(&self.text[copied..edit.start]);
+            renamed.push_str(&edit.replacement);
+            copied = edit.end;
+        }
+        renamed.push_str(&self.text[copied..]);
+        renamed
+    }
+}
+
+/// Replacements planned for one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileRename {
+    pub path: PathBuf,
+    pub lines: Vec<LineRename>,
+}
+
+impl FileRename {
+    pub fn replacement_count(&self) -> usize {
+        self.lines.iter().map(|line| line.edits.len()).sum()
+    }
+}
+
+/// Group matched lines by file, keeping lines with a whole-word match.
+pub fn plan_file_renames(
+    matched_lines: impl IntoIterator<Item = (PathBuf, usize, String)>,
+    variants: &[CaseVariant],
+) -> Vec<FileRename> {
+    let mut files: BTreeMap<PathBuf, BTreeMap<usize, LineRename>> = BTreeMap::new();
+    for (path, line, text) in matched_lines {
+        let lines = files.entry(path).or_default();
+        if lines.contains_key(&line) {
+            continue;
+        }
+        let edits = line_edits(&text, variants);
+        if !edits.is_empty() {
+            lines.insert(line, LineRename { line, text, edits });
+        }
+    }
+
+    files
+        .into_iter()
+        .filter(|(_, lines)| !lines.is_empty())
+        .map(|(path, lines)| FileRename {
+            path,
+            lines: lines.into_values().collect(),
+        })
+        .collect()
+}
+
+/// A previewed rename waiting to be applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectRename {
+    pub old_name: String,
+    pub new_name: String,
+    pub files: Vec<FileRename>,
+    /// Files the user opted out of
+    pub excluded: HashSet<PathBuf>,
+    /// The search stopped at `RENAME_IN_FILES_MATCH_LIMIT`
+    pub truncated: bool,
+}
+
+impl ProjectRename {
+    pub fn toggle(&mut self, path: &Path) {
+        if !self.excluded.remove(path) {
+            self.excluded.insert(path.to_path_buf());
+        }
+    }
+
+    pub fn is_included(&self, path: &Path) -> bool {
+        !self.excluded.contains(path)
+    }
+
+    pub fn included_files(&self) -> impl Iterator<Item = &FileRename> {
+        self.files
+            .iter()
+            .filter(|file| self.is_included(&file.path))
+    }
+}
+
+/// Picker payload for the rename preview.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectRenameChoice {
+    Apply,
+    ToggleFile(PathBuf),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant_pairs(old: &str, new: &str) -> Vec<(String, String)> {
+        case_variants(old, new)
+            .unwrap()
+            .into_iter()
+            .map(|variant| (variant.from, variant.to))
+            .collect()
+    }
+
+    #[test]
+    fn case_variants_follow_each_naming_convention() {
+        let pairs = variant_pairs("userName", "accountId");
+        let expected = [
+            ("userName", "accountId"),
+            ("user_name", "account_id"),
+            ("USER_NAME", "ACCOUNT_ID"),
+            ("UserName", "AccountId"),
+        ];
+        assert_eq!(
+            pairs,
+            expected
+                .iter()
+                .map(|(from, to)| (from.to_string(), to.to_string()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            split_words("HTTPServer2Config"),
+            ["http", "server2", "config"]
+        );
+    }
+
+    #[test]
+    fn case_variants_reject_non_identifiers() {
+        assert!(case_variants("foo bar", "baz").is_err());
+        assert!(case_variants("foo", "1foo").is_err());
+        assert!(case_variants("foo", "foo").is_err());
+    }
+
+    #[test]
+    fn line_edits_match_whole_words_only() {
+        let variants = case_variants("user_name", "account_id").unwrap();
+        let line = LineRename {
+            line: 0,
+            text: "let user_name = UserName::new(user_names, USER_NAME);".to_string(),
+            edits: line_edits(
+                "let user_name = UserName::new(user_names, USER_NAME);",
+                &variants,
+            ),
+        };
+
+        assert_eq!(line.edits.len(), 3);
+        assert_eq!(
+            line.renamed_text(),
+            "let account_id = AccountId::new(user_names, ACCOUNT_ID);"
+        );
+    }
+
+    #[test]
+    fn renames_are_grouped_by_file_and_line() {
+        let variants = case_variants("old", "new").unwrap();
+        let files = plan_file_renames(
+            [
+                (PathBuf::from("b.rs"), 3, "old(old)".to_string()),
+                (PathBuf::from("b.rs"), 3, "old(old)".to_string()),
+                (PathBuf::from("a.rs"), 0, "Old".to_string()),
+                (PathBuf::from("c.rs"), 1, "bold".to_string()),
+            ],
+            &variants,
+        );
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, PathBuf::from("a.rs"));
+        assert_eq!(files[1].replacement_count(), 2);
+    }
+
+    #[test]
+    fn identifier_at_finds_the_word_around_the_cursor() {
+        assert_eq!(identifier_at("let old_name = 1;", 6), Some("old_name"));
+        assert_eq!(identifier_at("let old_name = 1;", 12), Some("old_name"));
+        assert_eq!(identifier_at("let old_name = 1;", 13), None);
+        assert_eq!(identifier_at("x = 42", 5), None);
+    }
+
+    #[test]
+    fn rename_command_defaults_to_the_word_at_the_cursor() {
+        assert_eq!(
+            parse_command(":rename-in-files old new", || None),
+            Some(Ok(("old".to_string(), "new".to_string())))
+        );
+        assert_eq!(
+            parse_command("rename-in-files new", || Some("cursor".to_string())),
+            Some(Ok(("cursor".to_string(), "new".to_string())))
+        );
+        assert!(matches!(
+            parse_command("rename-in-files", || Some("cursor".to_string())),
+            Some(Err(_))
+        ));
+        assert_eq!(parse_command("rename new", || None), None);
+    }
+}
@@ -0,0 +1,339 @@
+// ABOUTME: Language-independent task providers for Makefile targets and justfile recipes
+// ABOUTME: Parses the build files in the project root into runnable tasks with descriptions
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use nucleotide_events::v2::run::{
+    CommandSpec, ResolvedTask, RunKind, SourceLocation, TaskTemplate,
+};
+use regex::Regex;
+
+const TAG_MAKEFILE: &str = "makefile";
+const TAG_JUSTFILE: &str = "justfile";
+/// File names `make` reads, in the order it looks for them.
+const MAKEFILE_NAMES: &[&str] = &["GNUmakefile", "makefile", "Makefile"];
+/// File names `just` reads.
+const JUSTFILE_NAMES: &[&str] = &["justfile", "Justfile", ".justfile"];
+
+/// `targets: prerequisites`, excluding variable assignments like `A := b`.
+static MAKE_RULE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<targets>[^\s:#=][^:#=]*?)\s*::?(?:[^=]|$)").expect("valid make rule regex")
+});
+
+/// `name params: dependencies`, optionally quiet with a leading `@`.
+static JUST_RECIPE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^@?(?P<name>[A-Za-z_][A-Za-z0-9_-]*)(?P<params>[^:]*?):(?:[^=]|$)")
+        .expect("valid just recipe regex")
+});
+
+static JUST_DOC_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\[(?:.*,\s*)?doc\(\s*["'](?P<doc>.*?)["']\s*\)"#).expect("valid just doc regex")
+});
+
+/// A target or recipe found in a build file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectTaskEntry {
+    pub name: String,
+    pub description: Option<String>,
+    /// Zero-based line of the definition
+    pub line: usize,
+}
+
+/// Makefile targets, skipping special targets like `.PHONY`, pattern rules
+/// and targets built from variables. A `## text` comment after the rule or
+/// a `#` comment on the line above describes the target.
+pub fn parse_makefile(contents: &str) -> Vec<ProjectTaskEntry> {
+    let mut entries: Vec<ProjectTaskEntry> = Vec::new();
+    let mut comment: Option<String> = None;
+
+    for (line_index, line) in contents.lines().enumerate() {
+        if let Some(text) = line.strip_prefix('#') {
+            comment = Some(text.trim_start_matches('#').trim().to_string())
+                .filter(|text| !text.is_empty());
+            continue;
+        }
+        let previous_comment = comment.take();
+        if line.starts_with('\t') || line.starts_with(' ') {
+            continue;
+        }
+        let Some(captures) = MAKE_RULE_RE.captures(line) else {
+            continue;
+        };
+        let description = line
+            .split_once("##")
+            .map(|(_, text)| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .or(previous_comment);
+
+        for target in captures["targets"].split_whitespace() {
+            if target.starts_with('.') || target.contains(['%', '$', '(', ')']) {
+                continue;
+            }
+            if entries.iter().any(|entry| entry.name == target) {
+                continue;
+            }
+            entries.push(ProjectTaskEntry {
+                name: target.to_string(),
+                description: description.clone(),
+                line: line_index,
+            });
+        }
+    }
+    entries
+}
+
+/// Justfile recipes that can run without arguments. Private recipes, those
+/// starting with `_` or marked `[private]`, are skipped. A `[doc(...)]`
+/// attribute or a `#` comment above the recipe describes it.
+pub fn parse_justfile(contents: &str) -> Vec<ProjectTaskEntry> {
+    let mut entries = Vec::new();
+    let mut comment: Option<String> = None;
+    let mut doc: Option<String> = None;
+    let mut private = false;
+
+    for (line_index, line) in contents.lines().enumerate() {
+        if let Some(text) = line.strip_prefix('#') {
+            if !text.starts_with('!') {
+                comment = Some(text.trim().to_string()).filter(|text| !text.is_empty());
+            }
+            continue;
+        }
+        if line.starts_with('[') {
+            if let Some(captures) = JUST_DOC_RE.captures(line) {
+                doc = Some(captures["doc"].to_string());
+            }
+            private |= line.contains("private");
+            continue;
+        }
+        let previous_comment = comment.take();
+        let recipe_doc = doc.take();
+        let recipe_private = std::mem::take(&mut private);
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let Some(captures) = JUST_RECIPE_RE.captures(line) else {
+            continue;
+        };
+        let name = &captures["name"];
+        if matches!(
+            name,
+            "set" | "alias" | "export" | "import" | "mod" | "if" | "else"
+        ) || name.starts_with('_')
+            || recipe_private
+            || has_required_parameter(&captures["params"])
+        {
+            continue;
+        }
+        entries.push(ProjectTaskEntry {
+            name: name.to_string(),
+            description: recipe_doc.or(previous_comment),
+            line: line_index,
+        });
+    }
+    entries
+}
+
+/// Whether a recipe's parameter list has one without a default. `+args`
+/// needs at least one value; `*args` may be empty. A leading `$` exports
+/// the parameter and does not change this.
+fn has_required_parameter(params: &str) -> bool {
+    let mut params = params.trim();
+    let mut required = false;
+    while !params.is_empty() {
+        let param_end = params
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(params.len());
+        let param = &params[..param_end];
+        let rest = params[param_end..].trim_start();
+        let Some(default) = rest.strip_prefix('=') else {
+            required |= !param.trim_start_matches('$').starts_with('*');
+            params = rest;
+            continue;
+        };
+        params = skip_default(default.trim_start());
+    }
+    required
+}
+
+/// The text after a parameter default: a quoted string, a parenthesized
+/// expression or a bare word.
+fn skip_default(text: &str) -> &str {
+    let end = match text.chars().next() {
+        Some(quote @ ('"' | '\'')) => text[1..].find(quote).map_or(text.len(), |end| end + 2),
+        Some('(') => text.find(')').map_or(text.len(), |end| end + 1),
+        _ => text.find(char::is_whitespace).unwrap_or(text.len()),
+    };
+    text[end..].trim_start()
+}
+
+fn entry_tasks(
+    entries: Vec<ProjectTaskEntry>,
+    program: &str,
+    path: &Path,
+    tag: &str,
+) -> impl Iterator<Item = ResolvedTask> {
+    let cwd = path.parent().map(Path::to_path_buf);
+    entries.into_iter().map(move |entry| {
+        let mut command = CommandSpec::new(program).with_args([entry.name.clone()]);
+        command.cwd = cwd.clone();
+        ResolvedTask {
+            template: TaskTemplate {
+                label: format!("{program} {}", entry.name),
+                kind: RunKind::Run,
+                command: command.clone(),
+                source: Some(SourceLocation {
+                    path: path.to_path_buf(),
+                    line: entry.line,
+                    column: 0,
+                }),
+                tags: vec![tag.to_string()],
+                description: entry.description,
+            },
+            command,
+        }
+    })
+}
+
+/// Tasks for the Makefile targets and justfile recipes in `root`.
+pub fn discover_project_tasks(root: &Path) -> Vec<ResolvedTask> {
+    let read = |names: &[&str]| {
+        names.iter().find_map(|name| {
+            let path = root.join(name);
+            std::fs::read_to_string(&path)
+                .ok()
+                .map(|contents| (path, contents))
+        })
+    };
+
+    let mut tasks = Vec::new();
+    if let Some((path, contents)) = read(JUSTFILE_NAMES) {
+        tasks.extend(entry_tasks(
+            parse_justfile(&contents),
+            "just",
+            &path,
+            TAG_JUSTFILE,
+        ));
+    }
+    if let Some((path, contents)) = read(MAKEFILE_NAMES) {
+        tasks.extend(entry_tasks(
+            parse_makefile(&contents),
+            "make",
+            &path,
+            TAG_MAKEFILE,
+        ));
+    }
+    tasks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(entries: &[ProjectTaskEntry]) -> Vec<(&str, Option<&str>, usize)> {
+        entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.name.as_str(),
+                    entry.description.as_deref(),
+                    entry.line,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn makefile_targets_with_descriptions() {
+        let makefile = "\
+CC := gcc
+VERSION ?= 1.0
+.PHONY: build test
+
+# Build the binary
+build: main.o
+\t$(CC) -o app main.o
+
+test: build ## Run the test suite
+\t./run-tests
+
+%.o: %.c
+\t$(CC) -c $<
+
+$(OUT)/app: build
+clean install::
+\trm -rf out
+";
+        assert_eq!(
+            names(&parse_makefile(makefile)),
+            vec![
+                ("build", Some("Build the binary"), 5),
+                ("test", Some("Run the test suite"), 8),
+                ("clean", None, 15),
+                ("install", None, 15),
+            ]
+        );
+    }
+
+    #[test]
+    fn justfile_recipes_without_required_arguments() {
+        let justfile = r#"
+set shell := ["bash", "-c"]
+version := "1.0"
+alias b := build
+
+# Build everything
+build:
+    cargo build
+
+[doc("Run the tests")]
+test filter="" *args:
+    cargo test {{filter}} {{args}}
+
+deploy target:
+    ./deploy {{target}}
+
+[private]
+setup:
+    ./setup
+
+_helper:
+    echo hidden
+
+@lint: build
+    cargo clippy
+"#;
+        assert_eq!(
+            names(&parse_justfile(justfile)),
+            vec![
+                ("build", Some("Build everything"), 6),
+                ("test", Some("Run the tests"), 10),
+                ("lint", None, 23),
+            ]
+        );
+        assert!(has_required_parameter(" +files"));
+        assert!(!has_required_parameter(
+            " mode='debug dev' level=(1 + 2) *rest"
+        ));
+    }
+
+    #[test]
+    fn project_tasks_run_in_the_project_root() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp.path().join("Makefile"),
+            "all: ## Build all\n\tmake app\n",
+        )
+        .unwrap();
+        std::fs::write(temp.path().join("justfile"), "fmt:\n    cargo fmt\n").unwrap();
+
+        let tasks = discover_project_tasks(temp.path());
+        let labels = tasks.iter().map(ResolvedTask::label).collect::<Vec<_>>();
+        assert_eq!(labels, vec!["just fmt", "make all"]);
+        assert_eq!(tasks[1].command.program, "make");
+        assert_eq!(tasks[1].command.args, vec!["all"]);
+        assert_eq!(tasks[1].command.cwd.as_deref(), Some(temp.path()));
+        assert_eq!(tasks[1].description(), Some("Build all"));
+        assert!(discover_project_tasks(&temp.path().join("missing")).is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+// ABOUTME: Scaffolding templates offered by the New Project wizard
+// ABOUTME: Builds the command that creates a project and names the folder it creates
+
+use std::path::PathBuf;
+
+use nucleotide_events::v2::run::CommandSpec;
+
+use crate::config::ProjectTemplateConfig;
+
+/// Placeholder in template arguments replaced by the project name.
+pub const PROJECT_NAME_PLACEHOLDER: &str = "{name}";
+
+/// Templates offered when nothing is configured.
+pub fn builtin_templates() -> Vec<ProjectTemplateConfig> {
+    let template =
+        |name: &str, description: &str, command: &str, args: &[&str]| ProjectTemplateConfig {
+            name: name.to_string(),
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            description: Some(description.to_string()),
+        };
+    vec![
+        template("Rust binary", "cargo new", "cargo", &["new", "{name}"]),
+        template(
+            "Rust library",
+            "cargo new --lib",
+            "cargo",
+            &["new", "--lib", "{name}"],
+        ),
+        template(
+            "Vite app",
+            "npm create vite",
+            "npm",
+            &["create", "vite@latest", "{name}"],
+        ),
+    ]
+}
+
+/// Templates to offer: the configured ones, or the built-in ones when none
+/// are configured.
+pub fn available_templates(configured: &[ProjectTemplateConfig]) -> Vec<ProjectTemplateConfig> {
+    if configured.is_empty() {
+        builtin_templates()
+    } else {
+        configured.to_vec()
+    }
+}
+
+/// The trimmed project name, or why it can't name a folder.
+pub fn validate_project_name(name: &str) -> Result<&str, &'static str> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Project name is empty");
+    }
+    if matches!(name, "." | "..") || name.contains(['/', '\\']) {
+        return Err("Project name must be a single folder name");
+    }
+    Ok(name)
+}
+
+/// A project about to be scaffolded in `location`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewProject {
+    pub template: ProjectTemplateConfig,
+    pub location: PathBuf,
+    pub name: String,
+}
+
+impl NewProject {
+    /// Folder the scaffolding command creates, opened once it succeeds.
+    pub fn directory(&self) -> PathBuf {
+        self.location.join(&self.name)
+    }
+
+    /// The template's command with the project name filled in, run in the
+    /// location.
+    pub fn command(&self) -> CommandSpec {
+        CommandSpec::new(&self.template.command)
+            .with_args(
+                self.template
+                    .args
+                    .iter()
+                    .map(|arg| arg.replace(PROJECT_NAME_PLACEHOLDER, &self.name)),
+            )
+            .with_cwd(&self.location)
+    }
+
+    /// Whether the scaffolding command left the project folder behind.
+    pub fn was_created(&self) -> bool {
+        self.directory().is_dir()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_project(args: &[&str]) -> NewProject {
+        NewProject {
+            template: ProjectTemplateConfig {
+                name: "Rust binary".to_string(),
+                command: "cargo".to_string(),
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+                description: None,
+            },
+            location: PathBuf::from("/work"),
+            name: "demo".to_string(),
+        }
+    }
+
+    #[test]
+    fn command_fills_in_the_project_name_and_runs_in_the_location() {
+        let project = new_project(&["new", "{name}", "--name={name}-cli"]);
+
+        let command = project.command();
+
+        assert_eq!(command.program, "cargo");
+        assert_eq!(command.args, ["new", "demo", "--name=demo-cli"]);
+        assert_eq!(command.cwd, Some(PathBuf::from("/work")));
+        assert_eq!(project.directory(), PathBuf::from("/work/demo"));
+    }
+
+    #[test]
+    fn project_names_must_be_a_single_folder() {
+        assert_eq!(validate_project_name("  demo "), Ok("demo"));
+        assert!(validate_project_name("   ").is_err());
+        assert!(validate_project_name("..").is_err());
+        assert!(validate_project_name("apps/demo").is_err());
+    }
+
+    #[test]
+    fn configured_templates_replace_the_builtin_ones() {
+        assert_eq!(available_templates(&[]), builtin_templates());
+
+        let configured = [new_project(&[]).template];
+        assert_eq!(available_templates(&configured), configured);
+    }
+}
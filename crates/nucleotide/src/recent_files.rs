@@ -0,0 +1,456 @@
+// ABOUTME: Recently opened and recently closed files per project, with their selections and scroll
+// ABOUTME: Stored in the config directory; reopens files where they were closed and feeds the file finder
+
+use anyhow::{Context, Result};
+use gpui::{App, Global};
+use helix_core::{Position, Range, RopeSlice, Selection, pos_at_coords};
+use nucleotide_events::v2::document::DocumentViewState;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Prompt command reopening the most recently closed tab.
+pub const REOPEN_CLOSED_TAB_COMMAND: &str = "reopen-closed-tab";
+const STORE_FILE_NAME: &str = "recent-files.toml";
+/// Opened files remembered per project.
+const MAX_RECENT_FILES: usize = 50;
+/// Closed tabs remembered per project.
+const MAX_CLOSED_FILES: usize = 30;
+/// Projects remembered; the least recently used is dropped first.
+const MAX_PROJECTS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentFile {
+    pub path: PathBuf,
+    /// Zero-based cursor line when the file was last closed
+    #[serde(default)]
+    pub line: usize,
+    #[serde(default)]
+    pub column: usize,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub primary_selection: usize,
+    /// Zero-based line at the top of the view when the file was last closed
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub scroll_line: usize,
+    /// Selections when the file was last closed; empty in entries that only
+    /// kept the cursor
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub selections: Vec<RecentSelection>,
+}
+
+/// A selection range as zero-based lines and columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentSelection {
+    pub anchor_line: usize,
+    pub anchor_column: usize,
+    pub head_line: usize,
+    pub head_column: usize,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+impl RecentFile {
+    pub fn new(path: PathBuf, cursor: Option<Position>) -> Self {
+        let cursor = cursor.unwrap_or_default();
+        Self {
+            path,
+            line: cursor.row,
+            column: cursor.col,
+            primary_selection: 0,
+            scroll_line: 0,
+            selections: Vec::new(),
+        }
+    }
+
+    /// `path` closed with the selections and scroll position of `state`.
+    pub fn with_view_state(path: PathBuf, state: Option<&DocumentViewState>) -> Self {
+        let Some(state) = state else {
+            return Self::new(path, None);
+        };
+        Self {
+            selections: state
+                .ranges
+                .iter()
+                .map(|&(anchor, head)| RecentSelection {
+                    anchor_line: anchor.row,
+                    anchor_column: anchor.col,
+                    head_line: head.row,
+                    head_column: head.col,
+                })
+                .collect(),
+            primary_selection: state.primary_index,
+            scroll_line: state.top_line,
+            ..Self::new(path, Some(state.cursor))
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        Position::new(self.line, self.column)
+    }
+
+    /// The selection to reopen the file with in `text`, and the char index of
+    /// the line to scroll to. Positions past the end of a file that shrank
+    /// since are moved to its last line.
+    pub fn restore_in(&self, text: RopeSlice) -> (Selection, usize) {
+        let at = |line, column| pos_at_coords(text, Position::new(line, column), true);
+        let ranges = if self.selections.is_empty() {
+            vec![Range::point(at(self.line, self.column))]
+        } else {
+            self.selections
+                .iter()
+                .map(|selection| {
+                    Range::new(
+                        at(selection.anchor_line, selection.anchor_column),
+                        at(selection.head_line, selection.head_column),
+                    )
+                })
+                .collect()
+        };
+        let primary = self.primary_selection.min(ranges.len() - 1);
+        let last_line = text.len_lines().saturating_sub(1);
+        let scroll = text.line_to_char(self.scroll_line.min(last_line));
+        (Selection::new(ranges.into(), primary), scroll)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectHistory {
+    /// Project root; files opened without a project share the entry without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<PathBuf>,
+    /// Most recently opened first
+    #[serde(default)]
+    pub opened: Vec<RecentFile>,
+    /// Most recently closed first
+    #[serde(default)]
+    pub closed: Vec<RecentFile>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentFilesStore {
+    /// Most recently used project first
+    #[serde(default)]
+    pub projects: Vec<ProjectHistory>,
+}
+
+impl RecentFilesStore {
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .with_context(|| format!("failed to parse {}", path.display())),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error).with_context(|| format!("failed to read {}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        let contents = toml::to_string_pretty(self).context("failed to encode recent files")?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    fn project(&self, root: Option<&Path>) -> Option<&ProjectHistory> {
+        self.projects
+            .iter()
+            .find(|project| project.root.as_deref() == root)
+    }
+
+    /// The history for `root`, moved to the front as the most recently used.
+    fn project_mut(&mut self, root: Option<&Path>) -> &mut ProjectHistory {
+        let project = match self
+            .projects
+            .iter()
+            .position(|project| project.root.as_deref() == root)
+        {
+            Some(index) => self.projects.remove(index),
+            None => ProjectHistory {
+                root: root.map(Path::to_path_buf),
+                ..ProjectHistory::default()
+            },
+        };
+        self.projects.insert(0, project);
+        self.projects.truncate(MAX_PROJECTS);
+        &mut self.projects[0]
+    }
+
+    /// Moves `path` to the front of the opened files, keeping the cursor it
+    /// was last closed at. An open file is no longer a closed tab.
+    pub fn record_opened(&mut self, root: Option<&Path>, path: &Path) {
+        let project = self.project_mut(root);
+        let file = match project.opened.iter().position(|file| file.path == path) {
+            Some(index) => project.opened.remove(index),
+            None => RecentFile::new(path.to_path_buf(), None),
+        };
+        project.opened.insert(0, file);
+        project.opened.truncate(MAX_RECENT_FILES);
+        project.closed.retain(|closed| closed.path != path);
+    }
+
+    /// Remembers `file` as the most recently closed tab and its cursor for
+    /// the next time it is opened.
+    pub fn record_closed(&mut self, root: Option<&Path>, file: RecentFile) {
+        let project = self.project_mut(root);
+        match project
+            .opened
+            .iter_mut()
+            .find(|opened| opened.path == file.path)
+        {
+            Some(opened) => *opened = file.clone(),
+            None => {
+                project.opened.insert(0, file.clone());
+                project.opened.truncate(MAX_RECENT_FILES);
+            }
+        }
+        project.closed.retain(|closed| closed.path != file.path);
+        project.closed.insert(0, file);
+        project.closed.truncate(MAX_CLOSED_FILES);
+    }
+
+    /// Removes and returns the most recently closed tab of `root`.
+    pub fn pop_closed(&mut self, root: Option<&Path>) -> Option<RecentFile> {
+        let index = self
+            .projects
+            .iter()
+            .position(|project| project.root.as_deref() == root)?;
+        let project = &mut self.projects[index];
+        (!project.closed.is_empty()).then(|| project.closed.remove(0))
+    }
+
+    /// Opened files of `root`, most recent first.
+    pub fn recent(&self, root: Option<&Path>) -> &[RecentFile] {
+        self.project(root)
+            .map_or(&[], |project| project.opened.as_slice())
+    }
+    /// Roots of the remembered projects, most recently used first.
+    pub fn project_roots(&self) -> impl Iterator<Item = &Path> {
+        self.projects
+            .iter()
+            .filter_map(|project| project.root.as_deref())
+    }
+}
+
+/// The recent files store for this session, installed as a GPUI global.
+#[derive(Debug)]
+pub struct RecentFiles {
+    store: RecentFilesStore,
+    path: PathBuf,
+}
+
+impl Global for RecentFiles {}
+
+impl RecentFiles {
+    pub fn load_default() -> Self {
+        let path = default_store_path();
+        let store = RecentFilesStore::load(&path).unwrap_or_else(|error| {
+            nucleotide_logging::warn!(error = %error, "Ignoring unreadable recent files");
+            RecentFilesStore::default()
+        });
+        Self { store, path }
+    }
+
+    pub fn record_opened(&mut self, root: Option<&Path>, path: &Path) {
+        self.store.record_opened(root, path);
+        self.save();
+    }
+
+    pub fn record_closed(&mut self, root: Option<&Path>, file: RecentFile) {
+        self.store.record_closed(root, file);
+        self.save();
+    }
+
+    pub fn pop_closed(&mut self, root: Option<&Path>) -> Option<RecentFile> {
+        let file = self.store.pop_closed(root)?;
+        self.save();
+        Some(file)
+    }
+
+    pub fn recent(&self, root: Option<&Path>) -> &[RecentFile] {
+        self.store.recent(root)
+    }
+
+    pub fn project_roots(&self) -> impl Iterator<Item = &Path> {
+        self.store.project_roots()
+    }
+
+    fn save(&self) {
+        if let Err(error) = self.store.save(&self.path) {
+            nucleotide_logging::warn!(error = %error, "Failed to save recent files");
+        }
+    }
+}
+
+/// Recently opened files of `root`, most recent first; empty when the store
+/// isn't installed.
+pub fn recent_files(root: Option<&Path>, cx: &App) -> Vec<RecentFile> {
+    cx.try_global::<RecentFiles>()
+        .map(|recent| recent.recent(root).to_vec())
+        .unwrap_or_default()
+}
+
+pub fn default_store_path() -> PathBuf {
+    helix_loader::config_dir().join(STORE_FILE_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(files: &[RecentFile]) -> Vec<&str> {
+        files
+            .iter()
+            .map(|file| file.path.to_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn opened_files_are_tracked_per_project_most_recent_first() {
+        let mut store = RecentFilesStore::default();
+        let app = Some(Path::new("/work/app"));
+        let lib = Some(Path::new("/work/lib"));
+
+        store.record_opened(app, Path::new("/work/app/a.rs"));
+        store.record_opened(lib, Path::new("/work/lib/x.rs"));
+        store.record_opened(app, Path::new("/work/app/b.rs"));
+        store.record_opened(app, Path::new("/work/app/a.rs"));
+
+        assert_eq!(
+            paths(store.recent(app)),
+            vec!["/work/app/a.rs", "/work/app/b.rs"]
+        );
+        assert_eq!(paths(store.recent(lib)), vec!["/work/lib/x.rs"]);
+        assert!(store.recent(None).is_empty());
+        assert_eq!(store.projects[0].root.as_deref(), app);
+    }
+
+    #[test]
+    fn closed_tabs_reopen_in_reverse_order_with_their_cursor() {
+        let mut store = RecentFilesStore::default();
+        let root = Some(Path::new("/work/app"));
+        let closed = |path: &str, row, col| {
+            RecentFile::new(
+                PathBuf::from(path),
+                Some(helix_core::Position::new(row, col)),
+            )
+        };
+
+        store.record_opened(root, Path::new("/work/app/a.rs"));
+        store.record_closed(root, closed("/work/app/a.rs", 10, 4));
+        store.record_closed(root, closed("/work/app/b.rs", 2, 0));
+        store.record_closed(root, closed("/work/app/a.rs", 12, 1));
+
+        let reopened = store.pop_closed(root).unwrap();
+        assert_eq!(reopened.path, Path::new("/work/app/a.rs"));
+        assert_eq!(reopened.position(), helix_core::Position::new(12, 1));
+        assert_eq!(
+            store.pop_closed(root).unwrap().path,
+            Path::new("/work/app/b.rs")
+        );
+        assert_eq!(store.pop_closed(root), None);
+        assert_eq!(store.pop_closed(Some(Path::new("/elsewhere"))), None);
+
+        // Reopening keeps the cursor the file was closed at.
+        store.record_opened(root, Path::new("/work/app/a.rs"));
+        assert_eq!(store.recent(root)[0].line, 12);
+    }
+
+    #[test]
+    fn opening_a_file_removes_it_from_closed_tabs() {
+        let mut store = RecentFilesStore::default();
+        store.record_closed(None, RecentFile::new(PathBuf::from("/tmp/notes.md"), None));
+        store.record_opened(None, Path::new("/tmp/notes.md"));
+        assert_eq!(store.pop_closed(None), None);
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut store = RecentFilesStore::default();
+        for index in 0..MAX_RECENT_FILES + 5 {
+            let path = PathBuf::from(format!("/p/{index}.rs"));
+            store.record_closed(None, RecentFile::new(path, None));
+        }
+        let project = store.project(None).unwrap();
+        assert_eq!(project.opened.len(), MAX_RECENT_FILES);
+        assert_eq!(project.closed.len(), MAX_CLOSED_FILES);
+
+        for index in 0..MAX_PROJECTS {
+            let root = PathBuf::from(format!("/p{index}"));
+            store.record_opened(Some(&root), Path::new("/f"));
+        }
+        assert_eq!(store.projects.len(), MAX_PROJECTS);
+        assert!(store.project(None).is_none());
+    }
+
+    #[test]
+    fn closed_selections_reopen_clamped_to_a_shrunken_file() {
+        let state = DocumentViewState {
+            ranges: vec![
+                (Position::new(1, 0), Position::new(1, 3)),
+                (Position::new(40, 2), Position::new(42, 5)),
+            ],
+            primary_index: 1,
+            cursor: Position::new(42, 4),
+            top_line: 30,
+        };
+        let file = RecentFile::with_view_state(PathBuf::from("/p/a.rs"), Some(&state));
+        assert_eq!(file.position(), Position::new(42, 4));
+
+        let rope = helix_core::Rope::from(
+            "one
+two
+three
+",
+        );
+        let text = rope.slice(..);
+        let (selection, scroll) = file.restore_in(text);
+        let last_line = text.line_to_char(3);
+        assert_eq!(selection.ranges()[0], Range::new(4, 7));
+        assert_eq!(selection.primary(), Range::point(last_line));
+        assert_eq!(scroll, last_line);
+
+        // Entries saved before selections were kept reopen at the cursor.
+        let old = RecentFile::new(PathBuf::from("/p/a.rs"), Some(Position::new(1, 2)));
+        let (selection, scroll) = old.restore_in(text);
+        assert_eq!(selection, Selection::point(6));
+        assert_eq!(scroll, 0);
+    }
+
+    #[test]
+    fn store_round_trips_through_toml() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join(STORE_FILE_NAME);
+        let mut store = RecentFilesStore::default();
+        store.record_opened(Some(Path::new("/work/app")), Path::new("/work/app/a.rs"));
+        store.record_closed(
+            None,
+            RecentFile::new(
+                PathBuf::from("/tmp/b.md"),
+                Some(helix_core::Position::new(3, 7)),
+            ),
+        );
+        store.record_closed(
+            None,
+            RecentFile::with_view_state(
+                PathBuf::from("/tmp/c.md"),
+                Some(&DocumentViewState {
+                    ranges: vec![(Position::new(2, 0), Position::new(4, 1))],
+                    primary_index: 0,
+                    cursor: Position::new(4, 0),
+                    top_line: 1,
+                }),
+            ),
+        );
+
+        store.save(&path).unwrap();
+        assert_eq!(RecentFilesStore::load(&path).unwrap(), store);
+        assert_eq!(
+            RecentFilesStore::load(&temp.path().join("missing.toml")).unwrap(),
+            RecentFilesStore::default()
+        );
+    }
+}
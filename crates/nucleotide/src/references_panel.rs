@@ -0,0 +1,348 @@
+// ABOUTME: Find All References results grouped by file for the references panel
+// ABOUTME: Context lines around each reference, collapsible groups and the origin used to refresh
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use helix_core::{Position, Rope};
+use helix_lsp::util::lsp_pos_to_pos;
+
+use crate::types::LspLocation;
+
+/// Where a references request was made, so the panel can repeat it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferencesOrigin {
+    pub path: PathBuf,
+    /// Cursor of the request, in chars
+    pub position: Position,
+    /// Identifier under the cursor, for the panel title
+    pub symbol: String,
+}
+
+/// The answer to a references request shown in the panel.
+#[derive(Debug, Clone)]
+pub struct ReferencesResult {
+    pub origin: ReferencesOrigin,
+    pub locations: Vec<LspLocation>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReferenceEntry {
+    pub location: LspLocation,
+    /// Zero-based line of the reference
+    pub line: usize,
+    /// One-based column for display
+    pub column: usize,
+    /// The reference line and its context as `(zero-based line, text)`
+    pub lines: Vec<(usize, String)>,
+    /// Byte range of the reference within its line's text
+    pub highlight: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReferenceGroup {
+    pub path: PathBuf,
+    /// Path relative to the project root when it is inside it
+    pub label: String,
+    pub entries: Vec<ReferenceEntry>,
+    pub collapsed: bool,
+}
+
+/// State of the references panel.
+#[derive(Debug, Clone)]
+pub struct ReferencesPanel {
+    pub origin: ReferencesOrigin,
+    pub groups: Vec<ReferenceGroup>,
+    pub loading: bool,
+    pub error: Option<String>,
+}
+
+impl ReferencesPanel {
+    pub fn new(origin: ReferencesOrigin) -> Self {
+        Self {
+            origin,
+            groups: Vec::new(),
+            loading: true,
+            error: None,
+        }
+    }
+
+    /// Replace the results, keeping the files that were collapsed collapsed.
+    pub fn set_groups(&mut self, mut groups: Vec<ReferenceGroup>) {
+        let collapsed: HashSet<&Path> = self
+            .groups
+            .iter()
+            .filter(|group| group.collapsed)
+            .map(|group| group.path.as_path())
+            .collect();
+        for group in &mut groups {
+            group.collapsed = collapsed.contains(group.path.as_path());
+        }
+        self.groups = groups;
+        self.loading = false;
+    }
+
+    pub fn toggle_group(&mut self, index: usize) {
+        if let Some(group) = self.groups.get_mut(index) {
+            group.collapsed = !group.collapsed;
+        }
+    }
+
+    pub fn reference_count(&self) -> usize {
+        self.groups.iter().map(|group| group.entries.len()).sum()
+    }
+
+    /// `3 references in 2 files`
+    pub fn summary(&self) -> String {
+        let references = self.reference_count();
+        let files = self.groups.len();
+        format!(
+            "{references} reference{} in {files} file{}",
+            if references == 1 { "" } else { "s" },
+            if files == 1 { "" } else { "s" },
+        )
+    }
+}
+
+/// Files among `locations` whose text isn't in `texts` yet.
+pub fn missing_paths(locations: &[LspLocation], texts: &HashMap<PathBuf, Rope>) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    locations
+        .iter()
+        .map(|location| &location.path)
+        .filter(|path| !texts.contains_key(*path) && seen.insert(*path))
+        .cloned()
+        .collect()
+}
+
+/// Group `locations` by file, sorted by path and then position, with
+/// `context_lines` lines around each reference. Duplicates reported by
+/// several language servers are listed once.
+pub fn group_references(
+    locations: Vec<LspLocation>,
+    project_root: Option<&Path>,
+    texts: &HashMap<PathBuf, Rope>,
+    context_lines: usize,
+) -> Vec<ReferenceGroup> {
+    let mut by_path: BTreeMap<PathBuf, Vec<LspLocation>> = BTreeMap::new();
+    for location in locations {
+        let entries = by_path.entry(location.path.clone()).or_default();
+        if !entries
+            .iter()
+            .any(|existing| existing.range == location.range)
+        {
+            entries.push(location);
+        }
+    }
+
+    let mut groups: Vec<ReferenceGroup> = by_path
+        .into_iter()
+        .map(|(path, mut locations)| {
+            locations.sort_by_key(|location| {
+                (location.range.start.line, location.range.start.character)
+            });
+            let text = texts.get(&path);
+            let entries = locations
+                .into_iter()
+                .map(|location| reference_entry(location, text, context_lines))
+                .collect();
+            let label = project_root
+                .and_then(|root| path.strip_prefix(root).ok())
+                .unwrap_or(&path)
+                .display()
+                .to_string();
+            ReferenceGroup {
+                path,
+                label,
+                entries,
+                collapsed: false,
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| a.label.cmp(&b.label));
+    groups
+}
+
+fn reference_entry(
+    location: LspLocation,
+    text: Option<&Rope>,
+    context_lines: usize,
+) -> ReferenceEntry {
+    let line = location.range.start.line as usize;
+    let mut entry = ReferenceEntry {
+        line,
+        column: location.range.start.character as usize + 1,
+        lines: Vec::new(),
+        highlight: 0..0,
+        location,
+    };
+    let Some(text) = text.filter(|text| line < text.len_lines()) else {
+        return entry;
+    };
+
+    let line_start = text.line_to_char(line);
+    let line_text = line_text(text, line);
+    let line_chars = line_text.chars().count();
+    // Convert through the document so UTF-16 columns land on the right chars.
+    let to_column = |position| {
+        lsp_pos_to_pos(text, position, entry.location.offset_encoding)
+            .map(|pos| pos.saturating_sub(line_start).min(line_chars))
+    };
+    let start = to_column(entry.location.range.start).unwrap_or(0);
+    let end = if entry.location.range.end.line as usize == line {
+        to_column(entry.location.range.end).unwrap_or(start)
+    } else {
+        line_chars
+    };
+    entry.column = start + 1;
+    entry.highlight = char_to_byte(&line_text, start)..char_to_byte(&line_text, end.max(start));
+
+    let first = line.saturating_sub(context_lines);
+    let last = (line + context_lines).min(text.len_lines().saturating_sub(1));
+    entry.lines = (first..=last)
+        .map(|index| {
+            let text = if index == line {
+                line_text.clone()
+            } else {
+                self::line_text(text, index)
+            };
+            (index, text)
+        })
+        .collect();
+    entry
+}
+
+fn line_text(text: &Rope, line: usize) -> String {
+    text.line(line)
+        .to_string()
+        .trim_end_matches(['\n', '\r'])
+        .to_string()
+}
+
+fn char_to_byte(text: &str, chars: usize) -> usize {
+    text.char_indices()
+        .nth(chars)
+        .map_or(text.len(), |(index, _)| index)
+}
+
+/// The identifier at `pos` in `text`, or an empty string off one.
+pub fn symbol_at(text: &Rope, pos: usize) -> String {
+    let is_word = |ch: char| ch.is_alphanumeric() || ch == '_';
+    let pos = pos.min(text.len_chars());
+    let start = (0..pos)
+        .rev()
+        .take_while(|&index| is_word(text.char(index)))
+        .last()
+        .unwrap_or(pos);
+    let end = (pos..text.len_chars())
+        .take_while(|&index| is_word(text.char(index)))
+        .last()
+        .map_or(pos, |index| index + 1);
+    text.slice(start..end).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helix_lsp::{OffsetEncoding, lsp};
+
+    fn location(path: &str, line: u32, start: u32, end: u32) -> LspLocation {
+        LspLocation {
+            path: PathBuf::from(path),
+            range: lsp::Range::new(
+                lsp::Position::new(line, start),
+                lsp::Position::new(line, end),
+            ),
+            offset_encoding: OffsetEncoding::Utf16,
+        }
+    }
+
+    #[test]
+    fn references_are_grouped_by_file_with_context() {
+        let texts = HashMap::from([(
+            PathBuf::from("/p/src/b.rs"),
+            Rope::from("fn a() {}\nlet x = a();\nlet y = a();\n"),
+        )]);
+        let groups = group_references(
+            vec![
+                location("/p/src/b.rs", 2, 8, 9),
+                location("/p/src/b.rs", 1, 8, 9),
+                location("/p/src/b.rs", 1, 8, 9),
+                location("/p/a.rs", 0, 3, 4),
+            ],
+            Some(Path::new("/p")),
+            &texts,
+            1,
+        );
+
+        let labels: Vec<_> = groups.iter().map(|group| group.label.as_str()).collect();
+        assert_eq!(labels, vec!["a.rs", "src/b.rs"]);
+
+        let entries = &groups[1].entries;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].line, 1);
+        assert_eq!(
+            entries[0].lines,
+            vec![
+                (0, "fn a() {}".to_string()),
+                (1, "let x = a();".to_string()),
+                (2, "let y = a();".to_string()),
+            ]
+        );
+        assert_eq!(entries[0].highlight, 8..9);
+        assert_eq!(entries[1].lines.first().map(|line| line.0), Some(1));
+
+        // Files that couldn't be read still list their positions.
+        assert!(groups[0].entries[0].lines.is_empty());
+        assert_eq!(groups[0].entries[0].column, 4);
+    }
+
+    #[test]
+    fn utf16_columns_highlight_the_right_bytes() {
+        let texts = HashMap::from([(PathBuf::from("/p/a.rs"), Rope::from("\"😀\"; foo\n"))]);
+        // The emoji is two UTF-16 units, one char and four bytes.
+        let groups = group_references(vec![location("/p/a.rs", 0, 6, 9)], None, &texts, 0);
+        let entry = &groups[0].entries[0];
+        assert_eq!(entry.column, 6);
+        assert_eq!(&entry.lines[0].1[entry.highlight.clone()], "foo");
+    }
+
+    #[test]
+    fn refreshing_keeps_collapsed_files() {
+        let origin = ReferencesOrigin {
+            path: PathBuf::from("/p/a.rs"),
+            position: Position::new(0, 3),
+            symbol: "a".into(),
+        };
+        let texts = HashMap::new();
+        let locations = vec![location("/p/a.rs", 0, 3, 4), location("/p/b.rs", 4, 0, 1)];
+        let mut panel = ReferencesPanel::new(origin);
+        panel.set_groups(group_references(locations.clone(), None, &texts, 1));
+        panel.toggle_group(1);
+
+        panel.set_groups(group_references(locations, None, &texts, 1));
+        assert!(!panel.loading);
+        assert!(!panel.groups[0].collapsed);
+        assert!(panel.groups[1].collapsed);
+        assert_eq!(panel.summary(), "2 references in 2 files");
+        assert_eq!(
+            missing_paths(
+                &[location("/p/a.rs", 0, 0, 1), location("/p/a.rs", 1, 0, 1)],
+                &texts
+            ),
+            vec![PathBuf::from("/p/a.rs")]
+        );
+    }
+
+    #[test]
+    fn symbol_at_finds_the_identifier_around_the_cursor() {
+        let text = Rope::from("let foo_bar = baz(1);");
+        assert_eq!(symbol_at(&text, 4), "foo_bar");
+        assert_eq!(symbol_at(&text, 8), "foo_bar");
+        assert_eq!(symbol_at(&text, 11), "foo_bar");
+        assert_eq!(symbol_at(&text, 12), "");
+        assert_eq!(symbol_at(&text, 21), "");
+    }
+}
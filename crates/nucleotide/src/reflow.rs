@@ -0,0 +1,306 @@
+// ABOUTME: Hard-wraps prose and comments at a column, keeping comment prefixes and list markers
+// ABOUTME: Reflows the paragraph under each cursor, or the lines of each selection, in one transaction
+
+use helix_core::unicode::width::UnicodeWidthChar;
+use helix_core::{Rope, RopeSlice, Selection, Tendril, Transaction};
+
+/// Prompt command reflowing the focused document, with an optional column.
+pub const REFLOW_COMMAND: &str = "reflow-paragraph";
+/// Prompt commands for reflowing, with completion descriptions.
+pub const REFLOW_COMMANDS: &[(&str, &str)] = &[(
+    REFLOW_COMMAND,
+    "Reflow the paragraph or selection at the text width, or at the given column",
+)];
+
+/// Column given to a `reflow-paragraph [column]` command line. Returns `None`
+/// when the line is another command, and `Some(Ok(None))` when no column is
+/// given.
+pub fn parse_reflow_command(command: &str) -> Option<Result<Option<usize>, String>> {
+    let mut parts = command.trim().trim_start_matches(':').split_whitespace();
+    if parts.next() != Some(REFLOW_COMMAND) {
+        return None;
+    }
+    let column = match (parts.next(), parts.next()) {
+        (None, _) => Ok(None),
+        (Some(column), None) => match column.parse::<usize>() {
+            Ok(column) if column > 0 => Ok(Some(column)),
+            _ => Err(format!("Invalid reflow column: {column}")),
+        },
+        (Some(_), Some(_)) => Err(format!("Usage: {REFLOW_COMMAND} [column]")),
+    };
+    Some(column)
+}
+
+/// A line split into its prefix (indentation, comment tokens and block
+/// quote markers), list marker and remaining text.
+struct LineParts<'a> {
+    prefix: &'a str,
+    marker: &'a str,
+    body: &'a str,
+}
+
+fn split_line<'a>(line: &'a str, comment_tokens: &[&str]) -> LineParts<'a> {
+    let mut rest = line.trim_start_matches([' ', '\t']);
+    loop {
+        let token = comment_tokens
+            .iter()
+            .copied()
+            .chain(std::iter::once(">"))
+            .filter(|token| !token.is_empty() && rest.starts_with(token))
+            .max_by_key(|token| token.len());
+        let Some(token) = token else {
+            break;
+        };
+        rest = rest[token.len()..].trim_start_matches([' ', '\t']);
+    }
+    let prefix = &line[..line.len() - rest.len()];
+
+    let marker_len = list_marker_len(rest);
+    LineParts {
+        prefix,
+        marker: &rest[..marker_len],
+        body: &rest[marker_len..],
+    }
+}
+
+/// Length of the Markdown list marker starting `text`, such as `- ` or
+/// `12. `, including the spaces after it.
+fn list_marker_len(text: &str) -> usize {
+    let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    let symbol = match text.as_bytes() {
+        [b'-' | b'*' | b'+', ..] => 1,
+        _ if (1..=9).contains(&digits)
+            && matches!(text.as_bytes().get(digits), Some(b'.' | b')')) =>
+        {
+            digits + 1
+        }
+        _ => return 0,
+    };
+    let spaces = text[symbol..].len() - text[symbol..].trim_start_matches(' ').len();
+    if spaces == 0 { 0 } else { symbol + spaces }
+}
+
+/// Columns `text` takes up, with tabs advancing to the next multiple of
+/// `tab_width`.
+pub fn display_width(text: &str, tab_width: usize) -> usize {
+    text.chars().fold(0, |width, ch| match ch {
+        '\t' => (width / tab_width + 1) * tab_width,
+        _ => width + ch.width().unwrap_or(0),
+    })
+}
+
+/// Continuation prefix of a paragraph: the prefix with the list marker
+/// replaced by spaces.
+fn continuation_prefix(parts: &LineParts, tab_width: usize) -> String {
+    let marker_width = display_width(parts.marker, tab_width);
+    format!("{}{}", parts.prefix, " ".repeat(marker_width))
+}
+
+/// Fills `words` into lines at most `width` columns wide, starting the first
+/// line with `first` and later ones with `rest`. Words wider than the
+/// column get a line of their own.
+fn fill(
+    first: &str,
+    rest: &str,
+    words: &[&str],
+    width: usize,
+    tab_width: usize,
+    lines: &mut Vec<String>,
+) {
+    let mut line = first.to_string();
+    let mut line_width = display_width(first, tab_width);
+    let mut has_words = false;
+    for word in words {
+        let word_width = display_width(word, tab_width);
+        if has_words && line_width + 1 + word_width > width {
+            lines.push(std::mem::replace(&mut line, rest.to_string()));
+            line_width = display_width(rest, tab_width);
+            has_words = false;
+        }
+        if has_words {
+            line.push(' ');
+            line_width += 1;
+        }
+        line.push_str(word);
+        line_width += word_width;
+        has_words = true;
+    }
+    lines.push(line);
+}
+
+/// Reflows `lines` at `width` columns. Paragraphs end at blank lines, list
+/// items and changes of prefix; each keeps its first line's prefix and
+/// marker, and continues with the prefix indented past the marker.
+pub fn reflow_lines(
+    lines: &[&str],
+    width: usize,
+    tab_width: usize,
+    comment_tokens: &[&str],
+) -> Vec<String> {
+    let tab_width = tab_width.max(1);
+    let mut reflowed = Vec::with_capacity(lines.len());
+    // First line's prefix and marker, continuation prefix and words.
+    let mut paragraph: Option<(String, String, Vec<&str>)> = None;
+
+    for line in lines {
+        let parts = split_line(line, comment_tokens);
+        let continues = paragraph
+            .as_ref()
+            .is_some_and(|(_, rest, _)| parts.marker.is_empty() && parts.prefix == rest.as_str());
+        if parts.body.trim().is_empty() && parts.marker.is_empty() {
+            if let Some((first, rest, words)) = paragraph.take() {
+                fill(&first, &rest, &words, width, tab_width, &mut reflowed);
+            }
+            reflowed.push(parts.prefix.trim_end().to_string());
+        } else if continues {
+            if let Some((_, _, words)) = paragraph.as_mut() {
+                words.extend(parts.body.split_whitespace());
+            }
+        } else {
+            if let Some((first, rest, words)) = paragraph.take() {
+                fill(&first, &rest, &words, width, tab_width, &mut reflowed);
+            }
+            paragraph = Some((
+                format!("{}{}", parts.prefix, parts.marker),
+                continuation_prefix(&parts, tab_width),
+                parts.body.split_whitespace().collect(),
+            ));
+        }
+    }
+    if let Some((first, rest, words)) = paragraph {
+        fill(&first, &rest, &words, width, tab_width, &mut reflowed);
+    }
+    reflowed
+}
+
+fn line_text(text: RopeSlice, line: usize) -> String {
+    text.line(line)
+        .to_string()
+        .trim_end_matches(['\n', '\r'])
+        .to_string()
+}
+
+/// Lines of the paragraph around `line`: the neighbouring lines with text
+/// after their prefix. `None` when `line` itself is blank.
+fn paragraph_around(
+    text: RopeSlice,
+    line: usize,
+    comment_tokens: &[&str],
+) -> Option<(usize, usize)> {
+    let has_text = |line: usize| {
+        let line = line_text(text, line);
+        let parts = split_line(&line, comment_tokens);
+        !parts.marker.is_empty() || !parts.body.trim().is_empty()
+    };
+    if !has_text(line) {
+        return None;
+    }
+    let last = text.len_lines().saturating_sub(1);
+    let start = (0..line)
+        .rev()
+        .take_while(|&line| has_text(line))
+        .last()
+        .unwrap_or(line);
+    let end = (line + 1..=last)
+        .take_while(|&line| has_text(line))
+        .last()
+        .unwrap_or(line);
+    Some((start, end))
+}
+
+/// Reflows the paragraph under each cursor, and the lines covered by each
+/// selection, at `width` columns. Returns `None` when nothing changes.
+pub fn reflow_transaction(
+    text: &Rope,
+    selection: &Selection,
+    width: usize,
+    tab_width: usize,
+    comment_tokens: &[&str],
+    line_ending: &str,
+) -> Option<Transaction> {
+    let slice = text.slice(..);
+    let mut blocks: Vec<(usize, usize)> = Vec::new();
+    for range in selection.ranges() {
+        let block = if range.len() > 1 {
+            Some(range.line_range(slice))
+        } else {
+            paragraph_around(slice, range.cursor_line(slice), comment_tokens)
+        };
+        let Some((start, end)) = block else {
+            continue;
+        };
+        match blocks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => blocks.push((start, end)),
+        }
+    }
+
+    let mut changes = Vec::new();
+    for (start, end) in blocks {
+        let lines = (start..=end)
+            .map(|line| line_text(slice, line))
+            .collect::<Vec<_>>();
+        let lines = lines.iter().map(String::as_str).collect::<Vec<_>>();
+        let reflowed = reflow_lines(&lines, width, tab_width, comment_tokens).join(line_ending);
+        let from = slice.line_to_char(start);
+        let to = slice.line_to_char(end) + lines[lines.len() - 1].chars().count();
+        if slice.slice(from..to) != reflowed.as_str() {
+            changes.push((from, to, Some(Tendril::from(reflowed))));
+        }
+    }
+    (!changes.is_empty()).then(|| Transaction::change(text, changes.into_iter()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_and_list_items_keep_their_prefixes() {
+        let lines = [
+            "// A comment that runs on for a while",
+            "// and continues.",
+            "//",
+            "// - first item text here",
+            "// - second",
+        ];
+        assert_eq!(
+            reflow_lines(&lines, 18, 4, &["//", "///"]),
+            [
+                "// A comment that",
+                "// runs on for a",
+                "// while and",
+                "// continues.",
+                "//",
+                "// - first item",
+                "//   text here",
+                "// - second",
+            ]
+        );
+        assert_eq!(
+            reflow_lines(&["1. one two", "   three four"], 80, 4, &[]),
+            ["1. one two three four"]
+        );
+    }
+
+    #[test]
+    fn cursors_reflow_their_paragraph_only() {
+        let mut text = Rope::from("one two\nthree\n\nfour five six\n");
+        let transaction =
+            reflow_transaction(&text, &Selection::point(2), 80, 4, &[], "\n").unwrap();
+        assert!(transaction.apply(&mut text));
+        assert_eq!(text, "one two three\n\nfour five six\n");
+
+        assert!(reflow_transaction(&text, &Selection::point(14), 80, 4, &[], "\n").is_none());
+        assert_eq!(
+            parse_reflow_command(":reflow-paragraph 72"),
+            Some(Ok(Some(72)))
+        );
+        assert_eq!(parse_reflow_command("reflow-paragraph"), Some(Ok(None)));
+        assert!(matches!(
+            parse_reflow_command("reflow-paragraph 0"),
+            Some(Err(_))
+        ));
+        assert_eq!(parse_reflow_command("reflow"), None);
+    }
+}
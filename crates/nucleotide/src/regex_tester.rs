@@ -0,0 +1,686 @@
+// ABOUTME: Regex tester panel showing live matches, capture groups and a replacement preview
+// ABOUTME: Tests a pattern against typed sample text or a snapshot of the focused document
+
+use std::ops::Range;
+
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    App, AppContext as _, Context, Entity, EventEmitter, FocusHandle, Focusable, FontWeight,
+    InteractiveElement, IntoElement, ParentElement, Render, SharedString,
+    StatefulInteractiveElement, Styled, Window, div, px,
+};
+use nucleotide_ui::actions::text_input::SelectAll;
+use nucleotide_ui::{
+    Button, ButtonSize, ButtonVariant, StateView, TextInput, TextInputEvent, TextInputFocusStyle,
+    ThemedContext, Tooltipped,
+};
+use regex::{Regex, RegexBuilder};
+
+/// Command toggling the regex tester panel.
+pub const REGEX_TESTER_COMMAND: &str = "regex-tester";
+/// Prompt commands for the regex tester, with completion descriptions.
+pub const REGEX_TESTER_COMMANDS: &[(&str, &str)] =
+    &[(REGEX_TESTER_COMMAND, "Toggle the regex tester panel")];
+/// Matches listed by the tester; larger counts are shown as a lower bound.
+pub const MAX_TESTER_MATCHES: usize = 1_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegexFlags {
+    pub case_insensitive: bool,
+    pub multi_line: bool,
+    pub dot_matches_new_line: bool,
+    pub ignore_whitespace: bool,
+}
+
+pub fn compile(pattern: &str, flags: RegexFlags) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(flags.case_insensitive)
+        .multi_line(flags.multi_line)
+        .dot_matches_new_line(flags.dot_matches_new_line)
+        .ignore_whitespace(flags.ignore_whitespace)
+        .build()
+        .map_err(|err| err.to_string())
+}
+
+/// Sample text typed on one line, with `\n`, `\t` and `\\` escapes expanded.
+/// Other backslashes are kept as typed.
+pub fn unescape_sample(sample: &str) -> String {
+    let mut text = String::with_capacity(sample.len());
+    let mut chars = sample.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            text.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('t') => text.push('\t'),
+            Some('\\') => text.push('\\'),
+            Some(other) => {
+                text.push('\\');
+                text.push(other);
+            }
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureGroup {
+    /// Group name, or its index when unnamed
+    pub label: String,
+    /// Captured text; `None` when the group did not take part in the match
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TesterMatch {
+    /// Zero-based line the match starts on
+    pub line: usize,
+    pub line_text: String,
+    /// Byte range of the match within `line_text`, cut off at the line end
+    pub highlight: Range<usize>,
+    pub captures: Vec<CaptureGroup>,
+    /// The line with the match replaced, when a replacement is given
+    pub replaced_line: Option<String>,
+}
+
+/// Non-empty matches of `regex` in `text` with their capture groups, capped
+/// at [`MAX_TESTER_MATCHES`]. With a replacement, each match also carries
+/// its line as it reads after replacing, expanding `$1` and `${name}`.
+pub fn test_regex(text: &str, regex: &Regex, replacement: Option<&str>) -> Vec<TesterMatch> {
+    let line_starts = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+        .collect::<Vec<_>>();
+    let line_of = |byte: usize| line_starts.partition_point(|&start| start <= byte) - 1;
+    let line_text = |line: usize| {
+        let end = line_starts
+            .get(line + 1)
+            .map_or(text.len(), |next| next - 1);
+        (
+            line_starts[line],
+            text[line_starts[line]..end].trim_end_matches('\r'),
+        )
+    };
+    let names = regex.capture_names().collect::<Vec<_>>();
+
+    regex
+        .captures_iter(text)
+        .filter_map(|captures| {
+            let whole = captures.get(0)?;
+            if whole.is_empty() {
+                return None;
+            }
+            let line = line_of(whole.start());
+            let (start, content) = line_text(line);
+            let column = |byte: usize| (byte - start).min(content.len());
+            let highlight = column(whole.start())..column(whole.end());
+
+            let groups = names
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(index, name)| CaptureGroup {
+                    label: name.map_or_else(|| index.to_string(), str::to_string),
+                    text: captures.get(index).map(|group| group.as_str().to_string()),
+                })
+                .collect();
+            let replaced_line = replacement.map(|replacement| {
+                let mut replaced = content[..highlight.start].to_string();
+                captures.expand(replacement, &mut replaced);
+                let (end_start, end_content) = line_text(line_of(whole.end()));
+                let rest = (whole.end() - end_start).min(end_content.len());
+                replaced.push_str(&end_content[rest..]);
+                replaced
+            });
+
+            Some(TesterMatch {
+                line,
+                line_text: content.to_string(),
+                highlight,
+                captures: groups,
+                replaced_line,
+            })
+        })
+        .take(MAX_TESTER_MATCHES)
+        .collect()
+}
+
+/// `line` split into runs outside and inside `ranges`, which are sorted
+/// byte ranges that do not overlap. Empty runs are left out.
+pub fn highlight_runs<'a>(line: &'a str, ranges: &[Range<usize>]) -> Vec<(&'a str, bool)> {
+    let mut runs = Vec::new();
+    let mut offset = 0;
+    for range in ranges {
+        let start = range.start.clamp(offset, line.len());
+        let end = range.end.clamp(start, line.len());
+        runs.push((&line[offset..start], false));
+        runs.push((&line[start..end], true));
+        offset = end;
+    }
+    runs.push((&line[offset..], false));
+    runs.retain(|(run, _)| !run.is_empty());
+    runs
+}
+
+/// Label for the number of matches found.
+pub fn match_count_label(count: usize) -> String {
+    match count {
+        0 => "No matches".to_string(),
+        1 => "1 match".to_string(),
+        count if count >= MAX_TESTER_MATCHES => format!("{MAX_TESTER_MATCHES}+ matches"),
+        count => format!("{count} matches"),
+    }
+}
+
+/// Text the tester runs the pattern against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegexTesterSource {
+    Sample,
+    Document,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegexTesterEvent {
+    /// The tester wants a fresh snapshot of the focused document
+    DocumentRequested,
+    Dismissed,
+}
+
+pub struct RegexTester {
+    pattern_input: Entity<TextInput>,
+    replace_input: Entity<TextInput>,
+    sample_input: Entity<TextInput>,
+    flags: RegexFlags,
+    source: RegexTesterSource,
+    /// Name and text of the document snapshot tested against
+    document: Option<(String, String)>,
+    results: Result<Vec<TesterMatch>, String>,
+    /// The sample with every match replaced
+    replaced_sample: Option<String>,
+}
+
+impl RegexTester {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let input = |id: &'static str, placeholder: &'static str, cx: &mut Context<Self>| {
+            let input = cx.new(|cx| {
+                TextInput::new(id, cx)
+                    .size(nucleotide_ui::InputSize::Small)
+                    .focus_style(TextInputFocusStyle::Chrome)
+                    .placeholder(placeholder)
+            });
+            cx.subscribe(&input, Self::handle_input_event).detach();
+            input
+        };
+        Self {
+            pattern_input: input("regex-tester-pattern", "Pattern", cx),
+            replace_input: input("regex-tester-replace", "Replacement ($1, ${name})", cx),
+            sample_input: input("regex-tester-sample", "Sample text (\\n for new lines)", cx),
+            flags: RegexFlags::default(),
+            source: RegexTesterSource::Sample,
+            document: None,
+            results: Ok(Vec::new()),
+            replaced_sample: None,
+        }
+    }
+
+    pub fn source(&self) -> RegexTesterSource {
+        self.source
+    }
+
+    /// Focuses the pattern field with its text selected.
+    pub fn focus_pattern(&self, window: &mut Window, cx: &mut App) {
+        self.pattern_input.update(cx, |input, cx| {
+            input.select_all(&SelectAll, window, cx);
+        });
+        window.focus(&self.pattern_input.focus_handle(cx), cx);
+    }
+
+    /// Tests against `text`, a snapshot of the document called `name`.
+    pub fn set_document(&mut self, name: String, text: String, cx: &mut Context<Self>) {
+        self.document = Some((name, text));
+        self.refresh(cx);
+    }
+
+    fn set_source(&mut self, source: RegexTesterSource, cx: &mut Context<Self>) {
+        self.source = source;
+        if source == RegexTesterSource::Document {
+            cx.emit(RegexTesterEvent::DocumentRequested);
+        }
+        self.refresh(cx);
+    }
+
+    fn handle_input_event(
+        &mut self,
+        _input: Entity<TextInput>,
+        event: &TextInputEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            TextInputEvent::Changed(_) | TextInputEvent::Submitted(_) => self.refresh(cx),
+            TextInputEvent::Cancelled => cx.emit(RegexTesterEvent::Dismissed),
+        }
+    }
+
+    /// Re-runs the pattern after an input, flag or source change.
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let pattern = self.pattern_input.read(cx).value();
+        let replacement = self.replace_input.read(cx).value();
+        let replacement: Option<&str> = (!replacement.is_empty()).then_some(&replacement);
+        let sample = unescape_sample(&self.sample_input.read(cx).value());
+        let text = match self.source {
+            RegexTesterSource::Sample => sample.as_str(),
+            RegexTesterSource::Document => {
+                self.document.as_ref().map_or("", |(_, text)| text.as_str())
+            }
+        };
+
+        self.replaced_sample = None;
+        self.results = if pattern.is_empty() {
+            Ok(Vec::new())
+        } else {
+            compile(&pattern, self.flags).map(|regex| {
+                if let (RegexTesterSource::Sample, Some(replacement)) = (self.source, replacement) {
+                    self.replaced_sample = Some(regex.replace_all(text, replacement).into_owned());
+                }
+                test_regex(text, &regex, replacement)
+            })
+        };
+        cx.notify();
+    }
+
+    fn render_flag(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        tooltip: &'static str,
+        active: bool,
+        toggle: fn(&mut RegexFlags),
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        Button::new(id, label)
+            .variant(if active {
+                ButtonVariant::Secondary
+            } else {
+                ButtonVariant::Ghost
+            })
+            .size(ButtonSize::ExtraSmall)
+            .tooltip(tooltip)
+            .aria_label(tooltip)
+            .activate_on_mouse_down()
+            .on_click(cx.listener(move |this, _event, _window, cx| {
+                toggle(&mut this.flags);
+                this.refresh(cx);
+                cx.stop_propagation();
+            }))
+    }
+
+    fn render_source_tab(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        source: RegexTesterSource,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        Button::new(id, label)
+            .variant(if self.source == source {
+                ButtonVariant::Secondary
+            } else {
+                ButtonVariant::Ghost
+            })
+            .size(ButtonSize::ExtraSmall)
+            .activate_on_mouse_down()
+            .on_click(cx.listener(move |this, _event, _window, cx| {
+                this.set_source(source, cx);
+                cx.stop_propagation();
+            }))
+    }
+}
+
+impl Focusable for RegexTester {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.pattern_input.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<RegexTesterEvent> for RegexTester {}
+
+impl Render for RegexTester {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let tokens = cx.theme().tokens;
+        let font_family =
+            SharedString::from(cx.global::<crate::types::EditorFontConfig>().family.clone());
+        let secondary = tokens.chrome.text_chrome_secondary;
+        let highlight_background = tokens.editor.selection_secondary;
+        let flags = self.flags;
+
+        let runs_row =
+            |line: &str, ranges: &[Range<usize>]| {
+                div().flex().whitespace_nowrap().children(
+                    highlight_runs(line, ranges)
+                        .into_iter()
+                        .map(|(run, highlighted)| {
+                            div()
+                                .when(highlighted, |cell| {
+                                    cell.bg(highlight_background)
+                                        .rounded(tokens.sizes.radius_sm)
+                                })
+                                .child(run.to_string())
+                        }),
+                )
+            };
+        let heading = |label: String| {
+            div()
+                .pt(tokens.sizes.space_2)
+                .pb(tokens.sizes.space_1)
+                .text_xs()
+                .font_weight(FontWeight::MEDIUM)
+                .text_color(secondary)
+                .child(label)
+        };
+
+        let (status, status_color) = match &self.results {
+            Err(error) => (format!("Invalid regex: {error}"), tokens.editor.error),
+            Ok(_) if self.pattern_input.read(cx).value().is_empty() => (String::new(), secondary),
+            Ok(matches) => (match_count_label(matches.len()), secondary),
+        };
+
+        let mut body = div()
+            .id("regex-tester-results")
+            .flex()
+            .flex_col()
+            .size_full()
+            .min_h(px(0.0))
+            .overflow_y_scroll()
+            .px(tokens.sizes.space_2)
+            .pb(tokens.sizes.space_2)
+            .font_family(font_family)
+            .text_xs();
+
+        let matches = self.results.as_deref().unwrap_or_default();
+        match self.source {
+            RegexTesterSource::Sample => {
+                let sample = unescape_sample(&self.sample_input.read(cx).value());
+                if !sample.is_empty() {
+                    body = body.child(heading("Sample".to_string()));
+                    for (index, line) in sample.lines().enumerate() {
+                        let ranges = matches
+                            .iter()
+                            .filter(|found| found.line == index)
+                            .map(|found| found.highlight.clone())
+                            .collect::<Vec<_>>();
+                        body = body.child(runs_row(line, &ranges));
+                    }
+                }
+                if let Some(replaced) = &self.replaced_sample {
+                    body = body.child(heading("Replacement preview".to_string()));
+                    for line in replaced.lines() {
+                        body = body.child(div().whitespace_nowrap().child(line.to_string()));
+                    }
+                }
+            }
+            RegexTesterSource::Document if self.document.is_none() => {
+                body = body.child(
+                    StateView::new("regex-tester-no-document", "No document")
+                        .detail("Focus a document to test the pattern against it")
+                        .compact(true),
+                );
+            }
+            RegexTesterSource::Document => {}
+        }
+
+        if !matches.is_empty() {
+            body = body.child(heading("Matches".to_string()));
+        }
+        for (index, found) in matches.iter().enumerate() {
+            body = body.child(
+                div()
+                    .id(("regex-tester-match", index))
+                    .flex()
+                    .flex_col()
+                    .py(tokens.sizes.space_1)
+                    .border_b_1()
+                    .border_color(tokens.chrome.border_default)
+                    .child(
+                        div()
+                            .flex()
+                            .gap(tokens.sizes.space_2)
+                            .child(
+                                div()
+                                    .w(px(36.0))
+                                    .flex_shrink_0()
+                                    .flex()
+                                    .justify_end()
+                                    .text_color(secondary)
+                                    .child((found.line + 1).to_string()),
+                            )
+                            .child(runs_row(
+                                &found.line_text,
+                                std::slice::from_ref(&found.highlight),
+                            )),
+                    )
+                    .children(found.captures.iter().map(|group| {
+                        div()
+                            .pl(px(44.0))
+                            .whitespace_nowrap()
+                            .text_color(secondary)
+                            .child(match &group.text {
+                                Some(text) => format!("{}: {text}", group.label),
+                                None => format!("{}: (no match)", group.label),
+                            })
+                    }))
+                    .when_some(found.replaced_line.clone(), |row, replaced| {
+                        row.child(
+                            div()
+                                .pl(px(44.0))
+                                .whitespace_nowrap()
+                                .child(format!("→ {replaced}")),
+                        )
+                    }),
+            );
+        }
+
+        let document_label = self
+            .document
+            .as_ref()
+            .map(|(name, _)| name.clone())
+            .unwrap_or_default();
+
+        div()
+            .id("regex-tester")
+            .size_full()
+            .min_h(px(0.0))
+            .flex()
+            .flex_col()
+            .overflow_hidden()
+            .text_size(tokens.sizes.text_sm)
+            .text_color(tokens.chrome.text_on_chrome)
+            .child(
+                div()
+                    .h(tokens.sizes.space_8)
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .gap(tokens.sizes.space_2)
+                    .px(tokens.sizes.space_3)
+                    .border_b_1()
+                    .border_color(tokens.chrome.border_default)
+                    .child(div().font_weight(FontWeight::MEDIUM).child("Regex Tester"))
+                    .child(
+                        Button::icon_only("regex-tester-close", "icons/close.svg")
+                            .variant(ButtonVariant::Ghost)
+                            .size(ButtonSize::ExtraSmall)
+                            .tooltip("Close (Escape)")
+                            .aria_label("Close (Escape)")
+                            .activate_on_mouse_down()
+                            .on_click(cx.listener(|_this, _event, _window, cx| {
+                                cx.emit(RegexTesterEvent::Dismissed);
+                                cx.stop_propagation();
+                            })),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(tokens.sizes.space_1)
+                    .p(tokens.sizes.space_2)
+                    .border_b_1()
+                    .border_color(tokens.chrome.border_default)
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(tokens.sizes.space_1)
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .min_w(px(0.0))
+                                    .child(self.pattern_input.clone()),
+                            )
+                            .child(self.render_flag(
+                                "regex-tester-case",
+                                "i",
+                                "Case Insensitive",
+                                flags.case_insensitive,
+                                |flags| flags.case_insensitive ^= true,
+                                cx,
+                            ))
+                            .child(self.render_flag(
+                                "regex-tester-multi-line",
+                                "m",
+                                "Multi-line: ^ and $ match at line breaks",
+                                flags.multi_line,
+                                |flags| flags.multi_line ^= true,
+                                cx,
+                            ))
+                            .child(self.render_flag(
+                                "regex-tester-dot-all",
+                                "s",
+                                "Dot matches new lines",
+                                flags.dot_matches_new_line,
+                                |flags| flags.dot_matches_new_line ^= true,
+                                cx,
+                            ))
+                            .child(self.render_flag(
+                                "regex-tester-extended",
+                                "x",
+                                "Ignore whitespace and # comments",
+                                flags.ignore_whitespace,
+                                |flags| flags.ignore_whitespace ^= true,
+                                cx,
+                            )),
+                    )
+                    .child(self.replace_input.clone())
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(tokens.sizes.space_1)
+                            .child(self.render_source_tab(
+                                "regex-tester-source-sample",
+                                "Sample",
+                                RegexTesterSource::Sample,
+                                cx,
+                            ))
+                            .child(self.render_source_tab(
+                                "regex-tester-source-document",
+                                "Document",
+                                RegexTesterSource::Document,
+                                cx,
+                            ))
+                            .when(self.source == RegexTesterSource::Document, |row| {
+                                row.child(
+                                    div()
+                                        .flex_1()
+                                        .min_w(px(0.0))
+                                        .overflow_hidden()
+                                        .text_ellipsis()
+                                        .whitespace_nowrap()
+                                        .text_xs()
+                                        .text_color(secondary)
+                                        .child(document_label),
+                                )
+                                .child(
+                                    Button::icon_only(
+                                        "regex-tester-reload",
+                                        "icons/rotate-ccw.svg",
+                                    )
+                                    .variant(ButtonVariant::Ghost)
+                                    .size(ButtonSize::ExtraSmall)
+                                    .tooltip("Reload the focused document")
+                                    .aria_label("Reload the focused document")
+                                    .activate_on_mouse_down()
+                                    .on_click(cx.listener(
+                                        |_this, _event, _window, cx| {
+                                            cx.emit(RegexTesterEvent::DocumentRequested);
+                                            cx.stop_propagation();
+                                        },
+                                    )),
+                                )
+                            }),
+                    )
+                    .when(self.source == RegexTesterSource::Sample, |column| {
+                        column.child(self.sample_input.clone())
+                    })
+                    .when(!status.is_empty(), |column| {
+                        column.child(div().text_xs().text_color(status_color).child(status))
+                    }),
+            )
+            .child(body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_carry_captures_and_replaced_lines() {
+        let regex = compile(r"(?P<key>\w+)=(\d+)?", RegexFlags::default()).unwrap();
+        let matches = test_regex("a=1 b=\r\nc=3", &regex, Some("$2:${key}"));
+
+        assert_eq!(matches.len(), 3);
+        assert_eq!(matches[0].line_text, "a=1 b=");
+        assert_eq!(matches[0].highlight, 0..3);
+        assert_eq!(
+            matches[0].captures,
+            [
+                CaptureGroup {
+                    label: "key".to_string(),
+                    text: Some("a".to_string()),
+                },
+                CaptureGroup {
+                    label: "2".to_string(),
+                    text: Some("1".to_string()),
+                },
+            ]
+        );
+        assert_eq!(matches[0].replaced_line.as_deref(), Some("1:a b="));
+        assert_eq!(matches[1].captures[1].text, None);
+        assert_eq!(matches[1].replaced_line.as_deref(), Some("a=1 :b"));
+        assert_eq!((matches[2].line, matches[2].highlight.clone()), (1, 0..3));
+
+        let insensitive = RegexFlags {
+            case_insensitive: true,
+            ..RegexFlags::default()
+        };
+        assert_eq!(
+            test_regex("ABC", &compile("b", insensitive).unwrap(), None).len(),
+            1
+        );
+        assert!(compile("(", RegexFlags::default()).is_err());
+    }
+
+    #[test]
+    fn samples_and_highlights_are_split_for_display() {
+        assert_eq!(unescape_sample(r"a\nb\tc\\n\d"), "a\nb\tc\\n\\d");
+        assert_eq!(
+            highlight_runs("let x = 1", &[0..3, 8..9]),
+            [("let", true), (" x = ", false), ("1", true)]
+        );
+        assert_eq!(match_count_label(1), "1 match");
+        assert_eq!(match_count_label(MAX_TESTER_MATCHES), "1000+ matches");
+    }
+}
@@ -0,0 +1,140 @@
+// ABOUTME: Send-to-REPL commands: per-language REPL programs and the bytes sent to them
+// ABOUTME: Code is bracket-pasted so multi-line blocks reach the REPL as one input
+
+use crate::config::ReplCommandConfig;
+use nucleotide_events::v2::terminal::TerminalId;
+use nucleotide_terminal::session::SessionInput;
+
+/// Prompt commands for the REPL workflow, with completion descriptions.
+pub const REPL_COMMANDS: &[(&str, &str)] = &[
+    (
+        "repl-start",
+        "Start a REPL for the focused document's language",
+    ),
+    ("repl-send-line", "Send the cursor line to the REPL"),
+    (
+        "repl-send-selection",
+        "Send the primary selection to the REPL",
+    ),
+    ("repl-send-buffer", "Send the whole buffer to the REPL"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplAction {
+    Start,
+    SendLine,
+    SendSelection,
+    SendBuffer,
+}
+
+impl ReplAction {
+    pub fn from_command(command: &str) -> Option<Self> {
+        match command.trim().trim_start_matches(':') {
+            "repl-start" | "repl" => Some(Self::Start),
+            "repl-send-line" => Some(Self::SendLine),
+            "repl-send-selection" => Some(Self::SendSelection),
+            "repl-send-buffer" => Some(Self::SendBuffer),
+            _ => None,
+        }
+    }
+}
+
+/// A REPL running in the terminal panel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplSession {
+    pub terminal: TerminalId,
+    pub language: String,
+    pub bracketed_paste: bool,
+}
+
+/// REPL used for `language` when `[repl]` does not configure one.
+pub fn default_repl_command(language: &str) -> Option<ReplCommandConfig> {
+    let command = match language {
+        "python" if cfg!(windows) => "python",
+        "python" => "python3",
+        "javascript" | "typescript" => "node",
+        "ruby" => "irb",
+        _ => return None,
+    };
+    Some(ReplCommandConfig {
+        command: command.to_string(),
+        args: Vec::new(),
+        bracketed_paste: true,
+    })
+}
+
+/// Input that enters `code` into a REPL as if it were pasted and submitted.
+///
+/// The code goes through the terminal's paste encoding. An extra return
+/// closes an indented block, which Python's REPL needs before it runs one.
+pub fn repl_input(code: &str, bracketed_paste: bool) -> Vec<SessionInput> {
+    let code = code.replace("\r\n", "\n");
+    let code = code.trim_end_matches('\n');
+    let ends_indented_block = code.contains('\n')
+        && code
+            .lines()
+            .last()
+            .is_some_and(|line| line.starts_with([' ', '\t']));
+    let submit = if ends_indented_block { "\r\r" } else { "\r" };
+
+    vec![
+        SessionInput::Paste {
+            text: code.to_string(),
+            bracketed: bracketed_paste,
+        },
+        SessionInput::Bytes(submit.as_bytes().to_vec()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repl_commands_parse_with_optional_colon() {
+        assert_eq!(
+            ReplAction::from_command(":repl-start"),
+            Some(ReplAction::Start)
+        );
+        assert_eq!(
+            ReplAction::from_command(" repl-send-selection "),
+            Some(ReplAction::SendSelection)
+        );
+        assert_eq!(ReplAction::from_command("repl-send"), None);
+        for (name, _) in REPL_COMMANDS {
+            assert!(ReplAction::from_command(name).is_some(), "{name}");
+        }
+    }
+
+    #[test]
+    fn repl_input_pastes_code_and_submits_it() {
+        let paste = |text: &str, bracketed| SessionInput::Paste {
+            text: text.to_string(),
+            bracketed,
+        };
+        let submit = |bytes: &[u8]| SessionInput::Bytes(bytes.to_vec());
+
+        assert_eq!(
+            repl_input("print(1)\n", true),
+            vec![paste("print(1)", true), submit(b"\r")]
+        );
+        assert_eq!(
+            repl_input("1 + 1", false),
+            vec![paste("1 + 1", false), submit(b"\r")]
+        );
+        assert_eq!(
+            repl_input("for x in y:\r\n    print(x)\n", true),
+            vec![paste("for x in y:\n    print(x)", true), submit(b"\r\r")]
+        );
+    }
+
+    #[test]
+    fn builtin_repls_cover_common_languages() {
+        assert_eq!(
+            default_repl_command("ruby").map(|repl| repl.command),
+            Some("irb".to_string())
+        );
+        assert!(default_repl_command("typescript").is_some());
+        assert!(default_repl_command("rust").is_none());
+    }
+}
@@ -0,0 +1,428 @@
+// ABOUTME: Named run configurations from the workspace settings and the order they launch in
+// ABOUTME: Pre-launch chains, the last used configuration per project and problems parsed from run output
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result};
+use gpui::{App, Global};
+use helix_lsp::{OffsetEncoding, lsp};
+use nucleotide_events::v2::run::{CommandSpec, ResolvedTask, RunKind, TaskTemplate};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::RunConfiguration;
+use crate::types::LspLocation;
+
+/// Prompt command opening the run configuration picker.
+pub const RUN_CONFIGURATION_COMMAND: &str = "run-configuration";
+/// Prompt command reopening the problems found in the last run's output.
+pub const RUN_PROBLEMS_COMMAND: &str = "run-problems";
+/// Prompt commands for run configurations, with completion descriptions.
+pub const RUN_CONFIGURATION_COMMANDS: &[(&str, &str)] = &[
+    (
+        RUN_CONFIGURATION_COMMAND,
+        "Choose a run configuration and run it",
+    ),
+    (
+        RUN_PROBLEMS_COMMAND,
+        "List the file positions printed by the last run",
+    ),
+];
+const TAG_RUN_CONFIGURATION: &str = "run-configuration";
+const STORE_FILE_NAME: &str = "run-configurations.toml";
+/// Projects remembered; the least recently used is dropped first.
+const MAX_PROJECTS: usize = 20;
+const MAX_PROBLEMS: usize = 500;
+
+/// `path:line` or `path:line:column`, as printed by most compilers, linters
+/// and test runners.
+static PROBLEM_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:^|[\s(\[])(?P<path>(?:[A-Za-z]:)?[^\s:()\[\]]*[^\s:()\[\]/\\.][.][A-Za-z][A-Za-z0-9]*):(?P<line>\d+)(?::(?P<column>\d+))?",
+    )
+    .expect("valid problem location regex")
+});
+
+/// A run configuration picked in the Run menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunConfigurationChoice {
+    pub name: String,
+}
+
+/// The task that runs `config`, in its working directory under
+/// `project_root`.
+pub fn resolve_configuration(
+    config: &RunConfiguration,
+    project_root: Option<&Path>,
+) -> ResolvedTask {
+    let cwd = match (project_root, config.cwd.as_deref()) {
+        (Some(root), Some(cwd)) => Some(root.join(cwd)),
+        (Some(root), None) => Some(root.to_path_buf()),
+        (None, cwd) => cwd.map(Path::to_path_buf),
+    };
+    let mut command = CommandSpec::new(config.command.clone())
+        .with_args(config.args.iter().cloned())
+        .with_env(config.env.clone());
+    command.cwd = cwd;
+
+    ResolvedTask {
+        template: TaskTemplate {
+            label: config.name.clone(),
+            kind: RunKind::Run,
+            command: command.clone(),
+            source: None,
+            tags: vec![TAG_RUN_CONFIGURATION.to_string()],
+            description: None,
+        },
+        command,
+    }
+}
+
+/// Tasks to run for the configuration `name`: its pre-launch chain first,
+/// each one starting after the one before it succeeds.
+pub fn launch_sequence(
+    configs: &[RunConfiguration],
+    name: &str,
+    project_root: Option<&Path>,
+) -> Result<Vec<ResolvedTask>, String> {
+    let mut chain: Vec<&RunConfiguration> = Vec::new();
+    let mut next = Some(name);
+    while let Some(name) = next {
+        let config = configs
+            .iter()
+            .find(|config| config.name == name)
+            .ok_or_else(|| format!("Unknown run configuration \"{name}\""))?;
+        if chain.iter().any(|seen| seen.name == config.name) {
+            let cycle = chain
+                .iter()
+                .map(|config| config.name.as_str())
+                .chain(std::iter::once(name))
+                .collect::<Vec<_>>()
+                .join(" → ");
+            return Err(format!("Pre-launch cycle: {cycle}"));
+        }
+        chain.push(config);
+        next = config.pre_launch.as_deref();
+    }
+
+    Ok(chain
+        .into_iter()
+        .rev()
+        .map(|config| resolve_configuration(config, project_root))
+        .collect())
+}
+
+/// A file position reported in run output.
+#[derive(Debug, Clone)]
+pub struct RunProblem {
+    pub location: LspLocation,
+    /// One-based line and column for display
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// File positions in `output`, resolved against `cwd`. The message is the
+/// text after the position, else the text before it, else the last line of
+/// text above it for compilers that print the position under the message,
+/// like rustc's `-->` lines.
+pub fn parse_problems(output: &str, cwd: &Path) -> Vec<RunProblem> {
+    let has_text = |text: &str| text.chars().any(char::is_alphanumeric);
+    let mut problems: Vec<RunProblem> = Vec::new();
+    let mut previous_line = "";
+
+    for line in output.lines() {
+        let Some(captures) = PROBLEM_RE.captures(line) else {
+            if has_text(line) {
+                previous_line = line.trim();
+            }
+            continue;
+        };
+        let path = captures["path"].trim_start_matches("./");
+        let Ok(line_number) = captures["line"].parse::<usize>() else {
+            continue;
+        };
+        let column = captures
+            .name("column")
+            .and_then(|column| column.as_str().parse::<usize>().ok())
+            .unwrap_or(1);
+        if line_number == 0 {
+            continue;
+        }
+
+        let path = cwd.join(path);
+        let position =
+            lsp::Position::new((line_number - 1) as u32, column.saturating_sub(1) as u32);
+        let duplicate = problems.iter().any(|problem| {
+            problem.location.path == path && problem.location.range.start == position
+        });
+        if !duplicate {
+            let found = captures.get(0).expect("whole match");
+            let after = line[found.end()..].trim_start_matches(':').trim();
+            let before = line[..found.start()].trim();
+            let message = [after, before]
+                .into_iter()
+                .find(|text| has_text(text))
+                .unwrap_or(previous_line);
+            problems.push(RunProblem {
+                location: LspLocation {
+                    path,
+                    range: lsp::Range::new(position, position),
+                    offset_encoding: OffsetEncoding::Utf32,
+                },
+                line: line_number,
+                column,
+                message: message.to_string(),
+            });
+            if problems.len() == MAX_PROBLEMS {
+                break;
+            }
+        }
+    }
+    problems
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastRunConfiguration {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<PathBuf>,
+    pub name: String,
+}
+
+/// The run configuration last used in each project.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastRunConfigurationStore {
+    /// Most recently used project first
+    #[serde(default)]
+    pub projects: Vec<LastRunConfiguration>,
+}
+
+impl LastRunConfigurationStore {
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .with_context(|| format!("failed to parse {}", path.display())),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error).with_context(|| format!("failed to read {}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        let contents =
+            toml::to_string_pretty(self).context("failed to encode run configurations")?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    pub fn last(&self, root: Option<&Path>) -> Option<&str> {
+        self.projects
+            .iter()
+            .find(|project| project.root.as_deref() == root)
+            .map(|project| project.name.as_str())
+    }
+
+    pub fn record(&mut self, root: Option<&Path>, name: &str) {
+        self.projects
+            .retain(|project| project.root.as_deref() != root);
+        self.projects.insert(
+            0,
+            LastRunConfiguration {
+                root: root.map(Path::to_path_buf),
+                name: name.to_string(),
+            },
+        );
+        self.projects.truncate(MAX_PROJECTS);
+    }
+}
+
+/// The last used run configurations for this session, installed as a GPUI
+/// global.
+#[derive(Debug)]
+pub struct LastRunConfigurations {
+    store: LastRunConfigurationStore,
+    path: PathBuf,
+}
+
+impl Global for LastRunConfigurations {}
+
+impl LastRunConfigurations {
+    pub fn load_default() -> Self {
+        let path = default_store_path();
+        let store = LastRunConfigurationStore::load(&path).unwrap_or_else(|error| {
+            nucleotide_logging::warn!(error = %error, "Ignoring unreadable run configuration history");
+            LastRunConfigurationStore::default()
+        });
+        Self { store, path }
+    }
+
+    pub fn record(&mut self, root: Option<&Path>, name: &str) {
+        if self.store.last(root) == Some(name) {
+            return;
+        }
+        self.store.record(root, name);
+        if let Err(error) = self.store.save(&self.path) {
+            nucleotide_logging::warn!(error = %error, "Failed to save run configuration history");
+        }
+    }
+
+    pub fn last(&self, root: Option<&Path>) -> Option<&str> {
+        self.store.last(root)
+    }
+}
+
+/// The configuration among `configs` last used in `root`, or the first one.
+pub fn default_run_configuration<'a>(
+    configs: &'a [RunConfiguration],
+    root: Option<&Path>,
+    cx: &App,
+) -> Option<&'a RunConfiguration> {
+    let last = cx
+        .try_global::<LastRunConfigurations>()
+        .and_then(|store| store.last(root));
+    last.and_then(|name| configs.iter().find(|config| config.name == name))
+        .or_else(|| configs.first())
+}
+
+pub fn default_store_path() -> PathBuf {
+    helix_loader::config_dir().join(STORE_FILE_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn config(name: &str, pre_launch: Option<&str>) -> RunConfiguration {
+        RunConfiguration {
+            name: name.to_string(),
+            command: "cargo".to_string(),
+            args: vec![name.to_string()],
+            env: BTreeMap::new(),
+            cwd: None,
+            pre_launch: pre_launch.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn configurations_resolve_relative_to_the_project_root() {
+        let mut serve = config("serve", None);
+        serve.cwd = Some(PathBuf::from("crates/server"));
+        serve.env.insert("RUST_LOG".into(), "debug".into());
+
+        let task = resolve_configuration(&serve, Some(Path::new("/work/app")));
+        assert_eq!(task.label(), "serve");
+        assert_eq!(
+            task.command.cwd.as_deref(),
+            Some(Path::new("/work/app/crates/server"))
+        );
+        assert_eq!(task.command.args, vec!["serve"]);
+        assert_eq!(
+            task.command.env,
+            vec![("RUST_LOG".to_string(), "debug".to_string())]
+        );
+
+        let task = resolve_configuration(&config("build", None), Some(Path::new("/work/app")));
+        assert_eq!(task.command.cwd.as_deref(), Some(Path::new("/work/app")));
+    }
+
+    #[test]
+    fn pre_launch_chains_run_first_and_cycles_are_rejected() {
+        let configs = vec![
+            config("deploy", Some("test")),
+            config("test", Some("build")),
+            config("build", None),
+            config("loop-a", Some("loop-b")),
+            config("loop-b", Some("loop-a")),
+            config("broken", Some("missing")),
+        ];
+
+        let labels = |name| {
+            launch_sequence(&configs, name, None)
+                .map(|tasks| tasks.iter().map(|task| task.label().to_string()).collect())
+        };
+        assert_eq!(
+            labels("deploy"),
+            Ok(vec!["build".into(), "test".into(), "deploy".into()])
+        );
+        assert_eq!(labels("build"), Ok(vec!["build".to_string()]));
+        assert_eq!(
+            labels("loop-a"),
+            Err("Pre-launch cycle: loop-a → loop-b → loop-a".to_string())
+        );
+        assert_eq!(
+            labels("broken"),
+            Err("Unknown run configuration \"missing\"".to_string())
+        );
+    }
+
+    #[test]
+    fn problems_are_parsed_from_compiler_and_test_output() {
+        let output = "\
+   Compiling app v0.1.0
+error[E0425]: cannot find value `x` in this scope
+ --> src/main.rs:2:5
+  |
+src/lib.c:10:3: warning: unused variable
+thread 'main' panicked at ./src/util.rs:41:9:
+called `Option::unwrap()` on a `None` value
+ --> src/main.rs:2:5
+see http://example.com:80 and v1.2:3
+";
+        let problems = parse_problems(output, Path::new("/work/app"));
+        let found: Vec<_> = problems
+            .iter()
+            .map(|problem| {
+                (
+                    problem.location.path.to_str().unwrap(),
+                    problem.line,
+                    problem.column,
+                    problem.message.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "/work/app/src/main.rs",
+                    2,
+                    5,
+                    "error[E0425]: cannot find value `x` in this scope"
+                ),
+                ("/work/app/src/lib.c", 10, 3, "warning: unused variable"),
+                ("/work/app/src/util.rs", 41, 9, "thread 'main' panicked at"),
+            ]
+        );
+        assert_eq!(problems[0].location.range.start, lsp::Position::new(1, 4));
+        assert_eq!(problems[0].location.offset_encoding, OffsetEncoding::Utf32);
+    }
+
+    #[test]
+    fn last_used_configuration_is_remembered_per_project() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join(STORE_FILE_NAME);
+        let app = Some(Path::new("/work/app"));
+
+        let mut store = LastRunConfigurationStore::default();
+        store.record(app, "serve");
+        store.record(None, "script");
+        store.record(app, "test");
+        assert_eq!(store.last(app), Some("test"));
+        assert_eq!(store.last(None), Some("script"));
+        assert_eq!(store.last(Some(Path::new("/elsewhere"))), None);
+        assert_eq!(store.projects.len(), 2);
+
+        store.save(&path).unwrap();
+        assert_eq!(LastRunConfigurationStore::load(&path).unwrap(), store);
+        assert_eq!(
+            LastRunConfigurationStore::load(&temp.path().join("missing.toml")).unwrap(),
+            LastRunConfigurationStore::default()
+        );
+    }
+}
@@ -292,6 +292,7 @@ pub fn runnable_to_task_template(runnable: RaRunnable) -> ResolvedTask {
         command: command.clone(),
         source,
         tags: vec![TAG_RUST_ANALYZER.to_string()],
+        description: None,
     };
 
     ResolvedTask { template, command }
@@ -376,6 +377,7 @@ fn cargo_task(
         command: command.clone(),
         source,
         tags: tags.into_iter().collect(),
+        description: None,
     };
 
     ResolvedTask { template, command }
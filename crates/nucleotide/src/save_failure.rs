@@ -0,0 +1,203 @@
+// ABOUTME: Recovery options for failed saves: retry, save elsewhere, or save with elevated rights
+// ABOUTME: Builds the platform helper command that copies a file into place as an administrator
+
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use helix_core::Rope;
+use helix_core::encoding::Encoding;
+use helix_view::DocumentId;
+use helix_view::document::{DocumentSaveError, to_writer};
+use tokio::io::AsyncWriteExt;
+
+/// A save that failed and can be recovered from the save failure prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveFailure {
+    pub doc_id: DocumentId,
+    pub path: PathBuf,
+    pub message: String,
+    /// The write was refused for lack of permission
+    pub permission_denied: bool,
+}
+
+impl SaveFailure {
+    pub fn from_error(error: &anyhow::Error) -> Option<Self> {
+        let save_error = error.downcast_ref::<DocumentSaveError>()?;
+        let io_kind = save_error
+            .error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<io::Error>())
+            .map(io::Error::kind);
+        Some(Self {
+            doc_id: save_error.doc_id,
+            path: save_error.path.clone(),
+            message: save_error.error.to_string(),
+            permission_denied: io_kind == Some(io::ErrorKind::PermissionDenied),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveFailureAction {
+    Retry,
+    SaveAs,
+    SaveElevated,
+}
+
+/// Picker payload for the save failure prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveFailureChoice {
+    pub doc_id: DocumentId,
+    pub path: PathBuf,
+    pub action: SaveFailureAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElevatedCopyCommand {
+    pub program: &'static str,
+    /// Paths are passed through as they are, without a lossy conversion
+    pub args: Vec<OsString>,
+}
+
+/// Command copying `source` over `target` with administrator rights, asking
+/// for credentials through the platform's graphical prompt. Copying into the
+/// existing file keeps its owner and permissions.
+pub fn elevated_copy_command(source: &Path, target: &Path) -> Option<ElevatedCopyCommand> {
+    #[cfg(target_os = "macos")]
+    {
+        return Some(ElevatedCopyCommand {
+            program: "osascript",
+            args: vec!["-e".into(), applescript_copy_script(source, target).into()],
+        });
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        return Some(ElevatedCopyCommand {
+            program: "pkexec",
+            args: vec![
+                "cp".into(),
+                "--".into(),
+                source.as_os_str().to_owned(),
+                target.as_os_str().to_owned(),
+            ],
+        });
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = (source, target);
+        None
+    }
+}
+
+/// Write `text` to `target` through the platform's elevated copy helper. The
+/// text is staged in a temporary file only the current user can read.
+pub async fn write_elevated(
+    target: PathBuf,
+    text: Rope,
+    encoding_with_bom_info: (&'static Encoding, bool),
+) -> anyhow::Result<()> {
+    let staged = std::env::temp_dir().join(format!(
+        "nucleotide-elevated-save-{}-{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+    ));
+    let result = async {
+        let mut options = tokio::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options.open(&staged).await?;
+        to_writer(&mut file, encoding_with_bom_info, &text).await?;
+        file.flush().await?;
+        drop(file);
+
+        let command = elevated_copy_command(&staged, &target).ok_or_else(|| {
+            anyhow::anyhow!("saving as administrator is not supported on this platform")
+        })?;
+        let status = tokio::process::Command::new(command.program)
+            .args(&command.args)
+            .status()
+            .await?;
+        if !status.success() {
+            anyhow::bail!("{} exited with {status}", command.program);
+        }
+        Ok(())
+    }
+    .await;
+
+    let _ = tokio::fs::remove_file(&staged).await;
+    result
+}
+
+/// AppleScript running `cp` as an administrator, with both paths quoted for
+/// the shell and the script string.
+#[cfg(any(target_os = "macos", test))]
+fn applescript_copy_script(source: &Path, target: &Path) -> String {
+    let shell_quote =
+        |path: &Path| format!("'{}'", path.display().to_string().replace('\'', r"'\''"));
+    let command = format!("cp -- {} {}", shell_quote(source), shell_quote(target));
+    let command = command.replace('\\', r"\\").replace('"', r#"\""#);
+    format!(r#"do shell script "{command}" with administrator privileges"#)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_failures_are_recognised_with_their_io_cause() {
+        let doc_id = DocumentId::default();
+        let denied = anyhow::Error::new(DocumentSaveError {
+            doc_id,
+            path: PathBuf::from("/etc/hosts"),
+            error: io::Error::from(io::ErrorKind::PermissionDenied).into(),
+        });
+        let failure = SaveFailure::from_error(&denied).expect("save failure");
+        assert_eq!(failure.path, PathBuf::from("/etc/hosts"));
+        assert!(failure.permission_denied);
+
+        let stale = anyhow::Error::new(DocumentSaveError {
+            doc_id,
+            path: PathBuf::from("notes.md"),
+            error: anyhow::anyhow!("file modified by an external process"),
+        });
+        assert!(!SaveFailure::from_error(&stale).unwrap().permission_denied);
+        assert_eq!(SaveFailure::from_error(&anyhow::anyhow!("no path")), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn pkexec_copy_keeps_paths_that_are_not_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let source = Path::new("/tmp/staged");
+        let target = Path::new(OsStr::from_bytes(b"/etc/caf\xe9.conf"));
+        let command = elevated_copy_command(source, target).unwrap();
+        assert_eq!(command.program, "pkexec");
+        assert_eq!(
+            command.args,
+            [
+                "cp".into(),
+                "--".into(),
+                source.as_os_str().to_owned(),
+                target.as_os_str().to_owned()
+            ]
+        );
+    }
+
+    #[test]
+    fn applescript_copy_quotes_paths() {
+        assert_eq!(
+            applescript_copy_script(Path::new("/tmp/a b"), Path::new("/etc/it's \"x\"")),
+            r#"do shell script "cp -- '/tmp/a b' '/etc/it'\\''s \"x\"'" with administrator privileges"#
+        );
+    }
+}
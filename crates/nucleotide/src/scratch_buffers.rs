@@ -0,0 +1,304 @@
+// ABOUTME: Named scratch buffers with a chosen language, listed in a picker
+// ABOUTME: Optionally persists their contents across restarts in the app data dir
+
+use anyhow::{Context, Result};
+use helix_view::DocumentId;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Prompt command creating a scratch buffer, or switching to one by name.
+pub const SCRATCH_NEW_COMMAND: &str = "scratch-new";
+/// Prompt command listing the scratch buffers in a picker.
+pub const SCRATCH_BUFFERS_COMMAND: &str = "scratch-buffers";
+/// Prompt command discarding the focused scratch buffer and its saved contents.
+pub const SCRATCH_DELETE_COMMAND: &str = "scratch-delete";
+/// Prompt commands for scratch buffers, with completion descriptions.
+pub const SCRATCH_COMMANDS: &[(&str, &str)] = &[
+    (
+        SCRATCH_NEW_COMMAND,
+        "Create a scratch buffer: scratch-new [language] [name]",
+    ),
+    (SCRATCH_BUFFERS_COMMAND, "List scratch buffers"),
+    (
+        SCRATCH_DELETE_COMMAND,
+        "Discard the focused scratch buffer and its saved contents",
+    ),
+];
+/// Language of scratch buffers created without one.
+pub const DEFAULT_SCRATCH_LANGUAGE: &str = "text";
+/// Name of scratch buffers created without one, numbered after the first.
+const DEFAULT_SCRATCH_NAME: &str = "scratch";
+const STORE_FILE_NAME: &str = "scratch_buffers.toml";
+
+/// Arguments of a `scratch-new [language] [name]` command line. The name
+/// is the rest of the line, so it may contain spaces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NewScratch {
+    pub language: Option<String>,
+    pub name: Option<String>,
+}
+
+/// Parses a `scratch-new` command line. Returns `None` for other commands.
+pub fn parse_scratch_new(command: &str) -> Option<NewScratch> {
+    let rest = command
+        .trim()
+        .trim_start_matches(':')
+        .strip_prefix(SCRATCH_NEW_COMMAND)
+        .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))?
+        .trim();
+    let (language, name) = match rest.split_once(char::is_whitespace) {
+        Some((language, name)) => (language, name.trim()),
+        None => (rest, ""),
+    };
+    let non_empty = |text: &str| (!text.is_empty()).then(|| text.to_string());
+    Some(NewScratch {
+        language: non_empty(language),
+        name: non_empty(name),
+    })
+}
+
+/// A scratch buffer, open in the editor or only saved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScratchBuffer {
+    pub name: String,
+    pub language: String,
+    /// Document showing the buffer, `None` until a saved buffer is reopened
+    pub doc_id: Option<DocumentId>,
+    /// Saved contents of a buffer that is not open
+    pub text: String,
+}
+
+/// Picker entry for a scratch buffer, by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScratchBufferChoice {
+    pub name: String,
+}
+
+/// Scratch buffers of the session, including saved ones not reopened yet.
+#[derive(Debug, Clone, Default)]
+pub struct ScratchBuffers {
+    buffers: Vec<ScratchBuffer>,
+}
+
+impl ScratchBuffers {
+    /// Registry of the buffers in `store`, none of them open.
+    pub fn from_store(store: ScratchStore) -> Self {
+        Self {
+            buffers: store
+                .buffers
+                .into_iter()
+                .map(|saved| ScratchBuffer {
+                    name: saved.name,
+                    language: saved.language,
+                    doc_id: None,
+                    text: saved.text,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ScratchBuffer> {
+        self.buffers.iter()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ScratchBuffer> {
+        self.buffers.iter().find(|buffer| buffer.name == name)
+    }
+
+    pub fn name_of(&self, doc_id: DocumentId) -> Option<&str> {
+        self.buffers
+            .iter()
+            .find(|buffer| buffer.doc_id == Some(doc_id))
+            .map(|buffer| buffer.name.as_str())
+    }
+
+    pub fn is_scratch(&self, doc_id: DocumentId) -> bool {
+        self.name_of(doc_id).is_some()
+    }
+
+    /// `requested`, or the first free `scratch`, `scratch 2`, ... name.
+    pub fn new_name(&self, requested: Option<&str>) -> String {
+        if let Some(name) = requested {
+            return name.to_string();
+        }
+        (1..)
+            .map(|index| match index {
+                1 => DEFAULT_SCRATCH_NAME.to_string(),
+                index => format!("{DEFAULT_SCRATCH_NAME} {index}"),
+            })
+            .find(|name| self.get(name).is_none())
+            .unwrap_or_else(|| DEFAULT_SCRATCH_NAME.to_string())
+    }
+
+    /// Records `name` as shown by `doc_id`, replacing a buffer of that name.
+    pub fn open(&mut self, name: String, language: String, doc_id: DocumentId) {
+        self.buffers.retain(|buffer| buffer.name != name);
+        self.buffers.push(ScratchBuffer {
+            name,
+            language,
+            doc_id: Some(doc_id),
+            text: String::new(),
+        });
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<ScratchBuffer> {
+        let index = self.buffers.iter().position(|buffer| buffer.name == name)?;
+        Some(self.buffers.remove(index))
+    }
+
+    /// Drops buffers whose document was closed; closing a scratch buffer
+    /// discards it.
+    pub fn prune(&mut self, is_open: impl Fn(DocumentId) -> bool) {
+        self.buffers
+            .retain(|buffer| buffer.doc_id.is_none_or(|doc_id| is_open(doc_id)));
+    }
+
+    /// Store of every buffer, with `text_of` giving the contents of open ones.
+    pub fn to_store(&self, text_of: impl Fn(DocumentId) -> Option<String>) -> ScratchStore {
+        ScratchStore {
+            buffers: self
+                .buffers
+                .iter()
+                .filter_map(|buffer| {
+                    let text = match buffer.doc_id {
+                        Some(doc_id) => text_of(doc_id)?,
+                        None => buffer.text.clone(),
+                    };
+                    Some(SavedScratchBuffer {
+                        name: buffer.name.clone(),
+                        language: buffer.language.clone(),
+                        text,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Scratch buffers saved in the app data dir.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScratchStore {
+    #[serde(default)]
+    pub buffers: Vec<SavedScratchBuffer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedScratchBuffer {
+    pub name: String,
+    #[serde(default = "default_language")]
+    pub language: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+fn default_language() -> String {
+    DEFAULT_SCRATCH_LANGUAGE.to_string()
+}
+
+impl ScratchStore {
+    pub fn load_default() -> Result<Self> {
+        Self::load(&default_store_path())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .with_context(|| format!("failed to parse {}", path.display())),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error).with_context(|| format!("failed to read {}", path.display())),
+        }
+    }
+
+    pub fn save_default(&self) -> Result<()> {
+        self.save(&default_store_path())
+    }
+
+    /// Writes the store, or removes its file when there is nothing to keep.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if self.buffers.is_empty() {
+            return match std::fs::remove_file(path) {
+                Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+                    Err(error).with_context(|| format!("failed to remove {}", path.display()))
+                }
+                _ => Ok(()),
+            };
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        let contents = toml::to_string_pretty(self).context("failed to encode scratch buffers")?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+pub fn default_store_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("nucleotide")
+        .join(STORE_FILE_NAME)
+}
+
+/// Whether `command` is a typed Helix command that quits and refuses to
+/// while buffers have unsaved changes.
+pub fn is_quit_command(command: &str) -> bool {
+    crate::helix_command::typable_command_name(command).is_some_and(|name| {
+        matches!(
+            name,
+            "quit" | "quit-all" | "write-quit" | "write-quit-all" | "cquit"
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scratch_new_takes_a_language_and_a_name() {
+        assert_eq!(
+            parse_scratch_new(":scratch-new rust  api notes "),
+            Some(NewScratch {
+                language: Some("rust".into()),
+                name: Some("api notes".into()),
+            })
+        );
+        assert_eq!(
+            parse_scratch_new("scratch-new"),
+            Some(NewScratch::default())
+        );
+        assert_eq!(parse_scratch_new("scratch-newer"), None);
+        assert_eq!(parse_scratch_new("scratch-buffers"), None);
+    }
+
+    #[test]
+    fn closed_buffers_are_dropped_and_saved_ones_kept() {
+        let doc_id = DocumentId::default();
+        let mut buffers = ScratchBuffers::from_store(ScratchStore {
+            buffers: vec![SavedScratchBuffer {
+                name: "scratch".into(),
+                language: "text".into(),
+                text: "saved".into(),
+            }],
+        });
+        assert_eq!(buffers.new_name(None), "scratch 2");
+        buffers.open("scratch 2".into(), "rust".into(), doc_id);
+        assert_eq!(buffers.name_of(doc_id), Some("scratch 2"));
+
+        let store = buffers.to_store(|_| Some("fn main() {}".into()));
+        let texts = store
+            .buffers
+            .iter()
+            .map(|buffer| buffer.text.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, ["saved", "fn main() {}"]);
+        let encoded = toml::to_string_pretty(&store).unwrap();
+        assert_eq!(toml::from_str::<ScratchStore>(&encoded).unwrap(), store);
+
+        buffers.prune(|_| false);
+        assert!(!buffers.is_scratch(doc_id));
+        assert_eq!(buffers.iter().count(), 1);
+    }
+}
@@ -0,0 +1,127 @@
+// ABOUTME: Ordered teardown of background services when the application quits
+// ABOUTME: Tracks which steps are still running so a slow quit shows progress and still ends on time
+
+use std::time::{Duration, Instant};
+
+/// Longest a quit waits for background services before exiting anyway.
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long a quit may take before its progress is shown.
+pub const SHUTDOWN_PROGRESS_DELAY: Duration = Duration::from_millis(300);
+
+/// Part of the application stopped on quit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownStep {
+    /// Scratch buffers and pending document writes
+    Session,
+    Terminals,
+    /// `shutdown` and `exit` sent to every language server
+    LanguageServers,
+    ProjectLspManager,
+    VcsWatchers,
+}
+
+impl ShutdownStep {
+    pub const ALL: [ShutdownStep; 5] = [
+        Self::Session,
+        Self::Terminals,
+        Self::LanguageServers,
+        Self::ProjectLspManager,
+        Self::VcsWatchers,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Session => "saving session",
+            Self::Terminals => "closing terminals",
+            Self::LanguageServers => "stopping language servers",
+            Self::ProjectLspManager => "stopping project language services",
+            Self::VcsWatchers => "stopping version control watchers",
+        }
+    }
+}
+
+/// Steps of a quit in progress. The application exits once every step has
+/// finished or the deadline passes, whichever comes first.
+#[derive(Debug, Clone)]
+pub struct ShutdownProgress {
+    started: Instant,
+    timeout: Duration,
+    pending: Vec<ShutdownStep>,
+}
+
+impl ShutdownProgress {
+    pub fn new(now: Instant, timeout: Duration) -> Self {
+        Self {
+            started: now,
+            timeout,
+            pending: ShutdownStep::ALL.to_vec(),
+        }
+    }
+
+    pub fn finish(&mut self, step: ShutdownStep) {
+        self.pending.retain(|pending| *pending != step);
+    }
+
+    pub fn pending(&self) -> &[ShutdownStep] {
+        &self.pending
+    }
+
+    /// Whether the application may exit now.
+    pub fn is_done(&self, now: Instant) -> bool {
+        self.pending.is_empty() || self.timed_out(now)
+    }
+
+    pub fn timed_out(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started) >= self.timeout
+    }
+
+    /// Whether the quit has taken long enough to show what it waits on.
+    pub fn shows_progress(&self, now: Instant) -> bool {
+        !self.pending.is_empty()
+            && now.saturating_duration_since(self.started) >= SHUTDOWN_PROGRESS_DELAY
+    }
+
+    /// One-line description of what the quit is waiting on.
+    pub fn status(&self) -> String {
+        let waiting = self
+            .pending
+            .iter()
+            .map(|step| step.label())
+            .collect::<Vec<_>>();
+        if waiting.is_empty() {
+            "Shutting down".to_string()
+        } else {
+            format!("Shutting down: {}", waiting.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quit_waits_for_every_step_until_the_deadline() {
+        let start = Instant::now();
+        let mut progress = ShutdownProgress::new(start, Duration::from_secs(2));
+        assert!(!progress.is_done(start));
+        assert!(!progress.shows_progress(start + Duration::from_millis(100)));
+        assert!(progress.shows_progress(start + SHUTDOWN_PROGRESS_DELAY));
+
+        progress.finish(ShutdownStep::Session);
+        progress.finish(ShutdownStep::VcsWatchers);
+        progress.finish(ShutdownStep::ProjectLspManager);
+        assert_eq!(
+            progress.status(),
+            "Shutting down: closing terminals, stopping language servers"
+        );
+        assert!(progress.is_done(start + Duration::from_secs(2)));
+
+        progress.finish(ShutdownStep::Terminals);
+        progress.finish(ShutdownStep::LanguageServers);
+        assert!(progress.pending().is_empty());
+        assert!(progress.is_done(start));
+        assert!(!progress.shows_progress(start + Duration::from_secs(1)));
+    }
+}
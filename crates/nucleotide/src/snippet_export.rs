@@ -0,0 +1,425 @@
+// ABOUTME: Renders styled editor or terminal lines as framed SVG or HTML snippets
+// ABOUTME: Keeps syntax colors, line numbers and selections so exports match the theme
+
+use std::fmt::Write as _;
+
+/// Width and height of one monospace cell in exported SVG, in pixels.
+const SVG_CELL_WIDTH: f32 = 8.4;
+const SVG_LINE_HEIGHT: f32 = 20.0;
+const SVG_FONT_SIZE: f32 = 14.0;
+const SVG_PADDING: f32 = 16.0;
+const SVG_TITLE_BAR_HEIGHT: f32 = 32.0;
+const TAB_WIDTH: usize = 4;
+const FONT_FAMILY: &str = "ui-monospace, SFMono-Regular, Menlo, Consolas, monospace";
+
+/// An opaque sRGB color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl ExportColor {
+    pub fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// A run of identically styled text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportSpan {
+    pub text: String,
+    /// `None` uses the snippet's foreground
+    pub foreground: Option<ExportColor>,
+    /// Selection or cell background; `None` leaves the snippet background
+    pub background: Option<ExportColor>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+/// One exported line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportLine {
+    /// 1-based line number shown in the gutter
+    pub number: Option<usize>,
+    pub spans: Vec<ExportSpan>,
+}
+
+/// Everything needed to render a snippet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetExport {
+    /// Shown in the frame's title bar, usually the file name
+    pub title: String,
+    pub lines: Vec<ExportLine>,
+    pub foreground: ExportColor,
+    pub background: ExportColor,
+    /// Line number color
+    pub gutter: ExportColor,
+    /// Title bar color
+    pub frame: ExportColor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnippetExportFormat {
+    Svg,
+    Html,
+}
+
+impl SnippetExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Html => "html",
+        }
+    }
+}
+
+pub fn render_snippet(snippet: &SnippetExport, format: SnippetExportFormat) -> String {
+    match format {
+        SnippetExportFormat::Svg => render_svg(snippet),
+        SnippetExportFormat::Html => render_html(snippet),
+    }
+}
+
+pub(crate) fn escape_markup(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            ch if ch.is_control() => {}
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Expands tabs relative to the line start so columns stay aligned across spans.
+pub(crate) fn expand_tabs(text: &str, column: &mut usize) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if ch == '\t' {
+            let width = TAB_WIDTH - *column % TAB_WIDTH;
+            expanded.extend(std::iter::repeat_n(' ', width));
+            *column += width;
+        } else {
+            expanded.push(ch);
+            *column += 1;
+        }
+    }
+    expanded
+}
+
+fn gutter_width(snippet: &SnippetExport) -> usize {
+    snippet
+        .lines
+        .iter()
+        .filter_map(|line| line.number)
+        .max()
+        .map_or(0, |number| number.to_string().len())
+}
+
+fn span_style(span: &ExportSpan) -> String {
+    let mut style = String::new();
+    if let Some(foreground) = span.foreground {
+        let _ = write!(style, "color:{};", foreground.hex());
+    }
+    if let Some(background) = span.background {
+        let _ = write!(style, "background:{};", background.hex());
+    }
+    if span.bold {
+        style.push_str("font-weight:bold;");
+    }
+    if span.italic {
+        style.push_str("font-style:italic;");
+    }
+    if span.underline {
+        style.push_str("text-decoration:underline;");
+    }
+    style
+}
+
+fn render_html(snippet: &SnippetExport) -> String {
+    let gutter_width = gutter_width(snippet);
+    let mut html = String::new();
+    let _ = writeln!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body style=\"margin:0;padding:24px;background:transparent;\">",
+        escape_markup(&snippet.title)
+    );
+    let _ = writeln!(
+        html,
+        "<figure style=\"display:inline-block;margin:0;border-radius:8px;overflow:hidden;box-shadow:0 8px 24px rgba(0,0,0,0.35);background:{};\">",
+        snippet.background.hex()
+    );
+    let _ = writeln!(
+        html,
+        "<figcaption style=\"display:flex;align-items:center;gap:6px;padding:8px 12px;background:{};color:{};font:12px {FONT_FAMILY};\">\
+         <span style=\"width:10px;height:10px;border-radius:50%;background:#ff5f57;\"></span>\
+         <span style=\"width:10px;height:10px;border-radius:50%;background:#febc2e;\"></span>\
+         <span style=\"width:10px;height:10px;border-radius:50%;background:#28c840;\"></span>\
+         <span style=\"flex:1;text-align:center;\">{}</span></figcaption>",
+        snippet.frame.hex(),
+        snippet.gutter.hex(),
+        escape_markup(&snippet.title)
+    );
+    let _ = write!(
+        html,
+        "<pre style=\"margin:0;padding:12px 16px;color:{};font:13px/1.5 {FONT_FAMILY};\">",
+        snippet.foreground.hex()
+    );
+
+    for (index, line) in snippet.lines.iter().enumerate() {
+        if index > 0 {
+            html.push('\n');
+        }
+        if gutter_width > 0 {
+            let number = line.number.map(|n| n.to_string()).unwrap_or_default();
+            let _ = write!(
+                html,
+                "<span style=\"color:{};user-select:none;\">{number:>gutter_width$}  </span>",
+                snippet.gutter.hex()
+            );
+        }
+        let mut column = 0;
+        for span in &line.spans {
+            let text = escape_markup(&expand_tabs(&span.text, &mut column));
+            let style = span_style(span);
+            if style.is_empty() {
+                html.push_str(&text);
+            } else {
+                let _ = write!(html, "<span style=\"{style}\">{text}</span>");
+            }
+        }
+    }
+
+    html.push_str("</pre>\n</figure>\n</body>\n</html>\n");
+    html
+}
+
+fn render_svg(snippet: &SnippetExport) -> String {
+    let gutter_width = gutter_width(snippet);
+    let gutter_columns = if gutter_width > 0 {
+        gutter_width + 2
+    } else {
+        0
+    };
+    let code_columns = snippet
+        .lines
+        .iter()
+        .map(|line| {
+            let mut column = 0;
+            for span in &line.spans {
+                expand_tabs(&span.text, &mut column);
+            }
+            column
+        })
+        .max()
+        .unwrap_or(0);
+
+    let width = SVG_PADDING * 2.0 + (gutter_columns + code_columns.max(20)) as f32 * SVG_CELL_WIDTH;
+    let height = SVG_TITLE_BAR_HEIGHT
+        + SVG_PADDING * 2.0
+        + snippet.lines.len().max(1) as f32 * SVG_LINE_HEIGHT;
+    let text_left = SVG_PADDING + gutter_columns as f32 * SVG_CELL_WIDTH;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.0}\" height=\"{height:.0}\" viewBox=\"0 0 {width:.0} {height:.0}\">"
+    );
+    let _ = writeln!(
+        svg,
+        "<rect width=\"100%\" height=\"100%\" rx=\"8\" fill=\"{}\"/>",
+        snippet.background.hex()
+    );
+    let _ = writeln!(
+        svg,
+        "<path d=\"M0 8a8 8 0 0 1 8-8h{:.1}a8 8 0 0 1 8 8v{:.1}h-{width:.1}z\" fill=\"{}\"/>",
+        width - 16.0,
+        SVG_TITLE_BAR_HEIGHT - 8.0,
+        snippet.frame.hex()
+    );
+    for (index, color) in ["#ff5f57", "#febc2e", "#28c840"].into_iter().enumerate() {
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{}\" cy=\"{}\" r=\"6\" fill=\"{color}\"/>",
+            SVG_PADDING + index as f32 * 20.0,
+            SVG_TITLE_BAR_HEIGHT / 2.0
+        );
+    }
+    let _ = writeln!(
+        svg,
+        "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" font-family=\"{FONT_FAMILY}\" font-size=\"12\" fill=\"{}\">{}</text>",
+        width / 2.0,
+        SVG_TITLE_BAR_HEIGHT / 2.0 + 4.0,
+        snippet.gutter.hex(),
+        escape_markup(&snippet.title)
+    );
+
+    let _ = writeln!(
+        svg,
+        "<g font-family=\"{FONT_FAMILY}\" font-size=\"{SVG_FONT_SIZE}\" xml:space=\"preserve\">"
+    );
+    for (index, line) in snippet.lines.iter().enumerate() {
+        let top = SVG_TITLE_BAR_HEIGHT + SVG_PADDING + index as f32 * SVG_LINE_HEIGHT;
+        let baseline = top + SVG_LINE_HEIGHT * 0.75;
+
+        if let Some(number) = line.number {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{baseline:.1}\" text-anchor=\"end\" fill=\"{}\">{number}</text>",
+                SVG_PADDING + gutter_width as f32 * SVG_CELL_WIDTH,
+                snippet.gutter.hex()
+            );
+        }
+
+        // Backgrounds first so text is drawn on top of them.
+        let mut column = 0;
+        let mut runs = Vec::with_capacity(line.spans.len());
+        for span in &line.spans {
+            let start = column;
+            let text = expand_tabs(&span.text, &mut column);
+            if let Some(background) = span.background {
+                let _ = writeln!(
+                    svg,
+                    "<rect x=\"{:.1}\" y=\"{top:.1}\" width=\"{:.1}\" height=\"{SVG_LINE_HEIGHT}\" fill=\"{}\"/>",
+                    text_left + start as f32 * SVG_CELL_WIDTH,
+                    (column - start) as f32 * SVG_CELL_WIDTH,
+                    background.hex()
+                );
+            }
+            runs.push((start, text, span));
+        }
+
+        for (start, text, span) in runs {
+            if text.trim().is_empty() {
+                continue;
+            }
+            let mut attributes = format!(
+                "fill=\"{}\"",
+                span.foreground.unwrap_or(snippet.foreground).hex()
+            );
+            if span.bold {
+                attributes.push_str(" font-weight=\"bold\"");
+            }
+            if span.italic {
+                attributes.push_str(" font-style=\"italic\"");
+            }
+            if span.underline {
+                attributes.push_str(" text-decoration=\"underline\"");
+            }
+            // Each span is positioned by column so proportional fallback fonts cannot drift.
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{baseline:.1}\" {attributes}>{}</text>",
+                text_left + start as f32 * SVG_CELL_WIDTH,
+                escape_markup(&text)
+            );
+        }
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: ExportColor = ExportColor {
+        r: 0xff,
+        g: 0xff,
+        b: 0xff,
+    };
+    const BLACK: ExportColor = ExportColor { r: 0, g: 0, b: 0 };
+
+    fn span(text: &str) -> ExportSpan {
+        ExportSpan {
+            text: text.to_string(),
+            foreground: None,
+            background: None,
+            bold: false,
+            italic: false,
+            underline: false,
+        }
+    }
+
+    fn snippet(lines: Vec<ExportLine>) -> SnippetExport {
+        SnippetExport {
+            title: "main.rs".to_string(),
+            lines,
+            foreground: WHITE,
+            background: BLACK,
+            gutter: WHITE,
+            frame: BLACK,
+        }
+    }
+
+    #[test]
+    fn html_escapes_text_and_pads_line_numbers() {
+        let keyword = ExportSpan {
+            foreground: Some(ExportColor {
+                r: 0xc6,
+                g: 0x78,
+                b: 0xdd,
+            }),
+            bold: true,
+            ..span("fn")
+        };
+        let export = snippet(vec![
+            ExportLine {
+                number: Some(9),
+                spans: vec![keyword, span(" a<T>() {}")],
+            },
+            ExportLine {
+                number: Some(10),
+                spans: vec![span("\tx & y")],
+            },
+        ]);
+
+        let html = render_snippet(&export, SnippetExportFormat::Html);
+        assert!(
+            html.contains(
+                "<span style=\"color:#c678dd;font-weight:bold;\">fn</span> a&lt;T&gt;() {}"
+            )
+        );
+        assert!(html.contains(" 9  </span>"));
+        assert!(html.contains("10  </span>    x &amp; y"));
+        assert!(html.contains(">main.rs</span></figcaption>"));
+    }
+
+    #[test]
+    fn svg_places_spans_and_selection_backgrounds_by_column() {
+        let selected = ExportSpan {
+            background: Some(ExportColor {
+                r: 0x33,
+                g: 0x44,
+                b: 0x55,
+            }),
+            ..span("b")
+        };
+        let export = snippet(vec![ExportLine {
+            number: Some(1),
+            spans: vec![span("a\t"), selected],
+        }]);
+
+        let svg = render_snippet(&export, SnippetExportFormat::Svg);
+        let text_left = SVG_PADDING + 3.0 * SVG_CELL_WIDTH;
+        assert!(svg.contains(&format!(
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{SVG_CELL_WIDTH:.1}\"",
+            text_left + 4.0 * SVG_CELL_WIDTH,
+            SVG_TITLE_BAR_HEIGHT + SVG_PADDING
+        )));
+        assert!(svg.contains(&format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" fill=\"#ffffff\">b</text>",
+            text_left + 4.0 * SVG_CELL_WIDTH,
+            SVG_TITLE_BAR_HEIGHT + SVG_PADDING + SVG_LINE_HEIGHT * 0.75
+        )));
+        assert!(svg.contains(">main.rs</text>"));
+    }
+}
@@ -0,0 +1,119 @@
+// ABOUTME: Time spent in each phase of application startup
+// ABOUTME: Builds the startup report logged once the first frame has been drawn
+
+use std::time::{Duration, Instant};
+
+/// Part of startup measured on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupPhase {
+    ConfigLoad,
+    EditorInit,
+    ThemeInit,
+    LspManagerStart,
+}
+
+impl StartupPhase {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ConfigLoad => "config load",
+            Self::EditorInit => "editor init",
+            Self::ThemeInit => "theme init",
+            Self::LspManagerStart => "lsp manager start",
+        }
+    }
+}
+
+/// Phase durations from process start to the first frame.
+#[derive(Debug, Clone)]
+pub struct StartupProfile {
+    started: Instant,
+    phases: Vec<(StartupPhase, Duration)>,
+    first_frame: Option<Duration>,
+}
+
+impl gpui::Global for StartupProfile {}
+
+impl StartupProfile {
+    pub fn new(started: Instant) -> Self {
+        Self {
+            started,
+            phases: Vec::new(),
+            first_frame: None,
+        }
+    }
+
+    pub fn record(&mut self, phase: StartupPhase, duration: Duration) {
+        self.phases.push((phase, duration));
+    }
+
+    pub fn measure<T>(&mut self, phase: StartupPhase, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = f();
+        self.record(phase, started.elapsed());
+        result
+    }
+
+    /// Records the first frame, returning `false` when it was already
+    /// recorded.
+    pub fn finish(&mut self, now: Instant) -> bool {
+        if self.first_frame.is_some() {
+            return false;
+        }
+        self.first_frame = Some(now.saturating_duration_since(self.started));
+        true
+    }
+
+    pub fn first_frame(&self) -> Option<Duration> {
+        self.first_frame
+    }
+
+    /// Report lines: the time to the first frame, then each phase in the
+    /// order it ran.
+    pub fn report(&self) -> Vec<String> {
+        let mut lines = Vec::with_capacity(self.phases.len() + 1);
+        match self.first_frame {
+            Some(first_frame) => {
+                lines.push(format!("first frame after {}", format_millis(first_frame)))
+            }
+            None => lines.push("first frame not drawn yet".to_string()),
+        }
+        for (phase, duration) in &self.phases {
+            lines.push(format!(
+                "  {:<18}{:>8}",
+                phase.label(),
+                format_millis(*duration)
+            ));
+        }
+        lines
+    }
+}
+
+fn format_millis(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_lists_phases_after_the_first_frame() {
+        let start = Instant::now();
+        let mut profile = StartupProfile::new(start);
+        profile.record(StartupPhase::ConfigLoad, Duration::from_micros(12_300));
+        profile.record(StartupPhase::EditorInit, Duration::from_millis(240));
+
+        assert!(profile.finish(start + Duration::from_millis(410)));
+        assert!(!profile.finish(start + Duration::from_secs(2)));
+        assert_eq!(profile.first_frame(), Some(Duration::from_millis(410)));
+
+        assert_eq!(
+            profile.report(),
+            [
+                "first frame after 410.0ms",
+                "  config load         12.3ms",
+                "  editor init        240.0ms",
+            ]
+        );
+    }
+}
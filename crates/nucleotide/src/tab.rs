@@ -208,6 +208,8 @@ pub struct Tab {
     pub git_status: Option<VcsStatus>,
     /// Highest-priority diagnostic severity for the tab icon decoration
     pub diagnostic_severity: Option<DiagnosticSeverity>,
+    /// Number of diagnostics at `diagnostic_severity`, shown as a badge
+    diagnostic_count: usize,
     /// Whether this tab is currently active
     pub is_active: bool,
     /// Whether the document contents are still loading
@@ -287,6 +289,7 @@ impl Tab {
             is_preview: false,
             git_status,
             diagnostic_severity,
+            diagnostic_count: 0,
             is_active,
             is_loading: false,
             variant,
@@ -326,6 +329,11 @@ impl Tab {
         self
     }
 
+    pub fn diagnostic_count(mut self, count: usize) -> Self {
+        self.diagnostic_count = count;
+        self
+    }
+
     pub fn detail(mut self, detail: Option<String>) -> Self {
         self.label_detail = detail;
         self
@@ -416,6 +424,11 @@ impl Tab {
         self.diagnostic_severity
     }
 
+    #[cfg(test)]
+    pub(crate) fn diagnostic_badge_count(&self) -> usize {
+        self.diagnostic_count
+    }
+
     pub fn on_context_menu(
         mut self,
         handler: impl Fn(&MouseDownEvent, &mut Window, &mut App) + 'static,
@@ -546,6 +559,7 @@ impl ComponentFactory for Tab {
             is_preview: false,
             git_status: None,
             diagnostic_severity: None,
+            diagnostic_count: 0,
             is_active: false,
             is_loading: false,
             variant: TabVariant::Default,
@@ -639,6 +653,7 @@ impl RenderOnce for Tab {
         let tooltip = self.tooltip.clone();
         let git_status = self.git_status;
         let diagnostic_severity = self.diagnostic_severity;
+        let diagnostic_count = self.diagnostic_count;
         let text_color =
             Tab::deemphasized_text_color(text_color, is_active, disabled, deemphasized, tab_tokens);
         let content_row = Tab::build_content_row(
@@ -656,6 +671,7 @@ impl RenderOnce for Tab {
             disabled,
             git_status,
             diagnostic_severity,
+            diagnostic_count,
             text_color,
             tokens,
             on_close_handler,
@@ -961,6 +977,63 @@ impl Tab {
             .into_any_element()
     }
 
+    fn diagnostic_count_text(count: usize) -> Option<String> {
+        match count {
+            0 => None,
+            1..=99 => Some(count.to_string()),
+            _ => Some("99+".to_string()),
+        }
+    }
+
+    fn build_diagnostic_badge(
+        severity: DiagnosticSeverity,
+        count: usize,
+        tokens: nucleotide_ui::tokens::DesignTokens,
+    ) -> Option<gpui::AnyElement> {
+        let text = Tab::diagnostic_count_text(count)?;
+        let color = match severity {
+            DiagnosticSeverity::Error => tokens.editor.diagnostic_error,
+            DiagnosticSeverity::Warning => tokens.editor.diagnostic_warning,
+            DiagnosticSeverity::Info | DiagnosticSeverity::Hint => tokens.editor.diagnostic_info,
+        };
+
+        Some(
+            div()
+                .flex_none()
+                .px(px(4.0))
+                .rounded(px(6.0))
+                .bg(color.alpha(0.16))
+                .text_color(color)
+                .text_size(tokens.sizes.text_xs)
+                .font_weight(gpui::FontWeight::MEDIUM)
+                .child(text)
+                .into_any_element(),
+        )
+    }
+
+    /// VCS status shown as a dot next to the label; clean files have none.
+    fn vcs_dot_status(git_status: Option<VcsStatus>) -> Option<VcsStatus> {
+        git_status.filter(|status| !matches!(status, VcsStatus::Clean | VcsStatus::Unknown))
+    }
+
+    fn build_vcs_dot(
+        status: VcsStatus,
+        tokens: nucleotide_ui::tokens::DesignTokens,
+    ) -> gpui::AnyElement {
+        let color = match status {
+            VcsStatus::Added | VcsStatus::Untracked => tokens.editor.vcs_added,
+            VcsStatus::Deleted | VcsStatus::Conflicted => tokens.editor.vcs_deleted,
+            _ => tokens.editor.vcs_modified,
+        };
+
+        div()
+            .flex_none()
+            .size(px(5.0))
+            .rounded(px(2.5))
+            .bg(color)
+            .into_any_element()
+    }
+
     fn icon_vcs_status(
         git_status: Option<VcsStatus>,
         diagnostic_severity: Option<DiagnosticSeverity>,
@@ -1167,6 +1240,7 @@ impl Tab {
         disabled: bool,
         git_status: Option<VcsStatus>,
         diagnostic_severity: Option<DiagnosticSeverity>,
+        diagnostic_count: usize,
         text_color: gpui::Hsla,
         tokens: nucleotide_ui::tokens::DesignTokens,
         on_close: MouseEventHandler,
@@ -1200,6 +1274,9 @@ impl Tab {
         let trailing_slot = div().flex_none().ml_auto().child(trailing_slot);
         let content_icon = Tab::content_icon_kind(is_readonly, show_file_icons);
         let readonly_diagnostic_severity = show_file_icons.then_some(diagnostic_severity).flatten();
+        let diagnostic_badge = diagnostic_severity
+            .and_then(|severity| Tab::build_diagnostic_badge(severity, diagnostic_count, tokens));
+        let vcs_dot = Tab::vcs_dot_status(git_status);
         let loading_indicator =
             IndeterminateProgressIndicator::new(format!("tab-loading-{}", doc_id))
                 .size(TAB_SLOT_ICON_SIZE)
@@ -1239,6 +1316,10 @@ impl Tab {
                 text_color,
                 tokens,
             ))
+            .children(diagnostic_badge)
+            .when_some(vcs_dot, |row, status| {
+                row.child(Tab::build_vcs_dot(status, tokens))
+            })
             .child(trailing_slot)
             .into_any_element()
     }
@@ -1362,4 +1443,18 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn tab_badges_cap_diagnostic_counts_and_skip_clean_files() {
+        assert_eq!(Tab::diagnostic_count_text(0), None);
+        assert_eq!(Tab::diagnostic_count_text(7).as_deref(), Some("7"));
+        assert_eq!(Tab::diagnostic_count_text(120).as_deref(), Some("99+"));
+
+        assert_eq!(Tab::vcs_dot_status(None), None);
+        assert_eq!(Tab::vcs_dot_status(Some(VcsStatus::Clean)), None);
+        assert_eq!(
+            Tab::vcs_dot_status(Some(VcsStatus::Modified)),
+            Some(VcsStatus::Modified)
+        );
+    }
 }
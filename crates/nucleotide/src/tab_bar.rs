@@ -147,6 +147,33 @@ pub struct DocumentInfo {
     pub order: usize, // Tracks the order documents were opened
     pub git_status: Option<VcsStatus>,
     pub diagnostic_severity: Option<DiagnosticSeverity>,
+    /// Number of diagnostics at `diagnostic_severity`
+    pub diagnostic_count: usize,
+}
+
+/// Highest severity among `severities` that `visibility` shows on a tab,
+/// with the number of diagnostics at that severity.
+pub fn diagnostic_badge(
+    severities: impl IntoIterator<Item = Option<DiagnosticSeverity>>,
+    visibility: TabDiagnosticsVisibility,
+) -> (Option<DiagnosticSeverity>, usize) {
+    let (mut errors, mut warnings) = (0, 0);
+    for severity in severities {
+        match severity {
+            Some(DiagnosticSeverity::Error) => errors += 1,
+            Some(DiagnosticSeverity::Warning) => warnings += 1,
+            _ => {}
+        }
+    }
+
+    match visibility {
+        TabDiagnosticsVisibility::Off => (None, 0),
+        _ if errors > 0 => (Some(DiagnosticSeverity::Error), errors),
+        TabDiagnosticsVisibility::All if warnings > 0 => {
+            (Some(DiagnosticSeverity::Warning), warnings)
+        }
+        _ => (None, 0),
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -213,6 +240,8 @@ pub struct TabBar {
     end_children: Vec<AnyElement>,
     /// Documents whose contents are still being loaded
     loading_documents: HashSet<TabId>,
+    /// Labels of documents without a path, such as named scratch buffers
+    document_names: HashMap<TabId, String>,
 }
 
 impl TabBar {
@@ -263,6 +292,7 @@ impl TabBar {
             start_children: Vec::new(),
             end_children: Vec::new(),
             loading_documents: HashSet::new(),
+            document_names: HashMap::new(),
         }
     }
 
@@ -271,6 +301,11 @@ impl TabBar {
         self
     }
 
+    pub fn document_names(mut self, names: impl IntoIterator<Item = (TabId, String)>) -> Self {
+        self.document_names = names.into_iter().collect();
+        self
+    }
+
     pub fn track_scroll(mut self, scroll_handle: &ScrollHandle) -> Self {
         self.scroll_handle = Some(scroll_handle.clone());
         self
@@ -405,7 +440,10 @@ impl TabBar {
                 .map(std::string::ToString::to_string)
                 .unwrap_or_else(|| path.display().to_string())
         } else {
-            "[scratch]".to_string()
+            self.document_names
+                .get(&doc_info.id)
+                .cloned()
+                .unwrap_or_else(|| "[scratch]".to_string())
         }
     }
 
@@ -574,6 +612,11 @@ impl TabBar {
                 .loading(self.loading_documents.contains(&doc_id))
                 .deemphasized(self.deemphasized)
                 .show_file_icons(self.file_icons)
+                .diagnostic_count(
+                    diagnostic_severity
+                        .map(|_| doc_info.diagnostic_count)
+                        .unwrap_or_default(),
+                )
                 .tooltip(self.document_tooltip(doc_info, &label));
 
                 if let Some(on_tab_context_menu) = self.on_tab_context_menu.clone() {
@@ -939,6 +982,7 @@ mod tests {
             order,
             git_status: None,
             diagnostic_severity: None,
+            diagnostic_count: 0,
         }
     }
 
@@ -1373,6 +1417,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn pathless_tabs_use_their_document_name() {
+        let documents = vec![doc(None, 0)];
+        let tab_bar = TabBar::new(documents.clone(), None, None, |_, _, _| {}, |_, _, _| {})
+            .document_names([(documents[0].id, "notes".to_string())]);
+
+        let labels = tab_bar.document_labels(&documents);
+        assert_eq!(labels[0].title, "notes");
+    }
+
     #[test]
     fn build_tabs_gates_git_status_by_setting() {
         let documents = vec![DocumentInfo {
@@ -1465,6 +1519,55 @@ mod tests {
         assert_eq!(tabs[0].diagnostic_severity(), None);
     }
 
+    #[test]
+    fn diagnostic_badges_count_the_highest_visible_severity() {
+        let severities = [
+            Some(DiagnosticSeverity::Warning),
+            Some(DiagnosticSeverity::Error),
+            Some(DiagnosticSeverity::Warning),
+            Some(DiagnosticSeverity::Hint),
+            None,
+        ];
+
+        assert_eq!(
+            diagnostic_badge(severities, TabDiagnosticsVisibility::All),
+            (Some(DiagnosticSeverity::Error), 1)
+        );
+        assert_eq!(
+            diagnostic_badge(severities[..1].to_vec(), TabDiagnosticsVisibility::All),
+            (Some(DiagnosticSeverity::Warning), 1)
+        );
+        assert_eq!(
+            diagnostic_badge(severities[..1].to_vec(), TabDiagnosticsVisibility::Errors),
+            (None, 0)
+        );
+        assert_eq!(
+            diagnostic_badge(severities, TabDiagnosticsVisibility::Off),
+            (None, 0)
+        );
+
+        let documents = vec![DocumentInfo {
+            diagnostic_severity: Some(DiagnosticSeverity::Warning),
+            diagnostic_count: 3,
+            ..doc(Some("/project/a.rs"), 0)
+        }];
+        let tab_bar = TabBar::new(
+            documents.clone(),
+            None,
+            Some(PathBuf::from("/project")),
+            |_, _, _| {},
+            |_, _, _| {},
+        )
+        .show_diagnostics(TabDiagnosticsVisibility::All);
+        let labels = tab_bar.document_labels(&documents);
+        let tabs = tab_bar.build_tabs(&documents, &labels, 0);
+        assert_eq!(tabs[0].diagnostic_badge_count(), 3);
+
+        let errors_only_tab_bar = tab_bar.show_diagnostics(TabDiagnosticsVisibility::Errors);
+        let tabs = errors_only_tab_bar.build_tabs(&documents, &labels, 0);
+        assert_eq!(tabs[0].diagnostic_badge_count(), 0);
+    }
+
     #[test]
     fn build_tabs_shows_error_diagnostics_in_errors_mode() {
         let documents = vec![DocumentInfo {
@@ -0,0 +1,347 @@
+// ABOUTME: Markdown task list items: toggling `[ ]`/`[x]` checkboxes and archiving completed items
+// ABOUTME: Builds single undoable transactions for the toggle and move-to-Done commands
+
+use std::ops::Range;
+
+use helix_core::{Change, Rope, Transaction};
+use helix_view::{DocumentId, Editor, ViewId};
+
+/// Command toggling the task list checkbox on the cursor line.
+pub const TOGGLE_TASK_COMMAND: &str = "toggle-task";
+/// Command moving completed task list items to the Done section.
+pub const ARCHIVE_DONE_TASKS_COMMAND: &str = "archive-done-tasks";
+
+/// Heading of the section completed items are moved to.
+const DONE_HEADING: &str = "Done";
+
+/// A task list checkbox found on a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskCheckbox {
+    /// Characters before the list marker: indentation and quote markers
+    pub indent: usize,
+    /// Character offset of the state character between the brackets
+    pub state_offset: usize,
+    pub checked: bool,
+}
+
+/// The checkbox of a `- [ ] item` style line, with `-`, `*`, `+` or ordered
+/// list markers, optionally inside a block quote.
+pub fn task_checkbox(line: &str) -> Option<TaskCheckbox> {
+    let chars: Vec<char> = line.chars().collect();
+    let indent = chars
+        .iter()
+        .take_while(|ch| matches!(ch, ' ' | '\t' | '>'))
+        .count();
+
+    let mut index = indent;
+    match chars.get(index)? {
+        '-' | '*' | '+' => index += 1,
+        ch if ch.is_ascii_digit() => {
+            let digits = chars[index..]
+                .iter()
+                .take_while(|ch| ch.is_ascii_digit())
+                .count();
+            // CommonMark caps ordered list numbers at nine digits.
+            if digits > 9 || !matches!(chars.get(index + digits), Some('.' | ')')) {
+                return None;
+            }
+            index += digits + 1;
+        }
+        _ => return None,
+    }
+
+    let spaces = chars[index..]
+        .iter()
+        .take_while(|ch| matches!(ch, ' ' | '\t'))
+        .count();
+    if spaces == 0 {
+        return None;
+    }
+    index += spaces;
+
+    let checked = match chars.get(index..index + 3)? {
+        ['[', ' ', ']'] => false,
+        ['[', 'x' | 'X', ']'] => true,
+        _ => return None,
+    };
+    if !chars.get(index + 3).is_none_or(|ch| ch.is_whitespace()) {
+        return None;
+    }
+
+    Some(TaskCheckbox {
+        indent,
+        state_offset: index + 1,
+        checked,
+    })
+}
+
+/// Flips the checkbox on `line`, or `None` when the line has none.
+pub fn toggle_task_transaction(text: &Rope, line: usize) -> Option<Transaction> {
+    if line >= text.len_lines() {
+        return None;
+    }
+    let checkbox = task_checkbox(&text.line(line).to_string())?;
+    let position = text.line_to_char(line) + checkbox.state_offset;
+    let state = if checkbox.checked { " " } else { "x" };
+    Some(Transaction::change(
+        text,
+        std::iter::once((position, position + 1, Some(state.into()))),
+    ))
+}
+
+/// Moves every completed item, with the lines nested under it, to the end of
+/// the Done section, creating the section at the end of the document when
+/// there is none. Returns the transaction and the number of items moved.
+pub fn archive_completed_tasks(text: &Rope) -> Option<(Transaction, usize)> {
+    let lines: Vec<String> = text.lines().map(|line| line.to_string()).collect();
+    let done_section = done_section(&lines);
+
+    let mut blocks: Vec<Range<usize>> = Vec::new();
+    let mut in_fence = false;
+    let mut index = 0;
+    while index < lines.len() {
+        let line = &lines[index];
+        if is_fence(line) {
+            in_fence = !in_fence;
+        }
+        let in_done = done_section
+            .as_ref()
+            .is_some_and(|section| section.contains(&index));
+        let checkbox = (!in_fence && !in_done)
+            .then(|| task_checkbox(line))
+            .flatten();
+        let Some(checkbox) = checkbox.filter(|checkbox| checkbox.checked) else {
+            index += 1;
+            continue;
+        };
+
+        let mut end = index + 1;
+        while end < lines.len()
+            && !lines[end].trim().is_empty()
+            && leading_whitespace(&lines[end]) > checkbox.indent
+        {
+            end += 1;
+        }
+        blocks.push(index..end);
+        index = end;
+    }
+    if blocks.is_empty() {
+        return None;
+    }
+
+    let line_start = |line: usize| {
+        if line < text.len_lines() {
+            text.line_to_char(line)
+        } else {
+            text.len_chars()
+        }
+    };
+    let mut moved = String::new();
+    let mut changes: Vec<Change> = Vec::with_capacity(blocks.len() + 1);
+    for block in &blocks {
+        for line in &lines[block.clone()] {
+            moved.push_str(line);
+        }
+        if !moved.ends_with('\n') {
+            moved.push('\n');
+        }
+        changes.push((line_start(block.start), line_start(block.end), None));
+    }
+
+    let (position, prefix) = match done_section {
+        Some(section) => {
+            let last_content = (section.start + 1..section.end)
+                .rev()
+                .find(|&line| !lines[line].trim().is_empty());
+            match last_content {
+                Some(line) => {
+                    let position = line_start(line + 1);
+                    let needs_newline =
+                        position == text.len_chars() && !lines[line].ends_with('\n');
+                    (position, if needs_newline { "\n" } else { "" }.to_string())
+                }
+                None => {
+                    let heading = &lines[section.start];
+                    let newline = if heading.ends_with('\n') { "" } else { "\n" };
+                    (line_start(section.start + 1), format!("{newline}\n"))
+                }
+            }
+        }
+        None => {
+            // Separate the new section from what is left of the document.
+            let kept_end = match blocks.last() {
+                Some(block) if line_start(block.end) == text.len_chars() => line_start(block.start),
+                _ => text.len_chars(),
+            };
+            let kept = text.slice(..kept_end);
+            let ends_with = |suffix: &str| {
+                kept.len_chars() >= suffix.len()
+                    && kept.slice(kept.len_chars() - suffix.len()..) == suffix
+            };
+            let separator = if kept.len_chars() == 0 || ends_with("\n\n") {
+                ""
+            } else if ends_with("\n") {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            (
+                text.len_chars(),
+                format!("{separator}## {DONE_HEADING}\n\n"),
+            )
+        }
+    };
+    changes.push((position, position, Some(format!("{prefix}{moved}").into())));
+    changes.sort_by_key(|(from, to, _)| (*from, *to));
+
+    Some((Transaction::change(text, changes.into_iter()), blocks.len()))
+}
+
+/// Applies a task list edit as its own undo step.
+pub fn apply_task_edit(
+    editor: &mut Editor,
+    doc_id: DocumentId,
+    view_id: ViewId,
+    transaction: &Transaction,
+) {
+    let view = editor.tree.get_mut(view_id);
+    let Some(doc) = editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+    // Commit pending edits first so undo reverts only this change.
+    doc.append_changes_to_history(view);
+    doc.apply(transaction, view_id);
+    doc.append_changes_to_history(view);
+}
+
+/// Lines of the first Done heading and its content, up to the next heading of
+/// the same or a higher level.
+fn done_section(lines: &[String]) -> Option<Range<usize>> {
+    let mut in_fence = false;
+    let mut done: Option<(usize, usize)> = None;
+    for (index, line) in lines.iter().enumerate() {
+        if is_fence(line) {
+            in_fence = !in_fence;
+        }
+        if in_fence {
+            continue;
+        }
+        let Some((level, title)) = heading(line) else {
+            continue;
+        };
+        match done {
+            Some((start, done_level)) if level <= done_level => return Some(start..index),
+            Some(_) => {}
+            None if title.eq_ignore_ascii_case(DONE_HEADING) => done = Some((index, level)),
+            None => {}
+        }
+    }
+    done.map(|(start, _)| start..lines.len())
+}
+
+/// Level and title of an ATX heading.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let trimmed = line.trim_end();
+    let level = trimmed.chars().take_while(|&ch| ch == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &trimmed[level..];
+    if !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+fn is_fence(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+}
+
+fn leading_whitespace(line: &str) -> usize {
+    line.chars()
+        .take_while(|ch| matches!(ch, ' ' | '\t' | '>'))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(text: &str, transaction: Transaction) -> String {
+        let mut rope = Rope::from(text);
+        assert!(transaction.apply(&mut rope));
+        rope.to_string()
+    }
+
+    #[test]
+    fn task_checkboxes_are_recognised_after_list_markers() {
+        assert_eq!(
+            task_checkbox("- [ ] write docs"),
+            Some(TaskCheckbox {
+                indent: 0,
+                state_offset: 3,
+                checked: false
+            })
+        );
+        assert!(task_checkbox("  * [x] nested").unwrap().checked);
+        assert!(task_checkbox("12. [X] ordered\n").unwrap().checked);
+        assert_eq!(task_checkbox("> - [ ] quoted").unwrap().indent, 2);
+        assert_eq!(task_checkbox("- [ ]"), task_checkbox("- [ ]\n"));
+
+        assert_eq!(task_checkbox("- plain item"), None);
+        assert_eq!(task_checkbox("-[ ] no space"), None);
+        assert_eq!(task_checkbox("- [y] unknown"), None);
+        assert_eq!(task_checkbox("- [ ]text"), None);
+        assert_eq!(task_checkbox("[ ] no marker"), None);
+    }
+
+    #[test]
+    fn toggling_flips_only_the_state_character() {
+        let text = Rope::from("# Todo\n- [ ] one\n  - [x] two\nplain\n");
+
+        let checked = apply(
+            "# Todo\n- [ ] one\n  - [x] two\nplain\n",
+            toggle_task_transaction(&text, 1).unwrap(),
+        );
+        assert_eq!(checked, "# Todo\n- [x] one\n  - [x] two\nplain\n");
+        let unchecked = apply(
+            "# Todo\n- [ ] one\n  - [x] two\nplain\n",
+            toggle_task_transaction(&text, 2).unwrap(),
+        );
+        assert_eq!(unchecked, "# Todo\n- [ ] one\n  - [ ] two\nplain\n");
+
+        assert!(toggle_task_transaction(&text, 3).is_none());
+        assert!(toggle_task_transaction(&text, 99).is_none());
+    }
+
+    #[test]
+    fn completed_items_move_to_a_new_done_section() {
+        let text = "# Todo\n\n- [x] shipped\n  with notes\n- [ ] pending\n- [X] fixed\n";
+        let (transaction, moved) = archive_completed_tasks(&Rope::from(text)).unwrap();
+
+        assert_eq!(moved, 2);
+        assert_eq!(
+            apply(text, transaction),
+            "# Todo\n\n- [ ] pending\n\n## Done\n\n- [x] shipped\n  with notes\n- [X] fixed\n"
+        );
+    }
+
+    #[test]
+    fn completed_items_join_an_existing_done_section() {
+        let text = "## Todo\n- [x] a\n- [ ] b\n\n## Done\n\n- [x] old\n\n## Notes\n- [x] c\n```\n- [x] code\n```\n";
+        let (transaction, moved) = archive_completed_tasks(&Rope::from(text)).unwrap();
+
+        assert_eq!(moved, 2);
+        assert_eq!(
+            apply(text, transaction),
+            "## Todo\n- [ ] b\n\n## Done\n\n- [x] old\n- [x] a\n- [x] c\n\n## Notes\n```\n- [x] code\n```\n"
+        );
+    }
+
+    #[test]
+    fn archiving_without_completed_items_does_nothing() {
+        assert!(archive_completed_tasks(&Rope::from("- [ ] open\n## Done\n- [x] old\n")).is_none());
+        assert!(archive_completed_tasks(&Rope::from("")).is_none());
+    }
+}
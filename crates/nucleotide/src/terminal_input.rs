@@ -1,6 +1,8 @@
 // ABOUTME: App-level terminal key encoder wrapper
 // ABOUTME: Keeps terminal input call sites on the shared UI encoder
 
+use std::sync::Arc;
+
 use gpui::KeyDownEvent;
 use nucleotide_events::v2::terminal::{Event as TerminalEvent, TerminalId};
 
@@ -38,6 +40,96 @@ pub fn encode_key_event_for_terminal(id: TerminalId, event: &KeyDownEvent) -> Ve
     encode_key_event_with_mode(event, mode)
 }
 
+/// Handles the scrollback keys (shift+page up/down/home/end) for a terminal.
+/// Returns `true` when the key scrolled history and must not reach the PTY.
+#[cfg(feature = "terminal-emulator-core")]
+pub fn scroll_terminal_for_key(id: TerminalId, event: &KeyDownEvent) -> bool {
+    nucleotide_terminal_view::get_view_model(id)
+        .and_then(|vm| {
+            vm.lock()
+                .ok()
+                .map(|mut guard| guard.scroll_for_keystroke(&event.keystroke))
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(not(feature = "terminal-emulator-core"))]
+pub fn scroll_terminal_for_key(_id: TerminalId, _event: &KeyDownEvent) -> bool {
+    false
+}
+
+/// Whether the terminal has a mouse selection to copy.
+#[cfg(feature = "terminal-emulator-core")]
+pub fn terminal_has_selection(id: TerminalId) -> bool {
+    nucleotide_terminal_view::get_view_model(id)
+        .and_then(|vm| vm.lock().ok().map(|guard| guard.selected_text().is_some()))
+        .unwrap_or(false)
+}
+
+#[cfg(not(feature = "terminal-emulator-core"))]
+pub fn terminal_has_selection(_id: TerminalId) -> bool {
+    false
+}
+
+/// Whether the terminal's program asked for bracketed paste.
+#[cfg(feature = "terminal-emulator-core")]
+fn terminal_bracketed_paste(id: TerminalId) -> bool {
+    nucleotide_terminal_view::get_view_model(id)
+        .and_then(|vm| {
+            vm.lock()
+                .ok()
+                .map(|guard| guard.input_mode().bracketed_paste)
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(not(feature = "terminal-emulator-core"))]
+fn terminal_bracketed_paste(_id: TerminalId) -> bool {
+    false
+}
+
+/// Picker payload for a multi-line paste waiting for confirmation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminalPasteChoice {
+    pub id: TerminalId,
+    pub text: Arc<str>,
+    pub confirmed: bool,
+}
+
+/// The first line of a paste, shortened for the confirmation picker.
+pub fn paste_preview(text: &str) -> String {
+    const MAX_CHARS: usize = 80;
+    let first_line = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("");
+    let first_line = first_line.trim();
+    if first_line.chars().count() > MAX_CHARS {
+        let shortened = first_line.chars().take(MAX_CHARS).collect::<String>();
+        format!("{shortened}…")
+    } else {
+        first_line.to_string()
+    }
+}
+
+/// Pastes `text` into a terminal, bracketed when its program asked for it.
+pub fn paste_terminal_text<C: gpui::AppContext>(
+    core: &gpui::Entity<crate::Core>,
+    id: TerminalId,
+    text: &str,
+    cx: &mut C,
+) -> bool {
+    if text.is_empty() {
+        return false;
+    }
+
+    #[cfg(feature = "terminal-emulator-core")]
+    scroll_terminal_to_bottom(id);
+
+    let bracketed = terminal_bracketed_paste(id);
+    core.read_with(cx, |app, _| app.terminal_runtime.paste(id, text, bracketed))
+}
+
 #[cfg(feature = "terminal-emulator-core")]
 fn scroll_terminal_to_bottom(id: TerminalId) {
     if let Some(vm) = nucleotide_terminal_view::get_view_model(id)
@@ -93,10 +93,9 @@ pub mod test_support {
                     BridgedEvent::DocumentOpened { doc_id } => {
                         TestUpdate::DocumentOpened { doc_id }
                     }
-                    BridgedEvent::DocumentClosed {
-                        doc_id,
-                        was_modified: _,
-                    } => TestUpdate::DocumentClosed { doc_id },
+                    BridgedEvent::DocumentClosed { doc_id, .. } => {
+                        TestUpdate::DocumentClosed { doc_id }
+                    }
                     BridgedEvent::LanguageServerInitialized { server_id } => {
                         TestUpdate::LanguageServerInitialized {
                             server_id,
@@ -471,6 +471,7 @@ dark_theme = "custom_dark"
                     size: 16.0,
                     line_height: 1.5,
                 }),
+                scroll: Default::default(),
             },
             theme: ThemeConfig {
                 mode: ThemeMode::Light,
@@ -490,8 +491,19 @@ dark_theme = "custom_dark"
             lsp: LspConfig::default(),
             project_markers: ProjectMarkersConfig::default(),
             file_ops: FileOpsConfig::default(),
+            terminal: crate::config::TerminalConfig::default(),
+            export: crate::config::ExportConfig::default(),
+            file_associations: Vec::new(),
+            file_templates: Vec::new(),
+            project_templates: Vec::new(),
             remote: crate::config::RemoteConfig::default(),
             updates: crate::config::UpdatesConfig::default(),
+            usage: crate::config::UsageConfig::default(),
+            references: crate::config::ReferencesConfig::default(),
+            scratch: crate::config::ScratchConfig::default(),
+            content_index: crate::config::ContentIndexConfig::default(),
+            repl: Default::default(),
+            run_configurations: Vec::new(),
         };
 
         // Serialize to TOML
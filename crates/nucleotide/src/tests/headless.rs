@@ -0,0 +1,405 @@
+// ABOUTME: Headless harness that boots Application and Workspace in an offscreen GPUI test window
+// ABOUTME: Injects key sequences and exposes document and terminal state for end-to-end input tests
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use gpui::{AppContext, Entity, TestAppContext, VisualTestContext};
+use helix_view::document::Mode;
+use helix_view::input::KeyEvent;
+
+use crate::application::tests::{TEST_RUNTIME, new_test_application};
+use crate::input_coordinator::InputCoordinator;
+use crate::types::{EditorFontConfig, FontSettings, UiFontConfig};
+use crate::workspace::Workspace;
+use crate::{Application, Input, InputEvent, Update};
+
+/// How long [`HeadlessEditor::open_file`] waits for the document to load.
+const OPEN_FILE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long terminal helpers wait for the session to start or draw.
+#[cfg(feature = "terminal-emulator-core")]
+const TERMINAL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Application and Workspace wired together the way `main` does, inside a
+/// GPUI test window that is never shown.
+///
+/// Keys can be sent either as Helix key sequences straight to the
+/// application ([`Self::type_keys`]) or as GPUI keystrokes dispatched to the
+/// window ([`Self::simulate_keystrokes`]), which also exercises the
+/// workspace key handler and the input coordinator.
+pub(crate) struct HeadlessEditor {
+    pub(crate) cx: VisualTestContext,
+    pub(crate) core: Entity<Application>,
+    pub(crate) workspace: Entity<Workspace>,
+    input: Entity<Input>,
+}
+
+impl HeadlessEditor {
+    pub(crate) fn new(cx: &mut TestAppContext) -> Self {
+        cx.executor().allow_parking();
+        let core = new_test_application(cx);
+        cx.update(|cx| init_globals(&core, cx));
+
+        let input = cx.new(|_| Input);
+        core.update(cx, |_, cx| {
+            cx.subscribe(
+                &input,
+                |app: &mut Application, _, event: &InputEvent, cx| {
+                    app.handle_input_event(event.clone(), cx, TEST_RUNTIME.handle().clone());
+                },
+            )
+            .detach();
+        });
+
+        let window = cx.add_window({
+            let core = core.clone();
+            let input = input.clone();
+            move |_window, cx| {
+                let handle = TEST_RUNTIME.handle().clone();
+                let overlay = cx.new(|cx| {
+                    let view =
+                        crate::overlay::OverlayView::new(&cx.focus_handle(), &core, handle.clone());
+                    view.subscribe(&core, cx);
+                    view
+                });
+                let notifications = cx.new(|_| crate::notification::NotificationView::new());
+                let info = cx.new(|_| crate::info_box::InfoBoxView::new());
+                let update_controller = cx
+                    .global::<crate::updates::UpdateControllerHandle>()
+                    .0
+                    .clone();
+
+                let workspace = Workspace::with_views(
+                    core,
+                    input,
+                    handle,
+                    overlay,
+                    notifications,
+                    info,
+                    Arc::new(InputCoordinator::new()),
+                    update_controller,
+                    cx,
+                );
+                cx.subscribe(&cx.entity(), |workspace: &mut Workspace, _, event, cx| {
+                    workspace.handle_event(event, cx);
+                })
+                .detach();
+                workspace
+            }
+        });
+
+        let workspace = window.root(cx).expect("headless workspace window");
+        let mut editor = Self {
+            cx: VisualTestContext::from_window(*window, cx),
+            core,
+            workspace,
+            input,
+        };
+        editor.settle();
+        editor
+    }
+
+    /// Opens `path` the way the file tree does and waits until its view has focus.
+    pub(crate) fn open_file(&mut self, path: &Path) {
+        let path = path.to_path_buf();
+        self.workspace.update(&mut self.cx, |workspace, cx| {
+            workspace.handle_event(&Update::OpenFile(path.clone()), cx);
+        });
+
+        let deadline = std::time::Instant::now() + OPEN_FILE_TIMEOUT;
+        while !self
+            .focused_document_path()
+            .is_some_and(|open| open == path)
+        {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "timed out opening {}",
+                path.display()
+            );
+            // File reads run on the tokio runtime; let them make progress.
+            TEST_RUNTIME.block_on(tokio::time::sleep(Duration::from_millis(5)));
+            self.settle();
+        }
+        self.focus_document();
+    }
+
+    /// Sends Helix key notation such as `ihello<esc>` to the application,
+    /// as a keyboard macro would.
+    pub(crate) fn type_keys(&mut self, keys: &str) {
+        let keys = helix_view::input::parse_macro(keys).expect("valid Helix key sequence");
+        for key in keys {
+            self.send_key(key);
+        }
+    }
+
+    pub(crate) fn send_key(&mut self, key: KeyEvent) {
+        self.input.update(&mut self.cx, |_, cx| {
+            cx.emit(InputEvent::key(key));
+        });
+        self.settle();
+    }
+
+    /// Dispatches GPUI keystrokes such as `i a escape` to the focused element.
+    pub(crate) fn simulate_keystrokes(&mut self, keystrokes: &str) {
+        self.cx.simulate_keystrokes(keystrokes);
+        self.settle();
+    }
+
+    /// Runs pending work and draws a frame so views catch up with the editor.
+    pub(crate) fn settle(&mut self) {
+        self.cx.run_until_parked();
+        self.cx.update(|window, cx| window.draw(cx).clear());
+        self.cx.run_until_parked();
+    }
+
+    fn focus_document(&mut self) {
+        let workspace = self.workspace.clone();
+        self.cx.update(|window, cx| {
+            workspace.update(cx, |workspace, cx| workspace.focus_editor(window, cx));
+        });
+        self.settle();
+    }
+
+    pub(crate) fn document_text(&self) -> String {
+        self.core.read_with(&self.cx, |app, _| {
+            let (_, doc) = helix_view::current_ref!(app.editor);
+            doc.text().to_string()
+        })
+    }
+
+    pub(crate) fn focused_document_path(&self) -> Option<std::path::PathBuf> {
+        self.core.read_with(&self.cx, |app, _| {
+            let (_, doc) = helix_view::current_ref!(app.editor);
+            doc.path().cloned()
+        })
+    }
+
+    /// Char index of the primary cursor in the focused document.
+    pub(crate) fn cursor(&self) -> usize {
+        self.core.read_with(&self.cx, |app, _| {
+            let (view, doc) = helix_view::current_ref!(app.editor);
+            doc.selection(view.id)
+                .primary()
+                .cursor(doc.text().slice(..))
+        })
+    }
+
+    pub(crate) fn mode(&self) -> Mode {
+        self.core.read_with(&self.cx, |app, _| app.editor.mode())
+    }
+
+    /// Runs `program` in the embedded terminal panel.
+    #[cfg(feature = "terminal-emulator-core")]
+    pub(crate) fn open_terminal_command(&mut self, program: &str, args: &[&str]) {
+        let program = program.to_string();
+        let args = args.iter().map(|arg| arg.to_string()).collect();
+        self.workspace.update(&mut self.cx, |workspace, cx| {
+            workspace.open_terminal_command(program, args, cx);
+        });
+        self.settle();
+    }
+
+    /// Writes `bytes` to the embedded terminal's session once it is running.
+    #[cfg(feature = "terminal-emulator-core")]
+    pub(crate) fn write_terminal(&mut self, bytes: &[u8]) {
+        let id = self
+            .workspace
+            .read_with(&self.cx, |workspace, _| workspace.terminal_id())
+            .expect("an open terminal");
+
+        let deadline = std::time::Instant::now() + TERMINAL_TIMEOUT;
+        // Sessions spawn on the tokio runtime; input has nowhere to go until then.
+        while !self.core.read_with(&self.cx, |app, _| {
+            app.terminal_runtime.send_input(id, bytes.to_vec())
+        }) {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "timed out waiting for terminal {id:?} to start"
+            );
+            TEST_RUNTIME.block_on(tokio::time::sleep(Duration::from_millis(5)));
+            self.settle();
+        }
+        self.settle();
+    }
+
+    /// Waits until the terminal's visible rows satisfy `done` and returns them.
+    #[cfg(feature = "terminal-emulator-core")]
+    pub(crate) fn wait_for_terminal_lines(
+        &mut self,
+        done: impl Fn(&[String]) -> bool,
+    ) -> Vec<String> {
+        let deadline = std::time::Instant::now() + TERMINAL_TIMEOUT;
+        loop {
+            let lines = self.terminal_lines().unwrap_or_default();
+            if done(&lines) {
+                return lines;
+            }
+            assert!(
+                std::time::Instant::now() < deadline,
+                "timed out waiting for terminal output; showing {lines:?}"
+            );
+            TEST_RUNTIME.block_on(tokio::time::sleep(Duration::from_millis(10)));
+            self.settle();
+        }
+    }
+
+    /// Visible rows of the embedded terminal, without trailing blank rows.
+    #[cfg(feature = "terminal-emulator-core")]
+    pub(crate) fn terminal_lines(&mut self) -> Option<Vec<String>> {
+        use nucleotide_ui::ThemedContext as _;
+
+        let id = self
+            .workspace
+            .read_with(&self.cx, |workspace, _| workspace.terminal_id())?;
+        let model = nucleotide_terminal_view::get_view_model(id)?;
+        let tokens = self.cx.update(|_, cx| cx.theme().tokens);
+        let grid = model
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .styled_grid(&tokens);
+
+        let mut lines: Vec<String> = grid
+            .rows
+            .into_iter()
+            .map(|spans| {
+                spans
+                    .into_iter()
+                    .map(|span| span.text)
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        while lines.last().is_some_and(String::is_empty) {
+            lines.pop();
+        }
+        Some(lines)
+    }
+}
+
+/// The globals `main` installs before creating the workspace.
+fn init_globals(core: &Entity<Application>, cx: &mut gpui::App) {
+    nucleotide_ui::init(cx, None);
+    crate::overlay::init(cx);
+    crate::file_tree::init(cx);
+    nucleotide_appearance::SystemAppearance::init(cx);
+
+    let (config, helix_theme, syntax_loader) = {
+        let app = core.read(cx);
+        (
+            app.config.clone(),
+            app.editor.theme.clone(),
+            app.editor.syn_loader.load_full(),
+        )
+    };
+
+    let mut theme_manager =
+        crate::ThemeManager::new_with_chrome_style(helix_theme, config.ui_chrome_style());
+    theme_manager.set_system_appearance(nucleotide_appearance::SystemAppearance::global(cx));
+    cx.set_global(theme_manager.ui_theme().clone());
+    cx.set_global(theme_manager);
+    cx.set_global(nucleotide_ui::markdown::MarkdownSyntaxLoader::new(
+        syntax_loader,
+    ));
+    cx.set_global(nucleotide_ui::FocusCoordinator::default());
+
+    let vcs_service =
+        nucleotide_vcs::VcsServiceHandle::new(nucleotide_vcs::VcsConfig::default(), cx);
+    cx.set_global(vcs_service);
+
+    let editor_font = config.editor_font();
+    let ui_font = config.ui_font();
+    cx.set_global(FontSettings {
+        fixed_font: nucleotide_types::Font {
+            family: editor_font.family.clone(),
+            weight: editor_font.weight,
+            style: nucleotide_types::FontStyle::Normal,
+        },
+        var_font: nucleotide_types::Font {
+            family: ui_font.family.clone(),
+            weight: ui_font.weight,
+            style: nucleotide_types::FontStyle::Normal,
+        },
+    });
+    cx.set_global(EditorFontConfig {
+        family: editor_font.family,
+        size: editor_font.size,
+        weight: editor_font.weight,
+        line_height: editor_font.line_height,
+    });
+    cx.set_global(UiFontConfig {
+        family: ui_font.family,
+        size: ui_font.size,
+        weight: ui_font.weight,
+    });
+    cx.set_global(nucleotide_core::preview_tracker::PreviewTracker::new());
+
+    // Never check for updates from tests.
+    let updates = crate::config::UpdatesConfig {
+        enabled: false,
+        ..Default::default()
+    };
+    let update_controller = cx.new(|cx| crate::updates::UpdateController::new(updates, cx));
+    cx.set_global(crate::updates::UpdateControllerHandle(update_controller));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project_with_file(text: &str) -> (tempfile::TempDir, std::path::PathBuf) {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("notes.txt");
+        std::fs::write(&path, text).unwrap();
+        (temp, path)
+    }
+
+    #[gpui::test]
+    fn helix_key_sequences_edit_the_focused_document(cx: &mut TestAppContext) {
+        let (_temp, path) = project_with_file("world\n");
+        let mut editor = HeadlessEditor::new(cx);
+        editor.open_file(&path);
+
+        editor.type_keys("ihello <esc>");
+        assert_eq!(editor.document_text(), "hello world\n");
+        assert_eq!(editor.mode(), Mode::Normal);
+
+        editor.type_keys("gl");
+        assert_eq!(editor.cursor(), "hello world".len() - 1);
+
+        editor.type_keys("xd");
+        assert_eq!(editor.document_text(), "");
+    }
+
+    #[gpui::test]
+    fn window_keystrokes_reach_helix_through_the_input_coordinator(cx: &mut TestAppContext) {
+        let (_temp, path) = project_with_file("world\n");
+        let mut editor = HeadlessEditor::new(cx);
+        editor.open_file(&path);
+
+        editor.simulate_keystrokes("i");
+        assert_eq!(editor.mode(), Mode::Insert);
+
+        editor.simulate_keystrokes("a b escape");
+        assert_eq!(editor.document_text(), "abworld\n");
+        assert_eq!(editor.mode(), Mode::Normal);
+    }
+
+    #[cfg(all(unix, feature = "terminal-emulator-core"))]
+    #[gpui::test]
+    fn terminal_shows_input_written_to_its_session(cx: &mut TestAppContext) {
+        let mut editor = HeadlessEditor::new(cx);
+        editor.open_terminal_command("cat", &[]);
+
+        editor.write_terminal(b"hello\r");
+        // The PTY echoes the typed line, then `cat` prints it back.
+        let lines = editor.wait_for_terminal_lines(|lines| lines.len() >= 2);
+        assert_eq!(lines, ["hello", "hello"]);
+
+        editor
+            .core
+            .read_with(&editor.cx, |app, _| app.terminal_runtime.shutdown_all());
+    }
+}
@@ -3,6 +3,7 @@
 
 pub mod config_system_tests;
 pub mod environment_system_tests;
+pub mod headless;
 pub mod integration_tests;
 pub mod lsp_environment_integration_test;
 pub mod lsp_integration_tests;
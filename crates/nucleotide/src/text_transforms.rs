@@ -0,0 +1,266 @@
+// ABOUTME: Case and identifier-style transforms of the selected text, such as snake_case or Title Case
+// ABOUTME: Applies one transform to every selection of a document in a single transaction
+
+use helix_core::{Rope, Selection, Tendril, Transaction};
+
+/// A transform of the selected text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextTransform {
+    UpperCase,
+    LowerCase,
+    TitleCase,
+    CamelCase,
+    SnakeCase,
+    KebabCase,
+    PascalCase,
+}
+
+impl TextTransform {
+    pub const ALL: [TextTransform; 7] = [
+        TextTransform::UpperCase,
+        TextTransform::LowerCase,
+        TextTransform::TitleCase,
+        TextTransform::CamelCase,
+        TextTransform::SnakeCase,
+        TextTransform::KebabCase,
+        TextTransform::PascalCase,
+    ];
+
+    /// Prompt command applying the transform.
+    pub fn command(self) -> &'static str {
+        match self {
+            TextTransform::UpperCase => "transform-upper-case",
+            TextTransform::LowerCase => "transform-lower-case",
+            TextTransform::TitleCase => "transform-title-case",
+            TextTransform::CamelCase => "transform-camel-case",
+            TextTransform::SnakeCase => "transform-snake-case",
+            TextTransform::KebabCase => "transform-kebab-case",
+            TextTransform::PascalCase => "transform-pascal-case",
+        }
+    }
+
+    pub fn from_command(command: &str) -> Option<Self> {
+        let command = command.trim().trim_start_matches(':');
+        Self::ALL
+            .into_iter()
+            .find(|transform| transform.command() == command)
+    }
+
+    /// `text` transformed. Identifier styles treat the text, without its
+    /// surrounding whitespace, as one identifier.
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            TextTransform::UpperCase => text.to_uppercase(),
+            TextTransform::LowerCase => text.to_lowercase(),
+            TextTransform::TitleCase => title_case(text),
+            TextTransform::CamelCase
+            | TextTransform::SnakeCase
+            | TextTransform::KebabCase
+            | TextTransform::PascalCase => {
+                let trimmed = text.trim();
+                if trimmed.is_empty() {
+                    return text.to_string();
+                }
+                let start = text.len() - text.trim_start().len();
+                let words = identifier_words(trimmed);
+                let identifier = match self {
+                    TextTransform::SnakeCase => join_lowercase(&words, "_"),
+                    TextTransform::KebabCase => join_lowercase(&words, "-"),
+                    TextTransform::PascalCase => {
+                        words.iter().map(|word| capitalize(word)).collect()
+                    }
+                    _ => words
+                        .iter()
+                        .enumerate()
+                        .map(|(index, word)| match index {
+                            0 => word.to_lowercase(),
+                            _ => capitalize(word),
+                        })
+                        .collect(),
+                };
+                format!(
+                    "{}{identifier}{}",
+                    &text[..start],
+                    &text[start + trimmed.len()..]
+                )
+            }
+        }
+    }
+}
+
+/// Prompt commands for the transforms, with completion descriptions.
+pub const TEXT_TRANSFORM_COMMANDS: &[(&str, &str)] = &[
+    (
+        "transform-upper-case",
+        "Convert the selections to UPPER CASE",
+    ),
+    (
+        "transform-lower-case",
+        "Convert the selections to lower case",
+    ),
+    (
+        "transform-title-case",
+        "Convert the selections to Title Case",
+    ),
+    (
+        "transform-camel-case",
+        "Convert the selections to camelCase",
+    ),
+    (
+        "transform-snake-case",
+        "Convert the selections to snake_case",
+    ),
+    (
+        "transform-kebab-case",
+        "Convert the selections to kebab-case",
+    ),
+    (
+        "transform-pascal-case",
+        "Convert the selections to PascalCase",
+    ),
+];
+
+/// `word` with its first character upper case and the rest lower case.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+fn title_case(text: &str) -> String {
+    let mut title = String::with_capacity(text.len());
+    let mut word_start = true;
+    for ch in text.chars() {
+        if ch.is_alphanumeric() {
+            if word_start {
+                title.extend(ch.to_uppercase());
+            } else {
+                title.extend(ch.to_lowercase());
+            }
+            word_start = false;
+        } else {
+            title.push(ch);
+            word_start = ch.is_whitespace() || ch == '-' || ch == '_';
+        }
+    }
+    title
+}
+
+fn join_lowercase(words: &[String], separator: &str) -> String {
+    words
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Words of an identifier in any style, split at separators and at case
+/// changes: `parseHTTPResponse2` gives `parse`, `HTTP` and `Response2`.
+fn identifier_words(identifier: &str) -> Vec<String> {
+    let chars = identifier.chars().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (index, &ch) in chars.iter().enumerate() {
+        if !ch.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        let previous = index.checked_sub(1).map(|index| chars[index]);
+        let next = chars.get(index + 1).copied();
+        let boundary = ch.is_uppercase()
+            && previous.is_some_and(|previous| {
+                previous.is_lowercase()
+                    || previous.is_ascii_digit()
+                    || (previous.is_uppercase() && next.is_some_and(char::is_lowercase))
+            });
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(ch);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Applies `transform` to the text of every selection, keeping each range
+/// over its transformed text. Returns `None` when nothing changes.
+pub fn transform_transaction(
+    text: &Rope,
+    selection: &Selection,
+    transform: TextTransform,
+) -> Option<Transaction> {
+    let mut changed = false;
+    let transaction = Transaction::change_by_selection(text, selection, |range| {
+        let original = range.fragment(text.slice(..));
+        let transformed = transform.apply(&original);
+        changed |= transformed != original;
+        (range.from(), range.to(), Some(Tendril::from(transformed)))
+    });
+    changed.then_some(transaction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helix_core::Range;
+
+    #[test]
+    fn identifiers_convert_between_styles() {
+        let cases = [
+            (
+                TextTransform::SnakeCase,
+                "parseHTTPResponse2",
+                "parse_http_response2",
+            ),
+            (TextTransform::KebabCase, "user_id", "user-id"),
+            (TextTransform::CamelCase, "kebab-case-name", "kebabCaseName"),
+            (TextTransform::PascalCase, " snake_case ", " SnakeCase "),
+            (
+                TextTransform::TitleCase,
+                "the quick-brown FOX",
+                "The Quick-Brown Fox",
+            ),
+            (TextTransform::UpperCase, "mixed Case", "MIXED CASE"),
+        ];
+        for (transform, text, expected) in cases {
+            assert_eq!(transform.apply(text), expected, "{transform:?} of {text}");
+        }
+        assert_eq!(
+            TextTransform::from_command(":transform-kebab-case"),
+            Some(TextTransform::KebabCase)
+        );
+        for transform in TextTransform::ALL {
+            assert!(
+                TEXT_TRANSFORM_COMMANDS
+                    .iter()
+                    .any(|(name, _)| *name == transform.command())
+            );
+        }
+    }
+
+    #[test]
+    fn every_selection_is_transformed() {
+        let mut text = Rope::from("fooBar bazQux");
+        let selection = Selection::new(
+            [Range::new(0, 6), Range::new(7, 13)].into_iter().collect(),
+            0,
+        );
+        let transaction =
+            transform_transaction(&text, &selection, TextTransform::SnakeCase).unwrap();
+        assert!(transaction.apply(&mut text));
+        assert_eq!(text, "foo_bar baz_qux");
+        assert!(
+            transform_transaction(&text, &Selection::single(0, 3), TextTransform::LowerCase)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,448 @@
+// ABOUTME: Type hierarchy panel state: supertypes and subtypes trees of the type under the cursor
+// ABOUTME: Children are requested lazily as nodes expand; keyboard moves select, expand and collapse
+
+use helix_lsp::{LanguageServerId, lsp};
+
+use crate::types::LspLocation;
+
+/// Command opening the type hierarchy of the symbol under the cursor.
+pub const TYPE_HIERARCHY_COMMAND: &str = "type-hierarchy";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeHierarchyDirection {
+    Supertypes,
+    Subtypes,
+}
+
+impl TypeHierarchyDirection {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Supertypes => "Supertypes",
+            Self::Subtypes => "Subtypes",
+        }
+    }
+}
+
+/// A type reported by the language server.
+#[derive(Debug, Clone)]
+pub struct TypeHierarchyEntry {
+    pub item: lsp::TypeHierarchyItem,
+    /// Where the type is declared, when its URI is a file
+    pub location: Option<LspLocation>,
+}
+
+/// Language server answers for the type hierarchy panel, tagged with the
+/// request generation so answers for a replaced panel are dropped.
+#[derive(Debug, Clone)]
+pub enum TypeHierarchyUpdate {
+    Prepared {
+        generation: u64,
+        root: Result<(LanguageServerId, TypeHierarchyEntry), String>,
+    },
+    Resolved {
+        generation: u64,
+        direction: TypeHierarchyDirection,
+        node: usize,
+        children: Result<Vec<TypeHierarchyEntry>, String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeHierarchyChildren {
+    /// Not requested yet
+    Unresolved,
+    Loading,
+    Resolved(Vec<usize>),
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct TypeHierarchyNode {
+    pub entry: TypeHierarchyEntry,
+    pub depth: usize,
+    pub parent: Option<usize>,
+    pub children: TypeHierarchyChildren,
+    pub expanded: bool,
+}
+
+impl TypeHierarchyNode {
+    /// Whether the node may have children; unresolved nodes are assumed to.
+    pub fn is_expandable(&self) -> bool {
+        !matches!(&self.children, TypeHierarchyChildren::Resolved(children) if children.is_empty())
+    }
+}
+
+/// One direction of the hierarchy, rooted at the prepared type. Nodes are
+/// kept in the order they were resolved; the root is node 0.
+#[derive(Debug, Clone)]
+pub struct TypeHierarchyTree {
+    nodes: Vec<TypeHierarchyNode>,
+    selected: usize,
+}
+
+impl TypeHierarchyTree {
+    pub fn new(root: TypeHierarchyEntry) -> Self {
+        Self {
+            nodes: vec![TypeHierarchyNode {
+                entry: root,
+                depth: 0,
+                parent: None,
+                children: TypeHierarchyChildren::Unresolved,
+                expanded: false,
+            }],
+            selected: 0,
+        }
+    }
+
+    pub fn node(&self, index: usize) -> Option<&TypeHierarchyNode> {
+        self.nodes.get(index)
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index < self.nodes.len() {
+            self.selected = index;
+        }
+    }
+
+    /// Nodes shown in the panel, in tree order.
+    pub fn visible(&self) -> Vec<usize> {
+        let mut visible = Vec::new();
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            visible.push(index);
+            let node = &self.nodes[index];
+            if let (true, TypeHierarchyChildren::Resolved(children)) =
+                (node.expanded, &node.children)
+            {
+                stack.extend(children.iter().rev());
+            }
+        }
+        visible
+    }
+
+    /// Expand `index`, returning true when its children have to be requested.
+    pub fn expand(&mut self, index: usize) -> bool {
+        let Some(node) = self.nodes.get_mut(index) else {
+            return false;
+        };
+        node.expanded = true;
+        match node.children {
+            TypeHierarchyChildren::Unresolved | TypeHierarchyChildren::Failed(_) => {
+                node.children = TypeHierarchyChildren::Loading;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn collapse(&mut self, index: usize) {
+        if let Some(node) = self.nodes.get_mut(index) {
+            node.expanded = false;
+        }
+        // Keep the selection visible.
+        if self
+            .ancestors(self.selected)
+            .any(|ancestor| ancestor == index)
+        {
+            self.selected = index;
+        }
+    }
+
+    /// Toggle `index`, returning true when its children have to be requested.
+    pub fn toggle(&mut self, index: usize) -> bool {
+        match self.nodes.get(index) {
+            Some(node) if node.expanded => {
+                self.collapse(index);
+                false
+            }
+            Some(_) => self.expand(index),
+            None => false,
+        }
+    }
+
+    /// Store the answer to the children request of `index`.
+    pub fn set_children(
+        &mut self,
+        index: usize,
+        children: Result<Vec<TypeHierarchyEntry>, String>,
+    ) {
+        let Some(node) = self.nodes.get(index) else {
+            return;
+        };
+        if node.children != TypeHierarchyChildren::Loading {
+            return;
+        }
+        let depth = node.depth + 1;
+        let children = match children {
+            Ok(entries) => {
+                let first = self.nodes.len();
+                self.nodes
+                    .extend(entries.into_iter().map(|entry| TypeHierarchyNode {
+                        entry,
+                        depth,
+                        parent: Some(index),
+                        children: TypeHierarchyChildren::Unresolved,
+                        expanded: false,
+                    }));
+                TypeHierarchyChildren::Resolved((first..self.nodes.len()).collect())
+            }
+            Err(error) => TypeHierarchyChildren::Failed(error),
+        };
+        self.nodes[index].children = children;
+    }
+
+    /// Move the selection `delta` rows through the visible nodes.
+    pub fn move_selection(&mut self, delta: isize) {
+        let visible = self.visible();
+        let current = visible
+            .iter()
+            .position(|&index| index == self.selected)
+            .unwrap_or(0);
+        let target = current
+            .saturating_add_signed(delta)
+            .min(visible.len().saturating_sub(1));
+        self.selected = visible[target];
+    }
+
+    /// Expand the selection, or step into its first child when it is already
+    /// expanded. Returns the node whose children have to be requested.
+    pub fn expand_selected(&mut self) -> Option<usize> {
+        let selected = self.selected;
+        let node = &self.nodes[selected];
+        if node.expanded {
+            if let TypeHierarchyChildren::Resolved(children) = &node.children
+                && let Some(&first) = children.first()
+            {
+                self.selected = first;
+            }
+            return None;
+        }
+        if !node.is_expandable() {
+            return None;
+        }
+        self.expand(selected).then_some(selected)
+    }
+
+    /// Collapse the selection, or select its parent when it is collapsed.
+    pub fn collapse_selected(&mut self) {
+        let node = &self.nodes[self.selected];
+        if node.expanded && node.is_expandable() {
+            self.collapse(self.selected);
+        } else if let Some(parent) = node.parent {
+            self.selected = parent;
+        }
+    }
+
+    fn ancestors(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(
+            self.nodes.get(index).and_then(|node| node.parent),
+            |&parent| self.nodes[parent].parent,
+        )
+    }
+}
+
+/// State of the type hierarchy panel.
+#[derive(Debug, Clone)]
+pub struct TypeHierarchyPanel {
+    /// Name of the prepared type; empty until the server answers
+    pub symbol: String,
+    pub direction: TypeHierarchyDirection,
+    pub server_id: Option<LanguageServerId>,
+    pub supertypes: Option<TypeHierarchyTree>,
+    pub subtypes: Option<TypeHierarchyTree>,
+    pub loading: bool,
+    pub error: Option<String>,
+}
+
+impl TypeHierarchyPanel {
+    pub fn new(direction: TypeHierarchyDirection) -> Self {
+        Self {
+            symbol: String::new(),
+            direction,
+            server_id: None,
+            supertypes: None,
+            subtypes: None,
+            loading: true,
+            error: None,
+        }
+    }
+
+    /// Show the prepared type. Returns the node of the open tab whose
+    /// children have to be requested.
+    pub fn set_root(
+        &mut self,
+        root: Result<(LanguageServerId, TypeHierarchyEntry), String>,
+    ) -> Option<usize> {
+        self.loading = false;
+        match root {
+            Ok((server_id, entry)) => {
+                self.symbol = entry.item.name.clone();
+                self.server_id = Some(server_id);
+                self.supertypes = Some(TypeHierarchyTree::new(entry.clone()));
+                self.subtypes = Some(TypeHierarchyTree::new(entry));
+                self.tree_mut()?.expand(0).then_some(0)
+            }
+            Err(error) => {
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+
+    pub fn tree(&self) -> Option<&TypeHierarchyTree> {
+        match self.direction {
+            TypeHierarchyDirection::Supertypes => self.supertypes.as_ref(),
+            TypeHierarchyDirection::Subtypes => self.subtypes.as_ref(),
+        }
+    }
+
+    pub fn tree_mut(&mut self) -> Option<&mut TypeHierarchyTree> {
+        self.tree_for(self.direction)
+    }
+
+    pub fn tree_for(
+        &mut self,
+        direction: TypeHierarchyDirection,
+    ) -> Option<&mut TypeHierarchyTree> {
+        match direction {
+            TypeHierarchyDirection::Supertypes => self.supertypes.as_mut(),
+            TypeHierarchyDirection::Subtypes => self.subtypes.as_mut(),
+        }
+    }
+
+    /// Switch tabs, expanding the root the first time a tab is shown.
+    /// Returns the node whose children have to be requested.
+    pub fn set_direction(&mut self, direction: TypeHierarchyDirection) -> Option<usize> {
+        self.direction = direction;
+        let tree = self.tree_mut()?;
+        let root = tree.node(0)?;
+        (root.children == TypeHierarchyChildren::Unresolved && tree.expand(0)).then_some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str) -> TypeHierarchyEntry {
+        let range = lsp::Range::default();
+        TypeHierarchyEntry {
+            item: lsp::TypeHierarchyItem {
+                name: name.to_string(),
+                kind: lsp::SymbolKind::CLASS,
+                tags: None,
+                detail: None,
+                uri: lsp::Url::parse("file:///project/src/lib.rs").unwrap(),
+                range,
+                selection_range: range,
+                data: None,
+            },
+            location: None,
+        }
+    }
+
+    fn names(tree: &TypeHierarchyTree) -> Vec<String> {
+        tree.visible()
+            .into_iter()
+            .map(|index| tree.node(index).unwrap().entry.item.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn children_are_requested_once_when_expanded() {
+        let mut tree = TypeHierarchyTree::new(entry("Shape"));
+        assert!(tree.expand(0));
+        assert!(!tree.expand(0));
+        assert_eq!(names(&tree), vec!["Shape"]);
+
+        tree.set_children(0, Ok(vec![entry("Circle"), entry("Square")]));
+        assert_eq!(names(&tree), vec!["Shape", "Circle", "Square"]);
+
+        // Answers that weren't asked for are ignored.
+        tree.set_children(1, Ok(vec![entry("Unit")]));
+        assert_eq!(
+            tree.node(1).unwrap().children,
+            TypeHierarchyChildren::Unresolved
+        );
+
+        assert!(tree.toggle(1));
+        tree.set_children(1, Ok(Vec::new()));
+        assert!(!tree.node(1).unwrap().is_expandable());
+        assert!(!tree.toggle(0));
+        assert_eq!(names(&tree), vec!["Shape"]);
+        assert!(!tree.toggle(0));
+        assert_eq!(names(&tree), vec!["Shape", "Circle", "Square"]);
+    }
+
+    #[test]
+    fn failed_requests_are_retried_on_the_next_expand() {
+        let mut tree = TypeHierarchyTree::new(entry("Shape"));
+        tree.expand(0);
+        tree.set_children(0, Err("server stopped".into()));
+        assert_eq!(
+            tree.node(0).unwrap().children,
+            TypeHierarchyChildren::Failed("server stopped".into())
+        );
+        tree.collapse(0);
+        assert!(tree.expand(0));
+    }
+
+    #[test]
+    fn keyboard_moves_through_visible_nodes() {
+        let mut tree = TypeHierarchyTree::new(entry("Shape"));
+        assert_eq!(tree.expand_selected(), Some(0));
+        tree.set_children(0, Ok(vec![entry("Circle"), entry("Square")]));
+
+        // Right on an expanded node steps into its first child.
+        assert_eq!(tree.expand_selected(), None);
+        assert_eq!(tree.selected(), 1);
+        assert_eq!(tree.expand_selected(), Some(1));
+        tree.set_children(1, Ok(vec![entry("Ellipse")]));
+        tree.move_selection(1);
+        assert_eq!(tree.selected(), 3);
+        tree.move_selection(5);
+        assert_eq!(tree.selected(), 2);
+        tree.move_selection(-10);
+        assert_eq!(tree.selected(), 0);
+
+        // Left selects the parent, then collapses it.
+        tree.select(3);
+        tree.collapse_selected();
+        assert_eq!(tree.selected(), 1);
+        tree.collapse_selected();
+        assert_eq!(names(&tree), vec!["Shape", "Circle", "Square"]);
+
+        // Collapsing an ancestor moves the selection onto it.
+        tree.select(2);
+        tree.collapse(0);
+        assert_eq!(tree.selected(), 0);
+    }
+
+    #[test]
+    fn each_tab_resolves_its_root_when_first_shown() {
+        let mut panel = TypeHierarchyPanel::new(TypeHierarchyDirection::Supertypes);
+        let server_id = LanguageServerId::default();
+        assert_eq!(panel.set_root(Ok((server_id, entry("Shape")))), Some(0));
+        assert_eq!(panel.symbol, "Shape");
+        assert!(!panel.loading);
+
+        assert_eq!(
+            panel.set_direction(TypeHierarchyDirection::Subtypes),
+            Some(0)
+        );
+        assert_eq!(panel.set_direction(TypeHierarchyDirection::Subtypes), None);
+        assert_eq!(
+            panel.set_direction(TypeHierarchyDirection::Supertypes),
+            None
+        );
+
+        let mut failed = TypeHierarchyPanel::new(TypeHierarchyDirection::Subtypes);
+        assert_eq!(failed.set_root(Err("no type here".into())), None);
+        assert_eq!(failed.error.as_deref(), Some("no type here"));
+        assert!(failed.tree().is_none());
+    }
+}
@@ -61,6 +61,28 @@ pub struct GlobalSearchLocation {
     pub line: usize,
 }
 
+/// Picker payload for a commit in the history view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitLogEntry {
+    pub repository_root: std::path::PathBuf,
+    pub hash: String,
+    /// Limits the diff to this path when browsing a file's history
+    pub path: Option<std::path::PathBuf>,
+    pub subject: String,
+}
+
+/// Picker payload that re-captures the project environment for `root`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvironmentRecapture {
+    pub root: std::path::PathBuf,
+}
+
+/// Picker payload that opens a new terminal from the named profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminalProfileChoice {
+    pub name: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DiagnosticLocation {
     pub doc_id: helix_view::DocumentId,
@@ -137,10 +159,28 @@ pub enum Update {
     ShowCodeActions,
     ShowRunnables,
     ShowHoverDocs,
+    ShowFindBar,
     RunTask(nucleotide_events::v2::run::ResolvedTask),
+    ResolveMergeConflict(crate::merge_conflicts::MergeConflictChoice),
+    ProjectRename(crate::project_rename::ProjectRenameChoice),
+    RunConfiguration(crate::run_configurations::RunConfigurationChoice),
+    Cargo(crate::cargo_integration::CargoChoice),
+    Indentation(crate::indentation::IndentationChoice),
+    FileTemplate(crate::file_templates::FileTemplateChoice),
+    ProjectTemplate(crate::config::ProjectTemplateConfig),
+    TerminalPaste(crate::terminal_input::TerminalPasteChoice),
+    References(crate::references_panel::ReferencesResult),
+    TypeHierarchy(crate::type_hierarchy::TypeHierarchyUpdate),
+    DiskChange(crate::disk_changes::DiskChangeChoice),
+    SaveFailed(crate::save_failure::SaveFailure),
+    ResolveSaveFailure(crate::save_failure::SaveFailureChoice),
+    ShowCommitDiff(CommitLogEntry),
+    RecaptureEnvironment(EnvironmentRecapture),
+    OpenTerminalProfile(TerminalProfileChoice),
     ToggleFileTree,
     SemanticShortcut(SemanticShortcutIntent),
     TerminalPanel(gpui::Entity<nucleotide_terminal_panel::TerminalPanel>),
+    ErrorReport(Box<crate::error_report::ErrorReport>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -164,6 +204,13 @@ pub enum SemanticShortcutIntent {
     RunLast,
     RunFileTests,
     ToggleFileTree,
+    ShowAllTabs,
+    ActivateNextTab,
+    ActivatePreviousTab,
+    ActivateLastUsedTab,
+    ReopenClosedTab,
+    SwitchRecentDocument,
+    SwitchRecentDocumentReverse,
 }
 
 impl std::fmt::Debug for Update {
@@ -222,10 +269,38 @@ impl std::fmt::Debug for Update {
             Update::ShowCodeActions => write!(f, "ShowCodeActions"),
             Update::ShowRunnables => write!(f, "ShowRunnables"),
             Update::ShowHoverDocs => write!(f, "ShowHoverDocs"),
+            Update::ShowFindBar => write!(f, "ShowFindBar"),
             Update::RunTask(task) => write!(f, "RunTask({:?})", task.label()),
+            Update::ShowCommitDiff(entry) => write!(f, "ShowCommitDiff({})", entry.hash),
+            Update::RecaptureEnvironment(request) => {
+                write!(f, "RecaptureEnvironment({})", request.root.display())
+            }
+            Update::OpenTerminalProfile(choice) => {
+                write!(f, "OpenTerminalProfile({})", choice.name)
+            }
+            Update::ResolveMergeConflict(choice) => write!(
+                f,
+                "ResolveMergeConflict(line {}, {:?})",
+                choice.start_line, choice.resolution
+            ),
+            Update::ProjectRename(choice) => write!(f, "ProjectRename({choice:?})"),
+            Update::RunConfiguration(choice) => write!(f, "RunConfiguration({choice:?})"),
+            Update::Cargo(choice) => write!(f, "Cargo({choice:?})"),
+            Update::Indentation(choice) => write!(f, "Indentation({choice:?})"),
+            Update::FileTemplate(choice) => write!(f, "FileTemplate({})", choice.name),
+            Update::ProjectTemplate(template) => write!(f, "ProjectTemplate({})", template.name),
+            Update::TerminalPaste(choice) => write!(f, "TerminalPaste({})", choice.confirmed),
+            Update::References(result) => {
+                write!(f, "References({})", result.locations.len())
+            }
+            Update::TypeHierarchy(_) => write!(f, "TypeHierarchy"),
+            Update::DiskChange(choice) => write!(f, "DiskChange({choice:?})"),
+            Update::SaveFailed(failure) => write!(f, "SaveFailed({})", failure.path.display()),
+            Update::ResolveSaveFailure(choice) => write!(f, "ResolveSaveFailure({choice:?})"),
             Update::ToggleFileTree => write!(f, "ToggleFileTree"),
             Update::SemanticShortcut(intent) => write!(f, "SemanticShortcut({intent:?})"),
             Update::TerminalPanel(_) => write!(f, "TerminalPanel(...)"),
+            Update::ErrorReport(report) => write!(f, "ErrorReport({:?})", report.summary),
         }
     }
 }
@@ -0,0 +1,292 @@
+// ABOUTME: Local-only usage counts for commands, files and themes used to rank pickers by frecency
+// ABOUTME: Stored in the config directory and never sent anywhere; can be disabled and cleared
+
+use anyhow::{Context, Result};
+use gpui::{App, Global};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Prompt command that forgets all recorded usage.
+pub const CLEAR_USAGE_DATA_COMMAND: &str = "clear-usage-data";
+const STORE_FILE_NAME: &str = "usage.toml";
+/// Entries kept per kind; the least valuable are dropped first.
+const MAX_ENTRIES_PER_KIND: usize = 300;
+/// Usage loses half its weight after this long without being repeated.
+const HALF_LIFE_SECS: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+/// Scales frecency into a ranking boost comparable to a few matched characters.
+const BOOST_SCALE: f64 = 16.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UsageKind {
+    Command,
+    File,
+    Theme,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageEntry {
+    pub kind: UsageKind,
+    pub key: String,
+    pub count: u32,
+    pub last_used_unix_secs: u64,
+}
+
+impl UsageEntry {
+    fn frecency(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.last_used_unix_secs) as f64;
+        f64::from(self.count) * 0.5f64.powf(age / HALF_LIFE_SECS)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageStore {
+    #[serde(default)]
+    pub entries: Vec<UsageEntry>,
+}
+
+impl UsageStore {
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .with_context(|| format!("failed to parse {}", path.display())),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error).with_context(|| format!("failed to read {}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        let contents = toml::to_string_pretty(self).context("failed to encode usage data")?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    pub fn record(&mut self, kind: UsageKind, key: &str, now: u64) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.kind == kind && entry.key == key)
+        {
+            Some(entry) => {
+                entry.count = entry.count.saturating_add(1);
+                entry.last_used_unix_secs = now;
+            }
+            None => {
+                self.entries.push(UsageEntry {
+                    kind,
+                    key: key.to_string(),
+                    count: 1,
+                    last_used_unix_secs: now,
+                });
+                self.prune(kind, now);
+            }
+        }
+    }
+
+    fn prune(&mut self, kind: UsageKind, now: u64) {
+        let count = self.entries.iter().filter(|e| e.kind == kind).count();
+        if count <= MAX_ENTRIES_PER_KIND {
+            return;
+        }
+        if let Some(weakest) = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.kind == kind)
+            .min_by(|(_, a), (_, b)| a.frecency(now).total_cmp(&b.frecency(now)))
+            .map(|(index, _)| index)
+        {
+            self.entries.remove(weakest);
+        }
+    }
+
+    pub fn scores(&self, kind: UsageKind, now: u64) -> FrecencyScores {
+        FrecencyScores(
+            self.entries
+                .iter()
+                .filter(|entry| entry.kind == kind)
+                .map(|entry| (entry.key.clone(), entry.frecency(now)))
+                .collect(),
+        )
+    }
+}
+
+/// Frecency of every recorded key of one kind, for ranking.
+#[derive(Debug, Clone, Default)]
+pub struct FrecencyScores(HashMap<String, f64>);
+
+impl FrecencyScores {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn frecency(&self, key: &str) -> f64 {
+        self.0.get(key).copied().unwrap_or_default()
+    }
+
+    /// Amount to add to a fuzzy match score; zero for unused keys.
+    pub fn boost(&self, key: &str) -> u16 {
+        (self.frecency(key).ln_1p() * BOOST_SCALE)
+            .round()
+            .min(f64::from(u16::MAX)) as u16
+    }
+}
+
+/// The usage store for this session, installed as a GPUI global.
+#[derive(Debug)]
+pub struct UsageTracker {
+    store: UsageStore,
+    path: PathBuf,
+    enabled: bool,
+}
+
+impl Global for UsageTracker {}
+
+impl UsageTracker {
+    pub fn load_default(enabled: bool) -> Self {
+        let path = default_store_path();
+        let store = UsageStore::load(&path).unwrap_or_else(|error| {
+            nucleotide_logging::warn!(error = %error, "Ignoring unreadable usage data");
+            UsageStore::default()
+        });
+        Self {
+            store,
+            path,
+            enabled,
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn record(&mut self, kind: UsageKind, key: &str) {
+        if !self.enabled || key.is_empty() {
+            return;
+        }
+        self.store.record(kind, key, now_unix_secs());
+        if let Err(error) = self.store.save(&self.path) {
+            nucleotide_logging::warn!(error = %error, "Failed to save usage data");
+        }
+    }
+
+    /// Scores for ranking; empty while tracking is disabled.
+    pub fn scores(&self, kind: UsageKind) -> FrecencyScores {
+        if !self.enabled {
+            return FrecencyScores::default();
+        }
+        self.store.scores(kind, now_unix_secs())
+    }
+
+    /// Forgets all recorded usage and deletes the store file.
+    pub fn clear(&mut self) -> Result<()> {
+        self.store = UsageStore::default();
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(error) => {
+                Err(error).with_context(|| format!("failed to remove {}", self.path.display()))
+            }
+        }
+    }
+}
+
+/// Records a use of `key` if usage tracking is installed and enabled.
+pub fn record_usage(kind: UsageKind, key: &str, cx: &mut App) {
+    if cx.has_global::<UsageTracker>() {
+        cx.global_mut::<UsageTracker>().record(kind, key);
+    }
+}
+
+/// Current scores for `kind`, empty when tracking is unavailable.
+pub fn usage_scores(kind: UsageKind, cx: &App) -> FrecencyScores {
+    cx.try_global::<UsageTracker>()
+        .map(|tracker| tracker.scores(kind))
+        .unwrap_or_default()
+}
+
+pub fn default_store_path() -> PathBuf {
+    helix_loader::config_dir().join(STORE_FILE_NAME)
+}
+
+fn now_unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    #[test]
+    fn recent_and_frequent_keys_rank_higher() {
+        let now = 100 * DAY;
+        let mut store = UsageStore::default();
+        for _ in 0..4 {
+            store.record(UsageKind::Command, "write", now - 30 * DAY);
+        }
+        store.record(UsageKind::Command, "format", now);
+        store.record(UsageKind::Command, "format", now);
+        store.record(UsageKind::Theme, "onedark", now);
+
+        let scores = store.scores(UsageKind::Command, now);
+        assert!(scores.frecency("format") > scores.frecency("write"));
+        assert!(scores.boost("format") > scores.boost("write"));
+        assert_eq!(scores.boost("onedark"), 0);
+        assert_eq!(scores.boost("quit"), 0);
+    }
+
+    #[test]
+    fn each_kind_keeps_a_bounded_number_of_entries() {
+        let mut store = UsageStore::default();
+        store.record(UsageKind::Theme, "onedark", 0);
+        store.record(UsageKind::File, "src/main.rs", 0);
+        store.record(UsageKind::File, "src/main.rs", 0);
+        for index in 0..MAX_ENTRIES_PER_KIND {
+            store.record(UsageKind::File, &format!("src/{index}.rs"), 0);
+        }
+
+        let files = store
+            .entries
+            .iter()
+            .filter(|entry| entry.kind == UsageKind::File)
+            .count();
+        assert_eq!(files, MAX_ENTRIES_PER_KIND);
+        assert!(store.scores(UsageKind::File, 0).frecency("src/main.rs") > 0.0);
+        assert!(store.scores(UsageKind::Theme, 0).frecency("onedark") > 0.0);
+    }
+
+    #[test]
+    fn store_round_trips_and_clears() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join(STORE_FILE_NAME);
+        let mut tracker = UsageTracker {
+            store: UsageStore::default(),
+            path: path.clone(),
+            enabled: true,
+        };
+
+        tracker.record(UsageKind::Command, "write");
+        assert_eq!(UsageStore::load(&path).unwrap().entries.len(), 1);
+
+        tracker.set_enabled(false);
+        tracker.record(UsageKind::Command, "quit");
+        assert!(tracker.scores(UsageKind::Command).is_empty());
+        assert_eq!(UsageStore::load(&path).unwrap().entries.len(), 1);
+
+        tracker.clear().unwrap();
+        assert!(!path.exists());
+        assert_eq!(UsageStore::load(&path).unwrap(), UsageStore::default());
+    }
+}
@@ -0,0 +1,165 @@
+// ABOUTME: Completion popup session, in-flight request and accepted-item memory of the workspace
+// ABOUTME: Drops results of superseded requests and ranks previously accepted items first
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use helix_view::{DocumentId, ViewId};
+
+use super::prefix_extraction::PrefixExtractor;
+use crate::application::CompletionCancellation;
+
+#[derive(Clone, Debug)]
+pub(super) struct ActiveCompletionSession {
+    pub(super) doc_id: DocumentId,
+    pub(super) view_id: ViewId,
+    pub(super) document_version: i32,
+    pub(super) is_incomplete: bool,
+    pub(super) incomplete_server_ids: Vec<u64>,
+    pub(super) retained_items: Vec<nucleotide_events::completion::CompletionItem>,
+    pub(super) requested_prefix: String,
+}
+
+struct InFlightCompletion {
+    generation: u64,
+    cancellation: CompletionCancellation,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(super) struct CompletionMemoryKey {
+    pub(super) language: String,
+    pub(super) prefix: String,
+    pub(super) kind: Option<nucleotide_ui::completion_v2::CompletionItemKind>,
+    pub(super) insert_text: String,
+}
+
+#[derive(Default)]
+pub(super) struct CompletionMemory {
+    entries: HashMap<CompletionMemoryKey, u64>,
+    next_touch: u64,
+}
+
+impl CompletionMemory {
+    pub(super) fn priority(&self, key: &CompletionMemoryKey) -> u64 {
+        self.entries.get(key).copied().unwrap_or(0)
+    }
+
+    pub(super) fn memorize(&mut self, key: CompletionMemoryKey) {
+        self.next_touch = self.next_touch.saturating_add(1);
+        self.entries.insert(key, self.next_touch);
+    }
+}
+
+/// The completion popup and the requests feeding it.
+#[derive(Default)]
+pub(super) struct CompletionState {
+    /// Session behind the completion popup while it is shown
+    pub(super) session: Option<ActiveCompletionSession>,
+    /// Bumped for every completion request and whenever completions are
+    /// dismissed; results are only shown while their generation is current.
+    generation: u64,
+    in_flight: Option<InFlightCompletion>,
+    /// When the pending completion was triggered, for the latency budget
+    pub(super) triggered_at: Option<Instant>,
+    pub(super) memory: CompletionMemory,
+    pub(super) prefix_extractor: PrefixExtractor,
+}
+
+impl CompletionState {
+    pub(super) fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Tags a new request with the next generation and cancels the request
+    /// it supersedes.
+    pub(super) fn begin_request(&mut self, cancellation: CompletionCancellation) -> u64 {
+        self.cancel_request();
+        self.in_flight = Some(InFlightCompletion {
+            generation: self.generation,
+            cancellation,
+        });
+        self.generation
+    }
+
+    /// Invalidates the current request so its results are dropped, and asks
+    /// the servers to stop working on it.
+    pub(super) fn cancel_request(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        if let Some(in_flight) = self.in_flight.take() {
+            in_flight.cancellation.cancel();
+        }
+    }
+
+    /// Whether results tagged `generation` belong to the in-flight request.
+    /// Marks that request as done when they do.
+    pub(super) fn finish_request(&mut self, generation: u64) -> bool {
+        let current = self
+            .in_flight
+            .as_ref()
+            .is_some_and(|in_flight| in_flight.generation == generation);
+        if current {
+            self.in_flight = None;
+        }
+        current
+    }
+
+    /// Drops the popup session along with any request still running.
+    pub(super) fn dismiss(&mut self) {
+        self.cancel_request();
+        self.session = None;
+        self.triggered_at = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completion_results_apply_only_to_the_in_flight_generation() {
+        let mut completion = CompletionState::default();
+        assert!(!completion.finish_request(completion.generation()));
+
+        let stale = completion.begin_request(CompletionCancellation::default());
+        let current = completion.begin_request(CompletionCancellation::default());
+        assert_ne!(stale, current);
+        assert!(!completion.finish_request(stale));
+        assert!(completion.finish_request(current));
+        assert!(!completion.finish_request(current));
+
+        let dismissed = completion.begin_request(CompletionCancellation::default());
+        completion.dismiss();
+        assert!(!completion.finish_request(dismissed));
+    }
+
+    #[test]
+    fn completion_memory_prioritizes_recent_prefix_match() {
+        let mut memory = CompletionMemory::default();
+        let old_key = CompletionMemoryKey {
+            language: "rust".to_string(),
+            prefix: "fo".to_string(),
+            kind: Some(nucleotide_ui::completion_v2::CompletionItemKind::Function),
+            insert_text: "foo".to_string(),
+        };
+        let recent_key = CompletionMemoryKey {
+            language: "rust".to_string(),
+            prefix: "fo".to_string(),
+            kind: Some(nucleotide_ui::completion_v2::CompletionItemKind::Function),
+            insert_text: "foobar".to_string(),
+        };
+
+        memory.memorize(old_key.clone());
+        memory.memorize(recent_key.clone());
+
+        assert!(memory.priority(&recent_key) > memory.priority(&old_key));
+        assert_eq!(
+            memory.priority(&CompletionMemoryKey {
+                language: "rust".to_string(),
+                prefix: "ba".to_string(),
+                kind: Some(nucleotide_ui::completion_v2::CompletionItemKind::Function),
+                insert_text: "foobar".to_string(),
+            }),
+            0
+        );
+    }
+}
@@ -0,0 +1,1369 @@
+// ABOUTME: Right-click and dropdown menus owned by the workspace and what each one targets
+// ABOUTME: Builds, renders, dismisses and dispatches them together with their overlay manager popups
+
+use std::path::{Path, PathBuf};
+
+use gpui::{
+    Anchor, App, Bounds, Context, Deferred, Entity, FocusHandle, Focusable, InteractiveElement,
+    IntoElement, Pixels, Point, Window, point, px,
+};
+use nucleotide_ui::overlay_manager::{Dismissal, OverlayManager, PopupId, PopupLayer};
+use nucleotide_ui::{ContextMenu, PopupMenu, PopupMenuItem, PopupMenuSurface};
+
+use super::Workspace;
+use crate::file_tree::sidebar::ProjectTreeContextMenuIntent;
+use crate::tab::TabId;
+
+type TabContextMenuHandler = fn(&mut Workspace, TabId, &mut Context<Workspace>);
+type TabBarSplitMenuHandler = fn(&mut Workspace, &mut Context<Workspace>);
+type TabBarNewMenuHandler = fn(&mut Workspace, &mut Context<Workspace>);
+
+// Names the workspace's menus are registered under in the overlay manager.
+const TAB_CONTEXT_MENU_POPUP: PopupId = PopupId::new("tab-context-menu");
+const TAB_BAR_SPLIT_MENU_POPUP: PopupId = PopupId::new("tab-bar-split-menu");
+const TAB_BAR_NEW_MENU_POPUP: PopupId = PopupId::new("tab-bar-new-menu");
+const FILE_TREE_CONTEXT_MENU_POPUP: PopupId = PopupId::new("file-tree-context-menu");
+const CONTENT_CONTEXT_MENU_POPUP: PopupId = PopupId::new("content-context-menu");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TabContextMenuIntent {
+    Close,
+    CloseOthers,
+    CloseLeft,
+    CloseRight,
+    CloseClean,
+    CloseAll,
+    CopyPath,
+    CopyRelativePath,
+    RevealInOs,
+    RevealInProjectPanel,
+    OpenInTerminal,
+    ToggleReadOnly,
+    TogglePin,
+    ShowFileHistory,
+}
+
+impl TabContextMenuIntent {
+    fn label(self, is_pinned: bool, is_readonly: bool) -> &'static str {
+        match self {
+            Self::Close => "Close",
+            Self::CloseOthers => "Close Others",
+            Self::CloseLeft => "Close Left",
+            Self::CloseRight => "Close Right",
+            Self::CloseClean => "Close Clean",
+            Self::CloseAll => "Close All",
+            Self::CopyPath => "Copy Path",
+            Self::CopyRelativePath => "Copy Relative Path",
+            Self::RevealInOs => reveal_in_file_manager_label(false),
+            Self::RevealInProjectPanel => "Reveal In Project Panel",
+            Self::OpenInTerminal => "Open Terminal Here",
+            Self::ToggleReadOnly if is_readonly => "Make File Editable",
+            Self::ToggleReadOnly => "Make File Read-Only",
+            Self::TogglePin if is_pinned => "Unpin Tab",
+            Self::TogglePin => "Pin Tab",
+            Self::ShowFileHistory => "Show File History",
+        }
+    }
+
+    fn handler(self) -> TabContextMenuHandler {
+        match self {
+            Self::Close => Workspace::tab_cm_action_close,
+            Self::CloseOthers => Workspace::tab_cm_action_close_others,
+            Self::CloseLeft => Workspace::tab_cm_action_close_left,
+            Self::CloseRight => Workspace::tab_cm_action_close_right,
+            Self::CloseClean => Workspace::tab_cm_action_close_clean,
+            Self::CloseAll => Workspace::tab_cm_action_close_all,
+            Self::CopyPath => Workspace::tab_cm_action_copy_path,
+            Self::CopyRelativePath => Workspace::tab_cm_action_copy_relative_path,
+            Self::RevealInOs => Workspace::tab_cm_action_reveal_in_os,
+            Self::RevealInProjectPanel => Workspace::tab_cm_action_reveal_in_project_panel,
+            Self::OpenInTerminal => Workspace::tab_cm_action_open_in_terminal,
+            Self::ToggleReadOnly => Workspace::tab_cm_action_toggle_readonly,
+            Self::TogglePin => Workspace::tab_cm_action_toggle_pin,
+            Self::ShowFileHistory => Workspace::tab_cm_action_show_file_history,
+        }
+    }
+
+    fn disabled(
+        self,
+        target_index: Option<usize>,
+        total_items: usize,
+        has_clean_items: bool,
+    ) -> bool {
+        match self {
+            Self::Close | Self::CloseAll => target_index.is_none(),
+            Self::CloseOthers => total_items <= 1,
+            Self::CloseLeft => target_index.is_none_or(|index| index == 0),
+            Self::CloseRight => target_index.is_none_or(|index| index + 1 >= total_items),
+            Self::CloseClean => !has_clean_items,
+            Self::CopyPath
+            | Self::CopyRelativePath
+            | Self::RevealInOs
+            | Self::RevealInProjectPanel
+            | Self::OpenInTerminal
+            | Self::ToggleReadOnly
+            | Self::ShowFileHistory => target_index.is_none(),
+            Self::TogglePin => target_index.is_none(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TabContextMenuEntry {
+    Action(TabContextMenuIntent),
+    Separator,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) struct TabContextMenuCapabilities {
+    pub(super) has_file_path: bool,
+    pub(super) has_project_panel_path: bool,
+    pub(super) has_terminal_directory: bool,
+    pub(super) is_readonly: bool,
+}
+
+/// What the tab menu shows for the tab it was opened on, read from the
+/// workspace when it opens.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) struct TabContextMenuModel {
+    pub(super) capabilities: TabContextMenuCapabilities,
+    /// Position of the target among the visible tabs
+    pub(super) target_index: Option<usize>,
+    pub(super) visible_tabs: usize,
+    pub(super) has_clean_tabs: bool,
+    pub(super) target_is_pinned: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TabBarSplitMenuIntent {
+    Right,
+    Left,
+    Up,
+    Down,
+}
+
+impl TabBarSplitMenuIntent {
+    const ALL: [Self; 4] = [Self::Right, Self::Left, Self::Up, Self::Down];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Right => "Split Right",
+            Self::Left => "Split Left",
+            Self::Up => "Split Up",
+            Self::Down => "Split Down",
+        }
+    }
+
+    pub(super) fn commands(self) -> &'static [&'static str] {
+        match self {
+            Self::Right => &["vsplit"],
+            Self::Left => &["vsplit", "swap_view_left"],
+            Self::Up => &["hsplit", "swap_view_up"],
+            Self::Down => &["hsplit"],
+        }
+    }
+
+    fn handler(self) -> TabBarSplitMenuHandler {
+        match self {
+            Self::Right => Workspace::tab_bar_action_split_right,
+            Self::Left => Workspace::tab_bar_action_split_left,
+            Self::Up => Workspace::tab_bar_action_split_up,
+            Self::Down => Workspace::tab_bar_action_split_down,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TabBarNewMenuIntent {
+    NewFile,
+    OpenFile,
+    SearchProject,
+    SearchSymbols,
+    NewTerminal,
+    NewCenterTerminal,
+}
+
+impl TabBarNewMenuIntent {
+    fn label(self) -> &'static str {
+        match self {
+            Self::NewFile => "New File",
+            Self::OpenFile => "Open File",
+            Self::SearchProject => "Search Project",
+            Self::SearchSymbols => "Search Symbols",
+            Self::NewTerminal => "New Terminal",
+            Self::NewCenterTerminal => "New Center Terminal",
+        }
+    }
+
+    fn handler(self) -> TabBarNewMenuHandler {
+        match self {
+            Self::NewFile => Workspace::tab_bar_action_new_file,
+            Self::OpenFile => Workspace::tab_bar_action_open_file,
+            Self::SearchProject => Workspace::tab_bar_action_search_project,
+            Self::SearchSymbols => Workspace::tab_bar_action_search_symbols,
+            Self::NewTerminal => Workspace::tab_bar_action_new_terminal,
+            Self::NewCenterTerminal => Workspace::tab_bar_action_new_center_terminal,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TabBarNewMenuEntry {
+    Action(TabBarNewMenuIntent),
+    Separator,
+}
+
+const TAB_BAR_NEW_MENU_ENTRIES: &[TabBarNewMenuEntry] = &[
+    TabBarNewMenuEntry::Action(TabBarNewMenuIntent::NewFile),
+    TabBarNewMenuEntry::Action(TabBarNewMenuIntent::OpenFile),
+    TabBarNewMenuEntry::Separator,
+    TabBarNewMenuEntry::Action(TabBarNewMenuIntent::SearchProject),
+    TabBarNewMenuEntry::Action(TabBarNewMenuIntent::SearchSymbols),
+    TabBarNewMenuEntry::Separator,
+    TabBarNewMenuEntry::Action(TabBarNewMenuIntent::NewTerminal),
+    TabBarNewMenuEntry::Action(TabBarNewMenuIntent::NewCenterTerminal),
+];
+
+pub(super) fn reveal_in_file_manager_label(is_remote: bool) -> &'static str {
+    if cfg!(target_os = "macos") && !is_remote {
+        "Reveal in Finder"
+    } else if cfg!(target_os = "windows") && !is_remote {
+        "Reveal in File Explorer"
+    } else {
+        "Reveal in File Manager"
+    }
+}
+
+fn file_tree_menu_intents() -> &'static [ProjectTreeContextMenuIntent] {
+    ProjectTreeContextMenuIntent::common_file_operations()
+}
+
+fn tab_context_menu_entries(capabilities: TabContextMenuCapabilities) -> Vec<TabContextMenuEntry> {
+    let mut entries = vec![
+        TabContextMenuEntry::Action(TabContextMenuIntent::Close),
+        TabContextMenuEntry::Action(TabContextMenuIntent::CloseOthers),
+        TabContextMenuEntry::Separator,
+        TabContextMenuEntry::Action(TabContextMenuIntent::CloseLeft),
+        TabContextMenuEntry::Action(TabContextMenuIntent::CloseRight),
+        TabContextMenuEntry::Separator,
+        TabContextMenuEntry::Action(TabContextMenuIntent::CloseClean),
+        TabContextMenuEntry::Action(TabContextMenuIntent::CloseAll),
+    ];
+
+    if capabilities.has_file_path {
+        entries.extend([
+            TabContextMenuEntry::Separator,
+            TabContextMenuEntry::Action(TabContextMenuIntent::ToggleReadOnly),
+            TabContextMenuEntry::Separator,
+            TabContextMenuEntry::Action(TabContextMenuIntent::CopyPath),
+            TabContextMenuEntry::Action(TabContextMenuIntent::CopyRelativePath),
+            TabContextMenuEntry::Separator,
+            TabContextMenuEntry::Action(TabContextMenuIntent::RevealInOs),
+            TabContextMenuEntry::Action(TabContextMenuIntent::ShowFileHistory),
+        ]);
+    }
+
+    entries.extend([
+        TabContextMenuEntry::Separator,
+        TabContextMenuEntry::Action(TabContextMenuIntent::TogglePin),
+    ]);
+
+    if capabilities.has_project_panel_path {
+        entries.push(TabContextMenuEntry::Action(
+            TabContextMenuIntent::RevealInProjectPanel,
+        ));
+    }
+
+    if capabilities.has_terminal_directory {
+        entries.push(TabContextMenuEntry::Action(
+            TabContextMenuIntent::OpenInTerminal,
+        ));
+    }
+
+    entries
+}
+
+/// The view a right-click menu in the content area was opened on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) enum ContentContextMenuTarget {
+    #[default]
+    Editor,
+    Terminal,
+}
+
+/// The workspace's menus. Each item dispatches an action that
+/// [`ContextMenuState::register_actions`] routes back to the workspace
+/// command it names.
+#[derive(Default)]
+pub(super) struct ContextMenuState {
+    file_tree: ContextMenu,
+    file_tree_target: Option<(PathBuf, bool)>,
+    tab: ContextMenu,
+    tab_target: Option<TabId>,
+    tab_model: TabContextMenuModel,
+    tab_bar_split: ContextMenu,
+    tab_bar_split_button_bounds: Option<Bounds<Pixels>>,
+    tab_bar_new: ContextMenu,
+    content: ContextMenu,
+    content_target: ContentContextMenuTarget,
+    /// Focus to restore once the content menu is dismissed
+    content_focus: Option<FocusHandle>,
+    /// Whether the terminal had a selection when its menu opened
+    content_has_selection: bool,
+    /// LSP server list popup from the status bar
+    pub(super) lsp_open: bool,
+    pub(super) lsp_position: (f32, f32),
+}
+
+impl ContextMenuState {
+    /// Opens the file tree menu at `position` for the entry at `path`.
+    pub(super) fn open_file_tree(
+        &mut self,
+        position: (f32, f32),
+        path: PathBuf,
+        is_dir: bool,
+        cx: &mut App,
+    ) {
+        self.file_tree.open_at(position);
+        self.file_tree_target = Some((path, is_dir));
+        OverlayManager::open_global(FILE_TREE_CONTEXT_MENU_POPUP, PopupLayer::ContextMenu, cx);
+    }
+
+    /// Path of the file tree entry the menu was last opened on and whether
+    /// it is a directory.
+    pub(super) fn file_tree_target(&self) -> Option<(&Path, bool)> {
+        self.file_tree_target
+            .as_ref()
+            .map(|(path, is_dir)| (path.as_path(), *is_dir))
+    }
+
+    /// Opens the tab menu at `position` for `tab_id`, closing the other tab
+    /// bar menus.
+    pub(super) fn open_tab(
+        &mut self,
+        position: (f32, f32),
+        tab_id: TabId,
+        model: TabContextMenuModel,
+        cx: &mut App,
+    ) {
+        self.close_tab_bar_split(cx);
+        self.close_tab_bar_new(cx);
+        self.tab.open_at(position);
+        self.tab_target = Some(tab_id);
+        self.tab_model = model;
+        OverlayManager::open_global(TAB_CONTEXT_MENU_POPUP, PopupLayer::ContextMenu, cx);
+    }
+
+    /// Remembers where the split button was laid out so its menu hangs
+    /// from the button rather than the pointer.
+    pub(super) fn set_tab_bar_split_button_bounds(&mut self, bounds: Bounds<Pixels>) {
+        self.tab_bar_split_button_bounds = Some(bounds);
+    }
+
+    /// Where the split menu hangs from the split button, or `fallback`
+    /// before the button has been laid out.
+    pub(super) fn tab_bar_split_button_anchor(&self, fallback: Point<Pixels>) -> Point<Pixels> {
+        self.tab_bar_split_button_bounds
+            .map(|bounds| bounds.bottom_right())
+            .unwrap_or(fallback)
+    }
+
+    /// Opens the split menu at `position`, or closes it when already open.
+    pub(super) fn toggle_tab_bar_split(
+        &mut self,
+        position: Point<Pixels>,
+        focus: &FocusHandle,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        if self.tab_bar_split.is_open() {
+            self.close_tab_bar_menus(cx);
+            cx.notify();
+            return;
+        }
+
+        self.close_tab(cx);
+        self.close_tab_bar_new(cx);
+        self.tab_bar_split
+            .open_at((f32::from(position.x), f32::from(position.y)));
+        OverlayManager::open_global(TAB_BAR_SPLIT_MENU_POPUP, PopupLayer::ContextMenu, cx);
+        window.focus(focus, cx);
+        cx.notify();
+    }
+
+    /// Opens the editor or terminal menu under the pointer. Its items act on
+    /// the view that had focus when it opened.
+    pub(super) fn open_content(
+        &mut self,
+        target: ContentContextMenuTarget,
+        has_selection: bool,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        self.close_tab_bar_menus(cx);
+        self.close_file_tree(cx);
+        let position = window.mouse_position();
+        self.content
+            .open_at((f32::from(position.x), f32::from(position.y)));
+        self.content_target = target;
+        self.content_has_selection = has_selection;
+        self.content_focus = window.focused(cx);
+        OverlayManager::open_global(CONTENT_CONTEXT_MENU_POPUP, PopupLayer::ContextMenu, cx);
+        cx.notify();
+    }
+
+    /// Opens the LSP server list at `position`.
+    pub(super) fn open_lsp(&mut self, position: (f32, f32)) {
+        self.lsp_open = true;
+        self.lsp_position = position;
+    }
+
+    pub(super) fn any_tab_bar_menu_open(&self) -> bool {
+        self.tab.is_open() || self.tab_bar_split.is_open() || self.tab_bar_new.is_open()
+    }
+
+    pub(super) fn close_tab(&mut self, cx: &mut App) -> bool {
+        OverlayManager::close_global(TAB_CONTEXT_MENU_POPUP, cx);
+        let closed = self.tab.close();
+        let had_target = self.tab_target.take().is_some();
+        closed || had_target
+    }
+
+    pub(super) fn close_tab_bar_split(&mut self, cx: &mut App) -> bool {
+        OverlayManager::close_global(TAB_BAR_SPLIT_MENU_POPUP, cx);
+        self.tab_bar_split.close()
+    }
+
+    pub(super) fn close_tab_bar_new(&mut self, cx: &mut App) -> bool {
+        OverlayManager::close_global(TAB_BAR_NEW_MENU_POPUP, cx);
+        self.tab_bar_new.close()
+    }
+
+    pub(super) fn close_tab_bar_menus(&mut self, cx: &mut App) -> bool {
+        let mut closed = self.close_tab(cx);
+        closed |= self.close_tab_bar_split(cx);
+        closed |= self.close_tab_bar_new(cx);
+        closed
+    }
+
+    pub(super) fn close_file_tree(&mut self, cx: &mut App) -> bool {
+        OverlayManager::close_global(FILE_TREE_CONTEXT_MENU_POPUP, cx);
+        self.file_tree.close()
+    }
+
+    pub(super) fn close_content(&mut self, cx: &mut App) -> bool {
+        OverlayManager::close_global(CONTENT_CONTEXT_MENU_POPUP, cx);
+        self.content_focus = None;
+        self.content.close()
+    }
+
+    /// Closes the menus the overlay manager no longer has open, once another
+    /// popup displaced them or a dismissal closed them. Returns whether any
+    /// closed.
+    pub(super) fn close_displaced(&mut self, cx: &mut App) -> bool {
+        let mut closed = false;
+        if self.tab.is_open() && !OverlayManager::is_open_global(TAB_CONTEXT_MENU_POPUP, cx) {
+            closed |= self.close_tab(cx);
+        }
+        if self.tab_bar_split.is_open()
+            && !OverlayManager::is_open_global(TAB_BAR_SPLIT_MENU_POPUP, cx)
+        {
+            closed |= self.close_tab_bar_split(cx);
+        }
+        if self.tab_bar_new.is_open() && !OverlayManager::is_open_global(TAB_BAR_NEW_MENU_POPUP, cx)
+        {
+            closed |= self.close_tab_bar_new(cx);
+        }
+        if self.file_tree.is_open()
+            && !OverlayManager::is_open_global(FILE_TREE_CONTEXT_MENU_POPUP, cx)
+        {
+            closed |= self.close_file_tree(cx);
+        }
+        if self.content.is_open() && !OverlayManager::is_open_global(CONTENT_CONTEXT_MENU_POPUP, cx)
+        {
+            closed |= self.close_content(cx);
+        }
+        closed
+    }
+
+    fn dismiss_tab_bar_menus(
+        &mut self,
+        focus: &FocusHandle,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        if self.close_tab_bar_menus(cx) {
+            window.focus(focus, cx);
+            cx.notify();
+        }
+    }
+
+    fn dismiss_file_tree(&mut self, window: &mut Window, cx: &mut Context<Workspace>) {
+        if !self.close_file_tree(cx) {
+            return;
+        }
+
+        if let Some(coord) = cx.try_global::<nucleotide_ui::FocusCoordinator>().cloned() {
+            let _ = coord.focus_first(
+                window,
+                cx,
+                &[
+                    nucleotide_ui::FocusRole::Editor,
+                    nucleotide_ui::FocusRole::FileTree,
+                ],
+            );
+        }
+        cx.notify();
+    }
+
+    fn dismiss_content(&mut self, window: &mut Window, cx: &mut Context<Workspace>) {
+        let focus = self.content_focus.clone();
+        if self.close_content(cx) {
+            if let Some(focus) = focus {
+                window.focus(&focus, cx);
+            }
+            cx.notify();
+        }
+    }
+
+    /// Routes the actions the menu items dispatch to the workspace commands
+    /// they name.
+    pub(super) fn register_actions<E: InteractiveElement>(
+        element: E,
+        cx: &mut Context<Workspace>,
+    ) -> E {
+        element
+            .on_action(cx.listener(
+                |workspace, _: &crate::actions::editor::OpenContextMenu, window, cx| {
+                    workspace.open_content_context_menu(
+                        ContentContextMenuTarget::Editor,
+                        window,
+                        cx,
+                    );
+                },
+            ))
+            .on_action(cx.listener(
+                |workspace, _: &crate::actions::terminal::OpenContextMenu, window, cx| {
+                    workspace.open_content_context_menu(
+                        ContentContextMenuTarget::Terminal,
+                        window,
+                        cx,
+                    );
+                },
+            ))
+            .on_action(cx.listener(
+                |workspace, action: &crate::actions::project_tree::Operation, window, cx| {
+                    window.prevent_default();
+                    let menus = &mut workspace.context_menus;
+                    if menus.close_file_tree(cx) {
+                        cx.notify();
+                    }
+                    if let Some((path, is_dir)) = menus
+                        .file_tree_target()
+                        .map(|(path, is_dir)| (path.to_path_buf(), is_dir))
+                    {
+                        workspace.handle_project_tree_operation(action.intent, path, is_dir, cx);
+                    }
+                    cx.stop_propagation();
+                },
+            ))
+            .on_action(cx.listener(
+                |workspace, action: &crate::actions::tab_menus::ContextOperation, window, cx| {
+                    window.prevent_default();
+                    let target = workspace.context_menus.tab_target;
+                    if workspace.context_menus.close_tab(cx) {
+                        cx.notify();
+                    }
+                    if let Some(tab_id) = target {
+                        (action.intent.handler())(workspace, tab_id, cx);
+                    }
+                    cx.stop_propagation();
+                },
+            ))
+            .on_action(cx.listener(
+                |workspace, action: &crate::actions::tab_menus::SplitOperation, window, cx| {
+                    window.prevent_default();
+                    if workspace.context_menus.close_tab_bar_split(cx) {
+                        cx.notify();
+                    }
+                    (action.intent.handler())(workspace, cx);
+                    cx.stop_propagation();
+                },
+            ))
+            .on_action(cx.listener(
+                |workspace, action: &crate::actions::tab_menus::NewOperation, window, cx| {
+                    window.prevent_default();
+                    if workspace.context_menus.close_tab_bar_new(cx) {
+                        cx.notify();
+                    }
+                    (action.intent.handler())(workspace, cx);
+                    cx.stop_propagation();
+                },
+            ))
+    }
+
+    /// The file tree menu, anchored at the last click position.
+    pub(super) fn render_file_tree(
+        &mut self,
+        action_context: &FocusHandle,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Option<Deferred> {
+        if !self.file_tree.is_open() {
+            return None;
+        }
+
+        let menu = match self.file_tree.menu() {
+            Some(menu) => menu.clone(),
+            None => {
+                let action_context = action_context.clone();
+                let menu = PopupMenu::build(window, cx, move |mut menu, _window, _cx| {
+                    menu = menu.action_context(action_context);
+                    for intent in file_tree_menu_intents().iter().copied() {
+                        menu = menu.menu(
+                            intent.label(),
+                            Box::new(crate::actions::project_tree::Operation { intent }),
+                        );
+                    }
+                    menu
+                });
+                self.file_tree.set_menu(menu.clone(), cx, |workspace, cx| {
+                    if workspace.context_menus.close_file_tree(cx) {
+                        cx.notify();
+                    }
+                });
+                menu
+            }
+        };
+
+        Some(render_popup_menu(
+            menu,
+            FILE_TREE_CONTEXT_MENU_POPUP,
+            self.file_tree.position(),
+            Anchor::TopLeft,
+            (8.0, 8.0),
+            window,
+            cx,
+            |workspace, window, cx| workspace.context_menus.dismiss_file_tree(window, cx),
+        ))
+    }
+
+    /// The menu of the tab right-clicked in the tab bar.
+    pub(super) fn render_tab(
+        &mut self,
+        action_context: &FocusHandle,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Option<Deferred> {
+        if !self.tab.is_open() {
+            return None;
+        }
+
+        let menu = match self.tab.menu() {
+            Some(menu) => menu.clone(),
+            None => {
+                let model = self.tab_model;
+                let entries = tab_context_menu_entries(model.capabilities);
+                let action_context = action_context.clone();
+                let menu = PopupMenu::build(window, cx, move |mut menu, _window, _cx| {
+                    menu = menu.action_context(action_context);
+                    for entry in entries {
+                        match entry {
+                            TabContextMenuEntry::Action(intent) => {
+                                menu = menu.menu_with_check_and_disabled(
+                                    intent.label(
+                                        model.target_is_pinned,
+                                        model.capabilities.is_readonly,
+                                    ),
+                                    false,
+                                    Box::new(crate::actions::tab_menus::ContextOperation {
+                                        intent,
+                                    }),
+                                    intent.disabled(
+                                        model.target_index,
+                                        model.visible_tabs,
+                                        model.has_clean_tabs,
+                                    ),
+                                );
+                            }
+                            TabContextMenuEntry::Separator => {
+                                menu = menu.separator();
+                            }
+                        }
+                    }
+                    menu
+                });
+                self.tab.set_menu(menu.clone(), cx, |workspace, cx| {
+                    if workspace.context_menus.close_tab(cx) {
+                        cx.notify();
+                    }
+                });
+                menu
+            }
+        };
+
+        Some(render_popup_menu(
+            menu,
+            TAB_CONTEXT_MENU_POPUP,
+            self.tab.position(),
+            Anchor::TopLeft,
+            (0.0, 0.0),
+            window,
+            cx,
+            dismiss_tab_bar_menus,
+        ))
+    }
+
+    /// The split menu hanging from the tab bar's split button.
+    pub(super) fn render_tab_bar_split(
+        &mut self,
+        action_context: &FocusHandle,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Option<Deferred> {
+        if !self.tab_bar_split.is_open() {
+            return None;
+        }
+
+        let menu = match self.tab_bar_split.menu() {
+            Some(menu) => menu.clone(),
+            None => {
+                let action_context = action_context.clone();
+                let menu = PopupMenu::build(window, cx, move |mut menu, _window, _cx| {
+                    menu = menu.action_context(action_context);
+                    for intent in TabBarSplitMenuIntent::ALL {
+                        menu = menu.menu(
+                            intent.label(),
+                            Box::new(crate::actions::tab_menus::SplitOperation { intent }),
+                        );
+                    }
+                    menu
+                });
+                self.tab_bar_split
+                    .set_menu(menu.clone(), cx, |workspace, cx| {
+                        if workspace.context_menus.close_tab_bar_split(cx) {
+                            cx.notify();
+                        }
+                    });
+                menu
+            }
+        };
+
+        Some(render_popup_menu(
+            menu,
+            TAB_BAR_SPLIT_MENU_POPUP,
+            self.tab_bar_split.position(),
+            Anchor::TopRight,
+            (0.0, 0.0),
+            window,
+            cx,
+            dismiss_tab_bar_menus,
+        ))
+    }
+
+    /// The tab bar's "new" menu.
+    pub(super) fn render_tab_bar_new(
+        &mut self,
+        action_context: &FocusHandle,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Option<Deferred> {
+        if !self.tab_bar_new.is_open() {
+            return None;
+        }
+
+        let menu = match self.tab_bar_new.menu() {
+            Some(menu) => menu.clone(),
+            None => {
+                let action_context = action_context.clone();
+                let menu = PopupMenu::build(window, cx, move |mut menu, _window, _cx| {
+                    menu = menu.action_context(action_context);
+                    for entry in TAB_BAR_NEW_MENU_ENTRIES.iter().copied() {
+                        match entry {
+                            TabBarNewMenuEntry::Action(intent) => {
+                                menu = menu.menu(
+                                    intent.label(),
+                                    Box::new(crate::actions::tab_menus::NewOperation { intent }),
+                                );
+                            }
+                            TabBarNewMenuEntry::Separator => {
+                                menu = menu.separator();
+                            }
+                        }
+                    }
+                    menu
+                });
+                self.tab_bar_new
+                    .set_menu(menu.clone(), cx, |workspace, cx| {
+                        if workspace.context_menus.close_tab_bar_new(cx) {
+                            cx.notify();
+                        }
+                    });
+                menu
+            }
+        };
+
+        Some(render_popup_menu(
+            menu,
+            TAB_BAR_NEW_MENU_POPUP,
+            self.tab_bar_new.position(),
+            Anchor::TopLeft,
+            (8.0, 8.0),
+            window,
+            cx,
+            dismiss_tab_bar_menus,
+        ))
+    }
+
+    /// The editor or terminal menu opened by right-clicking the content area.
+    pub(super) fn render_content(
+        &mut self,
+        fallback_action_context: &FocusHandle,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Option<Deferred> {
+        if !self.content.is_open() {
+            return None;
+        }
+
+        let menu = match self.content.menu() {
+            Some(menu) => menu.clone(),
+            None => {
+                let action_context = self
+                    .content_focus
+                    .clone()
+                    .unwrap_or_else(|| fallback_action_context.clone());
+                let menu = match self.content_target {
+                    ContentContextMenuTarget::Editor => {
+                        PopupMenu::build(window, cx, move |menu, window, cx| {
+                            editor_context_menu(menu, action_context, window, cx)
+                        })
+                    }
+                    ContentContextMenuTarget::Terminal => {
+                        let has_selection = self.content_has_selection;
+                        PopupMenu::build(window, cx, move |menu, window, cx| {
+                            terminal_context_menu(menu, action_context, has_selection, window, cx)
+                        })
+                    }
+                };
+                self.content.set_menu(menu.clone(), cx, |workspace, cx| {
+                    if workspace.context_menus.close_content(cx) {
+                        cx.notify();
+                    }
+                });
+                menu
+            }
+        };
+
+        Some(render_popup_menu(
+            menu,
+            CONTENT_CONTEXT_MENU_POPUP,
+            self.content.position(),
+            Anchor::TopLeft,
+            (0.0, 0.0),
+            window,
+            cx,
+            |workspace, window, cx| workspace.context_menus.dismiss_content(window, cx),
+        ))
+    }
+}
+
+fn dismiss_tab_bar_menus(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let focus = workspace.focus_handle.clone();
+    workspace
+        .context_menus
+        .dismiss_tab_bar_menus(&focus, window, cx);
+}
+
+/// Focuses `menu`, unless a popup above it owns focus, and wraps it in a
+/// surface that runs `dismiss` on a click outside it.
+#[allow(clippy::too_many_arguments)]
+fn render_popup_menu(
+    menu: Entity<PopupMenu>,
+    popup: PopupId,
+    position: (f32, f32),
+    anchor: Anchor,
+    offset: (f32, f32),
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+    dismiss: fn(&mut Workspace, &mut Window, &mut Context<Workspace>),
+) -> Deferred {
+    let menu_focus = menu.read(cx).focus_handle(cx);
+    if OverlayManager::may_focus_global(popup, cx) && !menu_focus.contains_focused(window, cx) {
+        menu_focus.focus(window, cx);
+    }
+
+    let (x, y) = position;
+    let (offset_x, offset_y) = offset;
+    gpui::deferred(
+        PopupMenuSurface::new(menu)
+            .position(point(px(x), px(y)))
+            .anchor(anchor)
+            .offset(point(px(offset_x), px(offset_y)))
+            .on_light_dismiss(cx.listener(move |workspace, _event, window, cx| {
+                OverlayManager::dismiss_global(Dismissal::Click { inside: None }, cx);
+                dismiss(workspace, window, cx);
+            }))
+            .into_any_element(),
+    )
+    .with_priority(100)
+}
+
+fn editor_context_menu(
+    menu: PopupMenu,
+    action_context: FocusHandle,
+    window: &mut Window,
+    cx: &mut Context<PopupMenu>,
+) -> PopupMenu {
+    use crate::actions::{editor, text_input, workspace};
+
+    let transform_context = action_context.clone();
+    let sort_context = action_context.clone();
+    menu.action_context(action_context)
+        .menu("Undo", Box::new(text_input::Undo))
+        .menu("Redo", Box::new(text_input::Redo))
+        .separator()
+        .menu("Copy", Box::new(text_input::Copy))
+        .menu("Paste", Box::new(text_input::Paste))
+        .separator()
+        .menu("Toggle Comment", Box::new(editor::ToggleComment))
+        .submenu("Transform", window, cx, move |menu, _, _| {
+            menu.action_context(transform_context.clone())
+                .menu("Upper Case", Box::new(editor::TransformUpperCase))
+                .menu("Lower Case", Box::new(editor::TransformLowerCase))
+                .menu("Title Case", Box::new(editor::TransformTitleCase))
+                .menu("camelCase", Box::new(editor::TransformCamelCase))
+                .menu("snake_case", Box::new(editor::TransformSnakeCase))
+                .menu("kebab-case", Box::new(editor::TransformKebabCase))
+                .menu("PascalCase", Box::new(editor::TransformPascalCase))
+        })
+        .submenu("Sort Lines", window, cx, move |menu, _, _| {
+            menu.action_context(sort_context.clone())
+                .menu("Ascending", Box::new(editor::SortLines))
+                .menu("Descending", Box::new(editor::SortLinesDescending))
+                .menu("Numeric", Box::new(editor::SortLinesNumeric))
+                .menu(
+                    "Numeric Descending",
+                    Box::new(editor::SortLinesNumericDescending),
+                )
+                .separator()
+                .menu("Unique", Box::new(editor::UniqueLines))
+                .menu("Reverse", Box::new(editor::ReverseLines))
+        })
+        .separator()
+        .item(
+            PopupMenuItem::new("Code Actions")
+                .icon("icons/lightbulb.svg")
+                .action(Box::new(workspace::ShowCodeActions)),
+        )
+}
+
+fn terminal_context_menu(
+    menu: PopupMenu,
+    action_context: FocusHandle,
+    has_selection: bool,
+    window: &mut Window,
+    cx: &mut Context<PopupMenu>,
+) -> PopupMenu {
+    use crate::actions::{terminal, workspace};
+
+    let export_context = action_context.clone();
+    menu.action_context(action_context)
+        .item(
+            PopupMenuItem::new("Copy")
+                .disabled(!has_selection)
+                .action(Box::new(terminal::Copy)),
+        )
+        .item(PopupMenuItem::new("Paste").action(Box::new(terminal::Paste)))
+        .separator()
+        .submenu("Export", window, cx, move |menu, _, _| {
+            menu.action_context(export_context.clone())
+                .menu("As SVG", Box::new(workspace::ExportTerminalAsSvg))
+                .menu("As HTML", Box::new(workspace::ExportTerminalAsHtml))
+        })
+        .separator()
+        .item(
+            PopupMenuItem::new("Hide Terminal")
+                .icon("icons/close.svg")
+                .action(Box::new(workspace::ToggleTerminal)),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helix_view::DocumentId;
+
+    fn tab_menu_intents(capabilities: TabContextMenuCapabilities) -> Vec<TabContextMenuIntent> {
+        tab_context_menu_entries(capabilities)
+            .into_iter()
+            .filter_map(|entry| match entry {
+                TabContextMenuEntry::Action(intent) => Some(intent),
+                TabContextMenuEntry::Separator => None,
+            })
+            .collect()
+    }
+
+    fn tab_menu_labels(capabilities: TabContextMenuCapabilities) -> Vec<&'static str> {
+        tab_context_menu_entries(capabilities)
+            .iter()
+            .map(|entry| match entry {
+                TabContextMenuEntry::Action(intent) => intent.label(false, false),
+                TabContextMenuEntry::Separator => "|",
+            })
+            .collect()
+    }
+
+    #[gpui::test]
+    fn menus_remember_the_entry_they_were_opened_on(cx: &mut gpui::TestAppContext) {
+        cx.update(|cx| {
+            let mut menus = ContextMenuState::default();
+            assert_eq!(menus.file_tree_target(), None);
+            assert!(!menus.any_tab_bar_menu_open());
+
+            menus.open_file_tree((4.0, 8.0), PathBuf::from("/work/src"), true, cx);
+            assert!(menus.file_tree.is_open());
+            assert_eq!(menus.file_tree.position(), (4.0, 8.0));
+            assert_eq!(
+                menus.file_tree_target(),
+                Some((Path::new("/work/src"), true))
+            );
+
+            let tab_id = TabId::Document(DocumentId::default());
+            let model = TabContextMenuModel {
+                target_index: Some(0),
+                visible_tabs: 1,
+                ..TabContextMenuModel::default()
+            };
+            menus.open_tab((1.0, 2.0), tab_id, model, cx);
+            assert!(menus.any_tab_bar_menu_open());
+            assert_eq!(menus.tab_target, Some(tab_id));
+            assert_eq!(menus.tab_model, model);
+
+            assert!(menus.close_tab_bar_menus(cx));
+            assert!(!menus.any_tab_bar_menu_open());
+            assert_eq!(menus.tab_target, None);
+
+            menus.open_lsp((3.0, 5.0));
+            assert!(menus.lsp_open);
+            assert_eq!(menus.lsp_position, (3.0, 5.0));
+        });
+    }
+
+    #[test]
+    fn file_tree_context_menu_items_follow_sidebar_intent_order() {
+        let actual: Vec<_> = file_tree_menu_intents()
+            .iter()
+            .map(|intent| intent.label())
+            .collect();
+        let expected: Vec<_> = ProjectTreeContextMenuIntent::common_file_operations()
+            .iter()
+            .map(|intent| intent.label())
+            .collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn tab_context_menu_items_match_zed_close_actions() {
+        let intents = tab_menu_intents(TabContextMenuCapabilities::default());
+        let unpinned_labels = intents
+            .iter()
+            .map(|intent| intent.label(false, false))
+            .collect::<Vec<_>>();
+        let pinned_labels = intents
+            .iter()
+            .map(|intent| intent.label(true, false))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            unpinned_labels,
+            vec![
+                "Close",
+                "Close Others",
+                "Close Left",
+                "Close Right",
+                "Close Clean",
+                "Close All",
+                "Pin Tab"
+            ]
+        );
+        assert_eq!(pinned_labels.last(), Some(&"Unpin Tab"));
+    }
+
+    #[test]
+    fn tab_context_menu_entries_match_zed_grouping() {
+        assert_eq!(
+            tab_menu_labels(TabContextMenuCapabilities::default()),
+            vec![
+                "Close",
+                "Close Others",
+                "|",
+                "Close Left",
+                "Close Right",
+                "|",
+                "Close Clean",
+                "Close All",
+                "|",
+                "Pin Tab"
+            ]
+        );
+    }
+
+    #[test]
+    fn tab_context_menu_entries_add_zed_file_path_actions_for_file_tabs() {
+        let reveal_label = reveal_in_file_manager_label(false);
+
+        assert_eq!(
+            tab_menu_labels(TabContextMenuCapabilities {
+                has_file_path: true,
+                ..TabContextMenuCapabilities::default()
+            }),
+            vec![
+                "Close",
+                "Close Others",
+                "|",
+                "Close Left",
+                "Close Right",
+                "|",
+                "Close Clean",
+                "Close All",
+                "|",
+                "Make File Read-Only",
+                "|",
+                "Copy Path",
+                "Copy Relative Path",
+                "|",
+                reveal_label,
+                "Show File History",
+                "|",
+                "Pin Tab"
+            ]
+        );
+    }
+
+    #[test]
+    fn tab_context_menu_intents_add_zed_readonly_toggle_for_file_tabs() {
+        let labels = tab_menu_intents(TabContextMenuCapabilities {
+            has_file_path: true,
+            ..TabContextMenuCapabilities::default()
+        })
+        .iter()
+        .map(|intent| intent.label(false, false))
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            labels,
+            vec![
+                "Close",
+                "Close Others",
+                "Close Left",
+                "Close Right",
+                "Close Clean",
+                "Close All",
+                "Make File Read-Only",
+                "Copy Path",
+                "Copy Relative Path",
+                reveal_in_file_manager_label(false),
+                "Show File History",
+                "Pin Tab",
+            ]
+        );
+    }
+
+    #[test]
+    fn tab_context_menu_readonly_toggle_label_matches_zed_state() {
+        assert_eq!(
+            TabContextMenuIntent::ToggleReadOnly.label(false, false),
+            "Make File Read-Only"
+        );
+        assert_eq!(
+            TabContextMenuIntent::ToggleReadOnly.label(false, true),
+            "Make File Editable"
+        );
+    }
+
+    #[test]
+    fn tab_context_menu_entries_add_reveal_project_panel_for_visible_project_paths() {
+        let reveal_label = reveal_in_file_manager_label(false);
+
+        assert_eq!(
+            tab_menu_labels(TabContextMenuCapabilities {
+                has_file_path: true,
+                has_project_panel_path: true,
+                ..TabContextMenuCapabilities::default()
+            }),
+            vec![
+                "Close",
+                "Close Others",
+                "|",
+                "Close Left",
+                "Close Right",
+                "|",
+                "Close Clean",
+                "Close All",
+                "|",
+                "Make File Read-Only",
+                "|",
+                "Copy Path",
+                "Copy Relative Path",
+                "|",
+                reveal_label,
+                "Show File History",
+                "|",
+                "Pin Tab",
+                "Reveal In Project Panel"
+            ]
+        );
+    }
+
+    #[test]
+    fn tab_context_menu_entries_add_open_terminal_when_parent_directory_exists() {
+        let reveal_label = reveal_in_file_manager_label(false);
+
+        assert_eq!(
+            tab_menu_labels(TabContextMenuCapabilities {
+                has_file_path: true,
+                has_project_panel_path: true,
+                has_terminal_directory: true,
+                is_readonly: false,
+            }),
+            vec![
+                "Close",
+                "Close Others",
+                "|",
+                "Close Left",
+                "Close Right",
+                "|",
+                "Close Clean",
+                "Close All",
+                "|",
+                "Make File Read-Only",
+                "|",
+                "Copy Path",
+                "Copy Relative Path",
+                "|",
+                reveal_label,
+                "Show File History",
+                "|",
+                "Pin Tab",
+                "Reveal In Project Panel",
+                "Open Terminal Here"
+            ]
+        );
+    }
+
+    #[test]
+    fn reveal_in_file_manager_label_matches_zed_platform_label() {
+        if cfg!(target_os = "macos") {
+            assert_eq!(reveal_in_file_manager_label(false), "Reveal in Finder");
+        } else if cfg!(target_os = "windows") {
+            assert_eq!(
+                reveal_in_file_manager_label(false),
+                "Reveal in File Explorer"
+            );
+        } else {
+            assert_eq!(
+                reveal_in_file_manager_label(false),
+                "Reveal in File Manager"
+            );
+        }
+        assert_eq!(reveal_in_file_manager_label(true), "Reveal in File Manager");
+    }
+
+    #[test]
+    fn tab_context_menu_disabled_states_match_zed_rules() {
+        assert!(!TabContextMenuIntent::Close.disabled(Some(0), 1, false));
+        assert!(TabContextMenuIntent::CloseOthers.disabled(Some(0), 1, true));
+        assert!(!TabContextMenuIntent::CloseOthers.disabled(Some(0), 2, true));
+        assert!(TabContextMenuIntent::CloseLeft.disabled(Some(0), 3, true));
+        assert!(TabContextMenuIntent::CloseRight.disabled(Some(2), 3, true));
+        assert!(TabContextMenuIntent::CloseClean.disabled(Some(1), 3, false));
+        assert!(!TabContextMenuIntent::CloseClean.disabled(Some(1), 3, true));
+        assert!(TabContextMenuIntent::ToggleReadOnly.disabled(None, 3, true));
+        assert!(!TabContextMenuIntent::ToggleReadOnly.disabled(Some(1), 3, true));
+    }
+
+    #[test]
+    fn tab_bar_split_menu_items_match_zed_directional_split_actions() {
+        let labels = TabBarSplitMenuIntent::ALL
+            .iter()
+            .map(|intent| intent.label())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            labels,
+            vec!["Split Right", "Split Left", "Split Up", "Split Down"]
+        );
+    }
+
+    #[test]
+    fn tab_bar_split_menu_commands_match_directional_helix_primitives() {
+        let commands = TabBarSplitMenuIntent::ALL
+            .iter()
+            .map(|intent| intent.commands())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            commands,
+            vec![
+                &["vsplit"][..],
+                &["vsplit", "swap_view_left"][..],
+                &["hsplit", "swap_view_up"][..],
+                &["hsplit"][..],
+            ]
+        );
+    }
+
+    #[test]
+    fn tab_bar_new_menu_items_match_zed_new_actions() {
+        let labels = TAB_BAR_NEW_MENU_ENTRIES
+            .iter()
+            .filter_map(|entry| match entry {
+                TabBarNewMenuEntry::Action(intent) => Some(intent.label()),
+                TabBarNewMenuEntry::Separator => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            labels,
+            vec![
+                "New File",
+                "Open File",
+                "Search Project",
+                "Search Symbols",
+                "New Terminal",
+                "New Center Terminal"
+            ]
+        );
+    }
+
+    #[test]
+    fn tab_bar_new_menu_entries_match_zed_grouping() {
+        let labels = TAB_BAR_NEW_MENU_ENTRIES
+            .iter()
+            .map(|entry| match entry {
+                TabBarNewMenuEntry::Action(intent) => intent.label(),
+                TabBarNewMenuEntry::Separator => "|",
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            labels,
+            vec![
+                "New File",
+                "Open File",
+                "|",
+                "Search Project",
+                "Search Symbols",
+                "|",
+                "New Terminal",
+                "New Center Terminal"
+            ]
+        );
+    }
+}
@@ -0,0 +1,249 @@
+// ABOUTME: The find bar over the editor and the in-document find and replace it drives
+// ABOUTME: Tracks whether the bar is open and where incremental searches start from
+
+use gpui::{Context, Entity};
+
+use super::Workspace;
+use super::panel_state::PanelKind;
+use crate::find_bar::{self, FindBar, FindBarEvent, FindStatus, FindStep};
+use crate::types::{EditorStatus, Severity};
+
+/// The find bar and the search it runs while open.
+pub(super) struct FindBarState {
+    pub(super) bar: Entity<FindBar>,
+    open: bool,
+    /// Where incremental searches start, captured when the bar opens
+    origin: Option<usize>,
+}
+
+impl FindBarState {
+    pub(super) fn new(bar: Entity<FindBar>) -> Self {
+        Self {
+            bar,
+            open: false,
+            origin: None,
+        }
+    }
+
+    pub(super) fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub(super) fn open(&mut self, origin: Option<usize>) {
+        self.open = true;
+        self.origin = origin;
+    }
+
+    pub(super) fn close(&mut self) {
+        self.open = false;
+        self.origin = None;
+    }
+
+    pub(super) fn origin(&self) -> Option<usize> {
+        self.origin
+    }
+}
+
+impl Workspace {
+    /// Opens the find bar, seeding the query from a single-line selection.
+    pub(super) fn show_find_bar(&mut self, cx: &mut Context<Self>) {
+        let (origin, seed) = {
+            let core = self.core.read(cx);
+            let view_id = core.editor.tree.focus;
+            core.editor
+                .tree
+                .try_get(view_id)
+                .and_then(|view| core.editor.documents.get(&view.doc))
+                .map(|doc| {
+                    let primary = doc.selection(view_id).primary();
+                    let fragment = primary.fragment(doc.text().slice(..));
+                    let seed = (primary.len() > 1 && !fragment.contains('\n'))
+                        .then(|| fragment.into_owned());
+                    (Some(primary.from()), seed)
+                })
+                .unwrap_or_default()
+        };
+
+        self.find_bar.open(origin);
+        if let Some(seed) = seed {
+            self.find_bar
+                .bar
+                .update(cx, |bar, cx| bar.set_query(seed, cx));
+        }
+        self.panels.request_focus(PanelKind::FindBar);
+        self.find_in_document(Some(FindStep::Incremental), cx);
+        cx.notify();
+    }
+
+    fn hide_find_bar(&mut self, cx: &mut Context<Self>) {
+        self.find_bar.close();
+        self.needs_focus_restore = true;
+        cx.notify();
+    }
+
+    pub(super) fn handle_find_bar_event(&mut self, event: FindBarEvent, cx: &mut Context<Self>) {
+        match event {
+            FindBarEvent::QueryChanged => self.find_in_document(Some(FindStep::Incremental), cx),
+            FindBarEvent::FindNext => self.find_in_document(Some(FindStep::Next), cx),
+            FindBarEvent::FindPrevious => self.find_in_document(Some(FindStep::Previous), cx),
+            FindBarEvent::ReplaceNext => self.replace_in_document(false, cx),
+            FindBarEvent::ReplaceAll => self.replace_in_document(true, cx),
+            FindBarEvent::Dismissed => self.hide_find_bar(cx),
+        }
+    }
+
+    /// Selects the match `step` leads to in the focused document and updates
+    /// the match count; `None` only recounts.
+    fn find_in_document(&mut self, step: Option<FindStep>, cx: &mut Context<Self>) {
+        let (query, options) = {
+            let bar = self.find_bar.bar.read(cx);
+            (bar.query(cx), bar.options())
+        };
+        let origin = self.find_bar.origin();
+        let mut reveal_center_view = None;
+
+        let status = self.core.update(cx, |core, cx| {
+            if query.is_empty() {
+                return FindStatus::Idle;
+            }
+            let Ok(regex) = find_bar::compile(&query, options) else {
+                return FindStatus::InvalidPattern;
+            };
+
+            // Leave the query in Helix's search register so `n`/`N` continue it.
+            if step.is_some_and(|step| step != FindStep::Incremental) {
+                let pattern = find_bar::search_pattern(&query, options);
+                let latest = core
+                    .editor
+                    .registers
+                    .first('/', &core.editor)
+                    .map(|value| value.into_owned());
+                if latest.as_deref() != Some(pattern.as_str()) {
+                    let _ = core.editor.registers.push('/', pattern);
+                }
+                core.editor.registers.last_search_register = '/';
+            }
+
+            let wrap = core.editor.config().search.wrap_around;
+            let view_id = core.editor.tree.focus;
+            let Some(doc_id) = core.editor.tree.try_get(view_id).map(|view| view.doc) else {
+                return FindStatus::Idle;
+            };
+            let Some(doc) = core.editor.documents.get_mut(&doc_id) else {
+                return FindStatus::Idle;
+            };
+            let matches = find_bar::find_matches(doc.text().slice(..), &regex);
+            let selection = doc.selection(view_id).clone();
+            let primary = selection.primary();
+            let primary_range = primary.from()..primary.to();
+
+            let current = match step {
+                Some(step) => {
+                    let origin = origin.unwrap_or(primary_range.start);
+                    let target =
+                        find_bar::step_target(&matches, &primary_range, origin, step, wrap);
+                    if let Some(index) = target {
+                        let range =
+                            helix_core::Range::new(matches[index].start, matches[index].end);
+                        let primary_index = selection.primary_index();
+                        doc.set_selection(view_id, selection.replace(primary_index, range));
+                        reveal_center_view = Some(view_id);
+                        cx.notify();
+                    }
+                    target
+                }
+                None => find_bar::current_match(&matches, &primary_range),
+            };
+
+            FindStatus::Matches {
+                current,
+                total: matches.len(),
+            }
+        });
+
+        self.find_bar
+            .bar
+            .update(cx, |bar, cx| bar.set_status(status, cx));
+
+        if let Some(view_id) = reveal_center_view
+            && let Some(view_entity) = self.view_manager.get_document_view(&view_id)
+        {
+            view_entity.update(cx, |view, cx| {
+                view.request_cursor_center();
+                cx.notify();
+            });
+        }
+    }
+
+    /// Replaces the selected match and moves on to the next one, or replaces
+    /// every match; either way as a single undo step.
+    fn replace_in_document(&mut self, all: bool, cx: &mut Context<Self>) {
+        let (query, replacement, options) = {
+            let bar = self.find_bar.bar.read(cx);
+            (bar.query(cx), bar.replacement(cx), bar.options())
+        };
+        if query.is_empty() {
+            return;
+        }
+        let Ok(regex) = find_bar::compile(&query, options) else {
+            self.find_bar
+                .bar
+                .update(cx, |bar, cx| bar.set_status(FindStatus::InvalidPattern, cx));
+            return;
+        };
+
+        let replaced = self.core.update(cx, |core, cx| {
+            let view_id = core.editor.tree.focus;
+            let Some(doc_id) = core.editor.tree.try_get(view_id).map(|view| view.doc) else {
+                return 0;
+            };
+            let view = core.editor.tree.get_mut(view_id);
+            let Some(doc) = core.editor.documents.get_mut(&doc_id) else {
+                return 0;
+            };
+            let matches = find_bar::find_matches(doc.text().slice(..), &regex);
+            let targets = if all {
+                matches
+            } else {
+                let primary = doc.selection(view_id).primary();
+                find_bar::current_match(&matches, &(primary.from()..primary.to()))
+                    .map(|index| vec![matches[index].clone()])
+                    .unwrap_or_default()
+            };
+            if targets.is_empty() {
+                return 0;
+            }
+
+            let transaction = find_bar::replace_all_transaction(doc.text(), &targets, &replacement);
+            // Commit pending edits first so undo reverts only the replacement.
+            doc.append_changes_to_history(view);
+            doc.apply(&transaction, view_id);
+            doc.append_changes_to_history(view);
+            if !all {
+                // Continue after the inserted text rather than re-matching it.
+                let end = targets[0].start + replacement.chars().count();
+                doc.set_selection(view_id, helix_core::Selection::point(end));
+            }
+            cx.notify();
+            targets.len()
+        });
+
+        if all {
+            if replaced > 0 {
+                self.push_editor_status_notification(
+                    EditorStatus {
+                        status: format!(
+                            "Replaced {replaced} occurrence{}",
+                            if replaced == 1 { "" } else { "s" }
+                        ),
+                        severity: Severity::Info,
+                    },
+                    cx,
+                );
+            }
+            self.find_in_document(None, cx);
+        } else {
+            self.find_in_document(Some(FindStep::Next), cx);
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use nucleotide_env::{EnvironmentOrigin, ProjectEnvironment};
+use nucleotide_workspace::{ProjectEnvironmentOrigin, ProjectEnvironmentSnapshot};
+
+use crate::picker_view::PickerItem;
+use crate::types::EnvironmentRecapture;
+
+/// The capture currently shown for a project root and the one before it.
+pub(super) struct EnvironmentCaptureHistory {
+    pub(super) previous: Option<ProjectEnvironmentSnapshot>,
+    pub(super) current: ProjectEnvironmentSnapshot,
+}
+
+impl EnvironmentCaptureHistory {
+    /// Records `capture`, keeping the last different capture for diffing.
+    pub(super) fn record(
+        history: Option<Self>,
+        capture: ProjectEnvironmentSnapshot,
+    ) -> EnvironmentCaptureHistory {
+        match history {
+            Some(history) if history.current.variables == capture.variables => Self {
+                previous: history.previous,
+                current: capture,
+            },
+            Some(history) => Self {
+                previous: Some(history.current),
+                current: capture,
+            },
+            None => Self {
+                previous: None,
+                current: capture,
+            },
+        }
+    }
+}
+
+/// How a variable differs from the previous capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum VariableChange {
+    Unchanged,
+    Added,
+    Changed { previous: String },
+    Removed { previous: String },
+}
+
+/// Picker payload for one variable row.
+#[derive(Debug, Clone)]
+struct EnvironmentVariableEntry {
+    name: String,
+    value: Option<String>,
+    change: VariableChange,
+}
+
+/// Loads the environment local language servers and terminals receive for
+/// `root`, optionally discarding the cached capture first.
+pub(super) async fn load_local_environment(
+    project_environment: &ProjectEnvironment,
+    root: &Path,
+    recapture: bool,
+) -> Result<ProjectEnvironmentSnapshot, nucleotide_env::ShellEnvironmentError> {
+    if recapture {
+        project_environment.invalidate_directory_cache(root).await;
+    }
+
+    let variables = project_environment
+        .get_environment_for_directory(root)
+        .await?;
+    let origin = match project_environment.get_cached_origin(root).await {
+        Some(origin) => project_environment_origin(origin),
+        None if project_environment.cli_environment().is_some() => ProjectEnvironmentOrigin::Cli,
+        None => ProjectEnvironmentOrigin::Unknown,
+    };
+
+    Ok(ProjectEnvironmentSnapshot {
+        root: root.to_path_buf(),
+        variables: variables.into_iter().collect(),
+        origin,
+        diagnostics: project_environment.get_environment_diagnostics(root).await,
+    })
+}
+
+fn project_environment_origin(origin: EnvironmentOrigin) -> ProjectEnvironmentOrigin {
+    match origin {
+        EnvironmentOrigin::Cli => ProjectEnvironmentOrigin::Cli,
+        EnvironmentOrigin::NativeFlake => ProjectEnvironmentOrigin::NativeFlake,
+        EnvironmentOrigin::DirectoryShell => ProjectEnvironmentOrigin::DirectoryShell,
+        EnvironmentOrigin::Process => ProjectEnvironmentOrigin::ProcessBaseline,
+    }
+}
+
+pub(super) fn origin_label(origin: ProjectEnvironmentOrigin) -> &'static str {
+    match origin {
+        ProjectEnvironmentOrigin::Cli => "inherited from the launching terminal",
+        ProjectEnvironmentOrigin::NativeFlake => "nix flake (.envrc)",
+        ProjectEnvironmentOrigin::DirectoryShell => "login shell capture",
+        ProjectEnvironmentOrigin::ProcessBaseline => "process environment (shell capture failed)",
+        ProjectEnvironmentOrigin::Unknown => "unknown",
+    }
+}
+
+/// Every variable in either capture, sorted by name, with its change.
+pub(super) fn environment_changes(
+    previous: Option<&BTreeMap<String, String>>,
+    current: &BTreeMap<String, String>,
+) -> Vec<(String, VariableChange)> {
+    let Some(previous) = previous else {
+        return current
+            .keys()
+            .map(|name| (name.clone(), VariableChange::Unchanged))
+            .collect();
+    };
+
+    let mut changes: Vec<(String, VariableChange)> = current
+        .iter()
+        .map(|(name, value)| {
+            let change = match previous.get(name) {
+                None => VariableChange::Added,
+                Some(old) if old != value => VariableChange::Changed {
+                    previous: old.clone(),
+                },
+                Some(_) => VariableChange::Unchanged,
+            };
+            (name.clone(), change)
+        })
+        .collect();
+    changes.extend(
+        previous
+            .iter()
+            .filter(|(name, _)| !current.contains_key(*name))
+            .map(|(name, value)| {
+                (
+                    name.clone(),
+                    VariableChange::Removed {
+                        previous: value.clone(),
+                    },
+                )
+            }),
+    );
+    changes.sort_by(|(left, _), (right, _)| left.cmp(right));
+    changes
+}
+
+/// Picker rows: a re-capture row with the capture summary, then changed
+/// variables, then the rest.
+pub(super) fn environment_inspector_items(history: &EnvironmentCaptureHistory) -> Vec<PickerItem> {
+    let capture = &history.current;
+    let changes = environment_changes(
+        history
+            .previous
+            .as_ref()
+            .map(|previous| &previous.variables),
+        &capture.variables,
+    );
+    let changed = changes
+        .iter()
+        .filter(|(_, change)| *change != VariableChange::Unchanged)
+        .count();
+
+    let mut summary = format!(
+        "{} · {} variables",
+        origin_label(capture.origin),
+        capture.variables.len()
+    );
+    if history.previous.is_some() {
+        summary.push_str(&format!(" · {changed} changed since previous capture"));
+    }
+    if !capture.diagnostics.is_empty() {
+        summary.push_str(" · has diagnostics");
+    }
+
+    let mut items = vec![PickerItem {
+        label: "Re-capture environment now".into(),
+        sublabel: Some(summary.into()),
+        data: Arc::new(EnvironmentRecapture {
+            root: capture.root.clone(),
+        }),
+        file_path: None,
+        vcs_status: None,
+        columns: None,
+    }];
+
+    let (changed, unchanged): (Vec<_>, Vec<_>) = changes
+        .into_iter()
+        .partition(|(_, change)| *change != VariableChange::Unchanged);
+    for (name, change) in changed.into_iter().chain(unchanged) {
+        let value = capture.variables.get(&name).cloned();
+        let (marker, sublabel) = match &change {
+            VariableChange::Unchanged => (" ", value.clone().unwrap_or_default()),
+            VariableChange::Added => ("+", value.clone().unwrap_or_default()),
+            VariableChange::Changed { .. } => ("~", value.clone().unwrap_or_default()),
+            VariableChange::Removed { previous } => ("-", previous.clone()),
+        };
+        items.push(PickerItem {
+            label: format!("{marker} {name}").into(),
+            sublabel: Some(sublabel.into()),
+            data: Arc::new(EnvironmentVariableEntry {
+                name,
+                value,
+                change,
+            }),
+            file_path: None,
+            vcs_status: None,
+            columns: None,
+        });
+    }
+
+    items
+}
+
+/// Preview text for an inspector row.
+pub(super) fn environment_inspector_preview(
+    item: &PickerItem,
+    capture: &ProjectEnvironmentSnapshot,
+) -> Option<String> {
+    if item.data.downcast_ref::<EnvironmentRecapture>().is_some() {
+        let mut preview = format!(
+            "Project root: {}\nOrigin: {}\nVariables: {}\n\n\
+             Language servers and terminal sessions started in this project receive this \
+             environment. Running sessions keep the environment they started with.\n",
+            capture.root.display(),
+            origin_label(capture.origin),
+            capture.variables.len(),
+        );
+        if !capture.diagnostics.is_empty() {
+            preview.push_str("\nDiagnostics:\n");
+            for diagnostic in &capture.diagnostics {
+                preview.push_str(&format!("  {diagnostic}\n"));
+            }
+        }
+        return Some(preview);
+    }
+
+    let entry = item.data.downcast_ref::<EnvironmentVariableEntry>()?;
+    let mut preview = format!("{}\n\n", entry.name);
+    match &entry.change {
+        VariableChange::Unchanged => {}
+        VariableChange::Added => preview.push_str("Added since the previous capture\n\n"),
+        VariableChange::Changed { previous } => {
+            preview.push_str("Changed since the previous capture\n\nPrevious:\n");
+            push_value(&mut preview, &entry.name, previous);
+            preview.push_str("\nCurrent:\n");
+        }
+        VariableChange::Removed { previous } => {
+            preview.push_str("Removed since the previous capture\n\nPrevious:\n");
+            push_value(&mut preview, &entry.name, previous);
+        }
+    }
+    if let Some(value) = &entry.value {
+        push_value(&mut preview, &entry.name, value);
+    }
+    Some(preview)
+}
+
+/// Writes one path entry per line for PATH-like variables.
+fn push_value(preview: &mut String, name: &str, value: &str) {
+    let separator = if cfg!(windows) { ';' } else { ':' };
+    if name.ends_with("PATH") || name.ends_with("DIRS") {
+        for entry in value.split(separator) {
+            preview.push_str(&format!("  {entry}\n"));
+        }
+    } else {
+        preview.push_str(&format!("  {value}\n"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn changes_cover_added_changed_and_removed_variables() {
+        let previous = variables(&[("HOME", "/home/a"), ("OLD", "1"), ("PATH", "/bin")]);
+        let current = variables(&[("HOME", "/home/a"), ("NEW", "2"), ("PATH", "/nix/bin:/bin")]);
+
+        assert_eq!(
+            environment_changes(Some(&previous), &current),
+            vec![
+                ("HOME".to_string(), VariableChange::Unchanged),
+                ("NEW".to_string(), VariableChange::Added),
+                (
+                    "OLD".to_string(),
+                    VariableChange::Removed {
+                        previous: "1".to_string()
+                    }
+                ),
+                (
+                    "PATH".to_string(),
+                    VariableChange::Changed {
+                        previous: "/bin".to_string()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn recording_an_identical_capture_keeps_the_previous_one() {
+        let snapshot = |pairs: &[(&str, &str)]| ProjectEnvironmentSnapshot {
+            root: "/project".into(),
+            variables: variables(pairs),
+            origin: ProjectEnvironmentOrigin::DirectoryShell,
+            diagnostics: Vec::new(),
+        };
+
+        let history = EnvironmentCaptureHistory::record(None, snapshot(&[("A", "1")]));
+        let history = EnvironmentCaptureHistory::record(Some(history), snapshot(&[("A", "2")]));
+        let history = EnvironmentCaptureHistory::record(Some(history), snapshot(&[("A", "2")]));
+
+        assert_eq!(
+            history
+                .previous
+                .as_ref()
+                .and_then(|previous| previous.variables.get("A")),
+            Some(&"1".to_string())
+        );
+        assert_eq!(history.current.variables.get("A"), Some(&"2".to_string()));
+    }
+}
@@ -0,0 +1,989 @@
+// ABOUTME: File operations the workspace runs for the file tree, name prompts, deletes and undo
+// ABOUTME: Dispatches them to the workspace backend and tells language servers once they land
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use gpui::{App, Context, Entity};
+use nucleotide_events::v2::terminal::TerminalId;
+use nucleotide_events::v2::workspace::{
+    DeleteMode, Event as WsEvent, FileOpIntent, FileOpOutcome, PathCopyKind,
+};
+use nucleotide_logging::{debug, warn};
+use nucleotide_ui::{ButtonVariant, ConfirmDialog, ConfirmDialogEvent};
+use nucleotide_workspace::{FileKind, WorkspaceBackendHandle, WorkspaceIdentity};
+
+use super::{BackgroundActivityId, Workspace};
+use crate::Core;
+use crate::file_tree::sidebar::ProjectTreeContextMenuIntent;
+use crate::types::{EditorStatus, Severity};
+
+// Pending file operation kinds awaiting user input (used with the prompt overlay)
+pub(super) enum PendingFileOp {
+    NewFile {
+        parent: std::path::PathBuf,
+    },
+    NewFolder {
+        parent: std::path::PathBuf,
+    },
+    Rename {
+        path: std::path::PathBuf,
+        was_dir: bool,
+    },
+    Duplicate {
+        path: std::path::PathBuf,
+        is_dir: bool,
+    },
+    NewProject {
+        template: crate::config::ProjectTemplateConfig,
+        location: std::path::PathBuf,
+    },
+}
+
+/// What submitting a name to the prompt did with the operation waiting for it.
+pub(super) enum PromptSubmission {
+    /// The operation was dispatched to the workspace backend
+    Dispatched,
+    /// A new file still needs its template chosen
+    NewFile { parent: PathBuf },
+    /// A new project still needs scaffolding in the terminal panel
+    NewProject {
+        template: crate::config::ProjectTemplateConfig,
+        location: PathBuf,
+    },
+}
+
+/// A New Project command running in the terminal panel.
+pub(super) struct ScaffoldingProject {
+    pub(super) terminal_id: TerminalId,
+    pub(super) project: crate::project_templates::NewProject,
+    pub(super) activity_id: BackgroundActivityId,
+}
+
+/// Work a finished file operation leaves for the workspace.
+#[derive(Debug, Default)]
+pub(super) struct FileOpFollowUp {
+    pub(super) rescan: Option<PathBuf>,
+    pub(super) open: Option<PathBuf>,
+    pub(super) status: Option<EditorStatus>,
+}
+
+#[derive(Debug, Clone)]
+enum LspFileOperationNotification {
+    Created {
+        path: PathBuf,
+        is_dir: bool,
+    },
+    Deleted {
+        path: PathBuf,
+        was_dir: bool,
+    },
+    Renamed {
+        old_path: PathBuf,
+        new_path: PathBuf,
+        was_dir: bool,
+    },
+}
+
+/// A path waiting to be deleted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingDelete {
+    path: PathBuf,
+    was_directory: bool,
+    /// Whether the confirmation dialog is showing
+    confirming: bool,
+}
+
+/// The delete the user asked for, kept until it runs or is cancelled.
+#[derive(Debug, Default)]
+struct DeleteRequest(Option<PendingDelete>);
+
+impl DeleteRequest {
+    /// Remembers `path` as the next path to delete, replacing any earlier one.
+    fn request(&mut self, path: PathBuf, was_directory: bool) {
+        self.0 = Some(PendingDelete {
+            path,
+            was_directory,
+            confirming: false,
+        });
+    }
+
+    /// Shows the confirmation dialog for the requested delete.
+    fn confirm(&mut self) {
+        if let Some(delete) = &mut self.0 {
+            delete.confirming = true;
+        }
+    }
+
+    fn confirm_open(&self) -> bool {
+        self.0.as_ref().is_some_and(|delete| delete.confirming)
+    }
+
+    fn path(&self) -> Option<&Path> {
+        self.0.as_ref().map(|delete| delete.path.as_path())
+    }
+
+    /// Takes the requested delete, closing its confirmation dialog. Returns
+    /// the path and whether it was a directory.
+    fn take(&mut self) -> Option<(PathBuf, bool)> {
+        self.0
+            .take()
+            .map(|delete| (delete.path, delete.was_directory))
+    }
+}
+
+pub(super) struct FileOpsState {
+    core: Entity<Core>,
+    runtime: tokio::runtime::Handle,
+    /// Operation waiting for a name typed into the prompt
+    pending: Option<PendingFileOp>,
+    /// Template snippets to insert into new files once they are created and opened
+    templates: HashMap<PathBuf, String>,
+    /// New Project command whose folder opens once it succeeds
+    pub(super) scaffolding_project: Option<ScaffoldingProject>,
+    /// Defer a file tree refresh until after processing core events
+    pub(super) needs_file_tree_refresh: bool,
+    delete: DeleteRequest,
+}
+
+impl FileOpsState {
+    /// Starts handling the outcomes the workspace backend reports for
+    /// dispatched operations.
+    pub(super) fn new(
+        core: Entity<Core>,
+        runtime: tokio::runtime::Handle,
+        cx: &mut Context<Workspace>,
+    ) -> Self {
+        let events = core.update(cx, |core, _cx| {
+            core.workspace_file_ops.take_event_receiver()
+        });
+        if let Some(mut events) = events {
+            cx.spawn(async move |this, cx| {
+                while let Some(event) = events.recv().await {
+                    let Some(this) = this.upgrade() else {
+                        break;
+                    };
+                    this.update(cx, |workspace, cx| {
+                        let follow_up = workspace.file_ops.handle_event(event, cx);
+                        workspace.apply_file_op_follow_up(follow_up, cx);
+                    });
+                }
+            })
+            .detach();
+        }
+
+        Self {
+            core,
+            runtime,
+            pending: None,
+            templates: HashMap::new(),
+            scaffolding_project: None,
+            needs_file_tree_refresh: false,
+            delete: DeleteRequest::default(),
+        }
+    }
+
+    /// Remembers `op` and asks for the name it needs.
+    fn prompt_for_name(
+        &mut self,
+        op: PendingFileOp,
+        title: &'static str,
+        initial: String,
+        cx: &mut App,
+    ) {
+        self.pending = Some(op);
+        self.core.update(cx, move |_core, cx| {
+            let prompt = crate::prompt::Prompt::native(title, initial, |_input| {});
+            cx.emit(crate::Update::Prompt(prompt));
+        });
+    }
+
+    /// Runs the file operations of the file tree menu. Intents that are not
+    /// file operations are handled by the workspace.
+    pub(super) fn handle_project_tree_operation(
+        &mut self,
+        intent: ProjectTreeContextMenuIntent,
+        path: PathBuf,
+        is_directory: bool,
+        project_root: Option<&Path>,
+        cx: &mut Context<Workspace>,
+    ) {
+        match intent {
+            ProjectTreeContextMenuIntent::NewFile => {
+                let parent = context_menu_target_parent_path(&path, is_directory);
+                self.prompt_for_name(
+                    PendingFileOp::NewFile { parent },
+                    "New file name",
+                    String::new(),
+                    cx,
+                );
+            }
+            ProjectTreeContextMenuIntent::NewFolder => {
+                let parent = context_menu_target_parent_path(&path, is_directory);
+                self.prompt_for_name(
+                    PendingFileOp::NewFolder { parent },
+                    "New folder name",
+                    String::new(),
+                    cx,
+                );
+            }
+            ProjectTreeContextMenuIntent::Rename => {
+                self.start_rename(path, is_directory, cx);
+            }
+            ProjectTreeContextMenuIntent::Delete => {
+                self.request_delete(path, is_directory, cx);
+            }
+            ProjectTreeContextMenuIntent::Duplicate => {
+                let base_name = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|s| format!("{} copy", s))
+                    .unwrap_or_else(|| "copy".to_string());
+                self.prompt_for_name(
+                    PendingFileOp::Duplicate {
+                        path,
+                        is_dir: is_directory,
+                    },
+                    "Duplicate as",
+                    base_name,
+                    cx,
+                );
+            }
+            ProjectTreeContextMenuIntent::CopyPath => {
+                let text = path.display().to_string();
+                if !Workspace::copy_to_clipboard_impl(&text) {
+                    warn!(path=%text, "Failed to copy path to clipboard");
+                }
+                self.core
+                    .read(cx)
+                    .dispatch_workspace_event(WsEvent::FileOpRequested {
+                        intent: FileOpIntent::CopyPath {
+                            path,
+                            kind: PathCopyKind::Absolute,
+                        },
+                    });
+            }
+            ProjectTreeContextMenuIntent::CopyRelativePath => {
+                let text = project_root
+                    .and_then(|root| path.strip_prefix(root).ok())
+                    .unwrap_or(&path)
+                    .display()
+                    .to_string();
+                if !Workspace::copy_to_clipboard_impl(&text) {
+                    warn!(path=%text, "Failed to copy relative path to clipboard");
+                }
+                self.core
+                    .read(cx)
+                    .dispatch_workspace_event(WsEvent::FileOpRequested {
+                        intent: FileOpIntent::CopyPath {
+                            path,
+                            kind: PathCopyKind::RelativeToWorkspace,
+                        },
+                    });
+            }
+            ProjectTreeContextMenuIntent::ShowHistory
+            | ProjectTreeContextMenuIntent::OpenTerminalHere
+            | ProjectTreeContextMenuIntent::RevealInOs => {}
+        }
+    }
+
+    /// Asks for the new name of `path`, starting from its current one.
+    pub(super) fn start_rename(&mut self, path: PathBuf, was_dir: bool, cx: &mut App) {
+        let current_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("")
+            .to_string();
+        self.prompt_for_name(
+            PendingFileOp::Rename { path, was_dir },
+            "Rename to",
+            current_name,
+            cx,
+        );
+    }
+
+    /// Dispatches the operation waiting for a name, now that `name` was
+    /// submitted. Returns `None` when no operation was waiting.
+    pub(super) fn submit_name(
+        &mut self,
+        name: &str,
+        cx: &mut Context<Workspace>,
+    ) -> Option<PromptSubmission> {
+        let (event, refresh_dir, notification) = match self.pending.take()? {
+            PendingFileOp::NewFile { parent } => {
+                return Some(PromptSubmission::NewFile { parent });
+            }
+            PendingFileOp::NewProject { template, location } => {
+                return Some(PromptSubmission::NewProject { template, location });
+            }
+            PendingFileOp::NewFolder { parent } => (
+                WsEvent::FileOpRequested {
+                    intent: FileOpIntent::NewFolder {
+                        parent: parent.clone(),
+                        name: name.to_string(),
+                    },
+                },
+                Some(parent.clone()),
+                LspFileOperationNotification::Created {
+                    path: parent.join(name),
+                    is_dir: true,
+                },
+            ),
+            PendingFileOp::Rename { path, was_dir } => {
+                let parent = path.parent().map(Path::to_path_buf);
+                let new_path = parent
+                    .as_deref()
+                    .unwrap_or_else(|| Path::new("."))
+                    .join(name);
+                (
+                    WsEvent::FileOpRequested {
+                        intent: FileOpIntent::Rename {
+                            path: path.clone(),
+                            new_name: name.to_string(),
+                        },
+                    },
+                    parent,
+                    LspFileOperationNotification::Renamed {
+                        old_path: path,
+                        new_path,
+                        was_dir,
+                    },
+                )
+            }
+            PendingFileOp::Duplicate { path, is_dir } => {
+                let parent = path.parent().map(Path::to_path_buf);
+                let target_path = parent
+                    .as_deref()
+                    .unwrap_or_else(|| Path::new("."))
+                    .join(name);
+                (
+                    WsEvent::FileOpRequested {
+                        intent: FileOpIntent::Duplicate {
+                            path,
+                            target_name: name.to_string(),
+                        },
+                    },
+                    parent,
+                    LspFileOperationNotification::Created {
+                        path: target_path,
+                        is_dir,
+                    },
+                )
+            }
+        };
+
+        self.dispatch(event, cx);
+        self.observe_completion(notification, refresh_dir, cx);
+        Some(PromptSubmission::Dispatched)
+    }
+
+    fn delete_mode(&self, cx: &App) -> DeleteMode {
+        let core = self.core.read(cx);
+        effective_delete_mode(
+            core.config.gui.file_ops.delete_behavior,
+            &core.workspace_backend.identity(),
+        )
+    }
+
+    /// Deletes `path`, first asking for confirmation when the delete can't
+    /// be undone.
+    pub(super) fn request_delete(
+        &mut self,
+        path: PathBuf,
+        was_directory: bool,
+        cx: &mut Context<Workspace>,
+    ) {
+        self.delete.request(path, was_directory);
+        if delete_confirmation_required(self.delete_mode(cx)) {
+            self.delete.confirm();
+            cx.notify();
+        } else {
+            self.perform_delete(cx);
+        }
+    }
+
+    pub(super) fn delete_confirm_open(&self) -> bool {
+        self.delete.confirm_open()
+    }
+
+    pub(super) fn delete_confirm_dialog(&self, cx: &App) -> ConfirmDialog {
+        let delete_mode = self.delete_mode(cx);
+        let message = delete_confirmation_message(self.delete.path(), delete_mode);
+        let confirm_label = delete_confirmation_label(delete_mode);
+
+        ConfirmDialog::new("Confirm Delete", message, confirm_label)
+            .confirm_variant(ButtonVariant::Danger)
+    }
+
+    fn cancel_delete(&mut self, cx: &mut Context<Workspace>) {
+        self.delete.take();
+        cx.notify();
+    }
+
+    /// Runs the requested delete.
+    fn perform_delete(&mut self, cx: &mut Context<Workspace>) {
+        if let Some((path, was_dir)) = self.delete.take() {
+            let event = WsEvent::FileOpRequested {
+                intent: FileOpIntent::Delete {
+                    path: path.clone(),
+                    mode: self.delete_mode(cx),
+                },
+            };
+            self.dispatch(event, cx);
+            let refresh_dir = path.parent().map(Path::to_path_buf);
+            self.observe_completion(
+                LspFileOperationNotification::Deleted { path, was_dir },
+                refresh_dir,
+                cx,
+            );
+        }
+        cx.notify();
+    }
+
+    /// Templates configured for a new file at `path`.
+    pub(super) fn templates_for(
+        &self,
+        path: &Path,
+        cx: &App,
+    ) -> Vec<crate::file_templates::FileTemplate> {
+        let core = self.core.read(cx);
+        let entries = &core.config.gui.file_templates;
+        if entries.is_empty() {
+            return Vec::new();
+        }
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let language = nucleotide_ui::file_associations()
+            .language_for(&file_name)
+            .map(str::to_string)
+            .or_else(|| {
+                let loader = core.editor.syn_loader.load();
+                loader
+                    .language_for_filename(path)
+                    .map(|language| loader.language(language).config().language_id.clone())
+            });
+        crate::file_templates::matching_templates(
+            entries,
+            &crate::file_templates::templates_dir(),
+            path,
+            language.as_deref(),
+        )
+    }
+
+    /// Creates the file `name` in `parent`. With a template, the file is
+    /// opened once it exists and the template is inserted as a snippet.
+    /// Returns the error to show when the template can't be read.
+    pub(super) fn create_file(
+        &mut self,
+        parent: PathBuf,
+        name: String,
+        template: Option<crate::file_templates::FileTemplate>,
+        cx: &mut Context<Workspace>,
+    ) -> Option<EditorStatus> {
+        let path = parent.join(&name);
+        if let Some(template) = template {
+            match std::fs::read_to_string(&template.source) {
+                Ok(text) => {
+                    self.templates.insert(path.clone(), text);
+                }
+                Err(error) => {
+                    return Some(EditorStatus {
+                        status: format!(
+                            "Could not read template '{}': {error}",
+                            template.source.display()
+                        ),
+                        severity: Severity::Error,
+                    });
+                }
+            }
+        }
+
+        self.dispatch(
+            WsEvent::FileOpRequested {
+                intent: FileOpIntent::NewFile {
+                    parent: parent.clone(),
+                    name,
+                },
+            },
+            cx,
+        );
+        self.observe_completion(
+            LspFileOperationNotification::Created {
+                path,
+                is_dir: false,
+            },
+            Some(parent),
+            cx,
+        );
+        None
+    }
+
+    /// Takes the template to insert into the new file at `path`, once it
+    /// has been opened.
+    pub(super) fn take_template(&mut self, path: &Path) -> Option<String> {
+        self.templates.remove(path)
+    }
+
+    /// Asks where the project of `template` goes, then for its name.
+    pub(super) fn request_new_project_location(
+        &mut self,
+        template: crate::config::ProjectTemplateConfig,
+        cx: &mut Context<Workspace>,
+    ) {
+        let receiver = cx.prompt_for_paths(gpui::PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: Some("Create Project Here".into()),
+        });
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = receiver.await else {
+                return;
+            };
+            let Some(location) = paths.into_iter().next() else {
+                return;
+            };
+            if let Some(this) = this.upgrade() {
+                this.update(cx, |workspace, cx| {
+                    workspace.file_ops.prompt_for_name(
+                        PendingFileOp::NewProject { template, location },
+                        "Project name",
+                        String::new(),
+                        cx,
+                    );
+                });
+            }
+        })
+        .detach();
+    }
+
+    /// Undoes the last file operation. Returns the status to show when
+    /// there is nothing to undo.
+    pub(super) fn undo_last(&self, cx: &App) -> Option<EditorStatus> {
+        let core = self.core.read(cx);
+        if !core.workspace_file_ops.can_undo() {
+            return Some(EditorStatus {
+                status: "No file operation to undo".to_string(),
+                severity: Severity::Info,
+            });
+        }
+
+        core.dispatch_workspace_event(WsEvent::FileOpRequested {
+            intent: FileOpIntent::UndoLast,
+        });
+        None
+    }
+
+    fn dispatch(&self, event: WsEvent, cx: &App) {
+        self.core.read(cx).workspace_file_ops.dispatch(&event);
+    }
+
+    /// Waits for the backend to show the result of an operation, then tells
+    /// language servers about it and rescans `refresh_dir`.
+    fn observe_completion(
+        &self,
+        notification: LspFileOperationNotification,
+        refresh_dir: Option<PathBuf>,
+        cx: &mut Context<Workspace>,
+    ) {
+        let workspace_backend = self.core.read(cx).workspace_backend.clone();
+        let runtime = self.runtime.clone();
+
+        cx.spawn(async move |this, cx| {
+            let notification_for_task = notification.clone();
+            let observed = match runtime
+                .spawn(wait_for_file_operation_notification(
+                    workspace_backend,
+                    notification_for_task,
+                ))
+                .await
+            {
+                Ok(observed) => observed,
+                Err(error) => {
+                    warn!(
+                        error = %error,
+                        ?notification,
+                        "File operation completion observer failed"
+                    );
+                    false
+                }
+            };
+
+            if let Some(this) = this.upgrade() {
+                this.update(cx, |workspace, cx| {
+                    if observed {
+                        workspace.file_ops.notify_lsp(notification.clone(), cx);
+                    } else {
+                        warn!(
+                            ?notification,
+                            "File operation was not observed in workspace backend before timeout"
+                        );
+                    }
+
+                    if let Some(refresh_dir) = refresh_dir.as_ref() {
+                        workspace.rescan_directory(refresh_dir, cx);
+                    }
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn notify_lsp(&self, notification: LspFileOperationNotification, cx: &mut App) {
+        self.core.update(cx, move |core, _cx| {
+            for language_server in core.editor.language_servers.iter_clients() {
+                if !language_server.is_initialized() {
+                    continue;
+                }
+
+                match &notification {
+                    LspFileOperationNotification::Created { path, is_dir } => {
+                        language_server.did_create(path, *is_dir);
+                    }
+                    LspFileOperationNotification::Deleted { path, was_dir } => {
+                        language_server.did_delete(path, *was_dir);
+                    }
+                    LspFileOperationNotification::Renamed {
+                        old_path,
+                        new_path,
+                        was_dir,
+                    } => {
+                        language_server.did_rename(old_path, new_path, *was_dir);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Handles an outcome the workspace backend reported and returns what
+    /// the workspace still has to do about it.
+    fn handle_event(&mut self, event: WsEvent, cx: &mut App) -> FileOpFollowUp {
+        match event {
+            WsEvent::FileOpCompleted {
+                intent: FileOpIntent::UndoLast,
+                outcome,
+            } => {
+                let (status, notification, refresh_dir) = match outcome {
+                    FileOpOutcome::Renamed {
+                        old_path,
+                        new_path,
+                        is_dir,
+                    } => (
+                        format!(
+                            "Renamed '{}' back to '{}'",
+                            file_op_display_name(&old_path),
+                            file_op_display_name(&new_path)
+                        ),
+                        LspFileOperationNotification::Renamed {
+                            old_path,
+                            was_dir: is_dir,
+                            new_path: new_path.clone(),
+                        },
+                        new_path.parent().map(Path::to_path_buf),
+                    ),
+                    FileOpOutcome::Deleted { path, is_dir, .. } => (
+                        format!("Removed '{}'", file_op_display_name(&path)),
+                        LspFileOperationNotification::Deleted {
+                            path: path.clone(),
+                            was_dir: is_dir,
+                        },
+                        path.parent().map(Path::to_path_buf),
+                    ),
+                    FileOpOutcome::Created { path, is_dir }
+                    | FileOpOutcome::Duplicated {
+                        target: path,
+                        is_dir,
+                        ..
+                    } => (
+                        format!("Restored '{}'", file_op_display_name(&path)),
+                        LspFileOperationNotification::Created {
+                            path: path.clone(),
+                            is_dir,
+                        },
+                        path.parent().map(Path::to_path_buf),
+                    ),
+                };
+                self.notify_lsp(notification, cx);
+                FileOpFollowUp {
+                    rescan: refresh_dir,
+                    status: Some(EditorStatus {
+                        status,
+                        severity: Severity::Info,
+                    }),
+                    ..FileOpFollowUp::default()
+                }
+            }
+            WsEvent::FileOpCompleted {
+                intent: FileOpIntent::NewFile { .. },
+                outcome: FileOpOutcome::Created { path, .. },
+            } if self.templates.contains_key(&path) => FileOpFollowUp {
+                open: Some(path),
+                ..FileOpFollowUp::default()
+            },
+            WsEvent::FileOpCompleted { intent, .. } => {
+                debug!(intent = ?intent, "Workspace: File operation completed");
+                FileOpFollowUp::default()
+            }
+            WsEvent::FileOpFailed { intent, error } => {
+                if let FileOpIntent::NewFile { parent, name } = &intent {
+                    self.templates.remove(&parent.join(name));
+                }
+                let status = if intent == FileOpIntent::UndoLast {
+                    format!("Could not undo file operation: {error}")
+                } else {
+                    format!("File operation failed: {error}")
+                };
+                FileOpFollowUp {
+                    status: Some(EditorStatus {
+                        status,
+                        severity: Severity::Error,
+                    }),
+                    ..FileOpFollowUp::default()
+                }
+            }
+            _ => FileOpFollowUp::default(),
+        }
+    }
+}
+
+/// Answers the delete confirmation dialog.
+pub(super) fn handle_delete_confirm_event(
+    workspace: &mut Workspace,
+    event: ConfirmDialogEvent,
+    cx: &mut Context<Workspace>,
+) {
+    match event {
+        ConfirmDialogEvent::Cancelled => workspace.file_ops.cancel_delete(cx),
+        ConfirmDialogEvent::Confirmed => workspace.file_ops.perform_delete(cx),
+    }
+}
+
+pub(super) fn file_op_display_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Directory a file tree menu item acts in: the clicked directory, or the
+/// parent of the clicked file.
+pub(super) fn context_menu_target_parent_path(clicked: &Path, is_directory: bool) -> PathBuf {
+    if is_directory {
+        clicked.to_path_buf()
+    } else {
+        clicked
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or_else(|| std::path::Path::new("."))
+            .to_path_buf()
+    }
+}
+
+fn workspace_backend_supports_trash(_backend_identity: &WorkspaceIdentity) -> bool {
+    false
+}
+
+fn effective_delete_mode(
+    delete_behavior: crate::config::DeleteBehavior,
+    backend_identity: &WorkspaceIdentity,
+) -> DeleteMode {
+    match delete_behavior {
+        crate::config::DeleteBehavior::Trash
+            if workspace_backend_supports_trash(backend_identity) =>
+        {
+            DeleteMode::Trash
+        }
+        crate::config::DeleteBehavior::Trash | crate::config::DeleteBehavior::Permanent => {
+            DeleteMode::Permanent
+        }
+    }
+}
+
+fn delete_confirmation_required(mode: DeleteMode) -> bool {
+    matches!(mode, DeleteMode::Permanent)
+}
+
+fn delete_confirmation_label(mode: DeleteMode) -> &'static str {
+    match mode {
+        DeleteMode::Trash => "Move to Trash",
+        DeleteMode::Permanent => "Delete Permanently",
+    }
+}
+
+fn delete_confirmation_message(path: Option<&Path>, mode: DeleteMode) -> String {
+    let name = path
+        .and_then(|path| path.file_name())
+        .and_then(|name| name.to_str())
+        .unwrap_or("this item");
+
+    match mode {
+        DeleteMode::Trash => {
+            format!("Move '{name}' to Trash?")
+        }
+        DeleteMode::Permanent => {
+            format!("Delete '{name}' permanently?")
+        }
+    }
+}
+
+fn file_kind_matches_dir(kind: FileKind, is_dir: bool) -> bool {
+    (kind == FileKind::Directory) == is_dir
+}
+
+async fn file_kind_for_path_async(
+    backend: &WorkspaceBackendHandle,
+    path: &Path,
+) -> Option<FileKind> {
+    backend.stat(path).await.ok().map(|stat| stat.kind)
+}
+
+async fn file_operation_notification_succeeded_with_backend_async(
+    backend: &WorkspaceBackendHandle,
+    notification: &LspFileOperationNotification,
+) -> bool {
+    match notification {
+        LspFileOperationNotification::Created { path, is_dir } => {
+            file_kind_for_path_async(backend, path)
+                .await
+                .is_some_and(|kind| file_kind_matches_dir(kind, *is_dir))
+        }
+        LspFileOperationNotification::Deleted { path, .. } => {
+            file_kind_for_path_async(backend, path).await.is_none()
+        }
+        LspFileOperationNotification::Renamed {
+            old_path,
+            new_path,
+            was_dir,
+        } => {
+            old_path != new_path
+                && file_kind_for_path_async(backend, old_path).await.is_none()
+                && file_kind_for_path_async(backend, new_path)
+                    .await
+                    .is_some_and(|kind| file_kind_matches_dir(kind, *was_dir))
+        }
+    }
+}
+
+async fn wait_for_file_operation_notification(
+    backend: WorkspaceBackendHandle,
+    notification: LspFileOperationNotification,
+) -> bool {
+    for _ in 0..50 {
+        if file_operation_notification_succeeded_with_backend_async(&backend, &notification).await {
+            return true;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_operation_notification_succeeded(notification: &LspFileOperationNotification) -> bool {
+        let backend = nucleotide_workspace::local_workspace_backend();
+        futures_executor::block_on(file_operation_notification_succeeded_with_backend_async(
+            &backend,
+            notification,
+        ))
+    }
+
+    #[test]
+    fn deletes_wait_for_confirmation_until_taken() {
+        let mut delete = DeleteRequest::default();
+        delete.confirm();
+        assert!(!delete.confirm_open());
+
+        delete.request(PathBuf::from("/work/old.rs"), false);
+        assert!(!delete.confirm_open());
+        assert_eq!(delete.path(), Some(Path::new("/work/old.rs")));
+
+        delete.confirm();
+        assert!(delete.confirm_open());
+
+        assert_eq!(delete.take(), Some((PathBuf::from("/work/old.rs"), false)));
+        assert!(!delete.confirm_open());
+        assert_eq!(delete.take(), None);
+    }
+
+    #[test]
+    fn trash_delete_preference_degrades_to_confirmed_permanent_delete() {
+        let remote_identity =
+            WorkspaceIdentity::Remote(nucleotide_workspace::RemoteWorkspaceIdentity {
+                kind: nucleotide_workspace::RemoteWorkspaceKind::Ssh,
+                name: "example.test".to_string(),
+            });
+
+        let mode = effective_delete_mode(crate::config::DeleteBehavior::Trash, &remote_identity);
+
+        assert_eq!(mode, DeleteMode::Permanent);
+        assert!(delete_confirmation_required(mode));
+        assert_eq!(delete_confirmation_label(mode), "Delete Permanently");
+    }
+
+    #[test]
+    fn delete_confirmation_message_matches_effective_mode() {
+        let path = Path::new("/remote/project/src/lib.rs");
+
+        assert_eq!(
+            delete_confirmation_message(Some(path), DeleteMode::Permanent),
+            "Delete 'lib.rs' permanently?"
+        );
+        assert_eq!(
+            delete_confirmation_message(Some(path), DeleteMode::Trash),
+            "Move 'lib.rs' to Trash?"
+        );
+    }
+
+    #[test]
+    fn context_menu_target_parent_uses_entry_kind_without_filesystem_probe() {
+        assert_eq!(
+            context_menu_target_parent_path(Path::new("/workspace/src"), true),
+            PathBuf::from("/workspace/src")
+        );
+        assert_eq!(
+            context_menu_target_parent_path(Path::new("/workspace/src/main.rs"), false),
+            PathBuf::from("/workspace/src")
+        );
+        assert_eq!(
+            context_menu_target_parent_path(Path::new("main.rs"), false),
+            PathBuf::from(".")
+        );
+    }
+
+    #[test]
+    fn file_operation_notification_success_tracks_disk_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let created_file = dir.path().join("created.rs");
+        std::fs::write(&created_file, "").unwrap();
+        assert!(file_operation_notification_succeeded(
+            &LspFileOperationNotification::Created {
+                path: created_file,
+                is_dir: false,
+            }
+        ));
+
+        let deleted_file = dir.path().join("deleted.rs");
+        assert!(!deleted_file.exists());
+        assert!(file_operation_notification_succeeded(
+            &LspFileOperationNotification::Deleted {
+                path: deleted_file,
+                was_dir: false,
+            }
+        ));
+
+        let old_path = dir.path().join("old.rs");
+        let new_path = dir.path().join("new.rs");
+        std::fs::write(&new_path, "").unwrap();
+        assert!(file_operation_notification_succeeded(
+            &LspFileOperationNotification::Renamed {
+                old_path,
+                new_path,
+                was_dir: false,
+            }
+        ));
+    }
+}
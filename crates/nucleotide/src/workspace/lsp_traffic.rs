@@ -0,0 +1,186 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use nucleotide_lsp::{
+    MethodLatencyStats, TrafficDirection, TrafficExchange, method_latency_stats, pair_exchanges,
+    parse_traffic_log,
+};
+
+use crate::picker_view::PickerItem;
+use crate::types::GlobalSearchLocation;
+
+/// Most recent exchanges listed per server; the summary rows still cover the
+/// whole log.
+const LSP_TRAFFIC_EXCHANGE_LIMIT: usize = 500;
+
+/// Decoded traffic log for one language server.
+pub(super) struct LspTrafficLog {
+    pub(super) server: String,
+    pub(super) path: PathBuf,
+    pub(super) exchanges: Vec<TrafficExchange>,
+    pub(super) stats: Vec<MethodLatencyStats>,
+}
+
+/// Reads every active `<server>.jsonl` log in the inspector directory.
+/// Rotated generations are left alone so the viewer reflects the running sessions.
+pub(super) fn load_lsp_traffic_logs(log_dir: &Path) -> std::io::Result<Vec<LspTrafficLog>> {
+    let mut paths: Vec<PathBuf> = match std::fs::read_dir(log_dir) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "jsonl")
+            })
+            .collect(),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(error) => return Err(error),
+    };
+    paths.sort();
+
+    let mut logs = Vec::with_capacity(paths.len());
+    for path in paths {
+        let contents = std::fs::read_to_string(&path)?;
+        let exchanges = pair_exchanges(&parse_traffic_log(&contents));
+        if exchanges.is_empty() {
+            continue;
+        }
+        logs.push(LspTrafficLog {
+            server: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            stats: method_latency_stats(&exchanges),
+            exchanges,
+            path,
+        });
+    }
+    Ok(logs)
+}
+
+/// Picker rows: per-method latency summaries first, then recent exchanges
+/// newest first. Every row jumps to the request line in the server's log.
+pub(super) fn lsp_traffic_picker_items(logs: &[LspTrafficLog]) -> Vec<PickerItem> {
+    let mut items = Vec::new();
+
+    for log in logs {
+        for stats in &log.stats {
+            let slowest_line = log
+                .exchanges
+                .iter()
+                .filter(|exchange| exchange.method == stats.method)
+                .max_by_key(|exchange| exchange.latency)
+                .map_or(1, |exchange| exchange.request_line);
+            items.push(PickerItem::with_sublabel_and_path(
+                format!("{}  {}", log.server, stats.method),
+                method_stats_summary(stats),
+                log.path.clone(),
+                location_data(&log.path, slowest_line),
+            ));
+        }
+    }
+
+    for log in logs {
+        for exchange in log.exchanges.iter().rev().take(LSP_TRAFFIC_EXCHANGE_LIMIT) {
+            items.push(PickerItem::with_sublabel_and_path(
+                format!(
+                    "{}  {}  {}",
+                    format_latency(exchange.latency),
+                    log.server,
+                    exchange.method
+                ),
+                exchange_summary(exchange),
+                log.path.clone(),
+                location_data(&log.path, exchange.request_line),
+            ));
+        }
+    }
+
+    items
+}
+
+fn location_data(path: &Path, line: usize) -> Arc<dyn std::any::Any + Send + Sync> {
+    Arc::new(GlobalSearchLocation {
+        path: path.to_path_buf(),
+        line: line.saturating_sub(1),
+    })
+}
+
+fn method_stats_summary(stats: &MethodLatencyStats) -> String {
+    let mut summary = format!(
+        "{} request{} · avg {} · p95 {} · max {}",
+        stats.requests,
+        if stats.requests == 1 { "" } else { "s" },
+        format_latency(stats.average),
+        format_latency(stats.p95),
+        format_latency(stats.max),
+    );
+    if stats.failed > 0 {
+        summary.push_str(&format!(" · {} failed", stats.failed));
+    }
+    if stats.pending > 0 {
+        summary.push_str(&format!(" · {} unanswered", stats.pending));
+    }
+    summary
+}
+
+fn exchange_summary(exchange: &TrafficExchange) -> String {
+    let initiator = match exchange.initiator {
+        TrafficDirection::EditorToServer => "editor → server",
+        TrafficDirection::ServerToEditor => "server → editor",
+    };
+    let outcome = match exchange.response_line {
+        None => "no response",
+        Some(_) if exchange.failed => "error",
+        Some(_) => "ok",
+    };
+    format!(
+        "id {} · {initiator} · {outcome} · {}",
+        exchange.id,
+        exchange.requested_at.format("%H:%M:%S%.3f")
+    )
+}
+
+fn format_latency(latency: Option<Duration>) -> String {
+    match latency {
+        None => "—".to_string(),
+        Some(latency) if latency < Duration::from_millis(1) => {
+            format!("{}µs", latency.as_micros())
+        }
+        Some(latency) if latency < Duration::from_secs(1) => {
+            format!("{}ms", latency.as_millis())
+        }
+        Some(latency) => format!("{:.2}s", latency.as_secs_f64()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latency_formatting_picks_readable_units() {
+        assert_eq!(format_latency(None), "—");
+        assert_eq!(format_latency(Some(Duration::from_micros(420))), "420µs");
+        assert_eq!(format_latency(Some(Duration::from_millis(35))), "35ms");
+        assert_eq!(format_latency(Some(Duration::from_millis(1250))), "1.25s");
+    }
+
+    #[test]
+    fn stats_summary_mentions_failures_and_unanswered_requests() {
+        let stats = MethodLatencyStats {
+            method: "textDocument/completion".to_string(),
+            requests: 3,
+            pending: 1,
+            failed: 1,
+            average: Some(Duration::from_millis(40)),
+            p95: Some(Duration::from_millis(70)),
+            max: Some(Duration::from_millis(70)),
+        };
+        assert_eq!(
+            method_stats_summary(&stats),
+            "3 requests · avg 40ms · p95 70ms · max 70ms · 1 failed · 1 unanswered"
+        );
+    }
+}
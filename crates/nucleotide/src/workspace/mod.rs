@@ -1,25 +1,59 @@
 // ABOUTME: Workspace module decomposition for cleaner architecture
 // ABOUTME: Separates view management from workspace coordination logic
 
+mod completion_state;
+mod context_menus;
+mod document_find;
+mod environment_inspector;
+mod file_ops;
+mod lsp_traffic;
+mod panel_state;
+pub mod picker_sources;
 pub mod prefix_extraction;
+mod right_sidebar;
 mod split_resize;
+mod statusbar_focus;
+mod tab_navigation;
+mod tab_switcher;
+mod view_export;
 pub mod view_manager;
 
+use completion_state::{ActiveCompletionSession, CompletionMemoryKey, CompletionState};
+use context_menus::{
+    ContentContextMenuTarget, ContextMenuState, TabContextMenuCapabilities, TabContextMenuModel,
+};
+pub(crate) use context_menus::{TabBarNewMenuIntent, TabBarSplitMenuIntent, TabContextMenuIntent};
+use document_find::FindBarState;
+use file_ops::{
+    FileOpFollowUp, FileOpsState, PromptSubmission, ScaffoldingProject,
+    context_menu_target_parent_path, file_op_display_name, handle_delete_confirm_event,
+};
+use panel_state::{PanelKind, PanelState};
+use picker_sources::{
+    CompareSource, PICKER_COMMAND, RegisteredPickersSource, ScratchBufferSource,
+    parse_picker_command,
+};
 use prefix_extraction::PrefixExtractor;
+use right_sidebar::RightSidebar;
 use split_resize::{
     DocumentViewLayout, EditorPaneLayout, SplitPaneDivider, SplitPaneResizeAxis,
     SplitPaneResizeState, helix_rect_to_scaled_pixel_bounds, split_pane_resize_view_states,
     split_pane_resized_areas,
 };
+use statusbar_focus::StatusbarFocus;
+use tab_navigation::{
+    MruTabCycle, TabCycleDirection, TabMruTracker, adjacent_tab, tab_overview_diagnostic_severity,
+    tab_overview_flags,
+};
+use tab_switcher::{TAB_SWITCHER_PREVIEW_LINES, TabSwitcher};
 pub use view_manager::ViewManager;
 
 // Main workspace implementation
-#[cfg(target_os = "windows")]
-use std::collections::VecDeque;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 #[cfg(target_os = "windows")]
 use std::sync::{LazyLock, Mutex};
 
@@ -27,11 +61,12 @@ use std::sync::{LazyLock, Mutex};
 use gpui::MenuItem;
 use gpui::prelude::{FluentBuilder, StyledImage};
 use gpui::{
-    Anchor, App, AppContext, BorrowAppContext, Bounds, Context, DismissEvent, Entity, EventEmitter,
-    FocusHandle, Focusable, Hsla, InteractiveElement, IntoElement, KeyDownEvent, MouseButton,
-    MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, Pixels, Point, Render,
-    ScrollHandle, SharedString, Size, StatefulInteractiveElement, Styled, Subscription, TextStyle,
-    Window, WindowAppearance, canvas, div, img, point, px, relative, svg,
+    App, AppContext, BorrowAppContext, Bounds, Context, DismissEvent, Entity, EventEmitter,
+    FocusHandle, Focusable, Hsla, InteractiveElement, IntoElement, KeyDownEvent,
+    ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
+    ParentElement, Pixels, Point, Render, ScrollHandle, SharedString, Size,
+    StatefulInteractiveElement, Styled, TextStyle, Window, WindowAppearance, canvas, div, img,
+    point, px, relative, svg,
 };
 use gpui::{FontFeatures, FontWeight};
 use helix_core::syntax::config::LanguageServerFeature;
@@ -47,12 +82,14 @@ use nucleotide_ui::ThemedContext as UIThemedContext;
 
 // ViewManager already imported above via pub use
 use nucleotide_ui::notification::{StatusBarNotification, StatusBarNotificationSeverity};
+use nucleotide_ui::overlay_manager::{Dismissal, OverlayManager, PopupId};
+use nucleotide_ui::picker_source::{PickerSource, PickerSources};
 use nucleotide_ui::scrollbar::{Scrollbar, ScrollbarState};
 use nucleotide_ui::{
     AboutWindow, Button, ButtonSize, ButtonVariant, ConfirmDialog, ConfirmDialogEvent,
-    ConfirmDialogView, ContextMenuController, EditorPaneGrid, IndeterminateProgressIndicator,
-    MarkdownStyle, ModalLayer, PopupMenu, PopupMenuSurface, StateView, StatusBar, Tooltipped,
-    completion_menu_action_for_key, markdown_extended,
+    ConfirmDialogView, EditorPaneGrid, IndeterminateProgressIndicator, ModalLayer,
+    PopupMenuSurface, StatusBar, StatusBarAlign, StatusBarSegment, Tooltipped,
+    completion_menu_action_for_key,
 };
 
 use crate::input_coordinator::{InputContext, InputCoordinator};
@@ -61,32 +98,99 @@ use nucleotide_lsp::{LspStatusKind, LspStatusSummary, ServerStatus};
 use crate::application::{
     LspCompletionTrigger, find_workspace_root_from,
     workspace_backend_for_project_directory_with_bootstrap_progress_and_startup_context,
+    workspace_file_ops::UNDO_FILE_OP_COMMAND,
+};
+use crate::cargo_integration::{
+    CARGO_SETTINGS_COMMAND, CargoChoice, CargoSelection, CargoSelections, CargoWorkspace,
+    RUST_ANALYZER,
+};
+use crate::config::{
+    SplitFocusIndicator, SplitsConfig, TerminalCwdStrategy, TerminalProfileConfig,
+};
+use crate::content_index::{
+    CONTENT_INDEX_REBUILD_COMMAND, CONTENT_INDEX_STATUS_COMMAND, ContentIndex, ContentIndexHandle,
+    ContentIndexState, IndexLimits, WalkOptions, required_trigrams, status_label,
 };
 use crate::document::DocumentView;
+use crate::document_export::{DocumentExportFormat, DocumentExportOptions};
+use crate::error_report::ErrorReport;
+use crate::file_compare::{
+    COMPARE_CLIPBOARD_COMMAND, COMPARE_NEXT_COMMAND, COMPARE_PREVIOUS_COMMAND,
+    COMPARE_WITH_COMMAND, CompareAction, CompareChoice, CompareTarget, Comparison, Side,
+    adjacent_hunk, compare_hunks, copy_hunk_transaction, hunk_at, hunk_selection,
+};
 use crate::file_tree::{
     FileSystemEventKind, FileTreeConfig, FileTreeEvent, FileTreeView,
+    preview::{
+        FilePreview, FilePreviewFile, FilePreviewSource, PREVIEW_MAX_LINES, read_file_preview,
+        render_file_preview,
+    },
     sidebar::ProjectTreeContextMenuIntent,
 };
+use crate::find_bar::{FindBar, FindBarEvent};
+use crate::focus_debugger::{FocusSnapshot, RoleFocus, focus_debug_lines};
+use crate::hex_editor::HEX_EDIT_COMMAND;
+use crate::indentation::{
+    INDENTATION_COMMAND, IndentationChoice, SPACE_WIDTHS, convert_indentation_transaction,
+};
 use crate::info_box::InfoBoxView;
 use crate::key_hint_view::KeyHintView;
+use crate::live_grep::{
+    LIVE_GREP_COMMAND, LIVE_GREP_DEBOUNCE, LIVE_GREP_RESULT_LIMIT, LIVE_GREP_TITLE, line_regex,
+    match_ranges, preview_excerpt,
+};
+use crate::menu_state::{AppMenuState, AppMenus, MAX_MENU_RECENT_FILES, MAX_MENU_RECENT_PROJECTS};
+use crate::merge_conflicts::{
+    ConflictDirection, MergeConflictChoice, MergeConflictResolution, adjacent_conflict,
+    conflict_at_line, merge_conflict_preview, merge_conflict_transaction, parse_merge_conflicts,
+};
 use crate::notification::NotificationView;
+use crate::npm_scripts::NPM_SCRIPTS_COMMAND;
 use crate::overlay::OverlayView;
+use crate::picker_view::PickerItem;
+use crate::project_rename::{
+    CaseVariant, ProjectRename, ProjectRenameChoice, RENAME_IN_FILES_COMMAND,
+    RENAME_IN_FILES_MATCH_LIMIT,
+};
+use crate::recent_files::{REOPEN_CLOSED_TAB_COMMAND, RecentFile, RecentFiles};
+use crate::regex_tester::{REGEX_TESTER_COMMAND, RegexTesterEvent};
 use crate::remote_connections::{RemoteConnectionStore, target_to_string};
 use crate::remote_open::{
     RemoteOpenRequest, RemoteOpenTarget, RemoteOpenTargetKind, parse_remote_open_input,
     parse_remote_open_request,
 };
+use crate::repl::ReplAction;
+use crate::run_configurations::{
+    LastRunConfigurations, RUN_CONFIGURATION_COMMAND, RUN_PROBLEMS_COMMAND,
+};
+use crate::scratch_buffers::{
+    DEFAULT_SCRATCH_LANGUAGE, NewScratch, SCRATCH_BUFFERS_COMMAND, SCRATCH_DELETE_COMMAND,
+    SCRATCH_NEW_COMMAND, ScratchBufferChoice, ScratchBuffers, ScratchStore,
+};
+use crate::shutdown::{SHUTDOWN_TIMEOUT, ShutdownProgress, ShutdownStep};
+use crate::snippet_export::{SnippetExport, SnippetExportFormat};
+use crate::startup_profile::StartupProfile;
 use crate::tab::TabId;
+use crate::task_list::{
+    ARCHIVE_DONE_TASKS_COMMAND, TOGGLE_TASK_COMMAND, apply_task_edit, archive_completed_tasks,
+    toggle_task_transaction,
+};
+use crate::type_hierarchy::TYPE_HIERARCHY_COMMAND;
 use crate::types::{
-    EditorStatus, GlobalSearchLocation, HoverDocEntry, RegexSelectionAction, Severity,
+    CommitLogEntry, EditorStatus, EnvironmentRecapture, GlobalSearchLocation, HoverDocEntry,
+    RegexSelectionAction, Severity, TerminalProfileChoice,
 };
 use crate::updates::{UpdateController, UpdateControllerEvent, UpdateDialog};
+use crate::usage_stats::{CLEAR_USAGE_DATA_COMMAND, UsageKind, UsageTracker, record_usage};
 use crate::utils;
 use crate::{Core, Input, InputEvent};
 use nucleotide_env::EnvironmentOrigin;
+use nucleotide_events::v2::document::DocumentViewState;
 use nucleotide_events::v2::run::{ResolvedTask, RunId, RunStatus};
 use nucleotide_events::v2::terminal::{Event as TerminalEvent, TerminalId};
 use nucleotide_terminal::TerminalBounds;
+use nucleotide_terminal::session::SessionInput;
+use nucleotide_terminal_view::TerminalPaletteOverride;
 use nucleotide_workspace::local_workspace_backend;
 use nucleotide_workspace::{
     FileKind, FileSearchQuery, FileSearchResult, FileStat, ProjectEnvironmentOrigin,
@@ -96,22 +200,23 @@ use nucleotide_workspace::{
 };
 use slotmap::KeyData;
 // (no direct Workspace v2 items used here)
-use nucleotide_vcs::{VcsEvent, VcsServiceHandle};
+use nucleotide_vcs::git_log::{commit_graph_rows, run_git_log, run_git_show};
+use nucleotide_vcs::{GitOperation, GitOperationError, VcsEvent, VcsServiceHandle};
 #[cfg(target_os = "windows")]
 use smallvec::{SmallVec, smallvec};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum RemoteDocumentReloadDecision<D, V> {
+enum DocumentReloadDecision<D, V> {
     Reload { doc_id: D, view_id: V },
     Dirty { doc_id: D },
     Hidden { doc_id: D },
     NoMatch,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum RemoteDocumentReloadApply {
+enum DocumentReloadApply {
     Applied,
-    Dirty,
+    /// The buffer needs the user's decision before taking the disk contents
+    Conflict(crate::application::WorkspaceDocumentRead),
     Skipped,
 }
 
@@ -120,17 +225,33 @@ struct LoadingDocument {
     path: PathBuf,
     view_id: ViewId,
     initial_position: Option<Position>,
+    /// Where the file was last closed, restored when there's no position
+    remembered: Option<RecentFile>,
+}
+
+/// Reopens `doc` in `view_id` with the selections and scroll position it
+/// was last closed with.
+fn restore_recent_view(doc: &mut helix_view::Document, view_id: ViewId, file: &RecentFile) {
+    let (selection, scroll) = file.restore_in(doc.text().slice(..));
+    doc.set_selection(view_id, selection);
+    doc.set_view_offset(
+        view_id,
+        helix_view::view::ViewPosition {
+            anchor: scroll,
+            ..Default::default()
+        },
+    );
 }
 
 fn settings_file_open_backend() -> WorkspaceBackendHandle {
     local_workspace_backend()
 }
 
-fn remote_document_reload_decision<'a, D, V>(
+fn document_reload_decision<'a, D, V>(
     documents: impl IntoIterator<Item = (D, Option<&'a Path>, bool)>,
     visible_views: impl IntoIterator<Item = (V, D)>,
     path: &Path,
-) -> RemoteDocumentReloadDecision<D, V>
+) -> DocumentReloadDecision<D, V>
 where
     D: Copy + Eq,
     V: Copy,
@@ -139,20 +260,41 @@ where
         .into_iter()
         .find(|(_, doc_path, _)| doc_path.is_some_and(|doc_path| doc_path == path))
     else {
-        return RemoteDocumentReloadDecision::NoMatch;
+        return DocumentReloadDecision::NoMatch;
     };
 
     if is_modified {
-        return RemoteDocumentReloadDecision::Dirty { doc_id };
+        return DocumentReloadDecision::Dirty { doc_id };
     }
 
     visible_views
         .into_iter()
         .find_map(|(view_id, view_doc_id)| {
-            (view_doc_id == doc_id)
-                .then_some(RemoteDocumentReloadDecision::Reload { doc_id, view_id })
+            (view_doc_id == doc_id).then_some(DocumentReloadDecision::Reload { doc_id, view_id })
         })
-        .unwrap_or(RemoteDocumentReloadDecision::Hidden { doc_id })
+        .unwrap_or(DocumentReloadDecision::Hidden { doc_id })
+}
+
+/// Reads a changed file for reloading its buffer. Local buffers get their diff
+/// base from Helix's own providers, so only remote reads look one up.
+async fn read_document_for_reload(
+    workspace_backend: WorkspaceBackendHandle,
+    path: PathBuf,
+) -> anyhow::Result<crate::application::WorkspaceDocumentRead> {
+    if matches!(workspace_backend.identity(), WorkspaceIdentity::Remote(_)) {
+        return crate::application::read_workspace_document(workspace_backend, path).await;
+    }
+
+    let read = workspace_backend
+        .read_file(&path, ReadOptions::default())
+        .await?;
+    if read.truncated {
+        anyhow::bail!("file was truncated while reading {}", path.display());
+    }
+    Ok(crate::application::WorkspaceDocumentRead {
+        read,
+        diff_base: None,
+    })
 }
 
 fn project_status_types_from_lsp_project_type(
@@ -218,12 +360,19 @@ fn document_lsp_identifier(
 ) -> Option<helix_lsp::lsp::TextDocumentIdentifier> {
     doc.url().map(helix_lsp::lsp::TextDocumentIdentifier::new)
 }
-type TabContextMenuHandler = fn(&mut Workspace, TabId, &mut Context<Workspace>);
-type TabBarSplitMenuHandler = fn(&mut Workspace, &mut Context<Workspace>);
-type TabBarNewMenuHandler = fn(&mut Workspace, &mut Context<Workspace>);
-
 const STATUSBAR_NOTIFICATION_MESSAGE_MAX_CHARS: usize = 64;
 const STATUSBAR_BACKGROUND_ACTIVITY_MAX_CHARS: usize = 72;
+/// Space a text context item needs to be worth showing.
+const STATUSBAR_TEXT_ITEM_WIDTH: Pixels = px(72.0);
+// Status bar context items give way lowest priority first.
+const STATUSBAR_PRIORITY_POSITION: u8 = 200;
+const STATUSBAR_PRIORITY_LSP: u8 = 180;
+const STATUSBAR_PRIORITY_DIAGNOSTICS: u8 = 160;
+const STATUSBAR_PRIORITY_VCS: u8 = 140;
+const STATUSBAR_PRIORITY_LANGUAGE: u8 = 120;
+const STATUSBAR_PRIORITY_RUN: u8 = 100;
+const STATUSBAR_PRIORITY_INDENTATION: u8 = 80;
+const STATUSBAR_PRIORITY_FILE_FORMAT: u8 = 60;
 const IMAGE_ZOOM_STEP: f32 = 0.25;
 const IMAGE_ZOOM_MIN: f32 = 0.10;
 const IMAGE_ZOOM_MAX: f32 = 8.0;
@@ -319,6 +468,8 @@ struct StatusBarModel {
     position_text: String,
     document_metadata: Option<StatusBarDocumentMetadata>,
     vcs_ref: Option<String>,
+    /// Run configuration the Run button starts
+    run_configuration: Option<String>,
     lsp: Option<LspStatusSummary>,
     notification: Option<StatusBarNotification>,
     background_activity: Option<String>,
@@ -330,6 +481,8 @@ struct StatusBarDocumentMetadata {
     encoding: String,
     line_ending: &'static str,
     language: String,
+    /// Indentation of the document, such as `Spaces: 4`
+    indentation: String,
     errors: usize,
     warnings: usize,
 }
@@ -375,6 +528,28 @@ fn abbreviated_vcs_ref(head: &str) -> String {
     head.chars().take(8).collect()
 }
 
+/// Extra LSP menu line for a running server whose requests keep failing.
+fn lsp_request_health_detail(
+    health: nucleotide_lsp::ServerRequestHealth,
+    now: std::time::Instant,
+) -> Option<String> {
+    match health {
+        nucleotide_lsp::ServerRequestHealth::Healthy => None,
+        nucleotide_lsp::ServerRequestHealth::Degraded {
+            consecutive_failures,
+            retry_at,
+        } => Some(format!(
+            "{consecutive_failures} consecutive request failures; retrying in {}s",
+            retry_at.saturating_duration_since(now).as_secs().max(1)
+        )),
+        nucleotide_lsp::ServerRequestHealth::Probing {
+            consecutive_failures,
+        } => Some(format!(
+            "{consecutive_failures} consecutive request failures; probing on next request"
+        )),
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct LspRestartTarget {
     server_id: helix_lsp::LanguageServerId,
@@ -412,6 +587,102 @@ fn lsp_restart_plan(state: &nucleotide_lsp::LspState) -> Option<LspRestartPlan>
     })
 }
 
+/// Commit log picker rows: graph lanes, short hash and subject, with the author,
+/// date and refs underneath. The preview shows the commit's diff.
+fn commit_log_picker(
+    title: String,
+    repository_root: PathBuf,
+    path: Option<PathBuf>,
+    commits: Vec<nucleotide_vcs::CommitInfo>,
+    runtime_handle: tokio::runtime::Handle,
+) -> crate::picker::Picker {
+    use crate::picker_view::PickerItem;
+
+    // A path-limited log skips commits, so its parent links cannot form a graph.
+    let graph_rows = if path.is_none() {
+        commit_graph_rows(&commits)
+    } else {
+        Vec::new()
+    };
+    let items = commits
+        .into_iter()
+        .enumerate()
+        .map(|(index, commit)| {
+            let graph = graph_rows.get(index).map(String::as_str).unwrap_or("●");
+            let mut details = format!("{} · {}", commit.author, commit.date);
+            if !commit.refs.is_empty() {
+                details.push_str(&format!(" · {}", commit.refs));
+            }
+            PickerItem {
+                label: format!("{graph} {} {}", commit.short_hash(), commit.subject).into(),
+                sublabel: Some(details.into()),
+                data: Arc::new(CommitLogEntry {
+                    repository_root: repository_root.clone(),
+                    hash: commit.hash,
+                    path: path.clone(),
+                    subject: commit.subject,
+                }),
+                file_path: None,
+                vcs_status: None,
+                columns: None,
+            }
+        })
+        .collect();
+
+    crate::picker::Picker::native(title, items, |_index| {
+        // Selection is handled by OverlayView via CommitLogEntry payloads.
+    })
+    .with_preview(true)
+    .with_preview_text_task_provider_fn(move |item, cx| {
+        let entry = item.data.downcast_ref::<CommitLogEntry>()?.clone();
+        let runtime_handle = runtime_handle.clone();
+        Some(cx.spawn(async move |_view, _cx| {
+            let diff = runtime_handle
+                .spawn_blocking(move || {
+                    run_git_show(&entry.repository_root, &entry.hash, entry.path.as_deref())
+                })
+                .await;
+            let text = match diff {
+                Ok(Ok(diff)) => diff,
+                Ok(Err(error)) => format!("Could not show commit: {error}"),
+                Err(error) => format!("Could not show commit: {error}"),
+            };
+            // The extension selects diff highlighting for the preview.
+            Some((text, Some(PathBuf::from("commit.diff"))))
+        }))
+    })
+}
+
+/// Applies a conflict resolution as its own undo step.
+fn apply_merge_conflict_resolution(
+    core: &mut Core,
+    doc_id: DocumentId,
+    view_id: ViewId,
+    transaction: &helix_core::Transaction,
+    remaining: usize,
+) {
+    let view = core.editor.tree.get_mut(view_id);
+    let Some(doc) = core.editor.documents.get_mut(&doc_id) else {
+        return;
+    };
+    // Commit pending edits first so undo reverts only the resolution.
+    doc.append_changes_to_history(view);
+    doc.apply(transaction, view_id);
+    doc.append_changes_to_history(view);
+
+    match remaining {
+        0 => core.editor.set_status("All merge conflicts resolved"),
+        1 => core.editor.set_status("1 merge conflict remaining"),
+        remaining => core
+            .editor
+            .set_status(format!("{remaining} merge conflicts remaining")),
+    }
+}
+
+fn git_operation_title(operation: GitOperation) -> String {
+    format!("Git {}", operation.label().to_lowercase())
+}
+
 fn latest_background_activity(activities: &[BackgroundActivity]) -> Option<&BackgroundActivity> {
     activities.last()
 }
@@ -427,6 +698,28 @@ struct ImageTab {
     scroll_handle: ScrollHandle,
     vertical_scrollbar_state: ScrollbarState,
     horizontal_scrollbar_state: ScrollbarState,
+    view: FileTabView,
+}
+
+/// What a file tab outside the Helix document list shows.
+#[derive(Clone)]
+enum FileTabView {
+    Image,
+    Hex(Entity<crate::hex_editor::HexEditor>),
+}
+
+impl ImageTab {
+    fn hex_editor(&self) -> Option<&Entity<crate::hex_editor::HexEditor>> {
+        match &self.view {
+            FileTabView::Hex(editor) => Some(editor),
+            FileTabView::Image => None,
+        }
+    }
+
+    fn is_modified(&self, cx: &App) -> bool {
+        self.hex_editor()
+            .is_some_and(|editor| editor.read(cx).is_modified())
+    }
 }
 
 impl EnvironmentBadge {
@@ -451,8 +744,27 @@ impl EnvironmentBadge {
     }
 }
 
-fn titlebar_filename(_filename: Option<&str>) -> String {
-    "Nucleotide".to_string()
+fn titlebar_document(
+    project_directory: Option<&Path>,
+    document_path: Option<&Path>,
+    dirty: bool,
+) -> nucleotide_ui::titlebar::TitleBarDocument {
+    let project = project_directory
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().into_owned().into());
+    let path = document_path.map(|path| {
+        project_directory
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path)
+            .display()
+            .to_string()
+            .into()
+    });
+    nucleotide_ui::titlebar::TitleBarDocument {
+        project,
+        path,
+        dirty,
+    }
 }
 
 fn shorten_statusbar_text(text: &str, max_chars: usize) -> String {
@@ -550,6 +862,19 @@ fn should_open_with_image_viewer(
         )
 }
 
+/// Binary files open in the hex editor. Only local files are sniffed; a
+/// positioned open asks for a text location, so it goes to the text editor.
+fn should_open_with_hex_editor(
+    path: &Path,
+    has_initial_position: bool,
+    backend_identity: &WorkspaceIdentity,
+) -> bool {
+    !has_initial_position
+        && matches!(backend_identity, WorkspaceIdentity::Local)
+        && !is_image_file_path(path)
+        && crate::hex_editor::is_binary_file(path)
+}
+
 fn remote_image_cache_dir() -> PathBuf {
     dirs::cache_dir()
         .unwrap_or_else(std::env::temp_dir)
@@ -629,59 +954,6 @@ fn native_window_document_path(
     })
 }
 
-fn workspace_backend_supports_trash(_backend_identity: &WorkspaceIdentity) -> bool {
-    false
-}
-
-fn effective_delete_mode(
-    delete_behavior: crate::config::DeleteBehavior,
-    backend_identity: &WorkspaceIdentity,
-) -> nucleotide_events::v2::workspace::DeleteMode {
-    match delete_behavior {
-        crate::config::DeleteBehavior::Trash
-            if workspace_backend_supports_trash(backend_identity) =>
-        {
-            nucleotide_events::v2::workspace::DeleteMode::Trash
-        }
-        crate::config::DeleteBehavior::Trash | crate::config::DeleteBehavior::Permanent => {
-            nucleotide_events::v2::workspace::DeleteMode::Permanent
-        }
-    }
-}
-
-fn delete_confirmation_required(mode: nucleotide_events::v2::workspace::DeleteMode) -> bool {
-    matches!(
-        mode,
-        nucleotide_events::v2::workspace::DeleteMode::Permanent
-    )
-}
-
-fn delete_confirmation_label(mode: nucleotide_events::v2::workspace::DeleteMode) -> &'static str {
-    match mode {
-        nucleotide_events::v2::workspace::DeleteMode::Trash => "Move to Trash",
-        nucleotide_events::v2::workspace::DeleteMode::Permanent => "Delete Permanently",
-    }
-}
-
-fn delete_confirmation_message(
-    path: Option<&Path>,
-    mode: nucleotide_events::v2::workspace::DeleteMode,
-) -> String {
-    let name = path
-        .and_then(|path| path.file_name())
-        .and_then(|name| name.to_str())
-        .unwrap_or("this item");
-
-    match mode {
-        nucleotide_events::v2::workspace::DeleteMode::Trash => {
-            format!("Move '{name}' to Trash?")
-        }
-        nucleotide_events::v2::workspace::DeleteMode::Permanent => {
-            format!("Delete '{name}' permanently?")
-        }
-    }
-}
-
 fn image_zoom_percent(zoom: f32) -> String {
     format!("{:.0}%", zoom * 100.0)
 }
@@ -904,118 +1176,8 @@ fn windows_jump_list_menu_items() -> Vec<MenuItem> {
 #[cfg(not(any(target_os = "macos", target_os = "windows")))]
 fn add_recent_project(_path: &Path, _cx: &mut App) {}
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub(crate) enum TabContextMenuIntent {
-    Close,
-    CloseOthers,
-    CloseLeft,
-    CloseRight,
-    CloseClean,
-    CloseAll,
-    CopyPath,
-    CopyRelativePath,
-    RevealInOs,
-    RevealInProjectPanel,
-    OpenInTerminal,
-    ToggleReadOnly,
-    TogglePin,
-}
-
-impl TabContextMenuIntent {
-    fn label(self, is_pinned: bool, is_readonly: bool) -> &'static str {
-        match self {
-            Self::Close => "Close",
-            Self::CloseOthers => "Close Others",
-            Self::CloseLeft => "Close Left",
-            Self::CloseRight => "Close Right",
-            Self::CloseClean => "Close Clean",
-            Self::CloseAll => "Close All",
-            Self::CopyPath => "Copy Path",
-            Self::CopyRelativePath => "Copy Relative Path",
-            Self::RevealInOs => reveal_in_file_manager_label(false),
-            Self::RevealInProjectPanel => "Reveal In Project Panel",
-            Self::OpenInTerminal => "Open in Terminal",
-            Self::ToggleReadOnly if is_readonly => "Make File Editable",
-            Self::ToggleReadOnly => "Make File Read-Only",
-            Self::TogglePin if is_pinned => "Unpin Tab",
-            Self::TogglePin => "Pin Tab",
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum TabContextMenuEntry {
-    Action(TabContextMenuIntent),
-    Separator,
-}
-
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-struct TabContextMenuCapabilities {
-    has_file_path: bool,
-    has_project_panel_path: bool,
-    has_terminal_directory: bool,
-    is_readonly: bool,
-}
-
 const SPLIT_PANE_HANDLE_HITBOX_PX: f32 = nucleotide_ui::SPLITTER_HITBOX_PX;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub(crate) enum TabBarSplitMenuIntent {
-    Right,
-    Left,
-    Up,
-    Down,
-}
-
-impl TabBarSplitMenuIntent {
-    fn label(self) -> &'static str {
-        match self {
-            Self::Right => "Split Right",
-            Self::Left => "Split Left",
-            Self::Up => "Split Up",
-            Self::Down => "Split Down",
-        }
-    }
-
-    fn commands(self) -> &'static [&'static str] {
-        match self {
-            Self::Right => &["vsplit"],
-            Self::Left => &["vsplit", "swap_view_left"],
-            Self::Up => &["hsplit", "swap_view_up"],
-            Self::Down => &["hsplit"],
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub(crate) enum TabBarNewMenuIntent {
-    NewFile,
-    OpenFile,
-    SearchProject,
-    SearchSymbols,
-    NewTerminal,
-    NewCenterTerminal,
-}
-
-impl TabBarNewMenuIntent {
-    fn label(self) -> &'static str {
-        match self {
-            Self::NewFile => "New File",
-            Self::OpenFile => "Open File",
-            Self::SearchProject => "Search Project",
-            Self::SearchSymbols => "Search Symbols",
-            Self::NewTerminal => "New Terminal",
-            Self::NewCenterTerminal => "New Center Terminal",
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum TabBarNewMenuEntry {
-    Action(TabBarNewMenuIntent),
-    Separator,
-}
-
 pub struct Workspace {
     core: Entity<Core>,
     input: Entity<Input>,
@@ -1030,21 +1192,20 @@ pub struct Workspace {
     last_notified_editor_status: Option<EditorStatus>,
     focus_handle: FocusHandle,
     needs_focus_restore: bool,
-    statusbar_lsp_focus: FocusHandle,
-    statusbar_file_tree_focus: FocusHandle,
-    statusbar_terminal_focus: FocusHandle,
-    statusbar_lsp_restart_focus: FocusHandle,
-    statusbar_lsp_logs_focus: FocusHandle,
+    statusbar_focus: StatusbarFocus,
     file_tree: Option<Entity<FileTreeView>>,
-    show_file_tree: bool,
-    file_tree_width: f32,
-    file_tree_width_override: Option<f32>,
-    doc_sidebar_visible: bool,
-    doc_sidebar_loading: bool,
-    doc_sidebar_entries: Vec<HoverDocEntry>,
-    doc_sidebar_width: f32,
-    doc_sidebar_scroll_handle: ScrollHandle,
-    doc_sidebar_scrollbar_state: ScrollbarState,
+    // File tree, terminal and performance HUD visibility and panel sizes
+    panels: PanelState,
+    // Documentation, references, type hierarchy or regex tester on the
+    // right of the editor
+    right_sidebar: RightSidebar,
+    // Named scratch buffers, including saved ones not reopened yet
+    scratch_buffers: ScratchBuffers,
+    // Active file compared side by side with another document
+    comparison: Option<Comparison>,
+    // Trigram index of the local project's contents, when turned on
+    content_index: ContentIndexState,
+    content_index_activity: Option<BackgroundActivityId>,
     titlebar: Option<Entity<nucleotide_ui::titlebar::TitleBar>>,
     appearance_observer_set: bool,
     needs_appearance_update: bool,
@@ -1053,34 +1214,19 @@ pub struct Workspace {
     tab_bar_scroll_handle: ScrollHandle,
     last_scrolled_tab_doc_id: Option<TabId>,
     suppress_tab_bar_auto_scroll: bool,
+    tab_mru_cycle: MruTabCycle<TabId>,
+    tab_mru: TabMruTracker<TabId>,
+    tab_switcher: Option<TabSwitcher<TabId>>,
     image_tabs: Vec<ImageTab>,
     active_image_tab_id: Option<u64>,
     next_image_tab_index: u64,
-    // File tree context menu state
-    file_tree_context_menu: ContextMenuController,
-    file_tree_popup_menu: Option<Entity<PopupMenu>>,
-    file_tree_popup_menu_subscription: Option<Subscription>,
-    context_menu_path: Option<std::path::PathBuf>,
-    context_menu_is_directory: bool,
-    // Tab context menu state
-    tab_context_menu: ContextMenuController,
-    tab_context_popup_menu: Option<Entity<PopupMenu>>,
-    tab_context_popup_menu_subscription: Option<Subscription>,
-    tab_context_menu_doc_id: Option<TabId>,
+    // File tree, tab bar, content and LSP menus
+    context_menus: ContextMenuState,
+    // File tree space-bar preview
+    file_tree_preview: Option<FilePreview>,
+    file_tree_preview_load: Option<gpui::Task<()>>,
     pinned_documents: HashSet<TabId>,
-    // Tab bar split menu state
-    tab_bar_split_menu: ContextMenuController,
-    tab_bar_split_popup_menu: Option<Entity<PopupMenu>>,
-    tab_bar_split_popup_menu_subscription: Option<Subscription>,
-    tab_bar_split_button_bounds: Option<Bounds<Pixels>>,
     split_pane_resize: Option<SplitPaneResizeState>,
-    // Tab bar new item menu state
-    tab_bar_new_menu: ContextMenuController,
-    tab_bar_new_popup_menu: Option<Entity<PopupMenu>>,
-    tab_bar_new_popup_menu_subscription: Option<Subscription>,
-    // LSP server list popup state
-    lsp_menu_open: bool,
-    lsp_menu_pos: (f32, f32),
     document_order: Vec<helix_view::DocumentId>, // Ordered list of documents in opening order
     loading_documents: HashMap<DocumentId, LoadingDocument>,
     tab_bar_document_generation: u64,
@@ -1089,9 +1235,9 @@ pub struct Workspace {
     tab_bar_document_cache_misses: u64,
     input_coordinator: Arc<InputCoordinator>, // Central input coordination system
     current_project_root: Option<std::path::PathBuf>, // Track current project root for change detection
+    workspace_settings_path: Option<PathBuf>, // Workspace settings file merged into the active config
     environment_badge: Option<EnvironmentBadge>,
     _pending_lsp_startup: Option<std::path::PathBuf>, // Track pending server startup requests
-    prefix_extractor: PrefixExtractor,                // Language-aware completion prefix extraction
     about_window: Entity<AboutWindow>,                // About dialog window
     theme_debug: Entity<nucleotide_ui::ThemeDebugView>, // Theme debug overlay
     component_gallery: Entity<nucleotide_ui::ComponentGallery>, // Interactive component gallery
@@ -1101,39 +1247,50 @@ pub struct Workspace {
     notified_ready_update_version: Option<String>,
     update_restart_confirm_open: bool,
     window_was_active: bool,
-    // Pending file operation that expects a text input via prompt
-    pending_file_op: Option<PendingFileOp>,
-    // Defer a file tree refresh until after processing core events
-    needs_file_tree_refresh: bool,
-    // Delete confirmation modal state
-    delete_confirm_open: bool,
-    delete_confirm_path: Option<std::path::PathBuf>,
-    delete_confirm_was_directory: bool,
+    // Prompted file operations, pending deletes and New Project commands
+    file_ops: FileOpsState,
     // Unsaved close confirmation modal state
-    close_confirm_open: bool,
     close_confirm: Option<UnsavedCloseConfirmation<DocumentId>>,
+    // Error reports waiting to be shown, oldest first
+    error_reports: VecDeque<ErrorReport>,
+    // GUI find bar, shown over the editor while open
+    find_bar: FindBarState,
+    // Bumped for every live grep query so stale searches stop
+    live_grep_generation: u64,
     // Terminal panel state
-    terminal_panel_visible: bool,
     terminal_id: Option<TerminalId>,
     next_terminal_id: u64,
     next_run_id: u64,
     last_run_task: Option<ResolvedTask>,
+    /// Rest of a run configuration's pre-launch chain, started one by one
+    /// as each task succeeds
+    pending_run_tasks: VecDeque<ResolvedTask>,
+    /// File positions printed by the last run, listed by `run-problems`
+    run_problems: Vec<crate::run_configurations::RunProblem>,
+    /// Cargo workspace members of the project, loaded when the Cargo
+    /// settings open
+    cargo_workspace: Option<(PathBuf, CargoWorkspace)>,
     active_run_terminal: Option<(TerminalId, RunId)>,
     active_run_activity: Option<BackgroundActivityId>,
     run_output_terminal: Option<TerminalId>,
+    /// REPL started by `repl-start` or the first send-to-REPL command
+    repl_session: Option<crate::repl::ReplSession>,
+    /// Textual rename previewed by `rename-in-files`, waiting to be applied
+    project_rename: Option<ProjectRename>,
+    /// Disk contents of buffers waiting for a reload, keep or compare decision
+    disk_changes: HashMap<DocumentId, crate::application::WorkspaceDocumentRead>,
     next_background_activity_id: u64,
     background_activities: Vec<BackgroundActivity>,
     vcs_refresh_activity: Option<BackgroundActivityId>,
+    git_operation_activity: Option<BackgroundActivityId>,
+    /// Environment captures shown in the inspector, per project root
+    environment_captures: HashMap<PathBuf, environment_inspector::EnvironmentCaptureHistory>,
     // Debug: color major panes when enabled via env
     debug_colors_enabled: bool,
-    // Height of the bottom (terminal) pane in basic layout mode
-    basic_terminal_height: f32,
     // Embedded terminal panel entity for basic layout
     embedded_terminal_panel: Option<gpui::Entity<nucleotide_terminal_panel::TerminalPanel>>,
     // Cwd used to spawn the active terminal session.
     terminal_cwd: Option<PathBuf>,
-    // Request to focus terminal on next render (when toggled on via button)
-    terminal_focus_pending: bool,
     // Cache last applied editor size to avoid redundant resizes each frame
     last_editor_size: Option<(u16, u16)>,
     last_terminal_bounds: Option<(TerminalId, TerminalBounds)>,
@@ -1145,8 +1302,15 @@ pub struct Workspace {
     cached_font_metrics_key: Option<(String, f32, nucleotide_types::FontWeight)>,
     cached_char_width: Option<f32>,
     cached_line_height: Option<f32>,
-    active_completion_session: Option<ActiveCompletionSession>,
-    completion_memory: CompletionMemory,
+    // Completion popup, the request feeding it and accepted-item memory
+    completion: CompletionState,
+    /// Refreshes the focus debugger while it is shown
+    focus_debugger_refresh: Option<gpui::Task<()>>,
+    /// Background services still stopping after a quit
+    shutdown: Option<ShutdownProgress>,
+    /// Whether the first frame has yet to be drawn. Services it does not
+    /// need start once it has.
+    first_frame_pending: bool,
     last_native_window_metadata: Option<NativeWindowMetadata>,
     pending_remote_open: Option<PendingRemoteOpen>,
     remote_open_generation: u64,
@@ -1173,17 +1337,6 @@ fn pending_remote_open_matches(
     })
 }
 
-#[derive(Clone, Debug)]
-struct ActiveCompletionSession {
-    doc_id: DocumentId,
-    view_id: ViewId,
-    document_version: i32,
-    is_incomplete: bool,
-    incomplete_server_ids: Vec<u64>,
-    retained_items: Vec<nucleotide_events::completion::CompletionItem>,
-    requested_prefix: String,
-}
-
 #[derive(Clone, Copy, Debug)]
 struct CompletionAcceptTarget {
     doc_id: DocumentId,
@@ -1191,31 +1344,6 @@ struct CompletionAcceptTarget {
     document_version: i32,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
-struct CompletionMemoryKey {
-    language: String,
-    prefix: String,
-    kind: Option<nucleotide_ui::completion_v2::CompletionItemKind>,
-    insert_text: String,
-}
-
-#[derive(Default)]
-struct CompletionMemory {
-    entries: HashMap<CompletionMemoryKey, u64>,
-    next_touch: u64,
-}
-
-impl CompletionMemory {
-    fn priority(&self, key: &CompletionMemoryKey) -> u64 {
-        self.entries.get(key).copied().unwrap_or(0)
-    }
-
-    fn memorize(&mut self, key: CompletionMemoryKey) {
-        self.next_touch = self.next_touch.saturating_add(1);
-        self.entries.insert(key, self.next_touch);
-    }
-}
-
 fn should_retrigger_incomplete_completion_for_focused_session(
     session: &ActiveCompletionSession,
     current_prefix: &str,
@@ -1362,67 +1490,12 @@ fn ui_completion_item_from_event(
     }
 }
 
-// Pending file operation kinds awaiting user input (used with the prompt overlay)
-enum PendingFileOp {
-    NewFile {
-        parent: std::path::PathBuf,
-    },
-    NewFolder {
-        parent: std::path::PathBuf,
-    },
-    Rename {
-        path: std::path::PathBuf,
-        was_dir: bool,
-    },
-    Duplicate {
-        path: std::path::PathBuf,
-        is_dir: bool,
-    },
-}
-
-#[derive(Debug, Clone)]
-enum LspFileOperationNotification {
-    Created {
-        path: PathBuf,
-        is_dir: bool,
-    },
-    Deleted {
-        path: PathBuf,
-        was_dir: bool,
-    },
-    Renamed {
-        old_path: PathBuf,
-        new_path: PathBuf,
-        was_dir: bool,
-    },
-}
-
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum WorkspaceSelectionTarget {
     File,
     Directory,
 }
 
-#[cfg(test)]
-fn file_operation_notification_succeeded(notification: &LspFileOperationNotification) -> bool {
-    let backend = local_workspace_backend();
-    futures_executor::block_on(file_operation_notification_succeeded_with_backend_async(
-        &backend,
-        notification,
-    ))
-}
-
-fn file_kind_matches_dir(kind: FileKind, is_dir: bool) -> bool {
-    (kind == FileKind::Directory) == is_dir
-}
-
-async fn file_kind_for_path_async(
-    backend: &WorkspaceBackendHandle,
-    path: &Path,
-) -> Option<FileKind> {
-    backend.stat(path).await.ok().map(|stat| stat.kind)
-}
-
 fn workspace_selection_target_from_file_kind(kind: FileKind) -> Option<WorkspaceSelectionTarget> {
     match kind {
         FileKind::File | FileKind::Symlink => Some(WorkspaceSelectionTarget::File),
@@ -1471,60 +1544,12 @@ async fn workspace_selection_target_for_path_async(
     }
 }
 
-fn context_menu_target_parent_path(clicked: &Path, is_directory: bool) -> PathBuf {
-    if is_directory {
-        clicked.to_path_buf()
-    } else {
-        clicked
-            .parent()
-            .filter(|parent| !parent.as_os_str().is_empty())
-            .unwrap_or_else(|| std::path::Path::new("."))
-            .to_path_buf()
-    }
-}
-
-async fn file_operation_notification_succeeded_with_backend_async(
-    backend: &WorkspaceBackendHandle,
-    notification: &LspFileOperationNotification,
-) -> bool {
-    match notification {
-        LspFileOperationNotification::Created { path, is_dir } => {
-            file_kind_for_path_async(backend, path)
-                .await
-                .is_some_and(|kind| file_kind_matches_dir(kind, *is_dir))
-        }
-        LspFileOperationNotification::Deleted { path, .. } => {
-            file_kind_for_path_async(backend, path).await.is_none()
-        }
-        LspFileOperationNotification::Renamed {
-            old_path,
-            new_path,
-            was_dir,
-        } => {
-            old_path != new_path
-                && file_kind_for_path_async(backend, old_path).await.is_none()
-                && file_kind_for_path_async(backend, new_path)
-                    .await
-                    .is_some_and(|kind| file_kind_matches_dir(kind, *was_dir))
-        }
-    }
-}
-
-async fn wait_for_file_operation_notification(
-    backend: WorkspaceBackendHandle,
-    notification: LspFileOperationNotification,
-) -> bool {
-    for _ in 0..50 {
-        if file_operation_notification_succeeded_with_backend_async(&backend, &notification).await {
-            return true;
-        }
-        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
-    }
-
-    false
-}
-
 const GLOBAL_SEARCH_RESULT_LIMIT: usize = 5000;
+const GIT_LOG_LIMIT: usize = 500;
+/// Watcher changes this close together are applied to the content index at once.
+const CONTENT_INDEX_UPDATE_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+const FOCUS_DEBUGGER_REFRESH: std::time::Duration = std::time::Duration::from_millis(250);
+const SHUTDOWN_POLL: std::time::Duration = std::time::Duration::from_millis(100);
 const FILE_TREE_MIN_WIDTH: f32 = 96.0;
 const FILE_TREE_DEFAULT_WIDTH: f32 = 240.0;
 const FILE_TREE_MIN_EDITOR_WIDTH: f32 = 200.0;
@@ -1539,6 +1564,13 @@ fn file_tree_config_from_gui(config: &crate::config::GuiConfig) -> FileTreeConfi
         density: config.file_tree.density,
         flatten_empty_directories: config.file_tree.flatten_empty_directories,
         translucent_background: macos_system_sidebar_enabled(config),
+        metadata: crate::file_tree::FileTreeMetadataDisplay {
+            columns: config.file_tree.metadata_columns,
+            tooltips: config.file_tree.metadata_tooltips,
+        },
+        scan_limit: (config.file_tree.large_project_entries > 0)
+            .then_some(config.file_tree.large_project_entries),
+        page_size: config.file_tree.directory_page_size.max(1),
         ..FileTreeConfig::default()
     }
 }
@@ -1791,24 +1823,18 @@ fn should_unpreview_retained_tab_after_close_others(is_preview: bool) -> bool {
     is_preview
 }
 
-fn reveal_in_file_manager_label(is_remote: bool) -> &'static str {
-    if cfg!(target_os = "macos") && !is_remote {
-        "Reveal in Finder"
-    } else if cfg!(target_os = "windows") && !is_remote {
-        "Reveal in File Explorer"
-    } else {
-        "Reveal in File Manager"
-    }
-}
-
 #[cfg(test)]
-fn tab_bar_end_button_icon_paths() -> [&'static str; 2] {
-    ["icons/plus.svg", "icons/columns-2.svg"]
+fn tab_bar_end_button_icon_paths() -> [&'static str; 3] {
+    [
+        "icons/chevron-down.svg",
+        "icons/plus.svg",
+        "icons/columns-2.svg",
+    ]
 }
 
 #[cfg(test)]
-fn tab_bar_end_button_tooltips() -> [&'static str; 2] {
-    ["New File", "Split Pane"]
+fn tab_bar_end_button_tooltips() -> [&'static str; 3] {
+    ["All Tabs", "New File", "Split Pane"]
 }
 
 #[derive(Clone, Copy)]
@@ -1876,6 +1902,36 @@ fn cached_tab_bar_documents(
         .map(|cache| cache.documents.clone())
 }
 
+/// Diagnostic badge of `doc` for a tab bar presented with `key`.
+fn tab_document_diagnostic_badge(
+    doc: &helix_view::Document,
+    key: &TabBarDocumentCacheKey,
+) -> (Option<helix_core::diagnostic::Severity>, usize) {
+    if !key.show_file_icons {
+        return (None, 0);
+    }
+    crate::tab_bar::diagnostic_badge(
+        doc.diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.severity),
+        key.show_diagnostics,
+    )
+}
+
+/// Copy of `documents` with `update` applied, or `None` when no document
+/// changed so the cached tab bar can be kept as is.
+fn patch_tab_bar_documents(
+    documents: &[crate::tab_bar::DocumentInfo],
+    mut update: impl FnMut(&mut crate::tab_bar::DocumentInfo) -> bool,
+) -> Option<Arc<[crate::tab_bar::DocumentInfo]>> {
+    let mut patched = documents.to_vec();
+    let mut changed = false;
+    for document in &mut patched {
+        changed |= update(document);
+    }
+    changed.then(|| Arc::from(patched))
+}
+
 #[cfg(test)]
 fn max_tabs_close_candidates<T: Copy>(
     documents: &[MaxTabsDocument<T>],
@@ -2299,6 +2355,37 @@ async fn global_search_disk_matches_with_backend_async(
     excluded_relative_paths: Vec<PathBuf>,
     limit: usize,
 ) -> Result<Vec<GlobalSearchMatch>, String> {
+    let mut matches = Vec::new();
+    global_search_disk_batches_with_backend_async(
+        backend,
+        root,
+        query,
+        smart_case,
+        file_picker_config,
+        excluded_relative_paths,
+        limit,
+        |batch| {
+            matches.extend(batch);
+            true
+        },
+    )
+    .await?;
+    Ok(matches)
+}
+
+/// Streams disk matches to `on_batch` as the backend finds them, stopping
+/// at `limit` matches or once `on_batch` returns false.
+#[allow(clippy::too_many_arguments)]
+async fn global_search_disk_batches_with_backend_async(
+    backend: WorkspaceBackendHandle,
+    root: PathBuf,
+    query: String,
+    smart_case: bool,
+    file_picker_config: helix_view::editor::FilePickerConfig,
+    excluded_relative_paths: Vec<PathBuf>,
+    limit: usize,
+    mut on_batch: impl FnMut(Vec<GlobalSearchMatch>) -> bool + Send,
+) -> Result<(), String> {
     let root_stat = backend
         .stat(&root)
         .await
@@ -2308,7 +2395,7 @@ async fn global_search_disk_matches_with_backend_async(
     }
 
     if limit == 0 {
-        return Ok(Vec::new());
+        return Ok(());
     }
 
     let mut disk_stream = backend
@@ -2324,23 +2411,30 @@ async fn global_search_disk_matches_with_backend_async(
         .await
         .map_err(|err| err.to_string())?;
 
-    let mut matches = Vec::new();
+    let mut found = 0;
     while let Some(event) = disk_stream.next().await {
         match event.map_err(|err| err.to_string())? {
-            TextSearchEvent::Batch(batch) => append_global_search_text_matches(
-                &mut matches,
-                &root,
-                TextSearchResult {
-                    root: root.clone(),
-                    matches: batch,
-                    truncated: false,
-                },
-                limit,
-            ),
+            TextSearchEvent::Batch(batch) => {
+                let mut matches = Vec::new();
+                append_global_search_text_matches(
+                    &mut matches,
+                    &root,
+                    TextSearchResult {
+                        root: root.clone(),
+                        matches: batch,
+                        truncated: false,
+                    },
+                    limit - found,
+                );
+                found += matches.len();
+                if !on_batch(matches) || found >= limit {
+                    break;
+                }
+            }
             TextSearchEvent::Complete { .. } => break,
         }
     }
-    Ok(matches)
+    Ok(())
 }
 
 fn should_run_global_search_async(workspace_identity: &WorkspaceIdentity) -> bool {
@@ -2372,6 +2466,20 @@ fn global_search_open_document_matches(
     excluded_relative_paths
 }
 
+/// Paths and contents of open documents, searched in memory so unsaved edits
+/// are found.
+fn open_document_texts(editor: &helix_view::Editor) -> Vec<(PathBuf, Rope)> {
+    editor
+        .documents
+        .values()
+        .filter_map(|doc| {
+            doc.path()
+                .map(std::path::Path::to_path_buf)
+                .map(|path| (path, doc.text().to_owned()))
+        })
+        .collect()
+}
+
 fn global_search_text_query(
     root: &Path,
     query: &str,
@@ -2419,6 +2527,69 @@ fn append_global_search_text_matches(
     }
 }
 
+/// Disk matches read from the files the content index says can match, or
+/// `None` when the query has no literal text to narrow them by.
+fn content_index_matches(
+    content_index: &ContentIndexHandle,
+    query: &str,
+    regex: &regex::Regex,
+    excluded_relative_paths: &[PathBuf],
+    limit: usize,
+) -> Option<Vec<GlobalSearchMatch>> {
+    let trigrams = required_trigrams(query)?;
+    let index = content_index.index.read().ok()?;
+    let disk_matches = index.search(
+        regex,
+        &trigrams,
+        excluded_relative_paths,
+        limit,
+        &AtomicBool::new(false),
+    );
+    let mut matches = Vec::new();
+    append_global_search_text_matches(
+        &mut matches,
+        &content_index.root,
+        TextSearchResult {
+            root: content_index.root.clone(),
+            matches: disk_matches,
+            truncated: false,
+        },
+        limit,
+    );
+    Some(matches)
+}
+
+/// Project search through the content index, open documents first, or
+/// `None` when the index cannot narrow this query.
+fn indexed_global_search_matches(
+    content_index: &ContentIndexHandle,
+    query: &str,
+    smart_case: bool,
+    open_documents: &[(PathBuf, Rope)],
+    limit: usize,
+) -> Option<Vec<GlobalSearchMatch>> {
+    required_trigrams(query)?;
+    let regex = compile_global_search_regex(query, smart_case).ok()?;
+    let disk_regex = line_regex(query, smart_case).ok()?;
+    let mut matches = Vec::new();
+    let excluded_relative_paths = global_search_open_document_matches(
+        &mut matches,
+        &content_index.root,
+        open_documents,
+        &regex,
+        limit,
+    );
+    let disk_limit = limit.saturating_sub(matches.len());
+    matches.extend(content_index_matches(
+        content_index,
+        query,
+        &disk_regex,
+        &excluded_relative_paths,
+        disk_limit,
+    )?);
+    Some(matches)
+}
+
 fn global_search_custom_ignore_filenames(identity: WorkspaceIdentity) -> Vec<PathBuf> {
     match identity {
         WorkspaceIdentity::Local => vec![helix_loader::config_dir().join("ignore")],
@@ -2450,6 +2621,37 @@ fn global_search_picker(root: &Path, matches: Vec<GlobalSearchMatch>) -> crate::
     })
 }
 
+fn live_grep_items(
+    root: &Path,
+    matches: Vec<GlobalSearchMatch>,
+    regex: &regex::Regex,
+) -> Vec<crate::picker_view::PickerItem> {
+    use crate::picker_view::PickerItem;
+    use std::sync::Arc;
+
+    matches
+        .into_iter()
+        .map(|search_match| {
+            let path = search_match.path;
+            let label_path = path.strip_prefix(root).unwrap_or(&path);
+            let location = format!("{}:{}", label_path.display(), search_match.line + 1);
+            let ranges = match_ranges(regex, &search_match.line_text);
+            let data = GlobalSearchLocation {
+                path: path.clone(),
+                line: search_match.line,
+            };
+
+            PickerItem::with_search_match(
+                location,
+                search_match.line_text,
+                ranges,
+                path,
+                Arc::new(data),
+            )
+        })
+        .collect()
+}
+
 fn os_string_to_string(value: std::ffi::OsString) -> String {
     value
         .into_string()
@@ -2602,14 +2804,35 @@ impl Workspace {
         cwd: Option<PathBuf>,
         cx: &mut Context<Self>,
     ) -> TerminalId {
-        self.spawn_terminal_session_with_input(cwd, Vec::new(), None, cx)
+        self.spawn_terminal_session_with_input(cwd, Vec::new(), Vec::new(), cx)
     }
 
     fn spawn_terminal_session_with_input(
         &mut self,
         cwd: Option<PathBuf>,
         extra_env: Vec<(String, String)>,
-        initial_input: Option<Vec<u8>>,
+        initial_input: Vec<SessionInput>,
+        cx: &mut Context<Self>,
+    ) -> TerminalId {
+        let profile = self
+            .core
+            .read(cx)
+            .config
+            .gui
+            .terminal
+            .default_profile()
+            .cloned();
+        self.spawn_terminal_session_with_profile(cwd, profile, extra_env, initial_input, cx)
+    }
+
+    /// Spawns a shell session, taking shell, arguments, extra environment and
+    /// palette from `profile` when one is given.
+    fn spawn_terminal_session_with_profile(
+        &mut self,
+        cwd: Option<PathBuf>,
+        profile: Option<TerminalProfileConfig>,
+        mut extra_env: Vec<(String, String)>,
+        initial_input: Vec<SessionInput>,
         cx: &mut Context<Self>,
     ) -> TerminalId {
         let id = TerminalId(self.next_terminal_id);
@@ -2619,13 +2842,27 @@ impl Workspace {
         self.run_output_terminal = None;
         self.last_terminal_bounds = None;
 
-        let shell: Option<String> = None;
+        let mut shell = self.core.read(cx).config.gui.terminal.shell.clone();
+        let mut args = Vec::new();
+        let mut profile_name = None;
+        let mut palette = None;
+        if let Some(profile) = profile {
+            if profile.shell.is_some() {
+                shell = profile.shell;
+            }
+            args = profile.args;
+            // Profile variables go last so they win over the project environment.
+            extra_env.extend(profile.env);
+            palette = profile.palette.to_override();
+            profile_name = Some(profile.name);
+        }
         let workspace_root = self.current_project_root.clone();
-        let (terminal_runtime, project_environment) = {
+        let (terminal_runtime, project_environment, scrollback_lines) = {
             let core = self.core.read(cx);
             (
                 core.terminal_runtime.clone(),
                 core.project_environment.clone(),
+                core.config.gui.terminal.scrollback_lines,
             )
         };
         self.handle.spawn(async move {
@@ -2647,10 +2884,12 @@ impl Workspace {
                     program,
                     args,
                     env: Vec::new(),
+                    scrollback_lines,
                 });
+                Self::apply_terminal_palette(id, palette);
 
-                if let Some(bytes) = initial_input {
-                    terminal_runtime.dispatch(&TerminalEvent::Input { id, bytes });
+                for input in initial_input {
+                    terminal_runtime.queue_input(id, input);
                 }
                 return;
             }
@@ -2681,23 +2920,67 @@ impl Workspace {
                 id,
                 cwd,
                 shell,
+                args,
                 env,
+                profile: profile_name,
+                scrollback_lines,
             });
+            Self::apply_terminal_palette(id, palette);
 
-            if let Some(bytes) = initial_input {
-                terminal_runtime.dispatch(&TerminalEvent::Input { id, bytes });
+            for input in initial_input {
+                terminal_runtime.queue_input(id, input);
             }
         });
 
         id
     }
 
+    fn apply_terminal_palette(id: TerminalId, palette: Option<TerminalPaletteOverride>) {
+        if palette.is_none() {
+            return;
+        }
+        if let Some(view_model) = nucleotide_terminal_view::get_view_model(id)
+            && let Ok(mut view_model) = view_model.lock()
+        {
+            view_model.set_palette_override(palette);
+        }
+    }
+
+    /// Start directory for a new terminal following a profile's cwd strategy.
+    fn terminal_profile_cwd(
+        &self,
+        strategy: TerminalCwdStrategy,
+        cx: &mut Context<Self>,
+    ) -> Option<PathBuf> {
+        let project_cwd = Self::terminal_spawn_cwd(self.current_project_root.as_deref());
+        match strategy {
+            TerminalCwdStrategy::ProjectRoot => project_cwd,
+            TerminalCwdStrategy::CurrentFile => {
+                let directory = {
+                    let editor = &self.core.read(cx).editor;
+                    editor
+                        .tree
+                        .try_get(editor.tree.focus)
+                        .and_then(|view| editor.documents.get(&view.doc))
+                        .and_then(|doc| doc.path())
+                        .and_then(Path::parent)
+                        .map(Path::to_path_buf)
+                };
+                directory
+                    .map(|directory| self.terminal_directory_for_path(&directory, cx))
+                    .or(project_cwd)
+            }
+            TerminalCwdStrategy::Home => dirs::home_dir().or(project_cwd),
+        }
+    }
+
     fn spawn_terminal_command_session(
         &mut self,
         cwd: Option<PathBuf>,
         program: String,
         args: Vec<String>,
         extra_env: Vec<(String, String)>,
+        initial_input: Vec<SessionInput>,
         cx: &mut Context<Self>,
     ) -> TerminalId {
         let id = TerminalId(self.next_terminal_id);
@@ -2708,11 +2991,12 @@ impl Workspace {
         self.last_terminal_bounds = None;
 
         let workspace_root = self.current_project_root.clone();
-        let (terminal_runtime, project_environment) = {
+        let (terminal_runtime, project_environment, scrollback_lines) = {
             let core = self.core.read(cx);
             (
                 core.terminal_runtime.clone(),
                 core.project_environment.clone(),
+                core.config.gui.terminal.scrollback_lines,
             )
         };
         self.handle.spawn(async move {
@@ -2734,7 +3018,12 @@ impl Workspace {
                     program: proxy_program,
                     args: proxy_args,
                     env: Vec::new(),
+                    scrollback_lines,
                 });
+
+                for input in initial_input {
+                    terminal_runtime.queue_input(id, input);
+                }
                 return;
             }
 
@@ -2766,15 +3055,21 @@ impl Workspace {
                 program,
                 args,
                 env,
+                scrollback_lines,
             });
+
+            for input in initial_input {
+                terminal_runtime.queue_input(id, input);
+            }
         });
 
         id
     }
 
     fn set_embedded_terminal_panel(&mut self, terminal_id: TerminalId, cx: &mut Context<Self>) {
-        let height = self.basic_terminal_height;
+        let height = self.panels.terminal_height;
         let workspace = cx.entity().clone();
+        let open_file_workspace = workspace.downgrade();
         let entity = cx.new(|cx| {
             let mut p = nucleotide_terminal_panel::TerminalPanel::new(terminal_id, height, cx)
                 .on_close(move |id, _window, cx| {
@@ -2782,6 +3077,16 @@ impl Workspace {
                         workspace.close_terminal_panel_session(id, cx);
                     });
                     cx.stop_propagation();
+                })
+                .on_open_file(move |path, line, column, cx| {
+                    let _ = open_file_workspace.update(cx, |workspace, cx| {
+                        // Printed positions are one-based
+                        let position = Position::new(
+                            line.unwrap_or(1).saturating_sub(1),
+                            column.unwrap_or(1).saturating_sub(1),
+                        );
+                        workspace.open_file_at(&path, position, cx);
+                    });
                 });
             p.initialize(cx);
             p
@@ -2801,6 +3106,23 @@ impl Workspace {
         }
     }
 
+    /// The session shown in the embedded terminal panel.
+    #[cfg(test)]
+    pub(crate) fn terminal_id(&self) -> Option<TerminalId> {
+        self.terminal_id
+    }
+
+    /// Runs `program` in the embedded terminal panel, as runnables do.
+    #[cfg(test)]
+    pub(crate) fn open_terminal_command(
+        &mut self,
+        program: String,
+        args: Vec<String>,
+        cx: &mut Context<Self>,
+    ) -> TerminalId {
+        self.open_terminal_panel_for_command(None, program, args, Vec::new(), Vec::new(), cx)
+    }
+
     fn terminal_focus_handle(&self, cx: &App) -> Option<FocusHandle> {
         self.embedded_terminal_panel
             .as_ref()
@@ -2808,7 +3130,7 @@ impl Workspace {
     }
 
     fn terminal_is_focused(&self, window: &Window, cx: &App) -> bool {
-        self.terminal_panel_visible
+        self.panels.terminal_visible
             && self
                 .terminal_focus_handle(cx)
                 .is_some_and(|focus| focus.is_focused(window))
@@ -2823,14 +3145,14 @@ impl Workspace {
     }
 
     fn open_terminal_panel_at(&mut self, cwd: Option<PathBuf>, cx: &mut Context<Self>) {
-        self.open_terminal_panel_at_with_input(cwd, Vec::new(), None, cx);
+        self.open_terminal_panel_at_with_input(cwd, Vec::new(), Vec::new(), cx);
     }
 
     fn open_terminal_panel_at_with_input(
         &mut self,
         cwd: Option<PathBuf>,
         extra_env: Vec<(String, String)>,
-        initial_input: Option<Vec<u8>>,
+        initial_input: Vec<SessionInput>,
         cx: &mut Context<Self>,
     ) -> TerminalId {
         if let Some(existing_id) = self.terminal_id {
@@ -2838,8 +3160,7 @@ impl Workspace {
         }
         let id = self.spawn_terminal_session_with_input(cwd, extra_env, initial_input, cx);
         self.set_embedded_terminal_panel(id, cx);
-        self.terminal_panel_visible = true;
-        self.terminal_focus_pending = true;
+        self.panels.show_terminal();
         cx.notify();
         id
     }
@@ -2850,22 +3171,22 @@ impl Workspace {
         program: String,
         args: Vec<String>,
         extra_env: Vec<(String, String)>,
+        initial_input: Vec<SessionInput>,
         cx: &mut Context<Self>,
     ) -> TerminalId {
         if let Some(existing_id) = self.terminal_id {
             self.shutdown_terminal_session(existing_id, cx);
         }
-        let id = self.spawn_terminal_command_session(cwd, program, args, extra_env, cx);
+        let id =
+            self.spawn_terminal_command_session(cwd, program, args, extra_env, initial_input, cx);
         self.set_embedded_terminal_panel(id, cx);
-        self.terminal_panel_visible = true;
-        self.terminal_focus_pending = true;
+        self.panels.show_terminal();
         cx.notify();
         id
     }
 
     fn hide_terminal_panel(&mut self, cx: &mut Context<Self>) {
-        self.terminal_panel_visible = false;
-        self.terminal_focus_pending = false;
+        self.panels.hide_terminal();
         self.last_terminal_bounds = None;
         self.needs_focus_restore = true;
         if let Some(focus) = self.terminal_focus_handle(cx)
@@ -2889,6 +3210,13 @@ impl Workspace {
         if let Some(id) = cleared_id {
             nucleotide_terminal_view::unregister_view_model(id);
         }
+        if let Some(scaffolding) = self
+            .file_ops
+            .scaffolding_project
+            .take_if(|scaffolding| Some(scaffolding.terminal_id) == cleared_id)
+        {
+            self.finish_background_activity(scaffolding.activity_id, cx);
+        }
         if self.run_output_terminal == cleared_id {
             self.run_output_terminal = None;
         }
@@ -2905,6 +3233,44 @@ impl Workspace {
             return;
         }
 
+        if let Some(scaffolding) = self
+            .file_ops
+            .scaffolding_project
+            .take_if(|scaffolding| scaffolding.terminal_id == id)
+        {
+            self.finish_background_activity(scaffolding.activity_id, cx);
+            let project = scaffolding.project;
+            let failure = match code {
+                Some(0) | None if project.was_created() => None,
+                Some(0) | None => Some(format!(
+                    "New project command did not create {}",
+                    project.directory().display()
+                )),
+                Some(exit_code) => Some(format!(
+                    "New project command failed with exit code {exit_code}"
+                )),
+            };
+            if let Some(failure) = failure {
+                // Keep the output in view to show what went wrong
+                self.set_run_status(failure, Severity::Error, cx);
+                if !force_close_panel {
+                    cx.notify();
+                    return;
+                }
+            } else {
+                self.set_run_status(
+                    format!("Created project {}", project.name),
+                    Severity::Info,
+                    cx,
+                );
+                self.hide_terminal_panel(cx);
+                self.clear_terminal_panel_session(cx);
+                self.handle_open_directory(&project.directory(), cx);
+                cx.notify();
+                return;
+            }
+        }
+
         let was_active_run = self
             .active_run_terminal
             .is_some_and(|(terminal_id, _run_id)| terminal_id == id);
@@ -2920,7 +3286,19 @@ impl Workspace {
             if let Some(activity_id) = self.active_run_activity.take() {
                 self.finish_background_activity(activity_id, cx);
             }
-            self.terminal_focus_pending = false;
+            self.panels.cancel_focus(PanelKind::Terminal);
+            self.run_problems = self.collect_run_problems(id);
+
+            let mut pending = std::mem::take(&mut self.pending_run_tasks);
+            if status == RunStatus::Finished
+                && !force_close_panel
+                && let Some(next) = pending.pop_front()
+            {
+                self.run_task(next, cx);
+                self.pending_run_tasks = pending;
+                cx.notify();
+                return;
+            }
             let status_message = match (status, code) {
                 (RunStatus::Finished, Some(0) | None) => "Runnable finished".to_string(),
                 (RunStatus::Failed, Some(exit_code)) => {
@@ -2939,6 +3317,9 @@ impl Workspace {
                 },
                 cx,
             );
+            if status == RunStatus::Failed && !self.run_problems.is_empty() {
+                self.show_run_problems(cx);
+            }
         }
 
         if was_active_run && !force_close_panel {
@@ -3049,16 +3430,27 @@ impl Workspace {
 
     fn toggle_terminal_panel(&mut self, cx: &mut Context<Self>) {
         // Basic layout: toggle visibility of embedded bottom panel
-        if self.terminal_panel_visible {
+        if self.panels.terminal_visible {
             self.hide_terminal_panel(cx);
             cx.notify();
             return;
         }
 
         // Ensure terminal exists and embedded panel entity is available
-        let desired_cwd = Self::terminal_spawn_cwd(self.current_project_root.as_deref());
+        let strategy = self
+            .core
+            .read(cx)
+            .config
+            .gui
+            .terminal
+            .default_profile()
+            .map(|profile| profile.cwd)
+            .unwrap_or_default();
+        let desired_cwd = self.terminal_profile_cwd(strategy, cx);
+        // A current-file terminal is not restarted just because focus moved.
         let terminal_id = if let Some(id) = self.terminal_id
-            && Self::terminal_cwd_matches(self.terminal_cwd.as_deref(), desired_cwd.as_deref())
+            && (strategy == TerminalCwdStrategy::CurrentFile
+                || Self::terminal_cwd_matches(self.terminal_cwd.as_deref(), desired_cwd.as_deref()))
         {
             id
         } else {
@@ -3076,10 +3468,9 @@ impl Workspace {
             self.set_embedded_terminal_panel(terminal_id, cx);
         }
 
-        self.terminal_panel_visible = true;
         self.register_terminal_focus(cx);
         // Ask render to focus the terminal on the next frame
-        self.terminal_focus_pending = true;
+        self.panels.show_terminal();
         cx.notify();
     }
 
@@ -3143,6 +3534,11 @@ impl Workspace {
     fn request_focused_runnables(&mut self, action: RunnableAction, cx: &mut Context<Self>) {
         let document = match self.focused_runnable_document(cx) {
             Ok(document) => document,
+            Err(_) if action == RunnableAction::ShowPicker => {
+                // Project tasks do not need a focused file.
+                self.finish_runnable_request(action, Vec::new(), 0, cx);
+                return;
+            }
             Err(message) => {
                 self.set_run_status(message, Severity::Error, cx);
                 return;
@@ -3286,16 +3682,20 @@ impl Workspace {
     fn finish_runnable_request(
         &mut self,
         action: RunnableAction,
-        tasks: Vec<ResolvedTask>,
+        mut tasks: Vec<ResolvedTask>,
         cursor_line: usize,
         cx: &mut Context<Self>,
     ) {
+        if action == RunnableAction::ShowPicker {
+            tasks.extend(self.project_tasks(cx));
+        }
         if tasks.is_empty() {
-            self.set_run_status(
-                "No runnable Rust targets found in the focused file",
-                Severity::Error,
-                cx,
-            );
+            let message = if action == RunnableAction::ShowPicker {
+                "No runnable targets found in the focused file or project"
+            } else {
+                "No runnable Rust targets found in the focused file"
+            };
+            self.set_run_status(message, Severity::Error, cx);
             return;
         }
 
@@ -3329,486 +3729,1214 @@ impl Workspace {
         }
     }
 
-    fn show_runnables_picker(&mut self, tasks: Vec<ResolvedTask>, cx: &mut Context<Self>) {
+    /// The project's run configurations and the root they resolve against.
+    fn run_configurations(
+        &self,
+        cx: &Context<Self>,
+    ) -> (Vec<crate::config::RunConfiguration>, Option<PathBuf>) {
+        let core = self.core.read(cx);
+        (
+            core.config.gui.run_configurations.clone(),
+            core.project_directory.clone(),
+        )
+    }
+
+    fn show_run_configurations_picker(&mut self, cx: &mut Context<Self>) {
         use crate::picker_view::PickerItem;
+        use crate::run_configurations::{
+            RunConfigurationChoice, default_run_configuration, resolve_configuration,
+        };
 
-        let items = tasks
-            .into_iter()
-            .map(|task| {
-                let file_path = task.source().map(|source| source.path.clone());
+        let (mut configs, root) = self.run_configurations(cx);
+        if configs.is_empty() {
+            self.set_run_status(
+                format!(
+                    "No run configurations; add [[run]] entries to {}",
+                    crate::config::WORKSPACE_SETTINGS_PATH
+                ),
+                Severity::Info,
+                cx,
+            );
+            return;
+        }
+        // The last used configuration comes first so Enter runs it again.
+        if let Some(name) = default_run_configuration(&configs, root.as_deref(), cx)
+            .map(|config| config.name.clone())
+            && let Some(index) = configs.iter().position(|config| config.name == name)
+        {
+            let config = configs.remove(index);
+            configs.insert(0, config);
+        }
+
+        let items = configs
+            .iter()
+            .map(|config| {
+                let task = resolve_configuration(config, root.as_deref());
+                let mut sublabel = crate::runnables::shell_command_line(&task.command);
+                if let Some(pre_launch) = &config.pre_launch {
+                    sublabel.push_str(&format!("  (after {pre_launch})"));
+                }
                 PickerItem {
-                    label: task.label().to_string().into(),
-                    sublabel: Some(crate::runnables::shell_command_line(&task.command).into()),
-                    data: Arc::new(task),
-                    file_path,
+                    label: config.name.clone().into(),
+                    sublabel: Some(sublabel.into()),
+                    data: Arc::new(RunConfigurationChoice {
+                        name: config.name.clone(),
+                    }),
+                    file_path: None,
                     vcs_status: None,
                     columns: None,
                 }
             })
             .collect::<Vec<_>>();
 
-        let picker = crate::picker::Picker::native("Run", items, |_| {}).with_preview(true);
+        let picker = crate::picker::Picker::native("Run Configuration", items, |_| {});
         emit_picker_update(picker, &self.overlay, cx);
     }
 
-    fn run_task(&mut self, task: ResolvedTask, cx: &mut Context<Self>) {
-        let run_id = RunId(self.next_run_id);
-        self.next_run_id += 1;
+    /// Runs the configuration last used in this project, or the first one.
+    fn run_default_configuration(&mut self, cx: &mut Context<Self>) {
+        let (configs, root) = self.run_configurations(cx);
+        match crate::run_configurations::default_run_configuration(&configs, root.as_deref(), cx) {
+            Some(config) => self.run_configuration(&config.name, cx),
+            None => self.show_run_configurations_picker(cx),
+        }
+    }
 
-        let command_line = crate::runnables::shell_command_line(&task.command);
-        let run_message = format!("Running {}: {command_line}", task.label());
-        let cwd = task
-            .command
-            .cwd
-            .clone()
-            .or_else(|| Self::terminal_spawn_cwd(self.current_project_root.as_deref()))
-            .map(|cwd| {
-                let backend_identity = self.core.read(cx).workspace_backend.identity();
-                terminal_directory_for_workspace_path(
-                    &cwd,
-                    self.current_project_root.as_deref(),
-                    &backend_identity,
-                )
-            });
-        let env = task.command.env.clone();
-        let terminal_id = self.open_terminal_panel_for_command(
-            cwd,
-            task.command.program.clone(),
-            task.command.args.clone(),
-            env,
-            cx,
-        );
-        if let Some(activity_id) = self.active_run_activity.take() {
-            self.finish_background_activity(activity_id, cx);
+    /// Starts the pre-launch chain of the configuration `name`, then the
+    /// configuration itself.
+    fn run_configuration(&mut self, name: &str, cx: &mut Context<Self>) {
+        let (configs, root) = self.run_configurations(cx);
+        match crate::run_configurations::launch_sequence(&configs, name, root.as_deref()) {
+            Ok(tasks) => {
+                if cx.has_global::<LastRunConfigurations>() {
+                    cx.global_mut::<LastRunConfigurations>()
+                        .record(root.as_deref(), name);
+                }
+                let mut tasks = VecDeque::from(tasks);
+                if let Some(first) = tasks.pop_front() {
+                    self.run_task(first, cx);
+                    self.pending_run_tasks = tasks;
+                }
+                cx.notify();
+            }
+            Err(error) => self.set_run_status(error, Severity::Error, cx),
         }
-        let activity_id = self.start_background_activity(run_message.clone(), cx);
-        self.last_run_task = Some(task.clone());
-        self.active_run_terminal = Some((terminal_id, run_id));
-        self.active_run_activity = Some(activity_id);
+    }
 
-        self.core.update(cx, |app, app_cx| {
-            app.set_editor_status_feedback(app_cx, run_message, Severity::Info);
-        });
+    /// File positions in the output of the run in terminal `id`. Only the
+    /// visible screen is read; output scrolled into the history is not.
+    fn collect_run_problems(&self, id: TerminalId) -> Vec<crate::run_configurations::RunProblem> {
+        #[cfg(feature = "terminal-emulator-core")]
+        {
+            let cwd = self
+                .last_run_task
+                .as_ref()
+                .and_then(|task| task.command.cwd.clone())
+                .or_else(|| self.current_project_root.clone());
+            if let Some(cwd) = cwd
+                && let Some(model) = nucleotide_terminal_view::get_view_model(id)
+            {
+                let text = model
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .visible_text();
+                return crate::run_configurations::parse_problems(&text, &cwd);
+            }
+        }
+        #[cfg(not(feature = "terminal-emulator-core"))]
+        let _ = id;
+
+        Vec::new()
     }
 
-    fn set_run_status(
-        &mut self,
-        message: impl Into<String>,
-        severity: Severity,
-        cx: &mut Context<Self>,
-    ) {
-        let message = message.into();
-        self.core.update(cx, |app, app_cx| {
-            app.set_editor_status_feedback(app_cx, message, severity);
+    fn show_run_problems(&mut self, cx: &mut Context<Self>) {
+        use crate::picker_view::PickerItem;
+
+        if self.run_problems.is_empty() {
+            self.set_run_status("No problems in the last run's output", Severity::Info, cx);
+            return;
+        }
+
+        let root = self.core.read(cx).project_directory.clone();
+        let items = self
+            .run_problems
+            .iter()
+            .map(|problem| {
+                let path = &problem.location.path;
+                let path_label = root
+                    .as_deref()
+                    .and_then(|root| path.strip_prefix(root).ok())
+                    .unwrap_or(path)
+                    .display()
+                    .to_string();
+                let position = format!("{path_label}:{}:{}", problem.line, problem.column);
+                let label = if problem.message.is_empty() {
+                    position.clone()
+                } else {
+                    problem.message.clone()
+                };
+                PickerItem::with_sublabel_and_path(
+                    label,
+                    position,
+                    path.clone(),
+                    Arc::new(problem.location.clone()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let picker = crate::picker::Picker::native("Problems", items, |_| {
+            // Locations are opened by the overlay via typed item data.
         });
+        emit_picker_update(picker, &self.overlay, cx);
     }
 
-    fn start_background_activity(
-        &mut self,
-        message: impl Into<String>,
-        cx: &mut Context<Self>,
-    ) -> BackgroundActivityId {
-        let id = BackgroundActivityId(self.next_background_activity_id);
-        self.next_background_activity_id = self.next_background_activity_id.wrapping_add(1).max(1);
-        self.background_activities.push(BackgroundActivity {
-            id,
-            message: message.into(),
-        });
-        cx.notify();
-        id
+    /// Root of the open project when it is a local Cargo project.
+    fn cargo_project_root(&self, cx: &Context<Self>) -> Result<PathBuf, String> {
+        let core = self.core.read(cx);
+        let root = core
+            .project_directory
+            .clone()
+            .ok_or_else(|| "Open a Cargo project to choose Cargo settings".to_string())?;
+        if !matches!(core.workspace_backend.identity(), WorkspaceIdentity::Local) {
+            return Err("Cargo settings are only available for local workspaces".to_string());
+        }
+        let is_rust = nucleotide_project::project_status_service(cx)
+            .get_project_types()
+            .iter()
+            .any(|project_type| project_type.name == "rust");
+        if !is_rust && !root.join("Cargo.toml").is_file() {
+            return Err("The open project is not a Cargo project".to_string());
+        }
+        Ok(root)
     }
 
-    fn update_background_activity(
-        &mut self,
-        id: BackgroundActivityId,
-        message: impl Into<String>,
-        cx: &mut Context<Self>,
-    ) {
-        let message = message.into();
-        if let Some(activity) = self
-            .background_activities
-            .iter_mut()
-            .find(|activity| activity.id == id)
-            && activity.message != message
-        {
-            activity.message = message;
-            cx.notify();
-        }
+    /// Cargo selection of the open project, when it has a non-default one.
+    fn cargo_selection(&self, cx: &Context<Self>) -> Option<CargoSelection> {
+        let root = self.core.read(cx).project_directory.clone()?;
+        cx.try_global::<CargoSelections>()
+            .map(|selections| selections.get(&root))
+            .filter(|selection| !selection.is_default())
     }
 
-    fn finish_background_activity(&mut self, id: BackgroundActivityId, cx: &mut Context<Self>) {
-        let previous_len = self.background_activities.len();
-        self.background_activities
-            .retain(|activity| activity.id != id);
-        if self.background_activities.len() != previous_len {
-            cx.notify();
-        }
+    /// Loads the project's Cargo workspace in the background and opens the
+    /// Cargo settings picker.
+    fn show_cargo_settings(&mut self, cx: &mut Context<Self>) {
+        let root = match self.cargo_project_root(cx) {
+            Ok(root) => root,
+            Err(message) => {
+                self.set_run_status(message, Severity::Info, cx);
+                return;
+            }
+        };
+
+        let message = "Reading Cargo metadata...";
+        self.set_run_status(message, Severity::Info, cx);
+        let activity_id = self.start_background_activity(message, cx);
+        let runtime_handle = self.handle.clone();
+        cx.spawn(async move |this, cx| {
+            let metadata_root = root.clone();
+            let result = runtime_handle
+                .spawn_blocking(move || crate::cargo_integration::load_workspace(&metadata_root))
+                .await;
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            this.update(cx, |workspace, cx| {
+                workspace.finish_background_activity(activity_id, cx);
+                match result {
+                    Ok(Ok(cargo_workspace)) => {
+                        workspace.cargo_workspace = Some((root, cargo_workspace));
+                        workspace.show_cargo_settings_picker(cx);
+                    }
+                    Ok(Err(error)) => workspace.set_run_status(
+                        format!("Could not read Cargo metadata: {error}"),
+                        Severity::Error,
+                        cx,
+                    ),
+                    Err(error) => warn!(%error, "Cargo metadata task failed"),
+                }
+            });
+        })
+        .detach();
     }
 
-    fn current_background_activity(&self) -> Option<&BackgroundActivity> {
-        latest_background_activity(&self.background_activities)
+    /// The loaded Cargo workspace and the selection for it, when they
+    /// belong to the open project.
+    fn cargo_state(&self, cx: &Context<Self>) -> Option<(PathBuf, CargoWorkspace, CargoSelection)> {
+        let (root, cargo_workspace) = self.cargo_workspace.clone()?;
+        if self.core.read(cx).project_directory.as_ref() != Some(&root) {
+            return None;
+        }
+        let mut selection = cx
+            .try_global::<CargoSelections>()
+            .map(|selections| selections.get(&root))
+            .unwrap_or_default();
+        selection.retain_available(&cargo_workspace);
+        Some((root, cargo_workspace, selection))
     }
 
-    /// Compute document and LSP context for the status bar without triggering borrow conflicts.
-    fn statusbar_doc_info(
-        &self,
+    fn show_cargo_picker(
+        &mut self,
+        title: &str,
+        rows: Vec<(String, Option<String>, CargoChoice)>,
         cx: &mut Context<Self>,
-    ) -> (
-        helix_view::document::Mode,          // current mode
-        &'static str,                        // mode label
-        String,                              // file name display
-        String,                              // position text
-        Option<StatusBarDocumentMetadata>,   // encoding, language, diagnostics
-        bool,                                // has LSP state
-        Option<helix_lsp::LanguageServerId>, // preferred server for current doc
     ) {
-        let core = self.core.read(cx);
-        let editor = &core.editor;
+        use crate::picker_view::PickerItem;
 
-        let mut mode = helix_view::document::Mode::Normal;
-        let mut mode_name = "NORMAL";
-        let mut file_name = "[no file]".to_string();
-        let mut position_text = "1:1".to_string();
-        let mut document_metadata = None;
+        let items = rows
+            .into_iter()
+            .map(|(label, sublabel, choice)| PickerItem {
+                label: label.into(),
+                sublabel: sublabel.map(Into::into),
+                data: Arc::new(choice),
+                file_path: None,
+                vcs_status: None,
+                columns: None,
+            })
+            .collect::<Vec<_>>();
+        let picker = crate::picker::Picker::native(title, items, |_| {});
+        emit_picker_update(picker, &self.overlay, cx);
+    }
 
-        if let Some(tab) = self
-            .active_image_tab_id
-            .and_then(|doc_id| self.image_tabs.iter().find(|tab| tab.id == doc_id))
-        {
-            file_name = tab.path.display().to_string();
-            position_text = image_zoom_percent(tab.zoom);
-            return (mode, mode_name, file_name, position_text, None, false, None);
+    fn show_cargo_settings_picker(&mut self, cx: &mut Context<Self>) {
+        let Some((_, _, selection)) = self.cargo_state(cx) else {
+            self.show_cargo_settings(cx);
+            return;
+        };
+
+        let features = if selection.all_features {
+            "All features".to_string()
+        } else if selection.features.is_empty() {
+            "Default features".to_string()
+        } else {
+            selection.features.join(", ")
+        };
+        let mut rows = vec![
+            (
+                format!(
+                    "Package: {}",
+                    selection.package.as_deref().unwrap_or("All packages")
+                ),
+                Some("Package passed to runs and tests with --package".to_string()),
+                CargoChoice::ShowPackages,
+            ),
+            (
+                format!(
+                    "Target: {}",
+                    selection.target.as_ref().map_or_else(
+                        || "Default".to_string(),
+                        |target| format!("{} ({})", target.name, target.kind.label())
+                    )
+                ),
+                Some("Target built and run by run tasks".to_string()),
+                CargoChoice::ShowTargets,
+            ),
+            (
+                format!("Features: {features}"),
+                Some("Passed to runs, tests and rust-analyzer".to_string()),
+                CargoChoice::ShowFeatures,
+            ),
+        ];
+        if !selection.is_default() {
+            rows.push((
+                "Reset Cargo Settings".to_string(),
+                Some(selection.summary()),
+                CargoChoice::Reset,
+            ));
         }
+        self.show_cargo_picker("Cargo Settings", rows, cx);
+    }
 
-        // Get info from focused view if available
-        if let Some(view_id) = self.view_manager.focused_view_id()
-            && let Some((view, doc)) = editor
-                .tree
-                .try_get(view_id)
-                .and_then(|v| editor.document(v.doc).map(|d| (v, d)))
-        {
-            mode = editor.mode();
-            mode_name = match mode {
-                helix_view::document::Mode::Normal => "NORMAL",
-                helix_view::document::Mode::Insert => "INSERT",
-                helix_view::document::Mode::Select => "SELECT",
-            };
+    fn show_cargo_packages_picker(&mut self, cx: &mut Context<Self>) {
+        let Some((_, cargo_workspace, selection)) = self.cargo_state(cx) else {
+            return;
+        };
 
-            file_name = doc
-                .path()
-                .map(statusbar_file_label)
-                .unwrap_or_else(|| "[scratch]".to_string());
+        let mark = |selected: bool| selected.then(|| "Selected".to_string());
+        let rows = std::iter::once((
+            "All packages".to_string(),
+            mark(selection.package.is_none()),
+            CargoChoice::Package(None),
+        ))
+        .chain(cargo_workspace.packages.iter().map(|package| {
+            (
+                package.name.clone(),
+                mark(selection.package.as_ref() == Some(&package.name))
+                    .or_else(|| Some(package.manifest_path.display().to_string())),
+                CargoChoice::Package(Some(package.name.clone())),
+            )
+        }))
+        .collect();
+        self.show_cargo_picker("Cargo Package", rows, cx);
+    }
 
-            let position = helix_core::coords_at_pos(
-                doc.text().slice(..),
-                doc.selection(view.id)
-                    .primary()
-                    .cursor(doc.text().slice(..)),
-            );
-            position_text = format!("{}:{}", position.row + 1, position.col + 1);
-            document_metadata = Some(StatusBarDocumentMetadata {
-                encoding: doc.encoding().name().to_string(),
-                line_ending: statusbar_line_ending_label(doc.line_ending.as_str()),
-                language: statusbar_language_label(doc.language_name()),
-                errors: doc
-                    .diagnostics()
-                    .iter()
-                    .filter(|diagnostic| {
-                        diagnostic.severity == Some(helix_core::diagnostic::Severity::Error)
-                    })
-                    .count(),
-                warnings: doc
-                    .diagnostics()
-                    .iter()
-                    .filter(|diagnostic| {
-                        diagnostic.severity == Some(helix_core::diagnostic::Severity::Warning)
-                    })
-                    .count(),
-            });
+    fn show_cargo_targets_picker(&mut self, cx: &mut Context<Self>) {
+        let Some((_, cargo_workspace, selection)) = self.cargo_state(cx) else {
+            return;
+        };
+
+        let mut rows = vec![(
+            "Default".to_string(),
+            Some("The target cargo picks for the package".to_string()),
+            CargoChoice::Target(None),
+        )];
+        for package in cargo_workspace.selected_packages(&selection) {
+            for target in &package.targets {
+                let selected = selection.target.as_ref() == Some(target);
+                rows.push((
+                    format!("{} ({})", target.name, target.kind.label()),
+                    Some(if selected {
+                        format!("{} · Selected", package.name)
+                    } else {
+                        package.name.clone()
+                    }),
+                    CargoChoice::Target(Some(target.clone())),
+                ));
+            }
         }
+        self.show_cargo_picker("Cargo Target", rows, cx);
+    }
 
-        // Determine preferred LSP server for the current document
-        let preferred_server_id = if let Some(view_id) = self.view_manager.focused_view_id()
-            && let Some(view) = editor.tree.try_get(view_id)
-            && let Some(doc) = editor.document(view.doc)
-        {
-            doc.language_servers().next().map(|ls| ls.id())
-        } else {
-            None
+    fn show_cargo_features_picker(&mut self, cx: &mut Context<Self>) {
+        let Some((_, cargo_workspace, selection)) = self.cargo_state(cx) else {
+            return;
         };
 
-        let has_lsp_state = core.lsp_state.is_some();
-        (
-            mode,
-            mode_name,
-            file_name,
-            position_text,
-            document_metadata,
-            has_lsp_state,
-            preferred_server_id,
-        )
+        let check = |enabled: bool| if enabled { "[x]" } else { "[ ]" };
+        let mut rows = vec![
+            (
+                format!("{} All features", check(selection.all_features)),
+                Some("--all-features".to_string()),
+                CargoChoice::ToggleAllFeatures,
+            ),
+            (
+                format!(
+                    "{} No default features",
+                    check(selection.no_default_features)
+                ),
+                Some("--no-default-features".to_string()),
+                CargoChoice::ToggleNoDefaultFeatures,
+            ),
+        ];
+        let mut features = cargo_workspace
+            .selected_packages(&selection)
+            .flat_map(|package| package.features.iter().cloned())
+            .collect::<Vec<_>>();
+        features.sort();
+        features.dedup();
+        rows.extend(features.into_iter().map(|feature| {
+            (
+                format!("{} {feature}", check(selection.features.contains(&feature))),
+                None,
+                CargoChoice::ToggleFeature(feature),
+            )
+        }));
+        self.show_cargo_picker("Cargo Features", rows, cx);
     }
 
-    /// Build structured LSP status, preferring active work over the focused document server.
-    fn compute_statusbar_lsp_summary(
-        &self,
-        cx: &mut Context<Self>,
-        has_lsp_state: bool,
-        preferred_server_id: Option<helix_lsp::LanguageServerId>,
-    ) -> Option<LspStatusSummary> {
-        if !has_lsp_state {
-            return None;
+    fn handle_cargo_choice(&mut self, choice: &CargoChoice, cx: &mut Context<Self>) {
+        let Some((root, _, mut selection)) = self.cargo_state(cx) else {
+            return;
+        };
+        let previous_settings = selection.rust_analyzer_settings();
+
+        match choice {
+            CargoChoice::ShowPackages => {
+                self.show_cargo_packages_picker(cx);
+                return;
+            }
+            CargoChoice::ShowTargets => {
+                self.show_cargo_targets_picker(cx);
+                return;
+            }
+            CargoChoice::ShowFeatures => {
+                self.show_cargo_features_picker(cx);
+                return;
+            }
+            CargoChoice::Package(package) => {
+                selection.package = package.clone();
+                selection.target = None;
+            }
+            CargoChoice::Target(target) => selection.target = target.clone(),
+            CargoChoice::ToggleFeature(feature) => selection.toggle_feature(feature),
+            CargoChoice::ToggleAllFeatures => selection.all_features = !selection.all_features,
+            CargoChoice::ToggleNoDefaultFeatures => {
+                selection.no_default_features = !selection.no_default_features;
+            }
+            CargoChoice::Reset => selection = CargoSelection::default(),
         }
 
-        let lsp_state_entity = {
-            let core = self.core.read(cx);
-            core.lsp_state.clone()
-        }?;
+        if let Some((_, cargo_workspace)) = &self.cargo_workspace {
+            selection.retain_available(cargo_workspace);
+        }
+        let settings = selection.rust_analyzer_settings();
+        if cx.has_global::<CargoSelections>() {
+            cx.global_mut::<CargoSelections>()
+                .set(&root, selection.clone());
+        }
+        if settings != previous_settings {
+            self.core.update(cx, |core, _cx| {
+                core.editor
+                    .language_servers
+                    .set_config_override(RUST_ANALYZER, settings);
+            });
+            self.restart_language_server(RUST_ANALYZER, cx);
+        }
 
-        Some(statusbar_lsp_summary_for_state(
-            lsp_state_entity.read(cx),
-            preferred_server_id,
-        ))
+        match choice {
+            CargoChoice::ToggleFeature(_)
+            | CargoChoice::ToggleAllFeatures
+            | CargoChoice::ToggleNoDefaultFeatures => self.show_cargo_features_picker(cx),
+            _ => self.show_cargo_settings_picker(cx),
+        }
+        cx.notify();
     }
 
-    fn statusbar_model(&self, density: StatusBarDensity, cx: &mut Context<Self>) -> StatusBarModel {
-        let (
-            mode,
-            mode_label,
-            file_name,
-            position_text,
-            document_metadata,
-            has_lsp_state,
-            preferred_server_id,
-        ) = self.statusbar_doc_info(cx);
-        StatusBarModel {
-            mode,
-            mode_label,
-            file_name,
-            position_text,
-            document_metadata,
-            vcs_ref: cx
-                .has_global::<VcsServiceHandle>()
-                .then(|| {
-                    let vcs = cx.global::<VcsServiceHandle>();
-                    vcs.repository_ref(cx).or_else(|| {
-                        vcs.repository_head(cx)
-                            .map(|head| abbreviated_vcs_ref(&head))
-                    })
-                })
-                .flatten(),
-            lsp: self.compute_statusbar_lsp_summary(cx, has_lsp_state, preferred_server_id),
-            notification: self.notifications.read(cx).status_bar_notification(),
-            background_activity: self
-                .current_background_activity()
-                .map(|activity| activity.message.clone()),
-            density,
+    /// Stops and starts the project's language server `name` so it picks up
+    /// changed settings.
+    fn restart_language_server(&mut self, name: &str, cx: &mut Context<Self>) {
+        let Some(plan) = self
+            .core
+            .read(cx)
+            .lsp_state
+            .as_ref()
+            .and_then(|state| lsp_restart_plan(state.read(cx)))
+        else {
+            return;
+        };
+        let Some(sender) = self.core.read(cx).get_project_lsp_command_sender() else {
+            return;
+        };
+
+        for target in plan
+            .targets
+            .iter()
+            .filter(|target| target.server_name == name)
+        {
+            let (response, _response_rx) = tokio::sync::oneshot::channel();
+            let _ = sender.send(nucleotide_events::ProjectLspCommand::StopServer {
+                server_id: target.server_id,
+                response,
+                span: tracing::info_span!(
+                    "settings_lsp_restart_stop",
+                    server_name = %target.server_name
+                ),
+            });
+            let _ = sender.send(
+                nucleotide_events::ProjectLspCommand::LspServerStartupRequested {
+                    workspace_root: plan.workspace_root.clone(),
+                    server_name: target.server_name.clone(),
+                    language_id: target.language_id.clone(),
+                },
+            );
+            self.set_run_status(
+                format!("Restarting {name} with the new Cargo features"),
+                Severity::Info,
+                cx,
+            );
         }
     }
 
-    fn statusbar_text_item(
-        &self,
-        text: impl Into<SharedString>,
-        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
-    ) -> gpui::AnyElement {
-        div()
-            .h_full()
-            .px_2()
-            .max_w(px(120.0))
-            .flex()
-            .items_center()
-            .overflow_hidden()
-            .whitespace_nowrap()
-            .text_ellipsis()
-            .text_color(status_bar_tokens.text_secondary)
-            .child(text.into())
-            .into_any_element()
+    /// Reads the package.json scripts of the project and of the focused
+    /// file's package in the background and opens the scripts picker.
+    fn show_npm_scripts(&mut self, cx: &mut Context<Self>) {
+        let (root, identity) = {
+            let core = self.core.read(cx);
+            (
+                core.project_directory.clone(),
+                core.workspace_backend.identity(),
+            )
+        };
+        if !matches!(identity, WorkspaceIdentity::Local) {
+            self.set_run_status(
+                "NPM scripts are only available for local workspaces",
+                Severity::Info,
+                cx,
+            );
+            return;
+        }
+        let path = {
+            let editor = &self.core.read(cx).editor;
+            editor
+                .tree
+                .try_get(editor.tree.focus)
+                .and_then(|view| editor.documents.get(&view.doc))
+                .and_then(|doc| doc.path().map(Path::to_path_buf))
+        };
+
+        let runtime_handle = self.handle.clone();
+        cx.spawn(async move |this, cx| {
+            let result = runtime_handle
+                .spawn_blocking(move || {
+                    crate::npm_scripts::discover(root.as_deref(), path.as_deref())
+                })
+                .await;
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            this.update(cx, |workspace, cx| match result {
+                Ok(Ok(packages)) if packages.is_empty() => {
+                    workspace.set_run_status("No package.json scripts found", Severity::Info, cx)
+                }
+                Ok(Ok(packages)) => workspace.show_npm_scripts_picker(packages, cx),
+                Ok(Err(error)) => workspace.set_run_status(
+                    format!("Could not read package.json: {error}"),
+                    Severity::Error,
+                    cx,
+                ),
+                Err(error) => warn!(%error, "package.json script discovery failed"),
+            });
+        })
+        .detach();
     }
 
-    fn statusbar_diagnostics_item(
-        &self,
-        metadata: &StatusBarDocumentMetadata,
-        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
+    fn show_npm_scripts_picker(
+        &mut self,
+        packages: Vec<crate::npm_scripts::PackageScripts>,
         cx: &mut Context<Self>,
-    ) -> gpui::AnyElement {
-        let notification_tokens = cx.theme().tokens.notification_tokens();
-        div()
-            .h_full()
-            .px_2()
-            .flex()
-            .items_center()
-            .gap_2()
-            .text_color(status_bar_tokens.text_secondary)
-            .child(
-                div()
-                    .flex()
-                    .items_center()
-                    .gap_1()
-                    .child(
-                        svg()
-                            .path("icons/triangle-alert.svg")
-                            .size(px(12.0))
-                            .text_color(notification_tokens.warning_text),
-                    )
-                    .child(metadata.warnings.to_string()),
-            )
-            .child(
-                div()
-                    .flex()
-                    .items_center()
-                    .gap_1()
-                    .child(
-                        svg()
-                            .path("icons/circle-x.svg")
-                            .size(px(12.0))
-                            .text_color(notification_tokens.error_text),
-                    )
-                    .child(metadata.errors.to_string()),
-            )
-            .into_any_element()
-    }
+    ) {
+        use crate::picker_view::PickerItem;
 
-    fn statusbar_vcs_item(
-        &self,
-        vcs_ref: &str,
-        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
-    ) -> gpui::AnyElement {
-        div()
-            .h_full()
-            .max_w(px(200.0))
-            .px_2()
-            .flex()
-            .items_center()
-            .gap_1()
-            .overflow_hidden()
-            .text_color(status_bar_tokens.text_secondary)
-            .child(
-                svg()
-                    .path("icons/git-branch.svg")
-                    .size(px(12.0))
-                    .flex_shrink_0(),
-            )
-            .child(
-                div()
-                    .min_w_0()
-                    .overflow_hidden()
-                    .whitespace_nowrap()
-                    .text_ellipsis()
-                    .child(vcs_ref.to_string()),
-            )
-            .into_any_element()
-    }
+        let prefix = packages.len() > 1;
+        let items = packages
+            .iter()
+            .flat_map(|package| {
+                package.tasks(prefix).into_iter().zip(&package.scripts).map(
+                    move |(task, script)| PickerItem {
+                        label: task.label().to_string().into(),
+                        sublabel: Some(
+                            format!("{} · {}", package.manager.program(), script.command).into(),
+                        ),
+                        file_path: Some(package.manifest_path.clone()),
+                        data: Arc::new(task),
+                        vcs_status: None,
+                        columns: None,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
 
-    fn statusbar_mode_item(
-        &self,
-        model: &StatusBarModel,
-        geometry: StatusBarGeometry,
-        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
-        border_radius: Pixels,
-    ) -> gpui::AnyElement {
-        let (mode_color, mode_text) = match model.mode {
-            helix_view::document::Mode::Normal => (
-                status_bar_tokens.mode_normal,
-                status_bar_tokens.mode_normal_text,
-            ),
-            helix_view::document::Mode::Insert => (
-                status_bar_tokens.mode_insert,
-                status_bar_tokens.mode_insert_text,
-            ),
-            helix_view::document::Mode::Select => (
-                status_bar_tokens.mode_select,
-                status_bar_tokens.mode_select_text,
-            ),
-        };
-        div()
-            .flex_none()
-            .w(geometry.mode_width)
-            .h_full()
-            .flex()
-            .items_center()
-            .justify_center()
-            .child(
-                div()
-                    .min_w(px(60.0))
-                    .h(px(22.0))
-                    .px_2()
-                    .flex()
-                    .items_center()
-                    .justify_center()
-                    .rounded(border_radius)
-                    .bg(mode_color)
-                    .text_color(mode_text)
-                    .font_weight(FontWeight::MEDIUM)
-                    .child(model.mode_label),
-            )
-            .into_any_element()
+        // Selected tasks run through the overlay like other runnables.
+        let picker = crate::picker::Picker::native("NPM Scripts", items, |_| {}).with_preview(true);
+        emit_picker_update(picker, &self.overlay, cx);
     }
 
-    fn statusbar_lsp_item(
-        &self,
-        summary: Option<&LspStatusSummary>,
-        density: StatusBarDensity,
-        geometry: StatusBarGeometry,
-        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
-        cx: &mut Context<Self>,
-    ) -> gpui::AnyElement {
-        let unavailable = LspStatusSummary {
-            server_id: None,
-            server_name: "LSP:".to_string(),
-            kind: LspStatusKind::Unavailable,
-            title: "No servers".to_string(),
-            message: None,
-            percentage: None,
-            server_count: 0,
-        };
-        let summary = summary.unwrap_or(&unavailable);
-        let full_text = summary.full_text();
-        let accessible_label = format!("Language server status: {full_text}");
-        let server_width = match density {
-            StatusBarDensity::Wide => px(88.0),
-            StatusBarDensity::Medium => px(62.0),
-            StatusBarDensity::Compact => px(0.0),
-        };
-        let server_name = match density {
-            StatusBarDensity::Wide => summary.server_name.clone(),
-            StatusBarDensity::Medium => short_lsp_server_name(&summary.server_name),
-            StatusBarDensity::Compact => String::new(),
-        };
-        let activity = summary
-            .message
-            .as_deref()
-            .filter(|message| !message.is_empty())
-            .unwrap_or(&summary.title)
-            .to_string();
-        let icon_color = match summary.kind {
-            LspStatusKind::Failed => cx.theme().tokens.notification_tokens().error_text,
-            LspStatusKind::Working | LspStatusKind::Starting => status_bar_tokens.text_accent,
-            LspStatusKind::Idle => status_bar_tokens.text_primary,
-            LspStatusKind::Unavailable => status_bar_tokens.text_secondary,
-        };
-
-        let state_icon = match summary.kind {
-            LspStatusKind::Working | LspStatusKind::Starting => {
-                IndeterminateProgressIndicator::new("statusbar-lsp-spinner")
-                    .size(13.0)
-                    .text_color(icon_color)
-                    .into_any_element()
-            }
-            LspStatusKind::Failed => svg()
-                .path("icons/circle-x.svg")
-                .size(px(13.0))
-                .text_color(icon_color)
-                .into_any_element(),
-            LspStatusKind::Idle => div().text_color(icon_color).child("●").into_any_element(),
-            LspStatusKind::Unavailable => {
-                div().text_color(icon_color).child("○").into_any_element()
+    /// Makefile targets and justfile recipes of the local project root.
+    fn project_tasks(&self, cx: &Context<Self>) -> Vec<ResolvedTask> {
+        let core = self.core.read(cx);
+        match (&core.project_directory, core.workspace_backend.identity()) {
+            (Some(root), WorkspaceIdentity::Local) => {
+                crate::project_tasks::discover_project_tasks(root)
             }
-        };
+            _ => Vec::new(),
+        }
+    }
 
-        let mut content = div()
-            .w_full()
-            .min_w_0()
-            .flex()
+    fn show_runnables_picker(&mut self, tasks: Vec<ResolvedTask>, cx: &mut Context<Self>) {
+        use crate::picker_view::PickerItem;
+
+        let items = tasks
+            .into_iter()
+            .map(|task| {
+                let file_path = task.source().map(|source| source.path.clone());
+                let sublabel = task.description().map_or_else(
+                    || crate::runnables::shell_command_line(&task.command),
+                    str::to_string,
+                );
+                PickerItem {
+                    label: task.label().to_string().into(),
+                    sublabel: Some(sublabel.into()),
+                    data: Arc::new(task),
+                    file_path,
+                    vcs_status: None,
+                    columns: None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let picker = crate::picker::Picker::native("Run", items, |_| {}).with_preview(true);
+        emit_picker_update(picker, &self.overlay, cx);
+    }
+
+    fn run_task(&mut self, task: ResolvedTask, cx: &mut Context<Self>) {
+        let run_id = RunId(self.next_run_id);
+        self.next_run_id += 1;
+        self.pending_run_tasks.clear();
+
+        let mut command = task.command.clone();
+        if let Some(selection) = self.cargo_selection(cx) {
+            selection.apply_to_command(&mut command);
+        }
+        let command_line = crate::runnables::shell_command_line(&command);
+        let run_message = format!("Running {}: {command_line}", task.label());
+        let cwd = task
+            .command
+            .cwd
+            .clone()
+            .or_else(|| Self::terminal_spawn_cwd(self.current_project_root.as_deref()))
+            .map(|cwd| {
+                let backend_identity = self.core.read(cx).workspace_backend.identity();
+                terminal_directory_for_workspace_path(
+                    &cwd,
+                    self.current_project_root.as_deref(),
+                    &backend_identity,
+                )
+            });
+        let env = task.command.env.clone();
+        let terminal_id = self.open_terminal_panel_for_command(
+            cwd,
+            command.program,
+            command.args,
+            env,
+            Vec::new(),
+            cx,
+        );
+        if let Some(activity_id) = self.active_run_activity.take() {
+            self.finish_background_activity(activity_id, cx);
+        }
+        let activity_id = self.start_background_activity(run_message.clone(), cx);
+        self.last_run_task = Some(task.clone());
+        self.active_run_terminal = Some((terminal_id, run_id));
+        self.active_run_activity = Some(activity_id);
+
+        self.core.update(cx, |app, app_cx| {
+            app.set_editor_status_feedback(app_cx, run_message, Severity::Info);
+        });
+    }
+
+    fn set_run_status(
+        &mut self,
+        message: impl Into<String>,
+        severity: Severity,
+        cx: &mut Context<Self>,
+    ) {
+        let message = message.into();
+        self.core.update(cx, |app, app_cx| {
+            app.set_editor_status_feedback(app_cx, message, severity);
+        });
+    }
+
+    fn start_background_activity(
+        &mut self,
+        message: impl Into<String>,
+        cx: &mut Context<Self>,
+    ) -> BackgroundActivityId {
+        let id = BackgroundActivityId(self.next_background_activity_id);
+        self.next_background_activity_id = self.next_background_activity_id.wrapping_add(1).max(1);
+        self.background_activities.push(BackgroundActivity {
+            id,
+            message: message.into(),
+        });
+        cx.notify();
+        id
+    }
+
+    fn update_background_activity(
+        &mut self,
+        id: BackgroundActivityId,
+        message: impl Into<String>,
+        cx: &mut Context<Self>,
+    ) {
+        let message = message.into();
+        if let Some(activity) = self
+            .background_activities
+            .iter_mut()
+            .find(|activity| activity.id == id)
+            && activity.message != message
+        {
+            activity.message = message;
+            cx.notify();
+        }
+    }
+
+    fn finish_background_activity(&mut self, id: BackgroundActivityId, cx: &mut Context<Self>) {
+        let previous_len = self.background_activities.len();
+        self.background_activities
+            .retain(|activity| activity.id != id);
+        if self.background_activities.len() != previous_len {
+            cx.notify();
+        }
+    }
+
+    fn current_background_activity(&self) -> Option<&BackgroundActivity> {
+        latest_background_activity(&self.background_activities)
+    }
+
+    /// Compute document and LSP context for the status bar without triggering borrow conflicts.
+    fn statusbar_doc_info(
+        &self,
+        cx: &mut Context<Self>,
+    ) -> (
+        helix_view::document::Mode,          // current mode
+        &'static str,                        // mode label
+        String,                              // file name display
+        String,                              // position text
+        Option<StatusBarDocumentMetadata>,   // encoding, language, diagnostics
+        bool,                                // has LSP state
+        Option<helix_lsp::LanguageServerId>, // preferred server for current doc
+    ) {
+        let core = self.core.read(cx);
+        let editor = &core.editor;
+
+        let mut mode = helix_view::document::Mode::Normal;
+        let mut mode_name = "NORMAL";
+        let mut file_name = "[no file]".to_string();
+        let mut position_text = "1:1".to_string();
+        let mut document_metadata = None;
+
+        if let Some(tab) = self
+            .active_image_tab_id
+            .and_then(|doc_id| self.image_tabs.iter().find(|tab| tab.id == doc_id))
+        {
+            file_name = tab.path.display().to_string();
+            position_text = match tab.hex_editor() {
+                Some(hex_editor) => {
+                    mode_name = "HEX";
+                    hex_editor.read(cx).buffer().position_label()
+                }
+                None => image_zoom_percent(tab.zoom),
+            };
+            return (mode, mode_name, file_name, position_text, None, false, None);
+        }
+
+        // Get info from focused view if available
+        if let Some(view_id) = self.view_manager.focused_view_id()
+            && let Some((view, doc)) = editor
+                .tree
+                .try_get(view_id)
+                .and_then(|v| editor.document(v.doc).map(|d| (v, d)))
+        {
+            mode = editor.mode();
+            mode_name = match mode {
+                helix_view::document::Mode::Normal => "NORMAL",
+                helix_view::document::Mode::Insert => "INSERT",
+                helix_view::document::Mode::Select => "SELECT",
+            };
+
+            file_name = doc
+                .path()
+                .map(statusbar_file_label)
+                .unwrap_or_else(|| "[scratch]".to_string());
+
+            let position = helix_core::coords_at_pos(
+                doc.text().slice(..),
+                doc.selection(view.id)
+                    .primary()
+                    .cursor(doc.text().slice(..)),
+            );
+            position_text = format!("{}:{}", position.row + 1, position.col + 1);
+            document_metadata = Some(StatusBarDocumentMetadata {
+                encoding: doc.encoding().name().to_string(),
+                line_ending: statusbar_line_ending_label(doc.line_ending.as_str()),
+                language: statusbar_language_label(doc.language_name()),
+                indentation: crate::indentation::statusline_label(
+                    doc.indent_style,
+                    doc.tab_width(),
+                ),
+                errors: doc
+                    .diagnostics()
+                    .iter()
+                    .filter(|diagnostic| {
+                        diagnostic.severity == Some(helix_core::diagnostic::Severity::Error)
+                    })
+                    .count(),
+                warnings: doc
+                    .diagnostics()
+                    .iter()
+                    .filter(|diagnostic| {
+                        diagnostic.severity == Some(helix_core::diagnostic::Severity::Warning)
+                    })
+                    .count(),
+            });
+        }
+
+        // Determine preferred LSP server for the current document
+        let preferred_server_id = if let Some(view_id) = self.view_manager.focused_view_id()
+            && let Some(view) = editor.tree.try_get(view_id)
+            && let Some(doc) = editor.document(view.doc)
+        {
+            doc.language_servers().next().map(|ls| ls.id())
+        } else {
+            None
+        };
+
+        let has_lsp_state = core.lsp_state.is_some();
+        (
+            mode,
+            mode_name,
+            file_name,
+            position_text,
+            document_metadata,
+            has_lsp_state,
+            preferred_server_id,
+        )
+    }
+
+    /// Build structured LSP status, preferring active work over the focused document server.
+    fn compute_statusbar_lsp_summary(
+        &self,
+        cx: &mut Context<Self>,
+        has_lsp_state: bool,
+        preferred_server_id: Option<helix_lsp::LanguageServerId>,
+    ) -> Option<LspStatusSummary> {
+        if !has_lsp_state {
+            return None;
+        }
+
+        let lsp_state_entity = {
+            let core = self.core.read(cx);
+            core.lsp_state.clone()
+        }?;
+
+        Some(statusbar_lsp_summary_for_state(
+            lsp_state_entity.read(cx),
+            preferred_server_id,
+        ))
+    }
+
+    fn statusbar_model(&self, density: StatusBarDensity, cx: &mut Context<Self>) -> StatusBarModel {
+        let (
+            mode,
+            mode_label,
+            file_name,
+            position_text,
+            document_metadata,
+            has_lsp_state,
+            preferred_server_id,
+        ) = self.statusbar_doc_info(cx);
+        StatusBarModel {
+            mode,
+            mode_label,
+            file_name,
+            position_text,
+            document_metadata,
+            vcs_ref: cx
+                .has_global::<VcsServiceHandle>()
+                .then(|| {
+                    let vcs = cx.global::<VcsServiceHandle>();
+                    vcs.repository_ref(cx).or_else(|| {
+                        vcs.repository_head(cx)
+                            .map(|head| abbreviated_vcs_ref(&head))
+                    })
+                })
+                .flatten(),
+            run_configuration: {
+                let core = self.core.read(cx);
+                crate::run_configurations::default_run_configuration(
+                    &core.config.gui.run_configurations,
+                    core.project_directory.as_deref(),
+                    cx,
+                )
+                .map(|config| config.name.clone())
+            },
+            lsp: self.compute_statusbar_lsp_summary(cx, has_lsp_state, preferred_server_id),
+            notification: self.notifications.read(cx).status_bar_notification(),
+            background_activity: self
+                .current_background_activity()
+                .map(|activity| activity.message.clone()),
+            density,
+        }
+    }
+
+    fn statusbar_text_item(
+        &self,
+        text: impl Into<SharedString>,
+        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
+    ) -> gpui::AnyElement {
+        div()
+            .h_full()
+            .px_2()
+            .max_w(px(120.0))
+            .flex()
+            .items_center()
+            .overflow_hidden()
+            .whitespace_nowrap()
+            .text_ellipsis()
+            .text_color(status_bar_tokens.text_secondary)
+            .child(text.into())
+            .into_any_element()
+    }
+
+    fn statusbar_diagnostics_item(
+        &self,
+        metadata: &StatusBarDocumentMetadata,
+        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
+        cx: &mut Context<Self>,
+    ) -> gpui::AnyElement {
+        let notification_tokens = cx.theme().tokens.notification_tokens();
+        div()
+            .h_full()
+            .px_2()
+            .flex()
+            .items_center()
+            .gap_2()
+            .text_color(status_bar_tokens.text_secondary)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .child(
+                        svg()
+                            .path("icons/triangle-alert.svg")
+                            .size(px(12.0))
+                            .text_color(notification_tokens.warning_text),
+                    )
+                    .child(metadata.warnings.to_string()),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .child(
+                        svg()
+                            .path("icons/circle-x.svg")
+                            .size(px(12.0))
+                            .text_color(notification_tokens.error_text),
+                    )
+                    .child(metadata.errors.to_string()),
+            )
+            .into_any_element()
+    }
+
+    fn statusbar_vcs_item(
+        &self,
+        vcs_ref: &str,
+        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
+    ) -> gpui::AnyElement {
+        div()
+            .h_full()
+            .max_w(px(200.0))
+            .px_2()
+            .flex()
+            .items_center()
+            .gap_1()
+            .overflow_hidden()
+            .text_color(status_bar_tokens.text_secondary)
+            .child(
+                svg()
+                    .path("icons/git-branch.svg")
+                    .size(px(12.0))
+                    .flex_shrink_0(),
+            )
+            .child(
+                div()
+                    .min_w_0()
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .text_ellipsis()
+                    .child(vcs_ref.to_string()),
+            )
+            .into_any_element()
+    }
+
+    /// Indentation label that opens the indentation menu.
+    fn statusbar_indentation_item(
+        &self,
+        label: &str,
+        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
+        cx: &mut Context<Self>,
+    ) -> gpui::AnyElement {
+        let content = div()
+            .whitespace_nowrap()
+            .text_color(status_bar_tokens.text_secondary)
+            .child(label.to_string());
+        Button::new("statusbar-indentation", "")
+            .variant(ButtonVariant::Ghost)
+            .size(ButtonSize::ExtraSmall)
+            .content(content)
+            .tooltip("Select Indentation")
+            .aria_label(format!("Indentation: {label}"))
+            .focus_handle(self.statusbar_focus.indentation.clone())
+            .activate_on_mouse_down()
+            .on_click(
+                cx.listener(|this: &mut Workspace, _: &gpui::ClickEvent, _window, cx| {
+                    this.show_indentation_picker(cx);
+                }),
+            )
+            .into_any_element()
+    }
+
+    /// Run button for the project's run configuration, with a dropdown to
+    /// pick another one.
+    fn statusbar_run_item(
+        &self,
+        name: &str,
+        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
+        cx: &mut Context<Self>,
+    ) -> gpui::AnyElement {
+        let run_button = Button::icon_only("statusbar-run", "icons/play.svg")
+            .variant(ButtonVariant::Ghost)
+            .size(ButtonSize::ExtraSmall)
+            .tooltip(format!("Run {name}"))
+            .aria_label(format!("Run configuration {name}"))
+            .focus_handle(self.statusbar_focus.run.clone())
+            .activate_on_mouse_down()
+            .on_click(
+                cx.listener(|this: &mut Workspace, _: &gpui::ClickEvent, _window, cx| {
+                    this.run_default_configuration(cx);
+                }),
+            );
+
+        let menu_content = div()
+            .min_w_0()
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap_1()
+            .child(
+                div()
+                    .min_w_0()
+                    .max_w(px(120.0))
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .text_ellipsis()
+                    .text_color(status_bar_tokens.text_secondary)
+                    .child(name.to_string()),
+            )
+            .child(
+                svg()
+                    .path("icons/chevron-up.svg")
+                    .size(px(12.0))
+                    .text_color(status_bar_tokens.text_secondary)
+                    .flex_shrink_0(),
+            );
+        let menu_button = Button::new("statusbar-run-configurations", "")
+            .variant(ButtonVariant::Ghost)
+            .size(ButtonSize::ExtraSmall)
+            .content(menu_content)
+            .tooltip("Choose Run Configuration")
+            .aria_label("Choose run configuration")
+            .focus_handle(self.statusbar_focus.run_menu.clone())
+            .activate_on_mouse_down()
+            .on_click(
+                cx.listener(|this: &mut Workspace, _: &gpui::ClickEvent, _window, cx| {
+                    this.show_run_configurations_picker(cx);
+                }),
+            );
+
+        div()
+            .flex_none()
+            .h_full()
+            .flex()
+            .flex_row()
+            .items_center()
+            .child(run_button)
+            .child(menu_button)
+            .into_any_element()
+    }
+
+    fn statusbar_mode_item(
+        &self,
+        model: &StatusBarModel,
+        geometry: StatusBarGeometry,
+        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
+        border_radius: Pixels,
+    ) -> gpui::AnyElement {
+        let (mode_color, mode_text) = match model.mode {
+            helix_view::document::Mode::Normal => (
+                status_bar_tokens.mode_normal,
+                status_bar_tokens.mode_normal_text,
+            ),
+            helix_view::document::Mode::Insert => (
+                status_bar_tokens.mode_insert,
+                status_bar_tokens.mode_insert_text,
+            ),
+            helix_view::document::Mode::Select => (
+                status_bar_tokens.mode_select,
+                status_bar_tokens.mode_select_text,
+            ),
+        };
+        div()
+            .flex_none()
+            .w(geometry.mode_width)
+            .h_full()
+            .flex()
+            .items_center()
+            .justify_center()
+            .child(
+                div()
+                    .min_w(px(60.0))
+                    .h(px(22.0))
+                    .px_2()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .rounded(border_radius)
+                    .bg(mode_color)
+                    .text_color(mode_text)
+                    .font_weight(FontWeight::MEDIUM)
+                    .child(model.mode_label),
+            )
+            .into_any_element()
+    }
+
+    fn statusbar_lsp_item(
+        &self,
+        summary: Option<&LspStatusSummary>,
+        density: StatusBarDensity,
+        geometry: StatusBarGeometry,
+        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
+        cx: &mut Context<Self>,
+    ) -> gpui::AnyElement {
+        let unavailable = LspStatusSummary {
+            server_id: None,
+            server_name: "LSP:".to_string(),
+            kind: LspStatusKind::Unavailable,
+            title: "No servers".to_string(),
+            message: None,
+            percentage: None,
+            server_count: 0,
+        };
+        let summary = summary.unwrap_or(&unavailable);
+        let full_text = summary.full_text();
+        let accessible_label = format!("Language server status: {full_text}");
+        let server_width = match density {
+            StatusBarDensity::Wide => px(88.0),
+            StatusBarDensity::Medium => px(62.0),
+            StatusBarDensity::Compact => px(0.0),
+        };
+        let server_name = match density {
+            StatusBarDensity::Wide => summary.server_name.clone(),
+            StatusBarDensity::Medium => short_lsp_server_name(&summary.server_name),
+            StatusBarDensity::Compact => String::new(),
+        };
+        let activity = summary
+            .message
+            .as_deref()
+            .filter(|message| !message.is_empty())
+            .unwrap_or(&summary.title)
+            .to_string();
+        let icon_color = match summary.kind {
+            LspStatusKind::Failed => cx.theme().tokens.notification_tokens().error_text,
+            LspStatusKind::Working | LspStatusKind::Starting => status_bar_tokens.text_accent,
+            LspStatusKind::Idle => status_bar_tokens.text_primary,
+            LspStatusKind::Unavailable => status_bar_tokens.text_secondary,
+        };
+
+        let state_icon = match summary.kind {
+            LspStatusKind::Working | LspStatusKind::Starting => {
+                IndeterminateProgressIndicator::new("statusbar-lsp-spinner")
+                    .size(13.0)
+                    .text_color(icon_color)
+                    .into_any_element()
+            }
+            LspStatusKind::Failed => svg()
+                .path("icons/circle-x.svg")
+                .size(px(13.0))
+                .text_color(icon_color)
+                .into_any_element(),
+            LspStatusKind::Idle => div().text_color(icon_color).child("●").into_any_element(),
+            LspStatusKind::Unavailable => {
+                div().text_color(icon_color).child("○").into_any_element()
+            }
+        };
+
+        let mut content = div()
+            .w_full()
+            .min_w_0()
+            .flex()
             .flex_row()
             .items_center()
             .gap_1()
@@ -3816,967 +4944,3285 @@ impl Workspace {
             .child(
                 div()
                     .flex_none()
-                    .w(px(14.0))
+                    .w(px(14.0))
+                    .flex()
+                    .justify_center()
+                    .child(state_icon),
+            );
+
+        if density != StatusBarDensity::Compact {
+            let percentage = summary
+                .percentage
+                .map(|percentage| format!("{percentage:>3}%"))
+                .unwrap_or_default();
+            content = content
+                .child(
+                    div()
+                        .flex_none()
+                        .w(server_width)
+                        .overflow_hidden()
+                        .whitespace_nowrap()
+                        .text_ellipsis()
+                        .font_weight(FontWeight::MEDIUM)
+                        .child(server_name),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .min_w_0()
+                        .overflow_hidden()
+                        .whitespace_nowrap()
+                        .text_ellipsis()
+                        .text_color(status_bar_tokens.text_secondary)
+                        .child(activity),
+                )
+                .child(
+                    div()
+                        .flex_none()
+                        .w(px(34.0))
+                        .flex()
+                        .justify_end()
+                        .text_color(status_bar_tokens.text_secondary)
+                        .child(percentage),
+                );
+        }
+
+        if density != StatusBarDensity::Compact {
+            content = content.child(
+                svg()
+                    .path("icons/chevron-up.svg")
+                    .size(px(12.0))
+                    .text_color(status_bar_tokens.text_secondary)
+                    .flex_shrink_0(),
+            );
+        }
+
+        Button::new("lsp-status-trigger", "")
+            .variant(ButtonVariant::Ghost)
+            .size(ButtonSize::ExtraSmall)
+            .width(geometry.lsp_width)
+            .content(content)
+            .tooltip(full_text)
+            .aria_label(accessible_label)
+            .focus_handle(self.statusbar_focus.lsp.clone())
+            .activate_on_mouse_down()
+            .on_click(
+                cx.listener(|this: &mut Workspace, ev: &gpui::ClickEvent, _window, cx| {
+                    let position = ev.position();
+                    this.context_menus
+                        .open_lsp((f32::from(position.x), f32::from(position.y)));
+                    cx.notify();
+                }),
+            )
+            .into_any_element()
+    }
+
+    /// Build the editor-aligned content lane for the unified status bar.
+    /// Adds the mode, message and context segments to `bar`. Density decides
+    /// which items exist at all; segment priorities decide which of those
+    /// give way when the window is still too narrow for them.
+    fn statusbar_segments(
+        &self,
+        bar: StatusBar,
+        model: &StatusBarModel,
+        geometry: StatusBarGeometry,
+        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
+        button_border_radius: Pixels,
+        cx: &mut Context<Self>,
+    ) -> StatusBar {
+        let mut bar = bar
+            .segment(
+                StatusBarSegment::new(
+                    geometry.mode_width,
+                    self.statusbar_mode_item(
+                        model,
+                        geometry,
+                        status_bar_tokens,
+                        button_border_radius,
+                    ),
+                )
+                .priority(u8::MAX),
+            )
+            .segment(
+                StatusBarSegment::new(
+                    px(0.0),
+                    div()
+                        .flex_1()
+                        .min_w_0()
+                        .px_3()
+                        .child(self.statusbar_message_slot(
+                            model.file_name.clone(),
+                            model.notification.clone(),
+                            model.background_activity.clone(),
+                            status_bar_tokens,
+                            cx,
+                        )),
+                )
+                .align(StatusBarAlign::Center)
+                .priority(u8::MAX),
+            );
+        let context = |width: Pixels, priority: u8, element: gpui::AnyElement| {
+            StatusBarSegment::new(width, element)
+                .align(StatusBarAlign::Right)
+                .priority(priority)
+        };
+
+        if model.density != StatusBarDensity::Compact {
+            bar = bar.segment(context(
+                geometry.position_width,
+                STATUSBAR_PRIORITY_POSITION,
+                div()
+                    .flex_none()
+                    .w(geometry.position_width)
+                    .h_full()
+                    .px_2()
                     .flex()
-                    .justify_center()
-                    .child(state_icon),
+                    .items_center()
+                    .justify_end()
+                    .whitespace_nowrap()
+                    .text_color(status_bar_tokens.text_secondary)
+                    .child(model.position_text.clone())
+                    .into_any_element(),
+            ));
+        }
+
+        if model.density == StatusBarDensity::Wide
+            && let Some(metadata) = model.document_metadata.as_ref()
+        {
+            if statusbar_show_secondary_document_metadata(model.density, model.vcs_ref.as_deref()) {
+                bar = bar
+                    .segment(context(
+                        STATUSBAR_TEXT_ITEM_WIDTH,
+                        STATUSBAR_PRIORITY_FILE_FORMAT,
+                        self.statusbar_text_item(metadata.encoding.clone(), status_bar_tokens),
+                    ))
+                    .segment(context(
+                        STATUSBAR_TEXT_ITEM_WIDTH,
+                        STATUSBAR_PRIORITY_FILE_FORMAT,
+                        self.statusbar_text_item(metadata.line_ending, status_bar_tokens),
+                    ));
+            }
+            bar = bar
+                .segment(context(
+                    STATUSBAR_TEXT_ITEM_WIDTH,
+                    STATUSBAR_PRIORITY_INDENTATION,
+                    self.statusbar_indentation_item(&metadata.indentation, status_bar_tokens, cx),
+                ))
+                .segment(context(
+                    STATUSBAR_TEXT_ITEM_WIDTH,
+                    STATUSBAR_PRIORITY_LANGUAGE,
+                    self.statusbar_text_item(metadata.language.clone(), status_bar_tokens),
+                ));
+        }
+
+        if model.density != StatusBarDensity::Compact
+            && let Some(name) = model.run_configuration.as_deref()
+        {
+            bar = bar.segment(context(
+                STATUSBAR_TEXT_ITEM_WIDTH,
+                STATUSBAR_PRIORITY_RUN,
+                self.statusbar_run_item(name, status_bar_tokens, cx),
+            ));
+        }
+
+        bar = bar.segment(context(
+            geometry.lsp_width,
+            STATUSBAR_PRIORITY_LSP,
+            self.statusbar_lsp_item(
+                model.lsp.as_ref(),
+                model.density,
+                geometry,
+                status_bar_tokens,
+                cx,
+            ),
+        ));
+
+        if model.density == StatusBarDensity::Wide {
+            if let Some(vcs_ref) = model.vcs_ref.as_ref() {
+                bar = bar.segment(context(
+                    STATUSBAR_TEXT_ITEM_WIDTH,
+                    STATUSBAR_PRIORITY_VCS,
+                    self.statusbar_vcs_item(vcs_ref, status_bar_tokens),
+                ));
+            }
+            if let Some(metadata) = model.document_metadata.as_ref() {
+                bar = bar.segment(context(
+                    STATUSBAR_TEXT_ITEM_WIDTH,
+                    STATUSBAR_PRIORITY_DIAGNOSTICS,
+                    self.statusbar_diagnostics_item(metadata, status_bar_tokens, cx),
+                ));
+            }
+        }
+
+        bar
+    }
+
+    fn statusbar_message_slot(
+        &self,
+        file_name: String,
+        notification: Option<StatusBarNotification>,
+        background_activity: Option<String>,
+        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
+        cx: &mut Context<Self>,
+    ) -> gpui::AnyElement {
+        if let Some(activity_message) = background_activity {
+            let notification_tokens = cx.theme().tokens.notification_tokens();
+            let message =
+                shorten_statusbar_text(&activity_message, STATUSBAR_BACKGROUND_ACTIVITY_MAX_CHARS);
+
+            return gpui::div()
+                .flex()
+                .flex_1()
+                .min_w_0()
+                .items_center()
+                .gap_2()
+                .overflow_hidden()
+                .child(
+                    IndeterminateProgressIndicator::new("statusbar-background-activity")
+                        .size(13.0)
+                        .text_color(notification_tokens.info_text),
+                )
+                .child(
+                    gpui::div()
+                        .flex_1()
+                        .min_w_0()
+                        .overflow_hidden()
+                        .whitespace_nowrap()
+                        .text_ellipsis()
+                        .text_color(status_bar_tokens.text_primary)
+                        .child(message),
+                )
+                .into_any_element();
+        }
+
+        let Some(notification) = notification else {
+            return gpui::div()
+                .flex_1()
+                .min_w_0()
+                .overflow_hidden()
+                .whitespace_nowrap()
+                .text_ellipsis()
+                .child(file_name)
+                .into_any_element();
+        };
+
+        let notification_tokens = cx.theme().tokens.notification_tokens();
+        let label_color = match notification.severity {
+            StatusBarNotificationSeverity::Info => notification_tokens.info_text,
+            StatusBarNotificationSeverity::Success => notification_tokens.success_text,
+            StatusBarNotificationSeverity::Warning => notification_tokens.warning_text,
+            StatusBarNotificationSeverity::Error => notification_tokens.error_text,
+        };
+        let status_icon = statusbar_notification_icon(notification.severity);
+        let message = shorten_statusbar_text(
+            &notification.message,
+            STATUSBAR_NOTIFICATION_MESSAGE_MAX_CHARS,
+        );
+
+        gpui::div()
+            .flex()
+            .flex_1()
+            .min_w_0()
+            .items_center()
+            .gap_2()
+            .overflow_hidden()
+            .child(
+                svg()
+                    .path(status_icon)
+                    .size(px(14.0))
+                    .flex_shrink_0()
+                    .text_color(label_color),
+            )
+            .child(
+                gpui::div()
+                    .flex_1()
+                    .min_w_0()
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .text_ellipsis()
+                    .text_color(status_bar_tokens.text_primary)
+                    .child(message),
+            )
+            .into_any_element()
+    }
+
+    /// Ensure document is in the order list, adding it to the end if new
+    fn ensure_document_in_order(&mut self, doc_id: helix_view::DocumentId) {
+        if !self.document_order.contains(&doc_id) {
+            self.document_order.push(doc_id);
+        }
+    }
+
+    fn invalidate_tab_bar_documents(&mut self) {
+        self.tab_bar_document_generation = self.tab_bar_document_generation.wrapping_add(1);
+    }
+
+    /// Patch the cached tab of `doc_id` with its current diagnostic badge
+    /// rather than rebuilding every tab.
+    fn refresh_tab_diagnostic_badge(&mut self, doc_id: DocumentId, cx: &Context<Self>) {
+        let Some(cache) = &self.tab_bar_document_cache else {
+            return;
+        };
+        let Some(doc) = self.core.read(cx).editor.document(doc_id) else {
+            self.invalidate_tab_bar_documents();
+            return;
+        };
+        let (severity, count) = tab_document_diagnostic_badge(doc, &cache.key);
+        let patched = patch_tab_bar_documents(&cache.documents, |document| {
+            if document.id != TabId::Document(doc_id)
+                || (document.diagnostic_severity, document.diagnostic_count) == (severity, count)
+            {
+                return false;
+            }
+            document.diagnostic_severity = severity;
+            document.diagnostic_count = count;
+            true
+        });
+
+        if let Some(documents) = patched
+            && let Some(cache) = self.tab_bar_document_cache.as_mut()
+        {
+            cache.documents = documents;
+        }
+    }
+
+    /// Patch the VCS status of cached tabs from the VCS service cache rather
+    /// than rebuilding every tab.
+    fn refresh_tab_vcs_badges(&mut self, cx: &Context<Self>) {
+        let Some(cache) = &self.tab_bar_document_cache else {
+            return;
+        };
+        if !cache.key.show_git_status {
+            return;
+        }
+        let vcs = cx.global::<VcsServiceHandle>();
+        let patched = patch_tab_bar_documents(&cache.documents, |document| {
+            let status = document
+                .path
+                .as_deref()
+                .and_then(|path| vcs.get_status_cached(path, cx));
+            if document.git_status == status {
+                return false;
+            }
+            document.git_status = status;
+            true
+        });
+
+        if let Some(documents) = patched
+            && let Some(cache) = self.tab_bar_document_cache.as_mut()
+        {
+            cache.documents = documents;
+        }
+    }
+
+    fn invalidate_tab_bar_document_if_presentation_changed(
+        &mut self,
+        doc_id: DocumentId,
+        cx: &Context<Self>,
+    ) {
+        let Some(cache) = &self.tab_bar_document_cache else {
+            self.invalidate_tab_bar_documents();
+            return;
+        };
+        let Some(cached) = cache
+            .documents
+            .iter()
+            .find(|document| document.id == TabId::Document(doc_id))
+        else {
+            self.invalidate_tab_bar_documents();
+            return;
+        };
+        let current_preview = cache.key.show_preview_tabs
+            && cx
+                .try_global::<nucleotide_core::preview_tracker::PreviewTracker>()
+                .is_some_and(|tracker| tracker.is_preview_doc(doc_id));
+        let presentation_changed =
+            self.core
+                .read(cx)
+                .editor
+                .document(doc_id)
+                .is_none_or(|document| {
+                    cached.is_modified != document.is_modified()
+                        || cached.is_readonly != document.readonly
+                        || cached.path.as_deref() != document.path()
+                        || cached.focused_at != document.focused_at
+                        || cached.is_preview != current_preview
+                });
+
+        if presentation_changed {
+            self.invalidate_tab_bar_documents();
+        }
+    }
+
+    fn image_tab_mut(&mut self, image_id: u64) -> Option<&mut ImageTab> {
+        self.image_tabs.iter_mut().find(|tab| tab.id == image_id)
+    }
+
+    fn next_image_tab_id(&mut self) -> u64 {
+        let id = self.next_image_tab_index;
+        self.next_image_tab_index = self.next_image_tab_index.saturating_add(1);
+        id
+    }
+
+    fn open_image_file_internal(
+        &mut self,
+        path: &std::path::Path,
+        should_focus: bool,
+        cx: &mut Context<Self>,
+    ) {
+        self.open_image_file_with_render_path(path, path, should_focus, cx)
+    }
+
+    fn open_image_file_with_render_path(
+        &mut self,
+        path: &std::path::Path,
+        render_path: &std::path::Path,
+        should_focus: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let path = path.to_path_buf();
+        if let Some(tab) = self
+            .image_tabs
+            .iter_mut()
+            .find(|tab| tab.path == path && matches!(tab.view, FileTabView::Image))
+        {
+            tab.focused_at = std::time::Instant::now();
+            self.active_image_tab_id = Some(tab.id);
+            self.tab_mru.record(TabId::Image(tab.id));
+        } else {
+            let render_path = render_path.to_path_buf();
+            let scroll_handle = ScrollHandle::new();
+            let vertical_scrollbar_state = ScrollbarState::new(scroll_handle.clone());
+            let horizontal_scrollbar_state = ScrollbarState::new(scroll_handle.clone());
+            let tab = ImageTab {
+                id: self.next_image_tab_id(),
+                path: path.clone(),
+                dimensions: image_file_dimensions(&render_path),
+                render_path,
+                focused_at: std::time::Instant::now(),
+                zoom: 1.0,
+                scroll_handle,
+                vertical_scrollbar_state,
+                horizontal_scrollbar_state,
+                view: FileTabView::Image,
+            };
+            self.active_image_tab_id = Some(tab.id);
+            self.tab_mru.record(TabId::Image(tab.id));
+            self.image_tabs.push(tab);
+        }
+
+        self.invalidate_tab_bar_documents();
+        self.allow_tab_bar_auto_scroll();
+
+        if let Some(file_tree) = &self.file_tree {
+            file_tree.update(cx, |tree, cx| {
+                tree.sync_selection_with_file(Some(&path), cx);
+            });
+        }
+
+        if should_focus {
+            self.needs_focus_restore = false;
+        }
+
+        cx.notify();
+    }
+
+    fn open_hex_editor(&mut self, path: &Path, should_focus: bool, cx: &mut Context<Self>) {
+        if let Some(tab) = self
+            .image_tabs
+            .iter()
+            .find(|tab| tab.path == path && tab.hex_editor().is_some())
+        {
+            let image_id = tab.id;
+            self.switch_to_image_tab(image_id, cx);
+            return;
+        }
+
+        let runtime_handle = self.handle.clone();
+        let path = path.to_path_buf();
+        cx.spawn(async move |this, cx| {
+            let result = match runtime_handle
+                .spawn(crate::hex_editor::read_hex_file(path.clone()))
+                .await
+            {
+                Ok(result) => result,
+                Err(error) => Err(anyhow::anyhow!("hex editor read task failed: {error}")),
+            };
+
+            if let Some(this) = this.upgrade() {
+                this.update(cx, |workspace, cx| match result {
+                    Ok((bytes, loaded_time)) => {
+                        workspace.add_hex_editor_tab(path, bytes, loaded_time, should_focus, cx);
+                    }
+                    Err(error) => {
+                        warn!(path = %path.display(), error = %error, "Failed to open hex editor");
+                        workspace.set_run_status(
+                            format!("Failed to open {}: {error}", path.display()),
+                            Severity::Error,
+                            cx,
+                        );
+                    }
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn add_hex_editor_tab(
+        &mut self,
+        path: PathBuf,
+        bytes: Vec<u8>,
+        loaded_time: std::time::SystemTime,
+        should_focus: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let tab_id = self.next_image_tab_id();
+        let hex_editor =
+            cx.new(|cx| crate::hex_editor::HexEditor::new(path.clone(), bytes, loaded_time, cx));
+        cx.subscribe(
+            &hex_editor,
+            move |workspace, _hex_editor, event: &crate::hex_editor::HexEditorEvent, cx| match event
+            {
+                crate::hex_editor::HexEditorEvent::Changed => {
+                    workspace.invalidate_tab_bar_documents();
+                    cx.notify();
+                }
+                crate::hex_editor::HexEditorEvent::SaveRequested => {
+                    workspace.save_hex_editor_tab(tab_id, cx);
+                }
+            },
+        )
+        .detach();
+
+        let scroll_handle = ScrollHandle::new();
+        let vertical_scrollbar_state = ScrollbarState::new(scroll_handle.clone());
+        let horizontal_scrollbar_state = ScrollbarState::new(scroll_handle.clone());
+        self.image_tabs.push(ImageTab {
+            id: tab_id,
+            path: path.clone(),
+            render_path: path.clone(),
+            dimensions: None,
+            focused_at: std::time::Instant::now(),
+            zoom: 1.0,
+            scroll_handle,
+            vertical_scrollbar_state,
+            horizontal_scrollbar_state,
+            view: FileTabView::Hex(hex_editor),
+        });
+        self.active_image_tab_id = Some(tab_id);
+        self.tab_mru.record(TabId::Image(tab_id));
+        self.invalidate_tab_bar_documents();
+        self.allow_tab_bar_auto_scroll();
+
+        if let Some(file_tree) = &self.file_tree {
+            file_tree.update(cx, |tree, cx| {
+                tree.sync_selection_with_file(Some(&path), cx);
+            });
+        }
+
+        if should_focus {
+            self.needs_focus_restore = false;
+            self.panels.request_focus(PanelKind::HexEditor);
+        }
+
+        cx.notify();
+    }
+
+    /// Write a hex tab's bytes back to its file with the configured save policy.
+    fn save_hex_editor_tab(&mut self, tab_id: u64, cx: &mut Context<Self>) {
+        let Some(hex_editor) = self
+            .image_tabs
+            .iter()
+            .find(|tab| tab.id == tab_id)
+            .and_then(|tab| tab.hex_editor().cloned())
+        else {
+            return;
+        };
+        let (path, bytes, revision, loaded_time) = {
+            let editor = hex_editor.read(cx);
+            (
+                editor.path().to_path_buf(),
+                editor.buffer().bytes().to_vec(),
+                editor.buffer().revision(),
+                editor.loaded_time(),
+            )
+        };
+        let policy = self
+            .core
+            .read(cx)
+            .config
+            .gui
+            .file_ops
+            .save
+            .policy_for(&path);
+        let runtime_handle = self.handle.clone();
+        cx.spawn(async move |this, cx| {
+            let write_path = path.clone();
+            let result = match runtime_handle
+                .spawn(async move {
+                    crate::local_save::write_local_file(
+                        &write_path,
+                        &bytes,
+                        false,
+                        loaded_time,
+                        &policy,
+                    )
+                    .await
+                    .map(|save_time| (save_time, bytes.len()))
+                })
+                .await
+            {
+                Ok(result) => result,
+                Err(error) => Err(anyhow::anyhow!("save task failed: {error}")),
+            };
+
+            if let Some(this) = this.upgrade() {
+                this.update(cx, |workspace, cx| match result {
+                    Ok((save_time, written)) => {
+                        hex_editor.update(cx, |editor, cx| {
+                            editor.mark_saved(revision, save_time, cx);
+                        });
+                        let name = path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_else(|| path.display().to_string());
+                        workspace.set_run_status(
+                            format!("'{name}' written, {written} bytes"),
+                            Severity::Info,
+                            cx,
+                        );
+                    }
+                    Err(error) => {
+                        warn!(path = %path.display(), error = %error, "Failed to save hex editor");
+                        workspace.set_run_status(
+                            format!("Failed to save {}: {error}", path.display()),
+                            Severity::Error,
+                            cx,
+                        );
+                    }
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn switch_to_image_tab(&mut self, image_id: u64, cx: &mut Context<Self>) {
+        if let Some(tab) = self.image_tab_mut(image_id) {
+            tab.focused_at = std::time::Instant::now();
+            let is_hex = tab.hex_editor().is_some();
+            self.active_image_tab_id = Some(image_id);
+            self.tab_mru.record(TabId::Image(image_id));
+            if is_hex {
+                self.panels.request_focus(PanelKind::HexEditor);
+            } else {
+                self.panels.cancel_focus(PanelKind::HexEditor);
+            }
+            self.invalidate_tab_bar_documents();
+            self.allow_tab_bar_auto_scroll();
+            cx.notify();
+        }
+    }
+
+    fn set_image_tab_zoom(&mut self, image_id: u64, zoom: f32, cx: &mut Context<Self>) {
+        if let Some(tab) = self.image_tab_mut(image_id) {
+            tab.zoom = zoom.clamp(IMAGE_ZOOM_MIN, IMAGE_ZOOM_MAX);
+            cx.notify();
+        }
+    }
+
+    fn visible_tab_document_ids(&self, cx: &mut Context<Self>) -> Vec<TabId> {
+        let core = self.core.read(cx);
+        let editor = &core.editor;
+
+        let mut visible_doc_ids = self
+            .document_order
+            .iter()
+            .copied()
+            .filter(|doc_id| editor.documents.contains_key(doc_id))
+            .map(TabId::Document)
+            .collect::<Vec<_>>();
+        visible_doc_ids.extend(self.image_tabs.iter().map(|tab| TabId::Image(tab.id)));
+
+        zed_style_tab_order(&visible_doc_ids, &self.pinned_documents)
+    }
+
+    fn tab_activation_documents(
+        &self,
+        cx: &mut Context<Self>,
+    ) -> Vec<TabActivationDocument<TabId>> {
+        let visible_doc_ids = self.visible_tab_document_ids(cx);
+        let core = self.core.read(cx);
+
+        visible_doc_ids
+            .into_iter()
+            .filter_map(|tab_id| {
+                if let TabId::Image(image_id) = tab_id
+                    && let Some(tab) = self.image_tabs.iter().find(|tab| tab.id == image_id)
+                {
+                    return Some(TabActivationDocument {
+                        id: tab_id,
+                        focused_at: tab.focused_at,
+                    });
+                }
+
+                let TabId::Document(doc_id) = tab_id else {
+                    return None;
+                };
+                let doc = core.editor.documents.get(&doc_id)?;
+                Some(TabActivationDocument {
+                    id: tab_id,
+                    focused_at: doc.focused_at,
+                })
+            })
+            .collect()
+    }
+
+    fn close_tab_documents(
+        &mut self,
+        doc_ids: impl IntoIterator<Item = DocumentId>,
+        cx: &mut Context<Self>,
+    ) {
+        self.close_tab_documents_with_force(doc_ids, false, cx);
+    }
+
+    fn close_tab_documents_with_force(
+        &mut self,
+        doc_ids: impl IntoIterator<Item = DocumentId>,
+        force: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let doc_ids = doc_ids.into_iter().collect::<Vec<_>>();
+        if doc_ids.is_empty() {
+            return;
+        }
+
+        let handle = self.handle.clone();
+        let (closed_doc_ids, close_statuses, modified_doc_ids, modified_names) =
+            self.core.update(cx, |core, cx| {
+                let _guard = handle.enter();
+                let mut closed_doc_ids = Vec::new();
+                let mut close_statuses = Vec::new();
+                let mut modified_doc_ids = Vec::new();
+                let mut modified_names = Vec::new();
+                let active_doc_id = core
+                    .editor
+                    .tree
+                    .try_get(core.editor.tree.focus)
+                    .map(|view| view.doc);
+                let close_targets = doc_ids
+                    .into_iter()
+                    .map(|doc_id| {
+                        let path = core
+                            .editor
+                            .documents
+                            .get(&doc_id)
+                            .and_then(|doc| doc.path().map(std::path::Path::to_path_buf));
+
+                        BatchCloseDocument {
+                            id: doc_id,
+                            is_active: active_doc_id == Some(doc_id),
+                            path,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let doc_ids = batch_close_document_order(&close_targets);
+
+                for doc_id in doc_ids {
+                    match core.editor.close_document(doc_id, force) {
+                        Ok(()) => {
+                            closed_doc_ids.push(doc_id);
+                        }
+                        Err(helix_view::editor::CloseError::BufferModified(name)) => {
+                            info!("Cannot close document {:?}: has unsaved changes", doc_id);
+                            if force {
+                                close_statuses.push(unsaved_buffers_remaining_status(vec![name]));
+                            } else {
+                                modified_doc_ids.push(doc_id);
+                                modified_names.push(name);
+                            }
+                        }
+                        Err(error @ helix_view::editor::CloseError::DoesNotExist) => {
+                            info!("Document {:?} does not exist", doc_id);
+                            close_statuses.push(close_error_status(error));
+                        }
+                        Err(error @ helix_view::editor::CloseError::SaveError(_)) => {
+                            info!("Failed to close document {:?}", doc_id);
+                            close_statuses.push(close_error_status(error));
+                        }
+                    }
+                }
+
+                if !closed_doc_ids.is_empty() {
+                    cx.emit(crate::Update::Redraw);
+                    cx.notify();
+                }
+
+                (
+                    closed_doc_ids,
+                    close_statuses,
+                    modified_doc_ids,
+                    modified_names,
+                )
+            });
+
+        for status in close_statuses {
+            self.push_editor_status_notification(status, cx);
+        }
+
+        if !modified_doc_ids.is_empty() {
+            self.request_unsaved_close(
+                PendingUnsavedClose::Batch {
+                    doc_ids: modified_doc_ids,
+                },
+                modified_names,
+                cx,
+            );
+        }
+
+        if !closed_doc_ids.is_empty() {
+            for doc_id in &closed_doc_ids {
+                self.loading_documents.remove(doc_id);
+            }
+            self.unregister_preview_documents(closed_doc_ids.iter().copied(), cx);
+            self.update_document_views(cx);
+            cx.notify();
+        }
+    }
+
+    fn close_tab_ids(&mut self, tab_ids: impl IntoIterator<Item = TabId>, cx: &mut Context<Self>) {
+        let mut document_ids = Vec::new();
+        for tab_id in tab_ids {
+            match tab_id {
+                TabId::Document(doc_id) => document_ids.push(doc_id),
+                TabId::Image(image_id) => self.close_image_tab(image_id, None, cx),
+            }
+        }
+
+        self.close_tab_documents(document_ids, cx);
+    }
+
+    fn close_single_tab_document(
+        &mut self,
+        doc_id: DocumentId,
+        active_doc_id: Option<TabId>,
+        activation_documents: &[TabActivationDocument<TabId>],
+        activate_on_close: crate::config::TabActivateOnClose,
+        cx: &mut Context<Self>,
+    ) {
+        let activation_target = tab_activation_target_after_close(
+            activation_documents,
+            TabId::Document(doc_id),
+            active_doc_id,
+            activate_on_close,
+        );
+        self.close_single_tab_document_with_activation_target(doc_id, activation_target, false, cx);
+    }
+
+    fn close_image_tab(
+        &mut self,
+        image_id: u64,
+        activation_target: Option<TabId>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(index) = self.image_tabs.iter().position(|tab| tab.id == image_id) else {
+            return;
+        };
+
+        if let Some(hex_editor) = self.image_tabs[index].hex_editor().cloned()
+            && !hex_editor.update(cx, |editor, _| editor.confirm_close())
+        {
+            self.set_run_status(
+                "Unsaved hex edits; save them or close the tab again to discard them",
+                Severity::Warning,
+                cx,
+            );
+            return;
+        }
+
+        self.image_tabs.remove(index);
+        self.pinned_documents.remove(&TabId::Image(image_id));
+        self.forget_tab_mru(TabId::Image(image_id));
+        self.invalidate_tab_bar_documents();
+
+        if self.active_image_tab_id == Some(image_id) {
+            self.active_image_tab_id = None;
+            if let Some(target_id) = activation_target {
+                match target_id {
+                    TabId::Image(image_id) => self.switch_to_image_tab(image_id, cx),
+                    TabId::Document(doc_id) => self.switch_to_tab_document(doc_id, cx),
+                }
+            }
+        }
+
+        cx.notify();
+    }
+
+    fn close_single_tab_document_with_activation_target(
+        &mut self,
+        doc_id: DocumentId,
+        activation_target: Option<TabId>,
+        force: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let handle = self.handle.clone();
+        let (closed, close_status, modified_name) = self.core.update(cx, |core, cx| {
+            let _guard = handle.enter();
+
+            match core.editor.close_document(doc_id, force) {
+                Ok(()) => {
+                    if let Some(TabId::Document(target_doc_id)) = activation_target
+                        && core.editor.documents.contains_key(&target_doc_id)
+                    {
+                        core.editor
+                            .switch(target_doc_id, helix_view::editor::Action::Replace);
+                    }
+                    cx.emit(crate::Update::Redraw);
+                    cx.notify();
+                    (true, None, None)
+                }
+                Err(helix_view::editor::CloseError::BufferModified(name)) => {
+                    info!("Cannot close document {:?}: has unsaved changes", doc_id);
+                    if force {
+                        (
+                            false,
+                            Some(unsaved_buffers_remaining_status(vec![name])),
+                            None,
+                        )
+                    } else {
+                        (false, None, Some(name))
+                    }
+                }
+                Err(error @ helix_view::editor::CloseError::DoesNotExist) => {
+                    info!("Document {:?} does not exist", doc_id);
+                    (false, Some(close_error_status(error)), None)
+                }
+                Err(error @ helix_view::editor::CloseError::SaveError(_)) => {
+                    info!("Failed to close document {:?}", doc_id);
+                    (false, Some(close_error_status(error)), None)
+                }
+            }
+        });
+
+        if let Some(status) = close_status {
+            self.push_editor_status_notification(status, cx);
+        }
+
+        if let Some(name) = modified_name {
+            self.request_unsaved_close(
+                PendingUnsavedClose::Single {
+                    doc_id,
+                    activation_target: match activation_target {
+                        Some(TabId::Document(doc_id)) => Some(doc_id),
+                        Some(TabId::Image(_)) | None => None,
+                    },
+                },
+                vec![name],
+                cx,
+            );
+            return;
+        }
+
+        if closed {
+            self.loading_documents.remove(&doc_id);
+            if activation_target.is_some() {
+                self.allow_tab_bar_auto_scroll();
+            }
+            if let Some(TabId::Image(image_id)) = activation_target {
+                self.switch_to_image_tab(image_id, cx);
+            }
+            self.unregister_preview_document(doc_id, cx);
+            self.update_document_views(cx);
+            cx.notify();
+        }
+    }
+
+    fn force_close_single_tab_document(
+        &mut self,
+        doc_id: DocumentId,
+        activation_target: Option<DocumentId>,
+        cx: &mut Context<Self>,
+    ) {
+        self.close_single_tab_document_with_activation_target(
+            doc_id,
+            activation_target.map(TabId::Document),
+            true,
+            cx,
+        );
+    }
+
+    fn force_close_tab_documents(
+        &mut self,
+        doc_ids: impl IntoIterator<Item = DocumentId>,
+        cx: &mut Context<Self>,
+    ) {
+        self.close_tab_documents_with_force(doc_ids, true, cx);
+    }
+
+    fn unregister_preview_document(&mut self, doc_id: DocumentId, cx: &mut Context<Self>) {
+        if let Some(tracker) = cx.try_global::<nucleotide_core::preview_tracker::PreviewTracker>() {
+            tracker.unregister_doc(doc_id);
+            self.invalidate_tab_bar_documents();
+        }
+    }
+
+    fn unregister_preview_documents(
+        &mut self,
+        doc_ids: impl IntoIterator<Item = DocumentId>,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(tracker) = cx.try_global::<nucleotide_core::preview_tracker::PreviewTracker>() {
+            for doc_id in doc_ids {
+                tracker.unregister_doc(doc_id);
+            }
+            self.invalidate_tab_bar_documents();
+        }
+    }
+
+    fn clear_preview_documents(&mut self, cx: &mut Context<Self>) {
+        if let Some(tracker) = cx.try_global::<nucleotide_core::preview_tracker::PreviewTracker>() {
+            tracker.clear();
+            self.invalidate_tab_bar_documents();
+        }
+    }
+
+    fn allow_tab_bar_auto_scroll(&mut self) {
+        self.suppress_tab_bar_auto_scroll = false;
+    }
+
+    fn active_document_and_view(&self, cx: &mut Context<Self>) -> Option<(DocumentId, ViewId)> {
+        let core = self.core.read(cx);
+        let view_id = self
+            .view_manager
+            .focused_view_id()
+            .filter(|view_id| core.editor.tree.contains(*view_id))
+            .unwrap_or(core.editor.tree.focus);
+        let doc_id = core.editor.tree.try_get(view_id)?.doc;
+        Some((doc_id, view_id))
+    }
+
+    fn active_tab_doc_id(&self, cx: &mut Context<Self>) -> Option<TabId> {
+        self.active_image_tab_id.map(TabId::Image).or_else(|| {
+            self.active_document_and_view(cx)
+                .map(|(doc_id, _)| TabId::Document(doc_id))
+        })
+    }
+
+    fn switch_to_tab_document(&mut self, doc_id: DocumentId, cx: &mut Context<Self>) {
+        self.allow_tab_bar_auto_scroll();
+        self.active_image_tab_id = None;
+        self.tab_mru.record(TabId::Document(doc_id));
+        self.invalidate_tab_bar_documents();
+        let handle = self.handle.clone();
+        self.core.update(cx, |core, cx| {
+            let _guard = handle.enter();
+            core.editor
+                .switch(doc_id, helix_view::editor::Action::Replace);
+            cx.emit(crate::Update::Redraw);
+            cx.notify();
+        });
+
+        self.update_document_views(cx);
+        cx.notify();
+    }
+
+    fn activate_tab(&mut self, tab_id: TabId, cx: &mut Context<Self>) {
+        self.context_menus.close_tab_bar_menus(cx);
+        match tab_id {
+            TabId::Image(image_id) => self.switch_to_image_tab(image_id, cx),
+            TabId::Document(doc_id) => self.switch_to_tab_document(doc_id, cx),
+        }
+    }
+
+    fn activate_adjacent_tab(&mut self, direction: TabCycleDirection, cx: &mut Context<Self>) {
+        let ordered_tabs = self.visible_tab_document_ids(cx);
+        let active_tab = self.active_tab_doc_id(cx);
+        if let Some(target) = adjacent_tab(&ordered_tabs, active_tab, direction) {
+            self.tab_mru_cycle.reset();
+            self.activate_tab(target, cx);
+        }
+    }
+
+    /// Open tabs ordered most recently used first.
+    fn mru_tab_order(&self, cx: &mut Context<Self>) -> Vec<TabId> {
+        let visible_tabs = self.visible_tab_document_ids(cx);
+        self.tab_mru.order_of(&visible_tabs)
+    }
+
+    /// Drops a closed tab from the MRU history and the open switcher.
+    fn forget_tab_mru(&mut self, tab_id: TabId) {
+        self.tab_mru.remove(tab_id);
+        if self
+            .tab_switcher
+            .as_mut()
+            .is_some_and(|switcher| !switcher.remove(tab_id))
+        {
+            self.tab_switcher = None;
+        }
+    }
+
+    fn activate_last_used_tab(&mut self, cx: &mut Context<Self>) {
+        let mru_order = self.mru_tab_order(cx);
+        let active_tab = self.active_tab_doc_id(cx);
+        if let Some(target) =
+            self.tab_mru_cycle
+                .step(mru_order, active_tab, std::time::Instant::now())
+        {
+            self.activate_tab(target, cx);
+        }
+    }
+
+    /// Opens a searchable list of every open tab in strip order.
+    fn show_all_tabs(&mut self, cx: &mut Context<Self>) {
+        use crate::picker_view::PickerItem;
+
+        self.context_menus.close_tab_bar_menus(cx);
+        let ordered_tabs = self.visible_tab_document_ids(cx);
+        let active_tab = self.active_tab_doc_id(cx);
+
+        let items = {
+            let core = self.core.read(cx);
+            let project_directory = core.project_directory.as_deref();
+            let mut items = Vec::with_capacity(ordered_tabs.len());
+
+            for (index, tab_id) in ordered_tabs.into_iter().enumerate() {
+                let (path, is_modified, diagnostic_severity, data) = match tab_id {
+                    TabId::Document(doc_id) => {
+                        let Some(doc) = core.editor.documents.get(&doc_id) else {
+                            continue;
+                        };
+                        let path = doc.path().map(Path::to_path_buf);
+                        let severity = tab_overview_diagnostic_severity(
+                            doc.diagnostics()
+                                .iter()
+                                .map(|diagnostic| diagnostic.severity),
+                        );
+                        let data = Arc::new((doc_id, path.clone()))
+                            as Arc<dyn std::any::Any + Send + Sync>;
+                        (path, doc.is_modified(), severity, data)
+                    }
+                    TabId::Image(image_id) => {
+                        let Some(tab) = self.image_tabs.iter().find(|tab| tab.id == image_id)
+                        else {
+                            continue;
+                        };
+                        let data =
+                            Arc::new(tab.path.clone()) as Arc<dyn std::any::Any + Send + Sync>;
+                        (Some(tab.path.clone()), tab.is_modified(cx), None, data)
+                    }
+                };
+
+                let path_label = match (&path, project_directory) {
+                    (Some(path), Some(project_directory)) => path
+                        .strip_prefix(project_directory)
+                        .unwrap_or(path)
+                        .display()
+                        .to_string(),
+                    (Some(path), None) => path.display().to_string(),
+                    (None, _) => "[scratch]".to_string(),
+                };
+
+                items.push(PickerItem::with_buffer_columns(
+                    (index + 1).to_string(),
+                    tab_overview_flags(
+                        active_tab == Some(tab_id),
+                        is_modified,
+                        self.pinned_documents.contains(&tab_id),
+                        diagnostic_severity,
+                    ),
+                    path_label,
+                    data,
+                ));
+            }
+
+            items
+        };
+
+        let picker = crate::picker::Picker::native("Open Tabs", items, |index| {
+            debug!(index = index, "Tab selected from tab overview");
+        })
+        .with_preview(true);
+        emit_picker_update(picker, &self.overlay, cx);
+    }
+
+    /// Opens a picker over the traffic inspector logs with per-method latency
+    /// summaries followed by individual request/response exchanges.
+    /// Opens the commit log for the repository, or the history of `path`.
+    fn show_git_log(&mut self, path: Option<PathBuf>, cx: &mut Context<Self>) {
+        let Some(repository_root) = self.current_project_root.clone() else {
+            return;
+        };
+        if !matches!(
+            self.core.read(cx).workspace_backend.identity(),
+            WorkspaceIdentity::Local
+        ) {
+            self.push_editor_status_notification(
+                EditorStatus {
+                    status: "Git history is only available for local workspaces".to_string(),
+                    severity: Severity::Warning,
+                },
+                cx,
+            );
+            return;
+        }
+
+        let runtime_handle = self.handle.clone();
+        let log_root = repository_root.clone();
+        let log_path = path.clone();
+        cx.spawn(async move |this, cx| {
+            let result = runtime_handle
+                .spawn_blocking(move || run_git_log(&log_root, log_path.as_deref(), GIT_LOG_LIMIT))
+                .await;
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            this.update(cx, |workspace, cx| {
+                let commits = match result {
+                    Ok(Ok(commits)) => commits,
+                    Ok(Err(error)) => {
+                        workspace.push_editor_status_notification(
+                            EditorStatus {
+                                status: format!("Could not read git history: {error}"),
+                                severity: Severity::Error,
+                            },
+                            cx,
+                        );
+                        return;
+                    }
+                    Err(error) => {
+                        warn!(%error, "Git log task failed");
+                        return;
+                    }
+                };
+                if commits.is_empty() {
+                    workspace.push_editor_status_notification(
+                        EditorStatus {
+                            status: "No commits found".to_string(),
+                            severity: Severity::Info,
+                        },
+                        cx,
+                    );
+                    return;
+                }
+
+                let title = match &path {
+                    Some(path) => format!(
+                        "History: {}",
+                        path.strip_prefix(&repository_root)
+                            .unwrap_or(path)
+                            .display()
+                    ),
+                    None => "Git Log".to_string(),
+                };
+                let picker = commit_log_picker(
+                    title,
+                    repository_root,
+                    path,
+                    commits,
+                    workspace.handle.clone(),
+                );
+                emit_picker_update(picker, &workspace.overlay, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn show_focused_file_history(&mut self, cx: &mut Context<Self>) {
+        let path = {
+            let editor = &self.core.read(cx).editor;
+            editor
+                .tree
+                .try_get(editor.tree.focus)
+                .and_then(|view| editor.documents.get(&view.doc))
+                .and_then(|doc| doc.path().map(Path::to_path_buf))
+        };
+        match path {
+            Some(path) => self.show_git_log(Some(path), cx),
+            None => self.push_editor_status_notification(
+                EditorStatus {
+                    status: "The current buffer has no file history".to_string(),
+                    severity: Severity::Info,
+                },
+                cx,
+            ),
+        }
+    }
+
+    /// Shows `git show` output for a commit in a read-only diff buffer.
+    fn open_commit_diff(&mut self, entry: CommitLogEntry, cx: &mut Context<Self>) {
+        let runtime_handle = self.handle.clone();
+        cx.spawn(async move |this, cx| {
+            let show_entry = entry.clone();
+            let result = runtime_handle
+                .spawn_blocking(move || {
+                    run_git_show(
+                        &show_entry.repository_root,
+                        &show_entry.hash,
+                        show_entry.path.as_deref(),
+                    )
+                })
+                .await;
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            this.update(cx, |workspace, cx| {
+                let diff = match result {
+                    Ok(Ok(diff)) => diff,
+                    Ok(Err(error)) => {
+                        workspace.push_editor_status_notification(
+                            EditorStatus {
+                                status: format!("Could not show commit {}: {error}", entry.hash),
+                                severity: Severity::Error,
+                            },
+                            cx,
+                        );
+                        return;
+                    }
+                    Err(error) => {
+                        warn!(%error, "Git show task failed");
+                        return;
+                    }
+                };
+
+                workspace.core.update(cx, |core, cx| {
+                    let doc_id = core.editor.new_file(helix_view::editor::Action::Replace);
+                    let view_id = core.editor.tree.focus;
+                    let loader = core.editor.syn_loader.load();
+                    let Some(doc) = core.editor.documents.get_mut(&doc_id) else {
+                        return;
+                    };
+                    doc.ensure_view_init(view_id);
+                    let transaction = helix_core::Transaction::insert(
+                        doc.text(),
+                        doc.selection(view_id),
+                        diff.into(),
+                    )
+                    .with_selection(Selection::point(0));
+                    doc.apply(&transaction, view_id);
+                    doc.reset_modified();
+                    doc.readonly = true;
+                    if let Err(error) = doc.set_language_by_language_id("diff", &loader) {
+                        debug!(%error, "No diff language configured for commit view");
+                    }
+                    core.editor.set_status(format!(
+                        "Commit {}: {}",
+                        &entry.hash[..entry.hash.len().min(8)],
+                        entry.subject
+                    ));
+                    cx.emit(crate::Update::Redraw);
+                });
+                workspace.update_document_views(cx);
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn show_merge_conflicts(&mut self, cx: &mut Context<Self>) {
+        use crate::picker_view::PickerItem;
+
+        let core = self.core.read(cx);
+        let editor = &core.editor;
+        let Some(view) = editor.tree.try_get(editor.tree.focus) else {
+            return;
+        };
+        let Some(doc) = editor.documents.get(&view.doc) else {
+            return;
+        };
+        let doc_id = doc.id();
+        let document_version = doc.version();
+        let path = doc.path().map(Path::to_path_buf);
+        let text = doc.text().slice(..);
+        let conflicts = parse_merge_conflicts(text);
+
+        if conflicts.is_empty() {
+            // Git still reports the file as conflicted until it is staged.
+            let marked_conflicted = path.as_deref().is_some_and(|path| {
+                cx.try_global::<VcsServiceHandle>()
+                    .and_then(|vcs| vcs.get_status_cached(path, cx))
+                    == Some(nucleotide_types::VcsStatus::Conflicted)
+            });
+            let status = if marked_conflicted {
+                "No conflict markers left; stage the file to mark it resolved"
+            } else {
+                "No merge conflicts in this document"
+            };
+            self.push_editor_status_notification(
+                EditorStatus {
+                    status: status.to_string(),
+                    severity: Severity::Info,
+                },
+                cx,
             );
+            return;
+        }
 
-        if density != StatusBarDensity::Compact {
-            let percentage = summary
-                .percentage
-                .map(|percentage| format!("{percentage:>3}%"))
-                .unwrap_or_default();
-            content = content
-                .child(
-                    div()
-                        .flex_none()
-                        .w(server_width)
-                        .overflow_hidden()
-                        .whitespace_nowrap()
-                        .text_ellipsis()
-                        .font_weight(FontWeight::MEDIUM)
-                        .child(server_name),
-                )
-                .child(
-                    div()
-                        .flex_1()
-                        .min_w_0()
-                        .overflow_hidden()
-                        .whitespace_nowrap()
-                        .text_ellipsis()
-                        .text_color(status_bar_tokens.text_secondary)
-                        .child(activity),
-                )
-                .child(
-                    div()
-                        .flex_none()
-                        .w(px(34.0))
-                        .flex()
-                        .justify_end()
-                        .text_color(status_bar_tokens.text_secondary)
-                        .child(percentage),
-                );
+        let mut items = Vec::with_capacity(conflicts.len() * MergeConflictResolution::ALL.len());
+        for (index, conflict) in conflicts.iter().enumerate() {
+            let preview = merge_conflict_preview(text, conflict);
+            for resolution in MergeConflictResolution::ALL {
+                items.push(PickerItem {
+                    label: format!(
+                        "Conflict {} (line {}): {}",
+                        index + 1,
+                        conflict.start_line + 1,
+                        resolution.label()
+                    )
+                    .into(),
+                    sublabel: Some(
+                        format!("{} ↔ {}", conflict.ours_label, conflict.theirs_label).into(),
+                    ),
+                    data: Arc::new(MergeConflictChoice {
+                        doc_id,
+                        document_version,
+                        start_line: conflict.start_line,
+                        resolution,
+                        preview: preview.clone(),
+                    }),
+                    file_path: path.clone(),
+                    vcs_status: None,
+                    columns: None,
+                });
+            }
         }
 
-        if density != StatusBarDensity::Compact {
-            content = content.child(
-                svg()
-                    .path("icons/chevron-up.svg")
-                    .size(px(12.0))
-                    .text_color(status_bar_tokens.text_secondary)
-                    .flex_shrink_0(),
+        let picker = crate::picker::Picker::native("Merge Conflicts", items, |_| {
+            // Selection is handled by OverlayView via MergeConflictChoice payloads.
+        })
+        .with_preview(true);
+        emit_picker_update(picker, &self.overlay, cx);
+    }
+
+    fn navigate_merge_conflict(&mut self, direction: ConflictDirection, cx: &mut Context<Self>) {
+        let mut moved_view = None;
+        self.core.update(cx, |core, cx| {
+            let view_id = core.editor.tree.focus;
+            let Some(doc_id) = core.editor.tree.try_get(view_id).map(|view| view.doc) else {
+                return;
+            };
+            let Some(doc) = core.editor.documents.get_mut(&doc_id) else {
+                return;
+            };
+
+            let target = {
+                let text = doc.text().slice(..);
+                let cursor_line = doc.selection(view_id).primary().cursor_line(text);
+                let conflicts = parse_merge_conflicts(text);
+                adjacent_conflict(&conflicts, cursor_line, direction).map(|conflict| {
+                    let index = conflicts
+                        .iter()
+                        .position(|candidate| candidate == conflict)
+                        .unwrap_or_default();
+                    (
+                        text.line_to_char(conflict.start_line),
+                        index + 1,
+                        conflicts.len(),
+                    )
+                })
+            };
+            let Some((position, number, total)) = target else {
+                core.editor
+                    .set_status("No merge conflicts in this document");
+                return;
+            };
+
+            doc.set_selection(view_id, Selection::point(position));
+            core.editor.ensure_cursor_in_view(view_id);
+            core.editor
+                .set_status(format!("Merge conflict {number} of {total}"));
+            moved_view = Some(view_id);
+            cx.emit(crate::Update::SelectionChanged { doc_id, view_id });
+            cx.emit(crate::Update::Redraw);
+        });
+
+        if let Some(view_id) = moved_view
+            && let Some(view_entity) = self.view_manager.get_document_view(&view_id)
+        {
+            view_entity.update(cx, |view, cx| {
+                view.request_cursor_center();
+                cx.notify();
+            });
+        }
+    }
+
+    fn resolve_merge_conflict_at_cursor(
+        &mut self,
+        resolution: MergeConflictResolution,
+        cx: &mut Context<Self>,
+    ) {
+        self.core.update(cx, |core, cx| {
+            let view_id = core.editor.tree.focus;
+            let Some(doc_id) = core.editor.tree.try_get(view_id).map(|view| view.doc) else {
+                return;
+            };
+            let Some(doc) = core.editor.documents.get(&doc_id) else {
+                return;
+            };
+            let text = doc.text();
+            let cursor_line = doc.selection(view_id).primary().cursor_line(text.slice(..));
+            let conflicts = parse_merge_conflicts(text.slice(..));
+            let Some(conflict) = conflict_at_line(&conflicts, cursor_line) else {
+                core.editor.set_error("No merge conflict at the cursor");
+                return;
+            };
+
+            let transaction = merge_conflict_transaction(text, conflict, resolution);
+            let remaining = conflicts.len() - 1;
+            apply_merge_conflict_resolution(core, doc_id, view_id, &transaction, remaining);
+            cx.emit(crate::Update::Redraw);
+        });
+    }
+
+    /// Menu of indentation settings and conversions for the focused
+    /// document.
+    fn show_indentation_picker(&mut self, cx: &mut Context<Self>) {
+        use crate::picker_view::PickerItem;
+        use helix_core::indent::IndentStyle;
+
+        let current = {
+            let editor = &self.core.read(cx).editor;
+            editor
+                .tree
+                .try_get(editor.tree.focus)
+                .and_then(|view| editor.document(view.doc))
+                .map(|doc| (doc.indent_style, doc.tab_width()))
+        };
+        let Some((style, tab_width)) = current else {
+            self.core.update(cx, |core, _| {
+                core.editor
+                    .set_error("No document to change the indentation of");
+            });
+            return;
+        };
+
+        let current_label = |active: bool| active.then(|| "Current".to_string());
+        let mut rows = SPACE_WIDTHS
+            .iter()
+            .map(|&width| {
+                (
+                    format!("Indent Using Spaces: {width}"),
+                    current_label(style == IndentStyle::Spaces(width)),
+                    IndentationChoice::UseSpaces(width),
+                )
+            })
+            .collect::<Vec<_>>();
+        rows.extend([
+            (
+                format!("Indent Using Tabs (Tab Size: {tab_width})"),
+                current_label(style == IndentStyle::Tabs),
+                IndentationChoice::UseTabs,
+            ),
+            (
+                "Detect Indentation from Content".to_string(),
+                None,
+                IndentationChoice::Detect,
+            ),
+            (
+                "Convert Indentation to Spaces".to_string(),
+                Some("Rewrite the leading whitespace of every line".to_string()),
+                IndentationChoice::ConvertToSpaces,
+            ),
+            (
+                "Convert Indentation to Tabs".to_string(),
+                Some("Rewrite the leading whitespace of every line".to_string()),
+                IndentationChoice::ConvertToTabs,
+            ),
+        ]);
+
+        let items = rows
+            .into_iter()
+            .map(|(label, sublabel, choice)| PickerItem {
+                label: label.into(),
+                sublabel: sublabel.map(Into::into),
+                data: Arc::new(choice),
+                file_path: None,
+                vcs_status: None,
+                columns: None,
+            })
+            .collect::<Vec<_>>();
+        let picker = crate::picker::Picker::native("Indentation", items, |_| {});
+        emit_picker_update(picker, &self.overlay, cx);
+    }
+
+    fn handle_indentation_choice(&mut self, choice: IndentationChoice, cx: &mut Context<Self>) {
+        use helix_core::indent::IndentStyle;
+
+        self.core.update(cx, |core, cx| {
+            let view_id = core.editor.tree.focus;
+            let Some(doc_id) = core.editor.tree.try_get(view_id).map(|view| view.doc) else {
+                return;
+            };
+            let Some(doc) = core.editor.documents.get_mut(&doc_id) else {
+                return;
+            };
+            let tab_width = doc.tab_width();
+            let unit = doc.indent_width();
+
+            let target = match choice {
+                IndentationChoice::UseSpaces(width) => {
+                    doc.indent_style = IndentStyle::Spaces(width);
+                    None
+                }
+                IndentationChoice::UseTabs => {
+                    doc.indent_style = IndentStyle::Tabs;
+                    None
+                }
+                IndentationChoice::Detect => {
+                    doc.detect_indent_and_line_ending();
+                    None
+                }
+                IndentationChoice::ConvertToSpaces => match doc.indent_style {
+                    IndentStyle::Spaces(width) => Some(IndentStyle::Spaces(width)),
+                    IndentStyle::Tabs => {
+                        Some(IndentStyle::Spaces(tab_width.min(u8::MAX as usize) as u8))
+                    }
+                },
+                IndentationChoice::ConvertToTabs => Some(IndentStyle::Tabs),
+            };
+
+            if let Some(target) = target {
+                let transaction =
+                    convert_indentation_transaction(doc.text(), unit, tab_width, target);
+                doc.indent_style = target;
+                if let Some(transaction) = transaction {
+                    apply_task_edit(&mut core.editor, doc_id, view_id, &transaction);
+                }
+            }
+
+            if let Some(doc) = core.editor.documents.get(&doc_id) {
+                let label = crate::indentation::statusline_label(doc.indent_style, doc.tab_width());
+                core.editor.set_status(format!("Indentation: {label}"));
+            }
+            cx.emit(crate::Update::Redraw);
+        });
+    }
+
+    /// Reflows the paragraph or selections of the focused document at
+    /// `column`, or at the document's text width.
+    fn reflow_focused_document(&mut self, column: Option<usize>, cx: &mut Context<Self>) {
+        self.core.update(cx, |core, cx| {
+            let view_id = core.editor.tree.focus;
+            let Some(doc_id) = core.editor.tree.try_get(view_id).map(|view| view.doc) else {
+                return;
+            };
+            let Some(doc) = core.editor.documents.get(&doc_id) else {
+                return;
+            };
+            let comment_tokens = doc
+                .language_config()
+                .and_then(|config| config.comment_tokens.clone())
+                .unwrap_or_default();
+            let comment_tokens = comment_tokens
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            let transaction = crate::reflow::reflow_transaction(
+                doc.text(),
+                doc.selection(view_id),
+                column.unwrap_or_else(|| doc.text_width()),
+                doc.tab_width(),
+                &comment_tokens,
+                doc.line_ending.as_str(),
+            );
+            match transaction {
+                Some(transaction) => {
+                    apply_task_edit(&mut core.editor, doc_id, view_id, &transaction);
+                }
+                None => core.editor.set_status("Nothing to reflow"),
+            }
+            cx.emit(crate::Update::Redraw);
+        });
+    }
+
+    fn transform_selections(
+        &mut self,
+        transform: crate::text_transforms::TextTransform,
+        cx: &mut Context<Self>,
+    ) {
+        self.core.update(cx, |core, cx| {
+            let view_id = core.editor.tree.focus;
+            let Some(doc_id) = core.editor.tree.try_get(view_id).map(|view| view.doc) else {
+                return;
+            };
+            let Some(doc) = core.editor.documents.get(&doc_id) else {
+                return;
+            };
+            let transaction = crate::text_transforms::transform_transaction(
+                doc.text(),
+                doc.selection(view_id),
+                transform,
+            );
+            if let Some(transaction) = transaction {
+                apply_task_edit(&mut core.editor, doc_id, view_id, &transaction);
+                cx.emit(crate::Update::Redraw);
+            }
+        });
+    }
+
+    /// Sorts, deduplicates, reverses or shuffles the selected lines of the
+    /// focused document, or all of its lines when nothing is selected.
+    fn apply_line_operation(
+        &mut self,
+        operation: crate::line_edits::LineOperation,
+        cx: &mut Context<Self>,
+    ) {
+        use std::hash::{BuildHasher, RandomState};
+
+        self.core.update(cx, |core, cx| {
+            let view_id = core.editor.tree.focus;
+            let Some(doc_id) = core.editor.tree.try_get(view_id).map(|view| view.doc) else {
+                return;
+            };
+            let Some(doc) = core.editor.documents.get(&doc_id) else {
+                return;
+            };
+            let transaction = crate::line_edits::line_operation_transaction(
+                doc.text(),
+                doc.selection(view_id),
+                operation,
+                RandomState::new().hash_one(doc_id),
+            );
+            if let Some(transaction) = transaction {
+                apply_task_edit(&mut core.editor, doc_id, view_id, &transaction);
+                cx.emit(crate::Update::Redraw);
+            }
+        });
+    }
+
+    /// Asks for a delimiter to align the selected lines on, selecting the
+    /// delimiters that would line up while it is typed.
+    fn show_align_prompt(&mut self, cx: &mut Context<Self>) {
+        let prompt = crate::prompt::Prompt::native(crate::align::ALIGN_PROMPT, "", |_| {})
+            .with_cancel(|| {});
+        cx.emit(crate::Update::Prompt(prompt));
+    }
+
+    /// Aligns the selected lines of the focused document on `delimiter`.
+    fn align_focused_document(&mut self, delimiter: &str, cx: &mut Context<Self>) {
+        self.core.update(cx, |core, cx| {
+            let regex = match crate::align::delimiter_regex(delimiter) {
+                Ok(Some(regex)) => regex,
+                Ok(None) => return,
+                Err(error) => {
+                    core.editor.set_error(error);
+                    return;
+                }
+            };
+            let view_id = core.editor.tree.focus;
+            let Some(doc_id) = core.editor.tree.try_get(view_id).map(|view| view.doc) else {
+                return;
+            };
+            let Some(doc) = core.editor.documents.get(&doc_id) else {
+                return;
+            };
+            let transaction = crate::align::align_transaction(
+                doc.text(),
+                doc.selection(view_id),
+                &regex,
+                doc.tab_width(),
+            );
+            match transaction {
+                Some(transaction) => {
+                    apply_task_edit(&mut core.editor, doc_id, view_id, &transaction);
+                }
+                None => core.editor.set_status("Nothing to align"),
+            }
+            cx.emit(crate::Update::Redraw);
+        });
+    }
+
+    /// Inserts a doc comment skeleton above the function at the cursor of
+    /// the focused document, and enters insert mode at its first tabstop.
+    fn generate_doc_comment(&mut self, cx: &mut Context<Self>) {
+        self.core.update(cx, |core, cx| {
+            let editor = &mut core.editor;
+            let view_id = editor.tree.focus;
+            let Some(doc_id) = editor.tree.try_get(view_id).map(|view| view.doc) else {
+                return;
+            };
+            let Some(doc) = editor.documents.get_mut(&doc_id) else {
+                return;
+            };
+            let cursor = doc
+                .selection(view_id)
+                .primary()
+                .cursor(doc.text().slice(..));
+            let (insert_at, snippet_text) = match crate::doc_comments::doc_comment_at(doc, cursor) {
+                Ok(found) => found,
+                Err(error) => {
+                    editor.set_error(error);
+                    return;
+                }
+            };
+            let snippet = snippet_completion_transaction(
+                doc.text(),
+                &Selection::point(insert_at),
+                &snippet_text,
+                None,
+                false,
+                &mut doc.snippet_ctx(),
             );
-        }
+            let (transaction, rendered_snippet) = match snippet {
+                Ok(snippet) => snippet,
+                Err(error) => {
+                    editor.set_error(format!("Invalid doc comment snippet: {error}"));
+                    return;
+                }
+            };
+            // Commit pending edits first so undo removes only the comment.
+            let view = editor.tree.get_mut(view_id);
+            doc.append_changes_to_history(view);
+            doc.apply(&transaction, view_id);
+            install_active_completion_snippet(doc, rendered_snippet);
+            editor.mode = helix_view::document::Mode::Insert;
+            cx.emit(crate::Update::Redraw);
+        });
+    }
 
-        Button::new("lsp-status-trigger", "")
-            .variant(ButtonVariant::Ghost)
-            .size(ButtonSize::ExtraSmall)
-            .width(geometry.lsp_width)
-            .content(content)
-            .tooltip(full_text)
-            .aria_label(accessible_label)
-            .focus_handle(self.statusbar_lsp_focus.clone())
-            .activate_on_mouse_down()
-            .on_click(
-                cx.listener(|this: &mut Workspace, ev: &gpui::ClickEvent, _window, cx| {
-                    this.lsp_menu_open = true;
-                    let position = ev.position();
-                    this.lsp_menu_pos = (f32::from(position.x), f32::from(position.y));
-                    cx.notify();
-                }),
-            )
-            .into_any_element()
+    /// Creates the scratch buffer `new.name` in `new.language`, or switches
+    /// to it when one of that name exists.
+    fn new_scratch_buffer(&mut self, new: NewScratch, cx: &mut Context<Self>) {
+        self.prune_scratch_buffers(cx);
+        let name = self.scratch_buffers.new_name(new.name.as_deref());
+        if self.scratch_buffers.get(&name).is_some() {
+            self.open_scratch_buffer(&name, cx);
+            return;
+        }
+        let language = new
+            .language
+            .unwrap_or_else(|| DEFAULT_SCRATCH_LANGUAGE.to_string());
+        self.create_scratch_document(name, language, String::new(), cx);
     }
 
-    /// Build the editor-aligned content lane for the unified status bar.
-    fn statusbar_main_content(
-        &self,
-        model: &StatusBarModel,
-        geometry: StatusBarGeometry,
-        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
-        button_border_radius: Pixels,
+    /// Opens a document for the scratch buffer `name` holding `text`.
+    fn create_scratch_document(
+        &mut self,
+        name: String,
+        language: String,
+        text: String,
         cx: &mut Context<Self>,
-    ) -> gpui::AnyElement {
-        let mut context = div().flex_none().h_full().flex().items_center();
+    ) {
+        let doc_id = self.core.update(cx, |core, cx| {
+            let loader = core.editor.syn_loader.load();
+            let known = loader.language_for_name(language.as_str()).is_some();
+            if !known && language != DEFAULT_SCRATCH_LANGUAGE {
+                core.editor
+                    .set_error(format!("Unknown language: {language}"));
+                return None;
+            }
 
-        if model.density != StatusBarDensity::Compact {
-            context = context.child(
-                div()
-                    .flex_none()
-                    .w(geometry.position_width)
-                    .h_full()
-                    .px_2()
-                    .flex()
-                    .items_center()
-                    .justify_end()
-                    .whitespace_nowrap()
-                    .text_color(status_bar_tokens.text_secondary)
-                    .child(model.position_text.clone()),
-            );
+            let doc_id = core.editor.new_file(helix_view::editor::Action::Replace);
+            let view_id = core.editor.tree.focus;
+            let doc = core.editor.documents.get_mut(&doc_id)?;
+            doc.ensure_view_init(view_id);
+            if !text.is_empty() {
+                let transaction = helix_core::Transaction::insert(
+                    doc.text(),
+                    doc.selection(view_id),
+                    text.into(),
+                )
+                .with_selection(Selection::point(0));
+                doc.apply(&transaction, view_id);
+                // Restored contents are already saved in the scratch store.
+                doc.reset_modified();
+            }
+            if known && let Err(error) = doc.set_language_by_language_id(&language, &loader) {
+                debug!(%error, language = %language, "Could not set scratch buffer language");
+            }
+            core.editor.set_status(format!("Scratch buffer: {name}"));
+            cx.emit(crate::Update::Redraw);
+            Some(doc_id)
+        });
+        if let Some(doc_id) = doc_id {
+            self.scratch_buffers.open(name, language, doc_id);
+            cx.notify();
         }
+    }
 
-        if model.density == StatusBarDensity::Wide
-            && let Some(metadata) = model.document_metadata.as_ref()
-        {
-            if statusbar_show_secondary_document_metadata(model.density, model.vcs_ref.as_deref()) {
-                context = context
-                    .child(self.statusbar_text_item(metadata.encoding.clone(), status_bar_tokens))
-                    .child(self.statusbar_text_item(metadata.line_ending, status_bar_tokens));
-            }
-            context = context
-                .child(self.statusbar_text_item(metadata.language.clone(), status_bar_tokens));
+    /// Switches to the scratch buffer `name`, reopening it when it was
+    /// restored from the scratch store.
+    fn open_scratch_buffer(&mut self, name: &str, cx: &mut Context<Self>) {
+        self.prune_scratch_buffers(cx);
+        let Some(buffer) = self.scratch_buffers.get(name).cloned() else {
+            self.core.update(cx, |core, _| {
+                core.editor
+                    .set_error(format!("No scratch buffer named {name}"));
+            });
+            return;
+        };
+        match buffer.doc_id {
+            Some(doc_id) => self.core.update(cx, |core, cx| {
+                core.editor
+                    .switch(doc_id, helix_view::editor::Action::Replace);
+                cx.emit(crate::Update::Redraw);
+            }),
+            None => self.create_scratch_document(buffer.name, buffer.language, buffer.text, cx),
         }
+    }
 
-        context = context.child(self.statusbar_lsp_item(
-            model.lsp.as_ref(),
-            model.density,
-            geometry,
-            status_bar_tokens,
-            cx,
-        ));
+    /// Forgets scratch buffers whose document was closed.
+    fn prune_scratch_buffers(&mut self, cx: &mut Context<Self>) {
+        let editor = &self.core.read(cx).editor;
+        self.scratch_buffers
+            .prune(|doc_id| editor.documents.contains_key(&doc_id));
+    }
 
-        if model.density == StatusBarDensity::Wide {
-            if let Some(vcs_ref) = model.vcs_ref.as_ref() {
-                context = context.child(self.statusbar_vcs_item(vcs_ref, status_bar_tokens));
-            }
-            if let Some(metadata) = model.document_metadata.as_ref() {
-                context =
-                    context.child(self.statusbar_diagnostics_item(metadata, status_bar_tokens, cx));
-            }
+    fn show_scratch_buffers_picker(&mut self, cx: &mut Context<Self>) {
+        self.prune_scratch_buffers(cx);
+        if self.scratch_buffers.iter().next().is_none() {
+            self.core.update(cx, |core, _| {
+                core.editor.set_status(format!(
+                    "No scratch buffers; create one with {SCRATCH_NEW_COMMAND}"
+                ));
+            });
+            return;
         }
+        let source = ScratchBufferSource {
+            workspace: cx.weak_entity(),
+        };
+        let picker = crate::picker::Picker::from_source(Arc::new(source));
+        emit_picker_update(picker, &self.overlay, cx);
+    }
 
-        div()
-            .flex()
-            .flex_1()
-            .min_w_0()
-            .flex_row()
-            .items_center()
-            .h_full()
-            .child(self.statusbar_mode_item(
-                model,
-                geometry,
-                status_bar_tokens,
-                button_border_radius,
-            ))
-            .child(
-                div()
-                    .flex_1()
-                    .min_w_0()
-                    .px_3()
-                    .child(self.statusbar_message_slot(
-                        model.file_name.clone(),
-                        model.notification.clone(),
-                        model.background_activity.clone(),
-                        status_bar_tokens,
-                        cx,
-                    )),
-            )
-            .child(context)
-            .into_any_element()
+    fn scratch_buffer_items(&mut self, cx: &mut Context<Self>) -> Vec<PickerItem> {
+        self.prune_scratch_buffers(cx);
+        self.scratch_buffers
+            .iter()
+            .map(|buffer| {
+                let state = if buffer.doc_id.is_some() {
+                    "open"
+                } else {
+                    "saved"
+                };
+                PickerItem {
+                    label: buffer.name.clone().into(),
+                    sublabel: Some(format!("{} · {state}", buffer.language).into()),
+                    data: Arc::new(ScratchBufferChoice {
+                        name: buffer.name.clone(),
+                    }),
+                    file_path: None,
+                    vcs_status: None,
+                    columns: None,
+                }
+            })
+            .collect()
     }
 
-    fn statusbar_message_slot(
-        &self,
-        file_name: String,
-        notification: Option<StatusBarNotification>,
-        background_activity: Option<String>,
-        status_bar_tokens: &nucleotide_ui::tokens::StatusBarTokens,
-        cx: &mut Context<Self>,
-    ) -> gpui::AnyElement {
-        if let Some(activity_message) = background_activity {
-            let notification_tokens = cx.theme().tokens.notification_tokens();
-            let message =
-                shorten_statusbar_text(&activity_message, STATUSBAR_BACKGROUND_ACTIVITY_MAX_CHARS);
+    /// Asks for the language and name of a new scratch buffer.
+    fn show_new_scratch_prompt(&mut self, cx: &mut Context<Self>) {
+        let prompt = crate::prompt::Prompt::native(":", format!("{SCRATCH_NEW_COMMAND} "), |_| {})
+            .with_cancel(|| {});
+        cx.emit(crate::Update::Prompt(prompt));
+    }
 
-            return gpui::div()
-                .flex()
-                .flex_1()
-                .min_w_0()
-                .items_center()
-                .gap_2()
-                .overflow_hidden()
-                .child(
-                    IndeterminateProgressIndicator::new("statusbar-background-activity")
-                        .size(13.0)
-                        .text_color(notification_tokens.info_text),
-                )
-                .child(
-                    gpui::div()
-                        .flex_1()
-                        .min_w_0()
-                        .overflow_hidden()
-                        .whitespace_nowrap()
-                        .text_ellipsis()
-                        .text_color(status_bar_tokens.text_primary)
-                        .child(message),
-                )
-                .into_any_element();
+    /// Closes the focused scratch buffer without saving, and forgets its
+    /// saved contents.
+    fn delete_focused_scratch_buffer(&mut self, cx: &mut Context<Self>) {
+        let name = self
+            .active_document_and_view(cx)
+            .and_then(|(doc_id, _)| self.scratch_buffers.name_of(doc_id))
+            .map(str::to_string);
+        let Some(name) = name else {
+            self.core.update(cx, |core, _| {
+                core.editor
+                    .set_error("The focused buffer is not a scratch buffer");
+            });
+            return;
+        };
+        self.close_active_buffer_document_with_force(true, cx);
+        self.scratch_buffers.remove(&name);
+        self.persist_scratch_buffers(cx);
+        self.core.update(cx, |core, _| {
+            core.editor
+                .set_status(format!("Deleted scratch buffer {name}"));
+        });
+    }
+
+    /// Saves the scratch buffers to the scratch store when persistence is
+    /// configured.
+    fn persist_scratch_buffers(&mut self, cx: &mut Context<Self>) {
+        if !self.core.read(cx).config.gui.scratch.persist {
+            return;
         }
+        self.prune_scratch_buffers(cx);
+        let editor = &self.core.read(cx).editor;
+        let store = self
+            .scratch_buffers
+            .to_store(|doc_id| editor.document(doc_id).map(|doc| doc.text().to_string()));
+        if let Err(error) = store.save_default() {
+            warn!(error = %error, "Could not save scratch buffers");
+        }
+    }
 
-        let Some(notification) = notification else {
-            return gpui::div()
-                .flex_1()
-                .min_w_0()
-                .overflow_hidden()
-                .whitespace_nowrap()
-                .text_ellipsis()
-                .child(file_name)
-                .into_any_element();
-        };
+    /// Before a typed quit command, saves the scratch buffers and marks them
+    /// unmodified so they do not stop the quit, when configured.
+    fn release_scratch_buffers_for_quit(&mut self, cx: &mut Context<Self>) {
+        if !self.core.read(cx).config.gui.scratch.skip_unsaved_prompt {
+            return;
+        }
+        self.persist_scratch_buffers(cx);
+        let scratch_buffers = &self.scratch_buffers;
+        self.core.update(cx, |core, _| {
+            for (doc_id, doc) in core.editor.documents.iter_mut() {
+                if scratch_buffers.is_scratch(*doc_id) {
+                    doc.reset_modified();
+                }
+            }
+        });
+    }
 
-        let notification_tokens = cx.theme().tokens.notification_tokens();
-        let label_color = match notification.severity {
-            StatusBarNotificationSeverity::Info => notification_tokens.info_text,
-            StatusBarNotificationSeverity::Success => notification_tokens.success_text,
-            StatusBarNotificationSeverity::Warning => notification_tokens.warning_text,
-            StatusBarNotificationSeverity::Error => notification_tokens.error_text,
+    /// Lists what the active file can be compared with: the other open
+    /// buffers, the clipboard, or a file.
+    fn show_compare_picker(&mut self, cx: &mut Context<Self>) {
+        if self.active_document_and_view(cx).is_none() {
+            self.core.update(cx, |core, _| {
+                core.editor.set_error("No document to compare");
+            });
+            return;
+        }
+        let source = CompareSource {
+            workspace: cx.weak_entity(),
         };
-        let status_icon = statusbar_notification_icon(notification.severity);
-        let message = shorten_statusbar_text(
-            &notification.message,
-            STATUSBAR_NOTIFICATION_MESSAGE_MAX_CHARS,
-        );
+        let picker = crate::picker::Picker::from_source(Arc::new(source));
+        emit_picker_update(picker, &self.overlay, cx);
+    }
 
-        gpui::div()
-            .flex()
-            .flex_1()
-            .min_w_0()
-            .items_center()
-            .gap_2()
-            .overflow_hidden()
-            .child(
-                svg()
-                    .path(status_icon)
-                    .size(px(14.0))
-                    .flex_shrink_0()
-                    .text_color(label_color),
-            )
-            .child(
-                gpui::div()
-                    .flex_1()
-                    .min_w_0()
-                    .overflow_hidden()
-                    .whitespace_nowrap()
-                    .text_ellipsis()
-                    .text_color(status_bar_tokens.text_primary)
-                    .child(message),
-            )
-            .into_any_element()
+    /// Opens the picker registered as `name`, or lists the registered
+    /// pickers without one.
+    fn open_registered_picker(&mut self, name: Option<&str>, cx: &mut Context<Self>) {
+        let source: Arc<dyn PickerSource> = match name {
+            None => Arc::new(RegisteredPickersSource {
+                workspace: cx.weak_entity(),
+            }),
+            Some(name) => {
+                let Some(source) = cx
+                    .try_global::<PickerSources>()
+                    .and_then(|sources| sources.get(name))
+                else {
+                    self.core.update(cx, |core, _| {
+                        core.editor.set_error(format!("No picker named {name}"));
+                    });
+                    return;
+                };
+                source
+            }
+        };
+        let picker = crate::picker::Picker::from_source(source);
+        emit_picker_update(picker, &self.overlay, cx);
     }
 
-    fn context_menu_intents() -> &'static [ProjectTreeContextMenuIntent] {
-        ProjectTreeContextMenuIntent::common_file_operations()
+    /// What the active file can be compared with: the other open buffers,
+    /// the clipboard and a file.
+    fn compare_items(&mut self, cx: &mut Context<Self>) -> Vec<PickerItem> {
+        let active_doc_id = self.active_document_and_view(cx).map(|(doc_id, _)| doc_id);
+        let mut items = {
+            let editor = &self.core.read(cx).editor;
+            editor
+                .documents()
+                .filter(|doc| Some(doc.id()) != active_doc_id)
+                .map(|doc| PickerItem {
+                    label: doc
+                        .relative_path()
+                        .map(|path| path.display().to_string())
+                        .or_else(|| self.scratch_buffers.name_of(doc.id()).map(str::to_string))
+                        .unwrap_or_else(|| doc.display_name().to_string())
+                        .into(),
+                    sublabel: Some("Open buffer".into()),
+                    data: Arc::new(CompareChoice::Buffer(doc.id())),
+                    file_path: None,
+                    vcs_status: None,
+                    columns: None,
+                })
+                .collect::<Vec<_>>()
+        };
+        items.extend(
+            [
+                (
+                    "Clipboard",
+                    "Compare with the text on the clipboard",
+                    CompareChoice::Clipboard,
+                ),
+                (
+                    "File...",
+                    "Compare with the file at a path",
+                    CompareChoice::File,
+                ),
+            ]
+            .into_iter()
+            .map(|(label, sublabel, choice)| PickerItem {
+                label: label.into(),
+                sublabel: Some(sublabel.into()),
+                data: Arc::new(choice),
+                file_path: None,
+                vcs_status: None,
+                columns: None,
+            }),
+        );
+        items
     }
 
-    #[cfg(test)]
-    fn tab_context_menu_intents(
-        has_file_path: bool,
-        has_project_panel_path: bool,
-        has_terminal_directory: bool,
-    ) -> Vec<TabContextMenuIntent> {
-        let mut intents = vec![
-            TabContextMenuIntent::Close,
-            TabContextMenuIntent::CloseOthers,
-            TabContextMenuIntent::CloseLeft,
-            TabContextMenuIntent::CloseRight,
-            TabContextMenuIntent::CloseClean,
-            TabContextMenuIntent::CloseAll,
-        ];
-
-        if has_file_path {
-            intents.extend([
-                TabContextMenuIntent::ToggleReadOnly,
-                TabContextMenuIntent::CopyPath,
-                TabContextMenuIntent::CopyRelativePath,
-                TabContextMenuIntent::RevealInOs,
-            ]);
-        }
-
-        intents.push(TabContextMenuIntent::TogglePin);
-
-        if has_project_panel_path {
-            intents.push(TabContextMenuIntent::RevealInProjectPanel);
+    fn handle_compare_choice(&mut self, choice: CompareChoice, cx: &mut Context<Self>) {
+        match choice {
+            CompareChoice::Buffer(doc_id) => {
+                self.start_comparison(CompareTarget::Buffer(doc_id), cx);
+            }
+            CompareChoice::Clipboard => self.compare_with_clipboard(cx),
+            CompareChoice::File => {
+                let prompt =
+                    crate::prompt::Prompt::native(":", format!("{COMPARE_WITH_COMMAND} "), |_| {})
+                        .with_cancel(|| {});
+                cx.emit(crate::Update::Prompt(prompt));
+            }
         }
+    }
 
-        if has_terminal_directory {
-            intents.push(TabContextMenuIntent::OpenInTerminal);
+    fn compare_with_clipboard(&mut self, cx: &mut Context<Self>) {
+        match cx.read_from_clipboard().and_then(|item| item.text()) {
+            Some(text) => self.start_comparison(CompareTarget::Text(text), cx),
+            None => self.core.update(cx, |core, _| {
+                core.editor
+                    .set_error("The clipboard has no text to compare");
+            }),
         }
-
-        intents
     }
 
-    fn tab_context_menu_entries(
-        has_file_path: bool,
-        has_project_panel_path: bool,
-        has_terminal_directory: bool,
-    ) -> Vec<TabContextMenuEntry> {
-        let mut entries = vec![
-            TabContextMenuEntry::Action(TabContextMenuIntent::Close),
-            TabContextMenuEntry::Action(TabContextMenuIntent::CloseOthers),
-            TabContextMenuEntry::Separator,
-            TabContextMenuEntry::Action(TabContextMenuIntent::CloseLeft),
-            TabContextMenuEntry::Action(TabContextMenuIntent::CloseRight),
-            TabContextMenuEntry::Separator,
-            TabContextMenuEntry::Action(TabContextMenuIntent::CloseClean),
-            TabContextMenuEntry::Action(TabContextMenuIntent::CloseAll),
-        ];
+    /// Compares the focused document with `target`, shown in a split to its
+    /// right. Ends the comparison open before.
+    fn start_comparison(&mut self, target: CompareTarget, cx: &mut Context<Self>) {
+        self.close_comparison(cx);
+        let Some((left_doc, left_view)) = self.active_document_and_view(cx) else {
+            self.core.update(cx, |core, _| {
+                core.editor.set_error("No document to compare");
+            });
+            return;
+        };
+        let local = matches!(
+            self.core.read(cx).workspace_backend.identity(),
+            WorkspaceIdentity::Local
+        );
 
-        if has_file_path {
-            entries.extend([
-                TabContextMenuEntry::Separator,
-                TabContextMenuEntry::Action(TabContextMenuIntent::ToggleReadOnly),
-                TabContextMenuEntry::Separator,
-                TabContextMenuEntry::Action(TabContextMenuIntent::CopyPath),
-                TabContextMenuEntry::Action(TabContextMenuIntent::CopyRelativePath),
-                TabContextMenuEntry::Separator,
-                TabContextMenuEntry::Action(TabContextMenuIntent::RevealInOs),
-            ]);
-        }
-
-        entries.extend([
-            TabContextMenuEntry::Separator,
-            TabContextMenuEntry::Action(TabContextMenuIntent::TogglePin),
-        ]);
+        let handle = self.handle.clone();
+        let started = self.core.update(cx, |core, cx| -> Result<Comparison, String> {
+            let _guard = handle.enter();
+            let editor = &mut core.editor;
+            editor.focus(left_view);
+            let (right_doc, right_temporary) = match target {
+                CompareTarget::Buffer(doc_id) if doc_id == left_doc => {
+                    return Err("Pick another buffer to compare with".to_string());
+                }
+                CompareTarget::Buffer(doc_id) => {
+                    editor.switch(doc_id, helix_view::editor::Action::VerticalSplit);
+                    (doc_id, false)
+                }
+                CompareTarget::File(_) if !local => {
+                    return Err(
+                        "Open the file and compare with its buffer in remote workspaces"
+                            .to_string(),
+                    );
+                }
+                CompareTarget::File(path) => {
+                    if editor.document_id_by_path(&path) == Some(left_doc) {
+                        return Err("That is the active file".to_string());
+                    }
+                    let doc_id = editor
+                        .open(&path, helix_view::editor::Action::VerticalSplit)
+                        .map_err(|error| format!("Could not open {}: {error}", path.display()))?;
+                    (doc_id, false)
+                }
+                CompareTarget::Text(text) => {
+                    let language = editor
+                        .document(left_doc)
+                        .and_then(|doc| doc.language_name())
+                        .map(str::to_string);
+                    let doc_id = editor.new_file(helix_view::editor::Action::VerticalSplit);
+                    let view_id = editor.tree.focus;
+                    let loader = editor.syn_loader.load();
+                    if let Some(doc) = editor.documents.get_mut(&doc_id) {
+                        doc.ensure_view_init(view_id);
+                        let transaction = helix_core::Transaction::insert(
+                            doc.text(),
+                            doc.selection(view_id),
+                            text.into(),
+                        )
+                        .with_selection(Selection::point(0));
+                        doc.apply(&transaction, view_id);
+                        doc.reset_modified();
+                        doc.readonly = true;
+                        if let Some(language) = language
+                            && let Err(error) = doc.set_language_by_language_id(&language, &loader)
+                        {
+                            debug!(%error, "Could not highlight the compared clipboard text");
+                        }
+                    }
+                    (doc_id, true)
+                }
+            };
+            let right_view = editor.tree.focus;
+            editor.focus(left_view);
 
-        if has_project_panel_path {
-            entries.push(TabContextMenuEntry::Action(
-                TabContextMenuIntent::RevealInProjectPanel,
+            let mut set_aside = |doc_id| {
+                editor
+                    .document_mut(doc_id)
+                    .and_then(|doc| doc.replace_diff_handle(None))
+            };
+            let comparison = Comparison {
+                left: (left_doc, left_view),
+                right: (right_doc, right_view),
+                left_diff: set_aside(left_doc),
+                right_diff: set_aside(right_doc),
+                right_temporary,
+            };
+            crate::file_compare::sync_diff_bases(editor, &comparison);
+            editor.set_status(format!(
+                "Comparing; use {COMPARE_NEXT_COMMAND} and {COMPARE_PREVIOUS_COMMAND} to step through differences"
             ));
-        }
+            cx.emit(crate::Update::Redraw);
+            Ok(comparison)
+        });
 
-        if has_terminal_directory {
-            entries.push(TabContextMenuEntry::Action(
-                TabContextMenuIntent::OpenInTerminal,
-            ));
+        match started {
+            Ok(comparison) => self.comparison = Some(comparison),
+            Err(error) => self.core.update(cx, |core, _| core.editor.set_error(error)),
         }
-
-        entries
-    }
-
-    fn tab_context_menu_handler(intent: TabContextMenuIntent) -> TabContextMenuHandler {
-        match intent {
-            TabContextMenuIntent::Close => Workspace::tab_cm_action_close,
-            TabContextMenuIntent::CloseOthers => Workspace::tab_cm_action_close_others,
-            TabContextMenuIntent::CloseLeft => Workspace::tab_cm_action_close_left,
-            TabContextMenuIntent::CloseRight => Workspace::tab_cm_action_close_right,
-            TabContextMenuIntent::CloseClean => Workspace::tab_cm_action_close_clean,
-            TabContextMenuIntent::CloseAll => Workspace::tab_cm_action_close_all,
-            TabContextMenuIntent::CopyPath => Workspace::tab_cm_action_copy_path,
-            TabContextMenuIntent::CopyRelativePath => Workspace::tab_cm_action_copy_relative_path,
-            TabContextMenuIntent::RevealInOs => Workspace::tab_cm_action_reveal_in_os,
-            TabContextMenuIntent::RevealInProjectPanel => {
-                Workspace::tab_cm_action_reveal_in_project_panel
-            }
-            TabContextMenuIntent::OpenInTerminal => Workspace::tab_cm_action_open_in_terminal,
-            TabContextMenuIntent::ToggleReadOnly => Workspace::tab_cm_action_toggle_readonly,
-            TabContextMenuIntent::TogglePin => Workspace::tab_cm_action_toggle_pin,
-        }
-    }
-
-    fn tab_context_menu_intent_disabled(
-        intent: TabContextMenuIntent,
-        target_index: Option<usize>,
-        total_items: usize,
-        has_clean_items: bool,
-    ) -> bool {
-        match intent {
-            TabContextMenuIntent::Close | TabContextMenuIntent::CloseAll => target_index.is_none(),
-            TabContextMenuIntent::CloseOthers => total_items <= 1,
-            TabContextMenuIntent::CloseLeft => target_index.is_none_or(|index| index == 0),
-            TabContextMenuIntent::CloseRight => {
-                target_index.is_none_or(|index| index + 1 >= total_items)
-            }
-            TabContextMenuIntent::CloseClean => !has_clean_items,
-            TabContextMenuIntent::CopyPath
-            | TabContextMenuIntent::CopyRelativePath
-            | TabContextMenuIntent::RevealInOs
-            | TabContextMenuIntent::RevealInProjectPanel
-            | TabContextMenuIntent::OpenInTerminal
-            | TabContextMenuIntent::ToggleReadOnly => target_index.is_none(),
-            TabContextMenuIntent::TogglePin => target_index.is_none(),
-        }
-    }
-
-    fn tab_bar_split_menu_intents() -> &'static [TabBarSplitMenuIntent] {
-        &[
-            TabBarSplitMenuIntent::Right,
-            TabBarSplitMenuIntent::Left,
-            TabBarSplitMenuIntent::Up,
-            TabBarSplitMenuIntent::Down,
-        ]
+        self.update_document_views(cx);
+        cx.notify();
     }
 
-    fn tab_bar_split_menu_handler(intent: TabBarSplitMenuIntent) -> TabBarSplitMenuHandler {
-        match intent {
-            TabBarSplitMenuIntent::Right => Workspace::tab_bar_action_split_right,
-            TabBarSplitMenuIntent::Left => Workspace::tab_bar_action_split_left,
-            TabBarSplitMenuIntent::Up => Workspace::tab_bar_action_split_up,
-            TabBarSplitMenuIntent::Down => Workspace::tab_bar_action_split_down,
-        }
+    /// Ends the comparison, giving both documents their own differs back
+    /// and closing the right side's view.
+    fn close_comparison(&mut self, cx: &mut Context<Self>) {
+        let Some(comparison) = self.comparison.take() else {
+            return;
+        };
+        self.core.update(cx, |core, cx| {
+            let editor = &mut core.editor;
+            let Comparison {
+                left,
+                right,
+                left_diff,
+                right_diff,
+                right_temporary,
+            } = comparison;
+            if let Some(doc) = editor.document_mut(left.0) {
+                doc.replace_diff_handle(left_diff);
+            }
+            if right_temporary {
+                if let Err(error) = editor.close_document(right.0, true) {
+                    debug!(?error, "Could not close the compared text");
+                }
+            } else {
+                if let Some(doc) = editor.document_mut(right.0) {
+                    doc.replace_diff_handle(right_diff);
+                }
+                if editor.tree.contains(right.1) && editor.tree.views().count() > 1 {
+                    editor.close(right.1);
+                }
+            }
+            cx.emit(crate::Update::Redraw);
+        });
+        self.update_document_views(cx);
+        cx.notify();
     }
 
-    fn activate_tab_bar_split_menu_intent(
-        &mut self,
-        intent: TabBarSplitMenuIntent,
-        cx: &mut Context<Self>,
-    ) {
-        if self.close_tab_bar_split_menu() {
-            cx.notify();
+    /// Steps through, copies across or closes the open comparison.
+    fn run_compare_action(&mut self, action: CompareAction, cx: &mut Context<Self>) {
+        // A comparison ends once either of its views is gone.
+        let views_open = self.comparison.as_ref().is_some_and(|comparison| {
+            let tree = &self.core.read(cx).editor.tree;
+            tree.contains(comparison.left.1) && tree.contains(comparison.right.1)
+        });
+        if !views_open || action == CompareAction::Close {
+            let was_open = self.comparison.is_some();
+            self.close_comparison(cx);
+            if action != CompareAction::Close || !was_open {
+                self.core.update(cx, |core, _| {
+                    core.editor.set_error(format!(
+                        "No comparison is open; start one with {COMPARE_WITH_COMMAND}"
+                    ));
+                });
+            }
+            return;
         }
-        let handler = Self::tab_bar_split_menu_handler(intent);
-        handler(self, cx);
-    }
-
-    #[cfg(test)]
-    fn tab_bar_new_menu_intents() -> &'static [TabBarNewMenuIntent] {
-        &[
-            TabBarNewMenuIntent::NewFile,
-            TabBarNewMenuIntent::OpenFile,
-            TabBarNewMenuIntent::SearchProject,
-            TabBarNewMenuIntent::SearchSymbols,
-            TabBarNewMenuIntent::NewTerminal,
-            TabBarNewMenuIntent::NewCenterTerminal,
-        ]
-    }
-
-    fn tab_bar_new_menu_entries() -> &'static [TabBarNewMenuEntry] {
-        &[
-            TabBarNewMenuEntry::Action(TabBarNewMenuIntent::NewFile),
-            TabBarNewMenuEntry::Action(TabBarNewMenuIntent::OpenFile),
-            TabBarNewMenuEntry::Separator,
-            TabBarNewMenuEntry::Action(TabBarNewMenuIntent::SearchProject),
-            TabBarNewMenuEntry::Action(TabBarNewMenuIntent::SearchSymbols),
-            TabBarNewMenuEntry::Separator,
-            TabBarNewMenuEntry::Action(TabBarNewMenuIntent::NewTerminal),
-            TabBarNewMenuEntry::Action(TabBarNewMenuIntent::NewCenterTerminal),
-        ]
-    }
+        let Some(comparison) = self.comparison.as_ref() else {
+            return;
+        };
 
-    fn tab_bar_new_menu_handler(intent: TabBarNewMenuIntent) -> TabBarNewMenuHandler {
-        match intent {
-            TabBarNewMenuIntent::NewFile => Workspace::tab_bar_action_new_file,
-            TabBarNewMenuIntent::OpenFile => Workspace::tab_bar_action_open_file,
-            TabBarNewMenuIntent::SearchProject => Workspace::tab_bar_action_search_project,
-            TabBarNewMenuIntent::SearchSymbols => Workspace::tab_bar_action_search_symbols,
-            TabBarNewMenuIntent::NewTerminal => Workspace::tab_bar_action_new_terminal,
-            TabBarNewMenuIntent::NewCenterTerminal => Workspace::tab_bar_action_new_center_terminal,
-        }
-    }
+        self.core.update(cx, |core, cx| {
+            let editor = &mut core.editor;
+            let (Some(left), Some(right)) = (
+                editor.document(comparison.left.0),
+                editor.document(comparison.right.0),
+            ) else {
+                return;
+            };
+            let (left_text, right_text) = (left.text().clone(), right.text().clone());
+            let hunks = compare_hunks(&left_text, &right_text);
+            if hunks.is_empty() {
+                editor.set_status("No differences");
+                return;
+            }
 
-    /// Ensure document is in the order list, adding it to the end if new
-    fn ensure_document_in_order(&mut self, doc_id: helix_view::DocumentId) {
-        if !self.document_order.contains(&doc_id) {
-            self.document_order.push(doc_id);
-        }
-    }
+            let side = comparison.side_of(editor.tree.focus).unwrap_or(Side::Left);
+            let (focused_doc, focused_view) = match side {
+                Side::Left => comparison.left,
+                Side::Right => comparison.right,
+            };
+            let cursor_line = editor.document(focused_doc).map_or(0, |doc| {
+                let text = doc.text().slice(..);
+                text.char_to_line(doc.selection(focused_view).primary().cursor(text))
+            });
 
-    fn invalidate_tab_bar_documents(&mut self) {
-        self.tab_bar_document_generation = self.tab_bar_document_generation.wrapping_add(1);
+            match action {
+                CompareAction::Next | CompareAction::Previous => {
+                    let forward = action == CompareAction::Next;
+                    let Some(index) = adjacent_hunk(&hunks, side, cursor_line, forward) else {
+                        return;
+                    };
+                    let hunk = &hunks[index];
+                    for ((doc_id, view_id), text, lines) in [
+                        (comparison.left, &left_text, &hunk.left),
+                        (comparison.right, &right_text, &hunk.right),
+                    ] {
+                        if let Some(doc) = editor.document_mut(doc_id) {
+                            doc.set_selection(view_id, hunk_selection(text, lines));
+                        }
+                        editor.ensure_cursor_in_view(view_id);
+                    }
+                    editor.set_status(format!("Difference {} of {}", index + 1, hunks.len()));
+                }
+                CompareAction::CopyLeft | CompareAction::CopyRight => {
+                    let Some(index) = hunk_at(&hunks, side, cursor_line) else {
+                        editor.set_error("No difference at the cursor");
+                        return;
+                    };
+                    let hunk = &hunks[index];
+                    let (target, from_text, from_lines, to_text, to_lines) =
+                        if action == CompareAction::CopyLeft {
+                            (
+                                comparison.left,
+                                &right_text,
+                                &hunk.right,
+                                &left_text,
+                                &hunk.left,
+                            )
+                        } else {
+                            (
+                                comparison.right,
+                                &left_text,
+                                &hunk.left,
+                                &right_text,
+                                &hunk.right,
+                            )
+                        };
+                    if editor.document(target.0).is_some_and(|doc| doc.readonly) {
+                        editor.set_error("That side of the comparison is read-only");
+                        return;
+                    }
+                    let transaction =
+                        copy_hunk_transaction(from_text, from_lines, to_text, to_lines);
+                    apply_task_edit(editor, target.0, target.1, &transaction);
+                    crate::file_compare::sync_diff_bases(editor, comparison);
+                    editor.set_status(format!(
+                        "Copied difference {} of {}",
+                        index + 1,
+                        hunks.len()
+                    ));
+                }
+                CompareAction::Close => {}
+            }
+            cx.emit(crate::Update::Redraw);
+        });
     }
 
-    fn invalidate_tab_bar_document_if_presentation_changed(
-        &mut self,
-        doc_id: DocumentId,
-        cx: &Context<Self>,
-    ) {
-        let Some(cache) = &self.tab_bar_document_cache else {
-            self.invalidate_tab_bar_documents();
-            return;
+    /// Indexes the local project's contents in the background, starting
+    /// from the index saved by an earlier session unless `rebuild` is set.
+    /// Stops indexing when the index is turned off or the project is remote.
+    fn start_content_index(&mut self, rebuild: bool, cx: &mut Context<Self>) {
+        let (config, options) = {
+            let core = self.core.read(cx);
+            (
+                core.config.gui.content_index.clone(),
+                WalkOptions::from_file_picker(&core.editor.config().file_picker),
+            )
         };
-        let Some(cached) = cache
-            .documents
-            .iter()
-            .find(|document| document.id == TabId::Document(doc_id))
-        else {
-            self.invalidate_tab_bar_documents();
+        let Some(root) = self.local_project_root(cx).filter(|_| config.enabled) else {
+            self.stop_content_index(cx);
             return;
         };
-        let current_preview = cache.key.show_preview_tabs
-            && cx
-                .try_global::<nucleotide_core::preview_tracker::PreviewTracker>()
-                .is_some_and(|tracker| tracker.is_preview_doc(doc_id));
-        let presentation_changed =
-            self.core
-                .read(cx)
-                .editor
-                .document(doc_id)
-                .is_none_or(|document| {
-                    cached.is_modified != document.is_modified()
-                        || cached.is_readonly != document.readonly
-                        || cached.path.as_deref() != document.path()
-                        || cached.focused_at != document.focused_at
-                        || cached.is_preview != current_preview
-                });
-
-        if presentation_changed {
-            self.invalidate_tab_bar_documents();
+        if !rebuild && self.content_index.covers(&root) {
+            return;
         }
-    }
-
-    fn image_tab_mut(&mut self, image_id: u64) -> Option<&mut ImageTab> {
-        self.image_tabs.iter_mut().find(|tab| tab.id == image_id)
-    }
+        self.stop_content_index(cx);
+
+        let content_index = self.content_index.start(root.clone());
+        let activity_id = self.start_background_activity("Indexing project contents", cx);
+        self.content_index_activity = Some(activity_id);
+
+        let limits = IndexLimits::from_config(&config);
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+        let build = self.handle.spawn_blocking({
+            let content_index = content_index.clone();
+            move || {
+                let store = crate::content_index::store_path(&root);
+                let saved = if config.persist && !rebuild {
+                    ContentIndex::load(&store, &root).unwrap_or_else(|error| {
+                        warn!(%error, "Discarding the saved content index");
+                        None
+                    })
+                } else {
+                    None
+                };
+                let mut index = saved.unwrap_or_else(|| ContentIndex::new(root.clone()));
+                let finished = index.refresh(&options, &limits, &content_index.cancel, |walked| {
+                    let _ = progress_tx.send(walked);
+                });
+                if !finished {
+                    return false;
+                }
+                if config.persist
+                    && let Err(error) = index.save(&store)
+                {
+                    warn!(%error, "Failed to save the content index");
+                }
+                if let Ok(mut current) = content_index.index.write() {
+                    *current = index;
+                }
+                content_index.ready.store(true, Ordering::Release);
+                true
+            }
+        });
 
-    fn next_image_tab_id(&mut self) -> u64 {
-        let id = self.next_image_tab_index;
-        self.next_image_tab_index = self.next_image_tab_index.saturating_add(1);
-        id
+        cx.spawn(async move |this, cx| {
+            // The channel closes once the build is done with it.
+            while let Some(walked) = progress_rx.recv().await {
+                let Some(this) = this.upgrade() else {
+                    return;
+                };
+                this.update(cx, |workspace, cx| {
+                    workspace.update_background_activity(
+                        activity_id,
+                        format!("Indexing project contents ({walked} files)"),
+                        cx,
+                    );
+                });
+            }
+            let built = matches!(build.await, Ok(true));
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            this.update(cx, |workspace, cx| {
+                workspace.finish_background_activity(activity_id, cx);
+                if !workspace.content_index.is_current(&content_index) {
+                    return;
+                }
+                workspace.content_index_activity = None;
+                if built {
+                    workspace.apply_content_index_updates(cx);
+                }
+            });
+        })
+        .detach();
     }
 
-    fn open_image_file_internal(
-        &mut self,
-        path: &std::path::Path,
-        should_focus: bool,
-        cx: &mut Context<Self>,
-    ) {
-        self.open_image_file_with_render_path(path, path, should_focus, cx)
+    fn stop_content_index(&mut self, cx: &mut Context<Self>) {
+        self.content_index.stop();
+        if let Some(activity_id) = self.content_index_activity.take() {
+            self.finish_background_activity(activity_id, cx);
+        }
     }
 
-    fn open_image_file_with_render_path(
+    /// Queues a watcher change for the content index. Changes made while
+    /// the index is built are applied once it is ready.
+    fn queue_content_index_update(
         &mut self,
-        path: &std::path::Path,
-        render_path: &std::path::Path,
-        should_focus: bool,
+        path: &Path,
+        kind: &FileSystemEventKind,
         cx: &mut Context<Self>,
     ) {
-        let path = path.to_path_buf();
-        if let Some(tab) = self.image_tabs.iter_mut().find(|tab| tab.path == path) {
-            tab.focused_at = std::time::Instant::now();
-            self.active_image_tab_id = Some(tab.id);
-        } else {
-            let render_path = render_path.to_path_buf();
-            let scroll_handle = ScrollHandle::new();
-            let vertical_scrollbar_state = ScrollbarState::new(scroll_handle.clone());
-            let horizontal_scrollbar_state = ScrollbarState::new(scroll_handle.clone());
-            let tab = ImageTab {
-                id: self.next_image_tab_id(),
-                path: path.clone(),
-                dimensions: image_file_dimensions(&render_path),
-                render_path,
-                focused_at: std::time::Instant::now(),
-                zoom: 1.0,
-                scroll_handle,
-                vertical_scrollbar_state,
-                horizontal_scrollbar_state,
-            };
-            self.active_image_tab_id = Some(tab.id);
-            self.image_tabs.push(tab);
-        }
-
-        self.invalidate_tab_bar_documents();
-        self.allow_tab_bar_auto_scroll();
-
-        if let Some(file_tree) = &self.file_tree {
-            file_tree.update(cx, |tree, cx| {
-                tree.sync_selection_with_file(Some(&path), cx);
-            });
-        }
-
-        if should_focus {
-            self.needs_focus_restore = false;
+        let schedule = match kind {
+            FileSystemEventKind::Renamed { from, to } => {
+                self.content_index.queue_changes([from.clone(), to.clone()])
+            }
+            _ => self.content_index.queue_changes([path.to_path_buf()]),
+        };
+        if !schedule {
+            return;
         }
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(CONTENT_INDEX_UPDATE_DELAY)
+                .await;
+            if let Some(this) = this.upgrade() {
+                this.update(cx, |workspace, cx| {
+                    workspace.content_index.finish_scheduled_update();
+                    workspace.apply_content_index_updates(cx);
+                });
+            }
+        })
+        .detach();
+    }
 
-        cx.notify();
+    fn apply_content_index_updates(&mut self, cx: &mut Context<Self>) {
+        let Some((content_index, paths)) = self.content_index.take_pending() else {
+            return;
+        };
+        let (config, options) = {
+            let core = self.core.read(cx);
+            (
+                core.config.gui.content_index.clone(),
+                WalkOptions::from_file_picker(&core.editor.config().file_picker),
+            )
+        };
+        let limits = IndexLimits::from_config(&config);
+        self.handle.spawn_blocking(move || {
+            if let Ok(mut index) = content_index.index.write() {
+                index.update(&paths, &options, &limits);
+            }
+        });
     }
 
-    fn switch_to_image_tab(&mut self, image_id: u64, cx: &mut Context<Self>) {
-        if let Some(tab) = self.image_tab_mut(image_id) {
-            tab.focused_at = std::time::Instant::now();
-            self.active_image_tab_id = Some(image_id);
-            self.invalidate_tab_bar_documents();
-            self.allow_tab_bar_auto_scroll();
-            cx.notify();
-        }
+    fn show_content_index_status(&mut self, cx: &mut Context<Self>) {
+        let status = match self.content_index.handle() {
+            Some(content_index) => match content_index.index.read() {
+                Ok(index) => status_label(&index, content_index.is_ready()),
+                Err(_) => "Content index is unavailable".to_string(),
+            },
+            None if !self.core.read(cx).config.gui.content_index.enabled => {
+                "Content index is off; turn on [content_index] enabled to index the project"
+                    .to_string()
+            }
+            None => "Content index only covers local projects".to_string(),
+        };
+        self.push_editor_status_notification(
+            EditorStatus {
+                status,
+                severity: Severity::Info,
+            },
+            cx,
+        );
     }
 
-    fn set_image_tab_zoom(&mut self, image_id: u64, zoom: f32, cx: &mut Context<Self>) {
-        if let Some(tab) = self.image_tab_mut(image_id) {
-            tab.zoom = zoom.clamp(IMAGE_ZOOM_MIN, IMAGE_ZOOM_MAX);
-            cx.notify();
+    fn rebuild_content_index(&mut self, cx: &mut Context<Self>) {
+        if !self.core.read(cx).config.gui.content_index.enabled {
+            self.show_content_index_status(cx);
+            return;
+        }
+        self.start_content_index(true, cx);
+        if self.content_index.handle().is_none() {
+            self.show_content_index_status(cx);
         }
     }
 
-    fn visible_tab_document_ids(&self, cx: &mut Context<Self>) -> Vec<TabId> {
-        let core = self.core.read(cx);
-        let editor = &core.editor;
-
-        let mut visible_doc_ids = self
-            .document_order
-            .iter()
-            .copied()
-            .filter(|doc_id| editor.documents.contains_key(doc_id))
-            .map(TabId::Document)
-            .collect::<Vec<_>>();
-        visible_doc_ids.extend(self.image_tabs.iter().map(|tab| TabId::Image(tab.id)));
+    fn toggle_task_at_cursor(&mut self, cx: &mut Context<Self>) {
+        self.core.update(cx, |core, cx| {
+            let view_id = core.editor.tree.focus;
+            let Some(doc_id) = core.editor.tree.try_get(view_id).map(|view| view.doc) else {
+                return;
+            };
+            let Some(doc) = core.editor.documents.get(&doc_id) else {
+                return;
+            };
+            let text = doc.text();
+            let cursor_line = doc.selection(view_id).primary().cursor_line(text.slice(..));
+            let Some(transaction) = toggle_task_transaction(text, cursor_line) else {
+                core.editor.set_error("No task list item at the cursor");
+                return;
+            };
 
-        zed_style_tab_order(&visible_doc_ids, &self.pinned_documents)
+            apply_task_edit(&mut core.editor, doc_id, view_id, &transaction);
+            cx.emit(crate::Update::Redraw);
+        });
     }
 
-    fn tab_activation_documents(
-        &self,
-        cx: &mut Context<Self>,
-    ) -> Vec<TabActivationDocument<TabId>> {
-        let visible_doc_ids = self.visible_tab_document_ids(cx);
-        let core = self.core.read(cx);
-
-        visible_doc_ids
-            .into_iter()
-            .filter_map(|tab_id| {
-                if let TabId::Image(image_id) = tab_id
-                    && let Some(tab) = self.image_tabs.iter().find(|tab| tab.id == image_id)
-                {
-                    return Some(TabActivationDocument {
-                        id: tab_id,
-                        focused_at: tab.focused_at,
-                    });
-                }
+    fn archive_done_tasks(&mut self, cx: &mut Context<Self>) {
+        self.core.update(cx, |core, cx| {
+            let view_id = core.editor.tree.focus;
+            let Some(doc_id) = core.editor.tree.try_get(view_id).map(|view| view.doc) else {
+                return;
+            };
+            let Some(doc) = core.editor.documents.get(&doc_id) else {
+                return;
+            };
+            let Some((transaction, moved)) = archive_completed_tasks(doc.text()) else {
+                core.editor.set_status("No completed tasks to move");
+                return;
+            };
 
-                let TabId::Document(doc_id) = tab_id else {
-                    return None;
-                };
-                let doc = core.editor.documents.get(&doc_id)?;
-                Some(TabActivationDocument {
-                    id: tab_id,
-                    focused_at: doc.focused_at,
-                })
-            })
-            .collect()
+            apply_task_edit(&mut core.editor, doc_id, view_id, &transaction);
+            match moved {
+                1 => core.editor.set_status("Moved 1 completed task to Done"),
+                moved => core
+                    .editor
+                    .set_status(format!("Moved {moved} completed tasks to Done")),
+            }
+            cx.emit(crate::Update::Redraw);
+        });
     }
 
-    fn close_tab_documents(
+    fn resolve_merge_conflict_choice(
         &mut self,
-        doc_ids: impl IntoIterator<Item = DocumentId>,
+        choice: &MergeConflictChoice,
         cx: &mut Context<Self>,
     ) {
-        self.close_tab_documents_with_force(doc_ids, false, cx);
+        self.core.update(cx, |core, cx| {
+            let focus = core.editor.tree.focus;
+            let Some(view_id) = core
+                .editor
+                .tree
+                .views()
+                .filter(|(view, _)| view.doc == choice.doc_id)
+                .max_by_key(|(view, _)| view.id == focus)
+                .map(|(view, _)| view.id)
+            else {
+                core.editor
+                    .set_error("The conflicted document is no longer open");
+                return;
+            };
+            let Some(doc) = core.editor.documents.get(&choice.doc_id) else {
+                return;
+            };
+            if doc.version() != choice.document_version {
+                core.editor
+                    .set_error("Document changed since the conflicts were listed; list them again");
+                return;
+            }
+
+            let text = doc.text();
+            let conflicts = parse_merge_conflicts(text.slice(..));
+            let Some(conflict) = conflicts
+                .iter()
+                .find(|conflict| conflict.start_line == choice.start_line)
+            else {
+                return;
+            };
+
+            let transaction = merge_conflict_transaction(text, conflict, choice.resolution);
+            let remaining = conflicts.len() - 1;
+            apply_merge_conflict_resolution(core, choice.doc_id, view_id, &transaction, remaining);
+            cx.emit(crate::Update::Redraw);
+        });
     }
 
-    fn close_tab_documents_with_force(
-        &mut self,
-        doc_ids: impl IntoIterator<Item = DocumentId>,
-        force: bool,
-        cx: &mut Context<Self>,
-    ) {
-        let doc_ids = doc_ids.into_iter().collect::<Vec<_>>();
-        if doc_ids.is_empty() {
-            return;
+    /// Exports the focused view's selection, or its viewport when nothing is
+    /// selected, as a framed snippet with syntax colors.
+    fn export_editor_snippet(&mut self, format: SnippetExportFormat, cx: &mut Context<Self>) {
+        let tokens = cx.theme().tokens;
+        let snippet = {
+            let editor = &self.core.read(cx).editor;
+            let loader = editor.syn_loader.load();
+            editor.tree.try_get(editor.tree.focus).and_then(|view| {
+                let doc = editor.documents.get(&view.doc)?;
+                Some(view_export::editor_snippet(
+                    doc,
+                    view,
+                    &editor.theme,
+                    &loader,
+                    &tokens,
+                ))
+            })
+        };
+        match snippet {
+            Some(snippet) => self.save_snippet_export(snippet, format, cx),
+            None => self.push_editor_status_notification(
+                EditorStatus {
+                    status: "There is no editor view to export".to_string(),
+                    severity: Severity::Info,
+                },
+                cx,
+            ),
         }
+    }
 
-        let handle = self.handle.clone();
-        let (closed_doc_ids, close_statuses, modified_doc_ids, modified_names) =
-            self.core.update(cx, |core, cx| {
-                let _guard = handle.enter();
-                let mut closed_doc_ids = Vec::new();
-                let mut close_statuses = Vec::new();
-                let mut modified_doc_ids = Vec::new();
-                let mut modified_names = Vec::new();
-                let active_doc_id = core
-                    .editor
-                    .tree
-                    .try_get(core.editor.tree.focus)
-                    .map(|view| view.doc);
-                let close_targets = doc_ids
-                    .into_iter()
-                    .map(|doc_id| {
-                        let path = core
-                            .editor
-                            .documents
-                            .get(&doc_id)
-                            .and_then(|doc| doc.path().map(std::path::Path::to_path_buf));
+    /// Exports the focused document as printable HTML or PDF. Rendering and
+    /// writing run in the background with progress in the status bar.
+    fn export_document(&mut self, format: DocumentExportFormat, cx: &mut Context<Self>) {
+        let tokens = cx.theme().tokens;
+        let (document, options) = {
+            let core = self.core.read(cx);
+            let editor = &core.editor;
+            let loader = editor.syn_loader.load();
+            let document = editor.tree.try_get(editor.tree.focus).and_then(|view| {
+                let doc = editor.documents.get(&view.doc)?;
+                Some(view_export::whole_document(
+                    doc,
+                    view,
+                    &editor.theme,
+                    &loader,
+                    &tokens,
+                ))
+            });
+            let export = &core.config.gui.export;
+            let font_family = export
+                .font_family
+                .clone()
+                .or_else(|| {
+                    core.config
+                        .gui
+                        .editor
+                        .font
+                        .as_ref()
+                        .map(|font| font.family.clone())
+                })
+                .map_or_else(
+                    || "monospace".to_string(),
+                    |family| format!("\"{family}\", monospace"),
+                );
+            let options = DocumentExportOptions {
+                font_family,
+                pdf_font_family: export.pdf_font_family.clone(),
+                font_size: export.font_size,
+                page_size: export.page_size,
+                line_numbers: export.line_numbers,
+            };
+            (document, options)
+        };
+        let Some(document) = document else {
+            self.push_editor_status_notification(
+                EditorStatus {
+                    status: "There is no document to export".to_string(),
+                    severity: Severity::Info,
+                },
+                cx,
+            );
+            return;
+        };
+        let Some(directory) = view_export::export_directory() else {
+            self.push_editor_status_notification(
+                EditorStatus {
+                    status: "Could not find a downloads or home directory for the export"
+                        .to_string(),
+                    severity: Severity::Error,
+                },
+                cx,
+            );
+            return;
+        };
 
-                        BatchCloseDocument {
-                            id: doc_id,
-                            is_active: active_doc_id == Some(doc_id),
-                            path,
-                        }
-                    })
-                    .collect::<Vec<_>>();
-                let doc_ids = batch_close_document_order(&close_targets);
+        let activity_title = format!("Exporting {} as {}", document.title, format.label());
+        let activity_id = self.start_background_activity(format!("{activity_title}…"), cx);
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+        let runtime_handle = self.handle.clone();
+        cx.spawn(async move |this, cx| {
+            let export = runtime_handle.spawn_blocking(move || {
+                view_export::write_document_export(
+                    &directory,
+                    &document,
+                    &options,
+                    format,
+                    |percent| {
+                        let _ = progress_tx.send(percent);
+                    },
+                )
+            });
+            // The sender is dropped when the export finishes, ending this loop.
+            while let Some(percent) = progress_rx.recv().await {
+                let Some(this) = this.upgrade() else {
+                    return;
+                };
+                this.update(cx, |workspace, cx| {
+                    workspace.update_background_activity(
+                        activity_id,
+                        format!("{activity_title}… {percent}%"),
+                        cx,
+                    );
+                });
+            }
+            let result = export.await;
 
-                for doc_id in doc_ids {
-                    match core.editor.close_document(doc_id, force) {
-                        Ok(()) => {
-                            closed_doc_ids.push(doc_id);
-                        }
-                        Err(helix_view::editor::CloseError::BufferModified(name)) => {
-                            info!("Cannot close document {:?}: has unsaved changes", doc_id);
-                            if force {
-                                close_statuses.push(unsaved_buffers_remaining_status(vec![name]));
-                            } else {
-                                modified_doc_ids.push(doc_id);
-                                modified_names.push(name);
-                            }
-                        }
-                        Err(error @ helix_view::editor::CloseError::DoesNotExist) => {
-                            info!("Document {:?} does not exist", doc_id);
-                            close_statuses.push(close_error_status(error));
-                        }
-                        Err(error @ helix_view::editor::CloseError::SaveError(_)) => {
-                            info!("Failed to close document {:?}", doc_id);
-                            close_statuses.push(close_error_status(error));
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            this.update(cx, |workspace, cx| {
+                workspace.finish_background_activity(activity_id, cx);
+                match result {
+                    Ok(Ok((path, warnings))) => {
+                        workspace.notifications.update(cx, |notifications, cx| {
+                            notifications.push_success(
+                                format!("Exported {}", format.label()),
+                                path.display().to_string(),
+                                cx,
+                            );
+                        });
+                        for warning in warnings {
+                            workspace.push_editor_status_notification(
+                                EditorStatus {
+                                    status: warning.to_string(),
+                                    severity: Severity::Warning,
+                                },
+                                cx,
+                            );
                         }
                     }
+                    Ok(Err(error)) => workspace.push_editor_status_notification(
+                        EditorStatus {
+                            status: format!("Could not export document: {error}"),
+                            severity: Severity::Error,
+                        },
+                        cx,
+                    ),
+                    Err(error) => warn!(%error, "Document export task failed"),
                 }
-
-                if !closed_doc_ids.is_empty() {
-                    cx.emit(crate::Update::Redraw);
-                    cx.notify();
-                }
-
-                (
-                    closed_doc_ids,
-                    close_statuses,
-                    modified_doc_ids,
-                    modified_names,
-                )
             });
-
-        for status in close_statuses {
-            self.push_editor_status_notification(status, cx);
-        }
-
-        if !modified_doc_ids.is_empty() {
-            self.request_unsaved_close(
-                PendingUnsavedClose::Batch {
-                    doc_ids: modified_doc_ids,
-                },
-                modified_names,
-                cx,
-            );
-        }
-
-        if !closed_doc_ids.is_empty() {
-            for doc_id in &closed_doc_ids {
-                self.loading_documents.remove(doc_id);
-            }
-            self.unregister_preview_documents(closed_doc_ids.iter().copied(), cx);
-            self.update_document_views(cx);
-            cx.notify();
-        }
+        })
+        .detach();
     }
 
-    fn close_tab_ids(&mut self, tab_ids: impl IntoIterator<Item = TabId>, cx: &mut Context<Self>) {
-        let mut document_ids = Vec::new();
-        for tab_id in tab_ids {
-            match tab_id {
-                TabId::Document(doc_id) => document_ids.push(doc_id),
-                TabId::Image(image_id) => self.close_image_tab(image_id, None, cx),
+    /// Exports the visible grid of the terminal panel's session.
+    fn export_terminal_snippet(&mut self, format: SnippetExportFormat, cx: &mut Context<Self>) {
+        #[cfg(feature = "terminal-emulator-core")]
+        {
+            let tokens = cx.theme().tokens;
+            let snippet = self
+                .terminal_id
+                .and_then(nucleotide_terminal_view::get_view_model)
+                .map(|model| {
+                    let model = model
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner());
+                    view_export::terminal_snippet(
+                        model.display_title(),
+                        model.styled_grid(&tokens),
+                        &tokens,
+                    )
+                });
+            if let Some(snippet) = snippet {
+                self.save_snippet_export(snippet, format, cx);
+                return;
             }
         }
+        #[cfg(not(feature = "terminal-emulator-core"))]
+        let _ = format;
 
-        self.close_tab_documents(document_ids, cx);
+        self.push_editor_status_notification(
+            EditorStatus {
+                status: "There is no terminal to export".to_string(),
+                severity: Severity::Info,
+            },
+            cx,
+        );
     }
 
-    fn close_single_tab_document(
+    fn save_snippet_export(
         &mut self,
-        doc_id: DocumentId,
-        active_doc_id: Option<TabId>,
-        activation_documents: &[TabActivationDocument<TabId>],
-        activate_on_close: crate::config::TabActivateOnClose,
+        snippet: SnippetExport,
+        format: SnippetExportFormat,
         cx: &mut Context<Self>,
     ) {
-        let activation_target = tab_activation_target_after_close(
-            activation_documents,
-            TabId::Document(doc_id),
-            active_doc_id,
-            activate_on_close,
-        );
-        self.close_single_tab_document_with_activation_target(doc_id, activation_target, false, cx);
+        let Some(directory) = view_export::export_directory() else {
+            self.push_editor_status_notification(
+                EditorStatus {
+                    status: "Could not find a downloads or home directory for the export"
+                        .to_string(),
+                    severity: Severity::Error,
+                },
+                cx,
+            );
+            return;
+        };
+
+        let runtime_handle = self.handle.clone();
+        cx.spawn(async move |this, cx| {
+            let result = runtime_handle
+                .spawn_blocking(move || {
+                    view_export::write_snippet_export(&directory, &snippet, format)
+                })
+                .await;
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            this.update(cx, |workspace, cx| match result {
+                Ok(Ok(path)) => workspace.notifications.update(cx, |notifications, cx| {
+                    notifications.push_success("Snippet exported", path.display().to_string(), cx);
+                }),
+                Ok(Err(error)) => workspace.push_editor_status_notification(
+                    EditorStatus {
+                        status: format!("Could not export snippet: {error}"),
+                        severity: Severity::Error,
+                    },
+                    cx,
+                ),
+                Err(error) => warn!(%error, "Snippet export task failed"),
+            });
+        })
+        .detach();
     }
 
-    fn close_image_tab(
+    /// Shows the environment passed to language servers and terminals for the
+    /// current project, diffed against the previous capture. `recapture`
+    /// discards the cached capture first.
+    fn show_environment_inspector(
         &mut self,
-        image_id: u64,
-        activation_target: Option<TabId>,
+        recapture: Option<EnvironmentRecapture>,
         cx: &mut Context<Self>,
     ) {
-        let Some(index) = self.image_tabs.iter().position(|tab| tab.id == image_id) else {
+        let Some(root) = recapture
+            .as_ref()
+            .map(|request| request.root.clone())
+            .or_else(|| self.current_project_root.clone())
+        else {
+            self.push_editor_status_notification(
+                EditorStatus {
+                    status: "Open a project to inspect its environment".to_string(),
+                    severity: Severity::Info,
+                },
+                cx,
+            );
             return;
         };
 
-        self.image_tabs.remove(index);
-        self.pinned_documents.remove(&TabId::Image(image_id));
-        self.invalidate_tab_bar_documents();
+        let (project_environment, workspace_backend) = {
+            let core = self.core.read(cx);
+            (
+                core.project_environment.clone(),
+                core.workspace_backend.clone(),
+            )
+        };
+        let recapture = recapture.is_some();
+        let runtime_handle = self.handle.clone();
+        let loaded_root = root.clone();
 
-        if self.active_image_tab_id == Some(image_id) {
-            self.active_image_tab_id = None;
-            if let Some(target_id) = activation_target {
-                match target_id {
-                    TabId::Image(image_id) => self.switch_to_image_tab(image_id, cx),
-                    TabId::Document(doc_id) => self.switch_to_tab_document(doc_id, cx),
+        cx.spawn(async move |this, cx| {
+            let result = runtime_handle
+                .spawn(async move {
+                    match workspace_backend.identity() {
+                        WorkspaceIdentity::Local => environment_inspector::load_local_environment(
+                            &project_environment,
+                            &root,
+                            recapture,
+                        )
+                        .await
+                        .map_err(|error| anyhow::anyhow!("{error}")),
+                        // The remote host owns its environment cache, so this
+                        // shows its current capture.
+                        WorkspaceIdentity::Remote(_) => workspace_backend
+                            .project_environment(&root)
+                            .await
+                            .map_err(|error| anyhow::anyhow!("{error}")),
+                    }
+                })
+                .await;
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            this.update(cx, |workspace, cx| {
+                let capture = match result {
+                    Ok(Ok(capture)) => capture,
+                    Ok(Err(error)) => {
+                        workspace.push_editor_status_notification(
+                            EditorStatus {
+                                status: format!("Could not load project environment: {error}"),
+                                severity: Severity::Error,
+                            },
+                            cx,
+                        );
+                        return;
+                    }
+                    Err(error) => {
+                        warn!(%error, "Environment inspector task failed");
+                        return;
+                    }
+                };
+
+                let history = environment_inspector::EnvironmentCaptureHistory::record(
+                    workspace.environment_captures.remove(&loaded_root),
+                    capture,
+                );
+                let items = environment_inspector::environment_inspector_items(&history);
+                let capture = history.current.clone();
+                workspace
+                    .environment_captures
+                    .insert(loaded_root.clone(), history);
+                if recapture {
+                    workspace.push_editor_status_notification(
+                        EditorStatus {
+                            status: "Environment re-captured; restart language servers and terminals to use it".to_string(),
+                            severity: Severity::Info,
+                        },
+                        cx,
+                    );
+                    workspace.refresh_environment_badge(Some(loaded_root.clone()), cx);
                 }
-            }
-        }
 
-        cx.notify();
+                let picker = crate::picker::Picker::native(
+                    format!("Environment: {}", loaded_root.display()),
+                    items,
+                    |_index| {
+                        // Re-capture is handled by OverlayView via EnvironmentRecapture payloads.
+                    },
+                )
+                .with_preview(true)
+                .with_preview_text_provider_fn(move |item, _cx| {
+                    environment_inspector::environment_inspector_preview(item, &capture)
+                        .map(|text| (text, None))
+                });
+                emit_picker_update(picker, &workspace.overlay, cx);
+            });
+        })
+        .detach();
     }
 
-    fn close_single_tab_document_with_activation_target(
-        &mut self,
-        doc_id: DocumentId,
-        activation_target: Option<TabId>,
-        force: bool,
-        cx: &mut Context<Self>,
-    ) {
-        let handle = self.handle.clone();
-        let (closed, close_status, modified_name) = self.core.update(cx, |core, cx| {
-            let _guard = handle.enter();
+    fn show_lsp_traffic(&mut self, cx: &mut Context<Self>) {
+        let log_dir = crate::lsp_traffic_logger::inspector_log_directory();
+        let inspector_enabled = self.core.read(cx).config.gui.lsp.traffic_inspector;
+        let runtime_handle = self.handle.clone();
 
-            match core.editor.close_document(doc_id, force) {
-                Ok(()) => {
-                    if let Some(TabId::Document(target_doc_id)) = activation_target
-                        && core.editor.documents.contains_key(&target_doc_id)
-                    {
-                        core.editor
-                            .switch(target_doc_id, helix_view::editor::Action::Replace);
+        cx.spawn(async move |this, cx| {
+            let result = runtime_handle
+                .spawn_blocking(move || lsp_traffic::load_lsp_traffic_logs(&log_dir))
+                .await;
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            this.update(cx, |workspace, cx| {
+                let logs = match result {
+                    Ok(Ok(logs)) => logs,
+                    Ok(Err(error)) => {
+                        warn!(%error, "Failed to read LSP traffic logs");
+                        workspace.push_editor_status_notification(
+                            EditorStatus {
+                                status: format!("Could not read LSP traffic logs: {error}"),
+                                severity: Severity::Error,
+                            },
+                            cx,
+                        );
+                        return;
                     }
-                    cx.emit(crate::Update::Redraw);
-                    cx.notify();
-                    (true, None, None)
-                }
-                Err(helix_view::editor::CloseError::BufferModified(name)) => {
-                    info!("Cannot close document {:?}: has unsaved changes", doc_id);
-                    if force {
-                        (
-                            false,
-                            Some(unsaved_buffers_remaining_status(vec![name])),
-                            None,
-                        )
-                    } else {
-                        (false, None, Some(name))
+                    Err(error) => {
+                        warn!(%error, "LSP traffic log task failed");
+                        return;
                     }
-                }
-                Err(error @ helix_view::editor::CloseError::DoesNotExist) => {
-                    info!("Document {:?} does not exist", doc_id);
-                    (false, Some(close_error_status(error)), None)
-                }
-                Err(error @ helix_view::editor::CloseError::SaveError(_)) => {
-                    info!("Failed to close document {:?}", doc_id);
-                    (false, Some(close_error_status(error)), None)
-                }
-            }
-        });
+                };
 
-        if let Some(status) = close_status {
-            self.push_editor_status_notification(status, cx);
-        }
+                if logs.is_empty() {
+                    let status = if inspector_enabled {
+                        "No LSP traffic recorded yet"
+                    } else {
+                        "Set traffic_inspector = true under [lsp] to record LSP traffic"
+                    };
+                    workspace.push_editor_status_notification(
+                        EditorStatus {
+                            status: status.to_string(),
+                            severity: Severity::Info,
+                        },
+                        cx,
+                    );
+                    return;
+                }
 
-        if let Some(name) = modified_name {
-            self.request_unsaved_close(
-                PendingUnsavedClose::Single {
-                    doc_id,
-                    activation_target: match activation_target {
-                        Some(TabId::Document(doc_id)) => Some(doc_id),
-                        Some(TabId::Image(_)) | None => None,
+                let picker = crate::picker::Picker::native(
+                    "LSP Traffic",
+                    lsp_traffic::lsp_traffic_picker_items(&logs),
+                    |_index| {
+                        // Selection is handled by OverlayView via GlobalSearchLocation payloads.
                     },
-                },
-                vec![name],
-                cx,
-            );
-            return;
-        }
-
-        if closed {
-            self.loading_documents.remove(&doc_id);
-            if activation_target.is_some() {
-                self.allow_tab_bar_auto_scroll();
-            }
-            if let Some(TabId::Image(image_id)) = activation_target {
-                self.switch_to_image_tab(image_id, cx);
-            }
-            self.unregister_preview_document(doc_id, cx);
-            self.update_document_views(cx);
+                )
+                .with_preview(true);
+                emit_picker_update(picker, &workspace.overlay, cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Opens the ctrl-tab switcher, or moves its highlight while it is showing.
+    fn cycle_tab_switcher(&mut self, direction: TabCycleDirection, cx: &mut Context<Self>) {
+        if let Some(switcher) = self.tab_switcher.as_mut() {
+            switcher.step(direction);
             cx.notify();
+            return;
         }
-    }
 
-    fn force_close_single_tab_document(
-        &mut self,
-        doc_id: DocumentId,
-        activation_target: Option<DocumentId>,
-        cx: &mut Context<Self>,
-    ) {
-        self.close_single_tab_document_with_activation_target(
-            doc_id,
-            activation_target.map(TabId::Document),
-            true,
-            cx,
-        );
+        self.context_menus.close_tab_bar_menus(cx);
+        if let Some(tab_id) = self.active_tab_doc_id(cx) {
+            self.tab_mru.record(tab_id);
+        }
+        let entries = self.mru_tab_order(cx);
+        self.tab_switcher = TabSwitcher::open(entries, direction);
+        cx.notify();
     }
 
-    fn force_close_tab_documents(
-        &mut self,
-        doc_ids: impl IntoIterator<Item = DocumentId>,
-        cx: &mut Context<Self>,
-    ) {
-        self.close_tab_documents_with_force(doc_ids, true, cx);
-    }
+    fn commit_tab_switcher(&mut self, cx: &mut Context<Self>) {
+        let Some(switcher) = self.tab_switcher.take() else {
+            return;
+        };
+        cx.notify();
 
-    fn unregister_preview_document(&mut self, doc_id: DocumentId, cx: &mut Context<Self>) {
-        if let Some(tracker) = cx.try_global::<nucleotide_core::preview_tracker::PreviewTracker>() {
-            tracker.unregister_doc(doc_id);
-            self.invalidate_tab_bar_documents();
+        let Some(tab_id) = switcher.selected() else {
+            return;
+        };
+        if self.active_tab_doc_id(cx) != Some(tab_id) {
+            self.tab_mru_cycle.reset();
+            self.activate_tab(tab_id, cx);
         }
     }
 
-    fn unregister_preview_documents(
-        &mut self,
-        doc_ids: impl IntoIterator<Item = DocumentId>,
-        cx: &mut Context<Self>,
-    ) {
-        if let Some(tracker) = cx.try_global::<nucleotide_core::preview_tracker::PreviewTracker>() {
-            for doc_id in doc_ids {
-                tracker.unregister_doc(doc_id);
-            }
-            self.invalidate_tab_bar_documents();
+    /// Keys pressed while the switcher is showing never reach the editor.
+    fn handle_tab_switcher_key(&mut self, ev: &KeyDownEvent, cx: &mut Context<Self>) -> bool {
+        if self.tab_switcher.is_none() {
+            return false;
         }
-    }
 
-    fn clear_preview_documents(&mut self, cx: &mut Context<Self>) {
-        if let Some(tracker) = cx.try_global::<nucleotide_core::preview_tracker::PreviewTracker>() {
-            tracker.clear();
-            self.invalidate_tab_bar_documents();
+        match ev.keystroke.key.as_str() {
+            "tab" if ev.keystroke.modifiers.shift => {
+                self.cycle_tab_switcher(TabCycleDirection::Previous, cx)
+            }
+            "tab" | "down" => self.cycle_tab_switcher(TabCycleDirection::Next, cx),
+            "up" => self.cycle_tab_switcher(TabCycleDirection::Previous, cx),
+            "enter" => self.commit_tab_switcher(cx),
+            "escape" => {
+                self.tab_switcher = None;
+                cx.notify();
+            }
+            _ => {}
         }
+        true
     }
 
-    fn allow_tab_bar_auto_scroll(&mut self) {
-        self.suppress_tab_bar_auto_scroll = false;
-    }
-
-    fn active_document_and_view(&self, cx: &mut Context<Self>) -> Option<(DocumentId, ViewId)> {
+    fn render_tab_switcher(&self, cx: &mut Context<Self>) -> Option<gpui::AnyElement> {
+        let switcher = self.tab_switcher.as_ref()?;
+        let ui_theme = cx.global::<nucleotide_ui::Theme>();
+        let sizes = &ui_theme.tokens.sizes;
+        let dd_tokens = ui_theme.tokens.dropdown_tokens();
+        let preview_font = cx
+            .global::<crate::types::FontSettings>()
+            .fixed_font
+            .family
+            .clone();
         let core = self.core.read(cx);
-        let view_id = self
-            .view_manager
-            .focused_view_id()
-            .filter(|view_id| core.editor.tree.contains(*view_id))
-            .unwrap_or(core.editor.tree.focus);
-        let doc_id = core.editor.tree.try_get(view_id)?.doc;
-        Some((doc_id, view_id))
-    }
+        let project_directory = core.project_directory.as_deref();
 
-    fn active_tab_doc_id(&self, cx: &mut Context<Self>) -> Option<TabId> {
-        self.active_image_tab_id.map(TabId::Image).or_else(|| {
-            self.active_document_and_view(cx)
-                .map(|(doc_id, _)| TabId::Document(doc_id))
-        })
-    }
+        let rows = switcher
+            .entries()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, tab_id)| {
+                let (path, is_modified) = match tab_id {
+                    TabId::Document(doc_id) => {
+                        let doc = core.editor.documents.get(doc_id)?;
+                        (doc.path(), doc.is_modified())
+                    }
+                    TabId::Image(image_id) => {
+                        let tab = self.image_tabs.iter().find(|tab| tab.id == *image_id)?;
+                        (Some(tab.path.as_path()), false)
+                    }
+                };
+                let mut name = path
+                    .and_then(|path| path.file_name())
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "[scratch]".to_string());
+                if is_modified {
+                    name.push_str(" +");
+                }
+                let directory = path
+                    .and_then(Path::parent)
+                    .map(|parent| {
+                        project_directory
+                            .and_then(|root| parent.strip_prefix(root).ok())
+                            .unwrap_or(parent)
+                            .display()
+                            .to_string()
+                    })
+                    .unwrap_or_default();
+                let selected = index == switcher.selected_index();
 
-    fn switch_to_tab_document(&mut self, doc_id: DocumentId, cx: &mut Context<Self>) {
-        self.allow_tab_bar_auto_scroll();
-        self.active_image_tab_id = None;
-        self.invalidate_tab_bar_documents();
-        let handle = self.handle.clone();
-        self.core.update(cx, |core, cx| {
-            let _guard = handle.enter();
-            core.editor
-                .switch(doc_id, helix_view::editor::Action::Replace);
-            cx.emit(crate::Update::Redraw);
-            cx.notify();
-        });
+                Some(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .px(sizes.space_4)
+                        .py(sizes.space_2)
+                        .when(selected, |row| {
+                            row.bg(dd_tokens.item_background_selected)
+                                .text_color(dd_tokens.item_text_selected)
+                        })
+                        .when(!selected, |row| row.text_color(dd_tokens.item_text))
+                        .child(div().text_size(sizes.text_md).truncate().child(name))
+                        .when(!directory.is_empty(), |row| {
+                            row.child(
+                                div()
+                                    .text_size(sizes.text_xs)
+                                    .text_color(dd_tokens.item_text_secondary)
+                                    .truncate()
+                                    .child(directory),
+                            )
+                        }),
+                )
+            })
+            .collect::<Vec<_>>();
 
-        self.update_document_views(cx);
-        cx.notify();
+        let preview_lines = switcher
+            .selected()
+            .and_then(|tab_id| match tab_id {
+                TabId::Document(doc_id) => core.editor.documents.get(&doc_id),
+                TabId::Image(_) => None,
+            })
+            .map(|doc| {
+                let text = doc.text();
+                let cursor_line = doc
+                    .selections()
+                    .values()
+                    .next()
+                    .map(|selection| text.char_to_line(selection.primary().cursor(text.slice(..))))
+                    .unwrap_or(0);
+                let first_line = cursor_line.saturating_sub(TAB_SWITCHER_PREVIEW_LINES / 3);
+                text.lines_at(first_line.min(text.len_lines()))
+                    .take(TAB_SWITCHER_PREVIEW_LINES)
+                    .map(|line| {
+                        line.to_string()
+                            .trim_end_matches(['\n', '\r'])
+                            .replace('\t', "    ")
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .flex()
+                .justify_center()
+                .pt(px(96.0))
+                .child(
+                    div()
+                        .id("tab-switcher")
+                        .flex()
+                        .flex_row()
+                        .w(px(760.0))
+                        .max_h(px(440.0))
+                        .overflow_hidden()
+                        .bg(dd_tokens.container_background)
+                        .border_1()
+                        .border_color(dd_tokens.border)
+                        .rounded(sizes.radius_md)
+                        .shadow(vec![ui_theme.tokens.chrome.shadow_md.to_box_shadow(false)])
+                        .child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .flex_none()
+                                .w(px(260.0))
+                                .py(sizes.space_2)
+                                .border_r_1()
+                                .border_color(dd_tokens.separator)
+                                .children(rows),
+                        )
+                        .child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .flex_1()
+                                .min_w(px(0.0))
+                                .p(sizes.space_4)
+                                .overflow_hidden()
+                                .font_family(preview_font)
+                                .text_size(sizes.text_sm)
+                                .text_color(dd_tokens.item_text_secondary)
+                                .children(preview_lines.into_iter().map(|line| {
+                                    div().whitespace_nowrap().overflow_hidden().child(line)
+                                })),
+                        ),
+                )
+                .into_any_element(),
+        )
     }
 
     fn replace_preview_tab_document(
@@ -4984,19 +8430,67 @@ impl Workspace {
         } else {
             parent.to_path_buf()
         };
+        Some(self.terminal_directory_for_path(&directory, cx))
+    }
+
+    /// Maps a workspace directory to a terminal cwd, keeping remote projects
+    /// on their remote host.
+    fn terminal_directory_for_path(&self, directory: &Path, cx: &mut Context<Self>) -> PathBuf {
         let backend_identity = self.core.read(cx).workspace_backend.identity();
-        Some(terminal_directory_for_workspace_path(
-            &directory,
+        terminal_directory_for_workspace_path(
+            directory,
             self.current_project_root.as_deref(),
             &backend_identity,
-        ))
+        )
+    }
+
+    /// Opens the tab menu for `tab_id` at `position`.
+    fn open_tab_context_menu(
+        &mut self,
+        tab_id: TabId,
+        position: gpui::Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let model = self.tab_context_menu_model(tab_id, cx);
+        self.context_menus.open_tab(
+            (f32::from(position.x), f32::from(position.y)),
+            tab_id,
+            model,
+            cx,
+        );
+        window.focus(&self.focus_handle, cx);
+        cx.notify();
     }
 
-    fn tab_context_menu_capabilities(&self, cx: &mut Context<Self>) -> TabContextMenuCapabilities {
-        let Some(tab_id) = self.tab_context_menu_doc_id else {
-            return TabContextMenuCapabilities::default();
+    fn tab_context_menu_model(&self, tab_id: TabId, cx: &mut Context<Self>) -> TabContextMenuModel {
+        let visible_tab_ids = self.visible_tab_document_ids(cx);
+        let has_clean_tabs = {
+            let core = self.core.read(cx);
+            visible_tab_ids.iter().any(|tab_id| match tab_id {
+                TabId::Image(_) => true,
+                TabId::Document(doc_id) => core
+                    .editor
+                    .documents
+                    .get(doc_id)
+                    .is_some_and(|doc| !doc.is_modified()),
+            })
         };
 
+        TabContextMenuModel {
+            capabilities: self.tab_context_menu_capabilities(tab_id, cx),
+            target_index: visible_tab_ids.iter().position(|id| *id == tab_id),
+            visible_tabs: visible_tab_ids.len(),
+            has_clean_tabs,
+            target_is_pinned: self.pinned_documents.contains(&tab_id),
+        }
+    }
+
+    fn tab_context_menu_capabilities(
+        &self,
+        tab_id: TabId,
+        cx: &mut Context<Self>,
+    ) -> TabContextMenuCapabilities {
         let tab_path = self.tab_document_path(tab_id, cx);
         let is_readonly = match tab_id {
             TabId::Image(_) => false,
@@ -5025,19 +8519,6 @@ impl Workspace {
             .is_some_and(|file_tree| file_tree.read(cx).contains_path(path))
     }
 
-    fn start_rename_file(&mut self, path: PathBuf, was_dir: bool, cx: &mut Context<Self>) {
-        let current_name = path
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("")
-            .to_string();
-        self.pending_file_op = Some(PendingFileOp::Rename { path, was_dir });
-        self.core.update(cx, move |_core, cx| {
-            let prompt = crate::prompt::Prompt::native("Rename to", current_name, |_input| {});
-            cx.emit(crate::Update::Prompt(prompt));
-        });
-    }
-
     fn relative_tab_path_text(&self, path: &Path) -> String {
         if let Some(root) = &self.current_project_root
             && let Ok(relative_path) = path.strip_prefix(root)
@@ -5083,6 +8564,12 @@ impl Workspace {
         }
     }
 
+    fn tab_cm_action_show_file_history(&mut self, tab_id: TabId, cx: &mut Context<Self>) {
+        if let Some(path) = self.tab_document_path(tab_id, cx) {
+            self.show_git_log(Some(path), cx);
+        }
+    }
+
     fn tab_cm_action_reveal_in_os(&mut self, tab_id: TabId, cx: &mut Context<Self>) {
         if let Some(path) = self.tab_document_path(tab_id, cx) {
             if self.warn_reveal_in_os_unavailable_for_remote(&path, cx) {
@@ -5104,7 +8591,7 @@ impl Workspace {
             return;
         };
 
-        self.show_file_tree = true;
+        self.panels.show_file_tree();
         file_tree.update(cx, |tree, cx| {
             tree.sync_selection_with_file(Some(path.as_path()), cx);
         });
@@ -5155,7 +8642,7 @@ impl Workspace {
         match tab_double_click_plan(path.is_some()) {
             TabDoubleClickPlan::Rename => {
                 if let Some(path) = path {
-                    self.start_rename_file(path, false, cx);
+                    self.file_ops.start_rename(path, false, cx);
                 }
             }
             TabDoubleClickPlan::Activate => match tab_id {
@@ -5282,7 +8769,119 @@ impl Workspace {
     }
 
     fn tab_bar_action_new_terminal(&mut self, cx: &mut Context<Self>) {
-        self.toggle_terminal_panel(cx);
+        let profiles = self.core.read(cx).config.gui.terminal.profiles.clone();
+        if !profiles.is_empty() {
+            self.show_terminal_profile_picker(profiles, cx);
+            return;
+        }
+
+        let strategy = self
+            .core
+            .read(cx)
+            .config
+            .gui
+            .terminal
+            .default_profile()
+            .map(|profile| profile.cwd)
+            .unwrap_or_default();
+        let cwd = self.new_terminal_cwd(strategy, cx);
+        self.open_terminal_panel_at(cwd, cx);
+    }
+
+    /// Directory of the panel terminal as last reported by its shell (OSC 7).
+    fn active_terminal_dir(&self) -> Option<PathBuf> {
+        let model = nucleotide_terminal_view::get_view_model(self.terminal_id?)?;
+        model
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .current_dir()
+    }
+
+    /// Start directory for a new terminal: the active terminal's directory
+    /// unless the profile asks for a specific place.
+    fn new_terminal_cwd(
+        &self,
+        strategy: TerminalCwdStrategy,
+        cx: &mut Context<Self>,
+    ) -> Option<PathBuf> {
+        if strategy == TerminalCwdStrategy::ProjectRoot
+            && let Some(directory) = self.active_terminal_dir()
+        {
+            return Some(directory);
+        }
+        self.terminal_profile_cwd(strategy, cx)
+    }
+
+    fn show_terminal_profile_picker(
+        &mut self,
+        profiles: Vec<TerminalProfileConfig>,
+        cx: &mut Context<Self>,
+    ) {
+        use crate::picker_view::PickerItem;
+
+        let default_shell = self.core.read(cx).config.gui.terminal.shell.clone();
+        let items = profiles
+            .into_iter()
+            .map(|profile| {
+                let shell = profile
+                    .shell
+                    .clone()
+                    .or_else(|| default_shell.clone())
+                    .unwrap_or_else(|| "Login shell".to_string());
+                let command = std::iter::once(shell)
+                    .chain(profile.args.iter().cloned())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                PickerItem {
+                    label: profile.name.clone().into(),
+                    sublabel: Some(command.into()),
+                    data: Arc::new(TerminalProfileChoice { name: profile.name }),
+                    file_path: None,
+                    vcs_status: None,
+                    columns: None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let picker = crate::picker::Picker::native("New Terminal", items, |_| {});
+        emit_picker_update(picker, &self.overlay, cx);
+    }
+
+    /// Replaces the panel terminal with a new session started from `name`.
+    fn open_terminal_profile(&mut self, name: &str, cx: &mut Context<Self>) {
+        let Some(profile) = self
+            .core
+            .read(cx)
+            .config
+            .gui
+            .terminal
+            .profile(name)
+            .cloned()
+        else {
+            self.push_editor_status_notification(
+                EditorStatus {
+                    status: format!("Terminal profile '{name}' is not defined"),
+                    severity: Severity::Warning,
+                },
+                cx,
+            );
+            return;
+        };
+
+        let cwd = self.new_terminal_cwd(profile.cwd, cx);
+        if let Some(existing_id) = self.terminal_id {
+            self.shutdown_terminal_session(existing_id, cx);
+        }
+        let id = self.spawn_terminal_session_with_profile(
+            cwd,
+            Some(profile),
+            Vec::new(),
+            Vec::new(),
+            cx,
+        );
+        self.set_embedded_terminal_panel(id, cx);
+        self.panels.show_terminal();
+        cx.notify();
     }
 
     fn tab_bar_action_new_center_terminal(&mut self, cx: &mut Context<Self>) {
@@ -5381,6 +8980,11 @@ impl Workspace {
 
                 // Check if completion was dismissed and manage context
                 let has_completion = workspace.overlay.read(cx).has_completion();
+                if !has_completion {
+                    // A dismissed popup must not be reopened by a request
+                    // that is still in flight.
+                    workspace.completion.cancel_request();
+                }
                 workspace.manage_completion_context(has_completion);
 
                 cx.notify();
@@ -5435,8 +9039,7 @@ impl Workspace {
             |workspace, _controller, event: &UpdateControllerEvent, cx| {
                 if *event == UpdateControllerEvent::ApplyArmed {
                     info!("Update helper is armed; shutting down for application update");
-                    quit(workspace.core.clone(), workspace.handle.clone(), cx);
-                    cx.quit();
+                    workspace.request_quit(cx);
                 }
             },
         )
@@ -5459,17 +9062,12 @@ impl Workspace {
             )
         };
         let root_path_for_manager = root_path.clone(); // Clone for later use
-
-        // Start VCS monitoring if we have a root path
-        if let Some(root_path) = &root_path {
-            let root_path_clone = root_path.clone();
-            let workspace_backend = vcs_workspace_backend.clone();
-            let vcs_handle = cx.global::<VcsServiceHandle>().service().clone();
-            vcs_handle.update(cx, |service, cx| {
-                service.set_workspace_backend(workspace_backend);
-                service.start_monitoring(root_path_clone, cx);
-            });
-        }
+        // init_editor already merged these into the startup config.
+        let workspace_settings_path = root_path
+            .as_deref()
+            .filter(|_| matches!(vcs_workspace_backend.identity(), WorkspaceIdentity::Local))
+            .map(crate::config::workspace_settings_path)
+            .filter(|path| path.is_file());
 
         let vcs_service = cx.global::<VcsServiceHandle>().service().clone();
         cx.subscribe(&vcs_service, |workspace, _service, event: &VcsEvent, cx| {
@@ -5517,8 +9115,35 @@ impl Workspace {
         let component_gallery = cx.new(nucleotide_ui::ComponentGallery::new);
         let update_dialog = cx.new(|cx| UpdateDialog::new(update_controller.clone(), cx));
 
-        let doc_sidebar_scroll_handle = ScrollHandle::new();
-        let doc_sidebar_scrollbar_state = ScrollbarState::new(doc_sidebar_scroll_handle.clone());
+        let find_bar = cx.new(FindBar::new);
+        cx.subscribe(
+            &find_bar,
+            |workspace, _find_bar, event: &FindBarEvent, cx| {
+                workspace.handle_find_bar_event(*event, cx);
+            },
+        )
+        .detach();
+
+        let right_sidebar = RightSidebar::new(DOC_SIDEBAR_DEFAULT_WIDTH, cx);
+        cx.subscribe(
+            &right_sidebar.regex_tester,
+            |workspace, _regex_tester, event: &RegexTesterEvent, cx| {
+                workspace.handle_regex_tester_event(*event, cx);
+            },
+        )
+        .detach();
+
+        let scratch_buffers = if core.read(cx).config.gui.scratch.persist {
+            match ScratchStore::load_default() {
+                Ok(store) => ScratchBuffers::from_store(store),
+                Err(error) => {
+                    warn!(error = %error, "Could not load saved scratch buffers");
+                    ScratchBuffers::default()
+                }
+            }
+        } else {
+            ScratchBuffers::default()
+        };
 
         let initial_tokens = cx.theme().tokens;
 
@@ -5536,21 +9161,14 @@ impl Workspace {
             last_notified_editor_status: None,
             focus_handle,
             needs_focus_restore: false,
-            statusbar_lsp_focus: cx.focus_handle(),
-            statusbar_file_tree_focus: cx.focus_handle(),
-            statusbar_terminal_focus: cx.focus_handle(),
-            statusbar_lsp_restart_focus: cx.focus_handle(),
-            statusbar_lsp_logs_focus: cx.focus_handle(),
+            statusbar_focus: StatusbarFocus::new(cx),
             file_tree,
-            show_file_tree: true,
-            file_tree_width: FILE_TREE_DEFAULT_WIDTH,
-            file_tree_width_override: None,
-            doc_sidebar_visible: false,
-            doc_sidebar_loading: false,
-            doc_sidebar_entries: Vec::new(),
-            doc_sidebar_width: DOC_SIDEBAR_DEFAULT_WIDTH,
-            doc_sidebar_scroll_handle,
-            doc_sidebar_scrollbar_state,
+            panels: PanelState::default(),
+            right_sidebar,
+            scratch_buffers,
+            comparison: None,
+            content_index: ContentIndexState::default(),
+            content_index_activity: None,
             titlebar: None,
             appearance_observer_set: false,
             needs_appearance_update: false,
@@ -5559,29 +9177,17 @@ impl Workspace {
             tab_bar_scroll_handle: ScrollHandle::new(),
             last_scrolled_tab_doc_id: None,
             suppress_tab_bar_auto_scroll: false,
+            tab_mru_cycle: MruTabCycle::default(),
+            tab_mru: TabMruTracker::default(),
+            tab_switcher: None,
             image_tabs: Vec::new(),
             active_image_tab_id: None,
             next_image_tab_index: 1,
-            file_tree_context_menu: ContextMenuController::new(),
-            file_tree_popup_menu: None,
-            file_tree_popup_menu_subscription: None,
-            context_menu_path: None,
-            context_menu_is_directory: false,
-            tab_context_menu: ContextMenuController::new(),
-            tab_context_popup_menu: None,
-            tab_context_popup_menu_subscription: None,
-            tab_context_menu_doc_id: None,
+            context_menus: ContextMenuState::default(),
+            file_tree_preview: None,
+            file_tree_preview_load: None,
             pinned_documents: HashSet::new(),
-            tab_bar_split_menu: ContextMenuController::new(),
-            tab_bar_split_popup_menu: None,
-            tab_bar_split_popup_menu_subscription: None,
-            tab_bar_split_button_bounds: None,
             split_pane_resize: None,
-            tab_bar_new_menu: ContextMenuController::new(),
-            tab_bar_new_popup_menu: None,
-            tab_bar_new_popup_menu_subscription: None,
-            lsp_menu_open: false,
-            lsp_menu_pos: (0.0, 0.0),
             document_order: Vec::new(),
             loading_documents: HashMap::new(),
             tab_bar_document_generation: 0,
@@ -5590,9 +9196,9 @@ impl Workspace {
             tab_bar_document_cache_misses: 0,
             input_coordinator,
             current_project_root: root_path_for_manager.clone(),
+            workspace_settings_path,
             environment_badge: None,
             _pending_lsp_startup: None,
-            prefix_extractor: PrefixExtractor::new(),
             about_window,
             theme_debug,
             component_gallery,
@@ -5602,24 +9208,29 @@ impl Workspace {
             notified_ready_update_version: None,
             update_restart_confirm_open: false,
             window_was_active: true,
-            pending_file_op: None,
-            needs_file_tree_refresh: false,
-            delete_confirm_open: false,
-            delete_confirm_path: None,
-            delete_confirm_was_directory: false,
-            close_confirm_open: false,
+            file_ops: FileOpsState::new(core.clone(), handle.clone(), cx),
             close_confirm: None,
-            terminal_panel_visible: false,
+            error_reports: VecDeque::new(),
+            find_bar: FindBarState::new(find_bar),
+            live_grep_generation: 0,
             terminal_id: None,
             next_terminal_id: 1,
             next_run_id: 1,
             last_run_task: None,
+            pending_run_tasks: VecDeque::new(),
+            run_problems: Vec::new(),
+            cargo_workspace: None,
             active_run_terminal: None,
             active_run_activity: None,
             run_output_terminal: None,
+            repl_session: None,
+            project_rename: None,
+            disk_changes: HashMap::new(),
             next_background_activity_id: 1,
             background_activities: Vec::new(),
             vcs_refresh_activity: None,
+            git_operation_activity: None,
+            environment_captures: HashMap::new(),
             debug_colors_enabled: matches!(
                 std::env::var("NUCL_DEBUG_COLORS")
                     .map(|v| v.to_ascii_lowercase())
@@ -5627,10 +9238,8 @@ impl Workspace {
                 Ok("1") | Ok("true") | Ok("yes") | Ok("on")
             ),
             // Basic layout is now the default
-            basic_terminal_height: 220.0,
             embedded_terminal_panel: None,
             terminal_cwd: None,
-            terminal_focus_pending: false,
             // Performance cache for editor sizing
             last_editor_size: None,
             last_terminal_bounds: None,
@@ -5642,8 +9251,10 @@ impl Workspace {
             cached_font_metrics_key: None,
             cached_char_width: None,
             cached_line_height: None,
-            active_completion_session: None,
-            completion_memory: CompletionMemory::default(),
+            completion: CompletionState::default(),
+            focus_debugger_refresh: None,
+            shutdown: None,
+            first_frame_pending: true,
             last_native_window_metadata: None,
             pending_remote_open: None,
             remote_open_generation: 0,
@@ -5669,6 +9280,15 @@ impl Workspace {
         // Setup LSP state subscription for project status updates
         workspace.setup_lsp_state_subscription(cx);
 
+        picker_sources::register(cx.weak_entity(), cx);
+
+        // Popups opened elsewhere may displace the workspace's menus and the
+        // overlay's completion list.
+        cx.observe_global::<OverlayManager>(|workspace, cx| {
+            workspace.close_displaced_popups(cx);
+        })
+        .detach();
+
         workspace.refresh_environment_badge(workspace.current_project_root.clone(), cx);
 
         if let Some(project_root) = workspace.current_project_root.clone() {
@@ -5688,19 +9308,7 @@ impl Workspace {
         }
     }
 
-    fn cancel_delete_confirm(&mut self, cx: &mut Context<Self>) {
-        self.delete_confirm_open = false;
-        self.delete_confirm_path = None;
-        self.delete_confirm_was_directory = false;
-        cx.notify();
-    }
-
-    fn confirm_delete_from_dialog(&mut self, cx: &mut Context<Self>) {
-        self.perform_delete_confirm(cx);
-    }
-
     fn clear_unsaved_close_confirm(&mut self, cx: &mut Context<Self>) {
-        self.close_confirm_open = false;
         self.close_confirm = None;
         cx.notify();
     }
@@ -5810,18 +9418,15 @@ impl Workspace {
         cx: &mut Context<Self>,
     ) {
         self.close_confirm = Some(UnsavedCloseConfirmation { action, names });
-        self.close_confirm_open = true;
         cx.notify();
     }
 
     fn perform_pending_unsaved_close(&mut self, cx: &mut Context<Self>) {
         let Some(pending) = self.close_confirm.take() else {
-            self.close_confirm_open = false;
             cx.notify();
             return;
         };
 
-        self.close_confirm_open = false;
         match pending.action {
             PendingUnsavedClose::Single {
                 doc_id,
@@ -5831,40 +9436,6 @@ impl Workspace {
         }
     }
 
-    fn request_delete_path(&mut self, path: PathBuf, was_directory: bool, cx: &mut Context<Self>) {
-        self.delete_confirm_path = Some(path);
-        self.delete_confirm_was_directory = was_directory;
-        let delete_mode = {
-            let core = self.core.read(cx);
-            effective_delete_mode(
-                core.config.gui.file_ops.delete_behavior,
-                &core.workspace_backend.identity(),
-            )
-        };
-        match delete_confirmation_required(delete_mode) {
-            true => {
-                self.delete_confirm_open = true;
-                cx.notify();
-            }
-            false => self.perform_delete_confirm(cx),
-        }
-    }
-
-    fn delete_confirm_dialog(&self, cx: &mut Context<Self>) -> ConfirmDialog {
-        let delete_mode = {
-            let core = self.core.read(cx);
-            effective_delete_mode(
-                core.config.gui.file_ops.delete_behavior,
-                &core.workspace_backend.identity(),
-            )
-        };
-        let message = delete_confirmation_message(self.delete_confirm_path.as_deref(), delete_mode);
-        let confirm_label = delete_confirmation_label(delete_mode);
-
-        ConfirmDialog::new("Confirm Delete", message, confirm_label)
-            .confirm_variant(ButtonVariant::Danger)
-    }
-
     fn unsaved_close_confirm_dialog(&self) -> ConfirmDialog {
         let names = self
             .close_confirm
@@ -5898,13 +9469,6 @@ impl Workspace {
         });
     }
 
-    fn handle_delete_confirm_event(&mut self, event: ConfirmDialogEvent, cx: &mut Context<Self>) {
-        match event {
-            ConfirmDialogEvent::Cancelled => self.cancel_delete_confirm(cx),
-            ConfirmDialogEvent::Confirmed => self.confirm_delete_from_dialog(cx),
-        }
-    }
-
     fn handle_unsaved_close_confirm_event(
         &mut self,
         event: ConfirmDialogEvent,
@@ -5916,6 +9480,43 @@ impl Workspace {
         }
     }
 
+    /// Queues `report` to be shown in the error dialog.
+    pub fn report_error(&mut self, report: ErrorReport, cx: &mut Context<Self>) {
+        error!(
+            summary = %report.summary,
+            location = ?report.location,
+            "Showing error report"
+        );
+        self.error_reports.push_back(report);
+        cx.notify();
+    }
+
+    fn error_report_dialog(report: &ErrorReport) -> ConfirmDialog {
+        ConfirmDialog::new(
+            report.dialog_title(),
+            report.dialog_message(),
+            "Copy Issue Report",
+        )
+        .cancel_label("Dismiss")
+    }
+
+    fn handle_error_report_event(&mut self, event: ConfirmDialogEvent, cx: &mut Context<Self>) {
+        let Some(report) = self.error_reports.pop_front() else {
+            return;
+        };
+        if event == ConfirmDialogEvent::Confirmed {
+            cx.write_to_clipboard(gpui::ClipboardItem::new_string(report.issue_body()));
+            self.push_editor_status_notification(
+                EditorStatus {
+                    status: "Issue report copied to the clipboard".to_string(),
+                    severity: Severity::Info,
+                },
+                cx,
+            );
+        }
+        cx.notify();
+    }
+
     fn sync_confirmation_modal(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if self.modal_layer.read(cx).has_active_modal() {
             return;
@@ -5929,15 +9530,10 @@ impl Workspace {
                 cx,
                 Workspace::handle_update_restart_confirm_event,
             );
-        } else if self.delete_confirm_open {
-            let dialog = self.delete_confirm_dialog(cx);
-            self.show_confirmation_dialog(
-                dialog,
-                window,
-                cx,
-                Workspace::handle_delete_confirm_event,
-            );
-        } else if self.close_confirm_open {
+        } else if self.file_ops.delete_confirm_open() {
+            let dialog = self.file_ops.delete_confirm_dialog(cx);
+            self.show_confirmation_dialog(dialog, window, cx, handle_delete_confirm_event);
+        } else if self.close_confirm.is_some() {
             let dialog = self.unsaved_close_confirm_dialog();
             self.show_confirmation_dialog(
                 dialog,
@@ -5945,587 +9541,372 @@ impl Workspace {
                 cx,
                 Workspace::handle_unsaved_close_confirm_event,
             );
+        } else if let Some(report) = self.error_reports.front() {
+            let dialog = Self::error_report_dialog(report);
+            self.show_confirmation_dialog(dialog, window, cx, Workspace::handle_error_report_event);
         }
     }
 
-    fn render_documentation_sidebar(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
-        let tokens = &cx.theme().tokens;
-        let gui_config = &self.core.read(cx).config.gui;
-        let file_tree_tokens = file_tree_tokens_for_gui_config(tokens, gui_config);
-        let markdown_style = MarkdownStyle::from_tokens(tokens).compact();
+    /// Closes the menus and completion list the overlay manager no longer
+    /// has open, once another popup displaced them or a dismissal closed
+    /// them. Returns whether any closed.
+    fn close_displaced_popups(&mut self, cx: &mut Context<Self>) -> bool {
+        let mut closed = self.context_menus.close_displaced(cx);
+        if self
+            .overlay
+            .update(cx, |overlay, cx| overlay.close_displaced_completion(cx))
+        {
+            self.completion.cancel_request();
+            self.manage_completion_context(false);
+            closed = true;
+        }
+        if closed {
+            cx.notify();
+        }
+        closed
+    }
 
-        let mut body = div()
-            .id("documentation-sidebar-body")
-            .flex()
-            .flex_col()
-            .size_full()
-            .min_h(px(0.0))
-            .overflow_y_scroll()
-            .track_scroll(&self.doc_sidebar_scroll_handle)
-            .px(tokens.sizes.space_3)
-            .py(tokens.sizes.space_3)
-            .gap(tokens.sizes.space_4);
-
-        if self.doc_sidebar_loading {
-            body = body.child(
-                StateView::new("documentation-sidebar-loading", "Loading documentation")
-                    .loading(true)
-                    .compact(true),
-            );
-        } else if self.doc_sidebar_entries.is_empty() {
-            body = body.child(
-                StateView::new("documentation-sidebar-empty", "No documentation available")
-                    .detail("Move the cursor to a symbol with hover documentation.")
-                    .icon("icons/book-text.svg")
-                    .compact(true),
-            );
-        } else {
-            for (index, entry) in self.doc_sidebar_entries.iter().enumerate() {
-                body = body.child(
-                    div()
-                        .flex()
-                        .flex_col()
-                        .gap(tokens.sizes.space_2)
-                        .when(index > 0, |section| {
-                            section
-                                .border_t_1()
-                                .border_color(file_tree_tokens.separator)
-                                .pt(tokens.sizes.space_4)
-                        })
-                        .child(
-                            div()
-                                .text_xs()
-                                .font_weight(FontWeight::MEDIUM)
-                                .text_color(file_tree_tokens.item_text_secondary)
-                                .child(entry.server_name.clone()),
-                        )
-                        .child(markdown_extended(
-                            entry.markdown.clone(),
-                            markdown_style.clone(),
-                        )),
-                );
-            }
+    /// Escape closes the topmost popup that allows it. Menus and modals
+    /// handle their own keys while focused, so this reaches editor popups.
+    fn dismiss_popup_on_escape(&mut self, ev: &KeyDownEvent, cx: &mut Context<Self>) -> bool {
+        if ev.keystroke.key != "escape" || ev.keystroke.modifiers.modified() {
+            return false;
+        }
+        if OverlayManager::dismiss_global(Dismissal::Escape, cx).is_empty() {
+            return false;
         }
+        self.close_displaced_popups(cx)
+    }
 
-        let body_container = div()
-            .relative()
-            .flex_1()
-            .w_full()
-            .min_h(px(0.0))
-            .overflow_hidden()
-            .child(div().size_full().min_h(px(0.0)).child(body))
-            .when_some(
-                Scrollbar::vertical(self.doc_sidebar_scrollbar_state.clone()),
-                |container, scrollbar| {
-                    container.child(
-                        div()
-                            .id("documentation-sidebar-scrollbar")
-                            .absolute()
-                            .top_0()
-                            .right_0()
-                            .bottom_0()
-                            .w(SCROLLBAR_THICKNESS)
-                            .child(scrollbar),
-                    )
-                },
-            );
+    /// Completion latency against its budget, shown over the editor's top-right corner.
+    fn render_performance_hud(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut lines = cx
+            .default_global::<nucleotide_ui::CompletionBudget>()
+            .hud_lines();
+        lines.extend(
+            cx.default_global::<nucleotide_ui::InputLatencyTracker>()
+                .hud_lines(),
+        );
+        let font = cx
+            .global::<crate::types::FontSettings>()
+            .fixed_font
+            .family
+            .clone();
+        let tokens = cx.theme().tokens;
 
         div()
-            .id("documentation-sidebar")
-            .w(px(self.doc_sidebar_width))
-            .h_full()
-            .flex_shrink_0()
-            .min_h(px(0.0))
+            .id("performance-hud")
+            .absolute()
+            .top(tokens.sizes.space_2)
+            .right(tokens.sizes.space_2)
             .flex()
             .flex_col()
-            .overflow_hidden()
-            .bg(file_tree_tokens.background)
-            .border_l_1()
-            .border_color(file_tree_tokens.border)
-            .text_color(file_tree_tokens.item_text)
-            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
-            .child(
-                div()
-                    .h(tokens.sizes.space_8)
-                    .flex()
-                    .items_center()
-                    .justify_between()
-                    .px(tokens.sizes.space_3)
-                    .border_b_1()
-                    .border_color(file_tree_tokens.separator)
-                    .child(
-                        div()
-                            .flex()
-                            .items_center()
-                            .gap(tokens.sizes.space_2)
-                            .text_sm()
-                            .font_weight(FontWeight::MEDIUM)
-                            .text_color(file_tree_tokens.item_text)
-                            .child(
-                                svg()
-                                    .path("icons/book-text.svg")
-                                    .size(px(14.0))
-                                    .text_color(file_tree_tokens.item_text)
-                                    .flex_shrink_0(),
-                            )
-                            .child("Documentation"),
-                    )
-                    .child(
-                        div()
-                            .id("documentation-sidebar-close")
-                            .size(tokens.sizes.space_6)
-                            .flex()
-                            .items_center()
-                            .justify_center()
-                            .rounded(tokens.sizes.radius_sm)
-                            .cursor_pointer()
-                            .hover(move |button| button.bg(file_tree_tokens.item_background_hover))
-                            .on_mouse_down(
-                                MouseButton::Left,
-                                cx.listener(|workspace, _event, _window, cx| {
-                                    workspace.close_documentation_sidebar(cx);
-                                    cx.stop_propagation();
-                                }),
-                            )
-                            .child(
-                                svg()
-                                    .path("icons/close.svg")
-                                    .size(px(12.0))
-                                    .text_color(file_tree_tokens.item_text_secondary),
-                            ),
-                    ),
+            .p(tokens.sizes.space_2)
+            .bg(tokens.chrome.popup_background)
+            .border_1()
+            .border_color(tokens.chrome.popup_border)
+            .rounded(tokens.sizes.radius_md)
+            .font_family(font)
+            .text_size(tokens.sizes.text_xs)
+            .text_color(tokens.chrome.popup_foreground)
+            .children(
+                lines
+                    .into_iter()
+                    .map(|line| div().whitespace_nowrap().child(line)),
             )
-            .child(body_container)
-            .into_any_element()
-    }
-
-    /// Execute the delete after confirmation
-    fn perform_delete_confirm(&mut self, cx: &mut Context<Self>) {
-        if let Some(path) = self.delete_confirm_path.clone() {
-            let was_dir = self.delete_confirm_was_directory;
-            let mode = {
-                let core = self.core.read(cx);
-                effective_delete_mode(
-                    core.config.gui.file_ops.delete_behavior,
-                    &core.workspace_backend.identity(),
-                )
-            };
-            let event = nucleotide_events::v2::workspace::Event::FileOpRequested {
-                intent: nucleotide_events::v2::workspace::FileOpIntent::Delete {
-                    path: path.clone(),
-                    mode,
-                },
-            };
-            self.dispatch_workspace_file_op_and_process(event, cx);
-            let notification = LspFileOperationNotification::Deleted {
-                path: path.clone(),
-                was_dir,
-            };
-            self.observe_file_operation_completion(
-                notification,
-                path.parent().map(|parent| parent.to_path_buf()),
-                cx,
-            );
-        }
-        self.delete_confirm_open = false;
-        self.delete_confirm_path = None;
-        self.delete_confirm_was_directory = false;
-        cx.notify();
-    }
-
-    fn build_file_tree_popup_menu(
-        &mut self,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) -> Entity<PopupMenu> {
-        if let Some(menu) = self.file_tree_popup_menu.clone() {
-            return menu;
-        }
-
-        let action_context = self.focus_handle.clone();
-        let menu = PopupMenu::build(window, cx, move |mut menu, _window, _cx| {
-            menu = menu.action_context(action_context);
-            for intent in Workspace::context_menu_intents().iter().copied() {
-                menu = menu.menu(
-                    intent.label(),
-                    Box::new(crate::actions::project_tree::Operation { intent }),
-                );
-            }
-            menu
-        });
-
-        self.file_tree_popup_menu_subscription = Some(cx.subscribe(
-            &menu,
-            |workspace, _menu, _event: &DismissEvent, cx| {
-                workspace.close_file_tree_context_menu(cx);
-            },
-        ));
-        self.file_tree_popup_menu = Some(menu.clone());
-        menu
-    }
-
-    /// Render the file tree context menu anchored at the last click position.
-    fn render_file_tree_context_menu(
-        &mut self,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) -> gpui::AnyElement {
-        let menu = self.build_file_tree_popup_menu(window, cx);
-        let menu_focus = menu.read(cx).focus_handle(cx);
-        if !menu_focus.contains_focused(window, cx) {
-            menu_focus.focus(window, cx);
-        }
-
-        let (x, y) = self.file_tree_context_menu.position();
-
-        PopupMenuSurface::new(menu)
-            .position(point(px(x), px(y)))
-            .anchor(Anchor::TopLeft)
-            .offset(point(px(8.0), px(8.0)))
-            .on_light_dismiss(cx.listener(|workspace, _event, window, cx| {
-                workspace.dismiss_file_tree_context_menu(window, cx);
-            }))
-            .into_any_element()
-    }
-
-    fn render_popup_menu_backdrop(
-        menu: Entity<PopupMenu>,
-        position: (f32, f32),
-        anchor: Anchor,
-        offset: (f32, f32),
-        cx: &mut Context<Self>,
-        dismiss: fn(&mut Workspace, &mut Window, &mut Context<Workspace>),
-    ) -> gpui::AnyElement {
-        let (x, y) = position;
-        let (offset_x, offset_y) = offset;
-
-        PopupMenuSurface::new(menu)
-            .position(point(px(x), px(y)))
-            .anchor(anchor)
-            .offset(point(px(offset_x), px(offset_y)))
-            .on_light_dismiss(cx.listener(move |workspace, _event, window, cx| {
-                dismiss(workspace, window, cx);
-            }))
-            .into_any_element()
     }
 
-    fn build_tab_context_popup_menu(
-        &mut self,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) -> Entity<PopupMenu> {
-        if let Some(menu) = self.tab_context_popup_menu.clone() {
-            return menu;
-        }
-
-        let visible_doc_ids = self.visible_tab_document_ids(cx);
-        let target_doc_id = self.tab_context_menu_doc_id;
-        let target_index =
-            target_doc_id.and_then(|doc_id| visible_doc_ids.iter().position(|id| *id == doc_id));
-        let menu_capabilities = self.tab_context_menu_capabilities(cx);
-        let has_clean_items = {
-            let core = self.core.read(cx);
-            visible_doc_ids.iter().any(|tab_id| match tab_id {
-                TabId::Image(_) => true,
-                TabId::Document(doc_id) => core
-                    .editor
-                    .documents
-                    .get(doc_id)
-                    .is_some_and(|doc| !doc.is_modified()),
-            })
-        };
-        let target_is_pinned = self
-            .tab_context_menu_doc_id
-            .is_some_and(|doc_id| self.pinned_documents.contains(&doc_id));
-        let entries = Self::tab_context_menu_entries(
-            menu_capabilities.has_file_path,
-            menu_capabilities.has_project_panel_path,
-            menu_capabilities.has_terminal_directory,
-        );
-        let visible_doc_count = visible_doc_ids.len();
-        let action_context = self.focus_handle.clone();
-
-        let menu = PopupMenu::build(window, cx, move |mut menu, _window, _cx| {
-            menu = menu.action_context(action_context);
-            for entry in entries {
-                match entry {
-                    TabContextMenuEntry::Action(intent) => {
-                        let disabled = Self::tab_context_menu_intent_disabled(
-                            intent,
-                            target_index,
-                            visible_doc_count,
-                            has_clean_items,
-                        );
-                        menu = menu.menu_with_check_and_disabled(
-                            intent.label(target_is_pinned, menu_capabilities.is_readonly),
-                            false,
-                            Box::new(crate::actions::tab_menus::ContextOperation { intent }),
-                            disabled,
-                        );
-                    }
-                    TabContextMenuEntry::Separator => {
-                        menu = menu.separator();
+    /// Shows or hides the focus debugger. Focus changes don't notify the
+    /// workspace, so it redraws on a timer while shown.
+    fn toggle_focus_debugger(&mut self, cx: &mut Context<Self>) {
+        if self.focus_debugger_refresh.take().is_none() {
+            self.focus_debugger_refresh = Some(cx.spawn(async move |this, cx| {
+                loop {
+                    cx.background_executor().timer(FOCUS_DEBUGGER_REFRESH).await;
+                    if this.update(cx, |_, cx| cx.notify()).is_err() {
+                        break;
                     }
                 }
-            }
-            menu
-        });
-
-        self.tab_context_popup_menu_subscription = Some(cx.subscribe(
-            &menu,
-            |workspace, _menu, _event: &DismissEvent, cx| {
-                if workspace.close_tab_context_menu() {
-                    cx.notify();
-                }
-            },
-        ));
-        self.tab_context_popup_menu = Some(menu.clone());
-        menu
-    }
-
-    fn render_tab_context_menu(
-        &mut self,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) -> gpui::AnyElement {
-        let menu = self.build_tab_context_popup_menu(window, cx);
-        let menu_focus = menu.read(cx).focus_handle(cx);
-        if !menu_focus.contains_focused(window, cx) {
-            menu_focus.focus(window, cx);
+            }));
         }
-
-        Self::render_popup_menu_backdrop(
-            menu,
-            self.tab_context_menu.position(),
-            Anchor::TopLeft,
-            (0.0, 0.0),
-            cx,
-            Workspace::dismiss_tab_bar_menus,
-        )
+        cx.notify();
     }
 
-    fn build_tab_bar_split_popup_menu(
-        &mut self,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) -> Entity<PopupMenu> {
-        if let Some(menu) = self.tab_bar_split_popup_menu.clone() {
-            return menu;
-        }
-
-        let action_context = self.focus_handle.clone();
-        let menu = PopupMenu::build(window, cx, move |mut menu, _window, _cx| {
-            menu = menu.action_context(action_context);
-            for intent in Self::tab_bar_split_menu_intents().iter().copied() {
-                menu = menu.menu(
-                    intent.label(),
-                    Box::new(crate::actions::tab_menus::SplitOperation { intent }),
-                );
-            }
-            menu
-        });
-
-        self.tab_bar_split_popup_menu_subscription = Some(cx.subscribe(
-            &menu,
-            |workspace, _menu, _event: &DismissEvent, cx| {
-                if workspace.close_tab_bar_split_menu() {
-                    cx.notify();
-                }
-            },
-        ));
-        self.tab_bar_split_popup_menu = Some(menu.clone());
-        menu
-    }
+    /// Where keyboard focus is and how the input context got there, for
+    /// the focus debugger.
+    fn focus_snapshot(&self, window: &Window, cx: &App) -> FocusSnapshot {
+        let roles = cx
+            .try_global::<nucleotide_ui::FocusCoordinator>()
+            .map(|coord| coord.registered_roles())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(role, handle)| {
+                let focus = if handle.is_focused(window) {
+                    RoleFocus::Focused
+                } else if handle.contains_focused(window, cx) {
+                    RoleFocus::ContainsFocus
+                } else {
+                    RoleFocus::Unfocused
+                };
+                (role, focus)
+            })
+            .collect();
+        let popups = cx.try_global::<OverlayManager>();
 
-    fn render_tab_bar_split_menu(
-        &mut self,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) -> gpui::AnyElement {
-        let menu = self.build_tab_bar_split_popup_menu(window, cx);
-        let menu_focus = menu.read(cx).focus_handle(cx);
-        if !menu_focus.contains_focused(window, cx) {
-            menu_focus.focus(window, cx);
-        }
+        FocusSnapshot {
+            has_focus: window.focused(cx).is_some(),
+            roles,
+            key_contexts: window
+                .context_stack()
+                .iter()
+                .filter(|context| !context.is_empty())
+                .map(|context| format!("{context:?}"))
+                .collect(),
+            input_context: self.input_coordinator.current_context(),
+            input_context_stack: self.input_coordinator.context_stack(),
+            popups: popups
+                .map(|popups| popups.z_order().map(PopupId::name).collect())
+                .unwrap_or_default(),
+            popup_focus_owner: popups
+                .and_then(|popups| popups.focus_owner())
+                .map(PopupId::name),
+            transitions: self.input_coordinator.recent_transitions(),
+        }
+    }
+
+    /// Focus chain and recent input context changes, shown over the
+    /// editor's bottom-left corner.
+    fn render_focus_debugger(&self, window: &Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let lines = focus_debug_lines(&self.focus_snapshot(window, cx), std::time::Instant::now());
+        let font = cx
+            .global::<crate::types::FontSettings>()
+            .fixed_font
+            .family
+            .clone();
+        let tokens = cx.theme().tokens;
 
-        Self::render_popup_menu_backdrop(
-            menu,
-            self.tab_bar_split_menu.position(),
-            Anchor::TopRight,
-            (0.0, 0.0),
-            cx,
-            Workspace::dismiss_tab_bar_menus,
-        )
+        div()
+            .id("focus-debugger")
+            .absolute()
+            .bottom(tokens.sizes.space_2)
+            .left(tokens.sizes.space_2)
+            .flex()
+            .flex_col()
+            .p(tokens.sizes.space_2)
+            .bg(tokens.chrome.popup_background)
+            .border_1()
+            .border_color(tokens.chrome.popup_border)
+            .rounded(tokens.sizes.radius_md)
+            .font_family(font)
+            .text_size(tokens.sizes.text_xs)
+            .text_color(tokens.chrome.popup_foreground)
+            .children(
+                lines
+                    .into_iter()
+                    .map(|line| div().whitespace_nowrap().child(line)),
+            )
     }
 
-    fn build_tab_bar_new_popup_menu(
-        &mut self,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) -> Entity<PopupMenu> {
-        if let Some(menu) = self.tab_bar_new_popup_menu.clone() {
-            return menu;
-        }
-
-        let action_context = self.focus_handle.clone();
-        let menu = PopupMenu::build(window, cx, move |mut menu, _window, _cx| {
-            menu = menu.action_context(action_context);
-            for entry in Self::tab_bar_new_menu_entries().iter().copied() {
-                match entry {
-                    TabBarNewMenuEntry::Action(intent) => {
-                        menu = menu.menu(
-                            intent.label(),
-                            Box::new(crate::actions::tab_menus::NewOperation { intent }),
-                        );
-                    }
-                    TabBarNewMenuEntry::Separator => {
-                        menu = menu.separator();
-                    }
-                }
+    /// Logs the startup report and starts the services deferred until the
+    /// first frame: the VCS scan and the content index.
+    fn finish_startup(&mut self, cx: &mut Context<Self>) {
+        if cx.has_global::<StartupProfile>() {
+            let profile = cx.global_mut::<StartupProfile>();
+            if profile.finish(std::time::Instant::now()) {
+                info!(report = %profile.report().join("\n"), "Startup report");
             }
-            menu
-        });
-
-        self.tab_bar_new_popup_menu_subscription = Some(cx.subscribe(
-            &menu,
-            |workspace, _menu, _event: &DismissEvent, cx| {
-                if workspace.close_tab_bar_new_menu() {
-                    cx.notify();
-                }
-            },
-        ));
-        self.tab_bar_new_popup_menu = Some(menu.clone());
-        menu
-    }
-
-    fn render_tab_bar_new_menu(
-        &mut self,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) -> gpui::AnyElement {
-        let menu = self.build_tab_bar_new_popup_menu(window, cx);
-        let menu_focus = menu.read(cx).focus_handle(cx);
-        if !menu_focus.contains_focused(window, cx) {
-            menu_focus.focus(window, cx);
         }
 
-        Self::render_popup_menu_backdrop(
-            menu,
-            self.tab_bar_new_menu.position(),
-            Anchor::TopLeft,
-            (8.0, 8.0),
-            cx,
-            Workspace::dismiss_tab_bar_menus,
-        )
+        let (root_path, workspace_backend) = {
+            let core = self.core.read(cx);
+            (
+                core.project_directory.clone(),
+                core.workspace_backend.clone(),
+            )
+        };
+        if let Some(root_path) = root_path {
+            let vcs_handle = cx.global::<VcsServiceHandle>().service().clone();
+            vcs_handle.update(cx, |service, cx| {
+                service.set_workspace_backend(workspace_backend);
+                service.start_monitoring(root_path, cx);
+            });
+        }
+        self.start_content_index(false, cx);
     }
 
-    // --- Context menu helpers ---
-    fn any_tab_bar_menu_open(&self) -> bool {
-        self.tab_context_menu.is_open()
-            || self.tab_bar_split_menu.is_open()
-            || self.tab_bar_new_menu.is_open()
-    }
+    /// Quits once background services have stopped, or after
+    /// [`SHUTDOWN_TIMEOUT`]. Quitting again while they stop exits at once.
+    fn request_quit(&mut self, cx: &mut Context<Self>) {
+        if self.shutdown.take().is_some() {
+            warn!("Quit requested again; exiting without waiting for shutdown");
+            nucleotide_logging::flush_logs();
+            cx.quit();
+            return;
+        }
+        info!("Shutting down background services");
+        self.shutdown = Some(ShutdownProgress::new(
+            std::time::Instant::now(),
+            SHUTDOWN_TIMEOUT,
+        ));
 
-    fn close_tab_context_menu(&mut self) -> bool {
-        let closed = self.tab_context_menu.close();
-        let had_menu = self.tab_context_popup_menu.take().is_some();
-        let had_subscription = self.tab_context_popup_menu_subscription.take().is_some();
-        let had_target = self.tab_context_menu_doc_id.take().is_some();
-        closed || had_menu || had_subscription || had_target
-    }
+        // Ensure editor state is cleanly flushed and views are closed before quit
+        self.persist_scratch_buffers(cx);
+        quit(self.core.clone(), self.handle.clone(), cx);
+        self.finish_shutdown_step(ShutdownStep::Session, cx);
 
-    fn close_tab_bar_split_menu(&mut self) -> bool {
-        let closed = self.tab_bar_split_menu.close();
-        let had_menu = self.tab_bar_split_popup_menu.take().is_some();
-        let had_subscription = self.tab_bar_split_popup_menu_subscription.take().is_some();
-        closed || had_menu || had_subscription
-    }
+        if cx.has_global::<VcsServiceHandle>() {
+            let vcs_handle = cx.global::<VcsServiceHandle>().service().clone();
+            vcs_handle.update(cx, |service, _cx| service.stop_monitoring());
+        }
+        self.finish_shutdown_step(ShutdownStep::VcsWatchers, cx);
 
-    fn close_tab_bar_new_menu(&mut self) -> bool {
-        let closed = self.tab_bar_new_menu.close();
-        let had_menu = self.tab_bar_new_popup_menu.take().is_some();
-        let had_subscription = self.tab_bar_new_popup_menu_subscription.take().is_some();
-        closed || had_menu || had_subscription
-    }
+        let terminals = self.core.read(cx).terminal_runtime.clone();
+        let terminal_shutdown = cx
+            .background_executor()
+            .spawn(async move { terminals.shutdown_all() });
+        cx.spawn(async move |this, cx| {
+            let closed = terminal_shutdown.await;
+            debug!(closed, "Terminal sessions closed");
+            this.update(cx, |workspace, cx| {
+                workspace.finish_shutdown_step(ShutdownStep::Terminals, cx)
+            })
+            .ok();
+        })
+        .detach();
 
-    fn close_tab_bar_menus(&mut self) -> bool {
-        let mut closed = self.close_tab_context_menu();
-        closed |= self.close_tab_bar_split_menu();
-        closed |= self.close_tab_bar_new_menu();
-        closed
+        let handle = self.handle.clone();
+        let (language_servers, project_lsp_manager) = self.core.update(cx, |core, _cx| {
+            (
+                core.shutdown_language_servers(&handle),
+                core.stop_project_lsp_manager(&handle),
+            )
+        });
+        self.await_shutdown_step(ShutdownStep::LanguageServers, Some(language_servers), cx);
+        self.await_shutdown_step(ShutdownStep::ProjectLspManager, project_lsp_manager, cx);
+
+        // Poll so the deadline applies even to a step that never finishes.
+        cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(SHUTDOWN_POLL).await;
+                match this.update(cx, |workspace, cx| workspace.check_shutdown(cx)) {
+                    Ok(false) => {}
+                    Ok(true) | Err(_) => break,
+                }
+            }
+        })
+        .detach();
     }
 
-    fn dismiss_tab_bar_menus(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        if self.close_tab_bar_menus() {
-            window.focus(&self.focus_handle, cx);
-            cx.notify();
-        }
+    fn await_shutdown_step(
+        &mut self,
+        step: ShutdownStep,
+        task: Option<tokio::task::JoinHandle<()>>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(task) = task else {
+            self.finish_shutdown_step(step, cx);
+            return;
+        };
+        cx.spawn(async move |this, cx| {
+            let _ = task.await;
+            this.update(cx, |workspace, cx| workspace.finish_shutdown_step(step, cx))
+                .ok();
+        })
+        .detach();
     }
 
-    fn close_file_tree_context_menu(&mut self, cx: &mut Context<Self>) -> bool {
-        let closed = self.file_tree_context_menu.close();
-        let had_menu = self.file_tree_popup_menu.take().is_some();
-        let had_subscription = self.file_tree_popup_menu_subscription.take().is_some();
-        if closed || had_menu || had_subscription {
-            cx.notify();
-            true
-        } else {
-            false
+    fn finish_shutdown_step(&mut self, step: ShutdownStep, cx: &mut Context<Self>) {
+        if let Some(progress) = &mut self.shutdown {
+            progress.finish(step);
         }
+        self.check_shutdown(cx);
     }
 
-    fn dismiss_file_tree_context_menu(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        if !self.close_file_tree_context_menu(cx) {
-            return;
+    /// Exits once the quit in progress is done, returning whether no quit
+    /// is left waiting.
+    fn check_shutdown(&mut self, cx: &mut Context<Self>) -> bool {
+        let Some(progress) = &self.shutdown else {
+            return true;
+        };
+        let now = std::time::Instant::now();
+        if !progress.is_done(now) {
+            if progress.shows_progress(now) {
+                cx.notify();
+            }
+            return false;
         }
-
-        if let Some(coord) = cx.try_global::<nucleotide_ui::FocusCoordinator>().cloned() {
-            let _ = coord.focus_first(
-                window,
-                cx,
-                &[
-                    nucleotide_ui::FocusRole::Editor,
-                    nucleotide_ui::FocusRole::FileTree,
-                ],
+        if !progress.pending().is_empty() {
+            warn!(
+                pending = ?progress.pending(),
+                "Shutdown timed out; exiting without waiting"
             );
         }
-        cx.notify();
+        info!("Background services stopped; exiting");
+        self.shutdown = None;
+        nucleotide_logging::flush_logs();
+        cx.quit();
+        true
     }
 
-    fn close_documentation_sidebar(&mut self, cx: &mut Context<Self>) {
-        if self.doc_sidebar_visible || self.doc_sidebar_loading {
-            self.doc_sidebar_visible = false;
-            self.doc_sidebar_loading = false;
-            cx.notify();
-        }
+    fn shutdown_status(&self) -> Option<String> {
+        self.shutdown
+            .as_ref()
+            .filter(|progress| progress.shows_progress(std::time::Instant::now()))
+            .map(ShutdownProgress::status)
     }
 
-    fn toggle_documentation_sidebar(&mut self, cx: &mut Context<Self>) -> bool {
-        if self.doc_sidebar_visible {
-            self.close_documentation_sidebar(cx);
-            return false;
-        }
+    /// What a slow quit is waiting on, shown across the top of the window.
+    fn render_shutdown_progress(status: String, cx: &mut Context<Self>) -> impl IntoElement {
+        let tokens = cx.theme().tokens;
 
-        self.doc_sidebar_visible = true;
-        self.doc_sidebar_loading = true;
-        self.doc_sidebar_entries.clear();
-        cx.notify();
-        true
+        div()
+            .id("shutdown-progress")
+            .absolute()
+            .top(tokens.sizes.space_4)
+            .left_0()
+            .right_0()
+            .flex()
+            .justify_center()
+            .child(
+                div()
+                    .px(tokens.sizes.space_3)
+                    .py(tokens.sizes.space_2)
+                    .bg(tokens.chrome.popup_background)
+                    .border_1()
+                    .border_color(tokens.chrome.popup_border)
+                    .rounded(tokens.sizes.radius_md)
+                    .text_size(tokens.sizes.text_sm)
+                    .text_color(tokens.chrome.popup_foreground)
+                    .child(status),
+            )
     }
 
-    fn set_documentation_sidebar_entries(
+    /// Opens the editor or terminal menu under the pointer. Its items act on
+    /// the view that had focus when it opened.
+    fn open_content_context_menu(
         &mut self,
-        entries: Vec<HoverDocEntry>,
+        target: ContentContextMenuTarget,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if !self.doc_sidebar_visible && !self.doc_sidebar_loading {
-            return;
-        }
+        let has_selection = target == ContentContextMenuTarget::Terminal
+            && self
+                .terminal_id
+                .is_some_and(crate::terminal_input::terminal_has_selection);
+        self.context_menus
+            .open_content(target, has_selection, window, cx);
+    }
 
-        self.doc_sidebar_visible = true;
-        self.doc_sidebar_loading = false;
-        self.doc_sidebar_entries = entries;
-        cx.notify();
+    /// Opens the split menu at `position`, or closes it when already open.
+    fn toggle_tab_bar_split_menu(
+        &mut self,
+        position: gpui::Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let focus = self.focus_handle.clone();
+        self.context_menus
+            .toggle_tab_bar_split(position, &focus, window, cx);
     }
 
     fn finish_active_resize(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -6559,15 +9940,13 @@ impl Workspace {
     }
 
     fn sync_documentation_sidebar_width_for_viewport(&mut self, available_width: f32) {
-        if !self.doc_sidebar_visible {
+        if !self.right_sidebar.is_visible() {
             return;
         }
 
         let width =
-            Self::clamped_documentation_sidebar_width(self.doc_sidebar_width, available_width);
-        if (self.doc_sidebar_width - width).abs() > 0.5 {
-            self.doc_sidebar_width = width;
-        }
+            Self::clamped_documentation_sidebar_width(self.right_sidebar.width, available_width);
+        self.right_sidebar.resize(width);
     }
 
     fn clamped_file_tree_default_width(viewport_width: f32) -> f32 {
@@ -6586,17 +9965,11 @@ impl Workspace {
 
     fn sync_file_tree_width_for_viewport(&mut self, viewport_width: f32) {
         let target_width = self
+            .panels
             .file_tree_width_override
             .unwrap_or(FILE_TREE_DEFAULT_WIDTH);
         let width = Self::clamped_file_tree_sidebar_width(target_width, viewport_width);
-
-        if (self.file_tree_width - width).abs() > 0.5 {
-            self.file_tree_width = width;
-        }
-
-        if let Some(override_width) = &mut self.file_tree_width_override {
-            *override_width = self.file_tree_width;
-        }
+        self.panels.fit_file_tree_width(width);
     }
 
     fn start_split_pane_resize(
@@ -6680,76 +10053,13 @@ impl Workspace {
         cx: &mut Context<Self>,
     ) {
         match intent {
-            ProjectTreeContextMenuIntent::NewFile => {
-                let parent = context_menu_target_parent_path(&path, is_directory);
-                self.pending_file_op = Some(PendingFileOp::NewFile { parent });
-                self.core.update(cx, |_core, cx| {
-                    let prompt = crate::prompt::Prompt::native("New file name", "", |_input| {});
-                    cx.emit(crate::Update::Prompt(prompt));
-                });
+            ProjectTreeContextMenuIntent::ShowHistory => {
+                self.show_git_log(Some(path), cx);
             }
-            ProjectTreeContextMenuIntent::NewFolder => {
-                let parent = context_menu_target_parent_path(&path, is_directory);
-                self.pending_file_op = Some(PendingFileOp::NewFolder { parent });
-                self.core.update(cx, |_core, cx| {
-                    let prompt = crate::prompt::Prompt::native("New folder name", "", |_input| {});
-                    cx.emit(crate::Update::Prompt(prompt));
-                });
-            }
-            ProjectTreeContextMenuIntent::Rename => {
-                self.start_rename_file(path, is_directory, cx);
-            }
-            ProjectTreeContextMenuIntent::Delete => {
-                self.request_delete_path(path, is_directory, cx);
-            }
-            ProjectTreeContextMenuIntent::Duplicate => {
-                let base_name = path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .map(|s| format!("{} copy", s))
-                    .unwrap_or_else(|| "copy".to_string());
-                self.pending_file_op = Some(PendingFileOp::Duplicate {
-                    path,
-                    is_dir: is_directory,
-                });
-                self.core.update(cx, move |_core, cx| {
-                    let prompt =
-                        crate::prompt::Prompt::native("Duplicate as", base_name, |_input| {});
-                    cx.emit(crate::Update::Prompt(prompt));
-                });
-            }
-            ProjectTreeContextMenuIntent::CopyPath => {
-                let text = path.display().to_string();
-                if !Self::copy_to_clipboard_impl(&text) {
-                    nucleotide_logging::warn!(path=%text, "Failed to copy path to clipboard");
-                }
-                let event = nucleotide_events::v2::workspace::Event::FileOpRequested {
-                    intent: nucleotide_events::v2::workspace::FileOpIntent::CopyPath {
-                        path,
-                        kind: nucleotide_events::v2::workspace::PathCopyKind::Absolute,
-                    },
-                };
-                self.core.read(cx).dispatch_workspace_event(event);
-            }
-            ProjectTreeContextMenuIntent::CopyRelativePath => {
-                let text = if let Some(root) = &self.current_project_root {
-                    match path.strip_prefix(root) {
-                        Ok(rel) => rel.display().to_string(),
-                        Err(_) => path.display().to_string(),
-                    }
-                } else {
-                    path.display().to_string()
-                };
-                if !Self::copy_to_clipboard_impl(&text) {
-                    nucleotide_logging::warn!(path=%text, "Failed to copy relative path to clipboard");
-                }
-                let event = nucleotide_events::v2::workspace::Event::FileOpRequested {
-                    intent: nucleotide_events::v2::workspace::FileOpIntent::CopyPath {
-                        path,
-                        kind: nucleotide_events::v2::workspace::PathCopyKind::RelativeToWorkspace,
-                    },
-                };
-                self.core.read(cx).dispatch_workspace_event(event);
+            ProjectTreeContextMenuIntent::OpenTerminalHere => {
+                let directory = context_menu_target_parent_path(&path, is_directory);
+                let cwd = self.terminal_directory_for_path(&directory, cx);
+                self.open_terminal_panel_at(Some(cwd), cx);
             }
             ProjectTreeContextMenuIntent::RevealInOs => {
                 if self.warn_reveal_in_os_unavailable_for_remote(&path, cx) {
@@ -6760,6 +10070,16 @@ impl Workspace {
                 };
                 self.core.read(cx).dispatch_workspace_event(event);
             }
+            intent => {
+                let project_root = self.current_project_root.clone();
+                self.file_ops.handle_project_tree_operation(
+                    intent,
+                    path,
+                    is_directory,
+                    project_root.as_deref(),
+                    cx,
+                );
+            }
         }
     }
 
@@ -6955,8 +10275,12 @@ impl Workspace {
         if self.terminal_is_focused(window, cx) {
             if let Some(panel) = &self.embedded_terminal_panel {
                 let id = panel.read(cx).active;
-                let bytes = crate::terminal_input::encode_key_event_for_terminal(id, ev);
-                crate::terminal_input::send_terminal_input(&self.core, id, bytes, cx);
+                if crate::terminal_input::scroll_terminal_for_key(id, ev) {
+                    cx.notify();
+                } else {
+                    let bytes = crate::terminal_input::encode_key_event_for_terminal(id, ev);
+                    crate::terminal_input::send_terminal_input(&self.core, id, bytes, cx);
+                }
             }
             // Prevent further handling by editor/others
             cx.stop_propagation();
@@ -6968,6 +10292,10 @@ impl Workspace {
             "Workspace received key event"
         );
 
+        if self.dismiss_popup_on_escape(ev, cx) {
+            return;
+        }
+
         // Check if completion is visible and handle navigation/control keys
         if self.overlay.read(cx).has_completion() && self.handle_regular_completion_menu_key(ev, cx)
         {
@@ -6998,7 +10326,10 @@ impl Workspace {
             InputResult::Handled => {
                 debug!("Key event handled by InputCoordinator");
             }
-            InputResult::SendToHelix(helix_key) => {
+            InputResult::SendToHelix {
+                key: helix_key,
+                received_at,
+            } => {
                 if !self.view_manager.is_document_view_focused(cx, window) {
                     self.handle_unfocused_semantic_shortcut(ev, cx);
                     return;
@@ -7011,9 +10342,14 @@ impl Workspace {
                     "Sending key to Helix editor"
                 );
 
-                // Send the key to Helix
+                // Send the key to Helix, tracing it until the document renders
+                let latency_id = cx
+                    .default_global::<nucleotide_ui::InputLatencyTracker>()
+                    .begin(received_at);
                 self.input.update(cx, |_, cx| {
-                    cx.emit(crate::InputEvent::key_down(helix_key, ev.is_held));
+                    cx.emit(crate::InputEvent::traced_key_down(
+                        helix_key, ev.is_held, latency_id,
+                    ));
                 });
 
                 // Extra debug for ctrl-x specifically
@@ -7057,11 +10393,7 @@ impl Workspace {
     ) {
         use crate::types::SemanticShortcutIntent as Intent;
         match intent {
-            Intent::Quit => {
-                let handle = self.handle.clone();
-                quit(self.core.clone(), handle, cx);
-                cx.quit();
-            }
+            Intent::Quit => self.request_quit(cx),
             Intent::OpenFile | Intent::ShowFileFinder => open(
                 self.core.clone(),
                 self.handle.clone(),
@@ -7091,9 +10423,20 @@ impl Workspace {
             Intent::RunLast => self.run_last(cx),
             Intent::RunFileTests => self.run_file_tests(cx),
             Intent::ToggleFileTree => {
-                self.show_file_tree = !self.show_file_tree;
+                self.panels.toggle_file_tree();
                 cx.notify();
             }
+            Intent::ShowAllTabs => self.show_all_tabs(cx),
+            Intent::ActivateNextTab => self.activate_adjacent_tab(TabCycleDirection::Next, cx),
+            Intent::ActivatePreviousTab => {
+                self.activate_adjacent_tab(TabCycleDirection::Previous, cx)
+            }
+            Intent::ActivateLastUsedTab => self.activate_last_used_tab(cx),
+            Intent::ReopenClosedTab => self.reopen_closed_tab(cx),
+            Intent::SwitchRecentDocument => self.cycle_tab_switcher(TabCycleDirection::Next, cx),
+            Intent::SwitchRecentDocumentReverse => {
+                self.cycle_tab_switcher(TabCycleDirection::Previous, cx)
+            }
         }
     }
 
@@ -7110,24 +10453,139 @@ impl Workspace {
         self.titlebar = Some(titlebar);
     }
 
-    fn update_titlebar_filename(
-        &mut self,
-        filename: Option<&str>,
-        notify: bool,
-        cx: &mut Context<Self>,
-    ) {
+    fn update_titlebar_document(&mut self, notify: bool, cx: &mut Context<Self>) {
         let Some(titlebar) = &self.titlebar else {
             return;
         };
 
-        let filename = titlebar_filename(filename);
+        let document = {
+            let core = self.core.read(cx);
+            let (document_path, dirty) = if let Some(tab) = self
+                .active_image_tab_id
+                .and_then(|doc_id| self.image_tabs.iter().find(|tab| tab.id == doc_id))
+            {
+                (Some(tab.path.clone()), false)
+            } else {
+                let editor = &core.editor;
+                editor
+                    .tree
+                    .try_get(editor.tree.focus)
+                    .and_then(|view| editor.document(view.doc))
+                    .map_or((None, false), |doc| {
+                        (doc.path().cloned(), doc.is_modified())
+                    })
+            };
+            titlebar_document(
+                core.project_directory.as_deref(),
+                document_path.as_deref(),
+                dirty,
+            )
+        };
         titlebar.update(cx, |titlebar, cx| {
-            if titlebar.set_filename(filename) && notify {
+            if titlebar.set_document(document) && notify {
                 cx.notify();
             }
         });
     }
 
+    /// What the application menus enable, check and list for this window.
+    fn app_menu_state(&self, window: &Window, cx: &App) -> AppMenuState {
+        let core = self.core.read(cx);
+        let editor = &core.editor;
+        let focused_document = editor
+            .tree
+            .try_get(editor.tree.focus)
+            .and_then(|view| editor.document(view.doc));
+        let root = core.project_directory.as_deref();
+        let (recent_files, recent_projects) = cx
+            .try_global::<RecentFiles>()
+            .map(|recent| {
+                (
+                    recent
+                        .recent(root)
+                        .iter()
+                        .map(|file| file.path.clone())
+                        .take(MAX_MENU_RECENT_FILES)
+                        .collect(),
+                    recent
+                        .project_roots()
+                        .filter(|project| Some(*project) != root)
+                        .map(Path::to_path_buf)
+                        .take(MAX_MENU_RECENT_PROJECTS)
+                        .collect(),
+                )
+            })
+            .unwrap_or_default();
+
+        AppMenuState {
+            has_document: focused_document.is_some(),
+            has_dirty_document: editor.documents.values().any(|doc| doc.is_modified()),
+            file_tree_visible: self.panels.file_tree_visible,
+            terminal_visible: self.panels.terminal_visible,
+            documentation_visible: self.right_sidebar.docs_visible,
+            windows: AppMenus::windows(window.window_handle().window_id().as_u64(), cx),
+            recent_files,
+            recent_projects,
+        }
+    }
+
+    /// Opens `text` sent through the macOS Services menu in a new scratch
+    /// buffer.
+    pub fn open_service_text(&mut self, text: String, cx: &mut Context<Self>) {
+        self.prune_scratch_buffers(cx);
+        let name = self.scratch_buffers.new_name(None);
+        self.create_scratch_document(name, DEFAULT_SCRATCH_LANGUAGE.to_string(), text, cx);
+    }
+
+    /// Shows the Share picker for the focused document.
+    fn share_focused_file(&mut self, cx: &mut Context<Self>) {
+        let path = {
+            let editor = &self.core.read(cx).editor;
+            editor
+                .tree
+                .try_get(editor.tree.focus)
+                .and_then(|view| editor.document(view.doc))
+                .and_then(|doc| doc.path().cloned())
+        };
+        let Some(path) = path else {
+            self.core.update(cx, |core, _| {
+                core.editor.set_error("Save the document before sharing it");
+            });
+            return;
+        };
+
+        #[cfg(target_os = "macos")]
+        crate::macos_services::share_file(&path);
+        #[cfg(not(target_os = "macos"))]
+        {
+            let _ = path;
+            self.core.update(cx, |core, _| {
+                core.editor.set_error("Sharing is only available on macOS");
+            });
+        }
+    }
+
+    /// Selects the focused document in the file tree, showing the tree.
+    fn reveal_active_file_in_tree(&mut self, cx: &mut Context<Self>) {
+        let path = {
+            let editor = &self.core.read(cx).editor;
+            editor
+                .tree
+                .try_get(editor.tree.focus)
+                .and_then(|view| editor.document(view.doc))
+                .and_then(|doc| doc.path().cloned())
+        };
+        let (Some(path), Some(file_tree)) = (path, &self.file_tree) else {
+            return;
+        };
+
+        self.panels.show_file_tree();
+        file_tree.update(cx, |tree, cx| {
+            tree.sync_selection_with_file(Some(path.as_path()), cx);
+        });
+        cx.notify();
+    }
+
     fn update_titlebar_leading_sidebar_background(
         &mut self,
         background: Option<(Pixels, Hsla, Hsla)>,
@@ -7146,10 +10604,7 @@ impl Workspace {
         });
     }
 
-    fn focused_native_window_metadata(
-        &self,
-        cx: &Context<Self>,
-    ) -> (Option<String>, NativeWindowMetadata) {
+    fn focused_native_window_metadata(&self, cx: &Context<Self>) -> NativeWindowMetadata {
         let core = self.core.read(cx);
         let workspace_identity = core.workspace_backend.identity();
 
@@ -7162,27 +10617,26 @@ impl Workspace {
                 .file_name()
                 .and_then(|name| name.to_str())
                 .map(str::to_string);
-            let title = native_window_title(focused_file_name.as_deref());
-            return (
-                focused_file_name,
-                NativeWindowMetadata {
-                    title,
-                    document_path: native_window_document_path(
-                        Some(tab.path.clone()),
-                        &workspace_identity,
-                    ),
-                    edited: core.editor.documents.values().any(|doc| doc.is_modified()),
-                },
-            );
+            return NativeWindowMetadata {
+                title: native_window_title(focused_file_name.as_deref()),
+                document_path: native_window_document_path(
+                    Some(tab.path.clone()),
+                    &workspace_identity,
+                ),
+                edited: false,
+            };
         }
 
         let editor = &core.editor;
         let mut focused_file_name = None;
         let mut focused_doc_path = None;
+        let mut edited = false;
 
         if let Some(view) = editor.tree.try_get(editor.tree.focus)
             && let Some(doc) = editor.document(view.doc)
         {
+            // The proxy icon and edited dot describe the focused document.
+            edited = doc.is_modified();
             focused_doc_path = doc.path().map(std::path::Path::to_path_buf);
             focused_file_name = doc.path().map(|path| {
                 path.file_name()
@@ -7192,28 +10646,30 @@ impl Workspace {
             });
         }
 
-        let edited = editor.documents.values().any(|doc| doc.is_modified());
         let title = native_window_title(focused_file_name.as_deref());
 
-        (
-            focused_file_name,
-            NativeWindowMetadata {
-                title,
-                document_path: native_window_document_path(focused_doc_path, &workspace_identity),
-                edited,
-            },
-        )
+        NativeWindowMetadata {
+            title,
+            document_path: native_window_document_path(focused_doc_path, &workspace_identity),
+            edited,
+        }
     }
 
     fn update_native_window_metadata(
         &mut self,
         window: &mut Window,
         metadata: NativeWindowMetadata,
+        cx: &mut App,
     ) {
         let previous = self.last_native_window_metadata.as_ref();
 
         if previous.is_none_or(|previous| previous.title != metadata.title) {
             window.set_window_title(&metadata.title);
+            AppMenus::set_window_title(
+                window.window_handle().window_id().as_u64(),
+                &metadata.title,
+                cx,
+            );
         }
 
         if previous.is_none_or(|previous| previous.document_path != metadata.document_path) {
@@ -7244,8 +10700,14 @@ impl Workspace {
             "Evaluating project directory change"
         );
 
+        let rust_analyzer_settings = cx
+            .try_global::<CargoSelections>()
+            .and_then(|selections| selections.get(&dir).rust_analyzer_settings());
         self.core.update(cx, |core, _cx| {
             core.project_directory = Some(dir.clone());
+            core.editor
+                .language_servers
+                .set_config_override(RUST_ANALYZER, rust_analyzer_settings);
         });
 
         let workspace_backend = self.core.read(cx).workspace_backend.clone();
@@ -7279,6 +10741,15 @@ impl Workspace {
             self.current_project_root = Some(dir.clone());
             self.refresh_environment_badge(Some(dir.clone()), cx);
 
+            // Swap workspace settings when leaving or entering a project that has them.
+            let has_workspace_settings = self
+                .local_project_root(cx)
+                .is_some_and(|root| crate::config::workspace_settings_path(&root).is_file());
+            if has_workspace_settings || self.workspace_settings_path.is_some() {
+                self.reload_configuration(cx);
+            }
+            self.start_content_index(false, cx);
+
             // Clear visible state immediately. Application owns process teardown
             // and will repopulate state only for the new project generation.
             if let Some(lsp_state_entity) = self.core.read(cx).lsp_state.clone() {
@@ -8332,6 +11803,16 @@ impl Workspace {
         line_change: &nucleotide_events::v2::document::DocumentLineChange,
         cx: &mut Context<Self>,
     ) {
+        if let Some(comparison) = self
+            .comparison
+            .as_ref()
+            .filter(|comparison| comparison.includes(doc_id))
+        {
+            self.core.update(cx, |core, _| {
+                crate::file_compare::sync_diff_bases(&mut core.editor, comparison);
+            });
+        }
+
         let is_modified = self
             .core
             .read(cx)
@@ -8362,10 +11843,40 @@ impl Workspace {
         }
 
         self.invalidate_document_view_metrics(doc_id, line_change, cx);
+        self.schedule_vcs_diff_update(doc_id, line_change, cx);
         self.update_specific_document_view(doc_id, cx);
         cx.notify();
     }
 
+    fn schedule_vcs_diff_update(
+        &self,
+        doc_id: helix_view::DocumentId,
+        line_change: &nucleotide_events::v2::document::DocumentLineChange,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(vcs_service) = cx
+            .try_global::<VcsServiceHandle>()
+            .map(|handle| handle.service().clone())
+        else {
+            return;
+        };
+        let Some((path, text)) = self.core.read(cx).editor.document(doc_id).and_then(|doc| {
+            doc.path()
+                .map(|path| (path.to_path_buf(), doc.text().clone()))
+        }) else {
+            return;
+        };
+        let line = |line: usize| u32::try_from(line).unwrap_or(u32::MAX);
+        let edit = nucleotide_vcs::DiffEdit::new(
+            line(line_change.old_lines.start)..line(line_change.old_lines.end),
+            line(line_change.new_lines.start)..line(line_change.new_lines.end),
+        );
+
+        vcs_service.update(cx, |service, cx| {
+            service.schedule_file_diff(&path, text, edit, cx);
+        });
+    }
+
     fn document_view_ids(
         &self,
         doc_id: helix_view::DocumentId,
@@ -8454,7 +11965,7 @@ impl Workspace {
         doc_id: helix_view::DocumentId,
         cx: &mut Context<Self>,
     ) {
-        self.invalidate_tab_bar_documents();
+        self.refresh_tab_diagnostic_badge(doc_id, cx);
         for view_id in self.document_view_ids(doc_id, cx) {
             if let Some(view) = self.view_manager.get_document_view(&view_id) {
                 view.update(cx, |view, _cx| {
@@ -8471,7 +11982,11 @@ impl Workspace {
         // New document opened - the view will be created automatically
         info!("Document opened: {:?}", doc_id);
         self.ensure_document_in_order(doc_id);
+        self.tab_mru.record(TabId::Document(doc_id));
         self.invalidate_tab_bar_documents();
+        self.core.update(cx, |core, _cx| {
+            crate::application::apply_file_association_language(&mut core.editor, doc_id);
+        });
 
         // Sync file tree selection with the newly opened document
         let doc_path = {
@@ -8482,6 +11997,14 @@ impl Workspace {
                 .map(|p| p.to_path_buf())
         };
 
+        if let Some(path) = &doc_path {
+            let root = self.core.read(cx).project_directory.clone();
+            if cx.has_global::<RecentFiles>() {
+                cx.global_mut::<RecentFiles>()
+                    .record_opened(root.as_deref(), path);
+            }
+        }
+
         if let Some(path) = doc_path
             && let Some(file_tree) = &self.file_tree
         {
@@ -8494,12 +12017,35 @@ impl Workspace {
         cx.notify();
     }
 
-    fn handle_document_closed(&mut self, doc_id: helix_view::DocumentId, cx: &mut Context<Self>) {
+    fn handle_document_closed(
+        &mut self,
+        doc_id: helix_view::DocumentId,
+        path: Option<&Path>,
+        view_state: Option<&DocumentViewState>,
+        cx: &mut Context<Self>,
+    ) {
         // Document closed - the view will be cleaned up automatically
         info!("Document closed: {:?}", doc_id);
+        if self
+            .comparison
+            .as_ref()
+            .is_some_and(|comparison| comparison.includes(doc_id))
+        {
+            self.close_comparison(cx);
+        }
+        if let Some(path) = path
+            && cx.has_global::<RecentFiles>()
+        {
+            let root = self.core.read(cx).project_directory.clone();
+            cx.global_mut::<RecentFiles>().record_closed(
+                root.as_deref(),
+                RecentFile::with_view_state(path.to_path_buf(), view_state),
+            );
+        }
         self.loading_documents.remove(&doc_id);
         self.document_order.retain(|candidate| *candidate != doc_id);
         self.pinned_documents.remove(&TabId::Document(doc_id));
+        self.forget_tab_mru(TabId::Document(doc_id));
         self.invalidate_tab_bar_documents();
         self.unregister_preview_document(doc_id, cx);
         cx.notify();
@@ -8511,21 +12057,25 @@ impl Workspace {
         self.invalidate_tab_bar_documents();
         self.view_manager.handle_view_focused(view_id, cx);
 
-        let focused_filename = self.current_filename(cx);
-        self.update_titlebar_filename(focused_filename.as_deref(), true, cx);
+        self.update_titlebar_document(true, cx);
 
         // Sync file tree selection with the newly focused view
-        let doc_path = {
+        let (focused_doc_id, doc_path) = {
             let core = self.core.read(cx);
             if let Some(view) = core.editor.tree.try_get(view_id) {
-                core.editor
+                let doc_path = core
+                    .editor
                     .document(view.doc)
                     .and_then(|doc| doc.path())
-                    .map(|p| p.to_path_buf())
+                    .map(|p| p.to_path_buf());
+                (Some(view.doc), doc_path)
             } else {
-                None
+                (None, None)
             }
         };
+        if let Some(doc_id) = focused_doc_id {
+            self.tab_mru.record(TabId::Document(doc_id));
+        }
 
         if let Some(path) = doc_path
             && let Some(file_tree) = &self.file_tree
@@ -8758,23 +12308,13 @@ impl Workspace {
                 .unwrap_or_else(helix_stdx::env::current_working_dir);
             let config = core.editor.config();
             let smart_case = config.search.smart_case;
-            let file_picker_config = config.file_picker.clone();
-            let open_documents = core
-                .editor
-                .documents
-                .values()
-                .filter_map(|doc| {
-                    doc.path()
-                        .map(std::path::Path::to_path_buf)
-                        .map(|path| (path, doc.text().to_owned()))
-                })
-                .collect::<Vec<_>>();
+            let file_picker_config = config.file_picker.clone();
 
             (
                 search_root,
                 smart_case,
                 file_picker_config,
-                open_documents,
+                open_document_texts(&core.editor),
                 core.workspace_backend.clone(),
             )
         };
@@ -8857,15 +12397,31 @@ impl Workspace {
             return;
         }
 
-        let matches = match global_search_matches_with_backend(
-            workspace_backend.as_ref(),
-            &search_root,
-            query,
-            smart_case,
-            &file_picker_config,
-            &open_documents,
-            GLOBAL_SEARCH_RESULT_LIMIT,
-        ) {
+        let indexed = self
+            .content_index
+            .ready_for(&search_root)
+            .and_then(|content_index| {
+                indexed_global_search_matches(
+                    &content_index,
+                    query,
+                    smart_case,
+                    &open_documents,
+                    GLOBAL_SEARCH_RESULT_LIMIT,
+                )
+            });
+        let result = match indexed {
+            Some(matches) => Ok(matches),
+            None => global_search_matches_with_backend(
+                workspace_backend.as_ref(),
+                &search_root,
+                query,
+                smart_case,
+                &file_picker_config,
+                &open_documents,
+                GLOBAL_SEARCH_RESULT_LIMIT,
+            ),
+        };
+        let matches = match result {
             Ok(matches) => matches,
             Err(err) => {
                 self.core.update(cx, |core, _cx| {
@@ -8917,6 +12473,282 @@ impl Workspace {
         });
     }
 
+    /// Search the project for `old_name` and its case variants and preview
+    /// the whole-word replacements with `new_name`.
+    fn start_project_rename(&mut self, old_name: String, new_name: String, cx: &mut Context<Self>) {
+        let variants = match crate::project_rename::case_variants(&old_name, &new_name) {
+            Ok(variants) => variants,
+            Err(message) => {
+                self.set_run_status(message, Severity::Error, cx);
+                return;
+            }
+        };
+        let pattern = crate::project_rename::search_pattern(&variants);
+
+        let (search_root, file_picker_config, open_documents, workspace_backend) = {
+            let core = self.core.read(cx);
+            let search_root = core
+                .project_directory
+                .clone()
+                .unwrap_or_else(helix_stdx::env::current_working_dir);
+            (
+                search_root,
+                core.editor.config().file_picker.clone(),
+                open_document_texts(&core.editor),
+                core.workspace_backend.clone(),
+            )
+        };
+
+        if !should_run_global_search_async(&workspace_backend.identity()) {
+            let result = global_search_matches_with_backend(
+                workspace_backend.as_ref(),
+                &search_root,
+                &pattern,
+                false,
+                &file_picker_config,
+                &open_documents,
+                RENAME_IN_FILES_MATCH_LIMIT,
+            );
+            self.finish_project_rename(old_name, new_name, &variants, result, cx);
+            return;
+        }
+
+        let regex = match compile_global_search_regex(&pattern, false) {
+            Ok(regex) => regex,
+            Err(err) => {
+                self.set_run_status(
+                    format!("Failed to compile regex: {err}"),
+                    Severity::Error,
+                    cx,
+                );
+                return;
+            }
+        };
+        let mut matches = Vec::new();
+        let excluded_relative_paths = global_search_open_document_matches(
+            &mut matches,
+            &search_root,
+            &open_documents,
+            &regex,
+            RENAME_IN_FILES_MATCH_LIMIT,
+        );
+        let disk_limit = RENAME_IN_FILES_MATCH_LIMIT.saturating_sub(matches.len());
+        if disk_limit == 0 {
+            self.finish_project_rename(old_name, new_name, &variants, Ok(matches), cx);
+            return;
+        }
+
+        self.set_run_status(
+            format!("Searching remote workspace for {old_name}"),
+            Severity::Info,
+            cx,
+        );
+        let runtime_handle = self.handle.clone();
+        cx.spawn(async move |this, cx| {
+            let disk_result = match runtime_handle
+                .spawn(global_search_disk_matches_with_backend_async(
+                    workspace_backend,
+                    search_root,
+                    pattern,
+                    false,
+                    file_picker_config,
+                    excluded_relative_paths,
+                    disk_limit,
+                ))
+                .await
+            {
+                Ok(result) => result,
+                Err(err) => Err(err.to_string()),
+            };
+
+            if let Some(this) = this.upgrade() {
+                this.update(cx, |workspace, cx| {
+                    let result = disk_result.map(|disk_matches| {
+                        matches.extend(disk_matches);
+                        matches
+                    });
+                    workspace.finish_project_rename(old_name, new_name, &variants, result, cx);
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn finish_project_rename(
+        &mut self,
+        old_name: String,
+        new_name: String,
+        variants: &[CaseVariant],
+        result: Result<Vec<GlobalSearchMatch>, String>,
+        cx: &mut Context<Self>,
+    ) {
+        let matches = match result {
+            Ok(matches) => matches,
+            Err(err) => {
+                self.set_run_status(err, Severity::Error, cx);
+                return;
+            }
+        };
+
+        let truncated = matches.len() >= RENAME_IN_FILES_MATCH_LIMIT;
+        let files = crate::project_rename::plan_file_renames(
+            matches
+                .into_iter()
+                .map(|search_match| (search_match.path, search_match.line, search_match.line_text)),
+            variants,
+        );
+        if files.is_empty() {
+            self.set_run_status(
+                format!("No whole-word matches for {old_name}"),
+                Severity::Info,
+                cx,
+            );
+            return;
+        }
+
+        self.project_rename = Some(ProjectRename {
+            old_name,
+            new_name,
+            files,
+            excluded: HashSet::new(),
+            truncated,
+        });
+        self.show_project_rename_picker(cx);
+    }
+
+    fn show_project_rename_picker(&mut self, cx: &mut Context<Self>) {
+        use crate::picker_view::PickerItem;
+
+        let Some(rename) = &self.project_rename else {
+            return;
+        };
+        let root = self.core.read(cx).project_directory.clone();
+
+        let (files, replacements) = rename
+            .included_files()
+            .fold((0, 0), |(files, replacements), file| {
+                (files + 1, replacements + file.replacement_count())
+            });
+        let mut summary = format!("{} → {}", rename.old_name, rename.new_name);
+        if rename.truncated {
+            summary.push_str(&format!(
+                " (search stopped after {RENAME_IN_FILES_MATCH_LIMIT} lines)"
+            ));
+        }
+        let mut items = vec![PickerItem {
+            label: format!("Apply {replacements} replacements in {files} files").into(),
+            sublabel: Some(summary.into()),
+            data: Arc::new(ProjectRenameChoice::Apply),
+            file_path: None,
+            vcs_status: None,
+            columns: None,
+        }];
+
+        items.extend(rename.files.iter().map(|file| {
+            let display_path = root
+                .as_deref()
+                .and_then(|root| file.path.strip_prefix(root).ok())
+                .unwrap_or(&file.path);
+            let mark = if rename.is_included(&file.path) {
+                "[x]"
+            } else {
+                "[ ]"
+            };
+            let first_line = &file.lines[0];
+            PickerItem {
+                label: format!(
+                    "{mark} {} ({})",
+                    display_path.display(),
+                    file.replacement_count()
+                )
+                .into(),
+                sublabel: Some(
+                    format!(
+                        "{}: {}",
+                        first_line.line + 1,
+                        first_line.renamed_text().trim()
+                    )
+                    .into(),
+                ),
+                data: Arc::new(ProjectRenameChoice::ToggleFile(file.path.clone())),
+                file_path: Some(file.path.clone()),
+                vcs_status: None,
+                columns: None,
+            }
+        }));
+
+        let picker =
+            crate::picker::Picker::native("Rename in Files", items, |_| {}).with_preview(true);
+        emit_picker_update(picker, &self.overlay, cx);
+    }
+
+    fn handle_project_rename_choice(
+        &mut self,
+        choice: &ProjectRenameChoice,
+        cx: &mut Context<Self>,
+    ) {
+        match choice {
+            ProjectRenameChoice::ToggleFile(path) => {
+                if let Some(rename) = &mut self.project_rename {
+                    rename.toggle(path);
+                }
+                self.show_project_rename_picker(cx);
+            }
+            ProjectRenameChoice::Apply => {
+                let Some(rename) = self.project_rename.take() else {
+                    return;
+                };
+                self.apply_project_rename_preview(rename, cx);
+            }
+        }
+    }
+
+    fn apply_project_rename_preview(&mut self, rename: ProjectRename, cx: &mut Context<Self>) {
+        let mut renamed_files = 0;
+        let mut replacements = 0;
+        let mut failures = Vec::new();
+        self.core.update(cx, |core, cx| {
+            let _guard = self.handle.enter();
+            for file in rename.included_files() {
+                match core.apply_project_rename(file) {
+                    Ok(count) => {
+                        renamed_files += 1;
+                        replacements += count;
+                    }
+                    Err(err) => failures.push(err),
+                }
+            }
+            cx.emit(crate::Update::Redraw);
+        });
+
+        let mut status = format!(
+            "Renamed {} to {}: {replacements} replacements in {renamed_files} files",
+            rename.old_name, rename.new_name
+        );
+        let severity = match failures.first() {
+            Some(first) => {
+                status.push_str(&format!("; skipped {} ({first})", failures.len()));
+                Severity::Warning
+            }
+            None => Severity::Info,
+        };
+        self.set_run_status(status, severity, cx);
+    }
+
+    /// The identifier under the primary cursor of the focused document.
+    fn focused_identifier(&self, cx: &mut Context<Self>) -> Option<String> {
+        let editor = &self.core.read(cx).editor;
+        let view = editor.tree.try_get(editor.tree.focus)?;
+        let doc = editor.documents.get(&view.doc)?;
+        let text = doc.text().slice(..);
+        let cursor = doc.selection(view.id).primary().cursor(text);
+        let line = text.char_to_line(cursor);
+        let line_text = text.line(line);
+        let cursor_byte = line_text.char_to_byte(cursor - text.line_to_char(line));
+        crate::project_rename::identifier_at(&line_text.to_string(), cursor_byte)
+            .map(str::to_string)
+    }
+
     fn handle_regex_selection_submitted(
         &mut self,
         action: RegexSelectionAction,
@@ -8998,120 +12830,277 @@ impl Workspace {
                 }
             }
 
-            cx.notify();
-        });
+            cx.notify();
+        });
+
+        if let Some((_, view_id)) = changed_selection
+            && let Some(view_entity) = self.view_manager.get_document_view(&view_id)
+        {
+            view_entity.update(cx, |view, cx| {
+                view.request_cursor_center();
+                cx.notify();
+            });
+        }
+    }
+
+    fn handle_command_submitted(&mut self, command: &str, cx: &mut Context<Self>) {
+        debug!("handle_command_submitted called with '{}'", command);
+
+        // Clear the overlay first to hide the prompt
+        self.overlay
+            .update(cx, |overlay, cx| overlay.dismiss_all(cx));
+
+        // If a file op is pending, the submitted text is the name it waits for
+        if let Some(submission) = self.file_ops.submit_name(command, cx) {
+            match submission {
+                PromptSubmission::Dispatched => {}
+                PromptSubmission::NewFile { parent } => {
+                    self.request_new_file(parent, command.to_string(), cx);
+                }
+                PromptSubmission::NewProject { template, location } => {
+                    self.scaffold_project(template, location, command, cx);
+                }
+            }
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == CLEAR_USAGE_DATA_COMMAND {
+            self.clear_usage_data(cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == UNDO_FILE_OP_COMMAND {
+            if let Some(status) = self.file_ops.undo_last(cx) {
+                self.push_editor_status_notification(status, cx);
+            }
+            record_usage(UsageKind::Command, UNDO_FILE_OP_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == TOGGLE_TASK_COMMAND {
+            self.toggle_task_at_cursor(cx);
+            record_usage(UsageKind::Command, TOGGLE_TASK_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == ARCHIVE_DONE_TASKS_COMMAND {
+            self.archive_done_tasks(cx);
+            record_usage(UsageKind::Command, ARCHIVE_DONE_TASKS_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == LIVE_GREP_COMMAND {
+            self.open_live_grep(cx);
+            record_usage(UsageKind::Command, LIVE_GREP_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == TYPE_HIERARCHY_COMMAND {
+            self.open_type_hierarchy(cx);
+            record_usage(UsageKind::Command, TYPE_HIERARCHY_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == RUN_CONFIGURATION_COMMAND {
+            self.show_run_configurations_picker(cx);
+            record_usage(UsageKind::Command, RUN_CONFIGURATION_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == RUN_PROBLEMS_COMMAND {
+            self.show_run_problems(cx);
+            record_usage(UsageKind::Command, RUN_PROBLEMS_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == CARGO_SETTINGS_COMMAND {
+            self.show_cargo_settings(cx);
+            record_usage(UsageKind::Command, CARGO_SETTINGS_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == INDENTATION_COMMAND {
+            self.show_indentation_picker(cx);
+            record_usage(UsageKind::Command, INDENTATION_COMMAND, cx);
+            return;
+        }
+
+        if let Some(column) = crate::reflow::parse_reflow_command(command) {
+            match column {
+                Ok(column) => self.reflow_focused_document(column, cx),
+                Err(message) => self.set_run_status(message, Severity::Error, cx),
+            }
+            record_usage(UsageKind::Command, crate::reflow::REFLOW_COMMAND, cx);
+            return;
+        }
+
+        if let Some(transform) = crate::text_transforms::TextTransform::from_command(command) {
+            self.transform_selections(transform, cx);
+            record_usage(UsageKind::Command, transform.command(), cx);
+            return;
+        }
+
+        if let Some(delimiter) = command
+            .trim()
+            .trim_start_matches(':')
+            .strip_prefix(crate::align::ALIGN_COMMAND)
+            .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+        {
+            match delimiter.trim() {
+                "" => self.show_align_prompt(cx),
+                delimiter => self.align_focused_document(delimiter, cx),
+            }
+            record_usage(UsageKind::Command, crate::align::ALIGN_COMMAND, cx);
+            return;
+        }
+
+        if let Some(path) = crate::file_compare::parse_compare_with(command) {
+            match path {
+                None => self.show_compare_picker(cx),
+                Some(path) => {
+                    let path = helix_stdx::path::expand_tilde(Path::new(path)).into_owned();
+                    self.start_comparison(CompareTarget::File(path), cx);
+                }
+            }
+            record_usage(UsageKind::Command, COMPARE_WITH_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == COMPARE_CLIPBOARD_COMMAND {
+            self.compare_with_clipboard(cx);
+            record_usage(UsageKind::Command, COMPARE_CLIPBOARD_COMMAND, cx);
+            return;
+        }
+
+        if let Some(action) = CompareAction::from_command(command) {
+            self.run_compare_action(action, cx);
+            record_usage(UsageKind::Command, action.command(), cx);
+            return;
+        }
+
+        if let Some(new) = crate::scratch_buffers::parse_scratch_new(command) {
+            self.new_scratch_buffer(new, cx);
+            record_usage(UsageKind::Command, SCRATCH_NEW_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == SCRATCH_BUFFERS_COMMAND {
+            self.show_scratch_buffers_picker(cx);
+            record_usage(UsageKind::Command, SCRATCH_BUFFERS_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == SCRATCH_DELETE_COMMAND {
+            self.delete_focused_scratch_buffer(cx);
+            record_usage(UsageKind::Command, SCRATCH_DELETE_COMMAND, cx);
+            return;
+        }
+
+        if let Some(name) = parse_picker_command(command) {
+            self.open_registered_picker(name, cx);
+            record_usage(UsageKind::Command, PICKER_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == CONTENT_INDEX_STATUS_COMMAND {
+            self.show_content_index_status(cx);
+            record_usage(UsageKind::Command, CONTENT_INDEX_STATUS_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == CONTENT_INDEX_REBUILD_COMMAND {
+            self.rebuild_content_index(cx);
+            record_usage(UsageKind::Command, CONTENT_INDEX_REBUILD_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == REGEX_TESTER_COMMAND {
+            self.toggle_regex_tester(cx);
+            record_usage(UsageKind::Command, REGEX_TESTER_COMMAND, cx);
+            return;
+        }
+
+        if command.trim().trim_start_matches(':') == crate::doc_comments::DOC_COMMENT_COMMAND {
+            self.generate_doc_comment(cx);
+            record_usage(
+                UsageKind::Command,
+                crate::doc_comments::DOC_COMMENT_COMMAND,
+                cx,
+            );
+            return;
+        }
+
+        if let Some(operation) = crate::line_edits::LineOperation::from_command(command) {
+            self.apply_line_operation(operation, cx);
+            record_usage(UsageKind::Command, operation.command(), cx);
+            return;
+        }
 
-        if let Some((_, view_id)) = changed_selection
-            && let Some(view_entity) = self.view_manager.get_document_view(&view_id)
-        {
-            view_entity.update(cx, |view, cx| {
-                view.request_cursor_center();
-                cx.notify();
-            });
+        if command.trim().trim_start_matches(':') == NPM_SCRIPTS_COMMAND {
+            self.show_npm_scripts(cx);
+            record_usage(UsageKind::Command, NPM_SCRIPTS_COMMAND, cx);
+            return;
         }
-    }
 
-    fn handle_command_submitted(&mut self, command: &str, cx: &mut Context<Self>) {
-        debug!("handle_command_submitted called with '{}'", command);
+        if command.trim().trim_start_matches(':') == REOPEN_CLOSED_TAB_COMMAND {
+            self.reopen_closed_tab(cx);
+            record_usage(UsageKind::Command, REOPEN_CLOSED_TAB_COMMAND, cx);
+            return;
+        }
 
-        // If a file op is pending, treat the submitted text as the name and dispatch an intent
-        if let Some(pending) = self.pending_file_op.take() {
-            use nucleotide_events::v2::workspace::{Event as WsEvent, FileOpIntent};
-
-            // Build event and decide which directory to rescan using references to avoid moves
-            let (event, refresh_dir, lsp_file_operation): (
-                WsEvent,
-                Option<std::path::PathBuf>,
-                Option<LspFileOperationNotification>,
-            ) = match &pending {
-                PendingFileOp::NewFile { parent } => (
-                    WsEvent::FileOpRequested {
-                        intent: FileOpIntent::NewFile {
-                            parent: parent.clone(),
-                            name: command.to_string(),
-                        },
-                    },
-                    Some(parent.clone()),
-                    Some(LspFileOperationNotification::Created {
-                        path: parent.join(command),
-                        is_dir: false,
-                    }),
-                ),
-                PendingFileOp::NewFolder { parent } => (
-                    WsEvent::FileOpRequested {
-                        intent: FileOpIntent::NewFolder {
-                            parent: parent.clone(),
-                            name: command.to_string(),
-                        },
-                    },
-                    Some(parent.clone()),
-                    Some(LspFileOperationNotification::Created {
-                        path: parent.join(command),
-                        is_dir: true,
-                    }),
-                ),
-                PendingFileOp::Rename { path, was_dir } => {
-                    let new_path = path
-                        .parent()
-                        .unwrap_or_else(|| std::path::Path::new("."))
-                        .join(command);
-                    (
-                        WsEvent::FileOpRequested {
-                            intent: FileOpIntent::Rename {
-                                path: path.clone(),
-                                new_name: command.to_string(),
-                            },
-                        },
-                        path.parent().map(|p| p.to_path_buf()),
-                        Some(LspFileOperationNotification::Renamed {
-                            old_path: path.clone(),
-                            new_path,
-                            was_dir: *was_dir,
-                        }),
-                    )
-                }
-                PendingFileOp::Duplicate { path, is_dir } => {
-                    let target_path = path
-                        .parent()
-                        .unwrap_or_else(|| std::path::Path::new("."))
-                        .join(command);
-                    (
-                        WsEvent::FileOpRequested {
-                            intent: FileOpIntent::Duplicate {
-                                path: path.clone(),
-                                target_name: command.to_string(),
-                            },
-                        },
-                        path.parent().map(|p| p.to_path_buf()),
-                        Some(LspFileOperationNotification::Created {
-                            path: target_path,
-                            is_dir: *is_dir,
-                        }),
-                    )
-                }
+        if command.trim().trim_start_matches(':') == HEX_EDIT_COMMAND {
+            let path = {
+                let editor = &self.core.read(cx).editor;
+                editor
+                    .tree
+                    .try_get(editor.tree.focus)
+                    .and_then(|view| editor.documents.get(&view.doc))
+                    .and_then(|doc| doc.path().map(Path::to_path_buf))
             };
-
-            // Clear the overlay and dispatch the event
-            self.overlay
-                .update(cx, |overlay, cx| overlay.dismiss_all(cx));
-            self.dispatch_workspace_file_op_and_process(event, cx);
-
-            if let Some(notification) = lsp_file_operation {
-                self.observe_file_operation_completion(notification, refresh_dir, cx);
+            match path {
+                Some(path) => self.open_hex_editor(&path, true, cx),
+                None => {
+                    self.set_run_status("No file to open in the hex editor", Severity::Error, cx)
+                }
             }
+            record_usage(UsageKind::Command, HEX_EDIT_COMMAND, cx);
             return;
         }
 
-        // No pending file op: proceed with normal command handling
+        if let Some(parsed) =
+            crate::project_rename::parse_command(command, || self.focused_identifier(cx))
+        {
+            match parsed {
+                Ok((old_name, new_name)) => self.start_project_rename(old_name, new_name, cx),
+                Err(message) => self.set_run_status(message, Severity::Error, cx),
+            }
+            record_usage(UsageKind::Command, RENAME_IN_FILES_COMMAND, cx);
+            return;
+        }
 
-        // Clear the overlay first to hide the prompt
-        self.overlay
-            .update(cx, |overlay, cx| overlay.dismiss_all(cx));
+        if let Some(action) = crate::repl::ReplAction::from_command(command) {
+            self.handle_repl_action(action, cx);
+            record_usage(
+                UsageKind::Command,
+                command.trim().trim_start_matches(':'),
+                cx,
+            );
+            return;
+        }
 
         if self.handle_runnable_command(command, cx) {
+            record_usage(
+                UsageKind::Command,
+                command.trim().trim_start_matches(':'),
+                cx,
+            );
             return;
         }
 
+        if let Some(name) = crate::helix_command::typable_command_name(command) {
+            record_usage(UsageKind::Command, name, cx);
+        }
+
         if let Some(force) = buffer_close_command_force(command) {
             self.close_active_buffer_document_with_force(force, cx);
             return;
@@ -9121,6 +13110,28 @@ impl Workspace {
         self.execute_raw_command(&command, cx);
     }
 
+    fn clear_usage_data(&mut self, cx: &mut Context<Self>) {
+        let result = if cx.has_global::<UsageTracker>() {
+            cx.global_mut::<UsageTracker>().clear()
+        } else {
+            Ok(())
+        };
+        let status = match result {
+            Ok(()) => EditorStatus {
+                status: "Usage data cleared".to_string(),
+                severity: Severity::Info,
+            },
+            Err(error) => {
+                warn!(error = %error, "Failed to clear usage data");
+                EditorStatus {
+                    status: format!("Failed to clear usage data: {error}"),
+                    severity: Severity::Error,
+                }
+            }
+        };
+        self.push_editor_status_notification(status, cx);
+    }
+
     fn handle_runnable_command(&mut self, command: &str, cx: &mut Context<Self>) -> bool {
         match command.trim().trim_start_matches(':') {
             "run" | "runnables" | "show-runnables" => {
@@ -9143,6 +13154,113 @@ impl Workspace {
         }
     }
 
+    fn handle_repl_action(&mut self, action: ReplAction, cx: &mut Context<Self>) {
+        let Some((language, code)) = self.focused_repl_source(action, cx) else {
+            self.set_run_status("No focused document to send to a REPL", Severity::Info, cx);
+            return;
+        };
+
+        let session = self.repl_session.clone().filter(|session| {
+            self.terminal_id == Some(session.terminal)
+                && nucleotide_terminal_view::get_view_model(session.terminal).is_some_and(|model| {
+                    !model
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .has_exited()
+                })
+        });
+
+        match session {
+            Some(session) if action != ReplAction::Start => {
+                if code.is_empty() {
+                    return;
+                }
+                let terminal_runtime = self.core.read(cx).terminal_runtime.clone();
+                for input in crate::repl::repl_input(&code, session.bracketed_paste) {
+                    terminal_runtime.queue_input(session.terminal, input);
+                }
+                if self.panels.reveal_terminal() {
+                    cx.notify();
+                }
+            }
+            _ => self.start_repl(action, language, code, cx),
+        }
+    }
+
+    /// Starts the REPL for `language` in the terminal panel, sending `code`
+    /// once it runs unless this is a plain `repl-start`.
+    fn start_repl(
+        &mut self,
+        action: ReplAction,
+        language: Option<String>,
+        code: String,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(language) = language else {
+            self.set_run_status(
+                "The focused document has no language to start a REPL for",
+                Severity::Info,
+                cx,
+            );
+            return;
+        };
+        let Some(repl) = self.core.read(cx).config.repl_command(&language) else {
+            self.set_run_status(
+                format!(
+                    "No REPL configured for {language}; add [repl.{language}] to nucleotide.toml"
+                ),
+                Severity::Warning,
+                cx,
+            );
+            return;
+        };
+
+        // The PTY buffers input, so code sent with the spawn reaches the REPL
+        // once it starts reading.
+        let initial_input = if action != ReplAction::Start && !code.is_empty() {
+            crate::repl::repl_input(&code, repl.bracketed_paste)
+        } else {
+            Vec::new()
+        };
+        let cwd = Self::terminal_spawn_cwd(self.current_project_root.as_deref())
+            .map(|directory| self.terminal_directory_for_path(&directory, cx));
+        let terminal = self.open_terminal_panel_for_command(
+            cwd,
+            repl.command,
+            repl.args,
+            Vec::new(),
+            initial_input,
+            cx,
+        );
+        // Keep typing in the editor; the REPL only receives sent code.
+        self.panels.cancel_focus(PanelKind::Terminal);
+        self.repl_session = Some(crate::repl::ReplSession {
+            terminal,
+            language,
+            bracketed_paste: repl.bracketed_paste,
+        });
+    }
+
+    /// Language of the focused document and the code `action` sends from it.
+    fn focused_repl_source(
+        &self,
+        action: ReplAction,
+        cx: &mut Context<Self>,
+    ) -> Option<(Option<String>, String)> {
+        let editor = &self.core.read(cx).editor;
+        let view = editor.tree.try_get(editor.tree.focus)?;
+        let doc = editor.documents.get(&view.doc)?;
+        let text = doc.text().slice(..);
+        let primary = doc.selection(view.id).primary();
+        let code = match action {
+            ReplAction::Start => String::new(),
+            ReplAction::SendLine => text.line(primary.cursor_line(text)).to_string(),
+            ReplAction::SendSelection => primary.fragment(text).into_owned(),
+            ReplAction::SendBuffer => text.to_string(),
+        };
+        Some((doc.language_name().map(str::to_string), code))
+    }
+
     fn execute_raw_command(&mut self, command: &str, cx: &mut Context<Self>) {
         use nucleotide_logging::debug;
         // Execute the command through helix's command system
@@ -9152,6 +13270,10 @@ impl Workspace {
 
         debug!(command = %command, "Executing raw command");
 
+        if crate::scratch_buffers::is_quit_command(command) {
+            self.release_scratch_buffers_for_quit(cx);
+        }
+
         // Store the current theme before executing the command
         let theme_before = core.read(cx).editor.theme.name().to_string();
         let theme_before_for_closure = theme_before.clone();
@@ -9194,6 +13316,8 @@ impl Workspace {
         // Check if theme changed after command execution and handle accordingly
         let theme_name_after = core.read(cx).editor.theme.name().to_string();
         if theme_before != theme_name_after {
+            record_usage(UsageKind::Theme, &theme_name_after, cx);
+
             // Use existing theme switching logic (maintains consistency)
             self.switch_theme_by_name_no_window(&theme_name_after, cx);
 
@@ -9276,6 +13400,12 @@ impl Workspace {
             );
         }
 
+        // Shells and REPLs were started in the old root; end them before the
+        // new root takes over rather than leaving them pointed at it.
+        if self.current_project_root.as_ref() != Some(&workspace_root) {
+            self.close_project_terminals(cx);
+        }
+
         // Use set_project_directory to properly initialize LSP and project management
         // Pass the workspace root (not the selected directory) for proper project management
         info!("🗂️ OPEN_DIR: Calling set_project_directory to trigger LSP restart");
@@ -9307,7 +13437,7 @@ impl Workspace {
         self.file_tree = Some(new_file_tree);
 
         // Make sure file tree is visible
-        self.show_file_tree = true;
+        self.panels.show_file_tree();
         cx.notify();
 
         // Show status message about the new project directory
@@ -9318,6 +13448,78 @@ impl Workspace {
         });
     }
 
+    /// Closes the project folder in place: the file tree, terminals, VCS
+    /// monitoring, content index and language servers of the current root
+    /// are torn down, leaving open buffers alone.
+    fn close_project_folder(&mut self, cx: &mut Context<Self>) {
+        let Some(root) = self.current_project_root.take() else {
+            self.core.update(cx, |core, cx| {
+                core.editor.set_status("No folder is open");
+                cx.notify();
+            });
+            return;
+        };
+        info!(project_root = %root.display(), "Closing project folder");
+
+        self.close_project_terminals(cx);
+        self.file_tree = None;
+        self.panels.hide_file_tree();
+        self.stop_content_index(cx);
+        let vcs_handle = cx.global::<VcsServiceHandle>().service().clone();
+        vcs_handle.update(cx, |service, _cx| service.stop_monitoring());
+        nucleotide_project::project_status_service(cx).set_project_root(None);
+        self.core.update(cx, |core, _cx| {
+            core.project_directory = None;
+        });
+
+        if let Some(lsp_state_entity) = self.core.read(cx).lsp_state.clone() {
+            lsp_state_entity.update(cx, |state, cx| {
+                state.clear_all_state();
+                cx.notify();
+            });
+        }
+        if let Some(sender) = self.core.read(cx).get_project_lsp_command_sender() {
+            let span = tracing::info_span!(
+                "workspace_project_lsp_close",
+                workspace_root = %root.display()
+            );
+            let (response_tx, _response_rx) = tokio::sync::oneshot::channel();
+            let command = nucleotide_events::ProjectLspCommand::CloseProjectSession {
+                response: response_tx,
+                span,
+            };
+            if let Err(error) = sender.send(command) {
+                error!(
+                    error = %error,
+                    project_root = %root.display(),
+                    "Failed to send CloseProjectSession command"
+                );
+            }
+        }
+
+        self.refresh_environment_badge(None, cx);
+        if self.workspace_settings_path.is_some() {
+            self.reload_configuration(cx);
+        }
+        self.refresh_project_indicators(cx);
+
+        self.core.update(cx, |core, cx| {
+            core.editor
+                .set_status(format!("Closed folder {}", root.display()));
+            cx.notify();
+        });
+        cx.notify();
+    }
+
+    /// Ends the terminal session and REPL started for the current project.
+    fn close_project_terminals(&mut self, cx: &mut Context<Self>) {
+        if let Some(terminal_id) = self.terminal_id {
+            self.close_terminal_panel_session(terminal_id, cx);
+        }
+        self.repl_session = None;
+        self.terminal_cwd = None;
+    }
+
     fn handle_open_file_keep_focus(&mut self, path: &std::path::Path, cx: &mut Context<Self>) {
         // Open file but don't steal focus from file tree
         let preview_from_project_panel = {
@@ -9781,6 +13983,44 @@ impl Workspace {
         self.open_file_internal(path, true, false, Some(position), cx);
     }
 
+    /// Whether files reopen where they were last closed.
+    fn restores_view_state(&self, cx: &App) -> bool {
+        self.core.read(cx).config.gui.editor.restore_cursor_position
+    }
+
+    /// Where `path` was last closed in this project, when files reopen
+    /// there.
+    fn remembered_view(&self, path: &Path, cx: &App) -> Option<RecentFile> {
+        if !self.restores_view_state(cx) {
+            return None;
+        }
+        let root = self.core.read(cx).project_directory.clone();
+        cx.try_global::<RecentFiles>()?
+            .recent(root.as_deref())
+            .iter()
+            .find(|file| file.path == path)
+            .cloned()
+    }
+
+    /// Reopen the most recently closed tab of this project at the cursor it
+    /// was closed with.
+    pub fn reopen_closed_tab(&mut self, cx: &mut Context<Self>) {
+        let root = self.core.read(cx).project_directory.clone();
+        let closed = cx
+            .has_global::<RecentFiles>()
+            .then(|| cx.global_mut::<RecentFiles>().pop_closed(root.as_deref()))
+            .flatten();
+        match closed {
+            // The file reopens where it was closed, selections included,
+            // unless restoring positions is off.
+            Some(file) if self.restores_view_state(cx) => {
+                self.open_file_internal(&file.path, true, false, None, cx)
+            }
+            Some(file) => self.open_file_at(&file.path, file.position(), cx),
+            None => self.set_run_status("No recently closed tabs", Severity::Info, cx),
+        }
+    }
+
     /// Open the nucleotide.toml settings file
     pub fn open_settings_file(&mut self, cx: &mut Context<Self>) {
         // Get the Helix config directory path
@@ -9818,6 +14058,59 @@ impl Workspace {
         );
     }
 
+    /// Open the workspace-scoped `.nucleotide/settings.toml` for the current project
+    pub fn open_workspace_settings_file(&mut self, cx: &mut Context<Self>) {
+        let Some(project_root) = self.local_project_root(cx) else {
+            self.push_editor_status_notification(
+                EditorStatus {
+                    status: "Workspace settings require a local project folder".to_string(),
+                    severity: Severity::Warning,
+                },
+                cx,
+            );
+            return;
+        };
+        let settings_path = crate::config::workspace_settings_path(&project_root);
+
+        info!(
+            "Opening workspace settings file: {}",
+            settings_path.display()
+        );
+
+        if !settings_path.exists() {
+            let created = settings_path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|()| {
+                    std::fs::write(&settings_path, crate::config::WORKSPACE_SETTINGS_TEMPLATE)
+                });
+            if let Err(e) = created {
+                nucleotide_logging::error!("Failed to create workspace settings file: {}", e);
+                return;
+            }
+
+            info!("Created workspace settings file");
+        }
+
+        self.finish_open_file_internal(
+            &settings_path,
+            true,
+            false,
+            None,
+            None,
+            settings_file_open_backend(),
+            cx,
+        );
+    }
+
+    /// Project root whose workspace settings apply. Remote projects have none.
+    fn local_project_root(&self, cx: &mut Context<Self>) -> Option<PathBuf> {
+        let workspace_identity = self.core.read(cx).workspace_backend.identity();
+        self.current_project_root
+            .clone()
+            .filter(|_| matches!(workspace_identity, WorkspaceIdentity::Local))
+    }
+
     fn apply_workspace_config(&mut self, config: &crate::config::Config, cx: &mut Context<Self>) {
         let preview_tabs_enabled = config.gui.preview_tabs.enabled;
         let file_tree_config = file_tree_config_from_gui(&config.gui);
@@ -9844,6 +14137,14 @@ impl Workspace {
         font_settings.var_font.family = ui_font.family.clone();
         font_settings.var_font.weight = ui_font.weight;
 
+        if cx.has_global::<UsageTracker>() {
+            cx.global_mut::<UsageTracker>()
+                .set_enabled(config.gui.usage.enabled);
+        }
+        cx.set_global(config.scroll_settings());
+        cx.set_global(config.gui.editor.mouse_selection.clone());
+        cx.set_global(config.gui.editor.cursor);
+
         cx.update_global(|theme_manager: &mut crate::ThemeManager, _cx| {
             theme_manager.set_ui_chrome_style(ui_chrome_style);
             theme_manager.set_ui_font_size(gpui::px(ui_font.size));
@@ -9915,6 +14216,8 @@ impl Workspace {
             self.enforce_max_tabs_to_target(settings_change_target, protected_doc_id, cx);
         }
 
+        self.start_content_index(false, cx);
+
         cx.notify();
     }
 
@@ -9944,11 +14247,14 @@ impl Workspace {
 
         // Attempt to reload configuration
         match crate::config::Config::load_from_dir(&config_dir) {
-            Ok(new_config) => {
+            Ok(mut new_config) => {
                 info!(
                     "Successfully reloaded configuration from: {}",
                     settings_path.display()
                 );
+                self.workspace_settings_path = self
+                    .local_project_root(cx)
+                    .and_then(|root| new_config.apply_workspace_settings_from(&root));
 
                 let workspace_config = new_config.clone();
                 self.core.update(cx, move |core, cx| {
@@ -9981,6 +14287,10 @@ impl Workspace {
     ) {
         let workspace_backend = self.core.read(cx).workspace_backend.clone();
         let backend_identity = workspace_backend.identity();
+        if should_open_with_hex_editor(path, initial_position.is_some(), &backend_identity) {
+            self.open_hex_editor(path, should_focus, cx);
+            return;
+        }
         if should_open_with_image_viewer(path, initial_position.is_some(), &backend_identity) {
             match backend_identity {
                 WorkspaceIdentity::Local => {
@@ -10083,6 +14393,10 @@ impl Workspace {
 
         let workspace_backend = self.core.read(cx).workspace_backend.clone();
         let runtime_handle = self.handle.clone();
+        let remembered = initial_position
+            .is_none()
+            .then(|| self.remembered_view(&path, cx))
+            .flatten();
         let message = format!("Loading remote file: {}", path.display());
         self.set_run_status(message.clone(), Severity::Info, cx);
         let activity_id = self.start_background_activity(message, cx);
@@ -10126,6 +14440,7 @@ impl Workspace {
                 path: path.clone(),
                 view_id,
                 initial_position,
+                remembered,
             },
         );
         self.ensure_document_in_order(doc_id);
@@ -10187,12 +14502,17 @@ impl Workspace {
                                         loading_document.view_id,
                                         document_read,
                                     )?;
-                                if let Some(position) = loading_document.initial_position
-                                    && let Some(doc) = core.editor.document_mut(doc_id)
-                                {
-                                    let offset =
-                                        pos_at_coords(doc.text().slice(..), position, true);
-                                    doc.set_selection(hydrated_view_id, Selection::point(offset));
+                                if let Some(doc) = core.editor.document_mut(doc_id) {
+                                    if let Some(position) = loading_document.initial_position {
+                                        let offset =
+                                            pos_at_coords(doc.text().slice(..), position, true);
+                                        doc.set_selection(
+                                            hydrated_view_id,
+                                            Selection::point(offset),
+                                        );
+                                    } else if let Some(file) = &loading_document.remembered {
+                                        restore_recent_view(doc, hydrated_view_id, file);
+                                    }
                                 }
                                 let is_visible = core
                                     .editor
@@ -10340,6 +14660,10 @@ impl Workspace {
         let mut opened_doc_id = None;
         let mut project_panel_preview = None;
         let mut document_read = document_read;
+        let remembered = initial_position
+            .is_none()
+            .then(|| self.remembered_view(path, cx))
+            .flatten();
         self.core.update(cx, |core, cx| {
             let _guard = self.handle.enter();
             let existed_already = core
@@ -10436,16 +14760,24 @@ impl Workspace {
                                 .switch(doc_id, helix_view::editor::Action::Replace);
                         }
 
-                        // Set the selection to the requested position, or to the start by default.
-                        // will reveal it after views are refreshed below.
+                        // Set the selection to the requested position, to where the file was
+                        // last closed, or to the start by default. A requested position is
+                        // revealed after views are refreshed below; a restored one keeps
+                        // its scroll position.
                         if let Some(doc) = core.editor.document_mut(doc_id) {
-                            let offset = initial_position
-                                .map(|position| pos_at_coords(doc.text().slice(..), position, true))
-                                .unwrap_or(0);
-                            let pos = Selection::point(offset);
-                            doc.set_selection(view_id, pos);
+                            match remembered.as_ref().filter(|_| !existed_already) {
+                                Some(file) => restore_recent_view(doc, view_id, file),
+                                None => {
+                                    let offset = initial_position
+                                        .map(|position| {
+                                            pos_at_coords(doc.text().slice(..), position, true)
+                                        })
+                                        .unwrap_or(0);
+                                    doc.set_selection(view_id, Selection::point(offset));
+                                    reveal_opened_view = Some(view_id);
+                                }
+                            }
                             core.editor.ensure_cursor_in_view(view_id);
-                            reveal_opened_view = Some(view_id);
                         }
                     }
                 }
@@ -10471,6 +14803,12 @@ impl Workspace {
             self.allow_tab_bar_auto_scroll();
         }
 
+        if let Some(doc_id) = opened_doc_id
+            && let Some(template) = self.file_ops.take_template(path)
+        {
+            self.insert_file_template(doc_id, path, &template, cx);
+        }
+
         // Update document views after opening file
         self.update_document_views(cx);
         if let Some(view_id) = reveal_opened_view
@@ -10613,6 +14951,7 @@ impl Workspace {
                 self.handle_overlay_update(cx);
             }
             crate::Update::OpenFile(path) => self.handle_open_file(path, cx),
+            crate::Update::ErrorReport(report) => self.report_error((**report).clone(), cx),
             crate::Update::OpenDirectory(path) => self.handle_open_directory(path, cx),
             crate::Update::OpenRemote(input) => self.handle_open_remote_submitted(input, cx),
             crate::Update::OpenRemoteWithOptions { input, options } => self
@@ -10681,6 +15020,57 @@ impl Workspace {
                 nucleotide_logging::debug!(label = %task.label(), "Workspace received RunTask");
                 self.run_task(task.clone(), cx);
             }
+            crate::Update::ResolveMergeConflict(choice) => {
+                self.resolve_merge_conflict_choice(choice, cx);
+            }
+            crate::Update::ProjectRename(choice) => {
+                self.handle_project_rename_choice(choice, cx);
+            }
+            crate::Update::References(result) => {
+                self.show_references(result.clone(), cx);
+            }
+            crate::Update::TypeHierarchy(update) => {
+                self.handle_type_hierarchy_update(update.clone(), cx);
+            }
+            crate::Update::RunConfiguration(choice) => {
+                self.run_configuration(&choice.name, cx);
+            }
+            crate::Update::Cargo(choice) => {
+                self.handle_cargo_choice(choice, cx);
+            }
+            crate::Update::Indentation(choice) => {
+                self.handle_indentation_choice(*choice, cx);
+            }
+            crate::Update::FileTemplate(choice) => {
+                let choice = choice.clone();
+                self.create_new_file(choice.parent, choice.name, choice.template, cx);
+            }
+            crate::Update::ProjectTemplate(template) => {
+                self.file_ops
+                    .request_new_project_location(template.clone(), cx);
+            }
+            crate::Update::TerminalPaste(choice) => {
+                self.handle_terminal_paste_choice(choice, cx);
+            }
+            crate::Update::DiskChange(choice) => {
+                self.handle_disk_change_choice(choice, cx);
+            }
+            crate::Update::SaveFailed(failure) => {
+                self.show_save_failure_prompt(failure, cx);
+            }
+            crate::Update::ResolveSaveFailure(choice) => {
+                self.handle_save_failure_choice(choice, cx);
+            }
+            crate::Update::ShowCommitDiff(entry) => {
+                self.open_commit_diff(entry.clone(), cx);
+            }
+            crate::Update::RecaptureEnvironment(request) => {
+                self.show_environment_inspector(Some(request.clone()), cx);
+            }
+            crate::Update::OpenTerminalProfile(choice) => {
+                self.open_terminal_profile(&choice.name, cx);
+            }
+            crate::Update::ShowFindBar => self.show_find_bar(cx),
             crate::Update::ShowHoverDocs => {
                 nucleotide_logging::debug!("Workspace received ShowHoverDocs");
                 if self.toggle_documentation_sidebar(cx) {
@@ -10691,7 +15081,7 @@ impl Workspace {
             }
             crate::Update::ToggleFileTree => {
                 info!("Toggling file tree from native editor input");
-                self.show_file_tree = !self.show_file_tree;
+                self.panels.toggle_file_tree();
                 cx.notify();
             }
             crate::Update::SemanticShortcut(intent) => {
@@ -10708,11 +15098,7 @@ impl Workspace {
             }
             crate::Update::ShouldQuit => {
                 info!("ShouldQuit event received - triggering application quit");
-                // Ensure editor state is cleanly flushed and views are closed before quit
-                let handle = self.handle.clone();
-                let core = self.core.clone();
-                quit(core, handle, cx);
-                cx.quit();
+                self.request_quit(cx);
             }
             crate::Update::CommandSubmitted(command) => self.handle_command_submitted(command, cx),
             crate::Update::SearchSubmitted(search_text) => {
@@ -10786,10 +15172,10 @@ impl Workspace {
             .focused_view_id()
             .and_then(|view_id| self.view_manager.get_document_view(&view_id))
             .is_some_and(|doc_view| doc_view.focus_handle(cx).contains_focused(window, cx));
-        let tab_bar_menu_focused = self.any_tab_bar_menu_open();
+        let tab_bar_menu_focused = self.context_menus.any_tab_bar_menu_open();
         let workspace_focused = self.focus_handle.contains_focused(window, cx);
-        let terminal_pane_focused =
-            self.terminal_is_focused(window, cx) || self.terminal_focus_pending;
+        let terminal_pane_focused = self.terminal_is_focused(window, cx)
+            || self.panels.focus_requested(PanelKind::Terminal);
         let editor_pane_focused = workspace_focused || active_document_focused;
         let show_focused_tab_bar_buttons =
             editor_pane_focused || terminal_pane_focused || tab_bar_menu_focused;
@@ -10910,42 +15296,8 @@ impl Workspace {
 
                 if let Some(doc) = editor.documents.get(&doc_id) {
                     let path = doc.path().map(|p| p.to_path_buf());
-                    let diagnostic_severity = if show_file_icons {
-                        match show_diagnostics {
-                            crate::config::TabDiagnosticsVisibility::Off => None,
-                            crate::config::TabDiagnosticsVisibility::Errors => doc
-                                .diagnostics()
-                                .iter()
-                                .any(|diagnostic| {
-                                    matches!(
-                                        diagnostic.severity,
-                                        Some(helix_core::diagnostic::Severity::Error)
-                                    )
-                                })
-                                .then_some(helix_core::diagnostic::Severity::Error),
-                            crate::config::TabDiagnosticsVisibility::All => {
-                                if doc.diagnostics().iter().any(|diagnostic| {
-                                    matches!(
-                                        diagnostic.severity,
-                                        Some(helix_core::diagnostic::Severity::Error)
-                                    )
-                                }) {
-                                    Some(helix_core::diagnostic::Severity::Error)
-                                } else if doc.diagnostics().iter().any(|diagnostic| {
-                                    matches!(
-                                        diagnostic.severity,
-                                        Some(helix_core::diagnostic::Severity::Warning)
-                                    )
-                                }) {
-                                    Some(helix_core::diagnostic::Severity::Warning)
-                                } else {
-                                    None
-                                }
-                            }
-                        }
-                    } else {
-                        None
-                    };
+                    let (diagnostic_severity, diagnostic_count) =
+                        tab_document_diagnostic_badge(doc, &tab_bar_cache_key);
 
                     documents.push(DocumentInfo {
                         id: TabId::Document(doc_id),
@@ -10959,6 +15311,7 @@ impl Workspace {
                         order: order_index, // Use position in Vec as order
                         git_status: None,   // Will be filled in after releasing core borrow
                         diagnostic_severity,
+                        diagnostic_count,
                     });
                 }
             }
@@ -10969,7 +15322,7 @@ impl Workspace {
                     id: TabId::Image(tab.id),
                     is_deleted: is_deleted_document_path(Some(&tab.path), &workspace_identity),
                     path: Some(tab.path.clone()),
-                    is_modified: false,
+                    is_modified: tab.is_modified(cx),
                     is_readonly: false,
                     is_pinned: self.pinned_documents.contains(&TabId::Image(tab.id)),
                     is_preview: false,
@@ -10977,6 +15330,7 @@ impl Workspace {
                     order: image_order_offset + index,
                     git_status: None,
                     diagnostic_severity: None,
+                    diagnostic_count: 0,
                 });
             }
 
@@ -11084,7 +15438,7 @@ impl Workspace {
                         // Update document views to reflect the change
                         workspace.active_image_tab_id = None;
                         workspace.invalidate_tab_bar_documents();
-                        workspace.close_tab_context_menu();
+                        workspace.context_menus.close_tab(cx);
                         workspace.allow_tab_bar_auto_scroll();
                         workspace.update_document_views(cx);
                     });
@@ -11095,7 +15449,7 @@ impl Workspace {
                 let activation_documents = activation_documents.clone();
                 move |doc_id, _window, cx| {
                     workspace.update(cx, |workspace, cx| {
-                        workspace.close_tab_context_menu();
+                        workspace.context_menus.close_tab(cx);
                         let activation_target = tab_activation_target_after_close(
                             &activation_documents,
                             doc_id,
@@ -11126,6 +15480,19 @@ impl Workspace {
         .git_status(show_git_status)
         .show_diagnostics(show_diagnostics)
         .loading_documents(self.loading_documents.keys().copied().map(TabId::Document))
+        .document_names(
+            self.scratch_buffers
+                .iter()
+                .filter_map(|buffer| Some((TabId::Document(buffer.doc_id?), buffer.name.clone())))
+                .chain(
+                    self.comparison
+                        .as_ref()
+                        .filter(|comparison| comparison.right_temporary)
+                        .map(|comparison| {
+                            (TabId::Document(comparison.right.0), "Clipboard".to_string())
+                        }),
+                ),
+        )
         .deemphasized(!editor_pane_focused)
         .track_scroll(&self.tab_bar_scroll_handle)
         .with_scroll_wheel_handler({
@@ -11173,6 +15540,24 @@ impl Workspace {
                         }),
                 )
         })
+        .when(has_documents, |tab_bar| {
+            tab_bar.end_child(
+                Button::icon_only("tab-all-tabs", "icons/chevron-down.svg")
+                    .variant(ButtonVariant::Secondary)
+                    .size(ButtonSize::Small)
+                    .tooltip("All Tabs")
+                    .activate_on_mouse_down()
+                    .on_click({
+                        let workspace = cx.entity().clone();
+                        move |_event, _window, cx| {
+                            workspace.update(cx, |workspace, cx| {
+                                workspace.show_all_tabs(cx);
+                            });
+                            cx.stop_propagation();
+                        }
+                    }),
+            )
+        })
         .when(show_tab_bar_buttons, |tab_bar| {
             tab_bar
                 .end_child(
@@ -11185,7 +15570,7 @@ impl Workspace {
                             let workspace = cx.entity().clone();
                             move |_event, _window, cx| {
                                 workspace.update(cx, |workspace, cx| {
-                                    workspace.close_tab_bar_menus();
+                                    workspace.context_menus.close_tab_bar_menus(cx);
                                     workspace.tab_bar_action_new_file(cx);
                                 });
                                 cx.stop_propagation();
@@ -11203,7 +15588,9 @@ impl Workspace {
                                     return;
                                 };
                                 workspace.update(cx, |workspace, _cx| {
-                                    workspace.tab_bar_split_button_bounds = Some(bounds);
+                                    workspace
+                                        .context_menus
+                                        .set_tab_bar_split_button_bounds(bounds);
                                 });
                             }
                         })
@@ -11218,27 +15605,14 @@ impl Workspace {
                                     let workspace = cx.entity().clone();
                                     move |event, window, cx| {
                                         workspace.update(cx, |workspace, cx| {
-                                            if workspace.tab_bar_split_menu.is_open() {
-                                                workspace.close_tab_bar_menus();
-                                                cx.notify();
-                                                return;
-                                            }
-
-                                            let fallback_position = event.position();
                                             let menu_position = workspace
-                                                .tab_bar_split_button_bounds
-                                                .map(|bounds| bounds.bottom_right())
-                                                .unwrap_or(fallback_position);
-                                            workspace.close_tab_context_menu();
-                                            workspace.close_tab_bar_new_menu();
-                                            workspace.tab_bar_split_menu.open_at((
-                                                f32::from(menu_position.x),
-                                                f32::from(menu_position.y),
-                                            ));
-                                            workspace.tab_bar_split_popup_menu = None;
-                                            workspace.tab_bar_split_popup_menu_subscription = None;
-                                            window.focus(&workspace.focus_handle, cx);
-                                            cx.notify();
+                                                .context_menus
+                                                .tab_bar_split_button_anchor(event.position());
+                                            workspace.toggle_tab_bar_split_menu(
+                                                menu_position,
+                                                window,
+                                                cx,
+                                            );
                                         });
                                         cx.stop_propagation();
                                     }
@@ -11250,7 +15624,7 @@ impl Workspace {
             let workspace = cx.entity().clone();
             move |doc_id, _window, cx| {
                 workspace.update(cx, |workspace, cx| {
-                    workspace.close_tab_bar_menus();
+                    workspace.context_menus.close_tab_bar_menus(cx);
                     workspace.tab_cm_action_toggle_pin(doc_id, cx);
                 });
             }
@@ -11259,7 +15633,7 @@ impl Workspace {
             let workspace = cx.entity().clone();
             move |doc_id, _window, cx| {
                 workspace.update(cx, |workspace, cx| {
-                    workspace.close_tab_bar_menus();
+                    workspace.context_menus.close_tab_bar_menus(cx);
                     workspace.tab_cm_action_toggle_readonly(doc_id, cx);
                 });
             }
@@ -11268,7 +15642,7 @@ impl Workspace {
             let workspace = cx.entity().clone();
             move |_event, _window, cx| {
                 workspace.update(cx, |workspace, cx| {
-                    workspace.close_tab_bar_menus();
+                    workspace.context_menus.close_tab_bar_menus(cx);
                     workspace.tab_bar_action_new_file(cx);
                 });
             }
@@ -11277,7 +15651,7 @@ impl Workspace {
             let workspace = cx.entity().clone();
             move |doc_id, _window, cx| {
                 workspace.update(cx, |workspace, cx| {
-                    workspace.close_tab_bar_menus();
+                    workspace.context_menus.close_tab_bar_menus(cx);
                     workspace.tab_action_double_click(doc_id, cx);
                 });
             }
@@ -11286,16 +15660,7 @@ impl Workspace {
             let workspace = cx.entity().clone();
             move |doc_id, event, window, cx| {
                 workspace.update(cx, |workspace, cx| {
-                    workspace.close_tab_bar_split_menu();
-                    workspace.close_tab_bar_new_menu();
-                    workspace
-                        .tab_context_menu
-                        .open_at((f32::from(event.position.x), f32::from(event.position.y)));
-                    workspace.tab_context_popup_menu = None;
-                    workspace.tab_context_popup_menu_subscription = None;
-                    workspace.tab_context_menu_doc_id = Some(doc_id);
-                    window.focus(&workspace.focus_handle, cx);
-                    cx.notify();
+                    workspace.open_tab_context_menu(doc_id, event.position, window, cx);
                 });
             }
         })
@@ -11525,18 +15890,17 @@ impl Workspace {
         let viewport_width = f32::from(window.viewport_size().width);
         let geometry = StatusBarGeometry::new(
             viewport_width,
-            self.show_file_tree,
-            self.file_tree_width,
+            self.panels.file_tree_visible,
+            self.panels.file_tree_width,
             &sizes,
         );
         let model = self.statusbar_model(geometry.density, cx);
         let chrome_metrics =
             nucleotide_ui::DensityMetrics::for_density(nucleotide_ui::ControlDensity::Comfortable);
-        let divider_color = status_bar_tokens.border;
         let native_sidebar_enabled = macos_system_sidebar_enabled(&self.core.read(cx).config.gui);
         let extend_sidebar_into_status_bar = should_extend_translucent_sidebar_into_status_bar(
-            self.show_file_tree,
-            self.file_tree_width,
+            self.panels.file_tree_visible,
+            self.panels.file_tree_width,
             native_sidebar_enabled,
         );
         let sidebar_background = if extend_sidebar_into_status_bar {
@@ -11547,30 +15911,17 @@ impl Workspace {
 
         let leading = div().size_full().bg(sidebar_background);
 
-        let content = div()
-            .size_full()
-            .when(extend_sidebar_into_status_bar, |content| {
-                content.border_t_1().border_color(divider_color)
-            })
-            .child(self.statusbar_main_content(
-                &model,
-                geometry,
-                &status_bar_tokens,
-                sizes.radius_md,
-                cx,
-            ));
-
         let workspace_entity = cx.entity().clone();
         let file_tree_button = Button::icon_only("file-tree-toggle", "icons/folder-tree.svg")
             .variant(ButtonVariant::Ghost)
             .size(ButtonSize::ExtraSmall)
             .tooltip("Toggle File Tree")
             .aria_label("Toggle file tree")
-            .focus_handle(self.statusbar_file_tree_focus.clone())
+            .focus_handle(self.statusbar_focus.file_tree.clone())
             .activate_on_mouse_down()
             .on_click(move |_event, _window, app_cx| {
                 workspace_entity.update(app_cx, |workspace, cx| {
-                    workspace.show_file_tree = !workspace.show_file_tree;
+                    workspace.panels.toggle_file_tree();
                     cx.notify();
                 });
             });
@@ -11581,7 +15932,7 @@ impl Workspace {
             .size(ButtonSize::ExtraSmall)
             .tooltip("Toggle Terminal")
             .aria_label("Toggle terminal panel")
-            .focus_handle(self.statusbar_terminal_focus.clone())
+            .focus_handle(self.statusbar_focus.terminal.clone())
             .activate_on_mouse_down()
             .on_click(move |_event, _window, app_cx| {
                 workspace_entity.update(app_cx, |workspace, cx| {
@@ -11595,9 +15946,6 @@ impl Workspace {
             .flex()
             .flex_row()
             .items_center()
-            .when(extend_sidebar_into_status_bar, |utilities| {
-                utilities.border_t_1().border_color(divider_color)
-            })
             .child(
                 div()
                     .w(chrome_metrics.row_height)
@@ -11617,360 +15965,919 @@ impl Workspace {
                     .child(terminal_button),
             );
 
-        StatusBar::new("workspace-status-bar")
-            .top_border(!extend_sidebar_into_status_bar)
+        let bar = StatusBar::new("workspace-status-bar")
+            .leading_border(!extend_sidebar_into_status_bar)
             .leading(geometry.sidebar_width, leading)
-            .content(content)
-            .trailing(utilities)
+            .available_width(
+                (px(viewport_width) - geometry.sidebar_width - geometry.utility_width).max(px(0.0)),
+            )
+            .trailing(utilities);
+        self.statusbar_segments(
+            bar,
+            &model,
+            geometry,
+            &status_bar_tokens,
+            sizes.radius_md,
+            cx,
+        )
+    }
+
+    fn handle_file_tree_event(&mut self, event: &FileTreeEvent, cx: &mut Context<Self>) {
+        match event {
+            FileTreeEvent::OpenFile { path, focus_editor } => {
+                info!(
+                    "FileTreeEvent::OpenFile received in workspace: {:?}, focus_editor={}",
+                    path, focus_editor
+                );
+                if *focus_editor {
+                    self.handle_open_file(path, cx);
+                } else {
+                    self.handle_open_file_keep_focus(path, cx);
+                }
+            }
+            FileTreeEvent::SelectionChanged { path: _ } => {
+                // Update UI if needed for selection changes
+                cx.notify();
+            }
+            FileTreeEvent::SelectionSetChanged { paths: _ } => {
+                // Update UI if needed for multi-selection changes
+                cx.notify();
+            }
+            FileTreeEvent::DirectoryToggled {
+                path: _,
+                expanded: _,
+            } => {
+                // Update UI for directory expansion/collapse
+                cx.notify();
+            }
+            FileTreeEvent::ContextMenuRequested {
+                path,
+                is_directory,
+                x,
+                y,
+            } => {
+                info!(
+                    "FileTreeEvent::ContextMenuRequested at ({}, {}): {:?}",
+                    x, y, path
+                );
+                self.context_menus
+                    .open_file_tree((*x, *y), path.clone(), *is_directory, cx);
+                cx.notify();
+            }
+            FileTreeEvent::OperationRequested {
+                intent,
+                path,
+                is_directory,
+            } => {
+                self.handle_project_tree_operation(*intent, path.clone(), *is_directory, cx);
+            }
+            FileTreeEvent::FileSystemChanged { path, kind } => {
+                self.invalidate_tab_bar_documents();
+                info!("File system change detected: {:?} - {:?}", path, kind);
+                // Tree updates and VCS refreshes are handled by the file tree at
+                // the debounced watcher batch boundary before this event is emitted.
+                self.notify_lsp_file_system_change(path, kind, cx);
+                self.schedule_document_reload(path, kind, cx);
+                self.queue_content_index_update(path, kind, cx);
+                cx.notify();
+            }
+            FileTreeEvent::VcsRefreshStarted { repository_root } => {
+                info!("VCS refresh started for repository: {:?}", repository_root);
+                if let Some(activity_id) = self.vcs_refresh_activity.take() {
+                    self.finish_background_activity(activity_id, cx);
+                }
+                let message = format!("Refreshing VCS status for {}", repository_root.display());
+                let activity_id = self.start_background_activity(message.clone(), cx);
+                self.vcs_refresh_activity = Some(activity_id);
+                self.push_editor_status_notification(
+                    EditorStatus {
+                        status: message,
+                        severity: Severity::Info,
+                    },
+                    cx,
+                );
+                cx.notify();
+            }
+            FileTreeEvent::VcsStatusChanged {
+                repository_root,
+                affected_files,
+            } => {
+                self.refresh_tab_vcs_badges(cx);
+                info!(
+                    "VCS status updated for repository: {:?} ({} files)",
+                    repository_root,
+                    affected_files.len()
+                );
+                if let Some(activity_id) = self.vcs_refresh_activity.take() {
+                    self.finish_background_activity(activity_id, cx);
+                }
+                // VCS status has been updated, file tree should already be refreshed
+                // Could trigger status bar updates or notifications here
+                cx.notify();
+            }
+            FileTreeEvent::VcsRefreshFailed {
+                repository_root,
+                error,
+            } => {
+                error!(
+                    "VCS refresh failed for repository: {:?} - {}",
+                    repository_root, error
+                );
+                if let Some(activity_id) = self.vcs_refresh_activity.take() {
+                    self.finish_background_activity(activity_id, cx);
+                }
+                self.push_editor_status_notification(
+                    EditorStatus {
+                        status: format!(
+                            "VCS refresh failed for {}: {error}",
+                            repository_root.display()
+                        ),
+                        severity: Severity::Error,
+                    },
+                    cx,
+                );
+                cx.notify();
+            }
+            FileTreeEvent::RefreshVcs { force } => {
+                info!("VCS refresh requested (force: {})", force);
+                if let Some(ref mut file_tree) = self.file_tree {
+                    file_tree.update(cx, |tree, tree_cx| {
+                        tree.handle_vcs_refresh(*force, tree_cx);
+                    });
+                }
+                cx.notify();
+            }
+            FileTreeEvent::ToggleVisibility => {
+                info!("Toggle file tree visibility requested");
+                self.panels.toggle_file_tree();
+                cx.notify();
+            }
+            FileTreeEvent::SearchRequested { initial_query } => {
+                self.start_file_tree_search(initial_query.clone(), cx);
+            }
+            FileTreeEvent::ChooseRootRequested => {
+                open_directory(self.core.clone(), self.handle.clone(), cx);
+            }
+            FileTreeEvent::PreviewChanged { path } => match path {
+                Some(path) => self.load_file_tree_preview(path.clone(), cx),
+                None => {
+                    self.file_tree_preview = None;
+                    self.file_tree_preview_load = None;
+                    cx.notify();
+                }
+            },
+        }
+    }
+
+    /// Reads the previewed file off the main thread. A newer preview drops
+    /// the pending read.
+    fn load_file_tree_preview(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        let is_image = is_image_file_path(&path);
+        self.file_tree_preview_load = Some(cx.spawn(async move |this, cx| {
+            let read_path = path.clone();
+            let result = cx
+                .background_executor()
+                .spawn(async move { read_file_preview(&read_path, is_image) })
+                .await;
+            if let Some(this) = this.upgrade() {
+                this.update(cx, |workspace, cx| {
+                    workspace.file_tree_preview_load = None;
+                    match result {
+                        Ok(file) => {
+                            workspace.file_tree_preview =
+                                Some(workspace.highlight_file_preview(file, cx));
+                        }
+                        Err(error) => {
+                            workspace.file_tree_preview = None;
+                            workspace.set_run_status(
+                                format!("Cannot preview {}: {error}", path.display()),
+                                Severity::Warning,
+                                cx,
+                            );
+                        }
+                    }
+                    cx.notify();
+                });
+            }
+        }));
+    }
+
+    /// Syntax colors for a text preview, from the language the file name or
+    /// shebang selects.
+    fn highlight_file_preview(&self, file: FilePreviewFile, cx: &App) -> FilePreview {
+        let lines = match &file.source {
+            FilePreviewSource::Text { excerpt, .. } => {
+                let editor = &self.core.read(cx).editor;
+                let loader = editor.syn_loader.load();
+                let rope = helix_core::Rope::from(excerpt.as_str());
+                let text = rope.slice(..);
+                let syntax = loader
+                    .language_for_filename(&file.path)
+                    .or_else(|| loader.language_for_shebang(text))
+                    .and_then(|language| {
+                        helix_core::syntax::Syntax::new(text, language, &loader).ok()
+                    });
+                let line_count = excerpt.lines().count().min(PREVIEW_MAX_LINES);
+                nucleotide_editor::styled_text_lines(
+                    text,
+                    syntax.as_ref(),
+                    &editor.theme,
+                    &loader,
+                    0..line_count,
+                )
+            }
+            FilePreviewSource::Image | FilePreviewSource::Binary => Vec::new(),
+        };
+        FilePreview { file, lines }
+    }
+
+    fn render_file_tree_preview(&self, top_inset: Pixels, cx: &App) -> Option<gpui::AnyElement> {
+        let preview = self.file_tree_preview.as_ref()?;
+        let tokens = cx.theme().tokens;
+        let code_font = SharedString::from(
+            cx.global::<crate::types::FontSettings>()
+                .fixed_font
+                .family
+                .clone(),
+        );
+        Some(
+            div()
+                .absolute()
+                .top(top_inset + tokens.sizes.space_3)
+                .left(px(self.panels.file_tree_width) + tokens.sizes.space_3)
+                .child(render_file_preview(preview, &tokens, code_font))
+                .into_any_element(),
+        )
+    }
+
+    /// Reload or prompt for an open document whose file changed on disk.
+    fn schedule_document_reload(
+        &mut self,
+        path: &Path,
+        kind: &FileSystemEventKind,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(
+            kind,
+            FileSystemEventKind::Created | FileSystemEventKind::Modified
+        ) {
+            return;
+        }
+
+        let (workspace_backend, decision) = {
+            let core = self.core.read(cx);
+            let documents = core
+                .editor
+                .documents
+                .iter()
+                .map(|(doc_id, doc)| (*doc_id, doc.path(), doc.is_modified()));
+            let visible_views = core
+                .editor
+                .tree
+                .views()
+                .map(|(view, _)| (view.id, view.doc));
+            (
+                core.workspace_backend.clone(),
+                document_reload_decision(documents, visible_views, path),
+            )
+        };
+
+        let doc_id = match decision {
+            DocumentReloadDecision::Reload { doc_id, .. }
+            | DocumentReloadDecision::Dirty { doc_id } => doc_id,
+            DocumentReloadDecision::Hidden { doc_id } => {
+                debug!(
+                    path = %path.display(),
+                    ?doc_id,
+                    "File changed for hidden buffer; leaving buffer unchanged"
+                );
+                return;
+            }
+            DocumentReloadDecision::NoMatch => return,
+        };
+
+        let runtime_handle = self.handle.clone();
+        let path = path.to_path_buf();
+        debug!(path = %path.display(), ?doc_id, "Scheduling document reload");
+
+        cx.spawn(async move |this, cx| {
+            let read = match runtime_handle
+                .spawn(read_document_for_reload(workspace_backend, path.clone()))
+                .await
+            {
+                Ok(result) => result,
+                Err(error) => Err(anyhow::anyhow!("document reload task failed: {error}")),
+            };
+
+            if let Some(this) = this.upgrade() {
+                this.update(cx, |workspace, cx| match read {
+                    Ok(document_read) => {
+                        workspace.finish_document_reload(doc_id, &path, document_read, cx);
+                    }
+                    Err(error) => {
+                        warn!(
+                            path = %path.display(),
+                            ?doc_id,
+                            error = %error,
+                            "Failed to read document after external change"
+                        );
+                        workspace.push_editor_status_notification(
+                            EditorStatus {
+                                status: format!(
+                                    "Failed to read changed file {}: {error}",
+                                    path.display()
+                                ),
+                                severity: Severity::Error,
+                            },
+                            cx,
+                        );
+                    }
+                });
+            }
+        })
+        .detach();
     }
 
-    fn handle_file_tree_event(&mut self, event: &FileTreeEvent, cx: &mut Context<Self>) {
-        match event {
-            FileTreeEvent::OpenFile { path, focus_editor } => {
-                info!(
-                    "FileTreeEvent::OpenFile received in workspace: {:?}, focus_editor={}",
-                    path, focus_editor
-                );
-                if *focus_editor {
-                    self.handle_open_file(path, cx);
-                } else {
-                    self.handle_open_file_keep_focus(path, cx);
-                }
-            }
-            FileTreeEvent::SelectionChanged { path: _ } => {
-                // Update UI if needed for selection changes
-                cx.notify();
-            }
-            FileTreeEvent::SelectionSetChanged { paths: _ } => {
-                // Update UI if needed for multi-selection changes
-                cx.notify();
-            }
-            FileTreeEvent::DirectoryToggled {
-                path: _,
-                expanded: _,
-            } => {
-                // Update UI for directory expansion/collapse
-                cx.notify();
-            }
-            FileTreeEvent::ContextMenuRequested {
-                path,
-                is_directory,
-                x,
-                y,
-            } => {
+    fn finish_document_reload(
+        &mut self,
+        doc_id: DocumentId,
+        path: &Path,
+        document_read: crate::application::WorkspaceDocumentRead,
+        cx: &mut Context<Self>,
+    ) {
+        let auto_reload = self.core.read(cx).config.gui.file_ops.auto_reload;
+        match self.apply_document_reload(doc_id, path, document_read, auto_reload, cx) {
+            Ok(DocumentReloadApply::Applied) => {
                 info!(
-                    "FileTreeEvent::ContextMenuRequested at ({}, {}): {:?}",
-                    x, y, path
+                    path = %path.display(),
+                    ?doc_id,
+                    "Reloaded clean document after external change"
                 );
-                self.file_tree_context_menu.open_at((*x, *y));
-                self.file_tree_popup_menu = None;
-                self.file_tree_popup_menu_subscription = None;
-                self.context_menu_path = Some(path.clone());
-                self.context_menu_is_directory = *is_directory;
-                cx.notify();
+                // Document::apply dispatches the precise content change
+                // through the Helix event bridge.
             }
-            FileTreeEvent::OperationRequested {
-                intent,
-                path,
-                is_directory,
-            } => {
-                self.handle_project_tree_operation(*intent, path.clone(), *is_directory, cx);
-            }
-            FileTreeEvent::FileSystemChanged { path, kind } => {
-                self.invalidate_tab_bar_documents();
-                info!("File system change detected: {:?} - {:?}", path, kind);
-                // Tree updates and VCS refreshes are handled by the file tree at
-                // the debounced watcher batch boundary before this event is emitted.
-                self.notify_lsp_file_system_change(path, kind, cx);
-                self.schedule_remote_document_reload(path, kind, cx);
-                cx.notify();
-            }
-            FileTreeEvent::VcsRefreshStarted { repository_root } => {
-                info!("VCS refresh started for repository: {:?}", repository_root);
-                if let Some(activity_id) = self.vcs_refresh_activity.take() {
-                    self.finish_background_activity(activity_id, cx);
-                }
-                let message = format!("Refreshing VCS status for {}", repository_root.display());
-                let activity_id = self.start_background_activity(message.clone(), cx);
-                self.vcs_refresh_activity = Some(activity_id);
-                self.push_editor_status_notification(
-                    EditorStatus {
-                        status: message,
-                        severity: Severity::Info,
-                    },
-                    cx,
-                );
-                cx.notify();
+            Ok(DocumentReloadApply::Conflict(document_read)) => {
+                self.disk_changes.insert(doc_id, document_read);
+                self.show_disk_change_prompt(doc_id, cx);
             }
-            FileTreeEvent::VcsStatusChanged {
-                repository_root,
-                affected_files,
-            } => {
-                self.invalidate_tab_bar_documents();
-                info!(
-                    "VCS status updated for repository: {:?} ({} files)",
-                    repository_root,
-                    affected_files.len()
+            Ok(DocumentReloadApply::Skipped) => {
+                debug!(
+                    path = %path.display(),
+                    ?doc_id,
+                    "Skipped stale or unchanged document reload"
                 );
-                if let Some(activity_id) = self.vcs_refresh_activity.take() {
-                    self.finish_background_activity(activity_id, cx);
-                }
-                // VCS status has been updated, file tree should already be refreshed
-                // Could trigger status bar updates or notifications here
-                cx.notify();
             }
-            FileTreeEvent::VcsRefreshFailed {
-                repository_root,
-                error,
-            } => {
-                error!(
-                    "VCS refresh failed for repository: {:?} - {}",
-                    repository_root, error
+            Err(error) => {
+                warn!(
+                    path = %path.display(),
+                    ?doc_id,
+                    error = %error,
+                    "Failed to reload document after external change"
                 );
-                if let Some(activity_id) = self.vcs_refresh_activity.take() {
-                    self.finish_background_activity(activity_id, cx);
-                }
                 self.push_editor_status_notification(
                     EditorStatus {
-                        status: format!(
-                            "VCS refresh failed for {}: {error}",
-                            repository_root.display()
-                        ),
+                        status: format!("Failed to reload {}: {error}", path.display()),
                         severity: Severity::Error,
                     },
                     cx,
                 );
-                cx.notify();
             }
-            FileTreeEvent::RefreshVcs { force } => {
-                info!("VCS refresh requested (force: {})", force);
-                if let Some(ref mut file_tree) = self.file_tree {
-                    file_tree.update(cx, |tree, tree_cx| {
-                        tree.handle_vcs_refresh(*force, tree_cx);
-                    });
-                }
-                cx.notify();
+        }
+    }
+
+    fn apply_document_reload(
+        &mut self,
+        doc_id: DocumentId,
+        path: &Path,
+        document_read: crate::application::WorkspaceDocumentRead,
+        auto_reload: bool,
+        cx: &mut Context<Self>,
+    ) -> anyhow::Result<DocumentReloadApply> {
+        self.core.update(cx, |core, _| {
+            let changed = core.editor.document(doc_id).is_some_and(|doc| {
+                crate::disk_changes::changed_since_load(
+                    doc.file_version(),
+                    doc.last_saved_time(),
+                    document_read
+                        .read
+                        .version
+                        .as_ref()
+                        .map(|version| version.as_bytes()),
+                    document_read.read.modified,
+                )
+            });
+            if !changed {
+                return Ok(DocumentReloadApply::Skipped);
             }
-            FileTreeEvent::ToggleVisibility => {
-                info!("Toggle file tree visibility requested");
-                self.show_file_tree = !self.show_file_tree;
-                cx.notify();
+
+            let documents = core
+                .editor
+                .documents
+                .iter()
+                .map(|(doc_id, doc)| (*doc_id, doc.path(), doc.is_modified()));
+            let visible_views = core
+                .editor
+                .tree
+                .views()
+                .map(|(view, _)| (view.id, view.doc));
+
+            match document_reload_decision(documents, visible_views, path) {
+                DocumentReloadDecision::Reload {
+                    doc_id: current_doc_id,
+                    view_id,
+                } if current_doc_id == doc_id && auto_reload => {
+                    crate::application::reload_workspace_document_from_read(
+                        &mut core.editor,
+                        doc_id,
+                        view_id,
+                        document_read,
+                    )?;
+                    Ok(DocumentReloadApply::Applied)
+                }
+                DocumentReloadDecision::Reload {
+                    doc_id: current_doc_id,
+                    ..
+                }
+                | DocumentReloadDecision::Dirty {
+                    doc_id: current_doc_id,
+                } if current_doc_id == doc_id => Ok(DocumentReloadApply::Conflict(document_read)),
+                _ => Ok(DocumentReloadApply::Skipped),
             }
-            FileTreeEvent::SearchRequested { initial_query } => {
-                self.start_file_tree_search(initial_query.clone(), cx);
+        })
+    }
+
+    /// Ask whether to reload, keep or compare a buffer whose file changed on disk.
+    fn show_disk_change_prompt(&mut self, doc_id: DocumentId, cx: &mut Context<Self>) {
+        use crate::disk_changes::{DiskChangeAction, DiskChangeChoice};
+        use crate::picker_view::PickerItem;
+
+        let Some(path) = self
+            .core
+            .read(cx)
+            .editor
+            .document(doc_id)
+            .and_then(|doc| doc.path().map(Path::to_path_buf))
+        else {
+            return;
+        };
+
+        let items = [
+            (
+                DiskChangeAction::Reload,
+                "Reload",
+                "Replace the buffer with the file on disk; undo brings your edits back",
+            ),
+            (
+                DiskChangeAction::KeepMine,
+                "Keep mine",
+                "Keep the buffer; saving overwrites the file on disk",
+            ),
+            (
+                DiskChangeAction::Compare,
+                "Compare",
+                "Show how the buffer differs from the file on disk",
+            ),
+        ]
+        .into_iter()
+        .map(|(action, label, description)| PickerItem {
+            label: label.into(),
+            sublabel: Some(description.into()),
+            data: Arc::new(DiskChangeChoice { doc_id, action }),
+            file_path: None,
+            vcs_status: None,
+            columns: None,
+        })
+        .collect();
+
+        let title = format!("{} changed on disk", file_op_display_name(&path));
+        let picker = crate::picker::Picker::native(title, items, |_| {});
+        emit_picker_update(picker, &self.overlay, cx);
+    }
+
+    /// Pastes the clipboard text into the terminal panel, first asking for
+    /// confirmation when it has several lines and that is configured.
+    fn paste_into_terminal(&mut self, cx: &mut Context<Self>) {
+        use crate::picker_view::PickerItem;
+        use crate::terminal_input::TerminalPasteChoice;
+
+        let Some(id) = self
+            .embedded_terminal_panel
+            .as_ref()
+            .map(|panel| panel.read(cx).active)
+        else {
+            return;
+        };
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+        let lines = nucleotide_terminal::paste::line_count(&text);
+        let confirm = self
+            .core
+            .read(cx)
+            .config
+            .gui
+            .terminal
+            .confirm_multiline_paste;
+        if !confirm || lines < 2 {
+            crate::terminal_input::paste_terminal_text(&self.core, id, &text, cx);
+            return;
+        }
+
+        let text: Arc<str> = Arc::from(text);
+        let items = [
+            (
+                true,
+                format!("Paste {lines} lines"),
+                crate::terminal_input::paste_preview(&text),
+            ),
+            (false, "Cancel".to_string(), String::new()),
+        ]
+        .into_iter()
+        .map(|(confirmed, label, description)| PickerItem {
+            label: label.into(),
+            sublabel: (!description.is_empty()).then(|| description.into()),
+            data: Arc::new(TerminalPasteChoice {
+                id,
+                text: text.clone(),
+                confirmed,
+            }),
+            file_path: None,
+            vcs_status: None,
+            columns: None,
+        })
+        .collect();
+
+        let picker = crate::picker::Picker::native("Paste several lines?", items, |_| {});
+        emit_picker_update(picker, &self.overlay, cx);
+    }
+
+    fn handle_terminal_paste_choice(
+        &mut self,
+        choice: &crate::terminal_input::TerminalPasteChoice,
+        cx: &mut Context<Self>,
+    ) {
+        if choice.confirmed {
+            crate::terminal_input::paste_terminal_text(&self.core, choice.id, &choice.text, cx);
+        }
+        // Typing continues in the terminal once the picker closes
+        self.panels.request_focus(PanelKind::Terminal);
+        cx.notify();
+    }
+
+    /// Offers to retry a failed save, save elsewhere, or, when the write was
+    /// refused for lack of permission, save with administrator rights.
+    fn show_save_failure_prompt(
+        &mut self,
+        failure: &crate::save_failure::SaveFailure,
+        cx: &mut Context<Self>,
+    ) {
+        use crate::picker_view::PickerItem;
+        use crate::save_failure::{SaveFailureAction, SaveFailureChoice, elevated_copy_command};
+
+        let local = self.save_dialogs_available(cx);
+        let mut actions = vec![(SaveFailureAction::Retry, "Retry", failure.message.clone())];
+        if local {
+            actions.push((
+                SaveFailureAction::SaveAs,
+                "Save As…",
+                "Choose another location for the file".to_string(),
+            ));
+        }
+        if local
+            && failure.permission_denied
+            && elevated_copy_command(&failure.path, &failure.path).is_some()
+        {
+            actions.push((
+                SaveFailureAction::SaveElevated,
+                "Save as Administrator",
+                "Write the file with administrator rights after authenticating".to_string(),
+            ));
+        }
+
+        let items = actions
+            .into_iter()
+            .map(|(action, label, description)| PickerItem {
+                label: label.into(),
+                sublabel: Some(description.into()),
+                data: Arc::new(SaveFailureChoice {
+                    doc_id: failure.doc_id,
+                    path: failure.path.clone(),
+                    action,
+                }),
+                file_path: None,
+                vcs_status: None,
+                columns: None,
+            })
+            .collect();
+
+        let title = format!("Could not save {}", file_op_display_name(&failure.path));
+        let picker = crate::picker::Picker::native(title, items, |_| {});
+        emit_picker_update(picker, &self.overlay, cx);
+    }
+
+    fn handle_save_failure_choice(
+        &mut self,
+        choice: &crate::save_failure::SaveFailureChoice,
+        cx: &mut Context<Self>,
+    ) {
+        use crate::save_failure::SaveFailureAction;
+
+        match choice.action {
+            SaveFailureAction::Retry => self.save_document_to(choice.doc_id, None, cx),
+            SaveFailureAction::SaveAs => self.save_document_as(choice.doc_id, cx),
+            SaveFailureAction::SaveElevated => {
+                self.save_document_elevated(choice.doc_id, choice.path.clone(), cx)
             }
         }
     }
 
-    fn schedule_remote_document_reload(
+    /// Native save dialogs and elevated writes only reach the local disk.
+    fn save_dialogs_available(&self, cx: &App) -> bool {
+        matches!(
+            self.core.read(cx).workspace_backend.identity(),
+            WorkspaceIdentity::Local
+        )
+    }
+
+    fn save_document_to(
         &mut self,
-        path: &Path,
-        kind: &FileSystemEventKind,
+        doc_id: DocumentId,
+        path: Option<PathBuf>,
         cx: &mut Context<Self>,
     ) {
-        if !matches!(
-            kind,
-            FileSystemEventKind::Created | FileSystemEventKind::Modified
-        ) {
-            return;
+        let handle = self.handle.clone();
+        let result = self.core.update(cx, |core, _| {
+            let _guard = handle.enter();
+            core.editor.save(doc_id, path, false)
+        });
+        if let Err(error) = result {
+            self.push_editor_status_notification(
+                EditorStatus {
+                    status: format!("Could not save: {error}"),
+                    severity: Severity::Error,
+                },
+                cx,
+            );
         }
+    }
 
-        let (workspace_backend, decision) = {
+    /// Asks for a new path with the platform save dialog and saves the
+    /// document there.
+    fn save_document_as(&mut self, doc_id: DocumentId, cx: &mut Context<Self>) {
+        let (directory, suggested_name) = {
             let core = self.core.read(cx);
-            let workspace_backend = core.workspace_backend.clone();
-            let decision = if matches!(workspace_backend.identity(), WorkspaceIdentity::Remote(_)) {
-                let documents = core
-                    .editor
-                    .documents
-                    .iter()
-                    .map(|(doc_id, doc)| (*doc_id, doc.path(), doc.is_modified()));
-                let visible_views = core
-                    .editor
-                    .tree
-                    .views()
-                    .map(|(view, _)| (view.id, view.doc));
-                remote_document_reload_decision(documents, visible_views, path)
-            } else {
-                RemoteDocumentReloadDecision::<DocumentId, ViewId>::NoMatch
-            };
-
-            (workspace_backend, decision)
+            let path = core
+                .editor
+                .document(doc_id)
+                .and_then(|doc| doc.path().map(Path::to_path_buf));
+            let directory = path
+                .as_deref()
+                .and_then(Path::parent)
+                .map(Path::to_path_buf)
+                .or_else(|| core.project_directory.clone())
+                .unwrap_or_else(helix_stdx::env::current_working_dir);
+            let suggested_name = path
+                .as_deref()
+                .and_then(Path::file_name)
+                .map(|name| name.to_string_lossy().into_owned());
+            (directory, suggested_name)
         };
 
-        let (doc_id, view_id) = match decision {
-            RemoteDocumentReloadDecision::Reload { doc_id, view_id } => (doc_id, view_id),
-            RemoteDocumentReloadDecision::Dirty { .. } => {
-                self.warn_remote_document_changed_with_unsaved_edits(path, cx);
-                return;
-            }
-            RemoteDocumentReloadDecision::Hidden { doc_id } => {
-                debug!(
-                    path = %path.display(),
-                    ?doc_id,
-                    "Remote file changed for hidden buffer; leaving buffer unchanged"
-                );
+        let receiver = cx.prompt_for_new_path(&directory, suggested_name.as_deref());
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = receiver.await else {
                 return;
+            };
+            if let Some(this) = this.upgrade() {
+                this.update(cx, |workspace, cx| {
+                    workspace.save_document_to(doc_id, Some(path), cx);
+                });
             }
-            RemoteDocumentReloadDecision::NoMatch => return,
+        })
+        .detach();
+    }
+
+    /// Writes the document through the platform's elevated copy helper,
+    /// which asks for administrator credentials.
+    fn save_document_elevated(
+        &mut self,
+        doc_id: DocumentId,
+        path: PathBuf,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((text, encoding_with_bom_info, revision)) = self.core.update(cx, |core, _| {
+            let doc = core.editor.document_mut(doc_id)?;
+            Some((
+                doc.text().clone(),
+                doc.encoding_with_bom_info(),
+                doc.get_current_revision(),
+            ))
+        }) else {
+            return;
         };
 
         let runtime_handle = self.handle.clone();
-        let path = path.to_path_buf();
-        debug!(
-            path = %path.display(),
-            ?doc_id,
-            ?view_id,
-            "Scheduling remote document reload"
-        );
-
         cx.spawn(async move |this, cx| {
-            let read = match runtime_handle
-                .spawn(crate::application::read_workspace_document(
-                    workspace_backend,
+            let result = match runtime_handle
+                .spawn(crate::save_failure::write_elevated(
                     path.clone(),
+                    text.clone(),
+                    encoding_with_bom_info,
                 ))
                 .await
             {
                 Ok(result) => result,
-                Err(error) => Err(anyhow::anyhow!(
-                    "remote document reload task failed: {error}"
-                )),
+                Err(error) => Err(anyhow::anyhow!("elevated save task failed: {error}")),
             };
 
             if let Some(this) = this.upgrade() {
-                this.update(cx, |workspace, cx| match read {
-                    Ok(document_read) => {
-                        match workspace.apply_remote_document_reload(
-                            doc_id,
-                            view_id,
-                            &path,
-                            document_read,
-                            cx,
-                        ) {
-                            Ok(RemoteDocumentReloadApply::Applied) => {
-                                info!(
-                                    path = %path.display(),
-                                    ?doc_id,
-                                    "Reloaded clean remote document after external change"
-                                );
-                                // Document::apply dispatches the precise content
-                                // change through the Helix event bridge.
-                            }
-                            Ok(RemoteDocumentReloadApply::Dirty) => {
-                                workspace
-                                    .warn_remote_document_changed_with_unsaved_edits(&path, cx);
-                            }
-                            Ok(RemoteDocumentReloadApply::Skipped) => {
-                                debug!(
-                                    path = %path.display(),
-                                    ?doc_id,
-                                    "Skipped stale remote document reload"
-                                );
-                            }
-                            Err(error) => {
-                                warn!(
-                                    path = %path.display(),
-                                    ?doc_id,
-                                    error = %error,
-                                    "Failed to reload remote document after external change"
-                                );
-                                workspace.push_editor_status_notification(
-                                    EditorStatus {
-                                        status: format!(
-                                            "Failed to reload remote file {}: {error}",
-                                            path.display()
-                                        ),
-                                        severity: Severity::Error,
-                                    },
-                                    cx,
-                                );
-                            }
-                        }
-                    }
-                    Err(error) => {
-                        warn!(
-                            path = %path.display(),
-                            ?doc_id,
-                            error = %error,
-                            "Failed to read remote document after external change"
-                        );
-                        workspace.push_editor_status_notification(
-                            EditorStatus {
-                                status: format!(
-                                    "Failed to read changed remote file {}: {error}",
-                                    path.display()
-                                ),
-                                severity: Severity::Error,
-                            },
-                            cx,
-                        );
-                    }
+                this.update(cx, |workspace, cx| match result {
+                    Ok(()) => workspace.finish_elevated_save(doc_id, &path, revision, text, cx),
+                    Err(error) => workspace.push_editor_status_notification(
+                        EditorStatus {
+                            status: format!(
+                                "Could not save {} as administrator: {error}",
+                                path.display()
+                            ),
+                            severity: Severity::Error,
+                        },
+                        cx,
+                    ),
                 });
             }
         })
         .detach();
     }
 
-    fn apply_remote_document_reload(
+    /// Records an elevated write as a save, as Helix does for its own writes.
+    fn finish_elevated_save(
         &mut self,
         doc_id: DocumentId,
-        view_id: ViewId,
         path: &Path,
-        document_read: crate::application::WorkspaceDocumentRead,
+        revision: usize,
+        text: Rope,
         cx: &mut Context<Self>,
-    ) -> anyhow::Result<RemoteDocumentReloadApply> {
-        self.core.update(cx, |core, _| {
-            let documents = core
-                .editor
-                .documents
-                .iter()
-                .map(|(doc_id, doc)| (*doc_id, doc.path(), doc.is_modified()));
-            let visible_views = core
-                .editor
-                .tree
-                .views()
-                .map(|(view, _)| (view.id, view.doc));
+    ) {
+        let file_version = nucleotide_workspace::file_version_for_path(path)
+            .ok()
+            .map(|version| version.as_bytes().to_vec());
+        let save_time = std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or_else(|_| std::time::SystemTime::now());
 
-            match remote_document_reload_decision(documents, visible_views, path) {
-                RemoteDocumentReloadDecision::Reload {
-                    doc_id: current_doc_id,
-                    view_id: current_view_id,
-                } if current_doc_id == doc_id && current_view_id == view_id => {
-                    crate::application::reload_workspace_document_from_read(
-                        &mut core.editor,
-                        doc_id,
-                        view_id,
-                        document_read,
-                    )?;
-                    Ok(RemoteDocumentReloadApply::Applied)
-                }
-                RemoteDocumentReloadDecision::Dirty {
-                    doc_id: current_doc_id,
-                } if current_doc_id == doc_id => Ok(RemoteDocumentReloadApply::Dirty),
-                _ => Ok(RemoteDocumentReloadApply::Skipped),
-            }
-        })
+        self.core.update(cx, |core, cx| {
+            let Some(doc) = core.editor.document_mut(doc_id) else {
+                return;
+            };
+            doc.set_last_saved_revision(revision, save_time);
+            doc.set_file_version(file_version);
+            core.editor.set_status(format!(
+                "'{}' written as administrator, {}L {}B",
+                path.display(),
+                text.len_lines(),
+                text.len_bytes()
+            ));
+            let saved = nucleotide_events::v2::document::Event::Saved {
+                doc_id,
+                path: path.to_path_buf(),
+                revision: revision as u64,
+            };
+            cx.emit(crate::Update::Event(crate::types::AppEvent::Document(
+                saved,
+            )));
+            cx.emit(crate::Update::Redraw);
+        });
     }
 
-    fn warn_remote_document_changed_with_unsaved_edits(
+    fn handle_disk_change_choice(
         &mut self,
-        path: &Path,
+        choice: &crate::disk_changes::DiskChangeChoice,
         cx: &mut Context<Self>,
     ) {
-        warn!(
-            path = %path.display(),
-            "Remote file changed externally while buffer has unsaved edits"
-        );
-        self.push_editor_status_notification(
-            EditorStatus {
+        use crate::disk_changes::DiskChangeAction;
+
+        let doc_id = choice.doc_id;
+        if choice.action == DiskChangeAction::Compare {
+            self.compare_with_disk(doc_id, cx);
+            return;
+        }
+        let Some(document_read) = self.disk_changes.remove(&doc_id) else {
+            return;
+        };
+        let path = document_read.read.path.clone();
+
+        let result = self.core.update(cx, |core, cx| {
+            let _guard = self.handle.enter();
+            let result = match choice.action {
+                DiskChangeAction::Reload => core
+                    .editor
+                    .tree
+                    .views()
+                    .find(|(view, _)| view.doc == doc_id)
+                    .map(|(view, _)| view.id)
+                    .ok_or_else(|| anyhow::anyhow!("the buffer is no longer visible"))
+                    .and_then(|view_id| {
+                        crate::application::reload_workspace_document_from_read(
+                            &mut core.editor,
+                            doc_id,
+                            view_id,
+                            document_read,
+                        )
+                    }),
+                DiskChangeAction::KeepMine | DiskChangeAction::Compare => {
+                    crate::application::keep_document_over_disk_changes(
+                        &mut core.editor,
+                        doc_id,
+                        &document_read.read,
+                    )
+                }
+            };
+            cx.emit(crate::Update::Redraw);
+            result
+        });
+
+        let status = match (result, choice.action) {
+            (Ok(()), DiskChangeAction::Reload) => EditorStatus {
+                status: format!("Reloaded {} from disk", path.display()),
+                severity: Severity::Info,
+            },
+            (Ok(()), _) => EditorStatus {
                 status: format!(
-                    "Remote file changed externally; unsaved buffer not reloaded: {}",
+                    "Kept your changes to {}; saving overwrites the file on disk",
                     path.display()
                 ),
-                severity: Severity::Warning,
+                severity: Severity::Info,
             },
-            cx,
-        );
+            (Err(error), _) => EditorStatus {
+                status: format!("Could not update {}: {error}", path.display()),
+                severity: Severity::Error,
+            },
+        };
+        self.push_editor_status_notification(status, cx);
+    }
+
+    /// Opens a read-only diff from the file on disk to the buffer beside it,
+    /// then asks again what to do with the buffer.
+    fn compare_with_disk(&mut self, doc_id: DocumentId, cx: &mut Context<Self>) {
+        let Some(document_read) = self.disk_changes.get(&doc_id) else {
+            return;
+        };
+        let project_directory = self.core.read(cx).project_directory.clone();
+
+        let opened = self.core.update(cx, |core, cx| {
+            let doc = core.editor.document(doc_id)?;
+            let mut bytes = document_read.read.bytes.as_slice();
+            let (disk_text, ..) =
+                helix_view::document::from_reader(&mut bytes, Some(doc.encoding())).ok()?;
+            let path = &document_read.read.path;
+            let label = project_directory
+                .as_deref()
+                .and_then(|root| path.strip_prefix(root).ok())
+                .unwrap_or(path)
+                .display()
+                .to_string();
+            let diff = crate::disk_changes::unified_diff(
+                &label,
+                &disk_text.to_string(),
+                &doc.text().to_string(),
+            );
+
+            let diff_doc_id = core
+                .editor
+                .new_file(helix_view::editor::Action::VerticalSplit);
+            let view_id = core.editor.tree.focus;
+            let loader = core.editor.syn_loader.load();
+            let diff_doc = core.editor.documents.get_mut(&diff_doc_id)?;
+            diff_doc.ensure_view_init(view_id);
+            let transaction = helix_core::Transaction::insert(
+                diff_doc.text(),
+                diff_doc.selection(view_id),
+                diff.into(),
+            )
+            .with_selection(Selection::point(0));
+            diff_doc.apply(&transaction, view_id);
+            diff_doc.reset_modified();
+            diff_doc.readonly = true;
+            if let Err(error) = diff_doc.set_language_by_language_id("diff", &loader) {
+                debug!(%error, "No diff language configured for disk comparison");
+            }
+            cx.emit(crate::Update::Redraw);
+            Some(())
+        });
+
+        if opened.is_none() {
+            self.push_editor_status_notification(
+                EditorStatus {
+                    status: "Could not compare the buffer with the file on disk".to_string(),
+                    severity: Severity::Error,
+                },
+                cx,
+            );
+            return;
+        }
+        self.update_document_views(cx);
+        self.show_disk_change_prompt(doc_id, cx);
+        cx.notify();
     }
 
     fn notify_lsp_file_system_change(
@@ -11995,107 +16902,224 @@ impl Workspace {
             ],
         };
 
-        self.core.update(cx, move |core, _cx| {
-            for (path, typ) in changes {
-                core.editor
-                    .language_servers
-                    .file_event_handler
-                    .file_event(path, typ);
-            }
+        self.core.update(cx, move |core, _cx| {
+            for (path, typ) in changes {
+                core.editor
+                    .language_servers
+                    .file_event_handler
+                    .file_event(path, typ);
+            }
+        });
+    }
+
+    /// Rescans, opens and reports what a finished file operation left to do.
+    fn apply_file_op_follow_up(&mut self, follow_up: FileOpFollowUp, cx: &mut Context<Self>) {
+        if let Some(dir) = follow_up.rescan {
+            self.rescan_directory(&dir, cx);
+        }
+        if let Some(path) = follow_up.open {
+            self.handle_open_file(&path, cx);
+        }
+        if let Some(status) = follow_up.status {
+            self.push_editor_status_notification(status, cx);
+        }
+    }
+
+    /// Starts the New Project wizard with a picker of scaffolding templates.
+    fn show_new_project_picker(&mut self, cx: &mut Context<Self>) {
+        use crate::picker_view::PickerItem;
+
+        let (templates, is_local) = {
+            let core = self.core.read(cx);
+            (
+                crate::project_templates::available_templates(&core.config.gui.project_templates),
+                matches!(core.workspace_backend.identity(), WorkspaceIdentity::Local),
+            )
+        };
+        if !is_local {
+            self.set_run_status(
+                "New projects can only be created on this machine",
+                Severity::Warning,
+                cx,
+            );
+            return;
+        }
+
+        let items = templates
+            .into_iter()
+            .map(|template| {
+                let sublabel = template.description.clone().unwrap_or_else(|| {
+                    let command = nucleotide_events::v2::run::CommandSpec::new(&template.command)
+                        .with_args(template.args.iter().cloned());
+                    crate::runnables::shell_command_line(&command)
+                });
+                PickerItem {
+                    label: template.name.clone().into(),
+                    sublabel: Some(sublabel.into()),
+                    data: Arc::new(template),
+                    file_path: None,
+                    vcs_status: None,
+                    columns: None,
+                }
+            })
+            .collect::<Vec<_>>();
+        let picker = crate::picker::Picker::native("New Project", items, |_| {});
+        emit_picker_update(picker, &self.overlay, cx);
+    }
+
+    /// Runs the scaffolding command of `template` for the project `name` in
+    /// the terminal panel. Its folder opens as the project once it succeeds.
+    fn scaffold_project(
+        &mut self,
+        template: crate::config::ProjectTemplateConfig,
+        location: PathBuf,
+        name: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let name = match crate::project_templates::validate_project_name(name) {
+            Ok(name) => name.to_string(),
+            Err(reason) => {
+                self.set_run_status(reason, Severity::Error, cx);
+                return;
+            }
+        };
+        let project = crate::project_templates::NewProject {
+            template,
+            location,
+            name,
+        };
+        if project.directory().exists() {
+            self.set_run_status(
+                format!("{} already exists", project.directory().display()),
+                Severity::Error,
+                cx,
+            );
+            return;
+        }
+
+        let command = project.command();
+        let message = format!(
+            "Creating project {}: {}",
+            project.name,
+            crate::runnables::shell_command_line(&command)
+        );
+        let terminal_id = self.open_terminal_panel_for_command(
+            command.cwd,
+            command.program,
+            command.args,
+            command.env,
+            Vec::new(),
+            cx,
+        );
+        let activity_id = self.start_background_activity(message.clone(), cx);
+        self.file_ops.scaffolding_project = Some(ScaffoldingProject {
+            terminal_id,
+            project,
+            activity_id,
         });
+        self.set_run_status(message, Severity::Info, cx);
     }
 
-    fn notify_lsp_file_operation(
-        &mut self,
-        notification: LspFileOperationNotification,
-        cx: &mut Context<Self>,
-    ) {
-        self.core.update(cx, move |core, _cx| {
-            for language_server in core.editor.language_servers.iter_clients() {
-                if !language_server.is_initialized() {
-                    continue;
-                }
+    /// Creates the file `name` in `parent`, first offering the templates
+    /// configured for it when there are any.
+    fn request_new_file(&mut self, parent: PathBuf, name: String, cx: &mut Context<Self>) {
+        use crate::picker_view::PickerItem;
 
-                match &notification {
-                    LspFileOperationNotification::Created { path, is_dir } => {
-                        language_server.did_create(path, *is_dir);
-                    }
-                    LspFileOperationNotification::Deleted { path, was_dir } => {
-                        language_server.did_delete(path, *was_dir);
-                    }
-                    LspFileOperationNotification::Renamed {
-                        old_path,
-                        new_path,
-                        was_dir,
-                    } => {
-                        language_server.did_rename(old_path, new_path, *was_dir);
-                    }
+        let templates = self.file_ops.templates_for(&parent.join(&name), cx);
+        if templates.is_empty() {
+            self.create_new_file(parent, name, None, cx);
+            return;
+        }
+
+        let items = std::iter::once(None)
+            .chain(templates.into_iter().map(Some))
+            .map(|template| {
+                let (label, sublabel) = match &template {
+                    Some(template) => (
+                        template.name.clone(),
+                        Some(template.source.display().to_string()),
+                    ),
+                    None => ("Empty File".to_string(), None),
+                };
+                PickerItem {
+                    label: label.into(),
+                    sublabel: sublabel.map(Into::into),
+                    data: Arc::new(crate::file_templates::FileTemplateChoice {
+                        parent: parent.clone(),
+                        name: name.clone(),
+                        template,
+                    }),
+                    file_path: None,
+                    vcs_status: None,
+                    columns: None,
                 }
-            }
-        });
+            })
+            .collect::<Vec<_>>();
+        let picker = crate::picker::Picker::native(format!("New File: {name}"), items, |_| {});
+        emit_picker_update(picker, &self.overlay, cx);
     }
 
-    fn observe_file_operation_completion(
+    /// Creates the file `name` in `parent`, with `template` inserted once it opens.
+    fn create_new_file(
         &mut self,
-        notification: LspFileOperationNotification,
-        refresh_dir: Option<PathBuf>,
+        parent: PathBuf,
+        name: String,
+        template: Option<crate::file_templates::FileTemplate>,
         cx: &mut Context<Self>,
     ) {
-        let workspace_backend = self.core.read(cx).workspace_backend.clone();
-        let runtime_handle = self.handle.clone();
-
-        cx.spawn(async move |this, cx| {
-            let notification_for_task = notification.clone();
-            let observed = match runtime_handle
-                .spawn(wait_for_file_operation_notification(
-                    workspace_backend,
-                    notification_for_task,
-                ))
-                .await
-            {
-                Ok(observed) => observed,
-                Err(error) => {
-                    warn!(
-                        error = %error,
-                        ?notification,
-                        "File operation completion observer failed"
-                    );
-                    false
-                }
-            };
-
-            if let Some(this) = this.upgrade() {
-                this.update(cx, |workspace, cx| {
-                    if observed {
-                        workspace.notify_lsp_file_operation(notification.clone(), cx);
-                    } else {
-                        warn!(
-                            ?notification,
-                            "File operation was not observed in workspace backend before timeout"
-                        );
-                    }
-
-                    if let Some(refresh_dir) = refresh_dir.as_ref() {
-                        workspace.rescan_directory(refresh_dir, cx);
-                    }
-                });
-            }
-        })
-        .detach();
+        if let Some(status) = self.file_ops.create_file(parent, name, template, cx) {
+            self.push_editor_status_notification(status, cx);
+        }
     }
 
-    fn dispatch_workspace_file_op_and_process(
+    /// Inserts `template` into the new document `doc_id` at `path` and enters
+    /// insert mode at its first tabstop.
+    fn insert_file_template(
         &mut self,
-        event: nucleotide_events::v2::workspace::Event,
+        doc_id: helix_view::DocumentId,
+        path: &Path,
+        template: &str,
         cx: &mut Context<Self>,
     ) {
-        self.core.read(cx).workspace_file_ops.dispatch(&event);
+        let variables = crate::file_templates::TemplateVariables::new(
+            path.to_path_buf(),
+            self.core.read(cx).project_directory.clone(),
+            chrono::Local::now(),
+        );
+        self.core.update(cx, |core, cx| {
+            let editor = &mut core.editor;
+            let view_id = editor.tree.focus;
+            let Some(doc) = editor.documents.get_mut(&doc_id) else {
+                return;
+            };
+            let snippet = snippet_completion_transaction(
+                doc.text(),
+                &Selection::point(0),
+                template,
+                None,
+                false,
+                &mut variables.render_ctx(doc.snippet_ctx()),
+            );
+            let (transaction, rendered_snippet) = match snippet {
+                Ok(snippet) => snippet,
+                Err(error) => {
+                    editor.set_error(format!("Invalid file template: {error}"));
+                    return;
+                }
+            };
+            doc.apply(&transaction, view_id);
+            let view = editor.tree.get_mut(view_id);
+            doc.append_changes_to_history(view);
+            install_active_completion_snippet(doc, rendered_snippet);
+            editor.mode = helix_view::document::Mode::Insert;
+            cx.emit(crate::Update::Redraw);
+        });
     }
 
     fn handle_vcs_service_event(&mut self, event: &VcsEvent, cx: &mut Context<Self>) {
         match event {
             VcsEvent::StatusUpdated { changes } => {
-                self.invalidate_tab_bar_documents();
+                self.refresh_tab_vcs_badges(cx);
                 debug!(
                     change_count = changes.len(),
                     "Workspace: VCS status updated"
@@ -12134,12 +17158,114 @@ impl Workspace {
                 );
                 cx.notify();
             }
+            VcsEvent::GitOperationStarted { operation } => {
+                if let Some(activity_id) = self.git_operation_activity.take() {
+                    self.finish_background_activity(activity_id, cx);
+                }
+                let message = format!("{}…", git_operation_title(*operation));
+                self.git_operation_activity = Some(self.start_background_activity(message, cx));
+            }
+            VcsEvent::GitOperationProgress {
+                operation,
+                progress,
+            } => {
+                if let Some(activity_id) = self.git_operation_activity {
+                    let message = match progress.percent {
+                        Some(percent) => format!(
+                            "{}: {} {percent}%",
+                            git_operation_title(*operation),
+                            progress.phase
+                        ),
+                        None => format!("{}: {}", git_operation_title(*operation), progress.phase),
+                    };
+                    self.update_background_activity(activity_id, message, cx);
+                }
+            }
+            VcsEvent::GitOperationFinished { operation, result } => {
+                self.handle_git_operation_finished(*operation, result, cx);
+            }
             VcsEvent::Error { message } => {
                 warn!(message = %message, "Workspace: VCS service error");
             }
         }
     }
 
+    fn run_git_operation(&mut self, operation: GitOperation, cx: &mut Context<Self>) {
+        let Some(vcs) = cx
+            .try_global::<VcsServiceHandle>()
+            .map(|vcs| vcs.service().clone())
+        else {
+            self.push_editor_status_notification(
+                EditorStatus {
+                    status: format!(
+                        "{} unavailable: VCS is not running",
+                        git_operation_title(operation)
+                    ),
+                    severity: Severity::Warning,
+                },
+                cx,
+            );
+            return;
+        };
+        vcs.update(cx, |service, cx| service.run_git_operation(operation, cx));
+    }
+
+    fn handle_git_operation_finished(
+        &mut self,
+        operation: GitOperation,
+        result: &Result<String, GitOperationError>,
+        cx: &mut Context<Self>,
+    ) {
+        let title = git_operation_title(operation);
+        // A rejected second request must not end the running operation's activity.
+        if !matches!(result, Err(GitOperationError::AlreadyRunning(_)))
+            && let Some(activity_id) = self.git_operation_activity.take()
+        {
+            self.finish_background_activity(activity_id, cx);
+        }
+
+        match result {
+            Ok(summary) => {
+                let summary = summary.clone();
+                self.notifications.update(cx, |notifications, cx| {
+                    notifications.push_success(title, summary, cx);
+                });
+            }
+            Err(GitOperationError::Cancelled) => {
+                self.push_editor_status_notification(
+                    EditorStatus {
+                        status: format!("{title} cancelled"),
+                        severity: Severity::Info,
+                    },
+                    cx,
+                );
+            }
+            Err(GitOperationError::Authentication(message)) => {
+                let status = EditorStatus {
+                    status: format!("{title} failed: {message}"),
+                    severity: Severity::Error,
+                };
+                let message = format!(
+                    "{message}. Configure a git credential helper or SSH key, then retry {}.",
+                    operation.label().to_lowercase()
+                );
+                self.notifications.update(cx, |notifications, cx| {
+                    notifications.push_banner("Git credentials required", message, status, cx);
+                });
+            }
+            Err(error) => {
+                self.push_editor_status_notification(
+                    EditorStatus {
+                        status: format!("{title} failed: {error}"),
+                        severity: Severity::Error,
+                    },
+                    cx,
+                );
+            }
+        }
+        cx.notify();
+    }
+
     fn handle_document_domain_event(
         &mut self,
         event: &crate::types::DocumentEvent,
@@ -12160,8 +17286,13 @@ impl Workspace {
             DocumentEvent::Opened { doc_id, .. } => {
                 self.handle_document_opened(*doc_id, cx);
             }
-            DocumentEvent::Closed { doc_id, .. } => {
-                self.handle_document_closed(*doc_id, cx);
+            DocumentEvent::Closed {
+                doc_id,
+                path,
+                view_state,
+                ..
+            } => {
+                self.handle_document_closed(*doc_id, path.as_deref(), view_state.as_ref(), cx);
             }
             DocumentEvent::Saved { doc_id, path, .. } => {
                 self.invalidate_tab_bar_documents();
@@ -12212,7 +17343,7 @@ impl Workspace {
     /// Manage completion input context based on completion state
     fn manage_completion_context(&mut self, has_completion: bool) {
         if !has_completion {
-            self.active_completion_session = None;
+            self.completion.session = None;
         }
 
         let completion_context_active =
@@ -12317,7 +17448,7 @@ impl Workspace {
         current_prefix: &str,
         cx: &mut Context<Self>,
     ) {
-        let Some(session) = self.active_completion_session.as_mut() else {
+        let Some(session) = self.completion.session.as_mut() else {
             return;
         };
 
@@ -12424,11 +17555,14 @@ impl Workspace {
                 && let Some(extension) = path.extension().and_then(|ext| ext.to_str())
             {
                 let language = self.map_extension_to_language(extension);
-                self.prefix_extractor.configure_for_language(&language);
+                self.completion
+                    .prefix_extractor
+                    .configure_for_language(&language);
             }
 
             // Use the enhanced prefix extractor for language-aware completion
             let (prefix, is_trigger_completion) = self
+                .completion
                 .prefix_extractor
                 .extract_prefix(&full_line, cursor_in_line);
 
@@ -12548,6 +17682,7 @@ impl Workspace {
         cx: &mut Context<Self>,
     ) {
         debug!(cursor = cursor, doc_id = ?doc_id, view_id = ?view_id, trigger = ?trigger, "Requesting completions through Nucleotide");
+        self.completion.triggered_at = Some(std::time::Instant::now());
 
         if matches!(trigger, LspCompletionTrigger::Manual)
             && self.manual_completion_needs_lsp_settle_delay(cursor, doc_id, cx)
@@ -12575,11 +17710,15 @@ impl Workspace {
         delay: std::time::Duration,
         cx: &mut Context<Self>,
     ) {
+        let generation = self.completion.generation();
         cx.spawn(async move |this, cx| {
             cx.background_executor().timer(delay).await;
 
             if let Some(this) = this.upgrade() {
                 this.update(cx, move |workspace, cx| {
+                    if workspace.completion.generation() != generation {
+                        return;
+                    }
                     let cursor = workspace
                         .completion_cursor(doc_id, view_id, cx)
                         .unwrap_or(cursor);
@@ -12642,12 +17781,21 @@ impl Workspace {
             }
         };
 
+        let generation = self
+            .completion
+            .begin_request(completion_request.cancellation());
         cx.spawn(async move |this, cx| {
             let completion_result = completion_request.collect().await;
 
             if let Some(this) = this.upgrade() {
                 this.update(cx, move |workspace, cx| {
-                    workspace.finish_completion_request(completion_result, doc_id, view_id, cx);
+                    workspace.finish_completion_request(
+                        completion_result,
+                        generation,
+                        doc_id,
+                        view_id,
+                        cx,
+                    );
                 });
             }
         })
@@ -12662,10 +17810,20 @@ impl Workspace {
             bool,
             Vec<u64>,
         )>,
+        generation: u64,
         doc_id: helix_view::DocumentId,
         view_id: helix_view::ViewId,
         cx: &mut Context<Self>,
     ) {
+        if !self.completion.finish_request(generation) {
+            nucleotide_logging::debug!(
+                generation,
+                current_generation = self.completion.generation(),
+                "Dropping stale completion results"
+            );
+            return;
+        }
+
         match completion_result {
             Ok((completion_items, prefix, is_incomplete, incomplete_server_ids)) => {
                 nucleotide_logging::debug!(
@@ -12789,9 +17947,16 @@ impl Workspace {
 
         for item in &mut ui_items {
             let key = Self::completion_memory_key(&language, &prefix, item);
-            item.selection_priority = self.completion_memory.priority(&key);
+            item.selection_priority = self.completion.memory.priority(&key);
         }
-        self.apply_completion_locality_scores(doc_id, view_id, &mut ui_items, cx);
+        let (candidate_cap, locality_scoring) = {
+            let budget = cx.default_global::<nucleotide_ui::CompletionBudget>();
+            (budget.candidate_cap(), budget.locality_scoring_enabled())
+        };
+        if locality_scoring {
+            self.apply_completion_locality_scores(doc_id, view_id, &mut ui_items, cx);
+        }
+        nucleotide_ui::cap_completion_candidates(&mut ui_items, candidate_cap);
 
         nucleotide_logging::debug!(
             ui_item_count = ui_items.len(),
@@ -12802,7 +17967,7 @@ impl Workspace {
             "Converted to UI completion items with prefix, creating filtered completion view"
         );
 
-        self.active_completion_session = Some(ActiveCompletionSession {
+        self.completion.session = Some(ActiveCompletionSession {
             doc_id,
             view_id,
             document_version,
@@ -12835,13 +18000,20 @@ impl Workspace {
         self.core.update(cx, |_core, cx| {
             cx.emit(crate::Update::Completion(completion_view_clone));
         });
+        if let Some(triggered_at) = self.completion.triggered_at.take() {
+            cx.default_global::<nucleotide_ui::CompletionBudget>()
+                .record(
+                    nucleotide_ui::CompletionStage::TriggerToPopup,
+                    triggered_at.elapsed(),
+                );
+        }
         cx.notify();
     }
 
     /// Hide completions
     fn hide_completions(&mut self, cx: &mut Context<Self>) {
         debug!("Hiding completions via overlay dismiss");
-        self.active_completion_session = None;
+        self.completion.dismiss();
         self.overlay.update(cx, |overlay, cx| {
             overlay.dismiss_completion(cx);
         });
@@ -12874,7 +18046,7 @@ impl Workspace {
         nucleotide_logging::debug!("Focusing file tree");
 
         if let Some(file_tree) = &self.file_tree
-            && self.show_file_tree
+            && self.panels.file_tree_visible
         {
             let file_tree_focus = file_tree.focus_handle(cx);
             window.focus(&file_tree_focus, cx);
@@ -12890,13 +18062,13 @@ impl Workspace {
 
     /// Toggle file tree visibility
     pub fn toggle_file_tree_visibility(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        self.show_file_tree = !self.show_file_tree;
+        self.panels.toggle_file_tree();
         nucleotide_logging::debug!(
-            visible = self.show_file_tree,
+            visible = self.panels.file_tree_visible,
             "Toggled file tree visibility"
         );
 
-        if self.show_file_tree {
+        if self.panels.file_tree_visible {
             // If we're showing the file tree, focus it
             self.focus_file_tree(window, cx);
         } else {
@@ -12950,7 +18122,7 @@ impl Workspace {
         &self,
         cx: &mut Context<Self>,
     ) -> Option<(CompletionAcceptTarget, String)> {
-        let session = self.active_completion_session.as_ref()?;
+        let session = self.completion.session.as_ref()?;
         let core = self.core.read(cx);
         let view_doc = core.editor.tree.try_get(session.view_id)?.doc;
         if view_doc != session.doc_id {
@@ -13005,7 +18177,7 @@ impl Workspace {
                 item_index = item_index,
                 "Dropping completion acceptance for stale completion session"
             );
-            self.active_completion_session = None;
+            self.completion.session = None;
             self.overlay.update(cx, |overlay, cx| {
                 overlay.dismiss_completion(cx);
             });
@@ -13123,7 +18295,7 @@ impl Workspace {
         };
 
         if accepted && let Some(key) = completion_memory_key {
-            self.completion_memory.memorize(key);
+            self.completion.memory.memorize(key);
         }
     }
 
@@ -13567,19 +18739,250 @@ impl Workspace {
             modifiers: KeyModifiers::empty(),
         };
 
-        self.input
-            .update(cx, |_, cx| cx.emit(crate::InputEvent::key(key_event)));
-        nucleotide_logging::debug!("Started search mode");
-    }
+        self.input
+            .update(cx, |_, cx| cx.emit(crate::InputEvent::key(key_event)));
+        nucleotide_logging::debug!("Started search mode");
+    }
+
+    /// Start global search across files
+    pub fn start_global_search(&mut self, cx: &mut Context<Self>) {
+        nucleotide_logging::debug!("Starting global search");
+
+        let prompt = crate::prompt::Prompt::native("global-search:", "", |_| {}).with_cancel(|| {});
+        self.core.update(cx, |_core, cx| {
+            cx.emit(crate::Update::Prompt(prompt));
+        });
+    }
+
+    /// Open the live grep picker, which searches the project as the query is
+    /// typed instead of waiting for a submitted search.
+    fn open_live_grep(&mut self, cx: &mut Context<Self>) {
+        let workspace = cx.weak_entity();
+        let core = self.core.downgrade();
+        let runtime_handle = self.handle.clone();
+        let workspace_backend = self.core.read(cx).workspace_backend.clone();
+
+        let picker = crate::picker::Picker::native(LIVE_GREP_TITLE, Vec::new(), |_index| {
+            // Selection is handled by OverlayView via GlobalSearchLocation payloads.
+        })
+        .with_preview(true)
+        .with_preview_text_task_provider_fn(move |item, cx| {
+            let location = item.data.downcast_ref::<GlobalSearchLocation>()?.clone();
+            // Open documents are previewed with their unsaved edits.
+            let open_text = core.upgrade().and_then(|core| {
+                core.read(cx)
+                    .editor
+                    .documents
+                    .values()
+                    .find(|doc| doc.path() == Some(&location.path))
+                    .map(|doc| doc.text().to_string())
+            });
+            let workspace_backend = workspace_backend.clone();
+            let runtime_handle = runtime_handle.clone();
+            Some(cx.spawn(async move |_view, _cx| {
+                let text = match open_text {
+                    Some(text) => text,
+                    None => {
+                        let path = location.path.clone();
+                        let read = runtime_handle
+                            .spawn(async move {
+                                workspace_backend
+                                    .read_file(&path, ReadOptions::default())
+                                    .await
+                            })
+                            .await;
+                        match read {
+                            Ok(Ok(read)) => String::from_utf8_lossy(&read.bytes).into_owned(),
+                            Ok(Err(error)) => format!("Could not read file: {error}"),
+                            Err(error) => format!("Could not read file: {error}"),
+                        }
+                    }
+                };
+                Some((preview_excerpt(&text, location.line), Some(location.path)))
+            }))
+        })
+        .with_query_change_fn(move |query, picker, cx| {
+            if let Some(workspace) = workspace.upgrade() {
+                workspace.update(cx, |workspace, cx| {
+                    workspace.run_live_grep(query, picker, cx);
+                });
+            }
+        });
+
+        self.core.update(cx, |_core, cx| {
+            cx.emit(crate::Update::Picker(picker));
+        });
+    }
+
+    /// Search for `query` once typing pauses, streaming matches into the live
+    /// grep picker. A newer query stops the search in progress.
+    fn run_live_grep(
+        &mut self,
+        query: SharedString,
+        picker: gpui::WeakEntity<crate::picker_view::PickerView>,
+        cx: &mut Context<Self>,
+    ) {
+        self.live_grep_generation = self.live_grep_generation.wrapping_add(1);
+        let generation = self.live_grep_generation;
+
+        let (search_root, smart_case, file_picker_config, open_documents, workspace_backend) = {
+            let core = self.core.read(cx);
+            let search_root = core
+                .project_directory
+                .clone()
+                .unwrap_or_else(helix_stdx::env::current_working_dir);
+            let config = core.editor.config();
+
+            (
+                search_root,
+                config.search.smart_case,
+                config.file_picker.clone(),
+                open_document_texts(&core.editor),
+                core.workspace_backend.clone(),
+            )
+        };
+        let content_index = self
+            .content_index
+            .ready_for(&search_root)
+            .filter(|_| !should_run_global_search_async(&workspace_backend.identity()));
+        let runtime_handle = self.handle.clone();
+        let query = query.to_string();
+
+        cx.spawn(async move |this, cx| {
+            if !query.is_empty() {
+                cx.background_executor().timer(LIVE_GREP_DEBOUNCE).await;
+            }
+            let is_current = |cx: &gpui::AsyncApp| {
+                this.read_with(cx, |workspace, _cx| {
+                    workspace.live_grep_generation == generation
+                })
+                .unwrap_or(false)
+            };
+            if !is_current(cx) {
+                return;
+            }
+
+            if query.is_empty() {
+                let _ = picker.update(cx, |picker, cx| {
+                    picker.set_items(Vec::new(), cx);
+                    picker.set_title(LIVE_GREP_TITLE, cx);
+                });
+                return;
+            }
+
+            let regexes = compile_global_search_regex(&query, smart_case)
+                .and_then(|regex| Ok((regex, line_regex(&query, smart_case)?)));
+            let (regex, highlight_regex) = match regexes {
+                Ok(regexes) => regexes,
+                Err(err) => {
+                    let _ = picker.update(cx, |picker, cx| {
+                        picker.set_items(Vec::new(), cx);
+                        picker.set_title(format!("{LIVE_GREP_TITLE}: {err}"), cx);
+                    });
+                    return;
+                }
+            };
+
+            // Open documents are searched in memory so unsaved edits match.
+            let mut matches = Vec::new();
+            let excluded_relative_paths = global_search_open_document_matches(
+                &mut matches,
+                &search_root,
+                &open_documents,
+                &regex,
+                LIVE_GREP_RESULT_LIMIT,
+            );
+            let mut found = matches.len();
+            let items = live_grep_items(&search_root, matches, &highlight_regex);
+            let updated = picker.update(cx, |picker, cx| {
+                picker.set_items(items, cx);
+                picker.set_title(format!("{LIVE_GREP_TITLE} (searching…)"), cx);
+            });
+            if updated.is_err() {
+                return;
+            }
+
+            let mut error = None;
+            let disk_limit = LIVE_GREP_RESULT_LIMIT.saturating_sub(found);
+            let content_index =
+                content_index.filter(|_| disk_limit > 0 && required_trigrams(&query).is_some());
+            if let Some(content_index) = content_index {
+                let query = query.clone();
+                let regex = highlight_regex.clone();
+                let search = runtime_handle.spawn_blocking(move || {
+                    content_index_matches(
+                        &content_index,
+                        &query,
+                        &regex,
+                        &excluded_relative_paths,
+                        disk_limit,
+                    )
+                    .unwrap_or_default()
+                });
+                match search.await {
+                    Ok(batch) => {
+                        if !is_current(cx) {
+                            return;
+                        }
+                        found += batch.len();
+                        let items = live_grep_items(&search_root, batch, &highlight_regex);
+                        if picker
+                            .update(cx, |picker, cx| picker.extend_items(items, cx))
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
+                    Err(err) => error = Some(err.to_string()),
+                }
+            } else if disk_limit > 0 {
+                let (batch_tx, mut batch_rx) = tokio::sync::mpsc::unbounded_channel();
+                let search = runtime_handle.spawn(global_search_disk_batches_with_backend_async(
+                    workspace_backend,
+                    search_root.clone(),
+                    query.clone(),
+                    smart_case,
+                    file_picker_config,
+                    excluded_relative_paths,
+                    disk_limit,
+                    move |batch| batch_tx.send(batch).is_ok(),
+                ));
+
+                while let Some(batch) = batch_rx.recv().await {
+                    // Returning drops the receiver, which stops the search.
+                    if !is_current(cx) {
+                        return;
+                    }
+                    found += batch.len();
+                    let items = live_grep_items(&search_root, batch, &highlight_regex);
+                    if picker
+                        .update(cx, |picker, cx| picker.extend_items(items, cx))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
 
-    /// Start global search across files
-    pub fn start_global_search(&mut self, cx: &mut Context<Self>) {
-        nucleotide_logging::debug!("Starting global search");
+                error = match search.await {
+                    Ok(Ok(())) => None,
+                    Ok(Err(err)) => Some(err),
+                    Err(err) => Some(err.to_string()),
+                };
+            }
 
-        let prompt = crate::prompt::Prompt::native("global-search:", "", |_| {}).with_cancel(|| {});
-        self.core.update(cx, |_core, cx| {
-            cx.emit(crate::Update::Prompt(prompt));
-        });
+            if !is_current(cx) {
+                return;
+            }
+            let title = match error {
+                Some(err) => format!("{LIVE_GREP_TITLE}: {err}"),
+                None if found >= LIVE_GREP_RESULT_LIMIT => {
+                    format!("{LIVE_GREP_TITLE} (first {found} matches)")
+                }
+                None => format!("{LIVE_GREP_TITLE} ({found} matches)"),
+            };
+            let _ = picker.update(cx, |picker, cx| picker.set_title(title, cx));
+        })
+        .detach();
     }
 
     fn start_file_tree_search(&mut self, initial_query: Option<String>, cx: &mut Context<Self>) {
@@ -13598,7 +19001,7 @@ impl Workspace {
         let query = (!query.is_empty()).then(|| query.to_string());
 
         if let Some(file_tree) = &self.file_tree {
-            self.show_file_tree = true;
+            self.panels.show_file_tree();
             file_tree.update(cx, |tree, cx| {
                 tree.set_search_query(query, cx);
             });
@@ -13664,7 +19067,7 @@ impl Workspace {
         total_area: HelixRect,
         editor_width: f32,
         editor_height: f32,
-        show_focus_indicator: bool,
+        splits: Option<&SplitsConfig>,
         cx: &mut Context<Self>,
     ) -> Option<gpui::AnyElement> {
         let view_entity = self
@@ -13688,10 +19091,42 @@ impl Workspace {
                         .border_color(theme.tokens.chrome.border_default)
                 })
                 .child(view_entity)
-                .when(show_focus_indicator && layout.is_focused, |d| {
-                    d.child(div().absolute().top_0().left_0().bottom_0().w(px(2.0)).bg(
-                        nucleotide_ui::tokens::with_alpha(theme.tokens.editor.focus_ring, 0.8),
-                    ))
+                .when_some(splits, |d, splits| {
+                    let accent =
+                        nucleotide_ui::tokens::with_alpha(theme.tokens.editor.focus_ring, 0.8);
+                    let dim = splits.inactive_dim_opacity();
+                    if !layout.is_focused {
+                        return d.when(dim > 0.0, |d| {
+                            d.child(div().absolute().top_0().left_0().right_0().bottom_0().bg(
+                                nucleotide_ui::tokens::with_alpha(
+                                    theme.tokens.editor.background,
+                                    dim,
+                                ),
+                            ))
+                        });
+                    }
+                    match splits.focus_indicator {
+                        SplitFocusIndicator::None => d,
+                        SplitFocusIndicator::Edge => d.child(
+                            div()
+                                .absolute()
+                                .top_0()
+                                .left_0()
+                                .bottom_0()
+                                .w(px(2.0))
+                                .bg(accent),
+                        ),
+                        SplitFocusIndicator::Border => d.child(
+                            div()
+                                .absolute()
+                                .top_0()
+                                .left_0()
+                                .right_0()
+                                .bottom_0()
+                                .border_1()
+                                .border_color(accent),
+                        ),
+                    }
                 })
                 .into_any_element(),
         )
@@ -14021,8 +19456,8 @@ impl Workspace {
         let gui_config = &self.core.read(cx).config.gui;
         should_render_app_titlebar(
             self.titlebar.is_some(),
-            self.show_file_tree,
-            self.file_tree_width,
+            self.panels.file_tree_visible,
+            self.panels.file_tree_width,
             macos_system_sidebar_enabled(gui_config),
         )
     }
@@ -14071,11 +19506,7 @@ impl Workspace {
         let title_bar_height = self.rendered_titlebar_height(window, cx);
 
         // Get actual file tree width (user may have resized it)
-        let file_tree_width = if self.show_file_tree {
-            px(self.file_tree_width)
-        } else {
-            px(0.0) // No file tree width if hidden
-        };
+        let file_tree_width = px(self.panels.visible_file_tree_width());
 
         // Get font and cursor metrics from the focused DocumentView if available
         let (line_height, char_width, gutter_width, cursor_position, cursor_size) =
@@ -14202,9 +19633,10 @@ impl Workspace {
         panel_height_px: f32,
         cell_height_px: f32,
         cell_width_px: f32,
+        scale_factor: f32,
         cx: &mut Context<Self>,
     ) {
-        if !self.terminal_panel_visible {
+        if !self.panels.terminal_visible {
             self.last_terminal_bounds = None;
             return;
         }
@@ -14214,6 +19646,11 @@ impl Workspace {
             return;
         };
 
+        // Snap cells per window scale; a monitor change alters the snapped
+        // size, which re-derives the bounds below and resizes the PTY.
+        let (cell_width_px, cell_height_px) =
+            nucleotide_types::snap_cell_size(cell_width_px, cell_height_px, scale_factor);
+
         let terminal_content_height_px = (panel_height_px
             - nucleotide_terminal_panel::TERMINAL_PANEL_HEADER_HEIGHT_PX)
             .max(cell_height_px);
@@ -14235,9 +19672,7 @@ impl Workspace {
             .map(|(prev_id, prev_bounds)| *prev_id != active_id || !prev_bounds.approx_eq(&bounds))
             .unwrap_or(true);
 
-        if (self.basic_terminal_height - panel_pixel_height).abs() > 0.5 {
-            self.basic_terminal_height = panel_pixel_height;
-        }
+        self.panels.resize_terminal(panel_pixel_height);
         panel.update(cx, |p, cx| {
             if (p.height_px - panel_pixel_height).abs() > 0.5 {
                 p.height_px = panel_pixel_height;
@@ -14275,6 +19710,12 @@ impl Focusable for Workspace {
 
 impl Render for Workspace {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.first_frame_pending {
+            self.first_frame_pending = false;
+            cx.on_next_frame(window, |workspace, _window, cx| {
+                workspace.finish_startup(cx);
+            });
+        }
         let window_is_active = window.is_window_active();
         if window_is_active && !self.window_was_active {
             self.update_controller
@@ -14282,8 +19723,14 @@ impl Render for Workspace {
         }
         self.window_was_active = window_is_active;
 
+        // Ctrl can be released before the ctrl-tab shortcut round-trips through
+        // the editor keymap, in which case no modifiers event will follow.
+        if self.tab_switcher.is_some() && !window.modifiers().control {
+            self.commit_tab_switcher(cx);
+        }
+
         // Close terminal panel when the shell process has exited
-        if self.terminal_panel_visible
+        if self.panels.terminal_visible
             && let Some(id) = self.terminal_id
             && let Some(vm) = nucleotide_terminal_view::get_view_model(id)
             && match vm.lock() {
@@ -14303,13 +19750,13 @@ impl Render for Workspace {
         }
 
         // Fallback: full refresh if any pending flag remains
-        if self.needs_file_tree_refresh {
+        if self.file_ops.needs_file_tree_refresh {
             if let Some(ref file_tree) = self.file_tree {
                 file_tree.update(cx, |view, tree_cx| {
                     view.refresh(tree_cx);
                 });
             }
-            self.needs_file_tree_refresh = false;
+            self.file_ops.needs_file_tree_refresh = false;
         }
 
         self.sync_file_tree_width_for_viewport(f32::from(window.viewport_size().width));
@@ -14425,10 +19872,36 @@ impl Render for Workspace {
             }
             self.needs_focus_restore = false;
         }
-        let (focused_file_name, native_metadata) = self.focused_native_window_metadata(cx);
+        if self.find_bar.is_open() && self.panels.take_focus(PanelKind::FindBar) {
+            self.find_bar
+                .bar
+                .update(cx, |bar, cx| bar.focus_query(window, cx));
+        }
+        if self.panels.take_focus(PanelKind::TypeHierarchy) {
+            window.focus(&self.right_sidebar.type_hierarchy_focus, cx);
+        }
+        if self.right_sidebar.regex_tester_open && self.panels.take_focus(PanelKind::RegexTester) {
+            self.right_sidebar
+                .regex_tester
+                .update(cx, |tester, cx| tester.focus_pattern(window, cx));
+        }
+        if self.panels.take_focus(PanelKind::HexEditor) {
+            if let Some(hex_editor) = self
+                .active_image_tab_id
+                .and_then(|tab_id| self.image_tabs.iter().find(|tab| tab.id == tab_id))
+                .and_then(|tab| tab.hex_editor().cloned())
+            {
+                window.focus(&hex_editor.focus_handle(cx), cx);
+            }
+        }
+        let native_metadata = self.focused_native_window_metadata(cx);
 
-        self.update_titlebar_filename(focused_file_name.as_deref(), false, cx);
-        self.update_native_window_metadata(window, native_metadata);
+        self.update_titlebar_document(false, cx);
+        self.update_native_window_metadata(window, native_metadata, cx);
+        if window.is_window_active() {
+            let state = self.app_menu_state(window, cx);
+            AppMenus::refresh(state, cx);
+        }
 
         // Recompute theme-derived colors only when marked dirty
         if self.colors_dirty {
@@ -14438,24 +19911,26 @@ impl Render for Workspace {
         let native_sidebar_enabled = macos_system_sidebar_enabled(&self.core.read(cx).config.gui);
         let rendered_titlebar = should_render_app_titlebar(
             self.titlebar.is_some(),
-            self.show_file_tree,
-            self.file_tree_width,
+            self.panels.file_tree_visible,
+            self.panels.file_tree_width,
             native_sidebar_enabled,
         )
         .then(|| self.titlebar.clone())
         .flatten();
         let titlebar_visible = rendered_titlebar.is_some();
-        let titlebar_sidebar_background =
-            if native_sidebar_enabled && self.show_file_tree && self.file_tree_width > 0.0 {
-                let file_tree_tokens = cx.theme().tokens.file_tree_tokens().translucent_sidebar();
-                Some((
-                    px(self.file_tree_width),
-                    file_tree_tokens.background,
-                    file_tree_tokens.separator,
-                ))
-            } else {
-                None
-            };
+        let titlebar_sidebar_background = if native_sidebar_enabled
+            && self.panels.file_tree_visible
+            && self.panels.file_tree_width > 0.0
+        {
+            let file_tree_tokens = cx.theme().tokens.file_tree_tokens().translucent_sidebar();
+            Some((
+                px(self.panels.file_tree_width),
+                file_tree_tokens.background,
+                file_tree_tokens.separator,
+            ))
+        } else {
+            None
+        };
         self.update_titlebar_leading_sidebar_background(titlebar_sidebar_background, cx);
 
         // Compute the editor content dimensions before reading Helix view areas,
@@ -14472,24 +19947,18 @@ impl Render for Workspace {
 
         let min_term = 80.0f32;
         let max_term = (available_h - f32::from(tab_bar_height) - 80.0).max(min_term);
-        if self.basic_terminal_height > max_term {
-            self.basic_terminal_height = max_term;
-        }
+        self.panels.clamp_terminal_height(max_term);
 
         let (line_h_px, char_w_px, _, _, _) = self.get_focused_document_view_layout(cx);
         let line_h_value = f32::from(line_h_px).max(1.0);
         let char_w_value = f32::from(char_w_px).max(1.0);
 
         let viewport_w_px = f32::from(window.viewport_size().width);
-        let file_tree_w_px = if self.show_file_tree {
-            self.file_tree_width
-        } else {
-            0.0
-        };
+        let file_tree_w_px = self.panels.visible_file_tree_width();
         let right_content_w_px = (viewport_w_px - file_tree_w_px).max(1.0);
         self.sync_documentation_sidebar_width_for_viewport(right_content_w_px);
-        let doc_sidebar_w_px = if self.doc_sidebar_visible {
-            self.doc_sidebar_width
+        let doc_sidebar_w_px = if self.right_sidebar.is_visible() {
+            self.right_sidebar.width
         } else {
             0.0
         };
@@ -14497,14 +19966,15 @@ impl Render for Workspace {
 
         self.sync_embedded_terminal_size(
             right_content_w_px,
-            self.basic_terminal_height,
+            self.panels.terminal_height,
             line_h_value,
             char_w_value,
+            window.scale_factor(),
             cx,
         );
 
-        let editor_h = if self.terminal_panel_visible {
-            (available_h - self.basic_terminal_height).max(0.0)
+        let editor_h = if self.panels.terminal_visible {
+            (available_h - self.panels.terminal_height).max(0.0)
         } else {
             available_h
         };
@@ -14540,7 +20010,20 @@ impl Render for Workspace {
         let active_image_tab = self
             .active_image_tab_id
             .and_then(|doc_id| self.image_tabs.iter().find(|tab| tab.id == doc_id).cloned());
-        if let Some(image_tab) = active_image_tab {
+        if let Some(hex_editor) = active_image_tab
+            .as_ref()
+            .and_then(|tab| tab.hex_editor().cloned())
+        {
+            docs_root = docs_root.child(
+                div()
+                    .id("hex-editor-container")
+                    .absolute()
+                    .top_0()
+                    .left_0()
+                    .size_full()
+                    .child(hex_editor),
+            );
+        } else if let Some(image_tab) = active_image_tab {
             docs_root = docs_root.child(
                 div()
                     .id("image-viewer-container")
@@ -14572,6 +20055,9 @@ impl Render for Workspace {
                 }
             } else {
                 if let Some(total_area) = editor_pane_layout.total_area() {
+                    let splits = editor_pane_layout
+                        .show_focus_indicator()
+                        .then(|| self.core.read(cx).config.gui.editor.splits.clone());
                     for pane in editor_pane_layout.panes() {
                         let layout = DocumentViewLayout {
                             view_id: pane.view_id,
@@ -14583,7 +20069,7 @@ impl Render for Workspace {
                             total_area,
                             editor_content_w_px,
                             editor_content_h_px,
-                            editor_pane_layout.show_focus_indicator(),
+                            splits.as_ref(),
                             cx,
                         ) {
                             docs_root = docs_root.child(doc_element);
@@ -14672,6 +20158,15 @@ impl Render for Workspace {
                         .border_color(cx.theme().tokens.chrome.border_default)
                     })
                     .when_some(Some(docs_root), gpui::ParentElement::child)
+                    .when(self.find_bar.is_open(), |this| {
+                        this.child(
+                            div()
+                                .absolute()
+                                .top(px(8.0))
+                                .right(px(16.0))
+                                .child(self.find_bar.bar.clone()),
+                        )
+                    })
                     .child(self.notifications.clone())
                     .when(!self.overlay.read(cx).is_empty(), |this| {
                         debug!("COMP: Workspace rendering overlay because it's not empty");
@@ -14694,24 +20189,28 @@ impl Render for Workspace {
                         |this| this.child(self.info.clone()),
                     )
                     .child(self.key_hints.clone())
-                    .when(self.tab_context_menu.is_open(), |this| {
-                        this.child(
-                            gpui::deferred(self.render_tab_context_menu(window, cx))
-                                .with_priority(100),
-                        )
+                    .when(self.panels.performance_hud_visible, |this| {
+                        this.child(self.render_performance_hud(cx))
                     })
-                    .when(self.tab_bar_split_menu.is_open(), |this| {
-                        this.child(
-                            gpui::deferred(self.render_tab_bar_split_menu(window, cx))
-                                .with_priority(100),
-                        )
+                    .when(self.focus_debugger_refresh.is_some(), |this| {
+                        this.child(self.render_focus_debugger(window, cx))
                     })
-                    .when(self.tab_bar_new_menu.is_open(), |this| {
-                        this.child(
-                            gpui::deferred(self.render_tab_bar_new_menu(window, cx))
-                                .with_priority(100),
-                        )
+                    .when_some(self.shutdown_status(), |this, status| {
+                        this.child(Self::render_shutdown_progress(status, cx))
                     })
+                    .children(
+                        self.context_menus
+                            .render_tab(&self.focus_handle, window, cx),
+                    )
+                    .children(self.context_menus.render_tab_bar_split(
+                        &self.focus_handle,
+                        window,
+                        cx,
+                    ))
+                    .children(
+                        self.context_menus
+                            .render_tab_bar_new(&self.focus_handle, window, cx),
+                    )
                     // Debug overlay tint on top of editor content; render via deferred to ensure top draw order
                     .when(self.debug_colors_enabled, |this| {
                         this.child(
@@ -14757,10 +20256,20 @@ impl Render for Workspace {
         workspace_div = workspace_div
             .track_focus(&self.focus_handle)
             .capture_key_down(cx.listener(|view, ev, _window, cx| {
-                if view.handle_regular_completion_menu_key(ev, cx) {
+                if view.handle_tab_switcher_key(ev, cx)
+                    || view.handle_regular_completion_menu_key(ev, cx)
+                {
                     cx.stop_propagation();
                 }
             }))
+            .on_modifiers_changed(
+                cx.listener(|view, ev: &ModifiersChangedEvent, _window, cx| {
+                    // Releasing ctrl commits the ctrl-tab switcher selection.
+                    if view.tab_switcher.is_some() && !ev.modifiers.control {
+                        view.commit_tab_switcher(cx);
+                    }
+                }),
+            )
             .on_key_down(cx.listener(|view, ev, window, cx| {
                 if view.modal_layer.read(cx).has_active_modal() {
                     cx.stop_propagation();
@@ -14810,9 +20319,10 @@ impl Render for Workspace {
         workspace_div = workspace_div.on_mouse_down(
             MouseButton::Left,
             cx.listener(|workspace, _event: &MouseDownEvent, _window, cx| {
-                if workspace.close_tab_bar_menus() {
+                if workspace.context_menus.close_tab_bar_menus(cx) {
                     cx.notify();
                 }
+                workspace.tab_switcher = None;
 
                 // Ensure workspace regains focus when clicked, so global shortcuts work
                 workspace.needs_focus_restore = true;
@@ -14877,6 +20387,19 @@ impl Render for Workspace {
             },
         ));
 
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::help::TogglePerformanceHud, _window, cx| {
+                workspace.panels.toggle_performance_hud();
+                cx.notify();
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::help::ToggleFocusDebugger, _window, cx| {
+                workspace.toggle_focus_debugger(cx);
+            },
+        ));
+
         // Theme Debug action opens the overlay
         workspace_div = workspace_div.on_action(cx.listener(
             move |workspace, _: &crate::actions::help::ThemeDebug, window, cx| {
@@ -14897,12 +20420,9 @@ impl Render for Workspace {
         ));
 
         // Global editor actions that work regardless of focus
-        let handle = self.handle.clone();
-        let core = self.core.clone();
         workspace_div = workspace_div.on_action(cx.listener(
-            move |_, _: &crate::actions::editor::Quit, _window, cx| {
-                quit(core.clone(), handle.clone(), cx);
-                cx.quit();
+            move |workspace, _: &crate::actions::editor::Quit, _window, cx| {
+                workspace.request_quit(cx);
             },
         ));
 
@@ -14925,6 +20445,12 @@ impl Render for Workspace {
             },
         ));
 
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::CloseFolder, _window, cx| {
+                workspace.close_project_folder(cx)
+            },
+        ));
+
         workspace_div = workspace_div.on_action(cx.listener(
             move |workspace, _: &crate::actions::editor::OpenRemote, _window, cx| {
                 workspace.show_open_remote_prompt(cx)
@@ -14950,6 +20476,12 @@ impl Render for Workspace {
             },
         ));
 
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::OpenWorkspaceSettings, _window, cx| {
+                workspace.open_workspace_settings_file(cx)
+            },
+        ));
+
         // Reload configuration action - reload nucleotide.toml without restart
         workspace_div = workspace_div.on_action(cx.listener(
             move |workspace, _: &crate::actions::editor::ReloadConfiguration, _window, cx| {
@@ -14966,8 +20498,16 @@ impl Render for Workspace {
 
         workspace_div = workspace_div.on_action(cx.listener(
             move |workspace, _: &crate::actions::editor::SaveAs, _window, cx| {
-                // TODO: Implement save as with file dialog
-                workspace.execute_raw_command("write", cx);
+                let doc_id = {
+                    let editor = &workspace.core.read(cx).editor;
+                    editor.tree.try_get(editor.tree.focus).map(|view| view.doc)
+                };
+                match doc_id {
+                    Some(doc_id) if workspace.save_dialogs_available(cx) => {
+                        workspace.save_document_as(doc_id, cx)
+                    }
+                    _ => workspace.execute_raw_command("write", cx),
+                }
             },
         ));
 
@@ -15010,22 +20550,188 @@ impl Render for Workspace {
         ));
 
         workspace_div = workspace_div.on_action(cx.listener(
-            move |workspace, _: &crate::actions::text_input::Copy, window, cx| {
-                workspace.send_editor_semantic_action(
-                    crate::application::editor_input::EditorSemanticAction::Copy,
-                    window,
-                    cx,
-                );
+            move |workspace, _: &crate::actions::text_input::Copy, window, cx| {
+                workspace.send_editor_semantic_action(
+                    crate::application::editor_input::EditorSemanticAction::Copy,
+                    window,
+                    cx,
+                );
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::text_input::Paste, window, cx| {
+                workspace.send_editor_semantic_action(
+                    crate::application::editor_input::EditorSemanticAction::Paste,
+                    window,
+                    cx,
+                );
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::DuplicateLines, window, cx| {
+                workspace.send_editor_semantic_action(
+                    crate::application::editor_input::EditorSemanticAction::DuplicateLines,
+                    window,
+                    cx,
+                );
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::MoveLinesUp, window, cx| {
+                workspace.send_editor_semantic_action(
+                    crate::application::editor_input::EditorSemanticAction::MoveLinesUp,
+                    window,
+                    cx,
+                );
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::MoveLinesDown, window, cx| {
+                workspace.send_editor_semantic_action(
+                    crate::application::editor_input::EditorSemanticAction::MoveLinesDown,
+                    window,
+                    cx,
+                );
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::JoinLines, window, cx| {
+                workspace.send_editor_semantic_action(
+                    crate::application::editor_input::EditorSemanticAction::JoinLines,
+                    window,
+                    cx,
+                );
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::ToggleComment, window, cx| {
+                workspace.send_editor_semantic_action(
+                    crate::application::editor_input::EditorSemanticAction::ToggleComment,
+                    window,
+                    cx,
+                );
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::ReflowParagraph, _window, cx| {
+                workspace.reflow_focused_document(None, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::TransformUpperCase, _window, cx| {
+                workspace
+                    .transform_selections(crate::text_transforms::TextTransform::UpperCase, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::TransformLowerCase, _window, cx| {
+                workspace
+                    .transform_selections(crate::text_transforms::TextTransform::LowerCase, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::TransformTitleCase, _window, cx| {
+                workspace
+                    .transform_selections(crate::text_transforms::TextTransform::TitleCase, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::TransformCamelCase, _window, cx| {
+                workspace
+                    .transform_selections(crate::text_transforms::TextTransform::CamelCase, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::TransformSnakeCase, _window, cx| {
+                workspace
+                    .transform_selections(crate::text_transforms::TextTransform::SnakeCase, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::TransformKebabCase, _window, cx| {
+                workspace
+                    .transform_selections(crate::text_transforms::TextTransform::KebabCase, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::TransformPascalCase, _window, cx| {
+                workspace
+                    .transform_selections(crate::text_transforms::TextTransform::PascalCase, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::SortLines, _window, cx| {
+                workspace.apply_line_operation(crate::line_edits::LineOperation::SortAscending, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::SortLinesDescending, _window, cx| {
+                workspace
+                    .apply_line_operation(crate::line_edits::LineOperation::SortDescending, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::SortLinesNumeric, _window, cx| {
+                workspace.apply_line_operation(crate::line_edits::LineOperation::SortNumeric, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace,
+                  _: &crate::actions::editor::SortLinesNumericDescending,
+                  _window,
+                  cx| {
+                workspace.apply_line_operation(
+                    crate::line_edits::LineOperation::SortNumericDescending,
+                    cx,
+                );
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::UniqueLines, _window, cx| {
+                workspace.apply_line_operation(crate::line_edits::LineOperation::Unique, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::ReverseLines, _window, cx| {
+                workspace.apply_line_operation(crate::line_edits::LineOperation::Reverse, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::ShuffleLines, _window, cx| {
+                workspace.apply_line_operation(crate::line_edits::LineOperation::Shuffle, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::editor::AlignBy, _window, cx| {
+                workspace.show_align_prompt(cx);
             },
         ));
 
         workspace_div = workspace_div.on_action(cx.listener(
-            move |workspace, _: &crate::actions::text_input::Paste, window, cx| {
-                workspace.send_editor_semantic_action(
-                    crate::application::editor_input::EditorSemanticAction::Paste,
-                    window,
-                    cx,
-                );
+            move |workspace, _: &crate::actions::editor::GenerateDocComment, _window, cx| {
+                workspace.generate_doc_comment(cx);
             },
         ));
 
@@ -15125,15 +20831,117 @@ impl Render for Workspace {
             },
         ));
 
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ShowRunConfigurations, _window, cx| {
+                workspace.show_run_configurations_picker(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::RunConfiguration, _window, cx| {
+                workspace.run_default_configuration(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ShowRunProblems, _window, cx| {
+                workspace.show_run_problems(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ShowCargoSettings, _window, cx| {
+                workspace.show_cargo_settings(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ShowIndentation, _window, cx| {
+                workspace.show_indentation_picker(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::NewProject, _window, cx| {
+                workspace.show_new_project_picker(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::NewScratchBuffer, _window, cx| {
+                workspace.show_new_scratch_prompt(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ShowScratchBuffers, _window, cx| {
+                workspace.show_scratch_buffers_picker(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::CompareActiveFileWith, _window, cx| {
+                workspace.show_compare_picker(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::CompareWithClipboard, _window, cx| {
+                workspace.compare_with_clipboard(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::NextDifference, _window, cx| {
+                workspace.run_compare_action(CompareAction::Next, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::PreviousDifference, _window, cx| {
+                workspace.run_compare_action(CompareAction::Previous, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::CopyDifferenceLeft, _window, cx| {
+                workspace.run_compare_action(CompareAction::CopyLeft, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::CopyDifferenceRight, _window, cx| {
+                workspace.run_compare_action(CompareAction::CopyRight, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::CloseComparison, _window, cx| {
+                workspace.run_compare_action(CompareAction::Close, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ShowNpmScripts, _window, cx| {
+                workspace.show_npm_scripts(cx);
+            },
+        ));
+
         // Toggle file tree action
         workspace_div = workspace_div.on_action(cx.listener(
             move |workspace, _: &crate::actions::workspace::ToggleFileTree, _window, cx| {
                 info!("ToggleFileTree action triggered from menu");
-                workspace.show_file_tree = !workspace.show_file_tree;
+                workspace.panels.toggle_file_tree();
                 cx.notify();
             },
         ));
 
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::RevealActiveFileInTree, _window, cx| {
+                workspace.reveal_active_file_in_tree(cx);
+            },
+        ));
+
         workspace_div = workspace_div.on_action(cx.listener(
             move |workspace, _: &crate::actions::workspace::ToggleDocumentation, _window, cx| {
                 info!("ToggleDocumentation action triggered from menu");
@@ -15174,6 +20982,13 @@ impl Render for Workspace {
             },
         ));
 
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ShowLayoutMenu, window, cx| {
+                let position = window.mouse_position();
+                workspace.toggle_tab_bar_split_menu(position, window, cx);
+            },
+        ));
+
         workspace_div = workspace_div.on_action(cx.listener(
             move |workspace, _: &crate::actions::workspace::UnpinAllTabs, _window, cx| {
                 workspace.unpin_all_tabs(cx);
@@ -15187,56 +21002,201 @@ impl Render for Workspace {
         ));
 
         workspace_div = workspace_div.on_action(cx.listener(
-            move |workspace, action: &crate::actions::project_tree::Operation, window, cx| {
-                window.prevent_default();
-                workspace.close_file_tree_context_menu(cx);
-                if let Some(path) = workspace.context_menu_path.clone() {
-                    workspace.handle_project_tree_operation(
-                        action.intent,
-                        path,
-                        workspace.context_menu_is_directory,
-                        cx,
-                    );
-                }
-                cx.stop_propagation();
+            move |workspace, _: &crate::actions::workspace::ShowAllTabs, _window, cx| {
+                workspace.show_all_tabs(cx);
             },
         ));
 
         workspace_div = workspace_div.on_action(cx.listener(
-            move |workspace, action: &crate::actions::tab_menus::ContextOperation, window, cx| {
-                window.prevent_default();
-                let target_doc_id = workspace.tab_context_menu_doc_id;
-                if workspace.close_tab_context_menu() {
-                    cx.notify();
-                }
-                if let Some(doc_id) = target_doc_id {
-                    let handler = Workspace::tab_context_menu_handler(action.intent);
-                    handler(workspace, doc_id, cx);
-                }
-                cx.stop_propagation();
+            move |workspace, _: &crate::actions::workspace::ShowLspTraffic, _window, cx| {
+                workspace.show_lsp_traffic(cx);
             },
         ));
 
         workspace_div = workspace_div.on_action(cx.listener(
-            move |workspace, action: &crate::actions::tab_menus::SplitOperation, window, cx| {
-                window.prevent_default();
-                workspace.activate_tab_bar_split_menu_intent(action.intent, cx);
-                cx.stop_propagation();
+            move |workspace,
+                  _: &crate::actions::workspace::ShowEnvironmentInspector,
+                  _window,
+                  cx| {
+                workspace.show_environment_inspector(None, cx);
             },
         ));
 
         workspace_div = workspace_div.on_action(cx.listener(
-            move |workspace, action: &crate::actions::tab_menus::NewOperation, window, cx| {
-                window.prevent_default();
-                if workspace.close_tab_bar_new_menu() {
-                    cx.notify();
+            move |workspace, _: &crate::actions::workspace::ClearUsageData, _window, cx| {
+                workspace.clear_usage_data(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ExportDocumentAsHtml, _window, cx| {
+                workspace.export_document(DocumentExportFormat::Html, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ExportDocumentAsPdf, _window, cx| {
+                workspace.export_document(DocumentExportFormat::Pdf, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ExportSnippetAsSvg, _window, cx| {
+                workspace.export_editor_snippet(SnippetExportFormat::Svg, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ExportSnippetAsHtml, _window, cx| {
+                workspace.export_editor_snippet(SnippetExportFormat::Html, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::terminal::Paste, _window, cx| {
+                workspace.paste_into_terminal(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ExportTerminalAsSvg, _window, cx| {
+                workspace.export_terminal_snippet(SnippetExportFormat::Svg, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ExportTerminalAsHtml, _window, cx| {
+                workspace.export_terminal_snippet(SnippetExportFormat::Html, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::git::Fetch, _window, cx| {
+                workspace.run_git_operation(GitOperation::Fetch, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::git::Pull, _window, cx| {
+                workspace.run_git_operation(GitOperation::Pull, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::git::Push, _window, cx| {
+                workspace.run_git_operation(GitOperation::Push, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::git::Stash, _window, cx| {
+                workspace.run_git_operation(GitOperation::Stash, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::git::StashPop, _window, cx| {
+                workspace.run_git_operation(GitOperation::StashPop, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::git::ShowConflicts, _window, cx| {
+                workspace.show_merge_conflicts(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::git::NextConflict, _window, cx| {
+                workspace.navigate_merge_conflict(ConflictDirection::Next, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::git::PreviousConflict, _window, cx| {
+                workspace.navigate_merge_conflict(ConflictDirection::Previous, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::git::AcceptOurs, _window, cx| {
+                workspace.resolve_merge_conflict_at_cursor(MergeConflictResolution::Ours, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::git::AcceptTheirs, _window, cx| {
+                workspace.resolve_merge_conflict_at_cursor(MergeConflictResolution::Theirs, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::git::AcceptBoth, _window, cx| {
+                workspace.resolve_merge_conflict_at_cursor(MergeConflictResolution::Both, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::git::ShowLog, _window, cx| {
+                workspace.show_git_log(None, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::git::ShowFileHistory, _window, cx| {
+                workspace.show_focused_file_history(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |_workspace, _: &crate::actions::git::CancelOperation, _window, cx| {
+                if let Some(vcs) = cx
+                    .try_global::<VcsServiceHandle>()
+                    .map(|vcs| vcs.service().clone())
+                {
+                    vcs.update(cx, |service, _cx| service.cancel_git_operation());
                 }
-                let handler = Workspace::tab_bar_new_menu_handler(action.intent);
-                handler(workspace, cx);
-                cx.stop_propagation();
             },
         ));
 
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ActivateNextTab, _window, cx| {
+                workspace.activate_adjacent_tab(TabCycleDirection::Next, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ActivatePreviousTab, _window, cx| {
+                workspace.activate_adjacent_tab(TabCycleDirection::Previous, cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ActivateLastUsedTab, _window, cx| {
+                workspace.activate_last_used_tab(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ReopenClosedTab, _window, cx| {
+                workspace.reopen_closed_tab(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ShowTypeHierarchy, _window, cx| {
+                workspace.open_type_hierarchy(cx);
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ToggleRegexTester, _window, cx| {
+                workspace.toggle_regex_tester(cx);
+            },
+        ));
+
+        workspace_div = ContextMenuState::register_actions(workspace_div, cx);
+
         // File finder action
         workspace_div = workspace_div.on_action(cx.listener(
             move |workspace, _: &crate::actions::workspace::ShowFileFinder, _window, cx| {
@@ -15256,6 +21216,34 @@ impl Render for Workspace {
             },
         ));
 
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, action: &crate::actions::app_menu::OpenRecent, _window, cx| {
+                if action.directory {
+                    workspace.handle_open_directory(&action.path, cx);
+                } else {
+                    workspace.handle_open_file(&action.path, cx);
+                }
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |_workspace, action: &crate::actions::app_menu::ActivateWindow, _window, cx| {
+                let target = cx
+                    .windows()
+                    .into_iter()
+                    .find(|window| window.window_id().as_u64() == action.window_id);
+                if let Some(target) = target {
+                    let _ = target.update(cx, |_, window, _cx| window.activate_window());
+                }
+            },
+        ));
+
+        workspace_div = workspace_div.on_action(cx.listener(
+            move |workspace, _: &crate::actions::workspace::ShareFile, _window, cx| {
+                workspace.share_focused_file(cx);
+            },
+        ));
+
         // NewWindow action
         workspace_div = workspace_div.on_action(cx.listener(
             move |_workspace, _: &crate::actions::workspace::NewWindow, _window, _cx| {
@@ -15368,8 +21356,7 @@ impl Render for Workspace {
                                     terminal_line_height_px,
                                 )
                                 .clamp(80.0, panel_max);
-                            if (this.basic_terminal_height - snapped_h).abs() > 0.5 {
-                                this.basic_terminal_height = snapped_h;
+                            if this.panels.resize_terminal(snapped_h) {
                                 if let Some(panel) = &this.embedded_terminal_panel {
                                     panel.update(cx, |p, _| p.height_px = snapped_h);
                                 }
@@ -15397,7 +21384,7 @@ impl Render for Workspace {
                         .child(main_content),
                 );
 
-                if self.terminal_panel_visible {
+                if self.panels.terminal_visible {
                     // Bottom terminal panel using shared split helper inside an absolute wrapper.
                     // Keep terminal focus and key handling scoped to the bottom panel content so
                     // editor clicks above it can focus documents normally.
@@ -15409,7 +21396,7 @@ impl Render for Workspace {
                             .right_0()
                             .bottom_0()
                             .child(nucleotide_ui::bottom_panel_split(
-                                self.basic_terminal_height,
+                                self.panels.terminal_height,
                                 80.0,
                                 panel_max,
                                 SPLIT_PANE_HANDLE_HITBOX_PX,
@@ -15444,15 +21431,14 @@ impl Render for Workspace {
 
                 let editor_stack = root;
 
-                if self.doc_sidebar_visible {
+                if self.right_sidebar.is_visible() {
                     let resize_available_w = right_content_w_px;
                     let max_width = Self::max_documentation_sidebar_width(resize_available_w);
                     let on_change_width = {
                         let entity = cx.entity().clone();
                         move |new_width: f32, app_cx: &mut gpui::App| {
                             entity.update(app_cx, |this: &mut Workspace, cx| {
-                                if (this.doc_sidebar_width - new_width).abs() > 0.5 {
-                                    this.doc_sidebar_width = new_width;
+                                if this.right_sidebar.resize(new_width) {
                                     cx.notify();
                                 }
                             });
@@ -15465,14 +21451,22 @@ impl Render for Workspace {
                         .h(content_max_h)
                         .min_h(px(0.0))
                         .child(nucleotide_ui::right_sidebar_split(
-                            self.doc_sidebar_width,
+                            self.right_sidebar.width,
                             DOC_SIDEBAR_MIN_WIDTH,
                             max_width,
                             SPLIT_PANE_HANDLE_HITBOX_PX,
                             DOC_SIDEBAR_DEFAULT_WIDTH,
                             on_change_width,
                             editor_stack,
-                            self.render_documentation_sidebar(cx),
+                            if self.right_sidebar.type_hierarchy.is_some() {
+                                self.render_type_hierarchy_panel(window, cx)
+                            } else if self.right_sidebar.regex_tester_open {
+                                self.render_regex_tester_panel(cx)
+                            } else if self.right_sidebar.references.is_some() {
+                                self.render_references_panel(cx)
+                            } else {
+                                self.render_documentation_sidebar(cx)
+                            },
                         ))
                         .into_any_element()
                 } else {
@@ -15480,7 +21474,7 @@ impl Render for Workspace {
                 }
             };
 
-            if self.show_file_tree {
+            if self.panels.file_tree_visible {
                 let viewport_w = f32::from(window.viewport_size().width);
                 let max_left = Self::max_file_tree_width(viewport_w);
                 let default_width = Self::clamped_file_tree_default_width(viewport_w);
@@ -15488,15 +21482,7 @@ impl Render for Workspace {
                     let entity = cx.entity().clone();
                     move |new_width: f32, app_cx: &mut gpui::App| {
                         entity.update(app_cx, |this: &mut Workspace, cx| {
-                            let next_override =
-                                ((new_width - default_width).abs() > 0.5).then_some(new_width);
-                            let changed = (this.file_tree_width - new_width).abs() > 0.5
-                                || this.file_tree_width_override != next_override;
-
-                            this.file_tree_width = new_width;
-                            this.file_tree_width_override = next_override;
-
-                            if changed {
+                            if this.panels.resize_file_tree(new_width, default_width) {
                                 cx.notify();
                             }
                         });
@@ -15603,7 +21589,7 @@ impl Render for Workspace {
                 }
 
                 container = container.child(nucleotide_ui::sidebar_split(
-                    self.file_tree_width,
+                    self.panels.file_tree_width,
                     FILE_TREE_MIN_WIDTH,
                     max_left,
                     SPLIT_PANE_HANDLE_HITBOX_PX,
@@ -15613,11 +21599,13 @@ impl Render for Workspace {
                     right,
                 ));
 
-                if self.file_tree_context_menu.is_open() {
-                    container = container.child(
-                        gpui::deferred(self.render_file_tree_context_menu(window, cx))
-                            .with_priority(100),
-                    );
+                container = container.children(self.context_menus.render_file_tree(
+                    &self.focus_handle,
+                    window,
+                    cx,
+                ));
+                if let Some(preview) = self.render_file_tree_preview(file_tree_top_inset, cx) {
+                    container = container.child(gpui::deferred(preview).with_priority(90));
                 }
 
                 container.into_any_element()
@@ -15630,21 +21618,19 @@ impl Render for Workspace {
                     .min_h(px(0.0))
                     .child(right);
 
-                if self.file_tree_context_menu.is_open() {
-                    container = container.child(
-                        gpui::deferred(self.render_file_tree_context_menu(window, cx))
-                            .with_priority(100),
-                    );
-                }
+                container = container.children(self.context_menus.render_file_tree(
+                    &self.focus_handle,
+                    window,
+                    cx,
+                ));
 
                 container.into_any_element()
             }
         };
 
         // If terminal was toggled on via button, focus it now (after elements are built)
-        if self.terminal_panel_visible && self.terminal_focus_pending {
+        if self.panels.take_terminal_focus() {
             self.focus_terminal_panel(window, cx);
-            self.terminal_focus_pending = false;
         }
 
         // Build final workspace with unified bottom status bar
@@ -15669,6 +21655,10 @@ impl Render for Workspace {
                     )
                     .child(self.render_unified_status_bar(window, cx)), // Unified bottom status bar pinned at bottom
             )
+            .children(
+                self.context_menus
+                    .render_content(&self.focus_handle, window, cx),
+            )
             // Add Linux client-side resize hitboxes so the window can be resized
             .map(|root| {
                 #[cfg(target_os = "linux")]
@@ -15794,7 +21784,7 @@ impl Render for Workspace {
                 }
                 root
             })
-            .when(self.lsp_menu_open, |container| {
+            .when(self.context_menus.lsp_open, |container| {
                 use gpui::{Anchor, point};
                 let ui_theme = cx.global::<nucleotide_ui::Theme>();
                 let dd_tokens = ui_theme.tokens.dropdown_tokens();
@@ -15803,6 +21793,7 @@ impl Render for Workspace {
                 // Snapshot LSP state
                 let server_rows: Vec<gpui::AnyElement> = {
                     let lsp_state_entity = self.core.read(cx).lsp_state.clone();
+                    let request_guard = self.core.read(cx).lsp_request_guard.clone();
                     if let Some(lsp_state) = lsp_state_entity {
                         let state = lsp_state.read(cx);
                         let mut rows: Vec<gpui::AnyElement> = Vec::new();
@@ -15834,6 +21825,7 @@ impl Render for Workspace {
                         }
 
                         for server in servers {
+                            let request_health = request_guard.health(server.id);
                             let (status_text, status_color) = match &server.status {
                                 ServerStatus::Starting => {
                                     ("Starting".to_string(), notification_tokens.info_text)
@@ -15841,6 +21833,9 @@ impl Render for Workspace {
                                 ServerStatus::Initializing => {
                                     ("Initializing".to_string(), notification_tokens.info_text)
                                 }
+                                ServerStatus::Running if request_health.is_degraded() => {
+                                    ("Degraded".to_string(), notification_tokens.warning_text)
+                                }
                                 ServerStatus::Running => {
                                     ("Running".to_string(), notification_tokens.success_text)
                                 }
@@ -15918,6 +21913,24 @@ impl Render for Workspace {
                                 );
                             }
 
+                            if matches!(server.status, ServerStatus::Running)
+                                && let Some(detail) = lsp_request_health_detail(
+                                    request_health,
+                                    std::time::Instant::now(),
+                                )
+                            {
+                                rows.push(
+                                    div()
+                                        .w_full()
+                                        .px(ui_theme.tokens.sizes.space_3)
+                                        .pb(ui_theme.tokens.sizes.space_2)
+                                        .text_size(ui_theme.tokens.sizes.text_xs)
+                                        .text_color(notification_tokens.warning_text)
+                                        .child(detail)
+                                        .into_any_element(),
+                                );
+                            }
+
                             // Progress rows for this server, or Idle if none
                             let progress_items =
                                 progress_by_server.remove(&server.id).unwrap_or_default();
@@ -16019,7 +22032,7 @@ impl Render for Workspace {
                     .icon("icons/rotate-ccw.svg")
                     .tooltip("Restart project language servers")
                     .aria_label("Restart project language servers")
-                    .focus_handle(self.statusbar_lsp_restart_focus.clone())
+                    .focus_handle(self.statusbar_focus.lsp_restart.clone())
                     .disabled(!restart_enabled)
                     .on_click(cx.listener(move |this, _event, _window, cx| {
                         let (Some(plan), Some(sender)) =
@@ -16058,7 +22071,7 @@ impl Render for Workspace {
                             },
                             cx,
                         );
-                        this.lsp_menu_open = false;
+                        this.context_menus.lsp_open = false;
                         cx.notify();
                     }));
 
@@ -16077,7 +22090,7 @@ impl Render for Workspace {
                     .icon("icons/file-text.svg")
                     .tooltip(logs_tooltip)
                     .aria_label("Copy LSP traffic log directory")
-                    .focus_handle(self.statusbar_lsp_logs_focus.clone())
+                    .focus_handle(self.statusbar_focus.lsp_logs.clone())
                     .disabled(!logs_enabled)
                     .on_click(cx.listener(move |this, _event, _window, cx| {
                         if Self::copy_to_clipboard_impl(&log_path_for_click) {
@@ -16103,7 +22116,7 @@ impl Render for Workspace {
                     .child(restart_button)
                     .child(logs_button);
 
-                let (x, y) = self.lsp_menu_pos;
+                let (x, y) = self.context_menus.lsp_position;
 
                 container.child(
                     PopupMenuSurface::new(
@@ -16124,7 +22137,7 @@ impl Render for Workspace {
                     .snap_margin(ui_theme.tokens.sizes.space_2)
                     .on_light_dismiss(cx.listener(
                         |this: &mut Workspace, _ev, window, cx| {
-                            this.lsp_menu_open = false;
+                            this.context_menus.lsp_open = false;
                             if let Some(coord) =
                                 cx.try_global::<nucleotide_ui::FocusCoordinator>().cloned()
                             {
@@ -16142,6 +22155,9 @@ impl Render for Workspace {
                     )),
                 )
             })
+            .when_some(self.render_tab_switcher(cx), |root, switcher| {
+                root.child(gpui::deferred(switcher).with_priority(100))
+            })
             .child(self.modal_layer.clone())
     }
 }
@@ -17227,7 +23243,12 @@ fn show_hover_docs(core: Entity<Core>, _handle: tokio::runtime::Handle, cx: &mut
             let mut requested = 0usize;
 
             let futures: FuturesOrdered<_> = doc
-                .language_servers_with_feature(LanguageServerFeature::Hover)
+                .language_servers_for_merge(
+                    &editor.language_server_merge,
+                    LanguageServerFeature::Hover,
+                    |policy| policy.hover,
+                )
+                .into_iter()
                 .filter(|ls| seen.insert(ls.id()))
                 .filter_map(|language_server| {
                     requested += 1;
@@ -17272,7 +23293,11 @@ fn show_hover_docs(core: Entity<Core>, _handle: tokio::runtime::Handle, cx: &mut
             match result {
                 Ok((server_name, Some(hover))) => {
                     let markdown = hover_contents_to_markdown(hover.contents);
-                    if !markdown.trim().is_empty() {
+                    // Servers are in priority order; identical docs from a
+                    // lower priority server add nothing.
+                    if !markdown.trim().is_empty()
+                        && !entries.iter().any(|entry| entry.markdown == markdown)
+                    {
                         entries.push(HoverDocEntry {
                             server_name,
                             markdown,
@@ -17429,6 +23454,41 @@ mod tests {
         assert!(cached_tab_bar_documents(Some(&cache), 7, &changed_key).is_none());
     }
 
+    #[test]
+    fn tab_bar_document_patches_only_copy_changed_documents() {
+        let document = |order| crate::tab_bar::DocumentInfo {
+            id: TabId::Image(order as u64),
+            path: Some(PathBuf::from(format!("/workspace/{order}.rs"))),
+            is_modified: false,
+            is_readonly: false,
+            is_deleted: false,
+            is_pinned: false,
+            is_preview: false,
+            focused_at: std::time::Instant::now(),
+            order,
+            git_status: None,
+            diagnostic_severity: None,
+            diagnostic_count: 0,
+        };
+        let documents = [document(0), document(1)];
+
+        assert!(patch_tab_bar_documents(&documents, |_| false).is_none());
+
+        let patched = patch_tab_bar_documents(&documents, |document| {
+            if document.order != 1 {
+                return false;
+            }
+            document.git_status = Some(nucleotide_types::VcsStatus::Modified);
+            true
+        })
+        .expect("one document changed");
+        assert_eq!(patched[0].git_status, None);
+        assert_eq!(
+            patched[1].git_status,
+            Some(nucleotide_types::VcsStatus::Modified)
+        );
+    }
+
     #[test]
     fn buffer_close_aliases_preserve_force() {
         for command in ["close", "bc", "bclose", "buffer-close"] {
@@ -17454,14 +23514,14 @@ mod tests {
     }
 
     #[test]
-    fn remote_document_reload_decision_reloads_visible_clean_match() {
+    fn document_reload_decision_reloads_visible_clean_match() {
         let path = Path::new("/remote/project/src/lib.rs");
         let docs = [(1_u8, Some(path), false)];
         let views = [(7_u8, 1_u8)];
 
         assert_eq!(
-            remote_document_reload_decision(docs, views, path),
-            RemoteDocumentReloadDecision::Reload {
+            document_reload_decision(docs, views, path),
+            DocumentReloadDecision::Reload {
                 doc_id: 1,
                 view_id: 7,
             }
@@ -17469,77 +23529,39 @@ mod tests {
     }
 
     #[test]
-    fn remote_document_reload_decision_reports_dirty_match() {
+    fn document_reload_decision_reports_dirty_match() {
         let path = Path::new("/remote/project/src/lib.rs");
         let docs = [(1_u8, Some(path), true)];
         let views = [(7_u8, 1_u8)];
 
         assert_eq!(
-            remote_document_reload_decision(docs, views, path),
-            RemoteDocumentReloadDecision::Dirty { doc_id: 1 }
+            document_reload_decision(docs, views, path),
+            DocumentReloadDecision::Dirty { doc_id: 1 }
         );
     }
 
     #[test]
-    fn remote_document_reload_decision_reports_hidden_match() {
+    fn document_reload_decision_reports_hidden_match() {
         let path = Path::new("/remote/project/src/lib.rs");
         let docs = [(1_u8, Some(path), false)];
         let views = [(7_u8, 2_u8)];
 
         assert_eq!(
-            remote_document_reload_decision(docs, views, path),
-            RemoteDocumentReloadDecision::Hidden { doc_id: 1 }
+            document_reload_decision(docs, views, path),
+            DocumentReloadDecision::Hidden { doc_id: 1 }
         );
     }
 
     #[test]
-    fn remote_document_reload_decision_ignores_unmatched_paths() {
+    fn document_reload_decision_ignores_unmatched_paths() {
         let path = Path::new("/remote/project/src/lib.rs");
         let other = Path::new("/remote/project/src/main.rs");
         let docs = [(1_u8, Some(other), false), (2_u8, None, false)];
         let views = [(7_u8, 1_u8)];
 
         assert_eq!(
-            remote_document_reload_decision(docs, views, path),
-            RemoteDocumentReloadDecision::NoMatch
-        );
-    }
-
-    #[test]
-    fn trash_delete_preference_degrades_to_confirmed_permanent_delete() {
-        let remote_identity =
-            WorkspaceIdentity::Remote(nucleotide_workspace::RemoteWorkspaceIdentity {
-                kind: nucleotide_workspace::RemoteWorkspaceKind::Ssh,
-                name: "example.test".to_string(),
-            });
-
-        let mode = effective_delete_mode(crate::config::DeleteBehavior::Trash, &remote_identity);
-
-        assert_eq!(
-            mode,
-            nucleotide_events::v2::workspace::DeleteMode::Permanent
-        );
-        assert!(delete_confirmation_required(mode));
-        assert_eq!(delete_confirmation_label(mode), "Delete Permanently");
-    }
-
-    #[test]
-    fn delete_confirmation_message_matches_effective_mode() {
-        let path = Path::new("/remote/project/src/lib.rs");
-
-        assert_eq!(
-            delete_confirmation_message(
-                Some(path),
-                nucleotide_events::v2::workspace::DeleteMode::Permanent,
-            ),
-            "Delete 'lib.rs' permanently?"
-        );
-        assert_eq!(
-            delete_confirmation_message(
-                Some(path),
-                nucleotide_events::v2::workspace::DeleteMode::Trash,
-            ),
-            "Move 'lib.rs' to Trash?"
+            document_reload_decision(docs, views, path),
+            DocumentReloadDecision::NoMatch
         );
     }
 
@@ -17625,22 +23647,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn context_menu_target_parent_uses_entry_kind_without_filesystem_probe() {
-        assert_eq!(
-            context_menu_target_parent_path(Path::new("/workspace/src"), true),
-            PathBuf::from("/workspace/src")
-        );
-        assert_eq!(
-            context_menu_target_parent_path(Path::new("/workspace/src/main.rs"), false),
-            PathBuf::from("/workspace/src")
-        );
-        assert_eq!(
-            context_menu_target_parent_path(Path::new("main.rs"), false),
-            PathBuf::from(".")
-        );
-    }
-
     #[test]
     fn workspace_selection_target_maps_backend_file_kinds() {
         assert_eq!(
@@ -17699,10 +23705,26 @@ mod tests {
     }
 
     #[test]
-    fn titlebar_filename_uses_focused_file_or_app_name() {
-        assert_eq!(titlebar_filename(Some("main.rs")), "Nucleotide");
-        assert_eq!(titlebar_filename(Some("")), "Nucleotide");
-        assert_eq!(titlebar_filename(None), "Nucleotide");
+    fn titlebar_document_shows_project_relative_path() {
+        let root = Path::new("/work/nucleotide");
+        let document = titlebar_document(
+            Some(root),
+            Some(Path::new("/work/nucleotide/src/main.rs")),
+            true,
+        );
+        assert_eq!(document.project.as_deref(), Some("nucleotide"));
+        assert_eq!(
+            document.path.as_deref(),
+            Some(Path::new("src/main.rs").display().to_string().as_str())
+        );
+        assert!(document.dirty);
+
+        let outside = titlebar_document(Some(root), Some(Path::new("/tmp/notes.md")), false);
+        assert_eq!(outside.path.as_deref(), Some("/tmp/notes.md"));
+        assert_eq!(
+            titlebar_document(None, None, false),
+            nucleotide_ui::titlebar::TitleBarDocument::default()
+        );
     }
 
     #[test]
@@ -17738,6 +23760,31 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn hex_editor_opens_local_binary_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let binary = temp_dir.path().join("firmware.bin");
+        let text = temp_dir.path().join("notes.txt");
+        std::fs::write(&binary, [0x7f, b'E', b'L', b'F', 0, 1]).unwrap();
+        std::fs::write(&text, "plain text\n").unwrap();
+
+        assert!(should_open_with_hex_editor(
+            &binary,
+            false,
+            &WorkspaceIdentity::Local
+        ));
+        assert!(!should_open_with_hex_editor(
+            &binary,
+            true,
+            &WorkspaceIdentity::Local
+        ));
+        assert!(!should_open_with_hex_editor(
+            &text,
+            false,
+            &WorkspaceIdentity::Local
+        ));
+    }
+
     #[test]
     fn remote_image_cache_paths_preserve_extension_and_escape_names() {
         let path = Path::new("ssh://devbox/home/me/project/assets/logo one.png");
@@ -17994,6 +24041,36 @@ mod tests {
         assert_eq!(abbreviated_vcs_ref("0123456789abcdef"), "01234567");
     }
 
+    #[test]
+    fn lsp_request_health_detail_describes_degraded_servers() {
+        let now = std::time::Instant::now();
+        assert_eq!(
+            lsp_request_health_detail(nucleotide_lsp::ServerRequestHealth::Healthy, now),
+            None
+        );
+        assert_eq!(
+            lsp_request_health_detail(
+                nucleotide_lsp::ServerRequestHealth::Degraded {
+                    consecutive_failures: 5,
+                    retry_at: now + std::time::Duration::from_secs(12),
+                },
+                now,
+            )
+            .as_deref(),
+            Some("5 consecutive request failures; retrying in 12s")
+        );
+        assert_eq!(
+            lsp_request_health_detail(
+                nucleotide_lsp::ServerRequestHealth::Probing {
+                    consecutive_failures: 6
+                },
+                now,
+            )
+            .as_deref(),
+            Some("6 consecutive request failures; probing on next request")
+        );
+    }
+
     #[test]
     fn statusbar_prioritizes_document_and_branch_labels_over_secondary_metadata() {
         assert_eq!(
@@ -18324,39 +24401,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn file_operation_notification_success_tracks_disk_state() {
-        let dir = tempfile::tempdir().unwrap();
-        let created_file = dir.path().join("created.rs");
-        std::fs::write(&created_file, "").unwrap();
-        assert!(file_operation_notification_succeeded(
-            &LspFileOperationNotification::Created {
-                path: created_file,
-                is_dir: false,
-            }
-        ));
-
-        let deleted_file = dir.path().join("deleted.rs");
-        assert!(!deleted_file.exists());
-        assert!(file_operation_notification_succeeded(
-            &LspFileOperationNotification::Deleted {
-                path: deleted_file,
-                was_dir: false,
-            }
-        ));
-
-        let old_path = dir.path().join("old.rs");
-        let new_path = dir.path().join("new.rs");
-        std::fs::write(&new_path, "").unwrap();
-        assert!(file_operation_notification_succeeded(
-            &LspFileOperationNotification::Renamed {
-                old_path,
-                new_path,
-                was_dir: false,
-            }
-        ));
-    }
-
     #[test]
     fn completion_refinement_follows_focused_document() {
         let doc_id = DocumentId::default();
@@ -18490,37 +24534,6 @@ mod tests {
         assert_eq!(completion_locality_score_for_text(text, 2, "missing"), 0);
     }
 
-    #[test]
-    fn completion_memory_prioritizes_recent_prefix_match() {
-        let mut memory = CompletionMemory::default();
-        let old_key = CompletionMemoryKey {
-            language: "rust".to_string(),
-            prefix: "fo".to_string(),
-            kind: Some(nucleotide_ui::completion_v2::CompletionItemKind::Function),
-            insert_text: "foo".to_string(),
-        };
-        let recent_key = CompletionMemoryKey {
-            language: "rust".to_string(),
-            prefix: "fo".to_string(),
-            kind: Some(nucleotide_ui::completion_v2::CompletionItemKind::Function),
-            insert_text: "foobar".to_string(),
-        };
-
-        memory.memorize(old_key.clone());
-        memory.memorize(recent_key.clone());
-
-        assert!(memory.priority(&recent_key) > memory.priority(&old_key));
-        assert_eq!(
-            memory.priority(&CompletionMemoryKey {
-                language: "rust".to_string(),
-                prefix: "ba".to_string(),
-                kind: Some(nucleotide_ui::completion_v2::CompletionItemKind::Function),
-                insert_text: "foobar".to_string(),
-            }),
-            0
-        );
-    }
-
     #[test]
     fn completion_commit_character_uses_unmodified_printable_key() {
         assert_eq!(
@@ -18834,20 +24847,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn file_tree_context_menu_items_follow_sidebar_intent_order() {
-        let actual: Vec<_> = Workspace::context_menu_intents()
-            .iter()
-            .map(|intent| intent.label())
-            .collect();
-        let expected: Vec<_> = ProjectTreeContextMenuIntent::common_file_operations()
-            .iter()
-            .map(|intent| intent.label())
-            .collect();
-
-        assert_eq!(actual, expected);
-    }
-
     #[test]
     fn tab_bar_layout_height_matches_rendered_row_count() {
         let row_height = px(32.0);
@@ -18898,311 +24897,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn tab_context_menu_items_match_zed_close_actions() {
-        let unpinned_labels = Workspace::tab_context_menu_intents(false, false, false)
-            .iter()
-            .map(|intent| intent.label(false, false))
-            .collect::<Vec<_>>();
-        let pinned_labels = Workspace::tab_context_menu_intents(false, false, false)
-            .iter()
-            .map(|intent| intent.label(true, false))
-            .collect::<Vec<_>>();
-
-        assert_eq!(
-            unpinned_labels,
-            vec![
-                "Close",
-                "Close Others",
-                "Close Left",
-                "Close Right",
-                "Close Clean",
-                "Close All",
-                "Pin Tab"
-            ]
-        );
-        assert_eq!(pinned_labels.last(), Some(&"Unpin Tab"));
-    }
-
-    #[test]
-    fn tab_context_menu_entries_match_zed_grouping() {
-        let labels = Workspace::tab_context_menu_entries(false, false, false)
-            .iter()
-            .map(|entry| match entry {
-                TabContextMenuEntry::Action(intent) => intent.label(false, false),
-                TabContextMenuEntry::Separator => "|",
-            })
-            .collect::<Vec<_>>();
-
-        assert_eq!(
-            labels,
-            vec![
-                "Close",
-                "Close Others",
-                "|",
-                "Close Left",
-                "Close Right",
-                "|",
-                "Close Clean",
-                "Close All",
-                "|",
-                "Pin Tab"
-            ]
-        );
-    }
-
-    #[test]
-    fn tab_context_menu_entries_add_zed_file_path_actions_for_file_tabs() {
-        let reveal_label = reveal_in_file_manager_label(false);
-        let labels = Workspace::tab_context_menu_entries(true, false, false)
-            .iter()
-            .map(|entry| match entry {
-                TabContextMenuEntry::Action(intent) => intent.label(false, false),
-                TabContextMenuEntry::Separator => "|",
-            })
-            .collect::<Vec<_>>();
-
-        assert_eq!(
-            labels,
-            vec![
-                "Close",
-                "Close Others",
-                "|",
-                "Close Left",
-                "Close Right",
-                "|",
-                "Close Clean",
-                "Close All",
-                "|",
-                "Make File Read-Only",
-                "|",
-                "Copy Path",
-                "Copy Relative Path",
-                "|",
-                reveal_label,
-                "|",
-                "Pin Tab"
-            ]
-        );
-    }
-
-    #[test]
-    fn tab_context_menu_intents_add_zed_readonly_toggle_for_file_tabs() {
-        let labels = Workspace::tab_context_menu_intents(true, false, false)
-            .iter()
-            .map(|intent| intent.label(false, false))
-            .collect::<Vec<_>>();
-
-        assert_eq!(
-            labels,
-            vec![
-                "Close",
-                "Close Others",
-                "Close Left",
-                "Close Right",
-                "Close Clean",
-                "Close All",
-                "Make File Read-Only",
-                "Copy Path",
-                "Copy Relative Path",
-                reveal_in_file_manager_label(false),
-                "Pin Tab",
-            ]
-        );
-    }
-
-    #[test]
-    fn tab_context_menu_readonly_toggle_label_matches_zed_state() {
-        assert_eq!(
-            TabContextMenuIntent::ToggleReadOnly.label(false, false),
-            "Make File Read-Only"
-        );
-        assert_eq!(
-            TabContextMenuIntent::ToggleReadOnly.label(false, true),
-            "Make File Editable"
-        );
-    }
-
-    #[test]
-    fn tab_context_menu_entries_add_reveal_project_panel_for_visible_project_paths() {
-        let reveal_label = reveal_in_file_manager_label(false);
-        let labels = Workspace::tab_context_menu_entries(true, true, false)
-            .iter()
-            .map(|entry| match entry {
-                TabContextMenuEntry::Action(intent) => intent.label(false, false),
-                TabContextMenuEntry::Separator => "|",
-            })
-            .collect::<Vec<_>>();
-
-        assert_eq!(
-            labels,
-            vec![
-                "Close",
-                "Close Others",
-                "|",
-                "Close Left",
-                "Close Right",
-                "|",
-                "Close Clean",
-                "Close All",
-                "|",
-                "Make File Read-Only",
-                "|",
-                "Copy Path",
-                "Copy Relative Path",
-                "|",
-                reveal_label,
-                "|",
-                "Pin Tab",
-                "Reveal In Project Panel"
-            ]
-        );
-    }
-
-    #[test]
-    fn tab_context_menu_entries_add_open_terminal_when_parent_directory_exists() {
-        let reveal_label = reveal_in_file_manager_label(false);
-        let labels = Workspace::tab_context_menu_entries(true, true, true)
-            .iter()
-            .map(|entry| match entry {
-                TabContextMenuEntry::Action(intent) => intent.label(false, false),
-                TabContextMenuEntry::Separator => "|",
-            })
-            .collect::<Vec<_>>();
-
-        assert_eq!(
-            labels,
-            vec![
-                "Close",
-                "Close Others",
-                "|",
-                "Close Left",
-                "Close Right",
-                "|",
-                "Close Clean",
-                "Close All",
-                "|",
-                "Make File Read-Only",
-                "|",
-                "Copy Path",
-                "Copy Relative Path",
-                "|",
-                reveal_label,
-                "|",
-                "Pin Tab",
-                "Reveal In Project Panel",
-                "Open in Terminal"
-            ]
-        );
-    }
-
-    #[test]
-    fn reveal_in_file_manager_label_matches_zed_platform_label() {
-        if cfg!(target_os = "macos") {
-            assert_eq!(reveal_in_file_manager_label(false), "Reveal in Finder");
-        } else if cfg!(target_os = "windows") {
-            assert_eq!(
-                reveal_in_file_manager_label(false),
-                "Reveal in File Explorer"
-            );
-        } else {
-            assert_eq!(
-                reveal_in_file_manager_label(false),
-                "Reveal in File Manager"
-            );
-        }
-        assert_eq!(reveal_in_file_manager_label(true), "Reveal in File Manager");
-    }
-
-    #[test]
-    fn tab_context_menu_disabled_states_match_zed_rules() {
-        assert!(!Workspace::tab_context_menu_intent_disabled(
-            TabContextMenuIntent::Close,
-            Some(0),
-            1,
-            false,
-        ));
-        assert!(Workspace::tab_context_menu_intent_disabled(
-            TabContextMenuIntent::CloseOthers,
-            Some(0),
-            1,
-            true,
-        ));
-        assert!(!Workspace::tab_context_menu_intent_disabled(
-            TabContextMenuIntent::CloseOthers,
-            Some(0),
-            2,
-            true,
-        ));
-        assert!(Workspace::tab_context_menu_intent_disabled(
-            TabContextMenuIntent::CloseLeft,
-            Some(0),
-            3,
-            true,
-        ));
-        assert!(Workspace::tab_context_menu_intent_disabled(
-            TabContextMenuIntent::CloseRight,
-            Some(2),
-            3,
-            true,
-        ));
-        assert!(Workspace::tab_context_menu_intent_disabled(
-            TabContextMenuIntent::CloseClean,
-            Some(1),
-            3,
-            false,
-        ));
-        assert!(!Workspace::tab_context_menu_intent_disabled(
-            TabContextMenuIntent::CloseClean,
-            Some(1),
-            3,
-            true,
-        ));
-        assert!(Workspace::tab_context_menu_intent_disabled(
-            TabContextMenuIntent::ToggleReadOnly,
-            None,
-            3,
-            true,
-        ));
-        assert!(!Workspace::tab_context_menu_intent_disabled(
-            TabContextMenuIntent::ToggleReadOnly,
-            Some(1),
-            3,
-            true,
-        ));
-    }
-
-    #[test]
-    fn tab_bar_split_menu_items_match_zed_directional_split_actions() {
-        let labels = Workspace::tab_bar_split_menu_intents()
-            .iter()
-            .map(|intent| intent.label())
-            .collect::<Vec<_>>();
-
-        assert_eq!(
-            labels,
-            vec!["Split Right", "Split Left", "Split Up", "Split Down"]
-        );
-    }
-
-    #[test]
-    fn tab_bar_split_menu_commands_match_directional_helix_primitives() {
-        let commands = Workspace::tab_bar_split_menu_intents()
-            .iter()
-            .map(|intent| intent.commands())
-            .collect::<Vec<_>>();
-
-        assert_eq!(
-            commands,
-            vec![
-                &["vsplit"][..],
-                &["vsplit", "swap_view_left"][..],
-                &["hsplit", "swap_view_up"][..],
-                &["hsplit"][..],
-            ]
-        );
-    }
-
     #[test]
     fn terminal_spawn_cwd_uses_loaded_project_root() {
         let project_root = PathBuf::from("/tmp/example-project");
@@ -19329,62 +25023,24 @@ mod tests {
         ));
     }
 
-    #[test]
-    fn tab_bar_new_menu_items_match_zed_new_actions() {
-        let labels = Workspace::tab_bar_new_menu_intents()
-            .iter()
-            .map(|intent| intent.label())
-            .collect::<Vec<_>>();
-
-        assert_eq!(
-            labels,
-            vec![
-                "New File",
-                "Open File",
-                "Search Project",
-                "Search Symbols",
-                "New Terminal",
-                "New Center Terminal"
-            ]
-        );
-    }
-
-    #[test]
-    fn tab_bar_new_menu_entries_match_zed_grouping() {
-        let labels = Workspace::tab_bar_new_menu_entries()
-            .iter()
-            .map(|entry| match entry {
-                TabBarNewMenuEntry::Action(intent) => intent.label(),
-                TabBarNewMenuEntry::Separator => "|",
-            })
-            .collect::<Vec<_>>();
-
-        assert_eq!(
-            labels,
-            vec![
-                "New File",
-                "Open File",
-                "|",
-                "Search Project",
-                "Search Symbols",
-                "|",
-                "New Terminal",
-                "New Center Terminal"
-            ]
-        );
-    }
-
     #[test]
     fn tab_bar_end_buttons_follow_zed_new_split_order() {
         assert_eq!(
             tab_bar_end_button_icon_paths(),
-            ["icons/plus.svg", "icons/columns-2.svg"]
+            [
+                "icons/chevron-down.svg",
+                "icons/plus.svg",
+                "icons/columns-2.svg"
+            ]
         );
     }
 
     #[test]
     fn tab_bar_end_button_tooltips_describe_actions() {
-        assert_eq!(tab_bar_end_button_tooltips(), ["New File", "Split Pane"]);
+        assert_eq!(
+            tab_bar_end_button_tooltips(),
+            ["All Tabs", "New File", "Split Pane"]
+        );
     }
 
     #[test]
@@ -19443,6 +25099,7 @@ mod tests {
         let mut gui_config = crate::config::GuiConfig::default();
         gui_config.file_tree.density = crate::file_tree::FileTreeDisplayDensity::Relaxed;
         gui_config.file_tree.flatten_empty_directories = false;
+        gui_config.file_tree.metadata_columns = true;
         gui_config.ui.look = crate::config::UiLook::System;
 
         let file_tree_config = file_tree_config_from_gui(&gui_config);
@@ -19452,6 +25109,8 @@ mod tests {
             crate::file_tree::FileTreeDisplayDensity::Relaxed
         );
         assert!(!file_tree_config.flatten_empty_directories);
+        assert!(file_tree_config.metadata.columns);
+        assert!(!file_tree_config.metadata.tooltips);
         assert_eq!(
             file_tree_config.translucent_background,
             cfg!(target_os = "macos")
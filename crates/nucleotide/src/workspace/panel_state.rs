@@ -0,0 +1,232 @@
+// ABOUTME: Visibility, size and deferred focus of the panels and HUDs around the editor
+// ABOUTME: Keeps panel toggles and resize bookkeeping apart from the workspace view
+
+use super::FILE_TREE_DEFAULT_WIDTH;
+
+/// Starting height of the terminal panel in basic layout mode.
+const TERMINAL_PANEL_DEFAULT_HEIGHT: f32 = 220.0;
+
+/// Widths and heights closer than this are treated as unchanged.
+const PANEL_SIZE_EPSILON: f32 = 0.5;
+
+/// Panels that take focus on the next render once they are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum PanelKind {
+    Terminal,
+    HexEditor,
+    TypeHierarchy,
+    RegexTester,
+    FindBar,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct PanelState {
+    pub(super) file_tree_visible: bool,
+    /// Current file tree width, clamped to the viewport
+    pub(super) file_tree_width: f32,
+    /// Width the file tree was dragged to, when not the default
+    pub(super) file_tree_width_override: Option<f32>,
+    pub(super) terminal_visible: bool,
+    /// Height of the bottom (terminal) pane in basic layout mode
+    pub(super) terminal_height: f32,
+    /// Performance HUD shown over the editor
+    pub(super) performance_hud_visible: bool,
+    /// Panel to focus on the next render, once its elements exist
+    pending_focus: Option<PanelKind>,
+}
+
+impl Default for PanelState {
+    fn default() -> Self {
+        Self {
+            file_tree_visible: true,
+            file_tree_width: FILE_TREE_DEFAULT_WIDTH,
+            file_tree_width_override: None,
+            terminal_visible: false,
+            terminal_height: TERMINAL_PANEL_DEFAULT_HEIGHT,
+            performance_hud_visible: false,
+            pending_focus: None,
+        }
+    }
+}
+
+impl PanelState {
+    /// Shows or hides the file tree. Returns whether it is now visible.
+    pub(super) fn toggle_file_tree(&mut self) -> bool {
+        self.file_tree_visible = !self.file_tree_visible;
+        self.file_tree_visible
+    }
+
+    pub(super) fn show_file_tree(&mut self) {
+        self.file_tree_visible = true;
+    }
+
+    pub(super) fn hide_file_tree(&mut self) {
+        self.file_tree_visible = false;
+    }
+
+    /// Width the file tree takes from the window, 0 while hidden.
+    pub(super) fn visible_file_tree_width(&self) -> f32 {
+        if self.file_tree_visible {
+            self.file_tree_width
+        } else {
+            0.0
+        }
+    }
+
+    /// Applies a width the file tree was dragged to. Widths near
+    /// `default_width` clear the override. Returns whether anything changed.
+    pub(super) fn resize_file_tree(&mut self, width: f32, default_width: f32) -> bool {
+        let next_override = ((width - default_width).abs() > PANEL_SIZE_EPSILON).then_some(width);
+        let changed = (self.file_tree_width - width).abs() > PANEL_SIZE_EPSILON
+            || self.file_tree_width_override != next_override;
+
+        self.file_tree_width = width;
+        self.file_tree_width_override = next_override;
+        changed
+    }
+
+    /// Applies the file tree width clamped to the current viewport.
+    pub(super) fn fit_file_tree_width(&mut self, width: f32) {
+        if (self.file_tree_width - width).abs() > PANEL_SIZE_EPSILON {
+            self.file_tree_width = width;
+        }
+
+        if let Some(override_width) = &mut self.file_tree_width_override {
+            *override_width = self.file_tree_width;
+        }
+    }
+
+    /// Shows or hides the performance HUD. Returns whether it is now visible.
+    pub(super) fn toggle_performance_hud(&mut self) -> bool {
+        self.performance_hud_visible = !self.performance_hud_visible;
+        self.performance_hud_visible
+    }
+
+    /// Focuses `panel` on the next render, replacing any earlier request.
+    pub(super) fn request_focus(&mut self, panel: PanelKind) {
+        self.pending_focus = Some(panel);
+    }
+
+    /// Drops a focus request for `panel`, keeping focus where it is.
+    pub(super) fn cancel_focus(&mut self, panel: PanelKind) {
+        if self.pending_focus == Some(panel) {
+            self.pending_focus = None;
+        }
+    }
+
+    pub(super) fn focus_requested(&self, panel: PanelKind) -> bool {
+        self.pending_focus == Some(panel)
+    }
+
+    /// Whether `panel` should be focused now. Clears the request.
+    pub(super) fn take_focus(&mut self, panel: PanelKind) -> bool {
+        let requested = self.focus_requested(panel);
+        if requested {
+            self.pending_focus = None;
+        }
+        requested
+    }
+
+    /// Shows the terminal panel and focuses it on the next render.
+    pub(super) fn show_terminal(&mut self) {
+        self.terminal_visible = true;
+        self.request_focus(PanelKind::Terminal);
+    }
+
+    /// Shows the terminal panel without taking focus from the editor.
+    /// Returns whether it was hidden.
+    pub(super) fn reveal_terminal(&mut self) -> bool {
+        !std::mem::replace(&mut self.terminal_visible, true)
+    }
+
+    /// Whether the visible terminal should be focused now. Clears the
+    /// request.
+    pub(super) fn take_terminal_focus(&mut self) -> bool {
+        self.terminal_visible && self.take_focus(PanelKind::Terminal)
+    }
+
+    pub(super) fn hide_terminal(&mut self) {
+        self.terminal_visible = false;
+        self.cancel_focus(PanelKind::Terminal);
+    }
+
+    /// Shrinks the terminal panel to at most `max_height`.
+    pub(super) fn clamp_terminal_height(&mut self, max_height: f32) {
+        self.terminal_height = self.terminal_height.min(max_height);
+    }
+
+    /// Sets the terminal panel height. Returns whether it changed.
+    pub(super) fn resize_terminal(&mut self, height: f32) -> bool {
+        if (self.terminal_height - height).abs() <= PANEL_SIZE_EPSILON {
+            return false;
+        }
+        self.terminal_height = height;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dragging_the_file_tree_back_to_its_default_clears_the_override() {
+        let mut panels = PanelState::default();
+        assert_eq!(panels.visible_file_tree_width(), FILE_TREE_DEFAULT_WIDTH);
+
+        assert!(panels.resize_file_tree(320.0, FILE_TREE_DEFAULT_WIDTH));
+        assert_eq!(panels.file_tree_width_override, Some(320.0));
+        assert!(!panels.resize_file_tree(320.2, FILE_TREE_DEFAULT_WIDTH));
+
+        panels.fit_file_tree_width(300.0);
+        assert_eq!(panels.file_tree_width, 300.0);
+        assert_eq!(panels.file_tree_width_override, Some(300.0));
+
+        assert!(panels.resize_file_tree(FILE_TREE_DEFAULT_WIDTH, FILE_TREE_DEFAULT_WIDTH));
+        assert_eq!(panels.file_tree_width_override, None);
+
+        assert!(!panels.toggle_file_tree());
+        assert_eq!(panels.visible_file_tree_width(), 0.0);
+        assert!(panels.toggle_performance_hud());
+        assert!(!panels.toggle_performance_hud());
+        panels.show_file_tree();
+        assert_eq!(panels.visible_file_tree_width(), 300.0);
+    }
+
+    #[test]
+    fn showing_the_terminal_requests_focus_until_hidden() {
+        let mut panels = PanelState::default();
+        panels.show_terminal();
+        assert!(panels.terminal_visible && panels.focus_requested(PanelKind::Terminal));
+
+        assert!(!panels.resize_terminal(TERMINAL_PANEL_DEFAULT_HEIGHT + 0.2));
+        assert!(panels.resize_terminal(300.0));
+        assert_eq!(panels.terminal_height, 300.0);
+        panels.clamp_terminal_height(250.0);
+        assert_eq!(panels.terminal_height, 250.0);
+
+        assert!(panels.take_terminal_focus());
+        assert!(!panels.take_terminal_focus());
+
+        panels.request_focus(PanelKind::Terminal);
+        panels.hide_terminal();
+        assert!(!panels.terminal_visible && !panels.focus_requested(PanelKind::Terminal));
+        assert!(!panels.take_terminal_focus());
+
+        assert!(panels.reveal_terminal());
+        assert!(!panels.reveal_terminal());
+        assert!(!panels.focus_requested(PanelKind::Terminal));
+    }
+
+    #[test]
+    fn later_focus_requests_replace_earlier_ones() {
+        let mut panels = PanelState::default();
+        panels.show_terminal();
+        panels.request_focus(PanelKind::HexEditor);
+        assert!(!panels.take_terminal_focus());
+
+        panels.cancel_focus(PanelKind::Terminal);
+        assert!(panels.take_focus(PanelKind::HexEditor));
+        assert!(!panels.take_focus(PanelKind::HexEditor));
+    }
+}
@@ -0,0 +1,166 @@
+// ABOUTME: Picker sources backed by workspace state: scratch buffers and compare targets
+// ABOUTME: Registers them by name for the picker command, next to app-wide sources
+
+use std::sync::Arc;
+
+use gpui::{App, SharedString, WeakEntity};
+use nucleotide_ui::picker_source::{PickerItemSink, PickerSource, PickerSources};
+
+use super::Workspace;
+use crate::file_compare::CompareChoice;
+use crate::picker_view::PickerItem;
+use crate::scratch_buffers::{SCRATCH_BUFFERS_COMMAND, ScratchBufferChoice};
+
+/// Prompt command opening a registered picker by name.
+pub const PICKER_COMMAND: &str = "picker";
+/// Prompt commands for registered pickers, with completion descriptions.
+pub const PICKER_COMMANDS: &[(&str, &str)] =
+    &[(PICKER_COMMAND, "Open a registered picker: picker <name>")];
+/// Name the compare picker is registered under.
+pub const COMPARE_SOURCE: &str = "compare";
+
+/// Name given to a `picker [name]` command line. Returns `None` for other
+/// commands, and `Some(None)` when no name is given.
+pub fn parse_picker_command(command: &str) -> Option<Option<&str>> {
+    let name = command
+        .trim()
+        .trim_start_matches(':')
+        .strip_prefix(PICKER_COMMAND)
+        .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))?
+        .trim();
+    Some((!name.is_empty()).then_some(name))
+}
+
+/// Registers the workspace's own sources in the app-wide registry.
+pub(super) fn register(workspace: WeakEntity<Workspace>, cx: &mut App) {
+    PickerSources::register_global(
+        SCRATCH_BUFFERS_COMMAND,
+        Arc::new(ScratchBufferSource {
+            workspace: workspace.clone(),
+        }),
+        cx,
+    );
+    PickerSources::register_global(COMPARE_SOURCE, Arc::new(CompareSource { workspace }), cx);
+}
+
+/// Scratch buffers of the session, opened on confirm.
+pub(super) struct ScratchBufferSource {
+    pub(super) workspace: WeakEntity<Workspace>,
+}
+
+impl PickerSource for ScratchBufferSource {
+    fn title(&self) -> SharedString {
+        "Scratch Buffers".into()
+    }
+
+    fn items(&self, sink: PickerItemSink, cx: &mut App) {
+        if let Some(workspace) = self.workspace.upgrade() {
+            let items = workspace.update(cx, |workspace, cx| workspace.scratch_buffer_items(cx));
+            sink.set(items, cx);
+        }
+    }
+
+    fn confirm(&self, item: &PickerItem, cx: &mut App) {
+        let (Some(workspace), Some(choice)) = (
+            self.workspace.upgrade(),
+            item.data.downcast_ref::<ScratchBufferChoice>(),
+        ) else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            workspace.open_scratch_buffer(&choice.name, cx);
+        });
+    }
+}
+
+/// Buffers, the clipboard and files to compare the active file with.
+pub(super) struct CompareSource {
+    pub(super) workspace: WeakEntity<Workspace>,
+}
+
+impl PickerSource for CompareSource {
+    fn title(&self) -> SharedString {
+        "Compare Active File With".into()
+    }
+
+    fn items(&self, sink: PickerItemSink, cx: &mut App) {
+        if let Some(workspace) = self.workspace.upgrade() {
+            let items = workspace.update(cx, |workspace, cx| workspace.compare_items(cx));
+            sink.set(items, cx);
+        }
+    }
+
+    fn confirm(&self, item: &PickerItem, cx: &mut App) {
+        let (Some(workspace), Some(choice)) = (
+            self.workspace.upgrade(),
+            item.data.downcast_ref::<CompareChoice>(),
+        ) else {
+            return;
+        };
+        let choice = *choice;
+        workspace.update(cx, |workspace, cx| {
+            workspace.handle_compare_choice(choice, cx);
+        });
+    }
+}
+
+/// The registered pickers, opening the chosen one.
+pub(super) struct RegisteredPickersSource {
+    pub(super) workspace: WeakEntity<Workspace>,
+}
+
+impl PickerSource for RegisteredPickersSource {
+    fn title(&self) -> SharedString {
+        "Pickers".into()
+    }
+
+    fn items(&self, sink: PickerItemSink, cx: &mut App) {
+        let items = cx
+            .try_global::<PickerSources>()
+            .map(|sources| {
+                sources
+                    .names()
+                    .filter_map(|name| {
+                        let source = sources.get(name)?;
+                        Some(PickerItem {
+                            label: name.clone(),
+                            sublabel: Some(source.title()),
+                            data: Arc::new(name.clone()),
+                            file_path: None,
+                            vcs_status: None,
+                            columns: None,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        sink.set(items, cx);
+    }
+
+    fn confirm(&self, item: &PickerItem, cx: &mut App) {
+        let (Some(workspace), Some(name)) = (
+            self.workspace.upgrade(),
+            item.data.downcast_ref::<SharedString>(),
+        ) else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            workspace.open_registered_picker(Some(name), cx);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picker_command_takes_a_source_name() {
+        assert_eq!(
+            parse_picker_command(":picker compare"),
+            Some(Some("compare"))
+        );
+        assert_eq!(parse_picker_command("picker"), Some(None));
+        assert_eq!(parse_picker_command("pickers"), None);
+    }
+}
@@ -0,0 +1,1404 @@
+// ABOUTME: Panels sharing the slot on the right of the editor: docs, references, type hierarchy, regex tester
+// ABOUTME: Opening one closes the others; the workspace renders them and routes their events
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    App, AppContext, Context, Entity, FocusHandle, FontWeight, InteractiveElement, IntoElement,
+    KeyDownEvent, MouseButton, ParentElement, ScrollHandle, SharedString,
+    StatefulInteractiveElement, Styled, Window, div, px, svg,
+};
+use helix_core::Rope;
+use nucleotide_types::scrollbar::SCROLLBAR_THICKNESS;
+use nucleotide_ui::scrollbar::{Scrollbar, ScrollbarState};
+use nucleotide_ui::{MarkdownStyle, StateView, ThemedContext, markdown_extended};
+use nucleotide_workspace::ReadOptions;
+
+use super::panel_state::PanelKind;
+use super::{Workspace, file_tree_tokens_for_gui_config, open_document_texts};
+use crate::references_panel::{ReferencesPanel, ReferencesResult, group_references, missing_paths};
+use crate::regex_tester::{RegexTester, RegexTesterEvent, RegexTesterSource};
+use crate::type_hierarchy::{
+    TypeHierarchyChildren, TypeHierarchyDirection, TypeHierarchyPanel, TypeHierarchyUpdate,
+};
+use crate::types::HoverDocEntry;
+
+/// Widths closer than this are treated as unchanged.
+const SIDEBAR_WIDTH_EPSILON: f32 = 0.5;
+
+pub(super) struct RightSidebar {
+    /// Width shared by every panel in the slot
+    pub(super) width: f32,
+    pub(super) docs_visible: bool,
+    pub(super) docs_loading: bool,
+    pub(super) docs_entries: Vec<HoverDocEntry>,
+    pub(super) docs_scroll_handle: ScrollHandle,
+    pub(super) docs_scrollbar_state: ScrollbarState,
+    // Find All References results
+    pub(super) references: Option<ReferencesPanel>,
+    references_generation: u64,
+    pub(super) references_scroll_handle: ScrollHandle,
+    pub(super) references_scrollbar_state: ScrollbarState,
+    // Type hierarchy of the symbol under the cursor
+    pub(super) type_hierarchy: Option<TypeHierarchyPanel>,
+    type_hierarchy_generation: u64,
+    pub(super) type_hierarchy_focus: FocusHandle,
+    pub(super) type_hierarchy_scroll_handle: ScrollHandle,
+    pub(super) type_hierarchy_scrollbar_state: ScrollbarState,
+    pub(super) regex_tester: Entity<RegexTester>,
+    pub(super) regex_tester_open: bool,
+}
+
+impl RightSidebar {
+    pub(super) fn new(width: f32, cx: &mut App) -> Self {
+        let docs_scroll_handle = ScrollHandle::new();
+        let references_scroll_handle = ScrollHandle::new();
+        let type_hierarchy_scroll_handle = ScrollHandle::new();
+        Self {
+            width,
+            docs_visible: false,
+            docs_loading: false,
+            docs_entries: Vec::new(),
+            docs_scrollbar_state: ScrollbarState::new(docs_scroll_handle.clone()),
+            docs_scroll_handle,
+            references: None,
+            references_generation: 0,
+            references_scrollbar_state: ScrollbarState::new(references_scroll_handle.clone()),
+            references_scroll_handle,
+            type_hierarchy: None,
+            type_hierarchy_generation: 0,
+            type_hierarchy_focus: cx.focus_handle(),
+            type_hierarchy_scrollbar_state: ScrollbarState::new(
+                type_hierarchy_scroll_handle.clone(),
+            ),
+            type_hierarchy_scroll_handle,
+            regex_tester: cx.new(RegexTester::new),
+            regex_tester_open: false,
+        }
+    }
+
+    /// Whether any panel takes up the right edge of the editor area.
+    pub(super) fn is_visible(&self) -> bool {
+        self.docs_visible
+            || self.references.is_some()
+            || self.type_hierarchy.is_some()
+            || self.regex_tester_open
+    }
+
+    /// Sets the width the slot was dragged or clamped to. Returns whether it
+    /// changed.
+    pub(super) fn resize(&mut self, width: f32) -> bool {
+        if (self.width - width).abs() <= SIDEBAR_WIDTH_EPSILON {
+            return false;
+        }
+        self.width = width;
+        true
+    }
+
+    /// Shows the documentation panel while its entries load.
+    pub(super) fn open_docs(&mut self) {
+        self.close_references();
+        self.close_type_hierarchy();
+        self.regex_tester_open = false;
+        self.docs_visible = true;
+        self.docs_loading = true;
+        self.docs_entries.clear();
+    }
+
+    /// Returns whether the documentation panel was shown.
+    pub(super) fn close_docs(&mut self) -> bool {
+        let was_open = self.docs_visible || self.docs_loading;
+        self.docs_visible = false;
+        self.docs_loading = false;
+        was_open
+    }
+
+    /// Shows `entries` unless the documentation panel was closed meanwhile.
+    /// Returns whether they are shown.
+    pub(super) fn set_docs_entries(&mut self, entries: Vec<HoverDocEntry>) -> bool {
+        if !self.docs_visible && !self.docs_loading {
+            return false;
+        }
+        self.docs_visible = true;
+        self.docs_loading = false;
+        self.docs_entries = entries;
+        true
+    }
+
+    /// Makes room for the references panel, keeping its current contents.
+    /// Returns the generation results for the new request must carry.
+    pub(super) fn open_references(&mut self) -> u64 {
+        self.close_docs();
+        self.close_type_hierarchy();
+        self.regex_tester_open = false;
+        self.references_generation = self.references_generation.wrapping_add(1);
+        self.references_generation
+    }
+
+    pub(super) fn references_current(&self, generation: u64) -> bool {
+        self.references_generation == generation
+    }
+
+    /// Returns whether the references panel was shown.
+    pub(super) fn close_references(&mut self) -> bool {
+        let was_open = self.references.take().is_some();
+        if was_open {
+            self.references_generation = self.references_generation.wrapping_add(1);
+        }
+        was_open
+    }
+
+    /// Shows `panel` in place of the others. Returns the generation
+    /// results for it must carry.
+    pub(super) fn open_type_hierarchy(&mut self, panel: TypeHierarchyPanel) -> u64 {
+        self.close_docs();
+        self.close_references();
+        self.regex_tester_open = false;
+        self.type_hierarchy_generation = self.type_hierarchy_generation.wrapping_add(1);
+        self.type_hierarchy = Some(panel);
+        self.type_hierarchy_generation
+    }
+
+    pub(super) fn type_hierarchy_generation(&self) -> u64 {
+        self.type_hierarchy_generation
+    }
+
+    /// Returns whether the type hierarchy was shown.
+    pub(super) fn close_type_hierarchy(&mut self) -> bool {
+        let was_open = self.type_hierarchy.take().is_some();
+        if was_open {
+            self.type_hierarchy_generation = self.type_hierarchy_generation.wrapping_add(1);
+        }
+        was_open
+    }
+
+    pub(super) fn open_regex_tester(&mut self) {
+        self.close_docs();
+        self.close_references();
+        self.close_type_hierarchy();
+        self.regex_tester_open = true;
+    }
+
+    /// Returns whether the regex tester was shown.
+    pub(super) fn close_regex_tester(&mut self) -> bool {
+        std::mem::replace(&mut self.regex_tester_open, false)
+    }
+}
+
+impl Workspace {
+    fn close_documentation_sidebar(&mut self, cx: &mut Context<Self>) {
+        if self.right_sidebar.close_docs() {
+            cx.notify();
+        }
+    }
+
+    pub(super) fn toggle_documentation_sidebar(&mut self, cx: &mut Context<Self>) -> bool {
+        if self.right_sidebar.docs_visible {
+            self.close_documentation_sidebar(cx);
+            return false;
+        }
+
+        self.right_sidebar.open_docs();
+        cx.notify();
+        true
+    }
+
+    /// Opens the regex tester in the right sidebar with its pattern focused,
+    /// or closes it when open.
+    pub(super) fn toggle_regex_tester(&mut self, cx: &mut Context<Self>) {
+        if self.right_sidebar.regex_tester_open {
+            self.close_regex_tester(cx);
+            return;
+        }
+        self.right_sidebar.open_regex_tester();
+        self.panels.request_focus(PanelKind::RegexTester);
+        if self.right_sidebar.regex_tester.read(cx).source() == RegexTesterSource::Document {
+            self.load_regex_tester_document(cx);
+        }
+        cx.notify();
+    }
+
+    fn close_regex_tester(&mut self, cx: &mut Context<Self>) {
+        if self.right_sidebar.close_regex_tester() {
+            self.needs_focus_restore = true;
+            cx.notify();
+        }
+    }
+
+    pub(super) fn handle_regex_tester_event(
+        &mut self,
+        event: RegexTesterEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            RegexTesterEvent::DocumentRequested => self.load_regex_tester_document(cx),
+            RegexTesterEvent::Dismissed => self.close_regex_tester(cx),
+        }
+    }
+
+    /// Gives the regex tester a snapshot of the focused document.
+    fn load_regex_tester_document(&mut self, cx: &mut Context<Self>) {
+        let snapshot = {
+            let editor = &self.core.read(cx).editor;
+            let view_id = editor.tree.focus;
+            editor
+                .tree
+                .try_get(view_id)
+                .and_then(|view| editor.documents.get(&view.doc))
+                .map(|doc| (doc.display_name().into_owned(), doc.text().to_string()))
+        };
+        if let Some((name, text)) = snapshot {
+            self.right_sidebar
+                .regex_tester
+                .update(cx, |tester, cx| tester.set_document(name, text, cx));
+        }
+    }
+
+    /// Show `result` in the references panel, reading the referenced files
+    /// for context lines in the background. A result for the panel's own
+    /// request refreshes it in place.
+    pub(super) fn show_references(&mut self, result: ReferencesResult, cx: &mut Context<Self>) {
+        let ReferencesResult {
+            origin,
+            locations,
+            error,
+        } = result;
+        let generation = self.right_sidebar.open_references();
+
+        let panel = match &mut self.right_sidebar.references {
+            Some(panel) if panel.origin == origin => panel,
+            panel => panel.insert(ReferencesPanel::new(origin)),
+        };
+        panel.loading = true;
+        panel.error = error;
+        if panel.error.is_some() {
+            panel.set_groups(Vec::new());
+            cx.notify();
+            return;
+        }
+
+        let (root, context_lines, open_texts, workspace_backend) = {
+            let core = self.core.read(cx);
+            (
+                core.project_directory.clone(),
+                core.config.gui.references.context_lines,
+                open_document_texts(&core.editor),
+                core.workspace_backend.clone(),
+            )
+        };
+        // Open documents are shown with their unsaved edits.
+        let mut texts: HashMap<PathBuf, Rope> = open_texts.into_iter().collect();
+        let missing = missing_paths(&locations, &texts);
+        let grouping = self.handle.spawn(async move {
+            for path in missing {
+                if let Ok(read) = workspace_backend
+                    .read_file(&path, ReadOptions::default())
+                    .await
+                {
+                    texts.insert(
+                        path,
+                        Rope::from(String::from_utf8_lossy(&read.bytes).as_ref()),
+                    );
+                }
+            }
+            group_references(locations, root.as_deref(), &texts, context_lines)
+        });
+
+        cx.spawn(async move |this, cx| {
+            let groups = grouping.await.unwrap_or_default();
+            let _ = this.update(cx, |workspace, cx| {
+                if !workspace.right_sidebar.references_current(generation) {
+                    return;
+                }
+                if let Some(panel) = &mut workspace.right_sidebar.references {
+                    panel.set_groups(groups);
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+        cx.notify();
+    }
+
+    /// Repeat the request behind the references panel.
+    fn refresh_references(&mut self, cx: &mut Context<Self>) {
+        let Some(panel) = &mut self.right_sidebar.references else {
+            return;
+        };
+        panel.loading = true;
+        let origin = panel.origin.clone();
+        self.core
+            .update(cx, |core, cx| core.refresh_references(origin, cx));
+        cx.notify();
+    }
+
+    fn close_references_panel(&mut self, cx: &mut Context<Self>) {
+        if self.right_sidebar.close_references() {
+            cx.notify();
+        }
+    }
+
+    /// Open the type hierarchy of the symbol under the cursor in the right
+    /// sidebar and give it keyboard focus.
+    pub fn open_type_hierarchy(&mut self, cx: &mut Context<Self>) {
+        let generation = self
+            .right_sidebar
+            .open_type_hierarchy(TypeHierarchyPanel::new(TypeHierarchyDirection::Supertypes));
+        self.panels.request_focus(PanelKind::TypeHierarchy);
+        self.core
+            .update(cx, |core, cx| core.prepare_type_hierarchy(generation, cx));
+        cx.notify();
+    }
+
+    fn close_type_hierarchy_panel(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.right_sidebar.close_type_hierarchy() {
+            self.focus_editor(window, cx);
+            cx.notify();
+        }
+    }
+
+    pub(super) fn handle_type_hierarchy_update(
+        &mut self,
+        update: TypeHierarchyUpdate,
+        cx: &mut Context<Self>,
+    ) {
+        let current_generation = self.right_sidebar.type_hierarchy_generation();
+        let Some(panel) = &mut self.right_sidebar.type_hierarchy else {
+            return;
+        };
+        match update {
+            TypeHierarchyUpdate::Prepared { generation, root } => {
+                if generation != current_generation {
+                    return;
+                }
+                if let Some(node) = panel.set_root(root) {
+                    let direction = panel.direction;
+                    self.resolve_type_hierarchy_node(direction, node, cx);
+                }
+            }
+            TypeHierarchyUpdate::Resolved {
+                generation,
+                direction,
+                node,
+                children,
+            } => {
+                if generation != current_generation {
+                    return;
+                }
+                if let Some(tree) = panel.tree_for(direction) {
+                    tree.set_children(node, children);
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    /// Ask the language server for the children of `node`, which the tree has
+    /// marked as loading.
+    fn resolve_type_hierarchy_node(
+        &mut self,
+        direction: TypeHierarchyDirection,
+        node: usize,
+        cx: &mut Context<Self>,
+    ) {
+        let generation = self.right_sidebar.type_hierarchy_generation();
+        let Some((server_id, item)) =
+            self.right_sidebar
+                .type_hierarchy
+                .as_mut()
+                .and_then(|panel| {
+                    let server_id = panel.server_id?;
+                    let item = panel.tree_for(direction)?.node(node)?.entry.item.clone();
+                    Some((server_id, item))
+                })
+        else {
+            return;
+        };
+        self.core.update(cx, |core, cx| {
+            core.resolve_type_hierarchy(generation, server_id, direction, node, item, cx)
+        });
+    }
+
+    fn set_type_hierarchy_direction(
+        &mut self,
+        direction: TypeHierarchyDirection,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(panel) = &mut self.right_sidebar.type_hierarchy else {
+            return;
+        };
+        if let Some(node) = panel.set_direction(direction) {
+            self.resolve_type_hierarchy_node(direction, node, cx);
+        }
+        cx.notify();
+    }
+
+    fn toggle_type_hierarchy_node(&mut self, node: usize, cx: &mut Context<Self>) {
+        let Some(panel) = &mut self.right_sidebar.type_hierarchy else {
+            return;
+        };
+        let direction = panel.direction;
+        let Some(tree) = panel.tree_mut() else {
+            return;
+        };
+        tree.select(node);
+        if tree.toggle(node) {
+            self.resolve_type_hierarchy_node(direction, node, cx);
+        }
+        cx.notify();
+    }
+
+    /// Jump to the declaration of `node` in the open tab of the panel.
+    fn open_type_hierarchy_node(
+        &mut self,
+        node: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(tree) = self
+            .right_sidebar
+            .type_hierarchy
+            .as_mut()
+            .and_then(|panel| panel.tree_mut())
+        else {
+            return;
+        };
+        tree.select(node);
+        let Some(entry) = tree.node(node).map(|node| node.entry.clone()) else {
+            return;
+        };
+        self.core.update(cx, |core, cx| match &entry.location {
+            Some(location) => match core.jump_to_lsp_location(location) {
+                Ok((doc_id, view_id)) => {
+                    cx.emit(crate::Update::SelectionChanged { doc_id, view_id });
+                    cx.emit(crate::Update::Redraw);
+                }
+                Err(err) => core.editor.set_error(err.to_string()),
+            },
+            None => core
+                .editor
+                .set_error(format!("{} is not declared in a file", entry.item.name)),
+        });
+        self.focus_editor(window, cx);
+        cx.notify();
+    }
+
+    /// Arrow keys move through and expand the tree, Enter opens the
+    /// selection, Tab switches between supertypes and subtypes and Escape
+    /// returns to the editor.
+    fn handle_type_hierarchy_key(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(panel) = &mut self.right_sidebar.type_hierarchy else {
+            return;
+        };
+        let direction = panel.direction;
+        match event.keystroke.key.as_str() {
+            "escape" => {
+                self.focus_editor(window, cx);
+                return;
+            }
+            "tab" => {
+                let next = match direction {
+                    TypeHierarchyDirection::Supertypes => TypeHierarchyDirection::Subtypes,
+                    TypeHierarchyDirection::Subtypes => TypeHierarchyDirection::Supertypes,
+                };
+                self.set_type_hierarchy_direction(next, cx);
+                return;
+            }
+            _ => {}
+        }
+        let Some(tree) = panel.tree_mut() else {
+            return;
+        };
+        match event.keystroke.key.as_str() {
+            "up" => tree.move_selection(-1),
+            "down" => tree.move_selection(1),
+            "home" => tree.select(0),
+            "right" => {
+                if let Some(node) = tree.expand_selected() {
+                    self.resolve_type_hierarchy_node(direction, node, cx);
+                }
+            }
+            "left" => tree.collapse_selected(),
+            "enter" => {
+                let node = tree.selected();
+                self.open_type_hierarchy_node(node, window, cx);
+                return;
+            }
+            "space" => {
+                let node = tree.selected();
+                self.toggle_type_hierarchy_node(node, cx);
+                return;
+            }
+            _ => return,
+        }
+        cx.notify();
+    }
+
+    fn toggle_references_group(&mut self, index: usize, cx: &mut Context<Self>) {
+        if let Some(panel) = &mut self.right_sidebar.references {
+            panel.toggle_group(index);
+            cx.notify();
+        }
+    }
+
+    /// Jump to a reference listed in the panel, closing the panel afterwards
+    /// when `references.clear_on_navigate` is set.
+    fn open_reference(
+        &mut self,
+        group: usize,
+        entry: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(location) = self
+            .right_sidebar
+            .references
+            .as_ref()
+            .and_then(|panel| panel.groups.get(group))
+            .and_then(|group| group.entries.get(entry))
+            .map(|entry| entry.location.clone())
+        else {
+            return;
+        };
+
+        let clear_on_navigate = self.core.update(cx, |core, cx| {
+            match core.jump_to_lsp_location(&location) {
+                Ok((doc_id, view_id)) => {
+                    cx.emit(crate::Update::SelectionChanged { doc_id, view_id });
+                    cx.emit(crate::Update::Redraw);
+                }
+                Err(err) => core.editor.set_error(err.to_string()),
+            }
+            core.config.gui.references.clear_on_navigate
+        });
+        if clear_on_navigate {
+            self.close_references_panel(cx);
+        }
+        self.focus_editor(window, cx);
+    }
+
+    pub(super) fn set_documentation_sidebar_entries(
+        &mut self,
+        entries: Vec<HoverDocEntry>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.right_sidebar.set_docs_entries(entries) {
+            cx.notify();
+        }
+    }
+
+    pub(super) fn render_documentation_sidebar(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
+        let tokens = &cx.theme().tokens;
+        let gui_config = &self.core.read(cx).config.gui;
+        let file_tree_tokens = file_tree_tokens_for_gui_config(tokens, gui_config);
+        let markdown_style = MarkdownStyle::from_tokens(tokens).compact();
+
+        let mut body = div()
+            .id("documentation-sidebar-body")
+            .flex()
+            .flex_col()
+            .size_full()
+            .min_h(px(0.0))
+            .overflow_y_scroll()
+            .track_scroll(&self.right_sidebar.docs_scroll_handle)
+            .px(tokens.sizes.space_3)
+            .py(tokens.sizes.space_3)
+            .gap(tokens.sizes.space_4);
+
+        if self.right_sidebar.docs_loading {
+            body = body.child(
+                StateView::new("documentation-sidebar-loading", "Loading documentation")
+                    .loading(true)
+                    .compact(true),
+            );
+        } else if self.right_sidebar.docs_entries.is_empty() {
+            body = body.child(
+                StateView::new("documentation-sidebar-empty", "No documentation available")
+                    .detail("Move the cursor to a symbol with hover documentation.")
+                    .icon("icons/book-text.svg")
+                    .compact(true),
+            );
+        } else {
+            for (index, entry) in self.right_sidebar.docs_entries.iter().enumerate() {
+                body = body.child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap(tokens.sizes.space_2)
+                        .when(index > 0, |section| {
+                            section
+                                .border_t_1()
+                                .border_color(file_tree_tokens.separator)
+                                .pt(tokens.sizes.space_4)
+                        })
+                        .child(
+                            div()
+                                .text_xs()
+                                .font_weight(FontWeight::MEDIUM)
+                                .text_color(file_tree_tokens.item_text_secondary)
+                                .child(entry.server_name.clone()),
+                        )
+                        .child(markdown_extended(
+                            entry.markdown.clone(),
+                            markdown_style.clone(),
+                        )),
+                );
+            }
+        }
+
+        let body_container = div()
+            .relative()
+            .flex_1()
+            .w_full()
+            .min_h(px(0.0))
+            .overflow_hidden()
+            .child(div().size_full().min_h(px(0.0)).child(body))
+            .when_some(
+                Scrollbar::vertical(self.right_sidebar.docs_scrollbar_state.clone()),
+                |container, scrollbar| {
+                    container.child(
+                        div()
+                            .id("documentation-sidebar-scrollbar")
+                            .absolute()
+                            .top_0()
+                            .right_0()
+                            .bottom_0()
+                            .w(SCROLLBAR_THICKNESS)
+                            .child(scrollbar),
+                    )
+                },
+            );
+
+        div()
+            .id("documentation-sidebar")
+            .w(px(self.right_sidebar.width))
+            .h_full()
+            .flex_shrink_0()
+            .min_h(px(0.0))
+            .flex()
+            .flex_col()
+            .overflow_hidden()
+            .bg(file_tree_tokens.background)
+            .border_l_1()
+            .border_color(file_tree_tokens.border)
+            .text_color(file_tree_tokens.item_text)
+            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+            .child(
+                div()
+                    .h(tokens.sizes.space_8)
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .px(tokens.sizes.space_3)
+                    .border_b_1()
+                    .border_color(file_tree_tokens.separator)
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(tokens.sizes.space_2)
+                            .text_sm()
+                            .font_weight(FontWeight::MEDIUM)
+                            .text_color(file_tree_tokens.item_text)
+                            .child(
+                                svg()
+                                    .path("icons/book-text.svg")
+                                    .size(px(14.0))
+                                    .text_color(file_tree_tokens.item_text)
+                                    .flex_shrink_0(),
+                            )
+                            .child("Documentation"),
+                    )
+                    .child(
+                        div()
+                            .id("documentation-sidebar-close")
+                            .size(tokens.sizes.space_6)
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .rounded(tokens.sizes.radius_sm)
+                            .cursor_pointer()
+                            .hover(move |button| button.bg(file_tree_tokens.item_background_hover))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|workspace, _event, _window, cx| {
+                                    workspace.close_documentation_sidebar(cx);
+                                    cx.stop_propagation();
+                                }),
+                            )
+                            .child(
+                                svg()
+                                    .path("icons/close.svg")
+                                    .size(px(12.0))
+                                    .text_color(file_tree_tokens.item_text_secondary),
+                            ),
+                    ),
+            )
+            .child(body_container)
+            .into_any_element()
+    }
+
+    pub(super) fn render_references_panel(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
+        // Copied out of the theme so the listeners below can borrow `cx`.
+        let tokens = cx.theme().tokens;
+        let gui_config = &self.core.read(cx).config.gui;
+        let file_tree_tokens = file_tree_tokens_for_gui_config(&tokens, gui_config);
+        let font_family =
+            SharedString::from(cx.global::<crate::types::EditorFontConfig>().family.clone());
+        let highlight_background = tokens.editor.selection_secondary;
+        let Some(panel) = self.right_sidebar.references.as_ref() else {
+            return div().into_any_element();
+        };
+
+        let mut body = div()
+            .id("references-panel-body")
+            .flex()
+            .flex_col()
+            .size_full()
+            .min_h(px(0.0))
+            .overflow_y_scroll()
+            .track_scroll(&self.right_sidebar.references_scroll_handle)
+            .py(tokens.sizes.space_2);
+
+        if panel.loading && panel.groups.is_empty() {
+            body = body.child(
+                StateView::new("references-panel-loading", "Finding references")
+                    .loading(true)
+                    .compact(true),
+            );
+        } else if let Some(error) = &panel.error {
+            body = body.child(
+                StateView::new("references-panel-error", "Couldn't find references")
+                    .detail(error.clone())
+                    .icon("icons/search.svg")
+                    .compact(true),
+            );
+        } else if panel.groups.is_empty() {
+            body = body.child(
+                StateView::new("references-panel-empty", "No references found")
+                    .icon("icons/search.svg")
+                    .compact(true),
+            );
+        }
+
+        for (group_index, group) in panel.groups.iter().enumerate() {
+            let chevron = if group.collapsed {
+                "icons/chevron-right.svg"
+            } else {
+                "icons/chevron-down.svg"
+            };
+            body = body.child(
+                div()
+                    .id(("references-group", group_index))
+                    .flex()
+                    .items_center()
+                    .gap(tokens.sizes.space_1)
+                    .px(tokens.sizes.space_2)
+                    .py(tokens.sizes.space_1)
+                    .cursor_pointer()
+                    .hover(move |row| row.bg(file_tree_tokens.item_background_hover))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |workspace, _event, _window, cx| {
+                            workspace.toggle_references_group(group_index, cx);
+                            cx.stop_propagation();
+                        }),
+                    )
+                    .child(
+                        svg()
+                            .path(chevron)
+                            .size(px(12.0))
+                            .text_color(file_tree_tokens.item_text_secondary)
+                            .flex_shrink_0(),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w(px(0.0))
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .whitespace_nowrap()
+                            .text_sm()
+                            .font_weight(FontWeight::MEDIUM)
+                            .child(group.label.clone()),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(file_tree_tokens.item_text_secondary)
+                            .child(group.entries.len().to_string()),
+                    ),
+            );
+            if group.collapsed {
+                continue;
+            }
+
+            for (entry_index, entry) in group.entries.iter().enumerate() {
+                let mut lines = div().flex().flex_col().min_w(px(0.0)).overflow_hidden();
+                if entry.lines.is_empty() {
+                    lines = lines.child(
+                        div()
+                            .text_color(file_tree_tokens.item_text_secondary)
+                            .child(format!("{}:{}", entry.line + 1, entry.column)),
+                    );
+                }
+                for (line, text) in &entry.lines {
+                    let is_match = *line == entry.line;
+                    let mut content = div().flex().whitespace_nowrap();
+                    if is_match && entry.highlight.end <= text.len() {
+                        let range = entry.highlight.clone();
+                        content = content
+                            .child(text[..range.start].to_string())
+                            .child(
+                                div()
+                                    .bg(highlight_background)
+                                    .rounded(tokens.sizes.radius_sm)
+                                    .child(text[range.clone()].to_string()),
+                            )
+                            .child(text[range.end..].to_string());
+                    } else {
+                        content = content.child(text.clone());
+                    }
+                    lines = lines.child(
+                        div()
+                            .flex()
+                            .gap(tokens.sizes.space_2)
+                            .when(!is_match, |row| {
+                                row.text_color(file_tree_tokens.item_text_secondary)
+                            })
+                            .child(
+                                div()
+                                    .w(px(36.0))
+                                    .flex_shrink_0()
+                                    .flex()
+                                    .justify_end()
+                                    .text_color(file_tree_tokens.item_text_secondary)
+                                    .child((line + 1).to_string()),
+                            )
+                            .child(content),
+                    );
+                }
+
+                body = body.child(
+                    div()
+                        .id(("references-entry", group_index * 100_000 + entry_index))
+                        .pl(tokens.sizes.space_4)
+                        .pr(tokens.sizes.space_2)
+                        .py(tokens.sizes.space_1)
+                        .font_family(font_family.clone())
+                        .text_xs()
+                        .overflow_hidden()
+                        .cursor_pointer()
+                        .hover(move |row| row.bg(file_tree_tokens.item_background_hover))
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |workspace, _event, window, cx| {
+                                workspace.open_reference(group_index, entry_index, window, cx);
+                                cx.stop_propagation();
+                            }),
+                        )
+                        .child(lines),
+                );
+            }
+        }
+
+        let body_container = div()
+            .relative()
+            .flex_1()
+            .w_full()
+            .min_h(px(0.0))
+            .overflow_hidden()
+            .child(div().size_full().min_h(px(0.0)).child(body))
+            .when_some(
+                Scrollbar::vertical(self.right_sidebar.references_scrollbar_state.clone()),
+                |container, scrollbar| {
+                    container.child(
+                        div()
+                            .id("references-panel-scrollbar")
+                            .absolute()
+                            .top_0()
+                            .right_0()
+                            .bottom_0()
+                            .w(SCROLLBAR_THICKNESS)
+                            .child(scrollbar),
+                    )
+                },
+            );
+
+        let title = if panel.origin.symbol.is_empty() {
+            "References".to_string()
+        } else {
+            format!("References: {}", panel.origin.symbol)
+        };
+        let header_button = |id: &'static str, icon: &'static str| {
+            div()
+                .id(id)
+                .size(tokens.sizes.space_6)
+                .flex()
+                .items_center()
+                .justify_center()
+                .rounded(tokens.sizes.radius_sm)
+                .cursor_pointer()
+                .hover(move |button| button.bg(file_tree_tokens.item_background_hover))
+                .child(
+                    svg()
+                        .path(icon)
+                        .size(px(12.0))
+                        .text_color(file_tree_tokens.item_text_secondary),
+                )
+        };
+
+        div()
+            .id("references-panel")
+            .w(px(self.right_sidebar.width))
+            .h_full()
+            .flex_shrink_0()
+            .min_h(px(0.0))
+            .flex()
+            .flex_col()
+            .overflow_hidden()
+            .bg(file_tree_tokens.background)
+            .border_l_1()
+            .border_color(file_tree_tokens.border)
+            .text_color(file_tree_tokens.item_text)
+            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+            .child(
+                div()
+                    .h(tokens.sizes.space_8)
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .gap(tokens.sizes.space_2)
+                    .px(tokens.sizes.space_3)
+                    .border_b_1()
+                    .border_color(file_tree_tokens.separator)
+                    .child(
+                        div()
+                            .flex()
+                            .flex_1()
+                            .min_w(px(0.0))
+                            .items_center()
+                            .gap(tokens.sizes.space_2)
+                            .text_sm()
+                            .font_weight(FontWeight::MEDIUM)
+                            .text_color(file_tree_tokens.item_text)
+                            .child(
+                                svg()
+                                    .path("icons/search.svg")
+                                    .size(px(14.0))
+                                    .text_color(file_tree_tokens.item_text)
+                                    .flex_shrink_0(),
+                            )
+                            .child(
+                                div()
+                                    .overflow_hidden()
+                                    .text_ellipsis()
+                                    .whitespace_nowrap()
+                                    .child(title),
+                            )
+                            .when(!panel.loading && panel.error.is_none(), |header| {
+                                header.child(
+                                    div()
+                                        .text_xs()
+                                        .font_weight(FontWeight::NORMAL)
+                                        .whitespace_nowrap()
+                                        .text_color(file_tree_tokens.item_text_secondary)
+                                        .child(panel.summary()),
+                                )
+                            }),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap(tokens.sizes.space_1)
+                            .child(
+                                header_button("references-panel-refresh", "icons/rotate-ccw.svg")
+                                    .on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(|workspace, _event, _window, cx| {
+                                            workspace.refresh_references(cx);
+                                            cx.stop_propagation();
+                                        }),
+                                    ),
+                            )
+                            .child(
+                                header_button("references-panel-close", "icons/close.svg")
+                                    .on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(|workspace, _event, _window, cx| {
+                                            workspace.close_references_panel(cx);
+                                            cx.stop_propagation();
+                                        }),
+                                    ),
+                            ),
+                    ),
+            )
+            .child(body_container)
+            .into_any_element()
+    }
+
+    pub(super) fn render_regex_tester_panel(&self, cx: &mut Context<Self>) -> gpui::AnyElement {
+        let tokens = cx.theme().tokens;
+        let gui_config = &self.core.read(cx).config.gui;
+        let file_tree_tokens = file_tree_tokens_for_gui_config(&tokens, gui_config);
+
+        div()
+            .id("regex-tester-panel")
+            .w(px(self.right_sidebar.width))
+            .h_full()
+            .flex_shrink_0()
+            .min_h(px(0.0))
+            .overflow_hidden()
+            .bg(file_tree_tokens.background)
+            .border_l_1()
+            .border_color(file_tree_tokens.border)
+            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+            .child(self.right_sidebar.regex_tester.clone())
+            .into_any_element()
+    }
+
+    pub(super) fn render_type_hierarchy_panel(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> gpui::AnyElement {
+        let tokens = cx.theme().tokens;
+        let gui_config = &self.core.read(cx).config.gui;
+        let file_tree_tokens = file_tree_tokens_for_gui_config(&tokens, gui_config);
+        let Some(panel) = self.right_sidebar.type_hierarchy.as_ref() else {
+            return div().into_any_element();
+        };
+        let focused = self.right_sidebar.type_hierarchy_focus.is_focused(window);
+
+        let mut tabs = div()
+            .flex()
+            .gap(tokens.sizes.space_1)
+            .px(tokens.sizes.space_2)
+            .py(tokens.sizes.space_1)
+            .border_b_1()
+            .border_color(file_tree_tokens.separator);
+        for direction in [
+            TypeHierarchyDirection::Supertypes,
+            TypeHierarchyDirection::Subtypes,
+        ] {
+            let active = panel.direction == direction;
+            tabs = tabs.child(
+                div()
+                    .id(direction.label())
+                    .px(tokens.sizes.space_2)
+                    .py(tokens.sizes.space_1)
+                    .rounded(tokens.sizes.radius_sm)
+                    .text_xs()
+                    .cursor_pointer()
+                    .when(active, |tab| {
+                        tab.bg(file_tree_tokens.item_background_selected)
+                            .text_color(file_tree_tokens.item_text_selected)
+                    })
+                    .when(!active, |tab| {
+                        tab.text_color(file_tree_tokens.item_text_secondary)
+                            .hover(move |tab| tab.bg(file_tree_tokens.item_background_hover))
+                    })
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |workspace, _event, window, cx| {
+                            window.focus(&workspace.right_sidebar.type_hierarchy_focus, cx);
+                            workspace.set_type_hierarchy_direction(direction, cx);
+                            cx.stop_propagation();
+                        }),
+                    )
+                    .child(direction.label()),
+            );
+        }
+
+        let mut body = div()
+            .id("type-hierarchy-body")
+            .flex()
+            .flex_col()
+            .size_full()
+            .min_h(px(0.0))
+            .overflow_y_scroll()
+            .track_scroll(&self.right_sidebar.type_hierarchy_scroll_handle)
+            .py(tokens.sizes.space_1);
+
+        let tree = panel.tree();
+        if panel.loading {
+            body = body.child(
+                StateView::new("type-hierarchy-loading", "Preparing type hierarchy")
+                    .loading(true)
+                    .compact(true),
+            );
+        } else if let Some(error) = &panel.error {
+            body = body.child(
+                StateView::new("type-hierarchy-error", "No type hierarchy")
+                    .detail(error.clone())
+                    .icon("icons/completion-class.svg")
+                    .compact(true),
+            );
+        }
+
+        let indent = |depth: usize| px(8.0 + depth as f32 * 14.0);
+        for index in tree.map(|tree| tree.visible()).unwrap_or_default() {
+            let Some(node) = tree.and_then(|tree| tree.node(index)) else {
+                continue;
+            };
+            let selected = tree.is_some_and(|tree| tree.selected() == index);
+            let chevron = match (&node.children, node.expanded) {
+                (TypeHierarchyChildren::Loading, _) => Some("icons/loader-circle.svg"),
+                _ if !node.is_expandable() => None,
+                (_, true) => Some("icons/chevron-down.svg"),
+                (_, false) => Some("icons/chevron-right.svg"),
+            };
+            body = body.child(
+                div()
+                    .id(("type-hierarchy-node", index))
+                    .flex()
+                    .items_center()
+                    .gap(tokens.sizes.space_1)
+                    .pl(indent(node.depth))
+                    .pr(tokens.sizes.space_2)
+                    .py(px(2.0))
+                    .text_sm()
+                    .cursor_pointer()
+                    .when(selected, |row| {
+                        row.bg(if focused {
+                            file_tree_tokens.item_background_selected
+                        } else {
+                            file_tree_tokens.item_background_hover
+                        })
+                        .text_color(file_tree_tokens.item_text_selected)
+                    })
+                    .when(!selected, |row| {
+                        row.hover(move |row| row.bg(file_tree_tokens.item_background_hover))
+                    })
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |workspace, _event, window, cx| {
+                            workspace.open_type_hierarchy_node(index, window, cx);
+                            cx.stop_propagation();
+                        }),
+                    )
+                    .child(
+                        div()
+                            .id(("type-hierarchy-chevron", index))
+                            .size(px(14.0))
+                            .flex_shrink_0()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .when_some(chevron, |toggle, chevron| {
+                                toggle
+                                    .on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(move |workspace, _event, window, cx| {
+                                            window.focus(
+                                                &workspace.right_sidebar.type_hierarchy_focus,
+                                                cx,
+                                            );
+                                            workspace.toggle_type_hierarchy_node(index, cx);
+                                            cx.stop_propagation();
+                                        }),
+                                    )
+                                    .child(
+                                        svg()
+                                            .path(chevron)
+                                            .size(px(12.0))
+                                            .text_color(file_tree_tokens.icon_color_secondary),
+                                    )
+                            }),
+                    )
+                    .child(
+                        div()
+                            .flex_shrink_0()
+                            .whitespace_nowrap()
+                            .child(node.entry.item.name.clone()),
+                    )
+                    .when_some(node.entry.item.detail.clone(), |row, detail| {
+                        row.child(
+                            div()
+                                .min_w(px(0.0))
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .whitespace_nowrap()
+                                .text_xs()
+                                .text_color(file_tree_tokens.item_text_secondary)
+                                .child(detail),
+                        )
+                    }),
+            );
+            if let (true, TypeHierarchyChildren::Failed(error)) = (node.expanded, &node.children) {
+                body = body.child(
+                    div()
+                        .pl(indent(node.depth + 1))
+                        .pr(tokens.sizes.space_2)
+                        .text_xs()
+                        .text_color(file_tree_tokens.item_text_secondary)
+                        .child(error.clone()),
+                );
+            }
+        }
+
+        let body_container = div()
+            .relative()
+            .flex_1()
+            .w_full()
+            .min_h(px(0.0))
+            .overflow_hidden()
+            .child(div().size_full().min_h(px(0.0)).child(body))
+            .when_some(
+                Scrollbar::vertical(self.right_sidebar.type_hierarchy_scrollbar_state.clone()),
+                |container, scrollbar| {
+                    container.child(
+                        div()
+                            .id("type-hierarchy-scrollbar")
+                            .absolute()
+                            .top_0()
+                            .right_0()
+                            .bottom_0()
+                            .w(SCROLLBAR_THICKNESS)
+                            .child(scrollbar),
+                    )
+                },
+            );
+
+        let title = if panel.symbol.is_empty() {
+            "Type Hierarchy".to_string()
+        } else {
+            format!("Type Hierarchy: {}", panel.symbol)
+        };
+
+        div()
+            .id("type-hierarchy-panel")
+            .track_focus(&self.right_sidebar.type_hierarchy_focus)
+            .on_key_down(cx.listener(|workspace, event: &KeyDownEvent, window, cx| {
+                workspace.handle_type_hierarchy_key(event, window, cx);
+                // Plain keys stay in the panel; shortcuts still reach the workspace.
+                let modifiers = event.keystroke.modifiers;
+                if !(modifiers.platform || modifiers.control) {
+                    cx.stop_propagation();
+                }
+            }))
+            .w(px(self.right_sidebar.width))
+            .h_full()
+            .flex_shrink_0()
+            .min_h(px(0.0))
+            .flex()
+            .flex_col()
+            .overflow_hidden()
+            .bg(file_tree_tokens.background)
+            .border_l_1()
+            .border_color(file_tree_tokens.border)
+            .text_color(file_tree_tokens.item_text)
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|workspace, _event, window, cx| {
+                    window.focus(&workspace.right_sidebar.type_hierarchy_focus, cx);
+                    cx.stop_propagation();
+                }),
+            )
+            .child(
+                div()
+                    .h(tokens.sizes.space_8)
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .gap(tokens.sizes.space_2)
+                    .px(tokens.sizes.space_3)
+                    .border_b_1()
+                    .border_color(file_tree_tokens.separator)
+                    .child(
+                        div()
+                            .flex()
+                            .flex_1()
+                            .min_w(px(0.0))
+                            .items_center()
+                            .gap(tokens.sizes.space_2)
+                            .text_sm()
+                            .font_weight(FontWeight::MEDIUM)
+                            .child(
+                                svg()
+                                    .path("icons/completion-class.svg")
+                                    .size(px(14.0))
+                                    .text_color(file_tree_tokens.item_text)
+                                    .flex_shrink_0(),
+                            )
+                            .child(
+                                div()
+                                    .overflow_hidden()
+                                    .text_ellipsis()
+                                    .whitespace_nowrap()
+                                    .child(title),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .id("type-hierarchy-close")
+                            .size(tokens.sizes.space_6)
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .rounded(tokens.sizes.radius_sm)
+                            .cursor_pointer()
+                            .hover(move |button| button.bg(file_tree_tokens.item_background_hover))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|workspace, _event, window, cx| {
+                                    workspace.close_type_hierarchy_panel(window, cx);
+                                    cx.stop_propagation();
+                                }),
+                            )
+                            .child(
+                                svg()
+                                    .path("icons/close.svg")
+                                    .size(px(12.0))
+                                    .text_color(file_tree_tokens.item_text_secondary),
+                            ),
+                    ),
+            )
+            .child(tabs)
+            .child(body_container)
+            .into_any_element()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    fn opening_a_panel_closes_the_others(cx: &mut gpui::TestAppContext) {
+        cx.update(|cx| {
+            let mut sidebar = RightSidebar::new(360.0, cx);
+            assert!(!sidebar.is_visible());
+
+            sidebar.open_docs();
+            assert!(sidebar.is_visible() && sidebar.docs_loading);
+            assert!(sidebar.set_docs_entries(Vec::new()));
+
+            let generation = sidebar
+                .open_type_hierarchy(TypeHierarchyPanel::new(TypeHierarchyDirection::Supertypes));
+            assert!(!sidebar.docs_visible);
+            assert!(!sidebar.set_docs_entries(Vec::new()));
+
+            sidebar.open_regex_tester();
+            assert!(sidebar.type_hierarchy.is_none());
+            assert_ne!(sidebar.type_hierarchy_generation(), generation);
+
+            let generation = sidebar.open_references();
+            assert!(!sidebar.regex_tester_open && sidebar.references_current(generation));
+            assert!(!sidebar.close_references());
+            assert!(sidebar.references_current(generation));
+
+            assert!(!sidebar.resize(360.2));
+            assert!(sidebar.resize(420.0));
+            assert_eq!(sidebar.width, 420.0);
+        });
+    }
+}
@@ -0,0 +1,30 @@
+// ABOUTME: Keyboard focus targets of the status bar buttons
+// ABOUTME: Created once with the workspace so each button keeps its focus across renders
+
+use gpui::{App, FocusHandle};
+
+pub(super) struct StatusbarFocus {
+    pub(super) lsp: FocusHandle,
+    pub(super) run: FocusHandle,
+    pub(super) run_menu: FocusHandle,
+    pub(super) indentation: FocusHandle,
+    pub(super) file_tree: FocusHandle,
+    pub(super) terminal: FocusHandle,
+    pub(super) lsp_restart: FocusHandle,
+    pub(super) lsp_logs: FocusHandle,
+}
+
+impl StatusbarFocus {
+    pub(super) fn new(cx: &mut App) -> Self {
+        Self {
+            lsp: cx.focus_handle(),
+            run: cx.focus_handle(),
+            run_menu: cx.focus_handle(),
+            indentation: cx.focus_handle(),
+            file_tree: cx.focus_handle(),
+            terminal: cx.focus_handle(),
+            lsp_restart: cx.focus_handle(),
+            lsp_logs: cx.focus_handle(),
+        }
+    }
+}
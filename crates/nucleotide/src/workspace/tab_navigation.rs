@@ -0,0 +1,324 @@
+use std::time::{Duration, Instant};
+
+use helix_core::diagnostic::Severity as DiagnosticSeverity;
+
+/// Repeated last-used tab requests within this window keep walking the same MRU snapshot.
+const MRU_TAB_CYCLE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum TabCycleDirection {
+    Next,
+    Previous,
+}
+
+/// Returns the tab next to `active` in strip order, wrapping at both ends.
+pub(super) fn adjacent_tab<T: Copy + Eq>(
+    ordered_tabs: &[T],
+    active_tab: Option<T>,
+    direction: TabCycleDirection,
+) -> Option<T> {
+    let Some(active_index) =
+        active_tab.and_then(|active| ordered_tabs.iter().position(|tab| *tab == active))
+    else {
+        return match direction {
+            TabCycleDirection::Next => ordered_tabs.first().copied(),
+            TabCycleDirection::Previous => ordered_tabs.last().copied(),
+        };
+    };
+
+    let tab_count = ordered_tabs.len();
+    let target_index = match direction {
+        TabCycleDirection::Next => (active_index + 1) % tab_count,
+        TabCycleDirection::Previous => (active_index + tab_count - 1) % tab_count,
+    };
+
+    (target_index != active_index).then(|| ordered_tabs[target_index])
+}
+
+/// Tabs ordered most recently used first.
+///
+/// Fed by view focus, tab activation and open events rather than derived
+/// from per-document focus timestamps, so buffer switches inside a view and
+/// background opens land in the order the user actually visited them.
+#[derive(Debug)]
+pub(super) struct TabMruTracker<T> {
+    order: Vec<T>,
+}
+
+impl<T> Default for TabMruTracker<T> {
+    fn default() -> Self {
+        Self { order: Vec::new() }
+    }
+}
+
+impl<T: Copy + Eq> TabMruTracker<T> {
+    /// Moves `id` to the front of the history.
+    pub(super) fn record(&mut self, id: T) {
+        self.remove(id);
+        self.order.insert(0, id);
+    }
+
+    pub(super) fn remove(&mut self, id: T) {
+        self.order.retain(|candidate| *candidate != id);
+    }
+
+    /// `tabs` ordered most recently used first. Tabs never used keep their
+    /// order after the rest.
+    pub(super) fn order_of(&self, tabs: &[T]) -> Vec<T> {
+        let mut ordered: Vec<T> = self
+            .order
+            .iter()
+            .copied()
+            .filter(|id| tabs.contains(id))
+            .collect();
+        ordered.extend(tabs.iter().copied().filter(|id| !self.order.contains(id)));
+        ordered
+    }
+}
+
+/// Tracks a run of last-used tab requests so repeated presses walk further back
+/// through history instead of toggling between the two most recent tabs.
+#[derive(Debug)]
+pub(super) struct MruTabCycle<T> {
+    snapshot: Vec<T>,
+    position: usize,
+    last_step_at: Option<Instant>,
+}
+
+impl<T> Default for MruTabCycle<T> {
+    fn default() -> Self {
+        Self {
+            snapshot: Vec::new(),
+            position: 0,
+            last_step_at: None,
+        }
+    }
+}
+
+impl<T: Copy + Eq> MruTabCycle<T> {
+    pub(super) fn step(
+        &mut self,
+        mru_order: Vec<T>,
+        active_tab: Option<T>,
+        now: Instant,
+    ) -> Option<T> {
+        let continuing = self
+            .last_step_at
+            .is_some_and(|last_step_at| now.duration_since(last_step_at) <= MRU_TAB_CYCLE_TIMEOUT)
+            && active_tab.is_some()
+            && self.snapshot.get(self.position).copied() == active_tab;
+
+        if continuing {
+            self.snapshot.retain(|tab| mru_order.contains(tab));
+        } else {
+            self.snapshot = mru_order;
+            if let Some(active_index) =
+                active_tab.and_then(|active| self.snapshot.iter().position(|tab| *tab == active))
+            {
+                let active = self.snapshot.remove(active_index);
+                self.snapshot.insert(0, active);
+            }
+        }
+
+        if self.snapshot.len() < 2 {
+            self.reset();
+            return None;
+        }
+
+        let current_index = active_tab
+            .and_then(|active| self.snapshot.iter().position(|tab| *tab == active))
+            .unwrap_or(0);
+        self.position = (current_index + 1) % self.snapshot.len();
+        self.last_step_at = Some(now);
+        self.snapshot.get(self.position).copied()
+    }
+
+    pub(super) fn reset(&mut self) {
+        self.snapshot.clear();
+        self.position = 0;
+        self.last_step_at = None;
+    }
+}
+
+/// Most severe diagnostic worth badging in the tab overview.
+pub(super) fn tab_overview_diagnostic_severity(
+    severities: impl IntoIterator<Item = Option<DiagnosticSeverity>>,
+) -> Option<DiagnosticSeverity> {
+    let mut badge = None;
+    for severity in severities {
+        match severity {
+            Some(DiagnosticSeverity::Error) => return Some(DiagnosticSeverity::Error),
+            Some(DiagnosticSeverity::Warning) => badge = Some(DiagnosticSeverity::Warning),
+            _ => {}
+        }
+    }
+    badge
+}
+
+/// Fixed-width flag column for the tab overview picker.
+pub(super) fn tab_overview_flags(
+    is_active: bool,
+    is_modified: bool,
+    is_pinned: bool,
+    diagnostic_severity: Option<DiagnosticSeverity>,
+) -> String {
+    let mut flags = String::new();
+    if is_modified {
+        flags.push('+');
+    }
+    if is_active {
+        flags.push('*');
+    }
+    if is_pinned {
+        flags.push('^');
+    }
+    match diagnostic_severity {
+        Some(DiagnosticSeverity::Error) => flags.push('E'),
+        Some(DiagnosticSeverity::Warning) => flags.push('W'),
+        _ => {}
+    }
+    format!("{flags:4}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjacent_tab_wraps_in_strip_order() {
+        let tabs = [1, 2, 3];
+        assert_eq!(
+            adjacent_tab(&tabs, Some(3), TabCycleDirection::Next),
+            Some(1)
+        );
+        assert_eq!(
+            adjacent_tab(&tabs, Some(1), TabCycleDirection::Previous),
+            Some(3)
+        );
+        assert_eq!(
+            adjacent_tab(&tabs, Some(2), TabCycleDirection::Next),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn adjacent_tab_ignores_single_tab_and_falls_back_without_active_tab() {
+        assert_eq!(adjacent_tab(&[7], Some(7), TabCycleDirection::Next), None);
+        assert_eq!(
+            adjacent_tab(&[1, 2], None, TabCycleDirection::Previous),
+            Some(2)
+        );
+        assert_eq!(
+            adjacent_tab::<u32>(&[], None, TabCycleDirection::Next),
+            None
+        );
+    }
+
+    #[test]
+    fn mru_tracker_moves_recorded_tabs_to_front() {
+        let mut tracker = TabMruTracker::default();
+        tracker.record(1);
+        tracker.record(2);
+        tracker.record(3);
+        tracker.record(1);
+        assert_eq!(tracker.order_of(&[1, 2, 3]), vec![1, 3, 2]);
+
+        tracker.remove(3);
+        assert_eq!(tracker.order_of(&[1, 2]), vec![1, 2]);
+    }
+
+    #[test]
+    fn mru_order_keeps_only_open_tabs_and_appends_unvisited_ones() {
+        let mut tracker = TabMruTracker::default();
+        tracker.record(2);
+        tracker.record(4);
+        assert_eq!(tracker.order_of(&[1, 2, 3]), vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn mru_cycle_walks_history_while_repeated() {
+        let now = Instant::now();
+        let mut cycle = MruTabCycle::default();
+
+        assert_eq!(cycle.step(vec![1, 2, 3], Some(1), now), Some(2));
+        // Activating tab 2 moves it to the front of focus history, but the
+        // running cycle keeps its snapshot and continues to the next entry.
+        assert_eq!(
+            cycle.step(vec![2, 1, 3], Some(2), now + Duration::from_millis(300)),
+            Some(3)
+        );
+        assert_eq!(
+            cycle.step(vec![3, 2, 1], Some(3), now + Duration::from_millis(600)),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn mru_cycle_restarts_after_timeout_or_external_switch() {
+        let now = Instant::now();
+        let mut cycle = MruTabCycle::default();
+
+        assert_eq!(cycle.step(vec![1, 2, 3], Some(1), now), Some(2));
+        assert_eq!(
+            cycle.step(vec![2, 1, 3], Some(2), now + Duration::from_secs(5)),
+            Some(1)
+        );
+
+        cycle.reset();
+        assert_eq!(cycle.step(vec![1, 2, 3], Some(1), now), Some(2));
+        // The user clicked tab 3 directly, so a new run starts from it.
+        assert_eq!(
+            cycle.step(vec![3, 2, 1], Some(3), now + Duration::from_millis(200)),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn mru_cycle_skips_closed_tabs_and_needs_two_tabs() {
+        let now = Instant::now();
+        let mut cycle = MruTabCycle::default();
+
+        assert_eq!(cycle.step(vec![1, 2, 3], Some(1), now), Some(2));
+        assert_eq!(
+            cycle.step(vec![2, 1], Some(2), now + Duration::from_millis(100)),
+            Some(1)
+        );
+        assert_eq!(cycle.step(vec![1], Some(1), now), None);
+    }
+
+    #[test]
+    fn tab_overview_badges_keep_column_width() {
+        assert_eq!(tab_overview_flags(false, false, false, None), "    ");
+        assert_eq!(
+            tab_overview_flags(true, true, true, Some(DiagnosticSeverity::Error)),
+            "+*^E"
+        );
+        assert_eq!(
+            tab_overview_flags(false, true, false, Some(DiagnosticSeverity::Warning)),
+            "+W  "
+        );
+    }
+
+    #[test]
+    fn tab_overview_severity_prefers_errors_over_warnings() {
+        assert_eq!(
+            tab_overview_diagnostic_severity([
+                Some(DiagnosticSeverity::Hint),
+                Some(DiagnosticSeverity::Warning),
+                None,
+            ]),
+            Some(DiagnosticSeverity::Warning)
+        );
+        assert_eq!(
+            tab_overview_diagnostic_severity([
+                Some(DiagnosticSeverity::Warning),
+                Some(DiagnosticSeverity::Error),
+            ]),
+            Some(DiagnosticSeverity::Error)
+        );
+        assert_eq!(
+            tab_overview_diagnostic_severity([Some(DiagnosticSeverity::Info)]),
+            None
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use super::tab_navigation::TabCycleDirection;
+
+/// Number of document lines shown in the switcher preview pane.
+pub(super) const TAB_SWITCHER_PREVIEW_LINES: usize = 18;
+
+/// Ctrl-tab switcher state: a frozen MRU snapshot and the highlighted entry.
+#[derive(Debug)]
+pub(super) struct TabSwitcher<T> {
+    entries: Vec<T>,
+    selected: usize,
+}
+
+impl<T: Copy + Eq> TabSwitcher<T> {
+    /// Opens on the entry one step away from the current tab, which is
+    /// expected to be first in `entries`.
+    pub(super) fn open(entries: Vec<T>, direction: TabCycleDirection) -> Option<Self> {
+        if entries.is_empty() {
+            return None;
+        }
+        let mut switcher = Self {
+            entries,
+            selected: 0,
+        };
+        switcher.step(direction);
+        Some(switcher)
+    }
+
+    pub(super) fn step(&mut self, direction: TabCycleDirection) {
+        let len = self.entries.len();
+        self.selected = match direction {
+            TabCycleDirection::Next => (self.selected + 1) % len,
+            TabCycleDirection::Previous => (self.selected + len - 1) % len,
+        };
+    }
+
+    /// Drops a tab that closed while the switcher was open. Returns
+    /// `false` when nothing is left to switch to.
+    pub(super) fn remove(&mut self, id: T) -> bool {
+        let Some(index) = self.entries.iter().position(|entry| *entry == id) else {
+            return true;
+        };
+        self.entries.remove(index);
+        if index < self.selected || self.selected >= self.entries.len() {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        !self.entries.is_empty()
+    }
+
+    pub(super) fn entries(&self) -> &[T] {
+        &self.entries
+    }
+
+    pub(super) fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub(super) fn selected(&self) -> Option<T> {
+        self.entries.get(self.selected).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn switcher_opens_on_previous_document_and_wraps() {
+        let mut switcher = TabSwitcher::open(vec![1, 2, 3], TabCycleDirection::Next).unwrap();
+        assert_eq!(switcher.selected(), Some(2));
+        switcher.step(TabCycleDirection::Next);
+        switcher.step(TabCycleDirection::Next);
+        assert_eq!(switcher.selected(), Some(1));
+
+        let switcher = TabSwitcher::open(vec![1, 2, 3], TabCycleDirection::Previous).unwrap();
+        assert_eq!(switcher.selected(), Some(3));
+
+        let switcher = TabSwitcher::open(vec![1], TabCycleDirection::Next).unwrap();
+        assert_eq!(switcher.selected(), Some(1));
+        assert!(TabSwitcher::<u32>::open(Vec::new(), TabCycleDirection::Next).is_none());
+    }
+
+    #[test]
+    fn switcher_keeps_selection_when_documents_close() {
+        let mut switcher = TabSwitcher::open(vec![1, 2, 3], TabCycleDirection::Next).unwrap();
+        switcher.step(TabCycleDirection::Next);
+        assert_eq!(switcher.selected(), Some(3));
+
+        assert!(switcher.remove(1));
+        assert_eq!(switcher.selected(), Some(3));
+        assert!(switcher.remove(3));
+        assert_eq!(switcher.selected(), Some(2));
+        assert!(!switcher.remove(2));
+        assert_eq!(switcher.selected(), None);
+    }
+}
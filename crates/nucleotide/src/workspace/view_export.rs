@@ -0,0 +1,256 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use gpui::{Hsla, Rgba};
+use helix_view::{Document, Theme, View};
+use nucleotide_editor::{StyledDocumentLinesParams, styled_document_lines};
+use nucleotide_ui::DesignTokens;
+
+use crate::document_export::{
+    DocumentExportFormat, DocumentExportOptions, DocumentExportWarning, render_document,
+};
+use crate::snippet_export::{
+    ExportColor, ExportLine, ExportSpan, SnippetExport, SnippetExportFormat, render_snippet,
+};
+
+fn export_color(color: Hsla) -> ExportColor {
+    let rgba = Rgba::from(color);
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    ExportColor {
+        r: channel(rgba.r),
+        g: channel(rgba.g),
+        b: channel(rgba.b),
+    }
+}
+
+/// Document lines to export and whether selections should be painted: a
+/// selection spanning more than one character is exported on its own,
+/// otherwise the lines visible in the view.
+fn export_line_range(doc: &Document, view: &View) -> (Range<usize>, bool) {
+    let text = doc.text().slice(..);
+    let primary = doc.selection(view.id).primary();
+    if primary.len() > 1 {
+        let first = text.char_to_line(primary.from());
+        let last = text.char_to_line(primary.to().saturating_sub(1));
+        return (first..last + 1, false);
+    }
+
+    let first = text.char_to_line(doc.view_offset(view.id).anchor.min(text.len_chars()));
+    let mut last = (first + view.inner_height()).min(text.len_lines());
+    // The empty line after a trailing newline is not worth exporting.
+    if last == text.len_lines() && last > first + 1 && text.line(last - 1).len_chars() == 0 {
+        last -= 1;
+    }
+    (first..last, true)
+}
+
+/// Styled lines of the view's selection or viewport, framed with the file name.
+pub(super) fn editor_snippet(
+    doc: &Document,
+    view: &View,
+    theme: &Theme,
+    syntax_loader: &helix_core::syntax::Loader,
+    tokens: &DesignTokens,
+) -> SnippetExport {
+    let (lines, include_selection) = export_line_range(doc, view);
+    styled_document_export(
+        doc,
+        view,
+        theme,
+        syntax_loader,
+        tokens,
+        lines,
+        include_selection,
+    )
+}
+
+/// Every line of the document, for File > Export.
+pub(super) fn whole_document(
+    doc: &Document,
+    view: &View,
+    theme: &Theme,
+    syntax_loader: &helix_core::syntax::Loader,
+    tokens: &DesignTokens,
+) -> SnippetExport {
+    let lines = 0..doc.text().len_lines();
+    styled_document_export(doc, view, theme, syntax_loader, tokens, lines, false)
+}
+
+fn styled_document_export(
+    doc: &Document,
+    view: &View,
+    theme: &Theme,
+    syntax_loader: &helix_core::syntax::Loader,
+    tokens: &DesignTokens,
+    lines: Range<usize>,
+    include_selection: bool,
+) -> SnippetExport {
+    let first_line = lines.start;
+    let styled = styled_document_lines(StyledDocumentLinesParams {
+        doc,
+        view,
+        theme,
+        syntax_loader,
+        lines,
+        include_selection,
+    });
+
+    SnippetExport {
+        title: doc
+            .relative_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| doc.display_name().into_owned()),
+        lines: styled
+            .into_iter()
+            .enumerate()
+            .map(|(index, spans)| ExportLine {
+                number: Some(first_line + index + 1),
+                spans: spans
+                    .into_iter()
+                    .map(|span| ExportSpan {
+                        text: span.text,
+                        foreground: span.fg.map(export_color),
+                        background: span.bg.map(export_color),
+                        bold: span.bold,
+                        italic: span.italic,
+                        underline: span.underline,
+                    })
+                    .collect(),
+            })
+            .collect(),
+        foreground: export_color(tokens.editor.text_primary),
+        background: export_color(tokens.editor.background),
+        gutter: export_color(tokens.editor.line_number),
+        frame: export_color(tokens.chrome.titlebar_background),
+    }
+}
+
+/// The visible terminal grid, framed with the terminal title.
+#[cfg(feature = "terminal-emulator-core")]
+pub(super) fn terminal_snippet(
+    title: String,
+    grid: nucleotide_terminal_view::TerminalStyledGrid,
+    tokens: &DesignTokens,
+) -> SnippetExport {
+    let mut lines: Vec<ExportLine> = grid
+        .rows
+        .into_iter()
+        .map(|spans| ExportLine {
+            number: None,
+            spans: spans
+                .into_iter()
+                .map(|span| ExportSpan {
+                    text: span.text,
+                    foreground: Some(export_color(span.fg)),
+                    background: span.bg.map(export_color),
+                    bold: span.bold,
+                    italic: span.italic,
+                    underline: span.underline,
+                })
+                .collect(),
+        })
+        .collect();
+    while lines.last().is_some_and(|line| line.spans.is_empty()) {
+        lines.pop();
+    }
+
+    SnippetExport {
+        title,
+        lines,
+        foreground: export_color(grid.foreground),
+        background: export_color(grid.background),
+        gutter: export_color(tokens.editor.line_number),
+        frame: export_color(tokens.chrome.titlebar_background),
+    }
+}
+
+/// Exports are written to the downloads folder, falling back to the home directory.
+pub(super) fn export_directory() -> Option<PathBuf> {
+    dirs::download_dir().or_else(dirs::home_dir)
+}
+
+/// `<title>-<timestamp>.<ext>`, keeping only the title's file name and
+/// characters that are safe on every platform.
+fn export_file_name(title: &str, timestamp: &str, extension: &str) -> String {
+    let name = Path::new(title)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name: String = name
+        .chars()
+        .map(|ch| {
+            if ch.is_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let name = name.trim_matches(['-', '.']);
+    let name = if name.is_empty() { "snippet" } else { name };
+    format!("{name}-{timestamp}.{extension}")
+}
+
+fn write_export(
+    directory: &Path,
+    title: &str,
+    extension: &str,
+    contents: &[u8],
+) -> std::io::Result<PathBuf> {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let path = directory.join(export_file_name(title, &timestamp, extension));
+    std::fs::create_dir_all(directory)?;
+    std::fs::write(&path, contents)?;
+    Ok(path)
+}
+
+/// Renders `snippet` and writes it to a new file in `directory`.
+pub(super) fn write_snippet_export(
+    directory: &Path,
+    snippet: &SnippetExport,
+    format: SnippetExportFormat,
+) -> std::io::Result<PathBuf> {
+    write_export(
+        directory,
+        &snippet.title,
+        format.extension(),
+        render_snippet(snippet, format).as_bytes(),
+    )
+}
+
+/// Renders a whole document, reporting progress, and writes it to a new file
+/// in `directory`. Returns the file and what the export printed differently.
+pub(super) fn write_document_export(
+    directory: &Path,
+    document: &SnippetExport,
+    options: &DocumentExportOptions,
+    format: DocumentExportFormat,
+    on_progress: impl FnMut(u8),
+) -> std::io::Result<(PathBuf, Vec<DocumentExportWarning>)> {
+    let export = render_document(document, options, format, on_progress);
+    let path = write_export(
+        directory,
+        &document.title,
+        format.extension(),
+        &export.contents,
+    )?;
+    Ok((path, export.warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_file_names_use_the_file_name_and_drop_unsafe_characters() {
+        assert_eq!(
+            export_file_name("src/main.rs", "20260101-120000", "svg"),
+            "main.rs-20260101-120000.svg"
+        );
+        assert_eq!(
+            export_file_name("zsh: cargo test", "1", "html"),
+            "zsh--cargo-test-1.html"
+        );
+        assert_eq!(export_file_name("", "1", "pdf"), "snippet-1.pdf");
+    }
+}
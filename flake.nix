@@ -504,6 +504,27 @@
                 <string>nucleotide.icns</string>
               </dict>
             </array>
+            <key>NSServices</key>
+            <array>
+                <dict>
+                    <key>NSMenuItem</key>
+                    <dict>
+                        <key>default</key>
+                        <string>New Nucleotide Buffer With Selection</string>
+                    </dict>
+                    <key>NSMessage</key>
+                    <string>newBufferWithSelection</string>
+                    <key>NSPortName</key>
+                    <string>Nucleotide</string>
+                    <key>NSRequiredContext</key>
+                    <dict/>
+                    <key>NSSendTypes</key>
+                    <array>
+                        <string>NSStringPboardType</string>
+                        <string>public.utf8-plain-text</string>
+                    </array>
+                </dict>
+            </array>
             <key>NSSupportsAutomaticTermination</key>
             <false/>
             <key>NSSupportsSuddenTermination</key>